@@ -31,8 +31,12 @@ pub fn render_tab(
         ThemeHue::Dark => palette.white,
         ThemeHue::Light => palette.black,
     };
+    // the active tab keeps its own color so that it can always be told apart from the rest
+    let tab_color = tab.color.as_ref().and_then(|c| palette.resolve_color(c));
     let background_color = if tab.active {
         palette.green
+    } else if let Some(tab_color) = tab_color {
+        tab_color
     } else if is_alternate_tab {
         alternate_tab_color
     } else {
@@ -92,6 +96,9 @@ pub fn tab_style(
     if tab.is_sync_panes_active {
         tabname.push_str(" (Sync)");
     }
+    if tab.has_bell {
+        tabname.push_str(" (Bell)");
+    }
     // we only color alternate tabs differently if we can't use the arrow fonts to separate them
     if !capabilities.arrow_fonts {
         is_alternate_tab = false;
@@ -11,6 +11,12 @@ struct State {
     received_events: Vec<Event>,
     received_payload: Option<String>,
     configuration: BTreeMap<String, String>,
+    session_tab_count: Option<usize>,
+    locked_mode_key: Option<String>,
+    counter: usize,
+    remaining_render_loop_iterations: usize,
+    owned_panes: Vec<u32>,
+    plugin_ids: Option<PluginIds>,
 }
 
 #[derive(Default, Serialize, Deserialize)]
@@ -19,7 +25,7 @@ struct TestWorker {
 }
 
 impl<'de> ZellijWorker<'de> for TestWorker {
-    fn on_message(&mut self, message: String, payload: String) {
+    fn on_message(&mut self, message: String, payload: Vec<u8>) {
         if message == "ping" {
             self.number_of_messages_received += 1;
             post_message_to_plugin(PluginMessage {
@@ -27,8 +33,10 @@ impl<'de> ZellijWorker<'de> for TestWorker {
                 name: "pong".into(),
                 payload: format!(
                     "{}, received {} messages",
-                    payload, self.number_of_messages_received
-                ),
+                    String::from_utf8_lossy(&payload),
+                    self.number_of_messages_received
+                )
+                .into_bytes(),
             });
         }
     }
@@ -49,6 +57,12 @@ impl ZellijPlugin for State {
             PermissionType::OpenTerminalsOrPlugins,
             PermissionType::WriteToStdin,
             PermissionType::WebAccess,
+            PermissionType::WriteToClipboard,
+            PermissionType::ReadPaneOutput,
+            PermissionType::ReadClipboard,
+            PermissionType::FocusSelf,
+            PermissionType::OpenUrls,
+            PermissionType::OpenFilesWithSystemOpener,
         ]);
         self.configuration = configuration;
         subscribe(&[
@@ -56,10 +70,21 @@ impl ZellijPlugin for State {
             EventType::Key,
             EventType::SystemClipboardFailure,
             EventType::CustomMessage,
-            EventType::FileSystemCreate,
-            EventType::FileSystemUpdate,
-            EventType::FileSystemDelete,
+            EventType::PermissionDenied,
+            EventType::RestoreState,
+            EventType::Paste,
+            EventType::SessionActivity,
+            EventType::ModeChanged,
+            EventType::TerminalPaneOpened,
         ]);
+        subscribe_with_file_system_filter(
+            &[
+                EventType::FileSystemCreate,
+                EventType::FileSystemUpdate,
+                EventType::FileSystemDelete,
+            ],
+            &["*.txt"],
+        );
     }
 
     fn update(&mut self, event: Event) -> bool {
@@ -274,14 +299,80 @@ impl ZellijPlugin for State {
                         context,
                     );
                 },
+                Key::Ctrl('5') => copy_to_clipboard("text copied by a plugin"),
+                Key::Ctrl('6') => log_message("message logged by a plugin"),
+                Key::Ctrl('7') => {
+                    self.session_tab_count = Some(get_session_layout().tabs.len());
+                },
+                Key::Ctrl('8') => open_url("https://zellij.dev"),
+                Key::Ctrl('9') => {
+                    render_overlay(2, 3, 20, 2, b"overlay line one\noverlay line two")
+                },
+                Key::Ctrl('0') => self.counter += 1,
+                Key::Ctrl('y') => {
+                    // render in a tight loop, each timer event renders and sets the next timer
+                    subscribe(&[EventType::Timer]);
+                    self.remaining_render_loop_iterations = 100;
+                    set_timeout(0.0);
+                },
+                Key::Alt(CharOrArrow::Char('l')) => {
+                    // spin forever, the plugin should be stopped once it runs out of fuel
+                    loop {
+                        self.counter = std::hint::black_box(self.counter.wrapping_add(1));
+                    }
+                },
+                Key::Alt(CharOrArrow::Char('s')) => request_session_activity(),
+                Key::Alt(CharOrArrow::Char('o')) => subscribe_pane_output(PaneId::Terminal(1)),
+                Key::Alt(CharOrArrow::Char('p')) => unsubscribe_pane_output(PaneId::Terminal(1)),
+                Key::Alt(CharOrArrow::Char('n')) => set_pane_name("fixture pane name"),
+                Key::Alt(CharOrArrow::Char('m')) => set_pane_name("fixture\u{1b}[31m pane name"),
+                Key::Alt(CharOrArrow::Char('t')) => set_tab_name("fixture tab name"),
+                Key::Alt(CharOrArrow::Char('r')) => subscribe(&[EventType::PluginMetrics]),
+                Key::Alt(CharOrArrow::Char('b')) => subscribe(&[EventType::Bell]),
+                Key::Alt(CharOrArrow::Char('v')) => {
+                    // copy what we read so that the test can see it
+                    match read_clipboard() {
+                        Some(contents) => copy_to_clipboard(&format!("read back: {}", contents)),
+                        None => copy_to_clipboard("read nothing"),
+                    }
+                },
+                Key::Alt(CharOrArrow::Char('f')) => focus_self(),
+                Key::Alt(CharOrArrow::Char('k')) => {
+                    let switch_to_locked_mode = [actions::Action::SwitchToMode(InputMode::Locked)];
+                    self.locked_mode_key = get_keybindings()
+                        .keys_for_actions(InputMode::Normal, &switch_to_locked_mode)
+                        .first()
+                        .map(|key| key.to_string());
+                },
+                Key::Alt(CharOrArrow::Char('e')) => open_terminal_pane("/path/to/my/dir", None),
+                Key::Alt(CharOrArrow::Char('i')) => self.plugin_ids = Some(get_plugin_ids()),
                 _ => {},
             },
+            Event::TerminalPaneOpened(pane_id) => self.owned_panes.push(*pane_id),
             Event::CustomMessage(message, payload) => {
                 if message == "pong" {
-                    self.received_payload = Some(payload.clone());
+                    self.received_payload = Some(String::from_utf8_lossy(payload).into_owned());
                 }
             },
-            Event::SystemClipboardFailure => {
+            Event::FileSystemCreate(paths) => {
+                if self.configuration.contains_key("report_file_system_events") {
+                    // plugins running in the background are never rendered, so we report the
+                    // event by renaming the tab instead
+                    go_to_tab_name(&format!("{:?}", paths));
+                }
+            },
+            Event::Timer(_) if self.remaining_render_loop_iterations > 0 => {
+                self.remaining_render_loop_iterations -= 1;
+                if self.remaining_render_loop_iterations == 0 {
+                    go_to_tab_name("render loop done");
+                } else {
+                    set_timeout(0.0);
+                }
+            },
+            Event::RestoreState(state) => {
+                self.counter = String::from_utf8_lossy(state).parse().unwrap_or(0);
+            },
+            Event::SystemClipboardFailure(_) => {
                 // this is just to trigger the worker message
                 post_message_to(PluginMessage {
                     worker_name: Some("test".into()),
@@ -296,9 +387,24 @@ impl ZellijPlugin for State {
         should_render
     }
 
+    fn serialize_state(&self) -> Vec<u8> {
+        self.counter.to_string().into_bytes()
+    }
+
     fn render(&mut self, rows: usize, cols: usize) {
         if let Some(payload) = self.received_payload.as_ref() {
             println!("Payload from worker: {:?}", payload);
+        } else if let Some(plugin_ids) = self.plugin_ids.as_ref() {
+            println!("Plugin id: {}", plugin_ids.plugin_id);
+            println!("Stable plugin id: {}", plugin_ids.stable_plugin_id);
+        } else if let Some(session_tab_count) = self.session_tab_count {
+            println!("Tabs in session: {}", session_tab_count);
+        } else if let Some(locked_mode_key) = self.locked_mode_key.as_ref() {
+            println!("Lock with: {}", locked_mode_key);
+        } else if self.counter > 0 {
+            println!("Counter: {}", self.counter);
+        } else if let Some(configured_value) = self.configuration.get("render_configured_value") {
+            println!("Configured value: {}", configured_value);
         } else {
             println!(
                 "Rows: {:?}, Cols: {:?}, Received events: {:?}",
@@ -1,5 +1,6 @@
 mod first_line;
 mod second_line;
+mod status_segments;
 mod tip;
 
 use ansi_term::{
@@ -20,6 +21,7 @@ use second_line::{
     locked_floating_panes_are_visible, locked_fullscreen_panes_to_hide, system_clipboard_error,
     text_copied_hint,
 };
+use status_segments::status_segments;
 use tip::utils::get_cached_tip_name;
 
 // for more of these, copy paste from: https://en.wikipedia.org/wiki/Box-drawing_character
@@ -35,6 +37,7 @@ struct State {
     mode_info: ModeInfo,
     text_copy_destination: Option<CopyDestination>,
     display_system_clipboard_failure: bool,
+    status_segments: Vec<StatusSegment>,
 }
 
 register_plugin!(State);
@@ -187,6 +190,7 @@ impl ZellijPlugin for State {
             EventType::CopyToClipboard,
             EventType::InputReceived,
             EventType::SystemClipboardFailure,
+            EventType::StatusSegmentsUpdate,
         ]);
     }
 
@@ -218,8 +222,10 @@ impl ZellijPlugin for State {
                 }
                 self.text_copy_destination = Some(copy_destination);
             },
-            Event::SystemClipboardFailure => {
+            Event::SystemClipboardFailure(_) => {
                 should_render = true;
+                // CopyToClipboard is sent before the copy is attempted, so it did not happen
+                self.text_copy_destination = None;
                 self.display_system_clipboard_failure = true;
             },
             Event::InputReceived => {
@@ -231,6 +237,12 @@ impl ZellijPlugin for State {
                 self.text_copy_destination = None;
                 self.display_system_clipboard_failure = false;
             },
+            Event::StatusSegmentsUpdate(status_segments) => {
+                if self.status_segments != status_segments {
+                    should_render = true;
+                }
+                self.status_segments = status_segments;
+            },
             _ => {},
         };
         should_render
@@ -245,7 +257,12 @@ impl ZellijPlugin for State {
         };
 
         let active_tab = self.tabs.iter().find(|t| t.active);
-        let first_line = first_line(&self.mode_info, active_tab, cols, separator);
+        let mut first_line = first_line(&self.mode_info, active_tab, cols, separator);
+        first_line.append(&status_segments(
+            &self.status_segments,
+            self.mode_info.style.colors,
+            cols.saturating_sub(first_line.len),
+        ));
         let second_line = self.second_line(cols);
 
         let background = match self.mode_info.style.colors.theme_hue {
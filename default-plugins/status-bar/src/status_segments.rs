@@ -0,0 +1,97 @@
+use ansi_term::ANSIStrings;
+use zellij_tile::prelude::*;
+use zellij_tile_utils::style;
+
+use crate::LinePart;
+
+/// The segments registered by plugins, ordered by descending priority. Segments with the lowest
+/// priority are left out until the rest fit in `max_len`.
+pub fn status_segments(segments: &[StatusSegment], palette: Palette, max_len: usize) -> LinePart {
+    let background = match palette.theme_hue {
+        ThemeHue::Dark => palette.black,
+        ThemeHue::Light => palette.white,
+    };
+    let mut segments: Vec<&StatusSegment> = segments
+        .iter()
+        .filter(|segment| !segment.content.is_empty())
+        .collect();
+    segments.sort_by(|a, b| b.priority.cmp(&a.priority));
+    let segment_len = |segment: &StatusSegment| printable_len(&segment.content) + 2; // padding
+    while segments.iter().map(|s| segment_len(s)).sum::<usize>() > max_len {
+        segments.pop();
+    }
+    let len = segments.iter().map(|s| segment_len(s)).sum();
+    let styled_segments: Vec<_> = segments
+        .iter()
+        .map(|segment| style!(palette.fg, background).paint(format!(" {} ", segment.content)))
+        .collect();
+    LinePart {
+        part: ANSIStrings(&styled_segments).to_string(),
+        len,
+    }
+}
+
+// the number of characters in the content, not counting ANSI escape sequences
+fn printable_len(content: &str) -> usize {
+    let mut len = 0;
+    let mut chars = content.chars();
+    while let Some(character) = chars.next() {
+        if character == '\u{1b}' {
+            // skip until the end of the sequence, eg. \u{1b}[1;32m
+            for character in chars.by_ref() {
+                if character.is_ascii_alphabetic() {
+                    break;
+                }
+            }
+        } else {
+            len += 1;
+        }
+    }
+    len
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unstyle(line_part: LinePart) -> String {
+        let string = line_part.to_string();
+        let re = regex::Regex::new(r"\x1b\[[0-9;]*m").unwrap();
+        re.replace_all(&string, "".to_string()).to_string()
+    }
+
+    fn two_segments() -> Vec<StatusSegment> {
+        vec![
+            StatusSegment {
+                plugin_id: 2,
+                render_callback_id: 0,
+                priority: 1,
+                content: "main".to_owned(),
+            },
+            StatusSegment {
+                plugin_id: 1,
+                render_callback_id: 0,
+                priority: 10,
+                content: "\u{1b}[32mCPU 12%\u{1b}[m".to_owned(),
+            },
+        ]
+    }
+
+    #[test]
+    fn segments_are_composed_by_descending_priority() {
+        let ret = status_segments(&two_segments(), Palette::default(), 80);
+        assert_eq!(ret.len, 15);
+        assert_eq!(unstyle(ret), " CPU 12%  main ".to_string());
+    }
+
+    #[test]
+    fn lowest_priority_segments_are_dropped_when_narrow() {
+        let ret = status_segments(&two_segments(), Palette::default(), 14);
+        assert_eq!(ret.len, 9);
+        assert_eq!(unstyle(ret), " CPU 12% ".to_string());
+
+        let ret = status_segments(&two_segments(), Palette::default(), 8);
+        assert_eq!(ret.len, 0);
+        assert_eq!(unstyle(ret), "".to_string());
+    }
+}
@@ -67,7 +67,8 @@ impl ZellijPlugin for State {
             },
             Event::CustomMessage(message, payload) => match serde_json::from_str(&message) {
                 Ok(MessageToPlugin::UpdateFileNameSearchResults) => {
-                    if let Ok(results_of_search) = serde_json::from_str::<ResultsOfSearch>(&payload)
+                    if let Ok(results_of_search) =
+                        serde_json::from_slice::<ResultsOfSearch>(&payload)
                     {
                         self.search_state
                             .update_file_name_search_results(results_of_search);
@@ -75,7 +76,8 @@ impl ZellijPlugin for State {
                     }
                 },
                 Ok(MessageToPlugin::UpdateFileContentsSearchResults) => {
-                    if let Ok(results_of_search) = serde_json::from_str::<ResultsOfSearch>(&payload)
+                    if let Ok(results_of_search) =
+                        serde_json::from_slice::<ResultsOfSearch>(&payload)
                     {
                         self.search_state
                             .update_file_contents_search_results(results_of_search);
@@ -198,12 +200,12 @@ impl ZellijPlugin for State {
                 post_message_to(PluginMessage {
                     worker_name: Some("file_name_search".into()),
                     name: serde_json::to_string(&MessageToSearch::FileSystemCreate).unwrap(),
-                    payload: serde_json::to_string(&paths).unwrap(),
+                    payload: serde_json::to_vec(&paths).unwrap(),
                 });
                 post_message_to(PluginMessage {
                     worker_name: Some("file_contents_search".into()),
                     name: serde_json::to_string(&MessageToSearch::FileSystemCreate).unwrap(),
-                    payload: serde_json::to_string(&paths).unwrap(),
+                    payload: serde_json::to_vec(&paths).unwrap(),
                 });
             },
             Event::FileSystemUpdate(paths) => {
@@ -214,12 +216,12 @@ impl ZellijPlugin for State {
                 post_message_to(PluginMessage {
                     worker_name: Some("file_name_search".into()),
                     name: serde_json::to_string(&MessageToSearch::FileSystemUpdate).unwrap(),
-                    payload: serde_json::to_string(&paths).unwrap(),
+                    payload: serde_json::to_vec(&paths).unwrap(),
                 });
                 post_message_to(PluginMessage {
                     worker_name: Some("file_contents_search".into()),
                     name: serde_json::to_string(&MessageToSearch::FileSystemUpdate).unwrap(),
-                    payload: serde_json::to_string(&paths).unwrap(),
+                    payload: serde_json::to_vec(&paths).unwrap(),
                 });
             },
             Event::FileSystemDelete(paths) => {
@@ -230,12 +232,12 @@ impl ZellijPlugin for State {
                 post_message_to(PluginMessage {
                     worker_name: Some("file_name_search".into()),
                     name: serde_json::to_string(&MessageToSearch::FileSystemDelete).unwrap(),
-                    payload: serde_json::to_string(&paths).unwrap(),
+                    payload: serde_json::to_vec(&paths).unwrap(),
                 });
                 post_message_to(PluginMessage {
                     worker_name: Some("file_contents_search".into()),
                     name: serde_json::to_string(&MessageToSearch::FileSystemDelete).unwrap(),
-                    payload: serde_json::to_string(&paths).unwrap(),
+                    payload: serde_json::to_vec(&paths).unwrap(),
                 });
             },
             _ => {
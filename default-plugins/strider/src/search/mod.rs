@@ -37,14 +37,14 @@ impl Search {
             ..Default::default()
         }
     }
-    fn on_message(&mut self, message: String, payload: String) {
+    fn on_message(&mut self, message: String, payload: Vec<u8>) {
         match serde_json::from_str::<MessageToSearch>(&message) {
             Ok(MessageToSearch::ScanFolder) => {
                 self.scan_hd();
                 post_message_to_plugin(PluginMessage {
                     worker_name: None,
                     name: serde_json::to_string(&MessageToPlugin::DoneScanningFolder).unwrap(),
-                    payload: "".to_owned(),
+                    payload: vec![],
                 });
             },
             Ok(MessageToSearch::Search) => {
@@ -53,13 +53,13 @@ impl Search {
                 }
             },
             Ok(MessageToSearch::FileSystemCreate) => {
-                self.rescan_files(payload);
+                self.rescan_files(&payload);
             },
             Ok(MessageToSearch::FileSystemUpdate) => {
-                self.rescan_files(payload);
+                self.rescan_files(&payload);
             },
             Ok(MessageToSearch::FileSystemDelete) => {
-                self.delete_files(payload);
+                self.delete_files(&payload);
             },
             Err(e) => eprintln!("Failed to deserialize worker message {:?}", e),
         }
@@ -118,7 +118,7 @@ impl Search {
         if let Some(file_names_search_results) = file_names_search_results {
             post_message_to_plugin(PluginMessage {
                 name: serde_json::to_string(&MessageToPlugin::UpdateFileNameSearchResults).unwrap(),
-                payload: serde_json::to_string(&file_names_search_results).unwrap(),
+                payload: serde_json::to_vec(&file_names_search_results).unwrap(),
                 ..Default::default()
             });
         }
@@ -126,13 +126,13 @@ impl Search {
             post_message_to_plugin(PluginMessage {
                 name: serde_json::to_string(&MessageToPlugin::UpdateFileContentsSearchResults)
                     .unwrap(),
-                payload: serde_json::to_string(&file_contents_search_results).unwrap(),
+                payload: serde_json::to_vec(&file_contents_search_results).unwrap(),
                 ..Default::default()
             });
         }
     }
-    pub fn rescan_files(&mut self, paths: String) {
-        match serde_json::from_str::<Vec<PathBuf>>(&paths) {
+    pub fn rescan_files(&mut self, paths: &[u8]) {
+        match serde_json::from_slice::<Vec<PathBuf>>(paths) {
             Ok(paths) => {
                 for path in paths {
                     self.add_file_entry(&path, path.metadata().ok());
@@ -143,8 +143,8 @@ impl Search {
             Err(e) => eprintln!("Failed to deserialize paths: {:?}", e),
         }
     }
-    pub fn delete_files(&mut self, paths: String) {
-        match serde_json::from_str::<Vec<PathBuf>>(&paths) {
+    pub fn delete_files(&mut self, paths: &[u8]) {
+        match serde_json::from_slice::<Vec<PathBuf>>(paths) {
             Ok(paths) => {
                 self.remove_existing_entries(&paths);
                 self.cached_file_name_results.clear();
@@ -280,13 +280,13 @@ impl Default for FileContentsWorker {
 }
 
 impl<'de> ZellijWorker<'de> for FileNameWorker {
-    fn on_message(&mut self, message: String, payload: String) {
+    fn on_message(&mut self, message: String, payload: Vec<u8>) {
         self.search.on_message(message, payload);
     }
 }
 
 impl<'de> ZellijWorker<'de> for FileContentsWorker {
-    fn on_message(&mut self, message: String, payload: String) {
+    fn on_message(&mut self, message: String, payload: Vec<u8>) {
         self.search.on_message(message, payload);
     }
 }
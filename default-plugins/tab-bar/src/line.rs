@@ -1,10 +1,48 @@
 use ansi_term::ANSIStrings;
-use unicode_width::UnicodeWidthStr;
+use std::cmp::min;
+use std::str::FromStr;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
 
+use crate::tab::tab_style;
 use crate::{LinePart, ARROW_SEPARATOR};
 use zellij_tile::prelude::*;
 use zellij_tile_utils::style;
 
+// tab names are not shrunk below this width, so that they stay somewhat recognizable
+const MIN_SHRUNK_TAB_NAME_WIDTH: usize = 3;
+
+/// What to do when the tabs do not all fit in the tab bar
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TabOverflow {
+    /// Keep the active tab in view, collapsing the tabs that do not fit on either side of it
+    Scroll,
+    /// Shrink the longest tab names evenly until all tabs fit, scrolling if they still do not
+    Shrink,
+    /// Show the tabs in order while they fit, followed by a "+N" count of the rest
+    Indicator,
+}
+
+impl Default for TabOverflow {
+    fn default() -> Self {
+        TabOverflow::Scroll
+    }
+}
+
+impl FromStr for TabOverflow {
+    type Err = String;
+    fn from_str(tab_overflow: &str) -> Result<Self, Self::Err> {
+        match tab_overflow {
+            "scroll" => Ok(TabOverflow::Scroll),
+            "shrink" => Ok(TabOverflow::Shrink),
+            "indicator" => Ok(TabOverflow::Indicator),
+            _ => Err(format!(
+                "Unknown tab overflow strategy '{}', expected one of: scroll, shrink, indicator",
+                tab_overflow
+            )),
+        }
+    }
+}
+
 fn get_current_title_len(current_title: &[LinePart]) -> usize {
     current_title.iter().map(|p| p.len).sum()
 }
@@ -171,6 +209,156 @@ fn right_more_message(
     }
 }
 
+// adds tabs in order while they fit in cols, always including the active tab, followed by a single
+// indicator with the count of the tabs that don't fit
+fn populate_tabs_before_overflow_indicator(
+    tabs_before_active: Vec<LinePart>,
+    tabs_after_active: Vec<LinePart>,
+    tabs_to_render: &mut Vec<LinePart>,
+    cols: usize,
+    palette: Palette,
+    capabilities: PluginCapabilities,
+) {
+    let before_active_count = tabs_before_active.len();
+    let mut hidden_count = before_active_count + tabs_after_active.len();
+    let mut rendered_before_active = vec![];
+    let mut rendered_after_active = vec![];
+    let mut total_size = get_current_title_len(tabs_to_render);
+    let tabs_in_order = tabs_before_active
+        .into_iter()
+        .map(|tab| (true, tab))
+        .chain(tabs_after_active.into_iter().map(|tab| (false, tab)));
+    for (is_before_active, tab) in tabs_in_order {
+        let indicator =
+            overflow_indicator_message(hidden_count - 1, palette, tab_separator(capabilities), 0);
+        if total_size + tab.len + indicator.len > cols {
+            break;
+        }
+        total_size += tab.len;
+        hidden_count -= 1;
+        if is_before_active {
+            rendered_before_active.push(tab);
+        } else {
+            rendered_after_active.push(tab);
+        }
+    }
+    // the hidden tabs are the ones after the last rendered tab (except for the active one)
+    let first_hidden_tab_index = if rendered_before_active.len() < before_active_count {
+        rendered_before_active.len()
+    } else {
+        before_active_count + 1 + rendered_after_active.len()
+    };
+    let indicator = overflow_indicator_message(
+        hidden_count,
+        palette,
+        tab_separator(capabilities),
+        first_hidden_tab_index,
+    );
+    rendered_before_active.append(tabs_to_render);
+    rendered_before_active.append(&mut rendered_after_active);
+    if total_size + indicator.len <= cols {
+        rendered_before_active.push(indicator);
+    }
+    *tabs_to_render = rendered_before_active;
+}
+
+fn overflow_indicator_message(
+    hidden_tab_count: usize,
+    palette: Palette,
+    separator: &str,
+    tab_index: usize,
+) -> LinePart {
+    if hidden_tab_count == 0 {
+        return LinePart::default();
+    }
+    let more_text = if hidden_tab_count < 10000 {
+        format!(" +{} ", hidden_tab_count)
+    } else {
+        " +many ".to_string()
+    };
+    // chars length plus separator length on both sides
+    let more_text_len = more_text.width() + 2 * separator.width();
+    let (text_color, sep_color) = match palette.theme_hue {
+        ThemeHue::Dark => (palette.white, palette.black),
+        ThemeHue::Light => (palette.black, palette.white),
+    };
+    let left_separator = style!(sep_color, palette.orange).paint(separator);
+    let more_styled_text = style!(text_color, palette.orange).bold().paint(more_text);
+    let right_separator = style!(palette.orange, sep_color).paint(separator);
+    let more_styled_text =
+        ANSIStrings(&[left_separator, more_styled_text, right_separator]).to_string();
+    LinePart {
+        part: more_styled_text,
+        len: more_text_len,
+        tab_index: Some(tab_index),
+    }
+}
+
+fn style_tabs(
+    tabs: &[(String, &TabInfo)],
+    max_tab_name_width: Option<usize>,
+    palette: Palette,
+    capabilities: PluginCapabilities,
+) -> Vec<LinePart> {
+    let mut is_alternate_tab = false;
+    tabs.iter()
+        .map(|(tab_name, tab)| {
+            let tab_name = match max_tab_name_width {
+                Some(max_tab_name_width) => shorten_tab_name(tab_name, max_tab_name_width),
+                None => tab_name.clone(),
+            };
+            let styled_tab = tab_style(tab_name, tab, is_alternate_tab, palette, capabilities);
+            is_alternate_tab = !is_alternate_tab;
+            styled_tab
+        })
+        .collect()
+}
+
+fn shorten_tab_name(tab_name: &str, max_width: usize) -> String {
+    if tab_name.width() <= max_width {
+        return tab_name.to_owned();
+    }
+    let mut shortened_tab_name = String::new();
+    let mut width = 0;
+    for character in tab_name.chars() {
+        let character_width = character.width().unwrap_or(0);
+        // we leave room for the ellipsis
+        if width + character_width + 1 > max_width {
+            break;
+        }
+        width += character_width;
+        shortened_tab_name.push(character);
+    }
+    shortened_tab_name.push('…');
+    shortened_tab_name
+}
+
+// the largest width the tab names can be shortened to so that all tabs fit in cols
+fn shrunk_tab_name_width(
+    tabs: &[(String, &TabInfo)],
+    styled_tabs: &[LinePart],
+    cols: usize,
+) -> usize {
+    let tab_name_widths: Vec<usize> = tabs.iter().map(|(tab_name, _)| tab_name.width()).collect();
+    // the separators, padding, etc. of the tabs keep their width
+    let fixed_width: usize = styled_tabs
+        .iter()
+        .zip(tab_name_widths.iter())
+        .map(|(styled_tab, tab_name_width)| styled_tab.len.saturating_sub(*tab_name_width))
+        .sum();
+    let longest_tab_name_width = tab_name_widths.iter().copied().max().unwrap_or(0);
+    (MIN_SHRUNK_TAB_NAME_WIDTH..=longest_tab_name_width)
+        .rev()
+        .find(|max_width| {
+            let shrunk_tab_names_width: usize = tab_name_widths
+                .iter()
+                .map(|tab_name_width| min(*tab_name_width, *max_width))
+                .sum();
+            fixed_width + shrunk_tab_names_width <= cols
+        })
+        .unwrap_or(MIN_SHRUNK_TAB_NAME_WIDTH)
+}
+
 fn tab_line_prefix(session_name: Option<&str>, palette: Palette, cols: usize) -> Vec<LinePart> {
     let prefix_text = " Zellij ".to_string();
 
@@ -216,15 +404,29 @@ pub fn tab_separator(capabilities: PluginCapabilities) -> &'static str {
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn tab_line(
     session_name: Option<&str>,
-    mut all_tabs: Vec<LinePart>,
+    tabs: &[(String, &TabInfo)], // (name to display, tab)
     active_tab_index: usize,
     cols: usize,
     palette: Palette,
     capabilities: PluginCapabilities,
     hide_session_name: bool,
+    tab_overflow: TabOverflow,
 ) -> Vec<LinePart> {
+    let mut prefix = match hide_session_name {
+        true => tab_line_prefix(None, palette, cols),
+        false => tab_line_prefix(session_name, palette, cols),
+    };
+    let prefix_len = get_current_title_len(&prefix);
+
+    let mut all_tabs = style_tabs(tabs, None, palette, capabilities);
+    let cols_for_tabs = cols.saturating_sub(prefix_len);
+    if tab_overflow == TabOverflow::Shrink && get_current_title_len(&all_tabs) > cols_for_tabs {
+        let max_tab_name_width = shrunk_tab_name_width(tabs, &all_tabs, cols_for_tabs);
+        all_tabs = style_tabs(tabs, Some(max_tab_name_width), palette, capabilities);
+    }
     let mut tabs_after_active = all_tabs.split_off(active_tab_index);
     let mut tabs_before_active = all_tabs;
     let active_tab = if !tabs_after_active.is_empty() {
@@ -232,11 +434,6 @@ pub fn tab_line(
     } else {
         tabs_before_active.pop().unwrap()
     };
-    let mut prefix = match hide_session_name {
-        true => tab_line_prefix(None, palette, cols),
-        false => tab_line_prefix(session_name, palette, cols),
-    };
-    let prefix_len = get_current_title_len(&prefix);
 
     // if active tab alone won't fit in cols, don't draw any tabs
     if prefix_len + active_tab.len > cols {
@@ -245,14 +442,138 @@ pub fn tab_line(
 
     let mut tabs_to_render = vec![active_tab];
 
-    populate_tabs_in_tab_line(
-        &mut tabs_before_active,
-        &mut tabs_after_active,
-        &mut tabs_to_render,
-        cols.saturating_sub(prefix_len),
-        palette,
-        capabilities,
-    );
+    match tab_overflow {
+        TabOverflow::Scroll | TabOverflow::Shrink => populate_tabs_in_tab_line(
+            &mut tabs_before_active,
+            &mut tabs_after_active,
+            &mut tabs_to_render,
+            cols_for_tabs,
+            palette,
+            capabilities,
+        ),
+        TabOverflow::Indicator => populate_tabs_before_overflow_indicator(
+            tabs_before_active,
+            tabs_after_active,
+            &mut tabs_to_render,
+            cols_for_tabs,
+            palette,
+            capabilities,
+        ),
+    }
     prefix.append(&mut tabs_to_render);
     prefix
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tabs(count: usize) -> Vec<TabInfo> {
+        (0..count)
+            .map(|position| TabInfo {
+                position,
+                name: format!("editor-{}", position),
+                active: position == 5,
+                ..Default::default()
+            })
+            .collect()
+    }
+
+    // Strip style information from the tab line and return its raw text instead
+    fn unstyle(tab_line: &[LinePart]) -> String {
+        let mut text = String::new();
+        let mut is_escape_sequence = false;
+        for character in tab_line.iter().flat_map(|line_part| line_part.part.chars()) {
+            if is_escape_sequence {
+                is_escape_sequence = character != 'm';
+            } else if character == '\u{1b}' {
+                is_escape_sequence = true;
+            } else {
+                text.push(character);
+            }
+        }
+        text
+    }
+
+    fn render_tab_line(tabs: &[TabInfo], cols: usize, tab_overflow: TabOverflow) -> Vec<LinePart> {
+        let tabs: Vec<(String, &TabInfo)> =
+            tabs.iter().map(|tab| (tab.name.clone(), tab)).collect();
+        let capabilities = PluginCapabilities { arrow_fonts: true }; // no separators
+        tab_line(
+            None,
+            &tabs,
+            5,
+            cols,
+            Palette::default(),
+            capabilities,
+            false,
+            tab_overflow,
+        )
+    }
+
+    #[test]
+    fn overflowing_tabs_scroll_with_active_tab_in_view() {
+        let tab_line = render_tab_line(&tabs(10), 58, TabOverflow::Scroll);
+        assert_eq!(
+            unstyle(&tab_line),
+            " Zellij  ← +4  editor-4  editor-5  editor-6  +3 → "
+        );
+    }
+
+    #[test]
+    fn overflowing_tabs_shrink_their_names_evenly() {
+        let tab_line = render_tab_line(&tabs(10), 58, TabOverflow::Shrink);
+        assert_eq!(
+            unstyle(&tab_line),
+            " Zellij  ed…  ed…  ed…  ed…  ed…  ed…  ed…  ed…  ed…  ed… "
+        );
+        let tab_indices: Vec<Option<usize>> = tab_line
+            .iter()
+            .filter(|line_part| line_part.len > 0)
+            .map(|line_part| line_part.tab_index)
+            .collect();
+        let prefix_and_all_tab_indices: Vec<Option<usize>> =
+            std::iter::once(None).chain((0..10).map(Some)).collect();
+        assert_eq!(
+            tab_indices, prefix_and_all_tab_indices,
+            "All tabs are rendered in order"
+        );
+    }
+
+    #[test]
+    fn shrunk_tabs_scroll_when_they_still_do_not_fit() {
+        let tab_line = render_tab_line(&tabs(10), 38, TabOverflow::Shrink);
+        assert_eq!(unstyle(&tab_line), " Zellij  ← +4  ed…  ed…  ed…  +3 → ");
+    }
+
+    #[test]
+    fn overflowing_tabs_are_counted_in_an_indicator() {
+        let tab_line = render_tab_line(&tabs(10), 58, TabOverflow::Indicator);
+        assert_eq!(
+            unstyle(&tab_line),
+            " Zellij  editor-0  editor-1  editor-2  editor-5  +6 "
+        );
+        assert_eq!(
+            tab_line.last().and_then(|indicator| indicator.tab_index),
+            Some(3),
+            "Clicking the indicator focuses the first tab that does not fit"
+        );
+    }
+
+    #[test]
+    fn tabs_that_fit_are_rendered_the_same_by_all_strategies() {
+        for tab_overflow in [
+            TabOverflow::Scroll,
+            TabOverflow::Shrink,
+            TabOverflow::Indicator,
+        ] {
+            let tab_line = render_tab_line(&tabs(6), 100, tab_overflow);
+            assert_eq!(
+                unstyle(&tab_line),
+                " Zellij  editor-0  editor-1  editor-2  editor-3  editor-4  editor-5 ",
+                "{:?}",
+                tab_overflow
+            );
+        }
+    }
+}
@@ -8,8 +8,7 @@ use std::convert::TryInto;
 use tab::get_tab_to_focus;
 use zellij_tile::prelude::*;
 
-use crate::line::tab_line;
-use crate::tab::tab_style;
+use crate::line::{tab_line, TabOverflow};
 
 #[derive(Debug, Default)]
 pub struct LinePart {
@@ -24,6 +23,7 @@ struct State {
     active_tab_idx: usize,
     mode_info: ModeInfo,
     tab_line: Vec<LinePart>,
+    tab_overflow: TabOverflow, // set with the "tab_overflow" plugin configuration
 }
 
 static ARROW_SEPARATOR: &str = "";
@@ -31,7 +31,16 @@ static ARROW_SEPARATOR: &str = "";
 register_plugin!(State);
 
 impl ZellijPlugin for State {
-    fn load(&mut self, _configuration: BTreeMap<String, String>) {
+    fn load(&mut self, configuration: BTreeMap<String, String>) {
+        self.tab_overflow = configuration
+            .get("tab_overflow")
+            .map(|tab_overflow| {
+                tab_overflow.parse().unwrap_or_else(|e| {
+                    eprintln!("{}", e);
+                    TabOverflow::default()
+                })
+            })
+            .unwrap_or_default();
         set_selectable(false);
         subscribe(&[
             EventType::TabUpdate,
@@ -89,10 +98,9 @@ impl ZellijPlugin for State {
         if self.tabs.is_empty() {
             return;
         }
-        let mut all_tabs: Vec<LinePart> = vec![];
+        let mut all_tabs: Vec<(String, &TabInfo)> = vec![];
         let mut active_tab_index = 0;
-        let mut is_alternate_tab = false;
-        for t in &mut self.tabs {
+        for t in &self.tabs {
             let mut tabname = t.name.clone();
             if t.active && self.mode_info.mode == InputMode::RenameTab {
                 if tabname.is_empty() {
@@ -102,24 +110,17 @@ impl ZellijPlugin for State {
             } else if t.active {
                 active_tab_index = t.position;
             }
-            let tab = tab_style(
-                tabname,
-                t,
-                is_alternate_tab,
-                self.mode_info.style.colors,
-                self.mode_info.capabilities,
-            );
-            is_alternate_tab = !is_alternate_tab;
-            all_tabs.push(tab);
+            all_tabs.push((tabname, t));
         }
         self.tab_line = tab_line(
             self.mode_info.session_name.as_deref(),
-            all_tabs,
+            &all_tabs,
             active_tab_index,
             cols.saturating_sub(1),
             self.mode_info.style.colors,
             self.mode_info.capabilities,
             self.mode_info.style.hide_session_name,
+            self.tab_overflow,
         );
 
         let output = self
@@ -3,9 +3,10 @@ use std::{fs::File, io::prelude::*, path::PathBuf, process, time::Duration};
 
 use crate::sessions::{
     assert_dead_session, assert_session, assert_session_ne, delete_session as delete_session_impl,
-    get_active_session, get_name_generator, get_resurrectable_sessions, get_sessions,
-    get_sessions_sorted_by_mtime, kill_session as kill_session_impl, match_session_name,
-    print_sessions, print_sessions_with_index, resurrection_layout, session_exists, ActiveSession,
+    delete_workspace as delete_workspace_impl, get_active_session, get_name_generator,
+    get_resurrectable_sessions, get_sessions, get_sessions_sorted_by_mtime, get_workspace,
+    kill_session as kill_session_impl, match_session_name, print_sessions,
+    print_sessions_with_index, resurrection_layout, session_exists, write_workspace, ActiveSession,
     SessionNameMatch,
 };
 use zellij_client::{
@@ -13,12 +14,12 @@ use zellij_client::{
         config_yaml_to_config_kdl, convert_old_yaml_files, layout_yaml_to_layout_kdl,
     },
     os_input_output::get_client_os_input,
-    start_client as start_client_impl, ClientInfo,
+    start_client as start_client_impl, start_detached_session, ClientInfo,
 };
 use zellij_server::{os_input_output::get_server_os_input, start_server as start_server_impl};
 use zellij_utils::{
-    cli::{CliArgs, Command, SessionCommand, Sessions},
-    data::ConnectToSession,
+    cli::{CliArgs, Command, SessionCommand, Sessions, WorkspaceCommand},
+    data::{ConnectToSession, WorkspaceInfo},
     envs,
     input::{
         actions::Action,
@@ -30,7 +31,7 @@ use zellij_utils::{
     setup::Setup,
 };
 
-pub(crate) use crate::sessions::list_sessions;
+pub(crate) use crate::sessions::{list_sessions, list_sessions_as_json, list_workspaces};
 
 pub(crate) fn kill_all_sessions(yes: bool) {
     match get_sessions() {
@@ -123,6 +124,109 @@ pub(crate) fn delete_session(target_session: &Option<String>, force: bool) {
     }
 }
 
+pub(crate) fn workspace_command(workspace_command: WorkspaceCommand, opts: CliArgs) {
+    match workspace_command {
+        WorkspaceCommand::Create {
+            workspace_name,
+            sessions,
+        } => create_workspace(workspace_name, sessions),
+        WorkspaceCommand::Attach {
+            workspace_name,
+            session,
+        } => attach_workspace(&workspace_name, session, opts),
+        WorkspaceCommand::Delete { workspace_name } => {
+            delete_workspace_impl(&workspace_name);
+            process::exit(0);
+        },
+    }
+}
+
+fn create_workspace(workspace_name: String, sessions: Vec<String>) {
+    if workspace_name.trim().is_empty() || workspace_name.contains('/') {
+        eprintln!("Invalid workspace name: {:?}.", workspace_name);
+        process::exit(1);
+    }
+    if get_workspace(&workspace_name).is_some() {
+        eprintln!("Workspace with name {:?} already exists.", workspace_name);
+        process::exit(1);
+    }
+    match write_workspace(&WorkspaceInfo::new(workspace_name.clone(), sessions)) {
+        Ok(_) => {
+            println!("Workspace: {:?} successfully created.", workspace_name);
+            process::exit(0);
+        },
+        Err(e) => {
+            eprintln!("Failed to create workspace {:?}: {:?}", workspace_name, e);
+            process::exit(1);
+        },
+    }
+}
+
+fn attach_workspace(workspace_name: &str, session: Option<String>, mut opts: CliArgs) {
+    let workspace = match get_workspace(workspace_name) {
+        Some(workspace) => workspace,
+        None => {
+            eprintln!("No workspace with the name {:?} found!", workspace_name);
+            process::exit(1);
+        },
+    };
+    match workspace.session_to_attach(session.as_deref()) {
+        Ok(session_name) => {
+            // the siblings are brought up in the background, so that they can be switched to from
+            // inside the session
+            let running_sessions: Vec<String> = get_sessions()
+                .unwrap_or_default()
+                .into_iter()
+                .map(|(name, _duration)| name)
+                .collect();
+            for sibling in
+                workspace.sessions_to_start_in_background(&session_name, &running_sessions)
+            {
+                start_session_in_background(&sibling, &opts);
+            }
+            opts.command = Some(Command::Sessions(Sessions::Attach {
+                session_name: Some(session_name),
+                create: true,
+                force_run_commands: false,
+                index: None,
+                options: None,
+                pane_view: None,
+                observer: false,
+            }));
+            start_client(opts);
+        },
+        Err(e) => {
+            eprintln!("{}", e);
+            process::exit(1);
+        },
+    }
+}
+
+fn start_session_in_background(session_name: &str, opts: &CliArgs) {
+    let (config, layout, config_options) = match Setup::from_cli_args(opts) {
+        Ok(results) => results,
+        Err(e) => {
+            eprintln!("Failed to start session {:?}: {}", session_name, e);
+            return;
+        },
+    };
+    // a sibling that exited is resurrected rather than started anew
+    let layout = resurrection_layout(session_name).unwrap_or(layout);
+    let os_input = get_os_input(get_client_os_input);
+    let mut opts = opts.clone();
+    opts.command = None;
+    if let Err(e) = start_detached_session(
+        Box::new(os_input),
+        opts,
+        config,
+        config_options,
+        session_name.to_owned(),
+        layout,
+    ) {
+        eprintln!("Failed to start session {:?}: {}", session_name, e);
+    }
+}
+
 fn get_os_input<OsInputOutput>(
     fn_get_os_input: fn() -> Result<OsInputOutput, nix::Error>,
 ) -> OsInputOutput {
@@ -174,6 +278,25 @@ pub(crate) fn send_action_to_session(
     requested_session_name: Option<String>,
     config: Option<Config>,
 ) {
+    let session_name = session_to_send_actions_to(requested_session_name);
+    attach_with_cli_client(cli_action, &session_name, config);
+}
+
+/// Client entrypoint for `zellij control`
+///
+/// Checks session to drive and attaches with a client reading JSON commands from STDIN
+pub(crate) fn start_control_client(requested_session_name: Option<String>) {
+    let session_name = session_to_send_actions_to(requested_session_name);
+    let os_input = get_os_input(zellij_client::os_input_output::get_cli_client_os_input);
+    zellij_client::control_client::start_control_client(
+        Box::new(os_input),
+        &session_name,
+        std::io::stdin().lock(),
+        std::io::stdout(),
+    );
+}
+
+fn session_to_send_actions_to(requested_session_name: Option<String>) -> String {
     match get_active_session() {
         ActiveSession::None => {
             eprintln!("There is no active session!");
@@ -190,7 +313,7 @@ pub(crate) fn send_action_to_session(
                     std::process::exit(1);
                 }
             }
-            attach_with_cli_client(cli_action, &session_name, config);
+            session_name
         },
         ActiveSession::Many => {
             let existing_sessions: Vec<String> = get_sessions()
@@ -200,7 +323,7 @@ pub(crate) fn send_action_to_session(
                 .collect();
             if let Some(session_name) = requested_session_name {
                 if existing_sessions.contains(&session_name) {
-                    attach_with_cli_client(cli_action, &session_name, config);
+                    session_name
                 } else {
                     eprintln!(
                         "Session '{}' not found. The following sessions are active:",
@@ -210,14 +333,14 @@ pub(crate) fn send_action_to_session(
                     std::process::exit(1);
                 }
             } else if let Ok(session_name) = envs::get_session_name() {
-                attach_with_cli_client(cli_action, &session_name, config);
+                session_name
             } else {
                 eprintln!("Please specify the session name to send actions to. The following sessions are active:");
                 list_sessions(false, false);
                 std::process::exit(1);
             }
         },
-    };
+    }
 }
 pub(crate) fn convert_old_config_file(old_config_file: PathBuf) {
     match File::open(&old_config_file) {
@@ -417,6 +540,8 @@ pub(crate) fn start_client(opts: CliArgs) {
                     force_run_commands: false,
                     index: None,
                     options: None,
+                    pane_view: None,
+                    observer: false,
                 }));
             } else {
                 opts.command = None;
@@ -436,6 +561,8 @@ pub(crate) fn start_client(opts: CliArgs) {
             force_run_commands,
             index,
             options,
+            pane_view,
+            observer,
         })) = opts.command.clone()
         {
             let config_options = match options.as_deref() {
@@ -487,7 +614,9 @@ pub(crate) fn start_client(opts: CliArgs) {
                 .and_then(|r| r.tab_position.clone());
             let pane_id_to_focus = reconnect_to_session
                 .as_ref()
-                .and_then(|r| r.pane_id.clone());
+                .and_then(|r| r.pane_id.clone())
+                .or_else(|| pane_view.map(|terminal_id| (terminal_id, false)));
+            let is_pane_view = pane_view.is_some();
             reconnect_to_session = start_client_impl(
                 Box::new(os_input),
                 opts,
@@ -497,6 +626,8 @@ pub(crate) fn start_client(opts: CliArgs) {
                 attach_layout,
                 tab_position_to_focus,
                 pane_id_to_focus,
+                is_pane_view,
+                observer,
                 is_a_reconnect,
             );
         } else {
@@ -511,6 +642,8 @@ pub(crate) fn start_client(opts: CliArgs) {
                     Some(layout),
                     None,
                     None,
+                    false,
+                    false,
                     is_a_reconnect,
                 );
             } else {
@@ -551,6 +684,8 @@ pub(crate) fn start_client(opts: CliArgs) {
                                 attach_layout,
                                 None,
                                 None,
+                                false,
+                                false,
                                 is_a_reconnect,
                             );
                         },
@@ -565,6 +700,8 @@ pub(crate) fn start_client(opts: CliArgs) {
                                 Some(layout),
                                 None,
                                 None,
+                                false,
+                                false,
                                 is_a_reconnect,
                             );
                         },
@@ -588,6 +725,8 @@ pub(crate) fn start_client(opts: CliArgs) {
                     Some(layout),
                     None,
                     None,
+                    false,
+                    false,
                     is_a_reconnect,
                 );
             }
@@ -20,6 +20,10 @@ fn main() {
             commands::send_action_to_session(cli_action, opts.session, config);
             std::process::exit(0);
         }
+        if let Some(Command::Sessions(Sessions::Control)) = opts.command {
+            commands::start_control_client(opts.session);
+            std::process::exit(0);
+        }
         if let Some(Command::Sessions(Sessions::Run {
             command,
             direction,
@@ -42,6 +46,7 @@ fn main() {
                 close_on_exit,
                 start_suspended,
                 configuration: None,
+                enter_mode: None,
             };
             commands::send_action_to_session(command_cli_action, opts.session, config);
             std::process::exit(0);
@@ -64,6 +69,7 @@ fn main() {
                 close_on_exit: false,
                 start_suspended: false,
                 configuration,
+                enter_mode: None,
             };
             commands::send_action_to_session(command_cli_action, opts.session, config);
             std::process::exit(0);
@@ -112,9 +118,19 @@ fn main() {
     if let Some(Command::Sessions(Sessions::ListSessions {
         no_formatting,
         short,
+        json,
     })) = opts.command
     {
-        commands::list_sessions(no_formatting, short);
+        if json {
+            commands::list_sessions_as_json();
+        } else {
+            commands::list_sessions(no_formatting, short);
+        }
+    } else if let Some(Command::Sessions(Sessions::ListWorkspaces { no_formatting })) = opts.command
+    {
+        commands::list_workspaces(no_formatting);
+    } else if let Some(Command::Sessions(Sessions::Workspace { ref command })) = opts.command {
+        commands::workspace_command(command.clone(), opts.clone());
     } else if let Some(Command::Sessions(Sessions::KillAllSessions { yes })) = opts.command {
         commands::kill_all_sessions(yes);
     } else if let Some(Command::Sessions(Sessions::KillSession { ref target_session })) =
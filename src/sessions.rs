@@ -6,14 +6,19 @@ use suggest::Suggest;
 use zellij_utils::{
     anyhow,
     consts::{
-        session_info_folder_for_session, session_layout_cache_file_name,
-        ZELLIJ_SESSION_INFO_CACHE_DIR, ZELLIJ_SOCK_DIR,
+        session_info_folder_for_session, session_layout_cache_file_name, workspace_info_file_name,
+        ZELLIJ_SESSION_INFO_CACHE_DIR, ZELLIJ_SOCK_DIR, ZELLIJ_WORKSPACE_INFO_CACHE_DIR,
     },
+    data::WorkspaceInfo,
     envs,
     humantime::format_duration,
     input::layout::Layout,
     interprocess::local_socket::LocalSocketStream,
-    ipc::{ClientToServerMsg, IpcReceiverWithContext, IpcSenderWithContext, ServerToClientMsg},
+    ipc::{
+        ClientToServerMsg, IpcReceiverWithContext, IpcSenderWithContext, ServerToClientMsg,
+        SessionListing,
+    },
+    serde_json,
 };
 
 pub(crate) fn get_sessions() -> Result<Vec<(String, Duration)>, io::ErrorKind> {
@@ -289,6 +294,169 @@ pub(crate) fn list_sessions(no_formatting: bool, short: bool) {
     process::exit(exit_code);
 }
 
+pub(crate) fn list_sessions_as_json() {
+    let exit_code = match get_sessions() {
+        // every live session lists all of them, so we ask the first one
+        Ok(running_sessions) => match running_sessions.first() {
+            Some((session_name, _timestamp)) => match request_session_list(session_name) {
+                Some(session_list) => match serde_json::to_string(&session_list) {
+                    Ok(session_list) => {
+                        println!("{}", session_list);
+                        0
+                    },
+                    Err(e) => {
+                        eprintln!("Error occurred: {:?}", e);
+                        1
+                    },
+                },
+                None => {
+                    eprintln!("Failed to list sessions from session: {:?}", session_name);
+                    1
+                },
+            },
+            None => {
+                println!("[]");
+                0
+            },
+        },
+        Err(e) => {
+            eprintln!("Error occurred: {:?}", e);
+            1
+        },
+    };
+    process::exit(exit_code);
+}
+
+fn request_session_list(session_name: &str) -> Option<Vec<SessionListing>> {
+    let path = &*ZELLIJ_SOCK_DIR.join(session_name);
+    let stream = LocalSocketStream::connect(path).ok()?;
+    let mut sender = IpcSenderWithContext::new(stream);
+    sender.send(ClientToServerMsg::ListSessions).ok()?;
+    let mut receiver: IpcReceiverWithContext<ServerToClientMsg> = sender.get_receiver();
+    match receiver.recv() {
+        Some((ServerToClientMsg::SessionList(session_list), _)) => Some(session_list),
+        None | Some((_, _)) => None,
+    }
+}
+
+pub(crate) fn get_workspaces() -> Vec<WorkspaceInfo> {
+    match fs::read_dir(&*ZELLIJ_WORKSPACE_INFO_CACHE_DIR) {
+        Ok(files) => {
+            let mut workspaces: Vec<WorkspaceInfo> = files
+                .filter_map(|f| f.ok().map(|f| f.path()))
+                .filter(|f| f.extension().map(|e| e == "kdl").unwrap_or(false))
+                .filter_map(|workspace_file| {
+                    let raw_workspace_info = fs::read_to_string(&workspace_file)
+                        .map_err(|e| log::error!("Failed to read workspace file: {:?}", e))
+                        .ok()?;
+                    WorkspaceInfo::from_string(&raw_workspace_info)
+                        .map_err(|e| log::error!("Failed to parse workspace file: {}", e))
+                        .ok()
+                })
+                .collect();
+            workspaces.sort_by(|a, b| a.name.cmp(&b.name));
+            workspaces
+        },
+        Err(e) if e.kind() == io::ErrorKind::NotFound => vec![],
+        Err(e) => {
+            log::error!(
+                "Failed to read workspace_info cache folder: \"{:?}\": {:?}",
+                &*ZELLIJ_WORKSPACE_INFO_CACHE_DIR,
+                e
+            );
+            vec![]
+        },
+    }
+}
+
+pub(crate) fn get_workspace(name: &str) -> Option<WorkspaceInfo> {
+    let raw_workspace_info = fs::read_to_string(workspace_info_file_name(name)).ok()?;
+    WorkspaceInfo::from_string(&raw_workspace_info)
+        .map_err(|e| log::error!("Failed to parse workspace file: {}", e))
+        .ok()
+}
+
+pub(crate) fn write_workspace(workspace: &WorkspaceInfo) -> io::Result<()> {
+    fs::create_dir_all(&*ZELLIJ_WORKSPACE_INFO_CACHE_DIR)?;
+    fs::write(
+        workspace_info_file_name(&workspace.name),
+        workspace.to_string(),
+    )
+}
+
+pub(crate) fn delete_workspace(name: &str) {
+    match fs::remove_file(workspace_info_file_name(name)) {
+        Ok(_) => println!("Workspace: {:?} successfully deleted.", name),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {
+            eprintln!("Workspace: {:?} not found.", name);
+            process::exit(2);
+        },
+        Err(e) => {
+            eprintln!("Failed to delete workspace {:?}: {:?}", name, e);
+            process::exit(1);
+        },
+    }
+}
+
+pub(crate) fn print_workspaces(workspaces: Vec<WorkspaceInfo>, no_formatting: bool) {
+    let running_sessions: Vec<String> = get_sessions()
+        .unwrap_or_default()
+        .into_iter()
+        .map(|(name, _)| name)
+        .collect();
+    let resurrectable_sessions: Vec<String> = get_resurrectable_sessions()
+        .into_iter()
+        .map(|(name, _, _)| name)
+        .collect();
+    for workspace in workspaces {
+        let sessions: Vec<String> = workspace
+            .sessions
+            .iter()
+            .enumerate()
+            .map(|(i, session_name)| {
+                let primary = if i == 0 { " (primary)" } else { "" };
+                let state = if running_sessions.contains(session_name) {
+                    None
+                } else if resurrectable_sessions.contains(session_name) {
+                    Some("EXITED")
+                } else {
+                    Some("NOT CREATED")
+                };
+                if no_formatting {
+                    let state = state.map(|s| format!(" ({})", s)).unwrap_or_default();
+                    format!("{}{}{}", session_name, primary, state)
+                } else {
+                    let state = state
+                        .map(|s| format!(" (\u{1b}[31;1m{}\u{1b}[m)", s))
+                        .unwrap_or_default();
+                    format!("\u{1b}[32;1m{}\u{1b}[m{}{}", session_name, primary, state)
+                }
+            })
+            .collect();
+        if no_formatting {
+            println!("{}: {}", workspace.name, sessions.join(", "));
+        } else {
+            println!(
+                "\u{1b}[35;1m{}\u{1b}[m: {}",
+                workspace.name,
+                sessions.join(", ")
+            );
+        }
+    }
+}
+
+pub(crate) fn list_workspaces(no_formatting: bool) {
+    let workspaces = get_workspaces();
+    let exit_code = if workspaces.is_empty() {
+        eprintln!("No zellij workspaces found.");
+        1
+    } else {
+        print_workspaces(workspaces, no_formatting);
+        0
+    };
+    process::exit(exit_code);
+}
+
 #[derive(Debug, Clone)]
 pub enum SessionNameMatch {
     AmbiguousPrefix(Vec<String>),
@@ -0,0 +1,209 @@
+//! The `[control_client]` is used to attach to a running server session and drive it with
+//! commands read as newline-delimited JSON (eg. from an editor integration), writing a JSON
+//! response line for each of them.
+//!
+//! Commands are either `{"command": "DumpLayout"}`, `{"command": "ListSessions"}` or
+//! `{"command": "Action", "action": ...}` for any other action (serialized as it is sent to the
+//! server). A command can carry an `id`, which is repeated in its response.
+use std::io::{self, BufRead, Write};
+use std::process;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::os_input_output::ClientOsApi;
+use zellij_utils::{
+    input::actions::Action,
+    ipc::{ClientToServerMsg, ServerToClientMsg, SessionListing},
+};
+
+#[derive(Deserialize, Debug)]
+#[serde(tag = "command")]
+enum ControlCommand {
+    DumpLayout,
+    ListSessions,
+    Action { action: Action },
+}
+
+#[derive(Serialize, Debug, Default)]
+struct ControlResponse {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    id: Option<serde_json::Value>,
+    ok: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    layout: Option<String>, // KDL
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sessions: Option<Vec<SessionListing>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    output: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+impl ControlResponse {
+    fn error(error: impl ToString) -> Self {
+        ControlResponse {
+            ok: false,
+            error: Some(error.to_string()),
+            ..Default::default()
+        }
+    }
+}
+
+pub fn start_control_client(
+    os_input: Box<dyn ClientOsApi>,
+    session_name: &str,
+    commands: impl BufRead,
+    responses: impl Write,
+) {
+    let zellij_ipc_pipe: PathBuf = {
+        let mut sock_dir = zellij_utils::consts::ZELLIJ_SOCK_DIR.clone();
+        fs::create_dir_all(&sock_dir).unwrap();
+        zellij_utils::shared::set_permissions(&sock_dir, 0o700).unwrap();
+        sock_dir.push(session_name);
+        sock_dir
+    };
+    os_input.connect_to_server(&*zellij_ipc_pipe);
+    let pane_id = os_input
+        .env_variable("ZELLIJ_PANE_ID")
+        .and_then(|e| e.trim().parse().ok());
+    let result =
+        handle_control_commands(&*os_input, &zellij_ipc_pipe, pane_id, commands, responses);
+    os_input.send_to_server(ClientToServerMsg::ClientExited);
+    if let Err(e) = result {
+        eprintln!("Failed to write control response: {}", e);
+        process::exit(2);
+    }
+}
+
+/// Handles the commands until they run out or the session goes away
+fn handle_control_commands(
+    os_input: &dyn ClientOsApi,
+    zellij_ipc_pipe: &Path,
+    pane_id: Option<u32>,
+    commands: impl BufRead,
+    mut responses: impl Write,
+) -> io::Result<()> {
+    for (request_id, line) in (0..).zip(commands.lines()) {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let (response, session_is_gone) =
+            match handle_control_command(os_input, zellij_ipc_pipe, pane_id, request_id, &line) {
+                Some(response) => (response, false),
+                None => (
+                    ControlResponse::error("Lost connection to the session"),
+                    true,
+                ),
+            };
+        serde_json::to_writer(&mut responses, &response)?;
+        writeln!(responses)?;
+        responses.flush()?;
+        if session_is_gone {
+            break;
+        }
+    }
+    Ok(())
+}
+
+/// Returns None if the connection to the session was lost
+fn handle_control_command(
+    os_input: &dyn ClientOsApi,
+    zellij_ipc_pipe: &Path,
+    pane_id: Option<u32>,
+    request_id: u64,
+    line: &str,
+) -> Option<ControlResponse> {
+    let request: serde_json::Value = match serde_json::from_str(line) {
+        Ok(request) => request,
+        Err(e) => return Some(ControlResponse::error(format!("Malformed command: {}", e))),
+    };
+    let id = request.get("id").cloned();
+    let command = match serde_json::from_value(request) {
+        Ok(command) => command,
+        Err(e) => {
+            let mut response = ControlResponse::error(format!("Unknown command: {}", e));
+            response.id = id;
+            return Some(response);
+        },
+    };
+    let mut response = match command {
+        ControlCommand::DumpLayout => {
+            os_input.send_to_server(ClientToServerMsg::Action(Action::DumpLayout, pane_id, None));
+            match recv_reply(os_input, request_id, true)? {
+                Ok(layout) => ControlResponse {
+                    ok: true,
+                    layout: Some(layout.join("\n")),
+                    ..Default::default()
+                },
+                Err(error) => ControlResponse::error(error.join("\n")),
+            }
+        },
+        ControlCommand::ListSessions => {
+            os_input.send_to_server(ClientToServerMsg::ListSessions);
+            let session_list = loop {
+                match os_input.recv_from_server()? {
+                    (ServerToClientMsg::SessionList(session_list), _) => break session_list,
+                    (ServerToClientMsg::Exit(_), _) => return None,
+                    _ => {},
+                }
+            };
+            // the server hangs up on clients once it lists the sessions for them
+            os_input.connect_to_server(zellij_ipc_pipe);
+            ControlResponse {
+                ok: true,
+                sessions: Some(session_list),
+                ..Default::default()
+            }
+        },
+        ControlCommand::Action { action } => {
+            os_input.send_to_server(ClientToServerMsg::Action(action, pane_id, None));
+            match recv_reply(os_input, request_id, false)? {
+                Ok(output) => ControlResponse {
+                    ok: true,
+                    output: Some(output),
+                    ..Default::default()
+                },
+                Err(error) => ControlResponse::error(error.join("\n")),
+            }
+        },
+    };
+    response.id = id;
+    Some(response)
+}
+
+/// Waits for the server to be done with the last action, returning the lines it logged (or the
+/// errors it reported). If `expects_output` is true, the action is done once it logs its output.
+/// Otherwise the action is followed by a ping with the id of the request, which the server only
+/// answers (to us alone) once it is done with the action. Input being unblocked is not enough, as
+/// the server unblocks the input of all clients once it is done with the action of any of them.
+fn recv_reply(
+    os_input: &dyn ClientOsApi,
+    request_id: u64,
+    expects_output: bool,
+) -> Option<Result<Vec<String>, Vec<String>>> {
+    if !expects_output {
+        os_input.send_to_server(ClientToServerMsg::Ping(request_id));
+    }
+    let mut output = vec![];
+    loop {
+        match os_input.recv_from_server()? {
+            (ServerToClientMsg::Log(log_lines), _) if expects_output => return Some(Ok(log_lines)),
+            (ServerToClientMsg::Log(log_lines), _) => output.extend(log_lines),
+            (ServerToClientMsg::LogError(log_lines), _) => return Some(Err(log_lines)),
+            (ServerToClientMsg::Pong(id), _) if id == request_id && !expects_output => {
+                return Some(Ok(output));
+            },
+            (ServerToClientMsg::Exit(_), _) => return None,
+            _ => {},
+        }
+    }
+}
+
+#[cfg(test)]
+#[path = "./unit/control_client_tests.rs"]
+mod control_client_tests;
@@ -3,6 +3,7 @@ use crate::{
     os_input_output::ClientOsApi, stdin_ansi_parser::AnsiStdinInstruction, ClientId,
     ClientInstruction, CommandIsExecuting, InputInstruction,
 };
+use std::str::FromStr;
 use zellij_utils::{
     channels::{Receiver, SenderWithContext, OPENCALLS},
     data::{InputMode, Key},
@@ -45,6 +46,9 @@ struct InputHandler {
     receive_input_instructions: Receiver<(InputInstruction, ErrorContext)>,
     holding_mouse: Option<HeldMouseButton>,
     mouse_mode_active: bool,
+    /// Whether the focused pane is in pass-through mode, receiving all keys but the escape key
+    passthrough: bool,
+    passthrough_escape_key: Key,
 }
 
 impl InputHandler {
@@ -58,6 +62,11 @@ impl InputHandler {
         mode: InputMode,
         receive_input_instructions: Receiver<(InputInstruction, ErrorContext)>,
     ) -> Self {
+        let passthrough_escape_key = options
+            .passthrough_escape_key
+            .as_deref()
+            .and_then(|key| Key::from_str(key).ok())
+            .unwrap_or(Key::Ctrl('g'));
         InputHandler {
             mode,
             os_input,
@@ -69,6 +78,8 @@ impl InputHandler {
             receive_input_instructions,
             holding_mouse: None,
             mouse_mode_active: false,
+            passthrough: false,
+            passthrough_escape_key,
         }
     }
 
@@ -139,6 +150,9 @@ impl InputHandler {
                 Ok((InputInstruction::SwitchToMode(input_mode), _error_context)) => {
                     self.mode = input_mode;
                 },
+                Ok((InputInstruction::SetPassthrough(passthrough), _error_context)) => {
+                    self.passthrough = passthrough;
+                },
                 Ok((
                     InputInstruction::AnsiStdinInstructions(ansi_stdin_instructions),
                     _error_context,
@@ -165,6 +179,17 @@ impl InputHandler {
         }
     }
     fn handle_key(&mut self, key: &Key, raw_bytes: Vec<u8>) {
+        if self.passthrough {
+            if key == &self.passthrough_escape_key {
+                // this is an optimistic update, the server will confirm it once the pane leaves
+                // pass-through mode
+                self.passthrough = false;
+                self.dispatch_action(Action::TogglePanePassthrough, None);
+            } else {
+                self.dispatch_action(Action::Write(raw_bytes), None);
+            }
+            return;
+        }
         let keybinds = &self.config.keybinds;
         for action in
             keybinds.get_actions_for_key_in_mode_or_default_action(&self.mode, key, raw_bytes)
@@ -319,6 +344,7 @@ impl InputHandler {
             | Action::CloseTab
             | Action::GoToTab(_)
             | Action::GoToTabName(_, _)
+            | Action::GoToTabByName(_)
             | Action::ToggleTab
             | Action::MoveFocusOrTab(_) => {
                 self.command_is_executing.blocking_input_thread();
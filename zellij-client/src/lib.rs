@@ -2,6 +2,7 @@ pub mod os_input_output;
 
 pub mod cli_client;
 mod command_is_executing;
+pub mod control_client;
 mod input_handler;
 pub mod old_config_converter;
 mod stdin_ansi_parser;
@@ -28,7 +29,7 @@ use zellij_utils::{
     envs,
     errors::{ClientContext, ContextType, ErrorInstruction},
     input::{config::Config, options::Options},
-    ipc::{ClientAttributes, ClientToServerMsg, ExitReason, ServerToClientMsg},
+    ipc::{ClientAttributes, ClientToServerMsg, ExitReason, ServerToClientMsg, SessionListing},
     termwiz::input::InputEvent,
 };
 use zellij_utils::{cli::CliArgs, input::layout::Layout};
@@ -49,6 +50,9 @@ pub(crate) enum ClientInstruction {
     LogError(Vec<String>),
     SwitchSession(ConnectToSession),
     SetSynchronizedOutput(Option<SyncOutput>),
+    SessionList(Vec<SessionListing>),
+    SetPassthrough(bool),
+    Pong(u64),
 }
 
 impl From<ServerToClientMsg> for ClientInstruction {
@@ -67,6 +71,13 @@ impl From<ServerToClientMsg> for ClientInstruction {
             ServerToClientMsg::SwitchSession(connect_to_session) => {
                 ClientInstruction::SwitchSession(connect_to_session)
             },
+            ServerToClientMsg::SessionList(session_list) => {
+                ClientInstruction::SessionList(session_list)
+            },
+            ServerToClientMsg::SetPassthrough(passthrough) => {
+                ClientInstruction::SetPassthrough(passthrough)
+            },
+            ServerToClientMsg::Pong(request_id) => ClientInstruction::Pong(request_id),
         }
     }
 }
@@ -87,6 +98,9 @@ impl From<&ClientInstruction> for ClientContext {
             ClientInstruction::DoneParsingStdinQuery => ClientContext::DoneParsingStdinQuery,
             ClientInstruction::SwitchSession(..) => ClientContext::SwitchSession,
             ClientInstruction::SetSynchronizedOutput(..) => ClientContext::SetSynchronisedOutput,
+            ClientInstruction::SessionList(..) => ClientContext::SessionList,
+            ClientInstruction::SetPassthrough(..) => ClientContext::SetPassthrough,
+            ClientInstruction::Pong(..) => ClientContext::Pong,
         }
     }
 }
@@ -135,10 +149,52 @@ impl ClientInfo {
     }
 }
 
+/// Starts a session without a client attached to it: the server is set up as it would be for a
+/// new client, which then leaves right away so that the session keeps running detached
+pub fn start_detached_session(
+    mut os_input: Box<dyn ClientOsApi>,
+    opts: CliArgs,
+    config: Config,
+    config_options: Options,
+    session_name: String,
+    layout: Layout,
+) -> io::Result<()> {
+    let palette = config
+        .theme_config(&config_options)
+        .unwrap_or_else(|| os_input.load_palette());
+    let client_attributes = ClientAttributes {
+        size: os_input.get_terminal_size_using_fd(0),
+        style: Style {
+            colors: palette,
+            rounded_corners: config.ui.pane_frames.rounded_corners,
+            hide_session_name: config.ui.pane_frames.hide_session_name,
+        },
+        keybinds: config.keybinds.clone(),
+    };
+    let mut ipc_pipe = ZELLIJ_SOCK_DIR.clone();
+    std::fs::create_dir_all(&ipc_pipe)?;
+    set_permissions(&ipc_pipe, 0o700)?;
+    ipc_pipe.push(&session_name);
+    // the server takes the name of the session from the environment
+    envs::set_session_name(session_name);
+    spawn_server(&*ipc_pipe, opts.debug)?;
+    os_input.connect_to_server(&*ipc_pipe);
+    os_input.send_to_server(ClientToServerMsg::NewClient(
+        client_attributes,
+        Box::new(opts),
+        Box::new(config_options),
+        Box::new(layout),
+        Some(config.plugins.clone()),
+    ));
+    os_input.send_to_server(ClientToServerMsg::ClientExited);
+    Ok(())
+}
+
 #[derive(Debug, Clone)]
 pub(crate) enum InputInstruction {
     KeyEvent(InputEvent, Vec<u8>),
     SwitchToMode(InputMode),
+    SetPassthrough(bool),
     AnsiStdinInstructions(Vec<AnsiStdinInstruction>),
     StartedParsing,
     DoneParsing,
@@ -154,6 +210,8 @@ pub fn start_client(
     layout: Option<Layout>,
     tab_position_to_focus: Option<usize>,
     pane_id_to_focus: Option<(u32, bool)>, // (pane_id, is_plugin)
+    is_pane_view: bool,
+    is_observer: bool,
     is_a_reconnect: bool,
 ) -> Option<ConnectToSession> {
     info!("Starting Zellij client!");
@@ -215,6 +273,8 @@ pub fn start_client(
                     config_options,
                     tab_position_to_focus,
                     pane_id_to_focus,
+                    is_pane_view,
+                    is_observer,
                 ),
                 ipc_pipe,
             )
@@ -471,6 +531,11 @@ pub fn start_client(
                     .send(InputInstruction::SwitchToMode(input_mode))
                     .unwrap();
             },
+            ClientInstruction::SetPassthrough(passthrough) => {
+                send_input_instructions
+                    .send(InputInstruction::SetPassthrough(passthrough))
+                    .unwrap();
+            },
             ClientInstruction::Log(lines_to_log) => {
                 for line in lines_to_log {
                     log::info!("{line}");
@@ -0,0 +1,169 @@
+use super::handle_control_commands;
+use crate::os_input_output::{ClientOsApi, StdinPoller};
+
+use std::collections::VecDeque;
+use std::io;
+use std::os::unix::io::RawFd;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use zellij_utils::anyhow::Result;
+use zellij_utils::data::Palette;
+use zellij_utils::errors::ErrorContext;
+use zellij_utils::input::actions::Action;
+use zellij_utils::ipc::{ClientToServerMsg, ServerToClientMsg};
+use zellij_utils::nix;
+use zellij_utils::pane_size::Size;
+use zellij_utils::serde_json::{self, Value};
+
+#[derive(Clone)]
+struct FakeClientOsApi {
+    messages_sent_to_server: Arc<Mutex<Vec<ClientToServerMsg>>>,
+    messages_from_server: Arc<Mutex<VecDeque<ServerToClientMsg>>>,
+}
+
+impl FakeClientOsApi {
+    pub fn new(messages_from_server: Vec<ServerToClientMsg>) -> Self {
+        FakeClientOsApi {
+            messages_sent_to_server: Arc::new(Mutex::new(vec![])),
+            messages_from_server: Arc::new(Mutex::new(messages_from_server.into())),
+        }
+    }
+}
+
+impl ClientOsApi for FakeClientOsApi {
+    fn get_terminal_size_using_fd(&self, _fd: RawFd) -> Size {
+        unimplemented!()
+    }
+    fn set_raw_mode(&mut self, _fd: RawFd) {
+        unimplemented!()
+    }
+    fn unset_raw_mode(&self, _fd: RawFd) -> Result<(), nix::Error> {
+        unimplemented!()
+    }
+    fn get_stdout_writer(&self) -> Box<dyn io::Write> {
+        unimplemented!()
+    }
+    fn get_stdin_reader(&self) -> Box<dyn io::Read> {
+        unimplemented!()
+    }
+    fn update_session_name(&mut self, _new_session_name: String) {}
+    fn read_from_stdin(&mut self) -> Result<Vec<u8>, &'static str> {
+        unimplemented!()
+    }
+    fn box_clone(&self) -> Box<dyn ClientOsApi> {
+        Box::new(self.clone())
+    }
+    fn send_to_server(&self, msg: ClientToServerMsg) {
+        self.messages_sent_to_server.lock().unwrap().push(msg);
+    }
+    fn recv_from_server(&self) -> Option<(ServerToClientMsg, ErrorContext)> {
+        self.messages_from_server
+            .lock()
+            .unwrap()
+            .pop_front()
+            .map(|msg| (msg, ErrorContext::new()))
+    }
+    fn handle_signals(&self, _sigwinch_cb: Box<dyn Fn()>, _quit_cb: Box<dyn Fn()>) {
+        unimplemented!()
+    }
+    fn connect_to_server(&self, _path: &Path) {}
+    fn load_palette(&self) -> Palette {
+        unimplemented!()
+    }
+    fn enable_mouse(&self) -> Result<()> {
+        Ok(())
+    }
+    fn disable_mouse(&self) -> Result<()> {
+        Ok(())
+    }
+    fn stdin_poller(&self) -> StdinPoller {
+        unimplemented!()
+    }
+}
+
+fn run_control_commands(os_input: &FakeClientOsApi, commands: &str) -> Vec<Value> {
+    let mut responses = vec![];
+    handle_control_commands(
+        os_input,
+        Path::new("/tmp/fake-session"),
+        None,
+        commands.as_bytes(),
+        &mut responses,
+    )
+    .unwrap();
+    String::from_utf8(responses)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).expect("response is well-formed JSON"))
+        .collect()
+}
+
+#[test]
+pub fn dump_layout_command_responds_with_the_layout() {
+    let layout = "layout {\n    pane\n}";
+    let os_input = FakeClientOsApi::new(vec![
+        // done with the action of another client, should not be taken as the response
+        ServerToClientMsg::UnblockInputThread,
+        ServerToClientMsg::Log(vec![layout.to_owned()]),
+    ]);
+    let responses = run_control_commands(&os_input, "{\"id\": 1, \"command\": \"DumpLayout\"}\n");
+    assert_eq!(responses.len(), 1, "one response per command");
+    assert_eq!(responses[0]["id"], 1);
+    assert_eq!(responses[0]["ok"], true);
+    assert_eq!(responses[0]["layout"], layout);
+    assert!(matches!(
+        os_input.messages_sent_to_server.lock().unwrap().as_slice(),
+        [ClientToServerMsg::Action(Action::DumpLayout, None, None)]
+    ));
+}
+
+#[test]
+pub fn unknown_commands_respond_with_an_error() {
+    let os_input = FakeClientOsApi::new(vec![]);
+    let responses = run_control_commands(
+        &os_input,
+        "{\"id\": \"a\", \"command\": \"MakeCoffee\"}\nnot json\n",
+    );
+    assert_eq!(responses.len(), 2, "one response per command");
+    assert_eq!(responses[0]["id"], "a");
+    assert_eq!(responses[0]["ok"], false);
+    assert!(responses[0]["error"]
+        .as_str()
+        .unwrap()
+        .contains("MakeCoffee"));
+    assert_eq!(responses[1]["ok"], false);
+    assert!(os_input.messages_sent_to_server.lock().unwrap().is_empty());
+}
+
+#[test]
+pub fn action_commands_respond_once_the_server_answers_their_ping() {
+    let os_input = FakeClientOsApi::new(vec![
+        // done with the action of another client, should not be taken as the response
+        ServerToClientMsg::UnblockInputThread,
+        ServerToClientMsg::Pong(0),
+        // the answer to a ping of an earlier request, should not be taken as the response
+        ServerToClientMsg::Pong(0),
+        ServerToClientMsg::LogError(vec!["no tab at position 3".to_owned()]),
+        ServerToClientMsg::Pong(1),
+    ]);
+    let responses = run_control_commands(
+        &os_input,
+        "{\"id\": 1, \"command\": \"Action\", \"action\": \"ToggleZoom\"}\n\
+         {\"id\": 2, \"command\": \"Action\", \"action\": {\"ToggleFloatingPanesInTab\": 3}}\n",
+    );
+    assert_eq!(responses.len(), 2, "one response per command");
+    assert_eq!(responses[0]["id"], 1);
+    assert_eq!(responses[0]["ok"], true);
+    assert_eq!(responses[1]["id"], 2);
+    assert_eq!(responses[1]["ok"], false);
+    assert_eq!(responses[1]["error"], "no tab at position 3");
+    assert!(matches!(
+        os_input.messages_sent_to_server.lock().unwrap().as_slice(),
+        [
+            ClientToServerMsg::Action(Action::ToggleZoom, None, None),
+            ClientToServerMsg::Ping(0),
+            ClientToServerMsg::Action(Action::ToggleFloatingPanesInTab(3), None, None),
+            ClientToServerMsg::Ping(1),
+        ]
+    ));
+}
@@ -43,6 +43,8 @@ fn read_fixture(fixture_name: &str) -> Vec<u8> {
 #[allow(unused)]
 pub mod commands {
     pub const QUIT: [u8; 1] = [17]; // ctrl-q
+    pub const TMUX_MODE: [u8; 1] = [2]; // ctrl-b
+    pub const LEAVE_PASSTHROUGH: [u8; 1] = [7]; // ctrl-g
     pub const ESC: [u8; 1] = [27];
     pub const ENTER: [u8; 1] = [10]; // char '\n'
 
@@ -318,3 +320,79 @@ pub fn move_focus_left_in_normal_mode() {
         "All actions sent to server properly"
     );
 }
+
+#[test]
+pub fn passthrough_sends_all_keys_but_the_escape_key_to_the_pane() {
+    let stdin_events = vec![
+        (
+            commands::TMUX_MODE.to_vec(),
+            InputEvent::Key(KeyEvent {
+                key: KeyCode::Char('b'),
+                modifiers: Modifiers::CTRL,
+            }),
+        ),
+        (
+            commands::LEAVE_PASSTHROUGH.to_vec(),
+            InputEvent::Key(KeyEvent {
+                key: KeyCode::Char('g'),
+                modifiers: Modifiers::CTRL,
+            }),
+        ),
+        (
+            commands::QUIT.to_vec(),
+            InputEvent::Key(KeyEvent {
+                key: KeyCode::Char('q'),
+                modifiers: Modifiers::CTRL,
+            }),
+        ),
+    ];
+
+    let events_sent_to_server = Arc::new(Mutex::new(vec![]));
+    let command_is_executing = CommandIsExecuting::new();
+    let client_os_api = Box::new(FakeClientOsApi::new(
+        events_sent_to_server.clone(),
+        command_is_executing.clone(),
+    ));
+    let config = Config::from_default_assets().unwrap();
+    let options = Options::default();
+
+    let (send_client_instructions, _receive_client_instructions): ChannelWithContext<
+        ClientInstruction,
+    > = channels::bounded(50);
+    let send_client_instructions = SenderWithContext::new(send_client_instructions);
+
+    let (send_input_instructions, receive_input_instructions): ChannelWithContext<
+        InputInstruction,
+    > = channels::bounded(50);
+    let send_input_instructions = SenderWithContext::new(send_input_instructions);
+    send_input_instructions
+        .send(InputInstruction::SetPassthrough(true))
+        .unwrap();
+    for event in stdin_events {
+        send_input_instructions
+            .send(InputInstruction::KeyEvent(event.1, event.0))
+            .unwrap();
+    }
+
+    let default_mode = InputMode::Normal;
+    input_loop(
+        client_os_api,
+        config,
+        options,
+        command_is_executing,
+        send_client_instructions,
+        default_mode,
+        receive_input_instructions,
+    );
+    // ctrl-b would otherwise switch to tmux mode, once the escape key is pressed ctrl-q quits again
+    let expected_actions_sent_to_server = vec![
+        Action::Write(commands::TMUX_MODE.to_vec()),
+        Action::TogglePanePassthrough,
+        Action::Quit,
+    ];
+    let received_actions = extract_actions_sent_to_server(events_sent_to_server);
+    assert_eq!(
+        expected_actions_sent_to_server, received_actions,
+        "All actions sent to server properly"
+    );
+}
@@ -24,11 +24,12 @@ use std::time::{Duration, Instant};
 use crate::plugins::{PluginId, PluginInstruction};
 use crate::screen::ScreenInstruction;
 use crate::thread_bus::Bus;
-use crate::ClientId;
+use crate::{ClientId, ServerInstruction};
 
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
 pub enum BackgroundJob {
     DisplayPaneError(Vec<PaneId>, String),
+    FlashPaneFrame(PaneId),
     AnimatePluginLoading(u32),                            // u32 - plugin_id
     StopPluginLoadingAnimation(u32),                      // u32 - plugin_id
     ReadAllSessionInfosOnMachine,                         // u32 - plugin_id
@@ -52,6 +53,8 @@ pub enum BackgroundJob {
         Vec<u8>,                  // body
         BTreeMap<String, String>, // context
     ),
+    WatchIdleClients(Duration),                       // idle timeout
+    OpenWithSystemOpener(PluginId, ClientId, String), // url or path
     Exit,
 }
 
@@ -59,6 +62,7 @@ impl From<&BackgroundJob> for BackgroundJobContext {
     fn from(background_job: &BackgroundJob) -> Self {
         match *background_job {
             BackgroundJob::DisplayPaneError(..) => BackgroundJobContext::DisplayPaneError,
+            BackgroundJob::FlashPaneFrame(..) => BackgroundJobContext::FlashPaneFrame,
             BackgroundJob::AnimatePluginLoading(..) => BackgroundJobContext::AnimatePluginLoading,
             BackgroundJob::StopPluginLoadingAnimation(..) => {
                 BackgroundJobContext::StopPluginLoadingAnimation
@@ -70,6 +74,8 @@ impl From<&BackgroundJob> for BackgroundJobContext {
             BackgroundJob::ReportLayoutInfo(..) => BackgroundJobContext::ReportLayoutInfo,
             BackgroundJob::RunCommand(..) => BackgroundJobContext::RunCommand,
             BackgroundJob::WebRequest(..) => BackgroundJobContext::WebRequest,
+            BackgroundJob::WatchIdleClients(..) => BackgroundJobContext::WatchIdleClients,
+            BackgroundJob::OpenWithSystemOpener(..) => BackgroundJobContext::OpenWithSystemOpener,
             BackgroundJob::Exit => BackgroundJobContext::Exit,
         }
     }
@@ -78,6 +84,11 @@ impl From<&BackgroundJob> for BackgroundJobContext {
 static FLASH_DURATION_MS: u64 = 1000;
 static PLUGIN_ANIMATION_OFFSET_DURATION_MD: u64 = 500;
 static SESSION_READ_DURATION: u64 = 60000;
+static IDLE_CLIENTS_CHECK_DURATION_MS: u64 = 1000;
+#[cfg(target_os = "macos")]
+static SYSTEM_OPENER: &str = "open";
+#[cfg(not(target_os = "macos"))]
+static SYSTEM_OPENER: &str = "xdg-open";
 
 pub(crate) fn background_jobs_main(
     bus: Bus<BackgroundJob>,
@@ -117,6 +128,23 @@ pub(crate) fn background_jobs_main(
                     }
                 });
             },
+            BackgroundJob::FlashPaneFrame(pane_id) => {
+                if job_already_running(job, &mut running_jobs) {
+                    continue;
+                }
+                task::spawn({
+                    let senders = bus.senders.clone();
+                    async move {
+                        let _ = senders.send_to_screen(
+                            ScreenInstruction::AddBellPaneFrameColorOverride(vec![pane_id]),
+                        );
+                        task::sleep(std::time::Duration::from_millis(FLASH_DURATION_MS)).await;
+                        let _ = senders.send_to_screen(
+                            ScreenInstruction::ClearPaneFrameColorOverride(vec![pane_id]),
+                        );
+                    }
+                });
+            },
             BackgroundJob::AnimatePluginLoading(pid) => {
                 let loading_plugin = Arc::new(AtomicBool::new(true));
                 if job_already_running(job, &mut running_jobs) {
@@ -302,6 +330,71 @@ pub(crate) fn background_jobs_main(
                     }
                 });
             },
+            BackgroundJob::OpenWithSystemOpener(plugin_id, client_id, target) => {
+                // when async_std::process stabilizes, we should change this to be async
+                std::thread::spawn({
+                    let senders = bus.senders.clone();
+                    move || {
+                        // xdg-open does not accept "--" to end its options, so a target that
+                        // would be taken for one is never passed to it
+                        if target.starts_with('-') {
+                            log::error!("Refusing to open {}: it looks like an option", target);
+                            let _ = senders.send_to_plugin(PluginInstruction::Update(vec![(
+                                Some(plugin_id),
+                                Some(client_id),
+                                Event::SystemOpenerFailure(
+                                    target,
+                                    "Targets starting with '-' cannot be opened".to_owned(),
+                                ),
+                            )]));
+                            return;
+                        }
+                        let status = std::process::Command::new(SYSTEM_OPENER)
+                            .arg(&target)
+                            .stdout(std::process::Stdio::null())
+                            .stderr(std::process::Stdio::null())
+                            .status();
+                        let error = match status {
+                            Ok(status) if status.success() => None,
+                            Ok(status) => Some(format!("{} exited with {}", SYSTEM_OPENER, status)),
+                            Err(e) => Some(format!("Failed to run {}: {}", SYSTEM_OPENER, e)),
+                        };
+                        if let Some(error) = error {
+                            log::error!("Failed to open {}: {}", target, error);
+                            let _ = senders.send_to_plugin(PluginInstruction::Update(vec![(
+                                Some(plugin_id),
+                                Some(client_id),
+                                Event::SystemOpenerFailure(target, error),
+                            )]));
+                        }
+                    }
+                });
+            },
+            BackgroundJob::WatchIdleClients(idle_timeout) => {
+                // the server knows when each client last sent input, this only asks it to check
+                // periodically
+                if running_jobs.get(&job).is_some() {
+                    continue;
+                }
+                running_jobs.insert(job, Instant::now());
+                task::spawn({
+                    let senders = bus.senders.clone();
+                    async move {
+                        loop {
+                            task::sleep(std::time::Duration::from_millis(
+                                IDLE_CLIENTS_CHECK_DURATION_MS,
+                            ))
+                            .await;
+                            if senders
+                                .send_to_server(ServerInstruction::DetachIdleClients(idle_timeout))
+                                .is_err()
+                            {
+                                break;
+                            }
+                        }
+                    }
+                });
+            },
             BackgroundJob::Exit => {
                 for loading_plugin in loading_plugins.values() {
                     loading_plugin.store(false, Ordering::SeqCst);
@@ -0,0 +1,78 @@
+//! Measures the time between receiving input from a client and dispatching the next render to it
+
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::time::{Duration, Instant};
+
+use zellij_utils::data::ClientInputLatency;
+
+use crate::ClientId;
+
+const MAX_SAMPLES_PER_CLIENT: usize = 100;
+const MIN_REPORT_INTERVAL: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Default)]
+pub struct InputLatencyTracker {
+    pending_input: HashMap<ClientId, Instant>, // the first input that has not been rendered yet
+    samples: BTreeMap<ClientId, VecDeque<Duration>>,
+    has_unreported_samples: bool,
+    last_report: Option<Instant>,
+}
+
+impl InputLatencyTracker {
+    pub fn input_received(&mut self, client_id: ClientId) {
+        // if there's already pending input, we keep its timestamp so that the sample includes the
+        // time it spent waiting behind newer input
+        self.pending_input
+            .entry(client_id)
+            .or_insert_with(Instant::now);
+    }
+    pub fn render_dispatched(&mut self, client_id: ClientId) {
+        if let Some(input_received_at) = self.pending_input.remove(&client_id) {
+            let client_samples = self.samples.entry(client_id).or_insert_with(VecDeque::new);
+            if client_samples.len() >= MAX_SAMPLES_PER_CLIENT {
+                client_samples.pop_front();
+            }
+            client_samples.push_back(input_received_at.elapsed());
+            self.has_unreported_samples = true;
+        }
+    }
+    pub fn remove_client(&mut self, client_id: ClientId) {
+        self.pending_input.remove(&client_id);
+        self.samples.remove(&client_id);
+    }
+    pub fn stats(&self) -> Vec<ClientInputLatency> {
+        self.samples
+            .iter()
+            .filter_map(|(client_id, client_samples)| {
+                let last = *client_samples.back()?;
+                let min = *client_samples.iter().min()?;
+                let max = *client_samples.iter().max()?;
+                let sample_count = client_samples.len();
+                let average = client_samples.iter().sum::<Duration>() / sample_count as u32;
+                Some(ClientInputLatency {
+                    client_id: *client_id,
+                    sample_count,
+                    last,
+                    average,
+                    min,
+                    max,
+                })
+            })
+            .collect()
+    }
+    /// Returns the current stats if new samples were recorded since they were last reported,
+    /// reporting at most once every `MIN_REPORT_INTERVAL` so as not to flood plugins
+    pub fn stats_to_report(&mut self) -> Option<Vec<ClientInputLatency>> {
+        let report_is_due = self
+            .last_report
+            .map(|last_report| last_report.elapsed() >= MIN_REPORT_INTERVAL)
+            .unwrap_or(true);
+        if self.has_unreported_samples && report_is_due {
+            self.has_unreported_samples = false;
+            self.last_report = Some(Instant::now());
+            Some(self.stats())
+        } else {
+            None
+        }
+    }
+}
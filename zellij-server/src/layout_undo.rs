@@ -0,0 +1,64 @@
+//! Closing a pane or a tab by accident (or switching the layout of a tab) loses the layout the
+//! user arranged. To be able to undo this, the screen keeps a bounded stack of these changes: the
+//! closed pane or tab itself is kept, along with the position and size of the tiled panes right
+//! before the change. The process that ran in a closed terminal pane cannot be brought back, so
+//! these panes are restored in their place holding their command (to be started again from the
+//! pane), while the plugins of closed plugin panes are loaded again in their place.
+
+use std::collections::VecDeque;
+
+use zellij_utils::data::PaneId;
+use zellij_utils::pane_size::PaneGeom;
+
+use crate::tab::{Pane, Tab};
+
+pub const DEFAULT_LAYOUT_UNDO_DEPTH: usize = 10;
+
+pub enum LayoutChange {
+    ClosedPane {
+        tab_index: usize,
+        pane: Box<dyn Pane>,
+        is_floating: bool,
+        tiled_pane_geoms: Vec<(PaneId, PaneGeom)>, // before the pane was closed
+    },
+    ClosedTab(Box<Tab>),
+    /// The tiled panes of the tab were arranged differently (eg. by switching its swap layout)
+    RearrangedPanes {
+        tab_index: usize,
+        tiled_pane_geoms: Vec<(PaneId, PaneGeom)>,
+    },
+}
+
+pub struct LayoutUndoStack {
+    max_depth: usize,
+    changes: VecDeque<LayoutChange>,
+}
+
+impl LayoutUndoStack {
+    pub fn new(max_depth: usize) -> Self {
+        LayoutUndoStack {
+            max_depth,
+            changes: VecDeque::new(),
+        }
+    }
+    /// Records a change, dropping the oldest one if there are more than can be undone
+    pub fn push(&mut self, change: LayoutChange) {
+        self.changes.push_back(change);
+        while self.changes.len() > self.max_depth {
+            self.changes.pop_front();
+        }
+    }
+    /// Takes the latest change, to be undone
+    pub fn pop(&mut self) -> Option<LayoutChange> {
+        self.changes.pop_back()
+    }
+    /// Takes the latest change if it is closing a pane in the tab with this index
+    pub fn pop_closed_pane_in_tab(&mut self, index: usize) -> Option<LayoutChange> {
+        match self.changes.back() {
+            Some(LayoutChange::ClosedPane { tab_index, .. }) if *tab_index == index => {
+                self.changes.pop_back()
+            },
+            _ => None,
+        }
+    }
+}
@@ -4,13 +4,18 @@ pub mod panes;
 pub mod tab;
 
 mod background_jobs;
+mod input_latency;
+mod layout_undo;
 mod logging_pipe;
 mod plugins;
+mod pop_out_window;
 mod pty;
 mod pty_writer;
 mod route;
 mod screen;
+mod session_activity;
 mod session_layout_metadata;
+mod session_listing;
 mod terminal_bytes;
 mod thread_bus;
 mod ui;
@@ -23,6 +28,7 @@ use std::{
     path::PathBuf,
     sync::{Arc, RwLock},
     thread,
+    time::{Duration, Instant},
 };
 use zellij_utils::envs;
 use zellij_utils::nix::sys::stat::{umask, Mode};
@@ -32,17 +38,21 @@ use wasmer::Store;
 
 use crate::{
     os_input_output::ServerOsApi,
-    plugins::{plugin_thread_main, PluginInstruction},
+    plugins::{plugin_thread_main, PluginInstruction, SystemClock},
     pty::{get_default_shell, pty_thread_main, Pty, PtyInstruction},
     screen::{screen_thread_main, ScreenInstruction},
+    session_listing::rename_session,
     thread_bus::{Bus, ThreadSenders},
 };
 use route::route_thread_main;
 use zellij_utils::{
     channels::{self, ChannelWithContext, SenderWithContext},
     cli::CliArgs,
-    consts::{DEFAULT_SCROLL_BUFFER_SIZE, SCROLL_BUFFER_SIZE},
-    data::{ConnectToSession, Event, PluginCapabilities},
+    consts::{
+        session_info_folder_for_session, DEFAULT_SCROLL_BUFFER_SIZE, SCROLL_BUFFER_SIZE,
+        ZELLIJ_SOCK_DIR,
+    },
+    data::{ConnectToSession, Event, Palette, PluginCapabilities},
     errors::{prelude::*, ContextType, ErrorInstruction, FatalError, ServerContext},
     home::get_default_data_dir,
     input::{
@@ -52,7 +62,7 @@ use zellij_utils::{
         options::Options,
         plugins::PluginsConfig,
     },
-    ipc::{ClientAttributes, ExitReason, ServerToClientMsg},
+    ipc::{ClientAttributes, ExitReason, ServerToClientMsg, SessionListing},
 };
 
 pub type ClientId = u16;
@@ -80,12 +90,23 @@ pub enum ServerInstruction {
         Options,
         Option<usize>,       // tab position to focus
         Option<(u32, bool)>, // (pane_id, is_plugin) => pane_id to focus
+        bool,                // view only the pane to focus, popped out of its tab
+        bool,                // attach as an observer that can't send input
         ClientId,
     ),
     ConnStatus(ClientId),
     ActiveClients(ClientId),
     Log(Vec<String>, ClientId),
     SwitchSession(ConnectToSession, ClientId),
+    ListSessions(Vec<SessionListing>, ClientId),
+    DetachIdleClients(Duration),     // idle timeout
+    RenameSession(String, ClientId), // String -> new session name
+    ForceFullRender(ClientId),       // render everything this client sees from scratch
+    SwitchWorkspace(String),         // String -> workspace name, created if it does not exist
+    // render what this client sees with its own palette rather than the session's
+    SetClientTheme(ClientId, Palette),
+    // open a pane mirroring the terminal pane with this id in the active tab of the client
+    AttachToPane(u32, ClientId),
 }
 
 impl From<&ServerInstruction> for ServerContext {
@@ -104,6 +125,13 @@ impl From<&ServerInstruction> for ServerContext {
             ServerInstruction::ActiveClients(_) => ServerContext::ActiveClients,
             ServerInstruction::Log(..) => ServerContext::Log,
             ServerInstruction::SwitchSession(..) => ServerContext::SwitchSession,
+            ServerInstruction::ListSessions(..) => ServerContext::ListSessions,
+            ServerInstruction::DetachIdleClients(..) => ServerContext::DetachIdleClients,
+            ServerInstruction::RenameSession(..) => ServerContext::RenameSession,
+            ServerInstruction::ForceFullRender(..) => ServerContext::ForceFullRender,
+            ServerInstruction::SwitchWorkspace(..) => ServerContext::SwitchWorkspace,
+            ServerInstruction::SetClientTheme(..) => ServerContext::SetClientTheme,
+            ServerInstruction::AttachToPane(..) => ServerContext::AttachToPane,
         }
     }
 }
@@ -186,12 +214,16 @@ macro_rules! send_to_client {
 #[derive(Clone, Debug, PartialEq)]
 pub(crate) struct SessionState {
     clients: HashMap<ClientId, Option<Size>>,
+    observers: HashSet<ClientId>, // clients that see the session but can't send it input
+    last_input: HashMap<ClientId, Instant>,
 }
 
 impl SessionState {
     pub fn new() -> Self {
         SessionState {
             clients: HashMap::new(),
+            observers: HashSet::new(),
+            last_input: HashMap::new(),
         }
     }
     pub fn new_client(&mut self) -> ClientId {
@@ -205,27 +237,59 @@ impl SessionState {
             }
         }
         self.clients.insert(next_client_id, None);
+        self.last_input.insert(next_client_id, Instant::now());
         next_client_id
     }
     pub fn remove_client(&mut self, client_id: ClientId) {
         self.clients.remove(&client_id);
+        self.observers.remove(&client_id);
+        self.last_input.remove(&client_id);
+    }
+    pub fn record_client_input(&mut self, client_id: ClientId, at: Instant) {
+        if self.clients.contains_key(&client_id) {
+            self.last_input.insert(client_id, at);
+        }
+    }
+    pub fn idle_clients(&self, idle_timeout: Duration, now: Instant) -> Vec<ClientId> {
+        // observers can't send input, so they are never considered idle
+        let mut idle_clients: Vec<ClientId> = self
+            .last_input
+            .iter()
+            .filter(|(client_id, _)| !self.observers.contains(client_id))
+            .filter(|(_, last_input)| now.saturating_duration_since(**last_input) >= idle_timeout)
+            .map(|(client_id, _)| *client_id)
+            .collect();
+        idle_clients.sort_unstable();
+        idle_clients
+    }
+    pub fn set_client_observer(&mut self, client_id: ClientId) {
+        self.observers.insert(client_id);
+    }
+    pub fn is_observer(&self, client_id: ClientId) -> bool {
+        self.observers.contains(&client_id)
     }
     pub fn set_client_size(&mut self, client_id: ClientId, size: Size) {
         self.clients.insert(client_id, Some(size));
     }
     pub fn min_client_terminal_size(&self) -> Option<Size> {
         // None if there are no client sizes
-        let mut rows: Vec<usize> = self
+        // observers don't get to shrink the session for the clients using it, unless they're all
+        // that is left
+        let has_sized_participants = self
             .clients
-            .values()
-            .filter_map(|size| size.map(|size| size.rows))
-            .collect();
+            .iter()
+            .any(|(client_id, size)| size.is_some() && !self.observers.contains(client_id));
+        let client_sizes = || {
+            self.clients
+                .iter()
+                .filter(|(client_id, _)| {
+                    !has_sized_participants || !self.observers.contains(client_id)
+                })
+                .filter_map(|(_, size)| *size)
+        };
+        let mut rows: Vec<usize> = client_sizes().map(|size| size.rows).collect();
         rows.sort_unstable();
-        let mut cols: Vec<usize> = self
-            .clients
-            .values()
-            .filter_map(|size| size.map(|size| size.cols))
-            .collect();
+        let mut cols: Vec<usize> = client_sizes().map(|size| size.cols).collect();
         cols.sort_unstable();
         let min_rows = rows.first();
         let min_cols = cols.first();
@@ -358,6 +422,26 @@ pub fn start_server(mut os_input: Box<dyn ServerOsApi>, socket_path: PathBuf) {
                     })
                 });
                 let cwd = config_options.default_cwd;
+                // a zero timeout (eg. from the command line) would detach every client right away
+                if let Some(idle_detach_timeout) = config_options
+                    .idle_detach_timeout
+                    .filter(|idle_detach_timeout| *idle_detach_timeout > 0)
+                {
+                    session_data
+                        .read()
+                        .unwrap()
+                        .as_ref()
+                        .unwrap()
+                        .senders
+                        .send_to_background_jobs(BackgroundJob::WatchIdleClients(
+                            Duration::from_secs(idle_detach_timeout),
+                        ))
+                        .unwrap();
+                }
+
+                for layout_warning in layout.validate() {
+                    log::warn!("Layout warning: {}", layout_warning);
+                }
 
                 let spawn_tabs = |tab_layout, floating_panes_layout, tab_name, swap_layouts| {
                     session_data
@@ -423,12 +507,27 @@ pub fn start_server(mut os_input: Box<dyn ServerOsApi>, socket_path: PathBuf) {
                     .senders
                     .send_to_plugin(PluginInstruction::AddClient(client_id))
                     .unwrap();
+                for run_plugin in &layout.background_plugins {
+                    session_data
+                        .read()
+                        .unwrap()
+                        .as_ref()
+                        .unwrap()
+                        .senders
+                        .send_to_plugin(PluginInstruction::LoadBackgroundPlugin(
+                            run_plugin.clone(),
+                            client_id,
+                        ))
+                        .unwrap();
+                }
             },
             ServerInstruction::AttachClient(
                 attrs,
                 options,
                 tab_position_to_focus,
                 pane_id_to_focus,
+                is_pane_view,
+                is_observer,
                 client_id,
             ) => {
                 let rlock = session_data.read().unwrap();
@@ -437,6 +536,12 @@ pub fn start_server(mut os_input: Box<dyn ServerOsApi>, socket_path: PathBuf) {
                     .write()
                     .unwrap()
                     .set_client_size(client_id, attrs.size);
+                if is_observer {
+                    session_state
+                        .write()
+                        .unwrap()
+                        .set_client_observer(client_id);
+                }
                 let min_size = session_state
                     .read()
                     .unwrap()
@@ -452,6 +557,8 @@ pub fn start_server(mut os_input: Box<dyn ServerOsApi>, socket_path: PathBuf) {
                         client_id,
                         tab_position_to_focus,
                         pane_id_to_focus,
+                        is_pane_view,
+                        is_observer,
                     ))
                     .unwrap();
                 session_data
@@ -479,6 +586,54 @@ pub fn start_server(mut os_input: Box<dyn ServerOsApi>, socket_path: PathBuf) {
                     ServerToClientMsg::SwitchToMode(mode),
                     session_state
                 );
+                // the client might have what it last saw of the session (or what another client
+                // with the same id saw) on screen, so we don't rely on rendering only the changes
+                session_data
+                    .senders
+                    .send_to_server(ServerInstruction::ForceFullRender(client_id))
+                    .unwrap();
+            },
+            ServerInstruction::ForceFullRender(client_id) => {
+                let rlock = session_data.read().unwrap();
+                let session_data = rlock.as_ref().unwrap();
+                session_data
+                    .senders
+                    .send_to_screen(ScreenInstruction::ForceFullRender(client_id))
+                    .unwrap();
+                session_data
+                    .senders
+                    .send_to_plugin(PluginInstruction::RerenderPlugins(client_id))
+                    .unwrap();
+            },
+            ServerInstruction::SwitchWorkspace(workspace_name) => {
+                session_data
+                    .read()
+                    .unwrap()
+                    .as_ref()
+                    .unwrap()
+                    .senders
+                    .send_to_screen(ScreenInstruction::SwitchWorkspace(workspace_name))
+                    .unwrap();
+            },
+            ServerInstruction::SetClientTheme(client_id, theme) => {
+                session_data
+                    .read()
+                    .unwrap()
+                    .as_ref()
+                    .unwrap()
+                    .senders
+                    .send_to_screen(ScreenInstruction::SetClientTheme(client_id, theme))
+                    .unwrap();
+            },
+            ServerInstruction::AttachToPane(terminal_id, client_id) => {
+                session_data
+                    .read()
+                    .unwrap()
+                    .as_ref()
+                    .unwrap()
+                    .senders
+                    .send_to_pty(PtyInstruction::SpawnPaneAlias(terminal_id, client_id))
+                    .unwrap();
             },
             ServerInstruction::UnblockInputThread => {
                 for client_id in session_state.read().unwrap().clients.keys() {
@@ -564,37 +719,58 @@ pub fn start_server(mut os_input: Box<dyn ServerOsApi>, socket_path: PathBuf) {
                 break;
             },
             ServerInstruction::DetachSession(client_ids) => {
-                for client_id in client_ids {
-                    let _ = os_input
-                        .send_to_client(client_id, ServerToClientMsg::Exit(ExitReason::Normal));
-                    remove_client!(client_id, os_input, session_state);
-                    if let Some(min_size) = session_state.read().unwrap().min_client_terminal_size()
-                    {
+                detach_clients(client_ids, &mut os_input, &session_state, &session_data);
+            },
+            ServerInstruction::DetachIdleClients(idle_timeout) => {
+                let idle_client_ids = session_state
+                    .read()
+                    .unwrap()
+                    .idle_clients(idle_timeout, Instant::now());
+                if !idle_client_ids.is_empty() {
+                    log::info!(
+                        "Detaching clients {:?}, idle for more than {:?}",
+                        idle_client_ids,
+                        idle_timeout
+                    );
+                    detach_clients(
+                        idle_client_ids,
+                        &mut os_input,
+                        &session_state,
+                        &session_data,
+                    );
+                }
+            },
+            ServerInstruction::RenameSession(new_session_name, client_id) => {
+                let old_session_name = envs::get_session_name().unwrap_or_default();
+                match rename_session(
+                    &*ZELLIJ_SOCK_DIR,
+                    session_info_folder_for_session,
+                    &old_session_name,
+                    &new_session_name,
+                ) {
+                    Ok(()) => {
+                        // panes opened from now on get the new name, processes that are already
+                        // running keep the environment they were started with
+                        envs::set_session_name(new_session_name.clone());
                         session_data
-                            .write()
+                            .read()
                             .unwrap()
                             .as_ref()
                             .unwrap()
                             .senders
-                            .send_to_screen(ScreenInstruction::TerminalResize(min_size))
+                            .send_to_screen(ScreenInstruction::SessionRenamed(new_session_name))
                             .unwrap();
-                    }
-                    session_data
-                        .write()
-                        .unwrap()
-                        .as_ref()
-                        .unwrap()
-                        .senders
-                        .send_to_screen(ScreenInstruction::RemoveClient(client_id))
-                        .unwrap();
-                    session_data
-                        .write()
-                        .unwrap()
-                        .as_ref()
-                        .unwrap()
-                        .senders
-                        .send_to_plugin(PluginInstruction::RemoveClient(client_id))
-                        .unwrap();
+                    },
+                    Err(e) => {
+                        let error_text = format!("{}", e.root_cause());
+                        Err::<(), _>(e).non_fatal();
+                        send_to_client!(
+                            client_id,
+                            os_input,
+                            ServerToClientMsg::LogError(vec![error_text]),
+                            session_state
+                        );
+                    },
                 }
             },
             ServerInstruction::Render(serialized_output) => {
@@ -637,6 +813,11 @@ pub fn start_server(mut os_input: Box<dyn ServerOsApi>, socket_path: PathBuf) {
                 let _ = os_input.send_to_client(client_id, ServerToClientMsg::Connected);
                 remove_client!(client_id, os_input, session_state);
             },
+            ServerInstruction::ListSessions(session_list, client_id) => {
+                let _ = os_input
+                    .send_to_client(client_id, ServerToClientMsg::SessionList(session_list));
+                remove_client!(client_id, os_input, session_state);
+            },
             ServerInstruction::ActiveClients(client_id) => {
                 let client_ids = session_state.read().unwrap().client_ids();
                 send_to_client!(
@@ -698,6 +879,44 @@ pub fn start_server(mut os_input: Box<dyn ServerOsApi>, socket_path: PathBuf) {
     drop(std::fs::remove_file(&socket_path));
 }
 
+fn detach_clients(
+    client_ids: Vec<ClientId>,
+    os_input: &mut Box<dyn ServerOsApi>,
+    session_state: &Arc<RwLock<SessionState>>,
+    session_data: &Arc<RwLock<Option<SessionMetaData>>>,
+) {
+    for client_id in client_ids {
+        let _ = os_input.send_to_client(client_id, ServerToClientMsg::Exit(ExitReason::Normal));
+        remove_client!(client_id, os_input, session_state);
+        if let Some(min_size) = session_state.read().unwrap().min_client_terminal_size() {
+            session_data
+                .write()
+                .unwrap()
+                .as_ref()
+                .unwrap()
+                .senders
+                .send_to_screen(ScreenInstruction::TerminalResize(min_size))
+                .unwrap();
+        }
+        session_data
+            .write()
+            .unwrap()
+            .as_ref()
+            .unwrap()
+            .senders
+            .send_to_screen(ScreenInstruction::RemoveClient(client_id))
+            .unwrap();
+        session_data
+            .write()
+            .unwrap()
+            .as_ref()
+            .unwrap()
+            .senders
+            .send_to_plugin(PluginInstruction::RemoveClient(client_id))
+            .unwrap();
+    }
+}
+
 pub struct SessionOptions {
     pub opts: Box<CliArgs>,
     pub config_options: Box<Options>,
@@ -744,6 +963,9 @@ fn init_session(
         channels::unbounded();
     let to_background_jobs = SenderWithContext::new(to_background_jobs);
 
+    // the panes of the session start with these on top of the environment of the server
+    os_input.set_session_env(opts.session_env.iter().cloned().collect());
+
     // Determine and initialize the data directory
     let data_dir = opts.data_dir.unwrap_or_else(get_default_data_dir);
 
@@ -752,6 +974,8 @@ fn init_session(
     };
 
     let serialization_interval = config_options.serialization_interval;
+    let plugin_metrics_interval = config_options.plugin_metrics_interval;
+    let paste_command = config_options.paste_command.clone();
 
     let default_shell = config_options.default_shell.clone().map(|command| {
         TerminalAction::RunCommand(RunCommand {
@@ -851,6 +1075,9 @@ fn init_session(
                     capabilities,
                     client_attributes,
                     default_shell,
+                    Arc::new(SystemClock),
+                    plugin_metrics_interval,
+                    paste_command,
                 )
                 .fatal()
             }
@@ -915,13 +1142,14 @@ fn init_session(
 
 #[cfg(not(feature = "singlepass"))]
 fn get_store() -> Store {
-    use wasmer::{BaseTunables, Cranelift, Engine, Pages, Target};
+    use wasmer::{BaseTunables, CompilerConfig, Cranelift, Engine, Pages, Target};
     log::info!("Compiling plugins using Cranelift");
 
     // workaround for https://github.com/bytecodealliance/wasmtime/security/advisories/GHSA-ff4p-7xrq-q5r8
     let mut tunables = BaseTunables::for_target(&Target::default());
     tunables.static_memory_bound = Pages(0);
-    let compiler = Cranelift::default();
+    let mut compiler = Cranelift::default();
+    compiler.push_middleware(plugins::metering_middleware());
     let mut engine: Engine = compiler.into();
     engine.set_tunables(tunables);
 
@@ -930,6 +1158,14 @@ fn get_store() -> Store {
 
 #[cfg(feature = "singlepass")]
 fn get_store() -> Store {
+    use wasmer::CompilerConfig;
     log::info!("Compiling plugins using Singlepass");
-    Store::new(wasmer::Singlepass::default())
+
+    let mut compiler = wasmer::Singlepass::default();
+    compiler.push_middleware(plugins::metering_middleware());
+    Store::new(compiler)
 }
+
+#[cfg(test)]
+#[path = "./unit/session_state_tests.rs"]
+mod session_state_tests;
@@ -1,9 +1,13 @@
 use std::{
-    collections::VecDeque,
+    collections::{BTreeMap, VecDeque},
+    fs::{File, OpenOptions},
     io::{Read, Seek, Write},
+    path::{Component, Path, PathBuf},
+    sync::{Arc, Mutex},
 };
 
 use crate::plugins::PluginId;
+use crate::{screen::ScreenInstruction, thread_bus::ThreadSenders, ClientId};
 use log::{debug, error};
 use wasmer_wasi::{WasiFile, WasiFsError};
 use zellij_utils::{errors::prelude::*, serde};
@@ -13,12 +17,159 @@ use serde::{Deserialize, Serialize};
 
 // 16kB log buffer
 const ZELLIJ_MAX_PIPE_BUFFER_SIZE: usize = 16_384;
+
+/// Where the log messages of a plugin (its STDERR and the `log_message` host call) are sent.
+///
+/// This is set when the plugin is loaded, from the `log_to` key of its configuration in the
+/// layout: `"server"` (the default), `"pane"` or `"file"` (in which case `log_file` holds the
+/// path, relative to the plugin's data directory - the one it sees as `/data`). A `log_file`
+/// reaching outside of that directory (or that cannot be opened) is ignored in favor of the
+/// server log.
+#[derive(Clone)]
+pub enum LoggingSink {
+    ServerLog,
+    File(PathBuf, Arc<Mutex<File>>), // the file is opened once, when the plugin is loaded
+    Pane(ThreadSenders, ClientId),   // shown over the bottom rows of the plugin's pane
+}
+
+impl Default for LoggingSink {
+    fn default() -> Self {
+        LoggingSink::ServerLog
+    }
+}
+
+impl std::fmt::Debug for LoggingSink {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LoggingSink::ServerLog => write!(f, "ServerLog"),
+            LoggingSink::File(path, _) => write!(f, "File({:?})", path),
+            LoggingSink::Pane(_, client_id) => write!(f, "Pane({})", client_id),
+        }
+    }
+}
+
+impl LoggingSink {
+    pub fn from_configuration(
+        configuration: &BTreeMap<String, String>,
+        plugin_data_dir: &Path,
+        senders: ThreadSenders,
+        client_id: ClientId,
+    ) -> Self {
+        match configuration.get("log_to").map(|l| l.as_str()) {
+            None | Some("server") => LoggingSink::ServerLog,
+            Some("pane") => LoggingSink::Pane(senders, client_id),
+            Some("file") => match configuration.get("log_file") {
+                Some(log_file) => match log_file_path(plugin_data_dir, log_file) {
+                    Some(log_file_path) => match OpenOptions::new()
+                        .create(true)
+                        .append(true)
+                        .open(&log_file_path)
+                    {
+                        Ok(log_file) => {
+                            LoggingSink::File(log_file_path, Arc::new(Mutex::new(log_file)))
+                        },
+                        Err(e) => {
+                            error!(
+                                "Failed to open plugin log_file '{}': {}, using the server log",
+                                log_file, e
+                            );
+                            LoggingSink::ServerLog
+                        },
+                    },
+                    None => {
+                        error!(
+                            "Plugin log_file '{}' is outside of its data directory, using the server log",
+                            log_file
+                        );
+                        LoggingSink::ServerLog
+                    },
+                },
+                None => {
+                    error!("Plugin set to log to a file without a log_file, using the server log");
+                    LoggingSink::ServerLog
+                },
+            },
+            Some(unknown) => {
+                error!("Unknown plugin log_to '{}', using the server log", unknown);
+                LoggingSink::ServerLog
+            },
+        }
+    }
+
+    pub fn log(&self, plugin_name: &str, plugin_id: PluginId, message: &str) {
+        match self {
+            LoggingSink::ServerLog => {
+                debug!(
+                    "|{:<25.25}| {} [{:<10.15}] {}",
+                    plugin_name,
+                    Local::now().format("%Y-%m-%d %H:%M:%S.%3f"),
+                    format!("id: {}", plugin_id),
+                    message
+                );
+            },
+            LoggingSink::File(path, file) => {
+                let mut file = file.lock().unwrap();
+                writeln!(
+                    file,
+                    "{} {}",
+                    Local::now().format("%Y-%m-%d %H:%M:%S.%3f"),
+                    message
+                )
+                .with_context(|| {
+                    format!(
+                        "failed to write log message of plugin {} to {}",
+                        plugin_name,
+                        path.display()
+                    )
+                })
+                .non_fatal();
+            },
+            LoggingSink::Pane(senders, client_id) => {
+                senders
+                    .send_to_screen(ScreenInstruction::PluginLogLine(
+                        plugin_id,
+                        *client_id,
+                        message.to_owned(),
+                    ))
+                    .with_context(|| {
+                        format!(
+                            "failed to send log message of plugin {} to its pane",
+                            plugin_name
+                        )
+                    })
+                    .non_fatal();
+            },
+        }
+    }
+}
+
+// the log file has to be a relative path within the data directory, its parent directory is
+// canonicalized so that a symlink cannot point it elsewhere
+fn log_file_path(plugin_data_dir: &Path, log_file: &str) -> Option<PathBuf> {
+    let log_file = Path::new(log_file);
+    let is_contained = log_file
+        .components()
+        .all(|component| matches!(component, Component::Normal(_)));
+    if !is_contained {
+        return None;
+    }
+    let log_file_path = plugin_data_dir.join(log_file);
+    let plugin_data_dir = plugin_data_dir.canonicalize().ok()?;
+    let log_file_dir = log_file_path.parent()?.canonicalize().ok()?;
+    if !log_file_dir.starts_with(&plugin_data_dir) {
+        return None;
+    }
+    Some(log_file_dir.join(log_file_path.file_name()?))
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(crate = "self::serde")]
 pub struct LoggingPipe {
     buffer: VecDeque<u8>,
     plugin_name: String,
     plugin_id: PluginId,
+    #[serde(skip)]
+    sink: LoggingSink,
 }
 
 impl LoggingPipe {
@@ -27,17 +178,17 @@ impl LoggingPipe {
             buffer: VecDeque::new(),
             plugin_name: String::from(plugin_name),
             plugin_id,
+            sink: LoggingSink::default(),
         }
     }
 
+    pub fn with_sink(mut self, sink: LoggingSink) -> Self {
+        self.sink = sink;
+        self
+    }
+
     fn log_message(&self, message: &str) {
-        debug!(
-            "|{:<25.25}| {} [{:<10.15}] {}",
-            self.plugin_name,
-            Local::now().format("%Y-%m-%d %H:%M:%S.%3f"),
-            format!("id: {}", self.plugin_id),
-            message
-        );
+        self.sink.log(&self.plugin_name, self.plugin_id, message);
     }
 }
 
@@ -250,4 +401,90 @@ mod logging_pipe_test {
 
         assert_eq!(pipe.buffer.len(), 0);
     }
+
+    #[test]
+    fn flushed_lines_are_appended_to_the_configured_log_file() {
+        let temp_dir = tempfile::tempdir().expect("Err tempdir");
+        let mut configuration = BTreeMap::new();
+        configuration.insert("log_to".to_owned(), "file".to_owned());
+        configuration.insert("log_file".to_owned(), "plugin.log".to_owned());
+        let sink = LoggingSink::from_configuration(
+            &configuration,
+            temp_dir.path(),
+            ThreadSenders::default(),
+            1,
+        );
+        let mut pipe = LoggingPipe::new("TestPipe", 0).with_sink(sink);
+
+        pipe.write_all(b"first message\nsecond message\n")
+            .expect("Err write");
+        pipe.flush().expect("Err flush");
+
+        let logged = std::fs::read_to_string(temp_dir.path().join("plugin.log")).expect("Err read");
+        let logged_lines: Vec<&str> = logged.lines().collect();
+        assert_eq!(logged_lines.len(), 2);
+        assert!(logged_lines[0].ends_with(" first message"));
+        assert!(logged_lines[1].ends_with(" second message"));
+    }
+
+    #[test]
+    fn the_log_file_is_kept_open_between_messages() {
+        let temp_dir = tempfile::tempdir().expect("Err tempdir");
+        let mut configuration = BTreeMap::new();
+        configuration.insert("log_to".to_owned(), "file".to_owned());
+        configuration.insert("log_file".to_owned(), "plugin.log".to_owned());
+        let sink = LoggingSink::from_configuration(
+            &configuration,
+            temp_dir.path(),
+            ThreadSenders::default(),
+            1,
+        );
+        let mut pipe = LoggingPipe::new("TestPipe", 0).with_sink(sink);
+
+        pipe.write_all(b"first message\n").expect("Err write");
+        pipe.flush().expect("Err flush");
+        // messages keep going to the file that was opened, even if it was moved in the meantime
+        std::fs::rename(
+            temp_dir.path().join("plugin.log"),
+            temp_dir.path().join("rotated.log"),
+        )
+        .expect("Err rename");
+        pipe.write_all(b"second message\n").expect("Err write");
+        pipe.flush().expect("Err flush");
+
+        let logged =
+            std::fs::read_to_string(temp_dir.path().join("rotated.log")).expect("Err read");
+        assert_eq!(logged.lines().count(), 2);
+        assert!(
+            !temp_dir.path().join("plugin.log").exists(),
+            "log file was not reopened"
+        );
+    }
+
+    #[test]
+    fn log_files_outside_of_the_data_directory_fall_back_to_the_server_log() {
+        let temp_dir = tempfile::tempdir().expect("Err tempdir");
+        let plugin_data_dir = temp_dir.path().join("data");
+        std::fs::create_dir(&plugin_data_dir).expect("Err create dir");
+        let outside_log_file = temp_dir.path().join("outside.log");
+        for log_file in [
+            "../outside.log".to_owned(),
+            outside_log_file.display().to_string(),
+        ] {
+            let mut configuration = BTreeMap::new();
+            configuration.insert("log_to".to_owned(), "file".to_owned());
+            configuration.insert("log_file".to_owned(), log_file.clone());
+            let sink = LoggingSink::from_configuration(
+                &configuration,
+                &plugin_data_dir,
+                ThreadSenders::default(),
+                1,
+            );
+            assert!(
+                matches!(sink, LoggingSink::ServerLog),
+                "{} was not used as the log file",
+                log_file
+            );
+        }
+    }
 }
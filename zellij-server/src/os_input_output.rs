@@ -153,6 +153,7 @@ fn command_exists(cmd: &RunCommand) -> bool {
 fn handle_openpty(
     open_pty_res: OpenptyResult,
     cmd: RunCommand,
+    session_env: &BTreeMap<String, String>,
     quit_cb: Box<dyn Fn(PaneId, Option<i32>, RunCommand) + Send>, // u32 is the exit status
     terminal_id: u32,
 ) -> Result<(RawFd, RawFd)> {
@@ -183,6 +184,8 @@ fn handle_openpty(
             }
             command
                 .args(&cmd.args)
+                .envs(session_env)
+                .envs(&cmd.env)
                 .env("ZELLIJ_PANE_ID", &format!("{}", terminal_id))
                 .pre_exec(move || -> std::io::Result<()> {
                     if libc::login_tty(pid_secondary) != 0 {
@@ -221,6 +224,7 @@ fn handle_openpty(
 fn handle_terminal(
     cmd: RunCommand,
     failover_cmd: Option<RunCommand>,
+    session_env: &BTreeMap<String, String>,
     orig_termios: termios::Termios,
     quit_cb: Box<dyn Fn(PaneId, Option<i32>, RunCommand) + Send>,
     terminal_id: u32,
@@ -230,12 +234,17 @@ fn handle_terminal(
     // Create a pipe to allow the child the communicate the shell's pid to its
     // parent.
     match openpty(None, Some(&orig_termios)) {
-        Ok(open_pty_res) => handle_openpty(open_pty_res, cmd, quit_cb, terminal_id),
+        Ok(open_pty_res) => handle_openpty(open_pty_res, cmd, session_env, quit_cb, terminal_id),
         Err(e) => match failover_cmd {
-            Some(failover_cmd) => {
-                handle_terminal(failover_cmd, None, orig_termios, quit_cb, terminal_id)
-                    .with_context(err_context)
-            },
+            Some(failover_cmd) => handle_terminal(
+                failover_cmd,
+                None,
+                session_env,
+                orig_termios,
+                quit_cb,
+                terminal_id,
+            )
+            .with_context(err_context),
             None => Err::<(i32, i32), _>(e)
                 .context("failed to start pty")
                 .with_context(err_context)
@@ -280,6 +289,7 @@ fn separate_command_arguments(command: &mut PathBuf, args: &mut Vec<String>) {
 fn spawn_terminal(
     terminal_action: TerminalAction,
     orig_termios: termios::Termios,
+    session_env: &BTreeMap<String, String>,
     quit_cb: Box<dyn Fn(PaneId, Option<i32>, RunCommand) + Send>, // u32 is the exit_status
     default_editor: Option<PathBuf>,
     terminal_id: u32,
@@ -337,6 +347,7 @@ fn spawn_terminal(
                 cwd,
                 hold_on_close: false,
                 hold_on_start: false,
+                env: BTreeMap::new(),
             }
         },
         TerminalAction::RunCommand(command) => command,
@@ -349,7 +360,14 @@ fn spawn_terminal(
         None
     };
 
-    handle_terminal(cmd, failover_cmd, orig_termios, quit_cb, terminal_id)
+    handle_terminal(
+        cmd,
+        failover_cmd,
+        session_env,
+        orig_termios,
+        quit_cb,
+        terminal_id,
+    )
 }
 
 // The ClientSender is in charge of sending messages to the client on a special thread
@@ -426,6 +444,7 @@ pub struct ServerOsInputOutput {
     // a command pane with a
     // non-existing command)
     cached_resizes: Arc<Mutex<Option<BTreeMap<u32, (u16, u16, Option<u16>, Option<u16>)>>>>, // <terminal_id, (cols, rows, width_in_pixels, height_in_pixels)>
+    session_env: Arc<Mutex<BTreeMap<String, String>>>,
 }
 
 // async fn in traits is not supported by rust, so dtolnay's excellent async_trait macro is being
@@ -480,6 +499,14 @@ pub trait ServerOsApi: Send + Sync {
     fn reserve_terminal_id(&self) -> Result<u32> {
         unimplemented!()
     }
+    // reserves these terminal ids again (eg. for panes brought back after being closed), failing
+    // without reserving any of them if one was taken in the meantime
+    fn reserve_terminal_ids(&self, terminal_ids: &[u32]) -> Result<()> {
+        Err(anyhow!(
+            "cannot reserve terminal ids {:?}, not supported",
+            terminal_ids
+        ))
+    }
     /// Read bytes from the standard output of the virtual terminal referred to by `fd`.
     fn read_from_tty_stdout(&self, fd: RawFd, buf: &mut [u8]) -> Result<usize>;
     /// Creates an `AsyncReader` that can be used to read from `fd` in an async context
@@ -524,6 +551,13 @@ pub trait ServerOsApi: Send + Sync {
     fn clear_terminal_id(&self, terminal_id: u32) -> Result<()>;
     fn cache_resizes(&mut self) {}
     fn apply_cached_resizes(&mut self) {}
+    /// Sets the environment variables all terminals spawned from now on start with, the
+    /// environment variables of the command itself take precedence
+    fn set_session_env(&self, _session_env: BTreeMap<String, String>) {}
+    /// Returns the environment variables all spawned terminals start with
+    fn get_session_env(&self) -> BTreeMap<String, String> {
+        BTreeMap::new()
+    }
 }
 
 impl ServerOsApi for ServerOsInputOutput {
@@ -580,6 +614,7 @@ impl ServerOsApi for ServerOsInputOutput {
             .lock()
             .to_anyhow()
             .with_context(err_context)?;
+        let session_env = self.get_session_env();
         let mut terminal_id = None;
         {
             let current_ids: BTreeSet<u32> = self
@@ -602,6 +637,7 @@ impl ServerOsApi for ServerOsInputOutput {
                 spawn_terminal(
                     terminal_action,
                     orig_termios.clone(),
+                    &session_env,
                     quit_cb,
                     default_editor,
                     terminal_id,
@@ -646,6 +682,26 @@ impl ServerOsApi for ServerOsInputOutput {
             None => Err(anyhow!("no more terminal IDs available")),
         }
     }
+    fn reserve_terminal_ids(&self, terminal_ids: &[u32]) -> Result<()> {
+        let err_context = || format!("failed to reserve terminal IDs {terminal_ids:?}");
+
+        let mut terminal_id_to_raw_fd = self
+            .terminal_id_to_raw_fd
+            .lock()
+            .to_anyhow()
+            .with_context(err_context)?;
+        if let Some(taken_terminal_id) = terminal_ids
+            .iter()
+            .find(|terminal_id| terminal_id_to_raw_fd.contains_key(terminal_id))
+        {
+            return Err(anyhow!("terminal ID {taken_terminal_id} is taken"))
+                .with_context(err_context);
+        }
+        for terminal_id in terminal_ids {
+            terminal_id_to_raw_fd.insert(*terminal_id, None);
+        }
+        Ok(())
+    }
     fn read_from_tty_stdout(&self, fd: RawFd, buf: &mut [u8]) -> Result<usize> {
         unistd::read(fd, buf).with_context(|| format!("failed to read stdout of raw FD {}", fd))
     }
@@ -819,6 +875,7 @@ impl ServerOsApi for ServerOsInputOutput {
         quit_cb: Box<dyn Fn(PaneId, Option<i32>, RunCommand) + Send>, // u32 is the exit status
     ) -> Result<(RawFd, RawFd)> {
         let default_editor = None; // no need for a default editor when running an explicit command
+        let session_env = self.get_session_env();
         self.orig_termios
             .lock()
             .to_anyhow()
@@ -826,6 +883,7 @@ impl ServerOsApi for ServerOsInputOutput {
                 spawn_terminal(
                     TerminalAction::RunCommand(run_command),
                     orig_termios.clone(),
+                    &session_env,
                     quit_cb,
                     default_editor,
                     terminal_id,
@@ -869,6 +927,12 @@ impl ServerOsApi for ServerOsInputOutput {
             }
         }
     }
+    fn set_session_env(&self, session_env: BTreeMap<String, String>) {
+        *self.session_env.lock().unwrap() = session_env;
+    }
+    fn get_session_env(&self) -> BTreeMap<String, String> {
+        self.session_env.lock().unwrap().clone()
+    }
 }
 
 impl Clone for Box<dyn ServerOsApi> {
@@ -885,6 +949,7 @@ pub fn get_server_os_input() -> Result<ServerOsInputOutput, nix::Error> {
         client_senders: Arc::new(Mutex::new(HashMap::new())),
         terminal_id_to_raw_fd: Arc::new(Mutex::new(BTreeMap::new())),
         cached_resizes: Arc::new(Mutex::new(None)),
+        session_env: Arc::new(Mutex::new(BTreeMap::new())),
     })
 }
 
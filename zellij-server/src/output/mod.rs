@@ -5,10 +5,11 @@ use crate::panes::Row;
 
 use crate::{
     panes::sixel::SixelImageStore,
-    panes::terminal_character::{AnsiCode, CharacterStyles},
+    panes::terminal_character::{AnsiCode, CharacterStyles, RESET_STYLES},
     panes::{LinkHandler, TerminalCharacter, EMPTY_TERMINAL_CHARACTER},
     ClientId,
 };
+use serde::{Deserialize, Serialize};
 use std::cell::RefCell;
 use std::fmt::Write;
 use std::rc::Rc;
@@ -16,9 +17,101 @@ use std::{
     collections::{HashMap, HashSet},
     str,
 };
+use zellij_utils::data::{Palette, PaletteColor};
 use zellij_utils::errors::prelude::*;
 use zellij_utils::pane_size::PaneGeom;
-use zellij_utils::pane_size::SizeInPixels;
+use zellij_utils::pane_size::{Size, SizeInPixels};
+use zellij_utils::serde;
+
+fn blend(color: (u8, u8, u8), target: (u8, u8, u8), amount: f64) -> (u8, u8, u8) {
+    let amount = amount.clamp(0.0, 1.0);
+    let blend_component =
+        |from: u8, to: u8| (from as f64 * (1.0 - amount) + to as f64 * amount).round() as u8;
+    (
+        blend_component(color.0, target.0),
+        blend_component(color.1, target.1),
+        blend_component(color.2, target.2),
+    )
+}
+
+fn ansi_code_to_rgb(
+    color: AnsiCode,
+    palette: &Palette,
+    changed_colors: &Option<[Option<AnsiCode>; 256]>,
+) -> Option<(u8, u8, u8)> {
+    let index = match color {
+        AnsiCode::RgbCode(rgb) => return Some(rgb),
+        AnsiCode::NamedColor(named_color) => named_color.to_color_index(),
+        AnsiCode::ColorIndex(index) => index,
+        _ => return None,
+    };
+    // the pane might have redefined this color
+    match changed_colors.and_then(|changed_colors| changed_colors[index as usize]) {
+        Some(AnsiCode::RgbCode(rgb)) => Some(rgb),
+        _ => Some(color_index_to_rgb(index, palette)),
+    }
+}
+
+fn palette_color_to_rgb(color: PaletteColor, palette: &Palette) -> (u8, u8, u8) {
+    match color {
+        PaletteColor::Rgb(rgb) => rgb,
+        PaletteColor::EightBit(index) => color_index_to_rgb(index, palette),
+    }
+}
+
+fn color_index_to_rgb(index: u8, palette: &Palette) -> (u8, u8, u8) {
+    // the default xterm colors, used if the theme does not define them
+    const BASIC_COLORS: [(u8, u8, u8); 16] = [
+        (0, 0, 0),
+        (205, 0, 0),
+        (0, 205, 0),
+        (205, 205, 0),
+        (0, 0, 238),
+        (205, 0, 205),
+        (0, 205, 205),
+        (229, 229, 229),
+        (127, 127, 127),
+        (255, 0, 0),
+        (0, 255, 0),
+        (255, 255, 0),
+        (92, 92, 255),
+        (255, 0, 255),
+        (0, 255, 255),
+        (255, 255, 255),
+    ];
+    const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+    match index {
+        0..=7 => {
+            let theme_colors = [
+                palette.black,
+                palette.red,
+                palette.green,
+                palette.yellow,
+                palette.blue,
+                palette.magenta,
+                palette.cyan,
+                palette.white,
+            ];
+            match theme_colors[index as usize] {
+                PaletteColor::Rgb(rgb) => rgb,
+                PaletteColor::EightBit(_) => BASIC_COLORS[index as usize],
+            }
+        },
+        8..=15 => BASIC_COLORS[index as usize],
+        16..=231 => {
+            let index = (index - 16) as usize;
+            (
+                CUBE_LEVELS[index / 36],
+                CUBE_LEVELS[(index / 6) % 6],
+                CUBE_LEVELS[index % 6],
+            )
+        },
+        _ => {
+            let gray = 8 + (index - 232) * 10;
+            (gray, gray, gray)
+        },
+    }
+}
 
 fn vte_goto_instruction(x_coords: usize, y_coords: usize, vte_output: &mut String) -> Result<()> {
     write!(
@@ -453,6 +546,86 @@ impl Output {
         self.client_character_chunks.values().any(|c| !c.is_empty())
             || self.sixel_chunks.values().any(|c| !c.is_empty())
     }
+    /// Lays out the character chunks rendered for this client on a grid of the given size, cells
+    /// that were not rendered are left empty
+    pub fn rendered_grid_for_client(
+        &self,
+        client_id: ClientId,
+        size: Size,
+    ) -> Option<RenderedGrid> {
+        let client_character_chunks = self.client_character_chunks.get(&client_id)?;
+        let empty_cell =
+            RenderedCell::new(&EMPTY_TERMINAL_CHARACTER, EMPTY_TERMINAL_CHARACTER.styles);
+        let mut lines = vec![vec![empty_cell; size.cols]; size.rows];
+        for character_chunk in client_character_chunks {
+            let line = match lines.get_mut(character_chunk.y) {
+                Some(line) => line,
+                None => continue,
+            };
+            let mut x = character_chunk.x;
+            for t_character in &character_chunk.terminal_characters {
+                let styles = adjust_styles_for_possible_selection(
+                    character_chunk.selection_and_colors(),
+                    t_character.styles,
+                    character_chunk.y,
+                    x,
+                );
+                if let Some(cell) = line.get_mut(x) {
+                    *cell = RenderedCell::new(t_character, styles);
+                }
+                x += t_character.width;
+            }
+        }
+        Some(RenderedGrid { lines })
+    }
+}
+
+/// The cells rendered to a client, in a form that can be compared or serialized (eg. for
+/// snapshot tests of rendered output)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(crate = "self::serde")]
+pub struct RenderedGrid {
+    pub lines: Vec<Vec<RenderedCell>>,
+}
+
+impl RenderedGrid {
+    pub fn crop(&self, geom: &PaneGeom) -> RenderedGrid {
+        let lines = self
+            .lines
+            .iter()
+            .skip(geom.y)
+            .take(geom.rows.as_usize())
+            .map(|line| {
+                line.iter()
+                    .skip(geom.x)
+                    .take(geom.cols.as_usize())
+                    .cloned()
+                    .collect()
+            })
+            .collect();
+        RenderedGrid { lines }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(crate = "self::serde")]
+pub struct RenderedCell {
+    pub character: char,
+    pub styles: String, // the VTE instructions to style this cell after a reset, empty if none
+}
+
+impl RenderedCell {
+    fn new(t_character: &TerminalCharacter, styles: CharacterStyles) -> Self {
+        let mut reset_styles = RESET_STYLES;
+        let styles = match reset_styles.update_and_return_diff(&styles, None) {
+            Some(styles_diff) if styles_diff != RESET_STYLES => styles_diff.to_string(),
+            _ => String::new(),
+        };
+        RenderedCell {
+            character: t_character.character,
+            styles,
+        }
+    }
 }
 
 // this struct represents the geometry of a group of floating panes
@@ -796,6 +969,68 @@ impl CharacterChunk {
     pub fn add_changed_colors(&mut self, changed_colors: Option<[Option<AnsiCode>; 256]>) {
         self.changed_colors = changed_colors;
     }
+    /// Replaces spaces, tabs and control characters with dimmed visible substitutes, up to
+    /// `written_width` (the cells after it only pad the line and were never written to). Only the
+    /// rendered characters change, not the pane contents they were read from.
+    pub fn show_invisibles(&mut self, written_width: usize) {
+        let mut previous_character_is_tab_fill = false;
+        let mut width = 0;
+        for terminal_character in self.terminal_characters.iter_mut() {
+            if width >= written_width {
+                break;
+            }
+            width += terminal_character.width;
+            let substitute = match terminal_character.character {
+                // a tab is shown as an arrow at its start followed by empty cells
+                _ if terminal_character.is_tab_fill && previous_character_is_tab_fill => None,
+                _ if terminal_character.is_tab_fill => Some('→'),
+                ' ' => Some('·'),
+                // the Unicode control pictures block mirrors the C0 control characters
+                c @ '\u{0}'..='\u{1f}' => char::from_u32(0x2400 + c as u32),
+                '\u{7f}' => Some('␡'),
+                _ => None,
+            };
+            previous_character_is_tab_fill = terminal_character.is_tab_fill;
+            if let Some(substitute) = substitute {
+                terminal_character.character = substitute;
+                terminal_character.width = 1;
+                terminal_character.styles = terminal_character.styles.dim(Some(AnsiCode::On));
+            }
+        }
+    }
+    /// Blends the colors of the rendered characters towards the background color of the palette
+    /// by `amount` (0.0 keeps them as they are, 1.0 makes them the background color). Only the
+    /// rendered characters change, not the pane contents they were read from.
+    pub fn dim(&mut self, amount: f64, palette: &Palette) {
+        let background = palette_color_to_rgb(palette.bg, palette);
+        // characters without a foreground are drawn in the default color of the terminal, we
+        // can only dim them if we know what it is
+        let default_foreground = match palette.fg {
+            PaletteColor::Rgb(rgb) => Some(rgb),
+            PaletteColor::EightBit(_) => None,
+        };
+        let changed_colors = self.changed_colors;
+        for terminal_character in self.terminal_characters.iter_mut() {
+            let styles = &mut terminal_character.styles;
+            let foreground = match styles.foreground {
+                Some(color) => ansi_code_to_rgb(color, palette, &changed_colors),
+                None => default_foreground,
+            };
+            if let Some(foreground) = foreground {
+                styles.foreground = Some(AnsiCode::RgbCode(blend(foreground, background, amount)));
+            }
+            let pane_background = styles
+                .background
+                .and_then(|color| ansi_code_to_rgb(color, palette, &changed_colors));
+            if let Some(pane_background) = pane_background {
+                styles.background = Some(AnsiCode::RgbCode(blend(
+                    pane_background,
+                    background,
+                    amount,
+                )));
+            }
+        }
+    }
     pub fn changed_colors(&self) -> Option<[Option<AnsiCode>; 256]> {
         self.changed_colors
     }
@@ -934,16 +1169,13 @@ impl OutputBuffer {
         y_offset: usize,
     ) -> Vec<CharacterChunk> {
         if self.should_update_all_lines {
-            let mut changed_chunks = Vec::new();
-            for line_index in 0..viewport_height {
-                let terminal_characters =
-                    self.extract_line_from_viewport(line_index, viewport, viewport_width);
-
-                let x = x_offset; // right now we only buffer full lines as this doesn't seem to have a huge impact on performance, but the infra is here if we want to change this
-                let y = line_index + y_offset;
-                changed_chunks.push(CharacterChunk::new(terminal_characters, x, y));
-            }
-            changed_chunks
+            self.all_chunks_in_viewport(
+                viewport,
+                viewport_width,
+                viewport_height,
+                x_offset,
+                y_offset,
+            )
         } else {
             let mut line_changes: Vec<_> = self.changed_lines.iter().copied().collect();
             line_changes.sort_unstable();
@@ -958,6 +1190,27 @@ impl OutputBuffer {
             changed_chunks
         }
     }
+    /// Every line of the viewport, cropped or padded to `viewport_width` and `viewport_height`,
+    /// whether or not it changed
+    pub fn all_chunks_in_viewport(
+        &self,
+        viewport: &[Row],
+        viewport_width: usize,
+        viewport_height: usize,
+        x_offset: usize,
+        y_offset: usize,
+    ) -> Vec<CharacterChunk> {
+        let mut chunks = Vec::new();
+        for line_index in 0..viewport_height {
+            let terminal_characters =
+                self.extract_line_from_viewport(line_index, viewport, viewport_width);
+
+            let x = x_offset; // right now we only buffer full lines as this doesn't seem to have a huge impact on performance, but the infra is here if we want to change this
+            let y = line_index + y_offset;
+            chunks.push(CharacterChunk::new(terminal_characters, x, y));
+        }
+        chunks
+    }
     fn extract_characters_from_row(
         &self,
         row: &Row,
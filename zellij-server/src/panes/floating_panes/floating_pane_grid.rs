@@ -631,6 +631,35 @@ impl<'a> FloatingPaneGrid<'a> {
         self.set_pane_geom(pane_id, geom).with_context(err_content)
     }
 
+    pub fn resize_pane_to(&mut self, pane_id: PaneId, size: Size) -> Result<()> {
+        let err_context = || format!("failed to resize pane {pane_id:?} to {size:?}");
+
+        let mut geom = self
+            .panes
+            .borrow()
+            .get(&pane_id)
+            .with_context(|| no_pane_id(&pane_id))
+            .with_context(err_context)?
+            .position_and_size();
+
+        let new_cols = size.cols.max(MIN_TERMINAL_WIDTH).min(self.viewport.cols);
+        let new_rows = size.rows.max(MIN_TERMINAL_HEIGHT).min(self.viewport.rows);
+        geom.cols.set_inner(new_cols);
+        geom.rows.set_inner(new_rows);
+
+        // move the pane back into the viewport if it now sticks out of it
+        let viewport_right_edge = self.viewport.x + self.viewport.cols;
+        let viewport_bottom_edge = self.viewport.y + self.viewport.rows;
+        if geom.x + new_cols > viewport_right_edge {
+            geom.x = viewport_right_edge - new_cols;
+        }
+        if geom.y + new_rows > viewport_bottom_edge {
+            geom.y = viewport_bottom_edge - new_rows;
+        }
+
+        self.set_pane_geom(pane_id, geom).with_context(err_context)
+    }
+
     pub fn next_selectable_pane_id_to_the_left(&self, current_pane_id: &PaneId) -> Option<PaneId> {
         let panes = self.panes.borrow();
         let current_pane = panes.get(current_pane_id)?;
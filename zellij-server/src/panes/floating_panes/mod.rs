@@ -22,10 +22,11 @@ use std::collections::{BTreeMap, HashMap, HashSet};
 use std::rc::Rc;
 use std::time::Instant;
 use zellij_utils::{
-    data::{ModeInfo, PaneId, Style},
+    data::{ModeInfo, Palette, PaneId, Style},
     errors::prelude::*,
     input::command::RunCommand,
     input::layout::{FloatingPaneLayout, Run, RunPlugin},
+    input::options::PaneFrameStyle,
     pane_size::{Dimension, Offset, PaneGeom, Size, SizeInPixels, Viewport},
 };
 
@@ -45,6 +46,7 @@ pub struct FloatingPanes {
     session_is_mirrored: bool,
     desired_pane_positions: HashMap<PaneId, PaneGeom>, // this represents the positions of panes the user moved with intention, rather than by resizing the terminal window
     z_indices: Vec<PaneId>,
+    pinned_panes: HashSet<PaneId>, // these are always kept above the other floating panes
     active_panes: ActivePanes,
     show_panes: bool,
     pane_being_moved_with_mouse: Option<(PaneId, Position)>,
@@ -80,6 +82,7 @@ impl FloatingPanes {
             style,
             desired_pane_positions: HashMap::new(),
             z_indices: vec![],
+            pinned_panes: HashSet::new(),
             show_panes: false,
             active_panes: ActivePanes::new(&os_input),
             pane_being_moved_with_mouse: None,
@@ -106,6 +109,7 @@ impl FloatingPanes {
             .insert(pane_id, pane.position_and_size());
         self.panes.insert(pane_id, pane);
         self.z_indices.push(pane_id);
+        self.keep_pinned_panes_on_top();
     }
     pub fn replace_active_pane(
         &mut self,
@@ -149,6 +153,10 @@ impl FloatingPanes {
                 Ok(removed_pane)
             });
 
+        if self.pinned_panes.remove(&pane_id) {
+            self.pinned_panes.insert(with_pane_id);
+        }
+
         // update the desired_pane_positions to relate to the new pane
         if let Some(desired_pane_position) = self.desired_pane_positions.remove(&pane_id) {
             self.desired_pane_positions
@@ -162,9 +170,39 @@ impl FloatingPanes {
     }
     pub fn remove_pane(&mut self, pane_id: PaneId) -> Option<Box<dyn Pane>> {
         self.z_indices.retain(|p_id| *p_id != pane_id);
+        self.pinned_panes.remove(&pane_id);
         self.desired_pane_positions.remove(&pane_id);
         self.panes.remove(&pane_id)
     }
+    pub fn toggle_pane_pinned(&mut self, pane_id: PaneId) {
+        let is_pinned = self.pane_is_pinned(&pane_id);
+        self.set_pane_pinned(pane_id, !is_pinned);
+    }
+    pub fn set_pane_pinned(&mut self, pane_id: PaneId, should_be_pinned: bool) {
+        if !self.panes.contains_key(&pane_id) {
+            return;
+        }
+        if should_be_pinned {
+            self.pinned_panes.insert(pane_id);
+        } else {
+            self.pinned_panes.remove(&pane_id);
+        }
+        self.keep_pinned_panes_on_top();
+        self.set_force_render();
+    }
+    pub fn pane_is_pinned(&self, pane_id: &PaneId) -> bool {
+        self.pinned_panes.contains(pane_id)
+    }
+    fn keep_pinned_panes_on_top(&mut self) {
+        // the relative order of the pinned and of the unpinned panes is kept
+        let pinned_panes = &self.pinned_panes;
+        let (pinned, mut unpinned): (Vec<PaneId>, Vec<PaneId>) = self
+            .z_indices
+            .drain(..)
+            .partition(|pane_id| pinned_panes.contains(pane_id));
+        unpinned.extend(pinned);
+        self.z_indices = unpinned;
+    }
     pub fn hold_pane(
         &mut self,
         pane_id: PaneId,
@@ -268,16 +306,12 @@ impl FloatingPanes {
         if position.rows.as_usize() > viewport.rows {
             position.rows = Dimension::fixed(viewport.rows);
         }
-        if position.x + position.cols.as_usize() > viewport.cols {
-            position.x = position
-                .x
-                .saturating_sub((position.x + position.cols.as_usize()) - viewport.cols);
-        }
-        if position.y + position.rows.as_usize() > viewport.rows {
-            position.y = position
-                .y
-                .saturating_sub((position.y + position.rows.as_usize()) - viewport.rows);
-        }
+        // coordinates out of the viewport (eg. over the tab-bar or past the screen edge) are
+        // clamped so that the whole pane is inside it
+        let max_x = viewport.x + viewport.cols - position.cols.as_usize();
+        let max_y = viewport.y + viewport.rows - position.rows.as_usize();
+        position.x = position.x.max(viewport.x).min(max_x);
+        position.y = position.y.max(viewport.y).min(max_y);
         position
     }
     pub fn first_floating_pane_id(&self) -> Option<PaneId> {
@@ -296,6 +330,13 @@ impl FloatingPanes {
             pane.render_full_viewport();
         }
     }
+    pub fn toggle_pane_frame(&mut self, pane_id: PaneId) -> Result<()> {
+        if let Some(pane) = self.panes.get_mut(&pane_id) {
+            pane.set_borderless(!pane.borderless());
+            pane.set_frame(!pane.borderless());
+        }
+        self.set_pane_frames()
+    }
     pub fn set_pane_frames(&mut self) -> Result<()> {
         let err_context =
             |pane_id: &PaneId| format!("failed to activate frame on pane {pane_id:?}");
@@ -313,7 +354,13 @@ impl FloatingPanes {
         }
         Ok(())
     }
-    pub fn render(&mut self, output: &mut Output) -> Result<()> {
+    pub fn render(
+        &mut self,
+        output: &mut Output,
+        focus_dimming: Option<f64>,
+        client_themes: &HashMap<ClientId, Palette>,
+        pane_frame_style: Option<PaneFrameStyle>,
+    ) -> Result<()> {
         let err_context = || "failed to render output";
         let connected_clients: Vec<ClientId> =
             { self.connected_clients.borrow().iter().copied().collect() };
@@ -349,6 +396,9 @@ impl FloatingPanes {
                 false,
                 false,
                 true,
+                focus_dimming,
+                client_themes,
+                pane_frame_style,
             );
             for client_id in &connected_clients {
                 let client_mode = self
@@ -466,6 +516,28 @@ impl FloatingPanes {
         return Ok(true);
     }
 
+    pub fn resize_pane_to(&mut self, pane_id: PaneId, size: Size) -> Result<()> {
+        let err_context = || format!("failed to resize floating pane {pane_id:?} to {size:?}");
+        let display_area = *self.display_area.borrow();
+        let viewport = *self.viewport.borrow();
+        let mut floating_pane_grid = FloatingPaneGrid::new(
+            &mut self.panes,
+            &mut self.desired_pane_positions,
+            display_area,
+            viewport,
+        );
+        floating_pane_grid
+            .resize_pane_to(pane_id, size)
+            .with_context(err_context)?;
+
+        for pane in self.panes.values_mut() {
+            resize_pty!(pane, os_api, self.senders, self.character_cell_size)
+                .with_context(err_context)?;
+        }
+        self.set_force_render();
+        Ok(())
+    }
+
     fn set_pane_active_at(&mut self, pane_id: PaneId) {
         if let Some(pane) = self.panes.get_mut(&pane_id) {
             pane.set_active_at(Instant::now());
@@ -716,6 +788,7 @@ impl FloatingPanes {
         }
         self.z_indices.retain(|p_id| *p_id != pane_id);
         self.z_indices.push(pane_id);
+        self.keep_pinned_panes_on_top();
         self.set_pane_active_at(pane_id);
         self.set_force_render();
     }
@@ -843,6 +916,7 @@ impl FloatingPanes {
     }
     pub fn drain(&mut self) -> BTreeMap<PaneId, Box<dyn Pane>> {
         self.z_indices.clear();
+        self.pinned_panes.clear();
         self.desired_pane_positions.clear();
         match self.panes.iter().next().map(|(pid, _p)| *pid) {
             Some(first_pid) => self.panes.split_off(&first_pid),
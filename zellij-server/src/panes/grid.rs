@@ -4,7 +4,7 @@ use std::cell::RefCell;
 use std::collections::HashMap;
 use std::rc::Rc;
 use unicode_width::UnicodeWidthChar;
-use zellij_utils::data::Style;
+use zellij_utils::data::{SelectionBounds, Style};
 use zellij_utils::errors::prelude::*;
 use zellij_utils::regex::Regex;
 
@@ -364,17 +364,25 @@ pub struct Grid {
     pub is_scrolled: bool,
     pub link_handler: Rc<RefCell<LinkHandler>>,
     pub ring_bell: bool,
+    pub pending_bell: bool, // the bell rang and the tab did not handle it yet
     scrollback_buffer_lines: usize,
     pub mouse_mode: MouseMode,
     pub mouse_tracking: MouseTracking,
     pub focus_event_tracking: bool,
     pub search_results: SearchResult,
+    // the search that was active when the viewport was searched with
+    // search_and_highlight_viewport, restored once that search is cleared
+    pub(crate) search_results_before_highlight: Option<SearchResult>,
     pub pending_clipboard_update: Option<String>,
+    pub pending_title_update: Option<String>, // the title changed and the tab did not report it yet
     ui_component_bytes: Option<Vec<u8>>,
     style: Style,
     debug: bool,
     arrow_fonts: bool,
     styled_underlines: bool,
+    transparent_background: bool,
+    keep_scrollback_in_alternate_screen: bool,
+    show_invisibles: bool,
 }
 
 #[derive(Clone, Debug)]
@@ -432,6 +440,7 @@ impl Debug for Grid {
                         character: sixel_indication_character(x),
                         width: 1,
                         styles: Default::default(),
+                        is_tab_fill: false,
                     };
                     row.add_character_at(fake_sixel_terminal_character, x);
                 }
@@ -466,6 +475,8 @@ impl Grid {
         debug: bool,
         arrow_fonts: bool,
         styled_underlines: bool,
+        transparent_background: bool,
+        keep_scrollback_in_alternate_screen: bool,
     ) -> Self {
         let sixel_grid = SixelGrid::new(character_cell_size.clone(), sixel_image_store);
         // make sure this is initialized as it is used internally
@@ -507,24 +518,35 @@ impl Grid {
             is_scrolled: false,
             link_handler,
             ring_bell: false,
+            pending_bell: false,
             scrollback_buffer_lines: 0,
             mouse_mode: MouseMode::default(),
             mouse_tracking: MouseTracking::default(),
             focus_event_tracking: false,
             character_cell_size,
             search_results: Default::default(),
+            search_results_before_highlight: None,
             sixel_grid,
             pending_clipboard_update: None,
+            pending_title_update: None,
             ui_component_bytes: None,
             style,
             debug,
             arrow_fonts,
             styled_underlines,
+            transparent_background,
+            keep_scrollback_in_alternate_screen,
+            show_invisibles: false,
         }
     }
     pub fn render_full_viewport(&mut self) {
         self.output_buffer.update_all_lines();
     }
+    pub fn toggle_show_invisibles(&mut self) {
+        self.show_invisibles = !self.show_invisibles;
+        self.render_full_viewport();
+        self.mark_for_rerender();
+    }
     pub fn update_line_for_rendering(&mut self, line_index: usize) {
         self.output_buffer.update_line(line_index);
     }
@@ -544,6 +566,8 @@ impl Grid {
         }
         let mut empty_character = EMPTY_TERMINAL_CHARACTER;
         empty_character.styles = styles;
+        // marked so that the tab can be told apart from spaces when showing invisibles
+        empty_character.is_tab_fill = true;
         self.pad_current_line_until(self.cursor.x, empty_character);
         self.output_buffer.update_line(self.cursor.y);
     }
@@ -574,6 +598,24 @@ impl Grid {
         )
     }
 
+    fn primary_screen_as_scrollback(&mut self) -> VecDeque<Row> {
+        // the primary screen is copied rather than moved, so that it is restored untouched once
+        // the alternate screen is exited
+        let mut lines_above = VecDeque::new();
+        if let Some(alternate_screen_state) = self.alternate_screen_state.as_ref() {
+            lines_above = alternate_screen_state.lines_above.clone();
+            let mut viewport = alternate_screen_state.viewport.clone();
+            let viewport_rows = viewport.len();
+            transfer_rows_from_viewport_to_lines_above(
+                &mut viewport,
+                &mut lines_above,
+                &mut self.sixel_grid,
+                viewport_rows,
+                self.width,
+            );
+        }
+        lines_above
+    }
     fn recalculate_scrollback_buffer_count(&self) -> usize {
         let mut scrollback_buffer_count = 0;
         for row in &self.lines_above {
@@ -597,6 +639,20 @@ impl Grid {
     fn clear_all_tabstops(&mut self) {
         self.horizontal_tabstops.clear();
     }
+    fn resize_horizontal_tabstops(&mut self, new_columns: usize) {
+        // tabstops set or cleared by the program (HTS/TBC) are kept, only the columns that did
+        // not exist before get the default tabstops
+        let previous_columns = self.width;
+        self.horizontal_tabstops
+            .retain(|&tabstop| tabstop <= new_columns);
+        if new_columns > previous_columns {
+            self.horizontal_tabstops.extend(
+                create_horizontal_tabstops(new_columns)
+                    .into_iter()
+                    .filter(|&tabstop| tabstop > previous_columns),
+            );
+        }
+    }
     fn save_cursor_position(&mut self) {
         self.saved_cursor_position = Some(self.cursor.clone());
     }
@@ -782,7 +838,7 @@ impl Grid {
         self.selection.reset();
         self.sixel_grid.character_cell_size_possibly_changed();
         if new_columns != self.width {
-            self.horizontal_tabstops = create_horizontal_tabstops(new_columns);
+            self.resize_horizontal_tabstops(new_columns);
             let mut cursor_canonical_line_index = self.cursor_canonical_line_index();
             let cursor_index_in_canonical_line = self.cursor_index_in_canonical_line();
             let saved_cursor_index_in_canonical_line = self.saved_cursor_index_in_canonical_line();
@@ -1046,6 +1102,27 @@ impl Grid {
 
         (changed_character_chunks, changed_sixel_image_chunks)
     }
+    /// The whole viewport as shown in a view of `rows` and `columns` at this position, cropped or
+    /// padded to it, without marking anything as rendered
+    pub fn viewport_chunks(
+        &self,
+        x_offset: usize,
+        y_offset: usize,
+        rows: usize,
+        columns: usize,
+    ) -> Vec<CharacterChunk> {
+        let mut character_chunks = self.output_buffer.all_chunks_in_viewport(
+            &self.viewport,
+            columns,
+            rows,
+            x_offset,
+            y_offset,
+        );
+        for character_chunk in character_chunks.iter_mut() {
+            character_chunk.add_changed_colors(self.changed_colors);
+        }
+        character_chunks
+    }
     pub fn serialize(&self, scrollback_lines_to_serialize: Option<usize>) -> Option<String> {
         match scrollback_lines_to_serialize {
             Some(scrollback_lines_to_serialize) => {
@@ -1079,9 +1156,18 @@ impl Grid {
         let (mut character_chunks, sixel_image_chunks) = self.read_changes(content_x, content_y);
         for character_chunk in character_chunks.iter_mut() {
             character_chunk.add_changed_colors(self.changed_colors);
-            if self
-                .selection
-                .contains_row(character_chunk.y.saturating_sub(content_y))
+            if self.show_invisibles {
+                let written_width = self
+                    .viewport
+                    .get(character_chunk.y.saturating_sub(content_y))
+                    .map(|row| row.width())
+                    .unwrap_or(0);
+                character_chunk.show_invisibles(written_width);
+            }
+            if !self.selection.is_empty()
+                && self
+                    .selection
+                    .contains_row(character_chunk.y.saturating_sub(content_y))
             {
                 let background_color = match style.colors.bg {
                     PaletteColor::Rgb(rgb) => AnsiCode::RgbCode(rgb),
@@ -1140,6 +1226,9 @@ impl Grid {
             Some((self.cursor.x, self.cursor.y))
         }
     }
+    pub fn cursor_row(&self) -> usize {
+        self.cursor.y
+    }
     /// Clears all buffers with text for a current screen
     pub fn clear_screen(&mut self) {
         if self.alternate_screen_state.is_some() {
@@ -1162,6 +1251,32 @@ impl Grid {
         scrollback.push_str(&viewport);
         scrollback
     }
+    /// The text of the viewport without any styling (along with all the lines scrolled out of it
+    /// if `include_scrollback`), with wrapped lines joined and trailing blank lines dropped
+    pub fn plain_text(&self, include_scrollback: bool) -> String {
+        let rows: Vec<&Row> = if include_scrollback {
+            self.lines_above
+                .iter()
+                .chain(self.viewport.iter())
+                .chain(self.lines_below.iter())
+                .collect()
+        } else {
+            self.viewport.iter().collect()
+        };
+        let mut lines: Vec<String> = vec![];
+        for row in rows {
+            let row_text: String = row.columns.iter().map(|c| c.character).collect();
+            match lines.last_mut() {
+                Some(line) if !row.is_canonical => line.push_str(&row_text),
+                _ => lines.push(row_text),
+            }
+        }
+        let mut lines: Vec<&str> = lines.iter().map(|line| line.trim_end()).collect();
+        while lines.last().map(|line| line.is_empty()).unwrap_or(false) {
+            lines.pop();
+        }
+        lines.join("\n")
+    }
     pub fn move_viewport_up(&mut self, count: usize) {
         for _ in 0..count {
             self.scroll_up_one_line();
@@ -1197,8 +1312,7 @@ impl Grid {
                     if self.viewport.get(scroll_region_bottom).is_some() {
                         self.viewport.remove(scroll_region_bottom);
                     }
-                    let mut pad_character = EMPTY_TERMINAL_CHARACTER;
-                    pad_character.styles = self.cursor.pending_styles;
+                    let pad_character = self.unpainted_pad_character();
                     let columns = VecDeque::from(vec![pad_character; self.width]);
                     self.viewport
                         .insert(scroll_region_top, Row::from_columns(columns).canonical());
@@ -1213,8 +1327,7 @@ impl Grid {
             .or_else(|| Some((0, self.height.saturating_sub(1))))
         {
             self.pad_lines_until(scroll_region_bottom, EMPTY_TERMINAL_CHARACTER);
-            let mut pad_character = EMPTY_TERMINAL_CHARACTER;
-            pad_character.styles = self.cursor.pending_styles;
+            let pad_character = self.unpainted_pad_character();
             for _ in 0..count {
                 self.viewport.remove(scroll_region_top);
                 let columns = VecDeque::from(vec![pad_character; self.width]);
@@ -1257,15 +1370,13 @@ impl Grid {
                         self.viewport.remove(0);
                     }
 
-                    let mut pad_character = EMPTY_TERMINAL_CHARACTER;
-                    pad_character.styles = self.cursor.pending_styles;
+                    let pad_character = self.unpainted_pad_character();
                     let columns = VecDeque::from(vec![pad_character; self.width]);
                     self.viewport.push(Row::from_columns(columns).canonical());
                     self.selection.move_up(1);
                 } else {
                     self.viewport.remove(scroll_region_top);
-                    let mut pad_character = EMPTY_TERMINAL_CHARACTER;
-                    pad_character.styles = self.cursor.pending_styles;
+                    let pad_character = self.unpainted_pad_character();
                     let columns = VecDeque::from(vec![pad_character; self.width]);
                     if self.viewport.len() >= scroll_region_bottom {
                         self.viewport
@@ -1468,6 +1579,17 @@ impl Grid {
         }
         self.output_buffer.update_line(self.cursor.y);
     }
+    fn unpainted_pad_character(&self) -> TerminalCharacter {
+        // cells that appear without the pane explicitly painting them (eg. lines scrolled into
+        // view) take the current styles (back color erase). When the background should be left
+        // transparent, these cells keep the reset styles of an empty character instead, so that
+        // they are drawn with the default background of the terminal
+        let mut pad_character = EMPTY_TERMINAL_CHARACTER;
+        if !self.transparent_background {
+            pad_character.styles = self.cursor.pending_styles;
+        }
+        pad_character
+    }
     fn pad_lines_until(&mut self, position: usize, pad_character: TerminalCharacter) {
         for _ in self.viewport.len()..=position {
             let columns = VecDeque::from(vec![pad_character; self.width]);
@@ -1568,8 +1690,7 @@ impl Grid {
     pub fn set_scroll_region(&mut self, top_line_index: usize, bottom_line_index: Option<usize>) {
         let bottom_line_index = bottom_line_index.unwrap_or(self.height);
         self.scroll_region = Some((top_line_index, bottom_line_index));
-        let mut pad_character = EMPTY_TERMINAL_CHARACTER;
-        pad_character.styles = self.cursor.pending_styles;
+        let pad_character = self.unpainted_pad_character();
         self.move_cursor_to(0, 0, pad_character); // DECSTBM moves the cursor to column 1 line 1 of the page
     }
     pub fn clear_scroll_region(&mut self) {
@@ -1702,6 +1823,7 @@ impl Grid {
         self.changed_colors = None;
         self.scrollback_buffer_lines = 0;
         self.search_results = Default::default();
+        self.search_results_before_highlight = None;
         self.sixel_scrolling = false;
         self.mouse_mode = MouseMode::NoEncoding;
         self.mouse_tracking = MouseTracking::Off;
@@ -1740,6 +1862,18 @@ impl Grid {
         self.update_selected_lines(&old_selection, &self.selection.clone());
         self.mark_for_rerender();
     }
+    pub fn get_selection_bounds(&self) -> Option<SelectionBounds> {
+        if self.selection.is_empty() {
+            return None;
+        }
+        let sorted_selection = self.selection.sorted();
+        Some(SelectionBounds {
+            start_line: sorted_selection.start.line.0,
+            start_column: sorted_selection.start.column.0,
+            end_line: sorted_selection.end.line.0,
+            end_column: sorted_selection.end.column.0,
+        })
+    }
     pub fn get_selected_text(&self) -> Option<String> {
         if self.selection.is_empty() {
             return None;
@@ -1821,6 +1955,16 @@ impl Grid {
             Some(selection.join("\n"))
         }
     }
+    /// The URI of the hyperlink (OSC 8) under this position in the viewport, if there is one
+    pub fn hyperlink_at(&self, position: &Position) -> Option<String> {
+        let line = usize::try_from(position.line.0).ok()?;
+        let row = self.viewport.get(line)?;
+        let character_index = row.absolute_character_index(position.column.0);
+        let terminal_character = row.columns.get(character_index)?;
+        self.link_handler
+            .borrow()
+            .uri(terminal_character.styles.link_anchor)
+    }
     pub fn absolute_position_in_scrollback(&self) -> usize {
         self.lines_above.len() + self.cursor.y
     }
@@ -1831,6 +1975,9 @@ impl Grid {
         }
     }
     fn set_title(&mut self, title: String) {
+        if self.title.as_ref() != Some(&title) {
+            self.pending_title_update = Some(title.clone());
+        }
         self.title = Some(title);
     }
     fn push_current_title_to_stack(&mut self) {
@@ -1843,7 +1990,7 @@ impl Grid {
     }
     fn pop_title_from_stack(&mut self) {
         if let Some(popped_title) = self.title_stack.pop() {
-            self.title = Some(popped_title);
+            self.set_title(popped_title);
         }
     }
     fn transfer_rows_to_lines_above(&mut self, count: usize) {
@@ -2149,6 +2296,7 @@ impl Perform for Grid {
             character: c,
             width: c.width().unwrap_or(0),
             styles: self.cursor.pending_styles,
+            is_tab_fill: false,
         };
         self.set_preceding_character(terminal_character);
         self.add_character(terminal_character);
@@ -2158,6 +2306,7 @@ impl Perform for Grid {
         match byte {
             7 => {
                 self.ring_bell = true;
+                self.pending_bell = true;
             },
             8 => {
                 // backspace
@@ -2632,6 +2781,9 @@ impl Perform for Grid {
                                 current_cursor,
                                 alternate_sixelgrid,
                             ));
+                            if self.keep_scrollback_in_alternate_screen {
+                                self.lines_above = self.primary_screen_as_scrollback();
+                            }
                             self.clear_viewport_before_rendering = true;
                             self.scrollback_buffer_lines =
                                 self.recalculate_scrollback_buffer_count();
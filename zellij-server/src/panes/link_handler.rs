@@ -49,6 +49,14 @@ impl LinkHandler {
         }
     }
 
+    /// The URI of the link started by this anchor, if any
+    pub fn uri(&self, link_anchor: Option<LinkAnchor>) -> Option<String> {
+        match link_anchor {
+            Some(LinkAnchor::Start(index)) => self.links.get(&index).map(|link| link.uri.clone()),
+            _ => None,
+        }
+    }
+
     pub fn output_osc8(&self, link_anchor: Option<LinkAnchor>) -> Option<String> {
         link_anchor.and_then(|link| match link {
             LinkAnchor::Start(index) => {
@@ -18,6 +18,7 @@ pub use floating_panes::*;
 pub use grid::*;
 pub use link_handler::*;
 pub(crate) use plugin_pane::*;
+pub use search::PaneSearchMatches;
 pub use sixel::*;
 pub(crate) use terminal_character::*;
 pub use terminal_pane::*;
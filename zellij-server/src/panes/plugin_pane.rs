@@ -1,11 +1,13 @@
-use std::collections::{BTreeSet, HashMap};
+use std::collections::{BTreeSet, HashMap, VecDeque};
 use std::time::Instant;
 
 use crate::output::{CharacterChunk, SixelImageChunk};
-use crate::panes::{grid::Grid, sixel::SixelImageStore, LinkHandler};
-use crate::plugins::PluginInstruction;
+use crate::panes::{
+    grid::Grid, sixel::SixelImageStore, LinkHandler, BRACKETED_PASTE_BEGIN, BRACKETED_PASTE_END,
+};
+use crate::plugins::{ChangedLine, PluginFrame, PluginInstruction};
 use crate::pty::VteBytes;
-use crate::tab::{AdjustedInput, Pane};
+use crate::tab::{AdjustedInput, Pane, PluginInput};
 use crate::ui::{
     loading_indication::LoadingIndication,
     pane_boundaries_frame::{FrameParams, PaneFrame},
@@ -14,18 +16,24 @@ use crate::ClientId;
 use std::cell::RefCell;
 use std::rc::Rc;
 use zellij_utils::data::{PaneId, PermissionStatus, PermissionType, PluginPermission};
-use zellij_utils::pane_size::{Offset, SizeInPixels};
+use zellij_utils::pane_size::{Offset, Size, SizeInPixels};
 use zellij_utils::position::Position;
 use zellij_utils::{
     channels::SenderWithContext,
     data::{Event, InputMode, Mouse, Palette, PaletteColor, Style},
     errors::prelude::*,
     input::layout::Run,
+    input::options::ScrollLines,
     pane_size::PaneGeom,
     shared::make_terminal_title,
     vte,
 };
 
+// how many of a plugin's latest log lines are shown over the bottom of its pane
+const MAX_LOG_LINES_IN_PANE: usize = 5;
+// how much of a bracketed paste is buffered before what arrived of it is sent to the plugin
+const MAX_PASTE_BUFFER_SIZE: usize = 1024 * 1024; // bytes
+
 macro_rules! style {
     ($fg:expr) => {
         ansi_term::Style::new().fg(match $fg {
@@ -53,6 +61,8 @@ macro_rules! get_or_create_grid {
                 $self.debug,
                 $self.arrow_fonts,
                 $self.styled_underlines,
+                false, // plugins paint their own background
+                false,
             );
             grid.hide_cursor();
             grid
@@ -79,14 +89,21 @@ pub(crate) struct PluginPane {
     character_cell_size: Rc<RefCell<Option<SizeInPixels>>>,
     vte_parsers: HashMap<ClientId, vte::Parser>,
     grids: HashMap<ClientId, Grid>,
+    frame_ids: HashMap<ClientId, u64>, // the frame each grid shows, if changes can be applied to it
+    log_lines: HashMap<ClientId, VecDeque<String>>, // when the plugin logs to its own pane
     prev_pane_name: String,
     frame: HashMap<ClientId, PaneFrame>,
     borderless: bool,
     exclude_from_sync: bool,
+    read_only: bool,
+    min_size: Option<Size>,
+    scroll_lines: Option<ScrollLines>,
     pane_frame_color_override: Option<(PaletteColor, Option<String>)>,
     invoked_with: Option<Run>,
     loading_indication: LoadingIndication,
     requesting_permissions: Option<PluginPermission>,
+    paste_buffer: Option<Vec<u8>>, // set while receiving a bracketed paste
+    partial_paste_marker: Vec<u8>, // the start of a paste marker the last input ended with
     debug: bool,
     arrow_fonts: bool,
     styled_underlines: bool,
@@ -130,16 +147,23 @@ impl PluginPane {
             terminal_emulator_colors,
             terminal_emulator_color_codes,
             exclude_from_sync: false,
+            read_only: false,
+            min_size: None,
+            scroll_lines: None,
             link_handler,
             character_cell_size,
             sixel_image_store,
             vte_parsers: HashMap::new(),
             grids: HashMap::new(),
+            frame_ids: HashMap::new(),
+            log_lines: HashMap::new(),
             style,
             pane_frame_color_override: None,
             invoked_with,
             loading_indication,
             requesting_permissions: None,
+            paste_buffer: None,
+            partial_paste_marker: vec![],
             debug,
             arrow_fonts,
             styled_underlines,
@@ -201,33 +225,52 @@ impl Pane for PluginPane {
         self.set_should_render(true);
     }
     fn handle_plugin_bytes(&mut self, client_id: ClientId, bytes: VteBytes) {
-        self.set_client_should_render(client_id, true);
-
-        let mut vte_bytes = bytes;
-        if let Some(plugin_permission) = &self.requesting_permissions {
-            vte_bytes = self
-                .display_request_permission_message(plugin_permission)
-                .into();
+        // the bytes are not a frame the changes of the next one can be applied to
+        self.frame_ids.remove(&client_id);
+        self.render_whole_frame(client_id, bytes);
+    }
+    fn handle_plugin_frame(&mut self, client_id: ClientId, frame: PluginFrame) {
+        match frame {
+            PluginFrame::Full { frame_id, bytes } => {
+                if self.render_whole_frame(client_id, bytes) {
+                    self.frame_ids.insert(client_id, frame_id);
+                } else {
+                    self.frame_ids.remove(&client_id);
+                }
+            },
+            PluginFrame::Changed {
+                frame_id,
+                base_frame_id,
+                lines,
+            } => {
+                if self.requesting_permissions.is_some() {
+                    // the frame is not shown while the permission request is, the plugin renders
+                    // again once it is answered
+                    self.frame_ids.remove(&client_id);
+                } else if self.frame_ids.get(&client_id) == Some(&base_frame_id)
+                    && self.apply_changed_lines(client_id, &lines)
+                {
+                    self.frame_ids.insert(client_id, frame_id);
+                } else {
+                    self.frame_ids.remove(&client_id);
+                    let _ = self
+                        .send_plugin_instructions
+                        .send(PluginInstruction::ResendPluginFrame(self.pid, client_id));
+                }
+            },
         }
-
-        let grid = get_or_create_grid!(self, client_id);
-
-        // this is part of the plugin contract, whenever we update the plugin and call its render function, we delete the existing viewport
-        // and scroll, reset the cursor position and make sure all the viewport is rendered
-        grid.delete_viewport_and_scroll();
-        grid.reset_cursor_position();
-        grid.render_full_viewport();
-
-        let vte_parser = self
-            .vte_parsers
+    }
+    fn handle_plugin_log_line(&mut self, client_id: ClientId, log_line: String) {
+        let log_lines = self
+            .log_lines
             .entry(client_id)
-            .or_insert_with(|| vte::Parser::new());
-
-        for &byte in &vte_bytes {
-            vte_parser.advance(grid, byte);
+            .or_insert_with(VecDeque::new);
+        log_lines.push_back(log_line);
+        if log_lines.len() > MAX_LOG_LINES_IN_PANE {
+            log_lines.pop_front();
         }
-
-        self.should_render.insert(client_id, true);
+        self.render_log_lines(client_id);
+        self.set_client_should_render(client_id, true);
     }
     fn cursor_coordinates(&self) -> Option<(usize, usize)> {
         None
@@ -249,7 +292,9 @@ impl Pane for PluginPane {
                 _ => None,
             }
         } else {
-            Some(AdjustedInput::WriteBytesToTerminal(input_bytes))
+            Some(AdjustedInput::WriteToPlugin(
+                self.separate_pasted_text(input_bytes),
+            ))
         }
     }
     fn position_and_size(&self) -> PaneGeom {
@@ -359,6 +404,9 @@ impl Pane for PluginPane {
                 pane_title,
                 frame_params,
             );
+            if self.read_only {
+                frame.indicate_read_only();
+            }
             if let Some((frame_color_override, _text)) = self.pane_frame_color_override.as_ref() {
                 frame.override_color(*frame_color_override);
             }
@@ -568,6 +616,24 @@ impl Pane for PluginPane {
     fn exclude_from_sync(&self) -> bool {
         self.exclude_from_sync
     }
+    fn set_read_only(&mut self, read_only: bool) {
+        self.read_only = read_only;
+    }
+    fn read_only(&self) -> bool {
+        self.read_only
+    }
+    fn set_min_size(&mut self, min_size: Option<Size>) {
+        self.min_size = min_size;
+    }
+    fn min_size(&self) -> Option<Size> {
+        self.min_size
+    }
+    fn set_scroll_lines(&mut self, scroll_lines: Option<ScrollLines>) {
+        self.scroll_lines = scroll_lines;
+    }
+    fn scroll_lines(&self) -> Option<ScrollLines> {
+        self.scroll_lines
+    }
     fn handle_right_click(&mut self, to: &Position, client_id: ClientId) {
         self.send_plugin_instructions
             .send(PluginInstruction::Update(vec![(
@@ -639,6 +705,72 @@ impl Pane for PluginPane {
 }
 
 impl PluginPane {
+    fn separate_pasted_text(&mut self, input_bytes: Vec<u8>) -> Vec<PluginInput> {
+        // a large paste can arrive over several writes, so we buffer it until its end marker
+        // arrives and the plugin receives the pasted text in one piece - a marker can itself be
+        // cut between two writes, so we hold on to its start until the next one
+        let mut input_bytes =
+            [std::mem::take(&mut self.partial_paste_marker), input_bytes].concat();
+        let mut plugin_input = vec![];
+        loop {
+            match self.paste_buffer.as_mut() {
+                Some(paste_buffer) => match find_marker(&input_bytes, BRACKETED_PASTE_END) {
+                    Some(end_position) => {
+                        paste_buffer.extend(input_bytes.drain(..end_position));
+                        input_bytes.drain(..BRACKETED_PASTE_END.len());
+                        let pasted_bytes = self.paste_buffer.take().unwrap_or_default();
+                        plugin_input.push(PluginInput::Paste(
+                            String::from_utf8_lossy(&pasted_bytes).to_string(),
+                        ));
+                    },
+                    None => {
+                        let marker_start = input_bytes.len()
+                            - partial_marker_len(&input_bytes, BRACKETED_PASTE_END);
+                        self.partial_paste_marker = input_bytes.split_off(marker_start);
+                        paste_buffer.append(&mut input_bytes);
+                        if paste_buffer.len() > MAX_PASTE_BUFFER_SIZE {
+                            // rather than holding on to a paste that does not end, we send what
+                            // arrived of it so far, up to the last character that arrived whole
+                            let flushed_len = match std::str::from_utf8(paste_buffer) {
+                                Err(e) if e.error_len().is_none() => e.valid_up_to(),
+                                _ => paste_buffer.len(),
+                            };
+                            let pasted_bytes: Vec<u8> = paste_buffer.drain(..flushed_len).collect();
+                            plugin_input.push(PluginInput::Paste(
+                                String::from_utf8_lossy(&pasted_bytes).to_string(),
+                            ));
+                        }
+                        break;
+                    },
+                },
+                None => match find_marker(&input_bytes, BRACKETED_PASTE_BEGIN) {
+                    Some(begin_position) => {
+                        let typed_bytes: Vec<u8> = input_bytes.drain(..begin_position).collect();
+                        if !typed_bytes.is_empty() {
+                            plugin_input.push(PluginInput::Keys(typed_bytes));
+                        }
+                        input_bytes.drain(..BRACKETED_PASTE_BEGIN.len());
+                        self.paste_buffer = Some(vec![]);
+                    },
+                    None => {
+                        // a lone ESC or "ESC [" is much more likely to be typed (eg. with the
+                        // Escape key or Alt+[) than to be a paste marker cut short, so we only
+                        // hold on to what is longer than that
+                        let marker_len = partial_marker_len(&input_bytes, BRACKETED_PASTE_BEGIN);
+                        if marker_len > 2 {
+                            let marker_start = input_bytes.len() - marker_len;
+                            self.partial_paste_marker = input_bytes.split_off(marker_start);
+                        }
+                        if !input_bytes.is_empty() {
+                            plugin_input.push(PluginInput::Keys(input_bytes));
+                        }
+                        break;
+                    },
+                },
+            }
+        }
+        plugin_input
+    }
     fn resize_grids(&mut self) {
         let content_rows = self.get_content_rows();
         let content_columns = self.get_content_columns();
@@ -650,6 +782,102 @@ impl PluginPane {
     fn set_client_should_render(&mut self, client_id: ClientId, should_render: bool) {
         self.should_render.insert(client_id, should_render);
     }
+    // returns true if each line of the frame was written to its own row, so that the changes of
+    // the next frame can be applied to them
+    fn render_whole_frame(&mut self, client_id: ClientId, bytes: VteBytes) -> bool {
+        self.set_client_should_render(client_id, true);
+
+        let mut vte_bytes = bytes;
+        let mut is_plugin_frame = true;
+        if let Some(plugin_permission) = &self.requesting_permissions {
+            vte_bytes = self
+                .display_request_permission_message(plugin_permission)
+                .into();
+            is_plugin_frame = false;
+        }
+
+        let grid = get_or_create_grid!(self, client_id);
+
+        // this is part of the plugin contract, whenever we update the plugin and call its render function, we delete the existing viewport
+        // and scroll, reset the cursor position and make sure all the viewport is rendered
+        grid.delete_viewport_and_scroll();
+        grid.reset_cursor_position();
+        grid.render_full_viewport();
+
+        let vte_parser = self
+            .vte_parsers
+            .entry(client_id)
+            .or_insert_with(|| vte::Parser::new());
+
+        let mut lines_in_their_rows = is_plugin_frame;
+        for (row, line) in vte_bytes.split(|byte| *byte == b'\n').enumerate() {
+            if row > 0 {
+                vte_parser.advance(grid, b'\n');
+            }
+            for &byte in line {
+                vte_parser.advance(grid, byte);
+            }
+            lines_in_their_rows = lines_in_their_rows && grid.cursor_row() == row;
+        }
+        self.render_log_lines(client_id);
+
+        self.should_render.insert(client_id, true);
+        lines_in_their_rows
+    }
+    // returns false if a line did not stay in its row (eg. because it was too long for the pane
+    // and wrapped), in which case the grid no longer shows the frame
+    fn apply_changed_lines(&mut self, client_id: ClientId, lines: &[ChangedLine]) -> bool {
+        self.set_client_should_render(client_id, true);
+        let grid = match self.grids.get_mut(&client_id) {
+            Some(grid) => grid,
+            None => return false,
+        };
+        let vte_parser = self
+            .vte_parsers
+            .entry(client_id)
+            .or_insert_with(|| vte::Parser::new());
+        for line in lines {
+            for byte in line.positioned_run() {
+                vte_parser.advance(grid, byte);
+            }
+            if grid.cursor_row() != line.row {
+                return false;
+            }
+        }
+        // the changed lines may have been drawn over the log lines
+        self.render_log_lines(client_id);
+        true
+    }
+    fn render_log_lines(&mut self, client_id: ClientId) {
+        // the log lines are drawn over the bottom rows of the plugin's own output, so that they
+        // survive the plugin re-rendering itself
+        let content_rows = self.get_content_rows();
+        let content_columns = self.get_content_columns();
+        let log_lines = match self.log_lines.get(&client_id) {
+            Some(log_lines) if !log_lines.is_empty() => log_lines,
+            _ => return,
+        };
+        let visible_lines = log_lines.len().min(content_rows);
+        let first_row = content_rows.saturating_sub(visible_lines) + 1;
+        let mut vte_bytes = String::new();
+        for (i, log_line) in log_lines
+            .iter()
+            .skip(log_lines.len() - visible_lines)
+            .enumerate()
+        {
+            let log_line: String = log_line.chars().take(content_columns).collect();
+            vte_bytes.push_str(&format!("\u{1b}[{};1H\u{1b}[2K{}", first_row + i, log_line));
+        }
+        if let Some(grid) = self.grids.get_mut(&client_id) {
+            let vte_parser = self
+                .vte_parsers
+                .entry(client_id)
+                .or_insert_with(|| vte::Parser::new());
+            for &byte in vte_bytes.as_bytes() {
+                vte_parser.advance(grid, byte);
+            }
+        }
+    }
     fn handle_plugin_bytes_for_all_clients(&mut self, bytes: VteBytes) {
         let client_ids: Vec<ClientId> = self.grids.keys().copied().collect();
         for client_id in client_ids {
@@ -707,3 +935,17 @@ impl PluginPane {
         messages
     }
 }
+
+fn find_marker(input_bytes: &[u8], marker: &[u8]) -> Option<usize> {
+    input_bytes
+        .windows(marker.len())
+        .position(|window| window == marker)
+}
+
+// the length of the start of the marker that the input ends with, if any
+fn partial_marker_len(input_bytes: &[u8], marker: &[u8]) -> usize {
+    (1..marker.len())
+        .rev()
+        .find(|len| input_bytes.ends_with(&marker[..*len]))
+        .unwrap_or(0)
+}
@@ -100,6 +100,15 @@ pub struct SearchResult {
     pub wrap_search: bool,
 }
 
+/// The matches of a search in the viewport of a pane. Lines are relative to the top of the
+/// viewport and the end of each match is exclusive, a match continuing on a wrapped line ends on
+/// a later line than the one it starts on.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PaneSearchMatches {
+    pub count: usize,
+    pub positions: Vec<(Position, Position)>, // (start, end)
+}
+
 impl SearchResult {
     /// This is only used for Debug formatting Grid, which itself is only used
     /// for tests.
@@ -408,6 +417,7 @@ impl Grid {
                 .update_lines(res.start.line() as usize, res.end.line() as usize);
         }
         self.search_results = Default::default();
+        self.search_results_before_highlight = None;
     }
 
     pub fn set_search_string(&mut self, needle: &str) {
@@ -427,6 +437,48 @@ impl Grid {
         self.is_scrolled = true;
     }
 
+    /// Highlights all the matches of the needle in the viewport (until the search is cleared),
+    /// without scrolling to or selecting any of them. A search that was already active is put
+    /// aside and restored by `clear_highlighted_search`.
+    pub fn search_and_highlight_viewport(
+        &mut self,
+        needle: &str,
+        case_sensitive: bool,
+    ) -> PaneSearchMatches {
+        let search_results_before_highlight = self
+            .search_results_before_highlight
+            .take()
+            .unwrap_or_else(|| self.search_results.clone());
+        self.clear_search();
+        self.search_results_before_highlight = Some(search_results_before_highlight);
+        self.search_results.needle = needle.to_string();
+        self.search_results.case_insensitive = !case_sensitive;
+        self.search_viewport();
+        let positions: Vec<(Position, Position)> = self
+            .search_results
+            .selections
+            .iter()
+            .map(|selection| (selection.start, selection.end))
+            .collect();
+        PaneSearchMatches {
+            count: positions.len(),
+            positions,
+        }
+    }
+
+    /// Clears the search of `search_and_highlight_viewport` and restores the one that was active
+    /// before it. Does nothing if it was already replaced by another search.
+    pub fn clear_highlighted_search(&mut self) {
+        if let Some(search_results_before_highlight) = self.search_results_before_highlight.take() {
+            self.clear_search();
+            for res in &search_results_before_highlight.selections {
+                self.output_buffer
+                    .update_lines(res.start.line() as usize, res.end.line() as usize);
+            }
+            self.search_results = search_results_before_highlight;
+        }
+    }
+
     pub fn search_viewport(&mut self) {
         for ridx in 0..self.viewport.len() {
             let row = &self.viewport[ridx];
@@ -65,6 +65,7 @@ pub struct SixelGrid {
     previous_cell_size: Option<SizeInPixels>,
     character_cell_size: Rc<RefCell<Option<SizeInPixels>>>,
     currently_parsing: Option<SixelDeserializer>,
+    currently_parsing_bytes: Option<Vec<u8>>,
     image_ids_to_reap: Vec<usize>,
     sixel_parser: Option<sixel_tokenizer::Parser>,
     pub sixel_image_store: Rc<RefCell<SixelImageStore>>,
@@ -84,6 +85,9 @@ impl SixelGrid {
         }
     }
     pub fn handle_byte(&mut self, byte: u8) {
+        if let Some(currently_parsing_bytes) = self.currently_parsing_bytes.as_mut() {
+            currently_parsing_bytes.push(byte);
+        }
         self.sixel_parser
             .as_mut()
             .unwrap()
@@ -112,9 +116,12 @@ impl SixelGrid {
             Some(max_height_in_pixels) => {
                 self.currently_parsing =
                     Some(SixelDeserializer::new().max_height(max_height_in_pixels));
+                // the image might be truncated, so its original bytes cannot be passed through
+                self.currently_parsing_bytes = None;
             },
             None => {
                 self.currently_parsing = Some(SixelDeserializer::new());
+                self.currently_parsing_bytes = Some(vec![]);
             },
         }
 
@@ -265,17 +272,30 @@ impl SixelGrid {
         self.sixel_image_store.borrow().sixel_images.keys().len()
     }
     pub fn new_sixel_image(&mut self, sixel_image_id: usize, sixel_image: SixelImage) {
-        self.sixel_image_store
-            .borrow_mut()
+        let mut sixel_image_store = self.sixel_image_store.borrow_mut();
+        sixel_image_store
             .sixel_images
             .insert(sixel_image_id, (sixel_image, HashMap::new()));
+        match self.currently_parsing_bytes.take() {
+            Some(mut original_bytes) => {
+                // the DCS string terminator is consumed by the parser before the image is done
+                original_bytes.extend_from_slice(b"\x1b\\");
+                sixel_image_store.original_images.insert(
+                    sixel_image_id,
+                    String::from_utf8_lossy(&original_bytes).to_string(),
+                );
+            },
+            None => {
+                sixel_image_store.original_images.remove(&sixel_image_id);
+            },
+        }
     }
     pub fn remove_pixels_from_image(&mut self, image_id: usize, pixel_rect: PixelRect) {
-        if let Some((sixel_image, sixel_image_cache)) = self
-            .sixel_image_store
-            .borrow_mut()
-            .sixel_images
-            .get_mut(&image_id)
+        let mut sixel_image_store = self.sixel_image_store.borrow_mut();
+        // once pixels are cut out, the original bytes no longer represent the image
+        sixel_image_store.original_images.remove(&image_id);
+        if let Some((sixel_image, sixel_image_cache)) =
+            sixel_image_store.sixel_images.get_mut(&image_id)
         {
             sixel_image.cut_out(
                 pixel_rect.x,
@@ -287,8 +307,10 @@ impl SixelGrid {
         }
     }
     pub fn reap_images(&mut self, ids_to_reap: Vec<usize>) {
+        let mut sixel_image_store = self.sixel_image_store.borrow_mut();
         for id in ids_to_reap {
-            drop(self.sixel_image_store.borrow_mut().sixel_images.remove(&id));
+            drop(sixel_image_store.sixel_images.remove(&id));
+            sixel_image_store.original_images.remove(&id);
         }
     }
     pub fn image_cell_coordinates_in_viewport(
@@ -428,6 +450,9 @@ type SixelImageCache = HashMap<PixelRect, String>;
 #[derive(Debug, Clone, Default)]
 pub struct SixelImageStore {
     sixel_images: HashMap<usize, (SixelImage, SixelImageCache)>,
+    // the bytes of images as they were sent by the application, re-emitted as is when the whole
+    // image is rendered so that it is not re-encoded
+    original_images: HashMap<usize, String>,
 }
 
 impl SixelImageStore {
@@ -439,10 +464,18 @@ impl SixelImageStore {
         pixel_width: usize,
         pixel_height: usize,
     ) -> Option<String> {
+        let original_image = self.original_images.get(&image_id);
         self.sixel_images
             .get_mut(&image_id)
             .map(|(sixel_image, sixel_image_cache)| {
-                if let Some(cached_image) = sixel_image_cache.get(&PixelRect::new(
+                let (image_pixel_height, image_pixel_width) = sixel_image.pixel_size();
+                let renders_whole_image = pixel_x == 0
+                    && pixel_y == 0
+                    && pixel_width == image_pixel_width
+                    && pixel_height == image_pixel_height;
+                if let Some(original_image) = original_image.filter(|_| renders_whole_image) {
+                    original_image.clone()
+                } else if let Some(cached_image) = sixel_image_cache.get(&PixelRect::new(
                     pixel_x,
                     pixel_y,
                     pixel_height,
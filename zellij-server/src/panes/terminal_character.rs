@@ -16,6 +16,7 @@ pub const EMPTY_TERMINAL_CHARACTER: TerminalCharacter = TerminalCharacter {
     character: ' ',
     width: 1,
     styles: RESET_STYLES,
+    is_tab_fill: false,
 };
 
 pub const RESET_STYLES: CharacterStyles = CharacterStyles {
@@ -83,6 +84,27 @@ pub enum NamedColor {
 }
 
 impl NamedColor {
+    /// The index of this color in the 256 color palette
+    pub fn to_color_index(self) -> u8 {
+        match self {
+            NamedColor::Black => 0,
+            NamedColor::Red => 1,
+            NamedColor::Green => 2,
+            NamedColor::Yellow => 3,
+            NamedColor::Blue => 4,
+            NamedColor::Magenta => 5,
+            NamedColor::Cyan => 6,
+            NamedColor::White => 7,
+            NamedColor::BrightBlack => 8,
+            NamedColor::BrightRed => 9,
+            NamedColor::BrightGreen => 10,
+            NamedColor::BrightYellow => 11,
+            NamedColor::BrightBlue => 12,
+            NamedColor::BrightMagenta => 13,
+            NamedColor::BrightCyan => 14,
+            NamedColor::BrightWhite => 15,
+        }
+    }
     fn to_foreground_ansi_code(self) -> String {
         let v = match self {
             NamedColor::Black => 30,
@@ -841,6 +863,7 @@ pub struct TerminalCharacter {
     pub character: char,
     pub styles: CharacterStyles,
     pub width: usize,
+    pub is_tab_fill: bool, // an empty cell skipped over by a tab character
 }
 
 impl TerminalCharacter {
@@ -849,6 +872,7 @@ impl TerminalCharacter {
             character,
             styles: CharacterStyles::default(),
             width: character.width().unwrap_or(0),
+            is_tab_fill: false,
         }
     }
 }
@@ -1,10 +1,10 @@
 use crate::output::{CharacterChunk, SixelImageChunk};
 use crate::panes::sixel::SixelImageStore;
-use crate::panes::LinkHandler;
 use crate::panes::{
     grid::Grid,
     terminal_character::{render_first_run_banner, TerminalCharacter, EMPTY_TERMINAL_CHARACTER},
 };
+use crate::panes::{LinkHandler, PaneSearchMatches};
 use crate::pty::VteBytes;
 use crate::tab::{AdjustedInput, Pane};
 use crate::ClientId;
@@ -16,11 +16,12 @@ use std::time::{self, Instant};
 use zellij_utils::input::command::RunCommand;
 use zellij_utils::pane_size::Offset;
 use zellij_utils::{
-    data::{InputMode, Palette, PaletteColor, PaneId, Style},
+    data::{InputMode, Palette, PaletteColor, PaneId, SelectionBounds, Style},
     errors::prelude::*,
     input::layout::Run,
+    input::options::ScrollLines,
     pane_size::PaneGeom,
-    pane_size::SizeInPixels,
+    pane_size::{Size, SizeInPixels},
     position::Position,
     shared::make_terminal_title,
     vte,
@@ -37,8 +38,8 @@ const UP_ARROW: &[u8] = &[27, 91, 65];
 const DOWN_ARROW: &[u8] = &[27, 91, 66];
 const HOME_KEY: &[u8] = &[27, 91, 72];
 const END_KEY: &[u8] = &[27, 91, 70];
-const BRACKETED_PASTE_BEGIN: &[u8] = &[27, 91, 50, 48, 48, 126];
-const BRACKETED_PASTE_END: &[u8] = &[27, 91, 50, 48, 49, 126];
+pub(crate) const BRACKETED_PASTE_BEGIN: &[u8] = &[27, 91, 50, 48, 48, 126];
+pub(crate) const BRACKETED_PASTE_END: &[u8] = &[27, 91, 50, 48, 49, 126];
 const ENTER_NEWLINE: &[u8] = &[10];
 const ESC: &[u8] = &[27];
 const ENTER_CARRIAGE_RETURN: &[u8] = &[13];
@@ -101,6 +102,9 @@ pub struct TerminalPane {
     frame: HashMap<ClientId, PaneFrame>,
     borderless: bool,
     exclude_from_sync: bool,
+    read_only: bool,
+    min_size: Option<Size>,
+    scroll_lines: Option<ScrollLines>,
     fake_cursor_locations: HashSet<(usize, usize)>, // (x, y) - these hold a record of previous fake cursors which we need to clear on render
     search_term: String,
     is_held: Option<(Option<i32>, IsFirstRun, RunCommand)>, // a "held" pane means that its command has either exited and the pane is waiting for a
@@ -111,6 +115,9 @@ pub struct TerminalPane {
     invoked_with: Option<Run>,
     #[allow(dead_code)]
     arrow_fonts: bool,
+    fed_by_plugin: Option<u32>, // the id of the plugin writing to this pane, which runs no process
+    alias_of: Option<u32>,      // the terminal pane this one mirrors, which gets its input instead
+    alias_view: Option<Vec<CharacterChunk>>, // the viewport of that pane, to render in this one
 }
 
 impl Pane for TerminalPane {
@@ -179,6 +186,10 @@ impl Pane for TerminalPane {
         // needs to be adjusted.
         // here we match against those cases - if need be, we adjust the input and if not
         // we send back the original input
+        if self.fed_by_plugin.is_some() {
+            // there is no process to receive the input
+            return None;
+        }
         if let Some((_exit_status, _is_first_run, run_command)) = &self.is_held {
             match input_bytes.as_slice() {
                 ENTER_CARRIAGE_RETURN | ENTER_NEWLINE | SPACE => {
@@ -283,6 +294,9 @@ impl Pane for TerminalPane {
         self.frame.clear();
         self.grid.render_full_viewport();
     }
+    fn toggle_show_invisibles(&mut self) {
+        self.grid.toggle_show_invisibles();
+    }
     fn selectable(&self) -> bool {
         self.selectable
     }
@@ -301,6 +315,14 @@ impl Pane for TerminalPane {
             if rows < 1 || columns < 1 {
                 return Ok(None);
             }
+            if self.alias_of.is_some() {
+                // an alias renders the grid of the pane it mirrors rather than its own
+                self.set_should_render(false);
+                return Ok(self
+                    .alias_view
+                    .take()
+                    .map(|character_chunks| (character_chunks, None, vec![])));
+            }
             match self.grid.render(content_x, content_y, &self.style) {
                 Ok(rendered_assets) => {
                     self.set_should_render(false);
@@ -379,6 +401,9 @@ impl Pane for TerminalPane {
                 frame.add_exit_status(exit_status.as_ref().copied());
             }
         }
+        if self.read_only {
+            frame.indicate_read_only();
+        }
         if let Some((frame_color_override, _text)) = self.pane_frame_color_override.as_ref() {
             frame.override_color(*frame_color_override);
         }
@@ -506,6 +531,9 @@ impl Pane for TerminalPane {
     fn dump_screen(&mut self, _client_id: ClientId, full: bool) -> String {
         self.grid.dump_screen(full)
     }
+    fn plain_text(&self, include_scrollback: bool) -> String {
+        self.grid.plain_text(include_scrollback)
+    }
     fn clear_screen(&mut self) {
         self.grid.clear_screen()
     }
@@ -543,6 +571,18 @@ impl Pane for TerminalPane {
         self.grid.pending_clipboard_update.take()
     }
 
+    fn drain_bell(&mut self, audible: bool) -> bool {
+        if !audible {
+            // otherwise the bell is forwarded to the user's terminal on the next render
+            self.grid.ring_bell = false;
+        }
+        std::mem::take(&mut self.grid.pending_bell)
+    }
+
+    fn drain_title_update(&mut self) -> Option<String> {
+        self.grid.pending_title_update.take()
+    }
+
     fn start_selection(&mut self, start: &Position, _client_id: ClientId) {
         self.grid.start_selection(start);
         self.set_should_render(true);
@@ -582,6 +622,14 @@ impl Pane for TerminalPane {
         self.grid.get_selected_text()
     }
 
+    fn hyperlink_at(&self, position: &Position) -> Option<String> {
+        self.grid.hyperlink_at(position)
+    }
+
+    fn get_selection_bounds(&self) -> Option<SelectionBounds> {
+        self.grid.get_selection_bounds()
+    }
+
     fn set_frame(&mut self, _frame: bool) {
         self.frame.clear();
     }
@@ -616,6 +664,28 @@ impl Pane for TerminalPane {
     fn exclude_from_sync(&self) -> bool {
         self.exclude_from_sync
     }
+    fn set_read_only(&mut self, read_only: bool) {
+        self.read_only = read_only;
+    }
+    fn read_only(&self) -> bool {
+        self.read_only
+    }
+
+    fn set_min_size(&mut self, min_size: Option<Size>) {
+        self.min_size = min_size;
+    }
+
+    fn min_size(&self) -> Option<Size> {
+        self.min_size
+    }
+
+    fn set_scroll_lines(&mut self, scroll_lines: Option<ScrollLines>) {
+        self.scroll_lines = scroll_lines;
+    }
+
+    fn scroll_lines(&self) -> Option<ScrollLines> {
+        self.scroll_lines
+    }
 
     fn mouse_left_click(&self, position: &Position, is_held: bool) -> Option<String> {
         self.grid.mouse_left_click_signal(position, is_held)
@@ -699,6 +769,21 @@ impl Pane for TerminalPane {
         self.grid.clear_search();
         self.search_term.clear();
     }
+    fn search_and_highlight(
+        &mut self,
+        needle: &str,
+        case_sensitive: bool,
+    ) -> Option<PaneSearchMatches> {
+        let search_matches = self
+            .grid
+            .search_and_highlight_viewport(needle, case_sensitive);
+        self.set_should_render(true);
+        Some(search_matches)
+    }
+    fn clear_highlighted_search(&mut self) {
+        self.grid.clear_highlighted_search();
+        self.set_should_render(true);
+    }
     fn is_alternate_mode_active(&self) -> bool {
         self.grid.is_alternate_mode_active()
     }
@@ -713,6 +798,9 @@ impl Pane for TerminalPane {
     fn add_red_pane_frame_color_override(&mut self, error_text: Option<String>) {
         self.pane_frame_color_override = Some((self.style.colors.red, error_text));
     }
+    fn add_bell_pane_frame_color_override(&mut self) {
+        self.pane_frame_color_override = Some((self.style.colors.orange, None));
+    }
     fn clear_pane_frame_color_override(&mut self) {
         self.pane_frame_color_override = None;
     }
@@ -766,6 +854,34 @@ impl Pane for TerminalPane {
     fn serialize(&self, scrollback_lines_to_serialize: Option<usize>) -> Option<String> {
         self.grid.serialize(scrollback_lines_to_serialize)
     }
+    fn set_fed_by_plugin(&mut self, plugin_id: u32) {
+        self.fed_by_plugin = Some(plugin_id);
+    }
+    fn fed_by_plugin(&self) -> Option<u32> {
+        self.fed_by_plugin
+    }
+    fn viewport_chunks(
+        &self,
+        content_x: usize,
+        content_y: usize,
+        rows: usize,
+        columns: usize,
+    ) -> Option<Vec<CharacterChunk>> {
+        Some(
+            self.grid
+                .viewport_chunks(content_x, content_y, rows, columns),
+        )
+    }
+    fn set_alias_of(&mut self, terminal_id: u32) {
+        self.alias_of = Some(terminal_id);
+    }
+    fn alias_of(&self) -> Option<u32> {
+        self.alias_of
+    }
+    fn set_alias_view(&mut self, character_chunks: Vec<CharacterChunk>) {
+        self.alias_view = Some(character_chunks);
+        self.set_should_render(true);
+    }
 }
 
 impl TerminalPane {
@@ -786,6 +902,8 @@ impl TerminalPane {
         debug: bool,
         arrow_fonts: bool,
         styled_underlines: bool,
+        transparent_background: bool,
+        keep_scrollback_in_alternate_screen: bool,
     ) -> TerminalPane {
         let initial_pane_title =
             initial_pane_title.unwrap_or_else(|| format!("Pane #{}", pane_index));
@@ -801,6 +919,8 @@ impl TerminalPane {
             debug,
             arrow_fonts,
             styled_underlines,
+            transparent_background,
+            keep_scrollback_in_alternate_screen,
         );
         TerminalPane {
             frame: HashMap::new(),
@@ -819,6 +939,9 @@ impl TerminalPane {
             prev_pane_name: pane_name,
             borderless: false,
             exclude_from_sync: false,
+            read_only: false,
+            min_size: None,
+            scroll_lines: None,
             fake_cursor_locations: HashSet::new(),
             search_term: String::new(),
             is_held: None,
@@ -826,6 +949,9 @@ impl TerminalPane {
             pane_frame_color_override: None,
             invoked_with,
             arrow_fonts,
+            fed_by_plugin: None,
+            alias_of: None,
+            alias_view: None,
         }
     }
     pub fn get_x(&self) -> usize {
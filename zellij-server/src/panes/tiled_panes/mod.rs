@@ -3,14 +3,16 @@ mod stacked_panes;
 mod tiled_pane_grid;
 
 use crate::resize_pty;
-use tiled_pane_grid::{split, TiledPaneGrid, RESIZE_PERCENT};
+use tiled_pane_grid::{
+    merge_direction, split, split_with_ratio, TiledPaneGrid, MIN_SPLIT_RATIO, RESIZE_PERCENT,
+};
 
 use crate::{
     os_input_output::ServerOsApi,
     output::Output,
     panes::ActivePanes,
     plugins::PluginInstruction,
-    tab::{pane_info_for_pane, Pane, MIN_TERMINAL_HEIGHT, MIN_TERMINAL_WIDTH},
+    tab::{pane_info_for_pane, Pane},
     thread_bus::ThreadSenders,
     ui::boundaries::Boundaries,
     ui::pane_contents_and_ui::PaneContentsAndUi,
@@ -18,13 +20,14 @@ use crate::{
 };
 use stacked_panes::StackedPanes;
 use zellij_utils::{
-    data::{Direction, ModeInfo, PaneId, PaneInfo, ResizeStrategy, Style},
+    data::{Direction, ModeInfo, Palette, PaneId, PaneInfo, ResizeStrategy, Style},
     errors::prelude::*,
     input::{
         command::RunCommand,
         layout::{Run, RunPlugin, SplitDirection},
+        options::PaneFrameStyle,
     },
-    pane_size::{Offset, PaneGeom, Size, SizeInPixels, Viewport},
+    pane_size::{Dimension, Offset, PaneGeom, Size, SizeInPixels, Viewport},
 };
 
 use std::{
@@ -340,11 +343,19 @@ impl TiledPanes {
         }
         self.reset_boundaries();
     }
+    pub fn toggle_pane_frame(&mut self, pane_id: PaneId) {
+        if let Some(pane) = self.panes.get_mut(&pane_id) {
+            pane.set_borderless(!pane.borderless());
+            pane.set_frame(!pane.borderless() && self.draw_pane_frames);
+        }
+        // the pane keeps its geometry, only its content offset changes
+        self.reapply_pane_frames();
+    }
     pub fn can_split_pane_horizontally(&mut self, client_id: ClientId) -> bool {
         if let Some(active_pane_id) = &self.active_panes.get(&client_id) {
             if let Some(active_pane) = self.panes.get_mut(active_pane_id) {
                 let full_pane_size = active_pane.position_and_size();
-                if full_pane_size.rows.as_usize() < MIN_TERMINAL_HEIGHT * 2
+                if full_pane_size.rows.as_usize() < active_pane.min_height() * 2
                     || full_pane_size.is_stacked
                 {
                     return false;
@@ -359,7 +370,7 @@ impl TiledPanes {
         if let Some(active_pane_id) = &self.active_panes.get(&client_id) {
             if let Some(active_pane) = self.panes.get_mut(active_pane_id) {
                 let full_pane_size = active_pane.position_and_size();
-                if full_pane_size.cols.as_usize() < MIN_TERMINAL_WIDTH * 2
+                if full_pane_size.cols.as_usize() < active_pane.min_width() * 2
                     || full_pane_size.is_stacked
                 {
                     return false;
@@ -431,6 +442,45 @@ impl TiledPanes {
             self.relayout(SplitDirection::Horizontal);
         }
     }
+    /// Splits the active pane of the client, giving it `ratio` of its space (along the
+    /// `direction` of the split) and the new pane the rest. The ratio is clamped so that both
+    /// panes keep at least their minimum size.
+    pub fn split_pane_with_ratio(
+        &mut self,
+        pid: PaneId,
+        mut new_pane: Box<dyn Pane>,
+        direction: SplitDirection,
+        ratio: f64,
+        client_id: ClientId,
+    ) {
+        let active_pane_id = &self.active_panes.get(&client_id).unwrap();
+        let active_pane = self.panes.get_mut(active_pane_id).unwrap();
+        let full_pane_size = active_pane.position_and_size();
+        let (space, min_space) = match direction {
+            SplitDirection::Vertical => (full_pane_size.cols.as_usize(), active_pane.min_width()),
+            SplitDirection::Horizontal => {
+                (full_pane_size.rows.as_usize(), active_pane.min_height())
+            },
+        };
+        let min_ratio = f64::max(MIN_SPLIT_RATIO, min_space as f64 / space.max(1) as f64);
+        let ratio = if ratio.is_nan() {
+            0.5
+        } else {
+            ratio.clamp(min_ratio.min(0.5), 1.0 - min_ratio.min(0.5))
+        };
+        if let Some((first_winsize, second_winsize)) =
+            split_with_ratio(direction, &full_pane_size, ratio)
+        {
+            active_pane.set_geom(first_winsize);
+            new_pane.set_geom(second_winsize);
+            self.panes.insert(pid, new_pane);
+            // the panes are laid out across the direction of the split
+            match direction {
+                SplitDirection::Vertical => self.relayout(SplitDirection::Horizontal),
+                SplitDirection::Horizontal => self.relayout(SplitDirection::Vertical),
+            }
+        }
+    }
     pub fn focus_pane_for_all_clients(&mut self, pane_id: PaneId) {
         let connected_clients: Vec<ClientId> =
             self.connected_clients.borrow().iter().copied().collect();
@@ -635,7 +685,14 @@ impl TiledPanes {
     pub fn has_panes(&self) -> bool {
         !self.panes.is_empty()
     }
-    pub fn render(&mut self, output: &mut Output, floating_panes_are_visible: bool) -> Result<()> {
+    pub fn render(
+        &mut self,
+        output: &mut Output,
+        floating_panes_are_visible: bool,
+        focus_dimming: Option<f64>,
+        client_themes: &HashMap<ClientId, Palette>,
+        pane_frame_style: Option<PaneFrameStyle>,
+    ) -> Result<()> {
         let err_context = || "failed to render tiled panes";
 
         let connected_clients: Vec<ClientId> =
@@ -675,6 +732,9 @@ impl TiledPanes {
                     pane_is_stacked_under,
                     pane_is_stacked_over,
                     should_draw_pane_frames,
+                    focus_dimming,
+                    client_themes,
+                    pane_frame_style,
                 );
                 for client_id in &connected_clients {
                     let client_mode = self
@@ -827,6 +887,64 @@ impl TiledPanes {
         }
         self.set_pane_frames(self.draw_pane_frames);
     }
+    pub fn set_pane_min_size(&mut self, pane_id: PaneId, min_size: Size) {
+        if let Some(pane) = self.panes.get_mut(&pane_id) {
+            pane.set_min_size(Some(min_size));
+            // the pane might already be smaller than its new minimum
+            let display_area = *self.display_area.borrow();
+            self.resize(display_area);
+        }
+    }
+    /// Gives each pane a share of the space proportional to the size its content prefers (see
+    /// [`Pane::preferred_size`]), panes whose preferred size is unknown get the average share of
+    /// the panes beside them, or an equal share if none of them are known
+    pub fn resize_to_content(&mut self) {
+        self.distribute_space_by_preferred_size(SplitDirection::Horizontal);
+        self.distribute_space_by_preferred_size(SplitDirection::Vertical);
+        let display_area = *self.display_area.borrow();
+        self.resize(display_area);
+    }
+    fn distribute_space_by_preferred_size(&mut self, direction: SplitDirection) {
+        let span = |geom: &PaneGeom, direction: SplitDirection| match direction {
+            SplitDirection::Horizontal => (geom.x, geom.x + geom.cols.as_usize()),
+            SplitDirection::Vertical => (geom.y, geom.y + geom.rows.as_usize()),
+        };
+        let pane_spans: Vec<PaneSpan> = self
+            .panes
+            .iter()
+            .filter(|(pane_id, _pane)| !self.panes_to_hide.contains(pane_id))
+            .map(|(pane_id, pane)| {
+                let geom = pane.current_geom();
+                let dimension = match direction {
+                    SplitDirection::Horizontal => geom.cols,
+                    SplitDirection::Vertical => geom.rows,
+                };
+                PaneSpan {
+                    pane_id: *pane_id,
+                    along: span(&geom, direction),
+                    across: span(&geom, !direction),
+                    // stacked panes keep their size
+                    percent: dimension.as_percent().filter(|_| !geom.is_stacked),
+                    preferred_size: pane.preferred_size().map(|size| match direction {
+                        SplitDirection::Horizontal => size.cols.max(1),
+                        SplitDirection::Vertical => size.rows.max(1),
+                    }),
+                }
+            })
+            .collect();
+        let mut new_percents = HashMap::new();
+        distribute_space_in_split(&pane_spans, None, &mut new_percents);
+        for (pane_id, percent) in new_percents {
+            if let Some(pane) = self.panes.get_mut(&pane_id) {
+                let mut geom = pane.current_geom();
+                match direction {
+                    SplitDirection::Horizontal => geom.cols.set_percent(percent),
+                    SplitDirection::Vertical => geom.rows.set_percent(percent),
+                }
+                pane.set_geom(geom);
+            }
+        }
+    }
 
     pub fn resize_active_pane(
         &mut self,
@@ -886,6 +1004,27 @@ impl TiledPanes {
         Ok(())
     }
 
+    pub fn resize_pane_to(&mut self, pane_id: PaneId, size: Size) -> Result<()> {
+        let err_context = || format!("failed to resize tiled pane {pane_id:?} to {size:?}");
+
+        let mut pane_grid = TiledPaneGrid::new(
+            &mut self.panes,
+            &self.panes_to_hide,
+            *self.display_area.borrow(),
+            *self.viewport.borrow(),
+        );
+        pane_grid
+            .resize_pane_to(&pane_id, size)
+            .with_context(err_context)?;
+
+        for pane in self.panes.values_mut() {
+            resize_pty!(pane, self.os_api, self.senders, self.character_cell_size)
+                .with_context(err_context)?;
+        }
+        self.reset_boundaries();
+        Ok(())
+    }
+
     pub fn focus_next_pane(&mut self, client_id: ClientId) {
         let connected_clients: Vec<ClientId> =
             { self.connected_clients.borrow().iter().copied().collect() };
@@ -948,11 +1087,126 @@ impl TiledPanes {
         self.set_pane_active_at(next_active_pane_id);
         self.reset_boundaries();
     }
+    pub fn stack_panes(
+        &mut self,
+        root_pane_id: PaneId,
+        pane_ids_to_stack: Vec<PaneId>,
+        client_id: ClientId,
+    ) -> Result<()> {
+        let err_context = || format!("failed to stack panes with root pane {:?}", root_pane_id);
+        let any_pane_is_stacked = std::iter::once(&root_pane_id)
+            .chain(pane_ids_to_stack.iter())
+            .filter_map(|pane_id| self.panes.get(pane_id))
+            .any(|pane| pane.current_geom().is_stacked);
+        if any_pane_is_stacked {
+            return Err(anyhow!("Cannot stack panes that are already in a stack"))
+                .with_context(err_context);
+        }
+        // the other panes leave the grid, letting their neighbours (eg. the root pane) take over
+        // their space
+        let mut panes_to_stack = vec![];
+        for pane_id in pane_ids_to_stack {
+            if pane_id == root_pane_id || !self.panes.contains_key(&pane_id) {
+                continue;
+            }
+            if let Some(pane) = self.remove_pane(pane_id) {
+                panes_to_stack.push(pane);
+            }
+        }
+        let stack_geom = self
+            .panes
+            .get(&root_pane_id)
+            .with_context(err_context)?
+            .position_and_size();
+        if stack_geom.rows.as_usize() < panes_to_stack.len() + 1 {
+            for pane in panes_to_stack.drain(..) {
+                self.insert_pane(pane.pid(), pane);
+            }
+            return Err(anyhow!("Not enough room for stacked panes")).with_context(err_context);
+        }
+        let mut root_pane_geom = stack_geom;
+        root_pane_geom.is_stacked = true;
+        self.panes
+            .get_mut(&root_pane_id)
+            .with_context(err_context)?
+            .set_geom(root_pane_geom);
+        for (i, mut pane) in panes_to_stack.drain(..).enumerate() {
+            let mut pane_geom = root_pane_geom;
+            pane_geom.y = stack_geom.y + i + 1;
+            pane_geom.rows = Dimension::fixed(1);
+            pane.set_geom(pane_geom);
+            self.add_pane_with_existing_geom(pane.pid(), pane);
+        }
+        StackedPanes::new_from_btreemap(&mut self.panes, &self.panes_to_hide)
+            .resize_panes_in_stack(&root_pane_id, stack_geom)
+            .with_context(err_context)?;
+        self.focus_pane(root_pane_id, client_id);
+        self.set_pane_active_at(root_pane_id);
+        self.set_force_render();
+        self.reapply_pane_frames();
+        Ok(())
+    }
+    pub fn cycle_stack(&mut self, search_backwards: bool, client_id: ClientId) -> bool {
+        // returns true if the focus moved to another pane in the stack
+        let active_pane_id = match self.get_active_pane_id(client_id) {
+            Some(active_pane_id) => active_pane_id,
+            None => return false,
+        };
+        let active_pane_is_stacked = self
+            .panes
+            .get(&active_pane_id)
+            .map(|p| p.current_geom().is_stacked)
+            .unwrap_or(false);
+        if !active_pane_is_stacked {
+            return false;
+        }
+        let pane_ids_in_stack =
+            match StackedPanes::new_from_btreemap(&mut self.panes, &self.panes_to_hide)
+                .pane_ids_in_stack(&active_pane_id)
+            {
+                Ok(pane_ids_in_stack) => pane_ids_in_stack,
+                Err(e) => {
+                    log::error!("Failed to cycle stack: {:?}", e);
+                    return false;
+                },
+            };
+        let stack_len = pane_ids_in_stack.len();
+        let next_pane_id = pane_ids_in_stack
+            .iter()
+            .position(|pane_id| *pane_id == active_pane_id)
+            .map(|position| {
+                if search_backwards {
+                    (position + stack_len - 1) % stack_len
+                } else {
+                    (position + 1) % stack_len
+                }
+            })
+            .and_then(|next_position| pane_ids_in_stack.get(next_position).copied());
+        match next_pane_id {
+            Some(next_pane_id) if next_pane_id != active_pane_id => {
+                self.focus_pane(next_pane_id, client_id);
+                self.set_pane_active_at(next_pane_id);
+                self.set_force_render();
+                true
+            },
+            _ => false,
+        }
+    }
     fn set_pane_active_at(&mut self, pane_id: PaneId) {
         if let Some(pane) = self.get_pane_mut(pane_id) {
             pane.set_active_at(Instant::now());
         }
     }
+    pub fn split_direction_for_pane(&mut self, pane_id: PaneId) -> Option<SplitDirection> {
+        let cursor_height_width_ratio = self.cursor_height_width_ratio();
+        let pane_grid = TiledPaneGrid::new(
+            &mut self.panes,
+            &self.panes_to_hide,
+            *self.display_area.borrow(),
+            *self.viewport.borrow(),
+        );
+        pane_grid.split_direction_for_pane(&pane_id, cursor_height_width_ratio)
+    }
     pub fn cursor_height_width_ratio(&self) -> Option<usize> {
         let character_cell_size = self.character_cell_size.borrow();
         character_cell_size.map(|size_in_pixels| {
@@ -1550,6 +1804,47 @@ impl TiledPanes {
             closed_pane
         }
     }
+    /// Whether the survivor can take over the space of the pane with `merge_pane_into`. This looks
+    /// at the panes as they are laid out outside of fullscreen, so it can be asked before leaving it
+    pub fn can_merge_pane_into(&self, pane_id: PaneId, survivor_id: PaneId) -> bool {
+        match (self.panes.get(&pane_id), self.panes.get(&survivor_id)) {
+            (Some(pane), Some(survivor)) => {
+                let freed_space = pane.position_and_size();
+                merge_direction(&freed_space, &survivor.position_and_size()).is_some()
+                    && freed_space.cols.as_percent().is_some()
+                    && freed_space.rows.as_percent().is_some()
+            },
+            _ => false,
+        }
+    }
+    pub fn merge_pane_into(
+        &mut self,
+        pane_id: PaneId,
+        survivor_id: PaneId,
+    ) -> Option<Box<dyn Pane>> {
+        let mut pane_grid = TiledPaneGrid::new(
+            &mut self.panes,
+            &self.panes_to_hide,
+            *self.display_area.borrow(),
+            *self.viewport.borrow(),
+        );
+        if !pane_grid.fill_space_over_pane_with(pane_id, survivor_id) {
+            return None;
+        }
+        let merged_pane = self.panes.remove(&pane_id);
+        let clients_in_pane: Vec<ClientId> = self
+            .active_panes
+            .iter()
+            .filter(|(_client_id, active_pane_id)| **active_pane_id == pane_id)
+            .map(|(client_id, _active_pane_id)| *client_id)
+            .collect();
+        for client_id in clients_in_pane {
+            self.active_panes
+                .insert(client_id, survivor_id, &mut self.panes);
+        }
+        self.set_pane_frames(self.draw_pane_frames); // recalculate pane frames and update size
+        merged_pane
+    }
     pub fn hold_pane(
         &mut self,
         pane_id: PaneId,
@@ -1780,3 +2075,154 @@ pub fn pane_geom_is_inside_viewport(viewport: &Viewport, geom: &PaneGeom) -> boo
         && geom.x >= viewport.x
         && geom.x + geom.cols.as_usize() <= viewport.x + viewport.cols
 }
+
+// a pane as seen when distributing the space in one direction between the panes: where it starts
+// and ends in this direction and across it, its current percent of the space (None if it keeps its
+// size) and the size its content prefers
+#[derive(Debug, Clone, Copy)]
+struct PaneSpan {
+    pane_id: PaneId,
+    along: (usize, usize),
+    across: (usize, usize),
+    percent: Option<f64>,
+    preferred_size: Option<usize>,
+}
+
+// the panes between the lines that cut all the way through these panes in this direction or
+// across it, ie. the parts of the split node they make up
+fn split_parts(panes: &[PaneSpan], along_direction: bool) -> Vec<Vec<PaneSpan>> {
+    let span = |pane: &PaneSpan| {
+        if along_direction {
+            pane.along
+        } else {
+            pane.across
+        }
+    };
+    let mut panes = panes.to_vec();
+    panes.sort_by_key(|pane| span(pane));
+    let mut parts: Vec<Vec<PaneSpan>> = vec![];
+    let mut part_end = 0;
+    for pane in panes {
+        let (start, end) = span(&pane);
+        match parts.last_mut() {
+            Some(part) if start < part_end => {
+                part.push(pane);
+                part_end = part_end.max(end);
+            },
+            _ => {
+                parts.push(vec![pane]);
+                part_end = end;
+            },
+        }
+    }
+    parts
+}
+
+fn current_percent(panes: &[PaneSpan]) -> Option<f64> {
+    if panes.len() == 1 {
+        return panes[0].percent;
+    }
+    let parts = split_parts(panes, true);
+    if parts.len() > 1 {
+        return parts.iter().map(|part| current_percent(part)).sum();
+    }
+    let parts = split_parts(panes, false);
+    if parts.len() > 1 {
+        let percents: Option<Vec<f64>> = parts.iter().map(|part| current_percent(part)).collect();
+        return percents.map(|percents| percents.iter().sum::<f64>() / percents.len() as f64);
+    }
+    None
+}
+
+fn preferred_size(panes: &[PaneSpan]) -> Option<f64> {
+    if panes.len() == 1 {
+        return panes[0].preferred_size.map(|size| size as f64);
+    }
+    let parts = split_parts(panes, true);
+    if parts.len() > 1 {
+        let preferred_sizes: Vec<Option<f64>> =
+            parts.iter().map(|part| preferred_size(part)).collect();
+        if preferred_sizes.iter().all(|size| size.is_none()) {
+            return None;
+        }
+        return Some(with_fallback_sizes(preferred_sizes).iter().sum());
+    }
+    let parts = split_parts(panes, false);
+    if parts.len() > 1 {
+        // the parts across this direction take the same space in it, so they need as much of it
+        // as the largest of them
+        return parts
+            .iter()
+            .filter_map(|part| preferred_size(part))
+            .fold(None, |largest, size| Some(size.max(largest.unwrap_or(0.0))));
+    }
+    None
+}
+
+// parts whose preferred size is unknown get the average of those that are known, or an equal
+// share if none of them are
+fn with_fallback_sizes(preferred_sizes: Vec<Option<f64>>) -> Vec<f64> {
+    let known_sizes: Vec<f64> = preferred_sizes.iter().filter_map(|size| *size).collect();
+    let fallback_size = if known_sizes.is_empty() {
+        1.0
+    } else {
+        known_sizes.iter().sum::<f64>() / known_sizes.len() as f64
+    };
+    preferred_sizes
+        .into_iter()
+        .map(|size| size.unwrap_or(fallback_size))
+        .collect()
+}
+
+// gives the parts of the split node made up of these panes a share of `percent` (or of the space
+// they take now if it is None) proportional to their preferred size, and so on down the splits
+// they are made up of - parts that keep their size and the splits inside them are left as they are
+fn distribute_space_in_split(
+    panes: &[PaneSpan],
+    percent: Option<f64>,
+    new_percents: &mut HashMap<PaneId, f64>,
+) {
+    if panes.len() == 1 {
+        if let (Some(percent), Some(_current_percent)) = (percent, panes[0].percent) {
+            new_percents.insert(panes[0].pane_id, percent);
+        }
+        return;
+    }
+    let parts = split_parts(panes, true);
+    if parts.len() > 1 {
+        let current_percents: Vec<Option<f64>> =
+            parts.iter().map(|part| current_percent(part)).collect();
+        let available_percent =
+            percent.unwrap_or_else(|| current_percents.iter().flatten().sum::<f64>());
+        let flexible_parts: Vec<&Vec<PaneSpan>> = parts
+            .iter()
+            .zip(current_percents.iter())
+            .filter(|(_part, current_percent)| current_percent.is_some())
+            .map(|(part, _current_percent)| part)
+            .collect();
+        let sizes = with_fallback_sizes(
+            flexible_parts
+                .iter()
+                .map(|part| preferred_size(part))
+                .collect(),
+        );
+        let total_size: f64 = sizes.iter().sum();
+        for (part, size) in flexible_parts.iter().zip(sizes) {
+            let part_percent = available_percent * size / total_size;
+            distribute_space_in_split(part, Some(part_percent), new_percents);
+        }
+        for (part, current_percent) in parts.iter().zip(current_percents) {
+            if current_percent.is_none() {
+                distribute_space_in_split(part, None, new_percents);
+            }
+        }
+        return;
+    }
+    // parts across this direction all take the same space in it
+    let parts = split_parts(panes, false);
+    if parts.len() > 1 {
+        for part in parts {
+            distribute_space_in_split(&part, percent, new_percents);
+        }
+    }
+}
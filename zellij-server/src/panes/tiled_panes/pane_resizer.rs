@@ -1,9 +1,9 @@
 use super::stacked_panes::StackedPanes;
-use crate::tab::Pane;
+use crate::tab::{Pane, MIN_TERMINAL_HEIGHT, MIN_TERMINAL_WIDTH};
 use cassowary::{
     strength::{REQUIRED, STRONG},
     Expression, Solver, Variable,
-    WeightedRelation::EQ,
+    WeightedRelation::{EQ, GE},
 };
 use std::cell::RefCell;
 use std::collections::{HashMap, HashSet};
@@ -15,6 +15,10 @@ use zellij_utils::{
     pane_size::{Constraint, Dimension, PaneGeom},
 };
 
+// stronger than keeping the ratios of the other panes, but not required so that a minimum size
+// never makes the layout unsolvable
+const MIN_SIZE: f64 = STRONG * 10.0;
+
 pub struct PaneResizer<'a> {
     panes: Rc<RefCell<HashMap<PaneId, &'a mut Box<dyn Pane>>>>,
     vars: HashMap<PaneId, Variable>,
@@ -30,6 +34,7 @@ struct Span {
     pos: usize,
     size: Dimension,
     size_var: Variable,
+    min_size: Option<usize>, // only set for panes that were given a minimum size
 }
 
 type Grid = Vec<Vec<Span>>;
@@ -99,6 +104,12 @@ impl<'a> PaneResizer<'a> {
                 .iter_mut()
                 .filter(|s| !s.size.is_fixed() && !finalised.contains(&s.pid))
                 .collect();
+            let is_above_min_size =
+                |s: &Span| rounded_sizes[&s.size_var] > s.min_size.unwrap_or(0) as isize;
+            if error < 0 && flex_spans.iter().any(|s| is_above_min_size(s)) {
+                // rounding should not take a pane below its minimum size if it can be avoided
+                flex_spans.retain(|s| is_above_min_size(s));
+            }
             flex_spans.sort_by_key(|s| rounded_sizes[&s.size_var]);
             if error < 0 {
                 flex_spans.reverse();
@@ -269,6 +280,7 @@ impl<'a> PaneResizer<'a> {
                 pos: position_and_size.x,
                 size: position_and_size.cols,
                 size_var,
+                min_size: pane.min_size().map(|_| pane.min_width()),
             }),
             SplitDirection::Vertical => Some(Span {
                 pid: pane.pid(),
@@ -276,6 +288,7 @@ impl<'a> PaneResizer<'a> {
                 pos: position_and_size.y,
                 size: position_and_size.rows,
                 size_var,
+                min_size: pane.min_size().map(|_| pane.min_height()),
             }),
         }
     }
@@ -293,6 +306,19 @@ fn constrain_spans(space: usize, spans: &[Span]) -> HashSet<cassowary::Constrain
         }
     });
 
+    // Minimum sizes are only honored if there's room for all of them, otherwise we lay out the
+    // spans as if they had none
+    let min_space = spans.iter().fold(0, |a, s| {
+        let min_size = match (s.size.constraint, s.min_size, s.direction) {
+            (Constraint::Fixed(sz), _, _) => sz,
+            (_, Some(min_size), _) => min_size,
+            (_, None, SplitDirection::Horizontal) => MIN_TERMINAL_WIDTH,
+            (_, None, SplitDirection::Vertical) => MIN_TERMINAL_HEIGHT,
+        };
+        a + min_size
+    });
+    let can_honor_min_sizes = min_space <= space;
+
     // Spans must use all of the available space
     let full_size = spans
         .iter()
@@ -306,6 +332,9 @@ fn constrain_spans(space: usize, spans: &[Span]) -> HashSet<cassowary::Constrain
             Constraint::Percent(p) => constraints
                 .insert((span.size_var / new_flex_space as f64) | EQ(STRONG) | (p / 100.0)),
         };
+        if let Some(min_size) = span.min_size.filter(|_| can_honor_min_sizes) {
+            constraints.insert(span.size_var | GE(MIN_SIZE) | min_size as f64);
+        }
     }
 
     constraints
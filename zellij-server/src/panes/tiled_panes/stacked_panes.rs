@@ -252,6 +252,15 @@ impl<'a> StackedPanes<'a> {
             .reduce_height(percent);
         Ok(())
     }
+    pub fn pane_ids_in_stack(&self, id: &PaneId) -> Result<Vec<PaneId>> {
+        // sorted top to bottom
+        let err_context = || format!("Failed to get pane ids in stack");
+        let all_stacked_pane_positions = self.positions_in_stack(id).with_context(err_context)?;
+        Ok(all_stacked_pane_positions
+            .iter()
+            .map(|(pane_id, _pane_position)| *pane_id)
+            .collect())
+    }
     pub fn min_stack_height(&mut self, id: &PaneId) -> Result<usize> {
         let err_context = || format!("Failed to increase_stack_height");
         let all_stacked_pane_positions = self.positions_in_stack(id).with_context(err_context)?;
@@ -17,7 +17,10 @@ use std::cell::RefCell;
 use std::rc::Rc;
 
 pub const RESIZE_PERCENT: f64 = 5.0;
+const MAX_RESIZE_TO_ATTEMPTS: usize = 32; // per axis
 const DEFAULT_CURSOR_HEIGHT_WIDTH_RATIO: usize = 4;
+// the part of a split pane each of the two resulting panes gets at the very least
+pub const MIN_SPLIT_RATIO: f64 = 0.1;
 
 type BorderAndPaneIds = (usize, Vec<PaneId>);
 
@@ -219,6 +222,69 @@ impl<'a> TiledPaneGrid<'a> {
         }
     }
 
+    /// Resizes the pane to `size` (including its frame) by moving its borders one axis at a time,
+    /// growing or shrinking its neighbors. If they can't make room (eg. because they are fixed or
+    /// at their minimum size), the pane ends up as close to `size` as possible.
+    pub fn resize_pane_to(&mut self, pane_id: &PaneId, size: Size) -> Result<()> {
+        let err_context = || format!("failed to resize pane {pane_id:?} to {size:?}");
+
+        let axes = [
+            (size.cols, [Direction::Right, Direction::Left]),
+            (size.rows, [Direction::Down, Direction::Up]),
+        ];
+        for (target, directions) in axes {
+            let is_horizontal = directions[0].is_horizontal();
+            let space = if is_horizontal {
+                self.display_area.cols
+            } else {
+                self.display_area.rows
+            };
+            let current_size = |pane_grid: &Self| -> Result<usize> {
+                let geom = pane_grid
+                    .get_pane_geom(pane_id)
+                    .with_context(|| no_pane_id(pane_id))
+                    .with_context(err_context)?;
+                Ok(if is_horizontal {
+                    geom.cols.as_usize()
+                } else {
+                    geom.rows.as_usize()
+                })
+            };
+            let mut step = space;
+            for _ in 0..MAX_RESIZE_TO_ATTEMPTS {
+                let current = current_size(self)?;
+                let (resize, difference) = match current.cmp(&target) {
+                    Ordering::Equal => break,
+                    Ordering::Less => (Resize::Increase, target - current),
+                    Ordering::Greater => (Resize::Decrease, current - target),
+                };
+                step = std::cmp::min(step, difference);
+                let change_by = step as f64 * 100.0 / space as f64;
+                let mut resized = false;
+                for direction in directions {
+                    let strategy = ResizeStrategy {
+                        resize,
+                        direction: Some(direction),
+                        invert_on_boundaries: false,
+                    };
+                    // this fails or does nothing if there's no room on this side, in which case
+                    // we try the other one
+                    let _ = self.change_pane_size(pane_id, &strategy, (change_by, change_by));
+                    if current_size(self)? != current {
+                        resized = true;
+                        break;
+                    }
+                }
+                if !resized {
+                    if step == 1 {
+                        break;
+                    }
+                    step /= 2;
+                }
+            }
+        }
+        Ok(())
+    }
     /// Change a tiled panes size based on the given strategy.
     ///
     /// Returns true upon successful resize, false otherwise.
@@ -522,9 +588,15 @@ impl<'a> TiledPaneGrid<'a> {
             .get_pane_geom(pane_id)
             .with_context(|| no_pane_id(pane_id))
             .with_context(err_context)?;
+        let min_terminal_width = self
+            .panes
+            .borrow()
+            .get(pane_id)
+            .map(|pane| pane.min_width())
+            .unwrap_or(MIN_TERMINAL_WIDTH);
         let current_fixed_cols = pane.cols.as_usize();
         let will_reduce_by = ((self.display_area.cols as f64 / 100.0) * reduce_by) as usize;
-        if current_fixed_cols.saturating_sub(will_reduce_by) < MIN_TERMINAL_WIDTH {
+        if current_fixed_cols.saturating_sub(will_reduce_by) < min_terminal_width {
             Ok(false)
         } else if let Some(cols) = pane.cols.as_percent() {
             Ok(cols - reduce_by >= RESIZE_PERCENT)
@@ -544,7 +616,11 @@ impl<'a> TiledPaneGrid<'a> {
         let min_terminal_height = if pane.is_stacked {
             StackedPanes::new(self.panes.clone()).min_stack_height(pane_id)?
         } else {
-            MIN_TERMINAL_HEIGHT
+            self.panes
+                .borrow()
+                .get(pane_id)
+                .map(|pane| pane.min_height())
+                .unwrap_or(MIN_TERMINAL_HEIGHT)
         };
         let current_fixed_rows = pane.rows.as_usize();
         let will_reduce_by = ((self.display_area.rows as f64 / 100.0) * reduce_by) as usize;
@@ -1323,6 +1399,39 @@ impl<'a> TiledPaneGrid<'a> {
         }
         false
     }
+    pub fn fill_space_over_pane_with(&mut self, id: PaneId, survivor_id: PaneId) -> bool {
+        // true => the survivor took over all the space of the pane
+        // false => the panes do not share a whole border, so didn't do anything
+        let (direction, freed_width, freed_height) = {
+            let panes = self.panes.borrow();
+            let (pane, survivor) = match (panes.get(&id), panes.get(&survivor_id)) {
+                (Some(pane), Some(survivor)) => (pane, survivor),
+                _ => return false,
+            };
+            let direction = match merge_direction(&pane.current_geom(), &survivor.current_geom()) {
+                Some(direction) => direction,
+                None => return false,
+            };
+            let freed_space = pane.position_and_size();
+            (
+                direction,
+                freed_space.cols.as_percent(),
+                freed_space.rows.as_percent(),
+            )
+        };
+        if let (Some(freed_width), Some(freed_height)) = (freed_width, freed_height) {
+            self.grow_panes(&[survivor_id], direction, (freed_width, freed_height));
+            let side_length = match direction {
+                SplitDirection::Vertical => self.display_area.rows,
+                SplitDirection::Horizontal => self.display_area.cols,
+            };
+            self.panes.borrow_mut().remove(&id);
+            let mut pane_resizer = PaneResizer::new(self.panes.clone());
+            let _ = pane_resizer.layout(direction, side_length);
+            return true;
+        }
+        false
+    }
     pub fn find_room_for_new_pane(
         &self,
         cursor_height_width_ratio: Option<usize>,
@@ -1367,6 +1476,23 @@ impl<'a> TiledPaneGrid<'a> {
             direction.map(|direction| (*t_id_to_split, direction))
         })
     }
+    /// The direction in which to split this pane so that a new pane is placed along its longest
+    /// side
+    pub fn split_direction_for_pane(
+        &self,
+        pane_id: &PaneId,
+        cursor_height_width_ratio: Option<usize>,
+    ) -> Option<SplitDirection> {
+        let panes = self.panes.borrow();
+        let pane = panes.get(pane_id)?;
+        if pane.rows() * cursor_height_width_ratio.unwrap_or(DEFAULT_CURSOR_HEIGHT_WIDTH_RATIO)
+            > pane.cols()
+        {
+            Some(SplitDirection::Horizontal)
+        } else {
+            Some(SplitDirection::Vertical)
+        }
+    }
     pub fn has_room_for_new_stacked_pane(&self) -> bool {
         let panes = self.panes.borrow();
         let flexible_pane_in_stack: Vec<(&PaneId, &&mut Box<dyn Pane>)> = panes
@@ -1385,6 +1511,16 @@ impl<'a> TiledPaneGrid<'a> {
 }
 
 pub fn split(direction: SplitDirection, rect: &PaneGeom) -> Option<(PaneGeom, PaneGeom)> {
+    split_with_ratio(direction, rect, 0.5)
+}
+
+/// Splits the rect so that the first (left or top) part takes `ratio` of it and the second part
+/// takes the rest. The sizes are kept as percentages so that they hold when the tab is resized.
+pub fn split_with_ratio(
+    direction: SplitDirection,
+    rect: &PaneGeom,
+    ratio: f64,
+) -> Option<(PaneGeom, PaneGeom)> {
     let space = match direction {
         SplitDirection::Vertical => rect.cols,
         SplitDirection::Horizontal => rect.rows,
@@ -1392,23 +1528,23 @@ pub fn split(direction: SplitDirection, rect: &PaneGeom) -> Option<(PaneGeom, Pa
     if let Some(p) = space.as_percent() {
         let first_rect = match direction {
             SplitDirection::Vertical => PaneGeom {
-                cols: Dimension::percent(p / 2.0),
+                cols: Dimension::percent(p * ratio),
                 ..*rect
             },
             SplitDirection::Horizontal => PaneGeom {
-                rows: Dimension::percent(p / 2.0),
+                rows: Dimension::percent(p * ratio),
                 ..*rect
             },
         };
         let second_rect = match direction {
             SplitDirection::Vertical => PaneGeom {
                 x: first_rect.x + 1,
-                cols: first_rect.cols,
+                cols: Dimension::percent(p * (1.0 - ratio)),
                 ..*rect
             },
             SplitDirection::Horizontal => PaneGeom {
                 y: first_rect.y + 1,
-                rows: first_rect.rows,
+                rows: Dimension::percent(p * (1.0 - ratio)),
                 ..*rect
             },
         };
@@ -1417,3 +1553,26 @@ pub fn split(direction: SplitDirection, rect: &PaneGeom) -> Option<(PaneGeom, Pa
         None
     }
 }
+
+/// The direction in which a pane can take over the space of another, ie. the direction in which
+/// the two share a whole border, if they do
+pub fn merge_direction(pane: &PaneGeom, survivor: &PaneGeom) -> Option<SplitDirection> {
+    if pane.is_stacked || survivor.is_stacked {
+        return None;
+    }
+    let side_by_side = pane.y == survivor.y
+        && pane.rows.as_usize() == survivor.rows.as_usize()
+        && (pane.x + pane.cols.as_usize() == survivor.x
+            || survivor.x + survivor.cols.as_usize() == pane.x);
+    let one_above_the_other = pane.x == survivor.x
+        && pane.cols.as_usize() == survivor.cols.as_usize()
+        && (pane.y + pane.rows.as_usize() == survivor.y
+            || survivor.y + survivor.rows.as_usize() == pane.y);
+    if side_by_side {
+        Some(SplitDirection::Horizontal)
+    } else if one_above_the_other {
+        Some(SplitDirection::Vertical)
+    } else {
+        None
+    }
+}
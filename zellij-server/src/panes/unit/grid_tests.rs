@@ -1,9 +1,10 @@
 use super::super::Grid;
+use crate::output::Output;
 use crate::panes::grid::SixelImageStore;
 use crate::panes::link_handler::LinkHandler;
 use ::insta::assert_snapshot;
 use std::cell::RefCell;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::rc::Rc;
 use zellij_utils::{
     data::{Palette, Style},
@@ -32,6 +33,8 @@ fn vttest1_0() {
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
     let mut grid = Grid::new(
         41,
         110,
@@ -44,6 +47,8 @@ fn vttest1_0() {
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
     );
     let fixture_name = "vttest1-0";
     let content = read_fixture(fixture_name);
@@ -61,6 +66,8 @@ fn vttest1_1() {
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
     let mut grid = Grid::new(
         41,
         110,
@@ -73,6 +80,8 @@ fn vttest1_1() {
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
     );
     let fixture_name = "vttest1-1";
     let content = read_fixture(fixture_name);
@@ -90,6 +99,8 @@ fn vttest1_2() {
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
     let mut grid = Grid::new(
         41,
         110,
@@ -102,6 +113,8 @@ fn vttest1_2() {
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
     );
     let fixture_name = "vttest1-2";
     let content = read_fixture(fixture_name);
@@ -119,6 +132,8 @@ fn vttest1_3() {
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
     let mut grid = Grid::new(
         41,
         110,
@@ -131,6 +146,8 @@ fn vttest1_3() {
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
     );
     let fixture_name = "vttest1-3";
     let content = read_fixture(fixture_name);
@@ -148,6 +165,8 @@ fn vttest1_4() {
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
     let mut grid = Grid::new(
         41,
         110,
@@ -160,6 +179,8 @@ fn vttest1_4() {
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
     );
     let fixture_name = "vttest1-4";
     let content = read_fixture(fixture_name);
@@ -177,6 +198,8 @@ fn vttest1_5() {
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
     let mut grid = Grid::new(
         41,
         110,
@@ -189,6 +212,8 @@ fn vttest1_5() {
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
     );
     let fixture_name = "vttest1-5";
     let content = read_fixture(fixture_name);
@@ -206,6 +231,8 @@ fn vttest2_0() {
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
     let mut grid = Grid::new(
         41,
         110,
@@ -218,6 +245,8 @@ fn vttest2_0() {
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
     );
     let fixture_name = "vttest2-0";
     let content = read_fixture(fixture_name);
@@ -235,6 +264,8 @@ fn vttest2_1() {
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
     let mut grid = Grid::new(
         41,
         110,
@@ -247,6 +278,8 @@ fn vttest2_1() {
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
     );
     let fixture_name = "vttest2-1";
     let content = read_fixture(fixture_name);
@@ -264,6 +297,8 @@ fn vttest2_2() {
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
     let mut grid = Grid::new(
         41,
         110,
@@ -276,6 +311,8 @@ fn vttest2_2() {
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
     );
     let fixture_name = "vttest2-2";
     let content = read_fixture(fixture_name);
@@ -293,6 +330,8 @@ fn vttest2_3() {
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
     let mut grid = Grid::new(
         41,
         110,
@@ -305,6 +344,8 @@ fn vttest2_3() {
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
     );
     let fixture_name = "vttest2-3";
     let content = read_fixture(fixture_name);
@@ -322,6 +363,8 @@ fn vttest2_4() {
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
     let mut grid = Grid::new(
         41,
         110,
@@ -334,6 +377,8 @@ fn vttest2_4() {
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
     );
     let fixture_name = "vttest2-4";
     let content = read_fixture(fixture_name);
@@ -351,6 +396,8 @@ fn vttest2_5() {
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
     let mut grid = Grid::new(
         41,
         110,
@@ -363,6 +410,8 @@ fn vttest2_5() {
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
     );
     let fixture_name = "vttest2-5";
     let content = read_fixture(fixture_name);
@@ -380,6 +429,8 @@ fn vttest2_6() {
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
     let mut grid = Grid::new(
         41,
         110,
@@ -392,6 +443,8 @@ fn vttest2_6() {
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
     );
     let fixture_name = "vttest2-6";
     let content = read_fixture(fixture_name);
@@ -409,6 +462,8 @@ fn vttest2_7() {
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
     let mut grid = Grid::new(
         41,
         110,
@@ -421,6 +476,8 @@ fn vttest2_7() {
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
     );
     let fixture_name = "vttest2-7";
     let content = read_fixture(fixture_name);
@@ -438,6 +495,8 @@ fn vttest2_8() {
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
     let mut grid = Grid::new(
         41,
         110,
@@ -450,6 +509,8 @@ fn vttest2_8() {
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
     );
     let fixture_name = "vttest2-8";
     let content = read_fixture(fixture_name);
@@ -467,6 +528,8 @@ fn vttest2_9() {
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
     let mut grid = Grid::new(
         41,
         110,
@@ -479,6 +542,8 @@ fn vttest2_9() {
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
     );
     let fixture_name = "vttest2-9";
     let content = read_fixture(fixture_name);
@@ -496,6 +561,8 @@ fn vttest2_10() {
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
     let mut grid = Grid::new(
         41,
         110,
@@ -508,6 +575,8 @@ fn vttest2_10() {
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
     );
     let fixture_name = "vttest2-10";
     let content = read_fixture(fixture_name);
@@ -525,6 +594,8 @@ fn vttest2_11() {
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
     let mut grid = Grid::new(
         41,
         110,
@@ -537,6 +608,8 @@ fn vttest2_11() {
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
     );
     let fixture_name = "vttest2-11";
     let content = read_fixture(fixture_name);
@@ -554,6 +627,8 @@ fn vttest2_12() {
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
     let mut grid = Grid::new(
         41,
         110,
@@ -566,6 +641,8 @@ fn vttest2_12() {
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
     );
     let fixture_name = "vttest2-12";
     let content = read_fixture(fixture_name);
@@ -583,6 +660,8 @@ fn vttest2_13() {
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
     let mut grid = Grid::new(
         41,
         110,
@@ -595,6 +674,8 @@ fn vttest2_13() {
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
     );
     let fixture_name = "vttest2-13";
     let content = read_fixture(fixture_name);
@@ -612,6 +693,8 @@ fn vttest2_14() {
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
     let mut grid = Grid::new(
         41,
         110,
@@ -624,6 +707,8 @@ fn vttest2_14() {
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
     );
     let fixture_name = "vttest2-14";
     let content = read_fixture(fixture_name);
@@ -641,6 +726,8 @@ fn vttest3_0() {
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
     let mut grid = Grid::new(
         41,
         110,
@@ -653,6 +740,8 @@ fn vttest3_0() {
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
     );
     let fixture_name = "vttest3-0";
     let content = read_fixture(fixture_name);
@@ -670,6 +759,8 @@ fn vttest8_0() {
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
     let mut grid = Grid::new(
         51,
         97,
@@ -682,6 +773,8 @@ fn vttest8_0() {
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
     );
     let fixture_name = "vttest8-0";
     let content = read_fixture(fixture_name);
@@ -699,6 +792,8 @@ fn vttest8_1() {
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
     let mut grid = Grid::new(
         51,
         97,
@@ -711,6 +806,8 @@ fn vttest8_1() {
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
     );
     let fixture_name = "vttest8-1";
     let content = read_fixture(fixture_name);
@@ -728,6 +825,8 @@ fn vttest8_2() {
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
     let mut grid = Grid::new(
         51,
         97,
@@ -740,6 +839,8 @@ fn vttest8_2() {
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
     );
     let fixture_name = "vttest8-2";
     let content = read_fixture(fixture_name);
@@ -757,6 +858,8 @@ fn vttest8_3() {
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
     let mut grid = Grid::new(
         51,
         97,
@@ -769,6 +872,8 @@ fn vttest8_3() {
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
     );
     let fixture_name = "vttest8-3";
     let content = read_fixture(fixture_name);
@@ -786,6 +891,8 @@ fn vttest8_4() {
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
     let mut grid = Grid::new(
         51,
         97,
@@ -798,6 +905,8 @@ fn vttest8_4() {
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
     );
     let fixture_name = "vttest8-4";
     let content = read_fixture(fixture_name);
@@ -815,6 +924,8 @@ fn vttest8_5() {
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
     let mut grid = Grid::new(
         51,
         97,
@@ -827,6 +938,8 @@ fn vttest8_5() {
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
     );
     let fixture_name = "vttest8-5";
     let content = read_fixture(fixture_name);
@@ -844,6 +957,8 @@ fn csi_b() {
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
     let mut grid = Grid::new(
         51,
         97,
@@ -856,6 +971,8 @@ fn csi_b() {
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
     );
     let fixture_name = "csi-b";
     let content = read_fixture(fixture_name);
@@ -873,6 +990,8 @@ fn csi_capital_i() {
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
     let mut grid = Grid::new(
         51,
         97,
@@ -885,6 +1004,8 @@ fn csi_capital_i() {
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
     );
     let fixture_name = "csi-capital-i";
     let content = read_fixture(fixture_name);
@@ -902,6 +1023,8 @@ fn csi_capital_z() {
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
     let mut grid = Grid::new(
         51,
         97,
@@ -914,6 +1037,8 @@ fn csi_capital_z() {
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
     );
     let fixture_name = "csi-capital-z";
     let content = read_fixture(fixture_name);
@@ -931,6 +1056,8 @@ fn terminal_reports() {
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
     let mut grid = Grid::new(
         51,
         97,
@@ -943,6 +1070,8 @@ fn terminal_reports() {
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
     );
     let fixture_name = "terminal_reports";
     let content = read_fixture(fixture_name);
@@ -960,6 +1089,8 @@ fn wide_characters() {
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
     let mut grid = Grid::new(
         21,
         104,
@@ -972,6 +1103,8 @@ fn wide_characters() {
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
     );
     let fixture_name = "wide_characters";
     let content = read_fixture(fixture_name);
@@ -989,6 +1122,8 @@ fn wide_characters_line_wrap() {
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
     let mut grid = Grid::new(
         21,
         104,
@@ -1001,6 +1136,8 @@ fn wide_characters_line_wrap() {
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
     );
     let fixture_name = "wide_characters_line_wrap";
     let content = read_fixture(fixture_name);
@@ -1018,6 +1155,8 @@ fn insert_character_in_line_with_wide_character() {
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
     let mut grid = Grid::new(
         21,
         104,
@@ -1030,6 +1169,8 @@ fn insert_character_in_line_with_wide_character() {
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
     );
     let fixture_name = "wide_characters_middle_line_insert";
     let content = read_fixture(fixture_name);
@@ -1047,6 +1188,8 @@ fn delete_char_in_middle_of_line_with_widechar() {
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
     let mut grid = Grid::new(
         21,
         104,
@@ -1059,6 +1202,8 @@ fn delete_char_in_middle_of_line_with_widechar() {
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
     );
     let fixture_name = "wide-chars-delete-middle";
     let content = read_fixture(fixture_name);
@@ -1076,6 +1221,8 @@ fn delete_char_in_middle_of_line_with_multiple_widechars() {
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
     let mut grid = Grid::new(
         21,
         104,
@@ -1088,6 +1235,8 @@ fn delete_char_in_middle_of_line_with_multiple_widechars() {
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
     );
     let fixture_name = "wide-chars-delete-middle-after-multi";
     let content = read_fixture(fixture_name);
@@ -1105,6 +1254,8 @@ fn fish_wide_characters_override_clock() {
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
     let mut grid = Grid::new(
         21,
         104,
@@ -1117,6 +1268,8 @@ fn fish_wide_characters_override_clock() {
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
     );
     let fixture_name = "fish_wide_characters_override_clock";
     let content = read_fixture(fixture_name);
@@ -1134,6 +1287,8 @@ fn bash_delete_wide_characters() {
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
     let mut grid = Grid::new(
         21,
         104,
@@ -1146,6 +1301,8 @@ fn bash_delete_wide_characters() {
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
     );
     let fixture_name = "bash_delete_wide_characters";
     let content = read_fixture(fixture_name);
@@ -1163,6 +1320,8 @@ fn delete_wide_characters_before_cursor() {
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
     let mut grid = Grid::new(
         21,
         104,
@@ -1175,6 +1334,8 @@ fn delete_wide_characters_before_cursor() {
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
     );
     let fixture_name = "delete_wide_characters_before_cursor";
     let content = read_fixture(fixture_name);
@@ -1192,6 +1353,8 @@ fn delete_wide_characters_before_cursor_when_cursor_is_on_wide_character() {
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
     let mut grid = Grid::new(
         21,
         104,
@@ -1204,6 +1367,8 @@ fn delete_wide_characters_before_cursor_when_cursor_is_on_wide_character() {
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
     );
     let fixture_name = "delete_wide_characters_before_cursor_when_cursor_is_on_wide_character";
     let content = read_fixture(fixture_name);
@@ -1221,6 +1386,8 @@ fn delete_wide_character_under_cursor() {
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
     let mut grid = Grid::new(
         21,
         104,
@@ -1233,6 +1400,8 @@ fn delete_wide_character_under_cursor() {
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
     );
     let fixture_name = "delete_wide_character_under_cursor";
     let content = read_fixture(fixture_name);
@@ -1250,6 +1419,8 @@ fn replace_wide_character_under_cursor() {
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
     let mut grid = Grid::new(
         21,
         104,
@@ -1262,6 +1433,8 @@ fn replace_wide_character_under_cursor() {
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
     );
     let fixture_name = "replace_wide_character_under_cursor";
     let content = read_fixture(fixture_name);
@@ -1279,6 +1452,8 @@ fn wrap_wide_characters() {
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
     let mut grid = Grid::new(
         21,
         90,
@@ -1291,6 +1466,8 @@ fn wrap_wide_characters() {
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
     );
     let fixture_name = "wide_characters_full";
     let content = read_fixture(fixture_name);
@@ -1308,6 +1485,8 @@ fn wrap_wide_characters_on_size_change() {
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
     let mut grid = Grid::new(
         21,
         93,
@@ -1320,6 +1499,8 @@ fn wrap_wide_characters_on_size_change() {
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
     );
     let fixture_name = "wide_characters_full";
     let content = read_fixture(fixture_name);
@@ -1338,6 +1519,8 @@ fn unwrap_wide_characters_on_size_change() {
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
     let mut grid = Grid::new(
         21,
         93,
@@ -1350,6 +1533,8 @@ fn unwrap_wide_characters_on_size_change() {
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
     );
     let fixture_name = "wide_characters_full";
     let content = read_fixture(fixture_name);
@@ -1369,6 +1554,8 @@ fn wrap_wide_characters_in_the_middle_of_the_line() {
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
     let mut grid = Grid::new(
         21,
         91,
@@ -1381,6 +1568,8 @@ fn wrap_wide_characters_in_the_middle_of_the_line() {
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
     );
     let fixture_name = "wide_characters_line_middle";
     let content = read_fixture(fixture_name);
@@ -1398,6 +1587,8 @@ fn wrap_wide_characters_at_the_end_of_the_line() {
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
     let mut grid = Grid::new(
         21,
         90,
@@ -1410,6 +1601,8 @@ fn wrap_wide_characters_at_the_end_of_the_line() {
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
     );
     let fixture_name = "wide_characters_line_end";
     let content = read_fixture(fixture_name);
@@ -1427,6 +1620,8 @@ fn copy_selected_text_from_viewport() {
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
     let mut grid = Grid::new(
         27,
         125,
@@ -1439,6 +1634,8 @@ fn copy_selected_text_from_viewport() {
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
     );
     let fixture_name = "grid_copy";
     let content = read_fixture(fixture_name);
@@ -1464,6 +1661,8 @@ fn copy_wrapped_selected_text_from_viewport() {
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
     let mut grid = Grid::new(
         22,
         73,
@@ -1476,6 +1675,8 @@ fn copy_wrapped_selected_text_from_viewport() {
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
     );
     let fixture_name = "grid_copy_wrapped";
     let content = read_fixture(fixture_name);
@@ -1500,6 +1701,8 @@ fn copy_selected_text_from_lines_above() {
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
     let mut grid = Grid::new(
         27,
         125,
@@ -1512,6 +1715,8 @@ fn copy_selected_text_from_lines_above() {
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
     );
     let fixture_name = "grid_copy";
     let content = read_fixture(fixture_name);
@@ -1537,6 +1742,8 @@ fn copy_selected_text_from_lines_below() {
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
     let mut grid = Grid::new(
         27,
         125,
@@ -1549,6 +1756,8 @@ fn copy_selected_text_from_lines_below() {
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
     );
     let fixture_name = "grid_copy";
     let content = read_fixture(fixture_name);
@@ -1582,6 +1791,8 @@ fn run_bandwhich_from_fish_shell() {
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
     let mut grid = Grid::new(
         28,
         116,
@@ -1594,6 +1805,8 @@ fn run_bandwhich_from_fish_shell() {
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
     );
     let fixture_name = "fish_and_bandwhich";
     let content = read_fixture(fixture_name);
@@ -1611,6 +1824,8 @@ fn fish_tab_completion_options() {
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
     let mut grid = Grid::new(
         28,
         116,
@@ -1623,6 +1838,8 @@ fn fish_tab_completion_options() {
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
     );
     let fixture_name = "fish_tab_completion_options";
     let content = read_fixture(fixture_name);
@@ -1646,6 +1863,8 @@ pub fn fish_select_tab_completion_options() {
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
     let mut grid = Grid::new(
         28,
         116,
@@ -1658,6 +1877,8 @@ pub fn fish_select_tab_completion_options() {
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
     );
     let fixture_name = "fish_select_tab_completion_options";
     let content = read_fixture(fixture_name);
@@ -1685,6 +1906,8 @@ pub fn vim_scroll_region_down() {
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
     let mut grid = Grid::new(
         28,
         116,
@@ -1697,6 +1920,8 @@ pub fn vim_scroll_region_down() {
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
     );
     let fixture_name = "vim_scroll_region_down";
     let content = read_fixture(fixture_name);
@@ -1721,6 +1946,8 @@ pub fn vim_ctrl_d() {
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
     let mut grid = Grid::new(
         28,
         116,
@@ -1733,6 +1960,8 @@ pub fn vim_ctrl_d() {
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
     );
     let fixture_name = "vim_ctrl_d";
     let content = read_fixture(fixture_name);
@@ -1756,6 +1985,8 @@ pub fn vim_ctrl_u() {
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
     let mut grid = Grid::new(
         28,
         116,
@@ -1768,6 +1999,8 @@ pub fn vim_ctrl_u() {
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
     );
     let fixture_name = "vim_ctrl_u";
     let content = read_fixture(fixture_name);
@@ -1785,6 +2018,8 @@ pub fn htop() {
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
     let mut grid = Grid::new(
         28,
         116,
@@ -1797,6 +2032,8 @@ pub fn htop() {
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
     );
     let fixture_name = "htop";
     let content = read_fixture(fixture_name);
@@ -1814,6 +2051,8 @@ pub fn htop_scrolling() {
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
     let mut grid = Grid::new(
         28,
         116,
@@ -1826,6 +2065,8 @@ pub fn htop_scrolling() {
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
     );
     let fixture_name = "htop_scrolling";
     let content = read_fixture(fixture_name);
@@ -1843,6 +2084,8 @@ pub fn htop_right_scrolling() {
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
     let mut grid = Grid::new(
         28,
         116,
@@ -1855,6 +2098,8 @@ pub fn htop_right_scrolling() {
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
     );
     let fixture_name = "htop_right_scrolling";
     let content = read_fixture(fixture_name);
@@ -1882,6 +2127,8 @@ pub fn vim_overwrite() {
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
     let mut grid = Grid::new(
         28,
         116,
@@ -1894,6 +2141,8 @@ pub fn vim_overwrite() {
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
     );
     let fixture_name = "vim_overwrite";
     let content = read_fixture(fixture_name);
@@ -1913,6 +2162,8 @@ pub fn clear_scroll_region() {
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
     let mut grid = Grid::new(
         28,
         116,
@@ -1925,6 +2176,8 @@ pub fn clear_scroll_region() {
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
     );
     let fixture_name = "clear_scroll_region";
     let content = read_fixture(fixture_name);
@@ -1942,6 +2195,8 @@ pub fn display_tab_characters_properly() {
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
     let mut grid = Grid::new(
         28,
         116,
@@ -1954,6 +2209,8 @@ pub fn display_tab_characters_properly() {
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
     );
     let fixture_name = "tab_characters";
     let content = read_fixture(fixture_name);
@@ -1971,6 +2228,8 @@ pub fn neovim_insert_mode() {
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
     let mut grid = Grid::new(
         28,
         116,
@@ -1983,6 +2242,8 @@ pub fn neovim_insert_mode() {
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
     );
     let fixture_name = "nvim_insert";
     let content = read_fixture(fixture_name);
@@ -2000,6 +2261,8 @@ pub fn bash_cursor_linewrap() {
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
     let mut grid = Grid::new(
         28,
         116,
@@ -2012,6 +2275,8 @@ pub fn bash_cursor_linewrap() {
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
     );
     let fixture_name = "bash_cursor_linewrap";
     let content = read_fixture(fixture_name);
@@ -2031,6 +2296,8 @@ pub fn fish_paste_multiline() {
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
     let mut grid = Grid::new(
         28,
         149,
@@ -2043,6 +2310,8 @@ pub fn fish_paste_multiline() {
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
     );
     let fixture_name = "fish_paste_multiline";
     let content = read_fixture(fixture_name);
@@ -2060,6 +2329,8 @@ pub fn git_log() {
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
     let mut grid = Grid::new(
         28,
         149,
@@ -2072,6 +2343,8 @@ pub fn git_log() {
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
     );
     let fixture_name = "git_log";
     let content = read_fixture(fixture_name);
@@ -2091,6 +2364,8 @@ pub fn git_diff_scrollup() {
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
     let mut grid = Grid::new(
         28,
         149,
@@ -2103,6 +2378,8 @@ pub fn git_diff_scrollup() {
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
     );
     let fixture_name = "git_diff_scrollup";
     let content = read_fixture(fixture_name);
@@ -2120,6 +2397,8 @@ pub fn emacs_longbuf() {
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
     let mut grid = Grid::new(
         60,
         284,
@@ -2132,6 +2411,8 @@ pub fn emacs_longbuf() {
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
     );
     let fixture_name = "emacs_longbuf_tutorial";
     let content = read_fixture(fixture_name);
@@ -2149,6 +2430,8 @@ pub fn top_and_quit() {
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
     let mut grid = Grid::new(
         56,
         235,
@@ -2161,6 +2444,8 @@ pub fn top_and_quit() {
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
     );
     let fixture_name = "top_and_quit";
     let content = read_fixture(fixture_name);
@@ -2185,6 +2470,8 @@ pub fn exa_plus_omf_theme() {
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
     let mut grid = Grid::new(
         56,
         235,
@@ -2197,6 +2484,8 @@ pub fn exa_plus_omf_theme() {
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
     );
     let fixture_name = "exa_plus_omf_theme";
     let content = read_fixture(fixture_name);
@@ -2214,6 +2503,8 @@ pub fn scroll_up() {
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
     let mut grid = Grid::new(
         10,
         50,
@@ -2226,6 +2517,8 @@ pub fn scroll_up() {
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
     );
     let fixture_name = "scrolling";
     let content = read_fixture(fixture_name);
@@ -2244,6 +2537,8 @@ pub fn scroll_down() {
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
     let mut grid = Grid::new(
         10,
         50,
@@ -2256,6 +2551,8 @@ pub fn scroll_down() {
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
     );
     let fixture_name = "scrolling";
     let content = read_fixture(fixture_name);
@@ -2275,6 +2572,8 @@ pub fn scroll_up_with_line_wraps() {
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
     let mut grid = Grid::new(
         10,
         25,
@@ -2287,6 +2586,8 @@ pub fn scroll_up_with_line_wraps() {
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
     );
     let fixture_name = "scrolling";
     let content = read_fixture(fixture_name);
@@ -2305,6 +2606,8 @@ pub fn scroll_down_with_line_wraps() {
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
     let mut grid = Grid::new(
         10,
         25,
@@ -2317,6 +2620,8 @@ pub fn scroll_down_with_line_wraps() {
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
     );
     let fixture_name = "scrolling";
     let content = read_fixture(fixture_name);
@@ -2336,6 +2641,8 @@ pub fn scroll_up_decrease_width_and_scroll_down() {
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
     let mut grid = Grid::new(
         10,
         50,
@@ -2348,6 +2655,8 @@ pub fn scroll_up_decrease_width_and_scroll_down() {
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
     );
     let fixture_name = "scrolling";
     let content = read_fixture(fixture_name);
@@ -2372,6 +2681,8 @@ pub fn scroll_up_increase_width_and_scroll_down() {
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
     let mut grid = Grid::new(
         10,
         25,
@@ -2384,6 +2695,8 @@ pub fn scroll_up_increase_width_and_scroll_down() {
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
     );
     let fixture_name = "scrolling";
     let content = read_fixture(fixture_name);
@@ -2408,6 +2721,8 @@ pub fn move_cursor_below_scroll_region() {
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
     let mut grid = Grid::new(
         34,
         114,
@@ -2420,6 +2735,8 @@ pub fn move_cursor_below_scroll_region() {
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
     );
     let fixture_name = "move_cursor_below_scroll_region";
     let content = read_fixture(fixture_name);
@@ -2437,6 +2754,8 @@ pub fn insert_wide_characters_in_existing_line() {
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
     let mut grid = Grid::new(
         21,
         86,
@@ -2449,6 +2768,8 @@ pub fn insert_wide_characters_in_existing_line() {
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
     );
     let fixture_name = "chinese_characters_line_middle";
     let content = read_fixture(fixture_name);
@@ -2472,6 +2793,8 @@ pub fn full_screen_scroll_region_and_scroll_up() {
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
     let mut grid = Grid::new(
         54,
         80,
@@ -2484,6 +2807,8 @@ pub fn full_screen_scroll_region_and_scroll_up() {
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
     );
     let fixture_name = "scroll_region_full_screen";
     let content = read_fixture(fixture_name);
@@ -2504,6 +2829,8 @@ pub fn ring_bell() {
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
     let mut grid = Grid::new(
         134,
         64,
@@ -2516,6 +2843,8 @@ pub fn ring_bell() {
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
     );
     let fixture_name = "ring_bell";
     let content = read_fixture(fixture_name);
@@ -2533,6 +2862,8 @@ pub fn alternate_screen_change_size() {
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
     let mut grid = Grid::new(
         20,
         20,
@@ -2545,6 +2876,8 @@ pub fn alternate_screen_change_size() {
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
     );
     let fixture_name = "alternate_screen_change_size";
     let content = read_fixture(fixture_name);
@@ -2566,6 +2899,8 @@ pub fn fzf_fullscreen() {
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
     let mut grid = Grid::new(
         51,
         112,
@@ -2578,6 +2913,8 @@ pub fn fzf_fullscreen() {
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
     );
     let fixture_name = "fzf_fullscreen";
     let content = read_fixture(fixture_name);
@@ -2599,6 +2936,8 @@ pub fn replace_multiple_wide_characters_under_cursor() {
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
     let mut grid = Grid::new(
         51,
         112,
@@ -2611,6 +2950,8 @@ pub fn replace_multiple_wide_characters_under_cursor() {
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
     );
     let fixture_name = "replace_multiple_wide_characters";
     let content = read_fixture(fixture_name);
@@ -2632,6 +2973,8 @@ pub fn replace_non_wide_characters_with_wide_characters() {
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
     let mut grid = Grid::new(
         51,
         112,
@@ -2644,6 +2987,8 @@ pub fn replace_non_wide_characters_with_wide_characters() {
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
     );
     let fixture_name = "replace_non_wide_characters_with_wide_characters";
     let content = read_fixture(fixture_name);
@@ -2661,6 +3006,8 @@ pub fn scroll_down_ansi() {
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
     let mut grid = Grid::new(
         51,
         112,
@@ -2673,6 +3020,8 @@ pub fn scroll_down_ansi() {
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
     );
     let fixture_name = "scroll_down";
     let content = read_fixture(fixture_name);
@@ -2690,6 +3039,8 @@ pub fn ansi_capital_t() {
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
     let mut grid = Grid::new(
         51,
         112,
@@ -2702,6 +3053,8 @@ pub fn ansi_capital_t() {
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
     );
     let content = "foo\u{1b}[14Tbar".as_bytes();
     for byte in content {
@@ -2718,6 +3071,8 @@ pub fn ansi_capital_s() {
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
     let mut grid = Grid::new(
         51,
         112,
@@ -2730,6 +3085,8 @@ pub fn ansi_capital_s() {
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
     );
     let content = "\n\n\n\n\n\n\n\n\n\n\n\n\n\n\n\n\n\n\n\nfoo\u{1b}[14Sbar".as_bytes();
     for byte in content {
@@ -2746,6 +3103,8 @@ fn terminal_pixel_size_reports() {
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
     let mut grid = Grid::new(
         51,
         97,
@@ -2761,6 +3120,8 @@ fn terminal_pixel_size_reports() {
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
     );
     let fixture_name = "terminal_pixel_size_reports";
     let content = read_fixture(fixture_name);
@@ -2784,6 +3145,8 @@ fn terminal_pixel_size_reports_in_unsupported_terminals() {
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
     let mut grid = Grid::new(
         51,
         97,
@@ -2796,6 +3159,8 @@ fn terminal_pixel_size_reports_in_unsupported_terminals() {
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
     );
     let fixture_name = "terminal_pixel_size_reports";
     let content = read_fixture(fixture_name);
@@ -2820,6 +3185,8 @@ pub fn ansi_csi_at_sign() {
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
     let mut grid = Grid::new(
         51,
         112,
@@ -2832,6 +3199,8 @@ pub fn ansi_csi_at_sign() {
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
     );
     let content = "foo\u{1b}[2D\u{1b}[2@".as_bytes();
     for byte in content {
@@ -2852,6 +3221,8 @@ pub fn sixel_images_are_reaped_when_scrolled_off() {
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
     let mut grid = Grid::new(
         51,
         112,
@@ -2864,6 +3235,8 @@ pub fn sixel_images_are_reaped_when_scrolled_off() {
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
     );
     let pane_content = read_fixture("sixel-image-500px.six");
     for byte in pane_content {
@@ -2893,6 +3266,8 @@ pub fn sixel_images_are_reaped_when_resetting() {
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
     let mut grid = Grid::new(
         51,
         112,
@@ -2905,6 +3280,8 @@ pub fn sixel_images_are_reaped_when_resetting() {
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
     );
     let pane_content = read_fixture("sixel-image-500px.six");
     for byte in pane_content {
@@ -2931,6 +3308,8 @@ pub fn sixel_image_in_alternate_buffer() {
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
     let mut grid = Grid::new(
         30,
         112,
@@ -2943,6 +3322,8 @@ pub fn sixel_image_in_alternate_buffer() {
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
     );
 
     let move_to_alternate_screen = "\u{1b}[?1049h";
@@ -2980,6 +3361,8 @@ pub fn sixel_with_image_scrolling_decsdm() {
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
     let mut grid = Grid::new(
         30,
         112,
@@ -2992,6 +3375,8 @@ pub fn sixel_with_image_scrolling_decsdm() {
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
     );
 
     // enter DECSDM
@@ -3048,6 +3433,8 @@ pub fn osc_4_background_query() {
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
     let mut grid = Grid::new(
         51,
         97,
@@ -3060,6 +3447,8 @@ pub fn osc_4_background_query() {
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
     );
     let content = "\u{1b}]10;?\u{1b}\\";
     for byte in content.as_bytes() {
@@ -3084,6 +3473,8 @@ pub fn osc_4_foreground_query() {
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
     let mut grid = Grid::new(
         51,
         97,
@@ -3096,6 +3487,8 @@ pub fn osc_4_foreground_query() {
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
     );
     let content = "\u{1b}]11;?\u{1b}\\";
     for byte in content.as_bytes() {
@@ -3122,6 +3515,8 @@ pub fn osc_4_color_query() {
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
     let mut grid = Grid::new(
         51,
         97,
@@ -3134,6 +3529,8 @@ pub fn osc_4_color_query() {
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
     );
     let content = "\u{1b}]4;222;?\u{1b}\\";
     for byte in content.as_bytes() {
@@ -3158,6 +3555,8 @@ pub fn xtsmgraphics_color_register_count() {
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
     let mut grid = Grid::new(
         51,
         97,
@@ -3170,6 +3569,8 @@ pub fn xtsmgraphics_color_register_count() {
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
     );
     let content = "\u{1b}[?1;1;S\u{1b}\\";
     for byte in content.as_bytes() {
@@ -3198,6 +3599,8 @@ pub fn xtsmgraphics_pixel_graphics_geometry() {
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
     let mut grid = Grid::new(
         51,
         97,
@@ -3210,6 +3613,8 @@ pub fn xtsmgraphics_pixel_graphics_geometry() {
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
     );
     let content = "\u{1b}[?2;1;S\u{1b}\\";
     for byte in content.as_bytes() {
@@ -3238,6 +3643,8 @@ pub fn cursor_hide_persists_through_alternate_screen() {
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
     let mut grid = Grid::new(
         30,
         112,
@@ -3250,6 +3657,8 @@ pub fn cursor_hide_persists_through_alternate_screen() {
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
     );
 
     let hide_cursor = "\u{1b}[?25l";
@@ -3292,6 +3701,8 @@ fn table_ui_component() {
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
     let mut grid = Grid::new(
         41,
         110,
@@ -3304,6 +3715,8 @@ fn table_ui_component() {
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
     );
     let fixture_name = "table-ui-component";
     let content = read_fixture(fixture_name);
@@ -3321,6 +3734,8 @@ fn table_ui_component_with_coordinates() {
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
     let mut grid = Grid::new(
         41,
         110,
@@ -3333,6 +3748,8 @@ fn table_ui_component_with_coordinates() {
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
     );
     let fixture_name = "table-ui-component-with-coordinates";
     let content = read_fixture(fixture_name);
@@ -3350,6 +3767,8 @@ fn ribbon_ui_component() {
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
     let mut grid = Grid::new(
         41,
         110,
@@ -3362,6 +3781,8 @@ fn ribbon_ui_component() {
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
     );
     let fixture_name = "ribbon-ui-component";
     let content = read_fixture(fixture_name);
@@ -3379,6 +3800,8 @@ fn ribbon_ui_component_with_coordinates() {
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
     let mut grid = Grid::new(
         41,
         110,
@@ -3391,6 +3814,8 @@ fn ribbon_ui_component_with_coordinates() {
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
     );
     let fixture_name = "ribbon-ui-component-with-coordinates";
     let content = read_fixture(fixture_name);
@@ -3408,6 +3833,8 @@ fn nested_list_ui_component() {
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
     let mut grid = Grid::new(
         41,
         120,
@@ -3420,6 +3847,8 @@ fn nested_list_ui_component() {
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
     );
     let fixture_name = "nested-list-ui-component";
     let content = read_fixture(fixture_name);
@@ -3437,6 +3866,8 @@ fn nested_list_ui_component_with_coordinates() {
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
     let mut grid = Grid::new(
         41,
         120,
@@ -3449,6 +3880,8 @@ fn nested_list_ui_component_with_coordinates() {
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
     );
     let fixture_name = "nested-list-ui-component-with-coordinates";
     let content = read_fixture(fixture_name);
@@ -3466,6 +3899,8 @@ fn text_ui_component() {
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
     let mut grid = Grid::new(
         41,
         120,
@@ -3478,6 +3913,8 @@ fn text_ui_component() {
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
     );
     let fixture_name = "text-ui-component";
     let content = read_fixture(fixture_name);
@@ -3495,6 +3932,8 @@ fn text_ui_component_with_coordinates() {
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
     let mut grid = Grid::new(
         41,
         120,
@@ -3507,6 +3946,8 @@ fn text_ui_component_with_coordinates() {
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
     );
     let fixture_name = "text-ui-component-with-coordinates";
     let content = read_fixture(fixture_name);
@@ -3515,3 +3956,292 @@ fn text_ui_component_with_coordinates() {
     }
     assert_snapshot!(format!("{:?}", grid));
 }
+
+fn grid_after_entering_alternate_screen(keep_scrollback_in_alternate_screen: bool) -> Grid {
+    let mut vte_parser = vte::Parser::new();
+    let sixel_image_store = Rc::new(RefCell::new(SixelImageStore::default()));
+    let terminal_emulator_color_codes = Rc::new(RefCell::new(HashMap::new()));
+    let debug = false;
+    let arrow_fonts = true;
+    let styled_underlines = true;
+    let transparent_background = false;
+    let mut grid = Grid::new(
+        3,
+        20,
+        Rc::new(RefCell::new(Palette::default())),
+        terminal_emulator_color_codes,
+        Rc::new(RefCell::new(LinkHandler::new())),
+        Rc::new(RefCell::new(None)),
+        sixel_image_store,
+        Style::default(),
+        debug,
+        arrow_fonts,
+        styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
+    );
+    let primary_screen_content = "line 1\r\nline 2\r\nline 3\r\nline 4\r\nline 5";
+    let alternate_screen_content = "\u{1b}[?1049halt 1\r\nalt 2\r\nalt 3";
+    for byte in primary_screen_content
+        .as_bytes()
+        .iter()
+        .chain(alternate_screen_content.as_bytes())
+    {
+        vte_parser.advance(&mut grid, *byte);
+    }
+    grid
+}
+
+#[test]
+pub fn alternate_screen_hides_scrollback() {
+    let mut grid = grid_after_entering_alternate_screen(false);
+    assert_eq!(grid.scrollback_position_and_length(), (0, 0));
+    grid.scroll_up_one_line();
+    assert!(
+        !grid.is_scrolled,
+        "nothing to scroll to in the alternate screen"
+    );
+    assert!(!format!("{:?}", grid).contains("line"));
+}
+
+#[test]
+pub fn alternate_screen_keeps_scrollback_accessible() {
+    let mut grid = grid_after_entering_alternate_screen(true);
+    assert_eq!(grid.scrollback_position_and_length(), (0, 5));
+    grid.scroll_up_one_line();
+    grid.scroll_up_one_line();
+    assert!(
+        grid.is_scrolled,
+        "the primary screen is above the alternate screen"
+    );
+    let viewport = format!("{:?}", grid);
+    assert!(viewport.contains("line 4") && viewport.contains("line 5"));
+    assert!(viewport.contains("alt 1") && !viewport.contains("alt 3"));
+    grid.reset_viewport();
+    let mut vte_parser = vte::Parser::new();
+    for byte in "\u{1b}[?1049l".as_bytes() {
+        vte_parser.advance(&mut grid, *byte);
+    }
+    assert_eq!(
+        grid.scrollback_position_and_length(),
+        (0, 2),
+        "the primary scrollback is restored as it was once the alternate screen is exited"
+    );
+}
+
+#[test]
+pub fn custom_tabstops_set_with_hts_and_cleared_with_tbc() {
+    let mut vte_parser = vte::Parser::new();
+    let sixel_image_store = Rc::new(RefCell::new(SixelImageStore::default()));
+    let terminal_emulator_color_codes = Rc::new(RefCell::new(HashMap::new()));
+    let debug = false;
+    let arrow_fonts = true;
+    let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
+    let mut grid = Grid::new(
+        10,
+        40,
+        Rc::new(RefCell::new(Palette::default())),
+        terminal_emulator_color_codes,
+        Rc::new(RefCell::new(LinkHandler::new())),
+        Rc::new(RefCell::new(None)),
+        sixel_image_store,
+        Style::default(),
+        debug,
+        arrow_fonts,
+        styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
+    );
+    // clear all default tabstops, then set tabstops at columns 5 and 13 and return home
+    let set_custom_tabstops = "\u{1b}[3g\u{1b}[1;6H\u{1b}H\u{1b}[1;14H\u{1b}H\u{1b}[H";
+    for byte in set_custom_tabstops.as_bytes() {
+        vte_parser.advance(&mut grid, *byte);
+    }
+    vte_parser.advance(&mut grid, b'\t');
+    assert_eq!(
+        grid.cursor_coordinates(),
+        Some((5, 0)),
+        "first custom tabstop"
+    );
+    vte_parser.advance(&mut grid, b'\t');
+    assert_eq!(
+        grid.cursor_coordinates(),
+        Some((13, 0)),
+        "second custom tabstop"
+    );
+
+    // clear the tabstop under the cursor (column 13)
+    let clear_current_tabstop = "\u{1b}[0g\r";
+    for byte in clear_current_tabstop.as_bytes() {
+        vte_parser.advance(&mut grid, *byte);
+    }
+    vte_parser.advance(&mut grid, b'\t');
+    vte_parser.advance(&mut grid, b'\t');
+    assert_eq!(
+        grid.cursor_coordinates(),
+        Some((39, 0)),
+        "no more tabstops, cursor moves to the last column"
+    );
+
+    // custom tabstops survive a resize
+    grid.change_size(10, 60);
+    vte_parser.advance(&mut grid, b'\r');
+    vte_parser.advance(&mut grid, b'\t');
+    assert_eq!(
+        grid.cursor_coordinates(),
+        Some((5, 0)),
+        "custom tabstop kept after resize"
+    );
+}
+
+#[test]
+pub fn sixel_image_bytes_are_passed_through_unchanged() {
+    let mut vte_parser = vte::Parser::new();
+    let sixel_image_store = Rc::new(RefCell::new(SixelImageStore::default()));
+    let terminal_emulator_color_codes = Rc::new(RefCell::new(HashMap::new()));
+    let character_cell_size = Rc::new(RefCell::new(Some(SizeInPixels {
+        width: 8,
+        height: 21,
+    })));
+    let debug = false;
+    let arrow_fonts = true;
+    let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
+    let mut grid = Grid::new(
+        10,
+        40,
+        Rc::new(RefCell::new(Palette::default())),
+        terminal_emulator_color_codes,
+        Rc::new(RefCell::new(LinkHandler::new())),
+        character_cell_size,
+        sixel_image_store.clone(),
+        Style::default(),
+        debug,
+        arrow_fonts,
+        styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
+    );
+    let sixel_payload = "\u{1b}P0;1;0q\"1;1;4;6#0;2;100;0;0#0~~~~\u{1b}\\";
+    for byte in sixel_payload.as_bytes() {
+        vte_parser.advance(&mut grid, *byte);
+    }
+    let (_character_chunks, sixel_image_chunks) = grid.read_changes(0, 0);
+    assert_eq!(sixel_image_chunks.len(), 1, "image rendered once");
+    let chunk = &sixel_image_chunks[0];
+    let rendered_image = sixel_image_store.borrow_mut().serialize_image(
+        chunk.sixel_image_id,
+        chunk.sixel_image_pixel_x,
+        chunk.sixel_image_pixel_y,
+        chunk.sixel_image_pixel_width,
+        chunk.sixel_image_pixel_height,
+    );
+    assert_eq!(
+        rendered_image.as_deref(),
+        Some(sixel_payload),
+        "image bytes round-tripped unchanged"
+    );
+    let clipped_image = sixel_image_store.borrow_mut().serialize_image(
+        chunk.sixel_image_id,
+        0,
+        0,
+        2,
+        chunk.sixel_image_pixel_height,
+    );
+    assert_ne!(
+        clipped_image.as_deref(),
+        Some(sixel_payload),
+        "clipped image is re-encoded rather than passed through"
+    );
+}
+
+#[test]
+pub fn hyperlink_survives_render_and_reflow_and_resolves_on_click() {
+    let mut vte_parser = vte::Parser::new();
+    let sixel_image_store = Rc::new(RefCell::new(SixelImageStore::default()));
+    let terminal_emulator_color_codes = Rc::new(RefCell::new(HashMap::new()));
+    let character_cell_size = Rc::new(RefCell::new(Some(SizeInPixels {
+        width: 8,
+        height: 21,
+    })));
+    let link_handler = Rc::new(RefCell::new(LinkHandler::new()));
+    let debug = false;
+    let arrow_fonts = true;
+    let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
+    let mut grid = Grid::new(
+        10,
+        20,
+        Rc::new(RefCell::new(Palette::default())),
+        terminal_emulator_color_codes,
+        link_handler.clone(),
+        character_cell_size.clone(),
+        sixel_image_store.clone(),
+        Style::default(),
+        debug,
+        arrow_fonts,
+        styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
+    );
+    let uri = "https://zellij.dev/documentation";
+    // the link text is wider than the grid, so it wraps into a second line
+    let content = format!(
+        "go to \u{1b}]8;;{}\u{1b}\\the zellij website and docs\u{1b}]8;;\u{1b}\\ now",
+        uri
+    );
+    for byte in content.as_bytes() {
+        vte_parser.advance(&mut grid, *byte);
+    }
+
+    let (character_chunks, _raw_vte_output, _sixel_image_chunks) = grid
+        .render(0, 0, &Style::default())
+        .unwrap()
+        .expect("grid has changes to render");
+    let mut output = Output::new(sixel_image_store, character_cell_size, styled_underlines);
+    output.add_clients(&HashSet::from([1]), link_handler, None);
+    output
+        .add_character_chunks_to_client(1, character_chunks, None)
+        .unwrap();
+    let rendered = output.serialize().unwrap().remove(&1).unwrap();
+    assert!(
+        rendered.contains(&format!("\u{1b}]8;;{}\u{1b}\\", uri)),
+        "link is re-emitted when rendering"
+    );
+
+    assert_eq!(
+        grid.hyperlink_at(&Position::new(0, 3)),
+        None,
+        "before the link"
+    );
+    assert_eq!(
+        grid.hyperlink_at(&Position::new(0, 10)).as_deref(),
+        Some(uri)
+    );
+    assert_eq!(
+        grid.hyperlink_at(&Position::new(1, 5)).as_deref(),
+        Some(uri),
+        "wrapped part of the link"
+    );
+    assert_eq!(
+        grid.hyperlink_at(&Position::new(1, 15)),
+        None,
+        "after the link"
+    );
+
+    grid.change_size(10, 40);
+    assert_eq!(
+        grid.hyperlink_at(&Position::new(0, 30)).as_deref(),
+        Some(uri),
+        "link kept when its line is reflowed"
+    );
+    assert_eq!(
+        grid.hyperlink_at(&Position::new(0, 35)),
+        None,
+        "after the link"
+    );
+}
@@ -31,6 +31,8 @@ fn create_pane() -> TerminalPane {
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
     let mut terminal_pane = TerminalPane::new(
         pid,
         fake_win_size,
@@ -47,6 +49,8 @@ fn create_pane() -> TerminalPane {
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
     ); // 0 is the pane index
     let content = read_fixture();
     terminal_pane.handle_pty_bytes(content);
@@ -419,3 +423,23 @@ pub fn searching_whole_word_case_insensitive() {
         format!("{:?}", terminal_pane.grid)
     );
 }
+
+#[test]
+pub fn clearing_a_highlighted_search_restores_the_active_search() {
+    let mut terminal_pane = create_pane();
+    terminal_pane.update_search_term("tortor");
+    terminal_pane.search_and_highlight("quam", true);
+    assert_eq!(terminal_pane.grid.search_results.needle, "quam");
+
+    terminal_pane.clear_highlighted_search();
+    assert_snapshot!(
+        "grid_copy_tortor_highlighted",
+        format!("{:?}", terminal_pane.grid)
+    );
+    // the search that is active now was not highlighted this way, so it is left alone
+    terminal_pane.clear_highlighted_search();
+    assert_snapshot!(
+        "grid_copy_tortor_highlighted",
+        format!("{:?}", terminal_pane.grid)
+    );
+}
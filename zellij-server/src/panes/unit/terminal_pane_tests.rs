@@ -1,10 +1,11 @@
 use super::super::TerminalPane;
+use crate::output::Output;
 use crate::panes::sixel::SixelImageStore;
 use crate::panes::LinkHandler;
 use crate::tab::Pane;
 use ::insta::assert_snapshot;
 use std::cell::RefCell;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::rc::Rc;
 use zellij_utils::{
     data::{Palette, Style},
@@ -39,6 +40,8 @@ pub fn scrolling_inside_a_pane() {
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
     let mut terminal_pane = TerminalPane::new(
         pid,
         fake_win_size,
@@ -55,6 +58,8 @@ pub fn scrolling_inside_a_pane() {
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
     ); // 0 is the pane index
     let mut text_to_fill_pane = String::new();
     for i in 0..30 {
@@ -87,6 +92,8 @@ pub fn sixel_image_inside_terminal_pane() {
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
     let mut terminal_pane = TerminalPane::new(
         pid,
         fake_win_size,
@@ -103,6 +110,8 @@ pub fn sixel_image_inside_terminal_pane() {
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
     ); // 0 is the pane index
     let sixel_image_bytes = "\u{1b}Pq
         #0;2;0;0;0#1;2;100;100;0#2;2;0;100;0
@@ -135,6 +144,8 @@ pub fn partial_sixel_image_inside_terminal_pane() {
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
     let mut terminal_pane = TerminalPane::new(
         pid,
         fake_win_size,
@@ -151,6 +162,8 @@ pub fn partial_sixel_image_inside_terminal_pane() {
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
     ); // 0 is the pane index
     let pane_content = read_fixture("sixel-image-500px.six");
     terminal_pane.handle_pty_bytes(pane_content);
@@ -177,6 +190,8 @@ pub fn overflowing_sixel_image_inside_terminal_pane() {
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
     let mut terminal_pane = TerminalPane::new(
         pid,
         fake_win_size,
@@ -193,6 +208,8 @@ pub fn overflowing_sixel_image_inside_terminal_pane() {
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
     ); // 0 is the pane index
     let pane_content = read_fixture("sixel-image-500px.six");
     terminal_pane.handle_pty_bytes(pane_content);
@@ -218,6 +235,8 @@ pub fn scrolling_through_a_sixel_image() {
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
     let mut terminal_pane = TerminalPane::new(
         pid,
         fake_win_size,
@@ -234,6 +253,8 @@ pub fn scrolling_through_a_sixel_image() {
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
     ); // 0 is the pane index
     let mut text_to_fill_pane = String::new();
     for i in 0..30 {
@@ -270,6 +291,8 @@ pub fn multiple_sixel_images_in_pane() {
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
     let mut terminal_pane = TerminalPane::new(
         pid,
         fake_win_size,
@@ -286,6 +309,8 @@ pub fn multiple_sixel_images_in_pane() {
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
     ); // 0 is the pane index
     let mut text_to_fill_pane = String::new();
     for i in 0..5 {
@@ -320,6 +345,8 @@ pub fn resizing_pane_with_sixel_images() {
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
     let mut terminal_pane = TerminalPane::new(
         pid,
         fake_win_size,
@@ -336,6 +363,8 @@ pub fn resizing_pane_with_sixel_images() {
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
     ); // 0 is the pane index
     let mut text_to_fill_pane = String::new();
     for i in 0..5 {
@@ -373,6 +402,8 @@ pub fn changing_character_cell_size_with_sixel_images() {
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
     let mut terminal_pane = TerminalPane::new(
         pid,
         fake_win_size,
@@ -389,6 +420,8 @@ pub fn changing_character_cell_size_with_sixel_images() {
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
     ); // 0 is the pane index
     let mut text_to_fill_pane = String::new();
     for i in 0..5 {
@@ -431,6 +464,8 @@ pub fn keep_working_after_corrupted_sixel_image() {
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
     let mut terminal_pane = TerminalPane::new(
         pid,
         fake_win_size,
@@ -447,6 +482,8 @@ pub fn keep_working_after_corrupted_sixel_image() {
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
     ); // 0 is the pane index
 
     let sixel_image_bytes = "\u{1b}PI AM CORRUPTED BWAHAHAq
@@ -487,6 +524,8 @@ pub fn pane_with_frame_position_is_on_frame() {
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
     let mut terminal_pane = TerminalPane::new(
         pid,
         fake_win_size,
@@ -503,6 +542,8 @@ pub fn pane_with_frame_position_is_on_frame() {
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
     ); // 0 is the pane index
 
     terminal_pane.set_content_offset(Offset::frame(1));
@@ -579,6 +620,8 @@ pub fn pane_with_bottom_and_right_borders_position_is_on_frame() {
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
     let mut terminal_pane = TerminalPane::new(
         pid,
         fake_win_size,
@@ -595,6 +638,8 @@ pub fn pane_with_bottom_and_right_borders_position_is_on_frame() {
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
     ); // 0 is the pane index
 
     terminal_pane.set_content_offset(Offset::shift(1, 1));
@@ -671,6 +716,8 @@ pub fn frameless_pane_position_is_on_frame() {
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
     let mut terminal_pane = TerminalPane::new(
         pid,
         fake_win_size,
@@ -687,6 +734,8 @@ pub fn frameless_pane_position_is_on_frame() {
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
     ); // 0 is the pane index
 
     terminal_pane.set_content_offset(Offset::default());
@@ -740,3 +789,130 @@ pub fn frameless_pane_position_is_on_frame() {
     assert!(!terminal_pane.position_is_on_frame(&Position::new(30, 130)));
     assert!(!terminal_pane.position_is_on_frame(&Position::new(30, 131)));
 }
+
+#[test]
+pub fn transparent_background_only_carries_the_background_color_to_unpainted_cells() {
+    let client_id = 1;
+    let mut fake_win_size = PaneGeom::default();
+    fake_win_size.cols.set_inner(10);
+    fake_win_size.rows.set_inner(5);
+
+    let pid = 1;
+    let style = Style::default();
+    let sixel_image_store = Rc::new(RefCell::new(SixelImageStore::default()));
+    let terminal_emulator_colors = Rc::new(RefCell::new(Palette::default()));
+    let terminal_emulator_color_codes = Rc::new(RefCell::new(HashMap::new()));
+    let debug = false;
+    let arrow_fonts = true;
+    let styled_underlines = true;
+    let transparent_background = true;
+    let keep_scrollback_in_alternate_screen = false;
+    let mut terminal_pane = TerminalPane::new(
+        pid,
+        fake_win_size,
+        style,
+        0,
+        String::new(),
+        Rc::new(RefCell::new(LinkHandler::new())),
+        Rc::new(RefCell::new(None)),
+        sixel_image_store.clone(),
+        terminal_emulator_colors,
+        terminal_emulator_color_codes,
+        None,
+        None,
+        debug,
+        arrow_fonts,
+        styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
+    ); // 0 is the pane index
+       // the application paints some reversed text and then scrolls a new line into view at
+       // the bottom of its scroll region, which it does not paint
+    terminal_pane.handle_pty_bytes(Vec::from("\u{1b}[1;5r\u{1b}[5;1H\u{1b}[7mapp\n".as_bytes()));
+    let (character_chunks, _raw_vte_output, _sixel_image_chunks) =
+        terminal_pane.render(None).unwrap().unwrap();
+    let mut output = Output::new(sixel_image_store, Rc::new(RefCell::new(None)), true);
+    output.add_clients(
+        &HashSet::from([client_id]),
+        Rc::new(RefCell::new(LinkHandler::new())),
+        None,
+    );
+    output
+        .add_character_chunks_to_client(client_id, character_chunks, None)
+        .unwrap();
+    let serialized_output = output.serialize().unwrap().remove(&client_id).unwrap();
+    let (painted_lines, scrolled_in_line) = serialized_output.split_once("\u{1b}[5;1H").unwrap();
+    assert!(
+        painted_lines.contains("\u{1b}[7m") && painted_lines.contains("app"),
+        "styles painted by the application are kept"
+    );
+    assert!(
+        !scrolled_in_line.contains("\u{1b}[7m"),
+        "blank cells that were not painted are not filled by the reversed style"
+    );
+}
+
+#[test]
+pub fn transparent_background_leaves_unpainted_cells_with_the_default_background() {
+    let client_id = 1;
+    let mut fake_win_size = PaneGeom::default();
+    fake_win_size.cols.set_inner(10);
+    fake_win_size.rows.set_inner(5);
+
+    let pid = 1;
+    let style = Style::default();
+    let sixel_image_store = Rc::new(RefCell::new(SixelImageStore::default()));
+    let terminal_emulator_colors = Rc::new(RefCell::new(Palette::default()));
+    let terminal_emulator_color_codes = Rc::new(RefCell::new(HashMap::new()));
+    let debug = false;
+    let arrow_fonts = true;
+    let styled_underlines = true;
+    let transparent_background = true;
+    let keep_scrollback_in_alternate_screen = false;
+    let pane_index = 0;
+    let mut terminal_pane = TerminalPane::new(
+        pid,
+        fake_win_size,
+        style,
+        pane_index,
+        String::new(),
+        Rc::new(RefCell::new(LinkHandler::new())),
+        Rc::new(RefCell::new(None)),
+        sixel_image_store.clone(),
+        terminal_emulator_colors,
+        terminal_emulator_color_codes,
+        None,
+        None,
+        debug,
+        arrow_fonts,
+        styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
+    );
+    // the application sets a blue background and scrolls a new line into view at the bottom of
+    // its scroll region, which should be left with the default background of the terminal
+    terminal_pane.handle_pty_bytes(Vec::from(
+        "\u{1b}[1;5r\u{1b}[5;1H\u{1b}[44mapp\n".as_bytes(),
+    ));
+    let (character_chunks, _raw_vte_output, _sixel_image_chunks) =
+        terminal_pane.render(None).unwrap().unwrap();
+    let mut output = Output::new(sixel_image_store, Rc::new(RefCell::new(None)), true);
+    output.add_clients(
+        &HashSet::from([client_id]),
+        Rc::new(RefCell::new(LinkHandler::new())),
+        None,
+    );
+    output
+        .add_character_chunks_to_client(client_id, character_chunks, None)
+        .unwrap();
+    let serialized_output = output.serialize().unwrap().remove(&client_id).unwrap();
+    let (_painted_lines, scrolled_in_line) = serialized_output.split_once("\u{1b}[5;1H").unwrap();
+    assert!(
+        scrolled_in_line.starts_with("\u{1b}[m"),
+        "line scrolled into view is reset to the default background"
+    );
+    assert!(
+        !scrolled_in_line.contains("\u{1b}[44m"),
+        "line scrolled into view is not filled with the background color set by the application"
+    );
+}
@@ -0,0 +1,235 @@
+//! A plugin redraws its whole pane on every render, even when only a single cell changed. To keep
+//! large plugin panes cheap to update, each frame is compared line by line with the previous frame
+//! of the same plugin and client, and only the changed lines are sent, each as a run positioned at
+//! its row.
+//!
+//! A frame is sent in full when there is no previous frame (eg. the first frame, or the first one
+//! after a resize), when the plugin rendered a different number of lines, when a changed line
+//! does more than writing to its own row or when sending the changes would not be any smaller.
+//!
+//! Every frame has an id and changed lines name the frame they apply to. A pane that does not show
+//! that frame (eg. because a frame never reached it or because it showed something else in the
+//! meantime) cannot apply them, and asks for the last frame in full instead.
+
+use super::PluginId;
+use crate::pty::VteBytes;
+use crate::ClientId;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PluginFrame {
+    Full {
+        frame_id: u64,
+        bytes: VteBytes,
+    },
+    Changed {
+        frame_id: u64,
+        base_frame_id: u64, // the frame these lines change
+        lines: Vec<ChangedLine>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChangedLine {
+    pub row: usize,
+    pub carried_styles: VteBytes, // the styles left on by the lines above, which this line starts with
+    pub bytes: VteBytes,
+}
+
+impl ChangedLine {
+    /// Moves the cursor to the start of the row, clears it and writes the line with the styles it
+    /// has in the whole frame
+    pub fn positioned_run(&self) -> VteBytes {
+        let mut run = format!("\u{1b}[{};1H\u{1b}[m\u{1b}[2K", self.row + 1).into_bytes();
+        run.extend(&self.carried_styles);
+        run.extend(&self.bytes);
+        run
+    }
+}
+
+/// Diffs the frames of all plugins, each against the previous frame of the same plugin and client
+#[derive(Debug, Clone, Default)]
+pub struct PluginFrameDiffer {
+    state: Arc<Mutex<PluginFrameDifferState>>,
+}
+
+#[derive(Debug, Default)]
+struct PluginFrameDifferState {
+    next_frame_id: u64,
+    previous_frames: HashMap<(PluginId, ClientId), (u64, Vec<VteBytes>)>, // frame id, lines
+}
+
+impl PluginFrameDiffer {
+    pub fn new() -> Self {
+        PluginFrameDiffer::default()
+    }
+    pub fn diff(
+        &self,
+        plugin_bytes: Vec<(PluginId, ClientId, VteBytes)>,
+    ) -> Vec<(PluginId, ClientId, PluginFrame)> {
+        let mut state = self.state.lock().unwrap();
+        let mut plugin_frames = vec![];
+        for (plugin_id, client_id, bytes) in plugin_bytes {
+            let frame_id = state.next_frame_id;
+            state.next_frame_id += 1;
+            let lines = split_lines(&bytes);
+            let changed_lines = state.previous_frames.get(&(plugin_id, client_id)).and_then(
+                |(base_frame_id, previous_lines)| {
+                    let changed_lines = changed_lines(previous_lines, &lines)?;
+                    let changes_len: usize = changed_lines
+                        .iter()
+                        .map(|line| line.positioned_run().len())
+                        .sum();
+                    if changes_len < bytes.len() {
+                        Some((*base_frame_id, changed_lines))
+                    } else {
+                        None
+                    }
+                },
+            );
+            state
+                .previous_frames
+                .insert((plugin_id, client_id), (frame_id, lines));
+            let frame = match changed_lines {
+                Some((base_frame_id, lines)) => PluginFrame::Changed {
+                    frame_id,
+                    base_frame_id,
+                    lines,
+                },
+                None => PluginFrame::Full { frame_id, bytes },
+            };
+            plugin_frames.push((plugin_id, client_id, frame));
+        }
+        plugin_frames
+    }
+    /// The last frame of this plugin and client in full, for a pane that could not apply its
+    /// changes
+    pub fn last_frame(&self, plugin_id: PluginId, client_id: ClientId) -> Option<PluginFrame> {
+        let state = self.state.lock().unwrap();
+        let (frame_id, lines) = state.previous_frames.get(&(plugin_id, client_id))?;
+        Some(PluginFrame::Full {
+            frame_id: *frame_id,
+            bytes: lines.join(&b'\n'),
+        })
+    }
+    /// Forgets the previous frames of this plugin, so that its next frames are sent in full
+    pub fn reset_plugin(&self, plugin_id: PluginId) {
+        self.state
+            .lock()
+            .unwrap()
+            .previous_frames
+            .retain(|(frame_plugin_id, _), _| *frame_plugin_id != plugin_id);
+    }
+    pub fn reset_client(&self, client_id: ClientId) {
+        self.state
+            .lock()
+            .unwrap()
+            .previous_frames
+            .retain(|(_, frame_client_id), _| *frame_client_id != client_id);
+    }
+}
+
+fn split_lines(bytes: &[u8]) -> Vec<VteBytes> {
+    bytes
+        .split(|byte| *byte == b'\n')
+        .map(|line| line.to_vec())
+        .collect()
+}
+
+fn changed_lines(previous_lines: &[VteBytes], lines: &[VteBytes]) -> Option<Vec<ChangedLine>> {
+    if previous_lines.len() != lines.len() {
+        return None;
+    }
+    let mut changed_lines = vec![];
+    let mut carried_styles = vec![];
+    for (row, (previous_line, line)) in previous_lines.iter().zip(lines).enumerate() {
+        if previous_line != line {
+            if reaches_outside_of_its_row(line) {
+                return None;
+            }
+            changed_lines.push(ChangedLine {
+                row,
+                carried_styles: carried_styles.clone(),
+                bytes: line.clone(),
+            });
+        }
+        carry_styles(line, &mut carried_styles);
+    }
+    Some(changed_lines)
+}
+
+// calls f with the parameters, intermediates and final byte of each CSI sequence in the line
+fn for_each_csi_sequence(line: &[u8], mut f: impl FnMut(&[u8], &[u8], &[u8])) {
+    let mut index = 0;
+    while index < line.len() {
+        if line[index] == 0x1b && line.get(index + 1) == Some(&b'[') {
+            let start = index;
+            let params_start = index + 2;
+            let params_len = line[params_start..]
+                .iter()
+                .take_while(|b| (0x30..=0x3f).contains(*b))
+                .count();
+            let intermediates_start = params_start + params_len;
+            let intermediates_len = line[intermediates_start..]
+                .iter()
+                .take_while(|b| (0x20..=0x2f).contains(*b))
+                .count();
+            let final_byte_index = intermediates_start + intermediates_len;
+            if final_byte_index >= line.len() {
+                break;
+            }
+            f(
+                &line[params_start..intermediates_start],
+                &line[intermediates_start..final_byte_index],
+                &line[start..=final_byte_index],
+            );
+            index = final_byte_index + 1;
+        } else {
+            index += 1;
+        }
+    }
+}
+
+// whether writing the line could move the cursor to, change or scroll other rows
+fn reaches_outside_of_its_row(line: &[u8]) -> bool {
+    let mut reaches_outside = false;
+    for_each_csi_sequence(line, |_params, _intermediates, sequence| {
+        if matches!(
+            sequence.last().copied(),
+            Some(b'A' | b'B' | b'E' | b'F' | b'H' | b'f' | b'd' | b'J' | b'L' | b'M' | b'S' | b'T')
+                | Some(b'r' | b'u')
+        ) {
+            reaches_outside = true;
+        }
+    });
+    reaches_outside
+        || line.iter().any(|b| matches!(b, 0x0b | 0x0c | 0x0e | 0x0f)) // VT, FF and charset shifts
+        || line.windows(2).any(|bytes| {
+            bytes[0] == 0x1b && matches!(bytes[1], b'D' | b'E' | b'M' | b'8' | b'(' | b')')
+        })
+}
+
+// keeps track of the styles (SGR) a line leaves on for the lines below it
+fn carry_styles(line: &[u8], carried_styles: &mut VteBytes) {
+    for_each_csi_sequence(line, |params, intermediates, sequence| {
+        let is_sgr = sequence.last() == Some(&b'm')
+            && intermediates.is_empty()
+            && params
+                .iter()
+                .all(|b| b.is_ascii_digit() || *b == b';' || *b == b':');
+        if !is_sgr {
+            return;
+        }
+        if params.iter().all(|b| *b == b'0') {
+            // a reset, nothing above it is carried any more
+            carried_styles.clear();
+        } else {
+            carried_styles.extend(sequence);
+        }
+    });
+}
+
+#[cfg(test)]
+#[path = "./unit/frame_diff_tests.rs"]
+mod frame_diff_tests;
@@ -1,6 +1,16 @@
+mod frame_diff;
+mod pane_output;
+mod plugin_clock;
+mod plugin_fuel;
 mod plugin_loader;
 mod plugin_map;
+mod plugin_metrics;
+mod plugin_persistent_data;
+mod plugin_secrets;
 mod plugin_worker;
+mod render_limiter;
+mod render_queue;
+mod streamed_frame;
 mod wasm_bridge;
 mod watch_filesystem;
 mod zellij_exports;
@@ -20,9 +30,20 @@ use crate::{pty::PtyInstruction, thread_bus::Bus, ClientId, ServerInstruction};
 
 use wasm_bridge::WasmBridge;
 
+pub use frame_diff::{ChangedLine, PluginFrame};
+pub use pane_output::{PaneOutputBuffer, MAX_BUFFERED_PANE_OUTPUT};
+pub use plugin_clock::{PluginClock, SystemClock};
+pub use plugin_fuel::metering_middleware;
+pub use render_queue::PluginRenderQueue;
+pub use streamed_frame::{StreamedFrame, MAX_STREAMED_FRAME_SIZE};
+
 use zellij_utils::{
     async_std::{channel, future::timeout, task},
-    data::{Event, EventType, PaneId, PermissionStatus, PermissionType, PluginCapabilities},
+    channels,
+    data::{
+        Event, EventType, PaneId, PermissionStatus, PermissionType, PluginCapabilities,
+        StatusSegment,
+    },
     errors::{prelude::*, ContextType, PluginContext},
     input::{
         command::TerminalAction,
@@ -61,6 +82,8 @@ pub enum PluginInstruction {
         Size,
     ),
     Resize(PluginId, usize, usize), // plugin_id, columns, rows
+    // sent by a pane that could not apply the changed lines of a frame
+    ResendPluginFrame(PluginId, ClientId),
     AddClient(ClientId),
     RemoveClient(ClientId),
     NewTab(
@@ -78,15 +101,15 @@ pub enum PluginInstruction {
         ClientId,
         String, // worker name
         Vec<(
-            String, // serialized message name
-            String, // serialized payload
+            String,  // serialized message name
+            Vec<u8>, // serialized payload
         )>,
     ),
     PostMessageToPlugin(
         PluginId,
         ClientId,
-        String, // serialized message
-        String, // serialized payload
+        String,  // serialized message
+        Vec<u8>, // serialized payload
     ),
     PluginSubscribedToEvents(PluginId, ClientId, HashSet<EventType>),
     PermissionRequestResult(
@@ -98,6 +121,20 @@ pub enum PluginInstruction {
     ),
     DumpLayout(SessionLayoutMetadata, ClientId),
     LogLayoutToHd(SessionLayoutMetadata),
+    MessageToPlugin(
+        RunPlugin,
+        ClientId,
+        String,         // message name
+        Option<String>, // payload
+    ),
+    LoadBackgroundPlugin(RunPlugin, ClientId),
+    Barrier(channels::Sender<()>), // answered once the plugins being loaded have loaded
+    StatusSegmentsRendered(PluginId, Vec<StatusSegment>),
+    PaneOutputQueued(PluginId, PaneId, PaneOutputBuffer), // output of a subscribed pane is waiting
+    CollectPluginMetrics, // sent periodically while plugins are subscribed to plugin metrics
+    RerenderPlugins(ClientId),
+    TerminalPaneOpened(PluginId, ClientId, u32), // u32 - the id of a pane opened by the plugin
+    TerminalPaneClosed(u32),                     // u32 - the id of a closed terminal pane
     Exit,
 }
 
@@ -109,6 +146,7 @@ impl From<&PluginInstruction> for PluginContext {
             PluginInstruction::Unload(..) => PluginContext::Unload,
             PluginInstruction::Reload(..) => PluginContext::Reload,
             PluginInstruction::Resize(..) => PluginContext::Resize,
+            PluginInstruction::ResendPluginFrame(..) => PluginContext::ResendPluginFrame,
             PluginInstruction::Exit => PluginContext::Exit,
             PluginInstruction::AddClient(_) => PluginContext::AddClient,
             PluginInstruction::RemoveClient(_) => PluginContext::RemoveClient,
@@ -129,6 +167,15 @@ impl From<&PluginInstruction> for PluginContext {
             },
             PluginInstruction::DumpLayout(..) => PluginContext::DumpLayout,
             PluginInstruction::LogLayoutToHd(..) => PluginContext::LogLayoutToHd,
+            PluginInstruction::MessageToPlugin(..) => PluginContext::MessageToPlugin,
+            PluginInstruction::LoadBackgroundPlugin(..) => PluginContext::LoadBackgroundPlugin,
+            PluginInstruction::Barrier(..) => PluginContext::Barrier,
+            PluginInstruction::StatusSegmentsRendered(..) => PluginContext::StatusSegmentsRendered,
+            PluginInstruction::PaneOutputQueued(..) => PluginContext::PaneOutputQueued,
+            PluginInstruction::CollectPluginMetrics => PluginContext::CollectPluginMetrics,
+            PluginInstruction::RerenderPlugins(..) => PluginContext::RerenderPlugins,
+            PluginInstruction::TerminalPaneOpened(..) => PluginContext::TerminalPaneOpened,
+            PluginInstruction::TerminalPaneClosed(..) => PluginContext::TerminalPaneClosed,
         }
     }
 }
@@ -144,6 +191,9 @@ pub(crate) fn plugin_thread_main(
     capabilities: PluginCapabilities,
     client_attributes: ClientAttributes,
     default_shell: Option<TerminalAction>,
+    clock: Arc<dyn PluginClock>,
+    plugin_metrics_interval: Option<u64>,
+    paste_command: Option<String>,
 ) -> Result<()> {
     info!("Wasm main thread starts");
 
@@ -167,6 +217,9 @@ pub(crate) fn plugin_thread_main(
         client_attributes,
         default_shell,
         layout.clone(),
+        clock,
+        plugin_metrics_interval,
+        paste_command,
     );
 
     loop {
@@ -249,6 +302,9 @@ pub(crate) fn plugin_thread_main(
             PluginInstruction::Resize(pid, new_columns, new_rows) => {
                 wasm_bridge.resize_plugin(pid, new_columns, new_rows, shutdown_send.clone())?;
             },
+            PluginInstruction::ResendPluginFrame(pid, client_id) => {
+                wasm_bridge.resend_plugin_frame(pid, client_id);
+            },
             PluginInstruction::AddClient(client_id) => {
                 wasm_bridge.add_client(client_id)?;
             },
@@ -337,12 +393,17 @@ pub(crate) fn plugin_thread_main(
             },
             PluginInstruction::PluginSubscribedToEvents(_plugin_id, _client_id, events) => {
                 for event in events {
-                    if let EventType::FileSystemCreate
-                    | EventType::FileSystemRead
-                    | EventType::FileSystemUpdate
-                    | EventType::FileSystemDelete = event
-                    {
-                        wasm_bridge.start_fs_watcher_if_not_started();
+                    match event {
+                        EventType::FileSystemCreate
+                        | EventType::FileSystemRead
+                        | EventType::FileSystemUpdate
+                        | EventType::FileSystemDelete => {
+                            wasm_bridge.start_fs_watcher_if_not_started();
+                        },
+                        EventType::PluginMetrics => {
+                            wasm_bridge.start_plugin_metrics_timer_if_not_started();
+                        },
+                        _ => {},
                     }
                 }
             },
@@ -384,6 +445,58 @@ pub(crate) fn plugin_thread_main(
                         .send_to_pty(PtyInstruction::LogLayoutToHd(session_layout_metadata)),
                 );
             },
+            PluginInstruction::MessageToPlugin(run_plugin, client_id, name, payload) => {
+                if let Err(e) = wasm_bridge.message_plugin(
+                    &run_plugin,
+                    client_id,
+                    name,
+                    payload.unwrap_or_default().into_bytes(),
+                    shutdown_send.clone(),
+                ) {
+                    log::error!("{:?}", e);
+                }
+            },
+            PluginInstruction::LoadBackgroundPlugin(run_plugin, client_id) => {
+                wasm_bridge
+                    .load_background_plugin(&run_plugin, client_id)
+                    .non_fatal();
+            },
+            PluginInstruction::Barrier(barrier) => {
+                wasm_bridge.add_barrier(barrier);
+            },
+            PluginInstruction::StatusSegmentsRendered(plugin_id, status_segments) => {
+                wasm_bridge
+                    .update_status_segments(plugin_id, status_segments)
+                    .non_fatal();
+            },
+            PluginInstruction::PaneOutputQueued(plugin_id, pane_id, pane_output) => {
+                wasm_bridge.send_pane_output(
+                    plugin_id,
+                    pane_id,
+                    pane_output,
+                    shutdown_send.clone(),
+                );
+            },
+            PluginInstruction::CollectPluginMetrics => {
+                wasm_bridge.send_plugin_metrics(shutdown_send.clone());
+            },
+            PluginInstruction::RerenderPlugins(client_id) => {
+                wasm_bridge.rerender_plugins_for_client(client_id, shutdown_send.clone())?;
+            },
+            PluginInstruction::TerminalPaneOpened(plugin_id, client_id, terminal_id) => {
+                wasm_bridge.add_owned_pane(plugin_id, terminal_id);
+                wasm_bridge.update_plugins(
+                    vec![(
+                        Some(plugin_id),
+                        Some(client_id),
+                        Event::TerminalPaneOpened(terminal_id),
+                    )],
+                    shutdown_send.clone(),
+                )?;
+            },
+            PluginInstruction::TerminalPaneClosed(terminal_id) => {
+                wasm_bridge.remove_owned_pane(terminal_id);
+            },
             PluginInstruction::Exit => {
                 break;
             },
@@ -422,6 +535,7 @@ fn populate_session_layout_metadata(
 ) {
     let plugin_ids = session_layout_metadata.all_plugin_ids();
     let mut plugin_ids_to_cmds: HashMap<u32, RunPlugin> = HashMap::new();
+    let mut plugin_ids_to_states: HashMap<u32, Vec<u8>> = HashMap::new();
     for plugin_id in plugin_ids {
         let plugin_cmd = wasm_bridge.run_plugin_of_plugin_id(plugin_id);
         match plugin_cmd {
@@ -430,8 +544,12 @@ fn populate_session_layout_metadata(
             },
             None => log::error!("Plugin with id: {plugin_id} not found"),
         }
+        if let Some(plugin_state) = wasm_bridge.serialize_plugin_state(plugin_id) {
+            plugin_ids_to_states.insert(plugin_id, plugin_state);
+        }
     }
     session_layout_metadata.update_plugin_cmds(plugin_ids_to_cmds);
+    session_layout_metadata.update_plugin_states(plugin_ids_to_states);
 }
 
 const EXIT_TIMEOUT: Duration = Duration::from_secs(3);
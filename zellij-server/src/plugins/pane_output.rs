@@ -0,0 +1,54 @@
+//! Plugins can subscribe to the raw output of a pane. Sending every chunk the pty produces as its
+//! own event would let a slow plugin pile up events without bound, so each subscription has a
+//! buffer instead: the screen appends the output to it and only tells the plugin thread when the
+//! buffer goes from empty to not empty. The output is taken out of the buffer once the plugin is
+//! free to handle it, so while the plugin is busy new output joins the chunk waiting for it. If
+//! the plugin falls too far behind, the oldest output is dropped rather than holding up the pty.
+
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+pub const MAX_BUFFERED_PANE_OUTPUT: usize = 1024 * 1024; // bytes
+
+#[derive(Clone)]
+pub struct PaneOutputBuffer {
+    max_len: usize,
+    bytes: Arc<Mutex<Vec<u8>>>,
+}
+
+impl fmt::Debug for PaneOutputBuffer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("PaneOutputBuffer")
+            .field("buffered_bytes", &self.bytes.lock().unwrap().len())
+            .finish()
+    }
+}
+
+impl PaneOutputBuffer {
+    pub fn new(max_len: usize) -> Self {
+        PaneOutputBuffer {
+            max_len,
+            bytes: Arc::new(Mutex::new(vec![])),
+        }
+    }
+    /// Appends the output to the buffer, returns true if the buffer was empty (ie. the plugin
+    /// needs to be told there is output to take)
+    pub fn push(&self, output: &[u8]) -> bool {
+        let mut bytes = self.bytes.lock().unwrap();
+        let was_empty = bytes.is_empty();
+        bytes.extend_from_slice(output);
+        if bytes.len() > self.max_len {
+            let excess = bytes.len() - self.max_len;
+            bytes.drain(..excess);
+        }
+        was_empty && !bytes.is_empty()
+    }
+    /// Takes all the output buffered so far, oldest first
+    pub fn take(&self) -> Vec<u8> {
+        std::mem::take(&mut *self.bytes.lock().unwrap())
+    }
+}
+
+#[cfg(test)]
+#[path = "./unit/pane_output_tests.rs"]
+mod pane_output_tests;
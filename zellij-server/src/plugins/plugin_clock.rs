@@ -0,0 +1,102 @@
+//! The plugin thread reads the time and schedules delayed work (eg. plugin timers) through a
+//! `PluginClock`. The server uses the `SystemClock`, while tests
+//! use a `ManualClock` and move it forward explicitly instead of sleeping.
+
+#[cfg(test)]
+use std::sync::{Condvar, Mutex};
+use std::time::{Duration, Instant};
+use zellij_utils::async_std::task;
+
+pub trait PluginClock: Send + Sync {
+    fn now(&self) -> Instant;
+    /// Runs `callback` once `delay` has passed
+    fn call_after(&self, delay: Duration, callback: Box<dyn FnOnce() + Send>);
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SystemClock;
+
+impl PluginClock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+    fn call_after(&self, delay: Duration, callback: Box<dyn FnOnce() + Send>) {
+        task::spawn(async move {
+            task::sleep(delay).await;
+            callback();
+        });
+    }
+}
+
+#[cfg(test)]
+pub struct ManualClock {
+    state: Mutex<ManualClockState>,
+    callback_scheduled: Condvar,
+}
+
+#[cfg(test)]
+struct ManualClockState {
+    now: Instant,
+    scheduled_callbacks: Vec<(Instant, Box<dyn FnOnce() + Send>)>,
+}
+
+#[cfg(test)]
+impl ManualClock {
+    pub fn new() -> Self {
+        ManualClock {
+            state: Mutex::new(ManualClockState {
+                now: Instant::now(),
+                scheduled_callbacks: vec![],
+            }),
+            callback_scheduled: Condvar::new(),
+        }
+    }
+    /// Moves the clock forward, running the callbacks that are due by then in the order of their
+    /// deadlines
+    pub fn advance(&self, duration: Duration) {
+        let mut due_callbacks = {
+            let mut state = self.state.lock().unwrap();
+            state.now += duration;
+            let now = state.now;
+            let (due_callbacks, scheduled_callbacks) = state
+                .scheduled_callbacks
+                .drain(..)
+                .partition(|(deadline, _)| *deadline <= now);
+            state.scheduled_callbacks = scheduled_callbacks;
+            due_callbacks
+        };
+        // callbacks are run without holding the lock so that they can schedule more callbacks
+        due_callbacks.sort_by_key(|(deadline, _)| *deadline);
+        for (_deadline, callback) in due_callbacks {
+            callback();
+        }
+    }
+    /// Blocks until at least `count` callbacks are waiting for the clock to move forward, so that
+    /// a test knows the work it is about to trigger has been scheduled
+    pub fn wait_for_scheduled_callbacks(&self, count: usize) {
+        let mut state = self.state.lock().unwrap();
+        while state.scheduled_callbacks.len() < count {
+            state = self.callback_scheduled.wait(state).unwrap();
+        }
+    }
+}
+
+#[cfg(test)]
+impl Default for ManualClock {
+    fn default() -> Self {
+        ManualClock::new()
+    }
+}
+
+#[cfg(test)]
+impl PluginClock for ManualClock {
+    fn now(&self) -> Instant {
+        self.state.lock().unwrap().now
+    }
+    fn call_after(&self, delay: Duration, callback: Box<dyn FnOnce() + Send>) {
+        let mut state = self.state.lock().unwrap();
+        let deadline = state.now + delay;
+        state.scheduled_callbacks.push((deadline, callback));
+        self.callback_scheduled.notify_all();
+    }
+}
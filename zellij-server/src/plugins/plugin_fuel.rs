@@ -0,0 +1,92 @@
+//! A plugin stuck in a loop would hang the thread it runs on, and with it every other plugin. To
+//! prevent this, plugins are compiled with wasmer's metering middleware, which counts the
+//! instructions a plugin executes and traps once it runs out of fuel. Each call into a plugin
+//! (eg. applying an event and rendering, or handling a worker message) gets a fresh budget of
+//! fuel, so only plugins that are actually stuck are stopped. These are then handled like any
+//! other plugin that crashed.
+//!
+//! The budget can be changed per plugin in the layout, eg. `plugin location="..." { max_fuel
+//! 1000000; }`, with `max_fuel 0` lifting the limit for that plugin.
+
+use std::sync::Arc;
+use wasmer::wasmparser::Operator;
+use wasmer::{Instance, Store};
+use wasmer_middlewares::metering::{get_remaining_points, set_remaining_points, MeteringPoints};
+use wasmer_middlewares::Metering;
+use zellij_utils::errors::prelude::*;
+use zellij_utils::input::layout::PluginUserConfiguration;
+
+pub const DEFAULT_PLUGIN_FUEL: u64 = 10_000_000_000;
+const MAX_FUEL_CONFIGURATION_KEY: &str = "max_fuel";
+// part of the name of compiled modules in the HD cache, so that modules compiled without (or with
+// another) metering are not loaded - to be changed whenever the metering middleware changes
+pub const METERING_CACHE_KEY: &str = "metered-v1";
+// exported by the modules compiled with the metering middleware
+const REMAINING_FUEL_GLOBAL: &str = "wasmer_metering_remaining_points";
+
+fn cost_of_operator(_operator: &Operator) -> u64 {
+    1
+}
+
+pub fn metering_middleware() -> Arc<Metering<fn(&Operator) -> u64>> {
+    Arc::new(Metering::new(
+        DEFAULT_PLUGIN_FUEL,
+        cost_of_operator as fn(&Operator) -> u64,
+    ))
+}
+
+pub fn max_fuel_from_configuration(configuration: &PluginUserConfiguration) -> u64 {
+    let max_fuel = match configuration.inner().get(MAX_FUEL_CONFIGURATION_KEY) {
+        Some(max_fuel) => max_fuel,
+        None => return DEFAULT_PLUGIN_FUEL,
+    };
+    match max_fuel.parse() {
+        Ok(0) => u64::MAX,
+        Ok(max_fuel) => max_fuel,
+        Err(e) => {
+            log::error!("Failed to parse plugin max_fuel {:?}: {}", max_fuel, e);
+            DEFAULT_PLUGIN_FUEL
+        },
+    }
+}
+
+/// Gives the instance a fresh budget of fuel, to be done before each call into the plugin
+pub fn refuel(store: &mut Store, instance: &Instance, fuel: u64) {
+    // this should not happen, as all modules are compiled with the metering middleware, but if a
+    // module does not count the fuel it uses there is nothing to refuel
+    if is_metered(instance) {
+        set_remaining_points(store, instance, fuel);
+    }
+}
+
+/// Explains the failure of a call into the plugin if it is because the plugin ran out of fuel
+pub fn explain_out_of_fuel<T>(
+    result: Result<T>,
+    store: &mut Store,
+    instance: &Instance,
+    fuel: u64,
+) -> Result<T> {
+    result.map_err(|e| {
+        if is_metered(instance)
+            && matches!(
+                get_remaining_points(store, instance),
+                MeteringPoints::Exhausted
+            )
+        {
+            e.context(format!(
+                "plugin ran out of fuel after executing {fuel} instructions, it might be stuck \
+                 in a loop (the limit can be changed with max_fuel in the plugin configuration)"
+            ))
+        } else {
+            e
+        }
+    })
+}
+
+fn is_metered(instance: &Instance) -> bool {
+    instance.exports.get_global(REMAINING_FUEL_GLOBAL).is_ok()
+}
+
+#[cfg(test)]
+#[path = "./unit/plugin_fuel_tests.rs"]
+mod plugin_fuel_tests;
@@ -1,12 +1,20 @@
 use crate::get_store;
-use crate::plugins::plugin_map::{PluginEnv, PluginMap, RunningPlugin, Subscriptions};
+use crate::plugins::plugin_clock::PluginClock;
+use crate::plugins::plugin_fuel::{
+    explain_out_of_fuel, max_fuel_from_configuration, refuel, METERING_CACHE_KEY,
+};
+use crate::plugins::plugin_map::{
+    PluginEnv, PluginMap, RunningPlugin, SessionLayoutCache, Subscriptions,
+};
+use crate::plugins::plugin_persistent_data::plugin_persistent_data_dir;
+use crate::plugins::plugin_secrets::resolve_plugin_secrets;
 use crate::plugins::plugin_worker::{plugin_worker, RunningWorker};
 use crate::plugins::zellij_exports::{wasi_write_object, zellij_exports};
-use crate::plugins::PluginId;
+use crate::plugins::{PluginId, StreamedFrame};
 use highway::{HighwayHash, PortableHash};
 use log::info;
 use std::{
-    collections::{HashMap, HashSet},
+    collections::{BTreeMap, HashMap, HashSet},
     fs,
     path::PathBuf,
     sync::{Arc, Mutex},
@@ -18,8 +26,11 @@ use zellij_utils::consts::ZELLIJ_PLUGIN_ARTIFACT_DIR;
 use zellij_utils::prost::Message;
 
 use crate::{
-    logging_pipe::LoggingPipe, screen::ScreenInstruction, thread_bus::ThreadSenders,
-    ui::loading_indication::LoadingIndication, ClientId,
+    logging_pipe::{LoggingPipe, LoggingSink},
+    screen::ScreenInstruction,
+    thread_bus::ThreadSenders,
+    ui::loading_indication::LoadingIndication,
+    ClientId,
 };
 
 use zellij_utils::plugin_api::action::ProtobufPluginConfiguration;
@@ -66,6 +77,9 @@ pub struct PluginLoader<'a> {
     client_attributes: ClientAttributes,
     default_shell: Option<TerminalAction>,
     default_layout: Box<Layout>,
+    session_layout_cache: Arc<Mutex<SessionLayoutCache>>,
+    clock: Arc<dyn PluginClock>,
+    paste_command: Option<String>,
 }
 
 impl<'a> PluginLoader<'a> {
@@ -84,6 +98,9 @@ impl<'a> PluginLoader<'a> {
         client_attributes: ClientAttributes,
         default_shell: Option<TerminalAction>,
         default_layout: Box<Layout>,
+        session_layout_cache: Arc<Mutex<SessionLayoutCache>>,
+        clock: Arc<dyn PluginClock>,
+        paste_command: Option<String>,
     ) -> Result<()> {
         let err_context = || format!("failed to reload plugin {plugin_id} from memory");
         let mut connected_clients: Vec<ClientId> =
@@ -108,6 +125,9 @@ impl<'a> PluginLoader<'a> {
             client_attributes,
             default_shell,
             default_layout,
+            session_layout_cache,
+            clock,
+            paste_command,
         )?;
         plugin_loader
             .load_module_from_memory()
@@ -148,6 +168,9 @@ impl<'a> PluginLoader<'a> {
         client_attributes: ClientAttributes,
         default_shell: Option<TerminalAction>,
         default_layout: Box<Layout>,
+        session_layout_cache: Arc<Mutex<SessionLayoutCache>>,
+        clock: Arc<dyn PluginClock>,
+        paste_command: Option<String>,
     ) -> Result<()> {
         let err_context = || format!("failed to start plugin {plugin_id} for client {client_id}");
         let mut plugin_loader = PluginLoader::new(
@@ -167,6 +190,9 @@ impl<'a> PluginLoader<'a> {
             client_attributes,
             default_shell,
             default_layout,
+            session_layout_cache,
+            clock,
+            paste_command,
         )?;
         plugin_loader
             .load_module_from_memory()
@@ -208,6 +234,9 @@ impl<'a> PluginLoader<'a> {
         client_attributes: ClientAttributes,
         default_shell: Option<TerminalAction>,
         default_layout: Box<Layout>,
+        session_layout_cache: Arc<Mutex<SessionLayoutCache>>,
+        clock: Arc<dyn PluginClock>,
+        paste_command: Option<String>,
     ) -> Result<()> {
         let mut new_plugins = HashSet::new();
         for plugin_id in plugin_map.lock().unwrap().plugin_ids() {
@@ -229,6 +258,9 @@ impl<'a> PluginLoader<'a> {
                 client_attributes.clone(),
                 default_shell.clone(),
                 default_layout.clone(),
+                session_layout_cache.clone(),
+                clock.clone(),
+                paste_command.clone(),
             )?;
             plugin_loader
                 .load_module_from_memory()
@@ -262,6 +294,9 @@ impl<'a> PluginLoader<'a> {
         client_attributes: ClientAttributes,
         default_shell: Option<TerminalAction>,
         default_layout: Box<Layout>,
+        session_layout_cache: Arc<Mutex<SessionLayoutCache>>,
+        clock: Arc<dyn PluginClock>,
+        paste_command: Option<String>,
     ) -> Result<()> {
         let err_context = || format!("failed to reload plugin id {plugin_id}");
 
@@ -287,6 +322,9 @@ impl<'a> PluginLoader<'a> {
             client_attributes,
             default_shell,
             default_layout,
+            session_layout_cache,
+            clock,
+            paste_command,
         )?;
         plugin_loader
             .compile_module()
@@ -324,6 +362,9 @@ impl<'a> PluginLoader<'a> {
         client_attributes: ClientAttributes,
         default_shell: Option<TerminalAction>,
         default_layout: Box<Layout>,
+        session_layout_cache: Arc<Mutex<SessionLayoutCache>>,
+        clock: Arc<dyn PluginClock>,
+        paste_command: Option<String>,
     ) -> Result<Self> {
         let plugin_own_data_dir = ZELLIJ_SESSION_CACHE_DIR
             .join(Url::from(&plugin.location).to_string())
@@ -350,6 +391,9 @@ impl<'a> PluginLoader<'a> {
             client_attributes,
             default_shell,
             default_layout,
+            session_layout_cache,
+            clock,
+            paste_command,
         })
     }
     pub fn new_from_existing_plugin_attributes(
@@ -367,6 +411,9 @@ impl<'a> PluginLoader<'a> {
         client_attributes: ClientAttributes,
         default_shell: Option<TerminalAction>,
         default_layout: Box<Layout>,
+        session_layout_cache: Arc<Mutex<SessionLayoutCache>>,
+        clock: Arc<dyn PluginClock>,
+        paste_command: Option<String>,
     ) -> Result<Self> {
         let err_context = || "Failed to find existing plugin";
         let (running_plugin, _subscriptions, _workers) = {
@@ -400,6 +447,9 @@ impl<'a> PluginLoader<'a> {
             client_attributes,
             default_shell,
             default_layout,
+            session_layout_cache,
+            clock,
+            paste_command,
         )
     }
     pub fn new_from_different_client_id(
@@ -417,6 +467,9 @@ impl<'a> PluginLoader<'a> {
         client_attributes: ClientAttributes,
         default_shell: Option<TerminalAction>,
         default_layout: Box<Layout>,
+        session_layout_cache: Arc<Mutex<SessionLayoutCache>>,
+        clock: Arc<dyn PluginClock>,
+        paste_command: Option<String>,
     ) -> Result<Self> {
         let err_context = || "Failed to find existing plugin";
         let running_plugin = {
@@ -451,6 +504,9 @@ impl<'a> PluginLoader<'a> {
             client_attributes,
             default_shell,
             default_layout,
+            session_layout_cache,
+            clock,
+            paste_command,
         )
     }
     pub fn load_module_from_memory(&mut self) -> Result<Module> {
@@ -525,9 +581,9 @@ impl<'a> PluginLoader<'a> {
         let module = fs::create_dir_all(ZELLIJ_PLUGIN_ARTIFACT_DIR.as_path())
             .map_err(anyError::new)
             .and_then(|_| {
-                // compile module
-                Module::new(&self.store.lock().unwrap().as_store_ref(), &wasm_bytes)
-                    .map_err(anyError::new)
+                // compile module, with a store of its own because the metering middleware of
+                // a store can only be used to compile a single module
+                Module::new(&get_store(), &wasm_bytes).map_err(anyError::new)
             })
             .and_then(|m| {
                 // serialize module to HD cache for faster loading in the future
@@ -612,8 +668,11 @@ impl<'a> PluginLoader<'a> {
                     .exports
                     .get_function("_start")
                     .with_context(err_context)?;
-                start_function_for_worker
+                refuel(&mut store, &instance, plugin_env.fuel);
+                let started_worker = start_function_for_worker
                     .call(&mut store, &[])
+                    .map_err(anyError::new);
+                explain_out_of_fuel(started_worker, &mut store, &instance, plugin_env.fuel)
                     .with_context(err_context)?;
 
                 let worker =
@@ -638,16 +697,26 @@ impl<'a> PluginLoader<'a> {
             workers,
         );
 
-        start_function
-            .call(&mut plugin.lock().unwrap().store, &[])
+        {
+            let mut running_plugin = plugin.lock().unwrap();
+            refuel(&mut running_plugin.store, instance, plugin_env.fuel);
+            let started = start_function
+                .call(&mut running_plugin.store, &[])
+                .map_err(anyError::new);
+            explain_out_of_fuel(
+                started,
+                &mut running_plugin.store,
+                instance,
+                plugin_env.fuel,
+            )
             .with_context(err_context)?;
+        }
 
-        let protobuf_plugin_configuration: ProtobufPluginConfiguration = self
-            .plugin
-            .userspace_configuration
-            .clone()
-            .try_into()
-            .map_err(|e| anyhow!("Failed to serialize user configuration: {:?}", e))?;
+        let protobuf_plugin_configuration: ProtobufPluginConfiguration =
+            resolve_plugin_secrets(&self.plugin.userspace_configuration)
+                .with_context(err_context)?
+                .try_into()
+                .map_err(|e| anyhow!("Failed to serialize user configuration: {:?}", e))?;
         let protobuf_bytes = protobuf_plugin_configuration.encode_to_vec();
         wasi_write_object(
             &plugin_env.wasi_env,
@@ -655,9 +724,15 @@ impl<'a> PluginLoader<'a> {
             // &self.plugin.userspace_configuration.inner(),
         )
         .with_context(err_context)?;
-        load_function
-            .call(&mut plugin.lock().unwrap().store, &[])
-            .with_context(err_context)?;
+        {
+            let mut running_plugin = plugin.lock().unwrap();
+            refuel(&mut running_plugin.store, instance, plugin_env.fuel);
+            let loaded = load_function
+                .call(&mut running_plugin.store, &[])
+                .map_err(anyError::new);
+            explain_out_of_fuel(loaded, &mut running_plugin.store, instance, plugin_env.fuel)
+                .with_context(err_context)?;
+        }
 
         display_loading_stage!(
             indicate_starting_plugin_success,
@@ -713,6 +788,9 @@ impl<'a> PluginLoader<'a> {
                     self.client_attributes.clone(),
                     self.default_shell.clone(),
                     self.default_layout.clone(),
+                    self.session_layout_cache.clone(),
+                    self.clock.clone(),
+                    self.paste_command.clone(),
                 )?;
                 plugin_loader_for_client
                     .load_module_from_memory()
@@ -753,7 +831,8 @@ impl<'a> PluginLoader<'a> {
                     .iter()
                     .map(ToString::to_string)
                     .collect();
-                let cached_path = ZELLIJ_PLUGIN_ARTIFACT_DIR.join(&hash);
+                let cached_path =
+                    ZELLIJ_PLUGIN_ARTIFACT_DIR.join(format!("{}-{}", hash, METERING_CACHE_KEY));
                 self.wasm_blob_on_hd = Some((wasm_bytes.clone(), cached_path.clone()));
                 Ok((wasm_bytes, cached_path))
             },
@@ -771,6 +850,12 @@ impl<'a> PluginLoader<'a> {
         };
         let mut store = get_store();
         let store_mut = &mut store;
+        let log_sink = LoggingSink::from_configuration(
+            self.plugin.userspace_configuration.inner(),
+            &self.plugin_own_data_dir,
+            self.senders.clone(),
+            self.client_id,
+        );
         let mut wasi_env = WasiState::new("Zellij")
             .env("CLICOLOR_FORCE", "1")
             .map_dir("/host", self.zellij_cwd.clone())
@@ -779,10 +864,10 @@ impl<'a> PluginLoader<'a> {
             .and_then(|wasi| {
                 wasi.stdin(Box::new(Pipe::new()))
                     .stdout(Box::new(Pipe::new()))
-                    .stderr(Box::new(LoggingPipe::new(
-                        &self.plugin.location.to_string(),
-                        self.plugin_id,
-                    )))
+                    .stderr(Box::new(
+                        LoggingPipe::new(&self.plugin.location.to_string(), self.plugin_id)
+                            .with_sink(log_sink.clone()),
+                    ))
                     .finalize(store_mut)
             })
             .with_context(err_context)?;
@@ -799,13 +884,26 @@ impl<'a> PluginLoader<'a> {
             senders: self.senders.clone(),
             wasi_env: wasi_env.data_mut(store_mut).clone(),
             plugin_own_data_dir: self.plugin_own_data_dir.clone(),
+            plugin_persistent_data_dir: plugin_persistent_data_dir(
+                &self.plugin_dir,
+                &self.plugin.location,
+            ),
             tab_index: self.tab_index,
             path_to_default_shell: self.path_to_default_shell.clone(),
             capabilities: self.capabilities.clone(),
             client_attributes: self.client_attributes.clone(),
             default_shell: self.default_shell.clone(),
             default_layout: self.default_layout.clone(),
+            session_layout_cache: self.session_layout_cache.clone(),
+            clock: self.clock.clone(),
+            paste_command: self.paste_command.clone(),
             plugin_cwd: self.zellij_cwd.clone(),
+            file_system_event_filter: Arc::new(Mutex::new(None)),
+            status_segments: Arc::new(Mutex::new(BTreeMap::new())),
+            last_focus_self: Arc::new(Mutex::new(None)),
+            streamed_frame: Arc::new(Mutex::new(StreamedFrame::default())),
+            log_sink,
+            fuel: max_fuel_from_configuration(&self.plugin.userspace_configuration),
         };
 
         let subscriptions = Arc::new(Mutex::new(HashSet::new()));
@@ -1,25 +1,28 @@
+use crate::plugins::plugin_clock::PluginClock;
 use crate::plugins::plugin_worker::MessageToWorker;
-use crate::plugins::PluginId;
+use crate::plugins::{PluginId, StreamedFrame};
+use globset::GlobSet;
 use std::{
-    collections::{HashMap, HashSet},
+    collections::{BTreeMap, HashMap, HashSet},
     path::PathBuf,
     sync::{Arc, Mutex},
+    time::{Duration, Instant},
 };
 use wasmer::{Instance, Store};
 use wasmer_wasi::WasiEnv;
 
-use crate::{thread_bus::ThreadSenders, ClientId};
+use crate::{logging_pipe::LoggingSink, thread_bus::ThreadSenders, ClientId};
 
 use zellij_utils::async_channel::Sender;
+use zellij_utils::{data::PermissionType, errors::prelude::*};
 use zellij_utils::{
-    data::EventType,
     data::PluginCapabilities,
+    data::{Event, EventType, KeybindsVec, PaneManifest, SessionLayout, TabInfo},
     input::command::TerminalAction,
     input::layout::{Layout, RunPlugin, RunPluginLocation},
     input::plugins::PluginConfig,
     ipc::ClientAttributes,
 };
-use zellij_utils::{data::PermissionType, errors::prelude::*};
 
 // the idea here is to provide atomicity when adding/removing plugins from the map (eg. when a new
 // client connects) but to also allow updates/renders not to block each other
@@ -211,6 +214,85 @@ impl PluginMap {
 
 pub type Subscriptions = HashSet<EventType>;
 
+/// The latest tabs and panes sent to plugins, so that they can query them synchronously rather
+/// than wait for the next event. Tabs are kept per client, since each has its own focus.
+///
+/// The keybindings come from the latest `ModeUpdate`, so they are those of the client's own
+/// configuration rather than the ones the plugin was loaded with.
+///
+/// The terminal panes each plugin opened with `open_terminal_pane`, which it may close, are kept
+/// here as well so that they can be forgotten as soon as they close, before their id is reused.
+#[derive(Default)]
+pub struct SessionLayoutCache {
+    tabs: HashMap<ClientId, Vec<TabInfo>>,
+    tabs_for_all_clients: Vec<TabInfo>,
+    panes: PaneManifest,
+    keybinds: HashMap<ClientId, KeybindsVec>,
+    keybinds_for_all_clients: Option<KeybindsVec>,
+    owned_panes: HashMap<PluginId, HashSet<u32>>,
+}
+
+impl SessionLayoutCache {
+    pub fn update(&mut self, client_id: Option<ClientId>, event: &Event) {
+        match (event, client_id) {
+            (Event::TabUpdate(tabs), Some(client_id)) => {
+                self.tabs.insert(client_id, tabs.clone());
+            },
+            (Event::TabUpdate(tabs), None) => {
+                self.tabs.clear();
+                self.tabs_for_all_clients = tabs.clone();
+            },
+            (Event::PaneUpdate(panes), _) => {
+                self.panes = panes.clone();
+            },
+            (Event::ModeUpdate(mode_info), Some(client_id)) => {
+                self.keybinds.insert(client_id, mode_info.keybinds.clone());
+            },
+            (Event::ModeUpdate(mode_info), None) => {
+                self.keybinds.clear();
+                self.keybinds_for_all_clients = Some(mode_info.keybinds.clone());
+            },
+            _ => {},
+        }
+    }
+    pub fn session_layout(&self, client_id: ClientId) -> SessionLayout {
+        SessionLayout {
+            tabs: self
+                .tabs
+                .get(&client_id)
+                .unwrap_or(&self.tabs_for_all_clients)
+                .clone(),
+            panes: self.panes.clone(),
+        }
+    }
+    pub fn keybinds(&self, client_id: ClientId) -> Option<KeybindsVec> {
+        self.keybinds
+            .get(&client_id)
+            .or(self.keybinds_for_all_clients.as_ref())
+            .cloned()
+    }
+    pub fn add_owned_pane(&mut self, plugin_id: PluginId, terminal_id: u32) {
+        self.owned_panes
+            .entry(plugin_id)
+            .or_default()
+            .insert(terminal_id);
+    }
+    pub fn owns_pane(&self, plugin_id: PluginId, terminal_id: u32) -> bool {
+        self.owned_panes
+            .get(&plugin_id)
+            .map(|owned_panes| owned_panes.contains(&terminal_id))
+            .unwrap_or(false)
+    }
+    pub fn remove_closed_pane(&mut self, terminal_id: u32) {
+        for owned_panes in self.owned_panes.values_mut() {
+            owned_panes.remove(&terminal_id);
+        }
+    }
+    pub fn remove_plugin(&mut self, plugin_id: PluginId) {
+        self.owned_panes.remove(&plugin_id);
+    }
+}
+
 #[derive(Clone)]
 pub struct PluginEnv {
     pub plugin_id: PluginId,
@@ -222,12 +304,22 @@ pub struct PluginEnv {
     pub client_id: ClientId,
     #[allow(dead_code)]
     pub plugin_own_data_dir: PathBuf,
+    pub plugin_persistent_data_dir: PathBuf,
     pub path_to_default_shell: PathBuf,
     pub capabilities: PluginCapabilities,
     pub client_attributes: ClientAttributes,
     pub default_shell: Option<TerminalAction>,
     pub default_layout: Box<Layout>,
     pub plugin_cwd: PathBuf,
+    pub file_system_event_filter: Arc<Mutex<Option<GlobSet>>>, // None forwards all paths
+    pub status_segments: Arc<Mutex<BTreeMap<u32, u32>>>,       // render callback id -> priority
+    pub last_focus_self: Arc<Mutex<Option<Instant>>>,          // used to rate-limit focus_self
+    pub streamed_frame: Arc<Mutex<StreamedFrame>>, // the frame the plugin streams with render_chunk
+    pub log_sink: LoggingSink,
+    pub session_layout_cache: Arc<Mutex<SessionLayoutCache>>,
+    pub clock: Arc<dyn PluginClock>,
+    pub paste_command: Option<String>, // prints the contents of the clipboard
+    pub fuel: u64, // the instructions each call into the plugin is allowed to execute
 }
 
 impl PluginEnv {
@@ -243,6 +335,12 @@ impl PluginEnv {
     pub fn set_permissions(&mut self, permissions: HashSet<PermissionType>) {
         self.permissions.lock().unwrap().replace(permissions);
     }
+
+    // the frame to show after a render in which the plugin printed `rendered_bytes`, None if it is
+    // streaming its frame with render_chunk and the last chunk has not arrived yet
+    pub fn take_streamed_frame(&self, rendered_bytes: Vec<u8>) -> Option<Vec<u8>> {
+        self.streamed_frame.lock().unwrap().take(rendered_bytes)
+    }
 }
 
 #[derive(Eq, PartialEq, Hash)]
@@ -256,6 +354,7 @@ pub struct RunningPlugin {
     pub plugin_env: PluginEnv,
     pub rows: usize,
     pub columns: usize,
+    pub last_render_duration: Duration,
     next_event_ids: HashMap<AtomicEvent, usize>,
     last_applied_event_ids: HashMap<AtomicEvent, usize>,
 }
@@ -274,6 +373,7 @@ impl RunningPlugin {
             plugin_env,
             rows,
             columns,
+            last_render_duration: Duration::ZERO,
             next_event_ids: HashMap::new(),
             last_applied_event_ids: HashMap::new(),
         }
@@ -0,0 +1,41 @@
+//! Plugins subscribed to `PluginMetrics` (eg. a plugin manager) are sent the resource usage of all
+//! running plugins at a fixed interval. Collecting it means locking every plugin instance to read
+//! the size of its memory, so the timer only runs while some plugin is subscribed: it is started
+//! when a plugin subscribes and stops on the first tick after the last subscriber is gone. The
+//! duration of each render is always recorded with the plugin, as that is only two clock reads.
+
+use super::plugin_map::RunningPlugin;
+use super::PluginId;
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+use zellij_utils::data::PluginMetric;
+
+pub const DEFAULT_PLUGIN_METRICS_INTERVAL: u64 = 1; // seconds
+
+/// The metrics of every plugin, one per plugin id even if it has an instance for each client
+pub fn collect_plugin_metrics(
+    running_plugins: Vec<(PluginId, Arc<Mutex<RunningPlugin>>)>,
+) -> Vec<PluginMetric> {
+    let mut plugin_metrics: BTreeMap<PluginId, PluginMetric> = BTreeMap::new();
+    for (plugin_id, running_plugin) in running_plugins {
+        let running_plugin = running_plugin.lock().unwrap();
+        let plugin_metric = plugin_metrics.entry(plugin_id).or_insert(PluginMetric {
+            plugin_id,
+            ..Default::default()
+        });
+        plugin_metric.memory_bytes += memory_bytes(&running_plugin);
+        plugin_metric.last_render_micros = plugin_metric
+            .last_render_micros
+            .max(running_plugin.last_render_duration.as_micros() as u64);
+    }
+    plugin_metrics.into_values().collect()
+}
+
+fn memory_bytes(running_plugin: &RunningPlugin) -> u64 {
+    running_plugin
+        .instance
+        .exports
+        .get_memory("memory")
+        .map(|memory| memory.view(&running_plugin.store).data_size())
+        .unwrap_or(0)
+}
@@ -0,0 +1,83 @@
+//! Each plugin has its own folder under the data dir in which it can persist state across
+//! sessions. Plugins access it through the host rather than through WASI, so that paths can be
+//! sandboxed to this folder.
+
+use highway::{HighwayHash, PortableHash};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use url::Url;
+use zellij_utils::errors::prelude::*;
+use zellij_utils::input::layout::RunPluginLocation;
+
+pub fn plugin_persistent_data_dir(plugin_dir: &Path, location: &RunPluginLocation) -> PathBuf {
+    // we use the location rather than the plugin id so that it is stable across sessions, hashed
+    // so that no url can name a folder outside of this one (each part is zero-padded so that
+    // different hashes can never concatenate to the same folder name)
+    let location_hash: String = PortableHash::default()
+        .hash256(Url::from(location).as_str().as_bytes())
+        .iter()
+        .map(|part| format!("{:016x}", part))
+        .collect();
+    plugin_dir.join("state").join(location_hash)
+}
+
+pub fn read_plugin_file(persistent_data_dir: &Path, path: &Path) -> Result<Vec<u8>> {
+    let err_context = || format!("failed to read plugin file {:?}", path);
+    let file_path = sandboxed_file_path(persistent_data_dir, path).with_context(err_context)?;
+    ensure_inside_data_dir(persistent_data_dir, &file_path).with_context(err_context)?;
+    fs::read(&file_path).with_context(err_context)
+}
+
+pub fn write_plugin_file(persistent_data_dir: &Path, path: &Path, contents: &[u8]) -> Result<()> {
+    let err_context = || format!("failed to write plugin file {:?}", path);
+    let file_path = sandboxed_file_path(persistent_data_dir, path).with_context(err_context)?;
+    fs::create_dir_all(persistent_data_dir).with_context(err_context)?;
+    ensure_inside_data_dir(persistent_data_dir, &file_path).with_context(err_context)?;
+    if let Some(parent_folder) = file_path.parent() {
+        fs::create_dir_all(parent_folder).with_context(err_context)?;
+    }
+    fs::write(&file_path, contents).with_context(err_context)
+}
+
+fn sandboxed_file_path(persistent_data_dir: &Path, path: &Path) -> Result<PathBuf> {
+    let mut file_path = persistent_data_dir.to_path_buf();
+    for component in path.components() {
+        match component {
+            Component::Normal(component) => file_path.push(component),
+            Component::CurDir => {},
+            Component::ParentDir | Component::RootDir | Component::Prefix(_) => {
+                return Err(anyhow!(
+                    "path must be relative to the plugin data folder and may not leave it"
+                ));
+            },
+        }
+    }
+    if file_path == persistent_data_dir {
+        return Err(anyhow!(
+            "path must point to a file in the plugin data folder"
+        ));
+    }
+    Ok(file_path)
+}
+
+fn ensure_inside_data_dir(persistent_data_dir: &Path, file_path: &Path) -> Result<()> {
+    // the path itself cannot leave the data folder, but one of its parts might still be a symlink
+    // pointing outside of it
+    let canonical_data_dir = persistent_data_dir
+        .canonicalize()
+        .context("plugin data folder does not exist")?;
+    let closest_existing_ancestor = file_path.ancestors().find(|ancestor| ancestor.exists());
+    if let Some(closest_existing_ancestor) = closest_existing_ancestor {
+        if !closest_existing_ancestor
+            .canonicalize()?
+            .starts_with(&canonical_data_dir)
+        {
+            return Err(anyhow!("path leads outside of the plugin data folder"));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+#[path = "./unit/plugin_persistent_data_tests.rs"]
+mod plugin_persistent_data_tests;
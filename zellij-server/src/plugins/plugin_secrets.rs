@@ -0,0 +1,208 @@
+//! Plugin configuration values can reference a secret instead of containing it, eg.
+//! `api_token "secret:env:GITHUB_TOKEN"`. Only the reference is kept in the layout (and so in
+//! serialized sessions and in the logs), the secret itself is resolved when the configuration is
+//! sent to the plugin.
+//!
+//! Secrets can come from:
+//! - `secret:env:NAME` - an environment variable of the server
+//! - `secret:file:/path/to/file` - a file only its owner (the user running the server) can
+//!   access, without its trailing newline
+//! - `secret:command:pass show github` - the STDOUT of a command run with `sh -c`, without its
+//!   trailing newline
+//!
+//! Plugins can load other plugins with a configuration of their choosing, so a reference could be
+//! used to read arbitrary files or run commands on their behalf. Configurations plugins send us
+//! (eg. with `start_or_reload_plugin` or `new_tabs_with_layout`) are therefore rejected if they
+//! reference a secret, only layouts and configurations written by the user can do so.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::os::unix::fs::{MetadataExt, PermissionsExt};
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use zellij_utils::errors::prelude::*;
+use zellij_utils::input::layout::{
+    FloatingPaneLayout, Layout, PluginUserConfiguration, Run, TiledPaneLayout,
+};
+use zellij_utils::nix::unistd::getuid;
+
+const SECRET_PREFIX: &str = "secret:";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum SecretSource {
+    Env(String),     // the name of an environment variable
+    File(PathBuf),   // a file only its owner can access
+    Command(String), // a command line run with `sh -c`
+}
+
+impl SecretSource {
+    fn from_configuration_value(value: &str) -> Option<Result<Self>> {
+        let reference = value.strip_prefix(SECRET_PREFIX)?;
+        Some(match reference.split_once(':') {
+            Some(("env", name)) => Ok(SecretSource::Env(name.to_owned())),
+            Some(("file", path)) => Ok(SecretSource::File(PathBuf::from(path))),
+            Some(("command", command_line)) => Ok(SecretSource::Command(command_line.to_owned())),
+            _ => Err(anyhow!(
+                "unknown secret source, expected 'env:', 'file:' or 'command:'"
+            )),
+        })
+    }
+    fn resolve(&self) -> Result<String> {
+        // none of the errors here should include what we read, as it might be the secret
+        match self {
+            SecretSource::Env(name) => std::env::var(name)
+                .map_err(|_| anyhow!("environment variable {:?} is not set or not unicode", name)),
+            SecretSource::File(path) => {
+                let metadata = fs::metadata(path)
+                    .with_context(|| format!("failed to read secret file {:?}", path))?;
+                if metadata.uid() != getuid().as_raw() {
+                    return Err(anyhow!(
+                        "secret file {:?} is not owned by the user running zellij",
+                        path
+                    ));
+                }
+                if metadata.permissions().mode() & 0o077 != 0 {
+                    return Err(anyhow!(
+                        "secret file {:?} can be accessed by other users, its permissions should be 600",
+                        path
+                    ));
+                }
+                let contents = fs::read_to_string(path)
+                    .map_err(|_| anyhow!("failed to read secret file {:?}", path))?;
+                Ok(without_trailing_newline(contents))
+            },
+            SecretSource::Command(command_line) => {
+                let output = Command::new("sh")
+                    .arg("-c")
+                    .arg(command_line)
+                    .stdin(Stdio::null())
+                    .stderr(Stdio::null())
+                    .output()
+                    .with_context(|| format!("failed to run secret command {:?}", command_line))?;
+                if !output.status.success() {
+                    return Err(anyhow!(
+                        "secret command {:?} failed ({})",
+                        command_line,
+                        output.status
+                    ));
+                }
+                let stdout = String::from_utf8(output.stdout).map_err(|_| {
+                    anyhow!("output of secret command {:?} is not unicode", command_line)
+                })?;
+                Ok(without_trailing_newline(stdout))
+            },
+        }
+    }
+}
+
+fn without_trailing_newline(mut secret: String) -> String {
+    if secret.ends_with('\n') {
+        secret.pop();
+        if secret.ends_with('\r') {
+            secret.pop();
+        }
+    }
+    secret
+}
+
+/// Returns the configuration with every secret reference replaced by the secret it references.
+/// The result is meant to be sent to the plugin only, it should never be logged or serialized.
+pub fn resolve_plugin_secrets(
+    configuration: &PluginUserConfiguration,
+) -> Result<PluginUserConfiguration> {
+    let mut resolved_configuration = BTreeMap::new();
+    for (key, value) in configuration.inner() {
+        let resolved_value = match SecretSource::from_configuration_value(value) {
+            Some(secret_source) => {
+                let secret = secret_source
+                    .and_then(|secret_source| secret_source.resolve())
+                    .with_context(|| {
+                        format!(
+                            "failed to resolve {:?} for plugin configuration key {:?}",
+                            value, key
+                        )
+                    })?;
+                log::info!(
+                    "Resolved {:?} for plugin configuration key {:?}",
+                    value,
+                    key
+                );
+                secret
+            },
+            None => value.clone(),
+        };
+        resolved_configuration.insert(key.clone(), resolved_value);
+    }
+    Ok(PluginUserConfiguration::new(resolved_configuration))
+}
+
+/// Fails if the configuration references a secret, for configurations that come from plugins
+pub fn reject_secret_references(configuration: &PluginUserConfiguration) -> Result<()> {
+    match configuration
+        .inner()
+        .iter()
+        .find(|(_key, value)| value.starts_with(SECRET_PREFIX))
+    {
+        Some((key, _value)) => Err(anyhow!(
+            "plugin configuration key {:?} references a secret, which only user configurations can do",
+            key
+        )),
+        None => Ok(()),
+    }
+}
+
+/// Fails if any plugin in the layout references a secret, for layouts that come from plugins
+pub fn reject_secret_references_in_layout(layout: &Layout) -> Result<()> {
+    for (_tab_name, tiled_panes, floating_panes) in &layout.tabs {
+        reject_secret_references_in_tiled_panes(tiled_panes)?;
+        reject_secret_references_in_floating_panes(floating_panes)?;
+    }
+    if let Some((tiled_panes, floating_panes)) = &layout.template {
+        reject_secret_references_in_tiled_panes(tiled_panes)?;
+        reject_secret_references_in_floating_panes(floating_panes)?;
+    }
+    for (tiled_panes, floating_panes) in &layout.swap_layouts {
+        reject_secret_references_in_tiled_panes(tiled_panes)?;
+        reject_secret_references_in_floating_panes(floating_panes)?;
+    }
+    for (swap_tiled_layouts, _name) in &layout.swap_tiled_layouts {
+        for tiled_panes in swap_tiled_layouts.values() {
+            reject_secret_references_in_tiled_panes(tiled_panes)?;
+        }
+    }
+    for (swap_floating_layouts, _name) in &layout.swap_floating_layouts {
+        for floating_panes in swap_floating_layouts.values() {
+            reject_secret_references_in_floating_panes(floating_panes)?;
+        }
+    }
+    for run_plugin in &layout.background_plugins {
+        reject_secret_references(&run_plugin.configuration)?;
+    }
+    Ok(())
+}
+
+fn reject_secret_references_in_run(run: &Option<Run>) -> Result<()> {
+    match run {
+        Some(Run::Plugin(run_plugin)) => reject_secret_references(&run_plugin.configuration),
+        _ => Ok(()),
+    }
+}
+
+fn reject_secret_references_in_tiled_panes(tiled_panes: &TiledPaneLayout) -> Result<()> {
+    reject_secret_references_in_run(&tiled_panes.run)?;
+    for child in &tiled_panes.children {
+        reject_secret_references_in_tiled_panes(child)?;
+    }
+    Ok(())
+}
+
+fn reject_secret_references_in_floating_panes(floating_panes: &[FloatingPaneLayout]) -> Result<()> {
+    for floating_pane in floating_panes {
+        reject_secret_references_in_run(&floating_pane.run)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+#[path = "./unit/plugin_secrets_tests.rs"]
+mod plugin_secrets_tests;
@@ -1,3 +1,4 @@
+use crate::plugins::plugin_fuel::{explain_out_of_fuel, refuel};
 use crate::plugins::plugin_map::PluginEnv;
 use crate::plugins::zellij_exports::wasi_write_object;
 use wasmer::{Instance, Store};
@@ -33,7 +34,7 @@ impl RunningWorker {
             plugin_env,
         }
     }
-    pub fn send_message(&mut self, message: String, payload: String) -> Result<()> {
+    pub fn send_message(&mut self, message: String, payload: Vec<u8>) -> Result<()> {
         let err_context = || format!("Failed to send message to worker");
         let protobuf_message = ProtobufMessage {
             name: message,
@@ -47,15 +48,23 @@ impl RunningWorker {
             .get_function(&self.name)
             .with_context(err_context)?;
         wasi_write_object(&self.plugin_env.wasi_env, &protobuf_bytes).with_context(err_context)?;
-        work_function
+        refuel(&mut self.store, &self.instance, self.plugin_env.fuel);
+        let worked = work_function
             .call(&mut self.store, &[])
-            .with_context(err_context)?;
+            .map_err(anyError::new);
+        explain_out_of_fuel(
+            worked,
+            &mut self.store,
+            &self.instance,
+            self.plugin_env.fuel,
+        )
+        .with_context(err_context)?;
         Ok(())
     }
 }
 
 pub enum MessageToWorker {
-    Message(String, String), // message, payload
+    Message(String, Vec<u8>), // message, payload
     Exit,
 }
 
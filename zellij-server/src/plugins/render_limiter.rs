@@ -0,0 +1,198 @@
+//! Plugins render whenever an event asks them to, so a plugin rendering in a tight loop can flood
+//! the screen thread with renders. The limiter caps the frames each plugin sends to the screen per
+//! second: a frame arriving too soon after the previous one is held back (replacing any frame
+//! already held back) and the latest held back frame is sent once the interval passes. The first
+//! frame after a quiet period is always sent immediately, so interactive plugins stay responsive.
+//! Frames are diffed against the previous one (see `frame_diff`) only once the limiter lets them
+//! through, right before they are queued.
+//!
+//! The cap can be changed per plugin in the layout, eg. `plugin location="..." { max_fps 30; }`,
+//! with `max_fps 0` disabling the limit for that plugin.
+
+use super::frame_diff::PluginFrameDiffer;
+use super::render_queue::PluginRenderQueue;
+use super::PluginId;
+use crate::{screen::ScreenInstruction, thread_bus::ThreadSenders, ClientId};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use zellij_utils::async_std::task;
+use zellij_utils::input::layout::PluginUserConfiguration;
+
+pub const DEFAULT_MAX_PLUGIN_FPS: u32 = 60;
+const MAX_FPS_CONFIGURATION_KEY: &str = "max_fps";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FrameDecision {
+    Render(Vec<u8>),
+    Defer(Duration), // the frame is held back, it should be flushed after this duration
+    Coalesced,       // the frame replaced one that was already held back
+}
+
+#[derive(Debug)]
+pub struct RenderLimiter {
+    default_max_fps: u32,
+    max_fps: HashMap<PluginId, u32>,
+    last_frames: HashMap<(PluginId, ClientId), Instant>,
+    pending_frames: HashMap<(PluginId, ClientId), Vec<u8>>,
+}
+
+impl RenderLimiter {
+    pub fn new(default_max_fps: u32) -> Self {
+        RenderLimiter {
+            default_max_fps,
+            max_fps: HashMap::new(),
+            last_frames: HashMap::new(),
+            pending_frames: HashMap::new(),
+        }
+    }
+    pub fn set_max_fps(&mut self, plugin_id: PluginId, max_fps: Option<u32>) {
+        match max_fps {
+            Some(max_fps) => {
+                self.max_fps.insert(plugin_id, max_fps);
+            },
+            None => {
+                self.max_fps.remove(&plugin_id);
+            },
+        }
+    }
+    pub fn remove_plugin(&mut self, plugin_id: PluginId) {
+        self.max_fps.remove(&plugin_id);
+        self.last_frames.retain(|(pid, _), _| *pid != plugin_id);
+        self.pending_frames.retain(|(pid, _), _| *pid != plugin_id);
+    }
+    pub fn frame(
+        &mut self,
+        plugin_id: PluginId,
+        client_id: ClientId,
+        bytes: Vec<u8>,
+        now: Instant,
+    ) -> FrameDecision {
+        let key = (plugin_id, client_id);
+        if let Some(pending_frame) = self.pending_frames.get_mut(&key) {
+            *pending_frame = bytes;
+            return FrameDecision::Coalesced;
+        }
+        let min_interval = match self.min_interval(plugin_id) {
+            Some(min_interval) => min_interval,
+            None => return FrameDecision::Render(bytes),
+        };
+        let elapsed = self
+            .last_frames
+            .get(&key)
+            .map(|last_frame| now.saturating_duration_since(*last_frame));
+        match elapsed {
+            Some(elapsed) if elapsed < min_interval => {
+                self.pending_frames.insert(key, bytes);
+                FrameDecision::Defer(min_interval - elapsed)
+            },
+            _ => {
+                self.last_frames.insert(key, now);
+                FrameDecision::Render(bytes)
+            },
+        }
+    }
+    pub fn take_pending_frame(
+        &mut self,
+        plugin_id: PluginId,
+        client_id: ClientId,
+        now: Instant,
+    ) -> Option<Vec<u8>> {
+        let key = (plugin_id, client_id);
+        let pending_frame = self.pending_frames.remove(&key)?;
+        self.last_frames.insert(key, now);
+        Some(pending_frame)
+    }
+    fn min_interval(&self, plugin_id: PluginId) -> Option<Duration> {
+        let max_fps = self
+            .max_fps
+            .get(&plugin_id)
+            .copied()
+            .unwrap_or(self.default_max_fps);
+        if max_fps == 0 {
+            None
+        } else {
+            Some(Duration::from_secs(1) / max_fps)
+        }
+    }
+}
+
+pub fn max_fps_from_configuration(configuration: &PluginUserConfiguration) -> Option<u32> {
+    let max_fps = configuration.inner().get(MAX_FPS_CONFIGURATION_KEY)?;
+    match max_fps.parse() {
+        Ok(max_fps) => Some(max_fps),
+        Err(e) => {
+            log::error!("Failed to parse plugin max_fps {:?}: {}", max_fps, e);
+            None
+        },
+    }
+}
+
+pub fn send_plugin_bytes(
+    render_limiter: &Arc<Mutex<RenderLimiter>>,
+    frame_differ: &PluginFrameDiffer,
+    render_queue: &PluginRenderQueue,
+    plugin_bytes: Vec<(PluginId, ClientId, Vec<u8>)>,
+    senders: &ThreadSenders,
+) {
+    let received_frames = !plugin_bytes.is_empty();
+    let mut frames_to_render = vec![];
+    for (plugin_id, client_id, bytes) in plugin_bytes {
+        let frame_decision =
+            render_limiter
+                .lock()
+                .unwrap()
+                .frame(plugin_id, client_id, bytes, Instant::now());
+        match frame_decision {
+            FrameDecision::Render(bytes) => frames_to_render.push((plugin_id, client_id, bytes)),
+            FrameDecision::Defer(delay) => {
+                task::spawn({
+                    let render_limiter = render_limiter.clone();
+                    let frame_differ = frame_differ.clone();
+                    let render_queue = render_queue.clone();
+                    let senders = senders.clone();
+                    async move {
+                        task::sleep(delay).await;
+                        let pending_frame = render_limiter.lock().unwrap().take_pending_frame(
+                            plugin_id,
+                            client_id,
+                            Instant::now(),
+                        );
+                        if let Some(bytes) = pending_frame {
+                            let plugin_bytes = vec![(plugin_id, client_id, bytes)];
+                            queue_plugin_bytes(
+                                &frame_differ,
+                                &render_queue,
+                                plugin_bytes,
+                                &senders,
+                            );
+                        }
+                    }
+                });
+            },
+            FrameDecision::Coalesced => {},
+        }
+    }
+    if received_frames && frames_to_render.is_empty() {
+        // all frames were held back, they will be sent once the interval passes
+        return;
+    }
+    queue_plugin_bytes(frame_differ, render_queue, frames_to_render, senders);
+}
+
+fn queue_plugin_bytes(
+    frame_differ: &PluginFrameDiffer,
+    render_queue: &PluginRenderQueue,
+    plugin_bytes: Vec<(PluginId, ClientId, Vec<u8>)>,
+    senders: &ThreadSenders,
+) {
+    let frame_count = render_queue.push(frame_differ.diff(plugin_bytes));
+    let _ = senders.send_to_screen(ScreenInstruction::PluginRenderQueued(
+        render_queue.clone(),
+        frame_count,
+    ));
+}
+
+#[cfg(test)]
+#[path = "./unit/render_limiter_tests.rs"]
+mod render_limiter_tests;
@@ -0,0 +1,99 @@
+//! Rendered plugin frames are not sent to the screen thread directly. Instead, each plugin has its
+//! own queue of frames and the screen thread is only told how many frames were queued. When
+//! handling this, the screen pops the same number of frames, taking one frame from each plugin in
+//! turn. This way a plugin rendering in a tight loop fills up its own queue, while the frames of
+//! other plugins are served on the next round rather than waiting behind all of its frames.
+//!
+//! The frames of a single plugin are always popped in the order they were queued.
+
+use super::frame_diff::PluginFrame;
+use super::PluginId;
+use crate::ClientId;
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt;
+use std::ops::Bound;
+use std::sync::{Arc, Mutex};
+
+#[derive(Clone, Default)]
+pub struct PluginRenderQueue {
+    state: Arc<Mutex<PluginRenderQueueState>>,
+}
+
+#[derive(Default)]
+struct PluginRenderQueueState {
+    frames: BTreeMap<PluginId, VecDeque<(ClientId, PluginFrame)>>,
+    last_served_plugin: Option<PluginId>,
+}
+
+impl fmt::Debug for PluginRenderQueue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let queued_frames: usize = self
+            .state
+            .lock()
+            .unwrap()
+            .frames
+            .values()
+            .map(|frames| frames.len())
+            .sum();
+        f.debug_struct("PluginRenderQueue")
+            .field("queued_frames", &queued_frames)
+            .finish()
+    }
+}
+
+impl PluginRenderQueue {
+    pub fn new() -> Self {
+        PluginRenderQueue::default()
+    }
+    /// Queues the frames and returns how many were queued
+    pub fn push(&self, plugin_frames: Vec<(PluginId, ClientId, PluginFrame)>) -> usize {
+        let frame_count = plugin_frames.len();
+        let mut state = self.state.lock().unwrap();
+        for (plugin_id, client_id, frame) in plugin_frames {
+            state
+                .frames
+                .entry(plugin_id)
+                .or_insert_with(VecDeque::new)
+                .push_back((client_id, frame));
+        }
+        frame_count
+    }
+    /// Pops up to `max_frames` frames, one from each plugin with queued frames in turn, starting
+    /// after the plugin that was served last
+    pub fn pop_round_robin(&self, max_frames: usize) -> Vec<(PluginId, ClientId, PluginFrame)> {
+        let mut state = self.state.lock().unwrap();
+        let mut plugin_frames = vec![];
+        while plugin_frames.len() < max_frames {
+            let next_plugin = match state.last_served_plugin {
+                Some(last_served_plugin) => state
+                    .frames
+                    .range((Bound::Excluded(last_served_plugin), Bound::Unbounded))
+                    .next()
+                    .or_else(|| state.frames.iter().next())
+                    .map(|(plugin_id, _)| *plugin_id),
+                None => state.frames.keys().next().copied(),
+            };
+            let plugin_id = match next_plugin {
+                Some(plugin_id) => plugin_id,
+                None => break,
+            };
+            if let Some(queued_frames) = state.frames.get_mut(&plugin_id) {
+                if let Some((client_id, frame)) = queued_frames.pop_front() {
+                    plugin_frames.push((plugin_id, client_id, frame));
+                }
+                if queued_frames.is_empty() {
+                    state.frames.remove(&plugin_id);
+                }
+            }
+            state.last_served_plugin = Some(plugin_id);
+        }
+        plugin_frames
+    }
+    pub fn remove_plugin(&self, plugin_id: PluginId) {
+        self.state.lock().unwrap().frames.remove(&plugin_id);
+    }
+}
+
+#[cfg(test)]
+#[path = "./unit/render_queue_tests.rs"]
+mod render_queue_tests;
@@ -0,0 +1,70 @@
+//! A plugin can stream a large frame in chunks with `render_chunk` rather than printing it all in
+//! `render`. The chunks are kept with the plugin until the last one arrives, so that they go away
+//! with the plugin when it is unloaded, and the whole frame is then rendered like any frame the
+//! plugin printed: through the render limiter and queue. A chunk of another frame drops whatever
+//! arrived of the current one, as does a frame growing larger than the limit, so that only
+//! complete frames are ever shown.
+
+pub const MAX_STREAMED_FRAME_SIZE: usize = 16 * 1024 * 1024; // bytes
+
+#[derive(Debug)]
+pub struct StreamedFrame {
+    max_len: usize,
+    was_streamed: bool, // whether render_chunk was called since the last render
+    pending: Option<(u64, Option<Vec<u8>>)>, // id and chunks (None if dropped) of the frame being streamed
+    complete: Option<Vec<u8>>,
+}
+
+impl Default for StreamedFrame {
+    fn default() -> Self {
+        StreamedFrame::new(MAX_STREAMED_FRAME_SIZE)
+    }
+}
+
+impl StreamedFrame {
+    pub fn new(max_len: usize) -> Self {
+        StreamedFrame {
+            max_len,
+            was_streamed: false,
+            pending: None,
+            complete: None,
+        }
+    }
+    /// Adds a chunk to the frame being streamed, returns false if this chunk made the frame too
+    /// large, in which case the frame is dropped along with the chunks still to come
+    pub fn add_chunk(&mut self, frame_id: u64, chunk: Vec<u8>, is_last: bool) -> bool {
+        self.was_streamed = true;
+        let mut pending = match self.pending.take() {
+            Some((pending_frame_id, bytes)) if pending_frame_id == frame_id => bytes,
+            _ => Some(vec![]), // a new frame started before the last one was complete
+        };
+        let mut fits = true;
+        if let Some(bytes) = pending.as_mut() {
+            bytes.extend(chunk);
+            fits = bytes.len() <= self.max_len;
+        }
+        if !fits {
+            pending = None;
+        }
+        if is_last {
+            self.complete = pending;
+        } else {
+            self.pending = Some((frame_id, pending));
+        }
+        fits
+    }
+    /// Returns the frame to show after a render in which the plugin printed `rendered_bytes`: those
+    /// if it did not stream its frame, the streamed frame if its last chunk arrived, otherwise
+    /// nothing
+    pub fn take(&mut self, rendered_bytes: Vec<u8>) -> Option<Vec<u8>> {
+        if std::mem::take(&mut self.was_streamed) {
+            self.complete.take()
+        } else {
+            Some(rendered_bytes)
+        }
+    }
+}
+
+#[cfg(test)]
+#[path = "./unit/streamed_frame_tests.rs"]
+mod streamed_frame_tests;
@@ -0,0 +1,222 @@
+use super::{ChangedLine, PluginFrame, PluginFrameDiffer};
+use crate::panes::{Grid, LinkHandler, SixelImageStore};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+use zellij_utils::{
+    data::{Palette, Style},
+    vte,
+};
+
+fn render(rows: usize, highlighted_row: Option<usize>) -> Vec<u8> {
+    (0..rows)
+        .map(|row| {
+            if Some(row) == highlighted_row {
+                format!("\u{1b}[1mline {}\u{1b}[m", row)
+            } else {
+                format!("line {}", row)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n\r")
+        .into_bytes()
+}
+
+// the lines, followed by enough unchanging lines for the changes to be smaller than the frame
+fn frame_with_lines(lines: &[&str]) -> Vec<u8> {
+    let mut lines: Vec<String> = lines.iter().map(|line| line.to_string()).collect();
+    lines.extend((0..10).map(|row| format!("unchanging line {}", row)));
+    lines.join("\n\r").into_bytes()
+}
+
+fn diff(differ: &PluginFrameDiffer, bytes: Vec<u8>) -> PluginFrame {
+    let (_plugin_id, _client_id, frame) = differ.diff(vec![(1, 1, bytes)]).remove(0);
+    frame
+}
+
+fn grid_showing(bytes: &[u8]) -> Grid {
+    let mut grid = Grid::new(
+        20,
+        80,
+        Rc::new(RefCell::new(Palette::default())),
+        Rc::new(RefCell::new(HashMap::new())),
+        Rc::new(RefCell::new(LinkHandler::new())),
+        Rc::new(RefCell::new(None)),
+        Rc::new(RefCell::new(SixelImageStore::default())),
+        Style::default(),
+        false,
+        true,
+        true,
+        false,
+        false,
+    );
+    let mut vte_parser = vte::Parser::new();
+    for &byte in bytes {
+        vte_parser.advance(&mut grid, byte);
+    }
+    grid
+}
+
+#[test]
+fn first_frame_is_sent_in_full() {
+    let differ = PluginFrameDiffer::new();
+    let frame = diff(&differ, render(10, None));
+    assert_eq!(
+        frame,
+        PluginFrame::Full {
+            frame_id: 0,
+            bytes: render(10, None)
+        }
+    );
+}
+
+#[test]
+fn a_one_cell_change_is_sent_much_smaller_than_the_first_frame() {
+    let differ = PluginFrameDiffer::new();
+    let first_frame: Vec<u8> = (0..40)
+        .map(|row| format!("\u{1b}[32m{:0>120}\u{1b}[m", row))
+        .collect::<Vec<_>>()
+        .join("\n\r")
+        .into_bytes();
+    let mut second_frame = first_frame.clone();
+    let changed_cell = second_frame.len() / 2;
+    second_frame[changed_cell] = b'x';
+    diff(&differ, first_frame.clone());
+    let changes_len: usize = match diff(&differ, second_frame) {
+        PluginFrame::Changed { lines, .. } => {
+            assert_eq!(lines.len(), 1, "only the line of the changed cell is sent");
+            lines.iter().map(|line| line.positioned_run().len()).sum()
+        },
+        frame => panic!("expected changed lines, got {:?}", frame),
+    };
+    assert!(
+        changes_len * 20 < first_frame.len(),
+        "changes of {} bytes are much smaller than the first frame of {} bytes",
+        changes_len,
+        first_frame.len()
+    );
+}
+
+#[test]
+fn only_changed_lines_are_sent() {
+    let differ = PluginFrameDiffer::new();
+    diff(&differ, render(10, None));
+    let frame = diff(&differ, render(10, Some(4)));
+    assert_eq!(
+        frame,
+        PluginFrame::Changed {
+            frame_id: 1,
+            base_frame_id: 0,
+            lines: vec![ChangedLine {
+                row: 4,
+                carried_styles: vec![],
+                bytes: b"\r\x1b[1mline 4\x1b[m".to_vec(),
+            }]
+        }
+    );
+}
+
+#[test]
+fn styles_left_on_by_the_lines_above_are_carried_into_changed_lines() {
+    let differ = PluginFrameDiffer::new();
+    diff(
+        &differ,
+        frame_with_lines(&["\u{1b}[31mred", "\u{1b}[1mbold red", "still bold red"]),
+    );
+    let frame = diff(
+        &differ,
+        frame_with_lines(&["\u{1b}[31mred", "\u{1b}[1mbold red", "changed bold red"]),
+    );
+    match frame {
+        PluginFrame::Changed { lines, .. } => {
+            assert_eq!(lines.len(), 1);
+            assert_eq!(lines[0].carried_styles, b"\x1b[31m\x1b[1m".to_vec());
+        },
+        frame => panic!("expected changed lines, got {:?}", frame),
+    }
+}
+
+#[test]
+fn changed_lines_written_over_the_previous_frame_show_the_new_frame() {
+    let differ = PluginFrameDiffer::new();
+    let first_frame = frame_with_lines(&[
+        "\u{1b}[31mred",
+        "\u{1b}[1mbold red",
+        "still bold red\u{1b}[m",
+        "plain",
+    ]);
+    let second_frame = frame_with_lines(&[
+        "\u{1b}[31mred",
+        "\u{1b}[1mbold",
+        "changed bold red\u{1b}[m",
+        "plain",
+    ]);
+    diff(&differ, first_frame.clone());
+    let mut changes = vec![];
+    match diff(&differ, second_frame.clone()) {
+        PluginFrame::Changed { lines, .. } => {
+            for line in lines {
+                changes.extend(line.positioned_run());
+            }
+        },
+        frame => panic!("expected changed lines, got {:?}", frame),
+    }
+    let mut grid_with_changes_applied = first_frame;
+    grid_with_changes_applied.extend(changes);
+    assert_eq!(
+        format!("{:?}", grid_showing(&grid_with_changes_applied)),
+        format!("{:?}", grid_showing(&second_frame)),
+    );
+}
+
+#[test]
+fn frames_with_a_different_number_of_lines_are_sent_in_full() {
+    let differ = PluginFrameDiffer::new();
+    diff(&differ, render(10, None));
+    let frame = diff(&differ, render(12, Some(4)));
+    assert!(matches!(frame, PluginFrame::Full { .. }));
+}
+
+#[test]
+fn changed_lines_moving_the_cursor_are_sent_in_full() {
+    let differ = PluginFrameDiffer::new();
+    diff(&differ, render(10, None));
+    let mut bytes = render(10, None);
+    bytes.extend(b"\x1b[2;1Hline 1");
+    let frame = diff(&differ, bytes);
+    assert!(matches!(frame, PluginFrame::Full { .. }));
+}
+
+#[test]
+fn last_frame_is_resent_in_full_with_its_id() {
+    let differ = PluginFrameDiffer::new();
+    diff(&differ, render(10, None));
+    diff(&differ, render(10, Some(4)));
+    assert_eq!(
+        differ.last_frame(1, 1),
+        Some(PluginFrame::Full {
+            frame_id: 1,
+            bytes: render(10, Some(4))
+        })
+    );
+    assert_eq!(
+        differ.last_frame(1, 2),
+        None,
+        "nothing was rendered for client 2"
+    );
+}
+
+#[test]
+fn frames_are_sent_in_full_after_the_plugin_is_reset() {
+    let differ = PluginFrameDiffer::new();
+    diff(&differ, render(10, None));
+    differ.reset_plugin(1);
+    let frame = diff(&differ, render(10, Some(4)));
+    assert_eq!(
+        frame,
+        PluginFrame::Full {
+            frame_id: 1,
+            bytes: render(10, Some(4))
+        }
+    );
+}
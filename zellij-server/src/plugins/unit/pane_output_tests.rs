@@ -0,0 +1,27 @@
+use super::PaneOutputBuffer;
+
+#[test]
+fn plugin_is_only_notified_when_the_buffer_was_empty() {
+    let pane_output = PaneOutputBuffer::new(1024);
+    assert!(pane_output.push(b"foo"), "first output notifies");
+    assert!(!pane_output.push(b"bar"), "output not taken yet");
+    assert_eq!(pane_output.take(), b"foobar".to_vec());
+    assert!(pane_output.push(b"baz"), "buffer emptied by take");
+}
+
+#[test]
+fn empty_output_does_not_notify() {
+    let pane_output = PaneOutputBuffer::new(1024);
+    assert!(!pane_output.push(b""));
+    assert!(pane_output.take().is_empty());
+}
+
+#[test]
+fn oldest_output_is_dropped_when_the_plugin_falls_behind() {
+    let pane_output = PaneOutputBuffer::new(8);
+    pane_output.push(b"0123");
+    pane_output.push(b"456789");
+    assert_eq!(pane_output.take(), b"23456789".to_vec());
+    pane_output.push(b"0123456789abc");
+    assert_eq!(pane_output.take(), b"56789abc".to_vec());
+}
@@ -0,0 +1,76 @@
+use super::*;
+use std::collections::BTreeMap;
+use wasmer::{imports, CompilerConfig, Module, Singlepass};
+
+// a stand-in for a plugin with an entry point that loops forever and one that returns
+const LOOPING_MODULE: &str = r#"
+(module
+  (func (export "spin")
+    (loop $forever
+      br $forever))
+  (func (export "add") (param i32 i32) (result i32)
+    local.get 0
+    local.get 1
+    i32.add))
+"#;
+
+fn metered_instance() -> (Store, Instance) {
+    let mut compiler = Singlepass::default();
+    compiler.push_middleware(metering_middleware());
+    let mut store = Store::new(compiler);
+    let module = Module::new(&store, LOOPING_MODULE).unwrap();
+    let instance = Instance::new(&mut store, &module, &imports! {}).unwrap();
+    (store, instance)
+}
+
+#[test]
+fn looping_call_is_trapped_once_out_of_fuel() {
+    let (mut store, instance) = metered_instance();
+    let fuel = 100_000;
+    refuel(&mut store, &instance, fuel);
+    let spin = instance.exports.get_function("spin").unwrap();
+    let result = spin.call(&mut store, &[]).map_err(anyError::new);
+    let error = explain_out_of_fuel(result, &mut store, &instance, fuel).unwrap_err();
+    assert!(
+        format!("{:?}", error).contains("plugin ran out of fuel"),
+        "error explains the plugin ran out of fuel: {:?}",
+        error
+    );
+
+    // the next call gets a fresh budget
+    refuel(&mut store, &instance, fuel);
+    let add = instance.exports.get_function("add").unwrap();
+    let result = add
+        .call(&mut store, &[wasmer::Value::I32(1), wasmer::Value::I32(2)])
+        .map_err(anyError::new);
+    let sum = explain_out_of_fuel(result, &mut store, &instance, fuel).unwrap();
+    assert_eq!(sum.to_vec(), vec![wasmer::Value::I32(3)]);
+}
+
+#[test]
+fn max_fuel_is_read_from_the_plugin_configuration() {
+    let configuration = |max_fuel: Option<&str>| {
+        let mut configuration = BTreeMap::new();
+        if let Some(max_fuel) = max_fuel {
+            configuration.insert("max_fuel".to_owned(), max_fuel.to_owned());
+        }
+        PluginUserConfiguration::new(configuration)
+    };
+    assert_eq!(
+        max_fuel_from_configuration(&configuration(None)),
+        DEFAULT_PLUGIN_FUEL
+    );
+    assert_eq!(
+        max_fuel_from_configuration(&configuration(Some("500"))),
+        500
+    );
+    assert_eq!(
+        max_fuel_from_configuration(&configuration(Some("0"))),
+        u64::MAX,
+        "0 lifts the limit"
+    );
+    assert_eq!(
+        max_fuel_from_configuration(&configuration(Some("lots"))),
+        DEFAULT_PLUGIN_FUEL
+    );
+}
@@ -0,0 +1,151 @@
+use super::{plugin_persistent_data_dir, read_plugin_file, write_plugin_file};
+use std::path::{Path, PathBuf};
+use tempfile::tempdir;
+use zellij_utils::input::layout::RunPluginLocation;
+use zellij_utils::input::plugins::PluginTag;
+
+#[test]
+fn plugin_file_persists_across_reloads() {
+    let data_dir = tempdir().unwrap();
+    let plugin_dir = data_dir.path().join("plugins");
+    let location = RunPluginLocation::File(PathBuf::from("/path/to/my/plugin.wasm"));
+    let persistent_data_dir = plugin_persistent_data_dir(&plugin_dir, &location);
+    write_plugin_file(
+        &persistent_data_dir,
+        Path::new("state/counter"),
+        "42".as_bytes(),
+    )
+    .unwrap();
+
+    // a reloaded plugin gets its folder from its location again
+    let reloaded_persistent_data_dir = plugin_persistent_data_dir(&plugin_dir, &location);
+    let contents =
+        read_plugin_file(&reloaded_persistent_data_dir, Path::new("state/counter")).unwrap();
+    assert_eq!(contents, "42".as_bytes(), "file read back after reload");
+}
+
+#[test]
+fn plugin_files_are_separate_per_plugin() {
+    let data_dir = tempdir().unwrap();
+    let plugin_dir = data_dir.path().join("plugins");
+    let first_plugin_data_dir = plugin_persistent_data_dir(
+        &plugin_dir,
+        &RunPluginLocation::File(PathBuf::from("/path/to/first/plugin.wasm")),
+    );
+    let second_plugin_data_dir = plugin_persistent_data_dir(
+        &plugin_dir,
+        &RunPluginLocation::Zellij(PluginTag::new("tab-bar")),
+    );
+    write_plugin_file(
+        &first_plugin_data_dir,
+        Path::new("file"),
+        "first".as_bytes(),
+    )
+    .unwrap();
+    assert!(
+        read_plugin_file(&second_plugin_data_dir, Path::new("file")).is_err(),
+        "file not visible to other plugin"
+    );
+}
+
+#[test]
+fn plugin_file_paths_cannot_leave_the_plugin_data_folder() {
+    let data_dir = tempdir().unwrap();
+    let plugin_dir = data_dir.path().join("plugins");
+    let location = RunPluginLocation::File(PathBuf::from("/path/to/my/plugin.wasm"));
+    let persistent_data_dir = plugin_persistent_data_dir(&plugin_dir, &location);
+    std::fs::write(data_dir.path().join("secret"), "secret").unwrap();
+    let secret_path = data_dir.path().join("secret");
+    assert!(
+        write_plugin_file(&persistent_data_dir, Path::new("../escape"), "".as_bytes()).is_err(),
+        "cannot write to parent folder"
+    );
+    assert!(
+        read_plugin_file(&persistent_data_dir, &secret_path).is_err(),
+        "cannot read absolute paths"
+    );
+    assert!(
+        read_plugin_file(
+            &persistent_data_dir,
+            Path::new("foo/../../../../../../../secret")
+        )
+        .is_err(),
+        "cannot traverse out of the plugin data folder"
+    );
+    assert!(
+        read_plugin_file(&persistent_data_dir, Path::new("")).is_err(),
+        "cannot read the plugin data folder itself"
+    );
+}
+
+#[test]
+fn plugin_file_paths_cannot_leave_the_plugin_data_folder_through_symlinks() {
+    let data_dir = tempdir().unwrap();
+    let plugin_dir = data_dir.path().join("plugins");
+    let location = RunPluginLocation::File(PathBuf::from("/path/to/my/plugin.wasm"));
+    let persistent_data_dir = plugin_persistent_data_dir(&plugin_dir, &location);
+    write_plugin_file(&persistent_data_dir, Path::new("file"), "".as_bytes()).unwrap();
+    std::os::unix::fs::symlink(data_dir.path(), persistent_data_dir.join("link")).unwrap();
+    std::fs::write(data_dir.path().join("secret"), "secret").unwrap();
+    assert!(
+        read_plugin_file(&persistent_data_dir, Path::new("link/secret")).is_err(),
+        "cannot read through a symlink leading out of the plugin data folder"
+    );
+}
+
+#[test]
+fn plugin_data_folder_is_inside_the_plugin_dir_whatever_the_plugin_url() {
+    let data_dir = tempdir().unwrap();
+    let plugin_dir = data_dir.path().join("plugins");
+    let location = RunPluginLocation::File(PathBuf::from("/../../../../tmp/escape"));
+    let persistent_data_dir = plugin_persistent_data_dir(&plugin_dir, &location);
+    assert_eq!(
+        persistent_data_dir.parent(),
+        Some(plugin_dir.join("state").as_path()),
+        "plugin data folder is directly inside the state folder"
+    );
+}
+
+#[test]
+fn plugin_data_folder_name_is_a_fixed_width_hex_hash() {
+    let plugin_dir = PathBuf::from("/plugins");
+    for location in vec![
+        RunPluginLocation::File(PathBuf::from("/path/to/plugin.wasm")),
+        RunPluginLocation::Zellij(PluginTag::new("tab-bar")),
+    ] {
+        let persistent_data_dir = plugin_persistent_data_dir(&plugin_dir, &location);
+        let folder_name = persistent_data_dir
+            .file_name()
+            .and_then(|folder_name| folder_name.to_str())
+            .unwrap();
+        assert_eq!(folder_name.len(), 64, "folder name is 256 bits of hex");
+        assert!(
+            folder_name.chars().all(|c| c.is_ascii_hexdigit()),
+            "folder name only contains hex digits"
+        );
+    }
+}
+
+#[test]
+fn no_folders_are_created_through_symlinks_leading_out_of_the_plugin_data_folder() {
+    let data_dir = tempdir().unwrap();
+    let plugin_dir = data_dir.path().join("plugins");
+    let location = RunPluginLocation::File(PathBuf::from("/path/to/my/plugin.wasm"));
+    let persistent_data_dir = plugin_persistent_data_dir(&plugin_dir, &location);
+    let outside_dir = tempdir().unwrap();
+    std::fs::create_dir_all(&persistent_data_dir).unwrap();
+    std::os::unix::fs::symlink(outside_dir.path(), persistent_data_dir.join("link")).unwrap();
+    assert!(
+        write_plugin_file(
+            &persistent_data_dir,
+            Path::new("link/new/file"),
+            "".as_bytes()
+        )
+        .is_err(),
+        "cannot write through a symlink leading out of the plugin data folder"
+    );
+    assert!(
+        !outside_dir.path().join("new").exists(),
+        "no folder was created outside of the plugin data folder"
+    );
+}
@@ -0,0 +1,200 @@
+use super::{reject_secret_references, reject_secret_references_in_layout, resolve_plugin_secrets};
+use std::collections::BTreeMap;
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::sync::Mutex;
+use tempfile::tempdir;
+use zellij_utils::input::layout::{Layout, PluginUserConfiguration};
+use zellij_utils::lazy_static::lazy_static;
+
+lazy_static! {
+    static ref LOGGED_MESSAGES: Mutex<Vec<String>> = Mutex::new(vec![]);
+}
+
+struct CapturingLogger;
+
+impl log::Log for CapturingLogger {
+    fn enabled(&self, _metadata: &log::Metadata) -> bool {
+        true
+    }
+    fn log(&self, record: &log::Record) {
+        LOGGED_MESSAGES
+            .lock()
+            .unwrap()
+            .push(format!("{}", record.args()));
+    }
+    fn flush(&self) {}
+}
+
+static LOGGER: CapturingLogger = CapturingLogger;
+
+fn capture_logs() {
+    // the logger can only be set once per process, so this might already have happened
+    let _ = log::set_logger(&LOGGER);
+    log::set_max_level(log::LevelFilter::Trace);
+}
+
+fn configuration_with(key: &str, value: &str) -> PluginUserConfiguration {
+    let mut configuration = BTreeMap::new();
+    configuration.insert(key.to_owned(), value.to_owned());
+    configuration.insert("theme".to_owned(), "dark".to_owned());
+    PluginUserConfiguration::new(configuration)
+}
+
+#[test]
+fn env_secret_is_resolved_without_being_logged() {
+    capture_logs();
+    let secret = "env-secret-ee5c2b17";
+    std::env::set_var("ZELLIJ_TEST_PLUGIN_SECRET", secret);
+    let configuration = configuration_with("api_token", "secret:env:ZELLIJ_TEST_PLUGIN_SECRET");
+    let resolved_configuration = resolve_plugin_secrets(&configuration).unwrap();
+    assert_eq!(
+        resolved_configuration.inner().get("api_token"),
+        Some(&secret.to_owned()),
+        "plugin receives the secret"
+    );
+    assert_eq!(
+        resolved_configuration.inner().get("theme"),
+        Some(&"dark".to_owned()),
+        "other configuration is untouched"
+    );
+    assert!(
+        !format!("{:?}", configuration).contains(secret),
+        "layout configuration only holds the reference"
+    );
+    let logged_messages = LOGGED_MESSAGES.lock().unwrap();
+    assert!(
+        logged_messages
+            .iter()
+            .any(|message| message.contains("secret:env:ZELLIJ_TEST_PLUGIN_SECRET")),
+        "secret reference was logged"
+    );
+    assert!(
+        !logged_messages
+            .iter()
+            .any(|message| message.contains(secret)),
+        "secret was not logged"
+    );
+}
+
+#[test]
+fn missing_env_secret_fails() {
+    std::env::remove_var("ZELLIJ_TEST_MISSING_PLUGIN_SECRET");
+    let configuration =
+        configuration_with("api_token", "secret:env:ZELLIJ_TEST_MISSING_PLUGIN_SECRET");
+    assert!(resolve_plugin_secrets(&configuration).is_err());
+}
+
+#[test]
+fn file_secret_is_resolved() {
+    let temp_folder = tempdir().unwrap();
+    let secret_file = temp_folder.path().join("token");
+    fs::write(&secret_file, "file-secret\n").unwrap();
+    fs::set_permissions(&secret_file, fs::Permissions::from_mode(0o600)).unwrap();
+    let configuration = configuration_with(
+        "api_token",
+        &format!("secret:file:{}", secret_file.display()),
+    );
+    let resolved_configuration = resolve_plugin_secrets(&configuration).unwrap();
+    assert_eq!(
+        resolved_configuration.inner().get("api_token"),
+        Some(&"file-secret".to_owned()),
+        "plugin receives the file contents without the trailing newline"
+    );
+}
+
+#[test]
+fn file_secret_readable_by_others_fails() {
+    let temp_folder = tempdir().unwrap();
+    let secret_file = temp_folder.path().join("token");
+    fs::write(&secret_file, "file-secret\n").unwrap();
+    fs::set_permissions(&secret_file, fs::Permissions::from_mode(0o644)).unwrap();
+    let configuration = configuration_with(
+        "api_token",
+        &format!("secret:file:{}", secret_file.display()),
+    );
+    let error = resolve_plugin_secrets(&configuration).unwrap_err();
+    assert!(
+        !format!("{:?}", error).contains("file-secret"),
+        "error does not include the file contents"
+    );
+}
+
+#[test]
+fn command_secret_is_resolved() {
+    let configuration = configuration_with("api_token", "secret:command:echo command-secret");
+    let resolved_configuration = resolve_plugin_secrets(&configuration).unwrap();
+    assert_eq!(
+        resolved_configuration.inner().get("api_token"),
+        Some(&"command-secret".to_owned()),
+        "plugin receives the output of the command without the trailing newline"
+    );
+}
+
+#[test]
+fn failing_command_secret_fails() {
+    // the output is uppercase so it can be told apart from the command line in the error
+    let configuration = configuration_with(
+        "api_token",
+        "secret:command:echo leaked | tr a-z A-Z; exit 1",
+    );
+    let error = resolve_plugin_secrets(&configuration).unwrap_err();
+    assert!(
+        !format!("{:?}", error).contains("LEAKED"),
+        "error does not include the output of the command"
+    );
+}
+
+#[test]
+fn secret_references_from_plugins_are_rejected() {
+    let configuration = configuration_with("api_token", "secret:env:HOME");
+    assert!(reject_secret_references(&configuration).is_err());
+    assert!(reject_secret_references(&configuration_with("api_token", "not-a-secret")).is_ok());
+    let layout_with_secret = Layout::from_str(
+        r#"
+        layout {
+            pane split_direction="vertical" {
+                pane
+                pane {
+                    plugin location="zellij:tab-bar" {
+                        api_token "secret:env:HOME"
+                    }
+                }
+            }
+        }
+    "#,
+        "layout_from_plugin".into(),
+        None,
+        None,
+    )
+    .unwrap();
+    assert!(
+        reject_secret_references_in_layout(&layout_with_secret).is_err(),
+        "secret in a nested plugin pane is found"
+    );
+    let layout_without_secret = Layout::from_str(
+        r#"
+        layout {
+            pane
+            floating_panes {
+                pane {
+                    plugin location="zellij:tab-bar" {
+                        theme "dark"
+                    }
+                }
+            }
+        }
+    "#,
+        "layout_from_plugin".into(),
+        None,
+        None,
+    )
+    .unwrap();
+    assert!(reject_secret_references_in_layout(&layout_without_secret).is_ok());
+}
+
+#[test]
+fn unknown_secret_source_fails() {
+    let configuration = configuration_with("api_token", "secret:vault:token");
+    assert!(resolve_plugin_secrets(&configuration).is_err());
+}
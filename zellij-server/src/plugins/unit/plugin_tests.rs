@@ -1,19 +1,31 @@
-use super::plugin_thread_main;
+use super::plugin_clock::{ManualClock, PluginClock, SystemClock};
+use super::plugin_fuel::metering_middleware;
+use super::watch_filesystem::DEBOUNCE_DURATION_MS;
+use super::{plugin_thread_main, PluginFrame};
+use super::{PaneOutputBuffer, MAX_BUFFERED_PANE_OUTPUT};
 use crate::screen::ScreenInstruction;
-use crate::{channels::SenderWithContext, thread_bus::Bus, ServerInstruction};
+use crate::session_layout_metadata::{PaneLayoutMetadata, SessionLayoutMetadata};
+use crate::ui::overlay::Overlayable;
+use crate::{channels::SenderWithContext, thread_bus::Bus, ClientId, ServerInstruction};
 use insta::assert_snapshot;
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashMap};
 use std::path::PathBuf;
 use tempfile::tempdir;
-use wasmer::Store;
-use zellij_utils::data::{Event, Key, PermissionStatus, PermissionType, PluginCapabilities};
+use wasmer::{CompilerConfig, Store};
+use zellij_utils::data::{
+    CharOrArrow, Event, InputMode, Key, ModeInfo, PaneId, PermissionStatus, PermissionType,
+    PluginCapabilities, SessionActivity, SessionActivityKind, TabInfo,
+};
 use zellij_utils::errors::ErrorContext;
+use zellij_utils::input::actions::Action;
+use zellij_utils::input::command::TerminalAction;
 use zellij_utils::input::layout::{Layout, PluginUserConfiguration, RunPlugin, RunPluginLocation};
 use zellij_utils::input::permission::PermissionCache;
 use zellij_utils::input::plugins::PluginsConfig;
 use zellij_utils::ipc::ClientAttributes;
 use zellij_utils::lazy_static::lazy_static;
-use zellij_utils::pane_size::Size;
+use zellij_utils::pane_size::{PaneGeom, Size};
+use zellij_utils::session_serialization::GlobalLayoutManifest;
 
 use crate::background_jobs::BackgroundJob;
 use crate::pty_writer::PtyWriteInstruction;
@@ -233,12 +245,93 @@ macro_rules! log_actions_in_thread_naked_variant {
     };
 }
 
+/// Stands in for the screen thread popping the frames plugins queue for rendering, applying the
+/// changed lines of each frame to the previous one so that tests receive whole frames as
+/// `ScreenInstruction::PluginBytes`
+fn pop_queued_plugin_renders(
+    screen_receiver: Receiver<(ScreenInstruction, ErrorContext)>,
+) -> Receiver<(ScreenInstruction, ErrorContext)> {
+    let (sender, receiver) = channels::unbounded();
+    std::thread::Builder::new()
+        .name("render queue thread".to_string())
+        .spawn(move || {
+            let mut frame_lines: HashMap<(u32, ClientId), Vec<Vec<u8>>> = HashMap::new();
+            while let Ok((instruction, err_ctx)) = screen_receiver.recv() {
+                let instruction = match instruction {
+                    ScreenInstruction::PluginRenderQueued(render_queue, frame_count) => {
+                        let plugin_bytes = render_queue
+                            .pop_round_robin(frame_count)
+                            .into_iter()
+                            .map(|(plugin_id, client_id, frame)| {
+                                let lines = frame_lines.entry((plugin_id, client_id)).or_default();
+                                match frame {
+                                    PluginFrame::Full { bytes, .. } => {
+                                        *lines = bytes
+                                            .split(|byte| *byte == b'\n')
+                                            .map(|line| line.to_vec())
+                                            .collect();
+                                    },
+                                    PluginFrame::Changed {
+                                        lines: changed_lines,
+                                        ..
+                                    } => {
+                                        for changed_line in changed_lines {
+                                            lines[changed_line.row] = changed_line.bytes;
+                                        }
+                                    },
+                                }
+                                (plugin_id, client_id, lines.join(&b'\n'))
+                            })
+                            .collect();
+                        ScreenInstruction::PluginBytes(plugin_bytes)
+                    },
+                    instruction => instruction,
+                };
+                if sender.send((instruction, err_ctx)).is_err() {
+                    break;
+                }
+            }
+        })
+        .unwrap();
+    receiver
+}
+
+/// Plugins are compiled the way the server compiles them, so that they run out of fuel when stuck
+fn metered_store() -> Store {
+    let mut compiler = wasmer::Singlepass::default();
+    compiler.push_middleware(metering_middleware());
+    Store::new(compiler)
+}
+
 fn create_plugin_thread(
     zellij_cwd: Option<PathBuf>,
 ) -> (
     SenderWithContext<PluginInstruction>,
     Receiver<(ScreenInstruction, ErrorContext)>,
     Box<dyn FnOnce()>,
+) {
+    create_plugin_thread_with_clock(zellij_cwd, Arc::new(SystemClock))
+}
+
+fn create_plugin_thread_with_clock(
+    zellij_cwd: Option<PathBuf>,
+    clock: Arc<dyn PluginClock>,
+) -> (
+    SenderWithContext<PluginInstruction>,
+    Receiver<(ScreenInstruction, ErrorContext)>,
+    Box<dyn FnOnce()>,
+) {
+    create_plugin_thread_with_clock_and_paste_command(zellij_cwd, clock, None)
+}
+
+fn create_plugin_thread_with_clock_and_paste_command(
+    zellij_cwd: Option<PathBuf>,
+    clock: Arc<dyn PluginClock>,
+    paste_command: Option<String>,
+) -> (
+    SenderWithContext<PluginInstruction>,
+    Receiver<(ScreenInstruction, ErrorContext)>,
+    Box<dyn FnOnce()>,
 ) {
     let zellij_cwd = zellij_cwd.unwrap_or_else(|| PathBuf::from("."));
     let (to_server, _server_receiver): ChannelWithContext<ServerInstruction> =
@@ -246,6 +339,7 @@ fn create_plugin_thread(
     let to_server = SenderWithContext::new(to_server);
 
     let (to_screen, screen_receiver): ChannelWithContext<ScreenInstruction> = channels::unbounded();
+    let screen_receiver = pop_queued_plugin_renders(screen_receiver);
     let to_screen = SenderWithContext::new(to_screen);
 
     let (to_plugin, plugin_receiver): ChannelWithContext<PluginInstruction> = channels::unbounded();
@@ -272,7 +366,7 @@ fn create_plugin_thread(
         None,
     )
     .should_silently_fail();
-    let store = Store::new(wasmer::Singlepass::default());
+    let store = metered_store();
     let data_dir = PathBuf::from(tempdir().unwrap().path());
     let default_shell = PathBuf::from(".");
     let plugin_capabilities = PluginCapabilities::default();
@@ -293,6 +387,9 @@ fn create_plugin_thread(
                 plugin_capabilities,
                 client_attributes,
                 default_shell_action,
+                clock,
+                None,
+                paste_command,
             )
             .expect("TEST")
         })
@@ -324,6 +421,7 @@ fn create_plugin_thread_with_server_receiver(
     let to_server = SenderWithContext::new(to_server);
 
     let (to_screen, screen_receiver): ChannelWithContext<ScreenInstruction> = channels::unbounded();
+    let screen_receiver = pop_queued_plugin_renders(screen_receiver);
     let to_screen = SenderWithContext::new(to_screen);
 
     let (to_plugin, plugin_receiver): ChannelWithContext<PluginInstruction> = channels::unbounded();
@@ -350,12 +448,13 @@ fn create_plugin_thread_with_server_receiver(
         None,
     )
     .should_silently_fail();
-    let store = Store::new(wasmer::Singlepass::default());
+    let store = metered_store();
     let data_dir = PathBuf::from(tempdir().unwrap().path());
     let default_shell = PathBuf::from(".");
     let plugin_capabilities = PluginCapabilities::default();
     let client_attributes = ClientAttributes::default();
     let default_shell_action = None; // TODO: change me
+    let clock = Arc::new(SystemClock);
     let plugin_thread = std::thread::Builder::new()
         .name("plugin_thread".to_string())
         .spawn(move || {
@@ -371,6 +470,9 @@ fn create_plugin_thread_with_server_receiver(
                 plugin_capabilities,
                 client_attributes,
                 default_shell_action,
+                clock,
+                None,
+                None,
             )
             .expect("TEST")
         })
@@ -408,6 +510,7 @@ fn create_plugin_thread_with_pty_receiver(
     let to_server = SenderWithContext::new(to_server);
 
     let (to_screen, screen_receiver): ChannelWithContext<ScreenInstruction> = channels::unbounded();
+    let screen_receiver = pop_queued_plugin_renders(screen_receiver);
     let to_screen = SenderWithContext::new(to_screen);
 
     let (to_plugin, plugin_receiver): ChannelWithContext<PluginInstruction> = channels::unbounded();
@@ -434,12 +537,13 @@ fn create_plugin_thread_with_pty_receiver(
         None,
     )
     .should_silently_fail();
-    let store = Store::new(wasmer::Singlepass::default());
+    let store = metered_store();
     let data_dir = PathBuf::from(tempdir().unwrap().path());
     let default_shell = PathBuf::from(".");
     let plugin_capabilities = PluginCapabilities::default();
     let client_attributes = ClientAttributes::default();
     let default_shell_action = None; // TODO: change me
+    let clock = Arc::new(SystemClock);
     let plugin_thread = std::thread::Builder::new()
         .name("plugin_thread".to_string())
         .spawn(move || {
@@ -455,6 +559,9 @@ fn create_plugin_thread_with_pty_receiver(
                 plugin_capabilities,
                 client_attributes,
                 default_shell_action,
+                clock,
+                None,
+                None,
             )
             .expect("TEST")
         })
@@ -487,6 +594,7 @@ fn create_plugin_thread_with_background_jobs_receiver(
     let to_server = SenderWithContext::new(to_server);
 
     let (to_screen, screen_receiver): ChannelWithContext<ScreenInstruction> = channels::unbounded();
+    let screen_receiver = pop_queued_plugin_renders(screen_receiver);
     let to_screen = SenderWithContext::new(to_screen);
 
     let (to_plugin, plugin_receiver): ChannelWithContext<PluginInstruction> = channels::unbounded();
@@ -513,12 +621,13 @@ fn create_plugin_thread_with_background_jobs_receiver(
         None,
     )
     .should_silently_fail();
-    let store = Store::new(wasmer::Singlepass::default());
+    let store = metered_store();
     let data_dir = PathBuf::from(tempdir().unwrap().path());
     let default_shell = PathBuf::from(".");
     let plugin_capabilities = PluginCapabilities::default();
     let client_attributes = ClientAttributes::default();
     let default_shell_action = None; // TODO: change me
+    let clock = Arc::new(SystemClock);
     let plugin_thread = std::thread::Builder::new()
         .name("plugin_thread".to_string())
         .spawn(move || {
@@ -534,6 +643,9 @@ fn create_plugin_thread_with_background_jobs_receiver(
                 plugin_capabilities,
                 client_attributes,
                 default_shell_action,
+                clock,
+                None,
+                None,
             )
             .expect("TEST")
         })
@@ -697,7 +809,7 @@ pub fn plugin_workers() {
     let _ = plugin_thread_sender.send(PluginInstruction::Update(vec![(
         None,
         Some(client_id),
-        Event::SystemClipboardFailure,
+        Event::SystemClipboardFailure(0),
     )])); // will be cached and sent to the plugin once it's loaded
     screen_thread.join().unwrap(); // this might take a while if the cache is cold
     teardown();
@@ -775,12 +887,12 @@ pub fn plugin_workers_persist_state() {
     let _ = plugin_thread_sender.send(PluginInstruction::Update(vec![(
         None,
         Some(client_id),
-        Event::SystemClipboardFailure,
+        Event::SystemClipboardFailure(0),
     )]));
     let _ = plugin_thread_sender.send(PluginInstruction::Update(vec![(
         None,
         Some(client_id),
-        Event::SystemClipboardFailure,
+        Event::SystemClipboardFailure(0),
     )]));
     screen_thread.join().unwrap(); // this might take a while if the cache is cold
     teardown();
@@ -808,8 +920,9 @@ pub fn can_subscribe_to_hd_events() {
     let temp_folder = tempdir().unwrap(); // placed explicitly in the test scope because its
                                           // destructor removes the directory
     let plugin_host_folder = PathBuf::from(temp_folder.path());
+    let clock = Arc::new(ManualClock::new());
     let (plugin_thread_sender, screen_receiver, teardown) =
-        create_plugin_thread(Some(plugin_host_folder));
+        create_plugin_thread_with_clock(Some(plugin_host_folder), clock.clone());
     let plugin_should_float = Some(false);
     let plugin_title = Some("test_plugin".to_owned());
     let run_plugin = RunPlugin {
@@ -843,31 +956,53 @@ pub fn can_subscribe_to_hd_events() {
         size,
         None,
     ));
-    // extra long time because we only start the fs watcher on plugin load
-    std::thread::sleep(std::time::Duration::from_millis(5000));
+    // the fs watcher is started when the plugin subscribes to FileSystem events while loading
+    let (barrier_sender, barrier_receiver) = channels::bounded(1);
+    let _ = plugin_thread_sender.send(PluginInstruction::Barrier(barrier_sender));
+    barrier_receiver.recv().unwrap();
+    // the plugin only subscribes to changes of "*.txt" files - the txt file is created first so
+    // that it is always part of the first batch of debounced events
     std::fs::OpenOptions::new()
         .create(true)
         .write(true)
-        .open(PathBuf::from(temp_folder.path()).join("test1"))
+        .open(PathBuf::from(temp_folder.path()).join("test1.txt"))
         .unwrap();
-    screen_thread.join().unwrap(); // this might take a while if the cache is cold
+    std::fs::OpenOptions::new()
+        .create(true)
+        .write(true)
+        .open(PathBuf::from(temp_folder.path()).join("test1.bin"))
+        .unwrap();
+    // the changes are sent to the plugin once the debounce duration passes on the plugin clock
+    clock.wait_for_scheduled_callbacks(1);
+    clock.advance(std::time::Duration::from_millis(DEBOUNCE_DURATION_MS));
+    screen_thread.join().unwrap();
     teardown();
-    let plugin_bytes_event = received_screen_instructions
+    let rendered_plugin_bytes: Vec<String> = received_screen_instructions
         .lock()
         .unwrap()
         .iter()
-        .find_map(|i| {
+        .filter_map(|i| {
             if let ScreenInstruction::PluginBytes(plugin_bytes) = i {
-                for (plugin_id, client_id, plugin_bytes) in plugin_bytes {
-                    let plugin_bytes = String::from_utf8_lossy(plugin_bytes).to_string();
-                    if plugin_bytes.contains("FileSystemCreate") {
-                        return Some((*plugin_id, *client_id, plugin_bytes));
-                    }
-                }
+                Some(plugin_bytes.clone())
+            } else {
+                None
             }
-            None
-        });
+        })
+        .flatten()
+        .map(|(_plugin_id, _client_id, plugin_bytes)| {
+            String::from_utf8_lossy(&plugin_bytes).to_string()
+        })
+        .collect();
+    let plugin_bytes_event = rendered_plugin_bytes.iter().find(|plugin_bytes| {
+        plugin_bytes.contains("FileSystemCreate") && plugin_bytes.contains("test1.txt")
+    });
     assert!(plugin_bytes_event.is_some());
+    assert!(
+        !rendered_plugin_bytes
+            .iter()
+            .any(|plugin_bytes| plugin_bytes.contains("test1.bin")),
+        "changes to paths not matching the filter are not sent to the plugin"
+    );
 }
 
 #[test]
@@ -5103,6 +5238,162 @@ pub fn send_configuration_to_plugins() {
     assert_snapshot!(format!("{:#?}", go_to_tab_event));
 }
 
+#[test]
+#[ignore]
+pub fn configuration_is_available_on_first_render() {
+    let temp_folder = tempdir().unwrap(); // placed explicitly in the test scope because its
+                                          // destructor removes the directory
+    let plugin_host_folder = PathBuf::from(temp_folder.path());
+    let cache_path = plugin_host_folder.join("permissions_test.kdl");
+    let (plugin_thread_sender, screen_receiver, teardown) =
+        create_plugin_thread(Some(plugin_host_folder));
+    let plugin_should_float = Some(false);
+    let plugin_title = Some("test_plugin".to_owned());
+    let mut configuration = BTreeMap::new();
+    configuration.insert(
+        "render_configured_value".to_owned(),
+        "fake_configured_value".to_owned(),
+    );
+    let run_plugin = RunPlugin {
+        _allow_exec_host_cmd: false,
+        location: RunPluginLocation::File(PathBuf::from(&*PLUGIN_FIXTURE)),
+        configuration: PluginUserConfiguration::new(configuration),
+    };
+    let tab_index = 1;
+    let client_id = 1;
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let received_screen_instructions = Arc::new(Mutex::new(vec![]));
+    let screen_thread = grant_permissions_and_log_actions_in_thread!(
+        received_screen_instructions,
+        ScreenInstruction::PluginBytes,
+        screen_receiver,
+        1,
+        &PermissionType::ChangeApplicationState,
+        cache_path,
+        plugin_thread_sender,
+        client_id
+    );
+
+    let _ = plugin_thread_sender.send(PluginInstruction::AddClient(client_id));
+    let _ = plugin_thread_sender.send(PluginInstruction::Load(
+        plugin_should_float,
+        false,
+        plugin_title,
+        run_plugin,
+        tab_index,
+        None,
+        client_id,
+        size,
+        None,
+    ));
+    screen_thread.join().unwrap(); // this might take a while if the cache is cold
+    teardown();
+    // the configuration is passed to the plugin's load method, so it is already there the first
+    // time the plugin renders
+    let first_render = received_screen_instructions
+        .lock()
+        .unwrap()
+        .iter()
+        .find_map(|i| {
+            if let ScreenInstruction::PluginBytes(plugin_bytes) = i {
+                plugin_bytes
+                    .first()
+                    .map(|(_, _, plugin_bytes)| String::from_utf8_lossy(plugin_bytes).to_string())
+            } else {
+                None
+            }
+        });
+    assert!(
+        first_render
+            .map(|first_render| first_render.contains("Configured value: fake_configured_value"))
+            .unwrap_or(false),
+        "configured value rendered on first render"
+    );
+}
+
+#[test]
+#[ignore]
+pub fn send_configuration_with_resolved_secrets_to_plugins() {
+    let temp_folder = tempdir().unwrap(); // placed explicitly in the test scope because its
+                                          // destructor removes the directory
+    let plugin_host_folder = PathBuf::from(temp_folder.path());
+    let cache_path = plugin_host_folder.join("permissions_test.kdl");
+    let (plugin_thread_sender, screen_receiver, teardown) =
+        create_plugin_thread(Some(plugin_host_folder));
+    let plugin_should_float = Some(false);
+    let plugin_title = Some("test_plugin".to_owned());
+    let secret = "plugin-secret-3f9a1c";
+    std::env::set_var("ZELLIJ_TEST_FIXTURE_PLUGIN_SECRET", secret);
+    let mut configuration = BTreeMap::new();
+    configuration.insert(
+        "api_token".to_owned(),
+        "secret:env:ZELLIJ_TEST_FIXTURE_PLUGIN_SECRET".to_owned(),
+    );
+    let run_plugin = RunPlugin {
+        _allow_exec_host_cmd: false,
+        location: RunPluginLocation::File(PathBuf::from(&*PLUGIN_FIXTURE)),
+        configuration: PluginUserConfiguration::new(configuration),
+    };
+    let tab_index = 1;
+    let client_id = 1;
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let received_screen_instructions = Arc::new(Mutex::new(vec![]));
+    let screen_thread = grant_permissions_and_log_actions_in_thread!(
+        received_screen_instructions,
+        ScreenInstruction::GoToTabName,
+        screen_receiver,
+        1,
+        &PermissionType::ChangeApplicationState,
+        cache_path,
+        plugin_thread_sender,
+        client_id
+    );
+
+    let _ = plugin_thread_sender.send(PluginInstruction::AddClient(client_id));
+    let _ = plugin_thread_sender.send(PluginInstruction::Load(
+        plugin_should_float,
+        false,
+        plugin_title,
+        run_plugin,
+        tab_index,
+        None,
+        client_id,
+        size,
+        None,
+    ));
+    std::thread::sleep(std::time::Duration::from_millis(500));
+    let _ = plugin_thread_sender.send(PluginInstruction::Update(vec![(
+        None,
+        Some(client_id),
+        Event::Key(Key::Ctrl('z')), // this triggers the enent in the fixture plugin
+    )]));
+    screen_thread.join().unwrap(); // this might take a while if the cache is cold
+    teardown();
+    // the fixture plugin renames the tab to its stringified (Debug) configuration
+    let configuration_received_by_plugin = received_screen_instructions
+        .lock()
+        .unwrap()
+        .iter()
+        .find_map(|i| {
+            if let ScreenInstruction::GoToTabName(name, ..) = i {
+                Some(name.clone())
+            } else {
+                None
+            }
+        })
+        .unwrap();
+    assert!(
+        configuration_received_by_plugin.contains(secret),
+        "plugin received the resolved secret"
+    );
+}
+
 #[test]
 #[ignore]
 pub fn request_plugin_permissions() {
@@ -5564,3 +5855,2479 @@ pub fn web_request_plugin_command() {
         .clone();
     assert_snapshot!(format!("{:#?}", new_tab_event));
 }
+
+#[test]
+#[ignore]
+pub fn message_to_plugin_launches_it_in_the_background() {
+    let temp_folder = tempdir().unwrap(); // placed explicitly in the test scope because its
+                                          // destructor removes the directory
+    let (plugin_thread_sender, screen_receiver, teardown) = create_plugin_thread(None);
+    let plugin_host_folder = PathBuf::from(temp_folder.path());
+    let cache_path = plugin_host_folder.join("permissions_test.kdl");
+    let run_plugin = RunPlugin {
+        _allow_exec_host_cmd: false,
+        location: RunPluginLocation::File(PathBuf::from(&*PLUGIN_FIXTURE)),
+        configuration: Default::default(),
+    };
+    let client_id = 1;
+    let received_screen_instructions = Arc::new(Mutex::new(vec![]));
+    let screen_thread = grant_permissions_and_log_actions_in_thread!(
+        received_screen_instructions,
+        ScreenInstruction::PluginBytes,
+        screen_receiver,
+        3,
+        &PermissionType::ChangeApplicationState,
+        cache_path,
+        plugin_thread_sender,
+        client_id
+    );
+
+    let _ = plugin_thread_sender.send(PluginInstruction::AddClient(client_id));
+    // the plugin is not loaded, so it is launched and the message is cached until it is
+    let _ = plugin_thread_sender.send(PluginInstruction::MessageToPlugin(
+        run_plugin,
+        client_id,
+        "pong".to_owned(), // the fixture plugin renders the payload of this message
+        Some("literal payload".to_owned()),
+    ));
+    std::thread::sleep(std::time::Duration::from_millis(500));
+    // background plugins have no size, so we give it one to have it rendered
+    let _ = plugin_thread_sender.send(PluginInstruction::Resize(0, 121, 20));
+    screen_thread.join().unwrap(); // this might take a while if the cache is cold
+    teardown();
+    let received_screen_instructions = received_screen_instructions.lock().unwrap();
+    let plugin_was_opened_in_a_pane = received_screen_instructions
+        .iter()
+        .any(|i| matches!(i, ScreenInstruction::AddPlugin(..)));
+    let rendered_payload = received_screen_instructions.iter().find_map(|i| {
+        if let ScreenInstruction::PluginBytes(plugin_bytes) = i {
+            for (_plugin_id, _client_id, plugin_bytes) in plugin_bytes {
+                let plugin_bytes = String::from_utf8_lossy(plugin_bytes).to_string();
+                if plugin_bytes.contains("Payload from worker") {
+                    return Some(plugin_bytes);
+                }
+            }
+        }
+        None
+    });
+    assert!(
+        !plugin_was_opened_in_a_pane,
+        "Plugin was launched in the background"
+    );
+    assert!(
+        rendered_payload
+            .map(|p| p.contains("\"literal payload\""))
+            .unwrap_or(false),
+        "Plugin received the named message"
+    );
+}
+
+#[test]
+#[ignore]
+pub fn background_plugin_receives_events_without_being_rendered() {
+    let temp_folder = tempdir().unwrap(); // placed explicitly in the test scope because its
+                                          // destructor removes the directory
+    let plugin_host_folder = PathBuf::from(temp_folder.path());
+    let cache_path = plugin_host_folder.join("permissions_test.kdl");
+    let (plugin_thread_sender, screen_receiver, teardown) =
+        create_plugin_thread(Some(plugin_host_folder));
+    let mut configuration = BTreeMap::new();
+    configuration.insert("report_file_system_events".to_owned(), "true".to_owned());
+    let run_plugin = RunPlugin {
+        _allow_exec_host_cmd: false,
+        location: RunPluginLocation::File(PathBuf::from(&*PLUGIN_FIXTURE)),
+        configuration: PluginUserConfiguration::new(configuration),
+    };
+    let client_id = 1;
+    let received_screen_instructions = Arc::new(Mutex::new(vec![]));
+    let screen_thread = grant_permissions_and_log_actions_in_thread!(
+        received_screen_instructions,
+        ScreenInstruction::GoToTabName,
+        screen_receiver,
+        1,
+        &PermissionType::ChangeApplicationState,
+        cache_path,
+        plugin_thread_sender,
+        client_id
+    );
+
+    let _ = plugin_thread_sender.send(PluginInstruction::AddClient(client_id));
+    let _ = plugin_thread_sender.send(PluginInstruction::LoadBackgroundPlugin(
+        run_plugin, client_id,
+    ));
+    // extra long time because we only start the fs watcher on plugin load
+    std::thread::sleep(std::time::Duration::from_millis(5000));
+    std::fs::OpenOptions::new()
+        .create(true)
+        .write(true)
+        .open(PathBuf::from(temp_folder.path()).join("test1.txt"))
+        .unwrap();
+    screen_thread.join().unwrap(); // this might take a while if the cache is cold
+    teardown();
+    let received_screen_instructions = received_screen_instructions.lock().unwrap();
+    // the fixture plugin renames the tab to the paths of the created files
+    let reported_file_system_event = received_screen_instructions.iter().find_map(|i| {
+        if let ScreenInstruction::GoToTabName(name, ..) = i {
+            Some(name.clone())
+        } else {
+            None
+        }
+    });
+    let plugin_was_rendered = received_screen_instructions
+        .iter()
+        .any(|i| matches!(i, ScreenInstruction::PluginBytes(..)));
+    let plugin_was_opened_in_a_pane = received_screen_instructions
+        .iter()
+        .any(|i| matches!(i, ScreenInstruction::AddPlugin(..)));
+    assert!(
+        reported_file_system_event
+            .map(|paths| paths.contains("test1.txt"))
+            .unwrap_or(false),
+        "background plugin received the file system event"
+    );
+    assert!(!plugin_was_rendered, "background plugin was not rendered");
+    assert!(
+        !plugin_was_opened_in_a_pane,
+        "background plugin has no pane"
+    );
+}
+
+#[test]
+#[ignore]
+pub fn copy_to_clipboard_plugin_command() {
+    let temp_folder = tempdir().unwrap(); // placed explicitly in the test scope because its
+                                          // destructor removes the directory
+    let plugin_host_folder = PathBuf::from(temp_folder.path());
+    let cache_path = plugin_host_folder.join("permissions_test.kdl");
+    let (plugin_thread_sender, screen_receiver, teardown) =
+        create_plugin_thread(Some(plugin_host_folder));
+    let plugin_should_float = Some(false);
+    let plugin_title = Some("test_plugin".to_owned());
+    let run_plugin = RunPlugin {
+        _allow_exec_host_cmd: false,
+        location: RunPluginLocation::File(PathBuf::from(&*PLUGIN_FIXTURE)),
+        configuration: Default::default(),
+    };
+    let tab_index = 1;
+    let client_id = 1;
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let received_screen_instructions = Arc::new(Mutex::new(vec![]));
+    let screen_thread = grant_permissions_and_log_actions_in_thread!(
+        received_screen_instructions,
+        ScreenInstruction::CopyTextToClipboard,
+        screen_receiver,
+        1,
+        &PermissionType::WriteToClipboard,
+        cache_path,
+        plugin_thread_sender,
+        client_id
+    );
+
+    let _ = plugin_thread_sender.send(PluginInstruction::AddClient(client_id));
+    let _ = plugin_thread_sender.send(PluginInstruction::Load(
+        plugin_should_float,
+        false,
+        plugin_title,
+        run_plugin,
+        tab_index,
+        None,
+        client_id,
+        size,
+        None,
+    ));
+    std::thread::sleep(std::time::Duration::from_millis(500));
+    let _ = plugin_thread_sender.send(PluginInstruction::Update(vec![(
+        None,
+        Some(client_id),
+        Event::Key(Key::Ctrl('5')), // this triggers the event in the fixture plugin
+    )]));
+    screen_thread.join().unwrap(); // this might take a while if the cache is cold
+    teardown();
+    let copied_text = received_screen_instructions
+        .lock()
+        .unwrap()
+        .iter()
+        .find_map(|i| {
+            if let ScreenInstruction::CopyTextToClipboard(text, copying_client_id) = i {
+                Some((text.clone(), *copying_client_id))
+            } else {
+                None
+            }
+        });
+    assert_eq!(
+        copied_text,
+        Some(("text copied by a plugin".to_owned(), client_id)),
+        "plugin asked to copy its text on behalf of its client"
+    );
+}
+
+#[test]
+#[ignore]
+pub fn read_clipboard_plugin_command() {
+    let temp_folder = tempdir().unwrap(); // placed explicitly in the test scope because its
+                                          // destructor removes the directory
+    let plugin_host_folder = PathBuf::from(temp_folder.path());
+    let cache_path = plugin_host_folder.join("permissions_test.kdl");
+    // this stands in for eg. "xclip -selection clipboard -out"
+    let paste_command = Some("echo -n text in the clipboard".to_owned());
+    let (plugin_thread_sender, screen_receiver, teardown) =
+        create_plugin_thread_with_clock_and_paste_command(
+            Some(plugin_host_folder),
+            Arc::new(SystemClock),
+            paste_command,
+        );
+    let plugin_should_float = Some(false);
+    let plugin_title = Some("test_plugin".to_owned());
+    let run_plugin = RunPlugin {
+        _allow_exec_host_cmd: false,
+        location: RunPluginLocation::File(PathBuf::from(&*PLUGIN_FIXTURE)),
+        configuration: Default::default(),
+    };
+    let tab_index = 1;
+    let client_id = 1;
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let received_screen_instructions = Arc::new(Mutex::new(vec![]));
+    let screen_thread = grant_permissions_and_log_actions_in_thread!(
+        received_screen_instructions,
+        ScreenInstruction::CopyTextToClipboard,
+        screen_receiver,
+        1,
+        &PermissionType::ReadClipboard,
+        cache_path,
+        plugin_thread_sender,
+        client_id
+    );
+
+    let _ = plugin_thread_sender.send(PluginInstruction::AddClient(client_id));
+    let _ = plugin_thread_sender.send(PluginInstruction::Load(
+        plugin_should_float,
+        false,
+        plugin_title,
+        run_plugin,
+        tab_index,
+        None,
+        client_id,
+        size,
+        None,
+    ));
+    std::thread::sleep(std::time::Duration::from_millis(500));
+    let _ = plugin_thread_sender.send(PluginInstruction::Update(vec![(
+        None,
+        Some(client_id),
+        Event::Key(Key::Alt(CharOrArrow::Char('v'))), // the fixture copies what it reads
+    )]));
+    screen_thread.join().unwrap(); // this might take a while if the cache is cold
+    teardown();
+    let copied_text = received_screen_instructions
+        .lock()
+        .unwrap()
+        .iter()
+        .find_map(|i| {
+            if let ScreenInstruction::CopyTextToClipboard(text, _) = i {
+                Some(text.clone())
+            } else {
+                None
+            }
+        });
+    assert_eq!(
+        copied_text,
+        Some("read back: text in the clipboard".to_owned()),
+        "plugin read what the paste command printed"
+    );
+}
+
+#[test]
+#[ignore]
+pub fn read_clipboard_plugin_command_reads_nothing_without_a_paste_command() {
+    let temp_folder = tempdir().unwrap(); // placed explicitly in the test scope because its
+                                          // destructor removes the directory
+    let plugin_host_folder = PathBuf::from(temp_folder.path());
+    let cache_path = plugin_host_folder.join("permissions_test.kdl");
+    let (plugin_thread_sender, screen_receiver, teardown) =
+        create_plugin_thread(Some(plugin_host_folder));
+    let plugin_should_float = Some(false);
+    let plugin_title = Some("test_plugin".to_owned());
+    let run_plugin = RunPlugin {
+        _allow_exec_host_cmd: false,
+        location: RunPluginLocation::File(PathBuf::from(&*PLUGIN_FIXTURE)),
+        configuration: Default::default(),
+    };
+    let tab_index = 1;
+    let client_id = 1;
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let received_screen_instructions = Arc::new(Mutex::new(vec![]));
+    let screen_thread = grant_permissions_and_log_actions_in_thread!(
+        received_screen_instructions,
+        ScreenInstruction::CopyTextToClipboard,
+        screen_receiver,
+        2,
+        &PermissionType::ReadClipboard,
+        cache_path,
+        plugin_thread_sender,
+        client_id
+    );
+
+    let _ = plugin_thread_sender.send(PluginInstruction::AddClient(client_id));
+    let _ = plugin_thread_sender.send(PluginInstruction::Load(
+        plugin_should_float,
+        false,
+        plugin_title,
+        run_plugin,
+        tab_index,
+        None,
+        client_id,
+        size,
+        None,
+    ));
+    std::thread::sleep(std::time::Duration::from_millis(500));
+    // what the plugin copies is not what it reads: only a real read of the clipboard is
+    let _ = plugin_thread_sender.send(PluginInstruction::Update(vec![(
+        None,
+        Some(client_id),
+        Event::Key(Key::Ctrl('5')), // this triggers a copy_to_clipboard call
+    )]));
+    let _ = plugin_thread_sender.send(PluginInstruction::Update(vec![(
+        None,
+        Some(client_id),
+        Event::Key(Key::Alt(CharOrArrow::Char('v'))), // the fixture copies what it reads
+    )]));
+    screen_thread.join().unwrap(); // this might take a while if the cache is cold
+    teardown();
+    let copied_text: Vec<String> = received_screen_instructions
+        .lock()
+        .unwrap()
+        .iter()
+        .filter_map(|i| {
+            if let ScreenInstruction::CopyTextToClipboard(text, _) = i {
+                Some(text.clone())
+            } else {
+                None
+            }
+        })
+        .collect();
+    assert_eq!(
+        copied_text.last(),
+        Some(&"read nothing".to_owned()),
+        "plugin could not read the clipboard, not even what it copied"
+    );
+}
+
+#[test]
+#[ignore]
+pub fn focus_self_plugin_command() {
+    let temp_folder = tempdir().unwrap(); // placed explicitly in the test scope because its
+                                          // destructor removes the directory
+    let plugin_host_folder = PathBuf::from(temp_folder.path());
+    let cache_path = plugin_host_folder.join("permissions_test.kdl");
+    let (plugin_thread_sender, screen_receiver, teardown) =
+        create_plugin_thread(Some(plugin_host_folder));
+    let plugin_should_float = Some(false);
+    let plugin_title = Some("test_plugin".to_owned());
+    let run_plugin = RunPlugin {
+        _allow_exec_host_cmd: false,
+        location: RunPluginLocation::File(PathBuf::from(&*PLUGIN_FIXTURE)),
+        configuration: Default::default(),
+    };
+    let tab_index = 1;
+    let client_id = 1;
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let received_screen_instructions = Arc::new(Mutex::new(vec![]));
+    let screen_thread = grant_permissions_and_log_actions_in_thread!(
+        received_screen_instructions,
+        ScreenInstruction::CopyTextToClipboard,
+        screen_receiver,
+        1,
+        &PermissionType::FocusSelf,
+        cache_path,
+        plugin_thread_sender,
+        client_id
+    );
+
+    let _ = plugin_thread_sender.send(PluginInstruction::AddClient(client_id));
+    let _ = plugin_thread_sender.send(PluginInstruction::Load(
+        plugin_should_float,
+        false,
+        plugin_title,
+        run_plugin,
+        tab_index,
+        None,
+        client_id,
+        size,
+        None,
+    ));
+    std::thread::sleep(std::time::Duration::from_millis(500));
+    // the second call comes right after the first one and should be rate-limited
+    for _ in 0..2 {
+        let _ = plugin_thread_sender.send(PluginInstruction::Update(vec![(
+            None,
+            Some(client_id),
+            Event::Key(Key::Alt(CharOrArrow::Char('f'))), // this triggers the focus_self call
+        )]));
+    }
+    // once this reaches the screen, we know both calls above were handled
+    let _ = plugin_thread_sender.send(PluginInstruction::Update(vec![(
+        None,
+        Some(client_id),
+        Event::Key(Key::Ctrl('5')), // this triggers a copy_to_clipboard call
+    )]));
+    screen_thread.join().unwrap(); // this might take a while if the cache is cold
+    teardown();
+    let focus_instructions: Vec<PaneId> = received_screen_instructions
+        .lock()
+        .unwrap()
+        .iter()
+        .filter_map(|i| {
+            if let ScreenInstruction::FocusPaneWithIdForAllClients(pane_id, _) = i {
+                Some(*pane_id)
+            } else {
+                None
+            }
+        })
+        .collect();
+    assert_eq!(
+        focus_instructions,
+        vec![PaneId::Plugin(0)],
+        "plugin focused its own pane once"
+    );
+}
+
+#[test]
+#[ignore]
+pub fn show_self_plugin_command_is_not_rate_limited() {
+    let temp_folder = tempdir().unwrap(); // placed explicitly in the test scope because its
+                                          // destructor removes the directory
+    let plugin_host_folder = PathBuf::from(temp_folder.path());
+    let cache_path = plugin_host_folder.join("permissions_test.kdl");
+    let (plugin_thread_sender, screen_receiver, teardown) =
+        create_plugin_thread(Some(plugin_host_folder));
+    let plugin_should_float = Some(false);
+    let plugin_title = Some("test_plugin".to_owned());
+    let run_plugin = RunPlugin {
+        _allow_exec_host_cmd: false,
+        location: RunPluginLocation::File(PathBuf::from(&*PLUGIN_FIXTURE)),
+        configuration: Default::default(),
+    };
+    let tab_index = 1;
+    let client_id = 1;
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let received_screen_instructions = Arc::new(Mutex::new(vec![]));
+    let screen_thread = grant_permissions_and_log_actions_in_thread!(
+        received_screen_instructions,
+        ScreenInstruction::CopyTextToClipboard,
+        screen_receiver,
+        1,
+        &PermissionType::FocusSelf,
+        cache_path,
+        plugin_thread_sender,
+        client_id
+    );
+
+    let _ = plugin_thread_sender.send(PluginInstruction::AddClient(client_id));
+    let _ = plugin_thread_sender.send(PluginInstruction::Load(
+        plugin_should_float,
+        false,
+        plugin_title,
+        run_plugin,
+        tab_index,
+        None,
+        client_id,
+        size,
+        None,
+    ));
+    std::thread::sleep(std::time::Duration::from_millis(500));
+    // the show_self calls come right after the focus_self call and should not be rate-limited
+    let _ = plugin_thread_sender.send(PluginInstruction::Update(vec![(
+        None,
+        Some(client_id),
+        Event::Key(Key::Alt(CharOrArrow::Char('f'))), // this triggers the focus_self call
+    )]));
+    for _ in 0..2 {
+        let _ = plugin_thread_sender.send(PluginInstruction::Update(vec![(
+            None,
+            Some(client_id),
+            Event::Key(Key::Ctrl('q')), // this triggers the show_self call
+        )]));
+    }
+    // once this reaches the screen, we know both calls above were handled
+    let _ = plugin_thread_sender.send(PluginInstruction::Update(vec![(
+        None,
+        Some(client_id),
+        Event::Key(Key::Ctrl('5')), // this triggers a copy_to_clipboard call
+    )]));
+    screen_thread.join().unwrap(); // this might take a while if the cache is cold
+    teardown();
+    let focus_instructions: Vec<PaneId> = received_screen_instructions
+        .lock()
+        .unwrap()
+        .iter()
+        .filter_map(|i| {
+            if let ScreenInstruction::FocusPaneWithId(pane_id, _, _) = i {
+                Some(*pane_id)
+            } else {
+                None
+            }
+        })
+        .collect();
+    assert_eq!(
+        focus_instructions,
+        vec![PaneId::Plugin(0), PaneId::Plugin(0)],
+        "plugin showed its own pane twice"
+    );
+}
+
+#[test]
+#[ignore]
+pub fn copy_to_clipboard_plugin_command_permission_denied() {
+    let temp_folder = tempdir().unwrap(); // placed explicitly in the test scope because its
+                                          // destructor removes the directory
+    let plugin_host_folder = PathBuf::from(temp_folder.path());
+    let cache_path = plugin_host_folder.join("permissions_test.kdl");
+    let (plugin_thread_sender, screen_receiver, teardown) =
+        create_plugin_thread(Some(plugin_host_folder));
+    let plugin_should_float = Some(false);
+    let plugin_title = Some("test_plugin".to_owned());
+    let run_plugin = RunPlugin {
+        _allow_exec_host_cmd: false,
+        location: RunPluginLocation::File(PathBuf::from(&*PLUGIN_FIXTURE)),
+        configuration: Default::default(),
+    };
+    let tab_index = 1;
+    let client_id = 1;
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let received_screen_instructions = Arc::new(Mutex::new(vec![]));
+    // here we create a fake screen thread that denies every permission request it gets and exits
+    // once the plugin renders the PermissionDenied event it was sent
+    let screen_thread = std::thread::Builder::new()
+        .name("fake_screen_thread".to_string())
+        .spawn({
+            let log = received_screen_instructions.clone();
+            let cache_path = cache_path.clone();
+            let plugin_thread_sender = plugin_thread_sender.clone();
+            move || loop {
+                let (event, _err_ctx) = screen_receiver
+                    .recv()
+                    .expect("failed to receive event on channel");
+                match event {
+                    ScreenInstruction::RequestPluginPermissions(_, plugin_permission) => {
+                        let _ =
+                            plugin_thread_sender.send(PluginInstruction::PermissionRequestResult(
+                                0,
+                                Some(client_id),
+                                plugin_permission.permissions,
+                                PermissionStatus::Denied,
+                                Some(cache_path.clone()),
+                            ));
+                    },
+                    ScreenInstruction::PluginBytes(ref plugin_bytes) => {
+                        let rendered_denial = plugin_bytes.iter().any(|(_, _, bytes)| {
+                            String::from_utf8_lossy(bytes)
+                                .contains("PermissionDenied(WriteToClipboard)")
+                        });
+                        log.lock().unwrap().push(event);
+                        if rendered_denial {
+                            break;
+                        }
+                    },
+                    ScreenInstruction::Exit => {
+                        break;
+                    },
+                    _ => {
+                        log.lock().unwrap().push(event);
+                    },
+                }
+            }
+        })
+        .unwrap();
+
+    let _ = plugin_thread_sender.send(PluginInstruction::AddClient(client_id));
+    let _ = plugin_thread_sender.send(PluginInstruction::Load(
+        plugin_should_float,
+        false,
+        plugin_title,
+        run_plugin,
+        tab_index,
+        None,
+        client_id,
+        size,
+        None,
+    ));
+    std::thread::sleep(std::time::Duration::from_millis(500));
+    let _ = plugin_thread_sender.send(PluginInstruction::Update(vec![(
+        None,
+        Some(client_id),
+        Event::Key(Key::Ctrl('5')), // this triggers the event in the fixture plugin
+    )]));
+    screen_thread.join().unwrap(); // this might take a while if the cache is cold
+    teardown();
+    let copied_text = received_screen_instructions
+        .lock()
+        .unwrap()
+        .iter()
+        .find_map(|i| {
+            if let ScreenInstruction::CopyTextToClipboard(text, _) = i {
+                Some(text.clone())
+            } else {
+                None
+            }
+        });
+    assert_eq!(
+        copied_text, None,
+        "text was not copied without the WriteToClipboard permission"
+    );
+}
+
+#[test]
+#[ignore]
+pub fn plugin_log_messages_are_sent_to_the_pane_sink() {
+    let temp_folder = tempdir().unwrap(); // placed explicitly in the test scope because its
+                                          // destructor removes the directory
+    let plugin_host_folder = PathBuf::from(temp_folder.path());
+    let cache_path = plugin_host_folder.join("permissions_test.kdl");
+    let (plugin_thread_sender, screen_receiver, teardown) =
+        create_plugin_thread(Some(plugin_host_folder));
+    let plugin_should_float = Some(false);
+    let plugin_title = Some("test_plugin".to_owned());
+    let mut configuration = BTreeMap::new();
+    configuration.insert("log_to".to_owned(), "pane".to_owned());
+    let run_plugin = RunPlugin {
+        _allow_exec_host_cmd: false,
+        location: RunPluginLocation::File(PathBuf::from(&*PLUGIN_FIXTURE)),
+        configuration: PluginUserConfiguration::new(configuration),
+    };
+    let tab_index = 1;
+    let client_id = 1;
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let received_screen_instructions = Arc::new(Mutex::new(vec![]));
+    let screen_thread = grant_permissions_and_log_actions_in_thread!(
+        received_screen_instructions,
+        ScreenInstruction::PluginLogLine,
+        screen_receiver,
+        1,
+        &PermissionType::ReadApplicationState,
+        cache_path,
+        plugin_thread_sender,
+        client_id
+    );
+
+    let _ = plugin_thread_sender.send(PluginInstruction::AddClient(client_id));
+    let _ = plugin_thread_sender.send(PluginInstruction::Load(
+        plugin_should_float,
+        false,
+        plugin_title,
+        run_plugin,
+        tab_index,
+        None,
+        client_id,
+        size,
+        None,
+    ));
+    std::thread::sleep(std::time::Duration::from_millis(500));
+    let _ = plugin_thread_sender.send(PluginInstruction::Update(vec![(
+        None,
+        Some(client_id),
+        Event::Key(Key::Ctrl('6')), // this triggers the event in the fixture plugin
+    )]));
+    screen_thread.join().unwrap(); // this might take a while if the cache is cold
+    teardown();
+    let logged_line = received_screen_instructions
+        .lock()
+        .unwrap()
+        .iter()
+        .find_map(|i| {
+            if let ScreenInstruction::PluginLogLine(_plugin_id, logging_client_id, log_line) = i {
+                Some((log_line.clone(), *logging_client_id))
+            } else {
+                None
+            }
+        });
+    assert_eq!(
+        logged_line,
+        Some(("message logged by a plugin".to_owned(), client_id)),
+        "plugin log message was sent to its pane"
+    );
+}
+
+#[test]
+#[ignore]
+pub fn get_session_layout_plugin_command() {
+    let temp_folder = tempdir().unwrap(); // placed explicitly in the test scope because its
+                                          // destructor removes the directory
+    let plugin_host_folder = PathBuf::from(temp_folder.path());
+    let cache_path = plugin_host_folder.join("permissions_test.kdl");
+    let (plugin_thread_sender, screen_receiver, teardown) =
+        create_plugin_thread(Some(plugin_host_folder));
+    let plugin_should_float = Some(false);
+    let plugin_title = Some("test_plugin".to_owned());
+    let run_plugin = RunPlugin {
+        _allow_exec_host_cmd: false,
+        location: RunPluginLocation::File(PathBuf::from(&*PLUGIN_FIXTURE)),
+        configuration: Default::default(),
+    };
+    let tab_index = 1;
+    let client_id = 1;
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let received_screen_instructions = Arc::new(Mutex::new(vec![]));
+    let screen_thread = grant_permissions_and_log_actions_in_thread!(
+        received_screen_instructions,
+        ScreenInstruction::PluginBytes,
+        screen_receiver,
+        3,
+        &PermissionType::ReadApplicationState,
+        cache_path,
+        plugin_thread_sender,
+        client_id
+    );
+
+    let _ = plugin_thread_sender.send(PluginInstruction::AddClient(client_id));
+    let _ = plugin_thread_sender.send(PluginInstruction::Load(
+        plugin_should_float,
+        false,
+        plugin_title,
+        run_plugin,
+        tab_index,
+        None,
+        client_id,
+        size,
+        None,
+    ));
+    std::thread::sleep(std::time::Duration::from_millis(500));
+    let tabs = vec![
+        TabInfo {
+            position: 0,
+            ..Default::default()
+        },
+        TabInfo {
+            position: 1,
+            active: true,
+            ..Default::default()
+        },
+    ];
+    let _ = plugin_thread_sender.send(PluginInstruction::Update(vec![(
+        None,
+        Some(client_id),
+        Event::TabUpdate(tabs),
+    )]));
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    let _ = plugin_thread_sender.send(PluginInstruction::Update(vec![(
+        None,
+        Some(client_id),
+        Event::Key(Key::Ctrl('7')), // this triggers the event in the fixture plugin
+    )]));
+    screen_thread.join().unwrap(); // this might take a while if the cache is cold
+    teardown();
+    let rendered_tab_count = received_screen_instructions
+        .lock()
+        .unwrap()
+        .iter()
+        .any(|i| {
+            if let ScreenInstruction::PluginBytes(plugin_bytes) = i {
+                plugin_bytes.iter().any(|(_, _, plugin_bytes)| {
+                    String::from_utf8_lossy(plugin_bytes).contains("Tabs in session: 2")
+                })
+            } else {
+                false
+            }
+        });
+    assert!(
+        rendered_tab_count,
+        "plugin rendered the tab count it got synchronously from the host"
+    );
+}
+
+#[test]
+#[ignore]
+pub fn get_keybindings_plugin_command() {
+    let temp_folder = tempdir().unwrap(); // placed explicitly in the test scope because its
+                                          // destructor removes the directory
+    let plugin_host_folder = PathBuf::from(temp_folder.path());
+    let cache_path = plugin_host_folder.join("permissions_test.kdl");
+    let (plugin_thread_sender, screen_receiver, teardown) =
+        create_plugin_thread(Some(plugin_host_folder));
+    let plugin_should_float = Some(false);
+    let plugin_title = Some("test_plugin".to_owned());
+    let run_plugin = RunPlugin {
+        _allow_exec_host_cmd: false,
+        location: RunPluginLocation::File(PathBuf::from(&*PLUGIN_FIXTURE)),
+        configuration: Default::default(),
+    };
+    let tab_index = 1;
+    let client_id = 1;
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let received_screen_instructions = Arc::new(Mutex::new(vec![]));
+    let screen_thread = grant_permissions_and_log_actions_in_thread!(
+        received_screen_instructions,
+        ScreenInstruction::PluginBytes,
+        screen_receiver,
+        3,
+        &PermissionType::ReadApplicationState,
+        cache_path,
+        plugin_thread_sender,
+        client_id
+    );
+
+    let _ = plugin_thread_sender.send(PluginInstruction::AddClient(client_id));
+    let _ = plugin_thread_sender.send(PluginInstruction::Load(
+        plugin_should_float,
+        false,
+        plugin_title,
+        run_plugin,
+        tab_index,
+        None,
+        client_id,
+        size,
+        None,
+    ));
+    std::thread::sleep(std::time::Duration::from_millis(500));
+    // the client's own configuration binds a key that is not one of the defaults
+    let mode_info = ModeInfo {
+        keybinds: vec![(
+            InputMode::Normal,
+            vec![(
+                Key::Alt(CharOrArrow::Char('z')),
+                vec![Action::SwitchToMode(InputMode::Locked)],
+            )],
+        )],
+        ..Default::default()
+    };
+    let _ = plugin_thread_sender.send(PluginInstruction::Update(vec![(
+        None,
+        Some(client_id),
+        Event::ModeUpdate(mode_info),
+    )]));
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    // this triggers the event in the fixture plugin
+    let _ = plugin_thread_sender.send(PluginInstruction::Update(vec![(
+        None,
+        Some(client_id),
+        Event::Key(Key::Alt(CharOrArrow::Char('k'))),
+    )]));
+    screen_thread.join().unwrap(); // this might take a while if the cache is cold
+    teardown();
+    let rendered_keybinding = received_screen_instructions
+        .lock()
+        .unwrap()
+        .iter()
+        .any(|i| {
+            if let ScreenInstruction::PluginBytes(plugin_bytes) = i {
+                plugin_bytes.iter().any(|(_, _, plugin_bytes)| {
+                    String::from_utf8_lossy(plugin_bytes).contains("Lock with: Alt+z")
+                })
+            } else {
+                false
+            }
+        });
+    assert!(
+        rendered_keybinding,
+        "plugin rendered the key bound to locking the session in the client's configuration"
+    );
+}
+
+#[test]
+#[ignore]
+pub fn open_url_plugin_command() {
+    let temp_folder = tempdir().unwrap(); // placed explicitly in the test scope because its
+                                          // destructor removes the directory
+    let plugin_host_folder = PathBuf::from(temp_folder.path());
+    let cache_path = plugin_host_folder.join("permissions_test.kdl");
+    let (plugin_thread_sender, background_jobs_receiver, screen_receiver, teardown) =
+        create_plugin_thread_with_background_jobs_receiver(Some(plugin_host_folder));
+    let plugin_should_float = Some(false);
+    let plugin_title = Some("test_plugin".to_owned());
+    let run_plugin = RunPlugin {
+        _allow_exec_host_cmd: false,
+        location: RunPluginLocation::File(PathBuf::from(&*PLUGIN_FIXTURE)),
+        configuration: Default::default(),
+    };
+    let tab_index = 1;
+    let client_id = 1;
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let received_background_jobs_instructions = Arc::new(Mutex::new(vec![]));
+    let background_jobs_thread = log_actions_in_thread!(
+        received_background_jobs_instructions,
+        BackgroundJob::OpenWithSystemOpener,
+        background_jobs_receiver,
+        1
+    );
+    let received_screen_instructions = Arc::new(Mutex::new(vec![]));
+    let _screen_thread = grant_permissions_and_log_actions_in_thread_naked_variant!(
+        received_screen_instructions,
+        ScreenInstruction::Exit,
+        screen_receiver,
+        1,
+        &PermissionType::OpenUrls,
+        cache_path,
+        plugin_thread_sender,
+        client_id
+    );
+
+    let _ = plugin_thread_sender.send(PluginInstruction::AddClient(client_id));
+    let _ = plugin_thread_sender.send(PluginInstruction::Load(
+        plugin_should_float,
+        false,
+        plugin_title,
+        run_plugin,
+        tab_index,
+        None,
+        client_id,
+        size,
+        None,
+    ));
+    std::thread::sleep(std::time::Duration::from_millis(500));
+    let _ = plugin_thread_sender.send(PluginInstruction::Update(vec![(
+        None,
+        Some(client_id),
+        Event::Key(Key::Ctrl('8')), // this triggers the event in the fixture plugin
+    )]));
+    background_jobs_thread.join().unwrap(); // this might take a while if the cache is cold
+    teardown();
+    let new_background_job = received_background_jobs_instructions
+        .lock()
+        .unwrap()
+        .iter()
+        .find_map(|i| {
+            if let BackgroundJob::OpenWithSystemOpener(..) = i {
+                Some(i.clone())
+            } else {
+                None
+            }
+        })
+        .clone();
+    assert!(format!("{:#?}", new_background_job).contains("https://zellij.dev"));
+}
+
+#[test]
+#[ignore]
+pub fn render_overlay_plugin_command() {
+    let temp_folder = tempdir().unwrap(); // placed explicitly in the test scope because its
+                                          // destructor removes the directory
+    let plugin_host_folder = PathBuf::from(temp_folder.path());
+    let cache_path = plugin_host_folder.join("permissions_test.kdl");
+    let (plugin_thread_sender, screen_receiver, teardown) =
+        create_plugin_thread(Some(plugin_host_folder));
+    let plugin_should_float = Some(false);
+    let plugin_title = Some("test_plugin".to_owned());
+    let run_plugin = RunPlugin {
+        _allow_exec_host_cmd: false,
+        location: RunPluginLocation::File(PathBuf::from(&*PLUGIN_FIXTURE)),
+        configuration: Default::default(),
+    };
+    let tab_index = 1;
+    let client_id = 1;
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let received_screen_instructions = Arc::new(Mutex::new(vec![]));
+    let screen_thread = grant_permissions_and_log_actions_in_thread!(
+        received_screen_instructions,
+        ScreenInstruction::RenderPluginOverlay,
+        screen_receiver,
+        1,
+        &PermissionType::ChangeApplicationState,
+        cache_path,
+        plugin_thread_sender,
+        client_id
+    );
+
+    let _ = plugin_thread_sender.send(PluginInstruction::AddClient(client_id));
+    let _ = plugin_thread_sender.send(PluginInstruction::Load(
+        plugin_should_float,
+        false,
+        plugin_title,
+        run_plugin,
+        tab_index,
+        None,
+        client_id,
+        size,
+        None,
+    ));
+    std::thread::sleep(std::time::Duration::from_millis(500));
+    let _ = plugin_thread_sender.send(PluginInstruction::Update(vec![(
+        None,
+        Some(client_id),
+        Event::Key(Key::Ctrl('9')), // this triggers the event in the fixture plugin
+    )]));
+    screen_thread.join().unwrap(); // this might take a while if the cache is cold
+    teardown();
+    let rendered_overlay = received_screen_instructions
+        .lock()
+        .unwrap()
+        .iter()
+        .find_map(|i| {
+            if let ScreenInstruction::RenderPluginOverlay(_plugin_id, plugin_overlay) = i {
+                Some(plugin_overlay.generate_overlay(size).unwrap())
+            } else {
+                None
+            }
+        })
+        .unwrap();
+    assert!(
+        rendered_overlay.contains("\u{1b}[4;3H\u{1b}[moverlay line one"),
+        "overlay is drawn at the coordinates the plugin requested"
+    );
+    assert!(
+        rendered_overlay.contains("\u{1b}[5;3H\u{1b}[moverlay line two"),
+        "overlay is drawn at the coordinates the plugin requested"
+    );
+}
+
+#[test]
+#[ignore]
+pub fn plugin_state_restored_after_session_serialization() {
+    let temp_folder = tempdir().unwrap(); // placed explicitly in the test scope because its
+                                          // destructor removes the directory
+    let plugin_host_folder = PathBuf::from(temp_folder.path());
+    let (plugin_thread_sender, pty_receiver, screen_receiver, teardown) =
+        create_plugin_thread_with_pty_receiver(Some(plugin_host_folder));
+    let plugin_should_float = Some(false);
+    let plugin_title = Some("test_plugin".to_owned());
+    let run_plugin = RunPlugin {
+        _allow_exec_host_cmd: false,
+        location: RunPluginLocation::File(PathBuf::from(&*PLUGIN_FIXTURE)),
+        configuration: Default::default(),
+    };
+    let tab_index = 1;
+    let client_id = 1;
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let received_pty_instructions = Arc::new(Mutex::new(vec![]));
+    let pty_thread = log_actions_in_thread!(
+        received_pty_instructions,
+        PtyInstruction::DumpLayout,
+        pty_receiver,
+        1
+    );
+    let received_screen_instructions = Arc::new(Mutex::new(vec![]));
+    let screen_thread = log_actions_in_thread_naked_variant!(
+        received_screen_instructions,
+        ScreenInstruction::Exit,
+        screen_receiver,
+        1
+    );
+
+    let _ = plugin_thread_sender.send(PluginInstruction::AddClient(client_id));
+    let _ = plugin_thread_sender.send(PluginInstruction::Load(
+        plugin_should_float,
+        false,
+        plugin_title.clone(),
+        run_plugin.clone(),
+        tab_index,
+        None,
+        client_id,
+        size,
+        None,
+    ));
+    std::thread::sleep(std::time::Duration::from_millis(500));
+    let first_plugin_id = 0;
+    // each of these increments a counter in the fixture plugin
+    let _ = plugin_thread_sender.send(PluginInstruction::Update(vec![
+        (Some(first_plugin_id), None, Event::Key(Key::Ctrl('0'))),
+        (Some(first_plugin_id), None, Event::Key(Key::Ctrl('0'))),
+        (Some(first_plugin_id), None, Event::Key(Key::Ctrl('0'))),
+    ]));
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    let mut session_layout_metadata = SessionLayoutMetadata::new(Box::new(Layout::default()));
+    session_layout_metadata.add_tab(
+        "tab 1".to_owned(),
+        true,
+        false,
+        None,
+        vec![PaneLayoutMetadata::new(
+            PaneId::Plugin(first_plugin_id),
+            PaneGeom::default(),
+            false,
+            None,
+            None,
+            true,
+            false,
+            false,
+            None,
+        )],
+        vec![],
+    );
+    let _ = plugin_thread_sender.send(PluginInstruction::DumpLayout(
+        session_layout_metadata,
+        client_id,
+    ));
+    pty_thread.join().unwrap(); // this might take a while if the cache is cold
+    let serialized_plugin_state = received_pty_instructions
+        .lock()
+        .unwrap()
+        .iter()
+        .find_map(|i| {
+            if let PtyInstruction::DumpLayout(session_layout_metadata, _client_id) = i {
+                let global_layout_manifest: GlobalLayoutManifest =
+                    session_layout_metadata.clone().into();
+                global_layout_manifest.tabs[0].1.tiled_panes[0]
+                    .plugin_state
+                    .clone()
+            } else {
+                None
+            }
+        })
+        .unwrap();
+
+    // resurrect the plugin as a new instance, with the state it serialized
+    let _ = plugin_thread_sender.send(PluginInstruction::Load(
+        plugin_should_float,
+        false,
+        plugin_title,
+        run_plugin,
+        tab_index,
+        None,
+        client_id,
+        size,
+        None,
+    ));
+    let restored_plugin_id = 1;
+    let _ = plugin_thread_sender.send(PluginInstruction::Update(vec![(
+        Some(restored_plugin_id),
+        None,
+        Event::RestoreState(serialized_plugin_state),
+    )]));
+    std::thread::sleep(std::time::Duration::from_millis(500));
+    teardown();
+    screen_thread.join().unwrap();
+    let restored_counter_rendered = received_screen_instructions
+        .lock()
+        .unwrap()
+        .iter()
+        .any(|i| {
+            if let ScreenInstruction::PluginBytes(plugin_bytes) = i {
+                plugin_bytes.iter().any(|(plugin_id, _, plugin_bytes)| {
+                    *plugin_id == restored_plugin_id
+                        && String::from_utf8_lossy(plugin_bytes).contains("Counter: 3")
+                })
+            } else {
+                false
+            }
+        });
+    assert!(
+        restored_counter_rendered,
+        "resurrected plugin rendered the counter it serialized"
+    );
+}
+
+#[test]
+#[ignore]
+pub fn plugin_renders_are_rate_limited() {
+    let temp_folder = tempdir().unwrap(); // placed explicitly in the test scope because its
+                                          // destructor removes the directory
+    let plugin_host_folder = PathBuf::from(temp_folder.path());
+    let cache_path = plugin_host_folder.join("permissions_test.kdl");
+    let (plugin_thread_sender, screen_receiver, teardown) =
+        create_plugin_thread(Some(plugin_host_folder));
+    let plugin_should_float = Some(false);
+    let plugin_title = Some("test_plugin".to_owned());
+    let max_fps = 5;
+    let mut configuration = BTreeMap::new();
+    configuration.insert("max_fps".to_owned(), max_fps.to_string());
+    let run_plugin = RunPlugin {
+        _allow_exec_host_cmd: false,
+        location: RunPluginLocation::File(PathBuf::from(&*PLUGIN_FIXTURE)),
+        configuration: PluginUserConfiguration::new(configuration),
+    };
+    let tab_index = 1;
+    let client_id = 1;
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let received_screen_instructions = Arc::new(Mutex::new(vec![]));
+    let screen_thread = grant_permissions_and_log_actions_in_thread!(
+        received_screen_instructions,
+        ScreenInstruction::GoToTabName,
+        screen_receiver,
+        1,
+        &PermissionType::ChangeApplicationState,
+        cache_path,
+        plugin_thread_sender,
+        client_id
+    );
+
+    let _ = plugin_thread_sender.send(PluginInstruction::AddClient(client_id));
+    let _ = plugin_thread_sender.send(PluginInstruction::Load(
+        plugin_should_float,
+        false,
+        plugin_title,
+        run_plugin,
+        tab_index,
+        None,
+        client_id,
+        size,
+        None,
+    ));
+    std::thread::sleep(std::time::Duration::from_millis(500));
+    let render_loop_start = std::time::Instant::now();
+    let _ = plugin_thread_sender.send(PluginInstruction::Update(vec![(
+        None,
+        Some(client_id),
+        Event::Key(Key::Ctrl('y')), // this starts a tight render loop in the fixture plugin
+    )]));
+    screen_thread.join().unwrap(); // this might take a while if the cache is cold
+    let render_loop_duration = render_loop_start.elapsed();
+    teardown();
+    let render_loop_frames = received_screen_instructions
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|i| {
+            if let ScreenInstruction::PluginBytes(plugin_bytes) = i {
+                plugin_bytes.iter().any(|(_, _, plugin_bytes)| {
+                    String::from_utf8_lossy(plugin_bytes).contains("Timer")
+                })
+            } else {
+                false
+            }
+        })
+        .count();
+    // the first frame is sent immediately and every following one only once its interval passes
+    let max_frames = (render_loop_duration.as_secs_f64() * max_fps as f64).ceil() as usize + 1;
+    assert!(
+        render_loop_frames <= max_frames,
+        "received {} frames in {:?}, expected no more than {}",
+        render_loop_frames,
+        render_loop_duration,
+        max_frames
+    );
+    assert!(
+        render_loop_frames < 100,
+        "frames rendered in a tight loop are coalesced"
+    );
+}
+
+#[test]
+#[ignore]
+pub fn paste_event_is_received_whole() {
+    let temp_folder = tempdir().unwrap(); // placed explicitly in the test scope because its
+                                          // destructor removes the directory
+    let plugin_host_folder = PathBuf::from(temp_folder.path());
+    let cache_path = plugin_host_folder.join("permissions_test.kdl");
+    let (plugin_thread_sender, screen_receiver, teardown) =
+        create_plugin_thread(Some(plugin_host_folder));
+    let plugin_should_float = Some(false);
+    let plugin_title = Some("test_plugin".to_owned());
+    let run_plugin = RunPlugin {
+        _allow_exec_host_cmd: false,
+        location: RunPluginLocation::File(PathBuf::from(&*PLUGIN_FIXTURE)),
+        configuration: Default::default(),
+    };
+    let tab_index = 1;
+    let client_id = 1;
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let received_screen_instructions = Arc::new(Mutex::new(vec![]));
+    let screen_thread = grant_permissions_and_log_actions_in_thread!(
+        received_screen_instructions,
+        ScreenInstruction::PluginBytes,
+        screen_receiver,
+        2,
+        &PermissionType::ChangeApplicationState,
+        cache_path,
+        plugin_thread_sender,
+        client_id
+    );
+
+    let _ = plugin_thread_sender.send(PluginInstruction::AddClient(client_id));
+    let _ = plugin_thread_sender.send(PluginInstruction::Load(
+        plugin_should_float,
+        false,
+        plugin_title,
+        run_plugin,
+        tab_index,
+        None,
+        client_id,
+        size,
+        None,
+    ));
+    std::thread::sleep(std::time::Duration::from_millis(500));
+    let _ = plugin_thread_sender.send(PluginInstruction::Update(vec![(
+        None,
+        Some(client_id),
+        Event::Paste("pasted line one\npasted line two".to_owned()),
+    )]));
+    screen_thread.join().unwrap(); // this might take a while if the cache is cold
+    teardown();
+    let rendered_paste = received_screen_instructions
+        .lock()
+        .unwrap()
+        .iter()
+        .find_map(|i| {
+            if let ScreenInstruction::PluginBytes(plugin_bytes) = i {
+                plugin_bytes.iter().find_map(|(_, _, plugin_bytes)| {
+                    let plugin_bytes = String::from_utf8_lossy(plugin_bytes).to_string();
+                    if plugin_bytes.contains("Paste(") {
+                        Some(plugin_bytes)
+                    } else {
+                        None
+                    }
+                })
+            } else {
+                None
+            }
+        })
+        .expect("plugin rendered the paste");
+    assert_eq!(
+        rendered_paste.matches("Paste(").count(),
+        1,
+        "plugin received a single paste event"
+    );
+    assert!(
+        rendered_paste.contains("Paste(\"pasted line one\\npasted line two\")"),
+        "plugin received the full pasted text"
+    );
+}
+
+#[test]
+#[ignore]
+pub fn plugin_stuck_in_a_loop_runs_out_of_fuel() {
+    let temp_folder = tempdir().unwrap(); // placed explicitly in the test scope because its
+                                          // destructor removes the directory
+    let plugin_host_folder = PathBuf::from(temp_folder.path());
+    let (plugin_thread_sender, screen_receiver, teardown) =
+        create_plugin_thread(Some(plugin_host_folder));
+    let mut configuration = BTreeMap::new();
+    configuration.insert("max_fuel".to_owned(), "10000000".to_owned());
+    let stuck_plugin = RunPlugin {
+        _allow_exec_host_cmd: false,
+        location: RunPluginLocation::File(PathBuf::from(&*PLUGIN_FIXTURE)),
+        configuration: PluginUserConfiguration::new(configuration),
+    };
+    let other_plugin = RunPlugin {
+        _allow_exec_host_cmd: false,
+        location: RunPluginLocation::File(PathBuf::from(&*PLUGIN_FIXTURE)),
+        configuration: Default::default(),
+    };
+    let tab_index = 1;
+    let client_id = 1;
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let _ = plugin_thread_sender.send(PluginInstruction::AddClient(client_id));
+    for run_plugin in [stuck_plugin, other_plugin] {
+        let _ = plugin_thread_sender.send(PluginInstruction::Load(
+            Some(false),
+            false,
+            Some("test_plugin".to_owned()),
+            run_plugin,
+            tab_index,
+            None,
+            client_id,
+            size,
+            None,
+        ));
+    }
+    let (stuck_plugin_id, other_plugin_id) = (0, 1);
+    std::thread::sleep(std::time::Duration::from_millis(500));
+    let _ = plugin_thread_sender.send(PluginInstruction::Update(vec![(
+        Some(stuck_plugin_id),
+        Some(client_id),
+        Event::Key(Key::Alt(CharOrArrow::Char('l'))), // this loops forever in the fixture plugin
+    )]));
+    let _ = plugin_thread_sender.send(PluginInstruction::Update(vec![(
+        Some(other_plugin_id),
+        Some(client_id),
+        Event::Key(Key::Alt(CharOrArrow::Char('x'))),
+    )]));
+
+    let mut stuck_plugin_error = None;
+    let mut other_plugin_rendered = false;
+    // this might take a while if the cache is cold
+    let deadline = std::time::Instant::now() + std::time::Duration::from_secs(60);
+    while stuck_plugin_error.is_none() || !other_plugin_rendered {
+        let timeout = deadline.saturating_duration_since(std::time::Instant::now());
+        let (instruction, _err_ctx) = screen_receiver
+            .recv_timeout(timeout)
+            .expect("stuck plugin stopped and other plugin rendered");
+        match instruction {
+            ScreenInstruction::UpdatePluginLoadingStage(plugin_id, loading_indication)
+                if plugin_id == stuck_plugin_id && loading_indication.is_error() =>
+            {
+                stuck_plugin_error = Some(format!("{:?}", loading_indication));
+            },
+            ScreenInstruction::PluginBytes(plugin_bytes) => {
+                other_plugin_rendered |= plugin_bytes.iter().any(|(plugin_id, _, bytes)| {
+                    *plugin_id == other_plugin_id
+                        && String::from_utf8_lossy(bytes).contains("Alt(Char('x'))")
+                });
+            },
+            _ => {},
+        }
+    }
+    teardown();
+    assert!(
+        stuck_plugin_error
+            .unwrap()
+            .contains("plugin ran out of fuel"),
+        "error rendered for the stuck plugin explains it ran out of fuel"
+    );
+}
+
+#[test]
+#[ignore]
+pub fn plugin_receives_session_activity_in_order() {
+    let temp_folder = tempdir().unwrap(); // placed explicitly in the test scope because its
+                                          // destructor removes the directory
+    let plugin_host_folder = PathBuf::from(temp_folder.path());
+    let cache_path = plugin_host_folder.join("permissions_test.kdl");
+    let (plugin_thread_sender, screen_receiver, teardown) =
+        create_plugin_thread(Some(plugin_host_folder));
+    let plugin_should_float = Some(false);
+    let plugin_title = Some("test_plugin".to_owned());
+    let run_plugin = RunPlugin {
+        _allow_exec_host_cmd: false,
+        location: RunPluginLocation::File(PathBuf::from(&*PLUGIN_FIXTURE)),
+        configuration: Default::default(),
+    };
+    let tab_index = 1;
+    let client_id = 1;
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let received_screen_instructions = Arc::new(Mutex::new(vec![]));
+    let screen_thread = grant_permissions_and_log_actions_in_thread!(
+        received_screen_instructions,
+        ScreenInstruction::RequestSessionActivity,
+        screen_receiver,
+        1,
+        &PermissionType::ReadApplicationState,
+        cache_path,
+        plugin_thread_sender,
+        client_id
+    );
+
+    let _ = plugin_thread_sender.send(PluginInstruction::AddClient(client_id));
+    let _ = plugin_thread_sender.send(PluginInstruction::Load(
+        plugin_should_float,
+        false,
+        plugin_title,
+        run_plugin,
+        tab_index,
+        None,
+        client_id,
+        size,
+        None,
+    ));
+    std::thread::sleep(std::time::Duration::from_millis(500));
+    let session_activity = |kind| SessionActivity {
+        timestamp: std::time::Duration::from_secs(1),
+        kind,
+    };
+    // these are sent by the screen as the user opens a pane running a command and then closes it
+    let _ = plugin_thread_sender.send(PluginInstruction::Update(vec![(
+        None,
+        None,
+        Event::SessionActivity(vec![
+            session_activity(SessionActivityKind::PaneOpened(PaneId::Terminal(2))),
+            session_activity(SessionActivityKind::CommandSpawned(
+                PaneId::Terminal(2),
+                "htop".to_owned(),
+            )),
+        ]),
+    )]));
+    let _ = plugin_thread_sender.send(PluginInstruction::Update(vec![(
+        None,
+        None,
+        Event::SessionActivity(vec![session_activity(SessionActivityKind::PaneClosed(
+            PaneId::Terminal(2),
+        ))]),
+    )]));
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    // this makes the fixture plugin request the whole log
+    let _ = plugin_thread_sender.send(PluginInstruction::Update(vec![(
+        None,
+        Some(client_id),
+        Event::Key(Key::Alt(CharOrArrow::Char('s'))),
+    )]));
+    screen_thread.join().unwrap(); // this might take a while if the cache is cold
+    teardown();
+    let received_screen_instructions = received_screen_instructions.lock().unwrap();
+    let rendered_session_activity = received_screen_instructions
+        .iter()
+        .filter_map(|i| {
+            if let ScreenInstruction::PluginBytes(plugin_bytes) = i {
+                plugin_bytes.iter().find_map(|(_, _, plugin_bytes)| {
+                    let plugin_bytes = String::from_utf8_lossy(plugin_bytes).to_string();
+                    if plugin_bytes.contains("PaneClosed(") {
+                        Some(plugin_bytes)
+                    } else {
+                        None
+                    }
+                })
+            } else {
+                None
+            }
+        })
+        .last()
+        .expect("plugin rendered the session activity");
+    let position_of = |entry: &str| {
+        rendered_session_activity
+            .find(entry)
+            .unwrap_or_else(|| panic!("plugin received {}", entry))
+    };
+    let pane_opened = position_of("PaneOpened(Terminal(2))");
+    let command_spawned = position_of("CommandSpawned(Terminal(2), \"htop\")");
+    let pane_closed = position_of("PaneClosed(Terminal(2))");
+    assert!(
+        pane_opened < command_spawned,
+        "plugin received the pane opening before its command"
+    );
+    assert!(
+        command_spawned < pane_closed,
+        "plugin received the pane closing last"
+    );
+    assert!(
+        received_screen_instructions.iter().any(|i| matches!(
+            i,
+            ScreenInstruction::RequestSessionActivity(0, requesting_client_id)
+                if *requesting_client_id == client_id
+        )),
+        "plugin requested the whole session activity log"
+    );
+}
+
+#[test]
+#[ignore]
+pub fn plugin_receives_its_own_metrics() {
+    let temp_folder = tempdir().unwrap(); // placed explicitly in the test scope because its
+                                          // destructor removes the directory
+    let plugin_host_folder = PathBuf::from(temp_folder.path());
+    let cache_path = plugin_host_folder.join("permissions_test.kdl");
+    let clock = Arc::new(ManualClock::new());
+    let (plugin_thread_sender, screen_receiver, teardown) =
+        create_plugin_thread_with_clock(Some(plugin_host_folder), clock.clone());
+    let plugin_should_float = Some(false);
+    let plugin_title = Some("test_plugin".to_owned());
+    let run_plugin = RunPlugin {
+        _allow_exec_host_cmd: false,
+        location: RunPluginLocation::File(PathBuf::from(&*PLUGIN_FIXTURE)),
+        configuration: Default::default(),
+    };
+    let tab_index = 1;
+    let client_id = 1;
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let received_screen_instructions = Arc::new(Mutex::new(vec![]));
+    let screen_thread = grant_permissions_and_log_actions_in_thread!(
+        received_screen_instructions,
+        ScreenInstruction::RequestSessionActivity,
+        screen_receiver,
+        1,
+        &PermissionType::ReadApplicationState,
+        cache_path,
+        plugin_thread_sender,
+        client_id
+    );
+
+    let _ = plugin_thread_sender.send(PluginInstruction::AddClient(client_id));
+    let _ = plugin_thread_sender.send(PluginInstruction::Load(
+        plugin_should_float,
+        false,
+        plugin_title,
+        run_plugin,
+        tab_index,
+        None,
+        client_id,
+        size,
+        None,
+    ));
+    std::thread::sleep(std::time::Duration::from_millis(500));
+    // this makes the fixture plugin subscribe to the plugin metrics
+    let _ = plugin_thread_sender.send(PluginInstruction::Update(vec![(
+        None,
+        Some(client_id),
+        Event::Key(Key::Alt(CharOrArrow::Char('r'))),
+    )]));
+    clock.wait_for_scheduled_callbacks(1);
+    clock.advance(std::time::Duration::from_secs(1));
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    // we use the session activity request as a marker for the end of the test
+    let _ = plugin_thread_sender.send(PluginInstruction::Update(vec![(
+        None,
+        Some(client_id),
+        Event::Key(Key::Alt(CharOrArrow::Char('s'))),
+    )]));
+    screen_thread.join().unwrap(); // this might take a while if the cache is cold
+    teardown();
+    let received_screen_instructions = received_screen_instructions.lock().unwrap();
+    let rendered_plugin_metrics = received_screen_instructions
+        .iter()
+        .filter_map(|i| {
+            if let ScreenInstruction::PluginBytes(plugin_bytes) = i {
+                plugin_bytes.iter().find_map(|(_, _, plugin_bytes)| {
+                    let plugin_bytes = String::from_utf8_lossy(plugin_bytes).to_string();
+                    if plugin_bytes.contains("PluginMetrics(") {
+                        Some(plugin_bytes)
+                    } else {
+                        None
+                    }
+                })
+            } else {
+                None
+            }
+        })
+        .last()
+        .expect("plugin received its metrics");
+    let own_metric = "PluginMetric { plugin_id: 0, memory_bytes: ";
+    let memory_bytes: u64 = rendered_plugin_metrics
+        .split(own_metric)
+        .nth(1)
+        .and_then(|rest| rest.split(',').next())
+        .and_then(|memory_bytes| memory_bytes.parse().ok())
+        .expect("plugin metrics contain the plugin's own id");
+    assert!(memory_bytes > 0, "plugin has a nonzero memory footprint");
+}
+
+#[test]
+#[ignore]
+pub fn subscribed_plugin_receives_pane_output() {
+    let temp_folder = tempdir().unwrap(); // placed explicitly in the test scope because its
+                                          // destructor removes the directory
+    let plugin_host_folder = PathBuf::from(temp_folder.path());
+    let cache_path = plugin_host_folder.join("permissions_test.kdl");
+    let (plugin_thread_sender, screen_receiver, teardown) =
+        create_plugin_thread(Some(plugin_host_folder));
+    let plugin_should_float = Some(false);
+    let plugin_title = Some("test_plugin".to_owned());
+    let run_plugin = RunPlugin {
+        _allow_exec_host_cmd: false,
+        location: RunPluginLocation::File(PathBuf::from(&*PLUGIN_FIXTURE)),
+        configuration: Default::default(),
+    };
+    let tab_index = 1;
+    let client_id = 1;
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let received_screen_instructions = Arc::new(Mutex::new(vec![]));
+    let screen_thread = grant_permissions_and_log_actions_in_thread!(
+        received_screen_instructions,
+        ScreenInstruction::UnsubscribePaneOutput,
+        screen_receiver,
+        1,
+        &PermissionType::ReadPaneOutput,
+        cache_path,
+        plugin_thread_sender,
+        client_id
+    );
+
+    let _ = plugin_thread_sender.send(PluginInstruction::AddClient(client_id));
+    let _ = plugin_thread_sender.send(PluginInstruction::Load(
+        plugin_should_float,
+        false,
+        plugin_title,
+        run_plugin,
+        tab_index,
+        None,
+        client_id,
+        size,
+        None,
+    ));
+    std::thread::sleep(std::time::Duration::from_millis(500));
+    // this makes the fixture plugin subscribe to the output of terminal pane 1
+    let _ = plugin_thread_sender.send(PluginInstruction::Update(vec![(
+        None,
+        Some(client_id),
+        Event::Key(Key::Alt(CharOrArrow::Char('o'))),
+    )]));
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    // this is what the screen does as the pty of the pane produces output
+    let pane_output = PaneOutputBuffer::new(MAX_BUFFERED_PANE_OUTPUT);
+    pane_output.push(b"foo");
+    pane_output.push(b"bar\r\n");
+    let _ = plugin_thread_sender.send(PluginInstruction::PaneOutputQueued(
+        0,
+        PaneId::Terminal(1),
+        pane_output.clone(),
+    ));
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    // this makes the fixture plugin unsubscribe
+    let _ = plugin_thread_sender.send(PluginInstruction::Update(vec![(
+        None,
+        Some(client_id),
+        Event::Key(Key::Alt(CharOrArrow::Char('p'))),
+    )]));
+    screen_thread.join().unwrap(); // this might take a while if the cache is cold
+    teardown();
+    let received_screen_instructions = received_screen_instructions.lock().unwrap();
+    assert!(
+        received_screen_instructions.iter().any(|i| matches!(
+            i,
+            ScreenInstruction::SubscribePaneOutput(PaneId::Terminal(1), 0)
+        )),
+        "plugin subscribed to the output of the pane"
+    );
+    let rendered_pane_output = received_screen_instructions
+        .iter()
+        .filter_map(|i| {
+            if let ScreenInstruction::PluginBytes(plugin_bytes) = i {
+                plugin_bytes.iter().find_map(|(_, _, plugin_bytes)| {
+                    let plugin_bytes = String::from_utf8_lossy(plugin_bytes).to_string();
+                    if plugin_bytes.contains("PaneOutput(") {
+                        Some(plugin_bytes)
+                    } else {
+                        None
+                    }
+                })
+            } else {
+                None
+            }
+        })
+        .last()
+        .expect("plugin rendered the pane output");
+    let expected_event = format!("PaneOutput(Terminal(1), {:?})", b"foobar\r\n".to_vec());
+    assert!(
+        rendered_pane_output.contains(&expected_event),
+        "plugin received the output as a single chunk"
+    );
+    assert!(
+        pane_output.take().is_empty(),
+        "the output was taken out of the buffer"
+    );
+}
+
+#[test]
+#[ignore]
+pub fn plugin_receives_mode_changes_in_order() {
+    let temp_folder = tempdir().unwrap(); // placed explicitly in the test scope because its
+                                          // destructor removes the directory
+    let plugin_host_folder = PathBuf::from(temp_folder.path());
+    let cache_path = plugin_host_folder.join("permissions_test.kdl");
+    let (plugin_thread_sender, screen_receiver, teardown) =
+        create_plugin_thread(Some(plugin_host_folder));
+    let plugin_should_float = Some(false);
+    let plugin_title = Some("test_plugin".to_owned());
+    let run_plugin = RunPlugin {
+        _allow_exec_host_cmd: false,
+        location: RunPluginLocation::File(PathBuf::from(&*PLUGIN_FIXTURE)),
+        configuration: Default::default(),
+    };
+    let tab_index = 1;
+    let client_id = 1;
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let received_screen_instructions = Arc::new(Mutex::new(vec![]));
+    let screen_thread = grant_permissions_and_log_actions_in_thread!(
+        received_screen_instructions,
+        ScreenInstruction::RequestSessionActivity,
+        screen_receiver,
+        1,
+        &PermissionType::ReadApplicationState,
+        cache_path,
+        plugin_thread_sender,
+        client_id
+    );
+
+    let _ = plugin_thread_sender.send(PluginInstruction::AddClient(client_id));
+    let _ = plugin_thread_sender.send(PluginInstruction::Load(
+        plugin_should_float,
+        false,
+        plugin_title,
+        run_plugin,
+        tab_index,
+        None,
+        client_id,
+        size,
+        None,
+    ));
+    std::thread::sleep(std::time::Duration::from_millis(500));
+    // these are sent by the screen as the client goes to pane mode, locks the interface and then
+    // unlocks it again
+    for input_mode in [InputMode::Pane, InputMode::Locked, InputMode::Normal] {
+        let _ = plugin_thread_sender.send(PluginInstruction::Update(vec![(
+            None,
+            Some(client_id),
+            Event::ModeChanged(input_mode),
+        )]));
+    }
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    // this makes the fixture plugin request the session activity, which is only used here to know
+    // the mode changes were handled
+    let _ = plugin_thread_sender.send(PluginInstruction::Update(vec![(
+        None,
+        Some(client_id),
+        Event::Key(Key::Alt(CharOrArrow::Char('s'))),
+    )]));
+    screen_thread.join().unwrap(); // this might take a while if the cache is cold
+    teardown();
+    let received_screen_instructions = received_screen_instructions.lock().unwrap();
+    let rendered_mode_changes = received_screen_instructions
+        .iter()
+        .filter_map(|i| {
+            if let ScreenInstruction::PluginBytes(plugin_bytes) = i {
+                plugin_bytes.iter().find_map(|(_, _, plugin_bytes)| {
+                    let plugin_bytes = String::from_utf8_lossy(plugin_bytes).to_string();
+                    if plugin_bytes.contains("ModeChanged(") {
+                        Some(plugin_bytes)
+                    } else {
+                        None
+                    }
+                })
+            } else {
+                None
+            }
+        })
+        .last()
+        .expect("plugin rendered the mode changes");
+    assert!(
+        rendered_mode_changes
+            .contains("ModeChanged(Pane), ModeChanged(Locked), ModeChanged(Normal)"),
+        "plugin received the mode changes in order: {}",
+        rendered_mode_changes
+    );
+}
+
+#[test]
+#[ignore]
+pub fn plugin_can_set_its_pane_and_tab_name() {
+    let temp_folder = tempdir().unwrap(); // placed explicitly in the test scope because its
+                                          // destructor removes the directory
+    let plugin_host_folder = PathBuf::from(temp_folder.path());
+    let cache_path = plugin_host_folder.join("permissions_test.kdl");
+    let (plugin_thread_sender, screen_receiver, teardown) =
+        create_plugin_thread(Some(plugin_host_folder));
+    let plugin_should_float = Some(false);
+    let plugin_title = Some("test_plugin".to_owned());
+    let run_plugin = RunPlugin {
+        _allow_exec_host_cmd: false,
+        location: RunPluginLocation::File(PathBuf::from(&*PLUGIN_FIXTURE)),
+        configuration: Default::default(),
+    };
+    let tab_index = 1;
+    let client_id = 1;
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let received_screen_instructions = Arc::new(Mutex::new(vec![]));
+    let screen_thread = grant_permissions_and_log_actions_in_thread!(
+        received_screen_instructions,
+        ScreenInstruction::RenamePane,
+        screen_receiver,
+        1,
+        &PermissionType::ChangeApplicationState,
+        cache_path,
+        plugin_thread_sender,
+        client_id
+    );
+
+    let _ = plugin_thread_sender.send(PluginInstruction::AddClient(client_id));
+    let _ = plugin_thread_sender.send(PluginInstruction::Load(
+        plugin_should_float,
+        false,
+        plugin_title,
+        run_plugin,
+        tab_index,
+        None,
+        client_id,
+        size,
+        None,
+    ));
+    std::thread::sleep(std::time::Duration::from_millis(500));
+    // a pane name with control characters, then a tab name and finally a valid pane name
+    for key in ['m', 't', 'n'] {
+        let _ = plugin_thread_sender.send(PluginInstruction::Update(vec![(
+            None,
+            Some(client_id),
+            Event::Key(Key::Alt(CharOrArrow::Char(key))),
+        )]));
+    }
+    screen_thread.join().unwrap(); // this might take a while if the cache is cold
+    teardown();
+    let received_screen_instructions = received_screen_instructions.lock().unwrap();
+    let pane_names: Vec<(PaneId, String)> = received_screen_instructions
+        .iter()
+        .filter_map(|i| match i {
+            ScreenInstruction::RenamePane(pane_id, name) => {
+                Some((*pane_id, String::from_utf8_lossy(name).to_string()))
+            },
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        pane_names,
+        vec![(PaneId::Plugin(0), "fixture pane name".to_owned())],
+        "the name with control characters was rejected"
+    );
+    assert!(
+        received_screen_instructions.iter().any(|i| matches!(
+            i,
+            ScreenInstruction::RenamePluginTab(0, name) if name == "fixture tab name"
+        )),
+        "plugin set the name of its tab"
+    );
+}
+
+#[test]
+#[ignore]
+pub fn set_pane_and_tab_name_plugin_commands_permission_denied() {
+    let temp_folder = tempdir().unwrap(); // placed explicitly in the test scope because its
+                                          // destructor removes the directory
+    let plugin_host_folder = PathBuf::from(temp_folder.path());
+    let cache_path = plugin_host_folder.join("permissions_test.kdl");
+    let (plugin_thread_sender, screen_receiver, teardown) =
+        create_plugin_thread(Some(plugin_host_folder));
+    let plugin_should_float = Some(false);
+    let plugin_title = Some("test_plugin".to_owned());
+    let run_plugin = RunPlugin {
+        _allow_exec_host_cmd: false,
+        location: RunPluginLocation::File(PathBuf::from(&*PLUGIN_FIXTURE)),
+        configuration: Default::default(),
+    };
+    let tab_index = 1;
+    let client_id = 1;
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let received_screen_instructions = Arc::new(Mutex::new(vec![]));
+    // here we create a fake screen thread that denies every permission request it gets and exits
+    // once the plugin renders the PermissionDenied event it was sent
+    let screen_thread = std::thread::Builder::new()
+        .name("fake_screen_thread".to_string())
+        .spawn({
+            let log = received_screen_instructions.clone();
+            let cache_path = cache_path.clone();
+            let plugin_thread_sender = plugin_thread_sender.clone();
+            move || loop {
+                let (event, _err_ctx) = screen_receiver
+                    .recv()
+                    .expect("failed to receive event on channel");
+                match event {
+                    ScreenInstruction::RequestPluginPermissions(_, plugin_permission) => {
+                        let _ =
+                            plugin_thread_sender.send(PluginInstruction::PermissionRequestResult(
+                                0,
+                                Some(client_id),
+                                plugin_permission.permissions,
+                                PermissionStatus::Denied,
+                                Some(cache_path.clone()),
+                            ));
+                    },
+                    ScreenInstruction::PluginBytes(ref plugin_bytes) => {
+                        let rendered_denial = plugin_bytes.iter().any(|(_, _, bytes)| {
+                            String::from_utf8_lossy(bytes)
+                                .contains("PermissionDenied(ChangeApplicationState)")
+                        });
+                        log.lock().unwrap().push(event);
+                        if rendered_denial {
+                            break;
+                        }
+                    },
+                    ScreenInstruction::Exit => {
+                        break;
+                    },
+                    _ => {
+                        log.lock().unwrap().push(event);
+                    },
+                }
+            }
+        })
+        .unwrap();
+
+    let _ = plugin_thread_sender.send(PluginInstruction::AddClient(client_id));
+    let _ = plugin_thread_sender.send(PluginInstruction::Load(
+        plugin_should_float,
+        false,
+        plugin_title,
+        run_plugin,
+        tab_index,
+        None,
+        client_id,
+        size,
+        None,
+    ));
+    std::thread::sleep(std::time::Duration::from_millis(500));
+    // a tab name and a pane name
+    for key in ['t', 'n'] {
+        let _ = plugin_thread_sender.send(PluginInstruction::Update(vec![(
+            None,
+            Some(client_id),
+            Event::Key(Key::Alt(CharOrArrow::Char(key))),
+        )]));
+    }
+    screen_thread.join().unwrap(); // this might take a while if the cache is cold
+    teardown();
+    let renamed = received_screen_instructions
+        .lock()
+        .unwrap()
+        .iter()
+        .any(|i| {
+            matches!(
+                i,
+                ScreenInstruction::RenamePane(..) | ScreenInstruction::RenamePluginTab(..)
+            )
+        });
+    assert!(
+        !renamed,
+        "pane and tab were not renamed without the ChangeApplicationState permission"
+    );
+}
+
+#[test]
+pub fn missing_plugin_file_is_reported_without_stopping_the_plugin_thread() {
+    let (plugin_thread_sender, screen_receiver, teardown) = create_plugin_thread(None);
+    let missing_plugin_path = PathBuf::from("/path/to/nonexistent/plugin.wasm");
+    let run_plugin = RunPlugin {
+        _allow_exec_host_cmd: false,
+        location: RunPluginLocation::File(missing_plugin_path),
+        configuration: Default::default(),
+    };
+    let tab_index = 1;
+    let client_id = 1;
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let _ = plugin_thread_sender.send(PluginInstruction::AddClient(client_id));
+    let _ = plugin_thread_sender.send(PluginInstruction::Load(
+        Some(false),
+        false,
+        Some("test_plugin".to_owned()),
+        run_plugin,
+        tab_index,
+        None,
+        client_id,
+        size,
+        None,
+    ));
+    let mut loading_error = None;
+    while loading_error.is_none() {
+        let (instruction, _err_ctx) = screen_receiver
+            .recv_timeout(std::time::Duration::from_secs(10))
+            .expect("loading error rendered");
+        match instruction {
+            ScreenInstruction::UpdatePluginLoadingStage(_plugin_id, loading_indication)
+                if loading_indication.is_error() =>
+            {
+                loading_error = Some(loading_indication.to_string());
+            },
+            _ => {},
+        }
+    }
+    // the plugin thread is still handling instructions
+    let (barrier_sender, barrier_receiver) = channels::bounded(1);
+    let _ = plugin_thread_sender.send(PluginInstruction::Barrier(barrier_sender));
+    let plugin_thread_responded = barrier_receiver
+        .recv_timeout(std::time::Duration::from_secs(10))
+        .is_ok();
+    teardown();
+    assert!(
+        loading_error
+            .unwrap()
+            .contains("plugin not found: /path/to/nonexistent/plugin.wasm"),
+        "error rendered for the plugin explains its file is missing"
+    );
+    assert!(plugin_thread_responded);
+}
+
+#[test]
+#[ignore]
+pub fn plugin_subscribed_to_the_bell_receives_it() {
+    let temp_folder = tempdir().unwrap(); // placed explicitly in the test scope because its
+                                          // destructor removes the directory
+    let plugin_host_folder = PathBuf::from(temp_folder.path());
+    let cache_path = plugin_host_folder.join("permissions_test.kdl");
+    let (plugin_thread_sender, screen_receiver, teardown) =
+        create_plugin_thread(Some(plugin_host_folder));
+    let plugin_should_float = Some(false);
+    let plugin_title = Some("test_plugin".to_owned());
+    let run_plugin = RunPlugin {
+        _allow_exec_host_cmd: false,
+        location: RunPluginLocation::File(PathBuf::from(&*PLUGIN_FIXTURE)),
+        configuration: Default::default(),
+    };
+    let tab_index = 1;
+    let client_id = 1;
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let received_screen_instructions = Arc::new(Mutex::new(vec![]));
+    let screen_thread = grant_permissions_and_log_actions_in_thread!(
+        received_screen_instructions,
+        ScreenInstruction::RequestSessionActivity,
+        screen_receiver,
+        1,
+        &PermissionType::ReadApplicationState,
+        cache_path,
+        plugin_thread_sender,
+        client_id
+    );
+
+    let _ = plugin_thread_sender.send(PluginInstruction::AddClient(client_id));
+    let _ = plugin_thread_sender.send(PluginInstruction::Load(
+        plugin_should_float,
+        false,
+        plugin_title,
+        run_plugin,
+        tab_index,
+        None,
+        client_id,
+        size,
+        None,
+    ));
+    std::thread::sleep(std::time::Duration::from_millis(500));
+    // this makes the fixture plugin subscribe to the bell
+    let _ = plugin_thread_sender.send(PluginInstruction::Update(vec![(
+        None,
+        Some(client_id),
+        Event::Key(Key::Alt(CharOrArrow::Char('b'))),
+    )]));
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    let _ = plugin_thread_sender.send(PluginInstruction::Update(vec![(
+        None,
+        None,
+        Event::Bell(PaneId::Terminal(1)),
+    )]));
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    // we use the session activity request as a marker for the end of the test
+    let _ = plugin_thread_sender.send(PluginInstruction::Update(vec![(
+        None,
+        Some(client_id),
+        Event::Key(Key::Alt(CharOrArrow::Char('s'))),
+    )]));
+    screen_thread.join().unwrap(); // this might take a while if the cache is cold
+    teardown();
+    let plugin_rendered_the_bell = received_screen_instructions
+        .lock()
+        .unwrap()
+        .iter()
+        .any(|i| match i {
+            ScreenInstruction::PluginBytes(plugin_bytes) => {
+                plugin_bytes.iter().any(|(_, _, plugin_bytes)| {
+                    String::from_utf8_lossy(plugin_bytes).contains("Bell(Terminal(1))")
+                })
+            },
+            _ => false,
+        });
+    assert!(plugin_rendered_the_bell, "plugin received the bell");
+}
+
+#[test]
+#[ignore]
+pub fn plugins_render_a_full_frame_for_a_reattaching_client() {
+    let temp_folder = tempdir().unwrap(); // placed explicitly in the test scope because its
+                                          // destructor removes the directory
+    let plugin_host_folder = PathBuf::from(temp_folder.path());
+    let cache_path = plugin_host_folder.join("permissions_test.kdl");
+    let (plugin_thread_sender, screen_receiver, teardown) =
+        create_plugin_thread(Some(plugin_host_folder));
+    let plugin_should_float = Some(false);
+    let plugin_title = Some("test_plugin".to_owned());
+    let run_plugin = RunPlugin {
+        _allow_exec_host_cmd: false,
+        location: RunPluginLocation::File(PathBuf::from(&*PLUGIN_FIXTURE)),
+        configuration: Default::default(),
+    };
+    let tab_index = 1;
+    let client_id = 1;
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let received_screen_instructions = Arc::new(Mutex::new(vec![]));
+    let screen_thread = grant_permissions_and_log_actions_in_thread!(
+        received_screen_instructions,
+        ScreenInstruction::RequestSessionActivity,
+        screen_receiver,
+        1,
+        &PermissionType::ReadApplicationState,
+        cache_path,
+        plugin_thread_sender,
+        client_id
+    );
+
+    let _ = plugin_thread_sender.send(PluginInstruction::AddClient(client_id));
+    let _ = plugin_thread_sender.send(PluginInstruction::Load(
+        plugin_should_float,
+        false,
+        plugin_title,
+        run_plugin,
+        tab_index,
+        None,
+        client_id,
+        size,
+        None,
+    ));
+    std::thread::sleep(std::time::Duration::from_millis(500));
+    let instructions_before_reattaching = received_screen_instructions.lock().unwrap().len();
+    // this is what the server sends when the client attaches again
+    let _ = plugin_thread_sender.send(PluginInstruction::RerenderPlugins(client_id));
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    // we use the session activity request as a marker for the end of the test
+    let _ = plugin_thread_sender.send(PluginInstruction::Update(vec![(
+        None,
+        Some(client_id),
+        Event::Key(Key::Alt(CharOrArrow::Char('s'))),
+    )]));
+    screen_thread.join().unwrap(); // this might take a while if the cache is cold
+    teardown();
+    let received_screen_instructions = received_screen_instructions.lock().unwrap();
+    let rerendered_frame = received_screen_instructions
+        .iter()
+        .skip(instructions_before_reattaching)
+        .find_map(|i| match i {
+            ScreenInstruction::PluginBytes(plugin_bytes) => plugin_bytes
+                .iter()
+                .find(|(_, frame_client_id, _)| *frame_client_id == client_id)
+                .map(|(_, _, frame)| String::from_utf8_lossy(frame).to_string()),
+            _ => None,
+        })
+        .expect("plugin rendered again for the reattaching client");
+    assert!(
+        rerendered_frame.contains("Rows: 20, Cols: 121"),
+        "the frame is of the whole plugin"
+    );
+}
+
+#[test]
+#[ignore]
+pub fn reloaded_plugin_keeps_its_stable_plugin_id() {
+    let (plugin_thread_sender, screen_receiver, teardown) = create_plugin_thread(None);
+    let run_plugin = RunPlugin {
+        _allow_exec_host_cmd: false,
+        location: RunPluginLocation::File(PathBuf::from(&*PLUGIN_FIXTURE)),
+        configuration: PluginUserConfiguration::new(BTreeMap::from([(
+            "fake_config_key".to_owned(),
+            "fake_config_value".to_owned(),
+        )])),
+    };
+    let tab_index = 1;
+    let client_id = 1;
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let differently_configured_plugin = RunPlugin {
+        _allow_exec_host_cmd: false,
+        location: RunPluginLocation::File(PathBuf::from(&*PLUGIN_FIXTURE)),
+        configuration: Default::default(),
+    };
+    let received_screen_instructions = Arc::new(Mutex::new(vec![]));
+    // we use loading the third plugin as a marker for the end of the test
+    let screen_thread = log_actions_in_thread!(
+        received_screen_instructions,
+        ScreenInstruction::AddPlugin,
+        screen_receiver,
+        3
+    );
+    let load_plugin = |run_plugin: RunPlugin| {
+        let _ = plugin_thread_sender.send(PluginInstruction::Load(
+            Some(false),
+            false,
+            None,
+            run_plugin,
+            tab_index,
+            None,
+            client_id,
+            size,
+            None,
+        ));
+        std::thread::sleep(std::time::Duration::from_millis(500));
+    };
+    let report_plugin_ids = || {
+        // the fixture plugin renders the ids it gets from the host
+        let _ = plugin_thread_sender.send(PluginInstruction::Update(vec![(
+            None,
+            Some(client_id),
+            Event::Key(Key::Alt(CharOrArrow::Char('i'))),
+        )]));
+        std::thread::sleep(std::time::Duration::from_millis(100));
+    };
+    let loaded_plugin_ids = || -> Vec<u32> {
+        received_screen_instructions
+            .lock()
+            .unwrap()
+            .iter()
+            .filter_map(|i| match i {
+                ScreenInstruction::AddPlugin(_, _, _, _, _, plugin_id, ..) => Some(*plugin_id),
+                _ => None,
+            })
+            .collect()
+    };
+
+    let _ = plugin_thread_sender.send(PluginInstruction::AddClient(client_id));
+    load_plugin(run_plugin.clone());
+    report_plugin_ids();
+    let first_plugin_id = loaded_plugin_ids()[0];
+    let _ = plugin_thread_sender.send(PluginInstruction::Unload(first_plugin_id));
+    load_plugin(run_plugin.clone());
+    report_plugin_ids();
+    load_plugin(differently_configured_plugin.clone());
+    screen_thread.join().unwrap(); // this might take a while if the cache is cold
+    teardown();
+    let second_plugin_id = loaded_plugin_ids()[1];
+    let reported_stable_plugin_id = |plugin_id: u32| -> String {
+        received_screen_instructions
+            .lock()
+            .unwrap()
+            .iter()
+            .filter_map(|i| match i {
+                ScreenInstruction::PluginBytes(plugin_bytes) => Some(plugin_bytes),
+                _ => None,
+            })
+            .flatten()
+            .filter(|(frame_plugin_id, _, _)| *frame_plugin_id == plugin_id)
+            .find_map(|(_, _, frame)| {
+                String::from_utf8_lossy(frame).lines().find_map(|line| {
+                    line.trim_end()
+                        .strip_prefix("Stable plugin id: ")
+                        .map(|stable_plugin_id| stable_plugin_id.to_owned())
+                })
+            })
+            .expect("plugin reported its stable plugin id")
+    };
+    assert_ne!(
+        first_plugin_id, second_plugin_id,
+        "reloaded plugin was assigned a new plugin id"
+    );
+    assert_eq!(
+        reported_stable_plugin_id(first_plugin_id),
+        reported_stable_plugin_id(second_plugin_id),
+        "reloaded plugin kept its stable plugin id"
+    );
+    assert_eq!(
+        reported_stable_plugin_id(second_plugin_id),
+        run_plugin.stable_plugin_id(),
+        "plugin got the stable plugin id of its location and configuration"
+    );
+    assert_ne!(
+        run_plugin.stable_plugin_id(),
+        differently_configured_plugin.stable_plugin_id(),
+        "the configuration of the plugin is part of its stable plugin id"
+    );
+}
+
+#[test]
+#[ignore]
+pub fn open_terminal_pane_plugin_command() {
+    let temp_folder = tempdir().unwrap(); // placed explicitly in the test scope because its
+                                          // destructor removes the directory
+    let plugin_host_folder = PathBuf::from(temp_folder.path());
+    let cache_path = plugin_host_folder.join("permissions_test.kdl");
+    let (plugin_thread_sender, pty_receiver, screen_receiver, teardown) =
+        create_plugin_thread_with_pty_receiver(Some(plugin_host_folder));
+    let plugin_should_float = Some(false);
+    let plugin_title = Some("test_plugin".to_owned());
+    let run_plugin = RunPlugin {
+        _allow_exec_host_cmd: false,
+        location: RunPluginLocation::File(PathBuf::from(&*PLUGIN_FIXTURE)),
+        configuration: Default::default(),
+    };
+    let tab_index = 1;
+    let client_id = 1;
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let received_pty_instructions = Arc::new(Mutex::new(vec![]));
+    let pty_thread = std::thread::Builder::new()
+        .name("pty thread".to_string())
+        .spawn({
+            let received_pty_instructions = received_pty_instructions.clone();
+            move || loop {
+                let (event, _err_ctx) = pty_receiver
+                    .recv()
+                    .expect("failed to receive event on channel");
+                let pane_requested =
+                    matches!(event, PtyInstruction::SpawnPluginOwnedTerminal { .. });
+                received_pty_instructions.lock().unwrap().push(event);
+                if pane_requested {
+                    break;
+                }
+            }
+        })
+        .unwrap();
+    let received_screen_instructions = Arc::new(Mutex::new(vec![]));
+    let _screen_thread = grant_permissions_and_log_actions_in_thread_naked_variant!(
+        received_screen_instructions,
+        ScreenInstruction::Exit,
+        screen_receiver,
+        1,
+        &PermissionType::OpenTerminalsOrPlugins,
+        cache_path,
+        plugin_thread_sender,
+        client_id
+    );
+
+    let _ = plugin_thread_sender.send(PluginInstruction::AddClient(client_id));
+    let _ = plugin_thread_sender.send(PluginInstruction::Load(
+        plugin_should_float,
+        false,
+        plugin_title,
+        run_plugin,
+        tab_index,
+        None,
+        client_id,
+        size,
+        None,
+    ));
+    std::thread::sleep(std::time::Duration::from_millis(500));
+    let _ = plugin_thread_sender.send(PluginInstruction::Update(vec![(
+        None,
+        Some(client_id),
+        Event::Key(Key::Alt(CharOrArrow::Char('e'))), // triggers the event in the fixture plugin
+    )]));
+    pty_thread.join().unwrap(); // this might take a while if the cache is cold
+    teardown();
+    let spawned_terminal_cwd =
+        received_pty_instructions
+            .lock()
+            .unwrap()
+            .iter()
+            .find_map(|i| match i {
+                PtyInstruction::SpawnPluginOwnedTerminal {
+                    terminal_action: TerminalAction::RunCommand(run_command),
+                    client_id: spawning_client_id,
+                    ..
+                } if *spawning_client_id == client_id => run_command.cwd.clone(),
+                _ => None,
+            });
+    assert_eq!(
+        spawned_terminal_cwd,
+        Some(PathBuf::from("/path/to/my/dir")),
+        "terminal pane opened in the cwd requested by the plugin"
+    );
+}
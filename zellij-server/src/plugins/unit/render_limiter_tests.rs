@@ -0,0 +1,93 @@
+use super::{max_fps_from_configuration, FrameDecision, RenderLimiter};
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+use zellij_utils::input::layout::PluginUserConfiguration;
+
+#[test]
+fn first_frame_is_rendered_immediately() {
+    let mut render_limiter = RenderLimiter::new(10);
+    let now = Instant::now();
+    assert_eq!(
+        render_limiter.frame(1, 1, b"frame".to_vec(), now),
+        FrameDecision::Render(b"frame".to_vec())
+    );
+}
+
+#[test]
+fn frames_above_the_cap_are_coalesced_into_the_latest_one() {
+    let mut render_limiter = RenderLimiter::new(10); // 100ms between frames
+    let now = Instant::now();
+    render_limiter.frame(1, 1, b"frame 1".to_vec(), now);
+    assert_eq!(
+        render_limiter.frame(1, 1, b"frame 2".to_vec(), now + Duration::from_millis(40)),
+        FrameDecision::Defer(Duration::from_millis(60))
+    );
+    assert_eq!(
+        render_limiter.frame(1, 1, b"frame 3".to_vec(), now + Duration::from_millis(50)),
+        FrameDecision::Coalesced
+    );
+    assert_eq!(
+        render_limiter.take_pending_frame(1, 1, now + Duration::from_millis(100)),
+        Some(b"frame 3".to_vec())
+    );
+    assert_eq!(
+        render_limiter.take_pending_frame(1, 1, now + Duration::from_millis(100)),
+        None,
+        "pending frame only sent once"
+    );
+    assert_eq!(
+        render_limiter.frame(1, 1, b"frame 4".to_vec(), now + Duration::from_millis(150)),
+        FrameDecision::Defer(Duration::from_millis(50)),
+        "interval counted from the flushed frame"
+    );
+}
+
+#[test]
+fn frames_are_limited_per_plugin_and_client() {
+    let mut render_limiter = RenderLimiter::new(10);
+    let now = Instant::now();
+    render_limiter.frame(1, 1, b"frame".to_vec(), now);
+    assert_eq!(
+        render_limiter.frame(1, 2, b"frame".to_vec(), now),
+        FrameDecision::Render(b"frame".to_vec())
+    );
+    assert_eq!(
+        render_limiter.frame(2, 1, b"frame".to_vec(), now),
+        FrameDecision::Render(b"frame".to_vec())
+    );
+}
+
+#[test]
+fn max_fps_can_be_overridden_per_plugin() {
+    let mut render_limiter = RenderLimiter::new(10);
+    render_limiter.set_max_fps(1, Some(0));
+    render_limiter.set_max_fps(2, Some(100));
+    let now = Instant::now();
+    render_limiter.frame(1, 1, b"frame 1".to_vec(), now);
+    assert_eq!(
+        render_limiter.frame(1, 1, b"frame 2".to_vec(), now),
+        FrameDecision::Render(b"frame 2".to_vec()),
+        "max_fps 0 disables the limit"
+    );
+    render_limiter.frame(2, 1, b"frame 1".to_vec(), now);
+    assert_eq!(
+        render_limiter.frame(2, 1, b"frame 2".to_vec(), now + Duration::from_millis(20)),
+        FrameDecision::Render(b"frame 2".to_vec())
+    );
+}
+
+#[test]
+fn max_fps_is_read_from_plugin_configuration() {
+    let mut configuration = BTreeMap::new();
+    configuration.insert("max_fps".to_owned(), "30".to_owned());
+    assert_eq!(
+        max_fps_from_configuration(&PluginUserConfiguration::new(configuration)),
+        Some(30)
+    );
+    let mut configuration = BTreeMap::new();
+    configuration.insert("max_fps".to_owned(), "fast".to_owned());
+    assert_eq!(
+        max_fps_from_configuration(&PluginUserConfiguration::new(configuration)),
+        None
+    );
+}
@@ -0,0 +1,99 @@
+use super::PluginRenderQueue;
+use crate::plugins::PluginFrame;
+use crate::ClientId;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+fn frame(plugin_id: u32, index: usize) -> (u32, ClientId, PluginFrame) {
+    let frame = PluginFrame::Full {
+        frame_id: index as u64,
+        bytes: format!("frame {}", index).into_bytes(),
+    };
+    (plugin_id, 1, frame)
+}
+
+fn frame_index(frame: &PluginFrame) -> usize {
+    match frame {
+        PluginFrame::Full { frame_id, .. } | PluginFrame::Changed { frame_id, .. } => {
+            *frame_id as usize
+        },
+    }
+}
+
+#[test]
+fn frames_are_popped_one_plugin_at_a_time() {
+    let render_queue = PluginRenderQueue::new();
+    render_queue.push(vec![frame(1, 0), frame(1, 1), frame(1, 2)]);
+    assert_eq!(render_queue.push(vec![frame(2, 0), frame(3, 0)]), 2);
+    let popped_plugins: Vec<u32> = render_queue
+        .pop_round_robin(5)
+        .iter()
+        .map(|(plugin_id, _, _)| *plugin_id)
+        .collect();
+    assert_eq!(popped_plugins, vec![1, 2, 3, 1, 1]);
+    assert!(render_queue.pop_round_robin(5).is_empty(), "queue drained");
+}
+
+#[test]
+fn round_robin_resumes_after_the_last_served_plugin() {
+    let render_queue = PluginRenderQueue::new();
+    render_queue.push(vec![frame(1, 0), frame(1, 1), frame(2, 0), frame(2, 1)]);
+    assert_eq!(render_queue.pop_round_robin(1), vec![frame(1, 0)]);
+    assert_eq!(render_queue.pop_round_robin(1), vec![frame(2, 0)]);
+    assert_eq!(render_queue.pop_round_robin(1), vec![frame(1, 1)]);
+    assert_eq!(render_queue.pop_round_robin(1), vec![frame(2, 1)]);
+}
+
+#[test]
+fn removed_plugins_have_their_frames_dropped() {
+    let render_queue = PluginRenderQueue::new();
+    render_queue.push(vec![frame(1, 0), frame(2, 0)]);
+    render_queue.remove_plugin(1);
+    assert_eq!(render_queue.pop_round_robin(2), vec![frame(2, 0)]);
+}
+
+#[test]
+fn plugins_make_render_progress_while_another_plugin_floods_the_queue() {
+    let render_queue = PluginRenderQueue::new();
+    let backlog: Vec<_> = (0..10_000).map(|index| frame(1, index)).collect();
+    render_queue.push(backlog);
+    let is_flooding = Arc::new(AtomicBool::new(true));
+    let flooding_plugin = std::thread::spawn({
+        let render_queue = render_queue.clone();
+        let is_flooding = is_flooding.clone();
+        move || {
+            let mut index = 10_000;
+            while is_flooding.load(Ordering::SeqCst) {
+                render_queue.push(vec![frame(1, index)]);
+                index += 1;
+            }
+        }
+    });
+    render_queue.push((0..100).map(|index| frame(2, index)).collect());
+
+    let mut flooding_plugin_frames = vec![];
+    let mut other_plugin_frames = vec![];
+    let mut pops = 0;
+    while other_plugin_frames.len() < 100 {
+        for (plugin_id, _client_id, frame) in render_queue.pop_round_robin(2) {
+            match plugin_id {
+                1 => flooding_plugin_frames.push(frame_index(&frame)),
+                _ => other_plugin_frames.push(frame_index(&frame)),
+            }
+        }
+        pops += 1;
+        assert!(
+            pops <= 100,
+            "other plugin still waiting for its frames behind the flooding plugin"
+        );
+    }
+    is_flooding.store(false, Ordering::SeqCst);
+    flooding_plugin.join().unwrap();
+
+    assert_eq!(other_plugin_frames, (0..100).collect::<Vec<_>>());
+    assert_eq!(
+        flooding_plugin_frames,
+        (0..100).collect::<Vec<_>>(),
+        "flooding plugin kept rendering, in order"
+    );
+}
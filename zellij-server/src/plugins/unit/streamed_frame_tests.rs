@@ -0,0 +1,49 @@
+use super::StreamedFrame;
+
+#[test]
+fn printed_frame_is_shown_when_nothing_was_streamed() {
+    let mut streamed_frame = StreamedFrame::default();
+    assert_eq!(
+        streamed_frame.take(b"printed".to_vec()),
+        Some(b"printed".to_vec())
+    );
+}
+
+#[test]
+fn chunks_are_composited_into_one_frame() {
+    let mut streamed_frame = StreamedFrame::default();
+    // an incomplete frame, dropped once the next one starts
+    streamed_frame.add_chunk(1, b"stale frame".to_vec(), false);
+    streamed_frame.add_chunk(2, b"first chunk\n\r".to_vec(), false);
+    assert_eq!(
+        streamed_frame.take(vec![]),
+        None,
+        "nothing shown before the last chunk"
+    );
+    streamed_frame.add_chunk(2, b"second chunk\n\r".to_vec(), false);
+    streamed_frame.add_chunk(2, b"third chunk".to_vec(), true);
+    assert_eq!(
+        streamed_frame.take(vec![]),
+        Some(b"first chunk\n\rsecond chunk\n\rthird chunk".to_vec())
+    );
+    assert_eq!(
+        streamed_frame.take(b"printed".to_vec()),
+        Some(b"printed".to_vec()),
+        "printed frames are shown again once the plugin stops streaming"
+    );
+}
+
+#[test]
+fn frames_larger_than_the_limit_are_dropped() {
+    let mut streamed_frame = StreamedFrame::new(10);
+    assert!(streamed_frame.add_chunk(1, b"12345".to_vec(), false));
+    assert!(!streamed_frame.add_chunk(1, b"678901".to_vec(), false));
+    assert!(streamed_frame.add_chunk(1, b"last".to_vec(), true));
+    assert_eq!(
+        streamed_frame.take(vec![]),
+        None,
+        "the rest of a dropped frame is not shown"
+    );
+    assert!(streamed_frame.add_chunk(2, b"next".to_vec(), true));
+    assert_eq!(streamed_frame.take(vec![]), Some(b"next".to_vec()));
+}
@@ -1,26 +1,44 @@
 use super::{PluginId, PluginInstruction};
+use crate::plugins::frame_diff::PluginFrameDiffer;
+use crate::plugins::pane_output::PaneOutputBuffer;
+use crate::plugins::plugin_clock::PluginClock;
+use crate::plugins::plugin_fuel::{explain_out_of_fuel, refuel};
 use crate::plugins::plugin_loader::PluginLoader;
-use crate::plugins::plugin_map::{AtomicEvent, PluginEnv, PluginMap, RunningPlugin, Subscriptions};
+use crate::plugins::plugin_map::{
+    AtomicEvent, PluginEnv, PluginMap, RunningPlugin, SessionLayoutCache, Subscriptions,
+};
+use crate::plugins::plugin_metrics::{collect_plugin_metrics, DEFAULT_PLUGIN_METRICS_INTERVAL};
 use crate::plugins::plugin_worker::MessageToWorker;
-use crate::plugins::watch_filesystem::watch_filesystem;
-use crate::plugins::zellij_exports::{wasi_read_string, wasi_write_object};
+use crate::plugins::render_limiter::{
+    max_fps_from_configuration, send_plugin_bytes, RenderLimiter, DEFAULT_MAX_PLUGIN_FPS,
+};
+use crate::plugins::render_queue::PluginRenderQueue;
+use crate::plugins::watch_filesystem::{
+    filter_file_system_event, watch_filesystem, DEBOUNCE_DURATION_MS,
+};
+use crate::plugins::zellij_exports::{
+    wasi_read_bytes, wasi_read_rendered_bytes, wasi_read_string, wasi_write_object,
+};
 use highway::{HighwayHash, PortableHash};
 use log::info;
 use std::{
-    collections::{HashMap, HashSet},
+    collections::{BTreeMap, HashMap, HashSet},
     path::PathBuf,
     str::FromStr,
     sync::{Arc, Mutex},
+    time::{Duration, Instant},
 };
 use wasmer::{Module, Store, Value};
 use zellij_utils::async_channel::Sender;
 use zellij_utils::async_std::task::{self, JoinHandle};
+use zellij_utils::channels;
 use zellij_utils::consts::ZELLIJ_CACHE_DIR;
 use zellij_utils::data::{PermissionStatus, PermissionType};
 use zellij_utils::downloader::download::Download;
 use zellij_utils::downloader::Downloader;
+use zellij_utils::input::parse_keys;
 use zellij_utils::input::permission::PermissionCache;
-use zellij_utils::notify_debouncer_full::{notify::RecommendedWatcher, Debouncer, FileIdMap};
+use zellij_utils::notify_debouncer_full::notify::RecommendedWatcher;
 use zellij_utils::plugin_api::event::ProtobufEvent;
 
 use zellij_utils::prost::Message;
@@ -30,7 +48,7 @@ use crate::{
     ui::loading_indication::LoadingIndication, ClientId,
 };
 use zellij_utils::{
-    data::{Event, EventType, PluginCapabilities},
+    data::{Event, EventType, PaneId, PluginCapabilities, StatusSegment},
     errors::prelude::*,
     input::{
         command::TerminalAction,
@@ -41,6 +59,9 @@ use zellij_utils::{
     pane_size::Size,
 };
 
+// the largest state a plugin can have stored with a serialized session, larger states are dropped
+const MAX_PLUGIN_STATE_SIZE: usize = 64 * 1024; // bytes
+
 pub struct WasmBridge {
     connected_clients: Arc<Mutex<Vec<ClientId>>>,
     plugins: PluginsConfig,
@@ -52,19 +73,30 @@ pub struct WasmBridge {
     next_plugin_id: PluginId,
     cached_events_for_pending_plugins: HashMap<PluginId, Vec<Event>>,
     cached_resizes_for_pending_plugins: HashMap<PluginId, (usize, usize)>, // (rows, columns)
-    cached_worker_messages: HashMap<PluginId, Vec<(ClientId, String, String, String)>>, // Vec<clientid,
+    cached_worker_messages: HashMap<PluginId, Vec<(ClientId, String, String, Vec<u8>)>>, // Vec<clientid,
     // worker_name,
     // message,
     // payload>
     loading_plugins: HashMap<(PluginId, RunPlugin), JoinHandle<()>>, // plugin_id to join-handle
     pending_plugin_reloads: HashSet<RunPlugin>,
+    background_plugins: HashSet<PluginId>, // loaded without a pane, these never render
     path_to_default_shell: PathBuf,
-    watcher: Option<Debouncer<RecommendedWatcher, FileIdMap>>,
+    watcher: Option<RecommendedWatcher>,
     zellij_cwd: PathBuf,
     capabilities: PluginCapabilities,
     client_attributes: ClientAttributes,
     default_shell: Option<TerminalAction>,
     default_layout: Box<Layout>,
+    frame_differ: PluginFrameDiffer,
+    session_layout_cache: Arc<Mutex<SessionLayoutCache>>,
+    clock: Arc<dyn PluginClock>,
+    render_limiter: Arc<Mutex<RenderLimiter>>,
+    render_queue: PluginRenderQueue,
+    status_segments: BTreeMap<(PluginId, u32), StatusSegment>, // (plugin id, render callback id)
+    pending_barriers: Vec<channels::Sender<()>>, // resolved once no plugins are loading
+    plugin_metrics_interval: Duration,
+    plugin_metrics_timer_is_running: bool,
+    paste_command: Option<String>, // reads the clipboard for plugins
 }
 
 impl WasmBridge {
@@ -79,6 +111,9 @@ impl WasmBridge {
         client_attributes: ClientAttributes,
         default_shell: Option<TerminalAction>,
         default_layout: Box<Layout>,
+        clock: Arc<dyn PluginClock>,
+        plugin_metrics_interval: Option<u64>,
+        paste_command: Option<String>,
     ) -> Self {
         let plugin_map = Arc::new(Mutex::new(PluginMap::default()));
         let connected_clients: Arc<Mutex<Vec<ClientId>>> = Arc::new(Mutex::new(vec![]));
@@ -101,11 +136,27 @@ impl WasmBridge {
             cached_worker_messages: HashMap::new(),
             loading_plugins: HashMap::new(),
             pending_plugin_reloads: HashSet::new(),
+            background_plugins: HashSet::new(),
             zellij_cwd,
             capabilities,
             client_attributes,
             default_shell,
             default_layout,
+            frame_differ: PluginFrameDiffer::new(),
+            session_layout_cache: Arc::new(Mutex::new(SessionLayoutCache::default())),
+            clock,
+            render_limiter: Arc::new(Mutex::new(RenderLimiter::new(DEFAULT_MAX_PLUGIN_FPS))),
+            render_queue: PluginRenderQueue::new(),
+            status_segments: BTreeMap::new(),
+            pending_barriers: vec![],
+            // a zero interval (eg. from the command line) would re-arm the timer in a busy loop
+            plugin_metrics_interval: Duration::from_secs(
+                plugin_metrics_interval
+                    .unwrap_or(DEFAULT_PLUGIN_METRICS_INTERVAL)
+                    .max(1),
+            ),
+            plugin_metrics_timer_is_running: false,
+            paste_command,
         }
     }
     pub fn load_plugin(
@@ -140,6 +191,18 @@ impl WasmBridge {
             .with_context(|| format!("failed to resolve plugin {run:?}"))
             .with_context(err_context)?;
         let plugin_name = run.location.to_string();
+        // checked before loading, so that a missing plugin is reported as such rather than as a
+        // failure to read or compile it
+        let missing_plugin_file = match &plugin.location {
+            RunPluginLocation::File(path) if !plugin.wasm_file_is_readable(&self.plugin_dir) => {
+                Some(path.clone())
+            },
+            _ => None,
+        };
+        self.render_limiter
+            .lock()
+            .unwrap()
+            .set_max_fps(plugin_id, max_fps_from_configuration(&run.configuration));
 
         self.cached_events_for_pending_plugins
             .insert(plugin_id, vec![]);
@@ -159,6 +222,9 @@ impl WasmBridge {
             let client_attributes = self.client_attributes.clone();
             let default_shell = self.default_shell.clone();
             let default_layout = self.default_layout.clone();
+            let session_layout_cache = self.session_layout_cache.clone();
+            let clock = self.clock.clone();
+            let paste_command = self.paste_command.clone();
             async move {
                 let _ =
                     senders.send_to_background_jobs(BackgroundJob::AnimatePluginLoading(plugin_id));
@@ -190,26 +256,33 @@ impl WasmBridge {
                     }
                 }
 
-                match PluginLoader::start_plugin(
-                    plugin_id,
-                    client_id,
-                    &plugin,
-                    tab_index,
-                    plugin_dir,
-                    plugin_cache,
-                    senders.clone(),
-                    store,
-                    plugin_map,
-                    size,
-                    connected_clients.clone(),
-                    &mut loading_indication,
-                    path_to_default_shell,
-                    zellij_cwd.clone(),
-                    capabilities,
-                    client_attributes,
-                    default_shell,
-                    default_layout,
-                ) {
+                let started_plugin = match missing_plugin_file {
+                    Some(path) => Err(anyhow!("plugin not found: {}", path.display())),
+                    None => PluginLoader::start_plugin(
+                        plugin_id,
+                        client_id,
+                        &plugin,
+                        tab_index,
+                        plugin_dir,
+                        plugin_cache,
+                        senders.clone(),
+                        store,
+                        plugin_map,
+                        size,
+                        connected_clients.clone(),
+                        &mut loading_indication,
+                        path_to_default_shell,
+                        zellij_cwd.clone(),
+                        capabilities,
+                        client_attributes,
+                        default_shell,
+                        default_layout,
+                        session_layout_cache,
+                        clock,
+                        paste_command,
+                    ),
+                };
+                match started_plugin {
                     Ok(_) => handle_plugin_successful_loading(&senders, plugin_id),
                     Err(e) => handle_plugin_loading_failure(
                         &senders,
@@ -229,6 +302,18 @@ impl WasmBridge {
     }
     pub fn unload_plugin(&mut self, pid: PluginId) -> Result<()> {
         info!("Bye from plugin {}", &pid);
+        self.background_plugins.remove(&pid);
+        self.frame_differ.reset_plugin(pid);
+        self.render_limiter.lock().unwrap().remove_plugin(pid);
+        self.render_queue.remove_plugin(pid);
+        self.remove_status_segments(pid)?;
+        self.session_layout_cache.lock().unwrap().remove_plugin(pid);
+        let _ = self
+            .senders
+            .send_to_screen(ScreenInstruction::ClearPluginOverlay(pid));
+        let _ = self
+            .senders
+            .send_to_screen(ScreenInstruction::ClearPluginPaneOutputSubscriptions(pid));
         let mut plugin_map = self.plugin_map.lock().unwrap();
         for (running_plugin, _, workers) in plugin_map.remove_plugins(pid) {
             for (_worker_name, worker_sender) in workers {
@@ -274,6 +359,9 @@ impl WasmBridge {
             let client_attributes = self.client_attributes.clone();
             let default_shell = self.default_shell.clone();
             let default_layout = self.default_layout.clone();
+            let session_layout_cache = self.session_layout_cache.clone();
+            let clock = self.clock.clone();
+            let paste_command = self.paste_command.clone();
             async move {
                 match PluginLoader::reload_plugin(
                     first_plugin_id,
@@ -290,6 +378,9 @@ impl WasmBridge {
                     client_attributes.clone(),
                     default_shell.clone(),
                     default_layout.clone(),
+                    session_layout_cache.clone(),
+                    clock.clone(),
+                    paste_command.clone(),
                 ) {
                     Ok(_) => {
                         handle_plugin_successful_loading(&senders, first_plugin_id);
@@ -314,6 +405,9 @@ impl WasmBridge {
                                 client_attributes.clone(),
                                 default_shell.clone(),
                                 default_layout.clone(),
+                                session_layout_cache.clone(),
+                                clock.clone(),
+                                paste_command.clone(),
                             ) {
                                 Ok(_) => handle_plugin_successful_loading(&senders, *plugin_id),
                                 Err(e) => handle_plugin_loading_failure(
@@ -360,6 +454,9 @@ impl WasmBridge {
             self.client_attributes.clone(),
             self.default_shell.clone(),
             self.default_layout.clone(),
+            self.session_layout_cache.clone(),
+            self.clock.clone(),
+            self.paste_command.clone(),
         ) {
             Ok(_) => {
                 let _ = self
@@ -379,6 +476,15 @@ impl WasmBridge {
     ) -> Result<()> {
         let err_context = move || format!("failed to resize plugin {pid}");
 
+        if self.background_plugins.contains(&pid) {
+            // there's no pane to render to
+            return Ok(());
+        }
+
+        // the pane reflows the lines of the previous frame to its new size, so they can no longer
+        // be changed line by line
+        self.frame_differ.reset_plugin(pid);
+
         let plugins_to_resize: Vec<(PluginId, ClientId, Arc<Mutex<RunningPlugin>>)> = self
             .plugin_map
             .lock()
@@ -400,6 +506,9 @@ impl WasmBridge {
                     .next_event_id(AtomicEvent::Resize);
                 task::spawn({
                     let senders = self.senders.clone();
+                    let frame_differ = self.frame_differ.clone();
+                    let render_limiter = self.render_limiter.clone();
+                    let render_queue = self.render_queue.clone();
                     let running_plugin = running_plugin.clone();
                     let plugin_id = plugin_id;
                     let client_id = client_id;
@@ -411,38 +520,20 @@ impl WasmBridge {
                             running_plugin.rows = new_rows;
                             running_plugin.columns = new_columns;
 
-                            let rendered_bytes = running_plugin
-                                .instance
-                                .clone()
-                                .exports
-                                .get_function("render")
-                                .map_err(anyError::new)
-                                .and_then(|render| {
-                                    render
-                                        .call(
-                                            &mut running_plugin.store,
-                                            &[
-                                                Value::I32(new_rows as i32),
-                                                Value::I32(new_columns as i32),
-                                            ],
-                                        )
-                                        .map_err(anyError::new)
-                                })
-                                .and_then(|_| wasi_read_string(&running_plugin.plugin_env.wasi_env))
-                                .with_context(err_context);
+                            let rendered_bytes =
+                                render_plugin(&mut running_plugin).with_context(err_context);
                             match rendered_bytes {
-                                Ok(rendered_bytes) => {
-                                    let plugin_bytes = vec![(
-                                        plugin_id,
-                                        client_id,
-                                        rendered_bytes.as_bytes().to_vec(),
-                                    )];
-                                    senders
-                                        .send_to_screen(ScreenInstruction::PluginBytes(
-                                            plugin_bytes,
-                                        ))
-                                        .unwrap();
+                                Ok(Some(rendered_bytes)) => {
+                                    let plugin_bytes = vec![(plugin_id, client_id, rendered_bytes)];
+                                    send_plugin_bytes(
+                                        &render_limiter,
+                                        &frame_differ,
+                                        &render_queue,
+                                        plugin_bytes,
+                                        &senders,
+                                    );
                                 },
+                                Ok(None) => {}, // still streaming its frame with render_chunk
                                 Err(e) => log::error!("{}", e),
                             }
                         }
@@ -458,6 +549,60 @@ impl WasmBridge {
         }
         Ok(())
     }
+    /// Asks the plugins of this client to render again rather than relying on the frames they
+    /// rendered before, eg. for a client reattaching to the session with the same id
+    pub fn rerender_plugins_for_client(
+        &mut self,
+        client_id: ClientId,
+        shutdown_sender: Sender<()>,
+    ) -> Result<()> {
+        let plugins_to_rerender: Vec<(PluginId, Arc<Mutex<RunningPlugin>>)> = self
+            .plugin_map
+            .lock()
+            .unwrap()
+            .running_plugins()
+            .iter()
+            .cloned()
+            .filter(|(plugin_id, plugin_client_id, _running_plugin)| {
+                *plugin_client_id == client_id && !self.background_plugins.contains(plugin_id)
+            })
+            .map(|(plugin_id, _client_id, running_plugin)| (plugin_id, running_plugin))
+            .collect();
+        // the client does not show the frames we diff against anymore
+        self.frame_differ.reset_client(client_id);
+        for (plugin_id, running_plugin) in plugins_to_rerender {
+            task::spawn({
+                let senders = self.senders.clone();
+                let render_limiter = self.render_limiter.clone();
+                let frame_differ = self.frame_differ.clone();
+                let render_queue = self.render_queue.clone();
+                let _s = shutdown_sender.clone();
+                async move {
+                    let mut running_plugin = running_plugin.lock().unwrap();
+                    let _s = _s; // guard to allow the task to complete before cleanup/shutdown
+                    if running_plugin.rows == 0 || running_plugin.columns == 0 {
+                        // not rendered yet, it will be once it is resized
+                        return;
+                    }
+                    match render_plugin(&mut running_plugin) {
+                        Ok(Some(rendered_bytes)) => {
+                            let plugin_bytes = vec![(plugin_id, client_id, rendered_bytes)];
+                            send_plugin_bytes(
+                                &render_limiter,
+                                &frame_differ,
+                                &render_queue,
+                                plugin_bytes,
+                                &senders,
+                            );
+                        },
+                        Ok(None) => {}, // still streaming its frame with render_chunk
+                        Err(e) => log::error!("Failed to rerender plugin {}: {:?}", plugin_id, e),
+                    }
+                }
+            });
+        }
+        Ok(())
+    }
     pub fn update_plugins(
         &mut self,
         mut updates: Vec<(Option<PluginId>, Option<ClientId>, Event)>,
@@ -465,6 +610,12 @@ impl WasmBridge {
     ) -> Result<()> {
         let err_context = || "failed to update plugin state".to_string();
 
+        {
+            let mut session_layout_cache = self.session_layout_cache.lock().unwrap();
+            for (_pid, cid, event) in &updates {
+                session_layout_cache.update(*cid, event);
+            }
+        }
         let plugins_to_update: Vec<(
             PluginId,
             ClientId,
@@ -489,36 +640,55 @@ impl WasmBridge {
                 // FIXME: This is very janky... Maybe I should write my own macro for Event -> EventType?
                 let event_type =
                     EventType::from_str(&event.to_string()).with_context(err_context)?;
+                let (events, event_type) = match &event {
+                    // plugins not subscribed to pastes receive the pasted text as keys
+                    Event::Paste(pasted_text) if !subs.contains(&EventType::Paste) => {
+                        let keys = parse_keys(pasted_text.as_bytes());
+                        (keys.into_iter().map(Event::Key).collect(), EventType::Key)
+                    },
+                    _ => (vec![event.clone()], event_type),
+                };
                 if (subs.contains(&event_type) || event_type == EventType::PermissionRequestResult)
                     && ((pid.is_none() && cid.is_none())
                         || (pid.is_none() && cid == Some(*client_id))
                         || (cid.is_none() && pid == Some(*plugin_id))
                         || (cid == Some(*client_id) && pid == Some(*plugin_id)))
                 {
+                    let is_background_plugin = self.background_plugins.contains(plugin_id);
                     task::spawn({
                         let senders = self.senders.clone();
+                        let frame_differ = self.frame_differ.clone();
+                        let render_limiter = self.render_limiter.clone();
+                        let render_queue = self.render_queue.clone();
                         let running_plugin = running_plugin.clone();
-                        let event = event.clone();
                         let plugin_id = *plugin_id;
                         let client_id = *client_id;
                         let _s = shutdown_sender.clone();
                         async move {
                             let mut running_plugin = running_plugin.lock().unwrap();
-                            let mut plugin_bytes = vec![];
                             let _s = _s; // guard to allow the task to complete before cleanup/shutdown
-                            match apply_event_to_plugin(
-                                plugin_id,
-                                client_id,
-                                &mut running_plugin,
-                                &event,
-                                &mut plugin_bytes,
-                            ) {
-                                Ok(()) => {
-                                    let _ = senders.send_to_screen(ScreenInstruction::PluginBytes(
-                                        plugin_bytes,
-                                    ));
-                                },
-                                Err(e) => {
+                            let mut plugin_bytes = vec![];
+                            let mut applied_events = false;
+                            for event in events {
+                                let event = match filter_file_system_event(
+                                    &event,
+                                    &running_plugin
+                                        .plugin_env
+                                        .file_system_event_filter
+                                        .lock()
+                                        .unwrap(),
+                                ) {
+                                    Some(event) => event,
+                                    None => continue,
+                                };
+                                applied_events = true;
+                                if let Err(e) = apply_event_to_plugin(
+                                    plugin_id,
+                                    client_id,
+                                    &mut running_plugin,
+                                    &event,
+                                    &mut plugin_bytes,
+                                ) {
                                     log::error!("{:?}", e);
 
                                     // https://stackoverflow.com/questions/66450942/in-rust-is-there-a-way-to-make-literal-newlines-in-r-using-windows-c
@@ -530,7 +700,17 @@ impl WasmBridge {
                                         stringified_error,
                                         senders.clone(),
                                     );
-                                },
+                                    return;
+                                }
+                            }
+                            if applied_events && !is_background_plugin {
+                                send_plugin_bytes(
+                                    &render_limiter,
+                                    &frame_differ,
+                                    &render_queue,
+                                    plugin_bytes,
+                                    &senders,
+                                );
                             }
                         }
                     });
@@ -563,9 +743,156 @@ impl WasmBridge {
                 let _ = self.reload_plugin(&run_plugin);
             }
         }
+        self.resolve_barriers_if_no_plugins_are_loading();
+        Ok(())
+    }
+    pub fn resend_plugin_frame(&self, pid: PluginId, client_id: ClientId) {
+        // queued like any other frame, so that it reaches the pane after the frames rendered
+        // before it
+        if let Some(frame) = self.frame_differ.last_frame(pid, client_id) {
+            let frame_count = self.render_queue.push(vec![(pid, client_id, frame)]);
+            let _ = self
+                .senders
+                .send_to_screen(ScreenInstruction::PluginRenderQueued(
+                    self.render_queue.clone(),
+                    frame_count,
+                ));
+        }
+    }
+    /// The barrier is resolved once no plugins are loading, so every instruction sent while
+    /// loading them (eg. their subscriptions) has been handled by then
+    pub fn add_barrier(&mut self, barrier: channels::Sender<()>) {
+        self.pending_barriers.push(barrier);
+        self.resolve_barriers_if_no_plugins_are_loading();
+    }
+    /// Replaces the status-bar segments of this plugin with the ones it just rendered, letting all
+    /// plugins know if any of them changed
+    pub fn update_status_segments(
+        &mut self,
+        plugin_id: PluginId,
+        status_segments: Vec<StatusSegment>,
+    ) -> Result<()> {
+        let status_segments: BTreeMap<(PluginId, u32), StatusSegment> = status_segments
+            .into_iter()
+            .map(|s| ((plugin_id, s.render_callback_id), s))
+            .collect();
+        let previous_status_segments: BTreeMap<(PluginId, u32), StatusSegment> = self
+            .status_segments
+            .iter()
+            .filter(|((pid, _), _)| *pid == plugin_id)
+            .map(|(key, s)| (*key, s.clone()))
+            .collect();
+        if status_segments == previous_status_segments {
+            return Ok(());
+        }
+        self.status_segments.retain(|(pid, _), _| *pid != plugin_id);
+        self.status_segments.extend(status_segments);
+        self.broadcast_status_segments()
+    }
+    fn remove_status_segments(&mut self, plugin_id: PluginId) -> Result<()> {
+        let segment_count = self.status_segments.len();
+        self.status_segments.retain(|(pid, _), _| *pid != plugin_id);
+        if self.status_segments.len() != segment_count {
+            self.broadcast_status_segments()?;
+        }
         Ok(())
     }
+    fn broadcast_status_segments(&self) -> Result<()> {
+        let mut status_segments: Vec<StatusSegment> =
+            self.status_segments.values().cloned().collect();
+        status_segments.sort_by(|a, b| b.priority.cmp(&a.priority));
+        self.senders
+            .send_to_plugin(PluginInstruction::Update(vec![(
+                None,
+                None,
+                Event::StatusSegmentsUpdate(status_segments),
+            )]))
+            .with_context(|| "failed to broadcast status segments")
+    }
+    fn resolve_barriers_if_no_plugins_are_loading(&mut self) {
+        if self.loading_plugins.is_empty() {
+            for barrier in self.pending_barriers.drain(..) {
+                let _ = barrier.send(());
+            }
+        }
+    }
+    /// Sends the output waiting in the buffer of the plugin's subscription to a pane as a single
+    /// `PaneOutput` event. The output is only taken out of the buffer once the plugin is free to
+    /// handle it, see [`PaneOutputBuffer`]
+    pub fn send_pane_output(
+        &mut self,
+        plugin_id: PluginId,
+        pane_id: PaneId,
+        pane_output: PaneOutputBuffer,
+        shutdown_sender: Sender<()>,
+    ) {
+        let running_plugins: Vec<(ClientId, Arc<Mutex<RunningPlugin>>)> = self
+            .plugin_map
+            .lock()
+            .unwrap()
+            .running_plugins_and_subscriptions()
+            .iter()
+            .filter(|(pid, _client_id, _running_plugin, _subscriptions)| *pid == plugin_id)
+            .map(|(_pid, client_id, running_plugin, _subscriptions)| {
+                (*client_id, running_plugin.clone())
+            })
+            .collect();
+        if running_plugins.is_empty()
+            || self
+                .cached_events_for_pending_plugins
+                .contains_key(&plugin_id)
+        {
+            // the plugin is not running (anymore), drop the output so that the buffer is not left
+            // full and the plugin is notified of new output if it does get to it
+            let _ = pane_output.take();
+            return;
+        }
+        let is_background_plugin = self.background_plugins.contains(&plugin_id);
+        task::spawn({
+            let senders = self.senders.clone();
+            let frame_differ = self.frame_differ.clone();
+            let render_limiter = self.render_limiter.clone();
+            let render_queue = self.render_queue.clone();
+            async move {
+                // guard to allow the task to complete before cleanup/shutdown
+                let _s = shutdown_sender;
+                let mut taken_output = None;
+                for (client_id, running_plugin) in running_plugins {
+                    let mut running_plugin = running_plugin.lock().unwrap();
+                    // taken only now that the plugin is free
+                    let output = taken_output.get_or_insert_with(|| pane_output.take());
+                    if output.is_empty() {
+                        return;
+                    }
+                    let event = Event::PaneOutput(pane_id, output.clone());
+                    let mut plugin_bytes = vec![];
+                    if let Err(e) = apply_event_to_plugin(
+                        plugin_id,
+                        client_id,
+                        &mut running_plugin,
+                        &event,
+                        &mut plugin_bytes,
+                    ) {
+                        log::error!("{:?}", e);
+                        let stringified_error = format!("{:?}", e).replace("\n", "\n\r");
+                        handle_plugin_crash(plugin_id, stringified_error, senders.clone());
+                        return;
+                    }
+                    if !is_background_plugin {
+                        send_plugin_bytes(
+                            &render_limiter,
+                            &frame_differ,
+                            &render_queue,
+                            plugin_bytes,
+                            &senders,
+                        );
+                    }
+                }
+            }
+        });
+    }
     pub fn remove_client(&mut self, client_id: ClientId) {
+        self.frame_differ.reset_client(client_id);
         self.connected_clients
             .lock()
             .unwrap()
@@ -579,9 +906,81 @@ impl WasmBridge {
         for plugin_id in &plugin_ids {
             drop(self.unload_plugin(*plugin_id));
         }
-        if let Some(watcher) = self.watcher.take() {
-            watcher.stop_nonblocking();
+        // dropping the watcher stops it
+        self.watcher = None;
+    }
+    /// Loads a plugin that runs for the whole session without a pane, receiving the events it
+    /// subscribes to without ever being rendered
+    pub fn load_background_plugin(
+        &mut self,
+        run_plugin: &RunPlugin,
+        client_id: ClientId,
+    ) -> Result<PluginId> {
+        // background plugins are not tied to a tab
+        let tab_index = 0;
+        let plugin_id = self
+            .load_plugin(
+                run_plugin,
+                tab_index,
+                Size::default(),
+                None,
+                Some(client_id),
+            )
+            .with_context(|| format!("failed to load background plugin {run_plugin:?}"))?;
+        self.background_plugins.insert(plugin_id);
+        Ok(plugin_id)
+    }
+    /// Sends a named message to all instances of the plugin, loading it in the background (without
+    /// a pane) first if it is not loaded
+    pub fn message_plugin(
+        &mut self,
+        run_plugin: &RunPlugin,
+        client_id: ClientId,
+        name: String,
+        payload: Vec<u8>,
+        shutdown_sender: Sender<()>,
+    ) -> Result<()> {
+        let err_context = || format!("failed to send message {name} to plugin {run_plugin:?}");
+        let mut plugin_ids: Vec<PluginId> = self
+            .loading_plugins
+            .keys()
+            .filter(|(_plugin_id, loading_plugin)| loading_plugin.location == run_plugin.location)
+            .map(|(plugin_id, _loading_plugin)| *plugin_id)
+            .collect();
+        plugin_ids.extend(
+            self.all_plugin_ids_for_plugin_location(&run_plugin.location)
+                .unwrap_or_default(),
+        );
+        plugin_ids.sort();
+        plugin_ids.dedup();
+        if plugin_ids.is_empty() {
+            // background plugins are not tied to a tab, and their size is only relevant once they
+            // are rendered in a pane
+            let tab_index = 0;
+            let plugin_id = self
+                .load_plugin(
+                    run_plugin,
+                    tab_index,
+                    Size::default(),
+                    None,
+                    Some(client_id),
+                )
+                .with_context(err_context)?;
+            plugin_ids.push(plugin_id);
         }
+        // messages to plugins that are still loading are cached until they are loaded
+        let updates = plugin_ids
+            .iter()
+            .map(|plugin_id| {
+                (
+                    Some(*plugin_id),
+                    Some(client_id),
+                    Event::CustomMessage(name.clone(), payload.clone()),
+                )
+            })
+            .collect();
+        self.update_plugins(updates, shutdown_sender)
+            .with_context(err_context)
     }
     pub fn run_plugin_of_loading_plugin_id(&self, plugin_id: PluginId) -> Option<&RunPlugin> {
         self.loading_plugins
@@ -595,12 +994,61 @@ impl WasmBridge {
             .unwrap()
             .run_plugin_of_plugin_id(plugin_id)
     }
+    /// Asks a running plugin for the state it would like restored if the session is resurrected,
+    /// plugins that do not implement `serialize_state` or whose state is empty or too large have
+    /// none
+    pub fn serialize_plugin_state(&self, plugin_id: PluginId) -> Option<Vec<u8>> {
+        let running_plugin = self
+            .plugin_map
+            .lock()
+            .unwrap()
+            .get_running_plugin(plugin_id, None)?;
+        let mut running_plugin = running_plugin.lock().unwrap();
+        let running_plugin = &mut *running_plugin;
+        // plugins compiled before this was introduced do not export this function
+        let serialize_state = running_plugin
+            .instance
+            .exports
+            .get_function("serialize_state")
+            .ok()?;
+        let fuel = running_plugin.plugin_env.fuel;
+        refuel(&mut running_plugin.store, &running_plugin.instance, fuel);
+        let state = serialize_state
+            .call(&mut running_plugin.store, &[])
+            .map_err(anyError::new)
+            .and_then(|_| wasi_read_bytes(&running_plugin.plugin_env.wasi_env));
+        let state = explain_out_of_fuel(
+            state,
+            &mut running_plugin.store,
+            &running_plugin.instance,
+            fuel,
+        )
+        .with_context(|| format!("failed to serialize state of plugin {plugin_id}"));
+        match state {
+            Ok(state) if state.len() > MAX_PLUGIN_STATE_SIZE => {
+                log::error!(
+                    "State of plugin {} is {} bytes, more than the maximum of {}, dropping it",
+                    plugin_id,
+                    state.len(),
+                    MAX_PLUGIN_STATE_SIZE
+                );
+                None
+            },
+            Ok(state) if state.is_empty() => None,
+            Ok(state) => Some(state),
+            Err(e) => {
+                log::error!("{:?}", e);
+                None
+            },
+        }
+    }
     fn apply_cached_events_and_resizes_for_plugin(
         &mut self,
         plugin_id: PluginId,
         shutdown_sender: Sender<()>,
     ) -> Result<()> {
         let err_context = || format!("Failed to apply cached events to plugin");
+        let is_background_plugin = self.background_plugins.contains(&plugin_id);
         if let Some(events) = self.cached_events_for_pending_plugins.remove(&plugin_id) {
             let all_connected_clients: Vec<ClientId> = self
                 .connected_clients
@@ -625,6 +1073,9 @@ impl WasmBridge {
                         }
                         task::spawn({
                             let senders = self.senders.clone();
+                            let frame_differ = self.frame_differ.clone();
+                            let render_limiter = self.render_limiter.clone();
+                            let render_queue = self.render_queue.clone();
                             let running_plugin = running_plugin.clone();
                             let client_id = *client_id;
                             let _s = shutdown_sender.clone();
@@ -639,9 +1090,14 @@ impl WasmBridge {
                                     &event,
                                     &mut plugin_bytes,
                                 ) {
+                                    Ok(()) if is_background_plugin => {},
                                     Ok(()) => {
-                                        let _ = senders.send_to_screen(
-                                            ScreenInstruction::PluginBytes(plugin_bytes),
+                                        send_plugin_bytes(
+                                            &render_limiter,
+                                            &frame_differ,
+                                            &render_queue,
+                                            plugin_bytes,
+                                            &senders,
                                         );
                                     },
                                     Err(e) => {
@@ -662,7 +1118,7 @@ impl WasmBridge {
     }
     pub fn apply_cached_worker_messages(&mut self, plugin_id: PluginId) -> Result<()> {
         if let Some(mut messages) = self.cached_worker_messages.remove(&plugin_id) {
-            let mut worker_messages: HashMap<(ClientId, String), Vec<(String, String)>> =
+            let mut worker_messages: HashMap<(ClientId, String), Vec<(String, Vec<u8>)>> =
                 HashMap::new();
             for (client_id, worker_name, message, payload) in messages.drain(..) {
                 worker_messages
@@ -724,7 +1180,7 @@ impl WasmBridge {
         plugin_id: PluginId,
         client_id: ClientId,
         worker_name: String,
-        mut messages: Vec<(String, String)>,
+        mut messages: Vec<(String, Vec<u8>)>,
     ) -> Result<()> {
         let worker =
             self.plugin_map
@@ -753,7 +1209,13 @@ impl WasmBridge {
     }
     pub fn start_fs_watcher_if_not_started(&mut self) {
         if self.watcher.is_none() {
-            self.watcher = match watch_filesystem(self.senders.clone(), &self.zellij_cwd) {
+            let watcher = watch_filesystem(
+                self.senders.clone(),
+                &self.zellij_cwd,
+                Duration::from_millis(DEBOUNCE_DURATION_MS),
+                self.clock.clone(),
+            );
+            self.watcher = match watcher {
                 Ok(watcher) => Some(watcher),
                 Err(e) => {
                     log::error!("Failed to watch filesystem: {:?}", e);
@@ -762,6 +1224,74 @@ impl WasmBridge {
             };
         }
     }
+    /// Lets this plugin close the terminal pane it opened with `open_terminal_pane`
+    pub fn add_owned_pane(&mut self, plugin_id: PluginId, terminal_id: u32) {
+        self.session_layout_cache
+            .lock()
+            .unwrap()
+            .add_owned_pane(plugin_id, terminal_id);
+    }
+    /// Forgets a closed terminal pane, so that the plugin that opened it cannot close whichever
+    /// pane gets its id next
+    pub fn remove_owned_pane(&mut self, terminal_id: u32) {
+        self.session_layout_cache
+            .lock()
+            .unwrap()
+            .remove_closed_pane(terminal_id);
+    }
+    pub fn start_plugin_metrics_timer_if_not_started(&mut self) {
+        if !self.plugin_metrics_timer_is_running {
+            self.plugin_metrics_timer_is_running = true;
+            let senders = self.senders.clone();
+            self.clock.call_after(
+                self.plugin_metrics_interval,
+                Box::new(move || {
+                    let _ = senders.send_to_plugin(PluginInstruction::CollectPluginMetrics);
+                }),
+            );
+        }
+    }
+    /// Sends the metrics of all plugins to the plugins subscribed to them and keeps the timer
+    /// going, or lets it stop if no plugin is subscribed anymore
+    pub fn send_plugin_metrics(&mut self, shutdown_sender: Sender<()>) {
+        self.plugin_metrics_timer_is_running = false;
+        let running_plugins = self
+            .plugin_map
+            .lock()
+            .unwrap()
+            .running_plugins_and_subscriptions();
+        let plugins_are_subscribed = running_plugins.iter().any(
+            |(_plugin_id, _client_id, _running_plugin, subscriptions)| {
+                subscriptions
+                    .lock()
+                    .unwrap()
+                    .contains(&EventType::PluginMetrics)
+            },
+        );
+        if !plugins_are_subscribed {
+            return;
+        }
+        task::spawn({
+            let senders = self.senders.clone();
+            let running_plugins = running_plugins
+                .into_iter()
+                .map(|(plugin_id, _client_id, running_plugin, _subscriptions)| {
+                    (plugin_id, running_plugin)
+                })
+                .collect();
+            let _s = shutdown_sender;
+            async move {
+                let _s = _s; // guard to allow the task to complete before cleanup/shutdown
+                let plugin_metrics = collect_plugin_metrics(running_plugins);
+                let _ = senders.send_to_plugin(PluginInstruction::Update(vec![(
+                    None,
+                    None,
+                    Event::PluginMetrics(plugin_metrics),
+                )]));
+            }
+        });
+        self.start_plugin_metrics_timer_if_not_started();
+    }
     pub fn cache_plugin_permissions(
         &mut self,
         plugin_id: PluginId,
@@ -833,12 +1363,27 @@ fn check_event_permission(
     }
     let permission = match event {
         Event::ModeUpdate(..)
+        | Event::ModeChanged(..)
         | Event::TabUpdate(..)
         | Event::PaneUpdate(..)
         | Event::SessionUpdate(..)
         | Event::CopyToClipboard(..)
-        | Event::SystemClipboardFailure
-        | Event::InputReceived => PermissionType::ReadApplicationState,
+        | Event::SystemClipboardFailure(..)
+        | Event::ClipboardCopySucceeded(..)
+        | Event::InputReceived
+        | Event::InputLatencyStats(..)
+        | Event::PaneClosed(..)
+        | Event::SessionSharingStatus(..)
+        | Event::EnteredCopyMode
+        | Event::ExitedCopyMode
+        | Event::SelectionChanged(..)
+        | Event::HyperlinkClicked(..)
+        | Event::PluginMetrics(..)
+        | Event::Bell(..)
+        | Event::PaneTitleChanged(..)
+        | Event::WorkspaceChanged(..)
+        | Event::SessionActivity(..) => PermissionType::ReadApplicationState,
+        Event::PaneOutput(..) => PermissionType::ReadPaneOutput,
         _ => return (PermissionStatus::Granted, None),
     };
 
@@ -857,6 +1402,24 @@ pub fn apply_event_to_plugin(
     running_plugin: &mut RunningPlugin,
     event: &Event,
     plugin_bytes: &mut Vec<(PluginId, ClientId, Vec<u8>)>,
+) -> Result<()> {
+    let fuel = running_plugin.plugin_env.fuel;
+    refuel(&mut running_plugin.store, &running_plugin.instance, fuel);
+    let applied = apply_event_and_render(plugin_id, client_id, running_plugin, event, plugin_bytes);
+    explain_out_of_fuel(
+        applied,
+        &mut running_plugin.store,
+        &running_plugin.instance,
+        fuel,
+    )
+}
+
+fn apply_event_and_render(
+    plugin_id: PluginId,
+    client_id: ClientId,
+    running_plugin: &mut RunningPlugin,
+    event: &Event,
+    plugin_bytes: &mut Vec<(PluginId, ClientId, Vec<u8>)>,
 ) -> Result<()> {
     let instance = &running_plugin.instance;
     let plugin_env = &running_plugin.plugin_env;
@@ -889,6 +1452,7 @@ pub fn apply_event_to_plugin(
                 should_render = true;
             }
             if rows > 0 && columns > 0 && should_render {
+                let render_start = Instant::now();
                 let rendered_bytes = instance
                     .exports
                     .get_function("render")
@@ -901,9 +1465,18 @@ pub fn apply_event_to_plugin(
                             )
                             .map_err(anyError::new)
                     })
-                    .and_then(|_| wasi_read_string(&plugin_env.wasi_env))
+                    .and_then(|_| wasi_read_rendered_bytes(&plugin_env.wasi_env))
                     .with_context(err_context)?;
-                plugin_bytes.push((plugin_id, client_id, rendered_bytes.as_bytes().to_vec()));
+                running_plugin.last_render_duration = render_start.elapsed();
+                if let Some(frame) = running_plugin
+                    .plugin_env
+                    .take_streamed_frame(rendered_bytes)
+                {
+                    plugin_bytes.push((plugin_id, client_id, frame));
+                }
+            }
+            if should_render {
+                render_status_segments(plugin_id, running_plugin).with_context(err_context)?;
             }
         },
         (PermissionStatus::Denied, permission) => {
@@ -920,6 +1493,79 @@ pub fn apply_event_to_plugin(
     Ok(())
 }
 
+// renders the plugin at its current size, returns None if the plugin is streaming its frame with
+// render_chunk and has not sent the last chunk yet
+fn render_plugin(running_plugin: &mut RunningPlugin) -> Result<Option<Vec<u8>>> {
+    let rows = running_plugin.rows;
+    let columns = running_plugin.columns;
+    let fuel = running_plugin.plugin_env.fuel;
+    refuel(&mut running_plugin.store, &running_plugin.instance, fuel);
+    let render_start = Instant::now();
+    let rendered_bytes = running_plugin
+        .instance
+        .clone()
+        .exports
+        .get_function("render")
+        .map_err(anyError::new)
+        .and_then(|render| {
+            render
+                .call(
+                    &mut running_plugin.store,
+                    &[Value::I32(rows as i32), Value::I32(columns as i32)],
+                )
+                .map_err(anyError::new)
+        })
+        .and_then(|_| wasi_read_rendered_bytes(&running_plugin.plugin_env.wasi_env));
+    running_plugin.last_render_duration = render_start.elapsed();
+    let rendered_bytes = explain_out_of_fuel(
+        rendered_bytes,
+        &mut running_plugin.store,
+        &running_plugin.instance,
+        fuel,
+    )?;
+    Ok(running_plugin
+        .plugin_env
+        .take_streamed_frame(rendered_bytes))
+}
+
+fn render_status_segments(plugin_id: PluginId, running_plugin: &mut RunningPlugin) -> Result<()> {
+    let registered_segments = running_plugin
+        .plugin_env
+        .status_segments
+        .lock()
+        .to_anyhow()?
+        .clone();
+    if registered_segments.is_empty() {
+        return Ok(());
+    }
+    let render_status_segment = running_plugin
+        .instance
+        .exports
+        .get_function("render_status_segment")?
+        .clone();
+    let mut status_segments = vec![];
+    for (render_callback_id, priority) in registered_segments {
+        render_status_segment.call(
+            &mut running_plugin.store,
+            &[Value::I32(render_callback_id as i32)],
+        )?;
+        let content = wasi_read_string(&running_plugin.plugin_env.wasi_env)?;
+        status_segments.push(StatusSegment {
+            plugin_id,
+            render_callback_id,
+            priority,
+            content: content.trim_end_matches('\n').to_owned(),
+        });
+    }
+    running_plugin
+        .plugin_env
+        .senders
+        .send_to_plugin(PluginInstruction::StatusSegmentsRendered(
+            plugin_id,
+            status_segments,
+        ))
+}
+
 pub fn handle_plugin_crash(plugin_id: PluginId, message: String, senders: ThreadSenders) {
     let mut loading_indication = LoadingIndication::new("Panic!".to_owned());
     loading_indication.indicate_loading_error(message);
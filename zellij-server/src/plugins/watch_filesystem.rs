@@ -1,110 +1,125 @@
 use super::PluginInstruction;
 use std::path::PathBuf;
 
+use crate::plugins::plugin_clock::PluginClock;
 use crate::thread_bus::ThreadSenders;
 use std::path::Path;
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 
-use zellij_utils::notify_debouncer_full::{
-    new_debouncer,
-    notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher},
-    DebounceEventResult, Debouncer, FileIdMap,
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use zellij_utils::notify_debouncer_full::notify::{
+    self, Event as NotifyEvent, EventKind, RecommendedWatcher, RecursiveMode, Watcher,
 };
-use zellij_utils::{data::Event, errors::prelude::Result};
+use zellij_utils::{data::Event, errors::prelude::*};
 
-const DEBOUNCE_DURATION_MS: u64 = 400;
+pub const DEBOUNCE_DURATION_MS: u64 = 400;
 
+/// Watches the filesystem under `zellij_cwd`, sending the changes to the plugins in batches once
+/// `debounce_duration` has passed on the plugin clock since the first change of a batch
 pub fn watch_filesystem(
     senders: ThreadSenders,
     zellij_cwd: &Path,
-) -> Result<Debouncer<RecommendedWatcher, FileIdMap>> {
-    let path_prefix_in_plugins = PathBuf::from("/host");
+    debounce_duration: Duration,
+    clock: Arc<dyn PluginClock>,
+) -> Result<RecommendedWatcher> {
     let current_dir = PathBuf::from(zellij_cwd);
-    let mut debouncer = new_debouncer(
-        Duration::from_millis(DEBOUNCE_DURATION_MS),
-        None,
-        move |result: DebounceEventResult| match result {
-            Ok(events) => {
-                let mut create_events = vec![];
-                let mut read_events = vec![];
-                let mut update_events = vec![];
-                let mut delete_events = vec![];
-                for event in events {
-                    match event.kind {
-                        EventKind::Access(_) => read_events.push(event),
-                        EventKind::Create(_) => create_events.push(event),
-                        EventKind::Modify(_) => update_events.push(event),
-                        EventKind::Remove(_) => delete_events.push(event),
-                        _ => {},
-                    }
+    let pending_events: Arc<Mutex<Vec<NotifyEvent>>> = Arc::new(Mutex::new(vec![]));
+    let mut watcher =
+        notify::recommended_watcher(move |result: notify::Result<NotifyEvent>| match result {
+            Ok(event) => {
+                let is_first_event_of_batch = {
+                    let mut pending_events = pending_events.lock().unwrap();
+                    pending_events.push(event);
+                    pending_events.len() == 1
+                };
+                if is_first_event_of_batch {
+                    let pending_events = pending_events.clone();
+                    let senders = senders.clone();
+                    let current_dir = current_dir.clone();
+                    clock.call_after(
+                        debounce_duration,
+                        Box::new(move || {
+                            let events = std::mem::take(&mut *pending_events.lock().unwrap());
+                            send_file_system_events(&senders, events, &current_dir);
+                        }),
+                    );
                 }
-                let create_paths: Vec<PathBuf> = create_events
-                    .drain(..)
-                    .map(|e| {
-                        e.paths
-                            .iter()
-                            .map(|p| {
-                                let stripped_prefix_path =
-                                    p.strip_prefix(&current_dir).unwrap_or_else(|_| p);
-                                path_prefix_in_plugins.join(stripped_prefix_path)
-                            })
-                            .collect()
-                    })
-                    .collect();
-                let read_paths: Vec<PathBuf> = read_events
-                    .drain(..)
-                    .map(|e| {
-                        e.paths
-                            .iter()
-                            .map(|p| {
-                                let stripped_prefix_path =
-                                    p.strip_prefix(&current_dir).unwrap_or_else(|_| p);
-                                path_prefix_in_plugins.join(stripped_prefix_path)
-                            })
-                            .collect()
-                    })
-                    .collect();
-                let update_paths: Vec<PathBuf> = update_events
-                    .drain(..)
-                    .map(|e| {
-                        e.paths
-                            .iter()
-                            .map(|p| {
-                                let stripped_prefix_path =
-                                    p.strip_prefix(&current_dir).unwrap_or_else(|_| p);
-                                path_prefix_in_plugins.join(stripped_prefix_path)
-                            })
-                            .collect()
-                    })
-                    .collect();
-                let delete_paths: Vec<PathBuf> = delete_events
-                    .drain(..)
-                    .map(|e| {
-                        e.paths
-                            .iter()
-                            .map(|p| {
-                                let stripped_prefix_path =
-                                    p.strip_prefix(&current_dir).unwrap_or_else(|_| p);
-                                path_prefix_in_plugins.join(stripped_prefix_path)
-                            })
-                            .collect()
-                    })
-                    .collect();
-                let _ = senders.send_to_plugin(PluginInstruction::Update(vec![
-                    (None, None, Event::FileSystemRead(read_paths)),
-                    (None, None, Event::FileSystemCreate(create_paths)),
-                    (None, None, Event::FileSystemUpdate(update_paths)),
-                    (None, None, Event::FileSystemDelete(delete_paths)),
-                ]));
             },
-            Err(errors) => errors
-                .iter()
-                .for_each(|error| log::error!("watch error: {error:?}")),
-        },
-    )?;
+            Err(error) => log::error!("watch error: {error:?}"),
+        })?;
+    watcher.watch(zellij_cwd, RecursiveMode::Recursive)?;
+    Ok(watcher)
+}
+
+fn send_file_system_events(senders: &ThreadSenders, events: Vec<NotifyEvent>, current_dir: &Path) {
+    let path_prefix_in_plugins = PathBuf::from("/host");
+    let mut create_paths = vec![];
+    let mut read_paths = vec![];
+    let mut update_paths = vec![];
+    let mut delete_paths = vec![];
+    for event in events {
+        let paths = match event.kind {
+            EventKind::Access(_) => &mut read_paths,
+            EventKind::Create(_) => &mut create_paths,
+            EventKind::Modify(_) => &mut update_paths,
+            EventKind::Remove(_) => &mut delete_paths,
+            _ => continue,
+        };
+        for path in event.paths {
+            let stripped_prefix_path = path.strip_prefix(current_dir).unwrap_or(path.as_path());
+            let path_in_plugin = path_prefix_in_plugins.join(stripped_prefix_path);
+            if !paths.contains(&path_in_plugin) {
+                paths.push(path_in_plugin);
+            }
+        }
+    }
+    let _ = senders.send_to_plugin(PluginInstruction::Update(vec![
+        (None, None, Event::FileSystemRead(read_paths)),
+        (None, None, Event::FileSystemCreate(create_paths)),
+        (None, None, Event::FileSystemUpdate(update_paths)),
+        (None, None, Event::FileSystemDelete(delete_paths)),
+    ]));
+}
+
+pub fn file_system_event_filter(path_globs: &[String]) -> Result<Option<GlobSet>> {
+    // paths are matched as the plugin sees them (eg. /host/src/main.rs), so that "*.rs" matches
+    // files in any folder and "/host/src/**" matches only those under src
+    if path_globs.is_empty() {
+        return Ok(None);
+    }
+    let mut glob_set_builder = GlobSetBuilder::new();
+    for path_glob in path_globs {
+        glob_set_builder
+            .add(Glob::new(path_glob).with_context(|| format!("invalid path glob {path_glob}"))?);
+    }
+    Ok(Some(glob_set_builder.build()?))
+}
 
-    debouncer
-        .watcher()
-        .watch(zellij_cwd, RecursiveMode::Recursive)?;
-    Ok(debouncer)
+/// Keeps only the paths of a FileSystem event that match the filter, returning `None` if there
+/// are no such paths so that the plugin is not woken up for nothing. Other events are unchanged.
+pub fn filter_file_system_event(event: &Event, filter: &Option<GlobSet>) -> Option<Event> {
+    let filter = match filter {
+        Some(filter) => filter,
+        None => return Some(event.clone()),
+    };
+    let matching_paths = |paths: &Vec<PathBuf>| -> Option<Vec<PathBuf>> {
+        let matching_paths: Vec<PathBuf> = paths
+            .iter()
+            .filter(|path| filter.is_match(path))
+            .cloned()
+            .collect();
+        if matching_paths.is_empty() {
+            None
+        } else {
+            Some(matching_paths)
+        }
+    };
+    match event {
+        Event::FileSystemCreate(paths) => matching_paths(paths).map(Event::FileSystemCreate),
+        Event::FileSystemRead(paths) => matching_paths(paths).map(Event::FileSystemRead),
+        Event::FileSystemUpdate(paths) => matching_paths(paths).map(Event::FileSystemUpdate),
+        Event::FileSystemDelete(paths) => matching_paths(paths).map(Event::FileSystemDelete),
+        event => Some(event.clone()),
+    }
 }
@@ -1,7 +1,13 @@
-use super::PluginInstruction;
+use super::{PluginInstruction, MAX_STREAMED_FRAME_SIZE};
 use crate::background_jobs::BackgroundJob;
 use crate::plugins::plugin_map::{PluginEnv, Subscriptions};
+use crate::plugins::plugin_persistent_data::{read_plugin_file, write_plugin_file};
+use crate::plugins::plugin_secrets::{
+    reject_secret_references, reject_secret_references_in_layout,
+};
 use crate::plugins::wasm_bridge::handle_plugin_crash;
+use crate::plugins::watch_filesystem::file_system_event_filter;
+use crate::pty::{get_default_shell, PtyInstruction};
 use crate::route::route_action;
 use crate::ServerInstruction;
 use log::{debug, warn};
@@ -12,8 +18,7 @@ use std::{
     process,
     str::FromStr,
     sync::{Arc, Mutex},
-    thread,
-    time::{Duration, Instant},
+    time::Duration,
 };
 use wasmer::{imports, AsStoreMut, Function, FunctionEnv, FunctionEnvMut, Imports};
 use wasmer_wasi::WasiEnv;
@@ -26,12 +31,13 @@ use zellij_utils::input::permission::PermissionCache;
 use url::Url;
 
 use crate::screen::ScreenInstruction;
+use crate::ui::overlay::plugin_overlay::PluginOverlay;
 
 use zellij_utils::{
     consts::{VERSION, ZELLIJ_SESSION_INFO_CACHE_DIR, ZELLIJ_SOCK_DIR},
     data::{
-        CommandToRun, Direction, Event, EventType, FileToOpen, InputMode, PaneId, PluginCommand,
-        PluginIds, PluginMessage, Resize, ResizeStrategy,
+        CommandToRun, Direction, Event, EventType, FileToOpen, InputMode, Keybindings, PaneId,
+        PluginCommand, PluginIds, PluginMessage, Resize, ResizeStrategy,
     },
     errors::prelude::*,
     input::{
@@ -41,13 +47,21 @@ use zellij_utils::{
         plugins::PluginType,
     },
     plugin_api::{
-        plugin_command::ProtobufPluginCommand,
+        event::{ProtobufKeybindings, ProtobufSessionLayout},
+        plugin_command::{PluginFileResult, ProtobufPluginCommand, ReadClipboardResult},
         plugin_ids::{ProtobufPluginIds, ProtobufZellijVersion},
     },
     prost::Message,
     serde,
 };
 
+// calls to focus_self that come sooner than this after the previous one are ignored, so that a
+// misbehaving plugin cannot keep stealing the focus from the user
+const FOCUS_SELF_MIN_INTERVAL: Duration = Duration::from_secs(1);
+
+// the only kinds of urls open_url passes on to the system opener
+const OPENABLE_URL_SCHEMES: &[&str] = &["http", "https", "mailto"];
+
 macro_rules! apply_action {
     ($action:ident, $error_message:ident, $env: ident) => {
         if let Err(e) = route_action(
@@ -84,6 +98,7 @@ pub fn zellij_exports(
 pub struct ForeignFunctionEnv {
     pub plugin_env: PluginEnv,
     pub subscriptions: Arc<Mutex<Subscriptions>>,
+    pub reported_denied_permissions: Arc<Mutex<HashSet<PermissionType>>>,
 }
 
 impl ForeignFunctionEnv {
@@ -91,6 +106,7 @@ impl ForeignFunctionEnv {
         ForeignFunctionEnv {
             plugin_env: plugin_env.clone(),
             subscriptions: subscriptions.clone(),
+            reported_denied_permissions: Arc::new(Mutex::new(HashSet::new())),
         }
     }
 }
@@ -107,6 +123,9 @@ fn host_run_plugin_command(env: FunctionEnvMut<ForeignFunctionEnv>) {
             match check_command_permission(&env.plugin_env, &command) {
                 (PermissionStatus::Granted, _) => match command {
                     PluginCommand::Subscribe(event_list) => subscribe(env, event_list)?,
+                    PluginCommand::SubscribeWithFileSystemFilter(event_list, path_globs) => {
+                        subscribe_with_file_system_filter(env, event_list, path_globs)?
+                    },
                     PluginCommand::Unsubscribe(event_list) => unsubscribe(env, event_list)?,
                     PluginCommand::SetSelectable(selectable) => set_selectable(env, selectable),
                     PluginCommand::GetPluginIds => get_plugin_ids(env),
@@ -244,6 +263,52 @@ fn host_run_plugin_command(env: FunctionEnvMut<ForeignFunctionEnv>) {
                     PluginCommand::RenameSession(new_session_name) => {
                         rename_session(env, new_session_name)
                     },
+                    PluginCommand::PluginReadFile(path) => plugin_read_file(env, path),
+                    PluginCommand::PluginWriteFile(path, contents) => {
+                        plugin_write_file(env, path, contents)
+                    },
+                    PluginCommand::RequestSessionSharingStatus => {
+                        request_session_sharing_status(env)
+                    },
+                    PluginCommand::CopyToClipboard(text) => copy_to_clipboard(env, text),
+                    PluginCommand::LogMessage(message) => log_message(env, message),
+                    PluginCommand::GetSessionLayout => get_session_layout(env),
+                    PluginCommand::OpenUrl(url) => open_url(env, url),
+                    PluginCommand::OpenFileWithSystemOpener(path) => {
+                        open_file_with_system_opener(env, path)
+                    },
+                    PluginCommand::RenderOverlay(x, y, width, height, contents) => {
+                        render_overlay(env, x, y, width, height, contents)
+                    },
+                    PluginCommand::ClearOverlay => clear_overlay(env),
+                    PluginCommand::NewPluginFedPane(pane_name) => {
+                        new_plugin_fed_pane(env, pane_name)
+                    },
+                    PluginCommand::WriteToPaneScrollback(pane_id, lines) => {
+                        write_to_pane_scrollback(env, pane_id, lines)
+                    },
+                    PluginCommand::RegisterStatusSegment(priority, render_callback_id) => {
+                        register_status_segment(env, priority, render_callback_id)?
+                    },
+                    PluginCommand::RequestSessionActivity => request_session_activity(env),
+                    PluginCommand::SubscribePaneOutput(pane_id) => {
+                        subscribe_pane_output(env, pane_id)
+                    },
+                    PluginCommand::UnsubscribePaneOutput(pane_id) => {
+                        unsubscribe_pane_output(env, pane_id)
+                    },
+                    PluginCommand::SetPaneName(name) => set_pane_name(env, name),
+                    PluginCommand::SetTabName(name) => set_tab_name(env, name),
+                    PluginCommand::ReadClipboard => read_clipboard(env),
+                    PluginCommand::FocusSelf => focus_self(env),
+                    PluginCommand::GetKeybindings => get_keybindings(env),
+                    PluginCommand::RenderChunk(frame_id, chunk, is_last) => {
+                        render_chunk(env, frame_id, chunk, is_last)
+                    },
+                    PluginCommand::OpenTerminalPane(cwd, command_to_run) => {
+                        open_terminal_pane(env, cwd, command_to_run)
+                    },
+                    PluginCommand::ClosePane(pane_id) => close_pane(env, pane_id),
                 },
                 (PermissionStatus::Denied, permission) => {
                     log::error!(
@@ -254,6 +319,9 @@ fn host_run_plugin_command(env: FunctionEnvMut<ForeignFunctionEnv>) {
                             .unwrap_or("UNKNOWN".to_owned()),
                         CommandType::from_str(&command.to_string()).with_context(err_context)?
                     );
+                    if let Some(permission) = permission {
+                        report_denied_permission(env, permission);
+                    }
                 },
             };
             Ok(())
@@ -262,6 +330,26 @@ fn host_run_plugin_command(env: FunctionEnvMut<ForeignFunctionEnv>) {
         .non_fatal();
 }
 
+fn report_denied_permission(env: &ForeignFunctionEnv, permission: PermissionType) {
+    // we only let the plugin know the first time, so that it can prompt the user for this
+    // permission without being flooded on every subsequent call
+    let is_first_denial = env
+        .reported_denied_permissions
+        .lock()
+        .map(|mut reported| reported.insert(permission))
+        .unwrap_or(false);
+    if is_first_denial {
+        let _ = env
+            .plugin_env
+            .senders
+            .send_to_plugin(PluginInstruction::Update(vec![(
+                Some(env.plugin_env.plugin_id),
+                Some(env.plugin_env.client_id),
+                Event::PermissionDenied(permission),
+            )]));
+    }
+}
+
 fn subscribe(env: &ForeignFunctionEnv, event_list: HashSet<EventType>) -> Result<()> {
     env.subscriptions
         .lock()
@@ -276,6 +364,16 @@ fn subscribe(env: &ForeignFunctionEnv, event_list: HashSet<EventType>) -> Result
         ))
 }
 
+fn subscribe_with_file_system_filter(
+    env: &ForeignFunctionEnv,
+    event_list: HashSet<EventType>,
+    path_globs: Vec<String>,
+) -> Result<()> {
+    let file_system_event_filter = file_system_event_filter(&path_globs)?;
+    *env.plugin_env.file_system_event_filter.lock().to_anyhow()? = file_system_event_filter;
+    subscribe(env, event_list)
+}
+
 fn unsubscribe(env: &ForeignFunctionEnv, event_list: HashSet<EventType>) -> Result<()> {
     env.subscriptions
         .lock()
@@ -341,6 +439,7 @@ fn get_plugin_ids(env: &ForeignFunctionEnv) {
     let ids = PluginIds {
         plugin_id: env.plugin_env.plugin_id,
         zellij_pid: process::id(),
+        stable_plugin_id: env.plugin_env.plugin.stable_plugin_id(),
     };
     ProtobufPluginIds::try_from(ids)
         .map_err(|e| anyhow!("Failed to serialized plugin ids: {}", e))
@@ -581,13 +680,12 @@ fn set_timeout(env: &ForeignFunctionEnv, secs: f64) {
     let update_target = Some(env.plugin_env.plugin_id);
     let client_id = env.plugin_env.client_id;
     let plugin_name = env.plugin_env.name();
-    // TODO: we should really use an async task for this
-    thread::spawn(move || {
-        let start_time = Instant::now();
-        thread::sleep(Duration::from_secs_f64(secs));
+    let clock = env.plugin_env.clock.clone();
+    let start_time = clock.now();
+    let on_timeout = move || {
         // FIXME: The way that elapsed time is being calculated here is not exact; it doesn't take into account the
         // time it takes an event to actually reach the plugin after it's sent to the `wasm` thread.
-        let elapsed_time = Instant::now().duration_since(start_time).as_secs_f64();
+        let elapsed_time = clock.now().duration_since(start_time).as_secs_f64();
 
         send_plugin_instructions
             .ok_or(anyhow!("found no sender to send plugin instruction to"))
@@ -607,7 +705,10 @@ fn set_timeout(env: &ForeignFunctionEnv, secs: f64) {
                 )
             })
             .non_fatal();
-    });
+    };
+    env.plugin_env
+        .clock
+        .call_after(Duration::from_secs_f64(secs), Box::new(on_timeout));
 }
 
 fn exec_cmd(env: &ForeignFunctionEnv, mut command_line: Vec<String>) {
@@ -684,6 +785,259 @@ fn web_request(
         ));
 }
 
+fn open_url(env: &ForeignFunctionEnv, url: String) {
+    // the system opener would happily open (or run) anything else it knows how to handle, eg. a
+    // local file given as a file:// url
+    let url = match Url::parse(&url) {
+        Ok(parsed_url) if OPENABLE_URL_SCHEMES.contains(&parsed_url.scheme()) => {
+            parsed_url.to_string()
+        },
+        _ => {
+            let _ = env
+                .plugin_env
+                .senders
+                .send_to_plugin(PluginInstruction::Update(vec![(
+                    Some(env.plugin_env.plugin_id),
+                    Some(env.plugin_env.client_id),
+                    Event::SystemOpenerFailure(
+                        url,
+                        format!(
+                            "Only {} urls can be opened",
+                            OPENABLE_URL_SCHEMES.join(", ")
+                        ),
+                    ),
+                )]));
+            return;
+        },
+    };
+    let _ = env
+        .plugin_env
+        .senders
+        .send_to_background_jobs(BackgroundJob::OpenWithSystemOpener(
+            env.plugin_env.plugin_id,
+            env.plugin_env.client_id,
+            url,
+        ));
+}
+
+fn render_overlay(
+    env: &ForeignFunctionEnv,
+    x: usize,
+    y: usize,
+    width: usize,
+    height: usize,
+    contents: Vec<u8>,
+) {
+    let plugin_overlay = PluginOverlay::new(x, y, width, height, contents);
+    let _ = env
+        .plugin_env
+        .senders
+        .send_to_screen(ScreenInstruction::RenderPluginOverlay(
+            env.plugin_env.plugin_id,
+            plugin_overlay,
+        ));
+}
+
+fn clear_overlay(env: &ForeignFunctionEnv) {
+    let _ = env
+        .plugin_env
+        .senders
+        .send_to_screen(ScreenInstruction::ClearPluginOverlay(
+            env.plugin_env.plugin_id,
+        ));
+}
+
+fn new_plugin_fed_pane(env: &ForeignFunctionEnv, pane_name: Option<String>) {
+    let _ = env
+        .plugin_env
+        .senders
+        .send_to_pty(PtyInstruction::SpawnPluginFedPane(
+            pane_name,
+            env.plugin_env.plugin_id,
+            env.plugin_env.client_id,
+        ));
+}
+
+fn write_to_pane_scrollback(env: &ForeignFunctionEnv, pane_id: u32, lines: Vec<String>) {
+    let _ = env
+        .plugin_env
+        .senders
+        .send_to_screen(ScreenInstruction::WriteToPaneScrollback(
+            pane_id,
+            lines,
+            env.plugin_env.plugin_id,
+        ));
+}
+
+fn register_status_segment(
+    env: &ForeignFunctionEnv,
+    priority: u32,
+    render_callback_id: u32,
+) -> Result<()> {
+    // the segment is rendered along with the plugin, the next time it renders
+    env.plugin_env
+        .status_segments
+        .lock()
+        .to_anyhow()?
+        .insert(render_callback_id, priority);
+    Ok(())
+}
+
+fn request_session_activity(env: &ForeignFunctionEnv) {
+    // the log is sent back to the plugin as an event
+    env.plugin_env
+        .senders
+        .send_to_screen(ScreenInstruction::RequestSessionActivity(
+            env.plugin_env.plugin_id,
+            env.plugin_env.client_id,
+        ))
+        .with_context(|| {
+            format!(
+                "failed to request session activity for plugin {}",
+                env.plugin_env.name()
+            )
+        })
+        .non_fatal();
+}
+
+fn subscribe_pane_output(env: &ForeignFunctionEnv, pane_id: PaneId) {
+    // the output is sent to the plugin as PaneOutput events until it unsubscribes
+    env.plugin_env
+        .senders
+        .send_to_screen(ScreenInstruction::SubscribePaneOutput(
+            pane_id,
+            env.plugin_env.plugin_id,
+        ))
+        .with_context(|| {
+            format!(
+                "failed to subscribe plugin {} to the output of pane {:?}",
+                env.plugin_env.name(),
+                pane_id
+            )
+        })
+        .non_fatal();
+}
+
+fn unsubscribe_pane_output(env: &ForeignFunctionEnv, pane_id: PaneId) {
+    env.plugin_env
+        .senders
+        .send_to_screen(ScreenInstruction::UnsubscribePaneOutput(
+            pane_id,
+            env.plugin_env.plugin_id,
+        ))
+        .with_context(|| {
+            format!(
+                "failed to unsubscribe plugin {} from the output of pane {:?}",
+                env.plugin_env.name(),
+                pane_id
+            )
+        })
+        .non_fatal();
+}
+
+fn set_pane_name(env: &ForeignFunctionEnv, name: String) {
+    if name.chars().any(char::is_control) {
+        log::error!(
+            "Plugin {} cannot set a pane name with control characters: {:?}",
+            env.plugin_env.name(),
+            name
+        );
+        return;
+    }
+    env.plugin_env
+        .senders
+        .send_to_screen(ScreenInstruction::RenamePane(
+            PaneId::Plugin(env.plugin_env.plugin_id),
+            name.into_bytes(),
+        ))
+        .with_context(|| {
+            format!(
+                "failed to set pane name of plugin {}",
+                env.plugin_env.name()
+            )
+        })
+        .non_fatal();
+}
+
+fn set_tab_name(env: &ForeignFunctionEnv, name: String) {
+    if name.chars().any(char::is_control) {
+        log::error!(
+            "Plugin {} cannot set a tab name with control characters: {:?}",
+            env.plugin_env.name(),
+            name
+        );
+        return;
+    }
+    // the screen looks up the tab the plugin's pane is currently in
+    env.plugin_env
+        .senders
+        .send_to_screen(ScreenInstruction::RenamePluginTab(
+            env.plugin_env.plugin_id,
+            name,
+        ))
+        .with_context(|| format!("failed to set tab name of plugin {}", env.plugin_env.name()))
+        .non_fatal();
+}
+
+fn focus_self_is_rate_limited(env: &ForeignFunctionEnv) -> bool {
+    let now = env.plugin_env.clock.now();
+    let mut last_focus_self = env.plugin_env.last_focus_self.lock().unwrap();
+    if let Some(last_focus_self) = *last_focus_self {
+        if now.duration_since(last_focus_self) < FOCUS_SELF_MIN_INTERVAL {
+            log::warn!(
+                "Plugin {} asked to focus itself too often, ignoring",
+                env.plugin_env.name()
+            );
+            return true;
+        }
+    }
+    *last_focus_self = Some(now);
+    false
+}
+
+fn focus_self(env: &ForeignFunctionEnv) {
+    if focus_self_is_rate_limited(env) {
+        return;
+    }
+    // unlike show_self, this moves every connected client to the plugin's pane (showing it if it
+    // is hidden), which is why it needs its own permission and is rate-limited
+    let should_float_if_hidden = true;
+    env.plugin_env
+        .senders
+        .send_to_screen(ScreenInstruction::FocusPaneWithIdForAllClients(
+            PaneId::Plugin(env.plugin_env.plugin_id),
+            should_float_if_hidden,
+        ))
+        .with_context(|| format!("failed to focus plugin {}", env.plugin_env.name()))
+        .non_fatal();
+}
+
+fn open_file_with_system_opener(env: &ForeignFunctionEnv, path: PathBuf) {
+    let path = env.plugin_env.plugin_cwd.join(path);
+    if !path.exists() {
+        let _ = env
+            .plugin_env
+            .senders
+            .send_to_plugin(PluginInstruction::Update(vec![(
+                Some(env.plugin_env.plugin_id),
+                Some(env.plugin_env.client_id),
+                Event::SystemOpenerFailure(
+                    path.display().to_string(),
+                    "File does not exist".to_owned(),
+                ),
+            )]));
+        return;
+    }
+    let _ = env
+        .plugin_env
+        .senders
+        .send_to_background_jobs(BackgroundJob::OpenWithSystemOpener(
+            env.plugin_env.plugin_id,
+            env.plugin_env.client_id,
+            path.display().to_string(),
+        ));
+}
+
 fn post_message_to(env: &ForeignFunctionEnv, plugin_message: PluginMessage) -> Result<()> {
     let worker_name = plugin_message
         .worker_name
@@ -751,6 +1105,7 @@ fn new_tabs_with_layout(env: &ForeignFunctionEnv, raw_layout: &str) -> Result<()
         None,
     )
     .map_err(|e| anyhow!("Failed to parse layout: {:?}", e))?;
+    reject_secret_references_in_layout(&layout)?;
     let mut tabs_to_open = vec![];
     let tabs = layout.tabs();
     if tabs.is_empty() {
@@ -1174,6 +1529,7 @@ fn start_or_reload_plugin(env: &ForeignFunctionEnv, url: &str) -> Result<()> {
         _allow_exec_host_cmd: false,
         configuration: PluginUserConfiguration::new(BTreeMap::new()), // TODO: allow passing configuration
     };
+    reject_secret_references(&run_plugin.configuration)?;
     let action = Action::StartOrReloadPlugin(run_plugin);
     apply_action!(action, error_msg, env);
     Ok(())
@@ -1247,6 +1603,266 @@ fn rename_session(env: &ForeignFunctionEnv, new_session_name: String) {
     apply_action!(action, error_msg, env);
 }
 
+fn plugin_read_file(env: &ForeignFunctionEnv, path: PathBuf) {
+    let plugin_persistent_data_dir = &env.plugin_env.plugin_persistent_data_dir;
+    let plugin_file_result = match read_plugin_file(plugin_persistent_data_dir, &path) {
+        Ok(contents) => PluginFileResult {
+            contents,
+            error: None,
+        },
+        Err(e) => PluginFileResult {
+            contents: vec![],
+            error: Some(format!("{:?}", e)),
+        },
+    };
+    wasi_write_object(
+        &env.plugin_env.wasi_env,
+        &plugin_file_result.encode_to_vec(),
+    )
+    .with_context(|| format!("failed to read file for plugin {}", env.plugin_env.name()))
+    .non_fatal();
+}
+
+fn plugin_write_file(env: &ForeignFunctionEnv, path: PathBuf, contents: Vec<u8>) {
+    let error = write_plugin_file(&env.plugin_env.plugin_persistent_data_dir, &path, &contents)
+        .err()
+        .map(|e| format!("{:?}", e));
+    let plugin_file_result = PluginFileResult {
+        contents: vec![],
+        error,
+    };
+    wasi_write_object(
+        &env.plugin_env.wasi_env,
+        &plugin_file_result.encode_to_vec(),
+    )
+    .with_context(|| format!("failed to write file for plugin {}", env.plugin_env.name()))
+    .non_fatal();
+}
+
+fn request_session_sharing_status(env: &ForeignFunctionEnv) {
+    // the status is sent back to the plugin as an event
+    env.plugin_env
+        .senders
+        .send_to_screen(ScreenInstruction::RequestSessionSharingStatus(
+            env.plugin_env.plugin_id,
+            env.plugin_env.client_id,
+        ))
+        .with_context(|| {
+            format!(
+                "failed to request session sharing status for plugin {}",
+                env.plugin_env.name()
+            )
+        })
+        .non_fatal();
+}
+
+fn copy_to_clipboard(env: &ForeignFunctionEnv, text: String) {
+    env.plugin_env
+        .senders
+        .send_to_screen(ScreenInstruction::CopyTextToClipboard(
+            text,
+            env.plugin_env.client_id,
+        ))
+        .with_context(|| {
+            format!(
+                "failed to copy text to clipboard for plugin {}",
+                env.plugin_env.name()
+            )
+        })
+        .non_fatal();
+}
+
+fn read_clipboard(env: &ForeignFunctionEnv) {
+    // the copy command only writes and we don't query the terminal for its OSC 52 clipboard, so
+    // the clipboard can only be read with the paste command
+    let contents = env
+        .plugin_env
+        .paste_command
+        .as_ref()
+        .and_then(|paste_command| match run_paste_command(paste_command) {
+            Ok(contents) => Some(contents),
+            Err(e) => {
+                // plugins are told the clipboard can't be read rather than about the error
+                log::error!(
+                    "Failed to read clipboard for plugin {}: {:?}",
+                    env.plugin_env.name(),
+                    e
+                );
+                None
+            },
+        });
+    let read_clipboard_result = ReadClipboardResult { contents };
+    wasi_write_object(
+        &env.plugin_env.wasi_env,
+        &read_clipboard_result.encode_to_vec(),
+    )
+    .with_context(|| {
+        format!(
+            "failed to read clipboard for plugin {}",
+            env.plugin_env.name()
+        )
+    })
+    .non_fatal();
+}
+
+/// Returns what the paste command printed, the contents of the clipboard
+fn run_paste_command(paste_command: &str) -> Result<String> {
+    let mut command_with_args = paste_command.split(' ');
+    let command = command_with_args.next().unwrap_or_default();
+    let output = process::Command::new(command)
+        .args(command_with_args)
+        .stdin(process::Stdio::null())
+        .stderr(process::Stdio::null())
+        .output()
+        .with_context(|| format!("couldn't spawn {}", command))?;
+    if !output.status.success() {
+        return Err(anyhow!("{} failed ({})", command, output.status));
+    }
+    String::from_utf8(output.stdout)
+        .with_context(|| format!("output of {} is not unicode", command))
+}
+
+fn log_message(env: &ForeignFunctionEnv, message: String) {
+    let plugin_name = env.plugin_env.plugin.location.to_string();
+    for line in message.lines() {
+        env.plugin_env
+            .log_sink
+            .log(&plugin_name, env.plugin_env.plugin_id, line);
+    }
+}
+
+fn get_session_layout(env: &ForeignFunctionEnv) {
+    // this is read from the plugin thread's cache rather than requested from the screen, so that
+    // the plugin gets it right away without blocking on (or blocking) the screen thread
+    let session_layout = env
+        .plugin_env
+        .session_layout_cache
+        .lock()
+        .unwrap()
+        .session_layout(env.plugin_env.client_id);
+    ProtobufSessionLayout::try_from(session_layout)
+        .map_err(|e| anyhow!("Failed to serialize session layout: {}", e))
+        .and_then(|serialized| {
+            wasi_write_object(&env.plugin_env.wasi_env, &serialized.encode_to_vec())?;
+            Ok(())
+        })
+        .with_context(|| {
+            format!(
+                "failed to get session layout for plugin {}",
+                env.plugin_env.name()
+            )
+        })
+        .non_fatal();
+}
+
+fn get_keybindings(env: &ForeignFunctionEnv) {
+    // the client's keybindings are sent with every mode change, until then those the plugin was
+    // loaded with are the best we know
+    let keybindings = Keybindings {
+        modes: env
+            .plugin_env
+            .session_layout_cache
+            .lock()
+            .unwrap()
+            .keybinds(env.plugin_env.client_id)
+            .unwrap_or_else(|| env.plugin_env.client_attributes.keybinds.to_keybinds_vec()),
+    };
+    ProtobufKeybindings::try_from(keybindings)
+        .map_err(|e| anyhow!("Failed to serialize keybindings: {}", e))
+        .and_then(|serialized| {
+            wasi_write_object(&env.plugin_env.wasi_env, &serialized.encode_to_vec())?;
+            Ok(())
+        })
+        .with_context(|| {
+            format!(
+                "failed to get keybindings for plugin {}",
+                env.plugin_env.name()
+            )
+        })
+        .non_fatal();
+}
+
+fn render_chunk(env: &ForeignFunctionEnv, frame_id: u64, chunk: Vec<u8>, is_last: bool) {
+    // the frame is rendered once its last chunk arrives, whatever the plugin printed is not it
+    let fits = env
+        .plugin_env
+        .streamed_frame
+        .lock()
+        .unwrap()
+        .add_chunk(frame_id, chunk, is_last);
+    if !fits {
+        log::error!(
+            "Plugin {} streamed a frame larger than {} bytes, dropping it",
+            env.plugin_env.name(),
+            MAX_STREAMED_FRAME_SIZE
+        );
+    }
+}
+
+fn open_terminal_pane(
+    env: &ForeignFunctionEnv,
+    cwd: PathBuf,
+    command_to_run: Option<CommandToRun>,
+) {
+    let err_context = || {
+        format!(
+            "failed to open terminal pane for plugin {}",
+            env.plugin_env.name()
+        )
+    };
+    let cwd = env.plugin_env.plugin_cwd.join(cwd);
+    let terminal_action = match command_to_run {
+        Some(command_to_run) => TerminalAction::RunCommand(RunCommand {
+            command: command_to_run.path,
+            args: command_to_run.args,
+            cwd: Some(cwd),
+            hold_on_close: true,
+            ..Default::default()
+        }),
+        None => {
+            let mut default_shell = env.plugin_env.default_shell.clone().unwrap_or_else(|| {
+                TerminalAction::RunCommand(RunCommand {
+                    command: get_default_shell(),
+                    ..Default::default()
+                })
+            });
+            default_shell.change_cwd(cwd);
+            default_shell
+        },
+    };
+    // the id of the pane is sent to the plugin with a TerminalPaneOpened event once it is open
+    env.plugin_env
+        .senders
+        .send_to_pty(PtyInstruction::SpawnPluginOwnedTerminal {
+            terminal_action,
+            plugin_id: env.plugin_env.plugin_id,
+            client_id: env.plugin_env.client_id,
+        })
+        .with_context(err_context)
+        .non_fatal();
+}
+
+fn close_pane(env: &ForeignFunctionEnv, pane_id: u32) {
+    let error_msg = || format!("failed to close pane in plugin {}", env.plugin_env.name());
+    // plugins may only close the panes they opened themselves
+    let owns_pane = env
+        .plugin_env
+        .session_layout_cache
+        .lock()
+        .unwrap()
+        .owns_pane(env.plugin_env.plugin_id, pane_id);
+    if !owns_pane {
+        log::error!(
+            "Plugin '{}' tried to close pane {} which it did not open",
+            env.plugin_env.name(),
+            pane_id
+        );
+        return;
+    }
+    let action = Action::CloseTerminalPane(pane_id);
+    apply_action!(action, error_msg, env);
+}
+
 // Custom panic handler for plugins.
 //
 // This is called when a panic occurs in a plugin. Since most panics will likely originate in the
@@ -1264,7 +1880,14 @@ fn report_panic(env: &ForeignFunctionEnv, msg: &str) {
 // Helper Functions ---------------------------------------------------------------------------------------------------
 
 pub fn wasi_read_string(wasi_env: &WasiEnv) -> Result<String> {
-    let err_context = || format!("failed to read string from WASI env '{wasi_env:?}'");
+    wasi_read_rendered_bytes(wasi_env)
+        .map(|buf| String::from_utf8_lossy(&buf).into_owned())
+        .with_context(|| format!("failed to read string from WASI env '{wasi_env:?}'"))
+}
+
+/// Reads what the plugin wrote to its stdout as is, without assuming it is valid UTF-8
+pub fn wasi_read_rendered_bytes(wasi_env: &WasiEnv) -> Result<Vec<u8>> {
+    let err_context = || format!("failed to read bytes from WASI env '{wasi_env:?}'");
 
     let mut buf = vec![];
     wasi_env
@@ -1274,10 +1897,16 @@ pub fn wasi_read_string(wasi_env: &WasiEnv) -> Result<String> {
         .and_then(|stdout| stdout.ok_or(anyhow!("failed to get mutable reference to stdout")))
         .and_then(|mut wasi_file| wasi_file.read_to_end(&mut buf).map_err(anyError::new))
         .with_context(err_context)?;
-    let buf = String::from_utf8_lossy(&buf);
 
     // https://stackoverflow.com/questions/66450942/in-rust-is-there-a-way-to-make-literal-newlines-in-r-using-windows-c
-    Ok(buf.replace("\n", "\n\r"))
+    let mut rendered_bytes = Vec::with_capacity(buf.len());
+    for byte in buf {
+        rendered_bytes.push(byte);
+        if byte == b'\n' {
+            rendered_bytes.push(b'\r');
+        }
+    }
+    Ok(rendered_bytes)
 }
 
 pub fn wasi_write_string(wasi_env: &WasiEnv, buf: &str) -> Result<()> {
@@ -1317,16 +1946,28 @@ fn check_command_permission(
         PluginCommand::OpenFile(..)
         | PluginCommand::OpenFileFloating(..)
         | PluginCommand::OpenFileInPlace(..) => PermissionType::OpenFiles,
+        PluginCommand::OpenUrl(..) => PermissionType::OpenUrls,
+        // the system opener runs executables and .desktop files, which opening a file in an
+        // editor pane does not
+        PluginCommand::OpenFileWithSystemOpener(..) => PermissionType::OpenFilesWithSystemOpener,
         PluginCommand::OpenTerminal(..)
         | PluginCommand::StartOrReloadPlugin(..)
         | PluginCommand::OpenTerminalFloating(..)
-        | PluginCommand::OpenTerminalInPlace(..) => PermissionType::OpenTerminalsOrPlugins,
+        | PluginCommand::OpenTerminalInPlace(..)
+        | PluginCommand::OpenTerminalPane(_, None)
+        | PluginCommand::ClosePane(..)
+        | PluginCommand::NewPluginFedPane(..) => PermissionType::OpenTerminalsOrPlugins,
         PluginCommand::OpenCommandPane(..)
         | PluginCommand::OpenCommandPaneFloating(..)
         | PluginCommand::OpenCommandPaneInPlace(..)
+        | PluginCommand::OpenTerminalPane(_, Some(_))
         | PluginCommand::RunCommand(..)
         | PluginCommand::ExecCmd(..) => PermissionType::RunCommands,
         PluginCommand::WebRequest(..) => PermissionType::WebAccess,
+        PluginCommand::CopyToClipboard(..) => PermissionType::WriteToClipboard,
+        PluginCommand::ReadClipboard => PermissionType::ReadClipboard,
+        PluginCommand::FocusSelf => PermissionType::FocusSelf,
+        PluginCommand::SubscribePaneOutput(..) => PermissionType::ReadPaneOutput,
         PluginCommand::Write(..) | PluginCommand::WriteChars(..) => PermissionType::WriteToStdin,
         PluginCommand::SwitchTabTo(..)
         | PluginCommand::SwitchToMode(..)
@@ -1375,7 +2016,14 @@ fn check_command_permission(
         | PluginCommand::DeleteDeadSession(..)
         | PluginCommand::DeleteAllDeadSessions
         | PluginCommand::RenameSession(..)
-        | PluginCommand::RenameTab(..) => PermissionType::ChangeApplicationState,
+        | PluginCommand::RenameTab(..)
+        | PluginCommand::SetPaneName(..)
+        | PluginCommand::SetTabName(..)
+        | PluginCommand::RenderOverlay(..) => PermissionType::ChangeApplicationState,
+        PluginCommand::RequestSessionSharingStatus
+        | PluginCommand::GetSessionLayout
+        | PluginCommand::GetKeybindings
+        | PluginCommand::RequestSessionActivity => PermissionType::ReadApplicationState,
         _ => return (PermissionStatus::Granted, None),
     };
 
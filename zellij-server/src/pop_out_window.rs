@@ -0,0 +1,45 @@
+//! Opens a new terminal window attaching to a single pane of this session as an isolated view.
+//! The pane itself is only moved out of its tab once the new client attaches, so nothing changes
+//! if the window fails to open.
+
+use std::process::Command;
+
+use zellij_utils::{
+    envs::{SESSION_NAME_ENV_KEY, ZELLIJ_ENV_KEY},
+    errors::prelude::*,
+};
+
+pub(crate) fn launch_pop_out_window(
+    terminal_command: &str,
+    session_name: &str,
+    terminal_id: u32,
+) -> Result<()> {
+    let err_context = || format!("failed to open a pop-out window with {terminal_command:?}");
+
+    let mut terminal_command_with_args = terminal_command.split_whitespace();
+    let terminal = terminal_command_with_args
+        .next()
+        .ok_or_else(|| anyhow!("pop_out_terminal_command is empty"))
+        .with_context(err_context)?;
+    // the server is the zellij executable, so we use it to attach the new window
+    let zellij = std::env::current_exe().with_context(err_context)?;
+    let mut child = Command::new(terminal)
+        .args(terminal_command_with_args)
+        .arg(zellij)
+        .args([
+            "attach",
+            session_name,
+            "--pane-view",
+            &terminal_id.to_string(),
+        ])
+        // otherwise the new client would think it's being started from inside this session
+        .env_remove(ZELLIJ_ENV_KEY)
+        .env_remove(SESSION_NAME_ENV_KEY)
+        .spawn()
+        .with_context(err_context)?;
+    // reap the window's process once it's closed so that it does not linger as a zombie
+    std::thread::spawn(move || {
+        let _ = child.wait();
+    });
+    Ok(())
+}
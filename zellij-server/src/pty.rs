@@ -8,18 +8,22 @@ use crate::{
     ClientId, ServerInstruction,
 };
 use async_std::task::{self, JoinHandle};
-use std::{collections::HashMap, os::unix::io::RawFd, path::PathBuf};
+use std::{
+    collections::{BTreeMap, HashMap},
+    os::unix::io::RawFd,
+    path::PathBuf,
+};
 use zellij_utils::nix::unistd::Pid;
 use zellij_utils::{
     async_std,
-    data::PaneId,
+    data::{Event, PaneId},
     errors::prelude::*,
     errors::{ContextType, PtyContext},
     input::{
         command::{RunCommand, TerminalAction},
         layout::{
             FloatingPaneLayout, Layout, PluginUserConfiguration, Run, RunPlugin, RunPluginLocation,
-            TiledPaneLayout,
+            SplitDirection, TiledPaneLayout,
         },
     },
     pane_size::Size,
@@ -53,6 +57,13 @@ pub enum PtyInstruction {
     SpawnTerminalHorizontally(Option<TerminalAction>, Option<String>, ClientId), // String is an
     // optional pane
     // name
+    SpawnTerminalWithRatio(
+        Option<TerminalAction>,
+        Option<PaneId>, // the pane to split, None - the focused pane of the client
+        SplitDirection,
+        f64, // the part of the space the split pane keeps
+        ClientId,
+    ),
     UpdateActivePane(Option<PaneId>, ClientId),
     GoToTab(TabIndex, ClientId),
     NewTab(
@@ -89,6 +100,17 @@ pub enum PtyInstruction {
         ClientId,
         Size,
     ),
+    SpawnPluginFedPane(Option<String>, u32, ClientId), // String is an optional pane name, u32 is
+    // the id of the plugin feeding the pane
+    QuerySessionEnv(ClientId),
+    SpawnPluginOwnedTerminal {
+        terminal_action: TerminalAction,
+        plugin_id: u32,
+        client_id: ClientId,
+    },
+    SpawnPaneAlias(u32, ClientId), // u32 - the id of the terminal pane to mirror
+    SpawnTerminalsForLayoutInPlace(Vec<Option<Run>>, usize), // the slots of a layout applied in
+    // place that no pane fills, usize is the tab index
     Exit,
 }
 
@@ -99,6 +121,7 @@ impl From<&PtyInstruction> for PtyContext {
             PtyInstruction::OpenInPlaceEditor(..) => PtyContext::OpenInPlaceEditor,
             PtyInstruction::SpawnTerminalVertically(..) => PtyContext::SpawnTerminalVertically,
             PtyInstruction::SpawnTerminalHorizontally(..) => PtyContext::SpawnTerminalHorizontally,
+            PtyInstruction::SpawnTerminalWithRatio(..) => PtyContext::SpawnTerminalWithRatio,
             PtyInstruction::UpdateActivePane(..) => PtyContext::UpdateActivePane,
             PtyInstruction::GoToTab(..) => PtyContext::GoToTab,
             PtyInstruction::ClosePane(_) => PtyContext::ClosePane,
@@ -110,6 +133,13 @@ impl From<&PtyInstruction> for PtyContext {
             PtyInstruction::DumpLayout(..) => PtyContext::DumpLayout,
             PtyInstruction::LogLayoutToHd(..) => PtyContext::LogLayoutToHd,
             PtyInstruction::FillPluginCwd(..) => PtyContext::FillPluginCwd,
+            PtyInstruction::SpawnPluginFedPane(..) => PtyContext::SpawnPluginFedPane,
+            PtyInstruction::QuerySessionEnv(..) => PtyContext::QuerySessionEnv,
+            PtyInstruction::SpawnPluginOwnedTerminal { .. } => PtyContext::SpawnPluginOwnedTerminal,
+            PtyInstruction::SpawnPaneAlias(..) => PtyContext::SpawnPaneAlias,
+            PtyInstruction::SpawnTerminalsForLayoutInPlace(..) => {
+                PtyContext::SpawnTerminalsForLayoutInPlace
+            },
             PtyInstruction::Exit => PtyContext::Exit,
         }
     }
@@ -455,6 +485,39 @@ pub(crate) fn pty_thread_main(mut pty: Pty, layout: Box<Layout>) -> Result<()> {
                     },
                 }
             },
+            PtyInstruction::SpawnTerminalWithRatio(
+                terminal_action,
+                pane_id,
+                direction,
+                ratio,
+                client_id,
+            ) => {
+                let err_context =
+                    || format!("failed to spawn terminal to split a pane for client {client_id}");
+
+                let client_or_pane = match pane_id {
+                    Some(pane_id) => ClientTabIndexOrPaneId::PaneId(pane_id),
+                    None => ClientTabIndexOrPaneId::ClientId(client_id),
+                };
+                match pty
+                    .spawn_terminal(terminal_action, client_or_pane)
+                    .with_context(err_context)
+                {
+                    Ok((terminal_id, _starts_held)) => {
+                        pty.bus
+                            .senders
+                            .send_to_screen(ScreenInstruction::SplitPaneWithRatio {
+                                pane_id,
+                                new_pane_id: PaneId::Terminal(terminal_id),
+                                direction,
+                                ratio,
+                                client_id,
+                            })
+                            .with_context(err_context)?;
+                    },
+                    Err(err) => Err::<(), _>(err).non_fatal(),
+                }
+            },
             PtyInstruction::UpdateActivePane(pane_id, client_id) => {
                 pty.set_active_pane(pane_id, client_id);
             },
@@ -665,6 +728,51 @@ pub(crate) fn pty_thread_main(mut pty: Pty, layout: Box<Layout>) -> Result<()> {
                     size,
                 )?;
             },
+            PtyInstruction::SpawnPluginFedPane(pane_name, plugin_id, client_id) => {
+                pty.spawn_plugin_fed_pane(pane_name, plugin_id, client_id)
+                    .with_context(|| format!("failed to open pane fed by plugin {plugin_id}"))
+                    .non_fatal();
+            },
+            PtyInstruction::QuerySessionEnv(client_id) => {
+                let err_context = || format!("failed to query session env for client {client_id}");
+                let session_env = pty
+                    .bus
+                    .os_input
+                    .as_ref()
+                    .map(|os_input| os_input.get_session_env())
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|(name, value)| format!("{}={}", name, value))
+                    .collect();
+                pty.bus
+                    .senders
+                    .send_to_server(ServerInstruction::Log(session_env, client_id))
+                    .with_context(err_context)?;
+            },
+            PtyInstruction::SpawnPluginOwnedTerminal {
+                terminal_action,
+                plugin_id,
+                client_id,
+            } => {
+                let err_context = || format!("failed to open terminal pane for plugin {plugin_id}");
+                pty.spawn_plugin_owned_terminal(terminal_action, plugin_id, client_id)
+                    .with_context(err_context)
+                    .non_fatal();
+            },
+            PtyInstruction::SpawnPaneAlias(source_terminal_id, client_id) => {
+                let err_context =
+                    || format!("failed to open an alias of pane {source_terminal_id}");
+                pty.spawn_pane_alias(source_terminal_id, client_id)
+                    .with_context(err_context)
+                    .non_fatal();
+            },
+            PtyInstruction::SpawnTerminalsForLayoutInPlace(run_instructions, tab_index) => {
+                let err_context =
+                    || format!("failed to spawn terminals for the layout of tab {tab_index}");
+                pty.spawn_terminals_for_layout_in_place(run_instructions, tab_index)
+                    .with_context(err_context)
+                    .non_fatal();
+            },
             PtyInstruction::Exit => break,
         }
     }
@@ -720,6 +828,7 @@ impl Pty {
                     cwd, // note: this might also be filled by the calling function, eg. spawn_terminal
                     hold_on_close: false,
                     hold_on_start: false,
+                    env: BTreeMap::new(),
                 })
             },
         }
@@ -803,22 +912,7 @@ impl Pty {
             return Ok((terminal_id, starts_held));
         }
 
-        let quit_cb = Box::new({
-            let senders = self.bus.senders.clone();
-            move |pane_id, exit_status, command| {
-                if hold_on_close {
-                    let _ = senders.send_to_screen(ScreenInstruction::HoldPane(
-                        pane_id,
-                        exit_status,
-                        command,
-                        None,
-                        None,
-                    ));
-                } else {
-                    let _ = senders.send_to_screen(ScreenInstruction::ClosePane(pane_id, None));
-                }
-            }
-        });
+        let quit_cb = pane_quit_cb(self.bus.senders.clone(), hold_on_close, None);
         let (terminal_id, pid_primary, child_fd): (u32, RawFd, RawFd) = self
             .bus
             .os_input
@@ -932,6 +1026,60 @@ impl Pty {
         let mut terminals_to_start = vec![];
         terminals_to_start.append(&mut new_pane_pids);
         terminals_to_start.append(&mut new_floating_panes_pids);
+        self.start_terminals_of_layout(terminals_to_start, tab_index)
+            .with_context(err_context)
+    }
+    /// Opens terminals for the slots of a layout applied in place to the tab with this index and
+    /// sends them to the tab to fill the layout with. A slot whose terminal fails to open is
+    /// left out rather than failing the others.
+    pub fn spawn_terminals_for_layout_in_place(
+        &mut self,
+        run_instructions: Vec<Option<Run>>,
+        tab_index: usize,
+    ) -> Result<()> {
+        let err_context = || format!("failed to spawn terminals for the layout of tab {tab_index}");
+
+        let default_shell = self.get_default_terminal(None, None);
+        let mut new_pane_pids = vec![];
+        let mut new_tab_pane_ids: Vec<(u32, Option<RunCommand>, Option<Run>)> = vec![];
+        for run_instruction in run_instructions {
+            match self.apply_run_instruction(
+                run_instruction.clone(),
+                default_shell.clone(),
+                tab_index,
+            ) {
+                Ok(Some((terminal_id, starts_held, run_command, pid_primary))) => {
+                    // Option<RunCommand> should only be Some if the pane starts held
+                    let hold_for_command = if starts_held {
+                        run_command.clone()
+                    } else {
+                        None
+                    };
+                    new_tab_pane_ids.push((terminal_id, hold_for_command, run_instruction));
+                    new_pane_pids.push((terminal_id, starts_held, run_command, pid_primary));
+                },
+                Ok(None) => {},
+                Err(e) => {
+                    log::error!("Failed to spawn terminal for layout: {:?}", e);
+                },
+            }
+        }
+        self.bus
+            .senders
+            .send_to_screen(ScreenInstruction::FillInPlaceLayout(
+                new_tab_pane_ids,
+                tab_index,
+            ))
+            .with_context(err_context)?;
+        self.start_terminals_of_layout(new_pane_pids, tab_index)
+            .with_context(err_context)
+    }
+    fn start_terminals_of_layout(
+        &mut self,
+        terminals_to_start: Vec<(u32, bool, Option<RunCommand>, Result<RawFd>)>,
+        tab_index: usize,
+    ) -> Result<()> {
+        let err_context = || format!("failed to start terminals for layout of tab {tab_index}");
         for (terminal_id, starts_held, run_command, pid_primary) in terminals_to_start {
             if starts_held {
                 // we do not run a command or start listening for bytes on held panes
@@ -999,33 +1147,13 @@ impl Pty {
         // command
         // successfully opened
         let err_context = || format!("failed to apply run instruction");
-        let quit_cb = Box::new({
-            let senders = self.bus.senders.clone();
-            move |pane_id, _exit_status, _command| {
-                let _ = senders.send_to_screen(ScreenInstruction::ClosePane(pane_id, None));
-            }
-        });
+        let quit_cb = pane_quit_cb(self.bus.senders.clone(), false, None);
         match run_instruction {
             Some(Run::Command(mut command)) => {
                 let starts_held = command.hold_on_start;
                 let hold_on_close = command.hold_on_close;
-                let quit_cb = Box::new({
-                    let senders = self.bus.senders.clone();
-                    move |pane_id, exit_status, command| {
-                        if hold_on_close {
-                            let _ = senders.send_to_screen(ScreenInstruction::HoldPane(
-                                pane_id,
-                                exit_status,
-                                command,
-                                Some(tab_index),
-                                None,
-                            ));
-                        } else {
-                            let _ =
-                                senders.send_to_screen(ScreenInstruction::ClosePane(pane_id, None));
-                        }
-                    }
-                });
+                let quit_cb =
+                    pane_quit_cb(self.bus.senders.clone(), hold_on_close, Some(tab_index));
                 if command.cwd.is_none() {
                     if let TerminalAction::RunCommand(cmd) = default_shell {
                         command.cwd = cmd.cwd;
@@ -1185,6 +1313,15 @@ impl Pty {
                     .context("no OS I/O interface found")
                     .and_then(|os_input| os_input.clear_terminal_id(id))
                     .with_context(err_context)?;
+                // the id can now be reused, so plugins that opened the pane may no longer close it
+                // and plugins subscribed to its output should not get that of the next pane
+                let _ = self
+                    .bus
+                    .senders
+                    .send_to_plugin(PluginInstruction::TerminalPaneClosed(id));
+                let _ = self.bus.senders.send_to_screen(
+                    ScreenInstruction::ClearPaneOutputSubscriptions(PaneId::Terminal(id)),
+                );
             },
             PaneId::Plugin(pid) => drop(
                 self.bus
@@ -1219,23 +1356,7 @@ impl Pty {
                 let _ = self.id_to_child_pid.remove(&id); // if all is wlel, this shouldn't be here
 
                 let hold_on_close = run_command.hold_on_close;
-                let quit_cb = Box::new({
-                    let senders = self.bus.senders.clone();
-                    move |pane_id, exit_status, command| {
-                        if hold_on_close {
-                            let _ = senders.send_to_screen(ScreenInstruction::HoldPane(
-                                pane_id,
-                                exit_status,
-                                command,
-                                None,
-                                None,
-                            ));
-                        } else {
-                            let _ =
-                                senders.send_to_screen(ScreenInstruction::ClosePane(pane_id, None));
-                        }
-                    }
-                });
+                let quit_cb = pane_quit_cb(self.bus.senders.clone(), hold_on_close, None);
                 let (pid_primary, child_fd): (RawFd, RawFd) = self
                     .bus
                     .os_input
@@ -1356,6 +1477,95 @@ impl Pty {
         ))?;
         Ok(())
     }
+    pub fn spawn_plugin_fed_pane(
+        &mut self,
+        pane_name: Option<String>,
+        plugin_id: u32,
+        client_id: ClientId,
+    ) -> Result<()> {
+        // the pane only displays what the plugin writes to it, so we reserve a terminal id for it
+        // without starting a process
+        let terminal_id = self
+            .bus
+            .os_input
+            .as_mut()
+            .context("no OS I/O interface found")
+            .and_then(|os_input| os_input.reserve_terminal_id())?;
+        self.bus
+            .senders
+            .send_to_screen(ScreenInstruction::NewPluginFedPane(
+                terminal_id,
+                pane_name,
+                plugin_id,
+                client_id,
+            ))?;
+        // the plugin needs the id to write to the pane
+        self.bus
+            .senders
+            .send_to_plugin(PluginInstruction::TerminalPaneOpened(
+                plugin_id,
+                client_id,
+                terminal_id,
+            ))?;
+        Ok(())
+    }
+    pub fn spawn_pane_alias(&mut self, source_terminal_id: u32, client_id: ClientId) -> Result<()> {
+        // the alias shows the output of the pane it mirrors, so like a pane fed by a plugin it
+        // only needs a terminal id and no process
+        let terminal_id = self
+            .bus
+            .os_input
+            .as_mut()
+            .context("no OS I/O interface found")
+            .and_then(|os_input| os_input.reserve_terminal_id())?;
+        self.bus
+            .senders
+            .send_to_screen(ScreenInstruction::NewPaneAlias(
+                terminal_id,
+                source_terminal_id,
+                client_id,
+            ))?;
+        Ok(())
+    }
+    /// Opens a terminal pane on behalf of a plugin, next to the pane focused by this client, and
+    /// tells the plugin its id
+    pub fn spawn_plugin_owned_terminal(
+        &mut self,
+        terminal_action: TerminalAction,
+        plugin_id: u32,
+        client_id: ClientId,
+    ) -> Result<()> {
+        let client_or_tab_index = ClientTabIndexOrPaneId::ClientId(client_id);
+        let (run_command, invoked_with) = match &terminal_action {
+            TerminalAction::RunCommand(run_command) => (
+                Some(run_command.clone()),
+                Some(Run::Command(run_command.clone())),
+            ),
+            _ => (None, None),
+        };
+        let pane_title = run_command
+            .as_ref()
+            .map(|run_command| run_command.to_string());
+        let (terminal_id, starts_held) =
+            self.spawn_terminal(Some(terminal_action), client_or_tab_index)?;
+        let hold_for_command = if starts_held { run_command } else { None };
+        self.bus.senders.send_to_screen(ScreenInstruction::NewPane(
+            PaneId::Terminal(terminal_id),
+            pane_title,
+            None, // should_float
+            hold_for_command,
+            invoked_with,
+            client_or_tab_index,
+        ))?;
+        self.bus
+            .senders
+            .send_to_plugin(PluginInstruction::TerminalPaneOpened(
+                plugin_id,
+                client_id,
+                terminal_id,
+            ))?;
+        Ok(())
+    }
 }
 
 impl Drop for Pty {
@@ -1369,6 +1579,33 @@ impl Drop for Pty {
     }
 }
 
+/// Returns the callback to run once the process of a pane exits: plugins are told about the exit
+/// status and the pane is then either held open or closed
+pub(crate) fn pane_quit_cb(
+    senders: ThreadSenders,
+    hold_on_close: bool,
+    tab_index: Option<usize>,
+) -> Box<dyn Fn(PaneId, Option<i32>, RunCommand) + Send> {
+    Box::new(move |pane_id, exit_status, command| {
+        let _ = senders.send_to_plugin(PluginInstruction::Update(vec![(
+            None,
+            None,
+            Event::PaneClosed(pane_id, exit_status),
+        )]));
+        if hold_on_close {
+            let _ = senders.send_to_screen(ScreenInstruction::HoldPane(
+                pane_id,
+                exit_status,
+                command,
+                tab_index,
+                None,
+            ));
+        } else {
+            let _ = senders.send_to_screen(ScreenInstruction::ClosePane(pane_id, None));
+        }
+    })
+}
+
 fn send_command_not_found_to_screen(
     senders: ThreadSenders,
     terminal_id: u32,
@@ -1,23 +1,26 @@
 use std::collections::VecDeque;
 use std::sync::{Arc, RwLock};
+use std::time::Instant;
 
 use crate::thread_bus::ThreadSenders;
 use crate::{
     os_input_output::ServerOsApi,
     plugins::PluginInstruction,
     pty::{ClientTabIndexOrPaneId, PtyInstruction},
-    screen::ScreenInstruction,
+    screen::{ScreenInstruction, SwapLayoutDirection},
+    session_listing::list_live_sessions,
     ServerInstruction, SessionMetaData, SessionState,
 };
 use zellij_utils::{
     channels::SenderWithContext,
+    consts::{session_info_cache_file_name, ZELLIJ_SOCK_DIR},
     data::{Direction, Event, PaneId, PluginCapabilities, ResizeStrategy},
     errors::prelude::*,
     input::{
         actions::{Action, SearchDirection, SearchOption},
         command::TerminalAction,
         get_mode_info,
-        layout::Layout,
+        layout::{Layout, PluginUserConfiguration, RunPlugin, RunPluginLocation, SplitDirection},
     },
     ipc::{
         ClientAttributes, ClientToServerMsg, ExitReason, IpcReceiverWithContext, ServerToClientMsg,
@@ -799,6 +802,312 @@ pub(crate) fn route_action(
                 .send_to_screen(ScreenInstruction::RenameSession(name, client_id))
                 .with_context(err_context)?;
         },
+        Action::ToggleRuler => {
+            senders
+                .send_to_screen(ScreenInstruction::ToggleRuler(client_id))
+                .with_context(err_context)?;
+        },
+        Action::PopOutPane => {
+            senders
+                .send_to_screen(ScreenInstruction::PopOutPane(client_id))
+                .with_context(err_context)?;
+        },
+        Action::QuerySessionEnv => {
+            senders
+                .send_to_pty(PtyInstruction::QuerySessionEnv(client_id))
+                .with_context(err_context)?;
+        },
+        Action::GoToTabByName(name) => {
+            senders
+                .send_to_screen(ScreenInstruction::GoToTabByName { name, client_id })
+                .with_context(err_context)?;
+        },
+        Action::TogglePanePassthrough => {
+            senders
+                .send_to_screen(ScreenInstruction::TogglePanePassthrough(client_id))
+                .with_context(err_context)?;
+        },
+        Action::UndoLayoutChange => {
+            senders
+                .send_to_screen(ScreenInstruction::UndoLayoutChange(client_id))
+                .with_context(err_context)?;
+        },
+        Action::SetPaneFrameStyle(pane_frame_style) => {
+            senders
+                .send_to_screen(ScreenInstruction::SetPaneFrameStyle(pane_frame_style))
+                .with_context(err_context)?;
+        },
+        Action::SplitPaneWithRatio(direction, percent) => {
+            let split_direction = match direction {
+                Direction::Left | Direction::Right => SplitDirection::Vertical,
+                Direction::Up | Direction::Down => SplitDirection::Horizontal,
+            };
+            senders
+                .send_to_pty(PtyInstruction::SpawnTerminalWithRatio(
+                    default_shell.clone(),
+                    pane_id,
+                    split_direction,
+                    percent as f64 / 100.0,
+                    client_id,
+                ))
+                .with_context(err_context)?;
+        },
+        Action::FocusPaneById(pane_id, is_plugin) => {
+            let pane_id = if is_plugin {
+                PaneId::Plugin(pane_id)
+            } else {
+                PaneId::Terminal(pane_id)
+            };
+            senders
+                .send_to_screen(ScreenInstruction::FocusPaneById(pane_id, client_id))
+                .with_context(err_context)?;
+        },
+        Action::ToggleShowInvisibles => {
+            senders
+                .send_to_screen(ScreenInstruction::ToggleShowInvisibles(pane_id, client_id))
+                .with_context(err_context)?;
+        },
+        Action::ResizePaneToPreset(preset) => {
+            senders
+                .send_to_screen(ScreenInstruction::ResizePaneToPreset(preset, client_id))
+                .with_context(err_context)?;
+        },
+        Action::SwitchWorkspace(workspace_name) => {
+            senders
+                .send_to_server(ServerInstruction::SwitchWorkspace(workspace_name))
+                .with_context(err_context)?;
+        },
+        Action::SetClientTheme(theme) => {
+            senders
+                .send_to_server(ServerInstruction::SetClientTheme(client_id, theme))
+                .with_context(err_context)?;
+        },
+        Action::AttachToPane(terminal_id) => {
+            senders
+                .send_to_server(ServerInstruction::AttachToPane(terminal_id, client_id))
+                .with_context(err_context)?;
+        },
+        Action::ApplyLayoutToTab(layout) => {
+            senders
+                .send_to_screen(ScreenInstruction::ApplyLayoutToTab {
+                    tab_index: None,
+                    layout,
+                    client_id,
+                })
+                .with_context(err_context)?;
+        },
+        Action::CapturePaneText(include_scrollback) => {
+            senders
+                .send_to_screen(ScreenInstruction::CapturePaneText {
+                    pane_id,
+                    include_scrollback,
+                    client_id,
+                })
+                .with_context(err_context)?;
+        },
+        Action::CaptureRendered => {
+            senders
+                .send_to_screen(ScreenInstruction::CaptureRendered(None, client_id, None))
+                .with_context(err_context)?;
+        },
+        Action::SearchInPane(query, case_sensitive) => {
+            senders
+                .send_to_screen(ScreenInstruction::SearchInPane {
+                    pane_id,
+                    query,
+                    case_sensitive,
+                    client_id,
+                    response: None,
+                })
+                .with_context(err_context)?;
+        },
+        Action::ClearSearch => {
+            senders
+                .send_to_screen(ScreenInstruction::ClearSearch(pane_id, client_id))
+                .with_context(err_context)?;
+        },
+        Action::ClosePanesMatching(predicate) => {
+            senders
+                .send_to_screen(ScreenInstruction::ClosePanesMatching {
+                    predicate,
+                    client_id,
+                })
+                .with_context(err_context)?;
+        },
+        Action::StackPanes(pane_ids) => {
+            senders
+                .send_to_screen(ScreenInstruction::StackPanes {
+                    pane_ids,
+                    client_id,
+                })
+                .with_context(err_context)?;
+        },
+        Action::CycleStack(direction) => {
+            senders
+                .send_to_screen(ScreenInstruction::CycleStack(direction, client_id))
+                .with_context(err_context)?;
+        },
+        Action::DuplicatePane => {
+            senders
+                .send_to_screen(ScreenInstruction::DuplicatePane(pane_id, client_id))
+                .with_context(err_context)?;
+        },
+        Action::FocusNextPaneSpatial => {
+            senders
+                .send_to_screen(ScreenInstruction::FocusNextPaneSpatial(client_id))
+                .with_context(err_context)?;
+        },
+        Action::FocusPaneByName(name) => {
+            senders
+                .send_to_screen(ScreenInstruction::FocusPaneByName { name, client_id })
+                .with_context(err_context)?;
+        },
+        Action::MergePanes(keep_pane_id, absorb_pane_id) => {
+            senders
+                .send_to_screen(ScreenInstruction::MergePanes {
+                    keep_pane_id,
+                    absorb_pane_id,
+                    client_id,
+                })
+                .with_context(err_context)?;
+        },
+        Action::MoveTab(direction) => {
+            senders
+                .send_to_screen(ScreenInstruction::MoveActiveTab(direction, client_id))
+                .with_context(err_context)?;
+        },
+        Action::ResizePaneTo(cols, rows) => {
+            senders
+                .send_to_screen(ScreenInstruction::ResizePaneTo {
+                    pane_id,
+                    cols,
+                    rows,
+                    client_id,
+                })
+                .with_context(err_context)?;
+        },
+        Action::ResizeTabToContent => {
+            senders
+                .send_to_screen(ScreenInstruction::ResizeTabToContent(None, client_id))
+                .with_context(err_context)?;
+        },
+        Action::SetFocusDimming(percent) => {
+            let amount = f64::from(percent.unwrap_or_default()) / 100.0;
+            senders
+                .send_to_screen(ScreenInstruction::SetFocusDimming(
+                    percent.is_some(),
+                    amount,
+                ))
+                .with_context(err_context)?;
+        },
+        Action::SetInputBroadcast(should_broadcast) => {
+            senders
+                .send_to_screen(ScreenInstruction::SetInputBroadcast(
+                    None,
+                    should_broadcast,
+                    client_id,
+                ))
+                .with_context(err_context)?;
+        },
+        Action::SetPaneMinSize(min_cols, min_rows) => {
+            senders
+                .send_to_screen(ScreenInstruction::SetPaneMinSize(
+                    pane_id, min_cols, min_rows, client_id,
+                ))
+                .with_context(err_context)?;
+        },
+        Action::SetPaneReadOnly(read_only) => {
+            senders
+                .send_to_screen(ScreenInstruction::SetPaneReadOnly(
+                    pane_id, read_only, client_id,
+                ))
+                .with_context(err_context)?;
+        },
+        Action::SetSynchronizedScroll(is_active) => {
+            senders
+                .send_to_screen(ScreenInstruction::SetSynchronizedScroll(
+                    None, is_active, client_id,
+                ))
+                .with_context(err_context)?;
+        },
+        Action::SetTabColor(color) => {
+            senders
+                .send_to_screen(ScreenInstruction::SetTabColor(None, color, client_id))
+                .with_context(err_context)?;
+        },
+        Action::SwapTabLayout(tab_position, previous) => {
+            let direction = if previous {
+                SwapLayoutDirection::Previous
+            } else {
+                SwapLayoutDirection::Next
+            };
+            senders
+                .send_to_screen(ScreenInstruction::SwapLayout {
+                    tab_position: (tab_position as usize).saturating_sub(1),
+                    direction,
+                    client_id,
+                })
+                .with_context(err_context)?;
+        },
+        Action::ToggleFloatingPanesInTab(tab_position) => {
+            senders
+                .send_to_screen(ScreenInstruction::ToggleFloatingPanesInTab(
+                    (tab_position as usize).saturating_sub(1),
+                    client_id,
+                ))
+                .with_context(err_context)?;
+        },
+        Action::TogglePaneFrame => {
+            senders
+                .send_to_screen(ScreenInstruction::TogglePaneFrame(pane_id, client_id))
+                .with_context(err_context)?;
+        },
+        Action::TogglePanePinned => {
+            senders
+                .send_to_screen(ScreenInstruction::TogglePanePinned(pane_id, client_id))
+                .with_context(err_context)?;
+        },
+        Action::ToggleZoom => {
+            senders
+                .send_to_screen(ScreenInstruction::ToggleZoom(client_id))
+                .with_context(err_context)?;
+        },
+        Action::SwitchModeForClient(input_mode) => {
+            senders
+                .send_to_screen(ScreenInstruction::ChangeMode(
+                    get_mode_info(input_mode, &client_attributes, capabilities),
+                    client_id,
+                ))
+                .with_context(err_context)?;
+        },
+        Action::SetScrollLines(scroll_lines) => {
+            senders
+                .send_to_screen(ScreenInstruction::SetPaneScrollLines(
+                    None,
+                    scroll_lines,
+                    client_id,
+                ))
+                .with_context(err_context)?;
+        },
+        Action::MessageToPlugin {
+            plugin_url,
+            name,
+            payload,
+        } => match RunPluginLocation::parse(&plugin_url, None) {
+            Ok(location) => {
+                let run_plugin = RunPlugin {
+                    location,
+                    _allow_exec_host_cmd: false,
+                    configuration: PluginUserConfiguration::default(),
+                };
+                senders
+                    .send_to_plugin(PluginInstruction::MessageToPlugin(
+                        run_plugin, client_id, name, payload,
+                    ))
+                    .with_context(err_context)?;
+            },
+            Err(e) => log::error!("Failed to parse plugin url {}: {}", plugin_url, e),
+        },
     }
     Ok(should_break)
 }
@@ -840,8 +1149,29 @@ pub(crate) fn route_thread_main(
                 >|
                  -> Result<bool> {
                     let mut should_break = false;
+                    // observers see the session but can't send it any input or control it, the
+                    // only thing they can do is leave it
+                    let is_observer = session_state
+                        .read()
+                        .to_anyhow()
+                        .with_context(err_context)?
+                        .is_observer(client_id);
                     match instruction {
                         ClientToServerMsg::Action(action, maybe_pane_id, maybe_client_id) => {
+                            if is_observer && !matches!(action, Action::Detach) {
+                                log::debug!("Dropping input from observer client {client_id}");
+                                // the client blocks its input thread until the action is handled
+                                let _ = os_input.send_to_client(
+                                    client_id,
+                                    ServerToClientMsg::UnblockInputThread,
+                                );
+                                return Ok(should_break);
+                            }
+                            session_state
+                                .write()
+                                .to_anyhow()
+                                .with_context(err_context)?
+                                .record_client_input(client_id, Instant::now());
                             let client_id = maybe_client_id.unwrap_or(client_id);
                             if let Some(rlocked_sessions) = rlocked_sessions.as_ref() {
                                 if let Action::SwitchToMode(input_mode) = action {
@@ -870,6 +1200,8 @@ pub(crate) fn route_thread_main(
                             }
                         },
                         ClientToServerMsg::TerminalResize(new_size) => {
+                            // observers are left out of the minimal size unless there are only
+                            // observers, see SessionState::min_client_terminal_size
                             session_state
                                 .write()
                                 .to_anyhow()
@@ -956,12 +1288,16 @@ pub(crate) fn route_thread_main(
                             opts,
                             tab_position_to_focus,
                             pane_id_to_focus,
+                            is_pane_view,
+                            is_observer,
                         ) => {
                             let attach_client_instruction = ServerInstruction::AttachClient(
                                 client_attributes,
                                 opts,
                                 tab_position_to_focus,
                                 pane_id_to_focus,
+                                is_pane_view,
+                                is_observer,
                                 client_id,
                             );
                             to_server
@@ -974,6 +1310,9 @@ pub(crate) fn route_thread_main(
                             let _ = to_server.send(ServerInstruction::RemoveClient(client_id));
                             return Ok(true);
                         },
+                        ClientToServerMsg::KillSession if is_observer => {
+                            log::error!("Observer client {client_id} can't kill the session");
+                        },
                         ClientToServerMsg::KillSession => {
                             to_server
                                 .send(ServerInstruction::KillSession)
@@ -983,13 +1322,40 @@ pub(crate) fn route_thread_main(
                             let _ = to_server.send(ServerInstruction::ConnStatus(client_id));
                             should_break = true;
                         },
+                        ClientToServerMsg::DetachSession(ref client_ids)
+                            if is_observer && client_ids.iter().any(|id| *id != client_id) =>
+                        {
+                            log::error!(
+                                "Observer client {client_id} can't detach other clients: {:?}",
+                                client_ids
+                            );
+                            should_break = true;
+                        },
                         ClientToServerMsg::DetachSession(client_id) => {
                             let _ = to_server.send(ServerInstruction::DetachSession(client_id));
                             should_break = true;
                         },
+                        ClientToServerMsg::Ping(request_id) => {
+                            send_to_screen_or_retry_queue!(
+                                rlocked_sessions,
+                                ScreenInstruction::Ping(request_id, client_id),
+                                instruction,
+                                retry_queue
+                            )
+                            .with_context(err_context)?;
+                        },
                         ClientToServerMsg::ListClients => {
                             let _ = to_server.send(ServerInstruction::ActiveClients(client_id));
                         },
+                        ClientToServerMsg::ListSessions => {
+                            // we list the sessions here rather than in the server thread, because
+                            // checking that a session is alive includes asking this one
+                            let session_list =
+                                list_live_sessions(&*ZELLIJ_SOCK_DIR, session_info_cache_file_name);
+                            let _ = to_server
+                                .send(ServerInstruction::ListSessions(session_list, client_id));
+                            should_break = true;
+                        },
                     }
                     Ok(should_break)
                 };
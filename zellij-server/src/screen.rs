@@ -5,43 +5,52 @@ use std::collections::{BTreeMap, HashMap, HashSet};
 use std::path::PathBuf;
 use std::rc::Rc;
 use std::str;
-use std::time::Duration;
+use std::time::{Duration, Instant};
+
+use globset::Glob;
 
 use zellij_utils::data::{
-    Direction, PaneManifest, PaneToResizeByPercent, PluginPermission, Resize, ResizeStrategy,
-    SessionInfo,
+    Direction, PaneManifest, PaneToResizeByPercent, PluginPermission, Resize, ResizePreset,
+    ResizeStrategy, SessionInfo, SessionSharingStatus,
 };
 use zellij_utils::errors::prelude::*;
+use zellij_utils::input::actions::PanePredicate;
 use zellij_utils::input::command::RunCommand;
-use zellij_utils::input::options::Clipboard;
+use zellij_utils::input::options::{Bell, Clipboard, PaneFrameStyle, ScrollLines, SurplusPanes};
 use zellij_utils::pane_size::{Size, SizeInPixels};
 use zellij_utils::{
-    consts::{session_info_folder_for_session, ZELLIJ_SOCK_DIR},
-    envs::set_session_name,
+    channels::{RecvTimeoutError, Sender},
     input::command::TerminalAction,
     input::layout::{
         FloatingPaneLayout, Layout, PluginUserConfiguration, Run, RunPlugin, RunPluginLocation,
-        SwapFloatingLayout, SwapTiledLayout, TiledPaneLayout,
+        SplitDirection, SwapFloatingLayout, SwapTiledLayout, TiledPaneLayout,
     },
     position::Position,
 };
 
 use crate::background_jobs::BackgroundJob;
+use crate::input_latency::InputLatencyTracker;
+use crate::layout_undo::{LayoutChange, LayoutUndoStack, DEFAULT_LAYOUT_UNDO_DEPTH};
 use crate::os_input_output::ResizeCache;
 use crate::panes::alacritty_functions::xparse_color;
 use crate::panes::terminal_character::AnsiCode;
+use crate::pop_out_window::launch_pop_out_window;
+use crate::session_activity::{SessionActivityLog, DEFAULT_SESSION_ACTIVITY_LOG_SIZE};
 use crate::session_layout_metadata::{PaneLayoutMetadata, SessionLayoutMetadata};
 
 use crate::{
-    output::Output,
-    panes::sixel::SixelImageStore,
-    plugins::PluginInstruction,
+    output::{Output, RenderedGrid},
+    panes::{sixel::SixelImageStore, PaneSearchMatches},
+    plugins::{
+        PaneOutputBuffer, PluginFrame, PluginInstruction, PluginRenderQueue,
+        MAX_BUFFERED_PANE_OUTPUT,
+    },
     pty::{ClientTabIndexOrPaneId, PtyInstruction, VteBytes},
-    tab::Tab,
+    tab::{Pane, Tab},
     thread_bus::Bus,
     ui::{
         loading_indication::LoadingIndication,
-        overlay::{Overlay, OverlayWindow},
+        overlay::{plugin_overlay::PluginOverlay, Overlay, OverlayWindow, Overlayable},
     },
     ClientId, ServerInstruction,
 };
@@ -137,11 +146,19 @@ type InitialTitle = String;
 type ShouldFloat = bool;
 type HoldForCommand = Option<RunCommand>;
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SwapLayoutDirection {
+    Next,
+    Previous,
+}
+
 /// Instructions that can be sent to the [`Screen`].
 #[derive(Debug, Clone)]
 pub enum ScreenInstruction {
     PtyBytes(u32, VteBytes),
     PluginBytes(Vec<(u32, ClientId, VteBytes)>), // u32 is plugin_id
+    PluginRenderQueued(PluginRenderQueue, usize), // usize is the number of frames queued
+    PluginLogLine(u32, ClientId, String),        // u32 is plugin_id
     Render,
     NewPane(
         PaneId,
@@ -259,6 +276,8 @@ pub enum ScreenInstruction {
         ClientId,
         Option<usize>,       // tab position to focus
         Option<(u32, bool)>, // (pane_id, is_plugin) => pane_id to focus
+        bool,                // view only the pane to focus, popped out of its tab
+        bool,                // attach as an observer that can't send input
     ),
     RemoveClient(ClientId),
     AddOverlay(Overlay, ClientId),
@@ -272,6 +291,7 @@ pub enum ScreenInstruction {
     SearchToggleWholeWord(ClientId),
     SearchToggleWrap(ClientId),
     AddRedPaneFrameColorOverride(Vec<PaneId>, Option<String>), // Option<String> => optional error text
+    AddBellPaneFrameColorOverride(Vec<PaneId>),
     ClearPaneFrameColorOverride(Vec<PaneId>),
     PreviousSwapLayout(ClientId),
     NextSwapLayout(ClientId),
@@ -301,6 +321,7 @@ pub enum ScreenInstruction {
     LaunchPlugin(RunPlugin, bool, bool, Option<PaneId>, ClientId), // bools are: should_float, should_open_in_place Option<PaneId> is the pane id to replace
     SuppressPane(PaneId, ClientId),                                // bool is should_float
     FocusPaneWithId(PaneId, bool, ClientId),                       // bool is should_float
+    FocusPaneWithIdForAllClients(PaneId, bool),                    // bool is should_float
     RenamePane(PaneId, Vec<u8>),
     RenameTab(usize, Vec<u8>),
     RequestPluginPermissions(
@@ -323,6 +344,140 @@ pub enum ScreenInstruction {
     ),
     DumpLayoutToHd,
     RenameSession(String, ClientId), // String -> new name
+    SessionRenamed(String),          // String -> new name, once the server renamed the session
+    ToggleRuler(ClientId),
+    SwapLayout {
+        tab_position: usize, // starting at 0, whether or not it is the active tab of a client
+        direction: SwapLayoutDirection,
+        client_id: ClientId,
+    },
+    FocusNextPaneSpatial(ClientId),
+    CaptureRendered(
+        Option<PaneId>, // None captures the whole screen
+        ClientId,
+        Option<Sender<Option<RenderedGrid>>>, // None replies to the client with the grid as JSON
+    ),
+    FocusPaneById(PaneId, ClientId),
+    RequestSessionSharingStatus(
+        u32, // u32 - plugin_id
+        ClientId,
+    ),
+    FocusPaneByName {
+        name: String,
+        client_id: ClientId,
+    },
+    PopOutPane(ClientId),
+    CopyTextToClipboard(String, ClientId),
+    SetPaneMinSize(
+        Option<PaneId>, // None - the focused pane of the client
+        usize,          // min cols
+        usize,          // min rows
+        ClientId,
+    ),
+    ResizePaneTo {
+        pane_id: Option<PaneId>, // None - the focused pane of the client
+        cols: usize,
+        rows: usize,
+        client_id: ClientId,
+    },
+    ResizePaneToPreset(ResizePreset, ClientId),
+    ToggleShowInvisibles(Option<PaneId>, ClientId), // None - the focused pane of the client
+    TogglePanePinned(Option<PaneId>, ClientId),     // None - the focused pane of the client
+    SetInputBroadcast(Option<usize>, bool, ClientId), // None - the active tab of the client
+    StackPanes {
+        pane_ids: Vec<PaneId>,
+        client_id: ClientId,
+    },
+    CycleStack(Direction, ClientId),
+    RenderPluginOverlay(u32, PluginOverlay), // u32 - plugin_id
+    ClearPluginOverlay(u32),                 // u32 - plugin_id
+    TogglePaneFrame(Option<PaneId>, ClientId), // None - the focused pane of the client
+    ToggleZoom(ClientId),
+    NewPluginFedPane(u32, Option<String>, u32, ClientId), // terminal id, optional pane name,
+    // plugin id
+    WriteToPaneScrollback(u32, Vec<String>, u32), // terminal id, lines, plugin id
+    DuplicatePane(Option<PaneId>, ClientId),      // None - the focused pane of the client
+    SearchInPane {
+        pane_id: Option<PaneId>, // None - the focused pane of the client
+        query: String,
+        case_sensitive: bool,
+        client_id: ClientId,
+        // the matches, None if the pane cannot be searched - without a response the client is
+        // only told about errors
+        response: Option<Sender<Option<PaneSearchMatches>>>,
+    },
+    ClearSearch(Option<PaneId>, ClientId), // None - the focused pane of the client
+    MoveTab {
+        from_index: usize,
+        to_index: usize,
+        client_id: ClientId,
+    },
+    MoveActiveTab(Direction, ClientId),
+    RequestSessionActivity(
+        u32, // u32 - plugin_id
+        ClientId,
+    ),
+    ClosePanesMatching {
+        predicate: PanePredicate,
+        client_id: ClientId,
+    },
+    SubscribePaneOutput(PaneId, u32),        // u32 - plugin_id
+    UnsubscribePaneOutput(PaneId, u32),      // u32 - plugin_id
+    ClearPluginPaneOutputSubscriptions(u32), // u32 - plugin_id
+    ClearPaneOutputSubscriptions(PaneId),
+    Ping(u64, ClientId), // u64 - request id
+    SetPaneScrollLines(
+        Option<PaneId>,      // None - the focused pane of the client
+        Option<ScrollLines>, // None - use the default
+        ClientId,
+    ),
+    SplitPaneWithRatio {
+        pane_id: Option<PaneId>, // the pane to split, None - the focused pane of the client
+        new_pane_id: PaneId,
+        direction: SplitDirection,
+        ratio: f64, // the part of the space the split pane keeps
+        client_id: ClientId,
+    },
+    RenamePluginTab(u32, String), // u32 - plugin_id, renames the tab the plugin is in
+    UndoLayoutChange(ClientId),   // eg. brings back the latest pane or tab that was closed
+    // a read only pane gets no input, but can still be scrolled
+    SetPaneReadOnly(Option<PaneId>, bool, ClientId), // None - the focused pane of the client
+    GoToTabByName {
+        name: String,
+        client_id: ClientId,
+    },
+    ApplyLayoutToTab {
+        tab_index: Option<usize>, // None - the active tab of the client
+        layout: TiledPaneLayout,
+        client_id: ClientId,
+    },
+    FillInPlaceLayout(Vec<(u32, HoldForCommand, Option<Run>)>, usize), // the terminals opened for
+    // the slots of a layout applied in place, usize is the tab index
+    ForceFullRender(ClientId),
+    SetFocusDimming(bool, f64), // f64 - how much to blend unfocused panes towards the background
+    ToggleFloatingPanesInTab(usize, ClientId), // usize - tab position, starting at 0
+    SwitchWorkspace(String),    // String - workspace name
+    ResizeTabToContent(Option<usize>, ClientId), // None - the active tab of the client
+    SetPanePassthrough(PaneId, bool), // bool - whether the pane is in pass-through mode
+    TogglePanePassthrough(ClientId),
+    CapturePaneText {
+        pane_id: Option<PaneId>, // None - the focused pane of the client
+        include_scrollback: bool,
+        client_id: ClientId,
+    },
+    // a None tab index is the active tab of the client, a None color clears the color of the tab
+    SetTabColor(Option<usize>, Option<String>, ClientId),
+    SetClientTheme(ClientId, Palette),
+    SetSynchronizedScroll(Option<usize>, bool, ClientId), // None - the active tab of the client
+    // None draws thin lines
+    SetPaneFrameStyle(Option<PaneFrameStyle>),
+    // terminal id of the alias, terminal id of the pane it mirrors
+    NewPaneAlias(u32, u32, ClientId),
+    MergePanes {
+        keep_pane_id: PaneId,
+        absorb_pane_id: PaneId,
+        client_id: ClientId,
+    },
 }
 
 impl From<&ScreenInstruction> for ScreenContext {
@@ -330,6 +485,8 @@ impl From<&ScreenInstruction> for ScreenContext {
         match *screen_instruction {
             ScreenInstruction::PtyBytes(..) => ScreenContext::HandlePtyBytes,
             ScreenInstruction::PluginBytes(..) => ScreenContext::PluginBytes,
+            ScreenInstruction::PluginRenderQueued(..) => ScreenContext::PluginRenderQueued,
+            ScreenInstruction::PluginLogLine(..) => ScreenContext::PluginLogLine,
             ScreenInstruction::Render => ScreenContext::Render,
             ScreenInstruction::NewPane(..) => ScreenContext::NewPane,
             ScreenInstruction::OpenInPlaceEditor(..) => ScreenContext::OpenInPlaceEditor,
@@ -465,6 +622,9 @@ impl From<&ScreenInstruction> for ScreenContext {
             ScreenInstruction::AddRedPaneFrameColorOverride(..) => {
                 ScreenContext::AddRedPaneFrameColorOverride
             },
+            ScreenInstruction::AddBellPaneFrameColorOverride(..) => {
+                ScreenContext::AddBellPaneFrameColorOverride
+            },
             ScreenInstruction::ClearPaneFrameColorOverride(..) => {
                 ScreenContext::ClearPaneFrameColorOverride
             },
@@ -493,6 +653,9 @@ impl From<&ScreenInstruction> for ScreenContext {
             ScreenInstruction::LaunchPlugin(..) => ScreenContext::LaunchPlugin,
             ScreenInstruction::SuppressPane(..) => ScreenContext::SuppressPane,
             ScreenInstruction::FocusPaneWithId(..) => ScreenContext::FocusPaneWithId,
+            ScreenInstruction::FocusPaneWithIdForAllClients(..) => {
+                ScreenContext::FocusPaneWithIdForAllClients
+            },
             ScreenInstruction::RenamePane(..) => ScreenContext::RenamePane,
             ScreenInstruction::RenameTab(..) => ScreenContext::RenameTab,
             ScreenInstruction::RequestPluginPermissions(..) => {
@@ -506,6 +669,72 @@ impl From<&ScreenInstruction> for ScreenContext {
             ScreenInstruction::NewInPlacePluginPane(..) => ScreenContext::NewInPlacePluginPane,
             ScreenInstruction::DumpLayoutToHd => ScreenContext::DumpLayoutToHd,
             ScreenInstruction::RenameSession(..) => ScreenContext::RenameSession,
+            ScreenInstruction::SessionRenamed(..) => ScreenContext::SessionRenamed,
+            ScreenInstruction::ToggleRuler(..) => ScreenContext::ToggleRuler,
+            ScreenInstruction::SwapLayout { .. } => ScreenContext::SwapLayout,
+            ScreenInstruction::FocusNextPaneSpatial(..) => ScreenContext::FocusNextPaneSpatial,
+            ScreenInstruction::CaptureRendered(..) => ScreenContext::CaptureRendered,
+            ScreenInstruction::FocusPaneById(..) => ScreenContext::FocusPaneById,
+            ScreenInstruction::RequestSessionSharingStatus(..) => {
+                ScreenContext::RequestSessionSharingStatus
+            },
+            ScreenInstruction::FocusPaneByName { .. } => ScreenContext::FocusPaneByName,
+            ScreenInstruction::PopOutPane(..) => ScreenContext::PopOutPane,
+            ScreenInstruction::CopyTextToClipboard(..) => ScreenContext::CopyTextToClipboard,
+            ScreenInstruction::SetPaneMinSize(..) => ScreenContext::SetPaneMinSize,
+            ScreenInstruction::ResizePaneTo { .. } => ScreenContext::ResizePaneTo,
+            ScreenInstruction::ResizePaneToPreset(..) => ScreenContext::ResizePaneToPreset,
+            ScreenInstruction::ToggleShowInvisibles(..) => ScreenContext::ToggleShowInvisibles,
+            ScreenInstruction::TogglePanePinned(..) => ScreenContext::TogglePanePinned,
+            ScreenInstruction::SetInputBroadcast(..) => ScreenContext::SetInputBroadcast,
+            ScreenInstruction::StackPanes { .. } => ScreenContext::StackPanes,
+            ScreenInstruction::CycleStack(..) => ScreenContext::CycleStack,
+            ScreenInstruction::RenderPluginOverlay(..) => ScreenContext::RenderPluginOverlay,
+            ScreenInstruction::ClearPluginOverlay(..) => ScreenContext::ClearPluginOverlay,
+            ScreenInstruction::TogglePaneFrame(..) => ScreenContext::TogglePaneFrame,
+            ScreenInstruction::ToggleZoom(..) => ScreenContext::ToggleZoom,
+            ScreenInstruction::NewPluginFedPane(..) => ScreenContext::NewPluginFedPane,
+            ScreenInstruction::WriteToPaneScrollback(..) => ScreenContext::WriteToPaneScrollback,
+            ScreenInstruction::DuplicatePane(..) => ScreenContext::DuplicatePane,
+            ScreenInstruction::SearchInPane { .. } => ScreenContext::SearchInPane,
+            ScreenInstruction::ClearSearch(..) => ScreenContext::ClearSearch,
+            ScreenInstruction::MoveTab { .. } => ScreenContext::MoveTab,
+            ScreenInstruction::MoveActiveTab(..) => ScreenContext::MoveActiveTab,
+            ScreenInstruction::RequestSessionActivity(..) => ScreenContext::RequestSessionActivity,
+            ScreenInstruction::ClosePanesMatching { .. } => ScreenContext::ClosePanesMatching,
+            ScreenInstruction::SubscribePaneOutput(..) => ScreenContext::SubscribePaneOutput,
+            ScreenInstruction::UnsubscribePaneOutput(..) => ScreenContext::UnsubscribePaneOutput,
+            ScreenInstruction::ClearPluginPaneOutputSubscriptions(..) => {
+                ScreenContext::ClearPluginPaneOutputSubscriptions
+            },
+            ScreenInstruction::ClearPaneOutputSubscriptions(..) => {
+                ScreenContext::ClearPaneOutputSubscriptions
+            },
+            ScreenInstruction::Ping(..) => ScreenContext::Ping,
+            ScreenInstruction::SetPaneScrollLines(..) => ScreenContext::SetPaneScrollLines,
+            ScreenInstruction::SplitPaneWithRatio { .. } => ScreenContext::SplitPaneWithRatio,
+            ScreenInstruction::RenamePluginTab(..) => ScreenContext::RenamePluginTab,
+            ScreenInstruction::UndoLayoutChange(..) => ScreenContext::UndoLayoutChange,
+            ScreenInstruction::SetPaneReadOnly(..) => ScreenContext::SetPaneReadOnly,
+            ScreenInstruction::GoToTabByName { .. } => ScreenContext::GoToTabByName,
+            ScreenInstruction::ApplyLayoutToTab { .. } => ScreenContext::ApplyLayoutToTab,
+            ScreenInstruction::FillInPlaceLayout(..) => ScreenContext::FillInPlaceLayout,
+            ScreenInstruction::ForceFullRender(..) => ScreenContext::ForceFullRender,
+            ScreenInstruction::SetFocusDimming(..) => ScreenContext::SetFocusDimming,
+            ScreenInstruction::ToggleFloatingPanesInTab(..) => {
+                ScreenContext::ToggleFloatingPanesInTab
+            },
+            ScreenInstruction::SwitchWorkspace(..) => ScreenContext::SwitchWorkspace,
+            ScreenInstruction::ResizeTabToContent(..) => ScreenContext::ResizeTabToContent,
+            ScreenInstruction::SetPanePassthrough(..) => ScreenContext::SetPanePassthrough,
+            ScreenInstruction::TogglePanePassthrough(..) => ScreenContext::TogglePanePassthrough,
+            ScreenInstruction::CapturePaneText { .. } => ScreenContext::CapturePaneText,
+            ScreenInstruction::SetTabColor(..) => ScreenContext::SetTabColor,
+            ScreenInstruction::SetClientTheme(..) => ScreenContext::SetClientTheme,
+            ScreenInstruction::SetSynchronizedScroll(..) => ScreenContext::SetSynchronizedScroll,
+            ScreenInstruction::SetPaneFrameStyle(..) => ScreenContext::SetPaneFrameStyle,
+            ScreenInstruction::NewPaneAlias(..) => ScreenContext::NewPaneAlias,
+            ScreenInstruction::MergePanes { .. } => ScreenContext::MergePanes,
         }
     }
 }
@@ -540,6 +769,17 @@ impl CopyOptions {
     }
 }
 
+/// The workspace a session starts in
+const DEFAULT_WORKSPACE: &str = "default";
+
+/// The tabs of a workspace that is not the current one, along with the tab each client was in,
+/// kept aside until the session switches back to it
+struct Workspace {
+    tabs: BTreeMap<usize, Tab>,
+    active_tab_indices: BTreeMap<ClientId, usize>,
+    tab_history: BTreeMap<ClientId, Vec<usize>>,
+}
+
 /// A [`Screen`] holds multiple [`Tab`]s, each one holding multiple [`panes`](crate::client::panes).
 /// It only directly controls which tab is active, delegating the rest to the individual `Tab`.
 pub(crate) struct Screen {
@@ -556,9 +796,12 @@ pub(crate) struct Screen {
     sixel_image_store: Rc<RefCell<SixelImageStore>>,
     /// The overlay that is drawn on top of [`Pane`]'s', [`Tab`]'s and the [`Screen`]
     overlay: OverlayWindow,
+    plugin_overlays: BTreeMap<u32, PluginOverlay>, // u32 is the plugin id
     terminal_emulator_colors: Rc<RefCell<Palette>>,
     terminal_emulator_color_codes: Rc<RefCell<HashMap<usize, String>>>,
     connected_clients: Rc<RefCell<HashSet<ClientId>>>,
+    /// Connected clients that see the session but can't send it input
+    observer_clients: HashSet<ClientId>,
     /// The indices of this [`Screen`]'s active [`Tab`]s.
     active_tab_indices: BTreeMap<ClientId, usize>,
     tab_history: BTreeMap<ClientId, Vec<usize>>,
@@ -570,6 +813,7 @@ pub(crate) struct Screen {
     session_serialization: bool,
     serialize_pane_viewport: bool,
     scrollback_lines_to_serialize: Option<usize>,
+    serialize_successfully_exited_panes: bool,
     session_is_mirrored: bool,
     copy_options: CopyOptions,
     debug: bool,
@@ -582,6 +826,31 @@ pub(crate) struct Screen {
     default_shell: Option<PathBuf>,
     styled_underlines: bool,
     arrow_fonts: bool,
+    transparent_background: bool,
+    keep_scrollback_in_alternate_screen: bool,
+    scroll_lines: ScrollLines, // for panes that don't have their own
+    input_latency: InputLatencyTracker,
+    reported_session_sharing_status: SessionSharingStatus,
+    session_activity: SessionActivityLog,
+    pane_output_subscriptions: HashMap<PaneId, BTreeMap<u32, PaneOutputBuffer>>, // u32 - plugin id
+    popped_out_panes: BTreeMap<PaneId, (usize, usize, bool)>, // (tab index, index of the tab it
+    // was popped out of, whether it was floating there)
+    pane_view_clients: BTreeMap<ClientId, PaneId>, // clients viewing a single pane in isolation
+    layout_undo_stack: LayoutUndoStack,
+    restored_plugin_panes: HashSet<u32>, // plugin ids of restored panes whose plugin is reloading
+    surplus_panes: SurplusPanes,
+    bell: Bell,
+    focus_dimming: Option<f64>,
+    client_themes: HashMap<ClientId, Palette>, // clients rendering with their own palette
+    pane_frame_style: Option<PaneFrameStyle>,
+    workspace: String, // the workspace the tabs above belong to
+    hidden_workspaces: BTreeMap<String, Workspace>,
+    // clients of a workspace that was just created, placed in its first tab once it is opened
+    clients_waiting_for_workspace: Vec<(ClientId, ModeInfo)>,
+    // panes that get all keys sent to them verbatim, except the passthrough_escape_key
+    passthrough_panes: HashSet<PaneId>,
+    // clients that were told the pane they focus is in pass-through mode
+    passthrough_clients: HashSet<ClientId>,
 }
 
 impl Screen {
@@ -601,8 +870,18 @@ impl Screen {
         session_serialization: bool,
         serialize_pane_viewport: bool,
         scrollback_lines_to_serialize: Option<usize>,
+        serialize_successfully_exited_panes: bool,
         styled_underlines: bool,
         arrow_fonts: bool,
+        transparent_background: bool,
+        keep_scrollback_in_alternate_screen: bool,
+        session_activity_log_size: usize,
+        scroll_lines: ScrollLines,
+        layout_undo_depth: usize,
+        surplus_panes: SurplusPanes,
+        bell: Bell,
+        pane_frame_style: Option<PaneFrameStyle>,
+        focus_dimming: Option<f64>,
     ) -> Self {
         let session_name = mode_info.session_name.clone().unwrap_or_default();
         let session_info = SessionInfo::new(session_name.clone());
@@ -618,9 +897,11 @@ impl Screen {
             sixel_image_store: Rc::new(RefCell::new(SixelImageStore::default())),
             style: client_attributes.style,
             connected_clients: Rc::new(RefCell::new(HashSet::new())),
+            observer_clients: HashSet::new(),
             active_tab_indices: BTreeMap::new(),
             tabs: BTreeMap::new(),
             overlay: OverlayWindow::default(),
+            plugin_overlays: BTreeMap::new(),
             terminal_emulator_colors: Rc::new(RefCell::new(Palette::default())),
             terminal_emulator_color_codes: Rc::new(RefCell::new(HashMap::new())),
             tab_history: BTreeMap::new(),
@@ -638,9 +919,31 @@ impl Screen {
             session_serialization,
             serialize_pane_viewport,
             scrollback_lines_to_serialize,
+            serialize_successfully_exited_panes,
             styled_underlines,
             arrow_fonts,
+            transparent_background,
+            keep_scrollback_in_alternate_screen,
+            scroll_lines,
             resurrectable_sessions,
+            input_latency: InputLatencyTracker::default(),
+            reported_session_sharing_status: SessionSharingStatus::default(),
+            session_activity: SessionActivityLog::new(session_activity_log_size),
+            pane_output_subscriptions: HashMap::new(),
+            popped_out_panes: BTreeMap::new(),
+            pane_view_clients: BTreeMap::new(),
+            layout_undo_stack: LayoutUndoStack::new(layout_undo_depth),
+            restored_plugin_panes: HashSet::new(),
+            surplus_panes,
+            bell,
+            focus_dimming,
+            client_themes: HashMap::new(),
+            pane_frame_style,
+            workspace: DEFAULT_WORKSPACE.to_owned(),
+            hidden_workspaces: BTreeMap::new(),
+            clients_waiting_for_workspace: vec![],
+            passthrough_panes: HashSet::new(),
+            passthrough_clients: HashSet::new(),
         }
     }
 
@@ -648,7 +951,12 @@ impl Screen {
     /// Currently, this is right after the last currently existing tab, or `0` if
     /// no tabs exist in this screen yet.
     fn get_new_tab_index(&self) -> usize {
-        if let Some(index) = self.tabs.keys().last() {
+        // tab indices are unique across workspaces, so that they can be switched back to
+        let hidden_tab_indices = self
+            .hidden_workspaces
+            .values()
+            .flat_map(|workspace| workspace.tabs.keys());
+        if let Some(index) = self.tabs.keys().chain(hidden_tab_indices).max() {
             *index + 1
         } else {
             0
@@ -926,6 +1234,475 @@ impl Screen {
         self.switch_active_tab_name(name, client_id)
     }
 
+    /// Focuses the first tab (in tab order) with this name. Unlike [`Screen::go_to_tab_name`],
+    /// there not being such a tab is an error.
+    pub fn go_to_tab_by_name(&mut self, name: &str, client_id: ClientId) -> Result<()> {
+        let err_context = || format!("failed to go to tab named {name:?} for client {client_id}");
+
+        let tab_position = self
+            .tabs
+            .values()
+            .filter(|tab| tab.name == name)
+            .map(|tab| tab.position)
+            .min()
+            .ok_or_else(|| anyhow!("no tab named {name:?}"))
+            .with_context(err_context)?;
+        self.switch_active_tab(tab_position, None, true, client_id)
+            .with_context(err_context)
+    }
+
+    /// Renders the tab this client is focused on from scratch rather than only what changed since
+    /// the last render
+    pub fn force_full_render(&mut self, client_id: ClientId) -> Result<()> {
+        self.get_active_tab_mut(client_id)
+            .with_context(|| format!("failed to force a full render for client {client_id}"))?
+            .force_full_render();
+        self.render()
+    }
+
+    /// Dims the panes that are not focused by blending their colors towards the background by
+    /// `amount` (between 0.0 and 1.0), in all tabs and in the ones created later on
+    pub fn set_focus_dimming(&mut self, enabled: bool, amount: f64) -> Result<()> {
+        self.focus_dimming = if enabled {
+            Some(amount.clamp(0.0, 1.0))
+        } else {
+            None
+        };
+        let focus_dimming = self.focus_dimming;
+        for tab in self.tabs_of_all_workspaces_mut() {
+            tab.set_focus_dimming(focus_dimming);
+        }
+        self.render()
+    }
+
+    /// Renders everything this client sees with `theme` instead of the session's palette, in all
+    /// tabs and in the ones created later on, and lets its plugins know so they can follow suit
+    pub fn set_client_theme(&mut self, client_id: ClientId, theme: Palette) -> Result<()> {
+        let err_context = || format!("failed to set theme for client {client_id}");
+
+        self.client_themes.insert(client_id, theme);
+        let mut mode_info = self
+            .mode_info
+            .get(&client_id)
+            .unwrap_or(&self.default_mode_info)
+            .clone();
+        mode_info.style.colors = theme;
+        self.mode_info.insert(client_id, mode_info.clone());
+        for tab in self.tabs_of_all_workspaces_mut() {
+            tab.set_client_theme(client_id, theme);
+            tab.change_mode_info(mode_info.clone(), client_id);
+            tab.update_input_modes().with_context(err_context)?;
+        }
+        self.render().with_context(err_context)
+    }
+
+    /// Puts the pane in (or takes it out of) pass-through mode, in which the clients focusing it
+    /// send it all keys as they are except the `passthrough_escape_key`
+    pub fn set_pane_passthrough(&mut self, pane_id: PaneId, passthrough: bool) -> Result<()> {
+        if !self
+            .tabs_of_all_workspaces()
+            .any(|tab| tab.has_pane_with_pid(&pane_id))
+        {
+            return Err(anyhow!("pane {:?} does not exist", pane_id))
+                .with_context(|| format!("failed to set pass-through mode for {pane_id:?}"));
+        }
+        if passthrough {
+            self.passthrough_panes.insert(pane_id);
+        } else {
+            self.passthrough_panes.remove(&pane_id);
+        }
+        self.render()
+    }
+
+    pub fn toggle_pane_passthrough(&mut self, client_id: ClientId) -> Result<()> {
+        let err_context = || format!("failed to toggle pass-through mode for client {client_id}");
+        let pane_id = self
+            .get_active_tab(client_id)
+            .with_context(err_context)?
+            .get_active_pane_id(client_id)
+            .with_context(err_context)?;
+        let passthrough = !self.passthrough_panes.contains(&pane_id);
+        self.set_pane_passthrough(pane_id, passthrough)
+            .with_context(err_context)
+    }
+
+    /// Lets the clients know when the pane they focus enters or leaves pass-through mode, since
+    /// their input handlers decide which keys are passed through
+    fn report_passthrough_to_clients(&mut self) {
+        // panes can also be closed with their tab or by the tab itself, we forget those here
+        let passthrough_panes: HashSet<PaneId> = self
+            .passthrough_panes
+            .iter()
+            .copied()
+            .filter(|pane_id| {
+                self.tabs_of_all_workspaces()
+                    .any(|tab| tab.has_pane_with_pid(pane_id))
+            })
+            .collect();
+        self.passthrough_panes = passthrough_panes;
+        let connected_clients: Vec<ClientId> =
+            self.connected_clients.borrow().iter().copied().collect();
+        for client_id in connected_clients {
+            let passthrough = self
+                .get_active_tab(client_id)
+                .ok()
+                .and_then(|tab| tab.get_active_pane_id(client_id))
+                .map(|pane_id| self.passthrough_panes.contains(&pane_id))
+                .unwrap_or(false);
+            if passthrough == self.passthrough_clients.contains(&client_id) {
+                continue;
+            }
+            if passthrough {
+                self.passthrough_clients.insert(client_id);
+            } else {
+                self.passthrough_clients.remove(&client_id);
+            }
+            if let Some(os_input) = &mut self.bus.os_input {
+                let _ = os_input
+                    .send_to_client(client_id, ServerToClientMsg::SetPassthrough(passthrough));
+            }
+        }
+    }
+
+    /// Reshapes the tab with this index to match `layout` in place, keeping its panes and opening
+    /// new ones only for the slots they do not fill (see [`Tab::apply_layout_in_place`]). The way
+    /// the panes were arranged before can be brought back with `undo_layout_change`.
+    pub fn apply_layout_to_tab(&mut self, tab_index: usize, layout: TiledPaneLayout) -> Result<()> {
+        let err_context = || format!("failed to apply layout to tab {tab_index}");
+
+        let surplus_panes = self.surplus_panes;
+        let mut terminals_to_open = vec![];
+        self.rearrange_tiled_panes(tab_index, |tab| {
+            terminals_to_open = tab.apply_layout_in_place(layout, surplus_panes)?;
+            Ok(())
+        })
+        .with_context(err_context)?;
+        if !terminals_to_open.is_empty() {
+            self.bus
+                .senders
+                .send_to_pty(PtyInstruction::SpawnTerminalsForLayoutInPlace(
+                    terminals_to_open,
+                    tab_index,
+                ))
+                .with_context(err_context)?;
+        }
+        Ok(())
+    }
+
+    /// Adds the terminals opened for the layout applied in place to the tab with this index, see
+    /// [`Tab::fill_in_place_layout`]
+    pub fn fill_in_place_layout(
+        &mut self,
+        tab_index: usize,
+        new_terminal_ids: Vec<(u32, HoldForCommand, Option<Run>)>,
+    ) -> Result<()> {
+        self.get_indexed_tab_mut(tab_index)
+            .ok_or_else(|| anyhow!("tab with index {tab_index} does not exist"))
+            .and_then(|tab| tab.fill_in_place_layout(new_terminal_ids))
+            .with_context(|| format!("failed to fill the layout of tab {tab_index}"))
+    }
+
+    /// Hides the floating panes of the tab with this index or shows them again as they were, see
+    /// [`Tab::toggle_floating_panes_visibility`]
+    pub fn toggle_floating_panes_in_tab(
+        &mut self,
+        tab_index: usize,
+        client_id: ClientId,
+    ) -> Result<()> {
+        self.tabs
+            .get_mut(&tab_index)
+            .ok_or_else(|| anyhow!("no tab with index {tab_index}"))
+            .with_context(|| format!("failed to toggle floating panes in tab {tab_index}"))?
+            .toggle_floating_panes_visibility(client_id);
+        Ok(())
+    }
+
+    /// Toggles the frame of this pane only, in whichever tab it is
+    pub fn toggle_pane_frame(&mut self, pane_id: PaneId) -> Result<()> {
+        self.tabs
+            .values_mut()
+            .find(|tab| tab.has_pane_with_pid(&pane_id))
+            .ok_or_else(|| anyhow!("pane {pane_id:?} does not exist"))
+            .and_then(|tab| tab.toggle_pane_frame(pane_id))
+            .with_context(|| format!("failed to toggle the frame of pane {pane_id:?}"))
+    }
+
+    /// Sizes the tiled panes of the tab with this index to their content, see
+    /// [`Tab::resize_tiled_panes_to_content`]
+    pub fn resize_tab_to_content(&mut self, tab_index: usize) -> Result<()> {
+        self.tabs
+            .get_mut(&tab_index)
+            .ok_or_else(|| anyhow!("no tab with index {tab_index}"))
+            .with_context(|| format!("failed to resize tab {tab_index} to its content"))?
+            .resize_tiled_panes_to_content();
+        Ok(())
+    }
+
+    /// Colors the tab in the tab bar, either with the name of a palette color (eg. "red") or with
+    /// a hex color (eg. "#ff0000"), or removes its color if `color` is `None`
+    pub fn set_tab_color(&mut self, tab_index: usize, color: Option<String>) -> Result<()> {
+        let err_context = || format!("failed to set the color of tab {tab_index}");
+        if let Some(color) = &color {
+            // named colors are resolved against the theme when the tab is rendered
+            if Palette::default().resolve_color(color).is_none() {
+                return Err(anyhow!("invalid color {color:?}")).with_context(err_context);
+            }
+        }
+        self.tabs
+            .get_mut(&tab_index)
+            .ok_or_else(|| anyhow!("no tab with index {tab_index}"))
+            .with_context(err_context)?
+            .set_color(color);
+        self.log_and_report_session_state()
+            .with_context(err_context)
+    }
+
+    /// While active, scrolling one of the panes of the tab scrolls all the others along with it
+    pub fn set_synchronized_scroll(&mut self, tab_index: usize, is_active: bool) -> Result<()> {
+        self.tabs
+            .get_mut(&tab_index)
+            .ok_or_else(|| anyhow!("no tab with index {tab_index}"))
+            .with_context(|| format!("failed to set synchronized scroll in tab {tab_index}"))?
+            .set_synchronized_scroll_is_active(is_active);
+        Ok(())
+    }
+
+    /// Draws the frames of all panes, including those in tabs created later on, with the
+    /// characters of `pane_frame_style`, or with thin lines if `None`
+    pub fn set_pane_frame_style(&mut self, pane_frame_style: Option<PaneFrameStyle>) -> Result<()> {
+        self.pane_frame_style = pane_frame_style;
+        let hidden_tabs = self
+            .hidden_workspaces
+            .values_mut()
+            .flat_map(|workspace| workspace.tabs.values_mut());
+        for tab in self.tabs.values_mut().chain(hidden_tabs) {
+            tab.set_pane_frame_style(pane_frame_style);
+        }
+        self.render()
+    }
+
+    /// Opens a pane in the active tab of this client that mirrors the terminal pane
+    /// `source_terminal_id` of any tab: it renders the grid of the source pane and its input goes
+    /// to the source pane, which is left alone when the alias is closed. The alias is closed with
+    /// the source pane.
+    pub fn new_pane_alias(
+        &mut self,
+        terminal_id: u32,
+        source_terminal_id: u32,
+        client_id: ClientId,
+    ) -> Result<()> {
+        let err_context = || format!("failed to open an alias of pane {source_terminal_id}");
+        let (source_terminal_id, source_title) = self
+            .tabs_of_all_workspaces()
+            .find_map(|tab| tab.get_pane_with_id(PaneId::Terminal(source_terminal_id)))
+            // an alias of an alias mirrors the same pane
+            .map(|pane| {
+                (
+                    pane.alias_of().unwrap_or(source_terminal_id),
+                    pane.current_title(),
+                )
+            })
+            .ok_or_else(|| anyhow!("no terminal pane with id {source_terminal_id}"))
+            .with_context(err_context)?;
+        self.get_active_tab_mut(client_id)
+            .and_then(|tab| {
+                tab.new_pane_alias(
+                    terminal_id,
+                    source_terminal_id,
+                    format!("{} (alias)", source_title),
+                    client_id,
+                )
+            })
+            .with_context(err_context)?;
+        self.log_and_report_session_state()
+            .and_then(|_| self.render())
+            .with_context(err_context)
+    }
+
+    /// Closes `absorb_pane_id` and grows `keep_pane_id` over the space it took, in the active tab
+    /// of this client. Only two tiled panes that share a whole border can be merged.
+    pub fn merge_panes(
+        &mut self,
+        keep_pane_id: PaneId,
+        absorb_pane_id: PaneId,
+        client_id: ClientId,
+    ) -> Result<()> {
+        let err_context = || format!("failed to merge panes for client {client_id}");
+        self.get_active_tab_mut(client_id)
+            .and_then(|tab| tab.merge_panes(keep_pane_id, absorb_pane_id))
+            .with_context(err_context)?;
+        self.log_and_report_session_state()
+            .and_then(|_| self.render())
+            .with_context(err_context)
+    }
+
+    /// Hides the tabs of the current workspace and shows those of the workspace with this name
+    /// instead, where each client goes back to the tab it was in. If there is no such workspace,
+    /// it is created with a tab from the default layout.
+    pub fn switch_workspace(&mut self, workspace_name: String) -> Result<()> {
+        let err_context = || format!("failed to switch to workspace {workspace_name}");
+        if workspace_name == self.workspace {
+            return Ok(());
+        }
+        let mut client_mode_infos = vec![];
+        for tab in self.tabs.values_mut() {
+            client_mode_infos.append(&mut tab.drain_connected_clients(None));
+            tab.visible(false).with_context(err_context)?;
+        }
+        let current_workspace = Workspace {
+            tabs: std::mem::take(&mut self.tabs),
+            active_tab_indices: std::mem::take(&mut self.active_tab_indices),
+            tab_history: std::mem::take(&mut self.tab_history),
+        };
+        self.hidden_workspaces
+            .insert(self.workspace.clone(), current_workspace);
+        self.reveal_workspace(workspace_name.clone(), client_mode_infos)
+            .with_context(err_context)
+    }
+
+    /// Makes the workspace with this name the current one and places these clients in its tabs
+    fn reveal_workspace(
+        &mut self,
+        workspace_name: String,
+        client_mode_infos: Vec<(ClientId, ModeInfo)>,
+    ) -> Result<()> {
+        self.workspace = workspace_name.clone();
+        match self
+            .hidden_workspaces
+            .remove(&workspace_name)
+            .filter(|workspace| !workspace.tabs.is_empty())
+        {
+            Some(workspace) => {
+                self.tabs = workspace.tabs;
+                self.active_tab_indices = workspace.active_tab_indices;
+                self.tab_history = workspace.tab_history;
+                for tab in self.tabs.values_mut() {
+                    // the session might have been resized while the workspace was hidden
+                    tab.resize_whole_tab(self.size)?;
+                }
+                let first_tab_index = self.tabs.keys().next().copied().unwrap_or_default();
+                for (client_id, mode_info) in client_mode_infos {
+                    let tab_index = self
+                        .active_tab_indices
+                        .get(&client_id)
+                        .copied()
+                        .filter(|tab_index| self.tabs.contains_key(tab_index))
+                        .unwrap_or(first_tab_index);
+                    self.active_tab_indices.insert(client_id, tab_index);
+                    if let Some(tab) = self.tabs.get_mut(&tab_index) {
+                        tab.add_multiple_clients(vec![(client_id, mode_info)])?;
+                        tab.visible(true)?;
+                        tab.force_full_render();
+                    }
+                    // the plugin panes of the workspace have to render again at the current size
+                    self.bus
+                        .senders
+                        .send_to_plugin(PluginInstruction::RerenderPlugins(client_id))?;
+                }
+                self.log_and_report_session_state()?;
+                self.render()?;
+            },
+            None => {
+                let client_id = client_mode_infos
+                    .first()
+                    .map(|(client_id, _mode_info)| *client_id)
+                    .or_else(|| self.connected_clients.borrow().iter().next().copied())
+                    .context("no client to open the first tab of the workspace for")?;
+                self.clients_waiting_for_workspace = client_mode_infos;
+                let tab_index = self.get_new_tab_index();
+                let swap_layouts = (
+                    self.default_layout.swap_tiled_layouts.clone(),
+                    self.default_layout.swap_floating_layouts.clone(),
+                );
+                self.new_tab(tab_index, swap_layouts, None, client_id)?;
+                let (tiled_panes_layout, floating_panes_layout) = self.default_layout.new_tab();
+                let default_shell = None; // the pty thread falls back to the default shell
+                self.bus.senders.send_to_plugin(PluginInstruction::NewTab(
+                    None,
+                    default_shell,
+                    Some(tiled_panes_layout),
+                    floating_panes_layout,
+                    tab_index,
+                    client_id,
+                ))?;
+            },
+        }
+        self.bus
+            .senders
+            .send_to_plugin(PluginInstruction::Update(vec![(
+                None,
+                None,
+                Event::WorkspaceChanged(workspace_name),
+            )]))
+    }
+
+    /// Moves the active tab of this client one position back (left/up) or forward (right/down),
+    /// wrapping around at either end like switching to the previous or next tab does
+    pub fn move_active_tab(&mut self, direction: Direction, client_id: ClientId) -> Result<()> {
+        let err_context =
+            || format!("failed to move the active tab {direction:?} for client {client_id}");
+
+        let active_tab_pos = self
+            .get_active_tab(client_id)
+            .with_context(err_context)?
+            .position;
+        let last_position = self.tabs.len().saturating_sub(1);
+        let new_tab_pos = match direction {
+            Direction::Left | Direction::Up if active_tab_pos == 0 => last_position,
+            Direction::Left | Direction::Up => active_tab_pos - 1,
+            Direction::Right | Direction::Down if active_tab_pos == last_position => 0,
+            Direction::Right | Direction::Down => active_tab_pos + 1,
+        };
+        self.move_tab(active_tab_pos, new_tab_pos, client_id)
+            .with_context(err_context)
+    }
+
+    /// Moves the tab at position `from_index` to position `to_index`, shifting the tabs in between
+    /// by one. Positions past the last tab are clamped to it. The client is focused on the moved
+    /// tab.
+    pub fn move_tab(
+        &mut self,
+        from_index: usize,
+        to_index: usize,
+        client_id: ClientId,
+    ) -> Result<()> {
+        let err_context = || {
+            format!(
+                "failed to move tab from position {from_index} to {to_index} for client {client_id}"
+            )
+        };
+
+        let last_position = match self.tabs.len().checked_sub(1) {
+            Some(last_position) => last_position,
+            None => return Ok(()),
+        };
+        let from_position = from_index.min(last_position);
+        let to_position = to_index.min(last_position);
+        for tab in self.tabs.values_mut() {
+            if tab.position == from_position {
+                tab.position = to_position;
+            } else if from_position < to_position
+                && tab.position > from_position
+                && tab.position <= to_position
+            {
+                tab.position -= 1;
+            } else if to_position < from_position
+                && tab.position >= to_position
+                && tab.position < from_position
+            {
+                tab.position += 1;
+            }
+        }
+        if self.get_active_tab(client_id).is_ok() {
+            // does nothing if the client is already focused on the moved tab
+            self.switch_active_tab(to_position, None, true, client_id)
+                .with_context(err_context)?;
+        }
+        self.log_and_report_session_state()
+            .with_context(err_context)?;
+        self.render().with_context(err_context)
+    }
+
     fn close_tab_at_index(&mut self, tab_index: usize) -> Result<()> {
         let err_context = || format!("failed to close tab at index {tab_index:?}");
 
@@ -938,7 +1715,20 @@ impl Screen {
             .senders
             .send_to_pty(PtyInstruction::CloseTab(pane_ids))
             .with_context(err_context)?;
-        if self.tabs.is_empty() {
+        if self.tabs.is_empty() && !self.hidden_workspaces.is_empty() {
+            // the session goes on in one of the other workspaces
+            let client_mode_infos_in_closed_tab = tab_to_close.drain_connected_clients(None);
+            self.active_tab_indices.clear();
+            self.tab_history.clear();
+            let workspace_name = self
+                .hidden_workspaces
+                .keys()
+                .next()
+                .cloned()
+                .with_context(err_context)?;
+            self.reveal_workspace(workspace_name, client_mode_infos_in_closed_tab)
+                .with_context(err_context)
+        } else if self.tabs.is_empty() {
             self.active_tab_indices.clear();
             self.bus
                 .senders
@@ -959,12 +1749,70 @@ impl Screen {
                     t.position -= 1;
                 }
             }
+            self.record_closed_tab(tab_to_close)
+                .with_context(err_context)?;
             self.log_and_report_session_state()
                 .with_context(err_context)?;
             self.render().with_context(err_context)
         }
     }
 
+    /// Closes the tab with this index, be it in the current workspace or in a hidden one
+    fn close_tab_of_any_workspace(&mut self, tab_index: usize) -> Result<()> {
+        let err_context = || format!("failed to close tab at index {tab_index:?}");
+
+        if self.tabs.contains_key(&tab_index) {
+            return self.close_tab_at_index(tab_index).with_context(err_context);
+        }
+        let workspace = self
+            .hidden_workspaces
+            .values_mut()
+            .find(|workspace| workspace.tabs.contains_key(&tab_index))
+            .with_context(err_context)?;
+        let tab_to_close = workspace
+            .tabs
+            .remove(&tab_index)
+            .with_context(err_context)?;
+        for tab in workspace.tabs.values_mut() {
+            if tab.position > tab_to_close.position {
+                tab.position -= 1;
+            }
+        }
+        // clients of the workspace go back to its first tab once it is revealed
+        workspace
+            .active_tab_indices
+            .retain(|_client_id, index| *index != tab_index);
+        for tab_history in workspace.tab_history.values_mut() {
+            tab_history.retain(|index| *index != tab_index);
+        }
+        self.bus
+            .senders
+            .send_to_pty(PtyInstruction::CloseTab(tab_to_close.get_all_pane_ids()))
+            .with_context(err_context)?;
+        self.log_and_report_session_state()
+            .with_context(err_context)
+    }
+
+    /// Keeps the closed tab to be brought back with `undo_layout_change`
+    fn record_closed_tab(&mut self, mut tab: Tab) -> Result<()> {
+        if !tab.has_selectable_tiled_panes() {
+            // the tab was closed because its last pane was, so undoing this brings back the tab
+            // along with the pane
+            match self.layout_undo_stack.pop_closed_pane_in_tab(tab.index) {
+                Some(LayoutChange::ClosedPane {
+                    pane,
+                    is_floating,
+                    tiled_pane_geoms,
+                    ..
+                }) => tab.restore_closed_pane(pane, is_floating, &tiled_pane_geoms, None)?,
+                _ => return Ok(()),
+            }
+        }
+        self.layout_undo_stack
+            .push(LayoutChange::ClosedTab(Box::new(tab)));
+        Ok(())
+    }
+
     // Closes the client_id's focused tab
     pub fn close_tab(&mut self, client_id: ClientId) -> Result<()> {
         let err_context = || format!("failed to close tab for client {client_id:?}");
@@ -1042,6 +1890,38 @@ impl Screen {
         }
     }
 
+    pub fn handle_plugin_bytes(
+        &mut self,
+        plugin_bytes: Vec<(u32, ClientId, VteBytes)>,
+    ) -> Result<()> {
+        for (pid, client_id, vte_bytes) in plugin_bytes {
+            for tab in self.tabs_of_all_workspaces_mut() {
+                if tab.has_plugin(pid) {
+                    tab.handle_plugin_bytes(pid, client_id, vte_bytes)
+                        .context("failed to process plugin bytes")?;
+                    break;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn handle_plugin_frames(
+        &mut self,
+        plugin_frames: Vec<(u32, ClientId, PluginFrame)>,
+    ) -> Result<()> {
+        for (pid, client_id, frame) in plugin_frames {
+            for tab in self.tabs_of_all_workspaces_mut() {
+                if tab.has_plugin(pid) {
+                    tab.handle_plugin_frame(pid, client_id, frame)
+                        .context("failed to process plugin frame")?;
+                    break;
+                }
+            }
+        }
+        Ok(())
+    }
+
     /// Renders this [`Screen`], which amounts to rendering its active [`Tab`].
     pub fn render(&mut self) -> Result<()> {
         let err_context = "failed to render screen";
@@ -1051,6 +1931,7 @@ impl Screen {
             self.character_cell_size.clone(),
             self.styled_underlines,
         );
+        self.update_pane_aliases().context(err_context)?;
         let mut tabs_to_close = vec![];
         for (tab_index, tab) in &mut self.tabs {
             if tab.has_selectable_tiled_panes() {
@@ -1062,22 +1943,298 @@ impl Screen {
         for tab_index in tabs_to_close {
             self.close_tab_at_index(tab_index).context(err_context)?;
         }
+        self.render_plugin_overlays(&mut output)
+            .context(err_context)?;
+        self.report_passthrough_to_clients();
         if output.is_dirty() {
             let serialized_output = output.serialize().context(err_context)?;
+            for client_id in serialized_output.keys() {
+                self.input_latency.render_dispatched(*client_id);
+            }
             self.bus
                 .senders
                 .send_to_server(ServerInstruction::Render(Some(serialized_output)))
-                .context(err_context)
+                .context(err_context)?;
+            self.report_input_latency().context(err_context)
         } else {
             Ok(())
         }
     }
 
+    /// Gives the aliases about to be rendered the current viewport of the pane they mirror, and
+    /// closes the aliases of panes that were closed, whose input would otherwise go to whichever
+    /// pane gets the same id next
+    fn update_pane_aliases(&mut self) -> Result<()> {
+        let err_context = || "failed to update pane aliases";
+        let aliases: Vec<(usize, PaneId, u32)> = self
+            .tabs
+            .iter()
+            .flat_map(|(tab_index, tab)| {
+                tab.pane_aliases()
+                    .into_iter()
+                    .map(move |(alias_id, source_terminal_id)| {
+                        (*tab_index, alias_id, source_terminal_id)
+                    })
+            })
+            .collect();
+        for (tab_index, alias_id, source_terminal_id) in aliases {
+            let alias_view = self.tabs.get(&tab_index).and_then(|tab| {
+                let alias = tab.get_pane_with_id(alias_id)?;
+                let source = self
+                    .tabs_of_all_workspaces()
+                    .find_map(|tab| tab.get_pane_with_id(PaneId::Terminal(source_terminal_id)))?;
+                source.viewport_chunks(
+                    alias.get_content_x(),
+                    alias.get_content_y(),
+                    alias.get_content_rows(),
+                    alias.get_content_columns(),
+                )
+            });
+            match (self.tabs.get_mut(&tab_index), alias_view) {
+                (Some(tab), Some(alias_view)) => tab.set_alias_view(alias_id, alias_view),
+                (Some(tab), None) => {
+                    tab.close_pane(alias_id, false, None);
+                    self.bus
+                        .senders
+                        .send_to_pty(PtyInstruction::ClosePane(alias_id))
+                        .with_context(err_context)?;
+                },
+                (None, _) => {},
+            }
+        }
+        Ok(())
+    }
+
+    fn render_plugin_overlays(&self, output: &mut Output) -> Result<()> {
+        // plugin overlays are drawn over everything else, so they are re-drawn whenever anything
+        // is rendered under them
+        let connected_clients: Vec<ClientId> =
+            self.connected_clients.borrow().iter().copied().collect();
+        for plugin_overlay in self.plugin_overlays.values() {
+            let vte_output = plugin_overlay
+                .generate_overlay(self.size)
+                .context("failed to render plugin overlays")?;
+            output.add_post_vte_instruction_to_multiple_clients(
+                connected_clients.iter().copied(),
+                &vte_output,
+            );
+        }
+        Ok(())
+    }
+
+    pub fn render_plugin_overlay(&mut self, plugin_id: u32, plugin_overlay: PluginOverlay) {
+        self.plugin_overlays.insert(plugin_id, plugin_overlay);
+        // the previous overlay of this plugin might have been larger
+        self.set_force_render_for_all_tabs();
+    }
+
+    pub fn clear_plugin_overlay(&mut self, plugin_id: u32) {
+        if self.plugin_overlays.remove(&plugin_id).is_some() {
+            // re-render whatever the overlay was covering
+            self.set_force_render_for_all_tabs();
+        }
+    }
+
+    fn set_force_render_for_all_tabs(&mut self) {
+        for tab in self.tabs.values_mut() {
+            tab.set_force_render();
+        }
+    }
+
+    /// Returns the cells currently rendered to this client, either of one pane or of the whole
+    /// screen
+    pub fn capture_rendered(
+        &mut self,
+        pane_id: Option<PaneId>,
+        client_id: ClientId,
+    ) -> Result<Option<RenderedGrid>> {
+        let err_context = || format!("failed to capture rendered output of client {client_id}");
+
+        // we first send out whatever is pending, so that re-rendering everything for the capture
+        // below does not swallow changes the client has yet to receive
+        self.render().with_context(err_context)?;
+        let mut output = Output::new(
+            self.sixel_image_store.clone(),
+            self.character_cell_size.clone(),
+            self.styled_underlines,
+        );
+        let size = self.size;
+        let tab = match self.get_active_tab_mut(client_id) {
+            Ok(tab) => tab,
+            Err(_) => return Ok(None),
+        };
+        tab.set_force_render();
+        tab.render(&mut output).with_context(err_context)?;
+        let rendered_grid = output.rendered_grid_for_client(client_id, size);
+        Ok(match pane_id {
+            Some(pane_id) => tab
+                .rendered_pane_geom(pane_id)
+                .and_then(|pane_geom| rendered_grid.map(|grid| grid.crop(&pane_geom))),
+            None => rendered_grid,
+        })
+    }
+
+    fn report_input_latency(&mut self) -> Result<()> {
+        if let Some(input_latency_stats) = self.input_latency.stats_to_report() {
+            self.bus
+                .senders
+                .send_to_plugin(PluginInstruction::Update(vec![(
+                    None,
+                    None,
+                    Event::InputLatencyStats(input_latency_stats),
+                )]))
+                .context("failed to report input latency")?;
+        }
+        Ok(())
+    }
+
+    fn session_sharing_status(&self) -> SessionSharingStatus {
+        SessionSharingStatus {
+            connected_clients: self.connected_clients.borrow().len(),
+            // in debug mode the pty thread logs the output of all panes to files
+            is_recording: self.debug,
+        }
+    }
+
+    /// Lets plugins know if the session started or stopped being shared or recorded
+    fn report_session_sharing_status(&mut self) -> Result<()> {
+        let session_sharing_status = self.session_sharing_status();
+        if session_sharing_status != self.reported_session_sharing_status {
+            self.reported_session_sharing_status = session_sharing_status;
+            self.bus
+                .senders
+                .send_to_plugin(PluginInstruction::Update(vec![(
+                    None,
+                    None,
+                    Event::SessionSharingStatus(session_sharing_status),
+                )]))
+                .context("failed to report session sharing status")?;
+        }
+        Ok(())
+    }
+
+    /// Records what changed in the session since it was last reported and sends any new entries
+    /// of the activity log to plugins
+    fn report_session_activity(&mut self, pane_manifest: &PaneManifest) -> Result<()> {
+        let focused_tabs = self
+            .active_tab_indices
+            .iter()
+            .filter_map(|(client_id, tab_index)| {
+                let tab_position = self.tabs.get(tab_index)?.position;
+                Some((*client_id, (*tab_index, tab_position)))
+            })
+            .collect();
+        let new_entries = self
+            .session_activity
+            .record_changes(pane_manifest, focused_tabs);
+        if !new_entries.is_empty() {
+            self.bus
+                .senders
+                .send_to_plugin(PluginInstruction::Update(vec![(
+                    None,
+                    None,
+                    Event::SessionActivity(new_entries),
+                )]))
+                .context("failed to report session activity")?;
+        }
+        Ok(())
+    }
+
+    pub fn subscribe_pane_output(&mut self, pane_id: PaneId, plugin_id: u32) -> Result<()> {
+        let err_context =
+            || format!("failed to subscribe plugin {plugin_id} to the output of pane {pane_id:?}");
+
+        let pane_exists = match pane_id {
+            PaneId::Terminal(pid) => self
+                .tabs_of_all_workspaces()
+                .any(|tab| tab.has_terminal_pid(pid)),
+            PaneId::Plugin(_) => {
+                return Err(anyhow!("only terminal panes have output")).with_context(err_context)
+            },
+        };
+        if !pane_exists {
+            return Err(anyhow!("pane not found")).with_context(err_context);
+        }
+        self.pane_output_subscriptions
+            .entry(pane_id)
+            .or_insert_with(BTreeMap::new)
+            .entry(plugin_id)
+            .or_insert_with(|| PaneOutputBuffer::new(MAX_BUFFERED_PANE_OUTPUT));
+        Ok(())
+    }
+    pub fn unsubscribe_pane_output(&mut self, pane_id: PaneId, plugin_id: u32) {
+        if let Some(subscriptions) = self.pane_output_subscriptions.get_mut(&pane_id) {
+            subscriptions.remove(&plugin_id);
+            if subscriptions.is_empty() {
+                self.pane_output_subscriptions.remove(&pane_id);
+            }
+        }
+    }
+    /// Removes the subscriptions of a plugin to the output of all panes, eg. when it is unloaded
+    pub fn clear_plugin_pane_output_subscriptions(&mut self, plugin_id: u32) {
+        self.pane_output_subscriptions
+            .retain(|_pane_id, subscriptions| {
+                subscriptions.remove(&plugin_id);
+                !subscriptions.is_empty()
+            });
+    }
+    /// Removes the subscriptions of all plugins to the output of a pane once it is closed, so that
+    /// they don't receive the output of the next pane that gets its id
+    pub fn clear_pane_output_subscriptions(&mut self, pane_id: PaneId) {
+        self.pane_output_subscriptions.remove(&pane_id);
+    }
+    /// Buffers the output of the pane for the plugins subscribed to it, letting the plugin thread
+    /// know when a plugin has new output waiting
+    fn send_pane_output(&self, pid: u32, output: &[u8]) -> Result<()> {
+        let pane_id = PaneId::Terminal(pid);
+        if let Some(subscriptions) = self.pane_output_subscriptions.get(&pane_id) {
+            for (plugin_id, pane_output) in subscriptions {
+                if pane_output.push(output) {
+                    self.bus
+                        .senders
+                        .send_to_plugin(PluginInstruction::PaneOutputQueued(
+                            *plugin_id,
+                            pane_id,
+                            pane_output.clone(),
+                        ))
+                        .with_context(|| format!("failed to send output of pane {pane_id:?}"))?;
+                }
+            }
+        }
+        Ok(())
+    }
+
     /// Returns a mutable reference to this [`Screen`]'s tabs.
     pub fn get_tabs_mut(&mut self) -> &mut BTreeMap<usize, Tab> {
         &mut self.tabs
     }
 
+    /// The tabs of the current workspace followed by those of the hidden ones, whose terminal
+    /// panes keep processing their output so that they are up to date when switched back to
+    fn tabs_of_all_workspaces_mut(&mut self) -> impl Iterator<Item = &mut Tab> {
+        self.tabs.values_mut().chain(
+            self.hidden_workspaces
+                .values_mut()
+                .flat_map(|workspace| workspace.tabs.values_mut()),
+        )
+    }
+
+    fn tabs_of_all_workspaces(&self) -> impl Iterator<Item = &Tab> {
+        self.tabs.values().chain(
+            self.hidden_workspaces
+                .values()
+                .flat_map(|workspace| workspace.tabs.values()),
+        )
+    }
+
+    /// The tabs in the order they appear in the tab bar, which is not that of their index once
+    /// they were moved
+    fn tabs_by_position(tabs: &BTreeMap<usize, Tab>) -> Vec<&Tab> {
+        let mut tabs: Vec<&Tab> = tabs.values().collect();
+        tabs.sort_by_key(|tab| tab.position);
+        tabs
+    }
+
     /// Returns an immutable reference to this [`Screen`]'s active [`Tab`].
     pub fn get_active_tab(&self, client_id: ClientId) -> Result<&Tab> {
         match self.active_tab_indices.get(&client_id) {
@@ -1132,6 +2289,17 @@ impl Screen {
         self.get_tabs_mut().get_mut(&tab_index)
     }
 
+    /// Re-flows the panes of the indexed [`Tab`] into its next or previous swap layout,
+    /// regardless of which tab is active for any client
+    pub fn swap_layout(&mut self, tab_index: usize, direction: SwapLayoutDirection) -> Result<()> {
+        let err_context = || format!("failed to swap layout of tab with index {tab_index}");
+        self.rearrange_tiled_panes(tab_index, |tab| match direction {
+            SwapLayoutDirection::Next => tab.next_swap_layout(None, false),
+            SwapLayoutDirection::Previous => tab.previous_swap_layout(None),
+        })
+        .with_context(err_context)
+    }
+
     /// Creates a new [`Tab`] in this [`Screen`]
     pub fn new_tab(
         &mut self,
@@ -1153,7 +2321,7 @@ impl Screen {
         let tab_name = tab_name.unwrap_or_else(|| String::new());
 
         let position = self.tabs.len();
-        let tab = Tab::new(
+        let mut tab = Tab::new(
             tab_index,
             position,
             tab_name,
@@ -1182,7 +2350,15 @@ impl Screen {
             self.debug,
             self.arrow_fonts,
             self.styled_underlines,
+            self.transparent_background,
+            self.keep_scrollback_in_alternate_screen,
+            self.bell,
         );
+        tab.set_focus_dimming(self.focus_dimming);
+        tab.set_pane_frame_style(self.pane_frame_style);
+        for (client_id, theme) in &self.client_themes {
+            tab.set_client_theme(*client_id, *theme);
+        }
         self.tabs.insert(tab_index, tab);
         Ok(())
     }
@@ -1263,6 +2439,19 @@ impl Screen {
             })
             .with_context(err_context)?;
 
+        // the clients of a workspace that was just created are placed in its first tab
+        let clients_waiting_for_workspace = std::mem::take(&mut self.clients_waiting_for_workspace);
+        if !clients_waiting_for_workspace.is_empty() {
+            for (waiting_client_id, _mode_info) in &clients_waiting_for_workspace {
+                self.update_client_tab_focus(*waiting_client_id, tab_index);
+            }
+            self.tabs
+                .get_mut(&tab_index)
+                .with_context(err_context)?
+                .add_multiple_clients(clients_waiting_for_workspace)
+                .with_context(err_context)?;
+        }
+
         if !self.active_tab_indices.contains_key(&client_id) {
             // this means this is a new client and we need to add it to our state properly
             self.add_client(client_id).with_context(err_context)?;
@@ -1308,7 +2497,7 @@ impl Screen {
     pub fn remove_client(&mut self, client_id: ClientId) -> Result<()> {
         let err_context = || format!("failed to remove client {client_id}");
 
-        for (_, tab) in self.tabs.iter_mut() {
+        for tab in self.tabs_of_all_workspaces_mut() {
             tab.remove_client(client_id);
             if tab.has_no_connected_clients() {
                 tab.visible(false).with_context(err_context)?;
@@ -1320,7 +2509,18 @@ impl Screen {
         if self.tab_history.contains_key(&client_id) {
             self.tab_history.remove(&client_id);
         }
+        for workspace in self.hidden_workspaces.values_mut() {
+            workspace.active_tab_indices.remove(&client_id);
+            workspace.tab_history.remove(&client_id);
+        }
+        self.clients_waiting_for_workspace
+            .retain(|(waiting_client_id, _mode_info)| *waiting_client_id != client_id);
         self.connected_clients.borrow_mut().remove(&client_id);
+        self.observer_clients.remove(&client_id);
+        self.client_themes.remove(&client_id);
+        self.input_latency.remove_client(client_id);
+        self.dock_pane_viewed_by(client_id)
+            .with_context(err_context)?;
         self.log_and_report_session_state()
             .with_context(err_context)
     }
@@ -1342,18 +2542,20 @@ impl Screen {
                 name: tab.name.clone(),
                 active: self.active_tab_indices.values().any(|i| i == &tab.index),
                 panes_to_hide: tab.panes_to_hide_count(),
-                is_fullscreen_active: tab.is_fullscreen_active(),
+                is_fullscreen_active: tab.is_fullscreen_active() || tab.is_zoomed(),
                 is_sync_panes_active: tab.is_sync_panes_active(),
                 are_floating_panes_visible: tab.are_floating_panes_visible(),
                 other_focused_clients: all_focused_clients,
                 active_swap_layout_name,
                 is_swap_layout_dirty,
+                has_bell: tab.has_bell(),
+                color: tab.color().cloned(),
             };
             tab_infos_for_screen_state.insert(tab.position, tab_info_for_screen);
         }
         for (client_id, active_tab_index) in self.active_tab_indices.iter() {
             let mut plugin_tab_updates = vec![];
-            for tab in self.tabs.values() {
+            for tab in Screen::tabs_by_position(&self.tabs) {
                 let other_focused_clients: Vec<ClientId> = if self.session_is_mirrored {
                     vec![]
                 } else {
@@ -1372,12 +2574,14 @@ impl Screen {
                     name: tab.name.clone(),
                     active: *active_tab_index == tab.index,
                     panes_to_hide: tab.panes_to_hide_count(),
-                    is_fullscreen_active: tab.is_fullscreen_active(),
+                    is_fullscreen_active: tab.is_fullscreen_active() || tab.is_zoomed(),
                     is_sync_panes_active: tab.is_sync_panes_active(),
                     are_floating_panes_visible: tab.are_floating_panes_visible(),
                     other_focused_clients,
                     active_swap_layout_name,
                     is_swap_layout_dirty,
+                    has_bell: tab.has_bell(),
+                    color: tab.color().cloned(),
                 };
                 plugin_tab_updates.push(tab_info_for_plugins);
             }
@@ -1410,11 +2614,14 @@ impl Screen {
         // generate own session info
         let pane_manifest = self.generate_and_report_pane_state()?;
         let tab_infos = self.generate_and_report_tab_state()?;
+        self.report_session_activity(&pane_manifest)
+            .with_context(err_context)?;
         let session_info = SessionInfo {
             name: self.session_name.clone(),
             tabs: tab_infos,
             panes: pane_manifest,
             connected_clients: self.active_tab_indices.keys().len(),
+            observer_clients: self.observer_clients.len(),
             is_current_session: true,
         };
         self.bus
@@ -1535,6 +2742,306 @@ impl Screen {
         }
     }
 
+    /// Closes the pane focused by the client, it can be brought back with `undo_layout_change`
+    pub fn close_focused_pane(&mut self, client_id: ClientId) -> Result<()> {
+        let err_context = || format!("failed to close focused pane for client {client_id}");
+
+        let client_id = if self.get_active_tab(client_id).is_ok() {
+            client_id
+        } else if let Some(first_client_id) = self.get_first_client_id() {
+            first_client_id
+        } else {
+            log::error!("No client ids in screen found");
+            return Ok(());
+        };
+        let active_tab = self
+            .get_active_tab_mut(client_id)
+            .with_context(err_context)?;
+        let tab_index = active_tab.index;
+        let pane_id = active_tab.get_active_pane_id(client_id);
+        let is_floating = active_tab
+            .get_floating_panes()
+            .any(|(floating_pane_id, _)| Some(*floating_pane_id) == pane_id);
+        // closing a pane that suppresses another (eg. the scrollback editor) brings that pane back
+        // instead, so the layout does not change
+        let is_suppressing_pane = active_tab
+            .get_suppressed_panes()
+            .any(|(suppressing_pane_id, _)| Some(*suppressing_pane_id) == pane_id);
+        let tiled_pane_geoms = active_tab.tiled_pane_geoms();
+        let closed_pane = active_tab
+            .close_focused_pane(client_id)
+            .with_context(err_context)?;
+        if let Some(pane) = closed_pane.filter(|_| !is_suppressing_pane) {
+            self.layout_undo_stack.push(LayoutChange::ClosedPane {
+                tab_index,
+                pane,
+                is_floating,
+                tiled_pane_geoms,
+            });
+        }
+        Ok(())
+    }
+
+    /// Closes the pane in the active tab of the client, or in whichever tab it is if there is no
+    /// client. It can be brought back with `undo_layout_change`.
+    pub fn close_pane(&mut self, pane_id: PaneId, client_id: Option<ClientId>) -> Result<()> {
+        let tab = match client_id {
+            Some(client_id) => match self.get_active_tab_mut(client_id) {
+                Ok(active_tab) => active_tab,
+                Err(err) => {
+                    Err::<(), _>(err).non_fatal();
+                    return Ok(());
+                },
+            },
+            None => match self
+                .tabs_of_all_workspaces_mut()
+                .find(|tab| tab.get_all_pane_ids().contains(&pane_id))
+            {
+                Some(tab) => tab,
+                None => return Ok(()),
+            },
+        };
+        let tab_index = tab.index;
+        let is_floating = tab
+            .get_floating_panes()
+            .any(|(floating_pane_id, _)| *floating_pane_id == pane_id);
+        let is_suppressing_pane = tab
+            .get_suppressed_panes()
+            .any(|(suppressing_pane_id, _)| *suppressing_pane_id == pane_id);
+        let tiled_pane_geoms = tab.tiled_pane_geoms();
+        let closed_pane = tab.close_pane(pane_id, false, client_id);
+        // the id can be reused by a new pane, which should not start in pass-through mode
+        self.passthrough_panes.remove(&pane_id);
+        if let Some(pane) = closed_pane.filter(|_| !is_suppressing_pane) {
+            self.layout_undo_stack.push(LayoutChange::ClosedPane {
+                tab_index,
+                pane,
+                is_floating,
+                tiled_pane_geoms,
+            });
+        }
+        Ok(())
+    }
+
+    /// Re-flows the panes of the client's active tab into its next or previous swap layout, the
+    /// way they were arranged before can be brought back with `undo_layout_change`
+    pub fn swap_active_tab_layout(
+        &mut self,
+        direction: SwapLayoutDirection,
+        client_id: ClientId,
+    ) -> Result<()> {
+        let err_context = || format!("failed to swap layout of active tab for client {client_id}");
+
+        let client_id = if self.get_active_tab(client_id).is_ok() {
+            client_id
+        } else if let Some(first_client_id) = self.get_first_client_id() {
+            first_client_id
+        } else {
+            log::error!("No client ids in screen found");
+            return Ok(());
+        };
+        let tab_index = self
+            .get_active_tab(client_id)
+            .with_context(err_context)?
+            .index;
+        self.rearrange_tiled_panes(tab_index, |tab| match direction {
+            SwapLayoutDirection::Next => tab.next_swap_layout(Some(client_id), true),
+            SwapLayoutDirection::Previous => tab.previous_swap_layout(Some(client_id)),
+        })
+        .with_context(err_context)
+    }
+
+    fn rearrange_tiled_panes(
+        &mut self,
+        tab_index: usize,
+        rearrange: impl FnOnce(&mut Tab) -> Result<()>,
+    ) -> Result<()> {
+        let tab = self
+            .get_indexed_tab_mut(tab_index)
+            .ok_or_else(|| anyhow!("tab with index {tab_index} does not exist"))?;
+        let tiled_pane_geoms = tab.tiled_pane_geoms();
+        rearrange(tab)?;
+        if tab.tiled_pane_geoms() != tiled_pane_geoms {
+            self.layout_undo_stack.push(LayoutChange::RearrangedPanes {
+                tab_index,
+                tiled_pane_geoms,
+            });
+        }
+        Ok(())
+    }
+
+    /// Undoes the latest change to the layout recorded (closing a pane or a tab, or re-flowing the
+    /// panes of a tab into a swap layout)
+    pub fn undo_layout_change(&mut self, client_id: ClientId) -> Result<()> {
+        let err_context = || format!("failed to undo layout change for client {client_id}");
+
+        let client_id = if self.get_active_tab(client_id).is_ok() {
+            client_id
+        } else if let Some(first_client_id) = self.get_first_client_id() {
+            first_client_id
+        } else {
+            log::error!("No client ids in screen found");
+            return Ok(());
+        };
+        match self.layout_undo_stack.pop() {
+            Some(LayoutChange::ClosedPane {
+                tab_index,
+                pane,
+                is_floating,
+                tiled_pane_geoms,
+            }) => {
+                let pane_id = pane.pid();
+                if !self.tabs.contains_key(&tab_index) {
+                    return Err(anyhow!("the tab the pane was closed in no longer exists"))
+                        .with_context(err_context);
+                }
+                self.reserve_pane_ids(&[pane_id])
+                    .with_context(err_context)?;
+                let tab = self.tabs.get_mut(&tab_index).with_context(err_context)?;
+                tab.restore_closed_pane(pane, is_floating, &tiled_pane_geoms, Some(client_id))
+                    .with_context(err_context)?;
+                let tab_position = tab.position;
+                self.reload_restored_plugin_panes(tab_index, vec![pane_id], client_id)
+                    .with_context(err_context)?;
+                self.switch_active_tab(tab_position, None, true, client_id)
+                    .with_context(err_context)?;
+            },
+            Some(LayoutChange::ClosedTab(tab)) => {
+                self.restore_closed_tab(*tab, client_id)
+                    .with_context(err_context)?;
+            },
+            Some(LayoutChange::RearrangedPanes {
+                tab_index,
+                tiled_pane_geoms,
+            }) => {
+                let tab = self
+                    .tabs
+                    .get_mut(&tab_index)
+                    .ok_or_else(|| anyhow!("the tab whose panes were rearranged no longer exists"))
+                    .with_context(err_context)?;
+                if !tab
+                    .restore_tiled_pane_geoms(&tiled_pane_geoms)
+                    .with_context(err_context)?
+                {
+                    return Err(anyhow!(
+                        "the panes of the tab changed since they were rearranged"
+                    ))
+                    .with_context(err_context);
+                }
+            },
+            None => {
+                return Err(anyhow!("there is no layout change to undo")).with_context(err_context);
+            },
+        }
+        self.log_and_report_session_state()
+            .and_then(|_| self.render())
+            .with_context(err_context)
+    }
+
+    fn restore_closed_tab(&mut self, mut tab: Tab, client_id: ClientId) -> Result<()> {
+        let tab_name = tab.name.clone();
+        let err_context = || format!("failed to restore closed tab {tab_name:?}");
+
+        self.reserve_pane_ids(&tab.get_all_pane_ids())
+            .with_context(err_context)?;
+        let tab_index = self.get_new_tab_index();
+        let tab_position = std::cmp::min(tab.position, self.tabs.len());
+        for t in self.tabs.values_mut() {
+            if t.position >= tab_position {
+                t.position += 1;
+            }
+        }
+        tab.index = tab_index;
+        tab.position = tab_position;
+        tab.hold_closed_terminal_panes();
+        tab.resize_whole_tab(self.size).with_context(err_context)?;
+        let pane_ids = tab.get_all_pane_ids();
+        self.tabs.insert(tab_index, tab);
+        self.reload_restored_plugin_panes(tab_index, pane_ids, client_id)
+            .with_context(err_context)?;
+        self.switch_active_tab(tab_position, None, true, client_id)
+            .with_context(err_context)
+    }
+
+    // terminal ids are released once their pane is closed, so a new pane might have taken the id
+    // of a closed pane in the meantime. Otherwise, the ids of the terminal panes are reserved again
+    // so that they are not given to new panes while the restored panes hold them.
+    fn reserve_pane_ids(&mut self, pane_ids: &[PaneId]) -> Result<()> {
+        if let Some(taken_pane_id) = pane_ids.iter().find(|pane_id| {
+            self.tabs_of_all_workspaces()
+                .any(|tab| tab.has_pane_with_pid(pane_id))
+        }) {
+            return Err(anyhow!(
+                "pane id {taken_pane_id:?} was taken by a new pane since the pane was closed"
+            ));
+        }
+        let terminal_ids: Vec<u32> = pane_ids
+            .iter()
+            .filter_map(|pane_id| match pane_id {
+                PaneId::Terminal(terminal_id) => Some(*terminal_id),
+                PaneId::Plugin(_) => None,
+            })
+            .collect();
+        match self.bus.os_input.as_ref() {
+            Some(os_input) if !terminal_ids.is_empty() => os_input
+                .reserve_terminal_ids(&terminal_ids)
+                .context("the pane was closed and its id was given to a new pane since"),
+            _ => Ok(()),
+        }
+    }
+
+    /// Loads the plugins of the plugin panes among those that were brought back after being
+    /// closed, each in place of its pane
+    fn reload_restored_plugin_panes(
+        &mut self,
+        tab_index: usize,
+        pane_ids: Vec<PaneId>,
+        client_id: ClientId,
+    ) -> Result<()> {
+        let err_context = || format!("failed to reload restored plugin panes");
+
+        let tab = self.tabs.get(&tab_index).with_context(err_context)?;
+        let plugin_panes: Vec<(u32, bool, Option<String>, RunPlugin)> = tab
+            .get_tiled_panes()
+            .map(|(pane_id, pane)| (pane_id, pane, false))
+            .chain(
+                tab.get_floating_panes()
+                    .map(|(pane_id, pane)| (pane_id, pane, true)),
+            )
+            .filter(|(pane_id, _pane, _is_floating)| pane_ids.contains(pane_id))
+            .filter_map(
+                |(pane_id, pane, is_floating)| match (pane_id, pane.invoked_with()) {
+                    (PaneId::Plugin(plugin_id), Some(Run::Plugin(run_plugin))) => Some((
+                        *plugin_id,
+                        is_floating,
+                        pane.custom_title(),
+                        run_plugin.clone(),
+                    )),
+                    _ => None,
+                },
+            )
+            .collect();
+        for (plugin_id, is_floating, pane_title, run_plugin) in plugin_panes {
+            self.restored_plugin_panes.insert(plugin_id);
+            let should_be_open_in_place = true;
+            self.bus
+                .senders
+                .send_to_plugin(PluginInstruction::Load(
+                    Some(is_floating),
+                    should_be_open_in_place,
+                    pane_title,
+                    run_plugin,
+                    tab_index,
+                    Some(PaneId::Plugin(plugin_id)),
+                    client_id,
+                    Size::default(),
+                    None,
+                ))
+                .with_context(err_context)?;
+        }
+        Ok(())
+    }
+
     pub fn change_mode(&mut self, mut mode_info: ModeInfo, client_id: ClientId) -> Result<()> {
         if mode_info.session_name.as_ref() != Some(&self.session_name) {
             mode_info.session_name = Some(self.session_name.clone());
@@ -1560,6 +3067,37 @@ impl Screen {
             active_tab!(self, client_id, |tab: &mut Tab| tab.clear_search(client_id));
         }
 
+        if previous_mode != mode_info.mode {
+            self.bus
+                .senders
+                .send_to_plugin(PluginInstruction::Update(vec![(
+                    None,
+                    Some(client_id),
+                    Event::ModeChanged(mode_info.mode),
+                )]))
+                .with_context(err_context)?;
+        }
+
+        // the search related modes are where the user scrolls and copies text, plugins assisting
+        // with this want to know when they are entered and left
+        if search_related_modes.contains(&previous_mode)
+            != search_related_modes.contains(&mode_info.mode)
+        {
+            let copy_mode_event = if search_related_modes.contains(&mode_info.mode) {
+                Event::EnteredCopyMode
+            } else {
+                Event::ExitedCopyMode
+            };
+            self.bus
+                .senders
+                .send_to_plugin(PluginInstruction::Update(vec![(
+                    None,
+                    Some(client_id),
+                    copy_mode_event,
+                )]))
+                .with_context(err_context)?;
+        }
+
         if previous_mode == InputMode::Scroll
             && (mode_info.mode == InputMode::Normal || mode_info.mode == InputMode::Locked)
         {
@@ -1587,6 +3125,9 @@ impl Screen {
         }
 
         self.style = mode_info.style;
+        if let Some(theme) = self.client_themes.get(&client_id) {
+            mode_info.style.colors = *theme;
+        }
         self.mode_info.insert(client_id, mode_info.clone());
         for tab in self.tabs.values_mut() {
             tab.change_mode_info(mode_info.clone(), client_id);
@@ -1763,33 +3304,445 @@ impl Screen {
         }
     }
 
+    /// Focuses the pane with this id in whichever tab it is, switching the client to that tab
     pub fn focus_pane_with_id(
         &mut self,
         pane_id: PaneId,
         should_float_if_hidden: bool,
         client_id: ClientId,
     ) -> Result<()> {
-        let err_context = || format!("failed to focus_plugin_pane");
-        let tab_index = self
+        let err_context = || format!("failed to focus pane {pane_id:?} for client {client_id}");
+
+        let (tab_index, tab_position) = self
             .tabs
             .iter()
             .find(|(_tab_index, tab)| tab.has_pane_with_pid(&pane_id))
-            .map(|(tab_index, _tab)| *tab_index);
-        match tab_index {
-            Some(tab_index) => {
-                self.go_to_tab(tab_index + 1, client_id)?;
-                self.tabs
-                    .get_mut(&tab_index)
-                    .with_context(err_context)?
-                    .focus_pane_with_id(pane_id, should_float_if_hidden, client_id)
-                    .context("failed to focus pane with id")?;
+            .map(|(tab_index, tab)| (*tab_index, tab.position))
+            .ok_or_else(|| anyhow!("pane {pane_id:?} does not exist"))
+            .with_context(err_context)?;
+        self.switch_active_tab(tab_position, None, true, client_id)
+            .with_context(err_context)?;
+        self.tabs
+            .get_mut(&tab_index)
+            .with_context(err_context)?
+            .focus_pane_with_id(pane_id, should_float_if_hidden, client_id)
+            .with_context(err_context)
+    }
+    /// Opens a new pane next to this one in the same cwd, running the same command in a new
+    /// process if it is a command pane. The scrollback of the pane is not copied.
+    pub fn duplicate_pane(&mut self, pane_id: PaneId, client_id: ClientId) -> Result<()> {
+        let err_context = || format!("failed to duplicate pane {pane_id:?} for client {client_id}");
+
+        if let PaneId::Plugin(_) = pane_id {
+            return Err(anyhow!("only terminal panes can be duplicated")).with_context(err_context);
+        }
+        // the new pane is split from the focused pane, and the pty thread takes its cwd from it
+        self.focus_pane_with_id(pane_id, false, client_id)
+            .with_context(err_context)?;
+        let active_tab = self
+            .get_active_tab_mut(client_id)
+            .with_context(err_context)?;
+        active_tab
+            .update_active_panes_in_pty_thread()
+            .with_context(err_context)?;
+        let terminal_action = active_tab.terminal_action_to_duplicate_pane(pane_id);
+        let pty_instruction = match active_tab.split_direction_for_pane(pane_id) {
+            Some(SplitDirection::Horizontal) => {
+                PtyInstruction::SpawnTerminalHorizontally(terminal_action, None, client_id)
+            },
+            Some(SplitDirection::Vertical) => {
+                PtyInstruction::SpawnTerminalVertically(terminal_action, None, client_id)
             },
             None => {
-                log::error!("Could not find pane with id: {:?}", pane_id);
+                let should_float = Some(true);
+                PtyInstruction::SpawnTerminal(
+                    terminal_action,
+                    should_float,
+                    None,
+                    ClientTabIndexOrPaneId::ClientId(client_id),
+                )
+            },
+        };
+        self.bus
+            .senders
+            .send_to_pty(pty_instruction)
+            .with_context(err_context)
+    }
+    pub fn set_pane_min_size(
+        &mut self,
+        pane_id: PaneId,
+        min_cols: usize,
+        min_rows: usize,
+    ) -> Result<()> {
+        let err_context = || format!("failed to set minimum size of pane {pane_id:?}");
+
+        let min_size = Size {
+            rows: min_rows,
+            cols: min_cols,
+        };
+        self.tabs
+            .values_mut()
+            .find(|tab| tab.has_pane_with_pid(&pane_id))
+            .ok_or_else(|| anyhow!("pane {pane_id:?} does not exist"))
+            .and_then(|tab| tab.set_pane_min_size(pane_id, min_size))
+            .with_context(err_context)
+    }
+    /// Sets the number of lines the mouse wheel scrolls this pane by, `None` to go back to the
+    /// default from the configuration
+    pub fn set_pane_scroll_lines(
+        &mut self,
+        pane_id: PaneId,
+        scroll_lines: Option<ScrollLines>,
+    ) -> Result<()> {
+        self.tabs
+            .values_mut()
+            .find(|tab| tab.has_pane_with_pid(&pane_id))
+            .ok_or_else(|| anyhow!("pane {pane_id:?} does not exist"))
+            .and_then(|tab| tab.set_pane_scroll_lines(pane_id, scroll_lines))
+            .with_context(|| format!("failed to set scroll lines of pane {pane_id:?}"))
+    }
+    /// Splits the tiled pane `pane_id` in whichever tab it is, see [`Tab::split_pane_with_ratio`]
+    pub fn split_pane_with_ratio(
+        &mut self,
+        pane_id: PaneId,
+        new_pane_id: PaneId,
+        direction: SplitDirection,
+        ratio: f64,
+        client_id: ClientId,
+    ) -> Result<()> {
+        self.tabs
+            .values_mut()
+            .find(|tab| tab.has_pane_with_pid(&pane_id))
+            .ok_or_else(|| anyhow!("pane {pane_id:?} does not exist"))
+            .and_then(|tab| {
+                tab.split_pane_with_ratio(pane_id, new_pane_id, direction, ratio, client_id)
+            })
+            .with_context(|| format!("failed to split pane {pane_id:?}"))
+    }
+    /// Resizes the pane to an absolute size in cells, returning the size that could actually be
+    /// applied (see [`Tab::resize_pane_to`])
+    pub fn resize_pane_to(&mut self, pane_id: PaneId, cols: usize, rows: usize) -> Result<Size> {
+        let err_context = || format!("failed to resize pane {pane_id:?} to {cols}x{rows}");
+
+        self.tabs
+            .values_mut()
+            .find(|tab| tab.has_pane_with_pid(&pane_id))
+            .ok_or_else(|| anyhow!("pane {pane_id:?} does not exist"))
+            .and_then(|tab| tab.resize_pane_to(pane_id, Size { rows, cols }))
+            .with_context(err_context)
+    }
+    pub fn search_and_highlight_in_pane(
+        &mut self,
+        pane_id: PaneId,
+        query: &str,
+        case_sensitive: bool,
+    ) -> Result<Option<PaneSearchMatches>> {
+        self.tabs
+            .values_mut()
+            .find(|tab| tab.has_pane_with_pid(&pane_id))
+            .ok_or_else(|| anyhow!("pane {pane_id:?} does not exist"))
+            .and_then(|tab| tab.search_and_highlight_in_pane(pane_id, query, case_sensitive))
+            .with_context(|| format!("failed to search for {query:?} in pane {pane_id:?}"))
+    }
+    /// The plain text contents of the pane, as they would be read rather than rendered (see
+    /// [`Tab::capture_pane_text`])
+    pub fn capture_pane_text(
+        &mut self,
+        pane_id: PaneId,
+        include_scrollback: bool,
+    ) -> Result<String> {
+        self.tabs
+            .values_mut()
+            .find(|tab| tab.has_pane_with_pid(&pane_id))
+            .ok_or_else(|| anyhow!("pane {pane_id:?} does not exist"))
+            .and_then(|tab| tab.capture_pane_text(pane_id, include_scrollback))
+            .with_context(|| format!("failed to capture the text of pane {pane_id:?}"))
+    }
+    pub fn clear_search_in_pane(&mut self, pane_id: PaneId) -> Result<()> {
+        self.tabs
+            .values_mut()
+            .find(|tab| tab.has_pane_with_pid(&pane_id))
+            .ok_or_else(|| anyhow!("pane {pane_id:?} does not exist"))
+            .and_then(|tab| tab.clear_search_in_pane(pane_id))
+            .with_context(|| format!("failed to clear search in pane {pane_id:?}"))
+    }
+    /// The pane an instruction is about: `pane_id` if given, otherwise the focused pane of the
+    /// client. Like with `active_tab_and_connected_client_id!`, clients that are not connected to
+    /// the screen (eg. `zellij action` run outside of the session) get the focused pane of the
+    /// first connected client.
+    pub fn pane_id_or_focused_pane(
+        &self,
+        pane_id: Option<PaneId>,
+        client_id: ClientId,
+    ) -> Result<PaneId> {
+        let focused_pane = |client_id| {
+            self.get_active_tab(client_id)
+                .ok()
+                .and_then(|tab| tab.get_active_pane_id(client_id))
+        };
+        match pane_id {
+            Some(pane_id) => Ok(pane_id),
+            None if self.active_tab_indices.contains_key(&client_id) => focused_pane(client_id)
+                .ok_or_else(|| anyhow!("client {client_id} has no focused pane")),
+            None => self
+                .get_first_client_id()
+                .and_then(focused_pane)
+                .ok_or_else(|| anyhow!("no client has a focused pane")),
+        }
+    }
+    /// The client an instruction is about: like with `active_tab_and_connected_client_id!`,
+    /// clients that are not connected to the screen (eg. `zellij action` run outside of the
+    /// session) stand for the first connected client.
+    pub fn connected_client_id(&self, client_id: ClientId) -> ClientId {
+        if self.active_tab_indices.contains_key(&client_id) {
+            client_id
+        } else {
+            self.get_first_client_id().unwrap_or(client_id)
+        }
+    }
+    /// The tab an instruction is about: `tab_index` if given, otherwise the active tab of the
+    /// client (see [`Screen::connected_client_id`])
+    pub fn tab_index_or_active_tab(
+        &self,
+        tab_index: Option<usize>,
+        client_id: ClientId,
+    ) -> Result<usize> {
+        match tab_index {
+            Some(tab_index) => Ok(tab_index),
+            None => self
+                .get_active_tab(self.connected_client_id(client_id))
+                .map(|tab| tab.index),
+        }
+    }
+    /// The index of the tab at this position of the tab bar (starting at 0)
+    pub fn tab_index_at_position(&self, tab_position: usize) -> Result<usize> {
+        self.tabs
+            .values()
+            .find(|tab| tab.position == tab_position)
+            .map(|tab| tab.index)
+            .ok_or_else(|| anyhow!("no tab at position {}", tab_position + 1))
+    }
+    pub fn toggle_show_invisibles(&mut self, pane_id: PaneId) -> Result<()> {
+        self.tabs
+            .values_mut()
+            .find(|tab| tab.has_pane_with_pid(&pane_id))
+            .ok_or_else(|| anyhow!("pane {pane_id:?} does not exist"))
+            .and_then(|tab| tab.toggle_pane_show_invisibles(pane_id))
+            .with_context(|| format!("failed to toggle showing invisibles in pane {pane_id:?}"))
+    }
+    /// Opens a new terminal window with the given command, viewing the focused pane of this client
+    /// in isolation (see [`Screen::view_pane_in_isolation`])
+    pub fn pop_out_pane(
+        &mut self,
+        terminal_command: Option<&str>,
+        client_id: ClientId,
+    ) -> Result<()> {
+        let err_context = || format!("failed to pop out pane for client {client_id}");
+
+        let active_pane_id = self
+            .get_active_tab(client_id)
+            .with_context(err_context)?
+            .get_active_pane_id(client_id)
+            .with_context(err_context)?;
+        let error_message = match (active_pane_id, terminal_command) {
+            _ if self.session_is_mirrored => Some("Cannot pop out panes of a mirrored session!"),
+            (PaneId::Plugin(_), _) => Some("Only terminal panes can be popped out!"),
+            (_, None) => Some("Set pop_out_terminal_command to pop out panes!"),
+            (PaneId::Terminal(terminal_id), Some(terminal_command)) => {
+                match launch_pop_out_window(terminal_command, &self.session_name, terminal_id) {
+                    Ok(()) => None,
+                    Err(e) => {
+                        log::error!("{:?}", e);
+                        Some("Failed to open a new terminal window!")
+                    },
+                }
             },
         };
+        if let Some(error_message) = error_message {
+            self.bus
+                .senders
+                .send_to_background_jobs(BackgroundJob::DisplayPaneError(
+                    vec![active_pane_id],
+                    error_message.into(),
+                ))
+                .with_context(err_context)?;
+        }
+        Ok(())
+    }
+    /// Moves the pane out of its tab into a new one viewed by this client alone (eg. a pop-out
+    /// window), until the client detaches and the pane is docked back into its tab. A pane that is
+    /// the only tiled pane of its tab, or one in a mirrored session, is viewed in place instead.
+    pub fn view_pane_in_isolation(&mut self, pane_id: PaneId, client_id: ClientId) -> Result<()> {
+        let err_context =
+            || format!("failed to view pane {pane_id:?} in isolation for client {client_id}");
+
+        if !self.popped_out_panes.contains_key(&pane_id) {
+            let origin_tab = self
+                .tabs
+                .values_mut()
+                .find(|tab| tab.has_pane_with_pid(&pane_id))
+                .ok_or_else(|| anyhow!("no pane with id {pane_id:?}"))
+                .with_context(err_context)?;
+            let origin_tab_keeps_tiled_panes = origin_tab
+                .get_tiled_panes()
+                .any(|(id, pane)| *id != pane_id && pane.selectable());
+            if !origin_tab_keeps_tiled_panes || self.session_is_mirrored {
+                return self
+                    .focus_pane_with_id(pane_id, false, client_id)
+                    .with_context(err_context);
+            }
+            let origin_tab_index = origin_tab.index;
+            let pane_was_floating = origin_tab
+                .get_floating_panes()
+                .any(|(id, _)| *id == pane_id);
+            let pane = origin_tab
+                .close_pane(pane_id, false, None)
+                .with_context(err_context)?;
+            let run_instruction = pane.invoked_with().cloned();
+            let tab_name = pane.current_title();
+            let tab_index = self.get_new_tab_index();
+            self.new_tab(tab_index, (vec![], vec![]), Some(tab_name), client_id)
+                .with_context(err_context)?;
+            let tab = self.tabs.get_mut(&tab_index).with_context(err_context)?;
+            tab.add_tiled_pane(pane, pane_id, None)
+                .with_context(err_context)?;
+            let mut layout = TiledPaneLayout::default();
+            layout.run = run_instruction.clone();
+            layout.ignore_run_instruction(run_instruction);
+            tab.apply_layout(layout, vec![], vec![], vec![], HashMap::new(), client_id)
+                .with_context(err_context)?;
+            tab.update_input_modes().with_context(err_context)?;
+            self.popped_out_panes
+                .insert(pane_id, (tab_index, origin_tab_index, pane_was_floating));
+        }
+        self.pane_view_clients.insert(client_id, pane_id);
+        self.focus_pane_with_id(pane_id, false, client_id)
+            .with_context(err_context)
+    }
+    fn dock_pane_viewed_by(&mut self, client_id: ClientId) -> Result<()> {
+        let err_context = || format!("failed to dock pane viewed by client {client_id}");
+
+        let pane_id = match self.pane_view_clients.remove(&client_id) {
+            Some(pane_id) => pane_id,
+            None => return Ok(()),
+        };
+        if self.pane_view_clients.values().any(|p| *p == pane_id) {
+            // another client still views this pane
+            return Ok(());
+        }
+        let (tab_index, origin_tab_index, pane_was_floating) =
+            match self.popped_out_panes.remove(&pane_id) {
+                Some(popped_out_pane) => popped_out_pane,
+                None => return Ok(()),
+            };
+        // the client might have left the pane for a tab of another workspace
+        let pane_is_still_popped_out = self
+            .tabs_of_all_workspaces()
+            .any(|tab| tab.index == tab_index && tab.has_pane_with_pid(&pane_id));
+        // if the tab the pane was popped out of was closed in the meantime, we dock it into the
+        // first tab we find
+        let destination_tab_index = Some(origin_tab_index)
+            .filter(|index| self.tabs_of_all_workspaces().any(|tab| tab.index == *index))
+            .or_else(|| self.tabs.keys().copied().find(|index| *index != tab_index));
+        if let Some(destination_tab_index) =
+            destination_tab_index.filter(|_| pane_is_still_popped_out)
+        {
+            let pane = self
+                .tabs_of_all_workspaces_mut()
+                .find(|tab| tab.index == tab_index)
+                .and_then(|tab| tab.close_pane(pane_id, false, None))
+                .with_context(err_context)?;
+            let pane_that_did_not_fit = self
+                .tabs_of_all_workspaces_mut()
+                .find(|tab| tab.index == destination_tab_index)
+                .with_context(err_context)?
+                .dock_pane(pane, pane_id, pane_was_floating)
+                .with_context(err_context)?;
+            match pane_that_did_not_fit {
+                Some(pane) => {
+                    // the pane stays in the tab it was popped out into rather than being lost
+                    log::error!(
+                        "no room to dock pane {pane_id:?} into tab {destination_tab_index}"
+                    );
+                    self.tabs_of_all_workspaces_mut()
+                        .find(|tab| tab.index == tab_index)
+                        .with_context(err_context)?
+                        .add_tiled_pane(pane, pane_id, None)
+                        .with_context(err_context)?;
+                },
+                None => self
+                    .close_tab_of_any_workspace(tab_index)
+                    .with_context(err_context)?,
+            }
+        }
         Ok(())
     }
+    /// Focuses the pane with the given name (eg. as set with `RenamePane`) in any tab. If several
+    /// panes share the name, the first one in tab order is focused.
+    pub fn focus_pane_by_name(&mut self, name: &str, client_id: ClientId) -> Result<()> {
+        let err_context = || format!("failed to focus pane named {name:?} for client {client_id}");
+
+        let mut tabs: Vec<&Tab> = self.tabs.values().collect();
+        tabs.sort_by_key(|tab| tab.position);
+        let pane_id = tabs
+            .iter()
+            .find_map(|tab| {
+                // tiled panes come before floating panes, each in the order of their ids
+                tab.get_tiled_panes()
+                    .chain(tab.get_floating_panes())
+                    .find(|(_pane_id, pane)| pane.current_title() == name)
+                    .map(|(pane_id, _pane)| *pane_id)
+            })
+            .ok_or_else(|| anyhow!("no pane named {name:?}"))
+            .with_context(err_context)?;
+        self.focus_pane_with_id(pane_id, false, client_id)
+            .with_context(err_context)
+    }
+    /// Closes the panes matching `predicate` in all tabs, each as if it was closed on its own.
+    /// Tabs left without panes are then closed when rendering.
+    pub fn close_panes_matching(
+        &mut self,
+        predicate: PanePredicate,
+        client_id: ClientId,
+    ) -> Result<()> {
+        let err_context =
+            || format!("failed to close panes matching {predicate:?} for client {client_id}");
+
+        let name_matcher = match &predicate {
+            PanePredicate::NameGlob(name_glob) => Some(
+                Glob::new(name_glob)
+                    .with_context(err_context)?
+                    .compile_matcher(),
+            ),
+            _ => None,
+        };
+        let matches = |pane: &dyn Pane| match &predicate {
+            PanePredicate::ExitedZero => pane.exited() && pane.exit_status() == Some(0),
+            PanePredicate::ExitedNonZero => pane.exited() && pane.exit_status() != Some(0),
+            PanePredicate::NameGlob(_) => name_matcher
+                .as_ref()
+                .map(|name_matcher| name_matcher.is_match(pane.current_title()))
+                .unwrap_or(false),
+        };
+        for tab in self.tabs.values_mut() {
+            let pane_ids: Vec<PaneId> = tab
+                .get_tiled_panes()
+                .chain(tab.get_floating_panes())
+                .filter(|(_pane_id, pane)| matches(pane.as_ref()))
+                .map(|(pane_id, _pane)| *pane_id)
+                .collect();
+            for pane_id in pane_ids {
+                tab.close_pane(pane_id, false, None);
+                self.bus
+                    .senders
+                    .send_to_pty(PtyInstruction::ClosePane(pane_id))
+                    .with_context(err_context)?;
+            }
+        }
+        self.log_and_report_session_state()
+            .with_context(err_context)?;
+        self.render().with_context(err_context)
+    }
     pub fn break_pane(
         &mut self,
         default_shell: Option<TerminalAction>,
@@ -1982,8 +3935,16 @@ impl Screen {
         let active_tab_index =
             first_client_id.and_then(|client_id| self.active_tab_indices.get(&client_id));
 
-        for (tab_index, tab) in self.tabs.values().enumerate() {
-            let tab_is_focused = active_tab_index == Some(&tab_index);
+        let tabs_of_hidden_workspaces = self
+            .hidden_workspaces
+            .values()
+            .flat_map(|workspace| Screen::tabs_by_position(&workspace.tabs));
+        for (is_in_current_workspace, tab) in Screen::tabs_by_position(&self.tabs)
+            .into_iter()
+            .map(|tab| (true, tab))
+            .chain(tabs_of_hidden_workspaces.map(|tab| (false, tab)))
+        {
+            let tab_is_focused = is_in_current_workspace && active_tab_index == Some(&tab.index);
             let hide_floating_panes = !tab.are_floating_panes_visible();
             let mut suppressed_panes = HashMap::new();
             for (triggering_pane_id, p) in tab.get_suppressed_panes() {
@@ -2006,6 +3967,16 @@ impl Screen {
                         _ => (*pane_id, p),
                     }
                 })
+                .filter(|(_pane_id, p)| {
+                    // command panes whose process exited successfully have done their job, so
+                    // unless configured otherwise they are not brought back on resurrection
+                    self.serialize_successfully_exited_panes
+                        || !p.exited()
+                        || p.exit_status() != Some(0)
+                })
+                // panes fed by a plugin or mirroring another pane have no command and would be
+                // resurrected as shells
+                .filter(|(_pane_id, p)| p.fed_by_plugin().is_none() && p.alias_of().is_none())
                 .map(|(pane_id, p)| {
                     PaneLayoutMetadata::new(
                         pane_id,
@@ -2014,6 +3985,8 @@ impl Screen {
                         p.invoked_with().cloned(),
                         p.custom_title(),
                         active_pane_id == Some(pane_id),
+                        false, // only floating panes can be pinned
+                        p.read_only(),
                         if self.serialize_pane_viewport {
                             p.serialize(self.scrollback_lines_to_serialize)
                         } else {
@@ -2037,6 +4010,14 @@ impl Screen {
                         _ => (*pane_id, p),
                     }
                 })
+                .filter(|(_pane_id, p)| {
+                    self.serialize_successfully_exited_panes
+                        || !p.exited()
+                        || p.exit_status() != Some(0)
+                })
+                // panes fed by a plugin or mirroring another pane have no command and would be
+                // resurrected as shells
+                .filter(|(_pane_id, p)| p.fed_by_plugin().is_none() && p.alias_of().is_none())
                 .map(|(pane_id, p)| {
                     PaneLayoutMetadata::new(
                         pane_id,
@@ -2045,6 +4026,8 @@ impl Screen {
                         p.invoked_with().cloned(),
                         p.custom_title(),
                         active_pane_id == Some(pane_id),
+                        tab.pane_is_pinned(&pane_id),
+                        p.read_only(),
                         if self.serialize_pane_viewport {
                             p.serialize(self.scrollback_lines_to_serialize)
                         } else {
@@ -2057,6 +4040,7 @@ impl Screen {
                 tab.name.clone(),
                 tab_is_focused,
                 hide_floating_panes,
+                tab.color().cloned(),
                 tiled_panes,
                 floating_panes,
             );
@@ -2065,6 +4049,9 @@ impl Screen {
     }
 }
 
+/// How long we wait for more terminal resizes after the first one before re-flowing to the latest
+const TERMINAL_RESIZE_COALESCING_WINDOW: Duration = Duration::from_millis(20);
+
 // The box is here in order to make the
 // NewClient enum smaller
 #[allow(clippy::boxed_local)]
@@ -2082,6 +4069,9 @@ pub(crate) fn screen_thread_main(
     let session_serialization = config_options.session_serialization.unwrap_or(true);
     let serialize_pane_viewport = config_options.serialize_pane_viewport.unwrap_or(false);
     let scrollback_lines_to_serialize = config_options.scrollback_lines_to_serialize;
+    let serialize_successfully_exited_panes = config_options
+        .serialize_successfully_exited_panes
+        .unwrap_or(true);
     let session_is_mirrored = config_options.mirror_session.unwrap_or(false);
     let default_shell = config_options.default_shell;
     let copy_options = CopyOptions::new(
@@ -2090,6 +4080,27 @@ pub(crate) fn screen_thread_main(
         config_options.copy_on_select.unwrap_or(true),
     );
     let styled_underlines = config_options.styled_underlines.unwrap_or(true);
+    let transparent_background = config_options.transparent_background.unwrap_or(false);
+    let keep_scrollback_in_alternate_screen = config_options
+        .keep_scrollback_in_alternate_screen
+        .unwrap_or(false);
+    let pop_out_terminal_command = config_options.pop_out_terminal_command;
+    // a log of size 0 (eg. from the command line) would not keep any activity for plugins to read
+    let session_activity_log_size = config_options
+        .session_activity_log_size
+        .filter(|session_activity_log_size| *session_activity_log_size > 0)
+        .unwrap_or(DEFAULT_SESSION_ACTIVITY_LOG_SIZE);
+    let scroll_lines = config_options.scroll_lines.unwrap_or_default();
+    let layout_undo_depth = config_options
+        .layout_undo_depth
+        .unwrap_or(DEFAULT_LAYOUT_UNDO_DEPTH);
+    let surplus_panes = config_options.surplus_panes.unwrap_or_default();
+    let bell = config_options.bell.unwrap_or_default();
+    let pane_frame_style = config_options.pane_frame_style;
+    let focus_dimming = config_options
+        .focus_dimming
+        .filter(|percent| *percent > 0)
+        .map(|percent| f64::from(percent.min(100)) / 100.0);
 
     let thread_senders = bus.senders.clone();
     let mut screen = Screen::new(
@@ -2114,44 +4125,96 @@ pub(crate) fn screen_thread_main(
         session_serialization,
         serialize_pane_viewport,
         scrollback_lines_to_serialize,
+        serialize_successfully_exited_panes,
         styled_underlines,
         arrow_fonts,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
+        session_activity_log_size,
+        scroll_lines,
+        layout_undo_depth,
+        surplus_panes,
+        bell,
+        pane_frame_style,
+        focus_dimming,
     );
 
     let mut pending_tab_ids: HashSet<usize> = HashSet::new();
     let mut pending_tab_switches: HashSet<(usize, ClientId)> = HashSet::new(); // usize is the
                                                                                // tab_index
+    let mut pending_terminal_resize: Option<(Size, Instant)> = None; // latest size, deadline
 
     loop {
-        let (event, mut err_ctx) = screen
-            .bus
-            .recv()
-            .context("failed to receive event on channel")?;
+        let (event, mut err_ctx) = match pending_terminal_resize {
+            Some((_new_size, deadline)) => {
+                match screen
+                    .bus
+                    .recv_timeout(deadline.saturating_duration_since(Instant::now()))
+                {
+                    Ok(received) => received,
+                    Err(RecvTimeoutError::Timeout) => {
+                        if let Some((new_size, _deadline)) = pending_terminal_resize.take() {
+                            let _resize_cache = ResizeCache::new(thread_senders.clone());
+                            screen.resize_to_screen(new_size)?;
+                        }
+                        continue;
+                    },
+                    Err(RecvTimeoutError::Disconnected) => {
+                        return Err(anyhow!("failed to receive event on channel"));
+                    },
+                }
+            },
+            None => screen
+                .bus
+                .recv()
+                .context("failed to receive event on channel")?,
+        };
         err_ctx.add_call(ContextType::Screen((&event).into()));
         // here we start caching resizes, so that we'll send them in bulk at the end of each event
         // when this cache is Dropped, for more information, see the comments in PtyWriter
         let _resize_cache = ResizeCache::new(thread_senders.clone());
 
+        if !matches!(event, ScreenInstruction::TerminalResize(..)) {
+            if let Some((new_size, _deadline)) = pending_terminal_resize.take() {
+                // whatever comes after the resize expects it to have been applied
+                screen.resize_to_screen(new_size)?;
+            }
+        }
+
         match event {
             ScreenInstruction::PtyBytes(pid, vte_bytes) => {
-                let all_tabs = screen.get_tabs_mut();
-                for tab in all_tabs.values_mut() {
+                screen.send_pane_output(pid, &vte_bytes).non_fatal();
+                let mut tab_was_marked_with_bell = false;
+                for tab in screen.tabs_of_all_workspaces_mut() {
                     if tab.has_terminal_pid(pid) {
+                        let had_bell = tab.has_bell();
                         tab.handle_pty_bytes(pid, vte_bytes)
                             .context("failed to process pty bytes")?;
+                        tab_was_marked_with_bell = !had_bell && tab.has_bell();
                         break;
                     }
                 }
+                if tab_was_marked_with_bell {
+                    screen.log_and_report_session_state()?;
+                }
             },
-            ScreenInstruction::PluginBytes(mut plugin_bytes) => {
-                for (pid, client_id, vte_bytes) in plugin_bytes.drain(..) {
-                    let all_tabs = screen.get_tabs_mut();
-                    for tab in all_tabs.values_mut() {
-                        if tab.has_plugin(pid) {
-                            tab.handle_plugin_bytes(pid, client_id, vte_bytes)
-                                .context("failed to process plugin bytes")?;
-                            break;
-                        }
+            ScreenInstruction::PluginBytes(plugin_bytes) => {
+                screen.handle_plugin_bytes(plugin_bytes)?;
+                screen.render()?;
+            },
+            ScreenInstruction::PluginRenderQueued(render_queue, frame_count) => {
+                // frames are popped one plugin at a time, so that a plugin flooding the queue
+                // does not hold back the renders of other plugins
+                let plugin_frames = render_queue.pop_round_robin(frame_count);
+                screen.handle_plugin_frames(plugin_frames)?;
+                screen.render()?;
+            },
+            ScreenInstruction::PluginLogLine(pid, client_id, log_line) => {
+                for tab in screen.tabs_of_all_workspaces_mut() {
+                    if tab.has_plugin(pid) {
+                        tab.handle_plugin_log_line(pid, client_id, log_line)
+                            .context("failed to process plugin log line")?;
+                        break;
                     }
                 }
                 screen.render()?;
@@ -2300,6 +4363,9 @@ pub(crate) fn screen_thread_main(
                 screen.render()?;
             },
             ScreenInstruction::WriteCharacter(bytes, client_id) => {
+                if screen.connected_clients.borrow().contains(&client_id) {
+                    screen.input_latency.input_received(client_id);
+                }
                 let mut state_changed = false;
                 active_tab_and_connected_client_id!(
                     screen,
@@ -2551,11 +4617,12 @@ pub(crate) fn screen_thread_main(
                 screen.log_and_report_session_state()?;
             },
             ScreenInstruction::ScrollUpAt(point, client_id) => {
+                let scroll_lines = screen.scroll_lines;
                 active_tab_and_connected_client_id!(
                     screen,
                     client_id,
                     |tab: &mut Tab, client_id: ClientId| tab
-                        .handle_scrollwheel_up(&point, 3, client_id), ?
+                        .handle_scrollwheel_up(&point, scroll_lines, client_id), ?
                 );
                 screen.render()?;
                 screen.unblock_input()?;
@@ -2570,11 +4637,12 @@ pub(crate) fn screen_thread_main(
                 screen.unblock_input()?;
             },
             ScreenInstruction::ScrollDownAt(point, client_id) => {
+                let scroll_lines = screen.scroll_lines;
                 active_tab_and_connected_client_id!(
                     screen,
                     client_id,
                     |tab: &mut Tab, client_id: ClientId| tab
-                        .handle_scrollwheel_down(&point, 3, client_id), ?
+                        .handle_scrollwheel_down(&point, scroll_lines, client_id), ?
                 );
                 screen.render()?;
                 screen.unblock_input()?;
@@ -2650,11 +4718,7 @@ pub(crate) fn screen_thread_main(
                 screen.unblock_input()?;
             },
             ScreenInstruction::CloseFocusedPane(client_id) => {
-                active_tab_and_connected_client_id!(
-                    screen,
-                    client_id,
-                    |tab: &mut Tab, client_id: ClientId| tab.close_focused_pane(client_id), ?
-                );
+                screen.close_focused_pane(client_id)?;
                 screen.render()?;
                 screen.unblock_input()?;
                 screen.log_and_report_session_state()?;
@@ -2675,23 +4739,7 @@ pub(crate) fn screen_thread_main(
                 screen.log_and_report_session_state()?;
             },
             ScreenInstruction::ClosePane(id, client_id) => {
-                match client_id {
-                    Some(client_id) => {
-                        active_tab!(screen, client_id, |tab: &mut Tab| tab.close_pane(
-                            id,
-                            false,
-                            Some(client_id)
-                        ));
-                    },
-                    None => {
-                        for tab in screen.tabs.values_mut() {
-                            if tab.get_all_pane_ids().contains(&id) {
-                                tab.close_pane(id, false, None);
-                                break;
-                            }
-                        }
-                    },
-                }
+                screen.close_pane(id, client_id)?;
                 screen.unblock_input()?;
                 screen.log_and_report_session_state()?;
             },
@@ -2706,7 +4754,10 @@ pub(crate) fn screen_thread_main(
                             run_command
                         ));
                     },
-                    (_, Some(tab_index)) => match screen.tabs.get_mut(&tab_index) {
+                    (_, Some(tab_index)) => match screen
+                        .tabs_of_all_workspaces_mut()
+                        .find(|tab| tab.index == tab_index)
+                    {
                         Some(tab) => tab.hold_pane(id, exit_status, is_first_run, run_command),
                         None => log::warn!(
                             "Tab with index {tab_index} not found. Cannot hold pane with id {:?}",
@@ -2714,7 +4765,7 @@ pub(crate) fn screen_thread_main(
                         ),
                     },
                     _ => {
-                        for tab in screen.tabs.values_mut() {
+                        for tab in screen.tabs_of_all_workspaces_mut() {
                             if tab.get_all_pane_ids().contains(&id) {
                                 tab.hold_pane(id, exit_status, is_first_run, run_command);
                                 break;
@@ -2909,9 +4960,13 @@ pub(crate) fn screen_thread_main(
                 screen.render()?;
             },
             ScreenInstruction::TerminalResize(new_size) => {
-                screen.resize_to_screen(new_size)?;
-                screen.log_and_report_session_state()?; // update tabs so that the ui indication will be send to the plugins
-                screen.render()?;
+                // resizes arriving in quick succession (eg. when dragging the window edge) are
+                // coalesced and only the latest one is applied once the window passes, so that
+                // we re-flow (and notify plugins) once rather than for every intermediate size
+                let deadline = pending_terminal_resize
+                    .map(|(_size, deadline)| deadline)
+                    .unwrap_or_else(|| Instant::now() + TERMINAL_RESIZE_COALESCING_WINDOW);
+                pending_terminal_resize = Some((new_size, deadline));
             },
             ScreenInstruction::TerminalPixelDimensions(pixel_dimensions) => {
                 screen.update_pixel_dimensions(pixel_dimensions);
@@ -2926,6 +4981,7 @@ pub(crate) fn screen_thread_main(
                 screen.update_terminal_color_registers(color_registers);
             },
             ScreenInstruction::ChangeMode(mode_info, client_id) => {
+                let client_id = screen.connected_client_id(client_id);
                 screen.change_mode(mode_info, client_id)?;
                 screen.render()?;
                 screen.unblock_input()?;
@@ -3010,8 +5066,17 @@ pub(crate) fn screen_thread_main(
                 screen.unblock_input()?;
                 screen.render()?;
             },
-            ScreenInstruction::AddClient(client_id, tab_position_to_focus, pane_id_to_focus) => {
+            ScreenInstruction::AddClient(
+                client_id,
+                tab_position_to_focus,
+                pane_id_to_focus,
+                is_pane_view,
+                is_observer,
+            ) => {
                 screen.add_client(client_id)?;
+                if is_observer {
+                    screen.observer_clients.insert(client_id);
+                }
                 let pane_id = pane_id_to_focus.map(|(pane_id, is_plugin)| {
                     if is_plugin {
                         PaneId::Plugin(pane_id)
@@ -3019,17 +5084,29 @@ pub(crate) fn screen_thread_main(
                         PaneId::Terminal(pane_id)
                     }
                 });
-                if let Some(pane_id) = pane_id {
-                    screen.focus_pane_with_id(pane_id, true, client_id)?;
-                } else if let Some(tab_position_to_focus) = tab_position_to_focus {
+                // observers should not steal the focus of everyone else in a mirrored session
+                let can_change_focus = !is_observer || !screen.session_is_mirrored;
+                if let (Some(pane_id), true) = (pane_id, is_pane_view) {
+                    screen
+                        .view_pane_in_isolation(pane_id, client_id)
+                        .non_fatal();
+                } else if let (Some(pane_id), true) = (pane_id, can_change_focus) {
+                    screen
+                        .focus_pane_with_id(pane_id, true, client_id)
+                        .non_fatal();
+                } else if let (Some(tab_position_to_focus), true) =
+                    (tab_position_to_focus, can_change_focus)
+                {
                     screen.go_to_tab(tab_position_to_focus, client_id)?;
                 }
                 screen.log_and_report_session_state()?;
+                screen.report_session_sharing_status()?;
                 screen.render()?;
             },
             ScreenInstruction::RemoveClient(client_id) => {
                 screen.remove_client(client_id)?;
                 screen.log_and_report_session_state()?;
+                screen.report_session_sharing_status()?;
                 screen.render()?;
             },
             ScreenInstruction::AddOverlay(overlay, _client_id) => {
@@ -3124,6 +5201,18 @@ pub(crate) fn screen_thread_main(
                 }
                 screen.render()?;
             },
+            ScreenInstruction::AddBellPaneFrameColorOverride(pane_ids) => {
+                let all_tabs = screen.get_tabs_mut();
+                for pane_id in pane_ids {
+                    for tab in all_tabs.values_mut() {
+                        if tab.has_pane_with_pid(&pane_id) {
+                            tab.add_bell_pane_frame_color_override(pane_id);
+                            break;
+                        }
+                    }
+                }
+                screen.render()?;
+            },
             ScreenInstruction::ClearPaneFrameColorOverride(pane_ids) => {
                 let all_tabs = screen.get_tabs_mut();
                 for pane_id in pane_ids {
@@ -3137,23 +5226,13 @@ pub(crate) fn screen_thread_main(
                 screen.render()?;
             },
             ScreenInstruction::PreviousSwapLayout(client_id) => {
-                active_tab_and_connected_client_id!(
-                    screen,
-                    client_id,
-                    |tab: &mut Tab, client_id: ClientId| tab.previous_swap_layout(Some(client_id)),
-                    ?
-                );
+                screen.swap_active_tab_layout(SwapLayoutDirection::Previous, client_id)?;
                 screen.render()?;
                 screen.log_and_report_session_state()?;
                 screen.unblock_input()?;
             },
             ScreenInstruction::NextSwapLayout(client_id) => {
-                active_tab_and_connected_client_id!(
-                    screen,
-                    client_id,
-                    |tab: &mut Tab, client_id: ClientId| tab.next_swap_layout(Some(client_id), true),
-                    ?
-                );
+                screen.swap_active_tab_layout(SwapLayoutDirection::Next, client_id)?;
                 screen.render()?;
                 screen.log_and_report_session_state()?;
                 screen.unblock_input()?;
@@ -3293,6 +5372,15 @@ pub(crate) fn screen_thread_main(
                             Some(pane_title),
                             client_tab_index_or_pane_id,
                         )?;
+                        // a restored plugin pane only holds the place of the plugin loaded again
+                        // in it, so it is not brought back once that one is closed
+                        if let PaneId::Plugin(restored_plugin_id) = pane_id_to_replace {
+                            if screen.restored_plugin_panes.remove(&restored_plugin_id) {
+                                for tab in screen.tabs.values_mut() {
+                                    tab.drop_suppressed_pane(&PaneId::Plugin(plugin_id));
+                                }
+                            }
+                        }
                     } else if let Some(client_id) = client_id {
                         let client_tab_index_or_pane_id =
                             ClientTabIndexOrPaneId::ClientId(client_id);
@@ -3321,8 +5409,7 @@ pub(crate) fn screen_thread_main(
                 screen.unblock_input()?;
             },
             ScreenInstruction::UpdatePluginLoadingStage(pid, loading_indication) => {
-                let all_tabs = screen.get_tabs_mut();
-                for tab in all_tabs.values_mut() {
+                for tab in screen.tabs_of_all_workspaces_mut() {
                     if tab.has_plugin(pid) {
                         tab.update_plugin_loading_stage(pid, loading_indication);
                         break;
@@ -3331,8 +5418,7 @@ pub(crate) fn screen_thread_main(
                 screen.render()?;
             },
             ScreenInstruction::StartPluginLoadingIndication(pid, loading_indication) => {
-                let all_tabs = screen.get_tabs_mut();
-                for tab in all_tabs.values_mut() {
+                for tab in screen.tabs_of_all_workspaces_mut() {
                     if tab.has_plugin(pid) {
                         tab.start_plugin_loading_indication(pid, loading_indication);
                         break;
@@ -3341,8 +5427,7 @@ pub(crate) fn screen_thread_main(
                 screen.render()?;
             },
             ScreenInstruction::ProgressPluginLoadingOffset(pid) => {
-                let all_tabs = screen.get_tabs_mut();
-                for tab in all_tabs.values_mut() {
+                for tab in screen.tabs_of_all_workspaces_mut() {
                     if tab.has_plugin(pid) {
                         tab.progress_plugin_loading_offset(pid);
                         break;
@@ -3509,19 +5594,30 @@ pub(crate) fn screen_thread_main(
                 screen.log_and_report_session_state()?;
             },
             ScreenInstruction::FocusPaneWithId(pane_id, should_float_if_hidden, client_id) => {
-                screen.focus_pane_with_id(pane_id, should_float_if_hidden, client_id)?;
+                screen
+                    .focus_pane_with_id(pane_id, should_float_if_hidden, client_id)
+                    .non_fatal();
+                screen.log_and_report_session_state()?;
+            },
+            ScreenInstruction::FocusPaneWithIdForAllClients(pane_id, should_float_if_hidden) => {
+                let connected_clients: Vec<ClientId> =
+                    screen.connected_clients.borrow().iter().copied().collect();
+                for client_id in connected_clients {
+                    screen
+                        .focus_pane_with_id(pane_id, should_float_if_hidden, client_id)
+                        .non_fatal();
+                }
                 screen.log_and_report_session_state()?;
             },
             ScreenInstruction::RenamePane(pane_id, new_name) => {
-                let all_tabs = screen.get_tabs_mut();
-                for tab in all_tabs.values_mut() {
-                    if tab.has_pane_with_pid(&pane_id) {
-                        match tab.rename_pane(new_name, pane_id) {
-                            Ok(()) => drop(screen.render()),
-                            Err(e) => log::error!("Failed to rename pane: {:?}", e),
-                        }
-                        break;
-                    }
+                let renamed = screen
+                    .tabs_of_all_workspaces_mut()
+                    .find(|tab| tab.has_pane_with_pid(&pane_id))
+                    .map(|tab| tab.rename_pane(new_name, pane_id));
+                match renamed {
+                    Some(Ok(())) => drop(screen.render()),
+                    Some(Err(e)) => log::error!("Failed to rename pane: {:?}", e),
+                    None => {},
                 }
                 screen.log_and_report_session_state()?;
             },
@@ -3537,8 +5633,7 @@ pub(crate) fn screen_thread_main(
                 screen.log_and_report_session_state()?;
             },
             ScreenInstruction::RequestPluginPermissions(plugin_id, plugin_permission) => {
-                let all_tabs = screen.get_tabs_mut();
-                let found = all_tabs.values_mut().any(|tab| {
+                let found = screen.tabs_of_all_workspaces_mut().any(|tab| {
                     if tab.has_plugin(plugin_id) {
                         tab.request_plugin_permissions(plugin_id, Some(plugin_permission.clone()));
                         true
@@ -3612,48 +5707,843 @@ pub(crate) fn screen_thread_main(
                         );
                     }
                 } else {
-                    let err_context = || format!("Failed to rename session");
-                    let old_session_name = screen.session_name.clone();
-
-                    // update state
-                    screen.session_name = name.clone();
-                    screen.default_mode_info.session_name = Some(name.clone());
-                    for (_client_id, mut mode_info) in screen.mode_info.iter_mut() {
-                        mode_info.session_name = Some(name.clone());
-                    }
-                    for (_, tab) in screen.tabs.iter_mut() {
-                        tab.rename_session(name.clone()).with_context(err_context)?;
-                    }
-
-                    // rename socket file
-                    let old_socket_file_path = ZELLIJ_SOCK_DIR.join(&old_session_name);
-                    let new_socket_file_path = ZELLIJ_SOCK_DIR.join(&name);
-                    if let Err(e) = std::fs::rename(old_socket_file_path, new_socket_file_path) {
-                        log::error!("Failed to rename ipc socket: {:?}", e);
-                    }
-
-                    // rename session_info folder (TODO: make this atomic, right now there is a
-                    // chance background_jobs will re-create this folder before it knows the
-                    // session was renamed)
-                    let old_session_info_folder =
-                        session_info_folder_for_session(&old_session_name);
-                    let new_session_info_folder = session_info_folder_for_session(&name);
-                    if let Err(e) =
-                        std::fs::rename(old_session_info_folder, new_session_info_folder)
-                    {
-                        log::error!("Failed to rename session_info folder: {:?}", e);
-                    }
-
-                    // report
+                    // rename the socket and session info folder, and set the env variable - our
+                    // state is updated once the server is done (see SessionRenamed)
                     screen
-                        .log_and_report_session_state()
-                        .with_context(err_context)?;
-
-                    // set the env variable
-                    set_session_name(name);
+                        .bus
+                        .senders
+                        .send_to_server(ServerInstruction::RenameSession(name, client_id))
+                        .with_context(|| format!("Failed to rename session"))?;
                 }
                 screen.unblock_input()?;
             },
+            ScreenInstruction::SessionRenamed(name) => {
+                let err_context = || format!("Failed to rename session");
+
+                // update state
+                screen.session_name = name.clone();
+                screen.default_mode_info.session_name = Some(name.clone());
+                for (_client_id, mut mode_info) in screen.mode_info.iter_mut() {
+                    mode_info.session_name = Some(name.clone());
+                }
+                for (_, tab) in screen.tabs.iter_mut() {
+                    tab.rename_session(name.clone()).with_context(err_context)?;
+                }
+
+                // report
+                screen
+                    .log_and_report_session_state()
+                    .with_context(err_context)?;
+            },
+            ScreenInstruction::ToggleRuler(client_id) => {
+                active_tab_and_connected_client_id!(
+                    screen,
+                    client_id,
+                    |tab: &mut Tab, client_id: ClientId| tab.toggle_ruler(client_id)
+                );
+                screen.render()?;
+                screen.unblock_input()?;
+            },
+            ScreenInstruction::SwapLayout {
+                tab_position,
+                direction,
+                client_id,
+            } => {
+                let result = screen
+                    .tab_index_at_position(tab_position)
+                    .and_then(|tab_index| screen.swap_layout(tab_index, direction));
+                if let Err(e) = result {
+                    log::error!("{:?}", e);
+                    if let Some(os_input) = &mut screen.bus.os_input {
+                        let _ = os_input.send_to_client(
+                            client_id,
+                            ServerToClientMsg::LogError(vec![e.root_cause().to_string()]),
+                        );
+                    }
+                }
+                screen.unblock_input()?;
+                screen.render()?;
+                screen.log_and_report_session_state()?;
+            },
+            ScreenInstruction::FocusNextPaneSpatial(client_id) => {
+                active_tab_and_connected_client_id!(
+                    screen,
+                    client_id,
+                    |tab: &mut Tab, client_id: ClientId| tab.focus_next_pane_spatial(client_id)
+                );
+                screen.render()?;
+                screen.unblock_input()?;
+                screen.log_and_report_session_state()?;
+            },
+            ScreenInstruction::CaptureRendered(pane_id, client_id, response) => {
+                let rendered_grid =
+                    screen.capture_rendered(pane_id, screen.connected_client_id(client_id))?;
+                match response {
+                    Some(response) => {
+                        if response.send(rendered_grid).is_err() {
+                            log::error!("Failed to respond with captured rendered output");
+                        }
+                    },
+                    None => {
+                        let serialized_grid = rendered_grid
+                            .ok_or_else(|| anyhow!("nothing is rendered to client {client_id}"))
+                            .and_then(|rendered_grid| {
+                                serde_json::to_string(&rendered_grid)
+                                    .context("failed to serialize rendered output")
+                            });
+                        match serialized_grid {
+                            Ok(serialized_grid) => {
+                                screen.bus.senders.send_to_server(ServerInstruction::Log(
+                                    vec![serialized_grid],
+                                    client_id,
+                                ))?;
+                            },
+                            Err(e) => {
+                                log::error!("{:?}", e);
+                                if let Some(os_input) = &mut screen.bus.os_input {
+                                    let _ = os_input.send_to_client(
+                                        client_id,
+                                        ServerToClientMsg::LogError(vec![e
+                                            .root_cause()
+                                            .to_string()]),
+                                    );
+                                }
+                            },
+                        }
+                    },
+                }
+            },
+            ScreenInstruction::FocusPaneById(pane_id, client_id) => {
+                let should_float_if_hidden = false;
+                let result = screen.focus_pane_with_id(pane_id, should_float_if_hidden, client_id);
+                if let Err(e) = result {
+                    log::error!("{:?}", e);
+                    if let Some(os_input) = &mut screen.bus.os_input {
+                        let _ = os_input.send_to_client(
+                            client_id,
+                            ServerToClientMsg::LogError(vec![e.root_cause().to_string()]),
+                        );
+                    }
+                }
+                screen.render()?;
+                screen.unblock_input()?;
+                screen.log_and_report_session_state()?;
+            },
+            ScreenInstruction::RequestSessionSharingStatus(plugin_id, client_id) => {
+                let session_sharing_status = screen.session_sharing_status();
+                screen
+                    .bus
+                    .senders
+                    .send_to_plugin(PluginInstruction::Update(vec![(
+                        Some(plugin_id),
+                        Some(client_id),
+                        Event::SessionSharingStatus(session_sharing_status),
+                    )]))?;
+            },
+            ScreenInstruction::PopOutPane(client_id) => {
+                screen
+                    .pop_out_pane(pop_out_terminal_command.as_deref(), client_id)
+                    .non_fatal();
+                screen.unblock_input()?;
+            },
+            ScreenInstruction::FocusPaneByName { name, client_id } => {
+                match screen.focus_pane_by_name(&name, screen.connected_client_id(client_id)) {
+                    Ok(()) => {
+                        screen.render()?;
+                        screen.log_and_report_session_state()?;
+                    },
+                    Err(e) => {
+                        log::error!("{:?}", e);
+                        if let Some(os_input) = &mut screen.bus.os_input {
+                            let _ = os_input.send_to_client(
+                                client_id,
+                                ServerToClientMsg::LogError(vec![e.root_cause().to_string()]),
+                            );
+                        }
+                    },
+                }
+                screen.unblock_input()?;
+            },
+            ScreenInstruction::CopyTextToClipboard(text, client_id) => {
+                // plugins are informed of the outcome just like when copying a selection
+                active_tab!(screen, client_id, |tab: &mut Tab| tab
+                    .write_selection_to_clipboard(&text), ?);
+            },
+            ScreenInstruction::SetPaneMinSize(pane_id, min_cols, min_rows, client_id) => {
+                let result = screen
+                    .pane_id_or_focused_pane(pane_id, client_id)
+                    .and_then(|pane_id| screen.set_pane_min_size(pane_id, min_cols, min_rows));
+                match result {
+                    Ok(()) => {
+                        screen.render()?;
+                        screen.log_and_report_session_state()?;
+                    },
+                    Err(e) => {
+                        log::error!("{:?}", e);
+                        if let Some(os_input) = &mut screen.bus.os_input {
+                            let _ = os_input.send_to_client(
+                                client_id,
+                                ServerToClientMsg::LogError(vec![e.root_cause().to_string()]),
+                            );
+                        }
+                    },
+                }
+                screen.unblock_input()?;
+            },
+            ScreenInstruction::ResizePaneTo {
+                pane_id,
+                cols,
+                rows,
+                client_id,
+            } => {
+                let result = screen
+                    .pane_id_or_focused_pane(pane_id, client_id)
+                    .and_then(|pane_id| screen.resize_pane_to(pane_id, cols, rows));
+                match result {
+                    Ok(applied_size) => {
+                        screen.render()?;
+                        screen.log_and_report_session_state()?;
+                        // report the size we ended up with, which might not be the requested one
+                        screen.bus.senders.send_to_server(ServerInstruction::Log(
+                            vec![format!("{}x{}", applied_size.cols, applied_size.rows)],
+                            client_id,
+                        ))?;
+                    },
+                    Err(e) => {
+                        log::error!("{:?}", e);
+                        if let Some(os_input) = &mut screen.bus.os_input {
+                            let _ = os_input.send_to_client(
+                                client_id,
+                                ServerToClientMsg::LogError(vec![e.root_cause().to_string()]),
+                            );
+                        }
+                    },
+                }
+            },
+            ScreenInstruction::ResizePaneToPreset(preset, client_id) => {
+                active_tab_and_connected_client_id!(
+                    screen,
+                    client_id,
+                    |tab: &mut Tab, client_id: ClientId| tab
+                        .resize_active_pane_to_preset(client_id, preset),
+                    ?
+                );
+                screen.unblock_input()?;
+                screen.render()?;
+                screen.log_and_report_session_state()?;
+            },
+            ScreenInstruction::ToggleShowInvisibles(pane_id, client_id) => {
+                let result = screen
+                    .pane_id_or_focused_pane(pane_id, client_id)
+                    .and_then(|pane_id| screen.toggle_show_invisibles(pane_id));
+                match result {
+                    Ok(()) => screen.render()?,
+                    Err(e) => {
+                        log::error!("{:?}", e);
+                        if let Some(os_input) = &mut screen.bus.os_input {
+                            let _ = os_input.send_to_client(
+                                client_id,
+                                ServerToClientMsg::LogError(vec![e.root_cause().to_string()]),
+                            );
+                        }
+                    },
+                }
+                screen.unblock_input()?;
+            },
+            ScreenInstruction::TogglePanePinned(pane_id, client_id) => {
+                match screen.pane_id_or_focused_pane(pane_id, client_id) {
+                    Ok(pane_id) => {
+                        let all_tabs = screen.get_tabs_mut();
+                        for tab in all_tabs.values_mut() {
+                            if tab.has_pane_with_pid(&pane_id) {
+                                tab.toggle_pane_pinned(pane_id);
+                                break;
+                            }
+                        }
+                    },
+                    Err(e) => {
+                        log::error!("{:?}", e);
+                        if let Some(os_input) = &mut screen.bus.os_input {
+                            let _ = os_input.send_to_client(
+                                client_id,
+                                ServerToClientMsg::LogError(vec![e.root_cause().to_string()]),
+                            );
+                        }
+                    },
+                }
+                screen.unblock_input()?;
+                screen.render()?;
+                screen.log_and_report_session_state()?;
+            },
+            ScreenInstruction::SetInputBroadcast(tab_index, should_broadcast, client_id) => {
+                let tab = screen
+                    .tab_index_or_active_tab(tab_index, client_id)
+                    .and_then(|tab_index| {
+                        screen
+                            .tabs
+                            .get_mut(&tab_index)
+                            .ok_or_else(|| anyhow!("Failed to find tab with index: {tab_index}"))
+                    });
+                match tab {
+                    Ok(tab) => tab.set_sync_panes_is_active(should_broadcast),
+                    Err(e) => {
+                        log::error!("{:?}", e);
+                        if let Some(os_input) = &mut screen.bus.os_input {
+                            let _ = os_input.send_to_client(
+                                client_id,
+                                ServerToClientMsg::LogError(vec![e.root_cause().to_string()]),
+                            );
+                        }
+                    },
+                }
+                screen.unblock_input()?;
+                screen.log_and_report_session_state()?;
+                screen.render()?;
+            },
+            ScreenInstruction::StackPanes {
+                pane_ids,
+                client_id,
+            } => {
+                active_tab_and_connected_client_id!(
+                    screen,
+                    client_id,
+                    |tab: &mut Tab, client_id: ClientId| tab
+                        .stack_panes(pane_ids, client_id)
+                        .non_fatal()
+                );
+                screen.log_and_report_session_state()?;
+                screen.render()?;
+                screen.unblock_input()?;
+            },
+            ScreenInstruction::CycleStack(direction, client_id) => {
+                active_tab_and_connected_client_id!(
+                    screen,
+                    client_id,
+                    |tab: &mut Tab, client_id: ClientId| tab.cycle_stack(direction, client_id)
+                );
+                screen.log_and_report_session_state()?;
+                screen.render()?;
+                screen.unblock_input()?;
+            },
+            ScreenInstruction::RenderPluginOverlay(plugin_id, plugin_overlay) => {
+                screen.render_plugin_overlay(plugin_id, plugin_overlay);
+                screen.render()?;
+            },
+            ScreenInstruction::ClearPluginOverlay(plugin_id) => {
+                screen.clear_plugin_overlay(plugin_id);
+                screen.render()?;
+            },
+            ScreenInstruction::TogglePaneFrame(pane_id, client_id) => {
+                let result = screen
+                    .pane_id_or_focused_pane(pane_id, client_id)
+                    .and_then(|pane_id| screen.toggle_pane_frame(pane_id));
+                if let Err(e) = result {
+                    log::error!("{:?}", e);
+                    if let Some(os_input) = &mut screen.bus.os_input {
+                        let _ = os_input.send_to_client(
+                            client_id,
+                            ServerToClientMsg::LogError(vec![e.root_cause().to_string()]),
+                        );
+                    }
+                }
+                screen.unblock_input()?;
+                screen.log_and_report_session_state()?;
+                screen.render()?;
+            },
+            ScreenInstruction::ToggleZoom(client_id) => {
+                active_tab_and_connected_client_id!(
+                    screen,
+                    client_id,
+                    |tab: &mut Tab, client_id: ClientId| tab.toggle_zoom(client_id).non_fatal()
+                );
+                screen.render()?;
+                screen.unblock_input()?;
+                screen.log_and_report_session_state()?;
+            },
+            ScreenInstruction::NewPluginFedPane(terminal_id, pane_name, plugin_id, client_id) => {
+                let connected_client_id = screen.connected_client_id(client_id);
+                let result = screen
+                    .get_active_tab_mut(connected_client_id)
+                    .and_then(|tab| {
+                        tab.new_plugin_fed_pane(
+                            terminal_id,
+                            pane_name,
+                            plugin_id,
+                            connected_client_id,
+                        )
+                    });
+                if let Err(e) = result {
+                    log::error!("{:?}", e);
+                    // no pane uses the terminal id reserved for it, so it can be released
+                    screen
+                        .bus
+                        .senders
+                        .send_to_pty(PtyInstruction::ClosePane(PaneId::Terminal(terminal_id)))
+                        .non_fatal();
+                    if let Some(os_input) = &mut screen.bus.os_input {
+                        let _ = os_input.send_to_client(
+                            client_id,
+                            ServerToClientMsg::LogError(vec![e.root_cause().to_string()]),
+                        );
+                    }
+                }
+                screen.log_and_report_session_state()?;
+                screen.render()?;
+            },
+            ScreenInstruction::WriteToPaneScrollback(terminal_id, lines, plugin_id) => {
+                for tab in screen.tabs_of_all_workspaces_mut() {
+                    if tab.has_terminal_pid(terminal_id) {
+                        tab.write_to_pane_scrollback(terminal_id, lines, plugin_id)
+                            .non_fatal();
+                        break;
+                    }
+                }
+                screen.render()?;
+            },
+            ScreenInstruction::DuplicatePane(pane_id, client_id) => {
+                let connected_client_id = screen.connected_client_id(client_id);
+                let result = screen
+                    .pane_id_or_focused_pane(pane_id, client_id)
+                    .and_then(|pane_id| screen.duplicate_pane(pane_id, connected_client_id));
+                if let Err(e) = result {
+                    log::error!("{:?}", e);
+                    if let Some(os_input) = &mut screen.bus.os_input {
+                        let _ = os_input.send_to_client(
+                            client_id,
+                            ServerToClientMsg::LogError(vec![e.root_cause().to_string()]),
+                        );
+                    }
+                }
+                screen.unblock_input()?;
+            },
+            ScreenInstruction::SearchInPane {
+                pane_id,
+                query,
+                case_sensitive,
+                client_id,
+                response,
+            } => {
+                let result =
+                    screen
+                        .pane_id_or_focused_pane(pane_id, client_id)
+                        .and_then(|pane_id| {
+                            screen.search_and_highlight_in_pane(pane_id, &query, case_sensitive)
+                        });
+                let search_matches = match result {
+                    Ok(search_matches) => search_matches,
+                    Err(e) => {
+                        log::error!("{:?}", e);
+                        if let (None, Some(os_input)) = (&response, &mut screen.bus.os_input) {
+                            let _ = os_input.send_to_client(
+                                client_id,
+                                ServerToClientMsg::LogError(vec![e.root_cause().to_string()]),
+                            );
+                        }
+                        None
+                    },
+                };
+                match response {
+                    Some(response) => {
+                        if response.send(search_matches).is_err() {
+                            log::error!("Failed to respond with search matches");
+                        }
+                    },
+                    None => screen.unblock_input()?,
+                }
+                screen.render()?;
+            },
+            ScreenInstruction::ClearSearch(pane_id, client_id) => {
+                screen
+                    .pane_id_or_focused_pane(pane_id, client_id)
+                    .and_then(|pane_id| screen.clear_search_in_pane(pane_id))
+                    .non_fatal();
+                screen.render()?;
+                screen.unblock_input()?;
+            },
+            ScreenInstruction::MoveTab {
+                from_index,
+                to_index,
+                client_id,
+            } => {
+                screen.move_tab(from_index, to_index, client_id)?;
+            },
+            ScreenInstruction::MoveActiveTab(direction, client_id) => {
+                let connected_client_id = screen.connected_client_id(client_id);
+                let result = screen.move_active_tab(direction, connected_client_id);
+                if let Err(e) = result {
+                    log::error!("{:?}", e);
+                    if let Some(os_input) = &mut screen.bus.os_input {
+                        let _ = os_input.send_to_client(
+                            client_id,
+                            ServerToClientMsg::LogError(vec![e.root_cause().to_string()]),
+                        );
+                    }
+                }
+                screen.unblock_input()?;
+            },
+            ScreenInstruction::RequestSessionActivity(plugin_id, client_id) => {
+                let session_activity = screen.session_activity.entries();
+                screen
+                    .bus
+                    .senders
+                    .send_to_plugin(PluginInstruction::Update(vec![(
+                        Some(plugin_id),
+                        Some(client_id),
+                        Event::SessionActivity(session_activity),
+                    )]))?;
+            },
+            ScreenInstruction::ClosePanesMatching {
+                predicate,
+                client_id,
+            } => {
+                if let Err(e) = screen.close_panes_matching(predicate, client_id) {
+                    log::error!("{:?}", e);
+                    if let Some(os_input) = &mut screen.bus.os_input {
+                        let _ = os_input.send_to_client(
+                            client_id,
+                            ServerToClientMsg::LogError(vec![e.root_cause().to_string()]),
+                        );
+                    }
+                }
+                screen.unblock_input()?;
+            },
+            ScreenInstruction::SubscribePaneOutput(pane_id, plugin_id) => {
+                screen.subscribe_pane_output(pane_id, plugin_id).non_fatal();
+            },
+            ScreenInstruction::UnsubscribePaneOutput(pane_id, plugin_id) => {
+                screen.unsubscribe_pane_output(pane_id, plugin_id);
+            },
+            ScreenInstruction::ClearPluginPaneOutputSubscriptions(plugin_id) => {
+                screen.clear_plugin_pane_output_subscriptions(plugin_id);
+            },
+            ScreenInstruction::ClearPaneOutputSubscriptions(pane_id) => {
+                screen.clear_pane_output_subscriptions(pane_id);
+            },
+            ScreenInstruction::Ping(request_id, client_id) => {
+                // everything the client sent before this was handled (or passed on to other
+                // threads), so that it knows which of the messages it got were for its request
+                if let Some(os_input) = &mut screen.bus.os_input {
+                    let _ = os_input.send_to_client(client_id, ServerToClientMsg::Pong(request_id));
+                }
+            },
+            ScreenInstruction::SetPaneScrollLines(pane_id, scroll_lines, client_id) => {
+                let result = screen
+                    .pane_id_or_focused_pane(pane_id, client_id)
+                    .and_then(|pane_id| screen.set_pane_scroll_lines(pane_id, scroll_lines));
+                if let Err(e) = result {
+                    log::error!("{:?}", e);
+                    if let Some(os_input) = &mut screen.bus.os_input {
+                        let _ = os_input.send_to_client(
+                            client_id,
+                            ServerToClientMsg::LogError(vec![e.root_cause().to_string()]),
+                        );
+                    }
+                }
+                screen.unblock_input()?;
+            },
+            ScreenInstruction::SplitPaneWithRatio {
+                pane_id,
+                new_pane_id,
+                direction,
+                ratio,
+                client_id,
+            } => {
+                let result =
+                    screen
+                        .pane_id_or_focused_pane(pane_id, client_id)
+                        .and_then(|pane_id| {
+                            screen.split_pane_with_ratio(
+                                pane_id,
+                                new_pane_id,
+                                direction,
+                                ratio,
+                                client_id,
+                            )
+                        });
+                if let Err(e) = result {
+                    log::error!("{:?}", e);
+                    // the new pane has nowhere to go
+                    screen
+                        .bus
+                        .senders
+                        .send_to_pty(PtyInstruction::ClosePane(new_pane_id))
+                        .context("failed to close the pane that could not be split off")?;
+                    if let Some(os_input) = &mut screen.bus.os_input {
+                        let _ = os_input.send_to_client(
+                            client_id,
+                            ServerToClientMsg::LogError(vec![e.root_cause().to_string()]),
+                        );
+                    }
+                }
+                screen.unblock_input()?;
+                screen.log_and_report_session_state()?;
+                screen.render()?;
+            },
+            ScreenInstruction::RenamePluginTab(plugin_id, new_name) => {
+                match screen
+                    .tabs
+                    .values_mut()
+                    .find(|tab| tab.has_plugin(plugin_id))
+                {
+                    Some(tab) => {
+                        tab.name = new_name;
+                    },
+                    None => {
+                        log::error!("Failed to find the tab of plugin: {:?}", plugin_id);
+                    },
+                }
+                screen.render()?;
+                screen.log_and_report_session_state()?;
+            },
+            ScreenInstruction::UndoLayoutChange(client_id) => {
+                if let Err(e) = screen.undo_layout_change(client_id) {
+                    log::error!("{:?}", e);
+                    if let Some(os_input) = &mut screen.bus.os_input {
+                        let _ = os_input.send_to_client(
+                            client_id,
+                            ServerToClientMsg::LogError(vec![e.root_cause().to_string()]),
+                        );
+                    }
+                }
+                screen.unblock_input()?;
+            },
+            ScreenInstruction::SetPaneReadOnly(pane_id, read_only, client_id) => {
+                match screen.pane_id_or_focused_pane(pane_id, client_id) {
+                    Ok(pane_id) => {
+                        let all_tabs = screen.get_tabs_mut();
+                        for tab in all_tabs.values_mut() {
+                            if tab.has_pane_with_pid(&pane_id) {
+                                tab.set_pane_read_only(pane_id, read_only);
+                                break;
+                            }
+                        }
+                    },
+                    Err(e) => {
+                        log::error!("{:?}", e);
+                        if let Some(os_input) = &mut screen.bus.os_input {
+                            let _ = os_input.send_to_client(
+                                client_id,
+                                ServerToClientMsg::LogError(vec![e.root_cause().to_string()]),
+                            );
+                        }
+                    },
+                }
+                screen.unblock_input()?;
+                screen.render()?;
+                screen.log_and_report_session_state()?;
+            },
+            ScreenInstruction::GoToTabByName { name, client_id } => {
+                match screen.go_to_tab_by_name(&name, client_id) {
+                    Ok(()) => {
+                        screen.render()?;
+                    },
+                    Err(e) => {
+                        log::error!("{:?}", e);
+                        if let Some(os_input) = &mut screen.bus.os_input {
+                            let _ = os_input.send_to_client(
+                                client_id,
+                                ServerToClientMsg::LogError(vec![e.root_cause().to_string()]),
+                            );
+                        }
+                    },
+                }
+                screen.unblock_input()?;
+            },
+            ScreenInstruction::ApplyLayoutToTab {
+                tab_index,
+                layout,
+                client_id,
+            } => {
+                let result = screen
+                    .tab_index_or_active_tab(tab_index, client_id)
+                    .and_then(|tab_index| screen.apply_layout_to_tab(tab_index, layout));
+                match result {
+                    Ok(()) => {
+                        screen.log_and_report_session_state()?;
+                        screen.render()?;
+                    },
+                    Err(e) => {
+                        log::error!("{:?}", e);
+                        if let Some(os_input) = &mut screen.bus.os_input {
+                            let _ = os_input.send_to_client(
+                                client_id,
+                                ServerToClientMsg::LogError(vec![e.root_cause().to_string()]),
+                            );
+                        }
+                    },
+                }
+                screen.unblock_input()?;
+            },
+            ScreenInstruction::FillInPlaceLayout(new_terminal_ids, tab_index) => {
+                screen
+                    .fill_in_place_layout(tab_index, new_terminal_ids)
+                    .non_fatal();
+                screen.log_and_report_session_state()?;
+                screen.render()?;
+            },
+            ScreenInstruction::ForceFullRender(client_id) => {
+                screen.force_full_render(client_id).non_fatal();
+            },
+            ScreenInstruction::SetFocusDimming(enabled, amount) => {
+                screen.set_focus_dimming(enabled, amount).non_fatal();
+                screen.unblock_input()?;
+            },
+            ScreenInstruction::ToggleFloatingPanesInTab(tab_position, client_id) => {
+                let result = screen
+                    .tab_index_at_position(tab_position)
+                    .and_then(|tab_index| {
+                        let focusing_client_id = screen.connected_client_id(client_id);
+                        screen.toggle_floating_panes_in_tab(tab_index, focusing_client_id)
+                    });
+                if let Err(e) = result {
+                    log::error!("{:?}", e);
+                    if let Some(os_input) = &mut screen.bus.os_input {
+                        let _ = os_input.send_to_client(
+                            client_id,
+                            ServerToClientMsg::LogError(vec![e.root_cause().to_string()]),
+                        );
+                    }
+                }
+                screen.unblock_input()?;
+                screen.log_and_report_session_state()?;
+                screen.render()?;
+            },
+            ScreenInstruction::SwitchWorkspace(workspace_name) => {
+                screen.switch_workspace(workspace_name).non_fatal();
+                screen.unblock_input()?;
+            },
+            ScreenInstruction::ResizeTabToContent(tab_index, client_id) => {
+                let result = screen
+                    .tab_index_or_active_tab(tab_index, client_id)
+                    .and_then(|tab_index| screen.resize_tab_to_content(tab_index));
+                match result {
+                    Ok(()) => {
+                        screen.render()?;
+                        screen.log_and_report_session_state()?;
+                    },
+                    Err(e) => {
+                        log::error!("{:?}", e);
+                        if let Some(os_input) = &mut screen.bus.os_input {
+                            let _ = os_input.send_to_client(
+                                client_id,
+                                ServerToClientMsg::LogError(vec![e.root_cause().to_string()]),
+                            );
+                        }
+                    },
+                }
+                screen.unblock_input()?;
+            },
+            ScreenInstruction::SetPanePassthrough(pane_id, passthrough) => {
+                screen
+                    .set_pane_passthrough(pane_id, passthrough)
+                    .non_fatal();
+            },
+            ScreenInstruction::TogglePanePassthrough(client_id) => {
+                screen.toggle_pane_passthrough(client_id).non_fatal();
+                screen.unblock_input()?;
+            },
+            ScreenInstruction::CapturePaneText {
+                pane_id,
+                include_scrollback,
+                client_id,
+            } => {
+                let result = screen
+                    .pane_id_or_focused_pane(pane_id, client_id)
+                    .and_then(|pane_id| screen.capture_pane_text(pane_id, include_scrollback));
+                match result {
+                    Ok(text) => {
+                        screen
+                            .bus
+                            .senders
+                            .send_to_server(ServerInstruction::Log(vec![text], client_id))?;
+                    },
+                    Err(e) => {
+                        log::error!("{:?}", e);
+                        if let Some(os_input) = &mut screen.bus.os_input {
+                            let _ = os_input.send_to_client(
+                                client_id,
+                                ServerToClientMsg::LogError(vec![e.root_cause().to_string()]),
+                            );
+                        }
+                    },
+                }
+            },
+            ScreenInstruction::SetTabColor(tab_index, color, client_id) => {
+                let result = screen
+                    .tab_index_or_active_tab(tab_index, client_id)
+                    .and_then(|tab_index| screen.set_tab_color(tab_index, color));
+                if let Err(e) = result {
+                    log::error!("{:?}", e);
+                    if let Some(os_input) = &mut screen.bus.os_input {
+                        let _ = os_input.send_to_client(
+                            client_id,
+                            ServerToClientMsg::LogError(vec![e.root_cause().to_string()]),
+                        );
+                    }
+                }
+                screen.unblock_input()?;
+                screen.render()?;
+            },
+            ScreenInstruction::SetClientTheme(client_id, theme) => {
+                let client_id = screen.connected_client_id(client_id);
+                screen.set_client_theme(client_id, theme).non_fatal();
+                screen.unblock_input()?;
+            },
+            ScreenInstruction::SetSynchronizedScroll(tab_index, is_active, client_id) => {
+                let result = screen
+                    .tab_index_or_active_tab(tab_index, client_id)
+                    .and_then(|tab_index| screen.set_synchronized_scroll(tab_index, is_active));
+                if let Err(e) = result {
+                    log::error!("{:?}", e);
+                    if let Some(os_input) = &mut screen.bus.os_input {
+                        let _ = os_input.send_to_client(
+                            client_id,
+                            ServerToClientMsg::LogError(vec![e.root_cause().to_string()]),
+                        );
+                    }
+                }
+                screen.unblock_input()?;
+            },
+            ScreenInstruction::SetPaneFrameStyle(pane_frame_style) => {
+                screen.set_pane_frame_style(pane_frame_style).non_fatal();
+                screen.unblock_input()?;
+            },
+            ScreenInstruction::NewPaneAlias(terminal_id, source_terminal_id, client_id) => {
+                let result = screen.new_pane_alias(
+                    terminal_id,
+                    source_terminal_id,
+                    screen.connected_client_id(client_id),
+                );
+                if let Err(e) = result {
+                    log::error!("{:?}", e);
+                    if let Some(os_input) = &mut screen.bus.os_input {
+                        let _ = os_input.send_to_client(
+                            client_id,
+                            ServerToClientMsg::LogError(vec![e.root_cause().to_string()]),
+                        );
+                    }
+                }
+                screen.unblock_input()?;
+            },
+            ScreenInstruction::MergePanes {
+                keep_pane_id,
+                absorb_pane_id,
+                client_id,
+            } => {
+                let connected_client_id = screen.connected_client_id(client_id);
+                let result = screen.merge_panes(keep_pane_id, absorb_pane_id, connected_client_id);
+                if let Err(e) = result {
+                    log::error!("{:?}", e);
+                    if let Some(os_input) = &mut screen.bus.os_input {
+                        let _ = os_input.send_to_client(
+                            client_id,
+                            ServerToClientMsg::LogError(vec![e.root_cause().to_string()]),
+                        );
+                    }
+                    // otherwise input is unblocked once the absorbed pane is closed
+                    screen.unblock_input()?;
+                }
+            },
         }
     }
     Ok(())
@@ -0,0 +1,94 @@
+//! A bounded log of what happened in the session (panes opening and closing, clients switching
+//! tabs and commands being spawned) that plugins can read for auditing. Rather than recording
+//! each action where it happens, the panes and focused tabs are compared with those seen the last
+//! time the session state was reported, so that every way of eg. opening a pane is covered.
+
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use zellij_utils::data::{PaneId, PaneManifest, SessionActivity, SessionActivityKind};
+
+use crate::ClientId;
+
+pub const DEFAULT_SESSION_ACTIVITY_LOG_SIZE: usize = 1000;
+
+#[derive(Debug)]
+pub struct SessionActivityLog {
+    max_entries: usize,
+    entries: VecDeque<SessionActivity>,
+    known_panes: BTreeSet<PaneId>,
+    focused_tabs: BTreeMap<ClientId, usize>, // tab index
+}
+
+impl SessionActivityLog {
+    pub fn new(max_entries: usize) -> Self {
+        SessionActivityLog {
+            max_entries,
+            entries: VecDeque::new(),
+            known_panes: BTreeSet::new(),
+            focused_tabs: BTreeMap::new(),
+        }
+    }
+    /// Records the changes since the last time this was called, returning the new entries.
+    /// `focused_tabs` holds the index and position of the tab each client is focused on.
+    pub fn record_changes(
+        &mut self,
+        pane_manifest: &PaneManifest,
+        focused_tabs: BTreeMap<ClientId, (usize, usize)>,
+    ) -> Vec<SessionActivity> {
+        let mut new_entries = vec![];
+        let mut current_panes = BTreeSet::new();
+        for pane_info in pane_manifest.panes.values().flatten() {
+            let pane_id = if pane_info.is_plugin {
+                PaneId::Plugin(pane_info.id)
+            } else {
+                PaneId::Terminal(pane_info.id)
+            };
+            current_panes.insert(pane_id);
+            if self.known_panes.contains(&pane_id) {
+                continue;
+            }
+            new_entries.push(SessionActivityKind::PaneOpened(pane_id));
+            if let Some(terminal_command) = &pane_info.terminal_command {
+                new_entries.push(SessionActivityKind::CommandSpawned(
+                    pane_id,
+                    terminal_command.clone(),
+                ));
+            }
+        }
+        for closed_pane in self.known_panes.difference(&current_panes) {
+            new_entries.push(SessionActivityKind::PaneClosed(*closed_pane));
+        }
+        for (client_id, (tab_index, tab_position)) in &focused_tabs {
+            // clients that just attached did not change tabs
+            match self.focused_tabs.get(client_id) {
+                Some(previous_tab_index) if previous_tab_index != tab_index => {
+                    new_entries.push(SessionActivityKind::TabChanged(*client_id, *tab_position));
+                },
+                _ => {},
+            }
+        }
+        self.known_panes = current_panes;
+        self.focused_tabs = focused_tabs
+            .into_iter()
+            .map(|(client_id, (tab_index, _tab_position))| (client_id, tab_index))
+            .collect();
+
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default();
+        let new_entries: Vec<SessionActivity> = new_entries
+            .into_iter()
+            .map(|kind| SessionActivity { timestamp, kind })
+            .collect();
+        self.entries.extend(new_entries.iter().cloned());
+        while self.entries.len() > self.max_entries {
+            self.entries.pop_front();
+        }
+        new_entries
+    }
+    /// All the entries in the log, oldest first
+    pub fn entries(&self) -> Vec<SessionActivity> {
+        self.entries.iter().cloned().collect()
+    }
+}
@@ -73,6 +73,7 @@ impl SessionLayoutMetadata {
         name: String,
         is_focused: bool,
         hide_floating_panes: bool,
+        color: Option<String>,
         tiled_panes: Vec<PaneLayoutMetadata>,
         floating_panes: Vec<PaneLayoutMetadata>,
     ) {
@@ -80,6 +81,7 @@ impl SessionLayoutMetadata {
             name: Some(name),
             is_focused,
             hide_floating_panes,
+            color,
             tiled_panes,
             floating_panes,
         })
@@ -191,6 +193,23 @@ impl SessionLayoutMetadata {
             }
         }
     }
+    pub fn update_plugin_states(&mut self, mut plugin_ids_to_states: HashMap<u32, Vec<u8>>) {
+        let mut update_state_in_pane_metadata = |pane_layout_metadata: &mut PaneLayoutMetadata| {
+            if let PaneId::Plugin(id) = pane_layout_metadata.id {
+                if let Some(plugin_state) = plugin_ids_to_states.remove(&id) {
+                    pane_layout_metadata.plugin_state = Some(plugin_state);
+                }
+            }
+        };
+        for tab in self.tabs.iter_mut() {
+            for pane_layout_metadata in tab.tiled_panes.iter_mut() {
+                update_state_in_pane_metadata(pane_layout_metadata);
+            }
+            for pane_layout_metadata in tab.floating_panes.iter_mut() {
+                update_state_in_pane_metadata(pane_layout_metadata);
+            }
+        }
+    }
 }
 
 impl Into<GlobalLayoutManifest> for SessionLayoutMetadata {
@@ -215,6 +234,7 @@ impl Into<TabLayoutManifest> for TabLayoutMetadata {
             floating_panes: self.floating_panes.into_iter().map(|t| t.into()).collect(),
             is_focused: self.is_focused,
             hide_floating_panes: self.hide_floating_panes,
+            color: self.color,
         }
     }
 }
@@ -228,7 +248,10 @@ impl Into<PaneLayoutManifest> for PaneLayoutMetadata {
             is_borderless: self.is_borderless,
             title: self.title,
             is_focused: self.is_focused,
+            is_pinned: self.is_pinned,
+            is_read_only: self.is_read_only,
             pane_contents: self.pane_contents,
+            plugin_state: self.plugin_state,
         }
     }
 }
@@ -240,6 +263,7 @@ pub struct TabLayoutMetadata {
     floating_panes: Vec<PaneLayoutMetadata>,
     is_focused: bool,
     hide_floating_panes: bool,
+    color: Option<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -251,7 +275,10 @@ pub struct PaneLayoutMetadata {
     is_borderless: bool,
     title: Option<String>,
     is_focused: bool,
+    is_pinned: bool,
+    is_read_only: bool,
     pane_contents: Option<String>,
+    plugin_state: Option<Vec<u8>>,
 }
 
 impl PaneLayoutMetadata {
@@ -262,6 +289,8 @@ impl PaneLayoutMetadata {
         run: Option<Run>,
         title: Option<String>,
         is_focused: bool,
+        is_pinned: bool,
+        is_read_only: bool,
         pane_contents: Option<String>,
     ) -> Self {
         PaneLayoutMetadata {
@@ -272,7 +301,10 @@ impl PaneLayoutMetadata {
             is_borderless,
             title,
             is_focused,
+            is_pinned,
+            is_read_only,
             pane_contents,
+            plugin_state: None,
         }
     }
 }
@@ -0,0 +1,147 @@
+//! Lists the live sessions on this machine in a structured way, for clients that want to consume
+//! them programmatically rather than reading the formatted `list-sessions` output, and renames
+//! the current session in the socket dir and the session info cache that make up this list.
+
+use std::fs;
+use std::io;
+use std::os::unix::fs::FileTypeExt;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, UNIX_EPOCH};
+
+use zellij_utils::{
+    data::SessionInfo,
+    errors::prelude::*,
+    interprocess::local_socket::LocalSocketStream,
+    ipc::{
+        ClientToServerMsg, IpcReceiverWithContext, IpcSenderWithContext, ServerToClientMsg,
+        SessionListing,
+    },
+};
+
+/// Lists the sessions whose sockets are in `sock_dir` and whose server is still responding,
+/// sorted by creation time. Attached clients (and how many of them are observers) and tabs are
+/// read from the session info each session periodically writes to the file returned by
+/// `session_info_file`.
+pub(crate) fn list_live_sessions(
+    sock_dir: &Path,
+    session_info_file: impl Fn(&str) -> PathBuf,
+) -> Vec<SessionListing> {
+    let files = match fs::read_dir(sock_dir) {
+        Ok(files) => files,
+        Err(e) => {
+            if e.kind() != io::ErrorKind::NotFound {
+                log::error!("Failed to read socket dir {:?}: {:?}", sock_dir, e);
+            }
+            return vec![];
+        },
+    };
+    let mut sessions: Vec<SessionListing> = files
+        .filter_map(|file| file.ok())
+        .filter(|file| {
+            file.file_type()
+                .map(|file_type| file_type.is_socket())
+                .unwrap_or(false)
+        })
+        .filter_map(|file| {
+            let session_name = file.file_name().into_string().ok()?;
+            if !session_is_alive(&file.path()) {
+                return None;
+            }
+            let created = file
+                .metadata()
+                .and_then(|metadata| metadata.created())
+                .ok()
+                .and_then(|created| created.duration_since(UNIX_EPOCH).ok())
+                .map(|created| created.as_secs())
+                .unwrap_or_default();
+            // a session that did not write its info yet is listed without clients or tabs
+            let session_info = fs::read_to_string(session_info_file(&session_name))
+                .ok()
+                .and_then(|raw_session_info| {
+                    SessionInfo::from_string(&raw_session_info, &session_name).ok()
+                });
+            let (attached_clients, observer_clients, tabs) = session_info
+                .map(|session_info| {
+                    let tabs = session_info.tabs.into_iter().map(|tab| tab.name).collect();
+                    (
+                        session_info.connected_clients,
+                        session_info.observer_clients,
+                        tabs,
+                    )
+                })
+                .unwrap_or_default();
+            Some(SessionListing {
+                name: session_name,
+                created,
+                attached_clients,
+                observer_clients,
+                tabs,
+            })
+        })
+        .collect();
+    sessions.sort_by(|a, b| a.created.cmp(&b.created).then_with(|| a.name.cmp(&b.name)));
+    sessions
+}
+
+/// Renames the socket of a session in `sock_dir` along with its session info folder, as returned by
+/// `session_info_folder`. Fails without touching either if a session (live or resurrectable)
+/// already goes by `new_session_name`.
+pub(crate) fn rename_session(
+    sock_dir: &Path,
+    session_info_folder: impl Fn(&str) -> PathBuf,
+    old_session_name: &str,
+    new_session_name: &str,
+) -> Result<()> {
+    let err_context = || {
+        format!(
+            "failed to rename session {} to {}",
+            old_session_name, new_session_name
+        )
+    };
+    let old_socket_path = sock_dir.join(old_session_name);
+    let new_socket_path = sock_dir.join(new_session_name);
+    let new_session_info_folder = session_info_folder(new_session_name);
+    if new_session_info_folder.exists() {
+        return Err(anyhow!("a session by this name already exists")).with_context(err_context);
+    }
+    // unlike renaming, linking fails if the new socket path is taken, so a session that was
+    // created under this name in the meantime is never overwritten
+    fs::hard_link(&old_socket_path, &new_socket_path).with_context(err_context)?;
+    fs::remove_file(&old_socket_path).with_context(err_context)?;
+    let old_session_info_folder = session_info_folder(old_session_name);
+    if old_session_info_folder.exists() {
+        fs::rename(old_session_info_folder, new_session_info_folder).with_context(err_context)?;
+    }
+    Ok(())
+}
+
+const SESSION_REPLY_TIMEOUT: Duration = Duration::from_millis(500);
+
+fn session_is_alive(socket_path: &Path) -> bool {
+    // the socket file of a session that did not exit cleanly is left behind, so we make sure
+    // there's a server answering on the other side
+    match LocalSocketStream::connect(socket_path) {
+        Ok(stream) => {
+            let mut sender = IpcSenderWithContext::new(stream);
+            let _ = sender.send(ClientToServerMsg::ConnStatus);
+            let mut receiver: IpcReceiverWithContext<ServerToClientMsg> = sender.get_receiver();
+            // a server that is stuck should not hang whoever is listing the sessions, so we only
+            // wait for its reply for so long
+            let (reply_sender, reply_receiver) = mpsc::channel();
+            thread::spawn(move || {
+                let _ = reply_sender.send(receiver.recv());
+            });
+            matches!(
+                reply_receiver.recv_timeout(SESSION_REPLY_TIMEOUT),
+                Ok(Some((ServerToClientMsg::Connected, _)))
+            )
+        },
+        Err(_) => false,
+    }
+}
+
+#[cfg(test)]
+#[path = "./unit/session_listing_tests.rs"]
+mod session_listing_tests;
@@ -17,7 +17,7 @@ use std::cell::RefCell;
 use std::collections::{BTreeMap, HashMap, HashSet};
 use std::rc::Rc;
 use zellij_utils::{
-    data::{Palette, PaneId, Style},
+    data::{Event, Palette, PaneId, Style},
     input::layout::{
         FloatingPaneLayout, PluginUserConfiguration, Run, RunPluginLocation, TiledPaneLayout,
     },
@@ -43,6 +43,8 @@ pub struct LayoutApplier<'a> {
     debug: bool,
     arrow_fonts: bool,
     styled_underlines: bool,
+    transparent_background: bool,
+    keep_scrollback_in_alternate_screen: bool,
 }
 
 impl<'a> LayoutApplier<'a> {
@@ -65,6 +67,8 @@ impl<'a> LayoutApplier<'a> {
         debug: bool,
         arrow_fonts: bool,
         styled_underlines: bool,
+        transparent_background: bool,
+        keep_scrollback_in_alternate_screen: bool,
     ) -> Self {
         let viewport = viewport.clone();
         let senders = senders.clone();
@@ -96,6 +100,8 @@ impl<'a> LayoutApplier<'a> {
             debug,
             arrow_fonts,
             styled_underlines,
+            transparent_background,
+            keep_scrollback_in_alternate_screen,
         }
     }
     pub fn apply_layout(
@@ -204,6 +210,47 @@ impl<'a> LayoutApplier<'a> {
         };
         Ok(())
     }
+    /// Places the given tiled panes in the slots of the layout by order, rather than by matching
+    /// their contents to the slots like `apply_tiled_panes_layout_to_existing_panes`
+    pub fn apply_tiled_panes_layout_to_panes_in_order(
+        &mut self,
+        layout: &TiledPaneLayout,
+        pane_ids: &[PaneId],
+    ) -> Result<()> {
+        let err_context = || format!("failed to apply tiled panes layout to panes in order");
+        let free_space = self.total_space_for_tiled_panes();
+        let positions_in_layout = layout
+            .position_panes_in_space(&free_space, Some(pane_ids.len()))
+            .map_err(anyError::msg)
+            .with_context(err_context)?;
+        let mut existing_panes = self.tiled_panes.drain();
+        // the panes are placed in the order they were given, so the focused pane is not moved to
+        // the focused slot of the layout
+        let refocus_pane = false;
+        let mut pane_focuser = PaneFocuser::new(refocus_pane);
+        for ((layout, position_and_size), pane_id) in positions_in_layout.into_iter().zip(pane_ids)
+        {
+            if let Some(mut pane) = existing_panes.remove(pane_id) {
+                self.apply_layout_properties_to_pane(&mut pane, &layout, Some(position_and_size));
+                pane_focuser.set_pane_id_in_focused_location(layout.focus, &pane);
+                pane_focuser.set_expanded_stacked_pane(layout.is_expanded_in_stack, &pane);
+                resize_pty!(pane, self.os_api, self.senders, self.character_cell_size)?;
+                self.tiled_panes
+                    .add_pane_with_existing_geom(pane.pid(), pane);
+            }
+        }
+        // panes that were opened in the meantime and have no slot in the layout
+        for (pane_id, pane) in existing_panes {
+            self.tiled_panes.insert_pane(pane_id, pane);
+        }
+        pane_focuser.focus_tiled_pane(&mut self.tiled_panes);
+        LayoutApplier::offset_viewport(
+            self.viewport.clone(),
+            self.tiled_panes,
+            self.draw_pane_frames,
+        );
+        Ok(())
+    }
     fn apply_tiled_panes_layout(
         &mut self,
         layout: TiledPaneLayout,
@@ -269,11 +316,17 @@ impl<'a> LayoutApplier<'a> {
                             new_plugin.handle_pty_bytes(pane_initial_contents.as_bytes().into());
                             new_plugin.handle_pty_bytes("\n\r".as_bytes().into());
                         }
+                        self.restore_plugin_state(pid, &layout.plugin_initial_state)
+                            .with_context(err_context)?;
 
                         new_plugin.set_borderless(layout.borderless);
                         if let Some(exclude_from_sync) = layout.exclude_from_sync {
                             new_plugin.set_exclude_from_sync(exclude_from_sync);
                         }
+                        if let Some(read_only) = layout.read_only {
+                            new_plugin.set_read_only(read_only);
+                        }
+                        new_plugin.set_scroll_lines(layout.scroll_lines);
                         self.tiled_panes
                             .add_pane_with_existing_geom(PaneId::Plugin(pid), Box::new(new_plugin));
                         set_focus_pane_id(layout, PaneId::Plugin(pid));
@@ -302,6 +355,8 @@ impl<'a> LayoutApplier<'a> {
                                 self.debug,
                                 self.arrow_fonts,
                                 self.styled_underlines,
+                                self.transparent_background,
+                                self.keep_scrollback_in_alternate_screen,
                             );
                             if let Some(pane_initial_contents) = &layout.pane_initial_contents {
                                 new_pane.handle_pty_bytes(pane_initial_contents.as_bytes().into());
@@ -311,6 +366,10 @@ impl<'a> LayoutApplier<'a> {
                             if let Some(exclude_from_sync) = layout.exclude_from_sync {
                                 new_pane.set_exclude_from_sync(exclude_from_sync);
                             }
+                            if let Some(read_only) = layout.read_only {
+                                new_pane.set_read_only(read_only);
+                            }
+                            new_pane.set_scroll_lines(layout.scroll_lines);
                             if let Some(held_command) = hold_for_command {
                                 new_pane.hold(None, true, held_command.clone());
                             }
@@ -398,8 +457,14 @@ impl<'a> LayoutApplier<'a> {
                     new_pane.handle_pty_bytes(pane_initial_contents.as_bytes().into());
                     new_pane.handle_pty_bytes("\n\r".as_bytes().into());
                 }
+                self.restore_plugin_state(pid, &floating_pane_layout.plugin_initial_state)
+                    .with_context(err_context)?;
                 new_pane.set_borderless(false);
                 new_pane.set_content_offset(Offset::frame(1));
+                if let Some(read_only) = floating_pane_layout.read_only {
+                    new_pane.set_read_only(read_only);
+                }
+                new_pane.set_scroll_lines(floating_pane_layout.scroll_lines);
                 resize_pty!(
                     new_pane,
                     self.os_api,
@@ -408,6 +473,10 @@ impl<'a> LayoutApplier<'a> {
                 )?;
                 self.floating_panes
                     .add_pane(PaneId::Plugin(pid), Box::new(new_pane));
+                if floating_pane_layout.pinned.unwrap_or(false) {
+                    self.floating_panes
+                        .set_pane_pinned(PaneId::Plugin(pid), true);
+                }
                 if floating_pane_layout.focus.unwrap_or(false) {
                     focused_floating_pane = Some(PaneId::Plugin(pid));
                 }
@@ -434,6 +503,8 @@ impl<'a> LayoutApplier<'a> {
                     self.debug,
                     self.arrow_fonts,
                     self.styled_underlines,
+                    self.transparent_background,
+                    self.keep_scrollback_in_alternate_screen,
                 );
                 if let Some(pane_initial_contents) = &floating_pane_layout.pane_initial_contents {
                     new_pane.handle_pty_bytes(pane_initial_contents.as_bytes().into());
@@ -441,6 +512,10 @@ impl<'a> LayoutApplier<'a> {
                 }
                 new_pane.set_borderless(false);
                 new_pane.set_content_offset(Offset::frame(1));
+                if let Some(read_only) = floating_pane_layout.read_only {
+                    new_pane.set_read_only(read_only);
+                }
+                new_pane.set_scroll_lines(floating_pane_layout.scroll_lines);
                 if let Some(held_command) = hold_for_command {
                     new_pane.hold(None, true, held_command.clone());
                 }
@@ -452,6 +527,10 @@ impl<'a> LayoutApplier<'a> {
                 )?;
                 self.floating_panes
                     .add_pane(PaneId::Terminal(*pid), Box::new(new_pane));
+                if floating_pane_layout.pinned.unwrap_or(false) {
+                    self.floating_panes
+                        .set_pane_pinned(PaneId::Terminal(*pid), true);
+                }
                 if floating_pane_layout.focus.unwrap_or(false) {
                     focused_floating_pane = Some(PaneId::Terminal(*pid));
                 }
@@ -467,6 +546,24 @@ impl<'a> LayoutApplier<'a> {
             Ok(false)
         }
     }
+    fn restore_plugin_state(
+        &self,
+        plugin_id: u32,
+        plugin_initial_state: &Option<Vec<u8>>,
+    ) -> Result<()> {
+        // the plugin is still loading at this point, so this event is cached and applied before
+        // it first renders
+        if let Some(plugin_initial_state) = plugin_initial_state {
+            self.senders
+                .send_to_plugin(PluginInstruction::Update(vec![(
+                    Some(plugin_id),
+                    None,
+                    Event::RestoreState(plugin_initial_state.clone()),
+                )]))
+                .with_context(|| format!("failed to restore state of plugin {plugin_id}"))?;
+        }
+        Ok(())
+    }
     pub fn apply_floating_panes_layout_to_existing_panes(
         &mut self,
         floating_panes_layout: &Vec<FloatingPaneLayout>,
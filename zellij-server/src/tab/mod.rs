@@ -12,7 +12,7 @@ use std::path::PathBuf;
 use uuid::Uuid;
 use zellij_utils::data::{
     Direction, PaneInfo, PermissionStatus, PermissionType, PluginPermission, ResizeByPercent,
-    ResizeStrategy,
+    ResizePreset, ResizeStrategy, SelectionBounds,
 };
 use zellij_utils::errors::prelude::*;
 use zellij_utils::input::command::RunCommand;
@@ -22,7 +22,10 @@ use zellij_utils::{position::Position, serde};
 use crate::background_jobs::BackgroundJob;
 use crate::pty_writer::PtyWriteInstruction;
 use crate::screen::CopyOptions;
-use crate::ui::{loading_indication::LoadingIndication, pane_boundaries_frame::FrameParams};
+use crate::ui::{
+    loading_indication::LoadingIndication, pane_boundaries_frame::FrameParams,
+    ruler::ruler_character_chunks,
+};
 use layout_applier::LayoutApplier;
 use swap_layouts::SwapLayouts;
 
@@ -32,9 +35,9 @@ use crate::{
     output::{CharacterChunk, Output, SixelImageChunk},
     panes::sixel::SixelImageStore,
     panes::{FloatingPanes, TiledPanes},
-    panes::{LinkHandler, PluginPane, TerminalPane},
-    plugins::PluginInstruction,
-    pty::{ClientTabIndexOrPaneId, PtyInstruction, VteBytes},
+    panes::{LinkHandler, PaneSearchMatches, PluginPane, TerminalPane},
+    plugins::{PluginFrame, PluginInstruction},
+    pty::{get_default_shell, ClientTabIndexOrPaneId, PtyInstruction, VteBytes},
     thread_bus::ThreadSenders,
     ClientId, ServerInstruction,
 };
@@ -52,8 +55,9 @@ use zellij_utils::{
         command::TerminalAction,
         layout::{
             FloatingPaneLayout, PluginUserConfiguration, Run, RunPlugin, RunPluginLocation,
-            SwapFloatingLayout, SwapTiledLayout, TiledPaneLayout,
+            SplitDirection, SwapFloatingLayout, SwapTiledLayout, TiledPaneLayout,
         },
+        options::{Bell, PaneFrameStyle, ScrollLines, SurplusPanes},
         parse_keys,
     },
     pane_size::{Offset, PaneGeom, Size, SizeInPixels, Viewport},
@@ -158,6 +162,7 @@ pub(crate) struct Tab {
     os_api: Box<dyn ServerOsApi>,
     pub senders: ThreadSenders,
     synchronize_is_active: bool,
+    synchronized_scroll_is_active: bool, // scrolling a pane scrolls all the others with it
     should_clear_display_before_rendering: bool,
     mode_info: Rc<RefCell<HashMap<ClientId, ModeInfo>>>,
     default_mode_info: ModeInfo,
@@ -174,6 +179,7 @@ pub(crate) struct Tab {
     focus_pane_id: Option<PaneId>,
     copy_on_select: bool,
     last_mouse_hold_position: Option<Position>,
+    reported_selection: Option<(PaneId, SelectionBounds)>,
     terminal_emulator_colors: Rc<RefCell<Palette>>,
     terminal_emulator_color_codes: Rc<RefCell<HashMap<usize, String>>>,
     pids_waiting_resize: HashSet<u32>, // u32 is the terminal_id
@@ -188,6 +194,36 @@ pub(crate) struct Tab {
     debug: bool,
     arrow_fonts: bool,
     styled_underlines: bool,
+    transparent_background: bool,
+    keep_scrollback_in_alternate_screen: bool,
+    // clients with a visible ruler => (x, y, columns, rows) of the last rendered ruler
+    ruler_clients: HashMap<ClientId, Option<(usize, usize, usize, usize)>>,
+    zoom_state: Option<ZoomState>,
+    // a layout applied in place that is waiting for the panes opened for some of its slots, with
+    // the panes already in the tab to place in it by order
+    pending_in_place_layout: Option<(TiledPaneLayout, Vec<PaneId>)>,
+    bell: Bell,
+    has_bell: bool, // a pane rang the bell while the tab was not focused
+    // how much to blend the unfocused panes towards the background, if at all
+    focus_dimming: Option<f64>,
+    // the pane each client had focused when the panes were last dimmed
+    last_dimmed_focus: HashMap<ClientId, PaneId>,
+    // the palettes of the clients that render with their own instead of the session's
+    client_themes: HashMap<ClientId, Palette>,
+    // the characters the pane frames are drawn with, thin lines if None
+    pane_frame_style: Option<PaneFrameStyle>,
+    // the name of a palette color or a hex color to show this tab with in the tab bar
+    color: Option<String>,
+}
+
+// the layout of the tab from before a pane was zoomed, restored when the zoom is toggled off
+#[derive(Clone, Debug)]
+struct ZoomState {
+    pane_id: PaneId,
+    display_area: Size,
+    tiled_pane_geoms: HashMap<PaneId, PaneGeom>,
+    floating_pane_geoms: HashMap<PaneId, PaneGeom>,
+    floating_panes_were_visible: bool,
 }
 
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
@@ -215,6 +251,8 @@ pub trait Pane {
     fn set_geom_override(&mut self, pane_geom: PaneGeom);
     fn handle_pty_bytes(&mut self, _bytes: VteBytes) {}
     fn handle_plugin_bytes(&mut self, _client_id: ClientId, _bytes: VteBytes) {}
+    fn handle_plugin_frame(&mut self, _client_id: ClientId, _frame: PluginFrame) {}
+    fn handle_plugin_log_line(&mut self, _client_id: ClientId, _log_line: String) {}
     fn cursor_coordinates(&self) -> Option<(usize, usize)>;
     fn adjust_input_to_terminal(&mut self, _input_bytes: Vec<u8>) -> Option<AdjustedInput> {
         None
@@ -258,6 +296,9 @@ pub trait Pane {
     fn dump_screen(&mut self, _client_id: ClientId, _full: bool) -> String {
         "".to_owned()
     }
+    fn plain_text(&self, _include_scrollback: bool) -> String {
+        "".to_owned()
+    }
     fn scroll_up(&mut self, count: usize, client_id: ClientId);
     fn scroll_down(&mut self, count: usize, client_id: ClientId);
     fn clear_scroll(&mut self);
@@ -282,6 +323,12 @@ pub trait Pane {
     fn get_selected_text(&self) -> Option<String> {
         None
     }
+    fn hyperlink_at(&self, _position: &Position) -> Option<String> {
+        None
+    }
+    fn get_selection_bounds(&self) -> Option<SelectionBounds> {
+        None
+    }
 
     fn right_boundary_x_coords(&self) -> usize {
         self.x() + self.cols()
@@ -342,10 +389,14 @@ pub trait Pane {
         self.cols() > reduce_by && self.cols() - reduce_by >= self.min_width()
     }
     fn min_width(&self) -> usize {
-        MIN_TERMINAL_WIDTH
+        self.min_size()
+            .map(|min_size| std::cmp::max(min_size.cols, MIN_TERMINAL_WIDTH))
+            .unwrap_or(MIN_TERMINAL_WIDTH)
     }
     fn min_height(&self) -> usize {
-        MIN_TERMINAL_HEIGHT
+        self.min_size()
+            .map(|min_size| std::cmp::max(min_size.rows, MIN_TERMINAL_HEIGHT))
+            .unwrap_or(MIN_TERMINAL_HEIGHT)
     }
     fn drain_messages_to_pty(&mut self) -> Vec<Vec<u8>> {
         // TODO: this is only relevant to terminal panes
@@ -355,7 +406,16 @@ pub trait Pane {
     fn drain_clipboard_update(&mut self) -> Option<String> {
         None
     }
+    // whether the bell rang since this was last called, only terminal panes ring the bell
+    fn drain_bell(&mut self, _audible: bool) -> bool {
+        false
+    }
+    // the title the pane's process set since this was last called (eg. with OSC 0/2)
+    fn drain_title_update(&mut self) -> Option<String> {
+        None
+    }
     fn render_full_viewport(&mut self) {}
+    fn toggle_show_invisibles(&mut self) {} // only relevant for terminals
     fn relative_position(&self, position_on_screen: &Position) -> Position {
         position_on_screen.relative_to(self.get_content_y(), self.get_content_x())
     }
@@ -392,6 +452,17 @@ pub trait Pane {
     fn borderless(&self) -> bool;
     fn set_exclude_from_sync(&mut self, exclude_from_sync: bool);
     fn exclude_from_sync(&self) -> bool;
+    fn set_read_only(&mut self, read_only: bool); // read only panes get no input from clients
+    fn read_only(&self) -> bool;
+    fn set_min_size(&mut self, min_size: Option<Size>); // including the pane frame
+    fn min_size(&self) -> Option<Size>;
+    // the size (including the pane frame) the pane's content would like to have, if known -
+    // fixed-size apps tell us about it through the pane's minimum size
+    fn preferred_size(&self) -> Option<Size> {
+        self.min_size()
+    }
+    fn set_scroll_lines(&mut self, scroll_lines: Option<ScrollLines>); // None for the default
+    fn scroll_lines(&self) -> Option<ScrollLines>;
 
     // TODO: this should probably be merged with the mouse_right_click
     fn handle_right_click(&mut self, _to: &Position, _client_id: ClientId) {}
@@ -449,6 +520,17 @@ pub trait Pane {
     fn clear_search(&mut self) {
         // No-op by default (only terminal-panes currently have search capability)
     }
+    fn search_and_highlight(
+        &mut self,
+        _needle: &str,
+        _case_sensitive: bool,
+    ) -> Option<PaneSearchMatches> {
+        // None by default (only terminal-panes currently have search capability)
+        None
+    }
+    fn clear_highlighted_search(&mut self) {
+        // No-op by default (only terminal-panes currently have search capability)
+    }
     fn is_alternate_mode_active(&self) -> bool {
         // False by default (only terminal-panes support alternate mode)
         false
@@ -457,6 +539,7 @@ pub trait Pane {
         // No-op by default, only terminal panes support holding
     }
     fn add_red_pane_frame_color_override(&mut self, _error_text: Option<String>);
+    fn add_bell_pane_frame_color_override(&mut self) {}
     fn clear_pane_frame_color_override(&mut self);
     fn frame_color_override(&self) -> Option<PaletteColor>;
     fn invoked_with(&self) -> Option<&Run>;
@@ -479,6 +562,31 @@ pub trait Pane {
     fn serialize(&self, _scrollback_lines_to_serialize: Option<usize>) -> Option<String> {
         None
     }
+    fn set_fed_by_plugin(&mut self, _plugin_id: u32) {
+        // No-op by default, only terminal panes can be fed by plugins
+    }
+    fn fed_by_plugin(&self) -> Option<u32> {
+        None
+    }
+    fn viewport_chunks(
+        &self,
+        _content_x: usize,
+        _content_y: usize,
+        _rows: usize,
+        _columns: usize,
+    ) -> Option<Vec<CharacterChunk>> {
+        // only terminal panes have a grid
+        None
+    }
+    fn set_alias_of(&mut self, _terminal_id: u32) {
+        // No-op by default, only terminal panes can mirror other terminal panes
+    }
+    fn alias_of(&self) -> Option<u32> {
+        None
+    }
+    fn set_alias_view(&mut self, _character_chunks: Vec<CharacterChunk>) {
+        // No-op by default, only terminal panes can mirror other terminal panes
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -488,6 +596,13 @@ pub enum AdjustedInput {
     PermissionRequestResult(Vec<PermissionType>, PermissionStatus),
     CloseThisPane,
     DropToShellInThisPane { working_dir: Option<PathBuf> },
+    WriteToPlugin(Vec<PluginInput>),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum PluginInput {
+    Keys(Vec<u8>),
+    Paste(String), // the text of a bracketed paste, without its start/end markers
 }
 pub fn get_next_terminal_position(
     tiled_panes: &TiledPanes,
@@ -538,6 +653,9 @@ impl Tab {
         debug: bool,
         arrow_fonts: bool,
         styled_underlines: bool,
+        transparent_background: bool,
+        keep_scrollback_in_alternate_screen: bool,
+        bell: Bell,
     ) -> Self {
         let name = if name.is_empty() {
             format!("Tab #{}", index + 1)
@@ -601,6 +719,7 @@ impl Tab {
             character_cell_size,
             sixel_image_store,
             synchronize_is_active: false,
+            synchronized_scroll_is_active: false,
             os_api,
             senders,
             should_clear_display_before_rendering: false,
@@ -617,6 +736,7 @@ impl Tab {
             focus_pane_id: None,
             copy_on_select: copy_options.copy_on_select,
             last_mouse_hold_position: None,
+            reported_selection: None,
             terminal_emulator_colors,
             terminal_emulator_color_codes,
             pids_waiting_resize: HashSet::new(),
@@ -628,6 +748,18 @@ impl Tab {
             debug,
             arrow_fonts,
             styled_underlines,
+            transparent_background,
+            keep_scrollback_in_alternate_screen,
+            ruler_clients: HashMap::new(),
+            zoom_state: None,
+            pending_in_place_layout: None,
+            bell,
+            has_bell: false,
+            focus_dimming: None,
+            last_dimmed_focus: HashMap::new(),
+            client_themes: HashMap::new(),
+            pane_frame_style: None,
+            color: None,
         }
     }
 
@@ -642,6 +774,9 @@ impl Tab {
     ) -> Result<()> {
         self.swap_layouts
             .set_base_layout((layout.clone(), floating_panes_layout.clone()));
+        if layout.color.is_some() {
+            self.color = layout.color.clone();
+        }
         let should_show_floating_panes = LayoutApplier::new(
             &self.viewport,
             &self.senders,
@@ -661,6 +796,8 @@ impl Tab {
             self.debug,
             self.arrow_fonts,
             self.styled_underlines,
+            self.transparent_background,
+            self.keep_scrollback_in_alternate_screen,
         )
         .apply_layout(
             layout,
@@ -724,6 +861,8 @@ impl Tab {
                 self.debug,
                 self.arrow_fonts,
                 self.styled_underlines,
+                self.transparent_background,
+                self.keep_scrollback_in_alternate_screen,
             )
             .apply_floating_panes_layout_to_existing_panes(
                 &layout_candidate,
@@ -780,6 +919,8 @@ impl Tab {
                 self.debug,
                 self.arrow_fonts,
                 self.styled_underlines,
+                self.transparent_background,
+                self.keep_scrollback_in_alternate_screen,
             )
             .apply_tiled_panes_layout_to_existing_panes(
                 &layout_candidate,
@@ -794,6 +935,159 @@ impl Tab {
         self.should_clear_display_before_rendering = true;
         Ok(())
     }
+    /// Reshapes the tiled panes of this tab to match `layout` without replacing them, so that their
+    /// processes keep running: the panes are placed in its slots by order (top to bottom, left to
+    /// right) and those beyond its slots are floated or closed. Returns the slots that are left, the
+    /// layout is applied once the terminals opened for them are added with
+    /// [`Tab::fill_in_place_layout`].
+    pub fn apply_layout_in_place(
+        &mut self,
+        layout: TiledPaneLayout,
+        surplus_panes: SurplusPanes,
+    ) -> Result<Vec<Option<Run>>> {
+        let tab_index = self.index;
+        let err_context = || format!("failed to apply layout in place to tab {}", tab_index);
+        if self.pending_in_place_layout.is_some() {
+            return Err(anyhow!(
+                "the panes of the last layout applied to this tab are still being opened"
+            ))
+            .with_context(err_context);
+        }
+        let slots = layout.extract_run_instructions();
+        self.unzoom().with_context(err_context)?;
+        if self.tiled_panes.fullscreen_is_active() {
+            self.tiled_panes.unset_fullscreen();
+        }
+        let mut tiled_panes: Vec<(PaneId, PaneGeom)> = self.tiled_pane_geoms();
+        tiled_panes.sort_by_key(|(_pane_id, geom)| (geom.y, geom.x));
+        let mut pane_ids: Vec<PaneId> = tiled_panes
+            .into_iter()
+            .map(|(pane_id, _geom)| pane_id)
+            .collect();
+        let empty_slots = slots.get(pane_ids.len()..).unwrap_or(&[]);
+        if empty_slots
+            .iter()
+            .any(|run| matches!(run, Some(Run::Plugin(..))))
+        {
+            return Err(anyhow!(
+                "plugin panes cannot be opened when applying a layout in place"
+            ))
+            .with_context(err_context);
+        }
+        let terminals_to_open = empty_slots.to_vec();
+        // the swap layouts no longer describe this tab
+        self.swap_layouts.set_is_tiled_damaged();
+        let surplus_pane_ids = pane_ids.split_off(slots.len().min(pane_ids.len()));
+        for pane_id in surplus_pane_ids {
+            match surplus_panes {
+                SurplusPanes::Float => {
+                    if let Some(pane) = self.close_pane(pane_id, true, None) {
+                        self.show_floating_panes();
+                        self.add_floating_pane(pane, pane_id, None)
+                            .with_context(err_context)?;
+                    }
+                },
+                SurplusPanes::Close => {
+                    self.senders
+                        .send_to_pty(PtyInstruction::ClosePane(pane_id))
+                        .with_context(err_context)?;
+                    self.close_pane(pane_id, false, None);
+                },
+            }
+        }
+        if terminals_to_open.is_empty() {
+            self.apply_layout_to_panes_in_order(&layout, &pane_ids)
+                .with_context(err_context)?;
+        } else {
+            self.pending_in_place_layout = Some((layout, pane_ids));
+        }
+        Ok(terminals_to_open)
+    }
+    /// Adds the terminals opened for the slots of the layout applied in place and applies it. Only
+    /// these terminals fill its slots, so a slot whose terminal failed to open is left out.
+    pub fn fill_in_place_layout(
+        &mut self,
+        new_terminal_ids: Vec<(u32, HoldForCommand, Option<Run>)>,
+    ) -> Result<()> {
+        let tab_index = self.index;
+        let err_context = || format!("failed to fill in place layout of tab {}", tab_index);
+        let (layout, mut pane_ids) = match self.pending_in_place_layout.take() {
+            Some(pending_in_place_layout) => pending_in_place_layout,
+            None => {
+                return Err(anyhow!("no layout is being applied to this tab"))
+                    .with_context(err_context)
+            },
+        };
+        // panes closed in the meantime no longer take a slot
+        pane_ids.retain(|pane_id| self.tiled_panes.panes_contain(pane_id));
+        for (terminal_id, hold_for_command, invoked_with) in new_terminal_ids {
+            let pane_id = PaneId::Terminal(terminal_id);
+            let next_terminal_position = self.get_next_terminal_position();
+            let new_pane = TerminalPane::new(
+                terminal_id,
+                PaneGeom::default(), // this will be filled out by the layout
+                self.style,
+                next_terminal_position,
+                String::new(),
+                self.link_handler.clone(),
+                self.character_cell_size.clone(),
+                self.sixel_image_store.clone(),
+                self.terminal_emulator_colors.clone(),
+                self.terminal_emulator_color_codes.clone(),
+                None,
+                invoked_with,
+                self.debug,
+                self.arrow_fonts,
+                self.styled_underlines,
+                self.transparent_background,
+                self.keep_scrollback_in_alternate_screen,
+            );
+            self.tiled_panes
+                .insert_pane_without_relayout(pane_id, Box::new(new_pane));
+            if let Some(hold_for_command) = hold_for_command {
+                let is_first_run = true;
+                self.hold_pane(pane_id, None, is_first_run, hold_for_command);
+            }
+            pane_ids.push(pane_id);
+        }
+        self.apply_layout_to_panes_in_order(&layout, &pane_ids)
+            .with_context(err_context)
+    }
+    fn apply_layout_to_panes_in_order(
+        &mut self,
+        layout: &TiledPaneLayout,
+        pane_ids: &[PaneId],
+    ) -> Result<()> {
+        LayoutApplier::new(
+            &self.viewport,
+            &self.senders,
+            &self.sixel_image_store,
+            &self.link_handler,
+            &self.terminal_emulator_colors,
+            &self.terminal_emulator_color_codes,
+            &self.character_cell_size,
+            &self.connected_clients,
+            &self.style,
+            &self.display_area,
+            &mut self.tiled_panes,
+            &mut self.floating_panes,
+            self.draw_pane_frames,
+            &mut self.focus_pane_id,
+            &self.os_api,
+            self.debug,
+            self.arrow_fonts,
+            self.styled_underlines,
+            self.transparent_background,
+            self.keep_scrollback_in_alternate_screen,
+        )
+        .apply_tiled_panes_layout_to_panes_in_order(layout, pane_ids)?;
+        self.tiled_panes.reapply_pane_frames();
+        let display_area = *self.display_area.borrow();
+        self.tiled_panes.resize(display_area);
+        self.should_clear_display_before_rendering = true;
+        self.set_force_render();
+        Ok(())
+    }
     pub fn previous_swap_layout(&mut self, client_id: Option<ClientId>) -> Result<()> {
         let search_backwards = true;
         if self.floating_panes.panes_are_visible() {
@@ -869,6 +1163,7 @@ impl Tab {
         Ok(())
     }
     pub fn add_client(&mut self, client_id: ClientId, mode_info: Option<ModeInfo>) -> Result<()> {
+        self.has_bell = false;
         let other_clients_exist_in_tab = { !self.connected_clients.borrow().is_empty() };
         if other_clients_exist_in_tab {
             if let Some(first_active_floating_pane_id) =
@@ -933,6 +1228,8 @@ impl Tab {
     pub fn remove_client(&mut self, client_id: ClientId) {
         self.focus_pane_id = None;
         self.connected_clients.borrow_mut().remove(&client_id);
+        self.ruler_clients.remove(&client_id);
+        self.client_themes.remove(&client_id);
         self.set_force_render();
     }
     pub fn drain_connected_clients(
@@ -1043,6 +1340,19 @@ impl Tab {
         self.set_force_render();
         Ok(())
     }
+    /// Hides the floating panes of this tab or shows them again, with the geometry they had and
+    /// the pane each client had focused. Floating panes added to the tab while they were hidden
+    /// are shown with them. Unlike [`Tab::toggle_floating_panes`], this does not open a floating
+    /// pane if the tab has none.
+    pub fn toggle_floating_panes_visibility(&mut self, client_id: ClientId) {
+        if self.floating_panes.panes_are_visible() {
+            self.hide_floating_panes();
+        } else if let Some(last_floating_pane_id) = self.floating_panes.last_floating_pane_id() {
+            self.show_floating_panes();
+            self.floating_panes
+                .focus_pane_if_client_not_focused(last_floating_pane_id, client_id);
+        }
+    }
     pub fn new_pane(
         &mut self,
         pid: PaneId,
@@ -1052,6 +1362,7 @@ impl Tab {
         client_id: Option<ClientId>,
     ) -> Result<()> {
         let err_context = || format!("failed to create new pane with id {pid:?}");
+        self.unzoom().with_context(err_context)?;
         match should_float {
             Some(true) => self.show_floating_panes(),
             Some(false) => self.hide_floating_panes(),
@@ -1078,6 +1389,8 @@ impl Tab {
                     self.debug,
                     self.arrow_fonts,
                     self.styled_underlines,
+                    self.transparent_background,
+                    self.keep_scrollback_in_alternate_screen,
                 )) as Box<dyn Pane>
             },
             PaneId::Plugin(plugin_pid) => {
@@ -1140,6 +1453,8 @@ impl Tab {
                     self.debug,
                     self.arrow_fonts,
                     self.styled_underlines,
+                    self.transparent_background,
+                    self.keep_scrollback_in_alternate_screen,
                 );
                 new_pane.update_name("EDITING SCROLLBACK"); // we do this here and not in the
                                                             // constructor so it won't be overrided
@@ -1214,6 +1529,8 @@ impl Tab {
                     self.debug,
                     self.arrow_fonts,
                     self.styled_underlines,
+                    self.transparent_background,
+                    self.keep_scrollback_in_alternate_screen,
                 );
                 let replaced_pane = if self.floating_panes.panes_contain(&old_pane_id) {
                     self.floating_panes
@@ -1314,6 +1631,7 @@ impl Tab {
         if self.floating_panes.panes_are_visible() {
             return Ok(());
         }
+        self.unzoom().with_context(err_context)?;
         self.close_down_to_max_terminals()
             .with_context(err_context)?;
         if self.tiled_panes.fullscreen_is_active() {
@@ -1338,6 +1656,8 @@ impl Tab {
                     self.debug,
                     self.arrow_fonts,
                     self.styled_underlines,
+                    self.transparent_background,
+                    self.keep_scrollback_in_alternate_screen,
                 );
                 self.tiled_panes
                     .split_pane_horizontally(pid, Box::new(new_terminal), client_id);
@@ -1373,6 +1693,7 @@ impl Tab {
         if self.floating_panes.panes_are_visible() {
             return Ok(());
         }
+        self.unzoom().with_context(err_context)?;
         self.close_down_to_max_terminals()
             .with_context(err_context)?;
         if self.tiled_panes.fullscreen_is_active() {
@@ -1397,6 +1718,8 @@ impl Tab {
                     self.debug,
                     self.arrow_fonts,
                     self.styled_underlines,
+                    self.transparent_background,
+                    self.keep_scrollback_in_alternate_screen,
                 );
                 self.tiled_panes
                     .split_pane_vertically(pid, Box::new(new_terminal), client_id);
@@ -1421,6 +1744,90 @@ impl Tab {
         }
         Ok(())
     }
+    /// Splits the tiled pane `pane_id` like `vertical_split`/`horizontal_split`, except that it
+    /// keeps `ratio` of its space rather than half of it. The new pane `pid` gets the rest and the
+    /// focus of the client.
+    pub fn split_pane_with_ratio(
+        &mut self,
+        pane_id: PaneId,
+        pid: PaneId,
+        direction: SplitDirection,
+        ratio: f64,
+        client_id: ClientId,
+    ) -> Result<()> {
+        let err_context = || {
+            format!("failed to split pane {pane_id:?} with ratio {ratio} for client {client_id}")
+        };
+        if self.floating_panes.panes_are_visible() {
+            return Err(anyhow!(
+                "cannot split a tiled pane while floating panes are shown"
+            ))
+            .with_context(err_context);
+        }
+        if !self.tiled_panes.panes_contain(&pane_id) {
+            return Err(anyhow!("pane {pane_id:?} is not a tiled pane")).with_context(err_context);
+        }
+        self.unzoom().with_context(err_context)?;
+        self.close_down_to_max_terminals()
+            .with_context(err_context)?;
+        if self.tiled_panes.fullscreen_is_active() {
+            self.toggle_active_pane_fullscreen(client_id);
+        }
+        // the pane to split becomes the active one, the new pane takes the focus from it below
+        self.tiled_panes.focus_pane(pane_id, client_id);
+        let can_split = match direction {
+            SplitDirection::Vertical => self.tiled_panes.can_split_pane_vertically(client_id),
+            SplitDirection::Horizontal => self.tiled_panes.can_split_pane_horizontally(client_id),
+        };
+        if can_split {
+            if let PaneId::Terminal(term_pid) = pid {
+                let next_terminal_position = self.get_next_terminal_position();
+                let new_terminal = TerminalPane::new(
+                    term_pid,
+                    PaneGeom::default(), // the initial size will be set later
+                    self.style,
+                    next_terminal_position,
+                    String::new(),
+                    self.link_handler.clone(),
+                    self.character_cell_size.clone(),
+                    self.sixel_image_store.clone(),
+                    self.terminal_emulator_colors.clone(),
+                    self.terminal_emulator_color_codes.clone(),
+                    None,
+                    None,
+                    self.debug,
+                    self.arrow_fonts,
+                    self.styled_underlines,
+                    self.transparent_background,
+                    self.keep_scrollback_in_alternate_screen,
+                );
+                self.tiled_panes.split_pane_with_ratio(
+                    pid,
+                    Box::new(new_terminal),
+                    direction,
+                    ratio,
+                    client_id,
+                );
+                self.should_clear_display_before_rendering = true;
+                self.tiled_panes.focus_pane(pid, client_id);
+                self.swap_layouts.set_is_tiled_damaged();
+            }
+        } else {
+            log::error!("No room to split pane {:?}", direction);
+            if let Some(active_pane_id) = self.tiled_panes.get_active_pane_id(client_id) {
+                self.senders
+                    .send_to_background_jobs(BackgroundJob::DisplayPaneError(
+                        vec![active_pane_id],
+                        "CAN'T SPLIT!".into(),
+                    ))
+                    .with_context(err_context)?;
+            }
+            self.senders
+                .send_to_pty(PtyInstruction::ClosePane(pid))
+                .with_context(err_context)?;
+        }
+        Ok(())
+    }
 
     pub fn get_active_pane(&self, client_id: ClientId) -> Option<&dyn Pane> {
         self.get_active_pane_id(client_id).and_then(|ap| {
@@ -1493,6 +1900,32 @@ impl Tab {
     pub fn has_non_suppressed_pane_with_pid(&self, pid: &PaneId) -> bool {
         self.tiled_panes.panes_contain(pid) || self.floating_panes.panes_contain(pid)
     }
+    /// The terminal action to start a duplicate of this pane with: the same command (or file) in
+    /// a new process, or `None` for a pane running the default shell
+    pub fn terminal_action_to_duplicate_pane(&self, pane_id: PaneId) -> Option<TerminalAction> {
+        let pane = self
+            .tiled_panes
+            .get_pane(pane_id)
+            .or_else(|| self.floating_panes.get_pane(pane_id))?;
+        match pane.invoked_with() {
+            Some(Run::Command(run_command)) => {
+                let mut run_command = run_command.clone();
+                run_command.hold_on_start = false;
+                Some(TerminalAction::RunCommand(run_command))
+            },
+            Some(Run::EditFile(file, line_number, cwd)) => Some(TerminalAction::OpenFile(
+                file.clone(),
+                *line_number,
+                cwd.clone(),
+            )),
+            _ => None,
+        }
+    }
+    /// The direction to split this pane in to make room for its duplicate, `None` if it is not a
+    /// tiled pane
+    pub fn split_direction_for_pane(&mut self, pane_id: PaneId) -> Option<SplitDirection> {
+        self.tiled_panes.split_direction_for_pane(pane_id)
+    }
     pub fn handle_pty_bytes(&mut self, pid: u32, bytes: VteBytes) -> Result<()> {
         if self.is_pending {
             self.pending_instructions
@@ -1548,6 +1981,48 @@ impl Tab {
         }
         Ok(())
     }
+    pub fn handle_plugin_frame(
+        &mut self,
+        pid: u32,
+        client_id: ClientId,
+        frame: PluginFrame,
+    ) -> Result<()> {
+        if let Some(plugin_pane) = self
+            .tiled_panes
+            .get_pane_mut(PaneId::Plugin(pid))
+            .or_else(|| self.floating_panes.get_pane_mut(PaneId::Plugin(pid)))
+            .or_else(|| {
+                self.suppressed_panes
+                    .values_mut()
+                    .find(|s_p| s_p.1.pid() == PaneId::Plugin(pid))
+                    .map(|s_p| &mut s_p.1)
+            })
+        {
+            plugin_pane.handle_plugin_frame(client_id, frame);
+        }
+        Ok(())
+    }
+    pub fn handle_plugin_log_line(
+        &mut self,
+        pid: u32,
+        client_id: ClientId,
+        log_line: String,
+    ) -> Result<()> {
+        if let Some(plugin_pane) = self
+            .tiled_panes
+            .get_pane_mut(PaneId::Plugin(pid))
+            .or_else(|| self.floating_panes.get_pane_mut(PaneId::Plugin(pid)))
+            .or_else(|| {
+                self.suppressed_panes
+                    .values_mut()
+                    .find(|s_p| s_p.1.pid() == PaneId::Plugin(pid))
+                    .map(|s_p| &mut s_p.1)
+            })
+        {
+            plugin_pane.handle_plugin_log_line(client_id, log_line);
+        }
+        Ok(())
+    }
     pub fn process_pending_vte_events(&mut self, pid: u32) -> Result<()> {
         if let Some(pending_vte_events) = self.pending_vte_events.get_mut(&pid) {
             let vte_events: Vec<VteBytes> = pending_vte_events.drain(..).collect();
@@ -1584,6 +2059,8 @@ impl Tab {
             terminal_output.handle_pty_bytes(bytes);
             let messages_to_pty = terminal_output.drain_messages_to_pty();
             let clipboard_update = terminal_output.drain_clipboard_update();
+            let bell_rang = terminal_output.drain_bell(self.bell.audible);
+            let title_update = terminal_output.drain_title_update();
             for message in messages_to_pty {
                 self.write_to_pane_id(message, PaneId::Terminal(pid), None)
                     .with_context(err_context)?;
@@ -1592,9 +2069,54 @@ impl Tab {
                 self.write_selection_to_clipboard(&string)
                     .with_context(err_context)?;
             }
+            if bell_rang {
+                self.ring_bell(PaneId::Terminal(pid))
+                    .with_context(err_context)?;
+            }
+            if let Some(title) = title_update {
+                self.senders
+                    .send_to_plugin(PluginInstruction::Update(vec![(
+                        None,
+                        None,
+                        Event::PaneTitleChanged(PaneId::Terminal(pid), title),
+                    )]))
+                    .with_context(|| format!("failed to inform plugins about title of pane {pid}"))
+                    .non_fatal();
+            }
+        }
+        Ok(())
+    }
+    fn ring_bell(&mut self, pane_id: PaneId) -> Result<()> {
+        if self.bell.visual {
+            self.senders
+                .send_to_background_jobs(BackgroundJob::FlashPaneFrame(pane_id))
+                .with_context(|| format!("failed to flash the frame of pane {pane_id:?}"))?;
+        }
+        // the mark is cleared once a client focuses the tab
+        if self.bell.tab && self.connected_clients.borrow().is_empty() {
+            self.has_bell = true;
+        }
+        if self.bell.plugin {
+            self.senders
+                .send_to_plugin(PluginInstruction::Update(vec![(
+                    None,
+                    None,
+                    Event::Bell(pane_id),
+                )]))
+                .with_context(|| format!("failed to inform plugins about bell in {pane_id:?}"))
+                .non_fatal();
         }
         Ok(())
     }
+    pub fn has_bell(&self) -> bool {
+        self.has_bell
+    }
+    pub fn color(&self) -> Option<&String> {
+        self.color.as_ref()
+    }
+    pub fn set_color(&mut self, color: Option<String>) {
+        self.color = color;
+    }
 
     pub fn write_to_terminals_on_current_tab(
         &mut self,
@@ -1712,14 +2234,28 @@ impl Tab {
             return Ok(should_update_ui);
         }
 
+        // input from clients never reaches read only panes, only the replies of the terminal
+        // emulator itself (eg. to a cursor position query) do
+        if client_id.is_some() && active_terminal.read_only() {
+            self.senders
+                .send_to_background_jobs(BackgroundJob::DisplayPaneError(
+                    vec![pane_id],
+                    "READ ONLY".into(),
+                ))
+                .with_context(err_context)?;
+            return Ok(should_update_ui);
+        }
+
         match pane_id {
             PaneId::Terminal(active_terminal_id) => {
+                // an alias has no process of its own, its input goes to the pane it mirrors
+                let input_terminal_id = active_terminal.alias_of().unwrap_or(active_terminal_id);
                 match active_terminal.adjust_input_to_terminal(input_bytes) {
                     Some(AdjustedInput::WriteBytesToTerminal(adjusted_input)) => {
                         self.senders
                             .send_to_pty_writer(PtyWriteInstruction::Write(
                                 adjusted_input,
-                                active_terminal_id,
+                                input_terminal_id,
                             ))
                             .with_context(err_context)?;
                     },
@@ -1753,14 +2289,29 @@ impl Tab {
                 }
             },
             PaneId::Plugin(pid) => match active_terminal.adjust_input_to_terminal(input_bytes) {
-                Some(AdjustedInput::WriteBytesToTerminal(adjusted_input)) => {
+                Some(AdjustedInput::WriteToPlugin(plugin_input)) => {
                     let mut plugin_updates = vec![];
-                    for key in parse_keys(&adjusted_input) {
-                        plugin_updates.push((Some(pid), client_id, Event::Key(key)));
+                    for input in plugin_input {
+                        match input {
+                            PluginInput::Keys(input_bytes) => {
+                                for key in parse_keys(&input_bytes) {
+                                    plugin_updates.push((Some(pid), client_id, Event::Key(key)));
+                                }
+                            },
+                            PluginInput::Paste(pasted_text) => {
+                                plugin_updates.push((
+                                    Some(pid),
+                                    client_id,
+                                    Event::Paste(pasted_text),
+                                ));
+                            },
+                        }
+                    }
+                    if !plugin_updates.is_empty() {
+                        self.senders
+                            .send_to_plugin(PluginInstruction::Update(plugin_updates))
+                            .with_context(err_context)?;
                     }
-                    self.senders
-                        .send_to_plugin(PluginInstruction::Update(plugin_updates))
-                        .with_context(err_context)?;
                 },
                 Some(AdjustedInput::PermissionRequestResult(permissions, status)) => {
                     self.request_plugin_permissions(pid, None);
@@ -1814,22 +2365,180 @@ impl Tab {
     pub fn is_fullscreen_active(&self) -> bool {
         self.tiled_panes.fullscreen_is_active()
     }
-    pub fn are_floating_panes_visible(&self) -> bool {
-        self.floating_panes.panes_are_visible()
-    }
-    pub fn focus_pane_left_fullscreen(&mut self, client_id: ClientId) {
-        if !self.is_fullscreen_active() {
-            return;
+    /// Expands the client's active tiled pane to the whole tab, hiding the floating panes. Toggling
+    /// again on the zoomed pane restores the exact layout from before the zoom, toggling on
+    /// another pane (eg. after focus moved while zoomed) zooms that pane instead.
+    pub fn toggle_zoom(&mut self, client_id: ClientId) -> Result<()> {
+        let err_context = || format!("failed to toggle zoom for client {client_id}");
+        let active_pane_id = self
+            .tiled_panes
+            .get_active_pane_id(client_id)
+            .with_context(err_context)?;
+        let zoomed_pane_id = self
+            .zoom_state
+            .as_ref()
+            .map(|zoom_state| zoom_state.pane_id);
+        if zoomed_pane_id.is_some() {
+            self.unzoom().with_context(err_context)?;
         }
-
-        self.tiled_panes.focus_pane_left_fullscreen(client_id);
-    }
-    pub fn focus_pane_right_fullscreen(&mut self, client_id: ClientId) {
-        if !self.is_fullscreen_active() {
-            return;
+        if zoomed_pane_id != Some(active_pane_id) {
+            self.zoom(active_pane_id, client_id);
         }
-
-        self.tiled_panes.focus_pane_right_fullscreen(client_id);
+        Ok(())
+    }
+    pub fn is_zoomed(&self) -> bool {
+        self.zoom_state.is_some()
+    }
+    fn zoom(&mut self, pane_id: PaneId, client_id: ClientId) {
+        let floating_panes_were_visible = self.floating_panes.panes_are_visible();
+        self.zoom_state = Some(ZoomState {
+            pane_id,
+            display_area: *self.display_area.borrow(),
+            tiled_pane_geoms: self
+                .tiled_panes
+                .get_panes()
+                .map(|(pane_id, pane)| (*pane_id, pane.position_and_size()))
+                .collect(),
+            floating_pane_geoms: self
+                .floating_panes
+                .get_panes()
+                .map(|(pane_id, pane)| (*pane_id, pane.position_and_size()))
+                .collect(),
+            floating_panes_were_visible,
+        });
+        if floating_panes_were_visible {
+            self.hide_floating_panes();
+        }
+        if self.tiled_panes.fullscreen_is_active() {
+            self.tiled_panes.unset_fullscreen();
+        }
+        self.tiled_panes.focus_pane(pane_id, client_id);
+        self.tiled_panes.toggle_active_pane_fullscreen(client_id);
+        self.should_clear_display_before_rendering = true;
+        self.set_force_render();
+    }
+    fn unzoom(&mut self) -> Result<()> {
+        let err_context = || format!("failed to restore layout from before zoom");
+        let zoom_state = match self.zoom_state.take() {
+            Some(zoom_state) => zoom_state,
+            None => return Ok(()),
+        };
+        if self.tiled_panes.fullscreen_is_active() {
+            self.tiled_panes.unset_fullscreen();
+        }
+        // the stored geometries only apply to the same panes in a same sized tab, otherwise we
+        // keep the layout the tab was resized into
+        let display_area_is_unchanged = *self.display_area.borrow() == zoom_state.display_area;
+        let tiled_panes_are_unchanged = self.tiled_panes.pane_ids().count()
+            == zoom_state.tiled_pane_geoms.len()
+            && self
+                .tiled_panes
+                .pane_ids()
+                .all(|pane_id| zoom_state.tiled_pane_geoms.contains_key(pane_id));
+        if display_area_is_unchanged && tiled_panes_are_unchanged {
+            for (pane_id, geom) in &zoom_state.tiled_pane_geoms {
+                if let Some(pane) = self.tiled_panes.get_pane_mut(*pane_id) {
+                    if pane.position_and_size() != *geom {
+                        pane.set_geom(*geom);
+                        resize_pty!(pane, self.os_api, self.senders, self.character_cell_size)
+                            .with_context(err_context)?;
+                    }
+                }
+            }
+        }
+        if display_area_is_unchanged {
+            for (pane_id, geom) in &zoom_state.floating_pane_geoms {
+                if let Some(pane) = self.floating_panes.get_pane_mut(*pane_id) {
+                    if pane.position_and_size() != *geom {
+                        pane.set_geom(*geom);
+                        resize_pty!(pane, self.os_api, self.senders, self.character_cell_size)
+                            .with_context(err_context)?;
+                    }
+                }
+            }
+        }
+        if zoom_state.floating_panes_were_visible && !self.floating_panes.panes_are_visible() {
+            self.show_floating_panes();
+        }
+        self.should_clear_display_before_rendering = true;
+        self.set_force_render();
+        Ok(())
+    }
+    // unlike unzoom, this does not restore the layout from before the zoom, for when the tiled
+    // panes it was stored for are changing anyway
+    fn drop_zoom(&mut self) {
+        if let Some(zoom_state) = self.zoom_state.take() {
+            if zoom_state.floating_panes_were_visible && !self.floating_panes.panes_are_visible() {
+                self.show_floating_panes();
+            }
+        }
+    }
+    pub fn are_floating_panes_visible(&self) -> bool {
+        self.floating_panes.panes_are_visible()
+    }
+    pub fn toggle_pane_pinned(&mut self, pane_id: PaneId) {
+        // only floating panes can be pinned, for tiled panes this does nothing
+        self.floating_panes.toggle_pane_pinned(pane_id);
+    }
+    pub fn pane_is_pinned(&self, pane_id: &PaneId) -> bool {
+        self.floating_panes.pane_is_pinned(pane_id)
+    }
+    pub fn set_pane_read_only(&mut self, pane_id: PaneId, read_only: bool) {
+        if let Some(pane) = self
+            .tiled_panes
+            .get_pane_mut(pane_id)
+            .or_else(|| self.floating_panes.get_pane_mut(pane_id))
+            .or_else(|| {
+                self.suppressed_panes
+                    .values_mut()
+                    .find(|s_p| s_p.1.pid() == pane_id)
+                    .map(|s_p| &mut s_p.1)
+            })
+        {
+            pane.set_read_only(read_only);
+        }
+    }
+    pub fn stack_panes(&mut self, pane_ids: Vec<PaneId>, client_id: ClientId) -> Result<()> {
+        // the first pane's place in the layout is taken by the stack
+        let err_context = || format!("failed to stack panes {:?}", pane_ids);
+        let root_pane_id = *pane_ids.first().with_context(err_context)?;
+        if let Some(pane_id) = pane_ids
+            .iter()
+            .find(|pane_id| !self.tiled_panes.panes_contain(pane_id))
+        {
+            return Err(anyhow!(
+                "Pane {:?} is not a tiled pane in this tab",
+                pane_id
+            ))
+            .with_context(err_context);
+        }
+        if self.tiled_panes.fullscreen_is_active() {
+            self.tiled_panes.unset_fullscreen();
+        }
+        self.tiled_panes
+            .stack_panes(root_pane_id, pane_ids.clone(), client_id)
+            .with_context(err_context)
+    }
+    pub fn cycle_stack(&mut self, direction: Direction, client_id: ClientId) {
+        let search_backwards = match direction {
+            Direction::Left | Direction::Up => true,
+            Direction::Right | Direction::Down => false,
+        };
+        self.tiled_panes.cycle_stack(search_backwards, client_id);
+    }
+    pub fn focus_pane_left_fullscreen(&mut self, client_id: ClientId) {
+        if !self.is_fullscreen_active() {
+            return;
+        }
+
+        self.tiled_panes.focus_pane_left_fullscreen(client_id);
+    }
+    pub fn focus_pane_right_fullscreen(&mut self, client_id: ClientId) {
+        if !self.is_fullscreen_active() {
+            return;
+        }
+
+        self.tiled_panes.focus_pane_right_fullscreen(client_id);
     }
     pub fn focus_pane_up_fullscreen(&mut self, client_id: ClientId) {
         if !self.is_fullscreen_active() {
@@ -1861,18 +2570,74 @@ impl Tab {
         self.tiled_panes.set_force_render();
         self.floating_panes.set_force_render();
     }
+    /// Blends the contents of the panes that are not focused towards the background by `amount`
+    /// (between 0.0 and 1.0) when rendering them, or stops doing so if `None`
+    pub fn set_focus_dimming(&mut self, focus_dimming: Option<f64>) {
+        self.focus_dimming = focus_dimming;
+        self.last_dimmed_focus.clear();
+        self.set_force_render();
+    }
+    #[cfg(test)]
+    pub fn focus_dimming(&self) -> Option<f64> {
+        self.focus_dimming
+    }
+    /// Draws the frames of the panes with the characters of `pane_frame_style`, or with thin
+    /// lines if `None`
+    pub fn set_pane_frame_style(&mut self, pane_frame_style: Option<PaneFrameStyle>) {
+        self.pane_frame_style = pane_frame_style;
+        self.set_force_render();
+    }
+    /// Renders the panes for this client with `theme` instead of the session's palette
+    pub fn set_client_theme(&mut self, client_id: ClientId, theme: Palette) {
+        self.client_themes.insert(client_id, theme);
+        self.set_force_render();
+    }
+    pub fn force_full_render(&mut self) {
+        self.should_clear_display_before_rendering = true;
+        self.set_force_render();
+    }
     pub fn is_sync_panes_active(&self) -> bool {
         self.synchronize_is_active
     }
     pub fn toggle_sync_panes_is_active(&mut self) {
         self.synchronize_is_active = !self.synchronize_is_active;
     }
+    pub fn set_sync_panes_is_active(&mut self, is_active: bool) {
+        self.synchronize_is_active = is_active;
+    }
+    pub fn set_synchronized_scroll_is_active(&mut self, is_active: bool) {
+        self.synchronized_scroll_is_active = is_active;
+    }
+    pub fn toggle_ruler(&mut self, client_id: ClientId) {
+        if self.ruler_clients.remove(&client_id).is_none() {
+            self.ruler_clients.insert(client_id, None);
+        }
+        // the ruler is drawn on top of the pane contents, so we need to re-render them in order
+        // to remove it
+        self.set_force_render();
+    }
+    pub fn toggle_pane_show_invisibles(&mut self, pane_id: PaneId) -> Result<()> {
+        let pane = self
+            .tiled_panes
+            .get_pane_mut(pane_id)
+            .or_else(|| self.floating_panes.get_pane_mut(pane_id))
+            .or_else(|| {
+                self.suppressed_panes
+                    .values_mut()
+                    .map(|(_, pane)| pane)
+                    .find(|pane| pane.pid() == pane_id)
+            })
+            .ok_or_else(|| anyhow!("pane {pane_id:?} does not exist"))
+            .with_context(|| format!("failed to toggle showing invisibles in pane {pane_id:?}"))?;
+        pane.toggle_show_invisibles();
+        Ok(())
+    }
     pub fn mark_active_pane_for_rerender(&mut self, client_id: ClientId) {
         if let Some(active_pane) = self.get_active_pane_mut(client_id) {
             active_pane.set_should_render(true);
         }
     }
-    fn update_active_panes_in_pty_thread(&self) -> Result<()> {
+    pub fn update_active_panes_in_pty_thread(&self) -> Result<()> {
         // this is a bit hacky and we should ideally not keep this state in two different places at
         // some point
         let connected_clients: Vec<ClientId> =
@@ -1888,6 +2653,18 @@ impl Tab {
         Ok(())
     }
 
+    /// The geometry of the pane (including its frame) if it is visible on screen
+    pub fn rendered_pane_geom(&self, pane_id: PaneId) -> Option<PaneGeom> {
+        let floating_pane = if self.floating_panes.panes_are_visible() {
+            self.floating_panes.get_pane(pane_id)
+        } else {
+            None
+        };
+        floating_pane
+            .or_else(|| self.tiled_panes.get_pane(pane_id))
+            .map(|pane| pane.position_and_size())
+    }
+
     pub fn render(&mut self, output: &mut Output) -> Result<()> {
         let err_context = || "failed to render tab".to_string();
 
@@ -1899,6 +2676,22 @@ impl Tab {
         self.update_active_panes_in_pty_thread()
             .with_context(err_context)?;
 
+        if self.ruler_areas_changed() {
+            // clear the ruler from its previous location (eg. if focus moved)
+            self.set_force_render();
+        }
+        if self.focus_dimming.is_some() {
+            let focus: HashMap<ClientId, PaneId> = connected_clients
+                .iter()
+                .filter_map(|c| self.get_active_pane_id(*c).map(|pane_id| (*c, pane_id)))
+                .collect();
+            if focus != self.last_dimmed_focus {
+                // dim the previously focused panes and restore the newly focused ones
+                self.set_force_render();
+                self.last_dimmed_focus = focus;
+            }
+        }
+
         let floating_panes_stack = self.floating_panes.stack();
         output.add_clients(
             &connected_clients,
@@ -1907,13 +2700,25 @@ impl Tab {
         );
 
         self.tiled_panes
-            .render(output, self.floating_panes.panes_are_visible())
+            .render(
+                output,
+                self.floating_panes.panes_are_visible(),
+                self.focus_dimming,
+                &self.client_themes,
+                self.pane_frame_style,
+            )
             .with_context(err_context)?;
         if self.floating_panes.panes_are_visible() && self.floating_panes.has_active_panes() {
             self.floating_panes
-                .render(output)
+                .render(
+                    output,
+                    self.focus_dimming,
+                    &self.client_themes,
+                    self.pane_frame_style,
+                )
                 .with_context(err_context)?;
         }
+        self.render_ruler(output).with_context(err_context)?;
 
         self.render_cursor(output);
         if output.has_rendered_assets() {
@@ -1940,6 +2745,43 @@ impl Tab {
             self.should_clear_display_before_rendering = false;
         }
     }
+    fn ruler_area(&self, client_id: ClientId) -> Option<(usize, usize, usize, usize)> {
+        self.get_active_pane(client_id).map(|active_pane| {
+            (
+                active_pane.get_content_x(),
+                active_pane.get_content_y(),
+                active_pane.get_content_columns(),
+                active_pane.get_content_rows(),
+            )
+        })
+    }
+    fn ruler_areas_changed(&self) -> bool {
+        self.ruler_clients
+            .iter()
+            .any(|(client_id, last_rendered_area)| {
+                last_rendered_area.is_some() && *last_rendered_area != self.ruler_area(*client_id)
+            })
+    }
+    fn render_ruler(&mut self, output: &mut Output) -> Result<()> {
+        // the ruler is drawn above all floating panes
+        let ruler_z_index = Some(self.floating_panes.visible_panes_count() + 1);
+        let ruler_color = self.style.colors.orange;
+        let ruler_client_ids: Vec<ClientId> = self.ruler_clients.keys().copied().collect();
+        for client_id in ruler_client_ids {
+            let ruler_area = self.ruler_area(client_id);
+            if let Some((x, y, columns, rows)) = ruler_area {
+                output
+                    .add_character_chunks_to_client(
+                        client_id,
+                        ruler_character_chunks(x, y, columns, rows, ruler_color),
+                        ruler_z_index,
+                    )
+                    .with_context(|| format!("failed to render ruler for client {client_id}"))?;
+            }
+            self.ruler_clients.insert(client_id, ruler_area);
+        }
+        Ok(())
+    }
     fn render_cursor(&mut self, output: &mut Output) {
         let connected_clients: Vec<ClientId> =
             { self.connected_clients.borrow().iter().copied().collect() };
@@ -2124,6 +2966,82 @@ impl Tab {
         Ok(())
     }
 
+    /// Resizes the pane to `size` cells (including its frame), returning the size it actually
+    /// ended up with. Tiled panes get as close as their neighbors allow, floating panes are only
+    /// limited by the size of the tab.
+    pub fn resize_pane_to(&mut self, pane_id: PaneId, size: Size) -> Result<Size> {
+        let err_context = || format!("failed to resize pane {pane_id:?} to {size:?}");
+
+        let pane = if self.floating_panes.panes_contain(&pane_id) {
+            self.swap_layouts.set_is_floating_damaged();
+            self.floating_panes
+                .resize_pane_to(pane_id, size)
+                .with_context(err_context)?;
+            self.set_force_render();
+            self.floating_panes.get_pane(pane_id)
+        } else if self.tiled_panes.panes_contain(&pane_id) {
+            self.swap_layouts.set_is_tiled_damaged();
+            self.tiled_panes
+                .resize_pane_to(pane_id, size)
+                .with_context(err_context)?;
+            self.tiled_panes.get_pane(pane_id)
+        } else {
+            None
+        };
+        pane.map(|pane| Size {
+            rows: pane.rows(),
+            cols: pane.cols(),
+        })
+        .ok_or_else(|| anyhow!("pane {pane_id:?} does not exist"))
+        .with_context(err_context)
+    }
+
+    /// Resizes the focused pane to a preset part of the tab along its dominant axis: the width if
+    /// it shares the tab's width with other panes, otherwise the height. Its siblings take up the
+    /// rest, as far as their minimum sizes allow.
+    pub fn resize_active_pane_to_preset(
+        &mut self,
+        client_id: ClientId,
+        preset: ResizePreset,
+    ) -> Result<()> {
+        let err_context = || format!("failed to resize pane of client {client_id} to {preset:?}");
+
+        let viewport = *self.viewport.borrow();
+        let (pane_id, pane_size) = match self.get_active_pane_id(client_id).and_then(|pane_id| {
+            self.floating_panes
+                .get_pane(pane_id)
+                .or_else(|| self.tiled_panes.get_pane(pane_id))
+                .map(|pane| {
+                    let size = Size {
+                        rows: pane.rows(),
+                        cols: pane.cols(),
+                    };
+                    (pane_id, size)
+                })
+        }) {
+            Some(active_pane) => active_pane,
+            None => return Ok(()),
+        };
+        let size = if pane_size.cols < viewport.cols {
+            Size {
+                cols: preset.portion_of(viewport.cols),
+                ..pane_size
+            }
+        } else if pane_size.rows < viewport.rows {
+            Size {
+                rows: preset.portion_of(viewport.rows),
+                ..pane_size
+            }
+        } else {
+            // the pane takes up the whole tab, there's no one to share it with
+            return Ok(());
+        };
+        self.swap_layouts.set_is_tiled_damaged();
+        self.resize_pane_to(pane_id, size)
+            .map(|_| ())
+            .with_context(err_context)
+    }
+
     fn set_pane_active_at(&mut self, pane_id: PaneId) {
         if let Some(pane) = self.tiled_panes.get_pane_mut(pane_id) {
             pane.set_active_at(Instant::now());
@@ -2151,6 +3069,46 @@ impl Tab {
         }
         self.tiled_panes.focus_previous_pane(client_id);
     }
+    /// Focuses the pane that follows the focused one in reading order (left-to-right, then
+    /// top-to-bottom), wrapping around to the first pane
+    pub fn focus_next_pane_spatial(&mut self, client_id: ClientId) {
+        if !self.has_selectable_panes() {
+            return;
+        }
+        if self.tiled_panes.fullscreen_is_active() {
+            self.switch_next_pane_fullscreen(client_id);
+            return;
+        }
+        let floating_panes_are_visible = self.floating_panes.panes_are_visible();
+        let pane_geoms: Vec<(PaneId, PaneGeom)> = if floating_panes_are_visible {
+            self.get_selectable_floating_panes()
+                .map(|(pane_id, p)| (*pane_id, p.position_and_size()))
+                .collect()
+        } else {
+            self.get_selectable_tiled_panes()
+                .map(|(pane_id, p)| (*pane_id, p.position_and_size()))
+                .collect()
+        };
+        let panes_in_reading_order = panes_in_reading_order(pane_geoms);
+        let next_pane_id = self
+            .get_active_pane_id(client_id)
+            .and_then(|active_pane_id| {
+                panes_in_reading_order
+                    .iter()
+                    .position(|pane_id| *pane_id == active_pane_id)
+            })
+            .and_then(|active_pane_position| panes_in_reading_order.get(active_pane_position + 1))
+            .or_else(|| panes_in_reading_order.first())
+            .copied();
+        if let Some(next_pane_id) = next_pane_id {
+            if floating_panes_are_visible {
+                self.floating_panes.focus_pane(next_pane_id, client_id);
+            } else {
+                self.tiled_panes.focus_pane(next_pane_id, client_id);
+                self.set_pane_active_at(next_pane_id);
+            }
+        }
+    }
     pub fn focus_pane_on_edge(&mut self, direction: Direction, client_id: ClientId) {
         if self.floating_panes.panes_are_visible() {
             self.floating_panes.focus_pane_on_edge(direction, client_id);
@@ -2366,6 +3324,50 @@ impl Tab {
             .copied()
             .collect()
     }
+    /// The number of lines the mouse wheel scrolls this pane by, `None` to use the default
+    pub fn set_pane_scroll_lines(
+        &mut self,
+        pane_id: PaneId,
+        scroll_lines: Option<ScrollLines>,
+    ) -> Result<()> {
+        match self.get_pane_with_id_mut(pane_id) {
+            Some(pane) => {
+                pane.set_scroll_lines(scroll_lines);
+                Ok(())
+            },
+            None => Err(anyhow!("pane {pane_id:?} does not exist"))
+                .with_context(|| format!("failed to set scroll lines of pane {pane_id:?}")),
+        }
+    }
+    /// Resizing panes or the tab shrinks other panes rather than taking this pane below
+    /// `min_size`, as long as there's room for it
+    pub fn set_pane_min_size(&mut self, pane_id: PaneId, min_size: Size) -> Result<()> {
+        if self.tiled_panes.panes_contain(&pane_id) {
+            self.tiled_panes.set_pane_min_size(pane_id, min_size);
+        } else if let Some(pane) = self.floating_panes.get_pane_mut(pane_id).or_else(|| {
+            self.suppressed_panes
+                .values_mut()
+                .map(|(_, pane)| pane)
+                .find(|pane| pane.pid() == pane_id)
+        }) {
+            // this only comes into play once the pane is tiled
+            pane.set_min_size(Some(min_size));
+        } else {
+            return Err(anyhow!("pane {pane_id:?} does not exist"))
+                .with_context(|| format!("failed to set minimum size of pane {pane_id:?}"));
+        }
+        Ok(())
+    }
+    /// Sizes the tiled panes in proportion to the size their content prefers, see
+    /// [`TiledPanes::resize_to_content`]
+    pub fn resize_tiled_panes_to_content(&mut self) {
+        if self.tiled_panes.fullscreen_is_active() {
+            self.tiled_panes.unset_fullscreen();
+        }
+        self.tiled_panes.resize_to_content();
+        self.swap_layouts.set_is_tiled_damaged();
+        self.set_force_render();
+    }
     pub fn set_pane_selectable(&mut self, id: PaneId, selectable: bool) {
         if self.is_pending {
             self.pending_instructions
@@ -2441,6 +3443,7 @@ impl Tab {
             if self.tiled_panes.fullscreen_is_active() {
                 self.tiled_panes.unset_fullscreen();
             }
+            self.drop_zoom();
             let closed_pane = self.tiled_panes.remove_pane(id);
             self.set_force_render();
             if self.auto_layout && !self.swap_layouts.is_tiled_damaged() {
@@ -2478,6 +3481,7 @@ impl Tab {
             if self.tiled_panes.fullscreen_is_active() {
                 self.tiled_panes.unset_fullscreen();
             }
+            self.drop_zoom();
             let closed_pane = self.tiled_panes.remove_pane(id);
             self.set_force_render();
             if self.auto_layout && !self.swap_layouts.is_tiled_damaged() {
@@ -2566,26 +3570,68 @@ impl Tab {
                 )
             })
     }
-    pub fn close_focused_pane(&mut self, client_id: ClientId) -> Result<()> {
+    /// Closes the pane focused by the client, returning it
+    pub fn close_focused_pane(&mut self, client_id: ClientId) -> Result<Option<Box<dyn Pane>>> {
         let err_context = |pane_id| {
             format!("failed to close focused pane (ID {pane_id:?}) for client {client_id}")
         };
 
         if self.floating_panes.panes_are_visible() {
             if let Some(active_floating_pane_id) = self.floating_panes.active_pane_id(client_id) {
-                self.close_pane(active_floating_pane_id, false, Some(client_id));
+                let closed_pane = self.close_pane(active_floating_pane_id, false, Some(client_id));
                 self.senders
                     .send_to_pty(PtyInstruction::ClosePane(active_floating_pane_id))
                     .with_context(|| err_context(active_floating_pane_id))?;
-                return Ok(());
+                return Ok(closed_pane);
             }
         }
         if let Some(active_pane_id) = self.tiled_panes.get_active_pane_id(client_id) {
-            self.close_pane(active_pane_id, false, Some(client_id));
+            let closed_pane = self.close_pane(active_pane_id, false, Some(client_id));
             self.senders
                 .send_to_pty(PtyInstruction::ClosePane(active_pane_id))
                 .with_context(|| err_context(active_pane_id))?;
+            return Ok(closed_pane);
         }
+        Ok(None)
+    }
+    pub fn merge_panes(&mut self, keep_pane_id: PaneId, absorb_pane_id: PaneId) -> Result<()> {
+        let err_context =
+            || format!("failed to merge pane {absorb_pane_id:?} into pane {keep_pane_id:?}");
+        if keep_pane_id == absorb_pane_id
+            || !self.tiled_panes.panes_contain(&keep_pane_id)
+            || !self.tiled_panes.panes_contain(&absorb_pane_id)
+        {
+            return Err(anyhow!("only two different tiled panes can be merged"))
+                .with_context(err_context);
+        }
+        if !self
+            .tiled_panes
+            .can_merge_pane_into(absorb_pane_id, keep_pane_id)
+        {
+            // checked before leaving fullscreen, so that a merge that cannot happen changes nothing
+            return Err(anyhow!("panes are not directly adjacent")).with_context(err_context);
+        }
+        if self.tiled_panes.fullscreen_is_active() {
+            self.tiled_panes.unset_fullscreen();
+        }
+        self.tiled_panes
+            .merge_pane_into(absorb_pane_id, keep_pane_id)
+            .ok_or_else(|| anyhow!("panes are not directly adjacent"))
+            .with_context(err_context)?;
+        self.senders
+            .send_to_pty(PtyInstruction::ClosePane(absorb_pane_id))
+            .with_context(err_context)?;
+        self.senders
+            .send_to_plugin(PluginInstruction::Update(vec![(
+                None,
+                None,
+                Event::PaneClosed(absorb_pane_id, None),
+            )]))
+            .with_context(err_context)
+            .non_fatal();
+        // the merge is not part of the swap layout, so we do not relayout as when closing a pane
+        self.swap_layouts.set_is_tiled_damaged();
+        self.set_force_render();
         Ok(())
     }
     pub fn clear_active_terminal_screen(&mut self, client_id: ClientId) -> Result<()> {
@@ -2637,6 +3683,9 @@ impl Tab {
         if let Some(active_pane) = self.get_active_pane_or_floating_pane_mut(client_id) {
             active_pane.scroll_up(1, client_id);
         }
+        let active_pane_id = self.get_active_pane_id(client_id);
+        self.scroll_synchronized_panes(active_pane_id, |pane| pane.scroll_up(1, client_id))
+            .non_fatal();
     }
 
     pub fn scroll_active_terminal_down(&mut self, client_id: ClientId) -> Result<()> {
@@ -2651,7 +3700,9 @@ impl Tab {
                 }
             }
         }
-        Ok(())
+        let active_pane_id = self.get_active_pane_id(client_id);
+        self.scroll_synchronized_panes(active_pane_id, |pane| pane.scroll_down(1, client_id))
+            .with_context(err_context)
     }
 
     pub fn scroll_active_terminal_up_page(&mut self, client_id: ClientId) {
@@ -2660,6 +3711,12 @@ impl Tab {
             let scroll_rows = active_pane.rows().max(1) - 1;
             active_pane.scroll_up(scroll_rows, client_id);
         }
+        let active_pane_id = self.get_active_pane_id(client_id);
+        self.scroll_synchronized_panes(active_pane_id, |pane| {
+            let scroll_rows = pane.rows().max(1) - 1;
+            pane.scroll_up(scroll_rows, client_id);
+        })
+        .non_fatal();
     }
 
     pub fn scroll_active_terminal_down_page(&mut self, client_id: ClientId) -> Result<()> {
@@ -2676,7 +3733,12 @@ impl Tab {
                 }
             }
         }
-        Ok(())
+        let active_pane_id = self.get_active_pane_id(client_id);
+        self.scroll_synchronized_panes(active_pane_id, |pane| {
+            let scroll_rows = pane.get_content_rows();
+            pane.scroll_down(scroll_rows, client_id);
+        })
+        .with_context(err_context)
     }
 
     pub fn scroll_active_terminal_up_half_page(&mut self, client_id: ClientId) {
@@ -2685,6 +3747,12 @@ impl Tab {
             let scroll_rows = (active_pane.rows().max(1) - 1) / 2;
             active_pane.scroll_up(scroll_rows, client_id);
         }
+        let active_pane_id = self.get_active_pane_id(client_id);
+        self.scroll_synchronized_panes(active_pane_id, |pane| {
+            let scroll_rows = (pane.rows().max(1) - 1) / 2;
+            pane.scroll_up(scroll_rows, client_id);
+        })
+        .non_fatal();
     }
 
     pub fn scroll_active_terminal_down_half_page(&mut self, client_id: ClientId) -> Result<()> {
@@ -2701,7 +3769,12 @@ impl Tab {
                 }
             }
         }
-        Ok(())
+        let active_pane_id = self.get_active_pane_id(client_id);
+        self.scroll_synchronized_panes(active_pane_id, |pane| {
+            let scroll_rows = (pane.rows().max(1) - 1) / 2;
+            pane.scroll_down(scroll_rows, client_id);
+        })
+        .with_context(err_context)
     }
 
     pub fn scroll_active_terminal_to_bottom(&mut self, client_id: ClientId) -> Result<()> {
@@ -2717,17 +3790,27 @@ impl Tab {
                 }
             }
         }
-        Ok(())
+        let active_pane_id = self.get_active_pane_id(client_id);
+        self.scroll_synchronized_panes(active_pane_id, |pane| pane.clear_scroll())
+            .with_context(err_context)
     }
 
     pub fn scroll_active_terminal_to_top(&mut self, client_id: ClientId) -> Result<()> {
-        if let Some(active_pane) = self.get_active_pane_or_floating_pane_mut(client_id) {
-            active_pane.clear_scroll();
-            if let Some(size) = active_pane.get_line_number() {
-                active_pane.scroll_up(size, client_id);
+        let err_context =
+            || format!("failed to scroll to top in active pane for client {client_id}");
+
+        let scroll_to_top = |pane: &mut Box<dyn Pane>| {
+            pane.clear_scroll();
+            if let Some(size) = pane.get_line_number() {
+                pane.scroll_up(size, client_id);
             }
+        };
+        if let Some(active_pane) = self.get_active_pane_or_floating_pane_mut(client_id) {
+            scroll_to_top(active_pane);
         }
-        Ok(())
+        let active_pane_id = self.get_active_pane_id(client_id);
+        self.scroll_synchronized_panes(active_pane_id, scroll_to_top)
+            .with_context(err_context)
     }
 
     pub fn clear_active_terminal_scroll(&mut self, client_id: ClientId) -> Result<()> {
@@ -2747,10 +3830,12 @@ impl Tab {
         Ok(())
     }
 
+    /// Scrolls the pane at `point` by its own scroll lines, or by `default_scroll_lines` if it has
+    /// none
     pub fn handle_scrollwheel_up(
         &mut self,
         point: &Position,
-        lines: usize,
+        default_scroll_lines: ScrollLines,
         client_id: ClientId,
     ) -> Result<()> {
         let err_context = || {
@@ -2759,10 +3844,19 @@ impl Tab {
 
         if let Some(pane) = self.get_pane_at(point, false).with_context(err_context)? {
             let relative_position = pane.relative_position(point);
-            if let Some(mouse_event) = pane.mouse_scroll_up(&relative_position) {
+            let lines = pane
+                .scroll_lines()
+                .unwrap_or(default_scroll_lines)
+                .lines_for_pane(pane.get_content_rows());
+            // read only panes are scrolled rather than sent the scrollwheel as input
+            let is_read_only = pane.read_only();
+            if let Some(mouse_event) = pane
+                .mouse_scroll_up(&relative_position)
+                .filter(|_| !is_read_only)
+            {
                 self.write_to_terminal_at(mouse_event.into_bytes(), point, client_id)
                     .with_context(err_context)?;
-            } else if pane.is_alternate_mode_active() {
+            } else if pane.is_alternate_mode_active() && !is_read_only {
                 // faux scrolling, send UP n times
                 // do n separate writes to make sure the sequence gets adjusted for cursor keys mode
                 for _ in 0..lines {
@@ -2771,15 +3865,26 @@ impl Tab {
                 }
             } else {
                 pane.scroll_up(lines, client_id);
+                let pane_id = pane.pid();
+                self.scroll_synchronized_panes(Some(pane_id), |pane| {
+                    let lines = pane
+                        .scroll_lines()
+                        .unwrap_or(default_scroll_lines)
+                        .lines_for_pane(pane.get_content_rows());
+                    pane.scroll_up(lines, client_id);
+                })
+                .with_context(err_context)?;
             }
         }
         Ok(())
     }
 
+    /// Scrolls the pane at `point` by its own scroll lines, or by `default_scroll_lines` if it has
+    /// none
     pub fn handle_scrollwheel_down(
         &mut self,
         point: &Position,
-        lines: usize,
+        default_scroll_lines: ScrollLines,
         client_id: ClientId,
     ) -> Result<()> {
         let err_context = || {
@@ -2790,10 +3895,19 @@ impl Tab {
 
         if let Some(pane) = self.get_pane_at(point, false).with_context(err_context)? {
             let relative_position = pane.relative_position(point);
-            if let Some(mouse_event) = pane.mouse_scroll_down(&relative_position) {
+            let lines = pane
+                .scroll_lines()
+                .unwrap_or(default_scroll_lines)
+                .lines_for_pane(pane.get_content_rows());
+            // read only panes are scrolled rather than sent the scrollwheel as input
+            let is_read_only = pane.read_only();
+            if let Some(mouse_event) = pane
+                .mouse_scroll_down(&relative_position)
+                .filter(|_| !is_read_only)
+            {
                 self.write_to_terminal_at(mouse_event.into_bytes(), point, client_id)
                     .with_context(err_context)?;
-            } else if pane.is_alternate_mode_active() {
+            } else if pane.is_alternate_mode_active() && !is_read_only {
                 // faux scrolling, send DOWN n times
                 // do n separate writes to make sure the sequence gets adjusted for cursor keys mode
                 for _ in 0..lines {
@@ -2802,17 +3916,60 @@ impl Tab {
                 }
             } else {
                 pane.scroll_down(lines, client_id);
+                let pane_id = pane.pid();
                 if !pane.is_scrolled() {
-                    if let PaneId::Terminal(pid) = pane.pid() {
+                    if let PaneId::Terminal(pid) = pane_id {
                         self.process_pending_vte_events(pid)
                             .with_context(err_context)?;
                     }
                 }
+                self.scroll_synchronized_panes(Some(pane_id), |pane| {
+                    let lines = pane
+                        .scroll_lines()
+                        .unwrap_or(default_scroll_lines)
+                        .lines_for_pane(pane.get_content_rows());
+                    pane.scroll_down(lines, client_id);
+                })
+                .with_context(err_context)?;
             }
         }
         Ok(())
     }
 
+    /// When scrolling is synchronized, scrolls the terminal panes of the tab other than
+    /// `scrolled_pane_id` along with it. `scroll` works out how far to scroll each pane from its
+    /// own size, so that scrolling by pages moves every pane by its own page, and panes without
+    /// enough scrollback stop at its ends.
+    fn scroll_synchronized_panes(
+        &mut self,
+        scrolled_pane_id: Option<PaneId>,
+        scroll: impl Fn(&mut Box<dyn Pane>),
+    ) -> Result<()> {
+        if !self.synchronized_scroll_is_active {
+            return Ok(());
+        }
+        let mut caught_up_terminal_ids = vec![];
+        for pane_id in self.get_all_pane_ids() {
+            if Some(pane_id) == scrolled_pane_id {
+                continue;
+            }
+            if let PaneId::Terminal(terminal_id) = pane_id {
+                if let Some(pane) = self.get_pane_with_id_mut(pane_id) {
+                    scroll(pane);
+                    if !pane.is_scrolled() {
+                        caught_up_terminal_ids.push(terminal_id);
+                    }
+                }
+            }
+        }
+        for terminal_id in caught_up_terminal_ids {
+            // output that arrived while the pane was scrolled back
+            self.process_pending_vte_events(terminal_id)
+                .context("failed to scroll synchronized panes")?;
+        }
+        Ok(())
+    }
+
     fn get_pane_at(
         &mut self,
         point: &Position,
@@ -2903,8 +4060,11 @@ impl Tab {
                 }
             } else {
                 pane.start_selection(&relative_position, client_id);
-                if let PaneId::Terminal(_) = pane.pid() {
+                let pane_id = pane.pid();
+                let selection_bounds = pane.get_selection_bounds();
+                if let PaneId::Terminal(_) = pane_id {
                     self.selecting_with_mouse = true;
+                    self.report_selection_change(pane_id, selection_bounds);
                 }
             }
         };
@@ -3097,9 +4257,16 @@ impl Tab {
                     .with_context(err_context)?;
             } else {
                 let relative_position = active_pane.relative_position(position);
-                if let PaneId::Terminal(_) = active_pane.pid() {
+                let pane_id = active_pane.pid();
+                if let PaneId::Terminal(_) = pane_id {
                     if selecting {
                         active_pane.end_selection(&relative_position, client_id);
+                        let selection_bounds = active_pane.get_selection_bounds();
+                        // a click rather than a selection
+                        let clicked_hyperlink = match selection_bounds {
+                            None => active_pane.hyperlink_at(&relative_position),
+                            Some(_) => None,
+                        };
                         if copy_on_release {
                             let selected_text = active_pane.get_selected_text();
                             active_pane.reset_selection();
@@ -3109,6 +4276,13 @@ impl Tab {
                                     .with_context(err_context)?;
                             }
                         }
+                        self.report_selection_change(pane_id, selection_bounds);
+                        if copy_on_release {
+                            self.report_selection_change(pane_id, None);
+                        }
+                        if let Some(uri) = clicked_hyperlink {
+                            self.report_hyperlink_click(pane_id, uri);
+                        }
                     }
                 } else {
                     // notify the release event to a plugin pane, should be renamed
@@ -3178,6 +4352,9 @@ impl Tab {
                 }
             } else if selecting {
                 active_pane.update_selection(&relative_position, client_id);
+                let pane_id = active_pane.pid();
+                let selection_bounds = active_pane.get_selection_bounds();
+                self.report_selection_change(pane_id, selection_bounds);
                 return Ok(true); // we need to re-render in this case so the selection is updated
             }
         }
@@ -3272,32 +4449,68 @@ impl Tab {
         Ok(false) // we shouldn't even get here, but might as well not needlessly render if we do
     }
 
+    fn report_selection_change(
+        &mut self,
+        pane_id: PaneId,
+        selection_bounds: Option<SelectionBounds>,
+    ) {
+        // holding the mouse reports the same selection many times, plugins only need the changes
+        let selection = selection_bounds.map(|bounds| (pane_id, bounds));
+        if selection == self.reported_selection {
+            return;
+        }
+        self.reported_selection = selection;
+        self.senders
+            .send_to_plugin(PluginInstruction::Update(vec![(
+                None,
+                None,
+                Event::SelectionChanged(pane_id, selection_bounds),
+            )]))
+            .with_context(|| format!("failed to inform plugins about selection in {pane_id:?}"))
+            .non_fatal();
+    }
+
+    fn report_hyperlink_click(&self, pane_id: PaneId, uri: String) {
+        self.senders
+            .send_to_plugin(PluginInstruction::Update(vec![(
+                None,
+                None,
+                Event::HyperlinkClicked(pane_id, uri),
+            )]))
+            .with_context(|| format!("failed to inform plugins about hyperlink in {pane_id:?}"))
+            .non_fatal();
+    }
+
     pub fn copy_selection(&self, client_id: ClientId) -> Result<()> {
         let selected_text = self
             .get_active_pane(client_id)
-            .and_then(|p| p.get_selected_text());
+            .and_then(|p| p.get_selected_text())
+            .filter(|selected_text| !selected_text.is_empty());
         if let Some(selected_text) = selected_text {
             self.write_selection_to_clipboard(&selected_text)
                 .with_context(|| {
                     format!("failed to write selection to clipboard for client {client_id}")
                 })?;
-            self.senders
-                .send_to_plugin(PluginInstruction::Update(vec![(
-                    None,
-                    None,
-                    Event::CopyToClipboard(self.clipboard_provider.as_copy_destination()),
-                )]))
-                .with_context(|| {
-                    format!("failed to inform plugins about copy selection for client {client_id}")
-                })
-                .non_fatal();
         }
         Ok(())
     }
 
-    fn write_selection_to_clipboard(&self, selection: &str) -> Result<()> {
+    pub fn write_selection_to_clipboard(&self, selection: &str) -> Result<()> {
         let err_context = || format!("failed to write selection to clipboard: '{}'", selection);
 
+        if selection.is_empty() {
+            // there's nothing to copy, so there's nothing to confirm either
+            return Ok(());
+        }
+        // plugins are told about the copy before it is attempted, and then about how it went
+        self.senders
+            .send_to_plugin(PluginInstruction::Update(vec![(
+                None,
+                None,
+                Event::CopyToClipboard(self.clipboard_provider.as_copy_destination()),
+            )]))
+            .context("failed to notify plugins about new clipboard event")
+            .non_fatal();
         let mut output = Output::default();
         let connected_clients: HashSet<ClientId> =
             { self.connected_clients.borrow().iter().copied().collect() };
@@ -3314,15 +4527,11 @@ impl Tab {
                         self.senders
                             .send_to_server(ServerInstruction::Render(Some(serialized_output)))
                     })
-                    .and_then(|_| {
-                        Ok(Event::CopyToClipboard(
-                            self.clipboard_provider.as_copy_destination(),
-                        ))
-                    })
+                    .and_then(|_| Ok(Event::ClipboardCopySucceeded(selection.len())))
                     .with_context(err_context)?,
                 Err(err) => {
                     Err::<(), _>(err).with_context(err_context).non_fatal();
-                    Event::SystemClipboardFailure
+                    Event::SystemClipboardFailure(selection.len())
                 },
             };
         self.senders
@@ -3438,6 +4647,105 @@ impl Tab {
         self.should_clear_display_before_rendering = true;
         self.set_force_render();
     }
+    /// Adds or removes the frame of a single pane, its geometry stays the same and its content
+    /// gains or loses the rows and columns of the frame
+    pub fn toggle_pane_frame(&mut self, pane_id: PaneId) -> Result<()> {
+        let err_context = || format!("failed to toggle frame of pane {pane_id:?}");
+        if self.floating_panes.panes_contain(&pane_id) {
+            self.floating_panes
+                .toggle_pane_frame(pane_id)
+                .with_context(err_context)?;
+        } else if self.tiled_panes.panes_contain(&pane_id) {
+            self.tiled_panes.toggle_pane_frame(pane_id);
+        } else {
+            return Err(anyhow!("pane {pane_id:?} does not exist")).with_context(err_context);
+        }
+        self.should_clear_display_before_rendering = true;
+        self.set_force_render();
+        Ok(())
+    }
+    pub fn new_plugin_fed_pane(
+        &mut self,
+        terminal_id: u32,
+        pane_name: Option<String>,
+        plugin_id: u32,
+        client_id: ClientId,
+    ) -> Result<()> {
+        let err_context = || format!("failed to open pane fed by plugin {plugin_id}");
+        let pane_id = PaneId::Terminal(terminal_id);
+        self.new_pane(pane_id, pane_name, None, None, Some(client_id))
+            .with_context(err_context)?;
+        self.tiled_panes
+            .get_pane_mut(pane_id)
+            .or_else(|| self.floating_panes.get_pane_mut(pane_id))
+            .ok_or_else(|| anyhow!("pane {pane_id:?} was not created"))
+            .with_context(err_context)?
+            .set_fed_by_plugin(plugin_id);
+        Ok(())
+    }
+    /// Opens a pane for this client that mirrors the terminal pane `source_terminal_id`
+    pub fn new_pane_alias(
+        &mut self,
+        terminal_id: u32,
+        source_terminal_id: u32,
+        pane_title: String,
+        client_id: ClientId,
+    ) -> Result<()> {
+        let err_context = || format!("failed to open an alias of pane {source_terminal_id}");
+        let pane_id = PaneId::Terminal(terminal_id);
+        self.new_pane(pane_id, Some(pane_title), None, None, Some(client_id))
+            .with_context(err_context)?;
+        self.get_pane_with_id_mut(pane_id)
+            .ok_or_else(|| anyhow!("pane {pane_id:?} was not created"))
+            .with_context(err_context)?
+            .set_alias_of(source_terminal_id);
+        Ok(())
+    }
+    /// Gives the alias `alias_id` the viewport of the pane it mirrors to render
+    pub fn set_alias_view(&mut self, alias_id: PaneId, character_chunks: Vec<CharacterChunk>) {
+        if let Some(alias) = self.get_pane_with_id_mut(alias_id) {
+            alias.set_alias_view(character_chunks);
+        }
+    }
+    /// The aliases in this tab and the terminal panes they mirror
+    pub fn pane_aliases(&self) -> Vec<(PaneId, u32)> {
+        self.tiled_panes
+            .get_panes()
+            .chain(self.floating_panes.get_panes())
+            .chain(
+                self.suppressed_panes
+                    .iter()
+                    .map(|(pane_id, s_p)| (pane_id, &s_p.1)),
+            )
+            .filter_map(|(pane_id, pane)| Some((*pane_id, pane.alias_of()?)))
+            .collect()
+    }
+    pub fn write_to_pane_scrollback(
+        &mut self,
+        terminal_id: u32,
+        lines: Vec<String>,
+        plugin_id: u32,
+    ) -> Result<()> {
+        let err_context = || {
+            format!("failed to write to scrollback of pane {terminal_id} for plugin {plugin_id}")
+        };
+        let pane_id = PaneId::Terminal(terminal_id);
+        let fed_by_plugin = self
+            .tiled_panes
+            .get_pane_mut(pane_id)
+            .or_else(|| self.floating_panes.get_pane_mut(pane_id))
+            .or_else(|| self.suppressed_panes.get_mut(&pane_id).map(|p| &mut p.1))
+            .and_then(|pane| pane.fed_by_plugin());
+        if fed_by_plugin != Some(plugin_id) {
+            return Err(anyhow!("pane is not fed by this plugin")).with_context(err_context);
+        }
+        // the lines go through the same parser as pty output, so they are styled, scrolled and
+        // searched like the output of any other terminal
+        let mut bytes = lines.join("\r\n").into_bytes();
+        bytes.extend_from_slice(b"\r\n");
+        self.handle_pty_bytes(terminal_id, bytes)
+            .with_context(err_context)
+    }
     pub fn panes_to_hide_count(&self) -> usize {
         self.tiled_panes.panes_to_hide_count()
     }
@@ -3494,6 +4802,67 @@ impl Tab {
         }
     }
 
+    /// Highlights the matches of the needle in the viewport of the pane, returning them or `None`
+    /// if the pane cannot be searched
+    pub fn search_and_highlight_in_pane(
+        &mut self,
+        pane_id: PaneId,
+        needle: &str,
+        case_sensitive: bool,
+    ) -> Result<Option<PaneSearchMatches>> {
+        let pane = self
+            .get_pane_with_id_mut(pane_id)
+            .ok_or_else(|| anyhow!("pane {pane_id:?} does not exist"))
+            .with_context(|| format!("failed to search in pane {pane_id:?}"))?;
+        Ok(pane.search_and_highlight(needle, case_sensitive))
+    }
+
+    pub fn capture_pane_text(
+        &mut self,
+        pane_id: PaneId,
+        include_scrollback: bool,
+    ) -> Result<String> {
+        let pane = self
+            .get_pane_with_id_mut(pane_id)
+            .ok_or_else(|| anyhow!("pane {pane_id:?} does not exist"))
+            .with_context(|| format!("failed to capture the text of pane {pane_id:?}"))?;
+        Ok(pane.plain_text(include_scrollback))
+    }
+
+    pub fn clear_search_in_pane(&mut self, pane_id: PaneId) -> Result<()> {
+        let pane = self
+            .get_pane_with_id_mut(pane_id)
+            .ok_or_else(|| anyhow!("pane {pane_id:?} does not exist"))
+            .with_context(|| format!("failed to clear search in pane {pane_id:?}"))?;
+        pane.clear_highlighted_search();
+        pane.set_should_render(true);
+        Ok(())
+    }
+
+    pub(crate) fn get_pane_with_id(&self, pane_id: PaneId) -> Option<&dyn Pane> {
+        self.tiled_panes
+            .get_pane(pane_id)
+            .or_else(|| self.floating_panes.get_pane(pane_id))
+            .or_else(|| {
+                self.suppressed_panes
+                    .values()
+                    .map(|(_, pane)| pane)
+                    .find(|pane| pane.pid() == pane_id)
+            })
+            .map(|pane| pane.as_ref())
+    }
+    fn get_pane_with_id_mut(&mut self, pane_id: PaneId) -> Option<&mut Box<dyn Pane>> {
+        self.tiled_panes
+            .get_pane_mut(pane_id)
+            .or_else(|| self.floating_panes.get_pane_mut(pane_id))
+            .or_else(|| {
+                self.suppressed_panes
+                    .values_mut()
+                    .map(|(_, pane)| pane)
+                    .find(|pane| pane.pid() == pane_id)
+            })
+    }
+
     pub fn is_pending(&self) -> bool {
         self.is_pending
     }
@@ -3517,6 +4886,21 @@ impl Tab {
             pane.add_red_pane_frame_color_override(error_text);
         }
     }
+    pub fn add_bell_pane_frame_color_override(&mut self, pane_id: PaneId) {
+        if let Some(pane) = self
+            .tiled_panes
+            .get_pane_mut(pane_id)
+            .or_else(|| self.floating_panes.get_pane_mut(pane_id))
+            .or_else(|| {
+                self.suppressed_panes
+                    .values_mut()
+                    .find(|s_p| s_p.1.pid() == pane_id)
+                    .map(|s_p| &mut s_p.1)
+            })
+        {
+            pane.add_bell_pane_frame_color_override();
+        }
+    }
     pub fn clear_pane_frame_color_override(&mut self, pane_id: PaneId) {
         if let Some(pane) = self
             .tiled_panes
@@ -3640,6 +5024,11 @@ impl Tab {
                 None => Ok(()),
             })
     }
+    /// Drops the pane suppressed by the pane with this id instead of bringing it back once that
+    /// pane is closed
+    pub fn drop_suppressed_pane(&mut self, pane_id: &PaneId) {
+        self.suppressed_panes.remove(pane_id);
+    }
     pub fn suppress_pane(&mut self, pane_id: PaneId, client_id: ClientId) {
         // this method places a pane in the suppressed pane with its own ID - this means we'll
         // not take it out of there when another pane is closed (eg. like happens with the
@@ -3725,6 +5114,128 @@ impl Tab {
         }
         Ok(())
     }
+    /// Adds a pane taken out of another tab, floating if it was floating or if there's no room
+    /// for it among the tiled panes. Returns the pane if there's no room for it at all.
+    pub fn dock_pane(
+        &mut self,
+        pane: Box<dyn Pane>,
+        pane_id: PaneId,
+        should_float: bool,
+    ) -> Result<Option<Box<dyn Pane>>> {
+        let err_context = || format!("failed to dock pane {pane_id:?}");
+        if !should_float && self.tiled_panes.has_room_for_new_pane() {
+            self.add_tiled_pane(pane, pane_id, None)
+                .with_context(err_context)?;
+        } else if self.floating_panes.find_room_for_new_pane().is_some() {
+            self.show_floating_panes();
+            self.add_floating_pane(pane, pane_id, None)
+                .with_context(err_context)?;
+        } else {
+            return Ok(Some(pane));
+        }
+        self.set_force_render();
+        Ok(None)
+    }
+    /// The position and size of each tiled pane, eg. to give them back with
+    /// `restore_tiled_pane_geoms`
+    pub fn tiled_pane_geoms(&self) -> Vec<(PaneId, PaneGeom)> {
+        self.get_tiled_panes()
+            .map(|(pane_id, pane)| (*pane_id, pane.position_and_size()))
+            .collect()
+    }
+    /// Gives the tiled panes back the positions and sizes they had, returns false without changing
+    /// anything if the panes or the space they take are no longer the same
+    pub fn restore_tiled_pane_geoms(
+        &mut self,
+        tiled_pane_geoms: &[(PaneId, PaneGeom)],
+    ) -> Result<bool> {
+        if !self.can_restore_tiled_pane_geoms(tiled_pane_geoms, None) {
+            return Ok(false);
+        }
+        self.set_tiled_pane_geoms(tiled_pane_geoms)?;
+        Ok(true)
+    }
+    /// Brings back a pane that was closed in this tab (eg. to undo closing it). If the tiled panes
+    /// are still those there were when it was closed, they get back the positions and sizes they
+    /// had then, otherwise the pane is added like a new one.
+    pub fn restore_closed_pane(
+        &mut self,
+        mut pane: Box<dyn Pane>,
+        is_floating: bool,
+        tiled_pane_geoms: &[(PaneId, PaneGeom)],
+        client_id: Option<ClientId>,
+    ) -> Result<()> {
+        let pane_id = pane.pid();
+        let err_context = || format!("failed to restore closed pane {pane_id:?}");
+        hold_closed_terminal_pane(&mut pane, &self.default_shell);
+        if is_floating {
+            self.show_floating_panes();
+            self.floating_panes.add_pane(pane_id, pane);
+            if let Some(client_id) = client_id {
+                self.floating_panes.focus_pane(pane_id, client_id);
+            }
+        } else if self.can_restore_tiled_pane_geoms(tiled_pane_geoms, Some(pane_id)) {
+            self.tiled_panes.add_pane_with_existing_geom(pane_id, pane);
+            self.set_tiled_pane_geoms(tiled_pane_geoms)
+                .with_context(err_context)?;
+            if let Some(client_id) = client_id {
+                self.tiled_panes.focus_pane(pane_id, client_id);
+            }
+        } else {
+            self.add_tiled_pane(pane, pane_id, client_id)
+                .with_context(err_context)?;
+        }
+        self.set_force_render();
+        Ok(())
+    }
+    /// Holds all the terminal panes of a tab that was brought back after being closed
+    pub fn hold_closed_terminal_panes(&mut self) {
+        for pane_id in self.get_all_pane_ids() {
+            if let Some(pane) = self
+                .tiled_panes
+                .get_pane_mut(pane_id)
+                .or_else(|| self.floating_panes.get_pane_mut(pane_id))
+            {
+                hold_closed_terminal_pane(pane, &self.default_shell);
+            }
+        }
+    }
+    fn can_restore_tiled_pane_geoms(
+        &self,
+        tiled_pane_geoms: &[(PaneId, PaneGeom)],
+        restored_pane_id: Option<PaneId>,
+    ) -> bool {
+        let area = |geom: &PaneGeom| geom.rows.as_usize() * geom.cols.as_usize();
+        let mut pane_ids: HashSet<PaneId> = self.tiled_panes.pane_ids().copied().collect();
+        pane_ids.extend(restored_pane_id);
+        let current_area: usize = self
+            .get_tiled_panes()
+            .map(|(_, pane)| area(&pane.position_and_size()))
+            .sum();
+        let restored_area: usize = tiled_pane_geoms.iter().map(|(_, geom)| area(geom)).sum();
+        let restored_pane_ids: HashSet<PaneId> = tiled_pane_geoms
+            .iter()
+            .map(|(pane_id, _)| *pane_id)
+            .collect();
+        pane_ids == restored_pane_ids && current_area == restored_area
+    }
+    fn set_tiled_pane_geoms(&mut self, tiled_pane_geoms: &[(PaneId, PaneGeom)]) -> Result<()> {
+        let err_context = || format!("failed to set tiled pane geoms");
+        if self.tiled_panes.fullscreen_is_active() {
+            self.tiled_panes.unset_fullscreen();
+        }
+        for (pane_id, geom) in tiled_pane_geoms {
+            if let Some(pane) = self.tiled_panes.get_pane_mut(*pane_id) {
+                pane.set_geom(*geom);
+                resize_pty!(pane, self.os_api, self.senders, self.character_cell_size)
+                    .with_context(err_context)?;
+            }
+        }
+        // the panes are no longer arranged by the swap layout
+        self.swap_layouts.set_is_tiled_damaged();
+        self.set_force_render();
+        Ok(())
+    }
     pub fn request_plugin_permissions(&mut self, pid: u32, permissions: Option<PluginPermission>) {
         if let Some(plugin_pane) = self
             .tiled_panes
@@ -3742,6 +5253,36 @@ impl Tab {
     }
 }
 
+/// Orders panes the way one would read them: by their top edge, with panes that start on the
+/// same line ordered from left to right
+fn panes_in_reading_order(mut pane_geoms: Vec<(PaneId, PaneGeom)>) -> Vec<PaneId> {
+    pane_geoms.sort_by_key(|(pane_id, geom)| (geom.y, geom.x, *pane_id));
+    pane_geoms
+        .into_iter()
+        .map(|(pane_id, _geom)| pane_id)
+        .collect()
+}
+
+/// The process that ran in a terminal pane brought back after being closed is gone, so the pane is
+/// held with its command (or the default shell) to start it again from the pane
+fn hold_closed_terminal_pane(pane: &mut Box<dyn Pane>, default_shell: &Option<PathBuf>) {
+    if let PaneId::Terminal(_) = pane.pid() {
+        let run_command = match pane.invoked_with() {
+            Some(Run::Command(run_command)) => run_command.clone(),
+            invoked_with => RunCommand {
+                command: default_shell.clone().unwrap_or_else(get_default_shell),
+                cwd: match invoked_with {
+                    Some(Run::Cwd(cwd)) => Some(cwd.clone()),
+                    _ => None,
+                },
+                ..Default::default()
+            },
+        };
+        let is_first_run = false;
+        pane.hold(None, is_first_run, run_command);
+    }
+}
+
 pub fn pane_info_for_pane(pane_id: &PaneId, pane: &Box<dyn Pane>) -> PaneInfo {
     let mut pane_info = PaneInfo::default();
     pane_info.pane_x = pane.x();
@@ -20,6 +20,7 @@ use zellij_utils::input::layout::{
     FloatingPaneLayout, Layout, PluginUserConfiguration, RunPluginLocation, SwapFloatingLayout,
     SwapTiledLayout, TiledPaneLayout,
 };
+use zellij_utils::input::options::{Bell, ScrollLines};
 use zellij_utils::input::plugins::PluginTag;
 use zellij_utils::ipc::IpcReceiverWithContext;
 use zellij_utils::pane_size::{Size, SizeInPixels};
@@ -223,6 +224,9 @@ fn create_new_tab(size: Size, default_mode: ModeInfo) -> Tab {
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
+    let bell = Bell::default();
     let mut tab = Tab::new(
         index,
         position,
@@ -248,6 +252,9 @@ fn create_new_tab(size: Size, default_mode: ModeInfo) -> Tab {
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
+        bell,
     );
     tab.apply_layout(
         TiledPaneLayout::default(),
@@ -300,6 +307,9 @@ fn create_new_tab_with_swap_layouts(
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
+    let bell = Bell::default();
     let mut tab = Tab::new(
         index,
         position,
@@ -325,6 +335,9 @@ fn create_new_tab_with_swap_layouts(
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
+        bell,
     );
     let (
         base_layout,
@@ -379,6 +392,9 @@ fn create_new_tab_with_os_api(
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
+    let bell = Bell::default();
     let mut tab = Tab::new(
         index,
         position,
@@ -404,6 +420,9 @@ fn create_new_tab_with_os_api(
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
+        bell,
     );
     tab.apply_layout(
         TiledPaneLayout::default(),
@@ -444,6 +463,9 @@ fn create_new_tab_with_layout(size: Size, default_mode: ModeInfo, layout: &str)
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
+    let bell = Bell::default();
     let mut tab = Tab::new(
         index,
         position,
@@ -469,6 +491,9 @@ fn create_new_tab_with_layout(size: Size, default_mode: ModeInfo, layout: &str)
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
+        bell,
     );
     let pane_ids = tab_layout
         .extract_run_instructions()
@@ -523,6 +548,9 @@ fn create_new_tab_with_mock_pty_writer(
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
+    let bell = Bell::default();
     let mut tab = Tab::new(
         index,
         position,
@@ -548,6 +576,9 @@ fn create_new_tab_with_mock_pty_writer(
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
+        bell,
     );
     tab.apply_layout(
         TiledPaneLayout::default(),
@@ -593,6 +624,9 @@ fn create_new_tab_with_sixel_support(
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
+    let bell = Bell::default();
     let mut tab = Tab::new(
         index,
         position,
@@ -618,6 +652,9 @@ fn create_new_tab_with_sixel_support(
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
+        bell,
     );
     tab.apply_layout(
         TiledPaneLayout::default(),
@@ -656,6 +693,8 @@ fn take_snapshot(ansi_instructions: &str, rows: usize, columns: usize, palette:
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
     let mut grid = Grid::new(
         rows,
         columns,
@@ -668,6 +707,8 @@ fn take_snapshot(ansi_instructions: &str, rows: usize, columns: usize, palette:
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
     );
     let mut vte_parser = vte::Parser::new();
     for &byte in ansi_instructions.as_bytes() {
@@ -691,6 +732,8 @@ fn take_snapshot_with_sixel(
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
     let mut grid = Grid::new(
         rows,
         columns,
@@ -703,6 +746,8 @@ fn take_snapshot_with_sixel(
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
     );
     let mut vte_parser = vte::Parser::new();
     for &byte in ansi_instructions.as_bytes() {
@@ -723,6 +768,8 @@ fn take_snapshot_and_cursor_position(
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
     let mut grid = Grid::new(
         rows,
         columns,
@@ -735,6 +782,8 @@ fn take_snapshot_and_cursor_position(
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
     );
     let mut vte_parser = vte::Parser::new();
     for &byte in ansi_instructions.as_bytes() {
@@ -967,6 +1016,158 @@ fn five_new_floating_panes() {
     assert_snapshot!(snapshot);
 }
 
+#[test]
+fn pinned_floating_pane_is_rendered_above_other_floating_panes() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let client_id = 1;
+    let mut tab = create_new_tab(size, ModeInfo::default());
+    let new_pane_id_1 = PaneId::Terminal(2);
+    let new_pane_id_2 = PaneId::Terminal(3);
+    tab.toggle_floating_panes(Some(client_id), None).unwrap();
+    tab.new_pane(new_pane_id_1, None, None, None, Some(client_id))
+        .unwrap();
+    tab.new_pane(new_pane_id_2, None, None, None, Some(client_id))
+        .unwrap();
+    // fill the first floating pane with "E"s, the second one (on top of it) stays empty
+    tab.handle_pty_bytes(2, Vec::from("\u{1b}#8".as_bytes()))
+        .unwrap();
+    let mut output = Output::default();
+    tab.render(&mut output).unwrap();
+    let unpinned_snapshot = take_snapshot(
+        output.serialize().unwrap().get(&client_id).unwrap(),
+        size.rows,
+        size.cols,
+        Palette::default(),
+    );
+
+    tab.toggle_pane_pinned(PaneId::Terminal(1));
+    assert!(
+        !tab.pane_is_pinned(&PaneId::Terminal(1)),
+        "tiled panes cannot be pinned"
+    );
+    tab.toggle_pane_pinned(new_pane_id_1);
+    assert!(tab.pane_is_pinned(&new_pane_id_1), "floating pane pinned");
+    // focusing the other pane does not bring it above the pinned pane
+    tab.floating_panes.focus_pane_for_all_clients(new_pane_id_2);
+    let mut output = Output::default();
+    tab.render(&mut output).unwrap();
+    let pinned_snapshot = take_snapshot(
+        output.serialize().unwrap().get(&client_id).unwrap(),
+        size.rows,
+        size.cols,
+        Palette::default(),
+    );
+    let count_fill_characters = |snapshot: &str| snapshot.chars().filter(|c| *c == 'E').count();
+    assert!(
+        count_fill_characters(&pinned_snapshot) > count_fill_characters(&unpinned_snapshot),
+        "the pinned pane covers the overlapping region"
+    );
+}
+
+#[test]
+fn read_only_pane_gets_no_input() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let client_id = 1;
+
+    let mut pty_instruction_bus = MockPtyInstructionBus::new();
+    let mut tab = create_new_tab_with_mock_pty_writer(
+        size,
+        ModeInfo::default(),
+        pty_instruction_bus.pty_write_sender(),
+    );
+    pty_instruction_bus.start();
+
+    tab.set_pane_read_only(PaneId::Terminal(1), true);
+    tab.write_to_active_terminal("rm -rf /".as_bytes().to_vec(), client_id)
+        .unwrap();
+    tab.write_to_terminal_at("exit".as_bytes().to_vec(), &Position::new(5, 5), client_id)
+        .unwrap();
+    tab.set_pane_read_only(PaneId::Terminal(1), false);
+    tab.write_to_active_terminal("ls".as_bytes().to_vec(), client_id)
+        .unwrap();
+
+    pty_instruction_bus.exit();
+
+    assert_eq!(
+        pty_instruction_bus.clone_output(),
+        vec!["ls".to_string()],
+        "only the input sent after the pane was unlocked reached its pty"
+    );
+}
+
+#[test]
+fn stacked_panes_render_one_pane_at_a_time() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let client_id = 1;
+    let mut tab = create_new_tab(size, ModeInfo::default());
+    let new_pane_id_1 = PaneId::Terminal(2);
+    let new_pane_id_2 = PaneId::Terminal(3);
+    tab.new_pane(new_pane_id_1, None, None, None, Some(client_id))
+        .unwrap();
+    tab.new_pane(new_pane_id_2, None, None, None, Some(client_id))
+        .unwrap();
+    tab.handle_pty_bytes(1, Vec::from("first pane".as_bytes()))
+        .unwrap();
+    tab.handle_pty_bytes(2, Vec::from("second pane".as_bytes()))
+        .unwrap();
+    tab.handle_pty_bytes(3, Vec::from("third pane".as_bytes()))
+        .unwrap();
+    tab.stack_panes(
+        vec![PaneId::Terminal(1), new_pane_id_1, new_pane_id_2],
+        client_id,
+    )
+    .unwrap();
+    let render_and_find_visible_panes = |tab: &mut Tab| {
+        let mut output = Output::default();
+        tab.render(&mut output).unwrap();
+        let snapshot = take_snapshot(
+            output.serialize().unwrap().get(&client_id).unwrap(),
+            size.rows,
+            size.cols,
+            Palette::default(),
+        );
+        ["first pane", "second pane", "third pane"]
+            .iter()
+            .filter(|pane_contents| snapshot.contains(*pane_contents))
+            .copied()
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        render_and_find_visible_panes(&mut tab),
+        vec!["first pane"],
+        "only the root pane of the stack is visible"
+    );
+    assert_eq!(
+        tab.get_active_pane_id(client_id),
+        Some(PaneId::Terminal(1)),
+        "focus is on the visible pane"
+    );
+    tab.cycle_stack(Direction::Down, client_id);
+    assert_eq!(
+        render_and_find_visible_panes(&mut tab),
+        vec!["second pane"],
+        "cycling the stack shows the next pane"
+    );
+    assert_eq!(tab.get_active_pane_id(client_id), Some(new_pane_id_1));
+    tab.cycle_stack(Direction::Up, client_id);
+    tab.cycle_stack(Direction::Up, client_id);
+    assert_eq!(
+        render_and_find_visible_panes(&mut tab),
+        vec!["third pane"],
+        "cycling the stack backwards wraps around"
+    );
+    assert_eq!(tab.get_active_pane_id(client_id), Some(new_pane_id_2));
+}
+
 #[test]
 fn increase_floating_pane_size() {
     let size = Size {
@@ -2507,9 +2708,9 @@ fn pane_in_sgr_button_event_tracking_mouse_mode() {
         .unwrap();
     tab.handle_middle_mouse_release(&Position::new(7, 75), client_id)
         .unwrap();
-    tab.handle_scrollwheel_up(&Position::new(5, 71), 1, client_id)
+    tab.handle_scrollwheel_up(&Position::new(5, 71), ScrollLines::Lines(1), client_id)
         .unwrap();
-    tab.handle_scrollwheel_down(&Position::new(5, 71), 1, client_id)
+    tab.handle_scrollwheel_down(&Position::new(5, 71), ScrollLines::Lines(1), client_id)
         .unwrap();
 
     pty_instruction_bus.exit();
@@ -2569,9 +2770,9 @@ fn pane_in_sgr_normal_event_tracking_mouse_mode() {
         .unwrap();
     tab.handle_middle_mouse_release(&Position::new(7, 75), client_id)
         .unwrap();
-    tab.handle_scrollwheel_up(&Position::new(5, 71), 1, client_id)
+    tab.handle_scrollwheel_up(&Position::new(5, 71), ScrollLines::Lines(1), client_id)
         .unwrap();
-    tab.handle_scrollwheel_down(&Position::new(5, 71), 1, client_id)
+    tab.handle_scrollwheel_down(&Position::new(5, 71), ScrollLines::Lines(1), client_id)
         .unwrap();
 
     pty_instruction_bus.exit();
@@ -2631,9 +2832,9 @@ fn pane_in_utf8_button_event_tracking_mouse_mode() {
         .unwrap();
     tab.handle_middle_mouse_release(&Position::new(7, 75), client_id)
         .unwrap();
-    tab.handle_scrollwheel_up(&Position::new(5, 71), 1, client_id)
+    tab.handle_scrollwheel_up(&Position::new(5, 71), ScrollLines::Lines(1), client_id)
         .unwrap();
-    tab.handle_scrollwheel_down(&Position::new(5, 71), 1, client_id)
+    tab.handle_scrollwheel_down(&Position::new(5, 71), ScrollLines::Lines(1), client_id)
         .unwrap();
 
     pty_instruction_bus.exit();
@@ -2693,9 +2894,9 @@ fn pane_in_utf8_normal_event_tracking_mouse_mode() {
         .unwrap();
     tab.handle_middle_mouse_release(&Position::new(7, 75), client_id)
         .unwrap();
-    tab.handle_scrollwheel_up(&Position::new(5, 71), 1, client_id)
+    tab.handle_scrollwheel_up(&Position::new(5, 71), ScrollLines::Lines(1), client_id)
         .unwrap();
-    tab.handle_scrollwheel_down(&Position::new(5, 71), 1, client_id)
+    tab.handle_scrollwheel_down(&Position::new(5, 71), ScrollLines::Lines(1), client_id)
         .unwrap();
 
     pty_instruction_bus.exit();
@@ -2889,6 +3090,7 @@ fn pane_faux_scrolling_in_alternate_mode() {
     };
     let client_id: u16 = 1;
     let lines_to_scroll = 3;
+    let scroll_lines = ScrollLines::Lines(lines_to_scroll);
 
     let mut pty_instruction_bus = MockPtyInstructionBus::new();
     let mut tab = create_new_tab_with_mock_pty_writer(
@@ -2902,25 +3104,25 @@ fn pane_faux_scrolling_in_alternate_mode() {
     let set_application_mode = String::from("\u{1b}[?1h");
 
     // no output since alternate scren not active yet
-    tab.handle_scrollwheel_up(&Position::new(1, 1), lines_to_scroll, client_id)
+    tab.handle_scrollwheel_up(&Position::new(1, 1), scroll_lines, client_id)
         .unwrap();
-    tab.handle_scrollwheel_down(&Position::new(1, 1), lines_to_scroll, client_id)
+    tab.handle_scrollwheel_down(&Position::new(1, 1), scroll_lines, client_id)
         .unwrap();
 
     tab.handle_pty_bytes(1, enable_alternate_screen.as_bytes().to_vec())
         .unwrap();
     // CSI A * lines_to_scroll, CSI B * lines_to_scroll
-    tab.handle_scrollwheel_up(&Position::new(1, 1), lines_to_scroll, client_id)
+    tab.handle_scrollwheel_up(&Position::new(1, 1), scroll_lines, client_id)
         .unwrap();
-    tab.handle_scrollwheel_down(&Position::new(1, 1), lines_to_scroll, client_id)
+    tab.handle_scrollwheel_down(&Position::new(1, 1), scroll_lines, client_id)
         .unwrap();
 
     tab.handle_pty_bytes(1, set_application_mode.as_bytes().to_vec())
         .unwrap();
     // SS3 A * lines_to_scroll, SS3 B * lines_to_scroll
-    tab.handle_scrollwheel_up(&Position::new(1, 1), lines_to_scroll, client_id)
+    tab.handle_scrollwheel_up(&Position::new(1, 1), scroll_lines, client_id)
         .unwrap();
-    tab.handle_scrollwheel_down(&Position::new(1, 1), lines_to_scroll, client_id)
+    tab.handle_scrollwheel_down(&Position::new(1, 1), scroll_lines, client_id)
         .unwrap();
 
     pty_instruction_bus.exit();
@@ -2934,6 +3136,67 @@ fn pane_faux_scrolling_in_alternate_mode() {
     assert_eq!(pty_instruction_bus.clone_output(), expected);
 }
 
+#[test]
+fn scrollwheel_scrolls_by_the_scroll_lines_of_the_pane() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let client_id = 1;
+    let mut tab = create_new_tab(size, ModeInfo::default());
+    let mut pane_contents = String::new();
+    for i in 0..100 {
+        pane_contents.push_str(&format!("line {}\n\r", i));
+    }
+    tab.handle_pty_bytes(1, pane_contents.into_bytes()).unwrap();
+    let first_line_in_viewport = |tab: &mut Tab| -> usize {
+        let pane = tab.get_active_pane_or_floating_pane_mut(client_id).unwrap();
+        let viewport = pane.dump_screen(client_id, false);
+        let first_line = viewport.lines().next().unwrap();
+        first_line
+            .trim_start_matches("line ")
+            .trim()
+            .parse()
+            .unwrap()
+    };
+    let position = Position::new(5, 10);
+    let default_scroll_lines = ScrollLines::default();
+
+    let bottom = first_line_in_viewport(&mut tab);
+    tab.handle_scrollwheel_up(&position, default_scroll_lines, client_id)
+        .unwrap();
+    assert_eq!(
+        first_line_in_viewport(&mut tab),
+        bottom - 3,
+        "default scroll lines"
+    );
+
+    tab.set_pane_scroll_lines(PaneId::Terminal(1), Some(ScrollLines::Lines(7)))
+        .unwrap();
+    tab.handle_scrollwheel_up(&position, default_scroll_lines, client_id)
+        .unwrap();
+    assert_eq!(
+        first_line_in_viewport(&mut tab),
+        bottom - 10,
+        "custom scroll lines"
+    );
+
+    tab.set_pane_scroll_lines(PaneId::Terminal(1), Some(ScrollLines::HalfPage))
+        .unwrap();
+    let half_page = tab
+        .get_active_pane_or_floating_pane_mut(client_id)
+        .unwrap()
+        .get_content_rows()
+        / 2;
+    tab.handle_scrollwheel_down(&position, default_scroll_lines, client_id)
+        .unwrap();
+    assert_eq!(
+        first_line_in_viewport(&mut tab),
+        bottom - 10 + half_page,
+        "half page scroll lines"
+    );
+}
+
 #[test]
 fn move_pane_focus_sends_tty_csi_event() {
     let size = Size {
@@ -6013,6 +6276,70 @@ fn floating_layout_with_plugins_and_commands_swaped_properly() {
     assert_snapshot!(snapshot);
 }
 
+#[test]
+fn floating_panes_are_placed_at_their_layout_geometry() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let base_layout = r#"
+        layout {
+            floating_panes {
+                pane x=10 y="25%" width=30 height=5
+                pane x=200 y=100 width=20 height=5
+            }
+        }
+    "#;
+    let (base_layout, base_floating_layout) =
+        Layout::from_kdl(base_layout, "file_name.kdl".into(), None, None)
+            .unwrap()
+            .template
+            .unwrap();
+    let new_floating_terminal_ids = vec![(1, None), (2, None)];
+    let new_terminal_ids = vec![(3, None)];
+    let tab = create_new_tab_with_swap_layouts(
+        size,
+        ModeInfo::default(),
+        (vec![], vec![]),
+        Some((
+            base_layout,
+            base_floating_layout,
+            new_terminal_ids,
+            new_floating_terminal_ids,
+            HashMap::new(),
+        )),
+        true,
+    );
+    let geometry_of = |terminal_id| {
+        tab.floating_panes
+            .get_pane(PaneId::Terminal(terminal_id))
+            .unwrap()
+            .position_and_size()
+    };
+    let placed_pane = geometry_of(1);
+    assert_eq!(
+        (placed_pane.x, placed_pane.y),
+        (10, 5),
+        "pane placed at its coordinates"
+    );
+    assert_eq!(
+        (placed_pane.cols.as_usize(), placed_pane.rows.as_usize()),
+        (30, 5),
+        "pane has its size"
+    );
+    let clamped_pane = geometry_of(2);
+    assert_eq!(
+        (clamped_pane.x, clamped_pane.y),
+        (101, 15),
+        "out of bounds pane clamped into the viewport"
+    );
+    assert_eq!(
+        (clamped_pane.cols.as_usize(), clamped_pane.rows.as_usize()),
+        (20, 5),
+        "clamped pane keeps its size"
+    );
+}
+
 #[test]
 fn base_floating_layout_is_included_in_swap_layouts() {
     let size = Size {
@@ -6987,3 +7314,200 @@ fn can_define_expanded_pane_in_stack() {
     );
     assert_snapshot!(snapshot);
 }
+
+#[test]
+fn toggle_ruler() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let client_id = 1;
+    let mut tab = create_new_tab(size, ModeInfo::default());
+    let mut output = Output::default();
+    tab.toggle_ruler(client_id);
+    tab.render(&mut output).unwrap();
+    let snapshot = take_snapshot(
+        output.serialize().unwrap().get(&client_id).unwrap(),
+        size.rows,
+        size.cols,
+        Palette::default(),
+    );
+    let lines: Vec<&str> = snapshot.lines().collect();
+    assert!(
+        lines[1].starts_with("01 (C): │01234567890123456789"),
+        "column indices rendered along the top of the pane"
+    );
+    assert!(
+        lines[2].starts_with("02 (C): │1 "),
+        "line indices rendered along the left of the pane"
+    );
+    assert!(
+        lines[11].starts_with("11 (C): │0 "),
+        "line indices rendered along the left of the pane"
+    );
+    assert!(
+        lines[18].starts_with("18 (C): │7 "),
+        "line indices rendered along the left of the pane"
+    );
+
+    let mut output = Output::default();
+    tab.toggle_ruler(client_id);
+    tab.render(&mut output).unwrap();
+    let snapshot = take_snapshot(
+        output.serialize().unwrap().get(&client_id).unwrap(),
+        size.rows,
+        size.cols,
+        Palette::default(),
+    );
+    let lines: Vec<&str> = snapshot.lines().collect();
+    assert!(
+        lines[1].starts_with("01 (C): │    "),
+        "ruler removed from the top of the pane"
+    );
+    assert!(
+        lines[2].starts_with("02 (C): │    "),
+        "ruler removed from the left of the pane"
+    );
+}
+
+#[test]
+fn toggle_show_invisibles() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let client_id = 1;
+    let mut tab = create_new_tab(size, ModeInfo::default());
+    tab.handle_pty_bytes(1, Vec::from("a b\tc".as_bytes()))
+        .unwrap();
+    let mut output = Output::default();
+    tab.toggle_pane_show_invisibles(PaneId::Terminal(1))
+        .unwrap();
+    tab.render(&mut output).unwrap();
+    let snapshot = take_snapshot(
+        output.serialize().unwrap().get(&client_id).unwrap(),
+        size.rows,
+        size.cols,
+        Palette::default(),
+    );
+    let lines: Vec<&str> = snapshot.lines().collect();
+    assert!(
+        lines[1].starts_with("01 (C): │a·b→    c "),
+        "spaces and tabs rendered as visible substitutes"
+    );
+
+    let mut output = Output::default();
+    tab.toggle_pane_show_invisibles(PaneId::Terminal(1))
+        .unwrap();
+    tab.render(&mut output).unwrap();
+    let snapshot = take_snapshot(
+        output.serialize().unwrap().get(&client_id).unwrap(),
+        size.rows,
+        size.cols,
+        Palette::default(),
+    );
+    let lines: Vec<&str> = snapshot.lines().collect();
+    // only the pane contents are rendered again, its frame did not change
+    assert!(
+        lines[1].contains(" a b     c ") && !lines[1].contains('·'),
+        "pane contents rendered unchanged once toggled off"
+    );
+}
+
+#[test]
+fn lines_written_to_plugin_fed_pane_are_rendered_and_searchable() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let client_id = 1;
+    let plugin_id = 10;
+    let mut tab = create_new_tab(size, ModeInfo::default());
+    tab.new_plugin_fed_pane(2, Some("plugin output".to_owned()), plugin_id, client_id)
+        .unwrap();
+    let mut lines = vec!["\u{1b}[31mneedle\u{1b}[m in the scrollback".to_owned()];
+    for i in 1..40 {
+        lines.push(format!("plugin line {}", i));
+    }
+    tab.write_to_pane_scrollback(2, lines, plugin_id).unwrap();
+    assert!(
+        tab.write_to_pane_scrollback(2, vec!["intruder".to_owned()], plugin_id + 1)
+            .is_err(),
+        "only the plugin feeding the pane can write to it"
+    );
+    let mut output = Output::default();
+    tab.render(&mut output).unwrap();
+    let snapshot = take_snapshot(
+        output.serialize().unwrap().get(&client_id).unwrap(),
+        size.rows,
+        size.cols,
+        Palette::default(),
+    );
+    assert!(
+        snapshot.contains("plugin line 39"),
+        "latest line rendered in the pane"
+    );
+    assert!(
+        !snapshot.contains("needle in the scrollback"),
+        "first line scrolled out of view"
+    );
+
+    tab.update_search_term("needle".as_bytes().to_vec(), client_id)
+        .unwrap();
+    tab.search_up(client_id);
+    let mut output = Output::default();
+    tab.render(&mut output).unwrap();
+    let snapshot = take_snapshot(
+        output.serialize().unwrap().get(&client_id).unwrap(),
+        size.rows,
+        size.cols,
+        Palette::default(),
+    );
+    assert!(
+        snapshot.contains("needle in the scrollback"),
+        "search scrolled to the line in the scrollback, its ANSI styling parsed away"
+    );
+}
+
+#[test]
+fn focus_dimming_dims_the_colors_of_unfocused_panes() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let client_id = 1;
+    let mut tab = create_new_tab(size, ModeInfo::default());
+    tab.new_pane(PaneId::Terminal(2), None, None, None, Some(client_id))
+        .unwrap();
+    tab.handle_pty_bytes(1, Vec::from("\u{1b}[38;2;200;100;40munfocused".as_bytes()))
+        .unwrap();
+    tab.handle_pty_bytes(2, Vec::from("\u{1b}[38;2;200;100;40mactive".as_bytes()))
+        .unwrap();
+    // the styles written since the cursor was last moved, up to where the text is rendered
+    fn styles_of<'a>(rendered: &'a str, text: &str) -> &'a str {
+        let text_index = rendered.find(text).unwrap();
+        rendered[..text_index].rsplit('H').next().unwrap()
+    }
+    tab.set_focus_dimming(Some(0.5));
+    let mut output = Output::default();
+    tab.render(&mut output).unwrap();
+    let rendered = output.serialize().unwrap().remove(&client_id).unwrap();
+    // the background of the default palette is black, so the colors are halved
+    assert!(
+        styles_of(&rendered, "unfocused").contains("\u{1b}[38;2;100;50;20m"),
+        "unfocused pane blended halfway towards the background"
+    );
+    assert!(
+        styles_of(&rendered, "active").contains("\u{1b}[38;2;200;100;40m"),
+        "focused pane rendered in its own colors"
+    );
+
+    tab.set_focus_dimming(None);
+    let mut output = Output::default();
+    tab.render(&mut output).unwrap();
+    let rendered = output.serialize().unwrap().remove(&client_id).unwrap();
+    assert!(
+        styles_of(&rendered, "unfocused").contains("\u{1b}[38;2;200;100;40m"),
+        "pane contents were not changed by dimming them"
+    );
+}
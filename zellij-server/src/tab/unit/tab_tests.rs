@@ -1,4 +1,4 @@
-use super::Tab;
+use super::{Tab, MIN_TERMINAL_HEIGHT};
 use crate::panes::sixel::SixelImageStore;
 use crate::screen::CopyOptions;
 use crate::{
@@ -10,8 +10,9 @@ use std::path::PathBuf;
 use zellij_utils::data::{Direction, Resize, ResizeStrategy};
 use zellij_utils::errors::prelude::*;
 use zellij_utils::input::layout::{SplitDirection, SplitSize, TiledPaneLayout};
+use zellij_utils::input::options::Bell;
 use zellij_utils::ipc::IpcReceiverWithContext;
-use zellij_utils::pane_size::{Size, SizeInPixels};
+use zellij_utils::pane_size::{PaneGeom, Size, SizeInPixels};
 
 use std::cell::RefCell;
 use std::collections::{HashMap, HashSet};
@@ -166,6 +167,9 @@ fn create_new_tab(size: Size) -> Tab {
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
+    let bell = Bell::default();
     let mut tab = Tab::new(
         index,
         position,
@@ -191,6 +195,9 @@ fn create_new_tab(size: Size) -> Tab {
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
+        bell,
     );
     tab.apply_layout(
         TiledPaneLayout::default(),
@@ -228,6 +235,9 @@ fn create_new_tab_with_layout(size: Size, layout: TiledPaneLayout) -> Tab {
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
+    let bell = Bell::default();
     let mut tab = Tab::new(
         index,
         position,
@@ -253,6 +263,9 @@ fn create_new_tab_with_layout(size: Size, layout: TiledPaneLayout) -> Tab {
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
+        bell,
     );
     let mut new_terminal_ids = vec![];
     for i in 0..layout.extract_run_instructions().len() {
@@ -296,6 +309,9 @@ fn create_new_tab_with_cell_size(
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
+    let bell = Bell::default();
     let mut tab = Tab::new(
         index,
         position,
@@ -321,6 +337,9 @@ fn create_new_tab_with_cell_size(
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
+        bell,
     );
     tab.apply_layout(
         TiledPaneLayout::default(),
@@ -951,6 +970,45 @@ fn switch_to_prev_pane_fullscreen() {
     );
 }
 
+#[test]
+fn focus_next_pane_spatial_visits_panes_in_reading_order() {
+    // ┌─────┬─────┐
+    // │  1  │  2  │
+    // ├─────┼─────┤
+    // │  4  │  3  │
+    // └─────┴─────┘
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let mut tab = create_new_tab(size);
+    tab.vertical_split(PaneId::Terminal(2), None, 1).unwrap();
+    tab.focus_pane_with_id(PaneId::Terminal(2), false, 1)
+        .unwrap();
+    tab.horizontal_split(PaneId::Terminal(3), None, 1).unwrap();
+    tab.focus_pane_with_id(PaneId::Terminal(1), false, 1)
+        .unwrap();
+    tab.horizontal_split(PaneId::Terminal(4), None, 1).unwrap();
+    tab.focus_pane_with_id(PaneId::Terminal(1), false, 1)
+        .unwrap();
+
+    let mut visited_panes = vec![];
+    for _ in 0..4 {
+        tab.focus_next_pane_spatial(1);
+        visited_panes.push(tab.get_active_pane_id(1).unwrap());
+    }
+    assert_eq!(
+        visited_panes,
+        vec![
+            PaneId::Terminal(2),
+            PaneId::Terminal(4),
+            PaneId::Terminal(3),
+            PaneId::Terminal(1),
+        ],
+        "focus visits panes left-to-right, top-to-bottom and wraps around"
+    );
+}
+
 #[test]
 pub fn close_pane_with_another_pane_above_it() {
     // ┌───────────┐            ┌───────────┐
@@ -14423,3 +14481,360 @@ fn correctly_resize_frameless_panes_on_pane_close() {
     let content_size = (pane.get_content_columns(), pane.get_content_rows());
     assert_eq!(content_size, (cols, rows));
 }
+
+#[test]
+fn toggle_frame_of_single_pane() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let mut tab = create_new_tab(size);
+    tab.new_pane(PaneId::Terminal(2), None, None, None, Some(1))
+        .unwrap();
+    let content_rows = |tab: &Tab, pane_id: PaneId| {
+        tab.tiled_panes
+            .get_pane(pane_id)
+            .unwrap()
+            .get_content_rows()
+    };
+    let framed_content_rows = content_rows(&tab, PaneId::Terminal(1));
+    let sibling_content_rows = content_rows(&tab, PaneId::Terminal(2));
+
+    tab.toggle_pane_frame(PaneId::Terminal(1)).unwrap();
+    assert_eq!(
+        content_rows(&tab, PaneId::Terminal(1)),
+        framed_content_rows + 2,
+        "pane content took over the rows of its top and bottom frame"
+    );
+    assert_eq!(
+        content_rows(&tab, PaneId::Terminal(2)),
+        sibling_content_rows,
+        "sibling pane content did not change"
+    );
+
+    tab.toggle_pane_frame(PaneId::Terminal(1)).unwrap();
+    assert_eq!(
+        content_rows(&tab, PaneId::Terminal(1)),
+        framed_content_rows,
+        "pane content gave the rows back to its frame"
+    );
+}
+
+#[test]
+fn toggle_zoom_restores_exact_layout() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let client_id = 1;
+    let mut tab = create_new_tab(size);
+    tab.vertical_split(PaneId::Terminal(2), None, client_id)
+        .unwrap();
+    tab.horizontal_split(PaneId::Terminal(3), None, client_id)
+        .unwrap();
+    tab.new_pane(PaneId::Terminal(4), None, Some(true), None, Some(client_id))
+        .unwrap();
+    let pane_geoms = |tab: &Tab| -> HashMap<PaneId, PaneGeom> {
+        tab.tiled_panes
+            .get_panes()
+            .chain(tab.floating_panes.get_panes())
+            .map(|(pane_id, pane)| (*pane_id, pane.current_geom()))
+            .collect()
+    };
+    let original_pane_geoms = pane_geoms(&tab);
+    let zoomed_pane_id = tab.tiled_panes.get_active_pane_id(client_id).unwrap();
+
+    tab.toggle_zoom(client_id).unwrap();
+    let zoomed_pane_geom = tab
+        .tiled_panes
+        .get_pane(zoomed_pane_id)
+        .unwrap()
+        .current_geom();
+    assert!(tab.is_zoomed(), "tab is zoomed");
+    assert_eq!(
+        (
+            zoomed_pane_geom.cols.as_usize(),
+            zoomed_pane_geom.rows.as_usize()
+        ),
+        (121, 20),
+        "zoomed pane takes the whole tab"
+    );
+    assert!(
+        !tab.are_floating_panes_visible(),
+        "floating panes hidden while zoomed"
+    );
+
+    tab.toggle_zoom(client_id).unwrap();
+    assert!(!tab.is_zoomed(), "tab is no longer zoomed");
+    assert!(
+        tab.are_floating_panes_visible(),
+        "floating panes shown again"
+    );
+    assert_eq!(
+        pane_geoms(&tab),
+        original_pane_geoms,
+        "layout restored after zoom"
+    );
+}
+
+#[test]
+fn toggle_zoom_on_another_pane_switches_zoomed_pane() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let client_id = 1;
+    let mut tab = create_new_tab(size);
+    tab.vertical_split(PaneId::Terminal(2), None, client_id)
+        .unwrap();
+    let original_pane_geoms: HashMap<PaneId, PaneGeom> = tab
+        .tiled_panes
+        .get_panes()
+        .map(|(pane_id, pane)| (*pane_id, pane.current_geom()))
+        .collect();
+    let first_zoomed_pane_id = tab.tiled_panes.get_active_pane_id(client_id).unwrap();
+
+    tab.toggle_zoom(client_id).unwrap();
+    tab.switch_next_pane_fullscreen(client_id);
+    let second_zoomed_pane_id = tab.tiled_panes.get_active_pane_id(client_id).unwrap();
+    assert_ne!(
+        first_zoomed_pane_id, second_zoomed_pane_id,
+        "focus moved while zoomed"
+    );
+    tab.toggle_zoom(client_id).unwrap();
+    assert!(tab.is_zoomed(), "tab is still zoomed");
+    let zoomed_pane_geom = tab
+        .tiled_panes
+        .get_pane(second_zoomed_pane_id)
+        .unwrap()
+        .current_geom();
+    assert_eq!(
+        (
+            zoomed_pane_geom.cols.as_usize(),
+            zoomed_pane_geom.rows.as_usize()
+        ),
+        (121, 20),
+        "newly focused pane is zoomed"
+    );
+
+    tab.toggle_zoom(client_id).unwrap();
+    assert!(!tab.is_zoomed(), "tab is no longer zoomed");
+    for (pane_id, geom) in original_pane_geoms {
+        assert_eq!(
+            tab.tiled_panes.get_pane(pane_id).unwrap().current_geom(),
+            geom,
+            "layout restored after zoom"
+        );
+    }
+}
+
+#[test]
+fn splitting_a_zoomed_pane_restores_layout_first() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let client_id = 1;
+    let mut tab = create_new_tab(size);
+    tab.vertical_split(PaneId::Terminal(2), None, client_id)
+        .unwrap();
+    let zoomed_pane_id = tab.tiled_panes.get_active_pane_id(client_id).unwrap();
+    let (sibling_pane_id, sibling_pane_geom) = tab
+        .tiled_panes
+        .get_panes()
+        .find(|(pane_id, _pane)| **pane_id != zoomed_pane_id)
+        .map(|(pane_id, pane)| (*pane_id, pane.current_geom()))
+        .unwrap();
+
+    tab.toggle_zoom(client_id).unwrap();
+    tab.horizontal_split(PaneId::Terminal(3), None, client_id)
+        .unwrap();
+    assert!(!tab.is_zoomed(), "zoom toggled off by the split");
+    assert!(
+        tab.tiled_panes.get_pane(PaneId::Terminal(3)).is_some(),
+        "zoomed pane was split"
+    );
+    assert_eq!(
+        tab.tiled_panes
+            .get_pane(sibling_pane_id)
+            .unwrap()
+            .current_geom(),
+        sibling_pane_geom,
+        "pane outside of the split kept its layout from before the zoom"
+    );
+}
+
+#[test]
+fn closing_a_pane_of_a_zoomed_tab_drops_the_zoom() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let client_id = 1;
+    let mut tab = create_new_tab(size);
+    tab.vertical_split(PaneId::Terminal(2), None, client_id)
+        .unwrap();
+    tab.horizontal_split(PaneId::Terminal(3), None, client_id)
+        .unwrap();
+    let zoomed_pane_id = tab.tiled_panes.get_active_pane_id(client_id).unwrap();
+
+    tab.toggle_zoom(client_id).unwrap();
+    tab.close_pane(zoomed_pane_id, false, Some(client_id));
+    assert!(!tab.is_zoomed(), "zoom dropped with the zoomed pane");
+    let pane_geoms: HashMap<PaneId, PaneGeom> = tab
+        .tiled_panes
+        .get_panes()
+        .map(|(pane_id, pane)| (*pane_id, pane.current_geom()))
+        .collect();
+
+    tab.toggle_zoom(client_id).unwrap();
+    assert!(tab.is_zoomed(), "toggling zooms the newly focused pane");
+    tab.toggle_zoom(client_id).unwrap();
+    assert!(!tab.is_zoomed(), "tab is no longer zoomed");
+    for (pane_id, geom) in pane_geoms {
+        assert_eq!(
+            tab.tiled_panes.get_pane(pane_id).unwrap().current_geom(),
+            geom,
+            "layout from after the close restored, not the one from before it"
+        );
+    }
+}
+
+#[test]
+fn split_pane_with_ratio() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let client_id = 1;
+    let mut tab = create_new_tab(size);
+    tab.split_pane_with_ratio(
+        PaneId::Terminal(1),
+        PaneId::Terminal(2),
+        SplitDirection::Vertical,
+        0.25,
+        client_id,
+    )
+    .unwrap();
+    let pane_cols = |tab: &Tab, pane_id| {
+        tab.tiled_panes
+            .get_pane(pane_id)
+            .unwrap()
+            .position_and_size()
+            .cols
+            .as_usize()
+    };
+    let first_pane_cols = pane_cols(&tab, PaneId::Terminal(1));
+    let second_pane_cols = pane_cols(&tab, PaneId::Terminal(2));
+    assert_eq!(
+        first_pane_cols + second_pane_cols,
+        121,
+        "panes fill the tab"
+    );
+    assert!(
+        (29..=31).contains(&first_pane_cols),
+        "first pane takes a quarter of the tab, got {} columns",
+        first_pane_cols
+    );
+
+    tab.resize_whole_tab(Size {
+        cols: 200,
+        rows: 20,
+    })
+    .unwrap();
+    let first_pane_cols = pane_cols(&tab, PaneId::Terminal(1));
+    let second_pane_cols = pane_cols(&tab, PaneId::Terminal(2));
+    assert_eq!(
+        first_pane_cols + second_pane_cols,
+        200,
+        "panes fill the resized tab"
+    );
+    assert!(
+        (49..=51).contains(&first_pane_cols),
+        "first pane still takes a quarter of the resized tab, got {} columns",
+        first_pane_cols
+    );
+}
+
+#[test]
+fn split_pane_with_ratio_splits_the_given_pane() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let client_id = 1;
+    let mut tab = create_new_tab(size);
+    tab.split_pane_with_ratio(
+        PaneId::Terminal(1),
+        PaneId::Terminal(2),
+        SplitDirection::Vertical,
+        0.5,
+        client_id,
+    )
+    .unwrap();
+    // the focus is now on the new pane, on the right
+    tab.split_pane_with_ratio(
+        PaneId::Terminal(1),
+        PaneId::Terminal(3),
+        SplitDirection::Horizontal,
+        0.5,
+        client_id,
+    )
+    .unwrap();
+    let pane_geom = |pane_id| {
+        tab.tiled_panes
+            .get_pane(pane_id)
+            .unwrap()
+            .position_and_size()
+    };
+    assert_eq!(
+        pane_geom(PaneId::Terminal(3)).x,
+        pane_geom(PaneId::Terminal(1)).x,
+        "new pane is below the pane that was split"
+    );
+    assert_eq!(
+        pane_geom(PaneId::Terminal(2)).rows.as_usize(),
+        20,
+        "the focused pane was not split"
+    );
+    assert_eq!(
+        tab.get_active_pane_id(client_id),
+        Some(PaneId::Terminal(3)),
+        "new pane is focused"
+    );
+}
+
+#[test]
+fn split_pane_with_ratio_keeps_both_panes_usable() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let client_id = 1;
+    let mut tab = create_new_tab(size);
+    tab.split_pane_with_ratio(
+        PaneId::Terminal(1),
+        PaneId::Terminal(2),
+        SplitDirection::Horizontal,
+        0.0,
+        client_id,
+    )
+    .unwrap();
+    let pane_rows = |tab: &Tab, pane_id| {
+        tab.tiled_panes
+            .get_pane(pane_id)
+            .unwrap()
+            .position_and_size()
+            .rows
+            .as_usize()
+    };
+    assert!(
+        pane_rows(&tab, PaneId::Terminal(1)) >= MIN_TERMINAL_HEIGHT,
+        "ratio is clamped to leave room for the split pane"
+    );
+    assert_eq!(
+        pane_rows(&tab, PaneId::Terminal(1)) + pane_rows(&tab, PaneId::Terminal(2)),
+        20,
+        "panes fill the tab"
+    );
+}
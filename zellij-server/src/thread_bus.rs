@@ -5,6 +5,7 @@ use crate::{
     pty::PtyInstruction, pty_writer::PtyWriteInstruction, screen::ScreenInstruction,
     ServerInstruction,
 };
+use std::time::Duration;
 use zellij_utils::errors::prelude::*;
 use zellij_utils::{channels, channels::SenderWithContext, errors::ErrorContext};
 
@@ -217,4 +218,20 @@ impl<T> Bus<T> {
         let idx = oper.index();
         oper.recv(&self.receivers[idx])
     }
+
+    pub fn recv_timeout(
+        &self,
+        timeout: Duration,
+    ) -> Result<(T, ErrorContext), channels::RecvTimeoutError> {
+        let mut selector = channels::Select::new();
+        self.receivers.iter().for_each(|r| {
+            selector.recv(r);
+        });
+        let oper = selector
+            .select_timeout(timeout)
+            .map_err(|_| channels::RecvTimeoutError::Timeout)?;
+        let idx = oper.index();
+        oper.recv(&self.receivers[idx])
+            .map_err(|_| channels::RecvTimeoutError::Disconnected)
+    }
 }
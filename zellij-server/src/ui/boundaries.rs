@@ -25,6 +25,17 @@ pub mod boundary_type {
     pub const HORIZONTAL_DOWN: &str = "┬";
     pub const HORIZONTAL_UP: &str = "┴";
     pub const CROSS: &str = "┼";
+    pub const TOP_RIGHT_HEAVY: &str = "┓";
+    pub const TOP_LEFT_HEAVY: &str = "┏";
+    pub const BOTTOM_RIGHT_HEAVY: &str = "┛";
+    pub const BOTTOM_LEFT_HEAVY: &str = "┗";
+    pub const VERTICAL_HEAVY: &str = "┃";
+    pub const HORIZONTAL_HEAVY: &str = "━";
+    pub const VERTICAL_LEFT_HEAVY: &str = "┫";
+    pub const VERTICAL_RIGHT_HEAVY: &str = "┣";
+    pub const VERTICAL_ASCII: &str = "|";
+    pub const HORIZONTAL_ASCII: &str = "-";
+    pub const CORNER_ASCII: &str = "+";
 }
 
 pub type BoundaryType = &'static str; // easy way to refer to boundary_type above
@@ -68,6 +79,7 @@ impl BoundarySymbol {
                 width: 1,
                 styles: RESET_STYLES
                     .foreground(self.color.map(|palette_color| palette_color.into())),
+                is_tab_fill: false,
             }
         };
         Ok(tc)
@@ -5,7 +5,10 @@
 //! prompt's:
 //!
 //! notification's:
+//!
+//! plugin overlays:
 
+pub mod plugin_overlay;
 pub mod prompt;
 
 use crate::ServerInstruction;
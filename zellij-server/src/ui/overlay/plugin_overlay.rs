@@ -0,0 +1,113 @@
+use zellij_utils::pane_size::Size;
+
+use super::Overlayable;
+use zellij_utils::errors::prelude::*;
+
+use std::fmt::Write;
+use unicode_width::UnicodeWidthChar;
+
+/// Contents a plugin draws on top of all panes at absolute screen coordinates, without owning a
+/// pane of its own (eg. an autocomplete popup)
+#[derive(Clone, Debug)]
+pub struct PluginOverlay {
+    x: usize,
+    y: usize,
+    width: usize,
+    height: usize,
+    contents: Vec<u8>,
+}
+
+impl PluginOverlay {
+    pub fn new(x: usize, y: usize, width: usize, height: usize, contents: Vec<u8>) -> Self {
+        PluginOverlay {
+            x,
+            y,
+            width,
+            height,
+            contents,
+        }
+    }
+}
+
+impl Overlayable for PluginOverlay {
+    fn generate_overlay(&self, size: Size) -> Result<String> {
+        let mut output = String::new();
+        if self.x >= size.cols || self.y >= size.rows {
+            return Ok(output);
+        }
+        let width = std::cmp::min(self.width, size.cols - self.x);
+        let height = std::cmp::min(self.height, size.rows - self.y);
+        let contents = String::from_utf8_lossy(&self.contents);
+        let mut lines = contents.lines();
+        // we save and restore the cursor so that it stays where the focused pane placed it
+        output.push_str("\u{1b}7");
+        for line_index in 0..height {
+            let line = lines.next().unwrap_or("");
+            write!(
+                &mut output,
+                "\u{1b}[{};{}H\u{1b}[m{}",
+                self.y + line_index + 1,
+                self.x + 1,
+                truncate_and_pad_line(line, width),
+            )
+            .context("failed to generate VTE output from plugin overlay")?;
+        }
+        output.push_str("\u{1b}[m\u{1b}8");
+        Ok(output)
+    }
+}
+
+fn truncate_and_pad_line(line: &str, width: usize) -> String {
+    // styling (SGR) escape sequences are kept as they are, only the printable characters count
+    // towards the width. Any other escape sequence or control character is dropped, since the
+    // overlay is written straight to the client's terminal and should not be able to move the
+    // cursor out of its bounds or change the terminal's state.
+    let mut truncated_line = String::new();
+    let mut line_width = 0;
+    let mut characters = line.chars().peekable();
+    while let Some(character) = characters.next() {
+        if character == '\u{1b}' {
+            match characters.next() {
+                Some('[') => {
+                    let mut sequence = String::from("\u{1b}[");
+                    for sequence_character in characters.by_ref() {
+                        sequence.push(sequence_character);
+                        if ('@'..='~').contains(&sequence_character) {
+                            break;
+                        }
+                    }
+                    if sequence.ends_with('m') {
+                        truncated_line.push_str(&sequence);
+                    }
+                },
+                Some(']') | Some('P') | Some('_') | Some('^') | Some('X') => {
+                    // string sequences (eg. OSC) end with BEL or ST (ESC \)
+                    while let Some(sequence_character) = characters.next() {
+                        if sequence_character == '\u{7}'
+                            || (sequence_character == '\u{1b}'
+                                && characters.next_if_eq(&'\\').is_some())
+                        {
+                            break;
+                        }
+                    }
+                },
+                _ => {},
+            }
+            continue;
+        }
+        if character.is_control() {
+            continue;
+        }
+        let character_width = character.width().unwrap_or(0);
+        if line_width + character_width > width {
+            break;
+        }
+        truncated_line.push(character);
+        line_width += character_width;
+    }
+    truncated_line.push_str("\u{1b}[m");
+    for _ in line_width..width {
+        truncated_line.push(' ');
+    }
+    truncated_line
+}
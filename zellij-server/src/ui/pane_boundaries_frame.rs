@@ -4,6 +4,7 @@ use crate::ui::boundaries::boundary_type;
 use crate::ClientId;
 use zellij_utils::data::{client_id_to_colors, PaletteColor, Style};
 use zellij_utils::errors::prelude::*;
+use zellij_utils::input::options::PaneFrameStyle;
 use zellij_utils::pane_size::Viewport;
 
 use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
@@ -29,6 +30,7 @@ fn foreground_color(characters: &str, color: Option<PaletteColor>) -> Vec<Termin
             character,
             styles,
             width: character.width().unwrap_or(0),
+            is_tab_fill: false,
         };
         colored_string.push(terminal_character);
     }
@@ -56,6 +58,7 @@ fn background_color(characters: &str, color: Option<PaletteColor>) -> Vec<Termin
             character,
             styles,
             width: character.width().unwrap_or(0),
+            is_tab_fill: false,
         };
         colored_string.push(terminal_character);
     }
@@ -78,6 +81,7 @@ pub struct FrameParams {
     pub pane_is_stacked_under: bool,
     pub pane_is_stacked_over: bool,
     pub should_draw_pane_frames: bool,
+    pub pane_frame_style: Option<PaneFrameStyle>, // None draws thin lines
 }
 
 #[derive(Default, PartialEq)]
@@ -93,9 +97,11 @@ pub struct PaneFrame {
     pub other_focused_clients: Vec<ClientId>,
     exit_status: Option<ExitStatus>,
     is_first_run: bool,
+    is_read_only: bool,
     pane_is_stacked_over: bool,
     pane_is_stacked_under: bool,
     should_draw_pane_frames: bool,
+    pane_frame_style: Option<PaneFrameStyle>,
 }
 
 impl PaneFrame {
@@ -117,9 +123,11 @@ impl PaneFrame {
             other_cursors_exist_in_session: frame_params.other_cursors_exist_in_session,
             exit_status: None,
             is_first_run: false,
+            is_read_only: false,
             pane_is_stacked_over: frame_params.pane_is_stacked_over,
             pane_is_stacked_under: frame_params.pane_is_stacked_under,
             should_draw_pane_frames: frame_params.should_draw_pane_frames,
+            pane_frame_style: frame_params.pane_frame_style,
         }
     }
     pub fn add_exit_status(&mut self, exit_status: Option<i32>) {
@@ -131,6 +139,9 @@ impl PaneFrame {
     pub fn indicate_first_run(&mut self) {
         self.is_first_run = true;
     }
+    pub fn indicate_read_only(&mut self) {
+        self.is_read_only = true;
+    }
     pub fn override_color(&mut self, color: PaletteColor) {
         self.color = Some(color);
     }
@@ -150,16 +161,46 @@ impl PaneFrame {
         } else {
             corner
         };
-        if self.style.rounded_corners {
-            match corner {
+        self.glyph(corner)
+    }
+    /// The character `boundary` (one of the thin line characters in `boundary_type`) is drawn
+    /// with in the style of this frame
+    fn glyph(&self, boundary: &'static str) -> &'static str {
+        let pane_frame_style = match self.pane_frame_style {
+            Some(pane_frame_style) => pane_frame_style,
+            None if self.style.rounded_corners => PaneFrameStyle::UnicodeRounded,
+            None => return boundary,
+        };
+        match pane_frame_style {
+            PaneFrameStyle::UnicodeRounded => match boundary {
                 boundary_type::TOP_RIGHT => boundary_type::TOP_RIGHT_ROUND,
                 boundary_type::TOP_LEFT => boundary_type::TOP_LEFT_ROUND,
                 boundary_type::BOTTOM_RIGHT => boundary_type::BOTTOM_RIGHT_ROUND,
                 boundary_type::BOTTOM_LEFT => boundary_type::BOTTOM_LEFT_ROUND,
-                _ => corner,
-            }
-        } else {
-            corner
+                _ => boundary,
+            },
+            PaneFrameStyle::UnicodeHeavy => match boundary {
+                boundary_type::TOP_RIGHT => boundary_type::TOP_RIGHT_HEAVY,
+                boundary_type::TOP_LEFT => boundary_type::TOP_LEFT_HEAVY,
+                boundary_type::BOTTOM_RIGHT => boundary_type::BOTTOM_RIGHT_HEAVY,
+                boundary_type::BOTTOM_LEFT => boundary_type::BOTTOM_LEFT_HEAVY,
+                boundary_type::VERTICAL => boundary_type::VERTICAL_HEAVY,
+                boundary_type::HORIZONTAL => boundary_type::HORIZONTAL_HEAVY,
+                boundary_type::VERTICAL_LEFT => boundary_type::VERTICAL_LEFT_HEAVY,
+                boundary_type::VERTICAL_RIGHT => boundary_type::VERTICAL_RIGHT_HEAVY,
+                _ => boundary,
+            },
+            PaneFrameStyle::Ascii => match boundary {
+                boundary_type::TOP_RIGHT
+                | boundary_type::TOP_LEFT
+                | boundary_type::BOTTOM_RIGHT
+                | boundary_type::BOTTOM_LEFT => boundary_type::CORNER_ASCII,
+                boundary_type::VERTICAL
+                | boundary_type::VERTICAL_LEFT
+                | boundary_type::VERTICAL_RIGHT => boundary_type::VERTICAL_ASCII,
+                boundary_type::HORIZONTAL => boundary_type::HORIZONTAL_ASCII,
+                _ => boundary,
+            },
         }
     }
     fn render_title_right_side(
@@ -167,6 +208,25 @@ impl PaneFrame {
         max_length: usize,
     ) -> Option<(Vec<TerminalCharacter>, usize)> {
         // string and length because of color
+        let read_only_indication = " READ ONLY ";
+        let read_only_indication_len = read_only_indication.chars().count();
+        if !self.is_read_only || read_only_indication_len > max_length {
+            return self.render_scroll_indication(max_length);
+        }
+        let mut right_side = foreground_color(read_only_indication, self.color);
+        let mut right_side_len = read_only_indication_len;
+        if let Some((mut scroll_indication, scroll_indication_len)) =
+            self.render_scroll_indication(max_length - read_only_indication_len)
+        {
+            right_side.append(&mut scroll_indication);
+            right_side_len += scroll_indication_len;
+        }
+        Some((right_side, right_side_len))
+    }
+    fn render_scroll_indication(
+        &self,
+        max_length: usize,
+    ) -> Option<(Vec<TerminalCharacter>, usize)> {
         if self.scroll_position.0 > 0 || self.scroll_position.1 > 0 {
             let prefix = " SCROLL: ";
             let full_indication =
@@ -198,8 +258,10 @@ impl PaneFrame {
         }
     }
     fn render_my_focus(&self, max_length: usize) -> Option<(Vec<TerminalCharacter>, usize)> {
-        let mut left_separator = foreground_color(boundary_type::VERTICAL_LEFT, self.color);
-        let mut right_separator = foreground_color(boundary_type::VERTICAL_RIGHT, self.color);
+        let mut left_separator =
+            foreground_color(self.glyph(boundary_type::VERTICAL_LEFT), self.color);
+        let mut right_separator =
+            foreground_color(self.glyph(boundary_type::VERTICAL_RIGHT), self.color);
         let full_indication_text = "MY FOCUS";
         let mut full_indication = vec![];
         full_indication.append(&mut left_separator);
@@ -228,8 +290,10 @@ impl PaneFrame {
         &self,
         max_length: usize,
     ) -> Option<(Vec<TerminalCharacter>, usize)> {
-        let mut left_separator = foreground_color(boundary_type::VERTICAL_LEFT, self.color);
-        let mut right_separator = foreground_color(boundary_type::VERTICAL_RIGHT, self.color);
+        let mut left_separator =
+            foreground_color(self.glyph(boundary_type::VERTICAL_LEFT), self.color);
+        let mut right_separator =
+            foreground_color(self.glyph(boundary_type::VERTICAL_RIGHT), self.color);
         let full_indication_text = "MY FOCUS AND:";
         let short_indication_text = "+";
         let mut full_indication = foreground_color(full_indication_text, self.color);
@@ -270,8 +334,10 @@ impl PaneFrame {
         &self,
         max_length: usize,
     ) -> Option<(Vec<TerminalCharacter>, usize)> {
-        let mut left_separator = foreground_color(boundary_type::VERTICAL_LEFT, self.color);
-        let mut right_separator = foreground_color(boundary_type::VERTICAL_RIGHT, self.color);
+        let mut left_separator =
+            foreground_color(self.glyph(boundary_type::VERTICAL_LEFT), self.color);
+        let mut right_separator =
+            foreground_color(self.glyph(boundary_type::VERTICAL_RIGHT), self.color);
         let full_indication_text = if self.other_focused_clients.len() == 1 {
             "FOCUSED USER:"
         } else {
@@ -448,7 +514,10 @@ impl PaneFrame {
                 col += right_side_len;
                 continue;
             } else {
-                title_line.append(&mut foreground_color(boundary_type::HORIZONTAL, self.color));
+                title_line.append(&mut foreground_color(
+                    self.glyph(boundary_type::HORIZONTAL),
+                    self.color,
+                ));
             }
             if col == self.geom.x + self.geom.cols - 1 {
                 break;
@@ -490,7 +559,10 @@ impl PaneFrame {
                 col += *middle_len;
                 continue;
             } else {
-                title_line.append(&mut foreground_color(boundary_type::HORIZONTAL, self.color));
+                title_line.append(&mut foreground_color(
+                    self.glyph(boundary_type::HORIZONTAL),
+                    self.color,
+                ));
             }
             if col == self.geom.x + self.geom.cols - 1 {
                 break;
@@ -525,7 +597,10 @@ impl PaneFrame {
                 col += *middle_len;
                 continue;
             } else {
-                title_line.append(&mut foreground_color(boundary_type::HORIZONTAL, self.color));
+                title_line.append(&mut foreground_color(
+                    self.glyph(boundary_type::HORIZONTAL),
+                    self.color,
+                ));
             }
             if col == self.geom.x + self.geom.cols - 1 {
                 break;
@@ -548,7 +623,7 @@ impl PaneFrame {
         let total_title_length = self.geom.cols.saturating_sub(2); // 2 for the left and right corners
         let mut middle = String::new();
         for _ in (left_side_len + right_side_len)..total_title_length {
-            middle.push_str(boundary_type::HORIZONTAL);
+            middle.push_str(self.glyph(boundary_type::HORIZONTAL));
         }
         let mut ret = vec![];
         ret.append(&mut left_boundary);
@@ -570,7 +645,7 @@ impl PaneFrame {
         let total_title_length = self.geom.cols.saturating_sub(2); // 2 for the left and right corners
         let mut middle_padding = String::new();
         for _ in *left_side_len..total_title_length {
-            middle_padding.push_str(boundary_type::HORIZONTAL);
+            middle_padding.push_str(self.glyph(boundary_type::HORIZONTAL));
         }
         let mut ret = vec![];
         ret.append(&mut left_boundary);
@@ -587,7 +662,7 @@ impl PaneFrame {
         let total_title_length = self.geom.cols.saturating_sub(2); // 2 for the left and right corners
         let mut middle_padding = String::new();
         for _ in 0..total_title_length {
-            middle_padding.push_str(boundary_type::HORIZONTAL);
+            middle_padding.push_str(self.glyph(boundary_type::HORIZONTAL));
         }
         let mut ret = vec![];
         ret.append(&mut left_boundary);
@@ -668,7 +743,7 @@ impl PaneFrame {
                 // render exit status and tips
                 let mut padding = String::new();
                 for _ in full_text_len..max_undertitle_length {
-                    padding.push_str(boundary_type::HORIZONTAL);
+                    padding.push_str(self.glyph(boundary_type::HORIZONTAL));
                 }
                 let mut ret = vec![];
                 ret.append(&mut left_boundary);
@@ -681,7 +756,7 @@ impl PaneFrame {
                 // render only exit status
                 let mut padding = String::new();
                 for _ in first_part_len..max_undertitle_length {
-                    padding.push_str(boundary_type::HORIZONTAL);
+                    padding.push_str(self.glyph(boundary_type::HORIZONTAL));
                 }
                 let mut ret = vec![];
                 ret.append(&mut left_boundary);
@@ -698,7 +773,7 @@ impl PaneFrame {
                 let full_text_len = first_part_len;
                 let mut padding = String::new();
                 for _ in full_text_len..max_undertitle_length {
-                    padding.push_str(boundary_type::HORIZONTAL);
+                    padding.push_str(self.glyph(boundary_type::HORIZONTAL));
                 }
                 let mut ret = vec![];
                 ret.append(&mut left_boundary);
@@ -754,7 +829,7 @@ impl PaneFrame {
                                 // bottom right corner
                                 self.get_corner(boundary_type::BOTTOM_RIGHT)
                             } else {
-                                boundary_type::HORIZONTAL
+                                self.glyph(boundary_type::HORIZONTAL)
                             };
 
                             let mut boundary_character = foreground_color(boundary, self.color);
@@ -766,9 +841,9 @@ impl PaneFrame {
                     }
                 } else {
                     let boundary_character_left =
-                        foreground_color(boundary_type::VERTICAL, self.color);
+                        foreground_color(self.glyph(boundary_type::VERTICAL), self.color);
                     let boundary_character_right =
-                        foreground_color(boundary_type::VERTICAL, self.color);
+                        foreground_color(self.glyph(boundary_type::VERTICAL), self.color);
 
                     let x = self.geom.x;
                     let y = self.geom.y + row;
@@ -827,7 +902,10 @@ impl PaneFrame {
                     left_bracket.len() + exited_text.len() + right_bracket.len(),
                 )
             },
-            None => (foreground_color(boundary_type::HORIZONTAL, self.color), 1),
+            None => (
+                foreground_color(self.glyph(boundary_type::HORIZONTAL), self.color),
+                1,
+            ),
         }
     }
     fn second_held_title_part_full(&self) -> (Vec<TerminalCharacter>, usize) {
@@ -898,7 +976,7 @@ impl PaneFrame {
         let mut ret = vec![];
         let mut padding = String::new();
         for _ in 0..max_undertitle_length {
-            padding.push_str(boundary_type::HORIZONTAL);
+            padding.push_str(self.glyph(boundary_type::HORIZONTAL));
         }
         ret.append(&mut left_boundary);
         ret.append(&mut foreground_color(&padding, self.color));
@@ -1,13 +1,14 @@
-use crate::output::Output;
+use crate::output::{CharacterChunk, Output};
 use crate::tab::Pane;
 use crate::ui::boundaries::Boundaries;
 use crate::ui::pane_boundaries_frame::FrameParams;
 use crate::ClientId;
 use std::collections::HashMap;
 use zellij_utils::data::{
-    client_id_to_colors, single_client_color, InputMode, PaletteColor, PaneId, Style,
+    client_id_to_colors, single_client_color, InputMode, Palette, PaletteColor, PaneId, Style,
 };
 use zellij_utils::errors::prelude::*;
+use zellij_utils::input::options::PaneFrameStyle;
 pub struct PaneContentsAndUi<'a> {
     pane: &'a mut Box<dyn Pane>,
     output: &'a mut Output,
@@ -18,6 +19,9 @@ pub struct PaneContentsAndUi<'a> {
     pane_is_stacked_under: bool,
     pane_is_stacked_over: bool,
     should_draw_pane_frames: bool,
+    focus_dimming: Option<f64>,
+    client_themes: &'a HashMap<ClientId, Palette>,
+    pane_frame_style: Option<PaneFrameStyle>,
 }
 
 impl<'a> PaneContentsAndUi<'a> {
@@ -31,6 +35,9 @@ impl<'a> PaneContentsAndUi<'a> {
         pane_is_stacked_under: bool,
         pane_is_stacked_over: bool,
         should_draw_pane_frames: bool,
+        focus_dimming: Option<f64>,
+        client_themes: &'a HashMap<ClientId, Palette>,
+        pane_frame_style: Option<PaneFrameStyle>,
     ) -> Self {
         let mut focused_clients: Vec<ClientId> = active_panes
             .iter()
@@ -48,6 +55,9 @@ impl<'a> PaneContentsAndUi<'a> {
             pane_is_stacked_under,
             pane_is_stacked_over,
             should_draw_pane_frames,
+            focus_dimming,
+            client_themes,
+            pane_frame_style,
         }
     }
     pub fn render_pane_contents_to_multiple_clients(
@@ -60,10 +70,45 @@ impl<'a> PaneContentsAndUi<'a> {
             self.pane.render(None).context(err_context)?
         {
             let clients: Vec<ClientId> = clients.collect();
+            let (undimmed_clients, dimmed_clients): (Vec<ClientId>, Vec<ClientId>) =
+                if self.focus_dimming.is_some() {
+                    clients
+                        .iter()
+                        .copied()
+                        .partition(|client_id| self.focused_clients.contains(client_id))
+                } else {
+                    (clients.clone(), vec![])
+                };
+            // clients with their own theme dim towards their own background
+            let (themed_dimmed_clients, dimmed_clients): (Vec<ClientId>, Vec<ClientId>) =
+                dimmed_clients
+                    .into_iter()
+                    .partition(|client_id| self.client_themes.contains_key(client_id));
+            for client_id in themed_dimmed_clients {
+                self.output
+                    .add_character_chunks_to_client(
+                        client_id,
+                        self.dimmed_character_chunks(
+                            &character_chunks,
+                            &self.style_for_client(client_id).colors,
+                        ),
+                        self.z_index,
+                    )
+                    .context(err_context)?;
+            }
+            if !dimmed_clients.is_empty() {
+                self.output
+                    .add_character_chunks_to_multiple_clients(
+                        self.dimmed_character_chunks(&character_chunks, &self.style.colors),
+                        dimmed_clients.into_iter(),
+                        self.z_index,
+                    )
+                    .context(err_context)?;
+            }
             self.output
                 .add_character_chunks_to_multiple_clients(
                     character_chunks,
-                    clients.iter().copied(),
+                    undimmed_clients.into_iter(),
                     self.z_index,
                 )
                 .context(err_context)?;
@@ -96,6 +141,13 @@ impl<'a> PaneContentsAndUi<'a> {
             .render(Some(client_id))
             .with_context(err_context)?
         {
+            let character_chunks =
+                if self.focus_dimming.is_some() && !self.focused_clients.contains(&client_id) {
+                    let colors = self.style_for_client(client_id).colors;
+                    self.dimmed_character_chunks(&character_chunks, &colors)
+                } else {
+                    character_chunks
+                };
             self.output
                 .add_character_chunks_to_client(client_id, character_chunks, self.z_index)
                 .with_context(err_context)?;
@@ -118,6 +170,28 @@ impl<'a> PaneContentsAndUi<'a> {
         }
         Ok(())
     }
+    fn style_for_client(&self, client_id: ClientId) -> Style {
+        let mut style = self.style;
+        if let Some(theme) = self.client_themes.get(&client_id) {
+            style.colors = *theme;
+        }
+        style
+    }
+    fn dimmed_character_chunks(
+        &self,
+        character_chunks: &[CharacterChunk],
+        colors: &Palette,
+    ) -> Vec<CharacterChunk> {
+        let amount = self.focus_dimming.unwrap_or(0.0);
+        character_chunks
+            .iter()
+            .cloned()
+            .map(|mut character_chunk| {
+                character_chunk.dim(amount, colors);
+                character_chunk
+            })
+            .collect()
+    }
     pub fn render_fake_cursor_if_needed(&mut self, client_id: ClientId) -> Result<()> {
         let pane_focused_for_client_id = self.focused_clients.contains(&client_id);
         let pane_focused_for_different_client = self
@@ -134,7 +208,8 @@ impl<'a> PaneContentsAndUi<'a> {
                 .with_context(|| {
                     format!("failed to render fake cursor if needed for client {client_id}")
                 })?;
-            if let Some(colors) = client_id_to_colors(*fake_cursor_client_id, self.style.colors) {
+            let client_colors = self.style_for_client(client_id).colors;
+            if let Some(colors) = client_id_to_colors(*fake_cursor_client_id, client_colors) {
                 if let Some(vte_output) = self.pane.render_fake_cursor(colors.0, colors.1) {
                     self.output.add_post_vte_instruction_to_client(
                         client_id,
@@ -199,24 +274,26 @@ impl<'a> PaneContentsAndUi<'a> {
                 focused_client,
                 is_main_client: pane_focused_for_client_id,
                 other_focused_clients: vec![],
-                style: self.style,
+                style: self.style_for_client(client_id),
                 color: frame_color,
                 other_cursors_exist_in_session: false,
                 pane_is_stacked_over: self.pane_is_stacked_over,
                 pane_is_stacked_under: self.pane_is_stacked_under,
                 should_draw_pane_frames: self.should_draw_pane_frames,
+                pane_frame_style: self.pane_frame_style,
             }
         } else {
             FrameParams {
                 focused_client,
                 is_main_client: pane_focused_for_client_id,
                 other_focused_clients,
-                style: self.style,
+                style: self.style_for_client(client_id),
                 color: frame_color,
                 other_cursors_exist_in_session: self.multiple_users_exist_in_session,
                 pane_is_stacked_over: self.pane_is_stacked_over,
                 pane_is_stacked_under: self.pane_is_stacked_under,
                 should_draw_pane_frames: self.should_draw_pane_frames,
+                pane_frame_style: self.pane_frame_style,
             }
         };
 
@@ -252,20 +329,21 @@ impl<'a> PaneContentsAndUi<'a> {
         session_is_mirrored: bool,
     ) -> Option<PaletteColor> {
         let pane_focused_for_client_id = self.focused_clients.contains(&client_id);
+        let colors = self.style_for_client(client_id).colors;
         if let Some(override_color) = self.pane.frame_color_override() {
             Some(override_color)
         } else if pane_focused_for_client_id {
             match mode {
                 InputMode::Normal | InputMode::Locked => {
                     if session_is_mirrored || !self.multiple_users_exist_in_session {
-                        let colors = single_client_color(self.style.colors); // mirrored sessions only have one focused color
+                        let colors = single_client_color(colors); // mirrored sessions only have one focused color
                         Some(colors.0)
                     } else {
-                        let colors = client_id_to_colors(client_id, self.style.colors);
+                        let colors = client_id_to_colors(client_id, colors);
                         colors.map(|colors| colors.0)
                     }
                 },
-                _ => Some(self.style.colors.orange),
+                _ => Some(colors.orange),
             }
         } else {
             None
@@ -0,0 +1,52 @@
+use crate::output::CharacterChunk;
+use crate::panes::{AnsiCode, CharacterStyles, TerminalCharacter};
+use zellij_utils::data::PaletteColor;
+
+fn ruler_character(index: usize, styles: CharacterStyles) -> TerminalCharacter {
+    // we only have room for one character per line/column, so we show the last digit of the
+    // index - this is enough to count and align by eye
+    let character = std::char::from_digit((index % 10) as u32, 10).unwrap_or(' ');
+    TerminalCharacter {
+        character,
+        styles,
+        width: 1,
+        is_tab_fill: false,
+    }
+}
+
+fn ruler_styles(index: usize, color: PaletteColor) -> CharacterStyles {
+    let mut styles = CharacterStyles::new();
+    styles.reset_all();
+    if index % 10 == 0 {
+        // every tenth index is emphasized so that it's easier to count
+        styles
+            .background(Some(AnsiCode::from(color)))
+            .bold(Some(AnsiCode::On))
+    } else {
+        styles.foreground(Some(AnsiCode::from(color)))
+    }
+}
+
+/// Returns the character chunks of a ruler overlaid on the content area at the given
+/// coordinates: column indices along the top line and line indices along the leftmost column
+pub fn ruler_character_chunks(
+    x: usize,
+    y: usize,
+    columns: usize,
+    rows: usize,
+    color: PaletteColor,
+) -> Vec<CharacterChunk> {
+    let mut character_chunks = vec![];
+    if columns == 0 || rows == 0 {
+        return character_chunks;
+    }
+    let column_indices = (0..columns)
+        .map(|column_index| ruler_character(column_index, ruler_styles(column_index, color)))
+        .collect();
+    character_chunks.push(CharacterChunk::new(column_indices, x, y));
+    for line_index in 1..rows {
+        let line_indication = vec![ruler_character(line_index, ruler_styles(line_index, color))];
+        character_chunks.push(CharacterChunk::new(line_indication, x, y + line_index));
+    }
+    character_chunks
+}
@@ -1,6 +1,14 @@
 use super::*;
 
+use crate::plugins::PluginInstruction;
+use crate::pty::pane_quit_cb;
+use crate::screen::ScreenInstruction;
+use crate::thread_bus::ThreadSenders;
 use nix::{pty::openpty, unistd::close};
+use std::time::Duration;
+use zellij_utils::channels::SenderWithContext;
+use zellij_utils::data::Event;
+use zellij_utils::errors::ErrorContext;
 
 struct TestTerminal {
     openpty: OpenptyResult,
@@ -40,6 +48,7 @@ fn get_cwd() {
         client_senders: Arc::default(),
         terminal_id_to_raw_fd: Arc::default(),
         cached_resizes: Arc::default(),
+        session_env: Arc::default(),
     };
 
     let pid = nix::unistd::getpid();
@@ -49,3 +58,205 @@ fn get_cwd() {
         pid
     );
 }
+
+fn spawn_command_with_pane_quit_cb(
+    run_command: RunCommand,
+) -> (
+    channels::Receiver<(ScreenInstruction, ErrorContext)>,
+    channels::Receiver<(PluginInstruction, ErrorContext)>,
+) {
+    spawn_command_in_session_with_pane_quit_cb(run_command, BTreeMap::new())
+}
+
+fn spawn_command_in_session_with_pane_quit_cb(
+    run_command: RunCommand,
+    session_env: BTreeMap<String, String>,
+) -> (
+    channels::Receiver<(ScreenInstruction, ErrorContext)>,
+    channels::Receiver<(PluginInstruction, ErrorContext)>,
+) {
+    let test_terminal = TestTerminal::new();
+    let test_termios =
+        termios::tcgetattr(test_terminal.slave()).expect("Could not configure the termios");
+    let server = ServerOsInputOutput {
+        orig_termios: Arc::new(Mutex::new(test_termios)),
+        client_senders: Arc::default(),
+        terminal_id_to_raw_fd: Arc::default(),
+        cached_resizes: Arc::default(),
+        session_env: Arc::default(),
+    };
+    server.set_session_env(session_env);
+    let (to_screen, screen_receiver) = channels::unbounded();
+    let (to_plugin, plugin_receiver) = channels::unbounded();
+    let senders = ThreadSenders {
+        to_screen: Some(SenderWithContext::new(to_screen)),
+        to_plugin: Some(SenderWithContext::new(to_plugin)),
+        ..Default::default()
+    };
+    let hold_on_close = run_command.hold_on_close;
+    server
+        .spawn_terminal(
+            TerminalAction::RunCommand(run_command),
+            pane_quit_cb(senders, hold_on_close, None),
+            None,
+        )
+        .expect("failed to spawn command");
+    (screen_receiver, plugin_receiver)
+}
+
+#[test]
+fn pane_closed_event_is_sent_with_exit_status_when_process_exits() {
+    let (screen_receiver, plugin_receiver) = spawn_command_with_pane_quit_cb(RunCommand {
+        command: PathBuf::from("false"),
+        hold_on_close: true,
+        ..Default::default()
+    });
+    let (plugin_instruction, _) = plugin_receiver
+        .recv_timeout(Duration::from_secs(5))
+        .expect("no instruction was sent to plugins");
+    let plugin_event = match plugin_instruction {
+        PluginInstruction::Update(mut updates) => updates.pop().map(|(_, _, event)| event),
+        _ => None,
+    };
+    assert_eq!(
+        plugin_event,
+        Some(Event::PaneClosed(PaneId::Terminal(0), Some(1))),
+        "PaneClosed event sent to plugins with the exit status of the process"
+    );
+    let (screen_instruction, _) = screen_receiver
+        .recv_timeout(Duration::from_secs(5))
+        .expect("no instruction was sent to screen");
+    assert!(
+        matches!(
+            screen_instruction,
+            ScreenInstruction::HoldPane(PaneId::Terminal(_), Some(1), ..)
+        ),
+        "pane held open when it should not close on exit"
+    );
+}
+
+#[test]
+fn pane_is_closed_when_process_exits_and_it_closes_on_exit() {
+    let (screen_receiver, plugin_receiver) = spawn_command_with_pane_quit_cb(RunCommand {
+        command: PathBuf::from("true"),
+        hold_on_close: false,
+        ..Default::default()
+    });
+    let (plugin_instruction, _) = plugin_receiver
+        .recv_timeout(Duration::from_secs(5))
+        .expect("no instruction was sent to plugins");
+    let plugin_event = match plugin_instruction {
+        PluginInstruction::Update(mut updates) => updates.pop().map(|(_, _, event)| event),
+        _ => None,
+    };
+    assert_eq!(
+        plugin_event,
+        Some(Event::PaneClosed(PaneId::Terminal(0), Some(0))),
+        "PaneClosed event sent to plugins with the exit status of the process"
+    );
+    let (screen_instruction, _) = screen_receiver
+        .recv_timeout(Duration::from_secs(5))
+        .expect("no instruction was sent to screen");
+    assert!(
+        matches!(
+            screen_instruction,
+            ScreenInstruction::ClosePane(PaneId::Terminal(_), None)
+        ),
+        "pane closed once its process exited"
+    );
+}
+
+#[test]
+fn command_pane_process_sees_its_env_variables() {
+    let run_command = RunCommand {
+        command: PathBuf::from("sh"),
+        args: vec![
+            "-c".to_owned(),
+            "test \"$ZELLIJ_TEST_PANE_ENV\" = \"from-layout\"".to_owned(),
+        ],
+        hold_on_close: true,
+        env: BTreeMap::from([("ZELLIJ_TEST_PANE_ENV".to_owned(), "from-layout".to_owned())]),
+        ..Default::default()
+    };
+    let (_screen_receiver, plugin_receiver) = spawn_command_with_pane_quit_cb(run_command);
+    let (plugin_instruction, _) = plugin_receiver
+        .recv_timeout(Duration::from_secs(5))
+        .expect("no instruction was sent to plugins");
+    let plugin_event = match plugin_instruction {
+        PluginInstruction::Update(mut updates) => updates.pop().map(|(_, _, event)| event),
+        _ => None,
+    };
+    assert_eq!(
+        plugin_event,
+        Some(Event::PaneClosed(PaneId::Terminal(0), Some(0))),
+        "process exited successfully, so it saw the env variable"
+    );
+}
+
+#[test]
+fn pane_env_variables_take_precedence_over_the_session_env_variables() {
+    let session_env = BTreeMap::from([
+        (
+            "ZELLIJ_TEST_SESSION_ENV".to_owned(),
+            "from-session".to_owned(),
+        ),
+        ("ZELLIJ_TEST_PANE_ENV".to_owned(), "from-session".to_owned()),
+    ]);
+    let run_command = RunCommand {
+        command: PathBuf::from("sh"),
+        args: vec![
+            "-c".to_owned(),
+            "test \"$ZELLIJ_TEST_SESSION_ENV\" = \"from-session\" \
+             && test \"$ZELLIJ_TEST_PANE_ENV\" = \"from-layout\""
+                .to_owned(),
+        ],
+        hold_on_close: true,
+        env: BTreeMap::from([("ZELLIJ_TEST_PANE_ENV".to_owned(), "from-layout".to_owned())]),
+        ..Default::default()
+    };
+    let (_screen_receiver, plugin_receiver) =
+        spawn_command_in_session_with_pane_quit_cb(run_command, session_env);
+    let (plugin_instruction, _) = plugin_receiver
+        .recv_timeout(Duration::from_secs(5))
+        .expect("no instruction was sent to plugins");
+    let plugin_event = match plugin_instruction {
+        PluginInstruction::Update(mut updates) => updates.pop().map(|(_, _, event)| event),
+        _ => None,
+    };
+    assert_eq!(
+        plugin_event,
+        Some(Event::PaneClosed(PaneId::Terminal(0), Some(0))),
+        "process saw the session env variable, and the pane's own env variable took precedence"
+    );
+}
+
+#[test]
+fn pane_without_env_variables_inherits_the_session_env_variables() {
+    let session_env = BTreeMap::from([(
+        "ZELLIJ_TEST_SESSION_ENV".to_owned(),
+        "from-session".to_owned(),
+    )]);
+    let run_command = RunCommand {
+        command: PathBuf::from("sh"),
+        args: vec![
+            "-c".to_owned(),
+            "test \"$ZELLIJ_TEST_SESSION_ENV\" = \"from-session\"".to_owned(),
+        ],
+        hold_on_close: true,
+        ..Default::default()
+    };
+    let (_screen_receiver, plugin_receiver) =
+        spawn_command_in_session_with_pane_quit_cb(run_command, session_env);
+    let (plugin_instruction, _) = plugin_receiver
+        .recv_timeout(Duration::from_secs(5))
+        .expect("no instruction was sent to plugins");
+    let plugin_event = match plugin_instruction {
+        PluginInstruction::Update(mut updates) => updates.pop().map(|(_, _, event)| event),
+        _ => None,
+    };
+    assert_eq!(
+        plugin_event,
+        Some(Event::PaneClosed(PaneId::Terminal(0), Some(0))),
+        "process exited successfully, so it inherited the session env variable"
+    );
+}
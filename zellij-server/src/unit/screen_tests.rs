@@ -1,46 +1,61 @@
-use super::{screen_thread_main, CopyOptions, Screen, ScreenInstruction};
+use super::{
+    screen_thread_main, CopyOptions, PanePredicate, Screen, ScreenInstruction, SwapLayoutDirection,
+    Workspace,
+};
 use crate::{
     channels::SenderWithContext,
     os_input_output::{AsyncReader, Pid, ServerOsApi},
-    route::route_action,
+    output::{Output, RenderedGrid},
+    route::{route_action, route_thread_main},
     thread_bus::Bus,
-    ClientId, ServerInstruction, SessionMetaData, ThreadSenders,
+    ui::overlay::{plugin_overlay::PluginOverlay, Overlayable},
+    ClientId, ServerInstruction, SessionMetaData, SessionState, ThreadSenders,
 };
 use insta::assert_snapshot;
 use std::path::PathBuf;
 use zellij_utils::cli::CliAction;
-use zellij_utils::data::{Event, PaneId, Resize, Style};
+use zellij_utils::data::{
+    CopyDestination, Event, Key, PaletteColor, PaneId, PermissionStatus, PermissionType,
+    PluginPermission, Resize, ResizePreset, ResizeStrategy, SelectionBounds, SessionActivity,
+    SessionActivityKind, SessionSharingStatus, Style,
+};
 use zellij_utils::errors::{prelude::*, ErrorContext};
 use zellij_utils::input::actions::Action;
 use zellij_utils::input::command::{RunCommand, TerminalAction};
 use zellij_utils::input::layout::{
     FloatingPaneLayout, Layout, Run, RunPlugin, RunPluginLocation, SplitDirection, TiledPaneLayout,
 };
-use zellij_utils::input::options::Options;
+use zellij_utils::input::options::{Bell, Options, PaneFrameStyle, ScrollLines, SurplusPanes};
 use zellij_utils::ipc::IpcReceiverWithContext;
 use zellij_utils::pane_size::{Size, SizeInPixels};
+use zellij_utils::position::Position;
+use zellij_utils::session_serialization::{serialize_session_layout, GlobalLayoutManifest};
 
 use crate::background_jobs::BackgroundJob;
 use crate::pty_writer::PtyWriteInstruction;
 use std::env::set_var;
 use std::os::unix::io::RawFd;
-use std::sync::{Arc, Mutex};
+use std::sync::{Arc, Mutex, RwLock};
 
-use crate::{plugins::PluginInstruction, pty::PtyInstruction};
+use crate::{
+    plugins::{ChangedLine, PaneOutputBuffer, PluginFrame, PluginInstruction, PluginRenderQueue},
+    pty::{ClientTabIndexOrPaneId, PtyInstruction},
+};
 use zellij_utils::ipc::PixelDimensions;
 
 use zellij_utils::{
     channels::{self, ChannelWithContext, Receiver},
     data::{Direction, InputMode, ModeInfo, Palette, PluginCapabilities},
-    interprocess::local_socket::LocalSocketStream,
-    ipc::{ClientAttributes, ClientToServerMsg, ServerToClientMsg},
+    interprocess::local_socket::{LocalSocketListener, LocalSocketStream},
+    ipc::{ClientAttributes, ClientToServerMsg, IpcSenderWithContext, ServerToClientMsg},
+    tempfile::tempdir,
 };
 
 use crate::panes::grid::Grid;
 use crate::panes::link_handler::LinkHandler;
 use crate::panes::sixel::SixelImageStore;
 use std::cell::RefCell;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::rc::Rc;
 use zellij_utils::vte;
 
@@ -68,6 +83,8 @@ fn take_snapshots_and_cursor_coordinates_from_render_events<'a>(
     let debug = false;
     let arrow_fonts = true;
     let styled_underlines = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
     let mut grid = Grid::new(
         screen_size.rows,
         screen_size.cols,
@@ -80,6 +97,8 @@ fn take_snapshots_and_cursor_coordinates_from_render_events<'a>(
         debug,
         arrow_fonts,
         styled_underlines,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
     );
     let snapshots: Vec<(Option<(usize, usize)>, String)> = all_events
         .filter_map(|server_instruction| {
@@ -133,6 +152,7 @@ fn send_cli_action_to_server(
 struct FakeInputOutput {
     fake_filesystem: Arc<Mutex<HashMap<String, String>>>,
     server_to_client_messages: Arc<Mutex<HashMap<ClientId, Vec<ServerToClientMsg>>>>,
+    reserved_terminal_ids: Arc<Mutex<HashSet<u32>>>,
 }
 
 impl ServerOsApi for FakeInputOutput {
@@ -221,6 +241,17 @@ impl ServerOsApi for FakeInputOutput {
     fn clear_terminal_id(&self, _terminal_id: u32) -> Result<()> {
         unimplemented!()
     }
+    fn reserve_terminal_ids(&self, terminal_ids: &[u32]) -> Result<()> {
+        let mut reserved_terminal_ids = self.reserved_terminal_ids.lock().unwrap();
+        if terminal_ids
+            .iter()
+            .any(|terminal_id| reserved_terminal_ids.contains(terminal_id))
+        {
+            return Err(anyhow!("terminal id taken"));
+        }
+        reserved_terminal_ids.extend(terminal_ids);
+        Ok(())
+    }
 }
 
 fn create_new_screen(size: Size) -> Screen {
@@ -243,10 +274,20 @@ fn create_new_screen(size: Size) -> Screen {
     let session_serialization = true;
     let serialize_pane_viewport = false;
     let scrollback_lines_to_serialize = None;
+    let serialize_successfully_exited_panes = true;
 
     let debug = false;
     let styled_underlines = true;
     let arrow_fonts = true;
+    let transparent_background = false;
+    let keep_scrollback_in_alternate_screen = false;
+    let session_activity_log_size = 1000;
+    let scroll_lines = ScrollLines::default();
+    let layout_undo_depth = 10;
+    let surplus_panes = SurplusPanes::default();
+    let bell = Bell::default();
+    let pane_frame_style = None;
+    let focus_dimming = None;
     let screen = Screen::new(
         bus,
         &client_attributes,
@@ -262,8 +303,18 @@ fn create_new_screen(size: Size) -> Screen {
         session_serialization,
         serialize_pane_viewport,
         scrollback_lines_to_serialize,
+        serialize_successfully_exited_panes,
         styled_underlines,
         arrow_fonts,
+        transparent_background,
+        keep_scrollback_in_alternate_screen,
+        session_activity_log_size,
+        scroll_lines,
+        layout_undo_depth,
+        surplus_panes,
+        bell,
+        pane_frame_style,
+        focus_dimming,
     );
     screen
 }
@@ -646,216 +697,657 @@ pub fn switch_to_tab_name() {
 }
 
 #[test]
-pub fn close_tab() {
+pub fn move_tab() {
     let size = Size {
         cols: 121,
         rows: 20,
     };
     let mut screen = create_new_screen(size);
 
-    new_tab(&mut screen, 1, 1);
-    new_tab(&mut screen, 2, 2);
-    screen.close_tab(1).expect("TEST");
+    new_tab(&mut screen, 1, 0);
+    new_tab(&mut screen, 2, 1);
+    new_tab(&mut screen, 3, 2);
+    screen.switch_tab_prev(None, true, 1).expect("TEST");
+    screen.move_tab(2, 0, 1).expect("TEST");
+
+    let tab_indices_by_position = |screen: &Screen| {
+        let mut tabs: Vec<(usize, usize)> = screen
+            .tabs
+            .values()
+            .map(|tab| (tab.position, tab.index))
+            .collect();
+        tabs.sort();
+        tabs.into_iter()
+            .map(|(_, index)| index)
+            .collect::<Vec<usize>>()
+    };
+    assert_eq!(
+        tab_indices_by_position(&screen),
+        vec![2, 0, 1],
+        "Last tab moved to the front"
+    );
+    assert_eq!(
+        screen.get_active_tab(1).unwrap().index,
+        2,
+        "Focus followed the moved tab"
+    );
+    assert_eq!(screen.get_active_tab(1).unwrap().position, 0);
+    assert_eq!(
+        screen.get_layout_metadata(None).all_terminal_ids(),
+        vec![3, 1, 2],
+        "Tabs dumped in the order of their position"
+    );
 
-    assert_eq!(screen.tabs.len(), 1, "Only one tab left");
+    screen.move_tab(0, 10, 1).expect("TEST");
     assert_eq!(
-        screen.get_active_tab(1).unwrap().position,
-        0,
-        "Active tab switched to previous tab"
+        tab_indices_by_position(&screen),
+        vec![0, 1, 2],
+        "Out of range position clamped to the last tab"
     );
+    assert_eq!(screen.get_active_tab(1).unwrap().position, 2);
 }
 
 #[test]
-pub fn close_the_middle_tab() {
+pub fn move_active_tab() {
     let size = Size {
         cols: 121,
         rows: 20,
     };
     let mut screen = create_new_screen(size);
 
-    new_tab(&mut screen, 1, 1);
-    new_tab(&mut screen, 2, 2);
-    new_tab(&mut screen, 3, 3);
-    screen.switch_tab_prev(None, true, 1).expect("TEST");
-    screen.close_tab(1).expect("TEST");
+    new_tab(&mut screen, 1, 0);
+    new_tab(&mut screen, 2, 1);
+    new_tab(&mut screen, 3, 2);
 
-    assert_eq!(screen.tabs.len(), 2, "Two tabs left");
+    let tab_indices_by_position = |screen: &Screen| {
+        let mut tabs: Vec<(usize, usize)> = screen
+            .tabs
+            .values()
+            .map(|tab| (tab.position, tab.index))
+            .collect();
+        tabs.sort();
+        tabs.into_iter()
+            .map(|(_, index)| index)
+            .collect::<Vec<usize>>()
+    };
+    screen.move_active_tab(Direction::Left, 1).expect("TEST");
     assert_eq!(
-        screen.get_active_tab(1).unwrap().position,
+        tab_indices_by_position(&screen),
+        vec![0, 2, 1],
+        "Active tab moved one position back"
+    );
+    screen.move_active_tab(Direction::Right, 1).expect("TEST");
+    screen.move_active_tab(Direction::Right, 1).expect("TEST");
+    assert_eq!(
+        tab_indices_by_position(&screen),
+        vec![2, 0, 1],
+        "Active tab moved forward past the last position wrapped around to the front"
+    );
+    assert_eq!(
+        screen.get_active_tab(1).unwrap().index,
+        2,
+        "Focus followed the moved tab"
+    );
+    screen.move_active_tab(Direction::Up, 1).expect("TEST");
+    assert_eq!(
+        tab_indices_by_position(&screen),
+        vec![0, 1, 2],
+        "Active tab moved back past the first position wrapped around to the end"
+    );
+}
+
+#[test]
+pub fn moved_tab_is_reported_to_plugins_in_its_new_position() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let client_id = 1;
+    let mut mock_screen = MockScreen::new(size);
+    let plugin_receiver = mock_screen.plugin_receiver.take().unwrap();
+    let screen_thread = mock_screen.run(None, vec![]);
+    let received_plugin_instructions = Arc::new(Mutex::new(vec![]));
+    let plugin_thread = log_actions_in_thread!(
+        received_plugin_instructions,
+        PluginInstruction::Exit,
+        plugin_receiver
+    );
+    let _ = mock_screen.to_screen.send(ScreenInstruction::NewTab(
+        None,
+        None,
+        Some(TiledPaneLayout::default()),
+        vec![],
+        None,
+        (vec![], vec![]),
+        client_id,
+    ));
+    let _ = mock_screen.to_screen.send(ScreenInstruction::ApplyLayout(
+        TiledPaneLayout::default(),
+        vec![],
+        vec![(1, None)],
+        vec![],
+        HashMap::new(),
         1,
-        "Active tab switched to previous tab"
+        client_id,
+    ));
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    let _ = mock_screen.to_screen.send(ScreenInstruction::MoveTab {
+        from_index: 1,
+        to_index: 0,
+        client_id,
+    });
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    mock_screen.teardown(vec![plugin_thread, screen_thread]);
+    let last_tab_update = received_plugin_instructions
+        .lock()
+        .unwrap()
+        .iter()
+        .filter_map(|instruction| match instruction {
+            PluginInstruction::Update(updates) => {
+                updates.iter().find_map(|(_, _, event)| match event {
+                    Event::TabUpdate(tab_infos) => Some(tab_infos.clone()),
+                    _ => None,
+                })
+            },
+            _ => None,
+        })
+        .last()
+        .unwrap();
+    assert_eq!(
+        last_tab_update
+            .iter()
+            .map(|tab_info| (tab_info.position, tab_info.active))
+            .collect::<Vec<_>>(),
+        vec![(0, true), (1, false)],
+        "the tabs are reported in the order of their position, the moved one first"
     );
 }
 
 #[test]
-fn move_focus_left_at_left_screen_edge_changes_tab() {
+pub fn close_panes_matching_exited_zero() {
     let size = Size {
         cols: 121,
         rows: 20,
     };
     let mut screen = create_new_screen(size);
+    let command = |command: &str| RunCommand {
+        command: PathBuf::from(command),
+        ..Default::default()
+    };
 
-    new_tab(&mut screen, 1, 1);
-    new_tab(&mut screen, 2, 2);
-    new_tab(&mut screen, 3, 3);
-    screen.switch_tab_prev(None, true, 1).expect("TEST");
-    screen.move_focus_left_or_previous_tab(1).expect("TEST");
+    new_tab(&mut screen, 1, 0);
+    {
+        let tab = screen.get_active_tab_mut(1).unwrap();
+        tab.new_pane(PaneId::Terminal(2), None, None, None, Some(1))
+            .unwrap();
+        tab.hold_pane(PaneId::Terminal(1), Some(0), false, command("true"));
+        tab.hold_pane(PaneId::Terminal(2), Some(1), false, command("false"));
+    }
+    new_tab(&mut screen, 3, 1);
+    screen.get_active_tab_mut(1).unwrap().hold_pane(
+        PaneId::Terminal(3),
+        Some(0),
+        false,
+        command("true"),
+    );
+
+    screen
+        .close_panes_matching(PanePredicate::ExitedZero, 1)
+        .expect("TEST");
 
+    assert_eq!(screen.tabs.len(), 1, "Tab left without panes was closed");
     assert_eq!(
-        screen.get_active_tab(1).unwrap().position,
-        0,
-        "Active tab switched to previous"
+        screen.tabs.get(&0).unwrap().get_all_pane_ids(),
+        vec![PaneId::Terminal(2)],
+        "Only the pane of the command that exited successfully was closed"
     );
 }
 
 #[test]
-fn move_focus_right_at_right_screen_edge_changes_tab() {
+pub fn set_scroll_lines_of_pane_in_any_tab() {
     let size = Size {
         cols: 121,
         rows: 20,
     };
     let mut screen = create_new_screen(size);
 
-    new_tab(&mut screen, 1, 1);
-    new_tab(&mut screen, 2, 2);
-    new_tab(&mut screen, 3, 3);
-    screen.switch_tab_prev(None, true, 1).expect("TEST");
-    screen.move_focus_right_or_next_tab(1).expect("TEST");
+    new_tab(&mut screen, 1, 0);
+    new_tab(&mut screen, 2, 1);
 
-    assert_eq!(
-        screen.get_active_tab(1).unwrap().position,
-        2,
-        "Active tab switched to next"
+    assert!(
+        screen
+            .set_pane_scroll_lines(PaneId::Terminal(1), Some(ScrollLines::HalfPage))
+            .is_ok(),
+        "Scroll lines set for a pane in an inactive tab"
+    );
+    assert!(
+        screen
+            .set_pane_scroll_lines(PaneId::Terminal(3), Some(ScrollLines::Lines(5)))
+            .is_err(),
+        "Scroll lines not set for a pane that does not exist"
     );
 }
 
 #[test]
-pub fn toggle_to_previous_tab_simple() {
-    let position_and_size = Size {
+pub fn split_pane_with_ratio_in_any_tab() {
+    let size = Size {
         cols: 121,
         rows: 20,
     };
-    let mut screen = create_new_screen(position_and_size);
+    let mut screen = create_new_screen(size);
 
-    new_tab(&mut screen, 1, 1);
-    new_tab(&mut screen, 2, 2);
-    screen.go_to_tab(1, 1).expect("TEST");
-    screen.go_to_tab(2, 1).expect("TEST");
+    new_tab(&mut screen, 1, 0);
+    new_tab(&mut screen, 2, 1);
 
-    screen.toggle_tab(1).expect("TEST");
-    assert_eq!(
-        screen.get_active_tab(1).unwrap().position,
-        0,
-        "Active tab toggler to previous tab"
+    assert!(
+        screen
+            .split_pane_with_ratio(
+                PaneId::Terminal(1),
+                PaneId::Terminal(3),
+                SplitDirection::Vertical,
+                0.3,
+                1
+            )
+            .is_ok(),
+        "Pane in an inactive tab was split"
     );
-
-    screen.toggle_tab(1).expect("TEST");
-    assert_eq!(
-        screen.get_active_tab(1).unwrap().position,
-        1,
-        "Active tab toggler to previous tab"
+    assert!(
+        screen
+            .tabs
+            .get(&0)
+            .unwrap()
+            .has_pane_with_pid(&PaneId::Terminal(3)),
+        "New pane was added next to the split pane"
+    );
+    assert!(
+        screen
+            .split_pane_with_ratio(
+                PaneId::Terminal(4),
+                PaneId::Terminal(5),
+                SplitDirection::Vertical,
+                0.3,
+                1
+            )
+            .is_err(),
+        "Pane that does not exist was not split"
     );
 }
 
 #[test]
-pub fn toggle_to_previous_tab_create_tabs_only() {
-    let position_and_size = Size {
+pub fn pane_id_or_focused_pane() {
+    let size = Size {
         cols: 121,
         rows: 20,
     };
-    let mut screen = create_new_screen(position_and_size);
+    let mut screen = create_new_screen(size);
 
     new_tab(&mut screen, 1, 0);
     new_tab(&mut screen, 2, 1);
-    new_tab(&mut screen, 3, 2);
 
     assert_eq!(
-        screen.tab_history.get(&1).unwrap(),
-        &[0, 1],
-        "Tab history is invalid"
+        screen.pane_id_or_focused_pane(None, 1).unwrap(),
+        PaneId::Terminal(2),
+        "Focused pane of the client in its active tab"
     );
-
-    screen.toggle_tab(1).expect("TEST");
     assert_eq!(
-        screen.get_active_tab(1).unwrap().position,
-        1,
-        "Active tab toggler to previous tab"
+        screen
+            .pane_id_or_focused_pane(Some(PaneId::Terminal(1)), 1)
+            .unwrap(),
+        PaneId::Terminal(1),
+        "Given pane rather than the focused one"
     );
     assert_eq!(
-        screen.tab_history.get(&1).unwrap(),
-        &[0, 2],
-        "Tab history is invalid"
+        screen.pane_id_or_focused_pane(None, 10).unwrap(),
+        PaneId::Terminal(2),
+        "Client that is not connected gets the focused pane of the first client"
     );
+}
 
-    screen.toggle_tab(1).expect("TEST");
+#[test]
+pub fn close_tab() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let mut screen = create_new_screen(size);
+
+    new_tab(&mut screen, 1, 1);
+    new_tab(&mut screen, 2, 2);
+    screen.close_tab(1).expect("TEST");
+
+    assert_eq!(screen.tabs.len(), 1, "Only one tab left");
     assert_eq!(
         screen.get_active_tab(1).unwrap().position,
-        2,
-        "Active tab toggler to previous tab"
-    );
-    assert_eq!(
-        screen.tab_history.get(&1).unwrap(),
-        &[0, 1],
-        "Tab history is invalid"
+        0,
+        "Active tab switched to previous tab"
     );
+}
 
-    screen.toggle_tab(1).expect("TEST");
+#[test]
+pub fn close_the_middle_tab() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let mut screen = create_new_screen(size);
+
+    new_tab(&mut screen, 1, 1);
+    new_tab(&mut screen, 2, 2);
+    new_tab(&mut screen, 3, 3);
+    screen.switch_tab_prev(None, true, 1).expect("TEST");
+    screen.close_tab(1).expect("TEST");
+
+    assert_eq!(screen.tabs.len(), 2, "Two tabs left");
     assert_eq!(
         screen.get_active_tab(1).unwrap().position,
         1,
-        "Active tab toggler to previous tab"
+        "Active tab switched to previous tab"
     );
 }
 
 #[test]
-pub fn toggle_to_previous_tab_delete() {
-    let position_and_size = Size {
+pub fn undo_closing_a_pane_restores_the_tiled_panes() {
+    let size = Size {
         cols: 121,
         rows: 20,
     };
-    let mut screen = create_new_screen(position_and_size);
+    let mut screen = create_new_screen(size);
 
     new_tab(&mut screen, 1, 0);
-    new_tab(&mut screen, 2, 1);
-    new_tab(&mut screen, 3, 2);
-    new_tab(&mut screen, 4, 3);
-
-    assert_eq!(
-        screen.tab_history.get(&1).unwrap(),
-        &[0, 1, 2],
-        "Tab history is invalid"
-    );
-    assert_eq!(
-        screen.get_active_tab(1).unwrap().position,
-        3,
-        "Active tab toggler to previous tab"
-    );
+    let active_tab = screen.get_active_tab_mut(1).unwrap();
+    active_tab
+        .vertical_split(PaneId::Terminal(2), None, 1)
+        .unwrap();
+    active_tab
+        .horizontal_split(PaneId::Terminal(3), None, 1)
+        .unwrap();
+    let mut tiled_pane_geoms = active_tab.tiled_pane_geoms();
+    tiled_pane_geoms.sort_by_key(|(pane_id, _)| *pane_id);
 
-    screen.toggle_tab(1).expect("TEST");
+    screen.close_focused_pane(1).expect("TEST");
     assert_eq!(
-        screen.tab_history.get(&1).unwrap(),
-        &[0, 1, 3],
-        "Tab history is invalid"
-    );
-    assert_eq!(
-        screen.get_active_tab(1).unwrap().position,
+        screen.get_active_tab(1).unwrap().get_tiled_pane_ids().len(),
         2,
-        "Active tab toggler to previous tab"
+        "Pane closed"
     );
 
-    screen.toggle_tab(1).expect("TEST");
+    screen.undo_layout_change(1).expect("TEST");
+    let active_tab = screen.get_active_tab(1).unwrap();
+    let mut restored_tiled_pane_geoms = active_tab.tiled_pane_geoms();
+    restored_tiled_pane_geoms.sort_by_key(|(pane_id, _)| *pane_id);
     assert_eq!(
-        screen.tab_history.get(&1).unwrap(),
-        &[0, 1, 2],
-        "Tab history is invalid"
+        restored_tiled_pane_geoms, tiled_pane_geoms,
+        "Panes have the positions and sizes they had before the pane was closed"
     );
     assert_eq!(
-        screen.get_active_tab(1).unwrap().position,
-        3,
-        "Active tab toggler to previous tab"
+        active_tab.get_active_pane_id(1),
+        Some(PaneId::Terminal(3)),
+        "Restored pane focused"
     );
-
-    screen.switch_tab_prev(None, true, 1).expect("TEST");
+    assert!(
+        screen.undo_layout_change(1).is_err(),
+        "Nothing left to undo"
+    );
+}
+
+#[test]
+pub fn undo_closing_a_pane_reserves_its_terminal_id_again() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let mut screen = create_new_screen(size);
+    let fake_os_input = FakeInputOutput::default();
+    screen.bus.os_input = Some(Box::new(fake_os_input.clone()));
+
+    new_tab(&mut screen, 1, 0);
+    screen
+        .get_active_tab_mut(1)
+        .unwrap()
+        .vertical_split(PaneId::Terminal(2), None, 1)
+        .unwrap();
+    screen.close_focused_pane(1).expect("TEST");
+    screen.undo_layout_change(1).expect("TEST");
+    assert!(
+        fake_os_input
+            .reserved_terminal_ids
+            .lock()
+            .unwrap()
+            .contains(&2),
+        "terminal id of the restored pane reserved again"
+    );
+}
+
+#[test]
+pub fn undo_closing_a_pane_whose_terminal_id_was_taken_fails() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let mut screen = create_new_screen(size);
+    let fake_os_input = FakeInputOutput::default();
+    screen.bus.os_input = Some(Box::new(fake_os_input.clone()));
+
+    new_tab(&mut screen, 1, 0);
+    screen
+        .get_active_tab_mut(1)
+        .unwrap()
+        .vertical_split(PaneId::Terminal(2), None, 1)
+        .unwrap();
+    screen.close_focused_pane(1).expect("TEST");
+    // a new terminal took the id of the closed pane in the meantime
+    fake_os_input
+        .reserved_terminal_ids
+        .lock()
+        .unwrap()
+        .insert(2);
+
+    assert!(
+        screen.undo_layout_change(1).is_err(),
+        "pane not restored over the terminal that took its id"
+    );
+    assert_eq!(
+        screen.get_active_tab(1).unwrap().get_all_pane_ids(),
+        vec![PaneId::Terminal(1)],
+        "only the remaining pane is in the tab"
+    );
+}
+
+#[test]
+pub fn undo_closing_a_tab_brings_it_back_in_its_position() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let mut screen = create_new_screen(size);
+
+    new_tab(&mut screen, 1, 1);
+    new_tab(&mut screen, 2, 2);
+    new_tab(&mut screen, 3, 3);
+    screen.switch_tab_prev(None, true, 1).expect("TEST");
+    screen.close_tab(1).expect("TEST");
+    assert_eq!(screen.tabs.len(), 2, "Two tabs left");
+
+    screen.undo_layout_change(1).expect("TEST");
+    assert_eq!(screen.tabs.len(), 3, "Tab brought back");
+    let active_tab = screen.get_active_tab(1).unwrap();
+    assert_eq!(active_tab.position, 1, "Tab back in its position");
+    assert_eq!(
+        active_tab.get_all_pane_ids(),
+        vec![PaneId::Terminal(2)],
+        "Tab brought back with its pane"
+    );
+}
+
+#[test]
+fn move_focus_left_at_left_screen_edge_changes_tab() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let mut screen = create_new_screen(size);
+
+    new_tab(&mut screen, 1, 1);
+    new_tab(&mut screen, 2, 2);
+    new_tab(&mut screen, 3, 3);
+    screen.switch_tab_prev(None, true, 1).expect("TEST");
+    screen.move_focus_left_or_previous_tab(1).expect("TEST");
+
+    assert_eq!(
+        screen.get_active_tab(1).unwrap().position,
+        0,
+        "Active tab switched to previous"
+    );
+}
+
+#[test]
+fn move_focus_right_at_right_screen_edge_changes_tab() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let mut screen = create_new_screen(size);
+
+    new_tab(&mut screen, 1, 1);
+    new_tab(&mut screen, 2, 2);
+    new_tab(&mut screen, 3, 3);
+    screen.switch_tab_prev(None, true, 1).expect("TEST");
+    screen.move_focus_right_or_next_tab(1).expect("TEST");
+
+    assert_eq!(
+        screen.get_active_tab(1).unwrap().position,
+        2,
+        "Active tab switched to next"
+    );
+}
+
+#[test]
+pub fn toggle_to_previous_tab_simple() {
+    let position_and_size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let mut screen = create_new_screen(position_and_size);
+
+    new_tab(&mut screen, 1, 1);
+    new_tab(&mut screen, 2, 2);
+    screen.go_to_tab(1, 1).expect("TEST");
+    screen.go_to_tab(2, 1).expect("TEST");
+
+    screen.toggle_tab(1).expect("TEST");
+    assert_eq!(
+        screen.get_active_tab(1).unwrap().position,
+        0,
+        "Active tab toggler to previous tab"
+    );
+
+    screen.toggle_tab(1).expect("TEST");
+    assert_eq!(
+        screen.get_active_tab(1).unwrap().position,
+        1,
+        "Active tab toggler to previous tab"
+    );
+}
+
+#[test]
+pub fn toggle_to_previous_tab_create_tabs_only() {
+    let position_and_size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let mut screen = create_new_screen(position_and_size);
+
+    new_tab(&mut screen, 1, 0);
+    new_tab(&mut screen, 2, 1);
+    new_tab(&mut screen, 3, 2);
+
+    assert_eq!(
+        screen.tab_history.get(&1).unwrap(),
+        &[0, 1],
+        "Tab history is invalid"
+    );
+
+    screen.toggle_tab(1).expect("TEST");
+    assert_eq!(
+        screen.get_active_tab(1).unwrap().position,
+        1,
+        "Active tab toggler to previous tab"
+    );
+    assert_eq!(
+        screen.tab_history.get(&1).unwrap(),
+        &[0, 2],
+        "Tab history is invalid"
+    );
+
+    screen.toggle_tab(1).expect("TEST");
+    assert_eq!(
+        screen.get_active_tab(1).unwrap().position,
+        2,
+        "Active tab toggler to previous tab"
+    );
+    assert_eq!(
+        screen.tab_history.get(&1).unwrap(),
+        &[0, 1],
+        "Tab history is invalid"
+    );
+
+    screen.toggle_tab(1).expect("TEST");
+    assert_eq!(
+        screen.get_active_tab(1).unwrap().position,
+        1,
+        "Active tab toggler to previous tab"
+    );
+}
+
+#[test]
+pub fn toggle_to_previous_tab_delete() {
+    let position_and_size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let mut screen = create_new_screen(position_and_size);
+
+    new_tab(&mut screen, 1, 0);
+    new_tab(&mut screen, 2, 1);
+    new_tab(&mut screen, 3, 2);
+    new_tab(&mut screen, 4, 3);
+
+    assert_eq!(
+        screen.tab_history.get(&1).unwrap(),
+        &[0, 1, 2],
+        "Tab history is invalid"
+    );
+    assert_eq!(
+        screen.get_active_tab(1).unwrap().position,
+        3,
+        "Active tab toggler to previous tab"
+    );
+
+    screen.toggle_tab(1).expect("TEST");
+    assert_eq!(
+        screen.tab_history.get(&1).unwrap(),
+        &[0, 1, 3],
+        "Tab history is invalid"
+    );
+    assert_eq!(
+        screen.get_active_tab(1).unwrap().position,
+        2,
+        "Active tab toggler to previous tab"
+    );
+
+    screen.toggle_tab(1).expect("TEST");
+    assert_eq!(
+        screen.tab_history.get(&1).unwrap(),
+        &[0, 1, 2],
+        "Tab history is invalid"
+    );
+    assert_eq!(
+        screen.get_active_tab(1).unwrap().position,
+        3,
+        "Active tab toggler to previous tab"
+    );
+
+    screen.switch_tab_prev(None, true, 1).expect("TEST");
     assert_eq!(
         screen.tab_history.get(&1).unwrap(),
         &[0, 1, 3],
@@ -940,485 +1432,3504 @@ fn switch_to_tab_with_fullscreen() {
 }
 
 #[test]
-fn update_screen_pixel_dimensions() {
+fn focus_pane_by_id_in_another_tab() {
     let size = Size {
         cols: 121,
         rows: 20,
     };
     let mut screen = create_new_screen(size);
-    let initial_pixel_dimensions = screen.pixel_dimensions;
-    screen.update_pixel_dimensions(PixelDimensions {
-        character_cell_size: Some(SizeInPixels {
-            height: 10,
-            width: 5,
-        }),
-        text_area_size: None,
-    });
-    let pixel_dimensions_after_first_update = screen.pixel_dimensions;
-    screen.update_pixel_dimensions(PixelDimensions {
-        character_cell_size: None,
-        text_area_size: Some(SizeInPixels {
-            height: 100,
-            width: 50,
-        }),
-    });
-    let pixel_dimensions_after_second_update = screen.pixel_dimensions;
-    screen.update_pixel_dimensions(PixelDimensions {
-        character_cell_size: None,
-        text_area_size: None,
-    });
-    let pixel_dimensions_after_third_update = screen.pixel_dimensions;
-    assert_eq!(
-        initial_pixel_dimensions,
-        PixelDimensions {
-            character_cell_size: None,
-            text_area_size: None
-        },
-        "Initial pixel dimensions empty"
-    );
+
+    new_tab(&mut screen, 1, 1);
+    screen
+        .get_active_tab_mut(1)
+        .unwrap()
+        .new_pane(PaneId::Terminal(2), None, None, None, Some(1))
+        .unwrap();
+    new_tab(&mut screen, 3, 2);
+
+    screen
+        .focus_pane_with_id(PaneId::Terminal(1), false, 1)
+        .expect("TEST");
+
     assert_eq!(
-        pixel_dimensions_after_first_update,
-        PixelDimensions {
-            character_cell_size: Some(SizeInPixels {
-                height: 10,
-                width: 5
-            }),
-            text_area_size: None
-        },
-        "character_cell_size updated properly",
+        screen.get_active_tab(1).unwrap().position,
+        0,
+        "Active tab switched to the one containing the pane"
     );
     assert_eq!(
-        pixel_dimensions_after_second_update,
-        PixelDimensions {
-            character_cell_size: Some(SizeInPixels {
-                height: 10,
-                width: 5
-            }),
-            text_area_size: Some(SizeInPixels {
-                height: 100,
-                width: 50,
-            }),
-        },
-        "text_area_size updated properly without overriding character_cell_size",
+        screen
+            .get_active_tab(1)
+            .unwrap()
+            .get_active_pane_id(1)
+            .unwrap(),
+        PaneId::Terminal(1),
+        "Pane focused by its id"
+    );
+}
+
+#[test]
+fn focus_pane_by_id_that_does_not_exist() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let mut screen = create_new_screen(size);
+
+    new_tab(&mut screen, 1, 1);
+    new_tab(&mut screen, 2, 2);
+
+    assert!(
+        screen
+            .focus_pane_with_id(PaneId::Terminal(3), false, 1)
+            .is_err(),
+        "Focusing a pane that does not exist is an error"
     );
     assert_eq!(
-        pixel_dimensions_after_third_update,
-        PixelDimensions {
-            character_cell_size: Some(SizeInPixels {
-                height: 10,
-                width: 5
-            }),
-            text_area_size: Some(SizeInPixels {
-                height: 100,
-                width: 50,
-            }),
-        },
-        "empty update does not delete existing data",
+        screen.get_active_tab(1).unwrap().position,
+        1,
+        "Active tab did not change"
     );
 }
 
 #[test]
-fn attach_after_first_tab_closed() {
-    // ensure https://github.com/zellij-org/zellij/issues/1645 is fixed
+fn focus_pane_by_name_across_tabs() {
     let size = Size {
         cols: 121,
         rows: 20,
     };
     let mut screen = create_new_screen(size);
 
-    new_tab(&mut screen, 1, 0);
+    new_tab(&mut screen, 1, 1);
     {
-        let active_tab = screen.get_active_tab_mut(1).unwrap();
-        active_tab
-            .new_pane(PaneId::Terminal(2), None, None, None, Some(1))
+        let tab = screen.get_active_tab_mut(1).unwrap();
+        tab.rename_pane("editor".as_bytes().to_vec(), PaneId::Terminal(1))
+            .unwrap();
+        tab.new_pane(PaneId::Terminal(2), None, None, None, Some(1))
             .unwrap();
-        active_tab.toggle_active_pane_fullscreen(1);
     }
-    new_tab(&mut screen, 2, 1);
+    new_tab(&mut screen, 3, 2);
+    {
+        let tab = screen.get_active_tab_mut(1).unwrap();
+        tab.rename_pane("editor".as_bytes().to_vec(), PaneId::Terminal(3))
+            .unwrap();
+        tab.new_pane(PaneId::Terminal(4), None, Some(true), None, Some(1))
+            .unwrap();
+        tab.rename_pane("logs".as_bytes().to_vec(), PaneId::Terminal(4))
+            .unwrap();
+        tab.toggle_floating_panes(Some(1), None).unwrap();
+    }
+    screen
+        .focus_pane_with_id(PaneId::Terminal(2), false, 1)
+        .unwrap();
 
-    screen.close_tab_at_index(0).expect("TEST");
-    screen.remove_client(1).expect("TEST");
-    screen.add_client(1).expect("TEST");
-}
+    screen.focus_pane_by_name("logs", 1).expect("TEST");
+    assert_eq!(
+        screen.get_active_tab(1).unwrap().position,
+        1,
+        "Active tab switched to the one containing the floating pane"
+    );
+    assert_eq!(
+        screen
+            .get_active_tab(1)
+            .unwrap()
+            .get_active_pane_id(1)
+            .unwrap(),
+        PaneId::Terminal(4),
+        "Floating pane focused by its name"
+    );
+    assert!(
+        screen
+            .get_active_tab(1)
+            .unwrap()
+            .are_floating_panes_visible(),
+        "Floating panes shown when focusing one of them"
+    );
 
-// Following are tests for sending CLI actions
-// these tests are only partially relevant to Screen
-// and are included here for two reasons:
-// 1. The best way to "integration test" these is combining the "screen_thread_main" and
-//    "route_action" functions and mocking everything around them
-// 2. These inadvertently also test many parts of Screen that are not tested elsewhere
+    screen.focus_pane_by_name("editor", 1).expect("TEST");
+    assert_eq!(
+        screen.get_active_tab(1).unwrap().position,
+        0,
+        "Active tab switched to the first one containing a pane with this name"
+    );
+    assert_eq!(
+        screen
+            .get_active_tab(1)
+            .unwrap()
+            .get_active_pane_id(1)
+            .unwrap(),
+        PaneId::Terminal(1),
+        "First pane with this name in tab order focused"
+    );
+
+    assert!(
+        screen.focus_pane_by_name("missing", 1).is_err(),
+        "Focusing a pane name that does not exist is an error"
+    );
+    assert_eq!(
+        screen.get_active_tab(1).unwrap().position,
+        0,
+        "Active tab did not change"
+    );
+}
 
 #[test]
-pub fn send_cli_write_chars_action_to_screen() {
+fn go_to_tab_by_name() {
     let size = Size {
         cols: 121,
         rows: 20,
     };
-    let client_id = 10; // fake client id should not appear in the screen's state
-    let mut mock_screen = MockScreen::new(size);
-    let pty_writer_receiver = mock_screen.pty_writer_receiver.take().unwrap();
-    let session_metadata = mock_screen.clone_session_metadata();
-    let screen_thread = mock_screen.run(None, vec![]);
-    let received_pty_instructions = Arc::new(Mutex::new(vec![]));
-    let pty_writer_thread = log_actions_in_thread!(
-        received_pty_instructions,
-        PtyWriteInstruction::Exit,
-        pty_writer_receiver
+    let mut screen = create_new_screen(size);
+
+    for (pid, tab_index, name) in [(1, 1, "editor"), (2, 2, "logs"), (3, 3, "logs")] {
+        new_tab(&mut screen, pid, tab_index);
+        screen.update_active_tab_name(vec![0], 1).expect("TEST");
+        screen
+            .update_active_tab_name(name.as_bytes().to_vec(), 1)
+            .expect("TEST");
+    }
+    // moving the tabs around changes their indices, but not their names
+    screen.move_tab(0, 2, 1).expect("TEST");
+
+    screen.go_to_tab_by_name("editor", 1).expect("TEST");
+    assert_eq!(
+        screen.get_active_tab(1).unwrap().name,
+        "editor",
+        "Went to the tab with this name"
+    );
+    assert_eq!(screen.get_active_tab(1).unwrap().position, 2);
+
+    screen.go_to_tab_by_name("logs", 1).expect("TEST");
+    assert_eq!(
+        screen.get_active_tab(1).unwrap().position,
+        0,
+        "Went to the first tab with this name"
+    );
+
+    assert!(
+        screen.go_to_tab_by_name("missing", 1).is_err(),
+        "Going to a tab name that does not exist is an error"
+    );
+    assert_eq!(screen.tabs.len(), 3, "No tab created");
+    assert_eq!(
+        screen.get_active_tab(1).unwrap().position,
+        0,
+        "Active tab did not change"
     );
-    let cli_action = CliAction::WriteChars {
-        chars: "input from the cli".into(),
-    };
-    send_cli_action_to_server(&session_metadata, cli_action, client_id);
-    std::thread::sleep(std::time::Duration::from_millis(100)); // give time for actions to be
-    mock_screen.teardown(vec![pty_writer_thread, screen_thread]);
-    assert_snapshot!(format!("{:?}", *received_pty_instructions.lock().unwrap()));
 }
 
 #[test]
-pub fn send_cli_write_action_to_screen() {
+fn apply_layout_to_tab_spawns_only_missing_panes() {
     let size = Size {
         cols: 121,
         rows: 20,
     };
-    let client_id = 10; // fake client id should not appear in the screen's state
+    let client_id = 1;
     let mut mock_screen = MockScreen::new(size);
-    let pty_writer_receiver = mock_screen.pty_writer_receiver.take().unwrap();
-    let session_metadata = mock_screen.clone_session_metadata();
-    let screen_thread = mock_screen.run(None, vec![]);
-    let received_pty_instructions = Arc::new(Mutex::new(vec![]));
-    let pty_writer_thread = log_actions_in_thread!(
-        received_pty_instructions,
-        PtyWriteInstruction::Exit,
-        pty_writer_receiver
-    );
-    let cli_action = CliAction::Write {
-        bytes: vec![102, 111, 111],
-    };
-    send_cli_action_to_server(&session_metadata, cli_action, client_id);
-    std::thread::sleep(std::time::Duration::from_millis(100)); // give time for actions to be
-    mock_screen.teardown(vec![pty_writer_thread, screen_thread]);
-    assert_snapshot!(format!("{:?}", *received_pty_instructions.lock().unwrap()));
-}
-
-#[test]
-pub fn send_cli_resize_action_to_screen() {
-    let size = Size { cols: 80, rows: 20 };
-    let client_id = 10; // fake client id should not appear in the screen's state
+    let pty_receiver = mock_screen.pty_receiver.take().unwrap();
     let mut initial_layout = TiledPaneLayout::default();
     initial_layout.children_split_direction = SplitDirection::Vertical;
     initial_layout.children = vec![TiledPaneLayout::default(), TiledPaneLayout::default()];
-    let mut mock_screen = MockScreen::new(size);
-    let session_metadata = mock_screen.clone_session_metadata();
     let screen_thread = mock_screen.run(Some(initial_layout), vec![]);
-    let received_server_instructions = Arc::new(Mutex::new(vec![]));
-    let server_receiver = mock_screen.server_receiver.take().unwrap();
-    let pty_writer_thread = log_actions_in_thread!(
-        received_server_instructions,
-        ServerInstruction::KillSession,
-        server_receiver
+    let received_pty_instructions = Arc::new(Mutex::new(vec![]));
+    let pty_thread = log_actions_in_thread!(
+        received_pty_instructions,
+        PtyInstruction::Exit,
+        pty_receiver
     );
-    let resize_cli_action = CliAction::Resize {
-        resize: Resize::Increase,
-        direction: Some(Direction::Left),
-    };
-    send_cli_action_to_server(&session_metadata, resize_cli_action, client_id);
-    mock_screen.teardown(vec![pty_writer_thread, screen_thread]);
-    let snapshots = take_snapshots_and_cursor_coordinates_from_render_events(
-        received_server_instructions.lock().unwrap().iter(),
-        size,
+    let mut three_pane_layout = TiledPaneLayout::default();
+    three_pane_layout.children_split_direction = SplitDirection::Horizontal;
+    three_pane_layout.children = vec![
+        TiledPaneLayout::default(),
+        TiledPaneLayout::default(),
+        TiledPaneLayout::default(),
+    ];
+    let _ = mock_screen
+        .to_screen
+        .send(ScreenInstruction::ApplyLayoutToTab {
+            tab_index: Some(0),
+            layout: three_pane_layout,
+            client_id,
+        });
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    mock_screen.teardown(vec![pty_thread, screen_thread]);
+    let spawned_terminals = received_pty_instructions
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|instruction| match instruction {
+            PtyInstruction::SpawnTerminalsForLayoutInPlace(run_instructions, 0) => {
+                run_instructions.len()
+            },
+            _ => 0,
+        })
+        .sum::<usize>();
+    assert_eq!(
+        spawned_terminals, 1,
+        "only the slot the existing panes do not fill gets a new pane"
     );
-    let snapshot_count = snapshots.len();
-    for (_cursor_coordinates, snapshot) in snapshots {
-        assert_snapshot!(format!("{}", snapshot));
-    }
-    assert_snapshot!(format!("{}", snapshot_count));
+    let closed_panes = received_pty_instructions
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|instruction| matches!(instruction, PtyInstruction::ClosePane(..)))
+        .count();
+    assert_eq!(closed_panes, 0, "existing panes were kept");
 }
 
 #[test]
-pub fn send_cli_focus_next_pane_action() {
-    let size = Size { cols: 80, rows: 20 };
+fn apply_layout_to_active_tab_of_cli_client() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
     let client_id = 10; // fake client id should not appear in the screen's state
+    let mut mock_screen = MockScreen::new(size);
+    let pty_receiver = mock_screen.pty_receiver.take().unwrap();
     let mut initial_layout = TiledPaneLayout::default();
     initial_layout.children_split_direction = SplitDirection::Vertical;
     initial_layout.children = vec![TiledPaneLayout::default(), TiledPaneLayout::default()];
-    let mut mock_screen = MockScreen::new(size);
-    let session_metadata = mock_screen.clone_session_metadata();
     let screen_thread = mock_screen.run(Some(initial_layout), vec![]);
-    let received_server_instructions = Arc::new(Mutex::new(vec![]));
-    let server_receiver = mock_screen.server_receiver.take().unwrap();
-    let server_instruction = log_actions_in_thread!(
-        received_server_instructions,
-        ServerInstruction::KillSession,
-        server_receiver
+    let received_pty_instructions = Arc::new(Mutex::new(vec![]));
+    let pty_thread = log_actions_in_thread!(
+        received_pty_instructions,
+        PtyInstruction::Exit,
+        pty_receiver
     );
-    let focus_next_pane_action = CliAction::FocusNextPane;
-    send_cli_action_to_server(&session_metadata, focus_next_pane_action, client_id);
+    let mut three_pane_layout = TiledPaneLayout::default();
+    three_pane_layout.children_split_direction = SplitDirection::Horizontal;
+    three_pane_layout.children = vec![
+        TiledPaneLayout::default(),
+        TiledPaneLayout::default(),
+        TiledPaneLayout::default(),
+    ];
+    let _ = mock_screen
+        .to_screen
+        .send(ScreenInstruction::ApplyLayoutToTab {
+            tab_index: None,
+            layout: three_pane_layout,
+            client_id,
+        });
     std::thread::sleep(std::time::Duration::from_millis(100));
-    mock_screen.teardown(vec![server_instruction, screen_thread]);
-    let snapshots = take_snapshots_and_cursor_coordinates_from_render_events(
-        received_server_instructions.lock().unwrap().iter(),
-        size,
+    mock_screen.teardown(vec![pty_thread, screen_thread]);
+    let spawned_terminals = received_pty_instructions
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|instruction| match instruction {
+            PtyInstruction::SpawnTerminalsForLayoutInPlace(run_instructions, 0) => {
+                run_instructions.len()
+            },
+            _ => 0,
+        })
+        .sum::<usize>();
+    assert_eq!(
+        spawned_terminals, 1,
+        "layout applied to the active tab of the first connected client"
     );
-    let snapshot_count = snapshots.len();
-    for (cursor_coordinates, _snapshot) in snapshots {
-        // here we assert he cursor_coordinates to let us know if we switched the pane focus
-        assert_snapshot!(format!("{:?}", cursor_coordinates));
-    }
-    assert_snapshot!(format!("{}", snapshot_count));
 }
 
 #[test]
-pub fn send_cli_focus_previous_pane_action() {
-    let size = Size { cols: 80, rows: 20 };
-    let client_id = 10; // fake client id should not appear in the screen's state
-    let mut initial_layout = TiledPaneLayout::default();
-    initial_layout.children_split_direction = SplitDirection::Vertical;
-    initial_layout.children = vec![TiledPaneLayout::default(), TiledPaneLayout::default()];
-    let mut mock_screen = MockScreen::new(size);
-    let session_metadata = mock_screen.clone_session_metadata();
-    let screen_thread = mock_screen.run(Some(initial_layout), vec![]);
-    let received_server_instructions = Arc::new(Mutex::new(vec![]));
-    let server_receiver = mock_screen.server_receiver.take().unwrap();
-    let server_instruction = log_actions_in_thread!(
-        received_server_instructions,
-        ServerInstruction::KillSession,
-        server_receiver
-    );
-    let focus_next_pane_action = CliAction::FocusPreviousPane;
-    send_cli_action_to_server(&session_metadata, focus_next_pane_action, client_id);
-    std::thread::sleep(std::time::Duration::from_millis(100));
-    mock_screen.teardown(vec![server_instruction, screen_thread]);
-    let snapshots = take_snapshots_and_cursor_coordinates_from_render_events(
-        received_server_instructions.lock().unwrap().iter(),
-        size,
+fn layout_applied_in_place_is_filled_only_with_the_panes_opened_for_it() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let client_id = 1;
+    let mut screen = create_new_screen(size);
+    new_tab(&mut screen, 1, 0);
+    let mut stacked_layout = TiledPaneLayout::default();
+    stacked_layout.children_split_direction = SplitDirection::Horizontal;
+    stacked_layout.children = vec![
+        TiledPaneLayout::default(),
+        TiledPaneLayout::default(),
+        TiledPaneLayout::default(),
+    ];
+    screen.apply_layout_to_tab(0, stacked_layout).expect("TEST");
+    // a pane the user opens before the panes of the layout are opened
+    screen
+        .get_active_tab_mut(client_id)
+        .unwrap()
+        .new_pane(PaneId::Terminal(2), None, None, None, Some(client_id))
+        .unwrap();
+    let pane_geom = |screen: &Screen, pane_id: PaneId| {
+        screen
+            .get_active_tab(client_id)
+            .unwrap()
+            .tiled_pane_geoms()
+            .into_iter()
+            .find(|(id, _)| *id == pane_id)
+            .map(|(_, geom)| geom)
+            .unwrap()
+    };
+    assert_eq!(
+        pane_geom(&screen, PaneId::Terminal(1)).y,
+        pane_geom(&screen, PaneId::Terminal(2)).y,
+        "the pane the user opened does not fill a slot of the layout"
     );
-    let snapshot_count = snapshots.len();
-    for (cursor_coordinates, _snapshot) in snapshots {
-        // here we assert he cursor_coordinates to let us know if we switched the pane focus
-        assert_snapshot!(format!("{:?}", cursor_coordinates));
-    }
-    assert_snapshot!(format!("{}", snapshot_count));
-}
 
-#[test]
-pub fn send_cli_move_focus_pane_action() {
-    let size = Size { cols: 80, rows: 20 };
-    let client_id = 10; // fake client id should not appear in the screen's state
-    let mut initial_layout = TiledPaneLayout::default();
-    initial_layout.children_split_direction = SplitDirection::Vertical;
-    initial_layout.children = vec![TiledPaneLayout::default(), TiledPaneLayout::default()];
-    let mut mock_screen = MockScreen::new(size);
-    let session_metadata = mock_screen.clone_session_metadata();
-    let screen_thread = mock_screen.run(Some(initial_layout), vec![]);
-    let received_server_instructions = Arc::new(Mutex::new(vec![]));
-    let server_receiver = mock_screen.server_receiver.take().unwrap();
-    let server_instruction = log_actions_in_thread!(
-        received_server_instructions,
-        ServerInstruction::KillSession,
-        server_receiver
+    // one of the two panes of the layout failed to open
+    screen
+        .fill_in_place_layout(0, vec![(3, None, None)])
+        .expect("TEST");
+    let first_pane_geom = pane_geom(&screen, PaneId::Terminal(1));
+    let second_pane_geom = pane_geom(&screen, PaneId::Terminal(3));
+    assert_eq!(
+        first_pane_geom.x, second_pane_geom.x,
+        "the pane opened for the layout is placed in it"
     );
-    let move_focus_action = CliAction::MoveFocus {
-        direction: Direction::Right,
-    };
-    send_cli_action_to_server(&session_metadata, move_focus_action, client_id);
-    std::thread::sleep(std::time::Duration::from_millis(100));
-    mock_screen.teardown(vec![server_instruction, screen_thread]);
-    let snapshots = take_snapshots_and_cursor_coordinates_from_render_events(
-        received_server_instructions.lock().unwrap().iter(),
-        size,
+    assert!(
+        first_pane_geom.y < second_pane_geom.y,
+        "the pane opened for the layout is placed below the first one"
+    );
+    assert!(
+        screen.fill_in_place_layout(0, vec![]).is_err(),
+        "the layout is no longer pending"
     );
-    let snapshot_count = snapshots.len();
-    for (cursor_coordinates, _snapshot) in snapshots {
-        // here we assert he cursor_coordinates to let us know if we switched the pane focus
-        assert_snapshot!(format!("{:?}", cursor_coordinates));
-    }
-    assert_snapshot!(format!("{}", snapshot_count));
 }
 
 #[test]
-pub fn send_cli_move_focus_or_tab_pane_action() {
-    let size = Size { cols: 80, rows: 20 };
-    let client_id = 10; // fake client id should not appear in the screen's state
-    let mut initial_layout = TiledPaneLayout::default();
-    initial_layout.children_split_direction = SplitDirection::Vertical;
-    initial_layout.children = vec![TiledPaneLayout::default(), TiledPaneLayout::default()];
-    let mut mock_screen = MockScreen::new(size);
-    let session_metadata = mock_screen.clone_session_metadata();
-    let screen_thread = mock_screen.run(Some(initial_layout), vec![]);
-    let received_server_instructions = Arc::new(Mutex::new(vec![]));
-    let server_receiver = mock_screen.server_receiver.take().unwrap();
-    let server_instruction = log_actions_in_thread!(
-        received_server_instructions,
-        ServerInstruction::KillSession,
-        server_receiver
-    );
-    let move_focus_action = CliAction::MoveFocusOrTab {
-        direction: Direction::Right,
+fn applying_a_layout_to_a_tab_can_be_undone() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
     };
-    send_cli_action_to_server(&session_metadata, move_focus_action, client_id);
-    std::thread::sleep(std::time::Duration::from_millis(100));
-    mock_screen.teardown(vec![server_instruction, screen_thread]);
-    let snapshots = take_snapshots_and_cursor_coordinates_from_render_events(
-        received_server_instructions.lock().unwrap().iter(),
-        size,
+    let client_id = 1;
+    let mut screen = create_new_screen(size);
+    new_tab(&mut screen, 1, 0);
+    screen
+        .get_active_tab_mut(client_id)
+        .unwrap()
+        .vertical_split(PaneId::Terminal(2), None, client_id)
+        .unwrap();
+    let mut tiled_pane_geoms = screen.get_active_tab(client_id).unwrap().tiled_pane_geoms();
+    tiled_pane_geoms.sort_by_key(|(pane_id, _)| *pane_id);
+
+    let mut stacked_layout = TiledPaneLayout::default();
+    stacked_layout.children_split_direction = SplitDirection::Horizontal;
+    stacked_layout.children = vec![TiledPaneLayout::default(), TiledPaneLayout::default()];
+    screen.apply_layout_to_tab(0, stacked_layout).expect("TEST");
+    let mut stacked_pane_geoms = screen.get_active_tab(client_id).unwrap().tiled_pane_geoms();
+    stacked_pane_geoms.sort_by_key(|(pane_id, _)| *pane_id);
+    assert_ne!(stacked_pane_geoms, tiled_pane_geoms, "layout applied");
+
+    screen.undo_layout_change(client_id).expect("TEST");
+    let mut restored_pane_geoms = screen.get_active_tab(client_id).unwrap().tiled_pane_geoms();
+    restored_pane_geoms.sort_by_key(|(pane_id, _)| *pane_id);
+    assert_eq!(
+        restored_pane_geoms, tiled_pane_geoms,
+        "panes arranged as they were before the layout was applied"
     );
-    let snapshot_count = snapshots.len();
-    for (cursor_coordinates, _snapshot) in snapshots {
-        // here we assert he cursor_coordinates to let us know if we switched the pane focus
-        assert_snapshot!(format!("{:?}", cursor_coordinates));
-    }
-    assert_snapshot!(format!("{}", snapshot_count));
 }
 
 #[test]
-pub fn send_cli_move_pane_action() {
-    let size = Size { cols: 80, rows: 20 };
-    let client_id = 10; // fake client id should not appear in the screen's state
-    let mut initial_layout = TiledPaneLayout::default();
-    initial_layout.children_split_direction = SplitDirection::Vertical;
-    initial_layout.children = vec![TiledPaneLayout::default(), TiledPaneLayout::default()];
+fn bell_in_unfocused_tab_is_reported_to_plugins_and_marks_the_tab() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let client_id = 1;
     let mut mock_screen = MockScreen::new(size);
-    let session_metadata = mock_screen.clone_session_metadata();
-    let screen_thread = mock_screen.run(Some(initial_layout), vec![]);
-    let received_server_instructions = Arc::new(Mutex::new(vec![]));
-    let server_receiver = mock_screen.server_receiver.take().unwrap();
-    let server_instruction = log_actions_in_thread!(
-        received_server_instructions,
-        ServerInstruction::KillSession,
-        server_receiver
+    mock_screen.config_options.bell = Some(Bell {
+        audible: true,
+        visual: false,
+        tab: true,
+        plugin: true,
+    });
+    let plugin_receiver = mock_screen.plugin_receiver.take().unwrap();
+    let screen_thread = mock_screen.run(None, vec![]);
+    let received_plugin_instructions = Arc::new(Mutex::new(vec![]));
+    let plugin_thread = log_actions_in_thread!(
+        received_plugin_instructions,
+        PluginInstruction::Exit,
+        plugin_receiver
     );
-    let cli_action = CliAction::MovePane {
-        direction: Some(Direction::Right),
-    };
-    send_cli_action_to_server(&session_metadata, cli_action, client_id);
+    // focus a second tab so that terminal 0 in the first one is in the background
+    let _ = mock_screen.to_screen.send(ScreenInstruction::NewTab(
+        None,
+        None,
+        Some(TiledPaneLayout::default()),
+        vec![],
+        None,
+        (vec![], vec![]),
+        client_id,
+    ));
+    let _ = mock_screen.to_screen.send(ScreenInstruction::ApplyLayout(
+        TiledPaneLayout::default(),
+        vec![],
+        vec![(1, None)],
+        vec![],
+        HashMap::new(),
+        1,
+        client_id,
+    ));
+    let _ = mock_screen
+        .to_screen
+        .send(ScreenInstruction::PtyBytes(0, b"\x07".to_vec()));
     std::thread::sleep(std::time::Duration::from_millis(100));
-    mock_screen.teardown(vec![server_instruction, screen_thread]);
-    let snapshots = take_snapshots_and_cursor_coordinates_from_render_events(
-        received_server_instructions.lock().unwrap().iter(),
-        size,
+    let _ = mock_screen
+        .to_screen
+        .send(ScreenInstruction::GoToTab(1, Some(client_id)));
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    mock_screen.teardown(vec![plugin_thread, screen_thread]);
+    let received_plugin_instructions = received_plugin_instructions.lock().unwrap();
+    let plugins_received_bell =
+        received_plugin_instructions
+            .iter()
+            .any(|instruction| match instruction {
+                PluginInstruction::Update(updates) => updates
+                    .iter()
+                    .any(|(_, _, event)| matches!(event, Event::Bell(PaneId::Terminal(0)))),
+                _ => false,
+            });
+    assert!(plugins_received_bell, "plugins were sent the bell");
+    let first_tab_bell_marks: Vec<bool> = received_plugin_instructions
+        .iter()
+        .filter_map(|instruction| match instruction {
+            PluginInstruction::Update(updates) => {
+                updates.iter().find_map(|(_, _, event)| match event {
+                    Event::TabUpdate(tab_infos) => tab_infos
+                        .iter()
+                        .find(|tab_info| tab_info.position == 0)
+                        .map(|tab_info| tab_info.has_bell),
+                    _ => None,
+                })
+            },
+            _ => None,
+        })
+        .collect();
+    assert!(
+        first_tab_bell_marks.contains(&true),
+        "tab in the background was marked with the bell"
+    );
+    assert_eq!(
+        first_tab_bell_marks.last(),
+        Some(&false),
+        "mark was cleared once the tab was focused"
     );
-    let snapshot_count = snapshots.len();
-    for (_cursor_coordinates, snapshot) in snapshots {
-        assert_snapshot!(format!("{}", snapshot));
-    }
-    assert_snapshot!(format!("{}", snapshot_count));
 }
 
 #[test]
-pub fn send_cli_dump_screen_action() {
-    let size = Size { cols: 80, rows: 20 };
-    let client_id = 10; // fake client id should not appear in the screen's state
-    let mut initial_layout = TiledPaneLayout::default();
-    initial_layout.children_split_direction = SplitDirection::Vertical;
-    initial_layout.children = vec![TiledPaneLayout::default(), TiledPaneLayout::default()];
+fn terminal_title_change_is_stored_and_reported_to_plugins() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
     let mut mock_screen = MockScreen::new(size);
-    let session_metadata = mock_screen.clone_session_metadata();
-    let screen_thread = mock_screen.run(Some(initial_layout), vec![]);
-    let received_server_instructions = Arc::new(Mutex::new(vec![]));
-    let server_receiver = mock_screen.server_receiver.take().unwrap();
-    let server_thread = log_actions_in_thread!(
-        received_server_instructions,
-        ServerInstruction::KillSession,
-        server_receiver
+    let plugin_receiver = mock_screen.plugin_receiver.take().unwrap();
+    let screen_thread = mock_screen.run(None, vec![]);
+    let received_plugin_instructions = Arc::new(Mutex::new(vec![]));
+    let plugin_thread = log_actions_in_thread!(
+        received_plugin_instructions,
+        PluginInstruction::Exit,
+        plugin_receiver
     );
-    let cli_action = CliAction::DumpScreen {
-        path: PathBuf::from("/tmp/foo"),
-        full: true,
-    };
     let _ = mock_screen.to_screen.send(ScreenInstruction::PtyBytes(
         0,
-        "fill pane up with something".as_bytes().to_vec(),
+        b"\x1b]2;my title\x07".to_vec(),
     ));
-    send_cli_action_to_server(&session_metadata, cli_action, client_id);
-    std::thread::sleep(std::time::Duration::from_millis(100));
-    mock_screen.teardown(vec![server_thread, screen_thread]);
-    assert_snapshot!(format!(
+    // setting the same title again is not a change
+    let _ = mock_screen.to_screen.send(ScreenInstruction::PtyBytes(
+        0,
+        b"\x1b]0;my title\x07".to_vec(),
+    ));
+    // report the session state, so that plugins see the stored title of the pane
+    let _ = mock_screen
+        .to_screen
+        .send(ScreenInstruction::TogglePaneFrames);
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    mock_screen.teardown(vec![plugin_thread, screen_thread]);
+    let received_plugin_instructions = received_plugin_instructions.lock().unwrap();
+    let title_events: Vec<Event> = received_plugin_instructions
+        .iter()
+        .filter_map(|instruction| match instruction {
+            PluginInstruction::Update(updates) => Some(updates.clone()),
+            _ => None,
+        })
+        .flatten()
+        .filter_map(|(_plugin_id, _client_id, event)| match event {
+            Event::PaneTitleChanged(..) => Some(event),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        title_events,
+        vec![Event::PaneTitleChanged(
+            PaneId::Terminal(0),
+            "my title".to_owned()
+        )],
+        "plugins were told about the new title once"
+    );
+    let last_reported_title = received_plugin_instructions
+        .iter()
+        .filter_map(|instruction| match instruction {
+            PluginInstruction::Update(updates) => {
+                updates.iter().find_map(|(_, _, event)| match event {
+                    Event::PaneUpdate(pane_manifest) => pane_manifest
+                        .panes
+                        .values()
+                        .flatten()
+                        .find(|pane_info| !pane_info.is_plugin && pane_info.id == 0)
+                        .map(|pane_info| pane_info.title.clone()),
+                    _ => None,
+                })
+            },
+            _ => None,
+        })
+        .last();
+    assert_eq!(
+        last_reported_title,
+        Some("my title".to_owned()),
+        "the pane stored the title it was given"
+    );
+}
+
+#[test]
+fn pane_served_to_second_client_as_isolated_view() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let mut screen = create_new_screen(size);
+    // in mirrored sessions all clients share the same tab, so panes are viewed in place
+    screen.session_is_mirrored = false;
+
+    new_tab(&mut screen, 1, 1);
+    screen
+        .get_active_tab_mut(1)
+        .unwrap()
+        .new_pane(PaneId::Terminal(2), None, None, None, Some(1))
+        .unwrap();
+    let pane_view_client_id = 2;
+    screen.add_client(pane_view_client_id).expect("TEST");
+    screen
+        .view_pane_in_isolation(PaneId::Terminal(1), pane_view_client_id)
+        .expect("TEST");
+
+    let pane_view_tab = screen.get_active_tab(pane_view_client_id).unwrap();
+    let panes_in_view: Vec<PaneId> = pane_view_tab
+        .get_tiled_panes()
+        .map(|(pane_id, _pane)| *pane_id)
+        .collect();
+    assert_eq!(
+        panes_in_view,
+        vec![PaneId::Terminal(1)],
+        "Only the popped out pane is served to the second client"
+    );
+    assert_eq!(
+        pane_view_tab.get_active_pane_id(pane_view_client_id),
+        Some(PaneId::Terminal(1)),
+        "Popped out pane focused for the second client"
+    );
+    let first_client_tab = screen.get_active_tab(1).unwrap();
+    assert_ne!(
+        first_client_tab.index, pane_view_tab.index,
+        "First client stays in its own tab"
+    );
+    assert!(
+        !first_client_tab.has_pane_with_pid(&PaneId::Terminal(1)),
+        "Pane moved out of the tab of the first client"
+    );
+    assert_eq!(
+        first_client_tab.get_active_pane_id(1),
+        Some(PaneId::Terminal(2)),
+        "Focus of the first client did not change"
+    );
+
+    screen.remove_client(pane_view_client_id).expect("TEST");
+    assert_eq!(
+        screen.tabs.len(),
+        1,
+        "Popped out tab closed once its client detached"
+    );
+    assert!(
+        screen
+            .get_active_tab(1)
+            .unwrap()
+            .has_pane_with_pid(&PaneId::Terminal(1)),
+        "Pane docked back into its tab"
+    );
+}
+
+#[test]
+fn floating_pane_viewed_from_a_hidden_workspace_is_docked_back_floating() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let mut screen = create_new_screen(size);
+    screen.session_is_mirrored = false;
+
+    new_tab(&mut screen, 1, 0);
+    screen
+        .get_active_tab_mut(1)
+        .unwrap()
+        .new_pane(PaneId::Terminal(2), None, Some(true), None, Some(1))
+        .unwrap();
+    let pane_view_client_id = 2;
+    screen.add_client(pane_view_client_id).expect("TEST");
+    screen
+        .view_pane_in_isolation(PaneId::Terminal(2), pane_view_client_id)
+        .expect("TEST");
+    // the second client switched to another workspace along with the popped out tab
+    let pane_view_tab_index = screen.active_tab_indices[&pane_view_client_id];
+    let pane_view_tab = screen.tabs.remove(&pane_view_tab_index).unwrap();
+    screen.active_tab_indices.remove(&pane_view_client_id);
+    screen.hidden_workspaces.insert(
+        "work".to_owned(),
+        Workspace {
+            tabs: BTreeMap::from([(pane_view_tab_index, pane_view_tab)]),
+            active_tab_indices: BTreeMap::from([(pane_view_client_id, pane_view_tab_index)]),
+            tab_history: BTreeMap::new(),
+        },
+    );
+
+    screen.remove_client(pane_view_client_id).expect("TEST");
+    assert!(
+        screen.hidden_workspaces["work"].tabs.is_empty(),
+        "Popped out tab closed in the hidden workspace"
+    );
+    assert!(
+        screen
+            .get_active_tab(1)
+            .unwrap()
+            .get_floating_panes()
+            .any(|(pane_id, _pane)| *pane_id == PaneId::Terminal(2)),
+        "Pane docked back into its tab as a floating pane"
+    );
+}
+
+#[test]
+fn popped_out_pane_is_docked_floating_when_its_tab_has_no_room_for_it() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let mut screen = create_new_screen(size);
+    screen.session_is_mirrored = false;
+
+    new_tab(&mut screen, 1, 0);
+    screen
+        .get_active_tab_mut(1)
+        .unwrap()
+        .new_pane(PaneId::Terminal(2), None, None, None, Some(1))
+        .unwrap();
+    let pane_view_client_id = 2;
+    screen.add_client(pane_view_client_id).expect("TEST");
+    screen
+        .view_pane_in_isolation(PaneId::Terminal(1), pane_view_client_id)
+        .expect("TEST");
+    // fill the tab the pane was popped out of
+    let origin_tab = screen.get_active_tab_mut(1).unwrap();
+    for terminal_id in 3.. {
+        origin_tab
+            .new_pane(PaneId::Terminal(terminal_id), None, None, None, Some(1))
+            .unwrap();
+        if !origin_tab.has_pane_with_pid(&PaneId::Terminal(terminal_id)) {
+            break;
+        }
+    }
+
+    screen.remove_client(pane_view_client_id).expect("TEST");
+    assert_eq!(
+        screen.tabs.len(),
+        1,
+        "Popped out tab closed once its client detached"
+    );
+    assert!(
+        screen
+            .get_active_tab(1)
+            .unwrap()
+            .get_floating_panes()
+            .any(|(pane_id, _pane)| *pane_id == PaneId::Terminal(1)),
+        "Pane docked back into its tab as a floating pane"
+    );
+}
+
+#[test]
+fn update_screen_pixel_dimensions() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let mut screen = create_new_screen(size);
+    let initial_pixel_dimensions = screen.pixel_dimensions;
+    screen.update_pixel_dimensions(PixelDimensions {
+        character_cell_size: Some(SizeInPixels {
+            height: 10,
+            width: 5,
+        }),
+        text_area_size: None,
+    });
+    let pixel_dimensions_after_first_update = screen.pixel_dimensions;
+    screen.update_pixel_dimensions(PixelDimensions {
+        character_cell_size: None,
+        text_area_size: Some(SizeInPixels {
+            height: 100,
+            width: 50,
+        }),
+    });
+    let pixel_dimensions_after_second_update = screen.pixel_dimensions;
+    screen.update_pixel_dimensions(PixelDimensions {
+        character_cell_size: None,
+        text_area_size: None,
+    });
+    let pixel_dimensions_after_third_update = screen.pixel_dimensions;
+    assert_eq!(
+        initial_pixel_dimensions,
+        PixelDimensions {
+            character_cell_size: None,
+            text_area_size: None
+        },
+        "Initial pixel dimensions empty"
+    );
+    assert_eq!(
+        pixel_dimensions_after_first_update,
+        PixelDimensions {
+            character_cell_size: Some(SizeInPixels {
+                height: 10,
+                width: 5
+            }),
+            text_area_size: None
+        },
+        "character_cell_size updated properly",
+    );
+    assert_eq!(
+        pixel_dimensions_after_second_update,
+        PixelDimensions {
+            character_cell_size: Some(SizeInPixels {
+                height: 10,
+                width: 5
+            }),
+            text_area_size: Some(SizeInPixels {
+                height: 100,
+                width: 50,
+            }),
+        },
+        "text_area_size updated properly without overriding character_cell_size",
+    );
+    assert_eq!(
+        pixel_dimensions_after_third_update,
+        PixelDimensions {
+            character_cell_size: Some(SizeInPixels {
+                height: 10,
+                width: 5
+            }),
+            text_area_size: Some(SizeInPixels {
+                height: 100,
+                width: 50,
+            }),
+        },
+        "empty update does not delete existing data",
+    );
+}
+
+#[test]
+fn attach_after_first_tab_closed() {
+    // ensure https://github.com/zellij-org/zellij/issues/1645 is fixed
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let mut screen = create_new_screen(size);
+
+    new_tab(&mut screen, 1, 0);
+    {
+        let active_tab = screen.get_active_tab_mut(1).unwrap();
+        active_tab
+            .new_pane(PaneId::Terminal(2), None, None, None, Some(1))
+            .unwrap();
+        active_tab.toggle_active_pane_fullscreen(1);
+    }
+    new_tab(&mut screen, 2, 1);
+
+    screen.close_tab_at_index(0).expect("TEST");
+    screen.remove_client(1).expect("TEST");
+    screen.add_client(1).expect("TEST");
+}
+
+// Following are tests for sending CLI actions
+// these tests are only partially relevant to Screen
+// and are included here for two reasons:
+// 1. The best way to "integration test" these is combining the "screen_thread_main" and
+//    "route_action" functions and mocking everything around them
+// 2. These inadvertently also test many parts of Screen that are not tested elsewhere
+
+#[test]
+pub fn send_cli_write_chars_action_to_screen() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let client_id = 10; // fake client id should not appear in the screen's state
+    let mut mock_screen = MockScreen::new(size);
+    let pty_writer_receiver = mock_screen.pty_writer_receiver.take().unwrap();
+    let session_metadata = mock_screen.clone_session_metadata();
+    let screen_thread = mock_screen.run(None, vec![]);
+    let received_pty_instructions = Arc::new(Mutex::new(vec![]));
+    let pty_writer_thread = log_actions_in_thread!(
+        received_pty_instructions,
+        PtyWriteInstruction::Exit,
+        pty_writer_receiver
+    );
+    let cli_action = CliAction::WriteChars {
+        chars: "input from the cli".into(),
+    };
+    send_cli_action_to_server(&session_metadata, cli_action, client_id);
+    std::thread::sleep(std::time::Duration::from_millis(100)); // give time for actions to be
+    mock_screen.teardown(vec![pty_writer_thread, screen_thread]);
+    assert_snapshot!(format!("{:?}", *received_pty_instructions.lock().unwrap()));
+}
+
+#[test]
+pub fn send_cli_write_action_to_screen() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let client_id = 10; // fake client id should not appear in the screen's state
+    let mut mock_screen = MockScreen::new(size);
+    let pty_writer_receiver = mock_screen.pty_writer_receiver.take().unwrap();
+    let session_metadata = mock_screen.clone_session_metadata();
+    let screen_thread = mock_screen.run(None, vec![]);
+    let received_pty_instructions = Arc::new(Mutex::new(vec![]));
+    let pty_writer_thread = log_actions_in_thread!(
+        received_pty_instructions,
+        PtyWriteInstruction::Exit,
+        pty_writer_receiver
+    );
+    let cli_action = CliAction::Write {
+        bytes: vec![102, 111, 111],
+    };
+    send_cli_action_to_server(&session_metadata, cli_action, client_id);
+    std::thread::sleep(std::time::Duration::from_millis(100)); // give time for actions to be
+    mock_screen.teardown(vec![pty_writer_thread, screen_thread]);
+    assert_snapshot!(format!("{:?}", *received_pty_instructions.lock().unwrap()));
+}
+
+#[test]
+pub fn send_cli_resize_action_to_screen() {
+    let size = Size { cols: 80, rows: 20 };
+    let client_id = 10; // fake client id should not appear in the screen's state
+    let mut initial_layout = TiledPaneLayout::default();
+    initial_layout.children_split_direction = SplitDirection::Vertical;
+    initial_layout.children = vec![TiledPaneLayout::default(), TiledPaneLayout::default()];
+    let mut mock_screen = MockScreen::new(size);
+    let session_metadata = mock_screen.clone_session_metadata();
+    let screen_thread = mock_screen.run(Some(initial_layout), vec![]);
+    let received_server_instructions = Arc::new(Mutex::new(vec![]));
+    let server_receiver = mock_screen.server_receiver.take().unwrap();
+    let pty_writer_thread = log_actions_in_thread!(
+        received_server_instructions,
+        ServerInstruction::KillSession,
+        server_receiver
+    );
+    let resize_cli_action = CliAction::Resize {
+        resize: Resize::Increase,
+        direction: Some(Direction::Left),
+    };
+    send_cli_action_to_server(&session_metadata, resize_cli_action, client_id);
+    mock_screen.teardown(vec![pty_writer_thread, screen_thread]);
+    let snapshots = take_snapshots_and_cursor_coordinates_from_render_events(
+        received_server_instructions.lock().unwrap().iter(),
+        size,
+    );
+    let snapshot_count = snapshots.len();
+    for (_cursor_coordinates, snapshot) in snapshots {
+        assert_snapshot!(format!("{}", snapshot));
+    }
+    assert_snapshot!(format!("{}", snapshot_count));
+}
+
+#[test]
+pub fn send_cli_focus_next_pane_action() {
+    let size = Size { cols: 80, rows: 20 };
+    let client_id = 10; // fake client id should not appear in the screen's state
+    let mut initial_layout = TiledPaneLayout::default();
+    initial_layout.children_split_direction = SplitDirection::Vertical;
+    initial_layout.children = vec![TiledPaneLayout::default(), TiledPaneLayout::default()];
+    let mut mock_screen = MockScreen::new(size);
+    let session_metadata = mock_screen.clone_session_metadata();
+    let screen_thread = mock_screen.run(Some(initial_layout), vec![]);
+    let received_server_instructions = Arc::new(Mutex::new(vec![]));
+    let server_receiver = mock_screen.server_receiver.take().unwrap();
+    let server_instruction = log_actions_in_thread!(
+        received_server_instructions,
+        ServerInstruction::KillSession,
+        server_receiver
+    );
+    let focus_next_pane_action = CliAction::FocusNextPane;
+    send_cli_action_to_server(&session_metadata, focus_next_pane_action, client_id);
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    mock_screen.teardown(vec![server_instruction, screen_thread]);
+    let snapshots = take_snapshots_and_cursor_coordinates_from_render_events(
+        received_server_instructions.lock().unwrap().iter(),
+        size,
+    );
+    let snapshot_count = snapshots.len();
+    for (cursor_coordinates, _snapshot) in snapshots {
+        // here we assert he cursor_coordinates to let us know if we switched the pane focus
+        assert_snapshot!(format!("{:?}", cursor_coordinates));
+    }
+    assert_snapshot!(format!("{}", snapshot_count));
+}
+
+#[test]
+pub fn send_cli_focus_previous_pane_action() {
+    let size = Size { cols: 80, rows: 20 };
+    let client_id = 10; // fake client id should not appear in the screen's state
+    let mut initial_layout = TiledPaneLayout::default();
+    initial_layout.children_split_direction = SplitDirection::Vertical;
+    initial_layout.children = vec![TiledPaneLayout::default(), TiledPaneLayout::default()];
+    let mut mock_screen = MockScreen::new(size);
+    let session_metadata = mock_screen.clone_session_metadata();
+    let screen_thread = mock_screen.run(Some(initial_layout), vec![]);
+    let received_server_instructions = Arc::new(Mutex::new(vec![]));
+    let server_receiver = mock_screen.server_receiver.take().unwrap();
+    let server_instruction = log_actions_in_thread!(
+        received_server_instructions,
+        ServerInstruction::KillSession,
+        server_receiver
+    );
+    let focus_next_pane_action = CliAction::FocusPreviousPane;
+    send_cli_action_to_server(&session_metadata, focus_next_pane_action, client_id);
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    mock_screen.teardown(vec![server_instruction, screen_thread]);
+    let snapshots = take_snapshots_and_cursor_coordinates_from_render_events(
+        received_server_instructions.lock().unwrap().iter(),
+        size,
+    );
+    let snapshot_count = snapshots.len();
+    for (cursor_coordinates, _snapshot) in snapshots {
+        // here we assert he cursor_coordinates to let us know if we switched the pane focus
+        assert_snapshot!(format!("{:?}", cursor_coordinates));
+    }
+    assert_snapshot!(format!("{}", snapshot_count));
+}
+
+#[test]
+pub fn send_cli_move_focus_pane_action() {
+    let size = Size { cols: 80, rows: 20 };
+    let client_id = 10; // fake client id should not appear in the screen's state
+    let mut initial_layout = TiledPaneLayout::default();
+    initial_layout.children_split_direction = SplitDirection::Vertical;
+    initial_layout.children = vec![TiledPaneLayout::default(), TiledPaneLayout::default()];
+    let mut mock_screen = MockScreen::new(size);
+    let session_metadata = mock_screen.clone_session_metadata();
+    let screen_thread = mock_screen.run(Some(initial_layout), vec![]);
+    let received_server_instructions = Arc::new(Mutex::new(vec![]));
+    let server_receiver = mock_screen.server_receiver.take().unwrap();
+    let server_instruction = log_actions_in_thread!(
+        received_server_instructions,
+        ServerInstruction::KillSession,
+        server_receiver
+    );
+    let move_focus_action = CliAction::MoveFocus {
+        direction: Direction::Right,
+    };
+    send_cli_action_to_server(&session_metadata, move_focus_action, client_id);
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    mock_screen.teardown(vec![server_instruction, screen_thread]);
+    let snapshots = take_snapshots_and_cursor_coordinates_from_render_events(
+        received_server_instructions.lock().unwrap().iter(),
+        size,
+    );
+    let snapshot_count = snapshots.len();
+    for (cursor_coordinates, _snapshot) in snapshots {
+        // here we assert he cursor_coordinates to let us know if we switched the pane focus
+        assert_snapshot!(format!("{:?}", cursor_coordinates));
+    }
+    assert_snapshot!(format!("{}", snapshot_count));
+}
+
+#[test]
+pub fn send_cli_move_focus_or_tab_pane_action() {
+    let size = Size { cols: 80, rows: 20 };
+    let client_id = 10; // fake client id should not appear in the screen's state
+    let mut initial_layout = TiledPaneLayout::default();
+    initial_layout.children_split_direction = SplitDirection::Vertical;
+    initial_layout.children = vec![TiledPaneLayout::default(), TiledPaneLayout::default()];
+    let mut mock_screen = MockScreen::new(size);
+    let session_metadata = mock_screen.clone_session_metadata();
+    let screen_thread = mock_screen.run(Some(initial_layout), vec![]);
+    let received_server_instructions = Arc::new(Mutex::new(vec![]));
+    let server_receiver = mock_screen.server_receiver.take().unwrap();
+    let server_instruction = log_actions_in_thread!(
+        received_server_instructions,
+        ServerInstruction::KillSession,
+        server_receiver
+    );
+    let move_focus_action = CliAction::MoveFocusOrTab {
+        direction: Direction::Right,
+    };
+    send_cli_action_to_server(&session_metadata, move_focus_action, client_id);
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    mock_screen.teardown(vec![server_instruction, screen_thread]);
+    let snapshots = take_snapshots_and_cursor_coordinates_from_render_events(
+        received_server_instructions.lock().unwrap().iter(),
+        size,
+    );
+    let snapshot_count = snapshots.len();
+    for (cursor_coordinates, _snapshot) in snapshots {
+        // here we assert he cursor_coordinates to let us know if we switched the pane focus
+        assert_snapshot!(format!("{:?}", cursor_coordinates));
+    }
+    assert_snapshot!(format!("{}", snapshot_count));
+}
+
+#[test]
+pub fn send_cli_move_pane_action() {
+    let size = Size { cols: 80, rows: 20 };
+    let client_id = 10; // fake client id should not appear in the screen's state
+    let mut initial_layout = TiledPaneLayout::default();
+    initial_layout.children_split_direction = SplitDirection::Vertical;
+    initial_layout.children = vec![TiledPaneLayout::default(), TiledPaneLayout::default()];
+    let mut mock_screen = MockScreen::new(size);
+    let session_metadata = mock_screen.clone_session_metadata();
+    let screen_thread = mock_screen.run(Some(initial_layout), vec![]);
+    let received_server_instructions = Arc::new(Mutex::new(vec![]));
+    let server_receiver = mock_screen.server_receiver.take().unwrap();
+    let server_instruction = log_actions_in_thread!(
+        received_server_instructions,
+        ServerInstruction::KillSession,
+        server_receiver
+    );
+    let cli_action = CliAction::MovePane {
+        direction: Some(Direction::Right),
+    };
+    send_cli_action_to_server(&session_metadata, cli_action, client_id);
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    mock_screen.teardown(vec![server_instruction, screen_thread]);
+    let snapshots = take_snapshots_and_cursor_coordinates_from_render_events(
+        received_server_instructions.lock().unwrap().iter(),
+        size,
+    );
+    let snapshot_count = snapshots.len();
+    for (_cursor_coordinates, snapshot) in snapshots {
+        assert_snapshot!(format!("{}", snapshot));
+    }
+    assert_snapshot!(format!("{}", snapshot_count));
+}
+
+#[test]
+pub fn send_cli_dump_screen_action() {
+    let size = Size { cols: 80, rows: 20 };
+    let client_id = 10; // fake client id should not appear in the screen's state
+    let mut initial_layout = TiledPaneLayout::default();
+    initial_layout.children_split_direction = SplitDirection::Vertical;
+    initial_layout.children = vec![TiledPaneLayout::default(), TiledPaneLayout::default()];
+    let mut mock_screen = MockScreen::new(size);
+    let session_metadata = mock_screen.clone_session_metadata();
+    let screen_thread = mock_screen.run(Some(initial_layout), vec![]);
+    let received_server_instructions = Arc::new(Mutex::new(vec![]));
+    let server_receiver = mock_screen.server_receiver.take().unwrap();
+    let server_thread = log_actions_in_thread!(
+        received_server_instructions,
+        ServerInstruction::KillSession,
+        server_receiver
+    );
+    let cli_action = CliAction::DumpScreen {
+        path: PathBuf::from("/tmp/foo"),
+        full: true,
+    };
+    let _ = mock_screen.to_screen.send(ScreenInstruction::PtyBytes(
+        0,
+        "fill pane up with something".as_bytes().to_vec(),
+    ));
+    send_cli_action_to_server(&session_metadata, cli_action, client_id);
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    mock_screen.teardown(vec![server_thread, screen_thread]);
+    assert_snapshot!(format!(
+        "{:?}",
+        *mock_screen.os_input.fake_filesystem.lock().unwrap()
+    ));
+}
+
+#[test]
+pub fn send_cli_edit_scrollback_action() {
+    let size = Size { cols: 80, rows: 20 };
+    let client_id = 10; // fake client id should not appear in the screen's state
+    let mut initial_layout = TiledPaneLayout::default();
+    initial_layout.children_split_direction = SplitDirection::Vertical;
+    initial_layout.children = vec![TiledPaneLayout::default(), TiledPaneLayout::default()];
+    let mut mock_screen = MockScreen::new(size);
+    let session_metadata = mock_screen.clone_session_metadata();
+    let screen_thread = mock_screen.run(Some(initial_layout), vec![]);
+    let received_pty_instructions = Arc::new(Mutex::new(vec![]));
+    let pty_receiver = mock_screen.pty_receiver.take().unwrap();
+    let pty_thread = log_actions_in_thread!(
+        received_pty_instructions,
+        PtyInstruction::Exit,
+        pty_receiver
+    );
+    let cli_action = CliAction::EditScrollback;
+    let _ = mock_screen.to_screen.send(ScreenInstruction::PtyBytes(
+        0,
+        "fill pane up with something".as_bytes().to_vec(),
+    ));
+    send_cli_action_to_server(&session_metadata, cli_action, client_id);
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    mock_screen.teardown(vec![pty_thread, screen_thread]);
+    let dumped_file_name = mock_screen
+        .os_input
+        .fake_filesystem
+        .lock()
+        .unwrap()
+        .keys()
+        .next()
+        .unwrap()
+        .clone();
+    let mut found_instruction = false;
+    for instruction in received_pty_instructions.lock().unwrap().iter() {
+        if let PtyInstruction::OpenInPlaceEditor(scrollback_contents_file, terminal_id, client_id) =
+            instruction
+        {
+            assert_eq!(scrollback_contents_file, &PathBuf::from(&dumped_file_name));
+            assert_eq!(terminal_id, &Some(1));
+            assert_eq!(client_id, &1);
+            found_instruction = true;
+        }
+    }
+    assert!(found_instruction);
+}
+
+#[test]
+pub fn send_cli_scroll_up_action() {
+    let size = Size { cols: 80, rows: 10 };
+    let client_id = 10; // fake client id should not appear in the screen's state
+    let mut initial_layout = TiledPaneLayout::default();
+    initial_layout.children_split_direction = SplitDirection::Vertical;
+    initial_layout.children = vec![TiledPaneLayout::default(), TiledPaneLayout::default()];
+    let mut mock_screen = MockScreen::new(size);
+    let session_metadata = mock_screen.clone_session_metadata();
+    let screen_thread = mock_screen.run(Some(initial_layout), vec![]);
+    let received_server_instructions = Arc::new(Mutex::new(vec![]));
+    let server_receiver = mock_screen.server_receiver.take().unwrap();
+    let server_instruction = log_actions_in_thread!(
+        received_server_instructions,
+        ServerInstruction::KillSession,
+        server_receiver
+    );
+    let cli_action = CliAction::ScrollUp;
+    let mut pane_contents = String::new();
+    for i in 0..20 {
+        pane_contents.push_str(&format!("fill pane up with something {}\n\r", i));
+    }
+    let _ = mock_screen.to_screen.send(ScreenInstruction::PtyBytes(
+        0,
+        pane_contents.as_bytes().to_vec(),
+    ));
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    // we send two actions here because only the last line in the pane is empty, so one action
+    // won't show in a render
+    send_cli_action_to_server(&session_metadata, cli_action.clone(), client_id);
+    send_cli_action_to_server(&session_metadata, cli_action.clone(), client_id);
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    mock_screen.teardown(vec![server_instruction, screen_thread]);
+    let snapshots = take_snapshots_and_cursor_coordinates_from_render_events(
+        received_server_instructions.lock().unwrap().iter(),
+        size,
+    );
+    let snapshot_count = snapshots.len();
+    for (_cursor_coordinates, snapshot) in snapshots {
+        assert_snapshot!(format!("{}", snapshot));
+    }
+    assert_snapshot!(format!("{}", snapshot_count));
+}
+
+#[test]
+pub fn send_cli_scroll_down_action() {
+    let size = Size { cols: 80, rows: 10 };
+    let client_id = 10; // fake client id should not appear in the screen's state
+    let mut initial_layout = TiledPaneLayout::default();
+    initial_layout.children_split_direction = SplitDirection::Vertical;
+    initial_layout.children = vec![TiledPaneLayout::default(), TiledPaneLayout::default()];
+    let mut mock_screen = MockScreen::new(size);
+    let session_metadata = mock_screen.clone_session_metadata();
+    let screen_thread = mock_screen.run(Some(initial_layout), vec![]);
+    let received_server_instructions = Arc::new(Mutex::new(vec![]));
+    let server_receiver = mock_screen.server_receiver.take().unwrap();
+    let server_instruction = log_actions_in_thread!(
+        received_server_instructions,
+        ServerInstruction::KillSession,
+        server_receiver
+    );
+    let scroll_up_cli_action = CliAction::ScrollUp;
+    let scroll_down_cli_action = CliAction::ScrollDown;
+    let mut pane_contents = String::new();
+    for i in 0..20 {
+        pane_contents.push_str(&format!("fill pane up with something {}\n\r", i));
+    }
+    let _ = mock_screen.to_screen.send(ScreenInstruction::PtyBytes(
+        0,
+        pane_contents.as_bytes().to_vec(),
+    ));
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    // scroll up some
+    send_cli_action_to_server(&session_metadata, scroll_up_cli_action.clone(), client_id);
+    send_cli_action_to_server(&session_metadata, scroll_up_cli_action.clone(), client_id);
+    send_cli_action_to_server(&session_metadata, scroll_up_cli_action.clone(), client_id);
+    send_cli_action_to_server(&session_metadata, scroll_up_cli_action.clone(), client_id);
+
+    // scroll down some
+    send_cli_action_to_server(&session_metadata, scroll_down_cli_action.clone(), client_id);
+    send_cli_action_to_server(&session_metadata, scroll_down_cli_action.clone(), client_id);
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    mock_screen.teardown(vec![server_instruction, screen_thread]);
+    let snapshots = take_snapshots_and_cursor_coordinates_from_render_events(
+        received_server_instructions.lock().unwrap().iter(),
+        size,
+    );
+    let snapshot_count = snapshots.len();
+    for (_cursor_coordinates, snapshot) in snapshots {
+        assert_snapshot!(format!("{}", snapshot));
+    }
+    assert_snapshot!(format!("{}", snapshot_count));
+}
+
+#[test]
+pub fn send_cli_scroll_to_bottom_action() {
+    let size = Size { cols: 80, rows: 10 };
+    let client_id = 10; // fake client id should not appear in the screen's state
+    let mut initial_layout = TiledPaneLayout::default();
+    initial_layout.children_split_direction = SplitDirection::Vertical;
+    initial_layout.children = vec![TiledPaneLayout::default(), TiledPaneLayout::default()];
+    let mut mock_screen = MockScreen::new(size);
+    let session_metadata = mock_screen.clone_session_metadata();
+    let screen_thread = mock_screen.run(Some(initial_layout), vec![]);
+    let received_server_instructions = Arc::new(Mutex::new(vec![]));
+    let server_receiver = mock_screen.server_receiver.take().unwrap();
+    let server_instruction = log_actions_in_thread!(
+        received_server_instructions,
+        ServerInstruction::KillSession,
+        server_receiver
+    );
+    let scroll_up_cli_action = CliAction::ScrollUp;
+    let scroll_to_bottom_action = CliAction::ScrollToBottom;
+    let mut pane_contents = String::new();
+    for i in 0..20 {
+        pane_contents.push_str(&format!("fill pane up with something {}\n\r", i));
+    }
+    let _ = mock_screen.to_screen.send(ScreenInstruction::PtyBytes(
+        0,
+        pane_contents.as_bytes().to_vec(),
+    ));
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    // scroll up some
+    send_cli_action_to_server(&session_metadata, scroll_up_cli_action.clone(), client_id);
+    send_cli_action_to_server(&session_metadata, scroll_up_cli_action.clone(), client_id);
+    send_cli_action_to_server(&session_metadata, scroll_up_cli_action.clone(), client_id);
+    send_cli_action_to_server(&session_metadata, scroll_up_cli_action.clone(), client_id);
+
+    // scroll to bottom
+    send_cli_action_to_server(
+        &session_metadata,
+        scroll_to_bottom_action.clone(),
+        client_id,
+    );
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    mock_screen.teardown(vec![server_instruction, screen_thread]);
+    let snapshots = take_snapshots_and_cursor_coordinates_from_render_events(
+        received_server_instructions.lock().unwrap().iter(),
+        size,
+    );
+    let snapshot_count = snapshots.len();
+    for (_cursor_coordinates, snapshot) in snapshots {
+        assert_snapshot!(format!("{}", snapshot));
+    }
+    assert_snapshot!(format!("{}", snapshot_count));
+}
+
+#[test]
+pub fn send_cli_scroll_to_top_action() {
+    let size = Size { cols: 80, rows: 10 };
+    let client_id = 10; // fake client id should not appear in the screen's state
+    let mut initial_layout = TiledPaneLayout::default();
+    initial_layout.children_split_direction = SplitDirection::Vertical;
+    initial_layout.children = vec![TiledPaneLayout::default(), TiledPaneLayout::default()];
+    let mut mock_screen = MockScreen::new(size);
+    let session_metadata = mock_screen.clone_session_metadata();
+    let screen_thread = mock_screen.run(Some(initial_layout), vec![]);
+    let received_server_instructions = Arc::new(Mutex::new(vec![]));
+    let server_receiver = mock_screen.server_receiver.take().unwrap();
+    let server_instruction = log_actions_in_thread!(
+        received_server_instructions,
+        ServerInstruction::KillSession,
+        server_receiver
+    );
+    let scroll_to_top_action = CliAction::ScrollToTop;
+    let mut pane_contents = String::new();
+    for i in 0..20 {
+        pane_contents.push_str(&format!("fill pane up with something {}\n\r", i));
+    }
+    let _ = mock_screen.to_screen.send(ScreenInstruction::PtyBytes(
+        0,
+        pane_contents.as_bytes().to_vec(),
+    ));
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    // scroll to top
+    send_cli_action_to_server(&session_metadata, scroll_to_top_action.clone(), client_id);
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    mock_screen.teardown(vec![server_instruction, screen_thread]);
+    let snapshots = take_snapshots_and_cursor_coordinates_from_render_events(
+        received_server_instructions.lock().unwrap().iter(),
+        size,
+    );
+    let snapshot_count = snapshots.len();
+    for (_cursor_coordinates, snapshot) in snapshots {
+        assert_snapshot!(format!("{}", snapshot));
+    }
+    assert_snapshot!(format!("{}", snapshot_count));
+}
+
+#[test]
+pub fn send_cli_page_scroll_up_action() {
+    let size = Size { cols: 80, rows: 10 };
+    let client_id = 10; // fake client id should not appear in the screen's state
+    let mut initial_layout = TiledPaneLayout::default();
+    initial_layout.children_split_direction = SplitDirection::Vertical;
+    initial_layout.children = vec![TiledPaneLayout::default(), TiledPaneLayout::default()];
+    let mut mock_screen = MockScreen::new(size);
+    let session_metadata = mock_screen.clone_session_metadata();
+    let screen_thread = mock_screen.run(Some(initial_layout), vec![]);
+    let received_server_instructions = Arc::new(Mutex::new(vec![]));
+    let server_receiver = mock_screen.server_receiver.take().unwrap();
+    let server_instruction = log_actions_in_thread!(
+        received_server_instructions,
+        ServerInstruction::KillSession,
+        server_receiver
+    );
+    let page_scroll_up_action = CliAction::PageScrollUp;
+    let mut pane_contents = String::new();
+    for i in 0..20 {
+        pane_contents.push_str(&format!("fill pane up with something {}\n\r", i));
+    }
+    let _ = mock_screen.to_screen.send(ScreenInstruction::PtyBytes(
+        0,
+        pane_contents.as_bytes().to_vec(),
+    ));
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    send_cli_action_to_server(&session_metadata, page_scroll_up_action, client_id);
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    mock_screen.teardown(vec![server_instruction, screen_thread]);
+    let snapshots = take_snapshots_and_cursor_coordinates_from_render_events(
+        received_server_instructions.lock().unwrap().iter(),
+        size,
+    );
+    let snapshot_count = snapshots.len();
+    for (_cursor_coordinates, snapshot) in snapshots {
+        assert_snapshot!(format!("{}", snapshot));
+    }
+    assert_snapshot!(format!("{}", snapshot_count));
+}
+
+#[test]
+pub fn send_cli_page_scroll_down_action() {
+    let size = Size { cols: 80, rows: 10 };
+    let client_id = 10; // fake client id should not appear in the screen's state
+    let mut initial_layout = TiledPaneLayout::default();
+    initial_layout.children_split_direction = SplitDirection::Vertical;
+    initial_layout.children = vec![TiledPaneLayout::default(), TiledPaneLayout::default()];
+    let mut mock_screen = MockScreen::new(size);
+    let session_metadata = mock_screen.clone_session_metadata();
+    let screen_thread = mock_screen.run(Some(initial_layout), vec![]);
+    let received_server_instructions = Arc::new(Mutex::new(vec![]));
+    let server_receiver = mock_screen.server_receiver.take().unwrap();
+    let server_instruction = log_actions_in_thread!(
+        received_server_instructions,
+        ServerInstruction::KillSession,
+        server_receiver
+    );
+    let page_scroll_up_action = CliAction::PageScrollUp;
+    let page_scroll_down_action = CliAction::PageScrollDown;
+    let mut pane_contents = String::new();
+    for i in 0..20 {
+        pane_contents.push_str(&format!("fill pane up with something {}\n\r", i));
+    }
+    let _ = mock_screen.to_screen.send(ScreenInstruction::PtyBytes(
+        0,
+        pane_contents.as_bytes().to_vec(),
+    ));
+    std::thread::sleep(std::time::Duration::from_millis(100));
+
+    // scroll up some
+    send_cli_action_to_server(&session_metadata, page_scroll_up_action.clone(), client_id);
+    send_cli_action_to_server(&session_metadata, page_scroll_up_action.clone(), client_id);
+
+    // scroll down
+    send_cli_action_to_server(&session_metadata, page_scroll_down_action, client_id);
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    mock_screen.teardown(vec![server_instruction, screen_thread]);
+    let snapshots = take_snapshots_and_cursor_coordinates_from_render_events(
+        received_server_instructions.lock().unwrap().iter(),
+        size,
+    );
+    let snapshot_count = snapshots.len();
+    for (_cursor_coordinates, snapshot) in snapshots {
+        assert_snapshot!(format!("{}", snapshot));
+    }
+    assert_snapshot!(format!("{}", snapshot_count));
+}
+
+#[test]
+pub fn send_cli_half_page_scroll_up_action() {
+    let size = Size { cols: 80, rows: 10 };
+    let client_id = 10; // fake client id should not appear in the screen's state
+    let mut initial_layout = TiledPaneLayout::default();
+    initial_layout.children_split_direction = SplitDirection::Vertical;
+    initial_layout.children = vec![TiledPaneLayout::default(), TiledPaneLayout::default()];
+    let mut mock_screen = MockScreen::new(size);
+    let session_metadata = mock_screen.clone_session_metadata();
+    let screen_thread = mock_screen.run(Some(initial_layout), vec![]);
+    let received_server_instructions = Arc::new(Mutex::new(vec![]));
+    let server_receiver = mock_screen.server_receiver.take().unwrap();
+    let server_instruction = log_actions_in_thread!(
+        received_server_instructions,
+        ServerInstruction::KillSession,
+        server_receiver
+    );
+    let half_page_scroll_up_action = CliAction::HalfPageScrollUp;
+    let mut pane_contents = String::new();
+    for i in 0..20 {
+        pane_contents.push_str(&format!("fill pane up with something {}\n\r", i));
+    }
+    let _ = mock_screen.to_screen.send(ScreenInstruction::PtyBytes(
+        0,
+        pane_contents.as_bytes().to_vec(),
+    ));
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    send_cli_action_to_server(&session_metadata, half_page_scroll_up_action, client_id);
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    mock_screen.teardown(vec![server_instruction, screen_thread]);
+    let snapshots = take_snapshots_and_cursor_coordinates_from_render_events(
+        received_server_instructions.lock().unwrap().iter(),
+        size,
+    );
+    let snapshot_count = snapshots.len();
+    for (_cursor_coordinates, snapshot) in snapshots {
+        assert_snapshot!(format!("{}", snapshot));
+    }
+    assert_snapshot!(format!("{}", snapshot_count));
+}
+
+#[test]
+pub fn send_cli_half_page_scroll_down_action() {
+    let size = Size { cols: 80, rows: 10 };
+    let client_id = 10; // fake client id should not appear in the screen's state
+    let mut initial_layout = TiledPaneLayout::default();
+    initial_layout.children_split_direction = SplitDirection::Vertical;
+    initial_layout.children = vec![TiledPaneLayout::default(), TiledPaneLayout::default()];
+    let mut mock_screen = MockScreen::new(size);
+    let session_metadata = mock_screen.clone_session_metadata();
+    let screen_thread = mock_screen.run(Some(initial_layout), vec![]);
+    let received_server_instructions = Arc::new(Mutex::new(vec![]));
+    let server_receiver = mock_screen.server_receiver.take().unwrap();
+    let server_instruction = log_actions_in_thread!(
+        received_server_instructions,
+        ServerInstruction::KillSession,
+        server_receiver
+    );
+    let half_page_scroll_up_action = CliAction::HalfPageScrollUp;
+    let half_page_scroll_down_action = CliAction::HalfPageScrollDown;
+    let mut pane_contents = String::new();
+    for i in 0..20 {
+        pane_contents.push_str(&format!("fill pane up with something {}\n\r", i));
+    }
+    let _ = mock_screen.to_screen.send(ScreenInstruction::PtyBytes(
+        0,
+        pane_contents.as_bytes().to_vec(),
+    ));
+    std::thread::sleep(std::time::Duration::from_millis(100));
+
+    // scroll up some
+    send_cli_action_to_server(
+        &session_metadata,
+        half_page_scroll_up_action.clone(),
+        client_id,
+    );
+    send_cli_action_to_server(
+        &session_metadata,
+        half_page_scroll_up_action.clone(),
+        client_id,
+    );
+
+    // scroll down
+    send_cli_action_to_server(&session_metadata, half_page_scroll_down_action, client_id);
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    mock_screen.teardown(vec![server_instruction, screen_thread]);
+    let snapshots = take_snapshots_and_cursor_coordinates_from_render_events(
+        received_server_instructions.lock().unwrap().iter(),
+        size,
+    );
+    let snapshot_count = snapshots.len();
+    for (_cursor_coordinates, snapshot) in snapshots {
+        assert_snapshot!(format!("{}", snapshot));
+    }
+    assert_snapshot!(format!("{}", snapshot_count));
+}
+
+#[test]
+pub fn send_cli_set_scroll_lines_action() {
+    let size = Size { cols: 80, rows: 10 };
+    let client_id = 1;
+    let mut initial_layout = TiledPaneLayout::default();
+    initial_layout.children = vec![TiledPaneLayout {
+        scroll_lines: Some(ScrollLines::Lines(2)),
+        ..Default::default()
+    }];
+    let mut mock_screen = MockScreen::new(size);
+    let session_metadata = mock_screen.clone_session_metadata();
+    let screen_thread = mock_screen.run(Some(initial_layout), vec![]);
+    let received_server_instructions = Arc::new(Mutex::new(vec![]));
+    let server_receiver = mock_screen.server_receiver.take().unwrap();
+    let server_instruction = log_actions_in_thread!(
+        received_server_instructions,
+        ServerInstruction::KillSession,
+        server_receiver
+    );
+    let mut pane_contents = String::new();
+    for i in 0..40 {
+        pane_contents.push_str(&format!("line {}\n\r", i));
+    }
+    let _ = mock_screen.to_screen.send(ScreenInstruction::PtyBytes(
+        0,
+        pane_contents.as_bytes().to_vec(),
+    ));
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    let point_in_pane = Position::new(5, 10);
+    // scrolled by the scroll lines from the layout
+    let _ = mock_screen
+        .to_screen
+        .send(ScreenInstruction::ScrollUpAt(point_in_pane, client_id));
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    send_cli_action_to_server(
+        &session_metadata,
+        CliAction::SetScrollLines {
+            scroll_lines: Some(ScrollLines::Lines(5)),
+        },
+        client_id,
+    );
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    // scrolled by the scroll lines set with the action
+    let _ = mock_screen
+        .to_screen
+        .send(ScreenInstruction::ScrollUpAt(point_in_pane, client_id));
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    mock_screen.teardown(vec![server_instruction, screen_thread]);
+    let snapshots = take_snapshots_and_cursor_coordinates_from_render_events(
+        received_server_instructions.lock().unwrap().iter(),
+        size,
+    );
+    // eg. "SCROLL:  2/33" in the frame of the pane, once it is scrolled
+    let scroll_position = |snapshot: &String| -> Option<usize> {
+        let position_start = snapshot.find("SCROLL:")? + "SCROLL:".len();
+        snapshot[position_start..]
+            .trim_start()
+            .chars()
+            .take_while(|c| c.is_ascii_digit())
+            .collect::<String>()
+            .parse()
+            .ok()
+    };
+    let mut scroll_positions: Vec<usize> = snapshots
+        .iter()
+        .filter_map(|(_cursor_coordinates, snapshot)| scroll_position(snapshot))
+        .collect();
+    scroll_positions.dedup();
+    assert_eq!(
+        scroll_positions,
+        vec![2, 7],
+        "the pane is scrolled by the lines from the layout, then by those set with the action"
+    );
+}
+
+#[test]
+pub fn send_cli_toggle_full_screen_action() {
+    let size = Size { cols: 80, rows: 10 };
+    let client_id = 10; // fake client id should not appear in the screen's state
+    let mut initial_layout = TiledPaneLayout::default();
+    initial_layout.children_split_direction = SplitDirection::Vertical;
+    initial_layout.children = vec![TiledPaneLayout::default(), TiledPaneLayout::default()];
+    let mut mock_screen = MockScreen::new(size);
+    let session_metadata = mock_screen.clone_session_metadata();
+    let screen_thread = mock_screen.run(Some(initial_layout), vec![]);
+    let received_server_instructions = Arc::new(Mutex::new(vec![]));
+    let server_receiver = mock_screen.server_receiver.take().unwrap();
+    let server_instruction = log_actions_in_thread!(
+        received_server_instructions,
+        ServerInstruction::KillSession,
+        server_receiver
+    );
+    let toggle_full_screen_action = CliAction::ToggleFullscreen;
+    send_cli_action_to_server(&session_metadata, toggle_full_screen_action, client_id);
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    mock_screen.teardown(vec![server_instruction, screen_thread]);
+    let snapshots = take_snapshots_and_cursor_coordinates_from_render_events(
+        received_server_instructions.lock().unwrap().iter(),
+        size,
+    );
+    let snapshot_count = snapshots.len();
+    for (_cursor_coordinates, snapshot) in snapshots {
+        assert_snapshot!(format!("{}", snapshot));
+    }
+    assert_snapshot!(format!("{}", snapshot_count));
+}
+
+#[test]
+pub fn send_cli_toggle_pane_frames_action() {
+    let size = Size { cols: 80, rows: 10 };
+    let client_id = 10; // fake client id should not appear in the screen's state
+    let mut initial_layout = TiledPaneLayout::default();
+    initial_layout.children_split_direction = SplitDirection::Vertical;
+    initial_layout.children = vec![TiledPaneLayout::default(), TiledPaneLayout::default()];
+    let mut mock_screen = MockScreen::new(size);
+    let session_metadata = mock_screen.clone_session_metadata();
+    let screen_thread = mock_screen.run(Some(initial_layout), vec![]);
+    let received_server_instructions = Arc::new(Mutex::new(vec![]));
+    let server_receiver = mock_screen.server_receiver.take().unwrap();
+    let server_instruction = log_actions_in_thread!(
+        received_server_instructions,
+        ServerInstruction::KillSession,
+        server_receiver
+    );
+    let toggle_pane_frames_action = CliAction::TogglePaneFrames;
+    send_cli_action_to_server(&session_metadata, toggle_pane_frames_action, client_id);
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    mock_screen.teardown(vec![server_instruction, screen_thread]);
+    let snapshots = take_snapshots_and_cursor_coordinates_from_render_events(
+        received_server_instructions.lock().unwrap().iter(),
+        size,
+    );
+    let snapshot_count = snapshots.len();
+    for (_cursor_coordinates, snapshot) in snapshots {
+        assert_snapshot!(format!("{}", snapshot));
+    }
+    assert_snapshot!(format!("{}", snapshot_count));
+}
+
+#[test]
+pub fn send_cli_toggle_active_tab_sync_action() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let client_id = 10; // fake client id should not appear in the screen's state
+    let mut mock_screen = MockScreen::new(size);
+    let pty_writer_receiver = mock_screen.pty_writer_receiver.take().unwrap();
+    let session_metadata = mock_screen.clone_session_metadata();
+    let mut initial_layout = TiledPaneLayout::default();
+    initial_layout.children_split_direction = SplitDirection::Vertical;
+    initial_layout.children = vec![TiledPaneLayout::default(), TiledPaneLayout::default()];
+    let screen_thread = mock_screen.run(Some(initial_layout), vec![]);
+    let received_pty_instructions = Arc::new(Mutex::new(vec![]));
+    let pty_writer_thread = log_actions_in_thread!(
+        received_pty_instructions,
+        PtyWriteInstruction::Exit,
+        pty_writer_receiver
+    );
+    let cli_toggle_active_tab_sync_action = CliAction::ToggleActiveSyncTab;
+    let cli_write_action = CliAction::Write {
+        bytes: vec![102, 111, 111],
+    };
+    send_cli_action_to_server(
+        &session_metadata,
+        cli_toggle_active_tab_sync_action,
+        client_id,
+    );
+    send_cli_action_to_server(&session_metadata, cli_write_action, client_id);
+    std::thread::sleep(std::time::Duration::from_millis(100)); // give time for actions to be
+    mock_screen.teardown(vec![pty_writer_thread, screen_thread]);
+    assert_snapshot!(format!("{:?}", *received_pty_instructions.lock().unwrap()));
+}
+
+#[test]
+pub fn screen_can_set_input_broadcast_for_tab() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let client_id = 10; // fake client id should not appear in the screen's state
+    let mut mock_screen = MockScreen::new(size);
+    let pty_writer_receiver = mock_screen.pty_writer_receiver.take().unwrap();
+    let session_metadata = mock_screen.clone_session_metadata();
+    let mut initial_layout = TiledPaneLayout::default();
+    initial_layout.children_split_direction = SplitDirection::Vertical;
+    initial_layout.children = vec![TiledPaneLayout::default(), TiledPaneLayout::default()];
+    let screen_thread = mock_screen.run(Some(initial_layout), vec![]);
+    let received_pty_instructions = Arc::new(Mutex::new(vec![]));
+    let pty_writer_thread = log_actions_in_thread!(
+        received_pty_instructions,
+        PtyWriteInstruction::Exit,
+        pty_writer_receiver
+    );
+    let tab_index = 0;
+    let _ = mock_screen
+        .to_screen
+        .send(ScreenInstruction::SetInputBroadcast(
+            Some(tab_index),
+            true,
+            client_id,
+        ));
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    send_cli_action_to_server(
+        &session_metadata,
+        CliAction::Write {
+            bytes: vec![102, 111, 111],
+        },
+        client_id,
+    );
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    let _ = mock_screen
+        .to_screen
+        .send(ScreenInstruction::SetInputBroadcast(
+            Some(tab_index),
+            false,
+            client_id,
+        ));
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    send_cli_action_to_server(
+        &session_metadata,
+        CliAction::Write {
+            bytes: vec![98, 97, 114],
+        },
+        client_id,
+    );
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    mock_screen.teardown(vec![pty_writer_thread, screen_thread]);
+    let writes: Vec<(Vec<u8>, u32)> = received_pty_instructions
+        .lock()
+        .unwrap()
+        .iter()
+        .filter_map(|instruction| match instruction {
+            PtyWriteInstruction::Write(bytes, terminal_id) => Some((bytes.clone(), *terminal_id)),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        &writes[..2],
+        &[(vec![102, 111, 111], 0), (vec![102, 111, 111], 1)],
+        "input written to all panes while broadcast is enabled"
+    );
+    assert_eq!(
+        writes[2..]
+            .iter()
+            .map(|(bytes, _)| bytes)
+            .collect::<Vec<_>>(),
+        vec![&vec![98, 97, 114]],
+        "input written only to the focused pane once broadcast is disabled"
+    );
+}
+
+#[test]
+pub fn send_cli_set_pane_read_only_action() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let client_id = 10; // fake client id should not appear in the screen's state
+    let mut mock_screen = MockScreen::new(size);
+    let pty_writer_receiver = mock_screen.pty_writer_receiver.take().unwrap();
+    let session_metadata = mock_screen.clone_session_metadata();
+    let screen_thread = mock_screen.run(None, vec![]);
+    let received_pty_instructions = Arc::new(Mutex::new(vec![]));
+    let pty_writer_thread = log_actions_in_thread!(
+        received_pty_instructions,
+        PtyWriteInstruction::Exit,
+        pty_writer_receiver
+    );
+    send_cli_action_to_server(
+        &session_metadata,
+        CliAction::SetPaneReadOnly { off: false },
+        client_id,
+    );
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    send_cli_action_to_server(
+        &session_metadata,
+        CliAction::Write {
+            bytes: vec![102, 111, 111],
+        },
+        client_id,
+    );
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    send_cli_action_to_server(
+        &session_metadata,
+        CliAction::SetPaneReadOnly { off: true },
+        client_id,
+    );
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    send_cli_action_to_server(
+        &session_metadata,
+        CliAction::Write {
+            bytes: vec![98, 97, 114],
+        },
+        client_id,
+    );
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    mock_screen.teardown(vec![pty_writer_thread, screen_thread]);
+    let writes: Vec<Vec<u8>> = received_pty_instructions
+        .lock()
+        .unwrap()
+        .iter()
+        .filter_map(|instruction| match instruction {
+            PtyWriteInstruction::Write(bytes, _terminal_id) => Some(bytes.clone()),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        writes,
+        vec![vec![98, 97, 114]],
+        "input written to the focused pane only once it is no longer read only"
+    );
+}
+
+#[test]
+pub fn screen_renders_plugin_overlay_on_top_of_panes() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let mut mock_screen = MockScreen::new(size);
+    let mut initial_layout = TiledPaneLayout::default();
+    initial_layout.children_split_direction = SplitDirection::Vertical;
+    initial_layout.children = vec![TiledPaneLayout::default(), TiledPaneLayout::default()];
+    let screen_thread = mock_screen.run(Some(initial_layout), vec![]);
+    let received_server_instructions = Arc::new(Mutex::new(vec![]));
+    let server_receiver = mock_screen.server_receiver.take().unwrap();
+    let server_thread = log_actions_in_thread!(
+        received_server_instructions,
+        ServerInstruction::KillSession,
+        server_receiver
+    );
+    let plugin_id = 1;
+    let _ = mock_screen
+        .to_screen
+        .send(ScreenInstruction::RenderPluginOverlay(
+            plugin_id,
+            PluginOverlay::new(2, 3, 20, 2, b"overlay line one\noverlay line two".to_vec()),
+        ));
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    mock_screen.teardown(vec![server_thread, screen_thread]);
+    let snapshots = take_snapshots_and_cursor_coordinates_from_render_events(
+        received_server_instructions.lock().unwrap().iter(),
+        size,
+    );
+    let (_cursor_coordinates, last_snapshot) = snapshots.last().unwrap();
+    let overlay_lines: Vec<String> = last_snapshot
+        .lines()
+        .skip(3)
+        .take(2)
+        .map(|line| line.chars().skip("03 (C): ".len() + 2).take(16).collect())
+        .collect();
+    assert_eq!(
+        overlay_lines,
+        vec!["overlay line one".to_owned(), "overlay line two".to_owned()],
+        "overlay drawn at its absolute coordinates on top of the panes"
+    );
+}
+
+#[test]
+pub fn plugin_overlay_only_keeps_styling_escape_sequences() {
+    let size = Size { cols: 20, rows: 5 };
+    let contents =
+        b"\x1b[31mred\x1b[2J\x1b[5;5H\x1b]0;title\x07\x1b]52;c;Zm9v\x1b\\\x1b7\x08ok".to_vec();
+    let overlay = PluginOverlay::new(0, 0, 10, 1, contents)
+        .generate_overlay(size)
+        .unwrap();
+    assert_eq!(
+        overlay, "\u{1b}7\u{1b}[1;1H\u{1b}[m\u{1b}[31mredok\u{1b}[m     \u{1b}[m\u{1b}8",
+        "cursor movement, clearing, OSC sequences and control characters were dropped"
+    );
+}
+
+#[test]
+pub fn send_cli_new_pane_action_with_default_parameters() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let client_id = 10; // fake client id should not appear in the screen's state
+    let mut mock_screen = MockScreen::new(size);
+    let pty_receiver = mock_screen.pty_receiver.take().unwrap();
+    let session_metadata = mock_screen.clone_session_metadata();
+    let mut initial_layout = TiledPaneLayout::default();
+    initial_layout.children_split_direction = SplitDirection::Vertical;
+    initial_layout.children = vec![TiledPaneLayout::default(), TiledPaneLayout::default()];
+    let screen_thread = mock_screen.run(Some(initial_layout), vec![]);
+    let received_pty_instructions = Arc::new(Mutex::new(vec![]));
+    let pty_thread = log_actions_in_thread!(
+        received_pty_instructions,
+        PtyInstruction::Exit,
+        pty_receiver
+    );
+    let cli_new_pane_action = CliAction::NewPane {
+        direction: None,
+        command: vec![],
+        plugin: None,
+        cwd: None,
+        floating: false,
+        in_place: false,
+        name: None,
+        close_on_exit: false,
+        start_suspended: false,
+        configuration: None,
+        enter_mode: None,
+    };
+    send_cli_action_to_server(&session_metadata, cli_new_pane_action, client_id);
+    std::thread::sleep(std::time::Duration::from_millis(100)); // give time for actions to be
+    mock_screen.teardown(vec![pty_thread, screen_thread]);
+    assert_snapshot!(format!("{:?}", *received_pty_instructions.lock().unwrap()));
+}
+
+#[test]
+pub fn send_cli_split_pane_with_ratio_action() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let client_id = 10; // fake client id should not appear in the screen's state
+    let mut mock_screen = MockScreen::new(size);
+    let pty_receiver = mock_screen.pty_receiver.take().unwrap();
+    let session_metadata = mock_screen.clone_session_metadata();
+    let screen_thread = mock_screen.run(None, vec![]);
+    let received_pty_instructions = Arc::new(Mutex::new(vec![]));
+    let pty_thread = log_actions_in_thread!(
+        received_pty_instructions,
+        PtyInstruction::Exit,
+        pty_receiver
+    );
+    let cli_split_pane_action = CliAction::SplitPaneWithRatio {
+        direction: Direction::Down,
+        percent: 30,
+    };
+    send_cli_action_to_server(&session_metadata, cli_split_pane_action, client_id);
+    std::thread::sleep(std::time::Duration::from_millis(100)); // give time for actions to be
+    mock_screen.teardown(vec![pty_thread, screen_thread]);
+    let spawned_split =
+        received_pty_instructions.lock().unwrap().iter().find_map(
+            |instruction| match instruction {
+                PtyInstruction::SpawnTerminalWithRatio(_, pane_id, direction, ratio, _) => {
+                    Some((*pane_id, *direction, *ratio))
+                },
+                _ => None,
+            },
+        );
+    assert_eq!(
+        spawned_split,
+        Some((None, SplitDirection::Horizontal, 0.3)),
+        "a terminal was spawned to split the focused pane, which keeps 30% of its rows"
+    );
+}
+
+#[test]
+pub fn send_cli_new_pane_action_with_split_direction() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let client_id = 10; // fake client id should not appear in the screen's state
+    let mut mock_screen = MockScreen::new(size);
+    let pty_receiver = mock_screen.pty_receiver.take().unwrap();
+    let session_metadata = mock_screen.clone_session_metadata();
+    let mut initial_layout = TiledPaneLayout::default();
+    initial_layout.children_split_direction = SplitDirection::Vertical;
+    initial_layout.children = vec![TiledPaneLayout::default(), TiledPaneLayout::default()];
+    let screen_thread = mock_screen.run(Some(initial_layout), vec![]);
+    let received_pty_instructions = Arc::new(Mutex::new(vec![]));
+    let pty_thread = log_actions_in_thread!(
+        received_pty_instructions,
+        PtyInstruction::Exit,
+        pty_receiver
+    );
+    let cli_new_pane_action = CliAction::NewPane {
+        direction: Some(Direction::Right),
+        command: vec![],
+        plugin: None,
+        cwd: None,
+        floating: false,
+        in_place: false,
+        name: None,
+        close_on_exit: false,
+        start_suspended: false,
+        configuration: None,
+        enter_mode: None,
+    };
+    send_cli_action_to_server(&session_metadata, cli_new_pane_action, client_id);
+    std::thread::sleep(std::time::Duration::from_millis(100)); // give time for actions to be
+    mock_screen.teardown(vec![pty_thread, screen_thread]);
+    assert_snapshot!(format!("{:?}", *received_pty_instructions.lock().unwrap()));
+}
+
+#[test]
+pub fn send_cli_new_pane_action_with_command_and_cwd() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let client_id = 10; // fake client id should not appear in the screen's state
+    let mut mock_screen = MockScreen::new(size);
+    let pty_receiver = mock_screen.pty_receiver.take().unwrap();
+    let session_metadata = mock_screen.clone_session_metadata();
+    let mut initial_layout = TiledPaneLayout::default();
+    initial_layout.children_split_direction = SplitDirection::Vertical;
+    initial_layout.children = vec![TiledPaneLayout::default(), TiledPaneLayout::default()];
+    let screen_thread = mock_screen.run(Some(initial_layout), vec![]);
+    let received_pty_instructions = Arc::new(Mutex::new(vec![]));
+    let pty_thread = log_actions_in_thread!(
+        received_pty_instructions,
+        PtyInstruction::Exit,
+        pty_receiver
+    );
+    let cli_new_pane_action = CliAction::NewPane {
+        direction: Some(Direction::Right),
+        command: vec!["htop".into()],
+        plugin: None,
+        cwd: Some("/some/folder".into()),
+        floating: false,
+        in_place: false,
+        name: None,
+        close_on_exit: false,
+        start_suspended: false,
+        configuration: None,
+        enter_mode: None,
+    };
+    send_cli_action_to_server(&session_metadata, cli_new_pane_action, client_id);
+    std::thread::sleep(std::time::Duration::from_millis(100)); // give time for actions to be
+    mock_screen.teardown(vec![pty_thread, screen_thread]);
+    assert_snapshot!(format!("{:?}", *received_pty_instructions.lock().unwrap()));
+}
+
+#[test]
+pub fn send_cli_new_pane_action_with_enter_mode() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let client_id = 10; // fake client id should not appear in the screen's state
+    let mut mock_screen = MockScreen::new(size);
+    let pty_receiver = mock_screen.pty_receiver.take().unwrap();
+    let session_metadata = mock_screen.clone_session_metadata();
+    let mut initial_layout = TiledPaneLayout::default();
+    initial_layout.children_split_direction = SplitDirection::Vertical;
+    initial_layout.children = vec![TiledPaneLayout::default(), TiledPaneLayout::default()];
+    let screen_thread = mock_screen.run(Some(initial_layout), vec![]);
+    let received_pty_instructions = Arc::new(Mutex::new(vec![]));
+    let pty_thread = log_actions_in_thread!(
+        received_pty_instructions,
+        PtyInstruction::Exit,
+        pty_receiver
+    );
+    let other_client_id = 2;
+    let _ = mock_screen.to_screen.send(ScreenInstruction::AddClient(
+        other_client_id,
+        None,
+        None,
+        false,
+        false,
+    ));
+    let cli_new_pane_action = CliAction::NewPane {
+        direction: None,
+        command: vec![],
+        plugin: None,
+        cwd: None,
+        floating: false,
+        in_place: false,
+        name: None,
+        close_on_exit: false,
+        start_suspended: false,
+        configuration: None,
+        enter_mode: Some(InputMode::Scroll),
+    };
+    send_cli_action_to_server(&session_metadata, cli_new_pane_action, client_id);
+    std::thread::sleep(std::time::Duration::from_millis(100)); // give time for actions to be
+    mock_screen.teardown(vec![pty_thread, screen_thread]);
+    let new_pane_was_spawned = received_pty_instructions
+        .lock()
+        .unwrap()
+        .iter()
+        .any(|instruction| matches!(instruction, PtyInstruction::SpawnTerminal(..)));
+    assert!(new_pane_was_spawned, "new pane spawned");
+    let server_to_client_messages = mock_screen
+        .os_input
+        .server_to_client_messages
+        .lock()
+        .unwrap();
+    let mode_switched_to_scroll = server_to_client_messages
+        .get(&1)
+        .unwrap()
+        .iter()
+        .any(|message| matches!(message, ServerToClientMsg::SwitchToMode(InputMode::Scroll)));
+    assert!(mode_switched_to_scroll, "client switched to scroll mode");
+    let other_client_switched_mode = server_to_client_messages
+        .get(&other_client_id)
+        .map(|messages| {
+            messages
+                .iter()
+                .any(|message| matches!(message, ServerToClientMsg::SwitchToMode(..)))
+        })
+        .unwrap_or(false);
+    assert!(
+        !other_client_switched_mode,
+        "only the client the pane was opened for switched mode"
+    );
+}
+
+#[test]
+pub fn send_cli_edit_action_with_default_parameters() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let client_id = 10; // fake client id should not appear in the screen's state
+    let mut mock_screen = MockScreen::new(size);
+    let pty_receiver = mock_screen.pty_receiver.take().unwrap();
+    let session_metadata = mock_screen.clone_session_metadata();
+    let mut initial_layout = TiledPaneLayout::default();
+    initial_layout.children_split_direction = SplitDirection::Vertical;
+    initial_layout.children = vec![TiledPaneLayout::default(), TiledPaneLayout::default()];
+    let screen_thread = mock_screen.run(Some(initial_layout), vec![]);
+    let received_pty_instructions = Arc::new(Mutex::new(vec![]));
+    let pty_thread = log_actions_in_thread!(
+        received_pty_instructions,
+        PtyInstruction::Exit,
+        pty_receiver
+    );
+    let cli_edit_action = CliAction::Edit {
+        file: PathBuf::from("/file/to/edit"),
+        direction: None,
+        line_number: None,
+        floating: false,
+        in_place: false,
+        cwd: None,
+    };
+    send_cli_action_to_server(&session_metadata, cli_edit_action, client_id);
+    std::thread::sleep(std::time::Duration::from_millis(100)); // give time for actions to be
+    mock_screen.teardown(vec![pty_thread, screen_thread]);
+    assert_snapshot!(format!("{:?}", *received_pty_instructions.lock().unwrap()));
+}
+
+#[test]
+pub fn send_cli_edit_action_with_line_number() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let client_id = 10; // fake client id should not appear in the screen's state
+    let mut mock_screen = MockScreen::new(size);
+    let pty_receiver = mock_screen.pty_receiver.take().unwrap();
+    let session_metadata = mock_screen.clone_session_metadata();
+    let mut initial_layout = TiledPaneLayout::default();
+    initial_layout.children_split_direction = SplitDirection::Vertical;
+    initial_layout.children = vec![TiledPaneLayout::default(), TiledPaneLayout::default()];
+    let screen_thread = mock_screen.run(Some(initial_layout), vec![]);
+    let received_pty_instructions = Arc::new(Mutex::new(vec![]));
+    let pty_thread = log_actions_in_thread!(
+        received_pty_instructions,
+        PtyInstruction::Exit,
+        pty_receiver
+    );
+    let cli_edit_action = CliAction::Edit {
+        file: PathBuf::from("/file/to/edit"),
+        direction: None,
+        line_number: Some(100),
+        floating: false,
+        in_place: false,
+        cwd: None,
+    };
+    send_cli_action_to_server(&session_metadata, cli_edit_action, client_id);
+    std::thread::sleep(std::time::Duration::from_millis(100)); // give time for actions to be
+    mock_screen.teardown(vec![pty_thread, screen_thread]);
+    assert_snapshot!(format!("{:?}", *received_pty_instructions.lock().unwrap()));
+}
+
+#[test]
+pub fn send_cli_edit_action_with_split_direction() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let client_id = 10; // fake client id should not appear in the screen's state
+    let mut mock_screen = MockScreen::new(size);
+    let pty_receiver = mock_screen.pty_receiver.take().unwrap();
+    let session_metadata = mock_screen.clone_session_metadata();
+    let mut initial_layout = TiledPaneLayout::default();
+    initial_layout.children_split_direction = SplitDirection::Vertical;
+    initial_layout.children = vec![TiledPaneLayout::default(), TiledPaneLayout::default()];
+    let screen_thread = mock_screen.run(Some(initial_layout), vec![]);
+    let received_pty_instructions = Arc::new(Mutex::new(vec![]));
+    let pty_thread = log_actions_in_thread!(
+        received_pty_instructions,
+        PtyInstruction::Exit,
+        pty_receiver
+    );
+    let cli_edit_action = CliAction::Edit {
+        file: PathBuf::from("/file/to/edit"),
+        direction: Some(Direction::Down),
+        line_number: None,
+        floating: false,
+        in_place: false,
+        cwd: None,
+    };
+    send_cli_action_to_server(&session_metadata, cli_edit_action, client_id);
+    std::thread::sleep(std::time::Duration::from_millis(100)); // give time for actions to be
+    mock_screen.teardown(vec![pty_thread, screen_thread]);
+    assert_snapshot!(format!("{:?}", *received_pty_instructions.lock().unwrap()));
+}
+
+#[test]
+pub fn send_cli_switch_mode_action() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let client_id = 10; // fake client id should not appear in the screen's state
+    let mut mock_screen = MockScreen::new(size);
+    let session_metadata = mock_screen.clone_session_metadata();
+    let mut initial_layout = TiledPaneLayout::default();
+    initial_layout.children_split_direction = SplitDirection::Vertical;
+    initial_layout.children = vec![TiledPaneLayout::default(), TiledPaneLayout::default()];
+    let screen_thread = mock_screen.run(Some(initial_layout), vec![]);
+    let cli_switch_mode = CliAction::SwitchMode {
+        input_mode: InputMode::Locked,
+    };
+    send_cli_action_to_server(&session_metadata, cli_switch_mode, client_id);
+    std::thread::sleep(std::time::Duration::from_millis(100)); // give time for actions to be
+    mock_screen.teardown(vec![screen_thread]);
+    assert_snapshot!(format!(
         "{:?}",
-        *mock_screen.os_input.fake_filesystem.lock().unwrap()
+        *mock_screen
+            .os_input
+            .server_to_client_messages
+            .lock()
+            .unwrap()
+    ));
+}
+
+#[test]
+pub fn send_cli_toggle_pane_embed_or_float() {
+    let size = Size { cols: 80, rows: 10 };
+    let client_id = 10; // fake client id should not appear in the screen's state
+    let mut initial_layout = TiledPaneLayout::default();
+    initial_layout.children_split_direction = SplitDirection::Vertical;
+    initial_layout.children = vec![TiledPaneLayout::default(), TiledPaneLayout::default()];
+    let mut mock_screen = MockScreen::new(size);
+    let session_metadata = mock_screen.clone_session_metadata();
+    let screen_thread = mock_screen.run(Some(initial_layout), vec![]);
+    let received_server_instructions = Arc::new(Mutex::new(vec![]));
+    let server_receiver = mock_screen.server_receiver.take().unwrap();
+    let server_instruction = log_actions_in_thread!(
+        received_server_instructions,
+        ServerInstruction::KillSession,
+        server_receiver
+    );
+    let toggle_pane_embed_or_floating = CliAction::TogglePaneEmbedOrFloating;
+    // first time to float
+    send_cli_action_to_server(
+        &session_metadata,
+        toggle_pane_embed_or_floating.clone(),
+        client_id,
+    );
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    // second time to embed
+    send_cli_action_to_server(
+        &session_metadata,
+        toggle_pane_embed_or_floating.clone(),
+        client_id,
+    );
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    mock_screen.teardown(vec![server_instruction, screen_thread]);
+    let snapshots = take_snapshots_and_cursor_coordinates_from_render_events(
+        received_server_instructions.lock().unwrap().iter(),
+        size,
+    );
+    let snapshot_count = snapshots.len();
+    for (_cursor_coordinates, snapshot) in snapshots {
+        assert_snapshot!(format!("{}", snapshot));
+    }
+    assert_snapshot!(format!("{}", snapshot_count));
+}
+
+#[test]
+pub fn send_cli_toggle_floating_panes() {
+    let size = Size { cols: 80, rows: 10 };
+    let client_id = 10; // fake client id should not appear in the screen's state
+    let mut initial_layout = TiledPaneLayout::default();
+    initial_layout.children_split_direction = SplitDirection::Vertical;
+    initial_layout.children = vec![TiledPaneLayout::default(), TiledPaneLayout::default()];
+    let mut mock_screen = MockScreen::new(size);
+    let session_metadata = mock_screen.clone_session_metadata();
+    let screen_thread = mock_screen.run(Some(initial_layout), vec![]);
+    let received_server_instructions = Arc::new(Mutex::new(vec![]));
+    let server_receiver = mock_screen.server_receiver.take().unwrap();
+    let server_instruction = log_actions_in_thread!(
+        received_server_instructions,
+        ServerInstruction::KillSession,
+        server_receiver
+    );
+    let toggle_pane_embed_or_floating = CliAction::TogglePaneEmbedOrFloating;
+    let toggle_floating_panes = CliAction::ToggleFloatingPanes;
+    // float the focused pane
+    send_cli_action_to_server(&session_metadata, toggle_pane_embed_or_floating, client_id);
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    // toggle floating panes (will hide the floated pane from the previous action)
+    send_cli_action_to_server(&session_metadata, toggle_floating_panes.clone(), client_id);
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    // toggle floating panes (will show the floated pane)
+    send_cli_action_to_server(&session_metadata, toggle_floating_panes.clone(), client_id);
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    mock_screen.teardown(vec![server_instruction, screen_thread]);
+    let snapshots = take_snapshots_and_cursor_coordinates_from_render_events(
+        received_server_instructions.lock().unwrap().iter(),
+        size,
+    );
+    let snapshot_count = snapshots.len();
+    for (_cursor_coordinates, snapshot) in snapshots {
+        assert_snapshot!(format!("{}", snapshot));
+    }
+    assert_snapshot!(format!("{}", snapshot_count));
+}
+
+#[test]
+pub fn send_cli_close_pane_action() {
+    let size = Size { cols: 80, rows: 10 };
+    let client_id = 10; // fake client id should not appear in the screen's state
+    let mut initial_layout = TiledPaneLayout::default();
+    initial_layout.children_split_direction = SplitDirection::Vertical;
+    initial_layout.children = vec![TiledPaneLayout::default(), TiledPaneLayout::default()];
+    let mut mock_screen = MockScreen::new(size);
+    let session_metadata = mock_screen.clone_session_metadata();
+    let screen_thread = mock_screen.run(Some(initial_layout), vec![]);
+    let received_server_instructions = Arc::new(Mutex::new(vec![]));
+    let server_receiver = mock_screen.server_receiver.take().unwrap();
+    let server_instruction = log_actions_in_thread!(
+        received_server_instructions,
+        ServerInstruction::KillSession,
+        server_receiver
+    );
+    let close_pane_action = CliAction::ClosePane;
+    send_cli_action_to_server(&session_metadata, close_pane_action, client_id);
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    mock_screen.teardown(vec![server_instruction, screen_thread]);
+    let snapshots = take_snapshots_and_cursor_coordinates_from_render_events(
+        received_server_instructions.lock().unwrap().iter(),
+        size,
+    );
+    let snapshot_count = snapshots.len();
+    for (_cursor_coordinates, snapshot) in snapshots {
+        assert_snapshot!(format!("{}", snapshot));
+    }
+    assert_snapshot!(format!("{}", snapshot_count));
+}
+
+#[test]
+pub fn send_cli_new_tab_action_default_params() {
+    let size = Size { cols: 80, rows: 10 };
+    let client_id = 10; // fake client id should not appear in the screen's state
+    let mut initial_layout = TiledPaneLayout::default();
+    initial_layout.children_split_direction = SplitDirection::Vertical;
+    initial_layout.children = vec![TiledPaneLayout::default(), TiledPaneLayout::default()];
+    let mut mock_screen = MockScreen::new(size);
+    let session_metadata = mock_screen.clone_session_metadata();
+    let screen_thread = mock_screen.run(Some(initial_layout), vec![]);
+    let received_plugin_instructions = Arc::new(Mutex::new(vec![]));
+    let plugin_receiver = mock_screen.plugin_receiver.take().unwrap();
+    let plugin_thread = log_actions_in_thread!(
+        received_plugin_instructions,
+        PluginInstruction::Exit,
+        plugin_receiver
+    );
+    let new_tab_action = CliAction::NewTab {
+        name: None,
+        layout: None,
+        layout_dir: None,
+        cwd: None,
+    };
+    send_cli_action_to_server(&session_metadata, new_tab_action, client_id);
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    mock_screen.teardown(vec![plugin_thread, screen_thread]);
+    let received_plugin_instructions = received_plugin_instructions.lock().unwrap();
+    let new_tab_action =
+        received_plugin_instructions
+            .iter()
+            .find(|instruction| match instruction {
+                PluginInstruction::NewTab(..) => true,
+                _ => false,
+            });
+    assert_snapshot!(format!("{:#?}", new_tab_action));
+}
+
+#[test]
+pub fn send_cli_new_tab_action_with_name_and_layout() {
+    let size = Size { cols: 80, rows: 10 };
+    let client_id = 10; // fake client id should not appear in the screen's state
+    let mut initial_layout = TiledPaneLayout::default();
+    initial_layout.children_split_direction = SplitDirection::Vertical;
+    initial_layout.children = vec![TiledPaneLayout::default(), TiledPaneLayout::default()];
+    let mut mock_screen = MockScreen::new(size);
+    let session_metadata = mock_screen.clone_session_metadata();
+    let screen_thread = mock_screen.run(Some(initial_layout), vec![]);
+    let received_plugin_instructions = Arc::new(Mutex::new(vec![]));
+    let plugin_receiver = mock_screen.plugin_receiver.take().unwrap();
+    let plugin_thread = log_actions_in_thread!(
+        received_plugin_instructions,
+        PluginInstruction::Exit,
+        plugin_receiver
+    );
+    let new_tab_action = CliAction::NewTab {
+        name: Some("my-awesome-tab-name".into()),
+        layout: Some(PathBuf::from(format!(
+            "{}/src/unit/fixtures/layout-with-three-panes.kdl",
+            env!("CARGO_MANIFEST_DIR")
+        ))),
+        layout_dir: None,
+        cwd: None,
+    };
+    send_cli_action_to_server(&session_metadata, new_tab_action, client_id);
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    mock_screen.teardown(vec![plugin_thread, screen_thread]);
+    let new_tab_instruction = received_plugin_instructions
+        .lock()
+        .unwrap()
+        .iter()
+        .rev()
+        .find(|i| {
+            if let PluginInstruction::NewTab(..) = i {
+                return true;
+            } else {
+                return false;
+            }
+        })
+        .unwrap()
+        .clone();
+    assert_snapshot!(format!("{:#?}", new_tab_instruction));
+}
+
+#[test]
+pub fn send_cli_new_tab_action_with_tab_cwd_in_layout() {
+    let size = Size { cols: 80, rows: 10 };
+    let client_id = 10; // fake client id should not appear in the screen's state
+    let mut initial_layout = TiledPaneLayout::default();
+    initial_layout.children_split_direction = SplitDirection::Vertical;
+    initial_layout.children = vec![TiledPaneLayout::default(), TiledPaneLayout::default()];
+    let mut mock_screen = MockScreen::new(size);
+    let session_metadata = mock_screen.clone_session_metadata();
+    let screen_thread = mock_screen.run(Some(initial_layout), vec![]);
+    let received_plugin_instructions = Arc::new(Mutex::new(vec![]));
+    let plugin_receiver = mock_screen.plugin_receiver.take().unwrap();
+    let plugin_thread = log_actions_in_thread!(
+        received_plugin_instructions,
+        PluginInstruction::Exit,
+        plugin_receiver
+    );
+    let new_tab_action = CliAction::NewTab {
+        name: None,
+        layout: Some(PathBuf::from(format!(
+            "{}/src/unit/fixtures/layout-with-tab-cwd.kdl",
+            env!("CARGO_MANIFEST_DIR")
+        ))),
+        layout_dir: None,
+        cwd: Some(PathBuf::from("/tmp")),
+    };
+    send_cli_action_to_server(&session_metadata, new_tab_action, client_id);
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    mock_screen.teardown(vec![plugin_thread, screen_thread]);
+    let new_tab_instruction = received_plugin_instructions
+        .lock()
+        .unwrap()
+        .iter()
+        .rev()
+        .find(|i| {
+            if let PluginInstruction::NewTab(..) = i {
+                return true;
+            } else {
+                return false;
+            }
+        })
+        .unwrap()
+        .clone();
+    match new_tab_instruction {
+        PluginInstruction::NewTab(_, _, Some(tiled_panes), floating_panes, ..) => {
+            // panes without a cwd of their own get the tab's cwd, relative to the layout's
+            let tab_cwd = Some(Run::Cwd(PathBuf::from("/tmp/foo")));
+            assert_eq!(tiled_panes.children[0].run, tab_cwd);
+            assert_eq!(
+                tiled_panes.children[1].run,
+                Some(Run::Cwd(PathBuf::from("/tmp/foo/bar")))
+            );
+            assert_eq!(floating_panes[0].run, tab_cwd);
+        },
+        _ => panic!("Unexpected new tab instruction: {:?}", new_tab_instruction),
+    }
+}
+
+#[test]
+pub fn send_cli_next_tab_action() {
+    let size = Size { cols: 80, rows: 10 };
+    let client_id = 10; // fake client id should not appear in the screen's state
+    let mut initial_layout = TiledPaneLayout::default();
+    initial_layout.children_split_direction = SplitDirection::Vertical;
+    initial_layout.children = vec![TiledPaneLayout::default(), TiledPaneLayout::default()];
+    let mut second_tab_layout = TiledPaneLayout::default();
+    second_tab_layout.children_split_direction = SplitDirection::Horizontal;
+    second_tab_layout.children = vec![TiledPaneLayout::default(), TiledPaneLayout::default()];
+    let mut mock_screen = MockScreen::new(size);
+    mock_screen.new_tab(second_tab_layout);
+    let session_metadata = mock_screen.clone_session_metadata();
+    let screen_thread = mock_screen.run(Some(initial_layout), vec![]);
+    let received_server_instructions = Arc::new(Mutex::new(vec![]));
+    let server_receiver = mock_screen.server_receiver.take().unwrap();
+    let server_thread = log_actions_in_thread!(
+        received_server_instructions,
+        ServerInstruction::KillSession,
+        server_receiver
+    );
+    let goto_next_tab = CliAction::GoToNextTab;
+    send_cli_action_to_server(&session_metadata, goto_next_tab, client_id);
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    mock_screen.teardown(vec![server_thread, screen_thread]);
+    let snapshots = take_snapshots_and_cursor_coordinates_from_render_events(
+        received_server_instructions.lock().unwrap().iter(),
+        size,
+    );
+    let snapshot_count = snapshots.len();
+    for (_cursor_coordinates, snapshot) in snapshots {
+        assert_snapshot!(format!("{}", snapshot));
+    }
+    assert_snapshot!(format!("{}", snapshot_count));
+}
+
+#[test]
+pub fn send_cli_previous_tab_action() {
+    let size = Size { cols: 80, rows: 10 };
+    let client_id = 10; // fake client id should not appear in the screen's state
+    let mut initial_layout = TiledPaneLayout::default();
+    initial_layout.children_split_direction = SplitDirection::Vertical;
+    initial_layout.children = vec![TiledPaneLayout::default(), TiledPaneLayout::default()];
+    let mut second_tab_layout = TiledPaneLayout::default();
+    second_tab_layout.children_split_direction = SplitDirection::Horizontal;
+    second_tab_layout.children = vec![TiledPaneLayout::default(), TiledPaneLayout::default()];
+    let mut mock_screen = MockScreen::new(size);
+    mock_screen.new_tab(second_tab_layout);
+    let session_metadata = mock_screen.clone_session_metadata();
+    let screen_thread = mock_screen.run(Some(initial_layout), vec![]);
+    let received_server_instructions = Arc::new(Mutex::new(vec![]));
+    let server_receiver = mock_screen.server_receiver.take().unwrap();
+    let server_thread = log_actions_in_thread!(
+        received_server_instructions,
+        ServerInstruction::KillSession,
+        server_receiver
+    );
+    let goto_previous_tab = CliAction::GoToPreviousTab;
+    send_cli_action_to_server(&session_metadata, goto_previous_tab, client_id);
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    mock_screen.teardown(vec![server_thread, screen_thread]);
+    let snapshots = take_snapshots_and_cursor_coordinates_from_render_events(
+        received_server_instructions.lock().unwrap().iter(),
+        size,
+    );
+    let snapshot_count = snapshots.len();
+    for (_cursor_coordinates, snapshot) in snapshots {
+        assert_snapshot!(format!("{}", snapshot));
+    }
+    assert_snapshot!(format!("{}", snapshot_count));
+}
+
+#[test]
+pub fn send_cli_goto_tab_action() {
+    let size = Size { cols: 80, rows: 10 };
+    let client_id = 10; // fake client id should not appear in the screen's state
+    let mut initial_layout = TiledPaneLayout::default();
+    initial_layout.children_split_direction = SplitDirection::Vertical;
+    initial_layout.children = vec![TiledPaneLayout::default(), TiledPaneLayout::default()];
+    let mut second_tab_layout = TiledPaneLayout::default();
+    second_tab_layout.children_split_direction = SplitDirection::Horizontal;
+    second_tab_layout.children = vec![TiledPaneLayout::default(), TiledPaneLayout::default()];
+    let mut mock_screen = MockScreen::new(size);
+    mock_screen.new_tab(second_tab_layout);
+    let session_metadata = mock_screen.clone_session_metadata();
+    let screen_thread = mock_screen.run(Some(initial_layout), vec![]);
+    let received_server_instructions = Arc::new(Mutex::new(vec![]));
+    let server_receiver = mock_screen.server_receiver.take().unwrap();
+    let server_thread = log_actions_in_thread!(
+        received_server_instructions,
+        ServerInstruction::KillSession,
+        server_receiver
+    );
+    let goto_tab = CliAction::GoToTab { index: 1 };
+    send_cli_action_to_server(&session_metadata, goto_tab, client_id);
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    mock_screen.teardown(vec![server_thread, screen_thread]);
+    let snapshots = take_snapshots_and_cursor_coordinates_from_render_events(
+        received_server_instructions.lock().unwrap().iter(),
+        size,
+    );
+    let snapshot_count = snapshots.len();
+    for (_cursor_coordinates, snapshot) in snapshots {
+        assert_snapshot!(format!("{}", snapshot));
+    }
+    assert_snapshot!(format!("{}", snapshot_count));
+}
+
+#[test]
+pub fn send_cli_close_tab_action() {
+    let size = Size { cols: 80, rows: 10 };
+    let client_id = 10; // fake client id should not appear in the screen's state
+    let mut initial_layout = TiledPaneLayout::default();
+    initial_layout.children_split_direction = SplitDirection::Vertical;
+    initial_layout.children = vec![TiledPaneLayout::default(), TiledPaneLayout::default()];
+    let mut second_tab_layout = TiledPaneLayout::default();
+    second_tab_layout.children_split_direction = SplitDirection::Horizontal;
+    second_tab_layout.children = vec![TiledPaneLayout::default(), TiledPaneLayout::default()];
+    let mut mock_screen = MockScreen::new(size);
+    mock_screen.new_tab(second_tab_layout);
+    let session_metadata = mock_screen.clone_session_metadata();
+    let screen_thread = mock_screen.run(Some(initial_layout), vec![]);
+    let received_server_instructions = Arc::new(Mutex::new(vec![]));
+    let server_receiver = mock_screen.server_receiver.take().unwrap();
+    let server_thread = log_actions_in_thread!(
+        received_server_instructions,
+        ServerInstruction::KillSession,
+        server_receiver
+    );
+    let close_tab = CliAction::CloseTab;
+    send_cli_action_to_server(&session_metadata, close_tab, client_id);
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    mock_screen.teardown(vec![server_thread, screen_thread]);
+    let snapshots = take_snapshots_and_cursor_coordinates_from_render_events(
+        received_server_instructions.lock().unwrap().iter(),
+        size,
+    );
+    let snapshot_count = snapshots.len();
+    for (_cursor_coordinates, snapshot) in snapshots {
+        assert_snapshot!(format!("{}", snapshot));
+    }
+    assert_snapshot!(format!("{}", snapshot_count));
+}
+
+#[test]
+pub fn send_cli_rename_tab() {
+    let size = Size { cols: 80, rows: 10 };
+    let client_id = 10; // fake client id should not appear in the screen's state
+    let mut initial_layout = TiledPaneLayout::default();
+    initial_layout.children_split_direction = SplitDirection::Vertical;
+    initial_layout.children = vec![TiledPaneLayout::default(), TiledPaneLayout::default()];
+    let mut second_tab_layout = TiledPaneLayout::default();
+    second_tab_layout.children_split_direction = SplitDirection::Horizontal;
+    second_tab_layout.children = vec![TiledPaneLayout::default(), TiledPaneLayout::default()];
+    let mut mock_screen = MockScreen::new(size);
+    mock_screen.new_tab(second_tab_layout);
+    let session_metadata = mock_screen.clone_session_metadata();
+    let screen_thread = mock_screen.run(Some(initial_layout), vec![]);
+    let received_plugin_instructions = Arc::new(Mutex::new(vec![]));
+    let plugin_receiver = mock_screen.plugin_receiver.take().unwrap();
+    let plugin_thread = log_actions_in_thread!(
+        received_plugin_instructions,
+        PluginInstruction::Exit,
+        plugin_receiver
+    );
+    let rename_tab = CliAction::RenameTab {
+        name: "new-tab-name".into(),
+    };
+    send_cli_action_to_server(&session_metadata, rename_tab, client_id);
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    mock_screen.teardown(vec![plugin_thread, screen_thread]);
+    let plugin_rename_tab_instruction = received_plugin_instructions
+        .lock()
+        .unwrap()
+        .iter()
+        .find(|instruction| match instruction {
+            PluginInstruction::Update(updates) => updates
+                .iter()
+                .find(|u| match u {
+                    (_, _, Event::TabUpdate(..)) => true,
+                    _ => false,
+                })
+                .is_some(),
+            _ => false,
+        })
+        .cloned();
+    assert_snapshot!(format!("{:#?}", plugin_rename_tab_instruction))
+}
+
+#[test]
+pub fn send_cli_undo_rename_tab() {
+    let size = Size { cols: 80, rows: 10 };
+    let client_id = 10; // fake client id should not appear in the screen's state
+    let mut initial_layout = TiledPaneLayout::default();
+    initial_layout.children_split_direction = SplitDirection::Vertical;
+    initial_layout.children = vec![TiledPaneLayout::default(), TiledPaneLayout::default()];
+    let mut second_tab_layout = TiledPaneLayout::default();
+    second_tab_layout.children_split_direction = SplitDirection::Horizontal;
+    second_tab_layout.children = vec![TiledPaneLayout::default(), TiledPaneLayout::default()];
+    let mut mock_screen = MockScreen::new(size);
+    mock_screen.new_tab(second_tab_layout);
+    let session_metadata = mock_screen.clone_session_metadata();
+    let screen_thread = mock_screen.run(Some(initial_layout), vec![]);
+    let received_plugin_instructions = Arc::new(Mutex::new(vec![]));
+    let plugin_receiver = mock_screen.plugin_receiver.take().unwrap();
+    let plugin_thread = log_actions_in_thread!(
+        received_plugin_instructions,
+        PluginInstruction::Exit,
+        plugin_receiver
+    );
+    let rename_tab = CliAction::RenameTab {
+        name: "new-tab-name".into(),
+    };
+    let undo_rename_tab = CliAction::UndoRenameTab;
+    // first rename the tab
+    send_cli_action_to_server(&session_metadata, rename_tab, client_id);
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    // then undo the tab rename to go back to the default name
+    send_cli_action_to_server(&session_metadata, undo_rename_tab, client_id);
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    mock_screen.teardown(vec![plugin_thread, screen_thread]);
+    let plugin_undo_rename_tab_instruction = received_plugin_instructions
+        .lock()
+        .unwrap()
+        .iter()
+        .find(|instruction| match instruction {
+            PluginInstruction::Update(updates) => updates
+                .iter()
+                .find(|u| match u {
+                    (_, _, Event::TabUpdate(..)) => true,
+                    _ => false,
+                })
+                .is_some(),
+            _ => false,
+        })
+        .cloned();
+    assert_snapshot!(format!("{:#?}", plugin_undo_rename_tab_instruction))
+}
+
+#[test]
+pub fn send_cli_query_tab_names_action() {
+    let size = Size { cols: 80, rows: 10 };
+    let client_id = 10; // fake client id should not appear in the screen's state
+    let mut mock_screen = MockScreen::new(size);
+    mock_screen.new_tab(TiledPaneLayout::default());
+    let session_metadata = mock_screen.clone_session_metadata();
+    let screen_thread = mock_screen.run(Some(TiledPaneLayout::default()), vec![]);
+    let received_server_instructions = Arc::new(Mutex::new(vec![]));
+    let server_receiver = mock_screen.server_receiver.take().unwrap();
+    let server_thread = log_actions_in_thread!(
+        received_server_instructions,
+        ServerInstruction::KillSession,
+        server_receiver
+    );
+    let query_tab_names = CliAction::QueryTabNames;
+    send_cli_action_to_server(&session_metadata, query_tab_names, client_id);
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    mock_screen.teardown(vec![server_thread, screen_thread]);
+    let log_tab_names_instruction = received_server_instructions
+        .lock()
+        .unwrap()
+        .iter()
+        .find(|instruction| match instruction {
+            ServerInstruction::Log(..) => true,
+            _ => false,
+        })
+        .cloned();
+    assert_snapshot!(format!("{:#?}", log_tab_names_instruction));
+}
+
+#[test]
+pub fn send_cli_launch_or_focus_plugin_action() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let client_id = 10; // fake client id should not appear in the screen's state
+    let mut mock_screen = MockScreen::new(size);
+    let pty_receiver = mock_screen.pty_receiver.take().unwrap();
+    let session_metadata = mock_screen.clone_session_metadata();
+    let screen_thread = mock_screen.run(None, vec![]);
+    let received_pty_instructions = Arc::new(Mutex::new(vec![]));
+    let pty_thread = log_actions_in_thread!(
+        received_pty_instructions,
+        PtyInstruction::Exit,
+        pty_receiver
+    );
+    let cli_action = CliAction::LaunchOrFocusPlugin {
+        floating: true,
+        in_place: false,
+        move_to_focused_tab: true,
+        url: url::Url::parse("file:/path/to/fake/plugin").unwrap(),
+        configuration: Default::default(),
+    };
+    send_cli_action_to_server(&session_metadata, cli_action, client_id);
+    std::thread::sleep(std::time::Duration::from_millis(100)); // give time for actions to be
+    mock_screen.teardown(vec![pty_thread, screen_thread]);
+
+    let pty_fill_plugin_cwd_instruction = received_pty_instructions
+        .lock()
+        .unwrap()
+        .iter()
+        .find(|instruction| match instruction {
+            PtyInstruction::FillPluginCwd(..) => true,
+            _ => false,
+        })
+        .cloned();
+
+    assert_snapshot!(format!("{:#?}", pty_fill_plugin_cwd_instruction));
+}
+
+#[test]
+pub fn send_cli_launch_or_focus_plugin_action_when_plugin_is_already_loaded() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let client_id = 10; // fake client id should not appear in the screen's state
+    let mut mock_screen = MockScreen::new(size);
+    let plugin_receiver = mock_screen.plugin_receiver.take().unwrap();
+    let session_metadata = mock_screen.clone_session_metadata();
+    let mut initial_layout = TiledPaneLayout::default();
+    let existing_plugin_pane = TiledPaneLayout {
+        run: Some(Run::Plugin(RunPlugin {
+            _allow_exec_host_cmd: false,
+            location: RunPluginLocation::File(PathBuf::from("/path/to/fake/plugin")),
+            configuration: Default::default(),
+        })),
+        ..Default::default()
+    };
+    initial_layout.children_split_direction = SplitDirection::Vertical;
+    initial_layout.children = vec![TiledPaneLayout::default(), existing_plugin_pane];
+    let screen_thread = mock_screen.run(Some(initial_layout), vec![]);
+    let received_plugin_instructions = Arc::new(Mutex::new(vec![]));
+    let plugin_thread = log_actions_in_thread!(
+        received_plugin_instructions,
+        PluginInstruction::Exit,
+        plugin_receiver
+    );
+    let received_server_instructions = Arc::new(Mutex::new(vec![]));
+    let server_receiver = mock_screen.server_receiver.take().unwrap();
+    let server_thread = log_actions_in_thread!(
+        received_server_instructions,
+        ServerInstruction::KillSession,
+        server_receiver
+    );
+    let cli_action = CliAction::LaunchOrFocusPlugin {
+        floating: true,
+        in_place: false,
+        move_to_focused_tab: true,
+        url: url::Url::parse("file:/path/to/fake/plugin").unwrap(),
+        configuration: Default::default(),
+    };
+    send_cli_action_to_server(&session_metadata, cli_action, client_id);
+    std::thread::sleep(std::time::Duration::from_millis(100)); // give time for actions to be
+    mock_screen.teardown(vec![plugin_thread, server_thread, screen_thread]);
+
+    let plugin_load_instruction_sent = received_plugin_instructions
+        .lock()
+        .unwrap()
+        .iter()
+        .find(|instruction| match instruction {
+            PluginInstruction::Load(..) => true,
+            _ => false,
+        })
+        .is_some();
+    assert!(
+        !plugin_load_instruction_sent,
+        "Plugin Load instruction should not be sent for an already loaded plugin"
+    );
+    let snapshots = take_snapshots_and_cursor_coordinates_from_render_events(
+        received_server_instructions.lock().unwrap().iter(),
+        size,
+    );
+    let snapshot_count = snapshots.len();
+    assert_eq!(
+        snapshot_count, 2,
+        "Another render was sent for focusing the already loaded plugin"
+    );
+    for (cursor_coordinates, _snapshot) in snapshots.iter().skip(1) {
+        assert!(
+            cursor_coordinates.is_none(),
+            "Cursor moved to existing plugin in final snapshot indicating focus changed"
+        );
+    }
+}
+
+#[test]
+pub fn rapid_terminal_resizes_only_resize_plugins_to_the_latest_size() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let mut mock_screen = MockScreen::new(size);
+    let plugin_receiver = mock_screen.plugin_receiver.take().unwrap();
+    let mut initial_layout = TiledPaneLayout::default();
+    let plugin_pane = TiledPaneLayout {
+        run: Some(Run::Plugin(RunPlugin {
+            _allow_exec_host_cmd: false,
+            location: RunPluginLocation::File(PathBuf::from("/path/to/fake/plugin")),
+            configuration: Default::default(),
+        })),
+        ..Default::default()
+    };
+    initial_layout.children_split_direction = SplitDirection::Vertical;
+    initial_layout.children = vec![TiledPaneLayout::default(), plugin_pane];
+    let screen_thread = mock_screen.run(Some(initial_layout), vec![]);
+    let received_plugin_instructions = Arc::new(Mutex::new(vec![]));
+    let plugin_thread = log_actions_in_thread!(
+        received_plugin_instructions,
+        PluginInstruction::Exit,
+        plugin_receiver
+    );
+    std::thread::sleep(std::time::Duration::from_millis(100)); // let the layout settle
+    let instructions_before_resizing = received_plugin_instructions.lock().unwrap().len();
+    for i in 1..=10 {
+        let _ = mock_screen
+            .to_screen
+            .send(ScreenInstruction::TerminalResize(Size {
+                cols: 121 + i * 2,
+                rows: 20 + i,
+            }));
+    }
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    mock_screen.teardown(vec![plugin_thread, screen_thread]);
+
+    let plugin_sizes: Vec<(usize, usize)> = received_plugin_instructions
+        .lock()
+        .unwrap()
+        .iter()
+        .skip(instructions_before_resizing)
+        .filter_map(|instruction| match instruction {
+            PluginInstruction::Resize(1, columns, rows) => Some((*columns, *rows)),
+            _ => None,
+        })
+        .collect();
+    assert!(!plugin_sizes.is_empty(), "Plugin was resized");
+    // re-flowing the tab can nudge the plugin by a column while the panes are laid out, but none
+    // of the sizes in between the first and the latest one should reach it
+    assert!(
+        plugin_sizes.iter().all(|(_columns, rows)| *rows == 28), // the 30 rows of the latest size minus the pane frame
+        "Plugin was only resized to the latest size: {:?}",
+        plugin_sizes
+    );
+}
+
+#[test]
+pub fn capture_rendered_pane() {
+    let size = Size { cols: 8, rows: 5 };
+    let client_id = 1;
+    let mut mock_screen = MockScreen::new(size);
+    mock_screen.config_options.pane_frames = Some(false);
+    let screen_thread = mock_screen.run(None, vec![]);
+    let received_server_instructions = Arc::new(Mutex::new(vec![]));
+    let server_receiver = mock_screen.server_receiver.take().unwrap();
+    let server_thread = log_actions_in_thread!(
+        received_server_instructions,
+        ServerInstruction::KillSession,
+        server_receiver
+    );
+    let _ = mock_screen.to_screen.send(ScreenInstruction::PtyBytes(
+        0,
+        "a\u{1b}[1mb\u{1b}[m".as_bytes().to_vec(),
+    ));
+    let (response_sender, response_receiver) = channels::bounded(1);
+    let _ = mock_screen
+        .to_screen
+        .send(ScreenInstruction::CaptureRendered(
+            Some(PaneId::Terminal(0)),
+            client_id,
+            Some(response_sender),
+        ));
+    let rendered_grid = response_receiver
+        .recv_timeout(std::time::Duration::from_secs(1))
+        .expect("no response to capture request");
+    mock_screen.teardown(vec![server_thread, screen_thread]);
+    assert_snapshot!(format!("{:#?}", rendered_grid));
+}
+
+#[test]
+pub fn send_cli_capture_rendered_action() {
+    let size = Size { cols: 8, rows: 5 };
+    let client_id = 10; // fake client id should not appear in the screen's state
+    let mut mock_screen = MockScreen::new(size);
+    mock_screen.config_options.pane_frames = Some(false);
+    let session_metadata = mock_screen.clone_session_metadata();
+    let screen_thread = mock_screen.run(None, vec![]);
+    let received_server_instructions = Arc::new(Mutex::new(vec![]));
+    let server_receiver = mock_screen.server_receiver.take().unwrap();
+    let server_thread = log_actions_in_thread!(
+        received_server_instructions,
+        ServerInstruction::KillSession,
+        server_receiver
+    );
+    let _ = mock_screen
+        .to_screen
+        .send(ScreenInstruction::PtyBytes(0, "a".as_bytes().to_vec()));
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    send_cli_action_to_server(&session_metadata, CliAction::CaptureRendered, client_id);
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    mock_screen.teardown(vec![server_thread, screen_thread]);
+    let replies: Vec<String> = received_server_instructions
+        .lock()
+        .unwrap()
+        .iter()
+        .filter_map(|instruction| match instruction {
+            ServerInstruction::Log(lines, log_client_id) if *log_client_id == client_id => {
+                Some(lines.join("\n"))
+            },
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        replies.len(),
+        1,
+        "rendered output sent back to the cli client"
+    );
+    assert!(
+        replies[0].starts_with(r#"{"lines":[[{"character":"a","styles":"#),
+        "rendered output of the first connected client sent as JSON"
+    );
+}
+
+#[test]
+pub fn screen_can_suppress_pane() {
+    let size = Size { cols: 80, rows: 20 };
+    let mut initial_layout = TiledPaneLayout::default();
+    initial_layout.children_split_direction = SplitDirection::Vertical;
+    initial_layout.children = vec![TiledPaneLayout::default(), TiledPaneLayout::default()];
+    let mut mock_screen = MockScreen::new(size);
+    let screen_thread = mock_screen.run(Some(initial_layout), vec![]);
+    let received_server_instructions = Arc::new(Mutex::new(vec![]));
+    let server_receiver = mock_screen.server_receiver.take().unwrap();
+    let server_thread = log_actions_in_thread!(
+        received_server_instructions,
+        ServerInstruction::KillSession,
+        server_receiver
+    );
+    let _ = mock_screen
+        .to_screen
+        .send(ScreenInstruction::SuppressPane(PaneId::Terminal(1), 1));
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    mock_screen.teardown(vec![server_thread, screen_thread]);
+
+    let snapshots = take_snapshots_and_cursor_coordinates_from_render_events(
+        received_server_instructions.lock().unwrap().iter(),
+        size,
+    );
+    let snapshot_count = snapshots.len();
+    for (_cursor_coordinates, snapshot) in snapshots {
+        assert_snapshot!(format!("{}", snapshot));
+    }
+    assert_snapshot!(format!("{}", snapshot_count));
+}
+
+#[test]
+pub fn screen_can_break_pane_to_a_new_tab() {
+    let size = Size { cols: 80, rows: 20 };
+    let mut initial_layout = TiledPaneLayout::default();
+    let mut pane_to_break_free = TiledPaneLayout::default();
+    pane_to_break_free.name = Some("pane_to_break_free".to_owned());
+    let mut pane_to_stay = TiledPaneLayout::default();
+    pane_to_stay.name = Some("pane_to_stay".to_owned());
+    initial_layout.children_split_direction = SplitDirection::Vertical;
+    initial_layout.children = vec![pane_to_break_free, pane_to_stay];
+    let mut mock_screen = MockScreen::new(size);
+    let screen_thread = mock_screen.run(Some(initial_layout), vec![]);
+    let received_server_instructions = Arc::new(Mutex::new(vec![]));
+    let server_receiver = mock_screen.server_receiver.take().unwrap();
+    let server_thread = log_actions_in_thread!(
+        received_server_instructions,
+        ServerInstruction::KillSession,
+        server_receiver
+    );
+
+    let _ = mock_screen.to_screen.send(ScreenInstruction::BreakPane(
+        Box::new(Layout::default()),
+        Default::default(),
+        1,
+    ));
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    // we send ApplyLayout, because in prod this is eventually received after the message traverses
+    // through the plugin and pty threads (to open extra stuff we need in the layout, eg. the
+    // default plugins)
+    let _ = mock_screen.to_screen.send(ScreenInstruction::ApplyLayout(
+        TiledPaneLayout::default(),
+        vec![], // floating_panes_layout
+        Default::default(),
+        vec![], // floating panes ids
+        Default::default(),
+        1,
+        1,
+    ));
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    // move back to make sure the other pane is in the previous tab
+    let _ = mock_screen
+        .to_screen
+        .send(ScreenInstruction::MoveFocusLeftOrPreviousTab(1));
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    // move forward to make sure the broken pane is in the previous tab
+    let _ = mock_screen
+        .to_screen
+        .send(ScreenInstruction::MoveFocusRightOrNextTab(1));
+    std::thread::sleep(std::time::Duration::from_millis(100));
+
+    mock_screen.teardown(vec![server_thread, screen_thread]);
+
+    let snapshots = take_snapshots_and_cursor_coordinates_from_render_events(
+        received_server_instructions.lock().unwrap().iter(),
+        size,
+    );
+    let snapshot_count = snapshots.len();
+    for (_cursor_coordinates, snapshot) in snapshots {
+        assert_snapshot!(format!("{}", snapshot));
+    }
+    assert_snapshot!(format!("{}", snapshot_count));
+}
+
+#[test]
+pub fn screen_cannot_break_last_selectable_pane_to_a_new_tab() {
+    let size = Size { cols: 80, rows: 20 };
+    let initial_layout = TiledPaneLayout::default();
+    let mut mock_screen = MockScreen::new(size);
+    let screen_thread = mock_screen.run(Some(initial_layout), vec![]);
+    let received_server_instructions = Arc::new(Mutex::new(vec![]));
+    let server_receiver = mock_screen.server_receiver.take().unwrap();
+    let server_thread = log_actions_in_thread!(
+        received_server_instructions,
+        ServerInstruction::KillSession,
+        server_receiver
+    );
+
+    let _ = mock_screen.to_screen.send(ScreenInstruction::BreakPane(
+        Box::new(Layout::default()),
+        Default::default(),
+        1,
+    ));
+    std::thread::sleep(std::time::Duration::from_millis(100));
+
+    mock_screen.teardown(vec![server_thread, screen_thread]);
+
+    let snapshots = take_snapshots_and_cursor_coordinates_from_render_events(
+        received_server_instructions.lock().unwrap().iter(),
+        size,
+    );
+    let snapshot_count = snapshots.len();
+    for (_cursor_coordinates, snapshot) in snapshots {
+        assert_snapshot!(format!("{}", snapshot));
+    }
+    assert_snapshot!(format!("{}", snapshot_count));
+}
+
+#[test]
+pub fn screen_can_break_floating_pane_to_a_new_tab() {
+    let size = Size { cols: 80, rows: 20 };
+    let mut initial_layout = TiledPaneLayout::default();
+    let mut pane_to_break_free = TiledPaneLayout::default();
+    pane_to_break_free.name = Some("tiled_pane".to_owned());
+    let mut floating_pane = FloatingPaneLayout::default();
+    floating_pane.name = Some("floating_pane_to_eject".to_owned());
+    let mut floating_panes_layout = vec![floating_pane];
+    initial_layout.children_split_direction = SplitDirection::Vertical;
+    initial_layout.children = vec![pane_to_break_free];
+    let mut mock_screen = MockScreen::new(size);
+    let screen_thread = mock_screen.run(Some(initial_layout), floating_panes_layout.clone());
+    let received_server_instructions = Arc::new(Mutex::new(vec![]));
+    let server_receiver = mock_screen.server_receiver.take().unwrap();
+    let server_thread = log_actions_in_thread!(
+        received_server_instructions,
+        ServerInstruction::KillSession,
+        server_receiver
+    );
+
+    let _ = mock_screen.to_screen.send(ScreenInstruction::BreakPane(
+        Box::new(Layout::default()),
+        Default::default(),
+        1,
+    ));
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    // we send ApplyLayout, because in prod this is eventually received after the message traverses
+    // through the plugin and pty threads (to open extra stuff we need in the layout, eg. the
+    // default plugins)
+    floating_panes_layout.get_mut(0).unwrap().already_running = true;
+    let _ = mock_screen.to_screen.send(ScreenInstruction::ApplyLayout(
+        TiledPaneLayout::default(),
+        floating_panes_layout,
+        vec![(1, None)], // tiled pane ids - send these because one needs to be created under the
+        // ejected floating pane, lest the tab be closed as having no tiled panes
+        // (this happens in prod in the pty thread)
+        vec![], // floating panes ids
+        Default::default(),
+        1,
+        1,
     ));
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    // move back to make sure the other pane is in the previous tab
+    let _ = mock_screen
+        .to_screen
+        .send(ScreenInstruction::MoveFocusLeftOrPreviousTab(1));
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    // move forward to make sure the broken pane is in the previous tab
+    let _ = mock_screen
+        .to_screen
+        .send(ScreenInstruction::MoveFocusRightOrNextTab(1));
+    std::thread::sleep(std::time::Duration::from_millis(100));
+
+    mock_screen.teardown(vec![server_thread, screen_thread]);
+
+    let snapshots = take_snapshots_and_cursor_coordinates_from_render_events(
+        received_server_instructions.lock().unwrap().iter(),
+        size,
+    );
+    let snapshot_count = snapshots.len();
+    for (_cursor_coordinates, snapshot) in snapshots {
+        assert_snapshot!(format!("{}", snapshot));
+    }
+    assert_snapshot!(format!("{}", snapshot_count));
 }
 
 #[test]
-pub fn send_cli_edit_scrollback_action() {
+pub fn screen_can_break_plugin_pane_to_a_new_tab() {
     let size = Size { cols: 80, rows: 20 };
-    let client_id = 10; // fake client id should not appear in the screen's state
     let mut initial_layout = TiledPaneLayout::default();
+    let mut pane_to_break_free = TiledPaneLayout::default();
+    pane_to_break_free.name = Some("plugin_pane_to_break_free".to_owned());
+    pane_to_break_free.run = Some(Run::Plugin(RunPlugin {
+        _allow_exec_host_cmd: false,
+        location: RunPluginLocation::File(PathBuf::from("/path/to/fake/plugin")),
+        configuration: Default::default(),
+    }));
+    let mut pane_to_stay = TiledPaneLayout::default();
+    pane_to_stay.name = Some("pane_to_stay".to_owned());
     initial_layout.children_split_direction = SplitDirection::Vertical;
-    initial_layout.children = vec![TiledPaneLayout::default(), TiledPaneLayout::default()];
+    initial_layout.children = vec![pane_to_break_free, pane_to_stay];
     let mut mock_screen = MockScreen::new(size);
-    let session_metadata = mock_screen.clone_session_metadata();
     let screen_thread = mock_screen.run(Some(initial_layout), vec![]);
-    let received_pty_instructions = Arc::new(Mutex::new(vec![]));
-    let pty_receiver = mock_screen.pty_receiver.take().unwrap();
-    let pty_thread = log_actions_in_thread!(
-        received_pty_instructions,
-        PtyInstruction::Exit,
-        pty_receiver
+    let received_server_instructions = Arc::new(Mutex::new(vec![]));
+    let server_receiver = mock_screen.server_receiver.take().unwrap();
+    let server_thread = log_actions_in_thread!(
+        received_server_instructions,
+        ServerInstruction::KillSession,
+        server_receiver
     );
-    let cli_action = CliAction::EditScrollback;
-    let _ = mock_screen.to_screen.send(ScreenInstruction::PtyBytes(
-        0,
-        "fill pane up with something".as_bytes().to_vec(),
+
+    let _ = mock_screen.to_screen.send(ScreenInstruction::BreakPane(
+        Box::new(Layout::default()),
+        Default::default(),
+        1,
     ));
-    send_cli_action_to_server(&session_metadata, cli_action, client_id);
     std::thread::sleep(std::time::Duration::from_millis(100));
-    mock_screen.teardown(vec![pty_thread, screen_thread]);
-    let dumped_file_name = mock_screen
-        .os_input
-        .fake_filesystem
+    // we send ApplyLayout, because in prod this is eventually received after the message traverses
+    // through the plugin and pty threads (to open extra stuff we need in the layout, eg. the
+    // default plugins)
+    let _ = mock_screen.to_screen.send(ScreenInstruction::ApplyLayout(
+        TiledPaneLayout::default(),
+        vec![], // floating_panes_layout
+        Default::default(),
+        vec![], // floating panes ids
+        Default::default(),
+        1,
+        1,
+    ));
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    // move back to make sure the other pane is in the previous tab
+    let _ = mock_screen
+        .to_screen
+        .send(ScreenInstruction::MoveFocusLeftOrPreviousTab(1));
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    // move forward to make sure the broken pane is in the previous tab
+    let _ = mock_screen
+        .to_screen
+        .send(ScreenInstruction::MoveFocusRightOrNextTab(1));
+    std::thread::sleep(std::time::Duration::from_millis(100));
+
+    mock_screen.teardown(vec![server_thread, screen_thread]);
+
+    let snapshots = take_snapshots_and_cursor_coordinates_from_render_events(
+        received_server_instructions.lock().unwrap().iter(),
+        size,
+    );
+    let snapshot_count = snapshots.len();
+    for (_cursor_coordinates, snapshot) in snapshots {
+        assert_snapshot!(format!("{}", snapshot));
+    }
+    assert_snapshot!(format!("{}", snapshot_count));
+}
+
+#[test]
+pub fn plugin_pane_asks_for_the_whole_frame_when_it_cannot_apply_changed_lines() {
+    let size = Size { cols: 80, rows: 20 };
+    let client_id = 1;
+    let mut initial_layout = TiledPaneLayout::default();
+    let mut plugin_pane = TiledPaneLayout::default();
+    plugin_pane.run = Some(Run::Plugin(RunPlugin {
+        _allow_exec_host_cmd: false,
+        location: RunPluginLocation::File(PathBuf::from("/path/to/fake/plugin")),
+        configuration: Default::default(),
+    }));
+    initial_layout.children_split_direction = SplitDirection::Vertical;
+    initial_layout.children = vec![plugin_pane, TiledPaneLayout::default()];
+    let mut mock_screen = MockScreen::new(size);
+    let screen_thread = mock_screen.run(Some(initial_layout), vec![]);
+    let received_plugin_instructions = Arc::new(Mutex::new(vec![]));
+    let plugin_receiver = mock_screen.plugin_receiver.take().unwrap();
+    let plugin_thread = log_actions_in_thread!(
+        received_plugin_instructions,
+        PluginInstruction::Exit,
+        plugin_receiver
+    );
+    let changed_line = |row: usize, text: &str| ChangedLine {
+        row,
+        carried_styles: vec![],
+        bytes: text.as_bytes().to_vec(),
+    };
+    let plugin_frames = vec![
+        PluginFrame::Full {
+            frame_id: 0,
+            bytes: b"line 0\n\rline 1\n\rline 2".to_vec(),
+        },
+        PluginFrame::Changed {
+            frame_id: 1,
+            base_frame_id: 0,
+            lines: vec![changed_line(1, "\rchanged line 1")],
+        },
+        // frame 2 never reached the pane
+        PluginFrame::Changed {
+            frame_id: 3,
+            base_frame_id: 2,
+            lines: vec![changed_line(2, "\rchanged line 2")],
+        },
+    ];
+    let render_queue = PluginRenderQueue::new();
+    for frame in plugin_frames {
+        let frame_count = render_queue.push(vec![(1, client_id, frame)]);
+        let _ = mock_screen
+            .to_screen
+            .send(ScreenInstruction::PluginRenderQueued(
+                render_queue.clone(),
+                frame_count,
+            ));
+        std::thread::sleep(std::time::Duration::from_millis(100));
+    }
+    mock_screen.teardown(vec![plugin_thread, screen_thread]);
+    let frame_resend_requests: Vec<(u32, ClientId)> = received_plugin_instructions
         .lock()
         .unwrap()
-        .keys()
-        .next()
-        .unwrap()
-        .clone();
-    let mut found_instruction = false;
-    for instruction in received_pty_instructions.lock().unwrap().iter() {
-        if let PtyInstruction::OpenInPlaceEditor(scrollback_contents_file, terminal_id, client_id) =
-            instruction
-        {
-            assert_eq!(scrollback_contents_file, &PathBuf::from(&dumped_file_name));
-            assert_eq!(terminal_id, &Some(1));
-            assert_eq!(client_id, &1);
-            found_instruction = true;
-        }
+        .iter()
+        .filter_map(|instruction| match instruction {
+            PluginInstruction::ResendPluginFrame(plugin_id, client_id) => {
+                Some((*plugin_id, *client_id))
+            },
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        frame_resend_requests,
+        vec![(1, client_id)],
+        "only the changes of the frame after the missing one could not be applied"
+    );
+}
+
+#[test]
+pub fn screen_can_break_floating_plugin_pane_to_a_new_tab() {
+    let size = Size { cols: 80, rows: 20 };
+    let mut initial_layout = TiledPaneLayout::default();
+    let mut pane_to_break_free = TiledPaneLayout::default();
+    pane_to_break_free.name = Some("tiled_pane".to_owned());
+    let mut floating_pane = FloatingPaneLayout::default();
+    floating_pane.name = Some("floating_plugin_pane_to_eject".to_owned());
+    floating_pane.run = Some(Run::Plugin(RunPlugin {
+        _allow_exec_host_cmd: false,
+        location: RunPluginLocation::File(PathBuf::from("/path/to/fake/plugin")),
+        configuration: Default::default(),
+    }));
+    let mut floating_panes_layout = vec![floating_pane];
+    initial_layout.children_split_direction = SplitDirection::Vertical;
+    initial_layout.children = vec![pane_to_break_free];
+    let mut mock_screen = MockScreen::new(size);
+    let screen_thread = mock_screen.run(Some(initial_layout), floating_panes_layout.clone());
+    let received_server_instructions = Arc::new(Mutex::new(vec![]));
+    let server_receiver = mock_screen.server_receiver.take().unwrap();
+    let server_thread = log_actions_in_thread!(
+        received_server_instructions,
+        ServerInstruction::KillSession,
+        server_receiver
+    );
+
+    let _ = mock_screen.to_screen.send(ScreenInstruction::BreakPane(
+        Box::new(Layout::default()),
+        Default::default(),
+        1,
+    ));
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    // we send ApplyLayout, because in prod this is eventually received after the message traverses
+    // through the plugin and pty threads (to open extra stuff we need in the layout, eg. the
+    // default plugins)
+    floating_panes_layout.get_mut(0).unwrap().already_running = true;
+    let _ = mock_screen.to_screen.send(ScreenInstruction::ApplyLayout(
+        TiledPaneLayout::default(),
+        floating_panes_layout,
+        vec![(1, None)], // tiled pane ids - send these because one needs to be created under the
+        // ejected floating pane, lest the tab be closed as having no tiled panes
+        // (this happens in prod in the pty thread)
+        vec![], // floating panes ids
+        Default::default(),
+        1,
+        1,
+    ));
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    // move back to make sure the other pane is in the previous tab
+    let _ = mock_screen
+        .to_screen
+        .send(ScreenInstruction::MoveFocusLeftOrPreviousTab(1));
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    // move forward to make sure the broken pane is in the previous tab
+    let _ = mock_screen
+        .to_screen
+        .send(ScreenInstruction::MoveFocusRightOrNextTab(1));
+    std::thread::sleep(std::time::Duration::from_millis(100));
+
+    mock_screen.teardown(vec![server_thread, screen_thread]);
+
+    let snapshots = take_snapshots_and_cursor_coordinates_from_render_events(
+        received_server_instructions.lock().unwrap().iter(),
+        size,
+    );
+    let snapshot_count = snapshots.len();
+    for (_cursor_coordinates, snapshot) in snapshots {
+        assert_snapshot!(format!("{}", snapshot));
     }
-    assert!(found_instruction);
+    assert_snapshot!(format!("{}", snapshot_count));
 }
 
 #[test]
-pub fn send_cli_scroll_up_action() {
-    let size = Size { cols: 80, rows: 10 };
-    let client_id = 10; // fake client id should not appear in the screen's state
+pub fn screen_can_move_pane_to_a_new_tab_right() {
+    let size = Size { cols: 80, rows: 20 };
     let mut initial_layout = TiledPaneLayout::default();
+    let mut pane_to_break_free = TiledPaneLayout::default();
+    pane_to_break_free.name = Some("pane_to_break_free".to_owned());
+    let mut pane_to_stay = TiledPaneLayout::default();
+    pane_to_stay.name = Some("pane_to_stay".to_owned());
     initial_layout.children_split_direction = SplitDirection::Vertical;
-    initial_layout.children = vec![TiledPaneLayout::default(), TiledPaneLayout::default()];
+    initial_layout.children = vec![pane_to_break_free, pane_to_stay];
     let mut mock_screen = MockScreen::new(size);
-    let session_metadata = mock_screen.clone_session_metadata();
     let screen_thread = mock_screen.run(Some(initial_layout), vec![]);
     let received_server_instructions = Arc::new(Mutex::new(vec![]));
     let server_receiver = mock_screen.server_receiver.take().unwrap();
-    let server_instruction = log_actions_in_thread!(
+    let server_thread = log_actions_in_thread!(
         received_server_instructions,
         ServerInstruction::KillSession,
         server_receiver
     );
-    let cli_action = CliAction::ScrollUp;
-    let mut pane_contents = String::new();
-    for i in 0..20 {
-        pane_contents.push_str(&format!("fill pane up with something {}\n\r", i));
-    }
-    let _ = mock_screen.to_screen.send(ScreenInstruction::PtyBytes(
-        0,
-        pane_contents.as_bytes().to_vec(),
+
+    let _ = mock_screen.to_screen.send(ScreenInstruction::BreakPane(
+        Box::new(Layout::default()),
+        Default::default(),
+        1,
     ));
     std::thread::sleep(std::time::Duration::from_millis(100));
-    // we send two actions here because only the last line in the pane is empty, so one action
-    // won't show in a render
-    send_cli_action_to_server(&session_metadata, cli_action.clone(), client_id);
-    send_cli_action_to_server(&session_metadata, cli_action.clone(), client_id);
+    let _ = mock_screen
+        .to_screen
+        .send(ScreenInstruction::MoveFocusLeftOrPreviousTab(1));
     std::thread::sleep(std::time::Duration::from_millis(100));
-    mock_screen.teardown(vec![server_instruction, screen_thread]);
+    let _ = mock_screen
+        .to_screen
+        .send(ScreenInstruction::BreakPaneRight(1));
+    std::thread::sleep(std::time::Duration::from_millis(100));
+
+    mock_screen.teardown(vec![server_thread, screen_thread]);
+
     let snapshots = take_snapshots_and_cursor_coordinates_from_render_events(
         received_server_instructions.lock().unwrap().iter(),
         size,
@@ -1431,44 +4942,42 @@ pub fn send_cli_scroll_up_action() {
 }
 
 #[test]
-pub fn send_cli_scroll_down_action() {
-    let size = Size { cols: 80, rows: 10 };
-    let client_id = 10; // fake client id should not appear in the screen's state
+pub fn screen_can_move_pane_to_a_new_tab_left() {
+    let size = Size { cols: 80, rows: 20 };
     let mut initial_layout = TiledPaneLayout::default();
+    let mut pane_to_break_free = TiledPaneLayout::default();
+    pane_to_break_free.name = Some("pane_to_break_free".to_owned());
+    let mut pane_to_stay = TiledPaneLayout::default();
+    pane_to_stay.name = Some("pane_to_stay".to_owned());
     initial_layout.children_split_direction = SplitDirection::Vertical;
-    initial_layout.children = vec![TiledPaneLayout::default(), TiledPaneLayout::default()];
+    initial_layout.children = vec![pane_to_break_free, pane_to_stay];
     let mut mock_screen = MockScreen::new(size);
-    let session_metadata = mock_screen.clone_session_metadata();
     let screen_thread = mock_screen.run(Some(initial_layout), vec![]);
     let received_server_instructions = Arc::new(Mutex::new(vec![]));
     let server_receiver = mock_screen.server_receiver.take().unwrap();
-    let server_instruction = log_actions_in_thread!(
+    let server_thread = log_actions_in_thread!(
         received_server_instructions,
         ServerInstruction::KillSession,
         server_receiver
     );
-    let scroll_up_cli_action = CliAction::ScrollUp;
-    let scroll_down_cli_action = CliAction::ScrollDown;
-    let mut pane_contents = String::new();
-    for i in 0..20 {
-        pane_contents.push_str(&format!("fill pane up with something {}\n\r", i));
-    }
-    let _ = mock_screen.to_screen.send(ScreenInstruction::PtyBytes(
-        0,
-        pane_contents.as_bytes().to_vec(),
+
+    let _ = mock_screen.to_screen.send(ScreenInstruction::BreakPane(
+        Box::new(Layout::default()),
+        Default::default(),
+        1,
     ));
     std::thread::sleep(std::time::Duration::from_millis(100));
-    // scroll up some
-    send_cli_action_to_server(&session_metadata, scroll_up_cli_action.clone(), client_id);
-    send_cli_action_to_server(&session_metadata, scroll_up_cli_action.clone(), client_id);
-    send_cli_action_to_server(&session_metadata, scroll_up_cli_action.clone(), client_id);
-    send_cli_action_to_server(&session_metadata, scroll_up_cli_action.clone(), client_id);
-
-    // scroll down some
-    send_cli_action_to_server(&session_metadata, scroll_down_cli_action.clone(), client_id);
-    send_cli_action_to_server(&session_metadata, scroll_down_cli_action.clone(), client_id);
+    let _ = mock_screen
+        .to_screen
+        .send(ScreenInstruction::MoveFocusLeftOrPreviousTab(1));
     std::thread::sleep(std::time::Duration::from_millis(100));
-    mock_screen.teardown(vec![server_instruction, screen_thread]);
+    let _ = mock_screen
+        .to_screen
+        .send(ScreenInstruction::BreakPaneLeft(1));
+    std::thread::sleep(std::time::Duration::from_millis(100));
+
+    mock_screen.teardown(vec![server_thread, screen_thread]);
+
     let snapshots = take_snapshots_and_cursor_coordinates_from_render_events(
         received_server_instructions.lock().unwrap().iter(),
         size,
@@ -1481,546 +4990,1296 @@ pub fn send_cli_scroll_down_action() {
 }
 
 #[test]
-pub fn send_cli_scroll_to_bottom_action() {
-    let size = Size { cols: 80, rows: 10 };
-    let client_id = 10; // fake client id should not appear in the screen's state
+pub fn screen_reports_input_latency_to_plugins() {
+    let size = Size { cols: 80, rows: 20 };
+    let client_id = 1;
     let mut initial_layout = TiledPaneLayout::default();
     initial_layout.children_split_direction = SplitDirection::Vertical;
     initial_layout.children = vec![TiledPaneLayout::default(), TiledPaneLayout::default()];
     let mut mock_screen = MockScreen::new(size);
-    let session_metadata = mock_screen.clone_session_metadata();
     let screen_thread = mock_screen.run(Some(initial_layout), vec![]);
     let received_server_instructions = Arc::new(Mutex::new(vec![]));
     let server_receiver = mock_screen.server_receiver.take().unwrap();
-    let server_instruction = log_actions_in_thread!(
+    let server_thread = log_actions_in_thread!(
         received_server_instructions,
         ServerInstruction::KillSession,
         server_receiver
     );
-    let scroll_up_cli_action = CliAction::ScrollUp;
-    let scroll_to_bottom_action = CliAction::ScrollToBottom;
-    let mut pane_contents = String::new();
-    for i in 0..20 {
-        pane_contents.push_str(&format!("fill pane up with something {}\n\r", i));
-    }
-    let _ = mock_screen.to_screen.send(ScreenInstruction::PtyBytes(
-        0,
-        pane_contents.as_bytes().to_vec(),
-    ));
+    let received_plugin_instructions = Arc::new(Mutex::new(vec![]));
+    let plugin_receiver = mock_screen.plugin_receiver.take().unwrap();
+    let plugin_thread = log_actions_in_thread!(
+        received_plugin_instructions,
+        PluginInstruction::Exit,
+        plugin_receiver
+    );
     std::thread::sleep(std::time::Duration::from_millis(100));
-    // scroll up some
-    send_cli_action_to_server(&session_metadata, scroll_up_cli_action.clone(), client_id);
-    send_cli_action_to_server(&session_metadata, scroll_up_cli_action.clone(), client_id);
-    send_cli_action_to_server(&session_metadata, scroll_up_cli_action.clone(), client_id);
-    send_cli_action_to_server(&session_metadata, scroll_up_cli_action.clone(), client_id);
+    let _ = mock_screen
+        .to_screen
+        .send(ScreenInstruction::WriteCharacter(
+            "a".as_bytes().to_vec(),
+            client_id,
+        ));
+    // the pane echoes the input back
+    let _ = mock_screen
+        .to_screen
+        .send(ScreenInstruction::PtyBytes(0, "a".as_bytes().to_vec()));
+    let _ = mock_screen.to_screen.send(ScreenInstruction::Render);
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    mock_screen.teardown(vec![plugin_thread, server_thread, screen_thread]);
+    let input_latency_stats = received_plugin_instructions
+        .lock()
+        .unwrap()
+        .iter()
+        .find_map(|instruction| match instruction {
+            PluginInstruction::Update(updates) => updates.iter().find_map(|u| match u {
+                (_, _, Event::InputLatencyStats(input_latency_stats)) => {
+                    Some(input_latency_stats.clone())
+                },
+                _ => None,
+            }),
+            _ => None,
+        })
+        .expect("input latency stats were reported to plugins");
+    assert_eq!(
+        input_latency_stats.len(),
+        1,
+        "stats reported for one client"
+    );
+    assert_eq!(
+        input_latency_stats[0].client_id, client_id,
+        "stats reported for the client that sent the input"
+    );
+    assert_eq!(
+        input_latency_stats[0].sample_count, 1,
+        "one latency sample recorded"
+    );
+    assert_eq!(
+        input_latency_stats[0].last, input_latency_stats[0].max,
+        "single sample is both the last and the max"
+    );
+}
 
-    // scroll to bottom
-    send_cli_action_to_server(
-        &session_metadata,
-        scroll_to_bottom_action.clone(),
-        client_id,
+#[test]
+pub fn bracketed_paste_is_sent_to_plugin_as_a_single_paste_event() {
+    let size = Size { cols: 80, rows: 20 };
+    let client_id = 1;
+    let plugin_id = 1;
+    let mut initial_layout = TiledPaneLayout::default();
+    let plugin_pane = TiledPaneLayout {
+        run: Some(Run::Plugin(RunPlugin {
+            _allow_exec_host_cmd: false,
+            location: RunPluginLocation::File(PathBuf::from("/path/to/fake/plugin")),
+            configuration: Default::default(),
+        })),
+        focus: Some(true),
+        ..Default::default()
+    };
+    initial_layout.children_split_direction = SplitDirection::Vertical;
+    initial_layout.children = vec![plugin_pane, TiledPaneLayout::default()];
+    let mut mock_screen = MockScreen::new(size);
+    let screen_thread = mock_screen.run(Some(initial_layout), vec![]);
+    let received_plugin_instructions = Arc::new(Mutex::new(vec![]));
+    let plugin_receiver = mock_screen.plugin_receiver.take().unwrap();
+    let plugin_thread = log_actions_in_thread!(
+        received_plugin_instructions,
+        PluginInstruction::Exit,
+        plugin_receiver
     );
     std::thread::sleep(std::time::Duration::from_millis(100));
-    mock_screen.teardown(vec![server_instruction, screen_thread]);
-    let snapshots = take_snapshots_and_cursor_coordinates_from_render_events(
-        received_server_instructions.lock().unwrap().iter(),
-        size,
+    // a large paste arrives in chunks, between the bracketed paste start and end markers
+    let _ = mock_screen
+        .to_screen
+        .send(ScreenInstruction::WriteCharacter(
+            "a\u{1b}[200~hello\n".as_bytes().to_vec(),
+            client_id,
+        ));
+    let _ = mock_screen
+        .to_screen
+        .send(ScreenInstruction::WriteCharacter(
+            "world\u{1b}[201~".as_bytes().to_vec(),
+            client_id,
+        ));
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    mock_screen.teardown(vec![plugin_thread, screen_thread]);
+    let plugin_input_events: Vec<Event> = received_plugin_instructions
+        .lock()
+        .unwrap()
+        .iter()
+        .filter_map(|instruction| match instruction {
+            PluginInstruction::Update(updates) => Some(updates.clone()),
+            _ => None,
+        })
+        .flatten()
+        .filter_map(|(pid, _, event)| match event {
+            Event::Key(..) | Event::Paste(..) if pid == Some(plugin_id) => Some(event),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        plugin_input_events,
+        vec![
+            Event::Key(Key::Char('a')),
+            Event::Paste("hello\nworld".to_owned())
+        ],
+        "typed input sent as keys and the whole paste sent as a single event"
     );
-    let snapshot_count = snapshots.len();
-    for (_cursor_coordinates, snapshot) in snapshots {
-        assert_snapshot!(format!("{}", snapshot));
+}
+
+#[test]
+pub fn bracketed_paste_marker_cut_between_writes_ends_the_paste() {
+    let size = Size { cols: 80, rows: 20 };
+    let client_id = 1;
+    let plugin_id = 1;
+    let mut initial_layout = TiledPaneLayout::default();
+    let plugin_pane = TiledPaneLayout {
+        run: Some(Run::Plugin(RunPlugin {
+            _allow_exec_host_cmd: false,
+            location: RunPluginLocation::File(PathBuf::from("/path/to/fake/plugin")),
+            configuration: Default::default(),
+        })),
+        focus: Some(true),
+        ..Default::default()
+    };
+    initial_layout.children_split_direction = SplitDirection::Vertical;
+    initial_layout.children = vec![plugin_pane, TiledPaneLayout::default()];
+    let mut mock_screen = MockScreen::new(size);
+    let screen_thread = mock_screen.run(Some(initial_layout), vec![]);
+    let received_plugin_instructions = Arc::new(Mutex::new(vec![]));
+    let plugin_receiver = mock_screen.plugin_receiver.take().unwrap();
+    let plugin_thread = log_actions_in_thread!(
+        received_plugin_instructions,
+        PluginInstruction::Exit,
+        plugin_receiver
+    );
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    for written_bytes in ["\u{1b}[2", "00~hello\u{1b}[2", "01~a"] {
+        let _ = mock_screen
+            .to_screen
+            .send(ScreenInstruction::WriteCharacter(
+                written_bytes.as_bytes().to_vec(),
+                client_id,
+            ));
     }
-    assert_snapshot!(format!("{}", snapshot_count));
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    mock_screen.teardown(vec![plugin_thread, screen_thread]);
+    let plugin_input_events: Vec<Event> = received_plugin_instructions
+        .lock()
+        .unwrap()
+        .iter()
+        .filter_map(|instruction| match instruction {
+            PluginInstruction::Update(updates) => Some(updates.clone()),
+            _ => None,
+        })
+        .flatten()
+        .filter_map(|(pid, _, event)| match event {
+            Event::Key(..) | Event::Paste(..) if pid == Some(plugin_id) => Some(event),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        plugin_input_events,
+        vec![Event::Paste("hello".to_owned()), Event::Key(Key::Char('a'))],
+        "the paste ends where its end marker does and what follows is typed input"
+    );
+}
+
+#[test]
+pub fn screen_reports_session_sharing_status_to_plugins() {
+    let size = Size { cols: 80, rows: 20 };
+    let client_id = 1;
+    let second_client_id = 2;
+    let plugin_id = 0;
+    let mut mock_screen = MockScreen::new(size);
+    let screen_thread = mock_screen.run(None, vec![]);
+    let received_server_instructions = Arc::new(Mutex::new(vec![]));
+    let server_receiver = mock_screen.server_receiver.take().unwrap();
+    let server_thread = log_actions_in_thread!(
+        received_server_instructions,
+        ServerInstruction::KillSession,
+        server_receiver
+    );
+    let received_plugin_instructions = Arc::new(Mutex::new(vec![]));
+    let plugin_receiver = mock_screen.plugin_receiver.take().unwrap();
+    let plugin_thread = log_actions_in_thread!(
+        received_plugin_instructions,
+        PluginInstruction::Exit,
+        plugin_receiver
+    );
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    let _ = mock_screen.to_screen.send(ScreenInstruction::AddClient(
+        second_client_id,
+        None,
+        None,
+        false,
+        false,
+    ));
+    let _ = mock_screen
+        .to_screen
+        .send(ScreenInstruction::RemoveClient(second_client_id));
+    let _ = mock_screen
+        .to_screen
+        .send(ScreenInstruction::RequestSessionSharingStatus(
+            plugin_id, client_id,
+        ));
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    mock_screen.teardown(vec![plugin_thread, server_thread, screen_thread]);
+    let session_sharing_statuses: Vec<(Option<u32>, Option<ClientId>, SessionSharingStatus)> =
+        received_plugin_instructions
+            .lock()
+            .unwrap()
+            .iter()
+            .filter_map(|instruction| match instruction {
+                PluginInstruction::Update(updates) => Some(updates.clone()),
+                _ => None,
+            })
+            .flatten()
+            .filter_map(|update| match update {
+                (plugin_id, client_id, Event::SessionSharingStatus(session_sharing_status)) => {
+                    Some((plugin_id, client_id, session_sharing_status))
+                },
+                _ => None,
+            })
+            .collect();
+    let not_recording = |connected_clients| SessionSharingStatus {
+        connected_clients,
+        is_recording: false,
+    };
+    assert_eq!(
+        session_sharing_statuses,
+        vec![
+            (None, None, not_recording(2)),
+            (None, None, not_recording(1)),
+            (Some(plugin_id), Some(client_id), not_recording(1)),
+        ],
+        "plugins are told when a client attaches and detaches, and when they ask"
+    );
+    assert!(session_sharing_statuses[0].2.is_shared());
+    assert!(!session_sharing_statuses[1].2.is_shared());
 }
 
 #[test]
-pub fn send_cli_scroll_to_top_action() {
-    let size = Size { cols: 80, rows: 10 };
-    let client_id = 10; // fake client id should not appear in the screen's state
-    let mut initial_layout = TiledPaneLayout::default();
-    initial_layout.children_split_direction = SplitDirection::Vertical;
-    initial_layout.children = vec![TiledPaneLayout::default(), TiledPaneLayout::default()];
+pub fn screen_reports_copy_mode_and_selection_to_plugins() {
+    let size = Size { cols: 80, rows: 20 };
+    let client_id = 1;
     let mut mock_screen = MockScreen::new(size);
-    let session_metadata = mock_screen.clone_session_metadata();
-    let screen_thread = mock_screen.run(Some(initial_layout), vec![]);
+    let screen_thread = mock_screen.run(None, vec![]);
     let received_server_instructions = Arc::new(Mutex::new(vec![]));
     let server_receiver = mock_screen.server_receiver.take().unwrap();
-    let server_instruction = log_actions_in_thread!(
+    let server_thread = log_actions_in_thread!(
         received_server_instructions,
         ServerInstruction::KillSession,
         server_receiver
     );
-    let scroll_to_top_action = CliAction::ScrollToTop;
-    let mut pane_contents = String::new();
-    for i in 0..20 {
-        pane_contents.push_str(&format!("fill pane up with something {}\n\r", i));
-    }
-    let _ = mock_screen.to_screen.send(ScreenInstruction::PtyBytes(
-        0,
-        pane_contents.as_bytes().to_vec(),
-    ));
+    let received_plugin_instructions = Arc::new(Mutex::new(vec![]));
+    let plugin_receiver = mock_screen.plugin_receiver.take().unwrap();
+    let plugin_thread = log_actions_in_thread!(
+        received_plugin_instructions,
+        PluginInstruction::Exit,
+        plugin_receiver
+    );
+    let mode_info = |mode| ModeInfo {
+        mode,
+        ..Default::default()
+    };
     std::thread::sleep(std::time::Duration::from_millis(100));
-    // scroll to top
-    send_cli_action_to_server(&session_metadata, scroll_to_top_action.clone(), client_id);
+    let _ = mock_screen.to_screen.send(ScreenInstruction::ChangeMode(
+        mode_info(InputMode::Scroll),
+        client_id,
+    ));
+    let _ = mock_screen.to_screen.send(ScreenInstruction::ChangeMode(
+        mode_info(InputMode::Search),
+        client_id,
+    ));
+    let _ = mock_screen.to_screen.send(ScreenInstruction::LeftClick(
+        Position::new(5, 10),
+        client_id,
+    ));
+    let _ = mock_screen
+        .to_screen
+        .send(ScreenInstruction::LeftMouseRelease(
+            Position::new(7, 20),
+            client_id,
+        ));
+    let _ = mock_screen.to_screen.send(ScreenInstruction::ChangeMode(
+        mode_info(InputMode::Normal),
+        client_id,
+    ));
     std::thread::sleep(std::time::Duration::from_millis(100));
-    mock_screen.teardown(vec![server_instruction, screen_thread]);
-    let snapshots = take_snapshots_and_cursor_coordinates_from_render_events(
-        received_server_instructions.lock().unwrap().iter(),
-        size,
-    );
-    let snapshot_count = snapshots.len();
-    for (_cursor_coordinates, snapshot) in snapshots {
-        assert_snapshot!(format!("{}", snapshot));
+    mock_screen.teardown(vec![plugin_thread, server_thread, screen_thread]);
+    let copy_mode_events: Vec<Event> = received_plugin_instructions
+        .lock()
+        .unwrap()
+        .iter()
+        .filter_map(|instruction| match instruction {
+            PluginInstruction::Update(updates) => Some(updates.clone()),
+            _ => None,
+        })
+        .flatten()
+        .filter_map(|(_plugin_id, _client_id, event)| match event {
+            Event::EnteredCopyMode | Event::ExitedCopyMode | Event::SelectionChanged(..) => {
+                Some(event)
+            },
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        copy_mode_events.len(),
+        4,
+        "entering copy mode, the selection, its clearing on copy and exiting were reported once \
+         each: {:?}",
+        copy_mode_events
+    );
+    assert_eq!(copy_mode_events[0], Event::EnteredCopyMode);
+    match copy_mode_events[1] {
+        Event::SelectionChanged(
+            PaneId::Terminal(_),
+            Some(SelectionBounds {
+                start_line,
+                start_column,
+                end_line,
+                end_column,
+            }),
+        ) => {
+            assert_eq!(
+                end_line - start_line,
+                2,
+                "selection spans the dragged lines"
+            );
+            assert_eq!(
+                end_column - start_column,
+                10,
+                "selection spans the dragged columns"
+            );
+        },
+        ref event => panic!("expected the selection bounds, got {:?}", event),
     }
-    assert_snapshot!(format!("{}", snapshot_count));
+    assert!(
+        matches!(copy_mode_events[2], Event::SelectionChanged(_, None)),
+        "selection is reported cleared after being copied on release"
+    );
+    assert_eq!(copy_mode_events[3], Event::ExitedCopyMode);
 }
 
 #[test]
-pub fn send_cli_page_scroll_up_action() {
-    let size = Size { cols: 80, rows: 10 };
-    let client_id = 10; // fake client id should not appear in the screen's state
-    let mut initial_layout = TiledPaneLayout::default();
-    initial_layout.children_split_direction = SplitDirection::Vertical;
-    initial_layout.children = vec![TiledPaneLayout::default(), TiledPaneLayout::default()];
+pub fn screen_reports_mode_changes_to_plugins() {
+    let size = Size { cols: 80, rows: 20 };
+    let client_id = 1;
     let mut mock_screen = MockScreen::new(size);
-    let session_metadata = mock_screen.clone_session_metadata();
-    let screen_thread = mock_screen.run(Some(initial_layout), vec![]);
-    let received_server_instructions = Arc::new(Mutex::new(vec![]));
-    let server_receiver = mock_screen.server_receiver.take().unwrap();
-    let server_instruction = log_actions_in_thread!(
-        received_server_instructions,
-        ServerInstruction::KillSession,
-        server_receiver
+    let screen_thread = mock_screen.run(None, vec![]);
+    let received_plugin_instructions = Arc::new(Mutex::new(vec![]));
+    let plugin_receiver = mock_screen.plugin_receiver.take().unwrap();
+    let plugin_thread = log_actions_in_thread!(
+        received_plugin_instructions,
+        PluginInstruction::Exit,
+        plugin_receiver
     );
-    let page_scroll_up_action = CliAction::PageScrollUp;
-    let mut pane_contents = String::new();
-    for i in 0..20 {
-        pane_contents.push_str(&format!("fill pane up with something {}\n\r", i));
-    }
-    let _ = mock_screen.to_screen.send(ScreenInstruction::PtyBytes(
-        0,
-        pane_contents.as_bytes().to_vec(),
-    ));
+    let mode_info = |mode| ModeInfo {
+        mode,
+        ..Default::default()
+    };
     std::thread::sleep(std::time::Duration::from_millis(100));
-    send_cli_action_to_server(&session_metadata, page_scroll_up_action, client_id);
+    for mode in [
+        InputMode::Pane,
+        InputMode::Pane,
+        InputMode::Locked,
+        InputMode::Normal,
+    ] {
+        let _ = mock_screen
+            .to_screen
+            .send(ScreenInstruction::ChangeMode(mode_info(mode), client_id));
+    }
     std::thread::sleep(std::time::Duration::from_millis(100));
-    mock_screen.teardown(vec![server_instruction, screen_thread]);
-    let snapshots = take_snapshots_and_cursor_coordinates_from_render_events(
-        received_server_instructions.lock().unwrap().iter(),
-        size,
+    mock_screen.teardown(vec![plugin_thread, screen_thread]);
+    let mode_changes: Vec<(Option<ClientId>, InputMode)> = received_plugin_instructions
+        .lock()
+        .unwrap()
+        .iter()
+        .filter_map(|instruction| match instruction {
+            PluginInstruction::Update(updates) => Some(updates.clone()),
+            _ => None,
+        })
+        .flatten()
+        .filter_map(|(_plugin_id, client_id, event)| match event {
+            Event::ModeChanged(mode) => Some((client_id, mode)),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        mode_changes,
+        vec![
+            (Some(client_id), InputMode::Pane),
+            (Some(client_id), InputMode::Locked),
+            (Some(client_id), InputMode::Normal),
+        ],
+        "entering and leaving modes is reported, staying in the same mode is not"
     );
-    let snapshot_count = snapshots.len();
-    for (_cursor_coordinates, snapshot) in snapshots {
-        assert_snapshot!(format!("{}", snapshot));
-    }
-    assert_snapshot!(format!("{}", snapshot_count));
+}
+
+fn tab_pane_geometries(
+    screen: &Screen,
+    tab_index: usize,
+) -> Vec<(u32, usize, usize, usize, usize)> {
+    let mut pane_geometries: Vec<(u32, usize, usize, usize, usize)> = screen
+        .tabs
+        .get(&tab_index)
+        .unwrap()
+        .pane_infos()
+        .iter()
+        .map(|p| (p.id, p.pane_x, p.pane_y, p.pane_columns, p.pane_rows))
+        .collect();
+    pane_geometries.sort();
+    pane_geometries
 }
 
 #[test]
-pub fn send_cli_page_scroll_down_action() {
-    let size = Size { cols: 80, rows: 10 };
-    let client_id = 10; // fake client id should not appear in the screen's state
-    let mut initial_layout = TiledPaneLayout::default();
-    initial_layout.children_split_direction = SplitDirection::Vertical;
-    initial_layout.children = vec![TiledPaneLayout::default(), TiledPaneLayout::default()];
-    let mut mock_screen = MockScreen::new(size);
-    let session_metadata = mock_screen.clone_session_metadata();
-    let screen_thread = mock_screen.run(Some(initial_layout), vec![]);
-    let received_server_instructions = Arc::new(Mutex::new(vec![]));
-    let server_receiver = mock_screen.server_receiver.take().unwrap();
-    let server_instruction = log_actions_in_thread!(
-        received_server_instructions,
-        ServerInstruction::KillSession,
-        server_receiver
+fn merging_adjacent_panes_grows_the_survivor_over_the_absorbed_pane() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let client_id = 1;
+    let tab_index = 0;
+    let mut screen = create_new_screen(size);
+    new_tab(&mut screen, 1, tab_index);
+    let active_tab = screen.get_active_tab_mut(client_id).unwrap();
+    active_tab
+        .vertical_split(PaneId::Terminal(2), None, client_id)
+        .unwrap();
+    active_tab
+        .horizontal_split(PaneId::Terminal(3), None, client_id)
+        .unwrap();
+    let geometries = tab_pane_geometries(&screen, tab_index);
+    let (_, x, y, columns, rows) = geometries[1];
+    let (_, _, _, _, absorbed_rows) = geometries[2];
+    let combined_area = (2, x, y, columns, rows + absorbed_rows);
+
+    assert!(
+        screen
+            .merge_panes(PaneId::Terminal(1), PaneId::Terminal(3), client_id)
+            .is_err(),
+        "panes that do not share a whole border cannot be merged"
+    );
+    assert_eq!(
+        tab_pane_geometries(&screen, tab_index),
+        geometries,
+        "panes were left as they were"
     );
-    let page_scroll_up_action = CliAction::PageScrollUp;
-    let page_scroll_down_action = CliAction::PageScrollDown;
-    let mut pane_contents = String::new();
-    for i in 0..20 {
-        pane_contents.push_str(&format!("fill pane up with something {}\n\r", i));
-    }
-    let _ = mock_screen.to_screen.send(ScreenInstruction::PtyBytes(
-        0,
-        pane_contents.as_bytes().to_vec(),
-    ));
-    std::thread::sleep(std::time::Duration::from_millis(100));
 
-    // scroll up some
-    send_cli_action_to_server(&session_metadata, page_scroll_up_action.clone(), client_id);
-    send_cli_action_to_server(&session_metadata, page_scroll_up_action.clone(), client_id);
+    screen
+        .merge_panes(PaneId::Terminal(2), PaneId::Terminal(3), client_id)
+        .expect("TEST");
+    let geometries = tab_pane_geometries(&screen, tab_index);
+    assert_eq!(geometries.len(), 2, "absorbed pane was closed");
+    assert_eq!(
+        geometries[1], combined_area,
+        "survivor occupies the area of both panes"
+    );
+}
 
-    // scroll down
-    send_cli_action_to_server(&session_metadata, page_scroll_down_action, client_id);
-    std::thread::sleep(std::time::Duration::from_millis(100));
-    mock_screen.teardown(vec![server_instruction, screen_thread]);
-    let snapshots = take_snapshots_and_cursor_coordinates_from_render_events(
-        received_server_instructions.lock().unwrap().iter(),
-        size,
+#[test]
+fn merging_panes_that_are_not_adjacent_keeps_the_fullscreen_pane() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let client_id = 1;
+    let tab_index = 0;
+    let mut screen = create_new_screen(size);
+    new_tab(&mut screen, 1, tab_index);
+    let active_tab = screen.get_active_tab_mut(client_id).unwrap();
+    active_tab
+        .vertical_split(PaneId::Terminal(2), None, client_id)
+        .unwrap();
+    active_tab
+        .horizontal_split(PaneId::Terminal(3), None, client_id)
+        .unwrap();
+    active_tab.toggle_active_pane_fullscreen(client_id);
+    assert!(active_tab.is_fullscreen_active());
+
+    assert!(
+        screen
+            .merge_panes(PaneId::Terminal(1), PaneId::Terminal(3), client_id)
+            .is_err(),
+        "panes that do not share a whole border cannot be merged"
+    );
+    assert!(
+        screen
+            .get_active_tab(client_id)
+            .unwrap()
+            .is_fullscreen_active(),
+        "fullscreen pane was left as it was"
+    );
+}
+
+#[test]
+fn swap_layout_of_indexed_tab() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let client_id = 1;
+    let tab_index = 0;
+    let mut screen = create_new_screen(size);
+    let base_layout = r#"
+        layout {
+            pane split_direction="vertical" {
+                pane
+                pane
+            }
+        }
+    "#;
+    let swap_layouts = r#"
+        layout {
+            swap_tiled_layout name="horizontal" {
+                tab {
+                    pane split_direction="horizontal" {
+                        pane
+                        pane
+                    }
+                }
+            }
+            swap_tiled_layout name="stacked" {
+                tab {
+                    pane stacked=true {
+                        pane
+                        pane
+                    }
+                }
+            }
+        }
+    "#;
+    let (base_layout, base_floating_layout) =
+        Layout::from_kdl(base_layout, "file_name.kdl".into(), None, None)
+            .unwrap()
+            .template
+            .unwrap();
+    let swap_layout = Layout::from_kdl(swap_layouts, "file_name.kdl".into(), None, None).unwrap();
+    screen
+        .new_tab(
+            tab_index,
+            (
+                swap_layout.swap_tiled_layouts.clone(),
+                swap_layout.swap_floating_layouts.clone(),
+            ),
+            None,
+            client_id,
+        )
+        .unwrap();
+    screen
+        .apply_layout(
+            base_layout,
+            base_floating_layout,
+            vec![(1, None), (2, None)],
+            vec![], // new floating terminal ids
+            HashMap::new(),
+            tab_index,
+            client_id,
+        )
+        .unwrap();
+    let base_geometries = tab_pane_geometries(&screen, tab_index);
+
+    screen
+        .swap_layout(tab_index, SwapLayoutDirection::Next)
+        .unwrap();
+    let horizontal_geometries = tab_pane_geometries(&screen, tab_index);
+    assert_eq!(
+        screen.tabs.get(&tab_index).unwrap().swap_layout_info().0,
+        Some("horizontal".to_owned()),
+        "swapped to the next layout"
+    );
+    assert_ne!(
+        base_geometries, horizontal_geometries,
+        "pane geometry changed after swapping layouts"
+    );
+    assert!(
+        horizontal_geometries
+            .iter()
+            .all(|(_, x, _, columns, _)| *x == 0 && *columns == size.cols),
+        "panes are laid out horizontally"
+    );
+
+    screen
+        .swap_layout(tab_index, SwapLayoutDirection::Next)
+        .unwrap();
+    screen
+        .swap_layout(tab_index, SwapLayoutDirection::Next)
+        .unwrap();
+    assert_eq!(
+        tab_pane_geometries(&screen, tab_index),
+        base_geometries,
+        "cycling through all swap layouts returns to the original geometry"
+    );
+
+    screen
+        .swap_layout(tab_index, SwapLayoutDirection::Previous)
+        .unwrap();
+    screen
+        .swap_layout(tab_index, SwapLayoutDirection::Previous)
+        .unwrap();
+    // the panes can take each other's places when passing through the stacked layout
+    let pane_positions = |geometries: Vec<(u32, usize, usize, usize, usize)>| {
+        let mut positions: Vec<(usize, usize, usize, usize)> = geometries
+            .into_iter()
+            .map(|(_pane_id, x, y, columns, rows)| (x, y, columns, rows))
+            .collect();
+        positions.sort();
+        positions
+    };
+    assert_eq!(
+        screen.tabs.get(&tab_index).unwrap().swap_layout_info().0,
+        Some("horizontal".to_owned()),
+        "cycled backwards to the horizontal layout"
+    );
+    assert_eq!(
+        pane_positions(tab_pane_geometries(&screen, tab_index)),
+        pane_positions(horizontal_geometries),
+        "cycling backwards lays the panes out as before"
     );
-    let snapshot_count = snapshots.len();
-    for (_cursor_coordinates, snapshot) in snapshots {
-        assert_snapshot!(format!("{}", snapshot));
-    }
-    assert_snapshot!(format!("{}", snapshot_count));
 }
 
 #[test]
-pub fn send_cli_half_page_scroll_up_action() {
+pub fn copy_text_to_clipboard_failure_is_reported_to_plugins() {
     let size = Size { cols: 80, rows: 10 };
-    let client_id = 10; // fake client id should not appear in the screen's state
-    let mut initial_layout = TiledPaneLayout::default();
-    initial_layout.children_split_direction = SplitDirection::Vertical;
-    initial_layout.children = vec![TiledPaneLayout::default(), TiledPaneLayout::default()];
+    let client_id = 1;
     let mut mock_screen = MockScreen::new(size);
-    let session_metadata = mock_screen.clone_session_metadata();
-    let screen_thread = mock_screen.run(Some(initial_layout), vec![]);
-    let received_server_instructions = Arc::new(Mutex::new(vec![]));
-    let server_receiver = mock_screen.server_receiver.take().unwrap();
-    let server_instruction = log_actions_in_thread!(
-        received_server_instructions,
-        ServerInstruction::KillSession,
-        server_receiver
+    // spawning this command fails, as would a broken system clipboard
+    mock_screen.config_options.copy_command = Some("zellij-test-missing-copy-command".to_owned());
+    mock_screen.new_tab(TiledPaneLayout::default());
+    let screen_thread = mock_screen.run(Some(TiledPaneLayout::default()), vec![]);
+    let received_plugin_instructions = Arc::new(Mutex::new(vec![]));
+    let plugin_receiver = mock_screen.plugin_receiver.take().unwrap();
+    let plugin_thread = log_actions_in_thread!(
+        received_plugin_instructions,
+        PluginInstruction::Exit,
+        plugin_receiver
     );
-    let half_page_scroll_up_action = CliAction::HalfPageScrollUp;
-    let mut pane_contents = String::new();
-    for i in 0..20 {
-        pane_contents.push_str(&format!("fill pane up with something {}\n\r", i));
-    }
-    let _ = mock_screen.to_screen.send(ScreenInstruction::PtyBytes(
-        0,
-        pane_contents.as_bytes().to_vec(),
-    ));
     std::thread::sleep(std::time::Duration::from_millis(100));
-    send_cli_action_to_server(&session_metadata, half_page_scroll_up_action, client_id);
+    let _ = mock_screen
+        .to_screen
+        .send(ScreenInstruction::CopyTextToClipboard(
+            "text copied by a plugin".to_owned(),
+            client_id,
+        ));
     std::thread::sleep(std::time::Duration::from_millis(100));
-    mock_screen.teardown(vec![server_instruction, screen_thread]);
-    let snapshots = take_snapshots_and_cursor_coordinates_from_render_events(
-        received_server_instructions.lock().unwrap().iter(),
-        size,
+    mock_screen.teardown(vec![plugin_thread, screen_thread]);
+    let clipboard_events: Vec<Event> = received_plugin_instructions
+        .lock()
+        .unwrap()
+        .iter()
+        .filter_map(|instruction| match instruction {
+            PluginInstruction::Update(updates) => Some(updates.clone()),
+            _ => None,
+        })
+        .flatten()
+        .filter_map(|(_plugin_id, _client_id, event)| match event {
+            Event::SystemClipboardFailure(..)
+            | Event::CopyToClipboard(..)
+            | Event::ClipboardCopySucceeded(..) => Some(event),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        clipboard_events,
+        vec![
+            Event::CopyToClipboard(CopyDestination::Command),
+            Event::SystemClipboardFailure(23)
+        ],
+        "plugins are told about the copy, and then that the clipboard could not be written to"
     );
-    let snapshot_count = snapshots.len();
-    for (_cursor_coordinates, snapshot) in snapshots {
-        assert_snapshot!(format!("{}", snapshot));
-    }
-    assert_snapshot!(format!("{}", snapshot_count));
 }
 
 #[test]
-pub fn send_cli_half_page_scroll_down_action() {
+pub fn copy_text_to_clipboard_success_is_reported_to_plugins() {
     let size = Size { cols: 80, rows: 10 };
-    let client_id = 10; // fake client id should not appear in the screen's state
-    let mut initial_layout = TiledPaneLayout::default();
-    initial_layout.children_split_direction = SplitDirection::Vertical;
-    initial_layout.children = vec![TiledPaneLayout::default(), TiledPaneLayout::default()];
+    let client_id = 1;
     let mut mock_screen = MockScreen::new(size);
-    let session_metadata = mock_screen.clone_session_metadata();
-    let screen_thread = mock_screen.run(Some(initial_layout), vec![]);
-    let received_server_instructions = Arc::new(Mutex::new(vec![]));
-    let server_receiver = mock_screen.server_receiver.take().unwrap();
-    let server_instruction = log_actions_in_thread!(
-        received_server_instructions,
-        ServerInstruction::KillSession,
-        server_receiver
+    // without a copy command, the text is sent to the terminal (OSC52), which always succeeds
+    mock_screen.config_options.copy_command = None;
+    mock_screen.new_tab(TiledPaneLayout::default());
+    let screen_thread = mock_screen.run(Some(TiledPaneLayout::default()), vec![]);
+    let received_plugin_instructions = Arc::new(Mutex::new(vec![]));
+    let plugin_receiver = mock_screen.plugin_receiver.take().unwrap();
+    let plugin_thread = log_actions_in_thread!(
+        received_plugin_instructions,
+        PluginInstruction::Exit,
+        plugin_receiver
     );
-    let half_page_scroll_up_action = CliAction::HalfPageScrollUp;
-    let half_page_scroll_down_action = CliAction::HalfPageScrollDown;
-    let mut pane_contents = String::new();
-    for i in 0..20 {
-        pane_contents.push_str(&format!("fill pane up with something {}\n\r", i));
-    }
-    let _ = mock_screen.to_screen.send(ScreenInstruction::PtyBytes(
-        0,
-        pane_contents.as_bytes().to_vec(),
-    ));
     std::thread::sleep(std::time::Duration::from_millis(100));
-
-    // scroll up some
-    send_cli_action_to_server(
-        &session_metadata,
-        half_page_scroll_up_action.clone(),
-        client_id,
-    );
-    send_cli_action_to_server(
-        &session_metadata,
-        half_page_scroll_up_action.clone(),
-        client_id,
-    );
-
-    // scroll down
-    send_cli_action_to_server(&session_metadata, half_page_scroll_down_action, client_id);
+    let _ = mock_screen
+        .to_screen
+        .send(ScreenInstruction::CopyTextToClipboard(
+            "".to_owned(),
+            client_id,
+        ));
+    let _ = mock_screen
+        .to_screen
+        .send(ScreenInstruction::CopyTextToClipboard(
+            "text copied by a plugin".to_owned(),
+            client_id,
+        ));
     std::thread::sleep(std::time::Duration::from_millis(100));
-    mock_screen.teardown(vec![server_instruction, screen_thread]);
-    let snapshots = take_snapshots_and_cursor_coordinates_from_render_events(
-        received_server_instructions.lock().unwrap().iter(),
-        size,
+    mock_screen.teardown(vec![plugin_thread, screen_thread]);
+    let clipboard_copy_events: Vec<Event> = received_plugin_instructions
+        .lock()
+        .unwrap()
+        .iter()
+        .filter_map(|instruction| match instruction {
+            PluginInstruction::Update(updates) => Some(updates.clone()),
+            _ => None,
+        })
+        .flatten()
+        .filter_map(|(_plugin_id, _client_id, event)| match event {
+            Event::SystemClipboardFailure(..) | Event::ClipboardCopySucceeded(..) => Some(event),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        clipboard_copy_events,
+        vec![Event::ClipboardCopySucceeded(23)],
+        "plugins are told the text was copied, but not about the empty copy"
     );
-    let snapshot_count = snapshots.len();
-    for (_cursor_coordinates, snapshot) in snapshots {
-        assert_snapshot!(format!("{}", snapshot));
-    }
-    assert_snapshot!(format!("{}", snapshot_count));
 }
 
 #[test]
-pub fn send_cli_toggle_full_screen_action() {
-    let size = Size { cols: 80, rows: 10 };
-    let client_id = 10; // fake client id should not appear in the screen's state
-    let mut initial_layout = TiledPaneLayout::default();
-    initial_layout.children_split_direction = SplitDirection::Vertical;
-    initial_layout.children = vec![TiledPaneLayout::default(), TiledPaneLayout::default()];
-    let mut mock_screen = MockScreen::new(size);
-    let session_metadata = mock_screen.clone_session_metadata();
-    let screen_thread = mock_screen.run(Some(initial_layout), vec![]);
-    let received_server_instructions = Arc::new(Mutex::new(vec![]));
-    let server_receiver = mock_screen.server_receiver.take().unwrap();
-    let server_instruction = log_actions_in_thread!(
-        received_server_instructions,
-        ServerInstruction::KillSession,
-        server_receiver
+fn resizing_respects_pane_min_size() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let client_id = 1;
+    let tab_index = 0;
+    let mut screen = create_new_screen(size);
+    new_tab(&mut screen, 1, tab_index);
+    screen
+        .get_active_tab_mut(client_id)
+        .unwrap()
+        .new_pane(PaneId::Terminal(2), None, None, None, Some(client_id))
+        .unwrap();
+    let pane_columns = |screen: &Screen| -> Vec<usize> {
+        tab_pane_geometries(screen, tab_index)
+            .iter()
+            .map(|(_, _, _, columns, _)| *columns)
+            .collect()
+    };
+
+    screen
+        .set_pane_min_size(PaneId::Terminal(2), 80, 0)
+        .expect("TEST");
+    let columns = pane_columns(&screen);
+    assert!(
+        columns[1] >= 80,
+        "pane grew to its minimum size: {:?}",
+        columns
     );
-    let toggle_full_screen_action = CliAction::ToggleFullscreen;
-    send_cli_action_to_server(&session_metadata, toggle_full_screen_action, client_id);
-    std::thread::sleep(std::time::Duration::from_millis(100));
-    mock_screen.teardown(vec![server_instruction, screen_thread]);
-    let snapshots = take_snapshots_and_cursor_coordinates_from_render_events(
-        received_server_instructions.lock().unwrap().iter(),
-        size,
+    assert_eq!(
+        columns.iter().sum::<usize>(),
+        121,
+        "other pane shrank instead"
     );
-    let snapshot_count = snapshots.len();
-    for (_cursor_coordinates, snapshot) in snapshots {
-        assert_snapshot!(format!("{}", snapshot));
+
+    {
+        let tab = screen.get_active_tab_mut(client_id).unwrap();
+        let _ = tab.resize(client_id, ResizeStrategy::new(Resize::Decrease, None));
+        tab.focus_pane_with_id(PaneId::Terminal(1), false, client_id)
+            .unwrap();
+        for _ in 0..5 {
+            let _ = tab.resize(
+                client_id,
+                ResizeStrategy::new(Resize::Increase, Some(Direction::Right)),
+            );
+        }
     }
-    assert_snapshot!(format!("{}", snapshot_count));
+    let columns = pane_columns(&screen);
+    assert!(
+        columns[1] >= 80,
+        "resizing panes does not shrink a pane below its minimum size: {:?}",
+        columns
+    );
+
+    screen
+        .resize_to_screen(Size {
+            cols: 100,
+            rows: 20,
+        })
+        .unwrap();
+    let columns = pane_columns(&screen);
+    assert!(
+        columns[1] >= 80,
+        "resizing the screen shrinks the other pane instead: {:?}",
+        columns
+    );
+    assert_eq!(columns.iter().sum::<usize>(), 100);
+
+    screen
+        .resize_to_screen(Size { cols: 60, rows: 20 })
+        .unwrap();
+    let columns = pane_columns(&screen);
+    assert!(
+        columns.iter().all(|columns| *columns > 0),
+        "panes are still laid out when there's no room for the minimum size: {:?}",
+        columns
+    );
+    assert_eq!(columns.iter().sum::<usize>(), 60);
+}
+
+fn route_client_over_socket(
+    mock_screen: &MockScreen,
+    listener: &LocalSocketListener,
+    socket_path: &std::path::Path,
+    session_data: &Arc<RwLock<Option<SessionMetaData>>>,
+    session_state: &Arc<RwLock<SessionState>>,
+    client_id: ClientId,
+) -> (
+    IpcSenderWithContext<ClientToServerMsg>,
+    std::thread::JoinHandle<()>,
+) {
+    let client_sender = IpcSenderWithContext::new(LocalSocketStream::connect(socket_path).unwrap());
+    let receiver = IpcReceiverWithContext::new(listener.accept().unwrap());
+    let route_thread = std::thread::spawn({
+        let session_data = session_data.clone();
+        let session_state = session_state.clone();
+        let os_input = Box::new(mock_screen.os_input.clone());
+        let to_server = mock_screen.to_server.clone();
+        move || {
+            route_thread_main(
+                session_data,
+                session_state,
+                os_input,
+                to_server,
+                receiver,
+                client_id,
+            )
+            .unwrap();
+        }
+    });
+    (client_sender, route_thread)
 }
 
 #[test]
-pub fn send_cli_toggle_pane_frames_action() {
-    let size = Size { cols: 80, rows: 10 };
-    let client_id = 10; // fake client id should not appear in the screen's state
-    let mut initial_layout = TiledPaneLayout::default();
-    initial_layout.children_split_direction = SplitDirection::Vertical;
-    initial_layout.children = vec![TiledPaneLayout::default(), TiledPaneLayout::default()];
+pub fn input_from_observer_clients_is_dropped_while_renders_still_flow() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let client_id = 1;
+    let observer_client_id = 2;
     let mut mock_screen = MockScreen::new(size);
-    let session_metadata = mock_screen.clone_session_metadata();
-    let screen_thread = mock_screen.run(Some(initial_layout), vec![]);
-    let received_server_instructions = Arc::new(Mutex::new(vec![]));
+    let pty_writer_receiver = mock_screen.pty_writer_receiver.take().unwrap();
     let server_receiver = mock_screen.server_receiver.take().unwrap();
-    let server_instruction = log_actions_in_thread!(
+    let session_data = Arc::new(RwLock::new(Some(mock_screen.clone_session_metadata())));
+    let screen_thread = mock_screen.run(None, vec![]);
+    let received_pty_instructions = Arc::new(Mutex::new(vec![]));
+    let pty_writer_thread = log_actions_in_thread!(
+        received_pty_instructions,
+        PtyWriteInstruction::Exit,
+        pty_writer_receiver
+    );
+    let received_server_instructions = Arc::new(Mutex::new(vec![]));
+    let server_thread = log_actions_in_thread!(
         received_server_instructions,
         ServerInstruction::KillSession,
         server_receiver
     );
-    let toggle_pane_frames_action = CliAction::TogglePaneFrames;
-    send_cli_action_to_server(&session_metadata, toggle_pane_frames_action, client_id);
-    std::thread::sleep(std::time::Duration::from_millis(100));
-    mock_screen.teardown(vec![server_instruction, screen_thread]);
-    let snapshots = take_snapshots_and_cursor_coordinates_from_render_events(
-        received_server_instructions.lock().unwrap().iter(),
-        size,
+    let _ = mock_screen.to_screen.send(ScreenInstruction::AddClient(
+        observer_client_id,
+        None,
+        None,
+        false,
+        true,
+    ));
+    let mut session_state = SessionState::new();
+    session_state.new_client();
+    session_state.new_client();
+    session_state.set_client_observer(observer_client_id);
+    let session_state = Arc::new(RwLock::new(session_state));
+
+    // both clients talk to their own router thread over a socket, like attached clients do
+    let sock_dir = tempdir().unwrap();
+    let socket_path = sock_dir.path().join("zellij-test");
+    let listener = LocalSocketListener::bind(socket_path.clone()).unwrap();
+    let (mut client_sender, client_route_thread) = route_client_over_socket(
+        &mock_screen,
+        &listener,
+        &socket_path,
+        &session_data,
+        &session_state,
+        client_id,
+    );
+    let (mut observer_sender, observer_route_thread) = route_client_over_socket(
+        &mock_screen,
+        &listener,
+        &socket_path,
+        &session_data,
+        &session_state,
+        observer_client_id,
+    );
+    observer_sender
+        .send(ClientToServerMsg::Action(
+            Action::Write("input from the observer".as_bytes().to_vec()),
+            None,
+            None,
+        ))
+        .unwrap();
+    client_sender
+        .send(ClientToServerMsg::Action(
+            Action::Write("input from the client".as_bytes().to_vec()),
+            None,
+            None,
+        ))
+        .unwrap();
+    // neither can observers control the session, the router thread of the observer ends with the
+    // rejected detach like it does with any detach
+    observer_sender
+        .send(ClientToServerMsg::KillSession)
+        .unwrap();
+    observer_sender
+        .send(ClientToServerMsg::DetachSession(vec![client_id]))
+        .unwrap();
+    client_sender.send(ClientToServerMsg::ClientExited).unwrap();
+    observer_route_thread.join().unwrap();
+    client_route_thread.join().unwrap();
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    let _ = mock_screen.to_screen.send(ScreenInstruction::Render);
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    mock_screen.teardown(vec![pty_writer_thread, server_thread, screen_thread]);
+
+    let written_input: Vec<Vec<u8>> = received_pty_instructions
+        .lock()
+        .unwrap()
+        .iter()
+        .filter_map(|instruction| match instruction {
+            PtyWriteInstruction::Write(bytes, _terminal_id) => Some(bytes.clone()),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        written_input,
+        vec!["input from the client".as_bytes().to_vec()],
+        "only input from the non-observer client reaches the terminal"
+    );
+    // the last instruction is the KillSession the server thread is torn down with
+    let observer_controlled_the_session = received_server_instructions
+        .lock()
+        .unwrap()
+        .split_last()
+        .map(|(_teardown, instructions)| {
+            instructions.iter().any(|instruction| {
+                matches!(
+                    instruction,
+                    ServerInstruction::KillSession | ServerInstruction::DetachSession(..)
+                )
+            })
+        })
+        .unwrap_or(false);
+    assert!(
+        !observer_controlled_the_session,
+        "the observer can neither kill the session nor detach other clients"
+    );
+    let last_render = received_server_instructions
+        .lock()
+        .unwrap()
+        .iter()
+        .rev()
+        .find_map(|instruction| match instruction {
+            ServerInstruction::Render(Some(output)) => Some(output.clone()),
+            _ => None,
+        })
+        .expect("screen was rendered");
+    assert!(
+        last_render.contains_key(&client_id) && last_render.contains_key(&observer_client_id),
+        "renders are still sent to both clients"
+    );
+    let observer_input_unblocked = mock_screen
+        .os_input
+        .server_to_client_messages
+        .lock()
+        .unwrap()
+        .get(&observer_client_id)
+        .map(|messages| {
+            messages
+                .iter()
+                .any(|message| matches!(message, ServerToClientMsg::UnblockInputThread))
+        })
+        .unwrap_or(false);
+    assert!(
+        observer_input_unblocked,
+        "the input thread of the observer is unblocked when its input is dropped"
     );
-    let snapshot_count = snapshots.len();
-    for (_cursor_coordinates, snapshot) in snapshots {
-        assert_snapshot!(format!("{}", snapshot));
-    }
-    assert_snapshot!(format!("{}", snapshot_count));
 }
 
 #[test]
-pub fn send_cli_toggle_active_tab_sync_action() {
+fn resize_pane_to_absolute_size() {
     let size = Size {
         cols: 121,
         rows: 20,
     };
-    let client_id = 10; // fake client id should not appear in the screen's state
-    let mut mock_screen = MockScreen::new(size);
-    let pty_writer_receiver = mock_screen.pty_writer_receiver.take().unwrap();
-    let session_metadata = mock_screen.clone_session_metadata();
-    let mut initial_layout = TiledPaneLayout::default();
-    initial_layout.children_split_direction = SplitDirection::Vertical;
-    initial_layout.children = vec![TiledPaneLayout::default(), TiledPaneLayout::default()];
-    let screen_thread = mock_screen.run(Some(initial_layout), vec![]);
-    let received_pty_instructions = Arc::new(Mutex::new(vec![]));
-    let pty_writer_thread = log_actions_in_thread!(
-        received_pty_instructions,
-        PtyWriteInstruction::Exit,
-        pty_writer_receiver
-    );
-    let cli_toggle_active_tab_sync_action = CliAction::ToggleActiveSyncTab;
-    let cli_write_action = CliAction::Write {
-        bytes: vec![102, 111, 111],
+    let client_id = 1;
+    let tab_index = 0;
+    let mut screen = create_new_screen(size);
+    new_tab(&mut screen, 1, tab_index);
+    {
+        let tab = screen.get_active_tab_mut(client_id).unwrap();
+        tab.new_pane(PaneId::Terminal(2), None, None, None, Some(client_id))
+            .unwrap();
+        tab.new_pane(PaneId::Terminal(3), None, Some(true), None, Some(client_id))
+            .unwrap();
+    }
+    let pane_size = |screen: &Screen, pane_id: u32| -> Size {
+        tab_pane_geometries(screen, tab_index)
+            .iter()
+            .find(|(id, ..)| *id == pane_id)
+            .map(|(_, _, _, cols, rows)| Size {
+                rows: *rows,
+                cols: *cols,
+            })
+            .unwrap()
     };
-    send_cli_action_to_server(
-        &session_metadata,
-        cli_toggle_active_tab_sync_action,
-        client_id,
+
+    let applied_size = screen
+        .resize_pane_to(PaneId::Terminal(2), 40, 20)
+        .expect("TEST");
+    assert_eq!(
+        applied_size,
+        Size { cols: 40, rows: 20 },
+        "tiled pane resized"
+    );
+    assert_eq!(
+        pane_size(&screen, 2),
+        applied_size,
+        "reported the actual size"
+    );
+    assert_eq!(pane_size(&screen, 1).cols, 81, "sibling pane made room");
+
+    let applied_size = screen
+        .resize_pane_to(PaneId::Terminal(2), 200, 10)
+        .expect("TEST");
+    assert!(
+        applied_size.cols < 121 && applied_size.cols > 40,
+        "tiled pane grew as much as its sibling allows: {:?}",
+        applied_size
+    );
+    assert_eq!(
+        applied_size.rows, 20,
+        "a lone pane in its column can't get shorter"
+    );
+    assert_eq!(
+        pane_size(&screen, 2),
+        applied_size,
+        "reported the actual size"
+    );
+
+    let applied_size = screen
+        .resize_pane_to(PaneId::Terminal(3), 33, 7)
+        .expect("TEST");
+    assert_eq!(
+        applied_size,
+        Size { cols: 33, rows: 7 },
+        "floating pane resized exactly"
+    );
+    assert_eq!(
+        pane_size(&screen, 3),
+        applied_size,
+        "reported the actual size"
+    );
+
+    let applied_size = screen
+        .resize_pane_to(PaneId::Terminal(3), 500, 500)
+        .expect("TEST");
+    assert_eq!(applied_size, size, "floating pane clamped to the tab");
+
+    assert!(
+        screen.resize_pane_to(PaneId::Terminal(4), 10, 10).is_err(),
+        "resizing a non-existent pane fails"
     );
-    send_cli_action_to_server(&session_metadata, cli_write_action, client_id);
-    std::thread::sleep(std::time::Duration::from_millis(100)); // give time for actions to be
-    mock_screen.teardown(vec![pty_writer_thread, screen_thread]);
-    assert_snapshot!(format!("{:?}", *received_pty_instructions.lock().unwrap()));
 }
 
 #[test]
-pub fn send_cli_new_pane_action_with_default_parameters() {
+pub fn send_cli_resize_pane_to_action() {
     let size = Size {
         cols: 121,
         rows: 20,
     };
     let client_id = 10; // fake client id should not appear in the screen's state
     let mut mock_screen = MockScreen::new(size);
-    let pty_receiver = mock_screen.pty_receiver.take().unwrap();
     let session_metadata = mock_screen.clone_session_metadata();
+    let server_receiver = mock_screen.server_receiver.take().unwrap();
     let mut initial_layout = TiledPaneLayout::default();
     initial_layout.children_split_direction = SplitDirection::Vertical;
     initial_layout.children = vec![TiledPaneLayout::default(), TiledPaneLayout::default()];
     let screen_thread = mock_screen.run(Some(initial_layout), vec![]);
-    let received_pty_instructions = Arc::new(Mutex::new(vec![]));
-    let pty_thread = log_actions_in_thread!(
-        received_pty_instructions,
-        PtyInstruction::Exit,
-        pty_receiver
+    let received_server_instructions = Arc::new(Mutex::new(vec![]));
+    let server_thread = log_actions_in_thread!(
+        received_server_instructions,
+        ServerInstruction::KillSession,
+        server_receiver
+    );
+    send_cli_action_to_server(
+        &session_metadata,
+        CliAction::ResizePaneTo { cols: 40, rows: 20 },
+        client_id,
+    );
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    mock_screen.teardown(vec![server_thread, screen_thread]);
+    let applied_size = received_server_instructions
+        .lock()
+        .unwrap()
+        .iter()
+        .find_map(|instruction| match instruction {
+            ServerInstruction::Log(lines, log_client_id) if *log_client_id == client_id => {
+                Some(lines.clone())
+            },
+            _ => None,
+        });
+    assert_eq!(
+        applied_size,
+        Some(vec!["40x20".to_owned()]),
+        "focused pane resized and its new size reported to the cli client"
     );
-    let cli_new_pane_action = CliAction::NewPane {
-        direction: None,
-        command: vec![],
-        plugin: None,
-        cwd: None,
-        floating: false,
-        in_place: false,
-        name: None,
-        close_on_exit: false,
-        start_suspended: false,
-        configuration: None,
-    };
-    send_cli_action_to_server(&session_metadata, cli_new_pane_action, client_id);
-    std::thread::sleep(std::time::Duration::from_millis(100)); // give time for actions to be
-    mock_screen.teardown(vec![pty_thread, screen_thread]);
-    assert_snapshot!(format!("{:?}", *received_pty_instructions.lock().unwrap()));
 }
 
 #[test]
-pub fn send_cli_new_pane_action_with_split_direction() {
+fn resize_active_pane_to_half_preset() {
     let size = Size {
         cols: 121,
         rows: 20,
     };
-    let client_id = 10; // fake client id should not appear in the screen's state
-    let mut mock_screen = MockScreen::new(size);
-    let pty_receiver = mock_screen.pty_receiver.take().unwrap();
-    let session_metadata = mock_screen.clone_session_metadata();
-    let mut initial_layout = TiledPaneLayout::default();
-    initial_layout.children_split_direction = SplitDirection::Vertical;
-    initial_layout.children = vec![TiledPaneLayout::default(), TiledPaneLayout::default()];
-    let screen_thread = mock_screen.run(Some(initial_layout), vec![]);
-    let received_pty_instructions = Arc::new(Mutex::new(vec![]));
-    let pty_thread = log_actions_in_thread!(
-        received_pty_instructions,
-        PtyInstruction::Exit,
-        pty_receiver
-    );
-    let cli_new_pane_action = CliAction::NewPane {
-        direction: Some(Direction::Right),
-        command: vec![],
-        plugin: None,
-        cwd: None,
-        floating: false,
-        in_place: false,
-        name: None,
-        close_on_exit: false,
-        start_suspended: false,
-        configuration: None,
+    let client_id = 1;
+    let tab_index = 0;
+    let mut screen = create_new_screen(size);
+    new_tab(&mut screen, 1, tab_index);
+    {
+        let tab = screen.get_active_tab_mut(client_id).unwrap();
+        tab.new_pane(PaneId::Terminal(2), None, None, None, Some(client_id))
+            .unwrap();
+        tab.resize_active_pane_to_preset(client_id, ResizePreset::Third)
+            .unwrap();
+    }
+    let pane_columns = |screen: &Screen| -> Vec<usize> {
+        tab_pane_geometries(screen, tab_index)
+            .iter()
+            .map(|(_, _, _, columns, _)| *columns)
+            .collect()
     };
-    send_cli_action_to_server(&session_metadata, cli_new_pane_action, client_id);
-    std::thread::sleep(std::time::Duration::from_millis(100)); // give time for actions to be
-    mock_screen.teardown(vec![pty_thread, screen_thread]);
-    assert_snapshot!(format!("{:?}", *received_pty_instructions.lock().unwrap()));
+    assert_eq!(
+        pane_columns(&screen),
+        vec![81, 40],
+        "focused pane took a third"
+    );
+
+    screen
+        .get_active_tab_mut(client_id)
+        .unwrap()
+        .resize_active_pane_to_preset(client_id, ResizePreset::Half)
+        .unwrap();
+    let columns = pane_columns(&screen);
+    assert_eq!(
+        columns[1], 61,
+        "focused pane takes half of the tab: {:?}",
+        columns
+    );
+    assert_eq!(
+        columns.iter().sum::<usize>(),
+        121,
+        "its sibling takes the rest"
+    );
+    let rows: Vec<usize> = tab_pane_geometries(&screen, tab_index)
+        .iter()
+        .map(|(_, _, _, _, rows)| *rows)
+        .collect();
+    assert_eq!(rows, vec![20, 20], "only the dominant axis was resized");
 }
 
 #[test]
-pub fn send_cli_new_pane_action_with_command_and_cwd() {
-    let size = Size {
-        cols: 121,
-        rows: 20,
-    };
+pub fn send_cli_rename_session_action() {
+    let size = Size { cols: 80, rows: 20 };
     let client_id = 10; // fake client id should not appear in the screen's state
     let mut mock_screen = MockScreen::new(size);
-    let pty_receiver = mock_screen.pty_receiver.take().unwrap();
     let session_metadata = mock_screen.clone_session_metadata();
-    let mut initial_layout = TiledPaneLayout::default();
-    initial_layout.children_split_direction = SplitDirection::Vertical;
-    initial_layout.children = vec![TiledPaneLayout::default(), TiledPaneLayout::default()];
-    let screen_thread = mock_screen.run(Some(initial_layout), vec![]);
-    let received_pty_instructions = Arc::new(Mutex::new(vec![]));
-    let pty_thread = log_actions_in_thread!(
-        received_pty_instructions,
-        PtyInstruction::Exit,
-        pty_receiver
+    let screen_thread = mock_screen.run(None, vec![]);
+    let received_plugin_instructions = Arc::new(Mutex::new(vec![]));
+    let plugin_receiver = mock_screen.plugin_receiver.take().unwrap();
+    let plugin_thread = log_actions_in_thread!(
+        received_plugin_instructions,
+        PluginInstruction::Exit,
+        plugin_receiver
     );
-    let cli_new_pane_action = CliAction::NewPane {
-        direction: Some(Direction::Right),
-        command: vec!["htop".into()],
-        plugin: None,
-        cwd: Some("/some/folder".into()),
-        floating: false,
-        in_place: false,
-        name: None,
-        close_on_exit: false,
-        start_suspended: false,
-        configuration: None,
+    let received_server_instructions = Arc::new(Mutex::new(vec![]));
+    let server_receiver = mock_screen.server_receiver.take().unwrap();
+    let server_thread = log_actions_in_thread!(
+        received_server_instructions,
+        ServerInstruction::KillSession,
+        server_receiver
+    );
+    let rename_session_action = CliAction::RenameSession {
+        name: "my-project".to_owned(),
     };
-    send_cli_action_to_server(&session_metadata, cli_new_pane_action, client_id);
-    std::thread::sleep(std::time::Duration::from_millis(100)); // give time for actions to be
-    mock_screen.teardown(vec![pty_thread, screen_thread]);
-    assert_snapshot!(format!("{:?}", *received_pty_instructions.lock().unwrap()));
-}
-
-#[test]
-pub fn send_cli_edit_action_with_default_parameters() {
-    let size = Size {
-        cols: 121,
-        rows: 20,
+    send_cli_action_to_server(&session_metadata, rename_session_action, client_id);
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    let plugins_saw_new_session_name = || {
+        received_plugin_instructions
+            .lock()
+            .unwrap()
+            .iter()
+            .any(|instruction| match instruction {
+                PluginInstruction::Update(updates) => updates.iter().any(|u| match u {
+                    (_, _, Event::ModeUpdate(mode_info)) => {
+                        mode_info.session_name.as_deref() == Some("my-project")
+                    },
+                    _ => false,
+                }),
+                _ => false,
+            })
     };
-    let client_id = 10; // fake client id should not appear in the screen's state
-    let mut mock_screen = MockScreen::new(size);
-    let pty_receiver = mock_screen.pty_receiver.take().unwrap();
-    let session_metadata = mock_screen.clone_session_metadata();
-    let mut initial_layout = TiledPaneLayout::default();
-    initial_layout.children_split_direction = SplitDirection::Vertical;
-    initial_layout.children = vec![TiledPaneLayout::default(), TiledPaneLayout::default()];
-    let screen_thread = mock_screen.run(Some(initial_layout), vec![]);
-    let received_pty_instructions = Arc::new(Mutex::new(vec![]));
-    let pty_thread = log_actions_in_thread!(
-        received_pty_instructions,
-        PtyInstruction::Exit,
-        pty_receiver
+    assert!(
+        !plugins_saw_new_session_name(),
+        "the session is not renamed before the server renamed it"
+    );
+    let renamed_in_registry =
+        received_server_instructions
+            .lock()
+            .unwrap()
+            .iter()
+            .any(|instruction| match instruction {
+                ServerInstruction::RenameSession(new_session_name, _client_id) => {
+                    new_session_name == "my-project"
+                },
+                _ => false,
+            });
+    assert!(
+        renamed_in_registry,
+        "server was asked to rename the session"
+    );
+    let _ = mock_screen
+        .to_screen
+        .send(ScreenInstruction::SessionRenamed("my-project".to_owned()));
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    mock_screen.teardown(vec![plugin_thread, server_thread, screen_thread]);
+    assert!(
+        plugins_saw_new_session_name(),
+        "plugins were told about the new session name"
     );
-    let cli_edit_action = CliAction::Edit {
-        file: PathBuf::from("/file/to/edit"),
-        direction: None,
-        line_number: None,
-        floating: false,
-        in_place: false,
-        cwd: None,
-    };
-    send_cli_action_to_server(&session_metadata, cli_edit_action, client_id);
-    std::thread::sleep(std::time::Duration::from_millis(100)); // give time for actions to be
-    mock_screen.teardown(vec![pty_thread, screen_thread]);
-    assert_snapshot!(format!("{:?}", *received_pty_instructions.lock().unwrap()));
 }
 
 #[test]
-pub fn send_cli_edit_action_with_line_number() {
+pub fn duplicate_command_pane() {
     let size = Size {
         cols: 121,
         rows: 20,
     };
-    let client_id = 10; // fake client id should not appear in the screen's state
     let mut mock_screen = MockScreen::new(size);
     let pty_receiver = mock_screen.pty_receiver.take().unwrap();
-    let session_metadata = mock_screen.clone_session_metadata();
+    let run_command = RunCommand {
+        command: PathBuf::from("tail"),
+        args: vec!["-f".to_owned(), "server.log".to_owned()],
+        cwd: Some(PathBuf::from("/tmp/project")),
+        hold_on_start: true,
+        ..Default::default()
+    };
+    let mut command_pane_layout = TiledPaneLayout::default();
+    command_pane_layout.run = Some(Run::Command(run_command.clone()));
     let mut initial_layout = TiledPaneLayout::default();
     initial_layout.children_split_direction = SplitDirection::Vertical;
-    initial_layout.children = vec![TiledPaneLayout::default(), TiledPaneLayout::default()];
+    initial_layout.children = vec![TiledPaneLayout::default(), command_pane_layout];
     let screen_thread = mock_screen.run(Some(initial_layout), vec![]);
     let received_pty_instructions = Arc::new(Mutex::new(vec![]));
     let pty_thread = log_actions_in_thread!(
@@ -2028,30 +6287,48 @@ pub fn send_cli_edit_action_with_line_number() {
         PtyInstruction::Exit,
         pty_receiver
     );
-    let cli_edit_action = CliAction::Edit {
-        file: PathBuf::from("/file/to/edit"),
-        direction: None,
-        line_number: Some(100),
-        floating: false,
-        in_place: false,
-        cwd: None,
-    };
-    send_cli_action_to_server(&session_metadata, cli_edit_action, client_id);
-    std::thread::sleep(std::time::Duration::from_millis(100)); // give time for actions to be
+    let command_pane_id = PaneId::Terminal(1);
+    let _ = mock_screen.to_screen.send(ScreenInstruction::DuplicatePane(
+        Some(command_pane_id),
+        mock_screen.main_client_id,
+    ));
+    std::thread::sleep(std::time::Duration::from_millis(100));
     mock_screen.teardown(vec![pty_thread, screen_thread]);
-    assert_snapshot!(format!("{:?}", *received_pty_instructions.lock().unwrap()));
+    let spawned_terminal_action = received_pty_instructions
+        .lock()
+        .unwrap()
+        .iter()
+        .find_map(|instruction| match instruction {
+            PtyInstruction::SpawnTerminalVertically(terminal_action, _, _)
+            | PtyInstruction::SpawnTerminalHorizontally(terminal_action, _, _) => {
+                Some(terminal_action.clone())
+            },
+            _ => None,
+        })
+        .expect("duplicate pane was spawned next to the command pane");
+    let expected_run_command = RunCommand {
+        hold_on_start: false,
+        ..run_command
+    };
+    match spawned_terminal_action {
+        Some(TerminalAction::RunCommand(spawned_run_command)) => assert_eq!(
+            spawned_run_command, expected_run_command,
+            "duplicate pane runs the same command in the same cwd, without waiting to be started"
+        ),
+        _ => panic!("duplicate pane was not spawned with the command of the original pane"),
+    }
 }
 
 #[test]
-pub fn send_cli_edit_action_with_split_direction() {
+pub fn send_cli_duplicate_pane_action() {
     let size = Size {
         cols: 121,
         rows: 20,
     };
     let client_id = 10; // fake client id should not appear in the screen's state
     let mut mock_screen = MockScreen::new(size);
-    let pty_receiver = mock_screen.pty_receiver.take().unwrap();
     let session_metadata = mock_screen.clone_session_metadata();
+    let pty_receiver = mock_screen.pty_receiver.take().unwrap();
     let mut initial_layout = TiledPaneLayout::default();
     initial_layout.children_split_direction = SplitDirection::Vertical;
     initial_layout.children = vec![TiledPaneLayout::default(), TiledPaneLayout::default()];
@@ -2062,22 +6339,27 @@ pub fn send_cli_edit_action_with_split_direction() {
         PtyInstruction::Exit,
         pty_receiver
     );
-    let cli_edit_action = CliAction::Edit {
-        file: PathBuf::from("/file/to/edit"),
-        direction: Some(Direction::Down),
-        line_number: None,
-        floating: false,
-        in_place: false,
-        cwd: None,
-    };
-    send_cli_action_to_server(&session_metadata, cli_edit_action, client_id);
-    std::thread::sleep(std::time::Duration::from_millis(100)); // give time for actions to be
+    send_cli_action_to_server(&session_metadata, CliAction::DuplicatePane, client_id);
+    std::thread::sleep(std::time::Duration::from_millis(100));
     mock_screen.teardown(vec![pty_thread, screen_thread]);
-    assert_snapshot!(format!("{:?}", *received_pty_instructions.lock().unwrap()));
+    let spawned_for_client = received_pty_instructions.lock().unwrap().iter().find_map(
+        |instruction| match instruction {
+            PtyInstruction::SpawnTerminalVertically(_, _, spawned_for_client)
+            | PtyInstruction::SpawnTerminalHorizontally(_, _, spawned_for_client) => {
+                Some(*spawned_for_client)
+            },
+            _ => None,
+        },
+    );
+    assert_eq!(
+        spawned_for_client,
+        Some(mock_screen.main_client_id),
+        "focused pane of the first connected client duplicated"
+    );
 }
 
 #[test]
-pub fn send_cli_switch_mode_action() {
+pub fn send_cli_merge_panes_action() {
     let size = Size {
         cols: 121,
         rows: 20,
@@ -2085,189 +6367,174 @@ pub fn send_cli_switch_mode_action() {
     let client_id = 10; // fake client id should not appear in the screen's state
     let mut mock_screen = MockScreen::new(size);
     let session_metadata = mock_screen.clone_session_metadata();
+    let pty_receiver = mock_screen.pty_receiver.take().unwrap();
+    let plugin_receiver = mock_screen.plugin_receiver.take().unwrap();
     let mut initial_layout = TiledPaneLayout::default();
     initial_layout.children_split_direction = SplitDirection::Vertical;
     initial_layout.children = vec![TiledPaneLayout::default(), TiledPaneLayout::default()];
     let screen_thread = mock_screen.run(Some(initial_layout), vec![]);
-    let cli_switch_mode = CliAction::SwitchMode {
-        input_mode: InputMode::Locked,
-    };
-    send_cli_action_to_server(&session_metadata, cli_switch_mode, client_id);
-    std::thread::sleep(std::time::Duration::from_millis(100)); // give time for actions to be
-    mock_screen.teardown(vec![screen_thread]);
-    assert_snapshot!(format!(
-        "{:?}",
-        *mock_screen
-            .os_input
-            .server_to_client_messages
-            .lock()
-            .unwrap()
-    ));
-}
-
-#[test]
-pub fn send_cli_toggle_pane_embed_or_float() {
-    let size = Size { cols: 80, rows: 10 };
-    let client_id = 10; // fake client id should not appear in the screen's state
-    let mut initial_layout = TiledPaneLayout::default();
-    initial_layout.children_split_direction = SplitDirection::Vertical;
-    initial_layout.children = vec![TiledPaneLayout::default(), TiledPaneLayout::default()];
-    let mut mock_screen = MockScreen::new(size);
-    let session_metadata = mock_screen.clone_session_metadata();
-    let screen_thread = mock_screen.run(Some(initial_layout), vec![]);
-    let received_server_instructions = Arc::new(Mutex::new(vec![]));
-    let server_receiver = mock_screen.server_receiver.take().unwrap();
-    let server_instruction = log_actions_in_thread!(
-        received_server_instructions,
-        ServerInstruction::KillSession,
-        server_receiver
+    let received_pty_instructions = Arc::new(Mutex::new(vec![]));
+    let pty_thread = log_actions_in_thread!(
+        received_pty_instructions,
+        PtyInstruction::Exit,
+        pty_receiver
     );
-    let toggle_pane_embed_or_floating = CliAction::TogglePaneEmbedOrFloating;
-    // first time to float
-    send_cli_action_to_server(
-        &session_metadata,
-        toggle_pane_embed_or_floating.clone(),
-        client_id,
+    let received_plugin_instructions = Arc::new(Mutex::new(vec![]));
+    let plugin_thread = log_actions_in_thread!(
+        received_plugin_instructions,
+        PluginInstruction::Exit,
+        plugin_receiver
     );
-    std::thread::sleep(std::time::Duration::from_millis(100));
-    // second time to embed
     send_cli_action_to_server(
         &session_metadata,
-        toggle_pane_embed_or_floating.clone(),
+        CliAction::MergePanes {
+            keep_pane_id: PaneId::Terminal(0),
+            absorb_pane_id: PaneId::Terminal(1),
+        },
         client_id,
     );
     std::thread::sleep(std::time::Duration::from_millis(100));
-    mock_screen.teardown(vec![server_instruction, screen_thread]);
-    let snapshots = take_snapshots_and_cursor_coordinates_from_render_events(
-        received_server_instructions.lock().unwrap().iter(),
-        size,
-    );
-    let snapshot_count = snapshots.len();
-    for (_cursor_coordinates, snapshot) in snapshots {
-        assert_snapshot!(format!("{}", snapshot));
-    }
-    assert_snapshot!(format!("{}", snapshot_count));
-}
-
-#[test]
-pub fn send_cli_toggle_floating_panes() {
-    let size = Size { cols: 80, rows: 10 };
-    let client_id = 10; // fake client id should not appear in the screen's state
-    let mut initial_layout = TiledPaneLayout::default();
-    initial_layout.children_split_direction = SplitDirection::Vertical;
-    initial_layout.children = vec![TiledPaneLayout::default(), TiledPaneLayout::default()];
-    let mut mock_screen = MockScreen::new(size);
-    let session_metadata = mock_screen.clone_session_metadata();
-    let screen_thread = mock_screen.run(Some(initial_layout), vec![]);
-    let received_server_instructions = Arc::new(Mutex::new(vec![]));
-    let server_receiver = mock_screen.server_receiver.take().unwrap();
-    let server_instruction = log_actions_in_thread!(
-        received_server_instructions,
-        ServerInstruction::KillSession,
-        server_receiver
-    );
-    let toggle_pane_embed_or_floating = CliAction::TogglePaneEmbedOrFloating;
-    let toggle_floating_panes = CliAction::ToggleFloatingPanes;
-    // float the focused pane
-    send_cli_action_to_server(&session_metadata, toggle_pane_embed_or_floating, client_id);
-    std::thread::sleep(std::time::Duration::from_millis(100));
-    // toggle floating panes (will hide the floated pane from the previous action)
-    send_cli_action_to_server(&session_metadata, toggle_floating_panes.clone(), client_id);
-    std::thread::sleep(std::time::Duration::from_millis(100));
-    // toggle floating panes (will show the floated pane)
-    send_cli_action_to_server(&session_metadata, toggle_floating_panes.clone(), client_id);
-    std::thread::sleep(std::time::Duration::from_millis(100));
-    mock_screen.teardown(vec![server_instruction, screen_thread]);
-    let snapshots = take_snapshots_and_cursor_coordinates_from_render_events(
-        received_server_instructions.lock().unwrap().iter(),
-        size,
+    mock_screen.teardown(vec![pty_thread, plugin_thread, screen_thread]);
+    let absorbed_pane_closed = received_pty_instructions
+        .lock()
+        .unwrap()
+        .iter()
+        .any(|instruction| matches!(instruction, PtyInstruction::ClosePane(PaneId::Terminal(1))));
+    assert!(absorbed_pane_closed, "absorbed pane closed");
+    let plugins_told_pane_closed =
+        received_plugin_instructions
+            .lock()
+            .unwrap()
+            .iter()
+            .any(|instruction| match instruction {
+                PluginInstruction::Update(updates) => updates.iter().any(|(_, _, event)| {
+                    matches!(event, Event::PaneClosed(PaneId::Terminal(1), None))
+                }),
+                _ => false,
+            });
+    assert!(
+        plugins_told_pane_closed,
+        "plugins told that the absorbed pane closed"
     );
-    let snapshot_count = snapshots.len();
-    for (_cursor_coordinates, snapshot) in snapshots {
-        assert_snapshot!(format!("{}", snapshot));
-    }
-    assert_snapshot!(format!("{}", snapshot_count));
 }
 
 #[test]
-pub fn send_cli_close_pane_action() {
-    let size = Size { cols: 80, rows: 10 };
-    let client_id = 10; // fake client id should not appear in the screen's state
-    let mut initial_layout = TiledPaneLayout::default();
-    initial_layout.children_split_direction = SplitDirection::Vertical;
-    initial_layout.children = vec![TiledPaneLayout::default(), TiledPaneLayout::default()];
+pub fn search_in_pane_highlights_matches() {
+    let size = Size { cols: 8, rows: 5 };
+    let client_id = 1;
     let mut mock_screen = MockScreen::new(size);
-    let session_metadata = mock_screen.clone_session_metadata();
-    let screen_thread = mock_screen.run(Some(initial_layout), vec![]);
+    mock_screen.config_options.pane_frames = Some(false);
+    let screen_thread = mock_screen.run(None, vec![]);
     let received_server_instructions = Arc::new(Mutex::new(vec![]));
     let server_receiver = mock_screen.server_receiver.take().unwrap();
-    let server_instruction = log_actions_in_thread!(
+    let server_thread = log_actions_in_thread!(
         received_server_instructions,
         ServerInstruction::KillSession,
         server_receiver
     );
-    let close_pane_action = CliAction::ClosePane;
-    send_cli_action_to_server(&session_metadata, close_pane_action, client_id);
-    std::thread::sleep(std::time::Duration::from_millis(100));
-    mock_screen.teardown(vec![server_instruction, screen_thread]);
-    let snapshots = take_snapshots_and_cursor_coordinates_from_render_events(
-        received_server_instructions.lock().unwrap().iter(),
-        size,
-    );
-    let snapshot_count = snapshots.len();
-    for (_cursor_coordinates, snapshot) in snapshots {
-        assert_snapshot!(format!("{}", snapshot));
-    }
-    assert_snapshot!(format!("{}", snapshot_count));
-}
+    // the second match is wrapped to the next line: "foo ab f" / "oo foo" / "FOO"
+    let _ = mock_screen.to_screen.send(ScreenInstruction::PtyBytes(
+        0,
+        "foo ab foo foo\r\nFOO".as_bytes().to_vec(),
+    ));
+    let (response_sender, response_receiver) = channels::bounded(1);
+    let _ = mock_screen.to_screen.send(ScreenInstruction::SearchInPane {
+        pane_id: Some(PaneId::Terminal(0)),
+        query: "foo".to_owned(),
+        case_sensitive: true,
+        client_id,
+        response: Some(response_sender),
+    });
+    let search_matches = response_receiver
+        .recv_timeout(std::time::Duration::from_secs(1))
+        .expect("no response to search request")
+        .expect("pane can be searched");
+    let (response_sender, response_receiver) = channels::bounded(1);
+    let _ = mock_screen
+        .to_screen
+        .send(ScreenInstruction::CaptureRendered(
+            Some(PaneId::Terminal(0)),
+            client_id,
+            Some(response_sender),
+        ));
+    let rendered_grid = response_receiver
+        .recv_timeout(std::time::Duration::from_secs(1))
+        .expect("no response to capture request")
+        .expect("pane is rendered");
+    let _ = mock_screen.to_screen.send(ScreenInstruction::ClearSearch(
+        Some(PaneId::Terminal(0)),
+        client_id,
+    ));
+    let (response_sender, response_receiver) = channels::bounded(1);
+    let _ = mock_screen
+        .to_screen
+        .send(ScreenInstruction::CaptureRendered(
+            Some(PaneId::Terminal(0)),
+            client_id,
+            Some(response_sender),
+        ));
+    let rendered_grid_after_clearing = response_receiver
+        .recv_timeout(std::time::Duration::from_secs(1))
+        .expect("no response to capture request")
+        .expect("pane is rendered");
+    mock_screen.teardown(vec![server_thread, screen_thread]);
 
-#[test]
-pub fn send_cli_new_tab_action_default_params() {
-    let size = Size { cols: 80, rows: 10 };
-    let client_id = 10; // fake client id should not appear in the screen's state
-    let mut initial_layout = TiledPaneLayout::default();
-    initial_layout.children_split_direction = SplitDirection::Vertical;
-    initial_layout.children = vec![TiledPaneLayout::default(), TiledPaneLayout::default()];
-    let mut mock_screen = MockScreen::new(size);
-    let session_metadata = mock_screen.clone_session_metadata();
-    let screen_thread = mock_screen.run(Some(initial_layout), vec![]);
-    let received_plugin_instructions = Arc::new(Mutex::new(vec![]));
-    let plugin_receiver = mock_screen.plugin_receiver.take().unwrap();
-    let plugin_thread = log_actions_in_thread!(
-        received_plugin_instructions,
-        PluginInstruction::Exit,
-        plugin_receiver
-    );
-    let new_tab_action = CliAction::NewTab {
-        name: None,
-        layout: None,
-        layout_dir: None,
-        cwd: None,
+    assert_eq!(search_matches.count, 3, "found all case sensitive matches");
+    assert_eq!(
+        search_matches.positions,
+        vec![
+            (Position::new(0, 0), Position::new(0, 3)),
+            (Position::new(0, 7), Position::new(1, 2)),
+            (Position::new(1, 3), Position::new(1, 6)),
+        ],
+        "match positions, including the one on the wrapped line"
+    );
+    let highlighted_cells = |rendered_grid: &RenderedGrid| -> Vec<(usize, usize)> {
+        let mut highlighted_cells = vec![];
+        for (y, line) in rendered_grid.lines.iter().enumerate() {
+            for (x, cell) in line.iter().enumerate() {
+                if !cell.styles.is_empty() {
+                    highlighted_cells.push((y, x));
+                }
+            }
+        }
+        highlighted_cells
     };
-    send_cli_action_to_server(&session_metadata, new_tab_action, client_id);
-    std::thread::sleep(std::time::Duration::from_millis(100));
-    mock_screen.teardown(vec![plugin_thread, screen_thread]);
-    let received_plugin_instructions = received_plugin_instructions.lock().unwrap();
-    let new_tab_action =
-        received_plugin_instructions
-            .iter()
-            .find(|instruction| match instruction {
-                PluginInstruction::NewTab(..) => true,
-                _ => false,
-            });
-    assert_snapshot!(format!("{:#?}", new_tab_action));
+    assert_eq!(
+        highlighted_cells(&rendered_grid),
+        vec![
+            (0, 0),
+            (0, 1),
+            (0, 2),
+            (0, 7),
+            (1, 0),
+            (1, 1),
+            (1, 3),
+            (1, 4),
+            (1, 5)
+        ],
+        "only the matched cells are highlighted"
+    );
+    assert!(
+        highlighted_cells(&rendered_grid_after_clearing).is_empty(),
+        "highlights removed once the search is cleared"
+    );
 }
 
 #[test]
-pub fn send_cli_new_tab_action_with_name_and_layout() {
-    let size = Size { cols: 80, rows: 10 };
-    let client_id = 10; // fake client id should not appear in the screen's state
-    let mut initial_layout = TiledPaneLayout::default();
-    initial_layout.children_split_direction = SplitDirection::Vertical;
-    initial_layout.children = vec![TiledPaneLayout::default(), TiledPaneLayout::default()];
+pub fn screen_reports_session_activity_to_plugins() {
+    let size = Size { cols: 80, rows: 20 };
+    let client_id = 1;
+    let plugin_id = 0;
     let mut mock_screen = MockScreen::new(size);
-    let session_metadata = mock_screen.clone_session_metadata();
-    let screen_thread = mock_screen.run(Some(initial_layout), vec![]);
+    let screen_thread = mock_screen.run(None, vec![]);
+    let received_server_instructions = Arc::new(Mutex::new(vec![]));
+    let server_receiver = mock_screen.server_receiver.take().unwrap();
+    let server_thread = log_actions_in_thread!(
+        received_server_instructions,
+        ServerInstruction::KillSession,
+        server_receiver
+    );
     let received_plugin_instructions = Arc::new(Mutex::new(vec![]));
     let plugin_receiver = mock_screen.plugin_receiver.take().unwrap();
     let plugin_thread = log_actions_in_thread!(
@@ -2275,49 +6542,118 @@ pub fn send_cli_new_tab_action_with_name_and_layout() {
         PluginInstruction::Exit,
         plugin_receiver
     );
-    let new_tab_action = CliAction::NewTab {
-        name: Some("my-awesome-tab-name".into()),
-        layout: Some(PathBuf::from(format!(
-            "{}/src/unit/fixtures/layout-with-three-panes.kdl",
-            env!("CARGO_MANIFEST_DIR")
-        ))),
-        layout_dir: None,
-        cwd: None,
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    let run_command = RunCommand {
+        command: PathBuf::from("htop"),
+        ..Default::default()
     };
-    send_cli_action_to_server(&session_metadata, new_tab_action, client_id);
+    let _ = mock_screen.to_screen.send(ScreenInstruction::NewPane(
+        PaneId::Terminal(1),
+        None,
+        None,
+        None,
+        Some(Run::Command(run_command)),
+        ClientTabIndexOrPaneId::ClientId(client_id),
+    ));
+    let _ = mock_screen
+        .to_screen
+        .send(ScreenInstruction::ClosePane(PaneId::Terminal(1), None));
+    let _ = mock_screen.to_screen.send(ScreenInstruction::NewTab(
+        None,
+        None,
+        Some(TiledPaneLayout::default()),
+        vec![],           // floating panes layout
+        None,             // tab name
+        (vec![], vec![]), // swap layouts
+        client_id,
+    ));
+    let _ = mock_screen.to_screen.send(ScreenInstruction::ApplyLayout(
+        TiledPaneLayout::default(),
+        vec![], // floating panes layout
+        vec![(2, None)],
+        vec![], // floating pane ids
+        HashMap::new(),
+        1,
+        client_id,
+    ));
+    let _ = mock_screen
+        .to_screen
+        .send(ScreenInstruction::SwitchTabPrev(client_id));
     std::thread::sleep(std::time::Duration::from_millis(100));
-    mock_screen.teardown(vec![plugin_thread, screen_thread]);
-    let new_tab_instruction = received_plugin_instructions
-        .lock()
-        .unwrap()
+    let _ = mock_screen
+        .to_screen
+        .send(ScreenInstruction::RequestSessionActivity(
+            plugin_id, client_id,
+        ));
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    mock_screen.teardown(vec![plugin_thread, server_thread, screen_thread]);
+    let session_activity_updates: Vec<(Option<u32>, Vec<SessionActivity>)> =
+        received_plugin_instructions
+            .lock()
+            .unwrap()
+            .iter()
+            .filter_map(|instruction| match instruction {
+                PluginInstruction::Update(updates) => Some(updates.clone()),
+                _ => None,
+            })
+            .flatten()
+            .filter_map(|update| match update {
+                (plugin_id, _client_id, Event::SessionActivity(session_activity)) => {
+                    Some((plugin_id, session_activity))
+                },
+                _ => None,
+            })
+            .collect();
+    let reported_entries: Vec<SessionActivity> = session_activity_updates
         .iter()
-        .rev()
-        .find(|i| {
-            if let PluginInstruction::NewTab(..) = i {
-                return true;
+        .filter(|(plugin_id, _)| plugin_id.is_none())
+        .flat_map(|(_, session_activity)| session_activity.clone())
+        .collect();
+    let reported_kinds: Vec<SessionActivityKind> = reported_entries
+        .iter()
+        .map(|entry| entry.kind.clone())
+        .collect();
+    assert_eq!(
+        reported_kinds,
+        vec![
+            SessionActivityKind::PaneOpened(PaneId::Terminal(0)),
+            SessionActivityKind::PaneOpened(PaneId::Terminal(1)),
+            SessionActivityKind::CommandSpawned(PaneId::Terminal(1), "htop".to_owned()),
+            SessionActivityKind::PaneClosed(PaneId::Terminal(1)),
+            SessionActivityKind::PaneOpened(PaneId::Terminal(2)),
+            SessionActivityKind::TabChanged(client_id, 1),
+            SessionActivityKind::TabChanged(client_id, 0),
+        ],
+        "new entries are reported to plugins as they happen, in order"
+    );
+    assert!(
+        reported_entries
+            .windows(2)
+            .all(|entries| entries[0].timestamp <= entries[1].timestamp),
+        "entries are timestamped in order"
+    );
+    let requested_entries = session_activity_updates
+        .iter()
+        .find_map(|(update_plugin_id, session_activity)| {
+            if *update_plugin_id == Some(plugin_id) {
+                Some(session_activity.clone())
             } else {
-                return false;
+                None
             }
         })
-        .unwrap()
-        .clone();
-    assert_snapshot!(format!("{:#?}", new_tab_instruction));
+        .expect("the log was sent to the plugin that requested it");
+    assert_eq!(
+        requested_entries, reported_entries,
+        "the whole log is sent when requested"
+    );
 }
 
 #[test]
-pub fn send_cli_next_tab_action() {
-    let size = Size { cols: 80, rows: 10 };
-    let client_id = 10; // fake client id should not appear in the screen's state
-    let mut initial_layout = TiledPaneLayout::default();
-    initial_layout.children_split_direction = SplitDirection::Vertical;
-    initial_layout.children = vec![TiledPaneLayout::default(), TiledPaneLayout::default()];
-    let mut second_tab_layout = TiledPaneLayout::default();
-    second_tab_layout.children_split_direction = SplitDirection::Horizontal;
-    second_tab_layout.children = vec![TiledPaneLayout::default(), TiledPaneLayout::default()];
+pub fn screen_buffers_pane_output_for_subscribed_plugins() {
+    let size = Size { cols: 80, rows: 20 };
+    let plugin_id = 1;
     let mut mock_screen = MockScreen::new(size);
-    mock_screen.new_tab(second_tab_layout);
-    let session_metadata = mock_screen.clone_session_metadata();
-    let screen_thread = mock_screen.run(Some(initial_layout), vec![]);
+    let screen_thread = mock_screen.run(None, vec![]);
     let received_server_instructions = Arc::new(Mutex::new(vec![]));
     let server_receiver = mock_screen.server_receiver.take().unwrap();
     let server_thread = log_actions_in_thread!(
@@ -2325,35 +6661,70 @@ pub fn send_cli_next_tab_action() {
         ServerInstruction::KillSession,
         server_receiver
     );
-    let goto_next_tab = CliAction::GoToNextTab;
-    send_cli_action_to_server(&session_metadata, goto_next_tab, client_id);
+    let received_plugin_instructions = Arc::new(Mutex::new(vec![]));
+    let plugin_receiver = mock_screen.plugin_receiver.take().unwrap();
+    let plugin_thread = log_actions_in_thread!(
+        received_plugin_instructions,
+        PluginInstruction::Exit,
+        plugin_receiver
+    );
     std::thread::sleep(std::time::Duration::from_millis(100));
-    mock_screen.teardown(vec![server_thread, screen_thread]);
-    let snapshots = take_snapshots_and_cursor_coordinates_from_render_events(
-        received_server_instructions.lock().unwrap().iter(),
-        size,
+    let _ = mock_screen
+        .to_screen
+        .send(ScreenInstruction::SubscribePaneOutput(
+            PaneId::Terminal(0),
+            plugin_id,
+        ));
+    let _ = mock_screen
+        .to_screen
+        .send(ScreenInstruction::PtyBytes(0, b"foo".to_vec()));
+    let _ = mock_screen
+        .to_screen
+        .send(ScreenInstruction::PtyBytes(0, b"bar\r\n".to_vec()));
+    let _ = mock_screen
+        .to_screen
+        .send(ScreenInstruction::UnsubscribePaneOutput(
+            PaneId::Terminal(0),
+            plugin_id,
+        ));
+    let _ = mock_screen
+        .to_screen
+        .send(ScreenInstruction::PtyBytes(0, b"baz".to_vec()));
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    mock_screen.teardown(vec![plugin_thread, server_thread, screen_thread]);
+    let queued_pane_outputs: Vec<(u32, PaneId, PaneOutputBuffer)> = received_plugin_instructions
+        .lock()
+        .unwrap()
+        .iter()
+        .filter_map(|instruction| match instruction {
+            PluginInstruction::PaneOutputQueued(plugin_id, pane_id, pane_output) => {
+                Some((*plugin_id, *pane_id, pane_output.clone()))
+            },
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        queued_pane_outputs.len(),
+        1,
+        "plugin thread told once about output waiting in the buffer"
+    );
+    let (queued_plugin_id, queued_pane_id, pane_output) = &queued_pane_outputs[0];
+    assert_eq!(*queued_plugin_id, plugin_id);
+    assert_eq!(*queued_pane_id, PaneId::Terminal(0));
+    assert_eq!(
+        pane_output.take(),
+        b"foobar\r\n".to_vec(),
+        "output buffered in order until the plugin unsubscribed"
     );
-    let snapshot_count = snapshots.len();
-    for (_cursor_coordinates, snapshot) in snapshots {
-        assert_snapshot!(format!("{}", snapshot));
-    }
-    assert_snapshot!(format!("{}", snapshot_count));
 }
 
 #[test]
-pub fn send_cli_previous_tab_action() {
-    let size = Size { cols: 80, rows: 10 };
-    let client_id = 10; // fake client id should not appear in the screen's state
-    let mut initial_layout = TiledPaneLayout::default();
-    initial_layout.children_split_direction = SplitDirection::Vertical;
-    initial_layout.children = vec![TiledPaneLayout::default(), TiledPaneLayout::default()];
-    let mut second_tab_layout = TiledPaneLayout::default();
-    second_tab_layout.children_split_direction = SplitDirection::Horizontal;
-    second_tab_layout.children = vec![TiledPaneLayout::default(), TiledPaneLayout::default()];
+pub fn pane_output_subscriptions_of_a_plugin_are_cleared() {
+    let size = Size { cols: 80, rows: 20 };
+    let unloaded_plugin_id = 1;
+    let other_plugin_id = 2;
     let mut mock_screen = MockScreen::new(size);
-    mock_screen.new_tab(second_tab_layout);
-    let session_metadata = mock_screen.clone_session_metadata();
-    let screen_thread = mock_screen.run(Some(initial_layout), vec![]);
+    let screen_thread = mock_screen.run(None, vec![]);
     let received_server_instructions = Arc::new(Mutex::new(vec![]));
     let server_receiver = mock_screen.server_receiver.take().unwrap();
     let server_thread = log_actions_in_thread!(
@@ -2361,35 +6732,55 @@ pub fn send_cli_previous_tab_action() {
         ServerInstruction::KillSession,
         server_receiver
     );
-    let goto_previous_tab = CliAction::GoToPreviousTab;
-    send_cli_action_to_server(&session_metadata, goto_previous_tab, client_id);
-    std::thread::sleep(std::time::Duration::from_millis(100));
-    mock_screen.teardown(vec![server_thread, screen_thread]);
-    let snapshots = take_snapshots_and_cursor_coordinates_from_render_events(
-        received_server_instructions.lock().unwrap().iter(),
-        size,
+    let received_plugin_instructions = Arc::new(Mutex::new(vec![]));
+    let plugin_receiver = mock_screen.plugin_receiver.take().unwrap();
+    let plugin_thread = log_actions_in_thread!(
+        received_plugin_instructions,
+        PluginInstruction::Exit,
+        plugin_receiver
     );
-    let snapshot_count = snapshots.len();
-    for (_cursor_coordinates, snapshot) in snapshots {
-        assert_snapshot!(format!("{}", snapshot));
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    for plugin_id in [unloaded_plugin_id, other_plugin_id] {
+        let _ = mock_screen
+            .to_screen
+            .send(ScreenInstruction::SubscribePaneOutput(
+                PaneId::Terminal(0),
+                plugin_id,
+            ));
     }
-    assert_snapshot!(format!("{}", snapshot_count));
+    // this is what the plugin thread sends when the plugin is unloaded
+    let _ = mock_screen
+        .to_screen
+        .send(ScreenInstruction::ClearPluginPaneOutputSubscriptions(
+            unloaded_plugin_id,
+        ));
+    let _ = mock_screen
+        .to_screen
+        .send(ScreenInstruction::PtyBytes(0, b"foo".to_vec()));
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    mock_screen.teardown(vec![plugin_thread, server_thread, screen_thread]);
+    let plugins_with_queued_pane_output: Vec<u32> = received_plugin_instructions
+        .lock()
+        .unwrap()
+        .iter()
+        .filter_map(|instruction| match instruction {
+            PluginInstruction::PaneOutputQueued(plugin_id, ..) => Some(*plugin_id),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        plugins_with_queued_pane_output,
+        vec![other_plugin_id],
+        "output is only buffered for the plugin that is still subscribed"
+    );
 }
 
 #[test]
-pub fn send_cli_goto_tab_action() {
-    let size = Size { cols: 80, rows: 10 };
-    let client_id = 10; // fake client id should not appear in the screen's state
-    let mut initial_layout = TiledPaneLayout::default();
-    initial_layout.children_split_direction = SplitDirection::Vertical;
-    initial_layout.children = vec![TiledPaneLayout::default(), TiledPaneLayout::default()];
-    let mut second_tab_layout = TiledPaneLayout::default();
-    second_tab_layout.children_split_direction = SplitDirection::Horizontal;
-    second_tab_layout.children = vec![TiledPaneLayout::default(), TiledPaneLayout::default()];
+pub fn pane_output_subscriptions_are_cleared_when_the_pane_closes() {
+    let size = Size { cols: 80, rows: 20 };
+    let plugin_id = 1;
     let mut mock_screen = MockScreen::new(size);
-    mock_screen.new_tab(second_tab_layout);
-    let session_metadata = mock_screen.clone_session_metadata();
-    let screen_thread = mock_screen.run(Some(initial_layout), vec![]);
+    let screen_thread = mock_screen.run(None, vec![]);
     let received_server_instructions = Arc::new(Mutex::new(vec![]));
     let server_receiver = mock_screen.server_receiver.take().unwrap();
     let server_thread = log_actions_in_thread!(
@@ -2397,34 +6788,58 @@ pub fn send_cli_goto_tab_action() {
         ServerInstruction::KillSession,
         server_receiver
     );
-    let goto_tab = CliAction::GoToTab { index: 1 };
-    send_cli_action_to_server(&session_metadata, goto_tab, client_id);
+    let received_plugin_instructions = Arc::new(Mutex::new(vec![]));
+    let plugin_receiver = mock_screen.plugin_receiver.take().unwrap();
+    let plugin_thread = log_actions_in_thread!(
+        received_plugin_instructions,
+        PluginInstruction::Exit,
+        plugin_receiver
+    );
     std::thread::sleep(std::time::Duration::from_millis(100));
-    mock_screen.teardown(vec![server_thread, screen_thread]);
-    let snapshots = take_snapshots_and_cursor_coordinates_from_render_events(
-        received_server_instructions.lock().unwrap().iter(),
-        size,
+    let _ = mock_screen
+        .to_screen
+        .send(ScreenInstruction::SubscribePaneOutput(
+            PaneId::Terminal(0),
+            plugin_id,
+        ));
+    // this is what the pty thread sends when the pane is closed, after which its id can be
+    // given to a new pane
+    let _ = mock_screen
+        .to_screen
+        .send(ScreenInstruction::ClearPaneOutputSubscriptions(
+            PaneId::Terminal(0),
+        ));
+    let _ = mock_screen
+        .to_screen
+        .send(ScreenInstruction::PtyBytes(0, b"foo".to_vec()));
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    mock_screen.teardown(vec![plugin_thread, server_thread, screen_thread]);
+    let pane_output_was_queued = received_plugin_instructions
+        .lock()
+        .unwrap()
+        .iter()
+        .any(|instruction| matches!(instruction, PluginInstruction::PaneOutputQueued(..)));
+    assert!(
+        !pane_output_was_queued,
+        "output of a pane with the id of a closed one is not sent to its subscribers"
     );
-    let snapshot_count = snapshots.len();
-    for (_cursor_coordinates, snapshot) in snapshots {
-        assert_snapshot!(format!("{}", snapshot));
-    }
-    assert_snapshot!(format!("{}", snapshot_count));
 }
 
 #[test]
-pub fn send_cli_close_tab_action() {
-    let size = Size { cols: 80, rows: 10 };
-    let client_id = 10; // fake client id should not appear in the screen's state
+pub fn plugin_can_name_its_pane_and_tab() {
+    let size = Size { cols: 80, rows: 20 };
+    let client_id = 1;
+    let plugin_id = 1;
     let mut initial_layout = TiledPaneLayout::default();
+    let mut plugin_pane = TiledPaneLayout::default();
+    plugin_pane.run = Some(Run::Plugin(RunPlugin {
+        _allow_exec_host_cmd: false,
+        location: RunPluginLocation::File(PathBuf::from("/path/to/fake/plugin")),
+        configuration: Default::default(),
+    }));
     initial_layout.children_split_direction = SplitDirection::Vertical;
-    initial_layout.children = vec![TiledPaneLayout::default(), TiledPaneLayout::default()];
-    let mut second_tab_layout = TiledPaneLayout::default();
-    second_tab_layout.children_split_direction = SplitDirection::Horizontal;
-    second_tab_layout.children = vec![TiledPaneLayout::default(), TiledPaneLayout::default()];
+    initial_layout.children = vec![plugin_pane, TiledPaneLayout::default()];
     let mut mock_screen = MockScreen::new(size);
-    mock_screen.new_tab(second_tab_layout);
-    let session_metadata = mock_screen.clone_session_metadata();
     let screen_thread = mock_screen.run(Some(initial_layout), vec![]);
     let received_server_instructions = Arc::new(Mutex::new(vec![]));
     let server_receiver = mock_screen.server_receiver.take().unwrap();
@@ -2433,79 +6848,247 @@ pub fn send_cli_close_tab_action() {
         ServerInstruction::KillSession,
         server_receiver
     );
-    let close_tab = CliAction::CloseTab;
-    send_cli_action_to_server(&session_metadata, close_tab, client_id);
+    let received_plugin_instructions = Arc::new(Mutex::new(vec![]));
+    let plugin_receiver = mock_screen.plugin_receiver.take().unwrap();
+    let plugin_thread = log_actions_in_thread!(
+        received_plugin_instructions,
+        PluginInstruction::Exit,
+        plugin_receiver
+    );
+    let _ = mock_screen.to_screen.send(ScreenInstruction::RenamePane(
+        PaneId::Plugin(plugin_id),
+        b"plugin pane name".to_vec(),
+    ));
+    let _ = mock_screen
+        .to_screen
+        .send(ScreenInstruction::RenamePluginTab(
+            plugin_id,
+            "plugin tab name".to_owned(),
+        ));
     std::thread::sleep(std::time::Duration::from_millis(100));
-    mock_screen.teardown(vec![server_thread, screen_thread]);
+    let _ = mock_screen
+        .to_screen
+        .send(ScreenInstruction::DumpLayout(None, client_id));
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    mock_screen.teardown(vec![plugin_thread, server_thread, screen_thread]);
+
     let snapshots = take_snapshots_and_cursor_coordinates_from_render_events(
         received_server_instructions.lock().unwrap().iter(),
         size,
     );
-    let snapshot_count = snapshots.len();
-    for (_cursor_coordinates, snapshot) in snapshots {
-        assert_snapshot!(format!("{}", snapshot));
-    }
-    assert_snapshot!(format!("{}", snapshot_count));
+    let (_cursor_coordinates, last_snapshot) = snapshots.last().unwrap();
+    assert!(
+        last_snapshot
+            .lines()
+            .next()
+            .unwrap()
+            .contains("plugin pane name"),
+        "pane name shown in the frame of the plugin pane"
+    );
+    let session_layout_metadata = received_plugin_instructions
+        .lock()
+        .unwrap()
+        .iter()
+        .find_map(|instruction| match instruction {
+            PluginInstruction::DumpLayout(session_layout_metadata, _client_id) => {
+                Some(session_layout_metadata.clone())
+            },
+            _ => None,
+        })
+        .unwrap();
+    let layout_manifest: GlobalLayoutManifest = session_layout_metadata.into();
+    let (tab_name, tab_layout_manifest) = &layout_manifest.tabs[0];
+    assert_eq!(tab_name, "plugin tab name", "tab name in the layout dump");
+    assert!(
+        tab_layout_manifest
+            .tiled_panes
+            .iter()
+            .any(|pane| pane.title.as_deref() == Some("plugin pane name")),
+        "pane name in the layout dump"
+    );
 }
 
 #[test]
-pub fn send_cli_rename_tab() {
-    let size = Size { cols: 80, rows: 10 };
-    let client_id = 10; // fake client id should not appear in the screen's state
-    let mut initial_layout = TiledPaneLayout::default();
-    initial_layout.children_split_direction = SplitDirection::Vertical;
-    initial_layout.children = vec![TiledPaneLayout::default(), TiledPaneLayout::default()];
-    let mut second_tab_layout = TiledPaneLayout::default();
-    second_tab_layout.children_split_direction = SplitDirection::Horizontal;
-    second_tab_layout.children = vec![TiledPaneLayout::default(), TiledPaneLayout::default()];
+fn toggling_floating_panes_in_tab_keeps_their_geometry_and_focus() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let client_id = 1;
+    let tab_index = 0;
+    let mut screen = create_new_screen(size);
+    new_tab(&mut screen, 1, tab_index);
+    let tab = screen.get_indexed_tab_mut(tab_index).unwrap();
+    tab.new_pane(PaneId::Terminal(2), None, Some(true), None, Some(client_id))
+        .unwrap();
+    tab.new_pane(PaneId::Terminal(3), None, Some(true), None, Some(client_id))
+        .unwrap();
+    tab.focus_pane_with_id(PaneId::Terminal(2), true, client_id)
+        .unwrap();
+    let floating_pane_geometries = |screen: &mut Screen| {
+        screen
+            .get_indexed_tab_mut(tab_index)
+            .unwrap()
+            .get_floating_panes()
+            .map(|(pane_id, pane)| (*pane_id, pane.position_and_size()))
+            .collect::<Vec<_>>()
+    };
+    let geometries_before_toggling = floating_pane_geometries(&mut screen);
+
+    screen
+        .toggle_floating_panes_in_tab(tab_index, client_id)
+        .unwrap();
+    let tab = screen.get_indexed_tab_mut(tab_index).unwrap();
+    assert!(!tab.are_floating_panes_visible(), "floating panes hidden");
+    assert_eq!(
+        tab.get_active_pane_id(client_id),
+        Some(PaneId::Terminal(1)),
+        "tiled pane focused while the floating panes are hidden"
+    );
+
+    screen
+        .toggle_floating_panes_in_tab(tab_index, client_id)
+        .unwrap();
+    let tab = screen.get_indexed_tab_mut(tab_index).unwrap();
+    assert!(
+        tab.are_floating_panes_visible(),
+        "floating panes shown again"
+    );
+    assert_eq!(
+        tab.get_active_pane_id(client_id),
+        Some(PaneId::Terminal(2)),
+        "previously focused floating pane focused again"
+    );
+    assert_eq!(
+        floating_pane_geometries(&mut screen),
+        geometries_before_toggling,
+        "floating panes kept their geometry"
+    );
+}
+
+#[test]
+fn switching_workspaces_changes_the_visible_tabs_and_keeps_both() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let client_id = 1;
     let mut mock_screen = MockScreen::new(size);
-    mock_screen.new_tab(second_tab_layout);
-    let session_metadata = mock_screen.clone_session_metadata();
-    let screen_thread = mock_screen.run(Some(initial_layout), vec![]);
-    let received_plugin_instructions = Arc::new(Mutex::new(vec![]));
     let plugin_receiver = mock_screen.plugin_receiver.take().unwrap();
+    let screen_thread = mock_screen.run(None, vec![]);
+    let received_plugin_instructions = Arc::new(Mutex::new(vec![]));
     let plugin_thread = log_actions_in_thread!(
         received_plugin_instructions,
         PluginInstruction::Exit,
         plugin_receiver
     );
-    let rename_tab = CliAction::RenameTab {
-        name: "new-tab-name".into(),
-    };
-    send_cli_action_to_server(&session_metadata, rename_tab, client_id);
+    // name the tab of the default workspace so that we can tell the workspaces apart
+    let _ = mock_screen
+        .to_screen
+        .send(ScreenInstruction::UpdateTabName(vec![0], client_id));
+    let _ = mock_screen.to_screen.send(ScreenInstruction::UpdateTabName(
+        "main".as_bytes().to_vec(),
+        client_id,
+    ));
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    let _ = mock_screen
+        .to_screen
+        .send(ScreenInstruction::SwitchWorkspace("work".to_owned()));
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    // this is what the pty thread sends back once it opened the terminal of the new workspace
+    let _ = mock_screen.to_screen.send(ScreenInstruction::ApplyLayout(
+        TiledPaneLayout::default(),
+        vec![],
+        vec![(1, None)],
+        vec![],
+        HashMap::new(),
+        1,
+        client_id,
+    ));
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    let _ = mock_screen
+        .to_screen
+        .send(ScreenInstruction::SwitchWorkspace("default".to_owned()));
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    let _ = mock_screen
+        .to_screen
+        .send(ScreenInstruction::SwitchWorkspace("work".to_owned()));
     std::thread::sleep(std::time::Duration::from_millis(100));
     mock_screen.teardown(vec![plugin_thread, screen_thread]);
-    let plugin_rename_tab_instruction = received_plugin_instructions
-        .lock()
-        .unwrap()
+    let received_plugin_instructions = received_plugin_instructions.lock().unwrap();
+    let workspace_changes: Vec<String> = received_plugin_instructions
         .iter()
-        .find(|instruction| match instruction {
-            PluginInstruction::Update(updates) => updates
-                .iter()
-                .find(|u| match u {
-                    (_, _, Event::TabUpdate(..)) => true,
-                    _ => false,
+        .filter_map(|instruction| match instruction {
+            PluginInstruction::Update(updates) => {
+                updates.iter().find_map(|(_, _, event)| match event {
+                    Event::WorkspaceChanged(workspace_name) => Some(workspace_name.clone()),
+                    _ => None,
                 })
-                .is_some(),
-            _ => false,
+            },
+            _ => None,
         })
-        .cloned();
-    assert_snapshot!(format!("{:#?}", plugin_rename_tab_instruction))
+        .collect();
+    assert_eq!(
+        workspace_changes,
+        vec!["work", "default", "work"],
+        "workspace switches"
+    );
+    let new_tab_instructions = received_plugin_instructions
+        .iter()
+        .filter(|instruction| matches!(instruction, PluginInstruction::NewTab(..)))
+        .count();
+    assert_eq!(
+        new_tab_instructions, 2,
+        "besides the first tab of the session, the work workspace was only created once"
+    );
+    let mut visible_tab_names: Vec<Vec<String>> = received_plugin_instructions
+        .iter()
+        .filter_map(|instruction| match instruction {
+            PluginInstruction::Update(updates) => {
+                updates.iter().find_map(|(_, _, event)| match event {
+                    Event::TabUpdate(tab_infos) if !tab_infos.is_empty() => Some(
+                        tab_infos
+                            .iter()
+                            .map(|tab_info| tab_info.name.clone())
+                            .collect(),
+                    ),
+                    _ => None,
+                })
+            },
+            _ => None,
+        })
+        .skip_while(|tab_names: &Vec<String>| tab_names != &vec!["main".to_owned()])
+        .collect();
+    visible_tab_names.dedup();
+    assert_eq!(
+        visible_tab_names,
+        vec![
+            // tabs are named after their index, which is unique across workspaces
+            vec!["main".to_owned()],
+            vec!["Tab #2".to_owned()],
+            vec!["main".to_owned()],
+            vec!["Tab #2".to_owned()],
+        ],
+        "the visible tabs changed with the workspace while both workspaces persisted"
+    );
 }
 
 #[test]
-pub fn send_cli_undo_rename_tab() {
-    let size = Size { cols: 80, rows: 10 };
-    let client_id = 10; // fake client id should not appear in the screen's state
+fn plugin_of_a_hidden_workspace_can_request_permissions() {
+    let size = Size { cols: 80, rows: 20 };
+    let client_id = 1;
+    let plugin_id = 1;
     let mut initial_layout = TiledPaneLayout::default();
+    let mut plugin_pane = TiledPaneLayout::default();
+    plugin_pane.run = Some(Run::Plugin(RunPlugin {
+        _allow_exec_host_cmd: false,
+        location: RunPluginLocation::File(PathBuf::from("/path/to/fake/plugin")),
+        configuration: Default::default(),
+    }));
+    plugin_pane.focus = Some(true);
     initial_layout.children_split_direction = SplitDirection::Vertical;
-    initial_layout.children = vec![TiledPaneLayout::default(), TiledPaneLayout::default()];
-    let mut second_tab_layout = TiledPaneLayout::default();
-    second_tab_layout.children_split_direction = SplitDirection::Horizontal;
-    second_tab_layout.children = vec![TiledPaneLayout::default(), TiledPaneLayout::default()];
+    initial_layout.children = vec![plugin_pane, TiledPaneLayout::default()];
     let mut mock_screen = MockScreen::new(size);
-    mock_screen.new_tab(second_tab_layout);
-    let session_metadata = mock_screen.clone_session_metadata();
     let screen_thread = mock_screen.run(Some(initial_layout), vec![]);
     let received_plugin_instructions = Arc::new(Mutex::new(vec![]));
     let plugin_receiver = mock_screen.plugin_receiver.take().unwrap();
@@ -2514,284 +7097,355 @@ pub fn send_cli_undo_rename_tab() {
         PluginInstruction::Exit,
         plugin_receiver
     );
-    let rename_tab = CliAction::RenameTab {
-        name: "new-tab-name".into(),
-    };
-    let undo_rename_tab = CliAction::UndoRenameTab;
-    // first rename the tab
-    send_cli_action_to_server(&session_metadata, rename_tab, client_id);
     std::thread::sleep(std::time::Duration::from_millis(100));
-    // then undo the tab rename to go back to the default name
-    send_cli_action_to_server(&session_metadata, undo_rename_tab, client_id);
+    let _ = mock_screen
+        .to_screen
+        .send(ScreenInstruction::SwitchWorkspace("work".to_owned()));
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    // this is what the pty thread sends back once it opened the terminal of the new workspace
+    let _ = mock_screen.to_screen.send(ScreenInstruction::ApplyLayout(
+        TiledPaneLayout::default(),
+        vec![],
+        vec![(2, None)],
+        vec![],
+        HashMap::new(),
+        1,
+        client_id,
+    ));
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    // the plugin asks for permissions while its workspace is hidden
+    let _ = mock_screen
+        .to_screen
+        .send(ScreenInstruction::RequestPluginPermissions(
+            plugin_id,
+            PluginPermission::new(
+                "fake plugin".to_owned(),
+                vec![PermissionType::ReadApplicationState],
+            ),
+        ));
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    let _ = mock_screen
+        .to_screen
+        .send(ScreenInstruction::SwitchWorkspace("default".to_owned()));
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    // this answers the permission request shown in the focused plugin pane
+    let _ = mock_screen
+        .to_screen
+        .send(ScreenInstruction::WriteCharacter(
+            "y".as_bytes().to_vec(),
+            client_id,
+        ));
     std::thread::sleep(std::time::Duration::from_millis(100));
     mock_screen.teardown(vec![plugin_thread, screen_thread]);
-    let plugin_undo_rename_tab_instruction = received_plugin_instructions
-        .lock()
-        .unwrap()
-        .iter()
-        .find(|instruction| match instruction {
-            PluginInstruction::Update(updates) => updates
-                .iter()
-                .find(|u| match u {
-                    (_, _, Event::TabUpdate(..)) => true,
-                    _ => false,
-                })
-                .is_some(),
-            _ => false,
-        })
-        .cloned();
-    assert_snapshot!(format!("{:#?}", plugin_undo_rename_tab_instruction))
-}
-
-#[test]
-pub fn send_cli_query_tab_names_action() {
-    let size = Size { cols: 80, rows: 10 };
-    let client_id = 10; // fake client id should not appear in the screen's state
-    let mut mock_screen = MockScreen::new(size);
-    mock_screen.new_tab(TiledPaneLayout::default());
-    let session_metadata = mock_screen.clone_session_metadata();
-    let screen_thread = mock_screen.run(Some(TiledPaneLayout::default()), vec![]);
-    let received_server_instructions = Arc::new(Mutex::new(vec![]));
-    let server_receiver = mock_screen.server_receiver.take().unwrap();
-    let server_thread = log_actions_in_thread!(
-        received_server_instructions,
-        ServerInstruction::KillSession,
-        server_receiver
+    let permission_was_granted =
+        received_plugin_instructions
+            .lock()
+            .unwrap()
+            .iter()
+            .any(|instruction| {
+                matches!(
+                    instruction,
+                    PluginInstruction::PermissionRequestResult(
+                        1,
+                        _,
+                        _,
+                        PermissionStatus::Granted,
+                        _
+                    )
+                )
+            });
+    assert!(
+        permission_was_granted,
+        "the permission request of the plugin in the hidden workspace was shown in its pane"
     );
-    let query_tab_names = CliAction::QueryTabNames;
-    send_cli_action_to_server(&session_metadata, query_tab_names, client_id);
-    std::thread::sleep(std::time::Duration::from_millis(100));
-    mock_screen.teardown(vec![server_thread, screen_thread]);
-    let log_tab_names_instruction = received_server_instructions
-        .lock()
-        .unwrap()
-        .iter()
-        .find(|instruction| match instruction {
-            ServerInstruction::Log(..) => true,
-            _ => false,
-        })
-        .cloned();
-    assert_snapshot!(format!("{:#?}", log_tab_names_instruction));
 }
 
 #[test]
-pub fn send_cli_launch_or_focus_plugin_action() {
+fn focus_dimming_is_set_in_the_tabs_of_hidden_workspaces() {
     let size = Size {
         cols: 121,
         rows: 20,
     };
-    let client_id = 10; // fake client id should not appear in the screen's state
-    let mut mock_screen = MockScreen::new(size);
-    let pty_receiver = mock_screen.pty_receiver.take().unwrap();
-    let session_metadata = mock_screen.clone_session_metadata();
-    let screen_thread = mock_screen.run(None, vec![]);
-    let received_pty_instructions = Arc::new(Mutex::new(vec![]));
-    let pty_thread = log_actions_in_thread!(
-        received_pty_instructions,
-        PtyInstruction::Exit,
-        pty_receiver
+    let client_id = 1;
+    let mut screen = create_new_screen(size);
+    new_tab(&mut screen, 1, 0);
+    new_tab(&mut screen, 2, 1);
+    // hide the second tab in another workspace, as switching workspaces would
+    let hidden_tab = screen.tabs.remove(&1).unwrap();
+    screen.active_tab_indices.insert(client_id, 0);
+    screen.hidden_workspaces.insert(
+        "work".to_owned(),
+        Workspace {
+            tabs: BTreeMap::from([(1, hidden_tab)]),
+            active_tab_indices: BTreeMap::from([(client_id, 1)]),
+            tab_history: BTreeMap::new(),
+        },
     );
-    let cli_action = CliAction::LaunchOrFocusPlugin {
-        floating: true,
-        in_place: false,
-        move_to_focused_tab: true,
-        url: url::Url::parse("file:/path/to/fake/plugin").unwrap(),
-        configuration: Default::default(),
-    };
-    send_cli_action_to_server(&session_metadata, cli_action, client_id);
-    std::thread::sleep(std::time::Duration::from_millis(100)); // give time for actions to be
-    mock_screen.teardown(vec![pty_thread, screen_thread]);
-
-    let pty_fill_plugin_cwd_instruction = received_pty_instructions
-        .lock()
-        .unwrap()
-        .iter()
-        .find(|instruction| match instruction {
-            PtyInstruction::FillPluginCwd(..) => true,
-            _ => false,
-        })
-        .cloned();
 
-    assert_snapshot!(format!("{:#?}", pty_fill_plugin_cwd_instruction));
+    screen.set_focus_dimming(true, 0.3).unwrap();
+    assert_eq!(
+        screen.tabs[&0].focus_dimming(),
+        Some(0.3),
+        "tab of the current workspace dimmed"
+    );
+    assert_eq!(
+        screen.hidden_workspaces["work"].tabs[&1].focus_dimming(),
+        Some(0.3),
+        "tab of the hidden workspace dimmed"
+    );
+
+    screen.set_focus_dimming(false, 0.3).unwrap();
+    assert_eq!(
+        screen.hidden_workspaces["work"].tabs[&1].focus_dimming(),
+        None,
+        "tab of the hidden workspace no longer dimmed"
+    );
 }
 
 #[test]
-pub fn send_cli_launch_or_focus_plugin_action_when_plugin_is_already_loaded() {
+fn panes_of_hidden_workspaces_are_dumped_with_the_layout_and_can_be_closed() {
     let size = Size {
         cols: 121,
         rows: 20,
     };
-    let client_id = 10; // fake client id should not appear in the screen's state
-    let mut mock_screen = MockScreen::new(size);
-    let plugin_receiver = mock_screen.plugin_receiver.take().unwrap();
-    let session_metadata = mock_screen.clone_session_metadata();
-    let mut initial_layout = TiledPaneLayout::default();
-    let existing_plugin_pane = TiledPaneLayout {
-        run: Some(Run::Plugin(RunPlugin {
-            _allow_exec_host_cmd: false,
-            location: RunPluginLocation::File(PathBuf::from("/path/to/fake/plugin")),
-            configuration: Default::default(),
-        })),
-        ..Default::default()
-    };
-    initial_layout.children_split_direction = SplitDirection::Vertical;
-    initial_layout.children = vec![TiledPaneLayout::default(), existing_plugin_pane];
-    let screen_thread = mock_screen.run(Some(initial_layout), vec![]);
-    let received_plugin_instructions = Arc::new(Mutex::new(vec![]));
-    let plugin_thread = log_actions_in_thread!(
-        received_plugin_instructions,
-        PluginInstruction::Exit,
-        plugin_receiver
+    let client_id = 1;
+    let mut screen = create_new_screen(size);
+    new_tab(&mut screen, 1, 0);
+    new_tab(&mut screen, 2, 1);
+    // hide the second tab in another workspace, as switching workspaces would
+    let hidden_tab = screen.tabs.remove(&1).unwrap();
+    screen.active_tab_indices.insert(client_id, 0);
+    screen.hidden_workspaces.insert(
+        "work".to_owned(),
+        Workspace {
+            tabs: BTreeMap::from([(1, hidden_tab)]),
+            active_tab_indices: BTreeMap::from([(client_id, 1)]),
+            tab_history: BTreeMap::new(),
+        },
     );
-    let received_server_instructions = Arc::new(Mutex::new(vec![]));
-    let server_receiver = mock_screen.server_receiver.take().unwrap();
-    let server_thread = log_actions_in_thread!(
-        received_server_instructions,
-        ServerInstruction::KillSession,
-        server_receiver
+
+    let mut dumped_terminal_ids = screen.get_layout_metadata(None).all_terminal_ids();
+    dumped_terminal_ids.sort();
+    assert_eq!(
+        dumped_terminal_ids,
+        vec![1, 2],
+        "panes of both workspaces dumped"
     );
-    let cli_action = CliAction::LaunchOrFocusPlugin {
-        floating: true,
-        in_place: false,
-        move_to_focused_tab: true,
-        url: url::Url::parse("file:/path/to/fake/plugin").unwrap(),
-        configuration: Default::default(),
-    };
-    send_cli_action_to_server(&session_metadata, cli_action, client_id);
-    std::thread::sleep(std::time::Duration::from_millis(100)); // give time for actions to be
-    mock_screen.teardown(vec![plugin_thread, server_thread, screen_thread]);
 
-    let plugin_load_instruction_sent = received_plugin_instructions
-        .lock()
-        .unwrap()
-        .iter()
-        .find(|instruction| match instruction {
-            PluginInstruction::Load(..) => true,
-            _ => false,
-        })
-        .is_some();
+    screen.close_pane(PaneId::Terminal(2), None).unwrap();
+    let hidden_pane_ids = screen.hidden_workspaces["work"].tabs[&1].get_all_pane_ids();
     assert!(
-        !plugin_load_instruction_sent,
-        "Plugin Load instruction should not be sent for an already loaded plugin"
+        !hidden_pane_ids.contains(&PaneId::Terminal(2)),
+        "pane of the hidden workspace closed"
     );
-    let snapshots = take_snapshots_and_cursor_coordinates_from_render_events(
-        received_server_instructions.lock().unwrap().iter(),
-        size,
+
+    screen.remove_client(client_id).unwrap();
+    assert!(
+        screen.hidden_workspaces["work"]
+            .active_tab_indices
+            .is_empty(),
+        "removed client forgotten by the hidden workspace"
     );
-    let snapshot_count = snapshots.len();
+}
+
+#[test]
+fn resize_tab_to_content_sizes_panes_in_proportion_to_their_preferred_size() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let client_id = 1;
+    let tab_index = 0;
+    let mut screen = create_new_screen(size);
+    new_tab(&mut screen, 1, tab_index);
+    screen
+        .get_active_tab_mut(client_id)
+        .unwrap()
+        .new_pane(PaneId::Terminal(2), None, None, None, Some(client_id))
+        .unwrap();
+    // fixed-size apps report the size they need as the minimum size of their pane
+    screen
+        .set_pane_min_size(PaneId::Terminal(1), 20, 5)
+        .expect("TEST");
+    screen
+        .set_pane_min_size(PaneId::Terminal(2), 40, 5)
+        .expect("TEST");
+
+    screen.resize_tab_to_content(tab_index).expect("TEST");
     assert_eq!(
-        snapshot_count, 2,
-        "Another render was sent for focusing the already loaded plugin"
+        tab_pane_geometries(&screen, tab_index),
+        vec![(1, 0, 0, 40, 20), (2, 40, 0, 81, 20)],
+        "the space is distributed in proportion to the preferred sizes of the panes"
     );
-    for (cursor_coordinates, _snapshot) in snapshots.iter().skip(1) {
-        assert!(
-            cursor_coordinates.is_none(),
-            "Cursor moved to existing plugin in final snapshot indicating focus changed"
-        );
-    }
 }
 
 #[test]
-pub fn screen_can_suppress_pane() {
-    let size = Size { cols: 80, rows: 20 };
+fn resize_tab_to_content_distributes_the_space_of_each_split_between_its_parts() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let client_id = 1;
+    let tab_index = 0;
+    let mut screen = create_new_screen(size);
+    new_tab(&mut screen, 1, tab_index);
+    // pane 1 on the left, panes 2 and 3 stacked on top of each other on the right
+    let tab = screen.get_active_tab_mut(client_id).unwrap();
+    tab.vertical_split(PaneId::Terminal(2), None, client_id)
+        .unwrap();
+    tab.horizontal_split(PaneId::Terminal(3), None, client_id)
+        .unwrap();
+    screen
+        .set_pane_min_size(PaneId::Terminal(1), 20, 5)
+        .expect("TEST");
+    screen
+        .set_pane_min_size(PaneId::Terminal(2), 40, 5)
+        .expect("TEST");
+    screen
+        .set_pane_min_size(PaneId::Terminal(3), 20, 15)
+        .expect("TEST");
+
+    screen.resize_tab_to_content(tab_index).expect("TEST");
+    assert_eq!(
+        tab_pane_geometries(&screen, tab_index),
+        vec![(1, 0, 0, 40, 20), (2, 40, 0, 81, 5), (3, 40, 5, 81, 15)],
+        "the right column is as wide as its widest pane needs, and split between its panes"
+    );
+}
+
+#[test]
+fn clients_are_told_when_the_pane_they_focus_enters_or_leaves_passthrough_mode() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let client_id = 1;
+    let mut mock_screen = MockScreen::new(size);
     let mut initial_layout = TiledPaneLayout::default();
     initial_layout.children_split_direction = SplitDirection::Vertical;
     initial_layout.children = vec![TiledPaneLayout::default(), TiledPaneLayout::default()];
-    let mut mock_screen = MockScreen::new(size);
     let screen_thread = mock_screen.run(Some(initial_layout), vec![]);
-    let received_server_instructions = Arc::new(Mutex::new(vec![]));
-    let server_receiver = mock_screen.server_receiver.take().unwrap();
-    let server_thread = log_actions_in_thread!(
-        received_server_instructions,
-        ServerInstruction::KillSession,
-        server_receiver
-    );
+    for pane_id in [PaneId::Terminal(0), PaneId::Terminal(1)] {
+        let _ = mock_screen
+            .to_screen
+            .send(ScreenInstruction::SetPanePassthrough(pane_id, true));
+    }
+    std::thread::sleep(std::time::Duration::from_millis(100));
     let _ = mock_screen
         .to_screen
-        .send(ScreenInstruction::SuppressPane(PaneId::Terminal(1), 1));
+        .send(ScreenInstruction::TogglePanePassthrough(client_id));
     std::thread::sleep(std::time::Duration::from_millis(100));
-    mock_screen.teardown(vec![server_thread, screen_thread]);
-
-    let snapshots = take_snapshots_and_cursor_coordinates_from_render_events(
-        received_server_instructions.lock().unwrap().iter(),
-        size,
+    // the other pane is still in pass-through mode
+    let _ = mock_screen
+        .to_screen
+        .send(ScreenInstruction::SwitchFocus(client_id));
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    mock_screen.teardown(vec![screen_thread]);
+    let server_to_client_messages = mock_screen
+        .os_input
+        .server_to_client_messages
+        .lock()
+        .unwrap();
+    let passthrough_updates: Vec<bool> = server_to_client_messages
+        .get(&client_id)
+        .unwrap()
+        .iter()
+        .filter_map(|message| match message {
+            ServerToClientMsg::SetPassthrough(passthrough) => Some(*passthrough),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        passthrough_updates,
+        vec![true, false, true],
+        "the client is only told when the state of its focused pane changes"
     );
-    let snapshot_count = snapshots.len();
-    for (_cursor_coordinates, snapshot) in snapshots {
-        assert_snapshot!(format!("{}", snapshot));
-    }
-    assert_snapshot!(format!("{}", snapshot_count));
 }
 
 #[test]
-pub fn screen_can_break_pane_to_a_new_tab() {
-    let size = Size { cols: 80, rows: 20 };
-    let mut initial_layout = TiledPaneLayout::default();
-    let mut pane_to_break_free = TiledPaneLayout::default();
-    pane_to_break_free.name = Some("pane_to_break_free".to_owned());
-    let mut pane_to_stay = TiledPaneLayout::default();
-    pane_to_stay.name = Some("pane_to_stay".to_owned());
-    initial_layout.children_split_direction = SplitDirection::Vertical;
-    initial_layout.children = vec![pane_to_break_free, pane_to_stay];
+fn keys_passed_through_by_the_client_reach_the_pty_of_the_pane() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let client_id = 1;
     let mut mock_screen = MockScreen::new(size);
-    let screen_thread = mock_screen.run(Some(initial_layout), vec![]);
-    let received_server_instructions = Arc::new(Mutex::new(vec![]));
-    let server_receiver = mock_screen.server_receiver.take().unwrap();
-    let server_thread = log_actions_in_thread!(
-        received_server_instructions,
-        ServerInstruction::KillSession,
-        server_receiver
+    let pty_writer_receiver = mock_screen.pty_writer_receiver.take().unwrap();
+    let session_metadata = mock_screen.clone_session_metadata();
+    let screen_thread = mock_screen.run(None, vec![]);
+    let received_pty_instructions = Arc::new(Mutex::new(vec![]));
+    let pty_writer_thread = log_actions_in_thread!(
+        received_pty_instructions,
+        PtyWriteInstruction::Exit,
+        pty_writer_receiver
     );
-
-    let _ = mock_screen.to_screen.send(ScreenInstruction::BreakPane(
-        Box::new(Layout::default()),
-        Default::default(),
-        1,
-    ));
-    std::thread::sleep(std::time::Duration::from_millis(100));
-    // we send ApplyLayout, because in prod this is eventually received after the message traverses
-    // through the plugin and pty threads (to open extra stuff we need in the layout, eg. the
-    // default plugins)
-    let _ = mock_screen.to_screen.send(ScreenInstruction::ApplyLayout(
-        TiledPaneLayout::default(),
-        vec![], // floating_panes_layout
-        Default::default(),
-        vec![], // floating panes ids
-        Default::default(),
-        1,
-        1,
-    ));
-    std::thread::sleep(std::time::Duration::from_millis(100));
-    // move back to make sure the other pane is in the previous tab
     let _ = mock_screen
         .to_screen
-        .send(ScreenInstruction::MoveFocusLeftOrPreviousTab(1));
+        .send(ScreenInstruction::SetPanePassthrough(
+            PaneId::Terminal(0),
+            true,
+        ));
     std::thread::sleep(std::time::Duration::from_millis(100));
-    // move forward to make sure the broken pane is in the previous tab
-    let _ = mock_screen
-        .to_screen
-        .send(ScreenInstruction::MoveFocusRightOrNextTab(1));
+    // ctrl-b, which would otherwise switch to tmux mode
+    send_cli_action_to_server(
+        &session_metadata,
+        CliAction::Write { bytes: vec![2] },
+        client_id,
+    );
     std::thread::sleep(std::time::Duration::from_millis(100));
-
-    mock_screen.teardown(vec![server_thread, screen_thread]);
-
-    let snapshots = take_snapshots_and_cursor_coordinates_from_render_events(
-        received_server_instructions.lock().unwrap().iter(),
-        size,
+    mock_screen.teardown(vec![pty_writer_thread, screen_thread]);
+    let writes: Vec<(Vec<u8>, u32)> = received_pty_instructions
+        .lock()
+        .unwrap()
+        .iter()
+        .filter_map(|instruction| match instruction {
+            PtyWriteInstruction::Write(bytes, terminal_id) => Some((bytes.clone(), *terminal_id)),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        writes,
+        vec![(vec![2], 0)],
+        "the key is written to the pane in pass-through mode"
     );
-    let snapshot_count = snapshots.len();
-    for (_cursor_coordinates, snapshot) in snapshots {
-        assert_snapshot!(format!("{}", snapshot));
-    }
-    assert_snapshot!(format!("{}", snapshot_count));
 }
 
 #[test]
-pub fn screen_cannot_break_last_selectable_pane_to_a_new_tab() {
-    let size = Size { cols: 80, rows: 20 };
-    let initial_layout = TiledPaneLayout::default();
+fn closed_pane_is_no_longer_in_passthrough_mode() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let client_id = 1;
+    let mut screen = create_new_screen(size);
+    new_tab(&mut screen, 1, 0);
+    screen
+        .get_active_tab_mut(client_id)
+        .unwrap()
+        .vertical_split(PaneId::Terminal(2), None, client_id)
+        .unwrap();
+    screen
+        .set_pane_passthrough(PaneId::Terminal(2), true)
+        .expect("TEST");
+    screen.close_pane(PaneId::Terminal(2), None).expect("TEST");
+    // a new pane that reuses the id of the closed one
+    screen
+        .get_active_tab_mut(client_id)
+        .unwrap()
+        .vertical_split(PaneId::Terminal(2), None, client_id)
+        .unwrap();
+    assert!(
+        !screen.passthrough_panes.contains(&PaneId::Terminal(2)),
+        "the new pane does not start in pass-through mode"
+    );
+}
+
+#[test]
+fn capture_pane_text_returns_the_plain_scrollback_of_the_pane() {
+    let size = Size { cols: 20, rows: 5 };
+    let client_id = 1;
     let mut mock_screen = MockScreen::new(size);
-    let screen_thread = mock_screen.run(Some(initial_layout), vec![]);
+    let screen_thread = mock_screen.run(None, vec![]);
     let received_server_instructions = Arc::new(Mutex::new(vec![]));
     let server_receiver = mock_screen.server_receiver.take().unwrap();
     let server_thread = log_actions_in_thread!(
@@ -2799,40 +7453,51 @@ pub fn screen_cannot_break_last_selectable_pane_to_a_new_tab() {
         ServerInstruction::KillSession,
         server_receiver
     );
-
-    let _ = mock_screen.to_screen.send(ScreenInstruction::BreakPane(
-        Box::new(Layout::default()),
-        Default::default(),
-        1,
+    // more lines than fit in the pane, one of them styled and one too long for it
+    let pane_contents = concat!(
+        "first line\r\n",
+        "\u{1b}[31msecond\u{1b}[m line\r\n",
+        "wrapped wrapped wrapped wrapped\r\n",
+        "last line\r\n\r\n",
+    );
+    let _ = mock_screen.to_screen.send(ScreenInstruction::PtyBytes(
+        0,
+        pane_contents.as_bytes().to_vec(),
     ));
+    let _ = mock_screen
+        .to_screen
+        .send(ScreenInstruction::CapturePaneText {
+            pane_id: Some(PaneId::Terminal(0)),
+            include_scrollback: true,
+            client_id,
+        });
     std::thread::sleep(std::time::Duration::from_millis(100));
-
     mock_screen.teardown(vec![server_thread, screen_thread]);
-
-    let snapshots = take_snapshots_and_cursor_coordinates_from_render_events(
-        received_server_instructions.lock().unwrap().iter(),
-        size,
+    let captured_text: Vec<Vec<String>> = received_server_instructions
+        .lock()
+        .unwrap()
+        .iter()
+        .filter_map(|instruction| match instruction {
+            ServerInstruction::Log(lines, _) => Some(lines.clone()),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        captured_text,
+        vec![vec![
+            "first line\nsecond line\nwrapped wrapped wrapped wrapped\nlast line".to_owned()
+        ]],
+        "wrapped lines are joined and trailing blank lines are trimmed"
     );
-    let snapshot_count = snapshots.len();
-    for (_cursor_coordinates, snapshot) in snapshots {
-        assert_snapshot!(format!("{}", snapshot));
-    }
-    assert_snapshot!(format!("{}", snapshot_count));
 }
 
 #[test]
-pub fn screen_can_break_floating_pane_to_a_new_tab() {
-    let size = Size { cols: 80, rows: 20 };
-    let mut initial_layout = TiledPaneLayout::default();
-    let mut pane_to_break_free = TiledPaneLayout::default();
-    pane_to_break_free.name = Some("tiled_pane".to_owned());
-    let mut floating_pane = FloatingPaneLayout::default();
-    floating_pane.name = Some("floating_pane_to_eject".to_owned());
-    let mut floating_panes_layout = vec![floating_pane];
-    initial_layout.children_split_direction = SplitDirection::Vertical;
-    initial_layout.children = vec![pane_to_break_free];
+pub fn send_cli_capture_pane_text_action() {
+    let size = Size { cols: 20, rows: 5 };
+    let client_id = 10; // fake client id should not appear in the screen's state
     let mut mock_screen = MockScreen::new(size);
-    let screen_thread = mock_screen.run(Some(initial_layout), floating_panes_layout.clone());
+    let session_metadata = mock_screen.clone_session_metadata();
+    let screen_thread = mock_screen.run(None, vec![]);
     let received_server_instructions = Arc::new(Mutex::new(vec![]));
     let server_receiver = mock_screen.server_receiver.take().unwrap();
     let server_thread = log_actions_in_thread!(
@@ -2840,285 +7505,594 @@ pub fn screen_can_break_floating_pane_to_a_new_tab() {
         ServerInstruction::KillSession,
         server_receiver
     );
-
-    let _ = mock_screen.to_screen.send(ScreenInstruction::BreakPane(
-        Box::new(Layout::default()),
-        Default::default(),
-        1,
+    let _ = mock_screen.to_screen.send(ScreenInstruction::PtyBytes(
+        0,
+        "focused pane\r\n".as_bytes().to_vec(),
     ));
     std::thread::sleep(std::time::Duration::from_millis(100));
-    // we send ApplyLayout, because in prod this is eventually received after the message traverses
-    // through the plugin and pty threads (to open extra stuff we need in the layout, eg. the
-    // default plugins)
-    floating_panes_layout.get_mut(0).unwrap().already_running = true;
-    let _ = mock_screen.to_screen.send(ScreenInstruction::ApplyLayout(
-        TiledPaneLayout::default(),
-        floating_panes_layout,
-        vec![(1, None)], // tiled pane ids - send these because one needs to be created under the
-        // ejected floating pane, lest the tab be closed as having no tiled panes
-        // (this happens in prod in the pty thread)
-        vec![], // floating panes ids
-        Default::default(),
-        1,
-        1,
-    ));
+    let capture_pane_text_action = CliAction::CapturePaneText { scrollback: false };
+    send_cli_action_to_server(&session_metadata, capture_pane_text_action, client_id);
     std::thread::sleep(std::time::Duration::from_millis(100));
-    // move back to make sure the other pane is in the previous tab
-    let _ = mock_screen
-        .to_screen
-        .send(ScreenInstruction::MoveFocusLeftOrPreviousTab(1));
+    mock_screen.teardown(vec![server_thread, screen_thread]);
+    let captured_text: Vec<(Vec<String>, ClientId)> = received_server_instructions
+        .lock()
+        .unwrap()
+        .iter()
+        .filter_map(|instruction| match instruction {
+            ServerInstruction::Log(lines, client_id) => Some((lines.clone(), *client_id)),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        captured_text,
+        vec![(vec!["focused pane".to_owned()], client_id)],
+        "text of the focused pane sent back to the cli client"
+    );
+}
+
+#[test]
+fn set_tab_color_is_reported_to_plugins_and_included_in_the_layout_dump() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let client_id = 1;
+    let mut mock_screen = MockScreen::new(size);
+    let plugin_receiver = mock_screen.plugin_receiver.take().unwrap();
+    let screen_thread = mock_screen.run(None, vec![]);
+    let received_plugin_instructions = Arc::new(Mutex::new(vec![]));
+    let plugin_thread = log_actions_in_thread!(
+        received_plugin_instructions,
+        PluginInstruction::Exit,
+        plugin_receiver
+    );
+    let _ = mock_screen.to_screen.send(ScreenInstruction::SetTabColor(
+        Some(0),
+        Some("#ff8800".to_owned()),
+        client_id,
+    ));
+    // an invalid color is rejected and the tab keeps its previous one
+    let _ = mock_screen.to_screen.send(ScreenInstruction::SetTabColor(
+        Some(0),
+        Some("not-a-color".to_owned()),
+        client_id,
+    ));
     std::thread::sleep(std::time::Duration::from_millis(100));
-    // move forward to make sure the broken pane is in the previous tab
     let _ = mock_screen
         .to_screen
-        .send(ScreenInstruction::MoveFocusRightOrNextTab(1));
+        .send(ScreenInstruction::DumpLayout(None, client_id));
     std::thread::sleep(std::time::Duration::from_millis(100));
+    mock_screen.teardown(vec![plugin_thread, screen_thread]);
 
-    mock_screen.teardown(vec![server_thread, screen_thread]);
-
-    let snapshots = take_snapshots_and_cursor_coordinates_from_render_events(
-        received_server_instructions.lock().unwrap().iter(),
-        size,
+    let received_plugin_instructions = received_plugin_instructions.lock().unwrap();
+    let last_reported_tab_color = received_plugin_instructions
+        .iter()
+        .filter_map(|instruction| match instruction {
+            PluginInstruction::Update(updates) => {
+                updates.iter().find_map(|(_, _, event)| match event {
+                    Event::TabUpdate(tab_infos) => {
+                        tab_infos.first().map(|tab_info| tab_info.color.clone())
+                    },
+                    _ => None,
+                })
+            },
+            _ => None,
+        })
+        .last()
+        .unwrap();
+    assert_eq!(
+        last_reported_tab_color,
+        Some("#ff8800".to_owned()),
+        "tab color reported to plugins"
+    );
+    let session_layout_metadata = received_plugin_instructions
+        .iter()
+        .find_map(|instruction| match instruction {
+            PluginInstruction::DumpLayout(session_layout_metadata, _client_id) => {
+                Some(session_layout_metadata.clone())
+            },
+            _ => None,
+        })
+        .unwrap();
+    let layout_manifest: GlobalLayoutManifest = session_layout_metadata.into();
+    let (kdl_layout, _pane_contents) = serialize_session_layout(layout_manifest).unwrap();
+    assert!(
+        kdl_layout.contains("color=\"#ff8800\""),
+        "tab color in the layout dump"
+    );
+    let dumped_layout = Layout::from_kdl(&kdl_layout, "layout_dump".into(), None, None).unwrap();
+    assert_eq!(
+        dumped_layout.tabs[0].1.color,
+        Some("#ff8800".to_owned()),
+        "tab color parsed back from the layout dump"
     );
-    let snapshot_count = snapshots.len();
-    for (_cursor_coordinates, snapshot) in snapshots {
-        assert_snapshot!(format!("{}", snapshot));
-    }
-    assert_snapshot!(format!("{}", snapshot_count));
 }
 
 #[test]
-pub fn screen_can_break_plugin_pane_to_a_new_tab() {
-    let size = Size { cols: 80, rows: 20 };
+pub fn clients_with_their_own_theme_are_rendered_with_their_own_palette() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let client_id = 1;
+    let other_client_id = 2;
+    let mut mock_screen = MockScreen::new(size);
+    let plugin_receiver = mock_screen.plugin_receiver.take().unwrap();
+    let server_receiver = mock_screen.server_receiver.take().unwrap();
     let mut initial_layout = TiledPaneLayout::default();
-    let mut pane_to_break_free = TiledPaneLayout::default();
-    pane_to_break_free.name = Some("plugin_pane_to_break_free".to_owned());
-    pane_to_break_free.run = Some(Run::Plugin(RunPlugin {
-        _allow_exec_host_cmd: false,
-        location: RunPluginLocation::File(PathBuf::from("/path/to/fake/plugin")),
-        configuration: Default::default(),
-    }));
-    let mut pane_to_stay = TiledPaneLayout::default();
-    pane_to_stay.name = Some("pane_to_stay".to_owned());
     initial_layout.children_split_direction = SplitDirection::Vertical;
-    initial_layout.children = vec![pane_to_break_free, pane_to_stay];
-    let mut mock_screen = MockScreen::new(size);
+    initial_layout.children = vec![TiledPaneLayout::default(), TiledPaneLayout::default()];
     let screen_thread = mock_screen.run(Some(initial_layout), vec![]);
+    let received_plugin_instructions = Arc::new(Mutex::new(vec![]));
+    let plugin_thread = log_actions_in_thread!(
+        received_plugin_instructions,
+        PluginInstruction::Exit,
+        plugin_receiver
+    );
     let received_server_instructions = Arc::new(Mutex::new(vec![]));
-    let server_receiver = mock_screen.server_receiver.take().unwrap();
     let server_thread = log_actions_in_thread!(
         received_server_instructions,
         ServerInstruction::KillSession,
         server_receiver
     );
-
-    let _ = mock_screen.to_screen.send(ScreenInstruction::BreakPane(
-        Box::new(Layout::default()),
-        Default::default(),
-        1,
-    ));
-    std::thread::sleep(std::time::Duration::from_millis(100));
-    // we send ApplyLayout, because in prod this is eventually received after the message traverses
-    // through the plugin and pty threads (to open extra stuff we need in the layout, eg. the
-    // default plugins)
-    let _ = mock_screen.to_screen.send(ScreenInstruction::ApplyLayout(
-        TiledPaneLayout::default(),
-        vec![], // floating_panes_layout
-        Default::default(),
-        vec![], // floating panes ids
-        Default::default(),
-        1,
-        1,
+    let _ = mock_screen.to_screen.send(ScreenInstruction::AddClient(
+        other_client_id,
+        None,
+        None,
+        false,
+        false,
     ));
-    std::thread::sleep(std::time::Duration::from_millis(100));
-    // move back to make sure the other pane is in the previous tab
+    // the focus colors of both clients and the color behind them, so that their boundaries are
+    // drawn with the theme's color
+    let theme = |color: PaletteColor| Palette {
+        magenta: color,
+        blue: color,
+        black: color,
+        ..Default::default()
+    };
+    let first_theme = theme(PaletteColor::Rgb((11, 22, 33)));
+    let second_theme = theme(PaletteColor::Rgb((44, 55, 66)));
     let _ = mock_screen
         .to_screen
-        .send(ScreenInstruction::MoveFocusLeftOrPreviousTab(1));
-    std::thread::sleep(std::time::Duration::from_millis(100));
-    // move forward to make sure the broken pane is in the previous tab
+        .send(ScreenInstruction::SetClientTheme(client_id, first_theme));
     let _ = mock_screen
         .to_screen
-        .send(ScreenInstruction::MoveFocusRightOrNextTab(1));
+        .send(ScreenInstruction::SetClientTheme(
+            other_client_id,
+            second_theme,
+        ));
     std::thread::sleep(std::time::Duration::from_millis(100));
+    mock_screen.teardown(vec![plugin_thread, server_thread, screen_thread]);
 
-    mock_screen.teardown(vec![server_thread, screen_thread]);
+    let rendered_for_client = |for_client_id: ClientId| -> String {
+        received_server_instructions
+            .lock()
+            .unwrap()
+            .iter()
+            .filter_map(|instruction| match instruction {
+                ServerInstruction::Render(Some(output)) => output.get(&for_client_id).cloned(),
+                _ => None,
+            })
+            .collect()
+    };
+    let first_theme_color = "\u{1b}[38;2;11;22;33m";
+    let second_theme_color = "\u{1b}[38;2;44;55;66m";
+    let first_client_output = rendered_for_client(client_id);
+    let other_client_output = rendered_for_client(other_client_id);
+    assert!(
+        first_client_output.contains(first_theme_color)
+            && !first_client_output.contains(second_theme_color),
+        "first client rendered with its own palette"
+    );
+    assert!(
+        other_client_output.contains(second_theme_color)
+            && !other_client_output.contains(first_theme_color),
+        "other client rendered with its own palette"
+    );
 
-    let snapshots = take_snapshots_and_cursor_coordinates_from_render_events(
-        received_server_instructions.lock().unwrap().iter(),
-        size,
+    let last_mode_update_palette = |for_client_id: ClientId| {
+        received_plugin_instructions
+            .lock()
+            .unwrap()
+            .iter()
+            .rev()
+            .find_map(|instruction| match instruction {
+                PluginInstruction::Update(updates) => updates.iter().find_map(|u| match u {
+                    (_, Some(c_id), Event::ModeUpdate(mode_info)) if *c_id == for_client_id => {
+                        Some(mode_info.style.colors)
+                    },
+                    _ => None,
+                }),
+                _ => None,
+            })
+    };
+    assert_eq!(
+        last_mode_update_palette(client_id),
+        Some(first_theme),
+        "plugins of the first client render with its palette"
     );
-    let snapshot_count = snapshots.len();
-    for (_cursor_coordinates, snapshot) in snapshots {
-        assert_snapshot!(format!("{}", snapshot));
+    assert_eq!(
+        last_mode_update_palette(other_client_id),
+        Some(second_theme),
+        "plugins of the other client render with its palette"
+    );
+}
+
+#[test]
+fn scrolling_a_pane_with_synchronized_scroll_scrolls_the_other_panes_in_the_tab() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let client_id = 1;
+    let tab_index = 0;
+    let mut screen = create_new_screen(size);
+    new_tab(&mut screen, 1, tab_index);
+    let line_number_and_rows = |screen: &Screen, pane_id: u32| -> (usize, usize) {
+        screen
+            .tabs
+            .get(&tab_index)
+            .unwrap()
+            .get_tiled_panes()
+            .find(|(id, _)| **id == PaneId::Terminal(pane_id))
+            .map(|(_, pane)| (pane.get_line_number().unwrap(), pane.rows()))
+            .unwrap()
+    };
+    {
+        let tab = screen.get_active_tab_mut(client_id).unwrap();
+        tab.new_pane(PaneId::Terminal(2), None, None, None, Some(client_id))
+            .unwrap();
+        tab.new_pane(PaneId::Terminal(3), None, None, None, Some(client_id))
+            .unwrap();
+        let long_output: String = (0..100).map(|i| format!("line {i}\n\r")).collect();
+        tab.handle_pty_bytes(1, long_output.clone().into_bytes())
+            .unwrap();
+        tab.handle_pty_bytes(3, long_output.into_bytes()).unwrap();
     }
-    assert_snapshot!(format!("{}", snapshot_count));
+    // just a few lines more than fit in the pane, less than a page to scroll back through
+    let (_, short_pane_rows) = line_number_and_rows(&screen, 2);
+    let short_output: String = (0..short_pane_rows + 2)
+        .map(|i| format!("line {i}\n\r"))
+        .collect();
+    screen
+        .get_active_tab_mut(client_id)
+        .unwrap()
+        .handle_pty_bytes(2, short_output.into_bytes())
+        .unwrap();
+
+    let (sibling_line_number, sibling_rows) = line_number_and_rows(&screen, 1);
+    let (short_line_number, _) = line_number_and_rows(&screen, 2);
+    screen
+        .get_active_tab_mut(client_id)
+        .unwrap()
+        .scroll_active_terminal_up_page(client_id);
+    assert_eq!(
+        line_number_and_rows(&screen, 1).0,
+        sibling_line_number,
+        "other panes do not scroll without synchronized scroll"
+    );
+
+    screen.set_synchronized_scroll(tab_index, true).unwrap();
+    screen
+        .get_active_tab_mut(client_id)
+        .unwrap()
+        .scroll_active_terminal_up_page(client_id);
+    let (scrolled_sibling_line_number, _) = line_number_and_rows(&screen, 1);
+    let (scrolled_short_line_number, _) = line_number_and_rows(&screen, 2);
+    assert_eq!(
+        sibling_line_number - scrolled_sibling_line_number,
+        sibling_rows - 1,
+        "other pane scrolled back by a page of its own"
+    );
+    assert!(
+        scrolled_short_line_number < short_line_number
+            && short_line_number - scrolled_short_line_number < short_pane_rows - 1,
+        "pane with less scrollback than a page scrolled back through all of it"
+    );
+
+    screen
+        .get_active_tab_mut(client_id)
+        .unwrap()
+        .scroll_active_terminal_up_page(client_id);
+    assert_eq!(
+        line_number_and_rows(&screen, 2).0,
+        scrolled_short_line_number,
+        "pane scrolled back to the top of its scrollback stays there"
+    );
+    assert!(
+        line_number_and_rows(&screen, 1).0 < scrolled_sibling_line_number,
+        "pane with more scrollback keeps scrolling back"
+    );
 }
 
 #[test]
-pub fn screen_can_break_floating_plugin_pane_to_a_new_tab() {
-    let size = Size { cols: 80, rows: 20 };
+pub fn pane_frames_are_drawn_with_the_characters_of_the_pane_frame_style() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let mut mock_screen = MockScreen::new(size);
+    mock_screen.config_options.pane_frame_style = Some(PaneFrameStyle::UnicodeHeavy);
+    let session_metadata = mock_screen.clone_session_metadata();
+    let server_receiver = mock_screen.server_receiver.take().unwrap();
     let mut initial_layout = TiledPaneLayout::default();
-    let mut pane_to_break_free = TiledPaneLayout::default();
-    pane_to_break_free.name = Some("tiled_pane".to_owned());
-    let mut floating_pane = FloatingPaneLayout::default();
-    floating_pane.name = Some("floating_plugin_pane_to_eject".to_owned());
-    floating_pane.run = Some(Run::Plugin(RunPlugin {
-        _allow_exec_host_cmd: false,
-        location: RunPluginLocation::File(PathBuf::from("/path/to/fake/plugin")),
-        configuration: Default::default(),
-    }));
-    let mut floating_panes_layout = vec![floating_pane];
     initial_layout.children_split_direction = SplitDirection::Vertical;
-    initial_layout.children = vec![pane_to_break_free];
-    let mut mock_screen = MockScreen::new(size);
-    let screen_thread = mock_screen.run(Some(initial_layout), floating_panes_layout.clone());
+    initial_layout.children = vec![TiledPaneLayout::default(), TiledPaneLayout::default()];
+    let screen_thread = mock_screen.run(Some(initial_layout), vec![]);
     let received_server_instructions = Arc::new(Mutex::new(vec![]));
-    let server_receiver = mock_screen.server_receiver.take().unwrap();
     let server_thread = log_actions_in_thread!(
         received_server_instructions,
         ServerInstruction::KillSession,
         server_receiver
     );
-
-    let _ = mock_screen.to_screen.send(ScreenInstruction::BreakPane(
-        Box::new(Layout::default()),
-        Default::default(),
-        1,
-    ));
+    let _ = mock_screen.to_screen.send(ScreenInstruction::Render);
     std::thread::sleep(std::time::Duration::from_millis(100));
-    // we send ApplyLayout, because in prod this is eventually received after the message traverses
-    // through the plugin and pty threads (to open extra stuff we need in the layout, eg. the
-    // default plugins)
-    floating_panes_layout.get_mut(0).unwrap().already_running = true;
-    let _ = mock_screen.to_screen.send(ScreenInstruction::ApplyLayout(
-        TiledPaneLayout::default(),
-        floating_panes_layout,
-        vec![(1, None)], // tiled pane ids - send these because one needs to be created under the
-        // ejected floating pane, lest the tab be closed as having no tiled panes
-        // (this happens in prod in the pty thread)
-        vec![], // floating panes ids
-        Default::default(),
-        1,
+    let renders_with_heavy_style = received_server_instructions.lock().unwrap().len();
+    // changed at runtime
+    send_cli_action_to_server(
+        &session_metadata,
+        CliAction::SetPaneFrameStyle {
+            pane_frame_style: Some(PaneFrameStyle::Ascii),
+        },
         1,
-    ));
-    std::thread::sleep(std::time::Duration::from_millis(100));
-    // move back to make sure the other pane is in the previous tab
-    let _ = mock_screen
-        .to_screen
-        .send(ScreenInstruction::MoveFocusLeftOrPreviousTab(1));
-    std::thread::sleep(std::time::Duration::from_millis(100));
-    // move forward to make sure the broken pane is in the previous tab
-    let _ = mock_screen
-        .to_screen
-        .send(ScreenInstruction::MoveFocusRightOrNextTab(1));
+    );
     std::thread::sleep(std::time::Duration::from_millis(100));
-
     mock_screen.teardown(vec![server_thread, screen_thread]);
 
-    let snapshots = take_snapshots_and_cursor_coordinates_from_render_events(
-        received_server_instructions.lock().unwrap().iter(),
-        size,
+    let rendered = |instructions: &[ServerInstruction]| -> String {
+        instructions
+            .iter()
+            .filter_map(|instruction| match instruction {
+                ServerInstruction::Render(Some(output)) => output.get(&1).cloned(),
+                _ => None,
+            })
+            .collect()
+    };
+    let received_server_instructions = received_server_instructions.lock().unwrap();
+    let heavy_output = rendered(&received_server_instructions[..renders_with_heavy_style]);
+    let ascii_output = rendered(&received_server_instructions[renders_with_heavy_style..]);
+    for glyph in ["┏", "┓", "┗", "┛", "━", "┃"] {
+        assert!(
+            heavy_output.contains(glyph),
+            "frames drawn with heavy character {}",
+            glyph
+        );
+    }
+    assert!(
+        !heavy_output.contains('┌') && !heavy_output.contains('│'),
+        "no thin lines in heavy frames"
     );
-    let snapshot_count = snapshots.len();
-    for (_cursor_coordinates, snapshot) in snapshots {
-        assert_snapshot!(format!("{}", snapshot));
+    for glyph in ["+", "-", "|"] {
+        assert!(
+            ascii_output.contains(glyph),
+            "frames drawn with ascii character {}",
+            glyph
+        );
     }
-    assert_snapshot!(format!("{}", snapshot_count));
+    assert!(
+        !ascii_output.contains('┏') && !ascii_output.contains('━') && !ascii_output.contains('┃'),
+        "frames redrawn without heavy characters once the style changes"
+    );
+}
+
+fn last_snapshot_of_tab(screen: &mut Screen, tab_index: usize, size: Size) -> String {
+    // what the client would see if this is its active tab
+    screen.update_pane_aliases().unwrap();
+    let mut output = Output::new(
+        screen.sixel_image_store.clone(),
+        screen.character_cell_size.clone(),
+        true,
+    );
+    screen
+        .tabs
+        .get_mut(&tab_index)
+        .unwrap()
+        .render(&mut output)
+        .unwrap();
+    let render = ServerInstruction::Render(Some(output.serialize().unwrap()));
+    let snapshots = take_snapshots_and_cursor_coordinates_from_render_events([render].iter(), size);
+    snapshots.last().unwrap().1.clone()
 }
 
 #[test]
-pub fn screen_can_move_pane_to_a_new_tab_right() {
-    let size = Size { cols: 80, rows: 20 };
-    let mut initial_layout = TiledPaneLayout::default();
-    let mut pane_to_break_free = TiledPaneLayout::default();
-    pane_to_break_free.name = Some("pane_to_break_free".to_owned());
-    let mut pane_to_stay = TiledPaneLayout::default();
-    pane_to_stay.name = Some("pane_to_stay".to_owned());
-    initial_layout.children_split_direction = SplitDirection::Vertical;
-    initial_layout.children = vec![pane_to_break_free, pane_to_stay];
-    let mut mock_screen = MockScreen::new(size);
-    let screen_thread = mock_screen.run(Some(initial_layout), vec![]);
-    let received_server_instructions = Arc::new(Mutex::new(vec![]));
-    let server_receiver = mock_screen.server_receiver.take().unwrap();
-    let server_thread = log_actions_in_thread!(
-        received_server_instructions,
-        ServerInstruction::KillSession,
-        server_receiver
+fn pane_alias_shows_the_output_of_the_pane_it_mirrors() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let client_id = 1;
+    let source_tab_index = 0;
+    let alias_tab_index = 1;
+    let mut screen = create_new_screen(size);
+    new_tab(&mut screen, 1, source_tab_index);
+    new_tab(&mut screen, 2, alias_tab_index);
+    screen
+        .tabs
+        .get_mut(&source_tab_index)
+        .unwrap()
+        .handle_pty_bytes(1, "output before the alias\n\r".as_bytes().to_vec())
+        .unwrap();
+
+    screen.new_pane_alias(3, 1, client_id).unwrap();
+    screen
+        .tabs
+        .get_mut(&source_tab_index)
+        .unwrap()
+        .handle_pty_bytes(
+            1,
+            // a full screen application drawing at absolute positions
+            "\u{1b}[?1049h\u{1b}[2J\u{1b}[5;10Hdrawn at row 5"
+                .as_bytes()
+                .to_vec(),
+        )
+        .unwrap();
+
+    assert!(
+        screen
+            .tabs
+            .get(&alias_tab_index)
+            .unwrap()
+            .get_tiled_panes()
+            .any(|(id, pane)| *id == PaneId::Terminal(3) && pane.alias_of() == Some(1)),
+        "alias opened in the active tab of the client"
+    );
+    let alias_snapshot = last_snapshot_of_tab(&mut screen, alias_tab_index, size);
+    // each line of the snapshot starts with eg. "04 (C): "
+    let screen_rows: Vec<&str> = alias_snapshot.lines().map(|line| &line[8..]).collect();
+    let alias = screen
+        .tabs
+        .get(&alias_tab_index)
+        .unwrap()
+        .get_pane_with_id(PaneId::Terminal(3))
+        .unwrap();
+    let drawn_row = screen_rows
+        .iter()
+        .position(|row| row.contains("drawn at row 5"))
+        .expect("alias shows the current output of the pane it mirrors");
+    assert_eq!(
+        (
+            drawn_row,
+            screen_rows[drawn_row].find("drawn at row 5").unwrap()
+        ),
+        (alias.get_content_y() + 4, alias.get_content_x() + 9),
+        "alias shows the grid of the pane it mirrors at the same position in it"
     );
+}
 
-    let _ = mock_screen.to_screen.send(ScreenInstruction::BreakPane(
-        Box::new(Layout::default()),
-        Default::default(),
-        1,
-    ));
-    std::thread::sleep(std::time::Duration::from_millis(100));
-    let _ = mock_screen
-        .to_screen
-        .send(ScreenInstruction::MoveFocusLeftOrPreviousTab(1));
-    std::thread::sleep(std::time::Duration::from_millis(100));
-    let _ = mock_screen
-        .to_screen
-        .send(ScreenInstruction::BreakPaneRight(1));
-    std::thread::sleep(std::time::Duration::from_millis(100));
+#[test]
+fn pane_alias_is_closed_with_the_pane_it_mirrors() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let client_id = 1;
+    let source_tab_index = 0;
+    let alias_tab_index = 1;
+    let mut screen = create_new_screen(size);
+    new_tab(&mut screen, 1, source_tab_index);
+    new_tab(&mut screen, 2, alias_tab_index);
+    screen.new_pane_alias(3, 1, client_id).unwrap();
 
-    mock_screen.teardown(vec![server_thread, screen_thread]);
+    screen
+        .tabs
+        .get_mut(&source_tab_index)
+        .unwrap()
+        .close_pane(PaneId::Terminal(1), false, None);
+    screen.update_pane_aliases().unwrap();
 
-    let snapshots = take_snapshots_and_cursor_coordinates_from_render_events(
-        received_server_instructions.lock().unwrap().iter(),
-        size,
+    assert!(
+        screen
+            .tabs
+            .get(&alias_tab_index)
+            .unwrap()
+            .get_pane_with_id(PaneId::Terminal(3))
+            .is_none(),
+        "alias closed once the pane it mirrors is gone, so that its input does not go to a pane \
+         that gets the same id later"
     );
-    let snapshot_count = snapshots.len();
-    for (_cursor_coordinates, snapshot) in snapshots {
-        assert_snapshot!(format!("{}", snapshot));
-    }
-    assert_snapshot!(format!("{}", snapshot_count));
 }
 
 #[test]
-pub fn screen_can_move_pane_to_a_new_tab_left() {
-    let size = Size { cols: 80, rows: 20 };
-    let mut initial_layout = TiledPaneLayout::default();
-    let mut pane_to_break_free = TiledPaneLayout::default();
-    pane_to_break_free.name = Some("pane_to_break_free".to_owned());
-    let mut pane_to_stay = TiledPaneLayout::default();
-    pane_to_stay.name = Some("pane_to_stay".to_owned());
-    initial_layout.children_split_direction = SplitDirection::Vertical;
-    initial_layout.children = vec![pane_to_break_free, pane_to_stay];
-    let mut mock_screen = MockScreen::new(size);
-    let screen_thread = mock_screen.run(Some(initial_layout), vec![]);
-    let received_server_instructions = Arc::new(Mutex::new(vec![]));
-    let server_receiver = mock_screen.server_receiver.take().unwrap();
-    let server_thread = log_actions_in_thread!(
-        received_server_instructions,
-        ServerInstruction::KillSession,
-        server_receiver
+fn connected_client_id() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let client_id = 1;
+    let cli_client_id = 10; // fake client id should not appear in the screen's state
+    let mut screen = create_new_screen(size);
+    new_tab(&mut screen, 1, 0);
+
+    assert_eq!(
+        screen.connected_client_id(client_id),
+        client_id,
+        "connected client stands for itself"
     );
+    assert_eq!(
+        screen.connected_client_id(cli_client_id),
+        client_id,
+        "client that is not connected to the screen stands for the first connected client"
+    );
+}
 
-    let _ = mock_screen.to_screen.send(ScreenInstruction::BreakPane(
-        Box::new(Layout::default()),
-        Default::default(),
+#[test]
+fn tab_index_or_active_tab() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let client_id = 1;
+    let cli_client_id = 10; // fake client id should not appear in the screen's state
+    let mut screen = create_new_screen(size);
+    new_tab(&mut screen, 1, 0);
+    new_tab(&mut screen, 2, 1);
+    screen.switch_tab_prev(None, true, client_id).expect("TEST");
+
+    assert_eq!(
+        screen.tab_index_or_active_tab(Some(1), client_id).unwrap(),
         1,
-    ));
-    std::thread::sleep(std::time::Duration::from_millis(100));
-    let _ = mock_screen
-        .to_screen
-        .send(ScreenInstruction::MoveFocusLeftOrPreviousTab(1));
-    std::thread::sleep(std::time::Duration::from_millis(100));
+        "given tab index used as is"
+    );
+    assert_eq!(
+        screen.tab_index_or_active_tab(None, client_id).unwrap(),
+        0,
+        "active tab of the client"
+    );
+    assert_eq!(
+        screen.tab_index_or_active_tab(None, cli_client_id).unwrap(),
+        0,
+        "client that is not connected to the screen gets the active tab of the first connected \
+         client"
+    );
+}
+
+#[test]
+fn plugin_fed_pane_that_does_not_fit_releases_its_terminal_id() {
+    // too small for a second pane
+    let size = Size { cols: 8, rows: 4 };
+    let client_id = 1;
+    let plugin_id = 10;
+    let mut mock_screen = MockScreen::new(size);
+    let pty_receiver = mock_screen.pty_receiver.take().unwrap();
+    let screen_thread = mock_screen.run(None, vec![]);
+    let received_pty_instructions = Arc::new(Mutex::new(vec![]));
+    let pty_thread = log_actions_in_thread!(
+        received_pty_instructions,
+        PtyInstruction::Exit,
+        pty_receiver
+    );
     let _ = mock_screen
         .to_screen
-        .send(ScreenInstruction::BreakPaneLeft(1));
+        .send(ScreenInstruction::NewPluginFedPane(
+            2,
+            Some("plugin output".to_owned()),
+            plugin_id,
+            client_id,
+        ));
     std::thread::sleep(std::time::Duration::from_millis(100));
-
-    mock_screen.teardown(vec![server_thread, screen_thread]);
-
-    let snapshots = take_snapshots_and_cursor_coordinates_from_render_events(
-        received_server_instructions.lock().unwrap().iter(),
-        size,
+    let screen_thread_exited = screen_thread.is_finished();
+    mock_screen.teardown(vec![pty_thread, screen_thread]);
+    assert!(!screen_thread_exited, "the session survives the failure");
+    let terminal_id_released = received_pty_instructions
+        .lock()
+        .unwrap()
+        .iter()
+        .any(|instruction| matches!(instruction, PtyInstruction::ClosePane(PaneId::Terminal(2))));
+    assert!(
+        terminal_id_released,
+        "the terminal id reserved for the pane is released"
     );
-    let snapshot_count = snapshots.len();
-    for (_cursor_coordinates, snapshot) in snapshots {
-        assert_snapshot!(format!("{}", snapshot));
-    }
-    assert_snapshot!(format!("{}", snapshot_count));
+    let error_logged = mock_screen
+        .os_input
+        .server_to_client_messages
+        .lock()
+        .unwrap()
+        .get(&client_id)
+        .map(|messages| {
+            messages
+                .iter()
+                .any(|message| matches!(message, ServerToClientMsg::LogError(..)))
+        })
+        .unwrap_or(false);
+    assert!(error_logged, "the client is told the pane was not opened");
 }
@@ -0,0 +1,226 @@
+use super::{list_live_sessions, rename_session};
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, Instant};
+use zellij_utils::{
+    data::{SessionInfo, TabInfo},
+    interprocess::local_socket::LocalSocketListener,
+    ipc::{
+        ClientToServerMsg, IpcReceiverWithContext, IpcSenderWithContext, ServerToClientMsg,
+        SessionListing,
+    },
+    tempfile::tempdir,
+};
+
+fn session_info_file(session_info_dir: &Path, session_name: &str) -> PathBuf {
+    session_info_dir
+        .join(session_name)
+        .join("session-metadata.kdl")
+}
+
+fn write_session_info(
+    session_info_dir: &Path,
+    session_name: &str,
+    connected_clients: usize,
+    observer_clients: usize,
+    tab_names: &[&str],
+) {
+    let session_info = SessionInfo {
+        name: session_name.to_owned(),
+        tabs: tab_names
+            .iter()
+            .enumerate()
+            .map(|(position, name)| TabInfo {
+                position,
+                name: name.to_string(),
+                ..Default::default()
+            })
+            .collect(),
+        connected_clients,
+        observer_clients,
+        ..Default::default()
+    };
+    let session_info_file = session_info_file(session_info_dir, session_name);
+    std::fs::create_dir_all(session_info_file.parent().unwrap()).unwrap();
+    std::fs::write(session_info_file, session_info.to_string()).unwrap();
+}
+
+fn start_mock_session(sock_dir: &Path, session_name: &str) {
+    // answers the connection status query of a single listing, like a live session would
+    let listener = LocalSocketListener::bind(sock_dir.join(session_name)).unwrap();
+    thread::spawn(move || {
+        if let Some(Ok(stream)) = listener.incoming().next() {
+            let mut receiver: IpcReceiverWithContext<ClientToServerMsg> =
+                IpcReceiverWithContext::new(stream);
+            if let Some((ClientToServerMsg::ConnStatus, _)) = receiver.recv() {
+                let mut sender: IpcSenderWithContext<ServerToClientMsg> = receiver.get_sender();
+                let _ = sender.send(ServerToClientMsg::Connected);
+            }
+        }
+    });
+}
+
+fn leave_stale_session_socket(sock_dir: &Path, session_name: &str) {
+    // the socket file stays behind after its listener is gone, as with a session that crashed
+    let listener = LocalSocketListener::bind(sock_dir.join(session_name)).unwrap();
+    drop(listener);
+}
+
+fn start_stuck_session(sock_dir: &Path, session_name: &str) {
+    // accepts connections but never answers them, like a session whose server is stuck
+    let listener = LocalSocketListener::bind(sock_dir.join(session_name)).unwrap();
+    thread::spawn(move || {
+        let _stream = listener.incoming().next();
+        thread::sleep(Duration::from_secs(10));
+    });
+}
+
+#[test]
+fn lists_live_sessions_with_their_clients_and_tabs() {
+    let sock_dir = tempdir().unwrap();
+    let session_info_dir = tempdir().unwrap();
+    start_mock_session(sock_dir.path(), "first-session");
+    write_session_info(
+        session_info_dir.path(),
+        "first-session",
+        2,
+        1,
+        &["editor", "logs"],
+    );
+    start_mock_session(sock_dir.path(), "second-session");
+    write_session_info(session_info_dir.path(), "second-session", 0, 0, &["Tab #1"]);
+    leave_stale_session_socket(sock_dir.path(), "stale-session");
+    write_session_info(session_info_dir.path(), "stale-session", 1, 0, &["Tab #1"]);
+
+    let mut session_list = list_live_sessions(sock_dir.path(), |session_name| {
+        session_info_file(session_info_dir.path(), session_name)
+    });
+    // we don't control the creation time of the sockets, so we only compare the rest
+    for session in session_list.iter_mut() {
+        session.created = 0;
+    }
+    session_list.sort_by(|a, b| a.name.cmp(&b.name));
+    assert_eq!(
+        session_list,
+        vec![
+            SessionListing {
+                name: "first-session".to_owned(),
+                created: 0,
+                attached_clients: 2,
+                observer_clients: 1,
+                tabs: vec!["editor".to_owned(), "logs".to_owned()],
+            },
+            SessionListing {
+                name: "second-session".to_owned(),
+                created: 0,
+                attached_clients: 0,
+                observer_clients: 0,
+                tabs: vec!["Tab #1".to_owned()],
+            },
+        ],
+        "live sessions are listed with their clients and tabs, stale sockets are left out"
+    );
+}
+
+#[test]
+fn lists_live_session_without_session_info() {
+    let sock_dir = tempdir().unwrap();
+    let session_info_dir = tempdir().unwrap();
+    start_mock_session(sock_dir.path(), "new-session");
+
+    let session_list = list_live_sessions(sock_dir.path(), |session_name| {
+        session_info_file(session_info_dir.path(), session_name)
+    });
+    assert_eq!(session_list.len(), 1, "live session is listed");
+    assert_eq!(session_list[0].name, "new-session");
+    assert_eq!(session_list[0].attached_clients, 0);
+    assert_eq!(session_list[0].observer_clients, 0);
+    assert!(session_list[0].tabs.is_empty());
+}
+
+#[test]
+fn stuck_session_does_not_hang_the_listing() {
+    let sock_dir = tempdir().unwrap();
+    let session_info_dir = tempdir().unwrap();
+    start_mock_session(sock_dir.path(), "live-session");
+    start_stuck_session(sock_dir.path(), "stuck-session");
+
+    let listing_started = Instant::now();
+    let session_list = list_live_sessions(sock_dir.path(), |session_name| {
+        session_info_file(session_info_dir.path(), session_name)
+    });
+    assert!(
+        listing_started.elapsed() < Duration::from_secs(5),
+        "listing returned without waiting for the stuck session"
+    );
+    let session_names: Vec<&str> = session_list
+        .iter()
+        .map(|session| session.name.as_str())
+        .collect();
+    assert_eq!(
+        session_names,
+        vec!["live-session"],
+        "stuck session is left out"
+    );
+}
+
+#[test]
+fn listing_a_missing_socket_dir_is_empty() {
+    let sock_dir = tempdir().unwrap();
+    let session_list = list_live_sessions(&sock_dir.path().join("missing"), |session_name| {
+        PathBuf::from(session_name)
+    });
+    assert!(session_list.is_empty());
+}
+
+#[test]
+fn renaming_a_session_renames_its_socket_and_session_info() {
+    let sock_dir = tempdir().unwrap();
+    let session_info_dir = tempdir().unwrap();
+    start_mock_session(sock_dir.path(), "generic-name");
+    write_session_info(session_info_dir.path(), "generic-name", 1, 0, &["editor"]);
+
+    rename_session(
+        sock_dir.path(),
+        |session_name| session_info_dir.path().join(session_name),
+        "generic-name",
+        "my-project",
+    )
+    .unwrap();
+
+    assert!(!sock_dir.path().join("generic-name").exists());
+    assert!(!session_info_dir.path().join("generic-name").exists());
+    let session_list = list_live_sessions(sock_dir.path(), |session_name| {
+        session_info_file(session_info_dir.path(), session_name)
+    });
+    assert_eq!(session_list.len(), 1, "renamed session is still live");
+    assert_eq!(session_list[0].name, "my-project");
+    assert_eq!(session_list[0].tabs, vec!["editor".to_owned()]);
+}
+
+#[test]
+fn renaming_a_session_to_the_name_of_another_session_fails() {
+    let sock_dir = tempdir().unwrap();
+    let session_info_dir = tempdir().unwrap();
+    leave_stale_session_socket(sock_dir.path(), "generic-name");
+    leave_stale_session_socket(sock_dir.path(), "live-session");
+    write_session_info(
+        session_info_dir.path(),
+        "resurrectable-session",
+        0,
+        0,
+        &["Tab #1"],
+    );
+
+    for taken_session_name in ["live-session", "resurrectable-session"] {
+        let renamed = rename_session(
+            sock_dir.path(),
+            |session_name| session_info_dir.path().join(session_name),
+            "generic-name",
+            taken_session_name,
+        );
+        assert!(renamed.is_err(), "{} is taken", taken_session_name);
+    }
+    assert!(sock_dir.path().join("generic-name").exists());
+    assert!(sock_dir.path().join("live-session").exists());
+}
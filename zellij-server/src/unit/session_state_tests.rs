@@ -0,0 +1,88 @@
+use super::SessionState;
+use std::time::{Duration, Instant};
+use zellij_utils::pane_size::Size;
+
+#[test]
+fn clients_idle_beyond_the_timeout_are_detached_while_the_session_persists() {
+    let idle_timeout = Duration::from_secs(60);
+    let mut session_state = SessionState::new();
+    let idle_client_id = session_state.new_client();
+    let active_client_id = session_state.new_client();
+    let observer_client_id = session_state.new_client();
+    session_state.set_client_observer(observer_client_id);
+
+    // simulate the clients having been attached for longer than the timeout, with only one of
+    // them sending input in the meantime
+    let now = Instant::now() + idle_timeout + Duration::from_secs(1);
+    session_state.record_client_input(active_client_id, now - Duration::from_secs(10));
+    assert!(
+        session_state
+            .idle_clients(idle_timeout, now - idle_timeout)
+            .is_empty(),
+        "no client is idle before the timeout"
+    );
+
+    let idle_client_ids = session_state.idle_clients(idle_timeout, now);
+    assert_eq!(
+        idle_client_ids,
+        vec![idle_client_id],
+        "only the client that sent no input is idle, observers never are"
+    );
+    for client_id in idle_client_ids {
+        session_state.remove_client(client_id);
+    }
+    let mut remaining_client_ids = session_state.client_ids();
+    remaining_client_ids.sort_unstable();
+    assert_eq!(
+        remaining_client_ids,
+        vec![active_client_id, observer_client_id],
+        "the session keeps its other clients"
+    );
+    assert!(
+        session_state.idle_clients(idle_timeout, now).is_empty(),
+        "a detached client is not detached again"
+    );
+}
+
+#[test]
+fn input_resets_the_idle_timer() {
+    let idle_timeout = Duration::from_secs(60);
+    let mut session_state = SessionState::new();
+    let client_id = session_state.new_client();
+    let now = Instant::now() + idle_timeout * 2;
+    assert_eq!(
+        session_state.idle_clients(idle_timeout, now),
+        vec![client_id]
+    );
+    session_state.record_client_input(client_id, now);
+    assert!(session_state.idle_clients(idle_timeout, now).is_empty());
+}
+
+#[test]
+fn observers_do_not_shrink_the_session() {
+    let mut session_state = SessionState::new();
+    let client_id = session_state.new_client();
+    let observer_client_id = session_state.new_client();
+    session_state.set_client_observer(observer_client_id);
+    session_state.set_client_size(observer_client_id, Size { rows: 10, cols: 40 });
+    assert_eq!(
+        session_state.min_client_terminal_size(),
+        Some(Size { rows: 10, cols: 40 }),
+        "the observer is sized to while it is the only sized client"
+    );
+    session_state.set_client_size(
+        client_id,
+        Size {
+            rows: 20,
+            cols: 121,
+        },
+    );
+    assert_eq!(
+        session_state.min_client_terminal_size(),
+        Some(Size {
+            rows: 20,
+            cols: 121
+        }),
+        "the smaller observer is left out once another client is sized"
+    );
+}
@@ -40,6 +40,19 @@ pub trait ZellijPlugin: Default {
     /// Will be called either after an `update` that requested it, or when the plugin otherwise needs to be re-rendered (eg. on startup, or when the plugin is resized).
     /// The `rows` and `cols` values represent the "content size" of the plugin (this will not include its surrounding frame if the user has pane frames enabled).
     fn render(&mut self, rows: usize, cols: usize) {}
+    /// Will be called when the session is serialized (eg. so that it can later be resurrected).
+    /// The returned bytes are stored along with the session and sent back to the plugin as an
+    /// [`Event::RestoreState`](prelude::Event::RestoreState) before its first render when the
+    /// session is resurrected (the plugin should [`subscribe`](shim::subscribe) to it in `load`).
+    /// Returning an empty `Vec` (the default) stores nothing.
+    fn serialize_state(&self) -> Vec<u8> {
+        vec![]
+    }
+    /// Will be called after each render for every status-bar segment this plugin registered with
+    /// [`register_status_segment`](shim::register_status_segment), with the id it was registered
+    /// with. The segment's content is whatever the plugin prints to STDOUT here (it should fit on
+    /// a single line).
+    fn render_status_segment(&mut self, render_callback_id: u32) {}
 }
 
 /// This trait is used to create workers. Workers can be used by plugins to run longer running
@@ -59,8 +72,9 @@ pub trait ZellijPlugin: Default {
 #[allow(unused_variables)]
 pub trait ZellijWorker<'de>: Default + Serialize + Deserialize<'de> {
     /// Triggered whenever the plugin sends the worker a message using the
-    /// [`post_message_to`](shim::post_message_to) method.
-    fn on_message(&mut self, message: String, payload: String) {}
+    /// [`post_message_to`](shim::post_message_to) method. The payload is passed as is, so it can
+    /// hold arbitrary (eg. serialized) bytes.
+    fn on_message(&mut self, message: String, payload: Vec<u8>) {}
 }
 
 pub const PLUGIN_MISMATCH: &str =
@@ -143,6 +157,23 @@ macro_rules! register_plugin {
             });
         }
 
+        #[no_mangle]
+        pub fn render_status_segment(render_callback_id: i32) {
+            STATE.with(|state| {
+                state
+                    .borrow_mut()
+                    .render_status_segment(render_callback_id as u32);
+            });
+        }
+
+        #[no_mangle]
+        pub fn serialize_state() {
+            STATE.with(|state| {
+                let state = state.borrow().serialize_state();
+                $crate::shim::object_to_stdout(&state);
+            });
+        }
+
         #[no_mangle]
         pub fn plugin_version() {
             println!("{}", $crate::prelude::VERSION);
@@ -161,7 +192,7 @@ macro_rules! register_plugin {
 /// pub struct FileSearchWorker {}
 ///
 /// impl ZellijWorker<'_> for FileSearchWorker {
-///     fn on_message(&mut self, message: String, payload: String) {
+///     fn on_message(&mut self, message: String, payload: Vec<u8>) {
 ///         // ...
 ///     }
 /// }
@@ -7,7 +7,10 @@ use std::{
 use zellij_utils::data::*;
 use zellij_utils::errors::prelude::*;
 pub use zellij_utils::plugin_api;
-use zellij_utils::plugin_api::plugin_command::ProtobufPluginCommand;
+use zellij_utils::plugin_api::event::{ProtobufKeybindings, ProtobufSessionLayout};
+use zellij_utils::plugin_api::plugin_command::{
+    PluginFileResult, ProtobufPluginCommand, ReadClipboardResult,
+};
 use zellij_utils::plugin_api::plugin_ids::{ProtobufPluginIds, ProtobufZellijVersion};
 
 pub use super::ui_components::*;
@@ -24,6 +27,18 @@ pub fn subscribe(event_types: &[EventType]) {
     unsafe { host_run_plugin_command() };
 }
 
+/// Subscribe to a list of [`Event`]s like [`subscribe`], only receiving the FileSystem events
+/// of paths that match at least one of the given globs (eg. `*.rs`). Paths are matched as the
+/// plugin sees them, under `/host`. Replaces any previous filter, an empty list removes it.
+pub fn subscribe_with_file_system_filter(event_types: &[EventType], path_globs: &[&str]) {
+    let event_types: HashSet<EventType> = event_types.iter().cloned().collect();
+    let path_globs = path_globs.iter().map(|p| p.to_string()).collect();
+    let plugin_command = PluginCommand::SubscribeWithFileSystemFilter(event_types, path_globs);
+    let protobuf_plugin_command: ProtobufPluginCommand = plugin_command.try_into().unwrap();
+    object_to_stdout(&protobuf_plugin_command.encode_to_vec());
+    unsafe { host_run_plugin_command() };
+}
+
 /// Unsubscribe to a list of [`Event`]s represented by their [`EventType`]s.
 pub fn unsubscribe(event_types: &[EventType]) {
     let event_types: HashSet<EventType> = event_types.iter().cloned().collect();
@@ -243,6 +258,17 @@ pub fn show_self(should_float_if_hidden: bool) {
     unsafe { host_run_plugin_command() };
 }
 
+/// Bring the plugin pane to the foreground for every connected client: switch all of them to its
+/// tab and focus it, showing it as a floating pane if it is hidden (`show_self` only does this for
+/// the plugin's own client). Requires the `FocusSelf` permission. Calls made less than a second
+/// after the previous one are ignored.
+pub fn focus_self() {
+    let plugin_command = PluginCommand::FocusSelf;
+    let protobuf_plugin_command: ProtobufPluginCommand = plugin_command.try_into().unwrap();
+    object_to_stdout(&protobuf_plugin_command.encode_to_vec());
+    unsafe { host_run_plugin_command() };
+}
+
 /// Switch to the specified Input Mode (eg. `Normal`, `Tab`, `Pane`)
 pub fn switch_to_input_mode(mode: &InputMode) {
     let plugin_command = PluginCommand::SwitchToMode(*mode);
@@ -710,6 +736,269 @@ pub fn rename_session(name: &str) {
     unsafe { host_run_plugin_command() };
 }
 
+/// Read a file from this plugin's persistent data folder, which survives across sessions. The
+/// path is relative to this folder and may not leave it.
+pub fn plugin_read_file<P: AsRef<Path>>(path: P) -> Result<Vec<u8>, String> {
+    let plugin_command = PluginCommand::PluginReadFile(path.as_ref().to_path_buf());
+    let protobuf_plugin_command: ProtobufPluginCommand = plugin_command.try_into().unwrap();
+    object_to_stdout(&protobuf_plugin_command.encode_to_vec());
+    unsafe { host_run_plugin_command() };
+    let plugin_file_result =
+        PluginFileResult::decode(bytes_from_stdin().unwrap().as_slice()).unwrap();
+    match plugin_file_result.error {
+        Some(error) => Err(error),
+        None => Ok(plugin_file_result.contents),
+    }
+}
+
+/// Write a file to this plugin's persistent data folder, which survives across sessions. The
+/// path is relative to this folder and may not leave it. Missing parent folders are created.
+pub fn plugin_write_file<P: AsRef<Path>>(path: P, contents: &[u8]) -> Result<(), String> {
+    let plugin_command =
+        PluginCommand::PluginWriteFile(path.as_ref().to_path_buf(), contents.to_vec());
+    let protobuf_plugin_command: ProtobufPluginCommand = plugin_command.try_into().unwrap();
+    object_to_stdout(&protobuf_plugin_command.encode_to_vec());
+    unsafe { host_run_plugin_command() };
+    let plugin_file_result =
+        PluginFileResult::decode(bytes_from_stdin().unwrap().as_slice()).unwrap();
+    match plugin_file_result.error {
+        Some(error) => Err(error),
+        None => Ok(()),
+    }
+}
+
+/// Request whether the session is shared with other clients or its output is being recorded. The
+/// answer arrives as a `SessionSharingStatus` event, which is also sent whenever this changes.
+pub fn request_session_sharing_status() {
+    let plugin_command = PluginCommand::RequestSessionSharingStatus;
+    let protobuf_plugin_command: ProtobufPluginCommand = plugin_command.try_into().unwrap();
+    object_to_stdout(&protobuf_plugin_command.encode_to_vec());
+    unsafe { host_run_plugin_command() };
+}
+
+/// Copy text to the system clipboard, the same way a selection is copied (using the configured
+/// `copy_command` or OSC52). Plugins are sent a `SystemClipboardFailure` event if this fails.
+pub fn copy_to_clipboard(text: &str) {
+    let plugin_command = PluginCommand::CopyToClipboard(text.to_owned());
+    let protobuf_plugin_command: ProtobufPluginCommand = plugin_command.try_into().unwrap();
+    object_to_stdout(&protobuf_plugin_command.encode_to_vec());
+    unsafe { host_run_plugin_command() };
+}
+
+/// Returns the contents of the clipboard as printed by the `paste_command` option, or `None` if
+/// that option is not set (the OSC 52 and copy command backends cannot be read) or the command
+/// fails. Requires the `ReadClipboard` permission (distinct from `WriteToClipboard`), without it
+/// this also returns `None`.
+pub fn read_clipboard() -> Option<String> {
+    let plugin_command = PluginCommand::ReadClipboard;
+    let protobuf_plugin_command: ProtobufPluginCommand = plugin_command.try_into().unwrap();
+    object_to_stdout(&protobuf_plugin_command.encode_to_vec());
+    unsafe { host_run_plugin_command() };
+    // nothing is written back if the permission was denied
+    bytes_from_stdin()
+        .ok()
+        .and_then(|bytes| ReadClipboardResult::decode(bytes.as_slice()).ok())
+        .and_then(|read_clipboard_result| read_clipboard_result.contents)
+}
+
+/// Returns the tabs and panes of the current session as of now, the same ones most recently sent
+/// in the `TabUpdate` and `PaneUpdate` events. This is useful to know the state of the session
+/// when the plugin starts, without waiting for these events. Requires the
+/// `ReadApplicationState` permission.
+pub fn get_session_layout() -> SessionLayout {
+    let plugin_command = PluginCommand::GetSessionLayout;
+    let protobuf_plugin_command: ProtobufPluginCommand = plugin_command.try_into().unwrap();
+    object_to_stdout(&protobuf_plugin_command.encode_to_vec());
+    unsafe { host_run_plugin_command() };
+    let protobuf_session_layout =
+        ProtobufSessionLayout::decode(bytes_from_stdin().unwrap().as_slice()).unwrap();
+    SessionLayout::try_from(protobuf_session_layout).unwrap()
+}
+
+/// Returns the keybindings of the client this plugin runs for, as they are now: for each input
+/// mode, the actions each key triggers. These are the same ones sent in the latest `ModeUpdate`
+/// event, so they reflect the user's configuration rather than the defaults (see
+/// [`Keybindings::keys_for_actions`] to find the keys to render in a hint). Requires the
+/// `ReadApplicationState` permission.
+pub fn get_keybindings() -> Keybindings {
+    let plugin_command = PluginCommand::GetKeybindings;
+    let protobuf_plugin_command: ProtobufPluginCommand = plugin_command.try_into().unwrap();
+    object_to_stdout(&protobuf_plugin_command.encode_to_vec());
+    unsafe { host_run_plugin_command() };
+    let protobuf_keybindings =
+        ProtobufKeybindings::decode(bytes_from_stdin().unwrap().as_slice()).unwrap();
+    Keybindings::try_from(protobuf_keybindings).unwrap()
+}
+
+/// Send part of this plugin's frame instead of printing it, for very large renders that would
+/// otherwise be sent all at once. Call it from `render` with the same `frame_id` for all chunks
+/// of a frame, setting `is_last` on the final one: the frame is shown once it is complete, and a
+/// chunk with a new `frame_id` drops the chunks of an incomplete frame, as does a frame growing
+/// past 16MiB. Anything printed in a `render` that called this is ignored.
+pub fn render_chunk(frame_id: u64, chunk: &[u8], is_last: bool) {
+    let plugin_command = PluginCommand::RenderChunk(frame_id, chunk.to_vec(), is_last);
+    let protobuf_plugin_command: ProtobufPluginCommand = plugin_command.try_into().unwrap();
+    object_to_stdout(&protobuf_plugin_command.encode_to_vec());
+    unsafe { host_run_plugin_command() };
+}
+
+/// Open a terminal pane owned by this plugin in `cwd` (relative to the plugin's cwd), running
+/// `command` or the default shell. Once the pane is open, its id is sent to the plugin with a
+/// `TerminalPaneOpened` event, and can be used to write to or close the pane later on. Requires
+/// the `OpenTerminalsOrPlugins` permission, or `RunCommands` if a command is given.
+pub fn open_terminal_pane<P: AsRef<Path>>(cwd: P, command: Option<CommandToRun>) {
+    let plugin_command = PluginCommand::OpenTerminalPane(cwd.as_ref().to_path_buf(), command);
+    let protobuf_plugin_command: ProtobufPluginCommand = plugin_command.try_into().unwrap();
+    object_to_stdout(&protobuf_plugin_command.encode_to_vec());
+    unsafe { host_run_plugin_command() };
+}
+
+/// Close a terminal pane previously opened by this plugin with `open_terminal_pane`. Panes the
+/// plugin did not open, or that already closed, are left alone. Requires the `OpenTerminalsOrPlugins` permission.
+pub fn close_pane(pane_id: u32) {
+    let plugin_command = PluginCommand::ClosePane(pane_id);
+    let protobuf_plugin_command: ProtobufPluginCommand = plugin_command.try_into().unwrap();
+    object_to_stdout(&protobuf_plugin_command.encode_to_vec());
+    unsafe { host_run_plugin_command() };
+}
+
+/// Log a message to this plugin's log sink, configured with `log_to` in the layout: the server
+/// log (the default), a `log_file` or the plugin's own pane. Messages printed to STDERR are sent
+/// to the same place.
+pub fn log_message(message: &str) {
+    let plugin_command = PluginCommand::LogMessage(message.to_owned());
+    let protobuf_plugin_command: ProtobufPluginCommand = plugin_command.try_into().unwrap();
+    object_to_stdout(&protobuf_plugin_command.encode_to_vec());
+    unsafe { host_run_plugin_command() };
+}
+
+/// Open an `http`, `https` or `mailto` URL with the system opener (eg. `xdg-open` or `open`),
+/// usually in the user's browser. If the URL is of another kind or opening it fails, the plugin
+/// receives a `SystemOpenerFailure` event. Requires the `OpenUrls` permission.
+pub fn open_url(url: &str) {
+    let plugin_command = PluginCommand::OpenUrl(url.to_owned());
+    let protobuf_plugin_command: ProtobufPluginCommand = plugin_command.try_into().unwrap();
+    object_to_stdout(&protobuf_plugin_command.encode_to_vec());
+    unsafe { host_run_plugin_command() };
+}
+
+/// Open a file (relative to the plugin's working directory) with the system opener (eg.
+/// `xdg-open` or `open`) rather than in a Zellij pane. If the file does not exist or cannot be
+/// opened, the plugin receives a `SystemOpenerFailure` event. Requires the
+/// `OpenFilesWithSystemOpener` permission, since the system opener might run the file if it is
+/// executable.
+pub fn open_file_with_system_opener<P: AsRef<Path>>(path: P) {
+    let plugin_command = PluginCommand::OpenFileWithSystemOpener(path.as_ref().to_path_buf());
+    let protobuf_plugin_command: ProtobufPluginCommand = plugin_command.try_into().unwrap();
+    object_to_stdout(&protobuf_plugin_command.encode_to_vec());
+    unsafe { host_run_plugin_command() };
+}
+
+/// Draw the given contents (lines of text, which may include ANSI styling) in a `width` x
+/// `height` rectangle at absolute screen coordinates, on top of all panes and without taking
+/// focus. The overlay stays until `clear_overlay` is called or the plugin is unloaded, and
+/// replaces any previous overlay of this plugin.
+pub fn render_overlay(x: usize, y: usize, width: usize, height: usize, contents: &[u8]) {
+    let plugin_command = PluginCommand::RenderOverlay(x, y, width, height, contents.to_vec());
+    let protobuf_plugin_command: ProtobufPluginCommand = plugin_command.try_into().unwrap();
+    object_to_stdout(&protobuf_plugin_command.encode_to_vec());
+    unsafe { host_run_plugin_command() };
+}
+
+/// Remove the overlay drawn by this plugin with `render_overlay`, if any.
+pub fn clear_overlay() {
+    let plugin_command = PluginCommand::ClearOverlay;
+    let protobuf_plugin_command: ProtobufPluginCommand = plugin_command.try_into().unwrap();
+    object_to_stdout(&protobuf_plugin_command.encode_to_vec());
+    unsafe { host_run_plugin_command() };
+}
+
+/// Open a terminal pane that runs no process and is fed only by this plugin through
+/// `write_to_pane_scrollback`. It shows up in `PaneUpdate` like any other terminal pane (with the
+/// given name, if any) and ignores user input. Once the pane is open, its id is sent to the plugin
+/// with a `TerminalPaneOpened` event.
+pub fn new_plugin_fed_pane(pane_name: Option<&str>) {
+    let plugin_command = PluginCommand::NewPluginFedPane(pane_name.map(|p| p.to_owned()));
+    let protobuf_plugin_command: ProtobufPluginCommand = plugin_command.try_into().unwrap();
+    object_to_stdout(&protobuf_plugin_command.encode_to_vec());
+    unsafe { host_run_plugin_command() };
+}
+
+/// Append lines to a pane opened by this plugin with `new_plugin_fed_pane`. The lines are parsed
+/// for ANSI like regular terminal output, so they can be styled, scrolled through and searched.
+pub fn write_to_pane_scrollback(terminal_pane_id: u32, lines: Vec<String>) {
+    let plugin_command = PluginCommand::WriteToPaneScrollback(terminal_pane_id, lines);
+    let protobuf_plugin_command: ProtobufPluginCommand = plugin_command.try_into().unwrap();
+    object_to_stdout(&protobuf_plugin_command.encode_to_vec());
+    unsafe { host_run_plugin_command() };
+}
+
+/// Register a segment to be shown in the status-bar. Whenever this plugin renders,
+/// `ZellijPlugin::render_status_segment` is called with `render_callback_id` to render the
+/// segment's content. Segments of all plugins are ordered by descending `priority`, and those with
+/// the lowest priority are left out when the status-bar is too narrow to show all of them.
+/// Registering the same `render_callback_id` again changes its priority.
+pub fn register_status_segment(priority: u32, render_callback_id: u32) {
+    let plugin_command = PluginCommand::RegisterStatusSegment(priority, render_callback_id);
+    let protobuf_plugin_command: ProtobufPluginCommand = plugin_command.try_into().unwrap();
+    object_to_stdout(&protobuf_plugin_command.encode_to_vec());
+    unsafe { host_run_plugin_command() };
+}
+
+/// Request the session activity log (panes opening and closing, clients changing tabs and
+/// commands being spawned). The log arrives as a `SessionActivity` event, which is also sent with
+/// each new entry to plugins subscribed to it.
+pub fn request_session_activity() {
+    let plugin_command = PluginCommand::RequestSessionActivity;
+    let protobuf_plugin_command: ProtobufPluginCommand = plugin_command.try_into().unwrap();
+    object_to_stdout(&protobuf_plugin_command.encode_to_vec());
+    unsafe { host_run_plugin_command() };
+}
+
+/// Receive the raw output of a terminal pane as `PaneOutput` events, as the pty produces it.
+/// Output produced while the plugin is busy is sent as a single chunk, and if the plugin falls
+/// too far behind the oldest output is dropped. Requires the `ReadPaneOutput` permission.
+pub fn subscribe_pane_output(pane_id: PaneId) {
+    let plugin_command = PluginCommand::SubscribePaneOutput(pane_id);
+    let protobuf_plugin_command: ProtobufPluginCommand = plugin_command.try_into().unwrap();
+    object_to_stdout(&protobuf_plugin_command.encode_to_vec());
+    unsafe { host_run_plugin_command() };
+}
+
+/// Stop receiving the output of a pane subscribed to with `subscribe_pane_output`
+pub fn unsubscribe_pane_output(pane_id: PaneId) {
+    let plugin_command = PluginCommand::UnsubscribePaneOutput(pane_id);
+    let protobuf_plugin_command: ProtobufPluginCommand = plugin_command.try_into().unwrap();
+    object_to_stdout(&protobuf_plugin_command.encode_to_vec());
+    unsafe { host_run_plugin_command() };
+}
+
+/// Changes the name (the title that appears in the frame) of this plugin's own pane.
+/// Names containing control characters are rejected. Requires the `ChangeApplicationState`
+/// permission.
+pub fn set_pane_name<S: AsRef<str>>(name: S)
+where
+    S: ToString,
+{
+    let plugin_command = PluginCommand::SetPaneName(name.to_string());
+    let protobuf_plugin_command: ProtobufPluginCommand = plugin_command.try_into().unwrap();
+    object_to_stdout(&protobuf_plugin_command.encode_to_vec());
+    unsafe { host_run_plugin_command() };
+}
+
+/// Changes the name of the tab this plugin's pane is in.
+/// Names containing control characters are rejected. Requires the `ChangeApplicationState`
+/// permission.
+pub fn set_tab_name<S: AsRef<str>>(name: S)
+where
+    S: ToString,
+{
+    let plugin_command = PluginCommand::SetTabName(name.to_string());
+    let protobuf_plugin_command: ProtobufPluginCommand = plugin_command.try_into().unwrap();
+    object_to_stdout(&protobuf_plugin_command.encode_to_vec());
+    unsafe { host_run_plugin_command() };
+}
+
 // Utility Functions
 
 #[allow(unused)]
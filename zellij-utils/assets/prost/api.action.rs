@@ -5,7 +5,7 @@ pub struct Action {
     pub name: i32,
     #[prost(
         oneof = "action::OptionalPayload",
-        tags = "2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24, 25, 26, 27, 28, 29, 30, 31, 32, 33, 34, 35, 36, 37, 38, 39, 40, 41, 42, 43, 44, 45, 46"
+        tags = "2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24, 25, 26, 27, 28, 29, 30, 31, 32, 33, 34, 35, 36, 37, 38, 39, 40, 41, 42, 43, 44, 45, 46, 47, 48"
     )]
     pub optional_payload: ::core::option::Option<action::OptionalPayload>,
 }
@@ -104,6 +104,10 @@ pub mod action {
         RenameSessionPayload(::prost::alloc::string::String),
         #[prost(message, tag = "46")]
         LaunchPluginPayload(super::LaunchOrFocusPluginPayload),
+        #[prost(message, tag = "47")]
+        MessageToPluginPayload(super::MessageToPluginPayload),
+        #[prost(string, tag = "48")]
+        GoToTabByNamePayload(::prost::alloc::string::String),
     }
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
@@ -146,6 +150,16 @@ pub struct LaunchOrFocusPluginPayload {
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MessageToPluginPayload {
+    #[prost(string, tag = "1")]
+    pub plugin_url: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub name: ::prost::alloc::string::String,
+    #[prost(string, optional, tag = "3")]
+    pub payload: ::core::option::Option<::prost::alloc::string::String>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
 pub struct GoToTabNamePayload {
     #[prost(string, tag = "1")]
     pub tab_name: ::prost::alloc::string::String,
@@ -406,6 +420,12 @@ pub enum ActionName {
     BreakPaneLeft = 79,
     RenameSession = 80,
     LaunchPlugin = 81,
+    ToggleRuler = 82,
+    MessageToPlugin = 83,
+    PopOutPane = 84,
+    GoToTabByName = 85,
+    TogglePanePassthrough = 86,
+    UndoLayoutChange = 87,
 }
 impl ActionName {
     /// String value of the enum field names used in the ProtoBuf definition.
@@ -496,6 +516,12 @@ impl ActionName {
             ActionName::BreakPaneLeft => "BreakPaneLeft",
             ActionName::RenameSession => "RenameSession",
             ActionName::LaunchPlugin => "LaunchPlugin",
+            ActionName::ToggleRuler => "ToggleRuler",
+            ActionName::MessageToPlugin => "MessageToPlugin",
+            ActionName::PopOutPane => "PopOutPane",
+            ActionName::GoToTabByName => "GoToTabByName",
+            ActionName::TogglePanePassthrough => "TogglePanePassthrough",
+            ActionName::UndoLayoutChange => "UndoLayoutChange",
         }
     }
     /// Creates an enum from field names used in the ProtoBuf definition.
@@ -583,6 +609,12 @@ impl ActionName {
             "BreakPaneLeft" => Some(Self::BreakPaneLeft),
             "RenameSession" => Some(Self::RenameSession),
             "LaunchPlugin" => Some(Self::LaunchPlugin),
+            "ToggleRuler" => Some(Self::ToggleRuler),
+            "MessageToPlugin" => Some(Self::MessageToPlugin),
+            "PopOutPane" => Some(Self::PopOutPane),
+            "GoToTabByName" => Some(Self::GoToTabByName),
+            "TogglePanePassthrough" => Some(Self::TogglePanePassthrough),
+            "UndoLayoutChange" => Some(Self::UndoLayoutChange),
             _ => None,
         }
     }
@@ -11,7 +11,7 @@ pub struct Event {
     pub name: i32,
     #[prost(
         oneof = "event::Payload",
-        tags = "2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15"
+        tags = "2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24, 25, 26, 27, 28, 29, 30, 31, 32, 33, 34, 35"
     )]
     pub payload: ::core::option::Option<event::Payload>,
 }
@@ -48,10 +48,206 @@ pub mod event {
         RunCommandResultPayload(super::RunCommandResultPayload),
         #[prost(message, tag = "15")]
         WebRequestResultPayload(super::WebRequestResultPayload),
+        #[prost(message, tag = "16")]
+        InputLatencyStatsPayload(super::InputLatencyStatsPayload),
+        #[prost(message, tag = "17")]
+        PaneClosedPayload(super::PaneClosedPayload),
+        #[prost(message, tag = "18")]
+        SessionSharingStatusPayload(super::SessionSharingStatusPayload),
+        #[prost(message, tag = "19")]
+        SelectionChangedPayload(super::SelectionChangedPayload),
+        #[prost(message, tag = "20")]
+        SystemOpenerFailurePayload(super::SystemOpenerFailurePayload),
+        #[prost(
+            enumeration = "super::super::plugin_permission::PermissionType",
+            tag = "21"
+        )]
+        PermissionDeniedPayload(i32),
+        #[prost(bytes, tag = "22")]
+        RestoreStatePayload(::prost::alloc::vec::Vec<u8>),
+        #[prost(string, tag = "23")]
+        PastePayload(::prost::alloc::string::String),
+        #[prost(message, tag = "24")]
+        StatusSegmentsPayload(super::StatusSegmentsPayload),
+        #[prost(message, tag = "25")]
+        SessionActivityPayload(super::SessionActivityPayload),
+        #[prost(message, tag = "26")]
+        PaneOutputPayload(super::PaneOutputPayload),
+        #[prost(enumeration = "super::super::input_mode::InputMode", tag = "27")]
+        ModeChangedPayload(i32),
+        #[prost(message, tag = "28")]
+        HyperlinkClickedPayload(super::HyperlinkClickedPayload),
+        #[prost(message, tag = "29")]
+        PluginMetricsPayload(super::PluginMetricsPayload),
+        #[prost(message, tag = "30")]
+        BellPayload(super::BellPayload),
+        #[prost(string, tag = "31")]
+        WorkspaceChangedPayload(::prost::alloc::string::String),
+        #[prost(uint64, tag = "32")]
+        ClipboardCopySucceededPayload(u64),
+        #[prost(uint64, tag = "33")]
+        SystemClipboardFailurePayload(u64),
+        #[prost(message, tag = "34")]
+        PaneTitleChangedPayload(super::PaneTitleChangedPayload),
+        #[prost(uint32, tag = "35")]
+        TerminalPaneOpenedPayload(u32),
     }
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
+pub struct BellPayload {
+    #[prost(message, optional, tag = "1")]
+    pub pane_id: ::core::option::Option<super::pane_id::PaneId>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PaneTitleChangedPayload {
+    #[prost(message, optional, tag = "1")]
+    pub pane_id: ::core::option::Option<super::pane_id::PaneId>,
+    #[prost(string, tag = "2")]
+    pub title: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PluginMetricsPayload {
+    #[prost(message, repeated, tag = "1")]
+    pub plugin_metrics: ::prost::alloc::vec::Vec<PluginMetric>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PluginMetric {
+    #[prost(uint32, tag = "1")]
+    pub plugin_id: u32,
+    #[prost(uint64, tag = "2")]
+    pub memory_bytes: u64,
+    #[prost(uint64, tag = "3")]
+    pub last_render_micros: u64,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct HyperlinkClickedPayload {
+    #[prost(message, optional, tag = "1")]
+    pub pane_id: ::core::option::Option<super::pane_id::PaneId>,
+    #[prost(string, tag = "2")]
+    pub uri: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PaneOutputPayload {
+    #[prost(message, optional, tag = "1")]
+    pub pane_id: ::core::option::Option<super::pane_id::PaneId>,
+    #[prost(bytes = "vec", tag = "2")]
+    pub output: ::prost::alloc::vec::Vec<u8>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SessionActivityPayload {
+    #[prost(message, repeated, tag = "1")]
+    pub session_activities: ::prost::alloc::vec::Vec<SessionActivity>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SessionActivity {
+    /// since the unix epoch
+    #[prost(uint64, tag = "1")]
+    pub timestamp_micros: u64,
+    #[prost(enumeration = "SessionActivityKind", tag = "2")]
+    pub kind: i32,
+    #[prost(message, optional, tag = "3")]
+    pub pane_id: ::core::option::Option<super::pane_id::PaneId>,
+    #[prost(uint32, optional, tag = "4")]
+    pub client_id: ::core::option::Option<u32>,
+    #[prost(uint32, optional, tag = "5")]
+    pub tab_position: ::core::option::Option<u32>,
+    #[prost(string, optional, tag = "6")]
+    pub command: ::core::option::Option<::prost::alloc::string::String>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct StatusSegmentsPayload {
+    #[prost(message, repeated, tag = "1")]
+    pub status_segments: ::prost::alloc::vec::Vec<StatusSegment>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct StatusSegment {
+    #[prost(uint32, tag = "1")]
+    pub plugin_id: u32,
+    #[prost(uint32, tag = "2")]
+    pub render_callback_id: u32,
+    #[prost(uint32, tag = "3")]
+    pub priority: u32,
+    #[prost(string, tag = "4")]
+    pub content: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SelectionChangedPayload {
+    #[prost(message, optional, tag = "1")]
+    pub pane_id: ::core::option::Option<super::pane_id::PaneId>,
+    #[prost(message, optional, tag = "2")]
+    pub bounds: ::core::option::Option<SelectionBounds>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SelectionBounds {
+    #[prost(int64, tag = "1")]
+    pub start_line: i64,
+    #[prost(uint32, tag = "2")]
+    pub start_column: u32,
+    #[prost(int64, tag = "3")]
+    pub end_line: i64,
+    #[prost(uint32, tag = "4")]
+    pub end_column: u32,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SystemOpenerFailurePayload {
+    #[prost(string, tag = "1")]
+    pub target: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub error: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SessionSharingStatusPayload {
+    #[prost(uint32, tag = "1")]
+    pub connected_clients: u32,
+    #[prost(bool, tag = "2")]
+    pub is_recording: bool,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PaneClosedPayload {
+    #[prost(message, optional, tag = "1")]
+    pub pane_id: ::core::option::Option<super::pane_id::PaneId>,
+    #[prost(int32, optional, tag = "2")]
+    pub exit_status: ::core::option::Option<i32>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct InputLatencyStatsPayload {
+    #[prost(message, repeated, tag = "1")]
+    pub client_input_latencies: ::prost::alloc::vec::Vec<ClientInputLatency>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ClientInputLatency {
+    #[prost(uint32, tag = "1")]
+    pub client_id: u32,
+    #[prost(uint64, tag = "2")]
+    pub sample_count: u64,
+    #[prost(uint64, tag = "3")]
+    pub last_micros: u64,
+    #[prost(uint64, tag = "4")]
+    pub average_micros: u64,
+    #[prost(uint64, tag = "5")]
+    pub min_micros: u64,
+    #[prost(uint64, tag = "6")]
+    pub max_micros: u64,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
 pub struct SessionUpdatePayload {
     #[prost(message, repeated, tag = "1")]
     pub session_manifests: ::prost::alloc::vec::Vec<SessionManifest>,
@@ -115,8 +311,8 @@ pub struct FileListPayload {
 pub struct CustomMessagePayload {
     #[prost(string, tag = "1")]
     pub message_name: ::prost::alloc::string::String,
-    #[prost(string, tag = "2")]
-    pub payload: ::prost::alloc::string::String,
+    #[prost(bytes = "vec", tag = "2")]
+    pub payload: ::prost::alloc::vec::Vec<u8>,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -172,6 +368,22 @@ pub struct SessionManifest {
     pub connected_clients: u32,
     #[prost(bool, tag = "5")]
     pub is_current_session: bool,
+    #[prost(uint32, tag = "6")]
+    pub observer_clients: u32,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SessionLayout {
+    #[prost(message, repeated, tag = "1")]
+    pub tabs: ::prost::alloc::vec::Vec<TabInfo>,
+    #[prost(message, repeated, tag = "2")]
+    pub panes: ::prost::alloc::vec::Vec<PaneManifest>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Keybindings {
+    #[prost(message, repeated, tag = "1")]
+    pub modes: ::prost::alloc::vec::Vec<InputModeKeybinds>,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -252,6 +464,10 @@ pub struct TabInfo {
     pub active_swap_layout_name: ::core::option::Option<::prost::alloc::string::String>,
     #[prost(bool, tag = "10")]
     pub is_swap_layout_dirty: bool,
+    #[prost(bool, tag = "11")]
+    pub has_bell: bool,
+    #[prost(string, optional, tag = "12")]
+    pub color: ::core::option::Option<::prost::alloc::string::String>,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -320,6 +536,27 @@ pub enum EventType {
     SessionUpdate = 16,
     RunCommandResult = 17,
     WebRequestResult = 18,
+    InputLatencyStats = 19,
+    PaneClosed = 20,
+    SessionSharingStatus = 21,
+    EnteredCopyMode = 22,
+    ExitedCopyMode = 23,
+    SelectionChanged = 24,
+    SystemOpenerFailure = 25,
+    PermissionDenied = 26,
+    RestoreState = 27,
+    Paste = 28,
+    StatusSegmentsUpdate = 29,
+    SessionActivity = 30,
+    PaneOutput = 31,
+    ModeChanged = 32,
+    HyperlinkClicked = 33,
+    PluginMetrics = 34,
+    Bell = 35,
+    WorkspaceChanged = 36,
+    ClipboardCopySucceeded = 37,
+    PaneTitleChanged = 38,
+    TerminalPaneOpened = 39,
 }
 impl EventType {
     /// String value of the enum field names used in the ProtoBuf definition.
@@ -347,6 +584,27 @@ impl EventType {
             EventType::SessionUpdate => "SessionUpdate",
             EventType::RunCommandResult => "RunCommandResult",
             EventType::WebRequestResult => "WebRequestResult",
+            EventType::InputLatencyStats => "InputLatencyStats",
+            EventType::PaneClosed => "PaneClosed",
+            EventType::SessionSharingStatus => "SessionSharingStatus",
+            EventType::EnteredCopyMode => "EnteredCopyMode",
+            EventType::ExitedCopyMode => "ExitedCopyMode",
+            EventType::SelectionChanged => "SelectionChanged",
+            EventType::SystemOpenerFailure => "SystemOpenerFailure",
+            EventType::PermissionDenied => "PermissionDenied",
+            EventType::RestoreState => "RestoreState",
+            EventType::Paste => "Paste",
+            EventType::StatusSegmentsUpdate => "StatusSegmentsUpdate",
+            EventType::SessionActivity => "SessionActivity",
+            EventType::PaneOutput => "PaneOutput",
+            EventType::ModeChanged => "ModeChanged",
+            EventType::HyperlinkClicked => "HyperlinkClicked",
+            EventType::PluginMetrics => "PluginMetrics",
+            EventType::Bell => "Bell",
+            EventType::WorkspaceChanged => "WorkspaceChanged",
+            EventType::ClipboardCopySucceeded => "ClipboardCopySucceeded",
+            EventType::PaneTitleChanged => "PaneTitleChanged",
+            EventType::TerminalPaneOpened => "TerminalPaneOpened",
         }
     }
     /// Creates an enum from field names used in the ProtoBuf definition.
@@ -371,6 +629,59 @@ impl EventType {
             "SessionUpdate" => Some(Self::SessionUpdate),
             "RunCommandResult" => Some(Self::RunCommandResult),
             "WebRequestResult" => Some(Self::WebRequestResult),
+            "InputLatencyStats" => Some(Self::InputLatencyStats),
+            "PaneClosed" => Some(Self::PaneClosed),
+            "SessionSharingStatus" => Some(Self::SessionSharingStatus),
+            "EnteredCopyMode" => Some(Self::EnteredCopyMode),
+            "ExitedCopyMode" => Some(Self::ExitedCopyMode),
+            "SelectionChanged" => Some(Self::SelectionChanged),
+            "SystemOpenerFailure" => Some(Self::SystemOpenerFailure),
+            "PermissionDenied" => Some(Self::PermissionDenied),
+            "RestoreState" => Some(Self::RestoreState),
+            "Paste" => Some(Self::Paste),
+            "StatusSegmentsUpdate" => Some(Self::StatusSegmentsUpdate),
+            "SessionActivity" => Some(Self::SessionActivity),
+            "PaneOutput" => Some(Self::PaneOutput),
+            "ModeChanged" => Some(Self::ModeChanged),
+            "HyperlinkClicked" => Some(Self::HyperlinkClicked),
+            "PluginMetrics" => Some(Self::PluginMetrics),
+            "Bell" => Some(Self::Bell),
+            "WorkspaceChanged" => Some(Self::WorkspaceChanged),
+            "ClipboardCopySucceeded" => Some(Self::ClipboardCopySucceeded),
+            "PaneTitleChanged" => Some(Self::PaneTitleChanged),
+            "TerminalPaneOpened" => Some(Self::TerminalPaneOpened),
+            _ => None,
+        }
+    }
+}
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum SessionActivityKind {
+    PaneOpened = 0,
+    PaneClosed = 1,
+    TabChanged = 2,
+    CommandSpawned = 3,
+}
+impl SessionActivityKind {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            SessionActivityKind::PaneOpened => "PaneOpened",
+            SessionActivityKind::PaneClosed => "PaneClosed",
+            SessionActivityKind::TabChanged => "TabChanged",
+            SessionActivityKind::CommandSpawned => "CommandSpawned",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "PaneOpened" => Some(Self::PaneOpened),
+            "PaneClosed" => Some(Self::PaneClosed),
+            "TabChanged" => Some(Self::TabChanged),
+            "CommandSpawned" => Some(Self::CommandSpawned),
             _ => None,
         }
     }
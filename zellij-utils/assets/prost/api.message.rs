@@ -3,8 +3,8 @@
 pub struct Message {
     #[prost(string, tag = "1")]
     pub name: ::prost::alloc::string::String,
-    #[prost(string, tag = "2")]
-    pub payload: ::prost::alloc::string::String,
+    #[prost(bytes = "vec", tag = "2")]
+    pub payload: ::prost::alloc::vec::Vec<u8>,
     #[prost(string, optional, tag = "3")]
     pub worker_name: ::core::option::Option<::prost::alloc::string::String>,
 }
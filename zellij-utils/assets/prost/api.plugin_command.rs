@@ -5,7 +5,7 @@ pub struct PluginCommand {
     pub name: i32,
     #[prost(
         oneof = "plugin_command::Payload",
-        tags = "2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24, 25, 26, 27, 28, 29, 30, 31, 32, 33, 34, 35, 36, 37, 38, 39, 40, 41, 42, 43, 44, 45, 46, 47"
+        tags = "2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24, 25, 26, 27, 28, 29, 30, 31, 32, 33, 34, 35, 36, 37, 38, 39, 40, 41, 42, 43, 44, 45, 46, 47, 48, 49, 50, 51, 52, 53, 54, 55, 56, 57, 58, 59, 60, 61, 62, 63, 64, 65"
     )]
     pub payload: ::core::option::Option<plugin_command::Payload>,
 }
@@ -106,10 +106,130 @@ pub mod plugin_command {
         RenameSessionPayload(::prost::alloc::string::String),
         #[prost(message, tag = "47")]
         ResizeFloatingPaneByPercentPayload(super::ResizeFloatingPaneByPercentPayload),
+        #[prost(string, tag = "48")]
+        PluginReadFilePayload(::prost::alloc::string::String),
+        #[prost(message, tag = "49")]
+        PluginWriteFilePayload(super::PluginWriteFilePayload),
+        #[prost(message, tag = "50")]
+        SubscribeWithFileSystemFilterPayload(super::SubscribeWithFileSystemFilterPayload),
+        #[prost(string, tag = "51")]
+        CopyToClipboardPayload(::prost::alloc::string::String),
+        #[prost(string, tag = "52")]
+        LogMessagePayload(::prost::alloc::string::String),
+        #[prost(string, tag = "53")]
+        OpenUrlPayload(::prost::alloc::string::String),
+        #[prost(string, tag = "54")]
+        OpenFileWithSystemOpenerPayload(::prost::alloc::string::String),
+        #[prost(message, tag = "55")]
+        RenderOverlayPayload(super::RenderOverlayPayload),
+        #[prost(message, tag = "56")]
+        NewPluginFedPanePayload(super::NewPluginFedPanePayload),
+        #[prost(message, tag = "57")]
+        WriteToPaneScrollbackPayload(super::WriteToPaneScrollbackPayload),
+        #[prost(message, tag = "58")]
+        RegisterStatusSegmentPayload(super::RegisterStatusSegmentPayload),
+        #[prost(message, tag = "59")]
+        SubscribePaneOutputPayload(super::super::pane_id::PaneId),
+        #[prost(message, tag = "60")]
+        UnsubscribePaneOutputPayload(super::super::pane_id::PaneId),
+        #[prost(string, tag = "61")]
+        SetPaneNamePayload(::prost::alloc::string::String),
+        #[prost(string, tag = "62")]
+        SetTabNamePayload(::prost::alloc::string::String),
+        #[prost(message, tag = "63")]
+        RenderChunkPayload(super::RenderChunkPayload),
+        #[prost(message, tag = "64")]
+        OpenTerminalPanePayload(super::OpenTerminalPanePayload),
+        #[prost(uint32, tag = "65")]
+        ClosePanePayload(u32),
     }
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SubscribeWithFileSystemFilterPayload {
+    #[prost(message, optional, tag = "1")]
+    pub subscriptions: ::core::option::Option<super::event::EventNameList>,
+    #[prost(string, repeated, tag = "2")]
+    pub path_globs: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PluginWriteFilePayload {
+    #[prost(string, tag = "1")]
+    pub path: ::prost::alloc::string::String,
+    #[prost(bytes = "vec", tag = "2")]
+    pub contents: ::prost::alloc::vec::Vec<u8>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct NewPluginFedPanePayload {
+    #[prost(string, optional, tag = "1")]
+    pub pane_name: ::core::option::Option<::prost::alloc::string::String>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct WriteToPaneScrollbackPayload {
+    #[prost(uint32, tag = "1")]
+    pub pane_id: u32,
+    #[prost(string, repeated, tag = "2")]
+    pub lines: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RegisterStatusSegmentPayload {
+    #[prost(uint32, tag = "1")]
+    pub priority: u32,
+    #[prost(uint32, tag = "2")]
+    pub render_callback_id: u32,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RenderOverlayPayload {
+    #[prost(uint32, tag = "1")]
+    pub x: u32,
+    #[prost(uint32, tag = "2")]
+    pub y: u32,
+    #[prost(uint32, tag = "3")]
+    pub width: u32,
+    #[prost(uint32, tag = "4")]
+    pub height: u32,
+    #[prost(bytes = "vec", tag = "5")]
+    pub contents: ::prost::alloc::vec::Vec<u8>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RenderChunkPayload {
+    #[prost(uint64, tag = "1")]
+    pub frame_id: u64,
+    #[prost(bytes = "vec", tag = "2")]
+    pub chunk: ::prost::alloc::vec::Vec<u8>,
+    #[prost(bool, tag = "3")]
+    pub is_last: bool,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct OpenTerminalPanePayload {
+    #[prost(string, tag = "1")]
+    pub cwd: ::prost::alloc::string::String,
+    #[prost(message, optional, tag = "2")]
+    pub command_to_run: ::core::option::Option<super::command::Command>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PluginFileResult {
+    #[prost(bytes = "vec", tag = "1")]
+    pub contents: ::prost::alloc::vec::Vec<u8>,
+    #[prost(string, optional, tag = "2")]
+    pub error: ::core::option::Option<::prost::alloc::string::String>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ReadClipboardResult {
+    #[prost(string, optional, tag = "1")]
+    pub contents: ::core::option::Option<::prost::alloc::string::String>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
 pub struct SwitchSessionPayload {
     #[prost(string, optional, tag = "1")]
     pub name: ::core::option::Option<::prost::alloc::string::String>,
@@ -331,6 +451,31 @@ pub enum CommandName {
     DeleteAllDeadSessions = 74,
     RenameSession = 75,
     ResizeFloatingPaneByPercent = 76,
+    PluginReadFile = 77,
+    PluginWriteFile = 78,
+    RequestSessionSharingStatus = 79,
+    SubscribeWithFileSystemFilter = 80,
+    CopyToClipboard = 81,
+    LogMessage = 82,
+    GetSessionLayout = 83,
+    OpenUrl = 84,
+    OpenFileWithSystemOpener = 85,
+    RenderOverlay = 86,
+    ClearOverlay = 87,
+    NewPluginFedPane = 88,
+    WriteToPaneScrollback = 89,
+    RegisterStatusSegment = 90,
+    RequestSessionActivity = 91,
+    SubscribePaneOutput = 92,
+    UnsubscribePaneOutput = 93,
+    SetPaneName = 94,
+    SetTabName = 95,
+    ReadClipboard = 96,
+    FocusSelf = 97,
+    GetKeybindings = 98,
+    RenderChunk = 99,
+    OpenTerminalPane = 100,
+    ClosePane = 101,
 }
 impl CommandName {
     /// String value of the enum field names used in the ProtoBuf definition.
@@ -416,6 +561,31 @@ impl CommandName {
             CommandName::DeleteAllDeadSessions => "DeleteAllDeadSessions",
             CommandName::RenameSession => "RenameSession",
             CommandName::ResizeFloatingPaneByPercent => "ResizeFloatingPaneByPercent",
+            CommandName::PluginReadFile => "PluginReadFile",
+            CommandName::PluginWriteFile => "PluginWriteFile",
+            CommandName::RequestSessionSharingStatus => "RequestSessionSharingStatus",
+            CommandName::SubscribeWithFileSystemFilter => "SubscribeWithFileSystemFilter",
+            CommandName::CopyToClipboard => "CopyToClipboard",
+            CommandName::LogMessage => "LogMessage",
+            CommandName::GetSessionLayout => "GetSessionLayout",
+            CommandName::OpenUrl => "OpenUrl",
+            CommandName::OpenFileWithSystemOpener => "OpenFileWithSystemOpener",
+            CommandName::RenderOverlay => "RenderOverlay",
+            CommandName::ClearOverlay => "ClearOverlay",
+            CommandName::NewPluginFedPane => "NewPluginFedPane",
+            CommandName::WriteToPaneScrollback => "WriteToPaneScrollback",
+            CommandName::RegisterStatusSegment => "RegisterStatusSegment",
+            CommandName::RequestSessionActivity => "RequestSessionActivity",
+            CommandName::SubscribePaneOutput => "SubscribePaneOutput",
+            CommandName::UnsubscribePaneOutput => "UnsubscribePaneOutput",
+            CommandName::SetPaneName => "SetPaneName",
+            CommandName::SetTabName => "SetTabName",
+            CommandName::ReadClipboard => "ReadClipboard",
+            CommandName::FocusSelf => "FocusSelf",
+            CommandName::GetKeybindings => "GetKeybindings",
+            CommandName::RenderChunk => "RenderChunk",
+            CommandName::OpenTerminalPane => "OpenTerminalPane",
+            CommandName::ClosePane => "ClosePane",
         }
     }
     /// Creates an enum from field names used in the ProtoBuf definition.
@@ -498,6 +668,31 @@ impl CommandName {
             "DeleteAllDeadSessions" => Some(Self::DeleteAllDeadSessions),
             "RenameSession" => Some(Self::RenameSession),
             "ResizeFloatingPaneByPercent" => Some(Self::ResizeFloatingPaneByPercent),
+            "PluginReadFile" => Some(Self::PluginReadFile),
+            "PluginWriteFile" => Some(Self::PluginWriteFile),
+            "RequestSessionSharingStatus" => Some(Self::RequestSessionSharingStatus),
+            "SubscribeWithFileSystemFilter" => Some(Self::SubscribeWithFileSystemFilter),
+            "CopyToClipboard" => Some(Self::CopyToClipboard),
+            "LogMessage" => Some(Self::LogMessage),
+            "GetSessionLayout" => Some(Self::GetSessionLayout),
+            "OpenUrl" => Some(Self::OpenUrl),
+            "OpenFileWithSystemOpener" => Some(Self::OpenFileWithSystemOpener),
+            "RenderOverlay" => Some(Self::RenderOverlay),
+            "ClearOverlay" => Some(Self::ClearOverlay),
+            "NewPluginFedPane" => Some(Self::NewPluginFedPane),
+            "WriteToPaneScrollback" => Some(Self::WriteToPaneScrollback),
+            "RegisterStatusSegment" => Some(Self::RegisterStatusSegment),
+            "RequestSessionActivity" => Some(Self::RequestSessionActivity),
+            "SubscribePaneOutput" => Some(Self::SubscribePaneOutput),
+            "UnsubscribePaneOutput" => Some(Self::UnsubscribePaneOutput),
+            "SetPaneName" => Some(Self::SetPaneName),
+            "SetTabName" => Some(Self::SetTabName),
+            "ReadClipboard" => Some(Self::ReadClipboard),
+            "FocusSelf" => Some(Self::FocusSelf),
+            "GetKeybindings" => Some(Self::GetKeybindings),
+            "RenderChunk" => Some(Self::RenderChunk),
+            "OpenTerminalPane" => Some(Self::OpenTerminalPane),
+            "ClosePane" => Some(Self::ClosePane),
             _ => None,
         }
     }
@@ -8,6 +8,12 @@ pub enum PermissionType {
     OpenTerminalsOrPlugins = 4,
     WriteToStdin = 5,
     WebAccess = 6,
+    WriteToClipboard = 7,
+    ReadPaneOutput = 8,
+    ReadClipboard = 9,
+    FocusSelf = 10,
+    OpenUrls = 11,
+    OpenFilesWithSystemOpener = 12,
 }
 impl PermissionType {
     /// String value of the enum field names used in the ProtoBuf definition.
@@ -23,6 +29,12 @@ impl PermissionType {
             PermissionType::OpenTerminalsOrPlugins => "OpenTerminalsOrPlugins",
             PermissionType::WriteToStdin => "WriteToStdin",
             PermissionType::WebAccess => "WebAccess",
+            PermissionType::WriteToClipboard => "WriteToClipboard",
+            PermissionType::ReadPaneOutput => "ReadPaneOutput",
+            PermissionType::ReadClipboard => "ReadClipboard",
+            PermissionType::FocusSelf => "FocusSelf",
+            PermissionType::OpenUrls => "OpenUrls",
+            PermissionType::OpenFilesWithSystemOpener => "OpenFilesWithSystemOpener",
         }
     }
     /// Creates an enum from field names used in the ProtoBuf definition.
@@ -35,6 +47,12 @@ impl PermissionType {
             "OpenTerminalsOrPlugins" => Some(Self::OpenTerminalsOrPlugins),
             "WriteToStdin" => Some(Self::WriteToStdin),
             "WebAccess" => Some(Self::WebAccess),
+            "WriteToClipboard" => Some(Self::WriteToClipboard),
+            "ReadPaneOutput" => Some(Self::ReadPaneOutput),
+            "ReadClipboard" => Some(Self::ReadClipboard),
+            "FocusSelf" => Some(Self::FocusSelf),
+            "OpenUrls" => Some(Self::OpenUrls),
+            "OpenFilesWithSystemOpener" => Some(Self::OpenFilesWithSystemOpener),
             _ => None,
         }
     }
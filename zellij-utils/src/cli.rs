@@ -1,8 +1,11 @@
-use crate::data::{Direction, InputMode, Resize};
+use crate::data::{Direction, InputMode, PaneId, Resize, ResizePreset};
 use crate::setup::Setup;
 use crate::{
     consts::{ZELLIJ_CONFIG_DIR_ENV, ZELLIJ_CONFIG_FILE_ENV},
-    input::{layout::PluginUserConfiguration, options::CliOptions},
+    input::{
+        layout::PluginUserConfiguration,
+        options::{CliOptions, PaneFrameStyle, ScrollLines},
+    },
 };
 use clap::{Parser, Subcommand};
 use serde::{Deserialize, Serialize};
@@ -33,6 +36,13 @@ fn validate_session(name: &str) -> Result<String, String> {
     Ok(name.to_owned())
 }
 
+fn parse_env_var(env_var: &str) -> Result<(String, String), String> {
+    match env_var.split_once('=') {
+        Some((name, value)) if !name.is_empty() => Ok((name.to_owned(), value.to_owned())),
+        _ => Err(format!("expected NAME=VALUE, got '{}'", env_var)),
+    }
+}
+
 #[derive(Parser, Default, Debug, Clone, Serialize, Deserialize)]
 #[clap(version, name = "zellij")]
 pub struct CliArgs {
@@ -70,6 +80,11 @@ pub struct CliArgs {
     /// Specify emitting additional debug information
     #[clap(short, long, value_parser)]
     pub debug: bool,
+
+    /// Set an environment variable (NAME=VALUE) for all the panes of a new session, can be
+    /// given more than once. The environment of a pane itself takes precedence.
+    #[clap(long, value_parser = parse_env_var)]
+    pub session_env: Vec<(String, String)>,
 }
 
 #[derive(Debug, Subcommand, Clone, Serialize, Deserialize)]
@@ -94,6 +109,42 @@ pub enum SessionCommand {
     Options(CliOptions),
 }
 
+#[derive(Debug, Subcommand, Clone, Serialize, Deserialize)]
+pub enum WorkspaceCommand {
+    /// Create a workspace grouping existing or future sessions, the first one being its primary
+    /// session
+    Create {
+        /// Name of the workspace
+        #[clap(value_parser)]
+        workspace_name: String,
+
+        /// Names of the sessions in the workspace, the first one is the primary session
+        #[clap(value_parser = validate_session, required = true)]
+        sessions: Vec<String>,
+    },
+
+    /// Attach to the primary session of a workspace (creating it if it does not exist), or to one
+    /// of its sibling sessions, starting the other sessions of the workspace in the background
+    #[clap(visible_alias = "a")]
+    Attach {
+        /// Name of the workspace
+        #[clap(value_parser)]
+        workspace_name: String,
+
+        /// Attach to this session of the workspace rather than its primary session
+        #[clap(short, long, value_parser)]
+        session: Option<String>,
+    },
+
+    /// Delete a workspace, leaving its sessions untouched
+    #[clap(visible_alias = "d")]
+    Delete {
+        /// Name of the workspace
+        #[clap(value_parser)]
+        workspace_name: String,
+    },
+}
+
 #[derive(Debug, Subcommand, Clone, Serialize, Deserialize)]
 pub enum Sessions {
     /// List active sessions
@@ -106,6 +157,10 @@ pub enum Sessions {
         /// Print just the session name
         #[clap(short, long, value_parser, takes_value(false), default_value("false"))]
         short: bool,
+
+        /// Print the live sessions along with their attached clients and tabs as JSON
+        #[clap(long, value_parser, takes_value(false), default_value("false"))]
+        json: bool,
     },
 
     /// Attach to a session
@@ -130,6 +185,16 @@ pub enum Sessions {
         /// If resurrecting a dead session, immediately run all its commands on startup
         #[clap(short, long, value_parser, takes_value(false), default_value("false"))]
         force_run_commands: bool,
+
+        /// Attach to a single terminal pane of the session as an isolated view (as pop-out windows
+        /// do), docking it back into its tab on detach
+        #[clap(long, value_parser)]
+        pane_view: Option<u32>,
+
+        /// Attach as an observer, seeing everything in the session without being able to send it
+        /// any input
+        #[clap(long, value_parser, takes_value(false), default_value("false"))]
+        observer: bool,
     },
 
     /// Kill a specific session
@@ -170,10 +235,28 @@ pub enum Sessions {
         force: bool,
     },
 
+    /// List workspaces, named groups of related sessions, along with the state of their sessions
+    #[clap(visible_alias = "lw")]
+    ListWorkspaces {
+        /// Do not add colors and formatting to the list (useful for parsing)
+        #[clap(short, long, value_parser, takes_value(false), default_value("false"))]
+        no_formatting: bool,
+    },
+
+    /// Create, attach to or delete a workspace, a named group of related sessions
+    #[clap(visible_alias = "w")]
+    Workspace {
+        #[clap(subcommand)]
+        command: WorkspaceCommand,
+    },
+
     /// Send actions to a specific session
     #[clap(visible_alias = "ac")]
     #[clap(subcommand)]
     Action(CliAction),
+    /// Drive a session with newline-delimited JSON commands read from STDIN, writing a JSON
+    /// response for each of them to STDOUT (eg. for editor integrations)
+    Control,
     /// Run a command in a new pane
     #[clap(visible_alias = "r")]
     Run {
@@ -417,6 +500,9 @@ pub enum CliAction {
         start_suspended: bool,
         #[clap(long, value_parser)]
         configuration: Option<PluginUserConfiguration>,
+        /// Switch to this input mode once the new pane is open [locked|pane|tab|resize|move|search|scroll|session]
+        #[clap(long, value_parser)]
+        enter_mode: Option<InputMode>,
     },
     /// Open the specified file in a new zellij pane with your default EDITOR
     Edit {
@@ -539,4 +625,241 @@ pub enum CliAction {
     RenameSession {
         name: String,
     },
+    /// Toggle a ruler with line and column indices on the focused pane
+    ToggleRuler,
+    /// Pop the focused pane out into a new terminal window (opened with the
+    /// `pop_out_terminal_command` option), docking it back when the window is closed
+    PopOutPane,
+    /// Query the environment variables set for all the panes of the session
+    QuerySessionEnv,
+    /// Toggle pass-through mode on the focused pane, forwarding every key to it verbatim except
+    /// the `passthrough_escape_key` (default: "Ctrl g")
+    TogglePanePassthrough,
+    /// Set the number of lines the mouse wheel scrolls the focused pane by
+    SetScrollLines {
+        /// A number of lines or "half_page", if not given the pane goes back to the
+        /// `scroll_lines` option
+        #[clap(value_parser)]
+        scroll_lines: Option<ScrollLines>,
+    },
+    /// Change the characters the frames of all panes are drawn with
+    SetPaneFrameStyle {
+        /// unicode-heavy, unicode-rounded or ascii, if not given frames are drawn with thin
+        /// unicode lines
+        #[clap(arg_enum, value_parser)]
+        pane_frame_style: Option<PaneFrameStyle>,
+    },
+    /// Undo the latest layout change, eg. bring back the latest pane or tab that was closed (up to
+    /// the `layout_undo_depth` option)
+    UndoLayoutChange,
+    /// Split the focused pane, keeping a given percent of its space rather than half of it
+    SplitPaneWithRatio {
+        /// Direction to open the new pane in (left and up split like right and down)
+        #[clap(short, long, value_parser)]
+        direction: Direction,
+        /// Percent of its space the focused pane keeps, between 1 and 99
+        #[clap(value_parser)]
+        percent: u8,
+    },
+    /// Focus the pane with this id (eg. the ZELLIJ_PANE_ID of a terminal pane) in whichever tab it
+    /// is, switching to that tab
+    FocusPaneById {
+        #[clap(value_parser)]
+        pane_id: u32,
+        /// The id is that of a plugin pane rather than a terminal pane
+        #[clap(short, long, value_parser, default_value("false"), takes_value(false))]
+        plugin: bool,
+    },
+    /// Toggle showing whitespace and control characters as visible glyphs in the focused pane
+    ToggleShowInvisibles,
+    /// Resize the focused pane along its dominant axis to a part of the tab
+    ResizePaneToPreset {
+        /// half, third or golden_ratio
+        #[clap(value_parser)]
+        preset: ResizePreset,
+    },
+    /// Switch all clients to the workspace with this name, creating it if it does not exist
+    SwitchWorkspace {
+        #[clap(value_parser)]
+        name: String,
+    },
+    /// Render everything the focused client sees with one of the themes of the configuration,
+    /// other clients keep theirs
+    SetClientTheme {
+        #[clap(value_parser)]
+        name: String,
+    },
+    /// Open a pane in the current tab mirroring the terminal pane with this id, which can be in
+    /// any tab
+    AttachToPane {
+        #[clap(value_parser)]
+        pane_id: u32,
+    },
+    /// Reshape the focused tab to match the tiled panes of a layout, keeping its panes and opening
+    /// new ones only for the parts of the layout they do not fill
+    ApplyLayoutToTab {
+        /// Layout to apply
+        #[clap(value_parser)]
+        layout: PathBuf,
+
+        /// Default folder to look for layouts
+        #[clap(long, value_parser)]
+        layout_dir: Option<PathBuf>,
+    },
+    /// Print the text of the focused pane to STDOUT, as it would be read rather than rendered:
+    /// without styles, with wrapped lines joined
+    CapturePaneText {
+        /// Include the scrollback of the pane
+        #[clap(short, long, value_parser, default_value("false"), takes_value(false))]
+        scrollback: bool,
+    },
+    /// Print the cells currently rendered to the focused client to STDOUT as JSON, each with its
+    /// character and the VTE instructions that style it
+    CaptureRendered,
+    /// Highlight the occurrences of some text in the focused pane, including its scrollback
+    SearchInPane {
+        #[clap(value_parser)]
+        query: String,
+        /// Only match occurrences with the same case
+        #[clap(short, long, value_parser, default_value("false"), takes_value(false))]
+        case_sensitive: bool,
+    },
+    /// Clear the highlighted occurrences of a search in the focused pane
+    ClearSearch,
+    /// Close the panes matching one of the options in all tabs
+    ClosePanesMatching {
+        /// Close the command panes whose command exited successfully
+        #[clap(
+            long,
+            value_parser,
+            default_value("false"),
+            takes_value(false),
+            conflicts_with("exited_non_zero"),
+            conflicts_with("name")
+        )]
+        exited_zero: bool,
+        /// Close the command panes whose command failed or was killed
+        #[clap(
+            long,
+            value_parser,
+            default_value("false"),
+            takes_value(false),
+            conflicts_with("name")
+        )]
+        exited_non_zero: bool,
+        /// Close the panes whose title matches this glob, eg. "cargo *"
+        #[clap(short, long, value_parser)]
+        name: Option<String>,
+    },
+    /// Stack tiled panes of the focused tab in the place of the first one
+    StackPanes {
+        /// eg. 3 or terminal_3 for a terminal pane and plugin_3 for a plugin pane
+        #[clap(value_parser, required(true))]
+        pane_ids: Vec<PaneId>,
+    },
+    /// Focus the next (down/right) or previous (up/left) pane of the focused stack
+    CycleStack {
+        #[clap(value_parser)]
+        direction: Direction,
+    },
+    /// Open a new pane next to the focused one in the same cwd, running the same command in a new
+    /// process if it is a command pane
+    DuplicatePane,
+    /// Focus the pane after the focused one in reading order (left-to-right, then
+    /// top-to-bottom), wrapping around to the first pane
+    FocusNextPaneSpatial,
+    /// Focus the pane with this name in whichever tab it is, switching to that tab. If several
+    /// panes share the name, the first one in tab order is focused
+    FocusPaneByName {
+        #[clap(value_parser)]
+        name: String,
+    },
+    /// Close a tiled pane and grow another one over the space it took, the two panes must share a
+    /// whole border
+    MergePanes {
+        /// The pane that grows, eg. 3 or terminal_3 for a terminal pane and plugin_3 for a plugin
+        /// pane
+        #[clap(value_parser)]
+        keep_pane_id: PaneId,
+        /// The pane that is closed
+        #[clap(value_parser)]
+        absorb_pane_id: PaneId,
+    },
+    /// Move the active tab one position back (left/up) or forward (right/down), wrapping around
+    MoveTab {
+        #[clap(value_parser)]
+        direction: Direction,
+    },
+    /// Resize the focused pane to this many columns and rows, or as close to it as its neighbours
+    /// allow, and print the size it ended up with
+    ResizePaneTo {
+        #[clap(value_parser)]
+        cols: usize,
+        #[clap(value_parser)]
+        rows: usize,
+    },
+    /// Size the tiled panes of the active tab in proportion to the size their content prefers
+    ResizeTabToContent,
+    /// Dim the panes that are not focused, in all tabs
+    SetFocusDimming {
+        /// Percent the colors of unfocused panes are blended towards the background, between 0
+        /// and 100, if not given (or 0) panes are no longer dimmed
+        #[clap(value_parser)]
+        percent: Option<u8>,
+    },
+    /// Send input to all panes of the active tab, whether or not it already did (unlike
+    /// ToggleActiveSyncTab)
+    SetInputBroadcast {
+        /// Send input only to the focused pane again
+        #[clap(long, value_parser, default_value("false"), takes_value(false))]
+        off: bool,
+    },
+    /// Keep the focused pane at least this many columns and rows, other panes shrink instead of it
+    /// when they or the tab are resized, as long as there's room
+    SetPaneMinSize {
+        #[clap(value_parser)]
+        cols: usize,
+        #[clap(value_parser)]
+        rows: usize,
+    },
+    /// Stop sending input to the focused pane, it can still be scrolled
+    SetPaneReadOnly {
+        /// Send input to the focused pane again
+        #[clap(long, value_parser, default_value("false"), takes_value(false))]
+        off: bool,
+    },
+    /// Scroll all panes of the active tab along with the one that is scrolled
+    SetSynchronizedScroll {
+        /// Scroll only the pane that is scrolled again
+        #[clap(long, value_parser, default_value("false"), takes_value(false))]
+        off: bool,
+    },
+    /// Color the active tab in the tab bar
+    SetTabColor {
+        /// The name of a palette color (eg. red) or a hex color (eg. "#ff0000"), if not given the
+        /// tab is no longer colored
+        #[clap(value_parser)]
+        color: Option<String>,
+    },
+    /// Rearrange the panes of a tab into its next swap layout, whether or not it is the active tab
+    SwapTabLayout {
+        /// The position of the tab in the tab bar, starting at 1
+        #[clap(value_parser)]
+        position: u32,
+        /// Go to the previous swap layout instead
+        #[clap(short, long, value_parser, default_value("false"), takes_value(false))]
+        previous: bool,
+    },
+    /// Hide the floating panes of a tab or show them again, whether or not it is the active tab
+    ToggleFloatingPanesInTab {
+        /// The position of the tab in the tab bar, starting at 1
+        #[clap(value_parser)]
+        position: u32,
+    },
+    /// Toggle the frame of the focused pane only, rather than the frames of all panes
+    TogglePaneFrame,
+    /// Pin the focused floating pane so that it is drawn above the other panes, or unpin it
+    TogglePanePinned,
+    /// Expand the focused pane to the whole tab, or restore the layout it was zoomed from
+    ToggleZoom,
 }
@@ -17,6 +17,21 @@ pub enum PaneId {
     Plugin(u32), // FIXME: Drop the trait object, make this a wrapper for the struct?
 }
 
+impl FromStr for PaneId {
+    type Err = String;
+    // eg. "3" or "terminal_3" for a terminal pane and "plugin_3" for a plugin pane
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parse_id = |id: &str| {
+            id.parse::<u32>()
+                .map_err(|_| format!("Failed to parse PaneId. Unknown PaneId: {}", s))
+        };
+        match s.strip_prefix("plugin_") {
+            Some(plugin_id) => parse_id(plugin_id).map(PaneId::Plugin),
+            None => parse_id(s.strip_prefix("terminal_").unwrap_or(s)).map(PaneId::Terminal),
+        }
+    }
+}
+
 pub fn client_id_to_colors(
     client_id: ClientId,
     colors: Palette,
@@ -311,6 +326,40 @@ impl FromStr for Resize {
     }
 }
 
+/// A size to resize a pane to along its dominant axis, as a part of the available space.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Deserialize, Serialize)]
+pub enum ResizePreset {
+    Half,
+    Third,
+    GoldenRatio,
+}
+
+impl ResizePreset {
+    pub fn portion_of(&self, space: usize) -> usize {
+        let ratio = match self {
+            ResizePreset::Half => 1.0 / 2.0,
+            ResizePreset::Third => 1.0 / 3.0,
+            ResizePreset::GoldenRatio => 2.0 / (1.0 + 5.0_f64.sqrt()), // ~0.618
+        };
+        (space as f64 * ratio).round() as usize
+    }
+}
+
+impl FromStr for ResizePreset {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Half" | "half" => Ok(ResizePreset::Half),
+            "Third" | "third" => Ok(ResizePreset::Third),
+            "GoldenRatio" | "golden_ratio" => Ok(ResizePreset::GoldenRatio),
+            _ => Err(format!(
+                "failed to parse resize preset. Unknown preset '{}'",
+                s
+            )),
+        }
+    }
+}
+
 /// Container type that fully describes resize operations.
 ///
 /// This is best thought of as follows:
@@ -494,18 +543,19 @@ pub enum Event {
     Mouse(Mouse),
     /// A timer expired set by the `set_timeout` method exported by `zellij-tile`.
     Timer(f64),
-    /// Text was copied to the clipboard anywhere in the app
+    /// Text is being copied to the clipboard anywhere in the app, sent before the clipboard is
+    /// written to (see `ClipboardCopySucceeded` and `SystemClipboardFailure` for how it went)
     CopyToClipboard(CopyDestination),
-    /// Failed to copy text to clipboard anywhere in the app
-    SystemClipboardFailure,
+    /// Failed to copy text to clipboard anywhere in the app, with the length in bytes of the text
+    SystemClipboardFailure(usize),
     /// Input was received anywhere in the app
     InputReceived,
     /// This plugin became visible or invisible
     Visible(bool),
     /// A message from one of the plugin's workers
     CustomMessage(
-        String, // message
-        String, // payload
+        String,  // message
+        Vec<u8>, // payload
     ),
     /// A file was created somewhere in the Zellij CWD folder
     FileSystemCreate(Vec<PathBuf>),
@@ -523,6 +573,60 @@ pub enum Event {
     ),
     RunCommandResult(Option<i32>, Vec<u8>, Vec<u8>, BTreeMap<String, String>), // exit_code, STDOUT, STDERR,
     // context
+    /// Rolling statistics of the time between receiving input from each client and dispatching
+    /// the next render to it
+    InputLatencyStats(Vec<ClientInputLatency>),
+    /// The process of a pane exited, along with its exit status if it is known
+    PaneClosed(PaneId, Option<i32>), // pane_id, exit_status
+    /// Whether the session is shared with other clients or its output is being recorded, sent
+    /// when this changes and when requested by the plugin
+    SessionSharingStatus(SessionSharingStatus),
+    /// The user entered one of the modes in which the focused pane can be scrolled and its text
+    /// selected (Scroll, Search or EnterSearch)
+    EnteredCopyMode,
+    /// The user left the Scroll, Search and EnterSearch modes
+    ExitedCopyMode,
+    /// The text selection in a terminal pane changed, `None` if it was cleared
+    SelectionChanged(PaneId, Option<SelectionBounds>),
+    /// A URL or file the plugin asked to open with the system opener could not be opened
+    SystemOpenerFailure(String, String), // url or path, error
+    /// The plugin attempted a command requiring a permission it was not granted, sent once per
+    /// permission so that the plugin can request it from the user
+    PermissionDenied(PermissionType),
+    /// The state this plugin returned from `serialize_state` when its session was serialized,
+    /// sent once before its first render when the session is resurrected
+    RestoreState(Vec<u8>),
+    /// Text the user pasted into this plugin's pane, sent whole instead of as `Key` events (to
+    /// plugins not subscribed to it, the pasted text is still sent as `Key` events)
+    Paste(String),
+    /// The status-bar segments registered by all plugins, ordered by descending priority. Sent
+    /// when a segment is registered, re-rendered with different content or removed
+    StatusSegmentsUpdate(Vec<StatusSegment>),
+    /// Entries of the session activity log, oldest first. Sent with each new entry and with the
+    /// whole log when requested by the plugin
+    SessionActivity(Vec<SessionActivity>),
+    /// Output of a pane the plugin subscribed to with `subscribe_pane_output`, as the raw bytes
+    /// produced by the pty. Output arriving while the plugin is busy is sent as a single chunk
+    PaneOutput(PaneId, Vec<u8>),
+    /// The input mode of the client changed, sent when entering and when leaving a mode
+    ModeChanged(InputMode),
+    /// The user clicked a hyperlink (OSC 8) in a terminal pane, with the URI it points to
+    HyperlinkClicked(PaneId, String),
+    /// The resource usage of all running plugins, sent periodically (see the
+    /// `plugin_metrics_interval` option) while at least one plugin is subscribed to it
+    PluginMetrics(Vec<PluginMetric>),
+    /// A terminal pane rang the bell (see the `bell` option)
+    Bell(PaneId),
+    /// The session switched to the workspace with this name, along with its set of tabs
+    WorkspaceChanged(String),
+    /// Text was copied to the clipboard anywhere in the app, with its length in bytes. Unlike
+    /// `CopyToClipboard`, this is only sent once the clipboard was written to successfully.
+    ClipboardCopySucceeded(usize),
+    /// The process in a terminal pane set its title (OSC 0/2), with the new title
+    PaneTitleChanged(PaneId, String),
+    /// A terminal pane this plugin asked for with `open_terminal_pane` or `new_plugin_fed_pane`
+    /// opened, with its id
+    TerminalPaneOpened(u32),
     WebRequestResult(
         u16,
         BTreeMap<String, String>,
@@ -559,6 +663,12 @@ pub enum Permission {
     OpenTerminalsOrPlugins,
     WriteToStdin,
     WebAccess,
+    WriteToClipboard,
+    ReadPaneOutput,
+    ReadClipboard,
+    FocusSelf,
+    OpenUrls,
+    OpenFilesWithSystemOpener,
 }
 
 impl PermissionType {
@@ -575,6 +685,16 @@ impl PermissionType {
             PermissionType::OpenTerminalsOrPlugins => "Start new terminals and plugins".to_owned(),
             PermissionType::WriteToStdin => "Write to standard input (STDIN)".to_owned(),
             PermissionType::WebAccess => "Make web requests".to_owned(),
+            PermissionType::WriteToClipboard => "Write to the clipboard".to_owned(),
+            PermissionType::ReadPaneOutput => "Read the output of panes".to_owned(),
+            PermissionType::ReadClipboard => "Read the clipboard".to_owned(),
+            PermissionType::FocusSelf => {
+                "Bring its own pane to the foreground for all connected clients".to_owned()
+            },
+            PermissionType::OpenUrls => "Open web and mailto links".to_owned(),
+            PermissionType::OpenFilesWithSystemOpener => {
+                "Open files with the system opener (which can run executables)".to_owned()
+            },
         }
     }
 }
@@ -681,6 +801,26 @@ impl Default for PaletteColor {
     }
 }
 
+impl PaletteColor {
+    /// Parses a hex color in the format `#RGB` or `#RRGGBB`
+    pub fn from_hex(hex: &str) -> Option<Self> {
+        let digits: Vec<u8> = hex
+            .strip_prefix('#')?
+            .chars()
+            .map(|c| c.to_digit(16).map(|d| d as u8))
+            .collect::<Option<_>>()?;
+        match digits.as_slice() {
+            [r, g, b] => Some(PaletteColor::Rgb((r * 17, g * 17, b * 17))),
+            [r1, r2, g1, g2, b1, b2] => Some(PaletteColor::Rgb((
+                r1 * 16 + r2,
+                g1 * 16 + g2,
+                b1 * 16 + b2,
+            ))),
+            _ => None,
+        }
+    }
+}
+
 impl FromStr for InputMode {
     type Err = ConversionError;
 
@@ -738,6 +878,38 @@ pub struct Palette {
     pub brown: PaletteColor,
 }
 
+impl Palette {
+    /// The entry of this palette with the given name (eg. "red" or "gold"), if there is one
+    pub fn color_by_name(&self, name: &str) -> Option<PaletteColor> {
+        match name {
+            "fg" => Some(self.fg),
+            "bg" => Some(self.bg),
+            "black" => Some(self.black),
+            "red" => Some(self.red),
+            "green" => Some(self.green),
+            "yellow" => Some(self.yellow),
+            "blue" => Some(self.blue),
+            "magenta" => Some(self.magenta),
+            "cyan" => Some(self.cyan),
+            "white" => Some(self.white),
+            "orange" => Some(self.orange),
+            "gray" => Some(self.gray),
+            "purple" => Some(self.purple),
+            "gold" => Some(self.gold),
+            "silver" => Some(self.silver),
+            "pink" => Some(self.pink),
+            "brown" => Some(self.brown),
+            _ => None,
+        }
+    }
+    /// Resolves a color given either as the name of one of the entries of this palette or as a
+    /// hex string (eg. "#ff8800")
+    pub fn resolve_color(&self, color: &str) -> Option<PaletteColor> {
+        self.color_by_name(color)
+            .or_else(|| PaletteColor::from_hex(color))
+    }
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
 pub struct Style {
     pub colors: Palette,
@@ -779,9 +951,94 @@ pub struct SessionInfo {
     pub tabs: Vec<TabInfo>,
     pub panes: PaneManifest,
     pub connected_clients: usize,
+    /// How many of the connected clients are observers, seeing the session without being able to
+    /// send it input
+    pub observer_clients: usize,
     pub is_current_session: bool,
 }
 
+/// A named group of related sessions, attached to together. The first session is the primary one,
+/// the others are its siblings
+#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct WorkspaceInfo {
+    pub name: String,
+    pub sessions: Vec<String>,
+}
+
+impl WorkspaceInfo {
+    pub fn new(name: String, sessions: Vec<String>) -> Self {
+        WorkspaceInfo { name, sessions }
+    }
+    pub fn primary_session(&self) -> Option<&String> {
+        self.sessions.first()
+    }
+    pub fn sibling_sessions(&self) -> &[String] {
+        self.sessions.get(1..).unwrap_or(&[])
+    }
+    /// The other sessions of the workspace that are not running and are started in the background
+    /// when attaching to `session_to_attach`, so that they can be switched to from inside it
+    pub fn sessions_to_start_in_background(
+        &self,
+        session_to_attach: &str,
+        running_sessions: &[String],
+    ) -> Vec<String> {
+        self.sessions
+            .iter()
+            .filter(|s| s.as_str() != session_to_attach && !running_sessions.contains(s))
+            .cloned()
+            .collect()
+    }
+    /// The session attaching to the workspace brings up: the primary session unless one of the
+    /// workspace's sessions is requested explicitly
+    pub fn session_to_attach(&self, requested_session: Option<&str>) -> Result<String, String> {
+        match requested_session {
+            Some(requested_session) => self
+                .sessions
+                .iter()
+                .find(|s| s.as_str() == requested_session)
+                .cloned()
+                .ok_or_else(|| {
+                    format!(
+                        "Session {:?} is not part of workspace {:?}",
+                        requested_session, self.name
+                    )
+                }),
+            None => self
+                .primary_session()
+                .cloned()
+                .ok_or_else(|| format!("Workspace {:?} has no sessions", self.name)),
+        }
+    }
+}
+
+/// The tabs and panes of the current session, as last sent to the plugin in the `TabUpdate` and
+/// `PaneUpdate` events
+#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct SessionLayout {
+    pub tabs: Vec<TabInfo>,
+    pub panes: PaneManifest,
+}
+
+/// The keybindings of the client the plugin runs for, as they are now (including any bindings
+/// the client overrode in its own configuration)
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Keybindings {
+    pub modes: KeybindsVec,
+}
+
+impl Keybindings {
+    /// The keys that trigger exactly these actions in this mode, eg. to render them in a hint
+    pub fn keys_for_actions(&self, mode: InputMode, actions: &[Action]) -> Vec<Key> {
+        self.modes
+            .iter()
+            .filter(|(input_mode, _)| *input_mode == mode)
+            .flat_map(|(_, keybinds)| keybinds.iter())
+            .filter(|(_, bound_actions)| bound_actions.as_slice() == actions)
+            .map(|(key, _)| key.clone())
+            .collect()
+    }
+}
+
 use std::hash::{Hash, Hasher};
 
 #[allow(clippy::derive_hash_xor_eq)]
@@ -809,6 +1066,86 @@ impl SessionInfo {
     }
 }
 
+/// A segment of the status-bar registered by a plugin, along with its latest rendered content
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub struct StatusSegment {
+    pub plugin_id: u32,
+    /// The id the plugin registered this segment with, passed back to it when rendering it
+    pub render_callback_id: u32,
+    /// When there is not enough room for all segments, those with the lowest priority are elided
+    pub priority: u32,
+    pub content: String,
+}
+
+/// The resource usage of a running plugin
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub struct PluginMetric {
+    pub plugin_id: u32,
+    /// The size of the wasm memory of the plugin, added up over its instances (one per client)
+    pub memory_bytes: u64,
+    /// How long the last render of the plugin took (the slowest one if it has several instances)
+    pub last_render_micros: u64,
+}
+
+/// An entry of the session activity log
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub struct SessionActivity {
+    /// When the activity happened, since the unix epoch
+    pub timestamp: Duration,
+    pub kind: SessionActivityKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub enum SessionActivityKind {
+    PaneOpened(PaneId),
+    PaneClosed(PaneId),
+    /// A client switched to the tab at this position
+    TabChanged(ClientId, usize), // client_id, tab position
+    /// A pane was opened running this command
+    CommandSpawned(PaneId, String), // pane_id, command
+}
+
+/// Input latency statistics of a single client, measured from receiving its input until
+/// dispatching the next render to it
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub struct ClientInputLatency {
+    pub client_id: ClientId,
+    /// The number of samples these statistics were calculated from
+    pub sample_count: usize,
+    pub last: Duration,
+    pub average: Duration,
+    pub min: Duration,
+    pub max: Duration,
+}
+
+/// Whether anyone other than the user might see the output of the session, for plugins that want
+/// to hide sensitive output
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub struct SessionSharingStatus {
+    /// The number of clients attached to the session
+    pub connected_clients: usize,
+    /// Whether the output of the session's panes is logged to files (eg. when running with
+    /// `--debug`)
+    pub is_recording: bool,
+}
+
+/// The bounds of a text selection in a terminal pane, relative to the top left of the pane's
+/// viewport. Lines in the scrollback above the viewport are negative. The end is exclusive.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub struct SelectionBounds {
+    pub start_line: isize,
+    pub start_column: usize,
+    pub end_line: isize,
+    pub end_column: usize,
+}
+
+impl SessionSharingStatus {
+    /// Whether more than one client is attached to the session
+    pub fn is_shared(&self) -> bool {
+        self.connected_clients > 1
+    }
+}
+
 /// Contains all the information for a currently opened tab.
 #[derive(Debug, Default, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
 pub struct TabInfo {
@@ -829,6 +1166,10 @@ pub struct TabInfo {
     pub active_swap_layout_name: Option<String>,
     /// Whether the user manually changed the layout, moving out of the swap layout scheme
     pub is_swap_layout_dirty: bool,
+    /// Whether a pane in this tab rang the bell since it was last focused (see the `bell` option)
+    pub has_bell: bool,
+    /// The color of this tab in the tab bar, either the name of a palette entry or a hex string
+    pub color: Option<String>,
 }
 
 /// The `PaneManifest` contains a dictionary of panes, indexed by the tab position (0 indexed).
@@ -896,10 +1237,11 @@ pub struct PaneInfo {
     pub is_selectable: bool,
 }
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
 pub struct PluginIds {
-    pub plugin_id: u32,
+    pub plugin_id: u32, // assigned whenever the plugin is loaded, changes when it is reloaded
     pub zellij_pid: u32,
+    pub stable_plugin_id: String, // derived from the plugin location and configuration
 }
 
 /// Tag used to identify the plugin in layout and config kdl files
@@ -1003,25 +1345,26 @@ pub struct ConnectToSession {
     pub pane_id: Option<(u32, bool)>, // (id, is_plugin)
 }
 
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
 pub struct PluginMessage {
     pub name: String,
-    pub payload: String,
+    /// Passed as is between the plugin and its workers, so it can hold any (eg. serialized) data
+    pub payload: Vec<u8>,
     pub worker_name: Option<String>,
 }
 
 impl PluginMessage {
-    pub fn new_to_worker(worker_name: &str, message: &str, payload: &str) -> Self {
+    pub fn new_to_worker(worker_name: &str, message: &str, payload: impl AsRef<[u8]>) -> Self {
         PluginMessage {
             name: message.to_owned(),
-            payload: payload.to_owned(),
+            payload: payload.as_ref().to_vec(),
             worker_name: Some(worker_name.to_owned()),
         }
     }
-    pub fn new_to_plugin(message: &str, payload: &str) -> Self {
+    pub fn new_to_plugin(message: &str, payload: impl AsRef<[u8]>) -> Self {
         PluginMessage {
             name: message.to_owned(),
-            payload: payload.to_owned(),
+            payload: payload.as_ref().to_vec(),
             worker_name: None,
         }
     }
@@ -1126,5 +1469,55 @@ pub enum PluginCommand {
         Vec<u8>,                  // body
         BTreeMap<String, String>, // context
     ),
-    RenameSession(String), // String -> new session name
+    RenameSession(String),   // String -> new session name
+    PluginReadFile(PathBuf), // relative to the plugin's own persistent data folder
+    PluginWriteFile(
+        PathBuf, // relative to the plugin's own persistent data folder
+        Vec<u8>, // contents
+    ),
+    RequestSessionSharingStatus,
+    SubscribeWithFileSystemFilter(
+        HashSet<EventType>,
+        Vec<String>, // path globs, FileSystem events are only sent for paths matching one of them
+    ),
+    CopyToClipboard(String), // text to copy
+    LogMessage(String),
+    GetSessionLayout,
+    OpenUrl(String),                   // opened with the system opener (eg. xdg-open)
+    OpenFileWithSystemOpener(PathBuf), // relative to the plugin's cwd
+    RenderOverlay(
+        usize,   // x
+        usize,   // y
+        usize,   // width
+        usize,   // height
+        Vec<u8>, // contents
+    ),
+    ClearOverlay,
+    NewPluginFedPane(Option<String>), // optional pane name
+    WriteToPaneScrollback(
+        u32,         // terminal pane id
+        Vec<String>, // lines
+    ),
+    RegisterStatusSegment(
+        u32, // priority
+        u32, // render callback id
+    ),
+    RequestSessionActivity,
+    SubscribePaneOutput(PaneId),
+    UnsubscribePaneOutput(PaneId),
+    SetPaneName(String), // the name of the plugin's own pane
+    SetTabName(String),  // the name of the tab the plugin's pane is in
+    ReadClipboard,
+    FocusSelf,
+    GetKeybindings,
+    RenderChunk(
+        u64,     // frame id
+        Vec<u8>, // the next chunk of the frame's rendered bytes
+        bool,    // whether this is the last chunk of the frame
+    ),
+    OpenTerminalPane(
+        PathBuf,              // cwd, relative to the plugin's cwd
+        Option<CommandToRun>, // runs in the pane instead of the default shell
+    ),
+    ClosePane(u32), // the id of a terminal pane opened by the plugin with OpenTerminalPane
 }
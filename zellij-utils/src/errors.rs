@@ -212,6 +212,8 @@ impl Display for ContextType {
 pub enum ScreenContext {
     HandlePtyBytes,
     PluginBytes,
+    PluginRenderQueued,
+    PluginLogLine,
     Render,
     NewPane,
     OpenInPlaceEditor,
@@ -323,6 +325,7 @@ pub enum ScreenContext {
     SearchToggleWholeWord,
     SearchToggleWrap,
     AddRedPaneFrameColorOverride,
+    AddBellPaneFrameColorOverride,
     ClearPaneFrameColorOverride,
     PreviousSwapLayout,
     NextSwapLayout,
@@ -339,6 +342,7 @@ pub enum ScreenContext {
     LaunchPlugin,
     SuppressPane,
     FocusPaneWithId,
+    FocusPaneWithIdForAllClients,
     RenamePane,
     RenameTab,
     RequestPluginPermissions,
@@ -350,6 +354,64 @@ pub enum ScreenContext {
     NewInPlacePluginPane,
     DumpLayoutToHd,
     RenameSession,
+    SessionRenamed,
+    ToggleRuler,
+    SwapLayout,
+    FocusNextPaneSpatial,
+    CaptureRendered,
+    FocusPaneById,
+    RequestSessionSharingStatus,
+    FocusPaneByName,
+    PopOutPane,
+    CopyTextToClipboard,
+    SetPaneMinSize,
+    ResizePaneTo,
+    ResizePaneToPreset,
+    ToggleShowInvisibles,
+    TogglePanePinned,
+    SetInputBroadcast,
+    StackPanes,
+    CycleStack,
+    RenderPluginOverlay,
+    ClearPluginOverlay,
+    TogglePaneFrame,
+    ToggleZoom,
+    NewPluginFedPane,
+    WriteToPaneScrollback,
+    DuplicatePane,
+    SearchInPane,
+    ClearSearch,
+    MoveTab,
+    MoveActiveTab,
+    RequestSessionActivity,
+    ClosePanesMatching,
+    SubscribePaneOutput,
+    UnsubscribePaneOutput,
+    ClearPluginPaneOutputSubscriptions,
+    ClearPaneOutputSubscriptions,
+    Ping,
+    SetPaneScrollLines,
+    SplitPaneWithRatio,
+    RenamePluginTab,
+    UndoLayoutChange,
+    SetPaneReadOnly,
+    GoToTabByName,
+    ApplyLayoutToTab,
+    FillInPlaceLayout,
+    ForceFullRender,
+    SetFocusDimming,
+    ToggleFloatingPanesInTab,
+    SwitchWorkspace,
+    ResizeTabToContent,
+    SetPanePassthrough,
+    TogglePanePassthrough,
+    CapturePaneText,
+    SetTabColor,
+    SetClientTheme,
+    SetSynchronizedScroll,
+    SetPaneFrameStyle,
+    NewPaneAlias,
+    MergePanes,
 }
 
 /// Stack call representations corresponding to the different types of [`PtyInstruction`]s.
@@ -359,6 +421,7 @@ pub enum PtyContext {
     OpenInPlaceEditor,
     SpawnTerminalVertically,
     SpawnTerminalHorizontally,
+    SpawnTerminalWithRatio,
     UpdateActivePane,
     GoToTab,
     NewTab,
@@ -370,6 +433,11 @@ pub enum PtyContext {
     DumpLayout,
     LogLayoutToHd,
     FillPluginCwd,
+    SpawnPluginFedPane,
+    QuerySessionEnv,
+    SpawnPluginOwnedTerminal,
+    SpawnPaneAlias,
+    SpawnTerminalsForLayoutInPlace,
     Exit,
 }
 
@@ -382,6 +450,7 @@ pub enum PluginContext {
     Unload,
     Reload,
     Resize,
+    ResendPluginFrame,
     Exit,
     AddClient,
     RemoveClient,
@@ -394,6 +463,15 @@ pub enum PluginContext {
     PermissionRequestResult,
     DumpLayout,
     LogLayoutToHd,
+    MessageToPlugin,
+    LoadBackgroundPlugin,
+    Barrier,
+    StatusSegmentsRendered,
+    PaneOutputQueued,
+    CollectPluginMetrics,
+    RerenderPlugins,
+    TerminalPaneOpened,
+    TerminalPaneClosed,
 }
 
 /// Stack call representations corresponding to the different types of [`ClientInstruction`]s.
@@ -414,6 +492,9 @@ pub enum ClientContext {
     DoneParsingStdinQuery,
     SwitchSession,
     SetSynchronisedOutput,
+    SessionList,
+    SetPassthrough,
+    Pong,
 }
 
 /// Stack call representations corresponding to the different types of [`ServerInstruction`]s.
@@ -432,6 +513,13 @@ pub enum ServerContext {
     ActiveClients,
     Log,
     SwitchSession,
+    ListSessions,
+    DetachIdleClients,
+    RenameSession,
+    ForceFullRender,
+    SwitchWorkspace,
+    SetClientTheme,
+    AttachToPane,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
@@ -446,6 +534,7 @@ pub enum PtyWriteContext {
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum BackgroundJobContext {
     DisplayPaneError,
+    FlashPaneFrame,
     AnimatePluginLoading,
     StopPluginLoadingAnimation,
     ReadAllSessionInfosOnMachine,
@@ -453,6 +542,8 @@ pub enum BackgroundJobContext {
     ReportLayoutInfo,
     RunCommand,
     WebRequest,
+    WatchIdleClients,
+    OpenWithSystemOpener,
     Exit,
 }
 
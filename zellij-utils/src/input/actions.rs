@@ -7,10 +7,10 @@ use super::layout::{
 };
 use crate::cli::CliAction;
 use crate::data::InputMode;
-use crate::data::{Direction, Resize};
+use crate::data::{Direction, Palette, PaneId, Resize, ResizePreset};
 use crate::home::{find_default_config_dir, get_layout_dir};
 use crate::input::config::{Config, ConfigError, KdlError};
-use crate::input::options::OnForceClose;
+use crate::input::options::{OnForceClose, PaneFrameStyle, ScrollLines};
 use miette::{NamedSource, Report};
 use serde::{Deserialize, Serialize};
 
@@ -90,6 +90,14 @@ impl FromStr for SearchOption {
     }
 }
 
+/// Which panes to close with [`Action::ClosePanesMatching`]
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub enum PanePredicate {
+    ExitedZero,       // command panes whose command exited successfully
+    ExitedNonZero,    // command panes whose command failed or was killed
+    NameGlob(String), // eg. "cargo *", matched against the pane title
+}
+
 // As these actions are bound to the default config, please
 // do take care when refactoring - or renaming.
 // They might need to be adjusted in the default config
@@ -253,6 +261,109 @@ pub enum Action {
     BreakPaneRight,
     BreakPaneLeft,
     RenameSession(String),
+    /// Toggle a ruler with line and column indices on the focused pane
+    ToggleRuler,
+    /// Send a named message to a plugin, launching it in the background first if it is not loaded
+    MessageToPlugin {
+        plugin_url: String,
+        name: String,
+        payload: Option<String>,
+    },
+    /// Pop the focused pane out into a new terminal window, docking it back when it is closed
+    PopOutPane,
+    /// Query the environment variables set for all the panes of the session
+    QuerySessionEnv,
+    /// Go to the first tab with this name, doing nothing (but reporting an error) if there is none
+    GoToTabByName(String),
+    /// Toggle pass-through mode on the focused pane, forwarding every key to it verbatim except
+    /// the `passthrough_escape_key`
+    TogglePanePassthrough,
+    /// Set the number of lines the mouse wheel scrolls the focused pane by, `None` to go back to
+    /// the `scroll_lines` option
+    SetScrollLines(Option<ScrollLines>),
+    /// Draw the frames of all panes with these characters, `None` for thin lines
+    SetPaneFrameStyle(Option<PaneFrameStyle>),
+    /// Undo the latest layout change, eg. bring back the latest pane or tab that was closed
+    UndoLayoutChange,
+    /// Split the focused pane in a direction (left and up split like right and down, as with
+    /// `NewPane`), the focused pane keeping this percent of its space rather than half of it
+    SplitPaneWithRatio(Direction, u8),
+    /// Focus the pane with this id in whichever tab it is, switching to that tab
+    FocusPaneById(u32, bool), // bool is is_plugin
+    /// Toggle showing whitespace and control characters as visible glyphs in the focused pane
+    ToggleShowInvisibles,
+    /// Resize the focused pane along its dominant axis to a part of the tab
+    ResizePaneToPreset(ResizePreset),
+    /// Switch all clients to the workspace with this name, creating it if it does not exist
+    SwitchWorkspace(String),
+    /// Render everything this client sees with this palette instead of the session's theme
+    SetClientTheme(Palette),
+    /// Open a pane in the current tab mirroring the terminal pane with this id
+    AttachToPane(u32),
+    /// Reshape the active tab to match this layout in place, keeping its panes
+    ApplyLayoutToTab(TiledPaneLayout),
+    /// Reply with the plain text of the focused pane, optionally with its scrollback
+    CapturePaneText(bool), // bool - include_scrollback
+    /// Reply with the cells currently rendered to the client, as JSON
+    CaptureRendered,
+    /// Highlight the occurrences of this text in the focused pane
+    SearchInPane(String, bool), // bool - case_sensitive
+    /// Clear the highlighted occurrences of a search in the focused pane
+    ClearSearch,
+    /// Close the panes matching the predicate in all tabs
+    ClosePanesMatching(PanePredicate),
+    /// Stack these tiled panes of the active tab in the place of the first one
+    StackPanes(Vec<PaneId>),
+    /// Focus the next (down/right) or previous (up/left) pane of the focused stack
+    CycleStack(Direction),
+    /// Open a new pane next to the focused one in the same cwd, running the same command
+    DuplicatePane,
+    /// Focus the pane after the focused one in reading order (left-to-right, then top-to-bottom)
+    FocusNextPaneSpatial,
+    /// Focus the pane with this name in whichever tab it is, switching to that tab
+    FocusPaneByName(String),
+    /// Close the second pane and grow the first one over the space it took, if they share a whole
+    /// border
+    MergePanes(PaneId, PaneId), // keep_pane_id, absorb_pane_id
+    /// Move the active tab one position back (left/up) or forward (right/down)
+    MoveTab(Direction),
+    /// Resize the focused pane to this many columns and rows, or as close to it as its neighbours
+    /// allow
+    ResizePaneTo(usize, usize), // cols, rows
+    /// Size the tiled panes of the active tab to their content
+    ResizeTabToContent,
+    /// Dim the panes that are not focused by blending their colors this percent towards the
+    /// background, `None` to stop dimming them
+    SetFocusDimming(Option<u8>),
+    /// Send input to all panes of the active tab or, if false, only to the focused pane
+    SetInputBroadcast(bool),
+    /// Keep the focused pane at least this many columns and rows when other panes or the tab are
+    /// resized
+    SetPaneMinSize(usize, usize), // min cols, min rows
+    /// Stop sending input to the focused pane (it can still be scrolled) or, if false, send it
+    /// input again
+    SetPaneReadOnly(bool),
+    /// Scroll all panes of the active tab along with the one that is scrolled or, if false, only
+    /// that one
+    SetSynchronizedScroll(bool),
+    /// Color the active tab in the tab bar with a palette color name or a hex color, `None` to
+    /// remove its color
+    SetTabColor(Option<String>),
+    /// Rearrange the panes of the tab at this position (starting at 1, like `GoToTab`) into its
+    /// next swap layout, or its previous one if true, whether or not it is the active tab
+    SwapTabLayout(u32, bool), // bool - previous
+    /// Hide the floating panes of the tab at this position (starting at 1, like `GoToTab`), or
+    /// show them again where they were, whether or not it is the active tab
+    ToggleFloatingPanesInTab(u32),
+    /// Toggle the frame of the focused pane only, rather than the frames of all panes
+    TogglePaneFrame,
+    /// Pin the focused floating pane so that it is drawn above the other panes, or unpin it
+    TogglePanePinned,
+    /// Expand the focused pane to the whole tab, or restore the layout it was zoomed from
+    ToggleZoom,
+    /// Switch the client to the specified input mode, for cli clients this is the first
+    /// connected client
+    SwitchModeForClient(InputMode),
 }
 
 impl Action {
@@ -310,13 +421,14 @@ impl Action {
                 close_on_exit,
                 start_suspended,
                 configuration,
+                enter_mode,
             } => {
                 let current_dir = get_current_dir();
                 let cwd = cwd
                     .map(|cwd| current_dir.join(cwd))
                     .or_else(|| Some(current_dir));
                 let user_configuration = configuration.unwrap_or_default();
-                if let Some(plugin) = plugin {
+                let mut actions = if let Some(plugin) = plugin {
                     let location = RunPluginLocation::parse(&plugin, cwd)
                         .map_err(|e| format!("Failed to parse plugin loction {plugin}: {}", e))?;
                     let plugin = RunPlugin {
@@ -325,9 +437,9 @@ impl Action {
                         configuration: user_configuration,
                     };
                     if floating {
-                        Ok(vec![Action::NewFloatingPluginPane(plugin, name)])
+                        vec![Action::NewFloatingPluginPane(plugin, name)]
                     } else if in_place {
-                        Ok(vec![Action::NewInPlacePluginPane(plugin, name)])
+                        vec![Action::NewInPlacePluginPane(plugin, name)]
                     } else {
                         // it is intentional that a new tiled plugin pane cannot include a
                         // direction
@@ -337,7 +449,7 @@ impl Action {
                         // is being loaded
                         // this is not the case with terminal panes for historical reasons of
                         // backwards compatibility to a time before we had auto layouts
-                        Ok(vec![Action::NewTiledPluginPane(plugin, name)])
+                        vec![Action::NewTiledPluginPane(plugin, name)]
                     }
                 } else if !command.is_empty() {
                     let mut command = command.clone();
@@ -353,28 +465,29 @@ impl Action {
                         hold_on_start,
                     };
                     if floating {
-                        Ok(vec![Action::NewFloatingPane(
-                            Some(run_command_action),
-                            name,
-                        )])
+                        vec![Action::NewFloatingPane(Some(run_command_action), name)]
                     } else if in_place {
-                        Ok(vec![Action::NewInPlacePane(Some(run_command_action), name)])
+                        vec![Action::NewInPlacePane(Some(run_command_action), name)]
                     } else {
-                        Ok(vec![Action::NewTiledPane(
+                        vec![Action::NewTiledPane(
                             direction,
                             Some(run_command_action),
                             name,
-                        )])
+                        )]
                     }
                 } else {
                     if floating {
-                        Ok(vec![Action::NewFloatingPane(None, name)])
+                        vec![Action::NewFloatingPane(None, name)]
                     } else if in_place {
-                        Ok(vec![Action::NewInPlacePane(None, name)])
+                        vec![Action::NewInPlacePane(None, name)]
                     } else {
-                        Ok(vec![Action::NewTiledPane(direction, None, name)])
+                        vec![Action::NewTiledPane(direction, None, name)]
                     }
+                };
+                if let Some(enter_mode) = enter_mode {
+                    actions.push(Action::SwitchModeForClient(enter_mode));
                 }
+                Ok(actions)
             },
             CliAction::Edit {
                 direction,
@@ -435,44 +548,7 @@ impl Action {
                     .map(|cwd| current_dir.join(cwd))
                     .or_else(|| Some(current_dir));
                 if let Some(layout_path) = layout {
-                    let layout_dir = layout_dir
-                        .or_else(|| config.and_then(|c| c.options.layout_dir))
-                        .or_else(|| get_layout_dir(find_default_config_dir()));
-                    let (path_to_raw_layout, raw_layout, swap_layouts) =
-                        Layout::stringified_from_path_or_default(Some(&layout_path), layout_dir)
-                            .map_err(|e| format!("Failed to load layout: {}", e))?;
-                    let layout = Layout::from_str(&raw_layout, path_to_raw_layout, swap_layouts.as_ref().map(|(f, p)| (f.as_str(), p.as_str())), cwd).map_err(|e| {
-                        let stringified_error = match e {
-                            ConfigError::KdlError(kdl_error) => {
-                                let error = kdl_error.add_src(layout_path.as_path().as_os_str().to_string_lossy().to_string(), String::from(raw_layout));
-                                let report: Report = error.into();
-                                format!("{:?}", report)
-                            }
-                            ConfigError::KdlDeserializationError(kdl_error) => {
-                                let error_message = match kdl_error.kind {
-                                    kdl::KdlErrorKind::Context("valid node terminator") => {
-                                        format!("Failed to deserialize KDL node. \nPossible reasons:\n{}\n{}\n{}\n{}",
-                                        "- Missing `;` after a node name, eg. { node; another_node; }",
-                                        "- Missing quotations (\") around an argument node eg. { first_node \"argument_node\"; }",
-                                        "- Missing an equal sign (=) between node arguments on a title line. eg. argument=\"value\"",
-                                        "- Found an extraneous equal sign (=) between node child arguments and their values. eg. { argument=\"value\" }")
-                                    },
-                                    _ => String::from(kdl_error.help.unwrap_or("Kdl Deserialization Error")),
-                                };
-                                let kdl_error = KdlError {
-                                    error_message,
-                                    src: Some(NamedSource::new(layout_path.as_path().as_os_str().to_string_lossy().to_string(), String::from(raw_layout))),
-                                    offset: Some(kdl_error.span.offset()),
-                                    len: Some(kdl_error.span.len()),
-                                    help_message: None,
-                                };
-                                let report: Report = kdl_error.into();
-                                format!("{:?}", report)
-                            },
-                            e => format!("{}", e)
-                        };
-                        stringified_error
-                    })?;
+                    let layout = layout_from_cli_path(layout_path, layout_dir, cwd, config)?;
                     let mut tabs = layout.tabs();
                     if tabs.len() > 1 {
                         return Err(format!("Tab layout cannot itself have tabs"));
@@ -558,10 +634,168 @@ impl Action {
                 Ok(vec![Action::LaunchPlugin(run_plugin, floating, in_place)])
             },
             CliAction::RenameSession { name } => Ok(vec![Action::RenameSession(name)]),
+            CliAction::ToggleRuler => Ok(vec![Action::ToggleRuler]),
+            CliAction::PopOutPane => Ok(vec![Action::PopOutPane]),
+            CliAction::QuerySessionEnv => Ok(vec![Action::QuerySessionEnv]),
+            CliAction::TogglePanePassthrough => Ok(vec![Action::TogglePanePassthrough]),
+            CliAction::SetScrollLines { scroll_lines } => {
+                Ok(vec![Action::SetScrollLines(scroll_lines)])
+            },
+            CliAction::SetPaneFrameStyle { pane_frame_style } => {
+                Ok(vec![Action::SetPaneFrameStyle(pane_frame_style)])
+            },
+            CliAction::UndoLayoutChange => Ok(vec![Action::UndoLayoutChange]),
+            CliAction::SplitPaneWithRatio { direction, percent } => {
+                if percent == 0 || percent >= 100 {
+                    return Err(format!("Percent must be between 1 and 99, got {}", percent));
+                }
+                Ok(vec![Action::SplitPaneWithRatio(direction, percent)])
+            },
+            CliAction::FocusPaneById { pane_id, plugin } => {
+                Ok(vec![Action::FocusPaneById(pane_id, plugin)])
+            },
+            CliAction::ToggleShowInvisibles => Ok(vec![Action::ToggleShowInvisibles]),
+            CliAction::ResizePaneToPreset { preset } => {
+                Ok(vec![Action::ResizePaneToPreset(preset)])
+            },
+            CliAction::SwitchWorkspace { name } => Ok(vec![Action::SwitchWorkspace(name)]),
+            CliAction::SetClientTheme { name } => config
+                .and_then(|c| c.themes.get_theme(&name).map(|theme| theme.palette))
+                .map(|palette| vec![Action::SetClientTheme(palette)])
+                .ok_or_else(|| format!("Theme {} is not defined in the configuration", name)),
+            CliAction::AttachToPane { pane_id } => Ok(vec![Action::AttachToPane(pane_id)]),
+            CliAction::CapturePaneText { scrollback } => {
+                Ok(vec![Action::CapturePaneText(scrollback)])
+            },
+            CliAction::CaptureRendered => Ok(vec![Action::CaptureRendered]),
+            CliAction::SearchInPane {
+                query,
+                case_sensitive,
+            } => Ok(vec![Action::SearchInPane(query, case_sensitive)]),
+            CliAction::ClearSearch => Ok(vec![Action::ClearSearch]),
+            CliAction::ClosePanesMatching {
+                exited_zero,
+                exited_non_zero,
+                name,
+            } => match (exited_zero, exited_non_zero, name) {
+                (true, false, None) => {
+                    Ok(vec![Action::ClosePanesMatching(PanePredicate::ExitedZero)])
+                },
+                (false, true, None) => Ok(vec![Action::ClosePanesMatching(
+                    PanePredicate::ExitedNonZero,
+                )]),
+                (false, false, Some(name_glob)) => Ok(vec![Action::ClosePanesMatching(
+                    PanePredicate::NameGlob(name_glob),
+                )]),
+                _ => Err(String::from(
+                    "Exactly one of --exited-zero, --exited-non-zero or --name is needed",
+                )),
+            },
+            CliAction::StackPanes { pane_ids } => Ok(vec![Action::StackPanes(pane_ids)]),
+            CliAction::CycleStack { direction } => Ok(vec![Action::CycleStack(direction)]),
+            CliAction::DuplicatePane => Ok(vec![Action::DuplicatePane]),
+            CliAction::FocusNextPaneSpatial => Ok(vec![Action::FocusNextPaneSpatial]),
+            CliAction::FocusPaneByName { name } => Ok(vec![Action::FocusPaneByName(name)]),
+            CliAction::MergePanes {
+                keep_pane_id,
+                absorb_pane_id,
+            } => Ok(vec![Action::MergePanes(keep_pane_id, absorb_pane_id)]),
+            CliAction::MoveTab { direction } => Ok(vec![Action::MoveTab(direction)]),
+            CliAction::ResizePaneTo { cols, rows } => Ok(vec![Action::ResizePaneTo(cols, rows)]),
+            CliAction::ResizeTabToContent => Ok(vec![Action::ResizeTabToContent]),
+            CliAction::SetFocusDimming { percent } => match percent {
+                Some(percent) if percent > 100 => Err(format!(
+                    "Percent must be between 0 and 100, got {}",
+                    percent
+                )),
+                _ => Ok(vec![Action::SetFocusDimming(
+                    percent.filter(|percent| *percent > 0),
+                )]),
+            },
+            CliAction::SetInputBroadcast { off } => Ok(vec![Action::SetInputBroadcast(!off)]),
+            CliAction::SetPaneMinSize { cols, rows } => {
+                Ok(vec![Action::SetPaneMinSize(cols, rows)])
+            },
+            CliAction::SetPaneReadOnly { off } => Ok(vec![Action::SetPaneReadOnly(!off)]),
+            CliAction::SetSynchronizedScroll { off } => {
+                Ok(vec![Action::SetSynchronizedScroll(!off)])
+            },
+            CliAction::SetTabColor { color } => Ok(vec![Action::SetTabColor(color)]),
+            CliAction::SwapTabLayout { position, previous } => {
+                Ok(vec![Action::SwapTabLayout(position, previous)])
+            },
+            CliAction::ToggleFloatingPanesInTab { position } => {
+                Ok(vec![Action::ToggleFloatingPanesInTab(position)])
+            },
+            CliAction::TogglePaneFrame => Ok(vec![Action::TogglePaneFrame]),
+            CliAction::TogglePanePinned => Ok(vec![Action::TogglePanePinned]),
+            CliAction::ToggleZoom => Ok(vec![Action::ToggleZoom]),
+            CliAction::ApplyLayoutToTab { layout, layout_dir } => {
+                let cwd = Some(get_current_dir());
+                let layout = layout_from_cli_path(layout, layout_dir, cwd, config)?;
+                let mut tabs = layout.tabs();
+                if tabs.len() > 1 {
+                    Err(String::from("Tab layout cannot itself have tabs"))
+                } else if !tabs.is_empty() {
+                    let (_tab_name, tiled_panes_layout, _floating_panes_layout) =
+                        tabs.drain(..).next().unwrap();
+                    Ok(vec![Action::ApplyLayoutToTab(tiled_panes_layout)])
+                } else {
+                    let (tiled_panes_layout, _floating_panes_layout) = layout.new_tab();
+                    Ok(vec![Action::ApplyLayoutToTab(tiled_panes_layout)])
+                }
+            },
         }
     }
 }
 
+/// Loads the layout at `layout_path` as the cli does, the errors being reports ready to be printed
+fn layout_from_cli_path(
+    layout_path: PathBuf,
+    layout_dir: Option<PathBuf>,
+    cwd: Option<PathBuf>,
+    config: Option<Config>,
+) -> Result<Layout, String> {
+    let layout_dir = layout_dir
+        .or_else(|| config.and_then(|c| c.options.layout_dir))
+        .or_else(|| get_layout_dir(find_default_config_dir()));
+    let (path_to_raw_layout, raw_layout, swap_layouts) =
+        Layout::stringified_from_path_or_default(Some(&layout_path), layout_dir)
+            .map_err(|e| format!("Failed to load layout: {}", e))?;
+    Layout::from_str(&raw_layout, path_to_raw_layout, swap_layouts.as_ref().map(|(f, p)| (f.as_str(), p.as_str())), cwd).map_err(|e| {
+        let stringified_error = match e {
+            ConfigError::KdlError(kdl_error) => {
+                let error = kdl_error.add_src(layout_path.as_path().as_os_str().to_string_lossy().to_string(), String::from(raw_layout));
+                let report: Report = error.into();
+                format!("{:?}", report)
+            }
+            ConfigError::KdlDeserializationError(kdl_error) => {
+                let error_message = match kdl_error.kind {
+                    kdl::KdlErrorKind::Context("valid node terminator") => {
+                        format!("Failed to deserialize KDL node. \nPossible reasons:\n{}\n{}\n{}\n{}",
+                        "- Missing `;` after a node name, eg. { node; another_node; }",
+                        "- Missing quotations (\") around an argument node eg. { first_node \"argument_node\"; }",
+                        "- Missing an equal sign (=) between node arguments on a title line. eg. argument=\"value\"",
+                        "- Found an extraneous equal sign (=) between node child arguments and their values. eg. { argument=\"value\" }")
+                    },
+                    _ => String::from(kdl_error.help.unwrap_or("Kdl Deserialization Error")),
+                };
+                let kdl_error = KdlError {
+                    error_message,
+                    src: Some(NamedSource::new(layout_path.as_path().as_os_str().to_string_lossy().to_string(), String::from(raw_layout))),
+                    offset: Some(kdl_error.span.offset()),
+                    len: Some(kdl_error.span.len()),
+                    help_message: None,
+                };
+                let report: Report = kdl_error.into();
+                format!("{:?}", report)
+            },
+            e => format!("{}", e)
+        };
+        stringified_error
+    })
+}
+
 impl From<OnForceClose> for Action {
     fn from(ofc: OnForceClose) -> Action {
         match ofc {
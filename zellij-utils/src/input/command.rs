@@ -1,6 +1,7 @@
 //! Trigger a command
 use crate::data::Direction;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::path::PathBuf;
 
 #[derive(Debug, Clone)]
@@ -35,6 +36,9 @@ pub struct RunCommand {
     pub hold_on_close: bool,
     #[serde(default)]
     pub hold_on_start: bool,
+    /// Environment variables set for this command on top of those of the session
+    #[serde(default)]
+    pub env: BTreeMap<String, String>,
 }
 
 impl std::fmt::Display for RunCommand {
@@ -78,6 +82,7 @@ impl From<RunCommandAction> for RunCommand {
             cwd: action.cwd,
             hold_on_close: action.hold_on_close,
             hold_on_start: action.hold_on_start,
+            env: BTreeMap::new(),
         }
     }
 }
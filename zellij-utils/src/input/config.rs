@@ -229,7 +229,7 @@ mod config_test {
     use super::*;
     use crate::data::{InputMode, Palette, PaletteColor, PluginTag};
     use crate::input::layout::RunPluginLocation;
-    use crate::input::options::{Clipboard, OnForceClose};
+    use crate::input::options::{Bell, Clipboard, OnForceClose, ScrollLines};
     use crate::input::plugins::{PluginConfig, PluginType, PluginsConfig};
     use crate::input::theme::{FrameConfig, Theme, Themes, UiConfig};
     use std::collections::HashMap;
@@ -414,6 +414,71 @@ mod config_test {
         );
     }
 
+    #[test]
+    fn can_define_scroll_lines_as_a_number_or_half_page() {
+        let config = Config::from_kdl("scroll_lines 5", None).unwrap();
+        assert_eq!(config.options.scroll_lines, Some(ScrollLines::Lines(5)));
+        let config = Config::from_kdl("scroll_lines \"half_page\"", None).unwrap();
+        assert_eq!(config.options.scroll_lines, Some(ScrollLines::HalfPage));
+        assert!(Config::from_kdl("scroll_lines 0", None).is_err());
+        assert!(Config::from_kdl("scroll_lines \"page\"", None).is_err());
+    }
+
+    #[test]
+    fn idle_detach_timeout_below_one_is_an_error() {
+        let config = Config::from_kdl("idle_detach_timeout 60", None).unwrap();
+        assert_eq!(config.options.idle_detach_timeout, Some(60));
+        assert!(Config::from_kdl("idle_detach_timeout -1", None).is_err());
+        assert!(Config::from_kdl("idle_detach_timeout 0", None).is_err());
+    }
+
+    #[test]
+    fn session_activity_log_size_below_one_is_an_error() {
+        let config = Config::from_kdl("session_activity_log_size 50", None).unwrap();
+        assert_eq!(config.options.session_activity_log_size, Some(50));
+        assert!(Config::from_kdl("session_activity_log_size 0", None).is_err());
+        assert!(Config::from_kdl("session_activity_log_size -5", None).is_err());
+    }
+
+    #[test]
+    fn negative_layout_undo_depth_is_an_error() {
+        let config = Config::from_kdl("layout_undo_depth 0", None).unwrap();
+        assert_eq!(config.options.layout_undo_depth, Some(0));
+        assert!(Config::from_kdl("layout_undo_depth -1", None).is_err());
+    }
+
+    #[test]
+    fn plugin_metrics_interval_of_zero_is_an_error() {
+        let config = Config::from_kdl("plugin_metrics_interval 5", None).unwrap();
+        assert_eq!(config.options.plugin_metrics_interval, Some(5));
+        assert!(Config::from_kdl("plugin_metrics_interval 0", None).is_err());
+    }
+
+    #[test]
+    fn can_define_any_combination_of_bell_handlers() {
+        let config = Config::from_kdl("bell \"visual\" \"tab\"", None).unwrap();
+        assert_eq!(
+            config.options.bell,
+            Some(Bell {
+                audible: false,
+                visual: true,
+                tab: true,
+                plugin: false,
+            })
+        );
+        let config = Config::from_kdl("bell \"none\"", None).unwrap();
+        assert_eq!(
+            config.options.bell,
+            Some(Bell {
+                audible: false,
+                visual: false,
+                tab: false,
+                plugin: false,
+            })
+        );
+        assert!(Config::from_kdl("bell \"loud\"", None).is_err());
+    }
+
     #[test]
     fn can_define_themes_in_configfile() {
         let config_contents = r#"
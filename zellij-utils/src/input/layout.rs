@@ -14,6 +14,7 @@ use crate::{
     input::{
         command::RunCommand,
         config::{Config, ConfigError},
+        options::ScrollLines,
     },
     pane_size::{Constraint, Dimension, PaneGeom},
     setup::{self},
@@ -93,6 +94,12 @@ impl Run {
                 if merged.args.is_empty() && !base_run_command.args.is_empty() {
                     merged.args = base_run_command.args.clone();
                 }
+                for (name, value) in &base_run_command.env {
+                    merged
+                        .env
+                        .entry(name.clone())
+                        .or_insert_with(|| value.clone());
+                }
                 Some(Run::Command(merged))
             },
             (Some(Run::Command(base_run_command)), Some(Run::Cwd(other_cwd))) => {
@@ -174,6 +181,15 @@ impl Run {
             }
         }
     }
+    pub fn add_env(&mut self, env: Option<BTreeMap<String, String>>) {
+        // adds to the env of a Run::Command if it is Some, overriding variables
+        // with the same name
+        if let Some(env) = env {
+            if let Run::Command(run_command) = self {
+                run_command.env.extend(env);
+            }
+        }
+    }
     pub fn add_close_on_exit(&mut self, close_on_exit: Option<bool>) {
         // overrides the hold_on_close of a Run::Command if it is Some
         // and not empty
@@ -234,6 +250,10 @@ impl RunPlugin {
             ..Default::default()
         })
     }
+    /// See [`RunPluginLocation::stable_plugin_id`]
+    pub fn stable_plugin_id(&self) -> String {
+        self.location.stable_plugin_id(&self.configuration)
+    }
 }
 
 #[allow(clippy::derive_hash_xor_eq)]
@@ -344,6 +364,21 @@ impl RunPluginLocation {
             RunPluginLocation::Remote(url) => format!("remote:{}", url),
         }
     }
+    /// An id for the plugin at this location running with this configuration. Unlike the plugin
+    /// id, which is assigned anew whenever the plugin is loaded, it is the same across reloads
+    /// (and for all instances of the plugin), so other plugins can keep addressing it by it.
+    pub fn stable_plugin_id(&self, configuration: &PluginUserConfiguration) -> String {
+        let mut stable_plugin_id = Url::from(self).to_string();
+        if !configuration.inner().is_empty() {
+            // encoded so that separators in the configuration values can't be mistaken for
+            // those between the pairs
+            let mut query = url::form_urlencoded::Serializer::new(String::new());
+            query.extend_pairs(configuration.inner().iter());
+            stable_plugin_id.push('?');
+            stable_plugin_id.push_str(&query.finish());
+        }
+        stable_plugin_id
+    }
 }
 
 impl From<&RunPluginLocation> for Url {
@@ -407,6 +442,57 @@ pub struct Layout {
     pub swap_layouts: Vec<(TiledPaneLayout, Vec<FloatingPaneLayout>)>,
     pub swap_tiled_layouts: Vec<SwapTiledLayout>,
     pub swap_floating_layouts: Vec<SwapFloatingLayout>,
+    pub background_plugins: Vec<RunPlugin>, // plugins running for the session without a pane
+}
+
+/// A problem in a layout that would only surface once its panes are spawned
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LayoutWarning {
+    MissingCwd {
+        pane_path: String, // eg. tab "editor" > pane #2
+        cwd: PathBuf,
+    },
+    UnresolvedCommand {
+        pane_path: String,
+        command: PathBuf,
+    },
+    MissingPluginFile {
+        pane_path: String,
+        plugin_path: PathBuf,
+    },
+    PercentSizesExceedFullSize {
+        pane_path: String,
+        total_percent: usize,
+    },
+}
+
+impl Display for LayoutWarning {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        match self {
+            LayoutWarning::MissingCwd { pane_path, cwd } => {
+                write!(f, "{}: cwd {:?} does not exist", pane_path, cwd)
+            },
+            LayoutWarning::UnresolvedCommand { pane_path, command } => {
+                write!(f, "{}: command {:?} could not be found", pane_path, command)
+            },
+            LayoutWarning::MissingPluginFile {
+                pane_path,
+                plugin_path,
+            } => write!(
+                f,
+                "{}: plugin file {:?} does not exist",
+                pane_path, plugin_path
+            ),
+            LayoutWarning::PercentSizesExceedFullSize {
+                pane_path,
+                total_percent,
+            } => write!(
+                f,
+                "{}: the sizes of its parts add up to {}%, which is more than 100%",
+                pane_path, total_percent
+            ),
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
@@ -477,8 +563,12 @@ pub struct FloatingPaneLayout {
     pub y: Option<PercentOrFixed>,
     pub run: Option<Run>,
     pub focus: Option<bool>,
+    pub pinned: Option<bool>,
+    pub read_only: Option<bool>,
+    pub scroll_lines: Option<ScrollLines>,
     pub already_running: bool,
     pub pane_initial_contents: Option<String>,
+    pub plugin_initial_state: Option<Vec<u8>>,
 }
 
 impl FloatingPaneLayout {
@@ -521,9 +611,13 @@ pub struct TiledPaneLayout {
     pub children_are_stacked: bool,
     pub is_expanded_in_stack: bool,
     pub exclude_from_sync: Option<bool>,
+    pub read_only: Option<bool>,
+    pub scroll_lines: Option<ScrollLines>,
     pub run_instructions_to_ignore: Vec<Option<Run>>,
     pub hide_floating_panes: bool, // only relevant if this is the base layout
+    pub color: Option<String>,     // only relevant if this is the base layout
     pub pane_initial_contents: Option<String>,
+    pub plugin_initial_state: Option<Vec<u8>>,
 }
 
 impl TiledPaneLayout {
@@ -1009,6 +1103,28 @@ impl Layout {
         }
     }
 
+    /// Checks the panes of the layout for missing cwds, commands that cannot be found, missing
+    /// plugin files and percent sizes that add up to more than 100%, without spawning anything
+    pub fn validate(&self) -> Vec<LayoutWarning> {
+        let mut warnings = vec![];
+        if self.has_tabs() {
+            for (tab_index, tab) in self.tabs.iter().enumerate() {
+                let (tab_name, tiled_panes, floating_panes) = tab;
+                let tab_path = match tab_name {
+                    Some(tab_name) => format!("tab {:?}", tab_name),
+                    None => format!("tab #{}", tab_index + 1),
+                };
+                validate_tiled_panes(tiled_panes, &tab_path, &mut warnings);
+                validate_floating_panes(floating_panes, &tab_path, &mut warnings);
+            }
+        } else if let Some((tiled_panes, floating_panes)) = &self.template {
+            let tab_path = String::from("tab");
+            validate_tiled_panes(tiled_panes, &tab_path, &mut warnings);
+            validate_floating_panes(floating_panes, &tab_path, &mut warnings);
+        }
+        warnings
+    }
+
     fn swap_layout_and_path(path: &Path) -> Option<(String, String)> {
         // Option<path, stringified_swap_layout>
         let mut swap_layout_path = PathBuf::from(path);
@@ -1043,6 +1159,101 @@ impl Layout {
     }
 }
 
+fn validate_tiled_panes(
+    layout: &TiledPaneLayout,
+    pane_path: &str,
+    warnings: &mut Vec<LayoutWarning>,
+) {
+    validate_run(layout.run.as_ref(), pane_path, warnings);
+    if !layout.children_are_stacked {
+        // stacked panes ignore their sizes
+        let total_percent: usize = layout
+            .children
+            .iter()
+            .filter_map(|child| match child.split_size {
+                Some(SplitSize::Percent(percent)) => Some(percent),
+                _ => None,
+            })
+            .sum();
+        if total_percent > 100 {
+            warnings.push(LayoutWarning::PercentSizesExceedFullSize {
+                pane_path: pane_path.to_owned(),
+                total_percent,
+            });
+        }
+    }
+    for (child_index, child) in layout.children.iter().enumerate() {
+        let child_path = match &child.name {
+            Some(name) => format!("{} > pane {:?}", pane_path, name),
+            None => format!("{} > pane #{}", pane_path, child_index + 1),
+        };
+        validate_tiled_panes(child, &child_path, warnings);
+    }
+}
+
+fn validate_floating_panes(
+    floating_panes: &[FloatingPaneLayout],
+    tab_path: &str,
+    warnings: &mut Vec<LayoutWarning>,
+) {
+    for (pane_index, floating_pane) in floating_panes.iter().enumerate() {
+        let pane_path = match &floating_pane.name {
+            Some(name) => format!("{} > floating pane {:?}", tab_path, name),
+            None => format!("{} > floating pane #{}", tab_path, pane_index + 1),
+        };
+        validate_run(floating_pane.run.as_ref(), &pane_path, warnings);
+    }
+}
+
+fn validate_run(run: Option<&Run>, pane_path: &str, warnings: &mut Vec<LayoutWarning>) {
+    if let Some(cwd) = run.and_then(|run| run.get_cwd()) {
+        if !cwd.is_dir() {
+            warnings.push(LayoutWarning::MissingCwd {
+                pane_path: pane_path.to_owned(),
+                cwd,
+            });
+        }
+    }
+    match run {
+        Some(Run::Command(run_command)) => {
+            if !command_is_resolvable(&run_command.command, run_command.cwd.as_ref()) {
+                warnings.push(LayoutWarning::UnresolvedCommand {
+                    pane_path: pane_path.to_owned(),
+                    command: run_command.command.clone(),
+                });
+            }
+        },
+        Some(Run::Plugin(run_plugin)) => {
+            if let RunPluginLocation::File(plugin_path) = &run_plugin.location {
+                // relative paths might still be found in the plugin dir once the plugin is loaded
+                let plugin_file_exists =
+                    plugin_path.exists() || plugin_path.with_extension("wasm").exists();
+                if plugin_path.is_absolute() && !plugin_file_exists {
+                    warnings.push(LayoutWarning::MissingPluginFile {
+                        pane_path: pane_path.to_owned(),
+                        plugin_path: plugin_path.clone(),
+                    });
+                }
+            }
+        },
+        _ => {},
+    }
+}
+
+fn command_is_resolvable(command: &Path, cwd: Option<&PathBuf>) -> bool {
+    if command.components().count() > 1 {
+        // this is a path to the command rather than its name, so it's not looked up in PATH
+        match cwd {
+            Some(cwd) => cwd.join(command).is_file(),
+            None => command.is_file(),
+        }
+    } else {
+        std::env::var_os("PATH")
+            .map(|paths| std::env::split_paths(&paths).any(|path| path.join(command).is_file()))
+            .unwrap_or(false)
+    }
+}
+
 fn split_space(
     space_to_split: &PaneGeom,
     layout: &TiledPaneLayout,
@@ -32,6 +32,57 @@ impl FromStr for OnForceClose {
     }
 }
 
+/// What to do with the panes of a tab that have no place in a layout applied to it in place
+#[derive(Copy, Clone, Debug, PartialEq, Deserialize, Serialize, ArgEnum)]
+pub enum SurplusPanes {
+    #[serde(alias = "float")]
+    Float,
+    #[serde(alias = "close")]
+    Close,
+}
+
+impl Default for SurplusPanes {
+    fn default() -> Self {
+        Self::Float
+    }
+}
+
+impl FromStr for SurplusPanes {
+    type Err = Box<dyn std::error::Error>;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "float" => Ok(Self::Float),
+            "close" => Ok(Self::Close),
+            e => Err(e.to_string().into()),
+        }
+    }
+}
+
+/// The set of characters the frames of panes are drawn with
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Deserialize, Serialize, ArgEnum)]
+pub enum PaneFrameStyle {
+    #[serde(alias = "unicode-heavy")]
+    UnicodeHeavy,
+    #[serde(alias = "unicode-rounded")]
+    UnicodeRounded,
+    #[serde(alias = "ascii")]
+    Ascii,
+}
+
+impl FromStr for PaneFrameStyle {
+    type Err = Box<dyn std::error::Error>;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "unicode-heavy" => Ok(Self::UnicodeHeavy),
+            "unicode-rounded" => Ok(Self::UnicodeRounded),
+            "ascii" => Ok(Self::Ascii),
+            e => Err(e.to_string().into()),
+        }
+    }
+}
+
 #[derive(Clone, Default, Debug, PartialEq, Deserialize, Serialize, Args)]
 /// Options that can be set either through the config file,
 /// or cli flags - cli flags should take precedence over the config file
@@ -143,6 +194,12 @@ pub struct Options {
     #[serde(default)]
     pub scrollback_lines_to_serialize: Option<usize>,
 
+    /// Whether command panes whose process exited successfully are serialized along with the
+    /// session, default is true
+    #[clap(long, value_parser)]
+    #[serde(default)]
+    pub serialize_successfully_exited_panes: Option<bool>,
+
     /// Whether to use ANSI styled underlines
     #[clap(long, value_parser)]
     #[serde(default)]
@@ -151,6 +208,90 @@ pub struct Options {
     /// The interval at which to serialize sessions for resurrection (in seconds)
     #[clap(long, value_parser)]
     pub serialization_interval: Option<u64>,
+
+    /// Whether cells that a pane did not paint are left with the terminal's default background
+    /// (eg. to keep a transparent terminal background visible), default is false
+    #[clap(long, value_parser)]
+    #[serde(default)]
+    pub transparent_background: Option<bool>,
+
+    /// Whether the scrollback of a pane stays accessible (eg. in scroll mode) while the program
+    /// running in it uses the alternate screen, rather than being hidden, default is false
+    #[clap(long, value_parser)]
+    #[serde(default)]
+    pub keep_scrollback_in_alternate_screen: Option<bool>,
+
+    /// A command that opens a new terminal window running the command appended to it, used to
+    /// pop panes out into their own window (eg. "alacritty -e")
+    #[clap(long, value_parser)]
+    #[serde(default)]
+    pub pop_out_terminal_command: Option<String>,
+
+    /// Detach clients that sent no input for this many seconds (at least 1), keeping the session
+    /// running
+    #[clap(long, value_parser)]
+    #[serde(default)]
+    pub idle_detach_timeout: Option<u64>,
+
+    /// The number of entries kept in the session activity log plugins can read (at least 1,
+    /// default: 1000)
+    #[clap(long, value_parser)]
+    #[serde(default)]
+    pub session_activity_log_size: Option<usize>,
+
+    /// The number of lines the mouse wheel scrolls a pane by, or "half_page" for half the height
+    /// of the pane (default: 3)
+    #[clap(long, value_parser)]
+    #[serde(default)]
+    pub scroll_lines: Option<ScrollLines>,
+
+    /// The number of layout changes (eg. closing a pane or a tab) that can be undone, 0 disables
+    /// undo (default: 10)
+    #[clap(long, value_parser)]
+    #[serde(default)]
+    pub layout_undo_depth: Option<usize>,
+
+    /// The interval at which plugins subscribed to plugin metrics are sent them (in seconds, at
+    /// least 1, default: 1)
+    #[clap(long, value_parser)]
+    #[serde(default)]
+    pub plugin_metrics_interval: Option<u64>,
+
+    /// What to do with the panes left over when a layout with fewer panes is applied to an
+    /// existing tab (float or close, default: float)
+    #[clap(long, arg_enum, hide_possible_values = true, value_parser)]
+    #[serde(default)]
+    pub surplus_panes: Option<SurplusPanes>,
+
+    /// How to react when a terminal pane rings the bell, any of "audible", "visual", "tab" and
+    /// "plugin" separated by commas, or "none" (default: audible,plugin)
+    #[clap(long, value_parser)]
+    #[serde(default)]
+    pub bell: Option<Bell>,
+
+    /// The key that leaves pass-through mode, where all other keys are sent to the focused pane
+    /// as they are (default: "Ctrl g")
+    #[clap(long, value_parser)]
+    #[serde(default)]
+    pub passthrough_escape_key: Option<String>,
+
+    /// The characters pane frames are drawn with (unicode-heavy, unicode-rounded or ascii,
+    /// default: thin unicode lines, with rounded corners if set in the ui config)
+    #[clap(long, arg_enum, hide_possible_values = true, value_parser)]
+    #[serde(default)]
+    pub pane_frame_style: Option<PaneFrameStyle>,
+
+    /// How much the panes that are not focused are dimmed, as the percent their colors are blended
+    /// towards the background (default: 0, no dimming)
+    #[clap(long, value_parser)]
+    #[serde(default)]
+    pub focus_dimming: Option<u8>,
+
+    /// Switch to using a user supplied command that prints the clipboard, for plugins that read
+    /// it (without one, plugins can't read the clipboard)
+    #[clap(long, value_parser)]
+    #[serde(default)]
+    pub paste_command: Option<String>,
 }
 
 #[derive(ArgEnum, Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
@@ -178,6 +319,93 @@ impl FromStr for Clipboard {
     }
 }
 
+pub const DEFAULT_SCROLL_LINES: usize = 3;
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScrollLines {
+    Lines(usize),
+    HalfPage,
+}
+
+impl Default for ScrollLines {
+    fn default() -> Self {
+        Self::Lines(DEFAULT_SCROLL_LINES)
+    }
+}
+
+impl FromStr for ScrollLines {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "half_page" => Ok(Self::HalfPage),
+            _ => match s.parse() {
+                Ok(0) | Err(_) => Err(format!(
+                    "Scroll lines must be a positive number or \"half_page\", found: {}",
+                    s
+                )),
+                Ok(lines) => Ok(Self::Lines(lines)),
+            },
+        }
+    }
+}
+
+impl ScrollLines {
+    /// The number of lines to scroll a pane whose content is `pane_rows` high
+    pub fn lines_for_pane(&self, pane_rows: usize) -> usize {
+        match self {
+            ScrollLines::Lines(lines) => *lines,
+            ScrollLines::HalfPage => std::cmp::max(pane_rows / 2, 1),
+        }
+    }
+}
+
+/// How zellij reacts when a terminal pane rings the bell, any combination of these can be set
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Bell {
+    /// Forward the bell to the terminal zellij is running in
+    pub audible: bool,
+    /// Briefly highlight the frame of the pane
+    pub visual: bool,
+    /// Mark the tab of the pane until it is focused, if it is not focused already
+    pub tab: bool,
+    /// Send `Event::Bell` to plugins
+    pub plugin: bool,
+}
+
+impl Default for Bell {
+    fn default() -> Self {
+        Bell {
+            audible: true,
+            visual: false,
+            tab: false,
+            plugin: true,
+        }
+    }
+}
+
+impl FromStr for Bell {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut bell = Bell {
+            audible: false,
+            visual: false,
+            tab: false,
+            plugin: false,
+        };
+        for handler in s.split(|c: char| c == ',' || c.is_whitespace()) {
+            match handler {
+                "audible" => bell.audible = true,
+                "visual" => bell.visual = true,
+                "tab" => bell.tab = true,
+                "plugin" => bell.plugin = true,
+                "none" | "" => {},
+                _ => return Err(format!("No such bell handler: {}", handler)),
+            }
+        }
+        Ok(bell)
+    }
+}
+
 impl Options {
     pub fn from_yaml(from_yaml: Option<Options>) -> Options {
         if let Some(opts) = from_yaml {
@@ -221,8 +449,35 @@ impl Options {
         let scrollback_lines_to_serialize = other
             .scrollback_lines_to_serialize
             .or(self.scrollback_lines_to_serialize);
+        let serialize_successfully_exited_panes = other
+            .serialize_successfully_exited_panes
+            .or(self.serialize_successfully_exited_panes);
         let styled_underlines = other.styled_underlines.or(self.styled_underlines);
         let serialization_interval = other.serialization_interval.or(self.serialization_interval);
+        let transparent_background = other.transparent_background.or(self.transparent_background);
+        let keep_scrollback_in_alternate_screen = other
+            .keep_scrollback_in_alternate_screen
+            .or(self.keep_scrollback_in_alternate_screen);
+        let pop_out_terminal_command = other
+            .pop_out_terminal_command
+            .or_else(|| self.pop_out_terminal_command.clone());
+        let idle_detach_timeout = other.idle_detach_timeout.or(self.idle_detach_timeout);
+        let session_activity_log_size = other
+            .session_activity_log_size
+            .or(self.session_activity_log_size);
+        let scroll_lines = other.scroll_lines.or(self.scroll_lines);
+        let layout_undo_depth = other.layout_undo_depth.or(self.layout_undo_depth);
+        let plugin_metrics_interval = other
+            .plugin_metrics_interval
+            .or(self.plugin_metrics_interval);
+        let surplus_panes = other.surplus_panes.or(self.surplus_panes);
+        let bell = other.bell.or(self.bell);
+        let passthrough_escape_key = other
+            .passthrough_escape_key
+            .or_else(|| self.passthrough_escape_key.clone());
+        let pane_frame_style = other.pane_frame_style.or(self.pane_frame_style);
+        let focus_dimming = other.focus_dimming.or(self.focus_dimming);
+        let paste_command = other.paste_command.or_else(|| self.paste_command.clone());
 
         Options {
             simplified_ui,
@@ -248,8 +503,23 @@ impl Options {
             session_serialization,
             serialize_pane_viewport,
             scrollback_lines_to_serialize,
+            serialize_successfully_exited_panes,
             styled_underlines,
             serialization_interval,
+            transparent_background,
+            keep_scrollback_in_alternate_screen,
+            pop_out_terminal_command,
+            idle_detach_timeout,
+            session_activity_log_size,
+            scroll_lines,
+            layout_undo_depth,
+            plugin_metrics_interval,
+            surplus_panes,
+            bell,
+            passthrough_escape_key,
+            pane_frame_style,
+            focus_dimming,
+            paste_command,
         }
     }
 
@@ -277,6 +547,10 @@ impl Options {
             merge_bool(other.session_serialization, self.session_serialization);
         let serialize_pane_viewport =
             merge_bool(other.serialize_pane_viewport, self.serialize_pane_viewport);
+        let serialize_successfully_exited_panes = merge_bool(
+            other.serialize_successfully_exited_panes,
+            self.serialize_successfully_exited_panes,
+        );
 
         let default_mode = other.default_mode.or(self.default_mode);
         let default_shell = other.default_shell.or_else(|| self.default_shell.clone());
@@ -302,6 +576,30 @@ impl Options {
             .or_else(|| self.scrollback_lines_to_serialize.clone());
         let styled_underlines = other.styled_underlines.or(self.styled_underlines);
         let serialization_interval = other.serialization_interval.or(self.serialization_interval);
+        let transparent_background = other.transparent_background.or(self.transparent_background);
+        let keep_scrollback_in_alternate_screen = other
+            .keep_scrollback_in_alternate_screen
+            .or(self.keep_scrollback_in_alternate_screen);
+        let pop_out_terminal_command = other
+            .pop_out_terminal_command
+            .or_else(|| self.pop_out_terminal_command.clone());
+        let idle_detach_timeout = other.idle_detach_timeout.or(self.idle_detach_timeout);
+        let session_activity_log_size = other
+            .session_activity_log_size
+            .or(self.session_activity_log_size);
+        let scroll_lines = other.scroll_lines.or(self.scroll_lines);
+        let layout_undo_depth = other.layout_undo_depth.or(self.layout_undo_depth);
+        let plugin_metrics_interval = other
+            .plugin_metrics_interval
+            .or(self.plugin_metrics_interval);
+        let surplus_panes = other.surplus_panes.or(self.surplus_panes);
+        let bell = other.bell.or(self.bell);
+        let passthrough_escape_key = other
+            .passthrough_escape_key
+            .or_else(|| self.passthrough_escape_key.clone());
+        let pane_frame_style = other.pane_frame_style.or(self.pane_frame_style);
+        let focus_dimming = other.focus_dimming.or(self.focus_dimming);
+        let paste_command = other.paste_command.or_else(|| self.paste_command.clone());
 
         Options {
             simplified_ui,
@@ -327,8 +625,23 @@ impl Options {
             session_serialization,
             serialize_pane_viewport,
             scrollback_lines_to_serialize,
+            serialize_successfully_exited_panes,
             styled_underlines,
             serialization_interval,
+            transparent_background,
+            keep_scrollback_in_alternate_screen,
+            pop_out_terminal_command,
+            idle_detach_timeout,
+            session_activity_log_size,
+            scroll_lines,
+            layout_undo_depth,
+            plugin_metrics_interval,
+            surplus_panes,
+            bell,
+            passthrough_escape_key,
+            pane_frame_style,
+            focus_dimming,
+            paste_command,
         }
     }
 
@@ -391,8 +704,23 @@ impl From<CliOptions> for Options {
             session_serialization: opts.session_serialization,
             serialize_pane_viewport: opts.serialize_pane_viewport,
             scrollback_lines_to_serialize: opts.scrollback_lines_to_serialize,
+            serialize_successfully_exited_panes: opts.serialize_successfully_exited_panes,
             styled_underlines: opts.styled_underlines,
             serialization_interval: opts.serialization_interval,
+            transparent_background: opts.transparent_background,
+            keep_scrollback_in_alternate_screen: opts.keep_scrollback_in_alternate_screen,
+            pop_out_terminal_command: opts.pop_out_terminal_command,
+            idle_detach_timeout: opts.idle_detach_timeout,
+            session_activity_log_size: opts.session_activity_log_size,
+            scroll_lines: opts.scroll_lines,
+            layout_undo_depth: opts.layout_undo_depth,
+            plugin_metrics_interval: opts.plugin_metrics_interval,
+            surplus_panes: opts.surplus_panes,
+            bell: opts.bell,
+            passthrough_escape_key: opts.passthrough_escape_key,
+            pane_frame_style: opts.pane_frame_style,
+            focus_dimming: opts.focus_dimming,
+            paste_command: opts.paste_command,
             ..Default::default()
         }
     }
@@ -120,16 +120,7 @@ impl PluginConfig {
         let err_context =
             |err: std::io::Error, path: &PathBuf| format!("{}: '{}'", err, path.display());
 
-        // Locations we check for valid plugins
-        let paths_arr = [
-            &self.path,
-            &self.path.with_extension("wasm"),
-            &plugin_dir.join(&self.path).with_extension("wasm"),
-        ];
-        // Throw out dupes, because it's confusing to read that zellij checked the same plugin
-        // location multiple times. Do NOT sort the vector here, because it will break the lookup!
-        let mut paths = paths_arr.to_vec();
-        paths.dedup();
+        let paths = self.wasm_file_locations(plugin_dir);
 
         // This looks weird and usually we would handle errors like this differently, but in this
         // case it's helpful for users and developers alike. This way we preserve all the lookup
@@ -137,7 +128,7 @@ impl PluginConfig {
         // and since the user will only get to see it when loading a plugin failed, we may as well
         // spell it out right here.
         let mut last_err: Result<Vec<u8>> = Err(anyhow!("failed to load plugin from disk"));
-        for path in paths {
+        for path in &paths {
             // Check if the plugin path matches an entry in the asset map. If so, load it directly
             // from memory, don't bother with the disk.
             #[cfg(not(target_family = "wasm"))]
@@ -197,6 +188,27 @@ impl PluginConfig {
         return last_err;
     }
 
+    /// Whether the wasm file of the plugin can be read from one of the locations
+    /// [`resolve_wasm_bytes`](Self::resolve_wasm_bytes) looks for it in
+    pub fn wasm_file_is_readable(&self, plugin_dir: &Path) -> bool {
+        self.wasm_file_locations(plugin_dir)
+            .iter()
+            .any(|path| path.is_file() && fs::File::open(path).is_ok())
+    }
+
+    // Locations we check for valid plugins, in order
+    fn wasm_file_locations(&self, plugin_dir: &Path) -> Vec<PathBuf> {
+        let mut paths = vec![
+            self.path.clone(),
+            self.path.with_extension("wasm"),
+            plugin_dir.join(&self.path).with_extension("wasm"),
+        ];
+        // Throw out dupes, because it's confusing to read that zellij checked the same plugin
+        // location multiple times. Do NOT sort the vector here, because it will break the lookup!
+        paths.dedup();
+        paths
+    }
+
     /// Sets the tab index inside of the plugin type of the run field.
     pub fn set_tab_index(&mut self, tab_index: usize) {
         match self.run {
@@ -210,6 +222,12 @@ impl PluginConfig {
     pub fn is_builtin(&self) -> bool {
         matches!(self.location, RunPluginLocation::Zellij(_))
     }
+
+    /// See [`RunPluginLocation::stable_plugin_id`]
+    pub fn stable_plugin_id(&self) -> String {
+        self.location
+            .stable_plugin_id(&self.userspace_configuration)
+    }
 }
 
 /// Type of the plugin. Defaults to Pane.
@@ -1,6 +1,6 @@
 use super::super::actions::*;
 use super::super::keybinds::*;
-use crate::data::{self, CharOrArrow, Direction, Key};
+use crate::data::{self, CharOrArrow, Direction, Key, ResizePreset};
 use crate::input::config::Config;
 use insta::assert_snapshot;
 use strum::IntoEnumIterator;
@@ -542,3 +542,728 @@ fn error_received_on_unknown_key_instruction() {
     let config_error = Config::from_kdl(config_contents, None).unwrap_err();
     assert_snapshot!(format!("{:?}", config_error));
 }
+
+#[test]
+fn can_bind_a_message_to_a_plugin() {
+    let config_contents = r#"
+        keybinds {
+            normal {
+                bind "Ctrl y" {
+                    MessageToPlugin "zellij:session-manager" {
+                        name "toggle_sort"
+                        payload "by name"
+                    }
+                }
+            }
+        }
+    "#;
+    let config = Config::from_kdl(config_contents, None).unwrap();
+    let ctrl_y_normal_mode_action = config
+        .keybinds
+        .get_actions_for_key_in_mode(&InputMode::Normal, &Key::Ctrl('y'));
+    assert_eq!(
+        ctrl_y_normal_mode_action,
+        Some(&vec![Action::MessageToPlugin {
+            plugin_url: "zellij:session-manager".to_owned(),
+            name: "toggle_sort".to_owned(),
+            payload: Some("by name".to_owned()),
+        }]),
+        "Message to plugin successfully bound in config"
+    );
+}
+
+#[test]
+fn error_received_on_message_to_plugin_without_a_name() {
+    let config_contents = r#"
+        keybinds {
+            normal {
+                bind "Ctrl y" { MessageToPlugin "zellij:session-manager"; }
+            }
+        }
+    "#;
+    assert!(Config::from_kdl(config_contents, None).is_err());
+}
+
+#[test]
+fn can_bind_going_to_a_tab_by_name() {
+    let config_contents = r#"
+        keybinds {
+            normal {
+                bind "Alt e" { GoToTabByName "editor"; SwitchToMode "Locked"; }
+            }
+        }
+    "#;
+    let config = Config::from_kdl(config_contents, None).unwrap();
+    let alt_e_normal_mode_action = config
+        .keybinds
+        .get_actions_for_key_in_mode(&InputMode::Normal, &Key::Alt(CharOrArrow::Char('e')));
+    assert_eq!(
+        alt_e_normal_mode_action,
+        Some(&vec![
+            Action::GoToTabByName("editor".to_owned()),
+            Action::SwitchToMode(InputMode::Locked)
+        ]),
+        "Going to a tab by name successfully bound in config"
+    );
+}
+
+#[test]
+fn can_bind_focusing_a_pane_by_id() {
+    let config_contents = r#"
+        keybinds {
+            normal {
+                bind "Alt 1" { FocusPaneById 3; }
+                bind "Alt 2" { FocusPaneById 1 plugin=true; }
+            }
+        }
+    "#;
+    let config = Config::from_kdl(config_contents, None).unwrap();
+    assert_eq!(
+        config
+            .keybinds
+            .get_actions_for_key_in_mode(&InputMode::Normal, &Key::Alt(CharOrArrow::Char('1'))),
+        Some(&vec![Action::FocusPaneById(3, false)]),
+        "Focusing a terminal pane by id successfully bound in config"
+    );
+    assert_eq!(
+        config
+            .keybinds
+            .get_actions_for_key_in_mode(&InputMode::Normal, &Key::Alt(CharOrArrow::Char('2'))),
+        Some(&vec![Action::FocusPaneById(1, true)]),
+        "Focusing a plugin pane by id successfully bound in config"
+    );
+}
+
+#[test]
+fn can_bind_resizing_a_pane_to_a_preset() {
+    let config_contents = r#"
+        keybinds {
+            resize {
+                bind "g" { ResizePaneToPreset "golden_ratio"; }
+            }
+        }
+    "#;
+    let config = Config::from_kdl(config_contents, None).unwrap();
+    let g_resize_mode_action = config
+        .keybinds
+        .get_actions_for_key_in_mode(&InputMode::Resize, &Key::Char('g'));
+    assert_eq!(
+        g_resize_mode_action,
+        Some(&vec![Action::ResizePaneToPreset(ResizePreset::GoldenRatio)]),
+        "Resizing a pane to a preset successfully bound in config"
+    );
+}
+
+#[test]
+fn error_received_on_unknown_resize_preset() {
+    let config_contents = r#"
+        keybinds {
+            resize {
+                bind "g" { ResizePaneToPreset "quarter"; }
+            }
+        }
+    "#;
+    assert!(Config::from_kdl(config_contents, None).is_err());
+}
+
+#[test]
+fn can_bind_switching_workspaces() {
+    let config_contents = r#"
+        keybinds {
+            tab {
+                bind "w" { SwitchWorkspace "work"; SwitchToMode "Normal"; }
+            }
+        }
+    "#;
+    let config = Config::from_kdl(config_contents, None).unwrap();
+    let w_tab_mode_action = config
+        .keybinds
+        .get_actions_for_key_in_mode(&InputMode::Tab, &Key::Char('w'));
+    assert_eq!(
+        w_tab_mode_action,
+        Some(&vec![
+            Action::SwitchWorkspace("work".to_owned()),
+            Action::SwitchToMode(InputMode::Normal)
+        ]),
+        "Switching workspaces successfully bound in config"
+    );
+}
+
+#[test]
+fn can_bind_attaching_to_a_pane() {
+    let config_contents = r#"
+        keybinds {
+            pane {
+                bind "a" { AttachToPane 3; SwitchToMode "Normal"; }
+            }
+        }
+    "#;
+    let config = Config::from_kdl(config_contents, None).unwrap();
+    let a_pane_mode_action = config
+        .keybinds
+        .get_actions_for_key_in_mode(&InputMode::Pane, &Key::Char('a'));
+    assert_eq!(
+        a_pane_mode_action,
+        Some(&vec![
+            Action::AttachToPane(3),
+            Action::SwitchToMode(InputMode::Normal)
+        ]),
+        "Attaching to a pane successfully bound in config"
+    );
+}
+
+#[test]
+fn can_bind_searching_in_a_pane() {
+    let config_contents = r#"
+        keybinds {
+            search {
+                bind "e" { SearchInPane "Error" case_sensitive=true; }
+                bind "w" { SearchInPane "warning"; }
+                bind "c" { ClearSearch; }
+            }
+        }
+    "#;
+    let config = Config::from_kdl(config_contents, None).unwrap();
+    let e_search_mode_action = config
+        .keybinds
+        .get_actions_for_key_in_mode(&InputMode::Search, &Key::Char('e'));
+    let w_search_mode_action = config
+        .keybinds
+        .get_actions_for_key_in_mode(&InputMode::Search, &Key::Char('w'));
+    let c_search_mode_action = config
+        .keybinds
+        .get_actions_for_key_in_mode(&InputMode::Search, &Key::Char('c'));
+    assert_eq!(
+        e_search_mode_action,
+        Some(&vec![Action::SearchInPane("Error".to_owned(), true)]),
+        "Case sensitive search successfully bound in config"
+    );
+    assert_eq!(
+        w_search_mode_action,
+        Some(&vec![Action::SearchInPane("warning".to_owned(), false)]),
+        "Search successfully bound in config"
+    );
+    assert_eq!(
+        c_search_mode_action,
+        Some(&vec![Action::ClearSearch]),
+        "Clearing a search successfully bound in config"
+    );
+}
+
+#[test]
+fn can_bind_closing_panes_matching_a_predicate() {
+    let config_contents = r#"
+        keybinds {
+            pane {
+                bind "z" { ClosePanesMatching "exited_zero"; }
+                bind "c" { ClosePanesMatching name="cargo *"; }
+            }
+        }
+    "#;
+    let config = Config::from_kdl(config_contents, None).unwrap();
+    let z_pane_mode_action = config
+        .keybinds
+        .get_actions_for_key_in_mode(&InputMode::Pane, &Key::Char('z'));
+    let c_pane_mode_action = config
+        .keybinds
+        .get_actions_for_key_in_mode(&InputMode::Pane, &Key::Char('c'));
+    assert_eq!(
+        z_pane_mode_action,
+        Some(&vec![Action::ClosePanesMatching(PanePredicate::ExitedZero)]),
+        "Closing panes whose command exited successfully bound in config"
+    );
+    assert_eq!(
+        c_pane_mode_action,
+        Some(&vec![Action::ClosePanesMatching(PanePredicate::NameGlob(
+            "cargo *".to_owned()
+        ))]),
+        "Closing panes matching a name bound in config"
+    );
+}
+
+#[test]
+fn can_bind_stacking_panes_and_cycling_stacks() {
+    let config_contents = r#"
+        keybinds {
+            pane {
+                bind "s" { StackPanes 1 2 "plugin_3"; SwitchToMode "Normal"; }
+                bind "]" { CycleStack "down"; }
+            }
+        }
+    "#;
+    let config = Config::from_kdl(config_contents, None).unwrap();
+    let s_pane_mode_action = config
+        .keybinds
+        .get_actions_for_key_in_mode(&InputMode::Pane, &Key::Char('s'));
+    let bracket_pane_mode_action = config
+        .keybinds
+        .get_actions_for_key_in_mode(&InputMode::Pane, &Key::Char(']'));
+    assert_eq!(
+        s_pane_mode_action,
+        Some(&vec![
+            Action::StackPanes(vec![
+                data::PaneId::Terminal(1),
+                data::PaneId::Terminal(2),
+                data::PaneId::Plugin(3)
+            ]),
+            Action::SwitchToMode(InputMode::Normal)
+        ]),
+        "Stacking panes successfully bound in config"
+    );
+    assert_eq!(
+        bracket_pane_mode_action,
+        Some(&vec![Action::CycleStack(Direction::Down)]),
+        "Cycling stacks successfully bound in config"
+    );
+}
+
+#[test]
+fn error_received_on_stacking_no_panes() {
+    let config_contents = r#"
+        keybinds {
+            pane {
+                bind "s" { StackPanes; }
+            }
+        }
+    "#;
+    assert!(Config::from_kdl(config_contents, None).is_err());
+}
+
+#[test]
+fn can_bind_focusing_the_next_pane_in_reading_order() {
+    let config_contents = r#"
+        keybinds {
+            pane {
+                bind "o" { FocusNextPaneSpatial; }
+            }
+        }
+    "#;
+    let config = Config::from_kdl(config_contents, None).unwrap();
+    let o_pane_mode_action = config
+        .keybinds
+        .get_actions_for_key_in_mode(&InputMode::Pane, &Key::Char('o'));
+    assert_eq!(
+        o_pane_mode_action,
+        Some(&vec![Action::FocusNextPaneSpatial]),
+        "Focusing the next pane in reading order successfully bound in config"
+    );
+}
+
+#[test]
+fn can_bind_focusing_a_pane_by_name() {
+    let config_contents = r#"
+        keybinds {
+            pane {
+                bind "b" { FocusPaneByName "build"; SwitchToMode "Normal"; }
+            }
+        }
+    "#;
+    let config = Config::from_kdl(config_contents, None).unwrap();
+    let b_pane_mode_action = config
+        .keybinds
+        .get_actions_for_key_in_mode(&InputMode::Pane, &Key::Char('b'));
+    assert_eq!(
+        b_pane_mode_action,
+        Some(&vec![
+            Action::FocusPaneByName("build".to_owned()),
+            Action::SwitchToMode(InputMode::Normal)
+        ]),
+        "Focusing a pane by name successfully bound in config"
+    );
+}
+
+#[test]
+fn can_bind_merging_panes() {
+    let config_contents = r#"
+        keybinds {
+            pane {
+                bind "m" { MergePanes 1 "terminal_2"; }
+            }
+        }
+    "#;
+    let config = Config::from_kdl(config_contents, None).unwrap();
+    let m_pane_mode_action = config
+        .keybinds
+        .get_actions_for_key_in_mode(&InputMode::Pane, &Key::Char('m'));
+    assert_eq!(
+        m_pane_mode_action,
+        Some(&vec![Action::MergePanes(
+            data::PaneId::Terminal(1),
+            data::PaneId::Terminal(2)
+        )]),
+        "Merging panes successfully bound in config"
+    );
+}
+
+#[test]
+fn error_received_on_merging_a_single_pane() {
+    let config_contents = r#"
+        keybinds {
+            pane {
+                bind "m" { MergePanes 1; }
+            }
+        }
+    "#;
+    let config = Config::from_kdl(config_contents, None);
+    assert!(config.is_err(), "Error received on merging a single pane");
+}
+
+#[test]
+fn can_bind_moving_the_active_tab() {
+    let config_contents = r#"
+        keybinds {
+            tab {
+                bind "H" { MoveTab "left"; }
+            }
+        }
+    "#;
+    let config = Config::from_kdl(config_contents, None).unwrap();
+    let h_tab_mode_action = config
+        .keybinds
+        .get_actions_for_key_in_mode(&InputMode::Tab, &Key::Char('H'));
+    assert_eq!(
+        h_tab_mode_action,
+        Some(&vec![Action::MoveTab(Direction::Left)]),
+        "Moving the active tab successfully bound in config"
+    );
+}
+
+#[test]
+fn can_bind_resizing_a_pane_to_a_size() {
+    let config_contents = r#"
+        keybinds {
+            resize {
+                bind "s" { ResizePaneTo 80 24; }
+            }
+        }
+    "#;
+    let config = Config::from_kdl(config_contents, None).unwrap();
+    let s_resize_mode_action = config
+        .keybinds
+        .get_actions_for_key_in_mode(&InputMode::Resize, &Key::Char('s'));
+    assert_eq!(
+        s_resize_mode_action,
+        Some(&vec![Action::ResizePaneTo(80, 24)]),
+        "Resizing a pane to a size successfully bound in config"
+    );
+}
+
+#[test]
+fn error_received_on_resizing_a_pane_to_a_negative_size() {
+    let config_contents = r#"
+        keybinds {
+            resize {
+                bind "s" { ResizePaneTo 80 -1; }
+            }
+        }
+    "#;
+    let config = Config::from_kdl(config_contents, None);
+    assert!(
+        config.is_err(),
+        "Error received on resizing a pane to a negative size"
+    );
+}
+
+#[test]
+fn can_bind_resizing_a_tab_to_its_content() {
+    let config_contents = r#"
+        keybinds {
+            resize {
+                bind "c" { ResizeTabToContent; }
+            }
+        }
+    "#;
+    let config = Config::from_kdl(config_contents, None).unwrap();
+    let c_resize_mode_action = config
+        .keybinds
+        .get_actions_for_key_in_mode(&InputMode::Resize, &Key::Char('c'));
+    assert_eq!(
+        c_resize_mode_action,
+        Some(&vec![Action::ResizeTabToContent]),
+        "Resizing a tab to its content successfully bound in config"
+    );
+}
+
+#[test]
+fn can_bind_focus_dimming() {
+    let config_contents = r#"
+        keybinds {
+            pane {
+                bind "d" { SetFocusDimming 30; }
+                bind "D" { SetFocusDimming; }
+            }
+        }
+    "#;
+    let config = Config::from_kdl(config_contents, None).unwrap();
+    let d_pane_mode_action = config
+        .keybinds
+        .get_actions_for_key_in_mode(&InputMode::Pane, &Key::Char('d'));
+    let shift_d_pane_mode_action = config
+        .keybinds
+        .get_actions_for_key_in_mode(&InputMode::Pane, &Key::Char('D'));
+    assert_eq!(
+        d_pane_mode_action,
+        Some(&vec![Action::SetFocusDimming(Some(30))]),
+        "Dimming unfocused panes successfully bound in config"
+    );
+    assert_eq!(
+        shift_d_pane_mode_action,
+        Some(&vec![Action::SetFocusDimming(None)]),
+        "Turning off dimming successfully bound in config"
+    );
+}
+
+#[test]
+fn error_received_on_focus_dimming_over_100_percent() {
+    let config_contents = r#"
+        keybinds {
+            pane {
+                bind "d" { SetFocusDimming 101; }
+            }
+        }
+    "#;
+    let config = Config::from_kdl(config_contents, None);
+    assert!(
+        config.is_err(),
+        "Error received on focus dimming over 100 percent"
+    );
+}
+
+#[test]
+fn can_bind_setting_input_broadcast() {
+    let config_contents = r#"
+        keybinds {
+            tab {
+                bind "y" { SetInputBroadcast true; }
+                bind "Y" { SetInputBroadcast false; }
+            }
+        }
+    "#;
+    let config = Config::from_kdl(config_contents, None).unwrap();
+    let y_tab_mode_action = config
+        .keybinds
+        .get_actions_for_key_in_mode(&InputMode::Tab, &Key::Char('y'));
+    let shift_y_tab_mode_action = config
+        .keybinds
+        .get_actions_for_key_in_mode(&InputMode::Tab, &Key::Char('Y'));
+    assert_eq!(
+        y_tab_mode_action,
+        Some(&vec![Action::SetInputBroadcast(true)]),
+        "Turning on input broadcast successfully bound in config"
+    );
+    assert_eq!(
+        shift_y_tab_mode_action,
+        Some(&vec![Action::SetInputBroadcast(false)]),
+        "Turning off input broadcast successfully bound in config"
+    );
+}
+
+#[test]
+fn can_bind_setting_the_minimum_size_of_a_pane() {
+    let config_contents = r#"
+        keybinds {
+            resize {
+                bind "m" { SetPaneMinSize 40 10; }
+            }
+        }
+    "#;
+    let config = Config::from_kdl(config_contents, None).unwrap();
+    let m_resize_mode_action = config
+        .keybinds
+        .get_actions_for_key_in_mode(&InputMode::Resize, &Key::Char('m'));
+    assert_eq!(
+        m_resize_mode_action,
+        Some(&vec![Action::SetPaneMinSize(40, 10)]),
+        "Setting the minimum size of a pane successfully bound in config"
+    );
+}
+
+#[test]
+fn can_bind_setting_a_pane_read_only() {
+    let config_contents = r#"
+        keybinds {
+            pane {
+                bind "o" { SetPaneReadOnly true; }
+            }
+        }
+    "#;
+    let config = Config::from_kdl(config_contents, None).unwrap();
+    let o_pane_mode_action = config
+        .keybinds
+        .get_actions_for_key_in_mode(&InputMode::Pane, &Key::Char('o'));
+    assert_eq!(
+        o_pane_mode_action,
+        Some(&vec![Action::SetPaneReadOnly(true)]),
+        "Setting a pane read only successfully bound in config"
+    );
+}
+
+#[test]
+fn can_bind_setting_synchronized_scroll() {
+    let config_contents = r#"
+        keybinds {
+            scroll {
+                bind "y" { SetSynchronizedScroll true; }
+            }
+        }
+    "#;
+    let config = Config::from_kdl(config_contents, None).unwrap();
+    let y_scroll_mode_action = config
+        .keybinds
+        .get_actions_for_key_in_mode(&InputMode::Scroll, &Key::Char('y'));
+    assert_eq!(
+        y_scroll_mode_action,
+        Some(&vec![Action::SetSynchronizedScroll(true)]),
+        "Setting synchronized scroll successfully bound in config"
+    );
+}
+
+#[test]
+fn error_received_on_setting_synchronized_scroll_without_a_bool() {
+    let config_contents = r#"
+        keybinds {
+            scroll {
+                bind "y" { SetSynchronizedScroll "yes"; }
+            }
+        }
+    "#;
+    let config = Config::from_kdl(config_contents, None);
+    assert!(
+        config.is_err(),
+        "Error received on setting synchronized scroll without a bool"
+    );
+}
+
+#[test]
+fn can_bind_setting_the_tab_color() {
+    let config_contents = r#"
+        keybinds {
+            tab {
+                bind "c" { SetTabColor "red"; }
+                bind "C" { SetTabColor; }
+            }
+        }
+    "#;
+    let config = Config::from_kdl(config_contents, None).unwrap();
+    let c_tab_mode_action = config
+        .keybinds
+        .get_actions_for_key_in_mode(&InputMode::Tab, &Key::Char('c'));
+    let shift_c_tab_mode_action = config
+        .keybinds
+        .get_actions_for_key_in_mode(&InputMode::Tab, &Key::Char('C'));
+    assert_eq!(
+        c_tab_mode_action,
+        Some(&vec![Action::SetTabColor(Some("red".to_owned()))]),
+        "Coloring the tab successfully bound in config"
+    );
+    assert_eq!(
+        shift_c_tab_mode_action,
+        Some(&vec![Action::SetTabColor(None)]),
+        "Removing the color of the tab successfully bound in config"
+    );
+}
+
+#[test]
+fn can_bind_swapping_the_layout_of_a_tab() {
+    let config_contents = r#"
+        keybinds {
+            tab {
+                bind "w" { SwapTabLayout 2; }
+                bind "W" { SwapTabLayout 2 previous=true; }
+            }
+        }
+    "#;
+    let config = Config::from_kdl(config_contents, None).unwrap();
+    let w_tab_mode_action = config
+        .keybinds
+        .get_actions_for_key_in_mode(&InputMode::Tab, &Key::Char('w'));
+    let shift_w_tab_mode_action = config
+        .keybinds
+        .get_actions_for_key_in_mode(&InputMode::Tab, &Key::Char('W'));
+    assert_eq!(
+        w_tab_mode_action,
+        Some(&vec![Action::SwapTabLayout(2, false)]),
+        "Swapping to the next layout of a tab successfully bound in config"
+    );
+    assert_eq!(
+        shift_w_tab_mode_action,
+        Some(&vec![Action::SwapTabLayout(2, true)]),
+        "Swapping to the previous layout of a tab successfully bound in config"
+    );
+}
+
+#[test]
+fn can_bind_toggling_the_floating_panes_of_a_tab() {
+    let config_contents = r#"
+        keybinds {
+            tab {
+                bind "f" { ToggleFloatingPanesInTab 3; }
+            }
+        }
+    "#;
+    let config = Config::from_kdl(config_contents, None).unwrap();
+    let f_tab_mode_action = config
+        .keybinds
+        .get_actions_for_key_in_mode(&InputMode::Tab, &Key::Char('f'));
+    assert_eq!(
+        f_tab_mode_action,
+        Some(&vec![Action::ToggleFloatingPanesInTab(3)]),
+        "Toggling the floating panes of a tab successfully bound in config"
+    );
+}
+
+#[test]
+fn can_bind_toggling_the_frame_of_a_pane() {
+    let config_contents = r#"
+        keybinds {
+            pane {
+                bind "b" { TogglePaneFrame; }
+            }
+        }
+    "#;
+    let config = Config::from_kdl(config_contents, None).unwrap();
+    let b_pane_mode_action = config
+        .keybinds
+        .get_actions_for_key_in_mode(&InputMode::Pane, &Key::Char('b'));
+    assert_eq!(
+        b_pane_mode_action,
+        Some(&vec![Action::TogglePaneFrame]),
+        "Toggling the frame of a pane successfully bound in config"
+    );
+}
+
+#[test]
+fn can_bind_pinning_a_pane() {
+    let config_contents = r#"
+        keybinds {
+            pane {
+                bind "i" { TogglePanePinned; }
+            }
+        }
+    "#;
+    let config = Config::from_kdl(config_contents, None).unwrap();
+    let i_pane_mode_action = config
+        .keybinds
+        .get_actions_for_key_in_mode(&InputMode::Pane, &Key::Char('i'));
+    assert_eq!(
+        i_pane_mode_action,
+        Some(&vec![Action::TogglePanePinned]),
+        "Pinning a pane successfully bound in config"
+    );
+}
+
+#[test]
+fn zoom_is_bound_in_pane_mode_by_default() {
+    let config = Config::from_default_assets().unwrap();
+    let shift_z_pane_mode_action = config
+        .keybinds
+        .get_actions_for_key_in_mode(&InputMode::Pane, &Key::Char('Z'));
+    assert_eq!(
+        shift_z_pane_mode_action,
+        Some(&vec![
+            Action::ToggleZoom,
+            Action::SwitchToMode(InputMode::Normal)
+        ]),
+        "Zooming the focused pane bound in pane mode by default"
+    );
+}
@@ -203,6 +203,46 @@ fn layout_with_hidden_floating_panes() {
     assert_eq!(layout, expected_layout);
 }
 
+#[test]
+fn layout_with_tab_colors() {
+    let kdl_layout = r##"
+        layout {
+            tab color="red" {
+                pane
+            }
+            tab color="#ff8800" {
+                pane
+            }
+            tab {
+                pane
+            }
+        }
+    "##;
+    let layout = Layout::from_kdl(kdl_layout, "layout_file_name".into(), None, None).unwrap();
+    let tab_colors: Vec<Option<String>> = layout
+        .tabs
+        .iter()
+        .map(|(_tab_name, tiled_panes, _floating_panes)| tiled_panes.color.clone())
+        .collect();
+    assert_eq!(
+        tab_colors,
+        vec![Some("red".to_owned()), Some("#ff8800".to_owned()), None]
+    );
+}
+
+#[test]
+fn layout_with_invalid_tab_color() {
+    let kdl_layout = r#"
+        layout {
+            tab color="not-a-color" {
+                pane
+            }
+        }
+    "#;
+    let layout = Layout::from_kdl(kdl_layout, "layout_file_name".into(), None, None);
+    assert!(layout.is_err(), "invalid tab color detected");
+}
+
 #[test]
 fn layout_with_floating_panes_template() {
     let kdl_layout = r#"
@@ -960,6 +1000,108 @@ fn circular_dependency_pane_templates_error() {
     assert!(layout.is_err(), "circular dependency detected");
 }
 
+#[test]
+fn layout_with_pane_template_instances() {
+    let kdl_layout = r#"
+        layout {
+            pane_template "project" split_direction="Vertical" {
+                pane command="{{editor:vim}}" cwd="{{cwd}}"
+                pane cwd="{{cwd}}"
+            }
+            pane_instance name="project" cwd="/tmp/project-a"
+            pane_instance name="project" cwd="/tmp/project-b" editor="hx" borderless=true
+        }
+    "#;
+    let layout = Layout::from_kdl(kdl_layout, "layout_file_name".into(), None, None).unwrap();
+    let project_pane = |cwd: &str, editor: &str, borderless: bool| TiledPaneLayout {
+        children_split_direction: SplitDirection::Vertical,
+        borderless,
+        children: vec![
+            TiledPaneLayout {
+                run: Some(Run::Command(RunCommand {
+                    command: PathBuf::from(editor),
+                    cwd: Some(PathBuf::from(cwd)),
+                    hold_on_close: true,
+                    ..Default::default()
+                })),
+                ..Default::default()
+            },
+            TiledPaneLayout {
+                run: Some(Run::Cwd(PathBuf::from(cwd))),
+                ..Default::default()
+            },
+        ],
+        ..Default::default()
+    };
+    let expected_layout = Layout {
+        template: Some((
+            TiledPaneLayout {
+                children: vec![
+                    project_pane("/tmp/project-a", "vim", false),
+                    project_pane("/tmp/project-b", "hx", true),
+                ],
+                ..Default::default()
+            },
+            vec![],
+        )),
+        ..Default::default()
+    };
+    assert_eq!(layout, expected_layout);
+}
+
+#[test]
+fn pane_template_leaves_placeholders_without_a_value_untouched() {
+    let kdl_layout = r#"
+        layout {
+            pane_template "project" {
+                pane command="echo" cwd="{{cwd}}" {
+                    args "{{not_a_parameter}}"
+                }
+            }
+            pane_instance name="project" cwd="/tmp/project-a"
+        }
+    "#;
+    let layout = Layout::from_kdl(kdl_layout, "layout_file_name".into(), None, None).unwrap();
+    let expected_layout = Layout {
+        template: Some((
+            TiledPaneLayout {
+                children: vec![TiledPaneLayout {
+                    children: vec![TiledPaneLayout {
+                        run: Some(Run::Command(RunCommand {
+                            command: PathBuf::from("echo"),
+                            args: vec!["{{not_a_parameter}}".to_owned()],
+                            cwd: Some(PathBuf::from("/tmp/project-a")),
+                            hold_on_close: true,
+                            ..Default::default()
+                        })),
+                        ..Default::default()
+                    }],
+                    ..Default::default()
+                }],
+                ..Default::default()
+            },
+            vec![],
+        )),
+        ..Default::default()
+    };
+    assert_eq!(layout, expected_layout);
+}
+
+#[test]
+fn recursive_pane_template_instances_error() {
+    let kdl_layout = r#"
+        layout {
+            pane_template "project" {
+                pane cwd="{{cwd}}"
+                pane_instance name="project" cwd="{{cwd}}"
+            }
+            pane_instance name="project" cwd="/tmp/project-a"
+        }
+    "#;
+    let layout = Layout::from_kdl(kdl_layout, "layout_file_name".into(), None, None);
+    assert!(layout.is_err(), "recursive pane_template detected");
+}
+
 #[test]
 fn children_not_as_first_child_of_tab_template() {
     let kdl_layout = r#"
@@ -1093,6 +1235,56 @@ fn layout_with_pane_excluded_from_sync() {
     assert_snapshot!(format!("{:#?}", layout));
 }
 
+#[test]
+fn layout_with_panes_with_scroll_lines() {
+    let kdl_layout = r#"
+        layout {
+            pane scroll_lines=5
+            pane {
+                scroll_lines "half_page"
+            }
+            floating_panes {
+                pane scroll_lines="half_page"
+            }
+        }
+    "#;
+    let layout = Layout::from_kdl(kdl_layout, "layout_file_name".into(), None, None).unwrap();
+    let expected_layout = Layout {
+        template: Some((
+            TiledPaneLayout {
+                children: vec![
+                    TiledPaneLayout {
+                        scroll_lines: Some(ScrollLines::Lines(5)),
+                        ..Default::default()
+                    },
+                    TiledPaneLayout {
+                        scroll_lines: Some(ScrollLines::HalfPage),
+                        ..Default::default()
+                    },
+                ],
+                ..Default::default()
+            },
+            vec![FloatingPaneLayout {
+                scroll_lines: Some(ScrollLines::HalfPage),
+                ..Default::default()
+            }],
+        )),
+        ..Default::default()
+    };
+    assert_eq!(layout, expected_layout);
+}
+
+#[test]
+fn pane_with_invalid_scroll_lines_error() {
+    let kdl_layout = r#"
+        layout {
+            pane scroll_lines=0
+        }
+    "#;
+    let layout = Layout::from_kdl(kdl_layout, "layout_file_name".into(), None, None);
+    assert!(layout.is_err(), "scroll lines must be a positive number");
+}
+
 #[test]
 fn cannot_define_tab_template_name_with_space() {
     let kdl_layout = r#"
@@ -1884,6 +2076,41 @@ fn global_cwd_and_tab_cwd_prepended_to_panes_with_and_without_cwd_in_tab_templat
     assert_snapshot!(format!("{:#?}", layout));
 }
 
+#[test]
+fn global_cwd_and_tab_cwd_prepended_to_floating_panes_in_tab() {
+    let kdl_layout = r#"
+        layout {
+            cwd "/tmp"
+            tab cwd="./foo" {
+                pane // should have /tmp/foo
+                pane cwd="./bar" // should have /tmp/foo/bar
+                floating_panes {
+                    pane // should have /tmp/foo
+                    pane cwd="./baz" // should have /tmp/foo/baz
+                }
+            }
+        }
+    "#;
+    let layout = Layout::from_kdl(kdl_layout, "layout_file_name".into(), None, None).unwrap();
+    let (_tab_name, tiled_panes, floating_panes) = layout.tabs().remove(0);
+    assert_eq!(
+        tiled_panes.children[0].run,
+        Some(Run::Cwd(PathBuf::from("/tmp/foo")))
+    );
+    assert_eq!(
+        tiled_panes.children[1].run,
+        Some(Run::Cwd(PathBuf::from("/tmp/foo/bar")))
+    );
+    assert_eq!(
+        floating_panes[0].run,
+        Some(Run::Cwd(PathBuf::from("/tmp/foo")))
+    );
+    assert_eq!(
+        floating_panes[1].run,
+        Some(Run::Cwd(PathBuf::from("/tmp/foo/baz")))
+    );
+}
+
 #[test]
 fn can_load_swap_layouts_from_a_different_file() {
     let kdl_layout = r#"
@@ -2208,3 +2435,230 @@ fn env_var_missing() {
     let layout = Layout::from_kdl(kdl_layout, "layout_file_name".into(), None, None);
     assert!(layout.is_err(), "invalid env var lookup should fail");
 }
+
+#[test]
+fn validate_layout_with_missing_cwd() {
+    let kdl_layout = r#"
+        layout {
+            pane
+            pane name="logs" cwd="/this/folder/does/not/exist"
+        }
+    "#;
+    let layout = Layout::from_kdl(kdl_layout, "layout_file_name".into(), None, None).unwrap();
+    assert_eq!(
+        layout.validate(),
+        vec![LayoutWarning::MissingCwd {
+            pane_path: "tab > pane \"logs\"".to_owned(),
+            cwd: PathBuf::from("/this/folder/does/not/exist"),
+        }],
+        "missing cwd reported with the pane name"
+    );
+}
+
+#[test]
+fn validate_layout_with_bad_plugin_path() {
+    let kdl_layout = r#"
+        layout {
+            tab name="plugins" {
+                pane
+                pane {
+                    plugin location="file:/this/plugin/does/not/exist.wasm"
+                }
+            }
+        }
+    "#;
+    let layout = Layout::from_kdl(kdl_layout, "layout_file_name".into(), None, None).unwrap();
+    assert_eq!(
+        layout.validate(),
+        vec![LayoutWarning::MissingPluginFile {
+            pane_path: "tab \"plugins\" > pane #2".to_owned(),
+            plugin_path: PathBuf::from("/this/plugin/does/not/exist.wasm"),
+        }],
+        "missing plugin file reported with the pane position"
+    );
+}
+
+#[test]
+fn validate_layout_with_unresolvable_command_and_oversized_parts() {
+    let kdl_layout = r#"
+        layout {
+            pane split_direction="vertical" {
+                pane size="60%"
+                pane size="60%" command="this-command-does-not-exist-anywhere"
+            }
+        }
+    "#;
+    let layout = Layout::from_kdl(kdl_layout, "layout_file_name".into(), None, None).unwrap();
+    assert_eq!(
+        layout.validate(),
+        vec![
+            LayoutWarning::PercentSizesExceedFullSize {
+                pane_path: "tab > pane #1".to_owned(),
+                total_percent: 120,
+            },
+            LayoutWarning::UnresolvedCommand {
+                pane_path: "tab > pane #1 > pane #2".to_owned(),
+                command: PathBuf::from("this-command-does-not-exist-anywhere"),
+            },
+        ],
+    );
+}
+
+#[test]
+fn validate_valid_layout() {
+    let cwd = std::env::temp_dir();
+    let kdl_layout = format!(
+        r#"
+        layout {{
+            cwd "{}"
+            pane size=1 borderless=true {{
+                plugin location="zellij:tab-bar"
+            }}
+            pane split_direction="vertical" {{
+                pane size="50%"
+                pane size="50%"
+            }}
+            floating_panes {{
+                pane
+            }}
+        }}
+    "#,
+        cwd.display()
+    );
+    let layout = Layout::from_kdl(&kdl_layout, "layout_file_name".into(), None, None).unwrap();
+    assert!(layout.validate().is_empty(), "valid layout has no warnings");
+}
+
+#[test]
+fn layout_with_command_pane_env() {
+    std::env::set_var("ZELLIJ_LAYOUT_TEST_ENV_PREFIX", "/opt/test");
+    let kdl_layout = r#"
+        layout {
+            pane command="htop" {
+                env {
+                    FOO "bar"
+                    RETRIES 3
+                    TOOLS_PATH "$ZELLIJ_LAYOUT_TEST_ENV_PREFIX/bin"
+                }
+            }
+        }
+    "#;
+    let layout = Layout::from_kdl(kdl_layout, "layout_file_name".into(), None, None).unwrap();
+    let expected_env: BTreeMap<String, String> = BTreeMap::from([
+        ("FOO".to_owned(), "bar".to_owned()),
+        ("RETRIES".to_owned(), "3".to_owned()),
+        ("TOOLS_PATH".to_owned(), "/opt/test/bin".to_owned()),
+    ]);
+    let pane_run = layout.template.unwrap().0.children[0].run.clone();
+    assert_eq!(
+        pane_run,
+        Some(Run::Command(RunCommand {
+            command: PathBuf::from("htop"),
+            hold_on_close: true,
+            env: expected_env,
+            ..Default::default()
+        }))
+    );
+}
+
+#[test]
+fn pane_template_env_is_extended_by_its_consumers_env() {
+    let kdl_layout = r#"
+        layout {
+            pane_template name="htop" {
+                command "htop"
+                env {
+                    FOO "template"
+                    BAR "template"
+                }
+            }
+            htop {
+                env {
+                    FOO "consumer"
+                }
+            }
+        }
+    "#;
+    let layout = Layout::from_kdl(kdl_layout, "layout_file_name".into(), None, None).unwrap();
+    let expected_env: BTreeMap<String, String> = BTreeMap::from([
+        ("BAR".to_owned(), "template".to_owned()),
+        ("FOO".to_owned(), "consumer".to_owned()),
+    ]);
+    match &layout.template.unwrap().0.children[0].run {
+        Some(Run::Command(run_command)) => assert_eq!(run_command.env, expected_env),
+        run => panic!("expected a command pane, got: {:?}", run),
+    }
+}
+
+#[test]
+fn env_without_command_is_an_error() {
+    let kdl_layout = r#"
+        layout {
+            pane {
+                env {
+                    FOO "bar"
+                }
+            }
+        }
+    "#;
+    let layout = Layout::from_kdl(kdl_layout, "layout_file_name".into(), None, None);
+    assert!(layout.is_err(), "env can only be set on command panes");
+}
+
+#[test]
+fn layout_with_background_plugins() {
+    let kdl_layout = r#"
+        layout {
+            pane
+            plugin_background location="file:/path/to/my/notifier.wasm"
+            plugin_background location="zellij:status-bar" {
+                config_key_1 "config_value_1"
+            }
+        }
+    "#;
+    let layout = Layout::from_kdl(kdl_layout, "layout_file_name".into(), None, None).unwrap();
+    let mut expected_plugin_configuration = BTreeMap::new();
+    expected_plugin_configuration.insert("config_key_1".to_owned(), "config_value_1".to_owned());
+    let expected_layout = Layout {
+        template: Some((
+            TiledPaneLayout {
+                children: vec![TiledPaneLayout::default()],
+                ..Default::default()
+            },
+            vec![],
+        )),
+        background_plugins: vec![
+            RunPlugin {
+                location: RunPluginLocation::File(PathBuf::from("/path/to/my/notifier.wasm")),
+                _allow_exec_host_cmd: false,
+                configuration: Default::default(),
+            },
+            RunPlugin {
+                location: RunPluginLocation::Zellij(PluginTag::new("status-bar")),
+                _allow_exec_host_cmd: false,
+                configuration: PluginUserConfiguration(expected_plugin_configuration),
+            },
+        ],
+        ..Default::default()
+    };
+    assert_eq!(
+        layout, expected_layout,
+        "background plugins are not placed in any pane"
+    );
+}
+
+#[test]
+fn stable_plugin_id_tells_configuration_values_apart_from_separate_keys() {
+    let location = RunPluginLocation::Zellij(PluginTag::new("status-bar"));
+    let configuration_with_separator_in_value =
+        PluginUserConfiguration::new(BTreeMap::from([("a".to_owned(), "b&c".to_owned())]));
+    let configuration_with_separate_keys = PluginUserConfiguration::new(BTreeMap::from([
+        ("a".to_owned(), "b".to_owned()),
+        ("c".to_owned(), "".to_owned()),
+    ]));
+    assert_ne!(
+        location.stable_plugin_id(&configuration_with_separator_in_value),
+        location.stable_plugin_id(&configuration_with_separate_keys),
+        "configuration values are encoded in the stable plugin id"
+    );
+}
@@ -83,12 +83,16 @@ pub enum ClientToServerMsg {
         Options,
         Option<usize>,       // tab position to focus
         Option<(u32, bool)>, // (pane_id, is_plugin) => pane id to focus
+        bool,                // view only the pane to focus, popped out of its tab
+        bool,                // attach as an observer that can't send input
     ),
     Action(Action, Option<u32>, Option<ClientId>), // u32 is the terminal id
     ClientExited,
     KillSession,
     ConnStatus,
     ListClients,
+    ListSessions,
+    Ping(u64), // u64 - request id, answered with a Pong once the screen is done with what came before
 }
 
 // Types of messages sent from the server to the client
@@ -103,6 +107,19 @@ pub enum ServerToClientMsg {
     Log(Vec<String>),
     LogError(Vec<String>),
     SwitchSession(ConnectToSession),
+    SessionList(Vec<SessionListing>),
+    SetPassthrough(bool), // true - the pane focused by this client is in pass-through mode
+    Pong(u64),            // u64 - the request id of the Ping this answers
+}
+
+/// A live session on this machine, as listed to clients asking for structured session data
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SessionListing {
+    pub name: String,
+    pub created: u64, // seconds since the unix epoch
+    pub attached_clients: usize,
+    pub observer_clients: usize, // attached clients that can't send input
+    pub tabs: Vec<String>,       // tab names, by position
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
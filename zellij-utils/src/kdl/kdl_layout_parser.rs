@@ -1,3 +1,4 @@
+use crate::data::Palette;
 use crate::input::{
     command::RunCommand,
     config::ConfigError,
@@ -6,7 +7,10 @@ use crate::input::{
         RunPlugin, RunPluginLocation, SplitDirection, SplitSize, SwapFloatingLayout,
         SwapTiledLayout, TiledPaneLayout,
     },
+    options::ScrollLines,
 };
+use crate::kdl::kdl_scroll_lines_entry;
+use crate::session_serialization::decode_plugin_state;
 
 use kdl::*;
 
@@ -61,6 +65,7 @@ impl<'a> KdlLayoutParser<'a> {
         word == "pane"
             || word == "layout"
             || word == "pane_template"
+            || word == "pane_instance"
             || word == "tab_template"
             || word == "default_tab_template"
             || word == "new_tab_template"
@@ -81,7 +86,10 @@ impl<'a> KdlLayoutParser<'a> {
             || word == "swap_tiled_layout"
             || word == "swap_floating_layout"
             || word == "hide_floating_panes"
+            || word == "color"
             || word == "contents_file"
+            || word == "plugin_state_file"
+            || word == "plugin_background"
     }
     fn is_a_valid_pane_property(&self, property_name: &str) -> bool {
         property_name == "borderless"
@@ -95,13 +103,17 @@ impl<'a> KdlLayoutParser<'a> {
             || property_name == "args"
             || property_name == "close_on_exit"
             || property_name == "start_suspended"
+            || property_name == "env"
             || property_name == "split_direction"
             || property_name == "pane"
             || property_name == "children"
             || property_name == "stacked"
             || property_name == "expanded"
             || property_name == "exclude_from_sync"
+            || property_name == "read_only"
+            || property_name == "scroll_lines"
             || property_name == "contents_file"
+            || property_name == "plugin_state_file"
     }
     fn is_a_valid_floating_pane_property(&self, property_name: &str) -> bool {
         property_name == "borderless"
@@ -114,11 +126,16 @@ impl<'a> KdlLayoutParser<'a> {
             || property_name == "args"
             || property_name == "close_on_exit"
             || property_name == "start_suspended"
+            || property_name == "env"
             || property_name == "x"
             || property_name == "y"
             || property_name == "width"
             || property_name == "height"
             || property_name == "contents_file"
+            || property_name == "plugin_state_file"
+            || property_name == "pinned"
+            || property_name == "read_only"
+            || property_name == "scroll_lines"
     }
     fn is_a_valid_tab_property(&self, property_name: &str) -> bool {
         property_name == "focus"
@@ -131,6 +148,7 @@ impl<'a> KdlLayoutParser<'a> {
             || property_name == "min_panes"
             || property_name == "exact_panes"
             || property_name == "hide_floating_panes"
+            || property_name == "color"
     }
     pub fn is_a_reserved_plugin_property(property_name: &str) -> bool {
         property_name == "location"
@@ -193,8 +211,7 @@ impl<'a> KdlLayoutParser<'a> {
     ) -> Result<(), ConfigError> {
         if is_part_of_stack {
             for child in children {
-                if kdl_name!(child) == "pane" || self.pane_templates.get(kdl_name!(child)).is_some()
-                {
+                if kdl_name!(child) == "pane" || self.is_pane_template_node(child) {
                     return Err(ConfigError::new_layout_kdl_error(
                         format!("Stacked panes cannot have children"),
                         child.span().offset(),
@@ -240,6 +257,12 @@ impl<'a> KdlLayoutParser<'a> {
             Ok(None)
         }
     }
+    fn parse_scroll_lines(&self, kdl_node: &KdlNode) -> Result<Option<ScrollLines>, ConfigError> {
+        match kdl_get_property_or_child!(kdl_node, "scroll_lines") {
+            Some(entry) => Ok(Some(kdl_scroll_lines_entry(entry)?)),
+            None => Ok(None),
+        }
+    }
     fn parse_percent_or_fixed(
         &self,
         kdl_node: &KdlNode,
@@ -414,6 +437,32 @@ impl<'a> KdlLayoutParser<'a> {
             None => Ok(None),
         }
     }
+    fn parse_env(
+        &self,
+        pane_node: &KdlNode,
+    ) -> Result<Option<BTreeMap<String, String>>, ConfigError> {
+        match kdl_get_child!(pane_node, "env") {
+            Some(kdl_env) => {
+                let mut env = BTreeMap::new();
+                for env_var in kdl_children_nodes!(kdl_env).unwrap_or_default() {
+                    let env_var_name = kdl_name!(env_var);
+                    let env_var_value = kdl_first_entry_as_string!(env_var)
+                        .map(|s| s.to_string())
+                        .or_else(|| kdl_first_entry_as_i64!(env_var).map(|i| i.to_string()))
+                        .ok_or(kdl_parsing_error!(
+                            format!("Failed to parse env var: {:?}", env_var_name),
+                            env_var
+                        ))?;
+                    // values are expanded the same way paths are (eg. "$HOME/bin" or "~/bin")
+                    let env_var_value = shellexpand::full(&env_var_value)
+                        .map_err(|e| kdl_parsing_error!(e.to_string(), env_var))?;
+                    env.insert(env_var_name.to_string(), env_var_value.to_string());
+                }
+                Ok(Some(env))
+            },
+            None => Ok(None),
+        }
+    }
     fn parse_pane_command(
         &self,
         pane_node: &KdlNode,
@@ -423,6 +472,7 @@ impl<'a> KdlLayoutParser<'a> {
         let edit = self.parse_path(pane_node, "edit")?;
         let cwd = self.parse_path(pane_node, "cwd")?;
         let args = self.parse_args(pane_node)?;
+        let env = self.parse_env(pane_node)?;
         let close_on_exit =
             kdl_get_bool_property_or_child_value_with_error!(pane_node, "close_on_exit");
         let start_suspended =
@@ -431,6 +481,7 @@ impl<'a> KdlLayoutParser<'a> {
             self.assert_no_bare_attributes_in_pane_node(
                 &command,
                 &args,
+                &env,
                 &close_on_exit,
                 &start_suspended,
                 pane_node,
@@ -446,6 +497,7 @@ impl<'a> KdlLayoutParser<'a> {
                 cwd,
                 hold_on_close,
                 hold_on_start,
+                env: env.unwrap_or_default(),
             }))),
             (None, Some(edit), Some(cwd)) => {
                 Ok(Some(Run::EditFile(cwd.join(edit), None, Some(cwd))))
@@ -521,8 +573,12 @@ impl<'a> KdlLayoutParser<'a> {
             .map(|name| name.to_string());
         let exclude_from_sync =
             kdl_get_bool_property_or_child_value_with_error!(kdl_node, "exclude_from_sync");
+        let read_only = kdl_get_bool_property_or_child_value_with_error!(kdl_node, "read_only");
+        let scroll_lines = self.parse_scroll_lines(kdl_node)?;
         let contents_file =
             kdl_get_string_property_or_child_value_with_error!(kdl_node, "contents_file");
+        let plugin_state_file =
+            kdl_get_string_property_or_child_value_with_error!(kdl_node, "plugin_state_file");
         let split_size = self.parse_split_size(kdl_node)?;
         let run = self.parse_command_plugin_or_edit_block(kdl_node)?;
         let children_split_direction = self.parse_split_direction(kdl_node)?;
@@ -558,6 +614,7 @@ impl<'a> KdlLayoutParser<'a> {
                 std::fs::read_to_string(parent_folder.join(contents_file)).ok()
             })
         });
+        let plugin_initial_state = self.parse_plugin_state_file(plugin_state_file);
         Ok(TiledPaneLayout {
             borderless: borderless.unwrap_or_default(),
             focus,
@@ -567,10 +624,13 @@ impl<'a> KdlLayoutParser<'a> {
             children_split_direction,
             external_children_index,
             exclude_from_sync,
+            read_only,
+            scroll_lines,
             children,
             children_are_stacked,
             is_expanded_in_stack,
             pane_initial_contents,
+            plugin_initial_state,
             ..Default::default()
         })
     }
@@ -585,16 +645,22 @@ impl<'a> KdlLayoutParser<'a> {
         let y = self.parse_percent_or_fixed(kdl_node, "y", true)?;
         let run = self.parse_command_plugin_or_edit_block(kdl_node)?;
         let focus = kdl_get_bool_property_or_child_value_with_error!(kdl_node, "focus");
+        let pinned = kdl_get_bool_property_or_child_value_with_error!(kdl_node, "pinned");
+        let read_only = kdl_get_bool_property_or_child_value_with_error!(kdl_node, "read_only");
+        let scroll_lines = self.parse_scroll_lines(kdl_node)?;
         let name = kdl_get_string_property_or_child_value_with_error!(kdl_node, "name")
             .map(|name| name.to_string());
         let contents_file =
             kdl_get_string_property_or_child_value_with_error!(kdl_node, "contents_file");
+        let plugin_state_file =
+            kdl_get_string_property_or_child_value_with_error!(kdl_node, "plugin_state_file");
         self.assert_no_mixed_children_and_properties(kdl_node)?;
         let pane_initial_contents = contents_file.and_then(|contents_file| {
             self.file_name.parent().and_then(|parent_folder| {
                 std::fs::read_to_string(parent_folder.join(contents_file)).ok()
             })
         });
+        let plugin_initial_state = self.parse_plugin_state_file(plugin_state_file);
         Ok(FloatingPaneLayout {
             name,
             height,
@@ -603,10 +669,23 @@ impl<'a> KdlLayoutParser<'a> {
             y,
             run,
             focus,
+            pinned,
+            read_only,
+            scroll_lines,
             pane_initial_contents,
+            plugin_initial_state,
             ..Default::default()
         })
     }
+    fn parse_plugin_state_file(&self, plugin_state_file: Option<&str>) -> Option<Vec<u8>> {
+        plugin_state_file.and_then(|plugin_state_file| {
+            self.file_name.parent().and_then(|parent_folder| {
+                std::fs::read_to_string(parent_folder.join(plugin_state_file))
+                    .ok()
+                    .and_then(|encoded_state| decode_plugin_state(&encoded_state))
+            })
+        })
+    }
     fn insert_children_to_pane_template(
         &self,
         kdl_node: &KdlNode,
@@ -692,6 +771,7 @@ impl<'a> KdlLayoutParser<'a> {
                 let is_expanded_in_stack =
                     kdl_get_bool_property_or_child_value_with_error!(kdl_node, "expanded");
                 let args = self.parse_args(kdl_node)?;
+                let env = self.parse_env(kdl_node)?;
                 let close_on_exit =
                     kdl_get_bool_property_or_child_value_with_error!(kdl_node, "close_on_exit");
                 let start_suspended =
@@ -700,6 +780,9 @@ impl<'a> KdlLayoutParser<'a> {
                 let run = self.parse_command_plugin_or_edit_block_for_template(kdl_node)?;
                 let exclude_from_sync =
                     kdl_get_bool_property_or_child_value_with_error!(kdl_node, "exclude_from_sync");
+                let read_only =
+                    kdl_get_bool_property_or_child_value_with_error!(kdl_node, "read_only");
+                let scroll_lines = self.parse_scroll_lines(kdl_node)?;
 
                 let external_children_index = if should_mark_external_children_index {
                     self.populate_external_children_index(kdl_node)?
@@ -710,6 +793,7 @@ impl<'a> KdlLayoutParser<'a> {
                     &run,
                     &pane_template.run,
                     &args,
+                    &env,
                     &close_on_exit,
                     &start_suspended,
                     kdl_node,
@@ -724,6 +808,7 @@ impl<'a> KdlLayoutParser<'a> {
                     // we need to do this because panes consuming a pane_template
                     // can have bare args without a command
                     pane_template_run_command.add_args(args);
+                    pane_template_run_command.add_env(env);
                     pane_template_run_command.add_close_on_exit(close_on_exit);
                     pane_template_run_command.add_start_suspended(start_suspended);
                 };
@@ -739,6 +824,12 @@ impl<'a> KdlLayoutParser<'a> {
                 if let Some(exclude_from_sync) = exclude_from_sync {
                     pane_template.exclude_from_sync = Some(exclude_from_sync);
                 }
+                if let Some(read_only) = read_only {
+                    pane_template.read_only = Some(read_only);
+                }
+                if let Some(scroll_lines) = scroll_lines {
+                    pane_template.scroll_lines = Some(scroll_lines);
+                }
                 if let Some(split_size) = split_size {
                     pane_template.split_size = Some(split_size);
                 }
@@ -798,6 +889,7 @@ impl<'a> KdlLayoutParser<'a> {
                 let name = kdl_get_string_property_or_child_value_with_error!(kdl_node, "name")
                     .map(|name| name.to_string());
                 let args = self.parse_args(kdl_node)?;
+                let env = self.parse_env(kdl_node)?;
                 let close_on_exit =
                     kdl_get_bool_property_or_child_value_with_error!(kdl_node, "close_on_exit");
                 let start_suspended =
@@ -807,6 +899,7 @@ impl<'a> KdlLayoutParser<'a> {
                     &run,
                     &pane_template.run,
                     &args,
+                    &env,
                     &close_on_exit,
                     &start_suspended,
                     kdl_node,
@@ -816,6 +909,7 @@ impl<'a> KdlLayoutParser<'a> {
                     // we need to do this because panes consuming a pane_template
                     // can have bare args without a command
                     pane_template_run_command.add_args(args);
+                    pane_template_run_command.add_env(env);
                     pane_template_run_command.add_close_on_exit(close_on_exit);
                     pane_template_run_command.add_start_suspended(start_suspended);
                 };
@@ -849,6 +943,7 @@ impl<'a> KdlLayoutParser<'a> {
                 let name = kdl_get_string_property_or_child_value_with_error!(kdl_node, "name")
                     .map(|name| name.to_string());
                 let args = self.parse_args(kdl_node)?;
+                let env = self.parse_env(kdl_node)?;
                 let close_on_exit =
                     kdl_get_bool_property_or_child_value_with_error!(kdl_node, "close_on_exit");
                 let start_suspended =
@@ -858,6 +953,7 @@ impl<'a> KdlLayoutParser<'a> {
                     &run,
                     &pane_template.run,
                     &args,
+                    &env,
                     &close_on_exit,
                     &start_suspended,
                     kdl_node,
@@ -867,6 +963,7 @@ impl<'a> KdlLayoutParser<'a> {
                     // we need to do this because panes consuming a pane_template
                     // can have bare args without a command
                     pane_template_run_command.add_args(args);
+                    pane_template_run_command.add_env(env);
                     pane_template_run_command.add_close_on_exit(close_on_exit);
                     pane_template_run_command.add_start_suspended(start_suspended);
                 };
@@ -1028,15 +1125,29 @@ impl<'a> KdlLayoutParser<'a> {
         }
     }
     fn parse_pane_template_node(&mut self, kdl_node: &KdlNode) -> Result<(), ConfigError> {
-        let template_name = kdl_get_string_property_or_child_value!(kdl_node, "name")
-            .map(|s| s.to_string())
-            .ok_or(ConfigError::new_layout_kdl_error(
+        let template_name = pane_template_name(kdl_node).map(|s| s.to_string()).ok_or(
+            ConfigError::new_layout_kdl_error(
                 "Pane templates must have a name".into(),
                 kdl_node.span().offset(),
                 kdl_node.span().len(),
-            ))?;
+            ),
+        )?;
         self.assert_legal_node_name(&template_name, kdl_node)?;
         self.assert_legal_template_name(&template_name, kdl_node)?;
+        let kdl_node = without_positional_template_name(kdl_node);
+        // parameters are substituted when the template is used, here we only need to make sure
+        // the template parses - with the defaults of its parameters
+        let mut kdl_node_with_defaults = kdl_node.clone();
+        substitute_template_parameters(&mut kdl_node_with_defaults, &HashMap::new());
+        let pane_template = self.parse_pane_template_body(&kdl_node_with_defaults)?;
+        self.pane_templates
+            .insert(template_name, (pane_template, kdl_node));
+        Ok(())
+    }
+    fn parse_pane_template_body(
+        &self,
+        kdl_node: &KdlNode,
+    ) -> Result<PaneOrFloatingPane, ConfigError> {
         let focus = kdl_get_bool_property_or_child_value_with_error!(kdl_node, "focus");
         let run = self.parse_command_plugin_or_edit_block(kdl_node)?;
 
@@ -1045,17 +1156,11 @@ impl<'a> KdlLayoutParser<'a> {
             self.has_only_neutral_pane_template_properties(&kdl_node)?;
         if can_be_either_floating_or_tiled {
             self.assert_valid_pane_or_floating_pane_properties(kdl_node)?;
-            self.pane_templates.insert(
-                template_name,
-                (
-                    PaneOrFloatingPane::Either(TiledPaneLayout {
-                        focus,
-                        run,
-                        ..Default::default()
-                    }),
-                    kdl_node.clone(),
-                ),
-            );
+            Ok(PaneOrFloatingPane::Either(TiledPaneLayout {
+                focus,
+                run,
+                ..Default::default()
+            }))
         } else if is_floating {
             self.assert_valid_floating_pane_properties(kdl_node)?;
             // floating pane properties
@@ -1063,21 +1168,15 @@ impl<'a> KdlLayoutParser<'a> {
             let width = self.parse_percent_or_fixed(kdl_node, "width", false)?;
             let x = self.parse_percent_or_fixed(kdl_node, "x", true)?;
             let y = self.parse_percent_or_fixed(kdl_node, "y", true)?;
-            self.pane_templates.insert(
-                template_name,
-                (
-                    PaneOrFloatingPane::FloatingPane(FloatingPaneLayout {
-                        focus,
-                        run,
-                        height,
-                        width,
-                        x,
-                        y,
-                        ..Default::default()
-                    }),
-                    kdl_node.clone(),
-                ),
-            );
+            Ok(PaneOrFloatingPane::FloatingPane(FloatingPaneLayout {
+                focus,
+                run,
+                height,
+                width,
+                x,
+                y,
+                ..Default::default()
+            }))
         } else {
             self.assert_valid_pane_properties(kdl_node)?;
             // pane properties
@@ -1098,27 +1197,19 @@ impl<'a> KdlLayoutParser<'a> {
                 None => (None, vec![]),
             };
             self.assert_no_mixed_children_and_properties(kdl_node)?;
-            self.pane_templates.insert(
-                template_name,
-                (
-                    PaneOrFloatingPane::Pane(TiledPaneLayout {
-                        borderless: borderless.unwrap_or_default(),
-                        focus,
-                        split_size,
-                        run,
-                        children_split_direction,
-                        external_children_index,
-                        children: pane_parts,
-                        children_are_stacked,
-                        is_expanded_in_stack,
-                        ..Default::default()
-                    }),
-                    kdl_node.clone(),
-                ),
-            );
+            Ok(PaneOrFloatingPane::Pane(TiledPaneLayout {
+                borderless: borderless.unwrap_or_default(),
+                focus,
+                split_size,
+                run,
+                children_split_direction,
+                external_children_index,
+                children: pane_parts,
+                children_are_stacked,
+                is_expanded_in_stack,
+                ..Default::default()
+            }))
         }
-
-        Ok(())
     }
     fn parse_tab_node(
         &mut self,
@@ -1140,6 +1231,7 @@ impl<'a> KdlLayoutParser<'a> {
         let is_focused = kdl_get_bool_property_or_child_value!(kdl_node, "focus").unwrap_or(false);
         let hide_floating_panes =
             kdl_get_bool_property_or_child_value!(kdl_node, "hide_floating_panes").unwrap_or(false);
+        let color = self.parse_tab_color(kdl_node)?;
         let children_split_direction = self.parse_split_direction(kdl_node)?;
         let mut child_floating_panes = vec![];
         let children = match kdl_children_nodes!(kdl_node) {
@@ -1148,6 +1240,7 @@ impl<'a> KdlLayoutParser<'a> {
                 self.parse_child_pane_nodes_for_tab(
                     children,
                     should_mark_external_children_index,
+                    tab_cwd.as_ref(),
                     &mut child_floating_panes,
                 )?
             },
@@ -1157,6 +1250,7 @@ impl<'a> KdlLayoutParser<'a> {
             children_split_direction,
             children,
             hide_floating_panes,
+            color,
             ..Default::default()
         };
         if let Some(cwd_prefix) = &self.cwd_prefix(tab_cwd.as_ref())? {
@@ -1164,10 +1258,28 @@ impl<'a> KdlLayoutParser<'a> {
         }
         Ok((is_focused, tab_name, pane_layout, child_floating_panes))
     }
+    fn parse_tab_color(&self, kdl_node: &KdlNode) -> Result<Option<String>, ConfigError> {
+        match kdl_get_string_property_or_child_value!(kdl_node, "color") {
+            // named colors are resolved against the theme when the tab is rendered, so any
+            // palette will do for checking that the color is valid here
+            Some(color) if Palette::default().resolve_color(color).is_none() => {
+                Err(ConfigError::new_layout_kdl_error(
+                    format!(
+                        "Invalid tab color '{}', expected a palette color or a hex color",
+                        color
+                    ),
+                    kdl_node.span().offset(),
+                    kdl_node.span().len(),
+                ))
+            },
+            color => Ok(color.map(|c| c.to_owned())),
+        }
+    }
     fn parse_child_pane_nodes_for_tab(
         &self,
         children: &[KdlNode],
         should_mark_external_children_index: bool,
+        tab_cwd: Option<&PathBuf>,
         child_floating_panes: &mut Vec<FloatingPaneLayout>,
     ) -> Result<Vec<TiledPaneLayout>, ConfigError> {
         let mut nodes = vec![];
@@ -1175,17 +1287,17 @@ impl<'a> KdlLayoutParser<'a> {
         for child in children {
             if kdl_name!(child) == "pane" {
                 nodes.push(self.parse_pane_node(child, is_part_of_stack)?);
-            } else if let Some((pane_template, pane_template_kdl_node)) =
-                self.pane_templates.get(kdl_name!(child)).cloned()
+            } else if let Some((pane_template, pane_template_kdl_node, instance_kdl_node)) =
+                self.pane_template_for_node(child)?
             {
                 nodes.push(self.parse_pane_node_with_template(
-                    child,
+                    &instance_kdl_node,
                     pane_template,
                     should_mark_external_children_index,
                     &pane_template_kdl_node,
                 )?);
             } else if kdl_name!(child) == "floating_panes" {
-                self.populate_floating_pane_children(child, child_floating_panes)?;
+                self.populate_floating_pane_children(child, tab_cwd, child_floating_panes)?;
             } else if self.is_a_valid_tab_property(kdl_name!(child)) {
                 return Err(ConfigError::new_layout_kdl_error(
                     format!("Tab property '{}' must be placed on the tab title line and not in the child braces", kdl_name!(child)),
@@ -1232,12 +1344,12 @@ impl<'a> KdlLayoutParser<'a> {
                     }
                 }
                 external_children_index = Some(i);
-            } else if let Some((pane_template, pane_template_kdl_node)) =
-                self.pane_templates.get(kdl_name!(child)).cloned()
+            } else if let Some((pane_template, pane_template_kdl_node, instance_kdl_node)) =
+                self.pane_template_for_node(child)?
             {
                 let should_mark_external_children_index = false;
                 nodes.push(self.parse_pane_node_with_template(
-                    child,
+                    &instance_kdl_node,
                     pane_template,
                     should_mark_external_children_index,
                     &pane_template_kdl_node,
@@ -1252,12 +1364,96 @@ impl<'a> KdlLayoutParser<'a> {
         }
         Ok((external_children_index, nodes))
     }
+    fn is_pane_template_node(&self, kdl_node: &KdlNode) -> bool {
+        kdl_name!(kdl_node) == "pane_instance"
+            || self.pane_templates.contains_key(kdl_name!(kdl_node))
+    }
+    // returns the pane_template this node uses (either by being named after it or through
+    // `pane_instance name="..."`) with its parameters substituted from the properties of the node,
+    // along with the node itself without these properties
+    fn pane_template_for_node(
+        &self,
+        kdl_node: &KdlNode,
+    ) -> Result<Option<(PaneOrFloatingPane, KdlNode, KdlNode)>, ConfigError> {
+        let is_pane_instance = kdl_name!(kdl_node) == "pane_instance";
+        let template_name = if is_pane_instance {
+            pane_instance_template_name(kdl_node).ok_or(ConfigError::new_layout_kdl_error(
+                "pane_instance must have the name of a pane_template, eg. pane_instance name=\"my_template\"".into(),
+                kdl_node.span().offset(),
+                kdl_node.span().len(),
+            ))?
+        } else {
+            kdl_name!(kdl_node)
+        };
+        let (pane_template, pane_template_kdl_node) = match self.pane_templates.get(template_name) {
+            Some(pane_template) => pane_template.clone(),
+            None if is_pane_instance => {
+                return Err(ConfigError::new_layout_kdl_error(
+                    format!("Unknown pane_template: '{}'", template_name),
+                    kdl_node.span().offset(),
+                    kdl_node.span().len(),
+                ));
+            },
+            None => return Ok(None),
+        };
+        let parameter_names = template_parameter_names(&pane_template_kdl_node);
+        let mut parameters = HashMap::new();
+        let mut instance_kdl_node = kdl_node.clone();
+        instance_kdl_node.entries_mut().retain(|entry| {
+            match entry.name().map(|name| name.value()) {
+                Some("name") if is_pane_instance => false,
+                Some(property_name) if parameter_names.contains(property_name) => {
+                    let value = match entry.value() {
+                        KdlValue::String(value) | KdlValue::RawString(value) => value.clone(),
+                        value => value.to_string(),
+                    };
+                    parameters.insert(property_name.to_owned(), value);
+                    false
+                },
+                _ => true,
+            }
+        });
+        if is_pane_instance {
+            for entry in instance_kdl_node.entries() {
+                let property_name = entry
+                    .name()
+                    .map(|name| name.value())
+                    .or_else(|| entry.value().as_string())
+                    .unwrap_or("");
+                if !self.is_a_valid_pane_property(property_name) {
+                    return Err(ConfigError::new_layout_kdl_error(
+                        format!(
+                            "'{}' is neither a parameter of the '{}' pane_template nor a pane property",
+                            property_name, template_name
+                        ),
+                        entry.span().offset(),
+                        entry.span().len(),
+                    ));
+                }
+            }
+        }
+        if parameter_names.is_empty() {
+            return Ok(Some((
+                pane_template,
+                pane_template_kdl_node,
+                instance_kdl_node,
+            )));
+        }
+        let mut pane_template_kdl_node = pane_template_kdl_node;
+        substitute_template_parameters(&mut pane_template_kdl_node, &parameters);
+        let pane_template = self.parse_pane_template_body(&pane_template_kdl_node)?;
+        Ok(Some((
+            pane_template,
+            pane_template_kdl_node,
+            instance_kdl_node,
+        )))
+    }
     fn has_child_nodes(&self, kdl_node: &KdlNode) -> bool {
         if let Some(children) = kdl_children_nodes!(kdl_node) {
             for child in children {
                 if kdl_name!(child) == "pane"
                     || kdl_name!(child) == "children"
-                    || self.pane_templates.get(kdl_name!(child)).is_some()
+                    || self.is_pane_template_node(child)
                 {
                     return true;
                 }
@@ -1275,9 +1471,7 @@ impl<'a> KdlLayoutParser<'a> {
                     || child_node_name == "children"
                 {
                     return true;
-                } else if let Some((_pane_template, _pane_template_kdl_node)) =
-                    self.pane_templates.get(child_node_name).cloned()
-                {
+                } else if self.is_pane_template_node(child) {
                     return true;
                 }
             }
@@ -1289,6 +1483,7 @@ impl<'a> KdlLayoutParser<'a> {
         pane_run: &Option<Run>,
         pane_template_run: &Option<Run>,
         args: &Option<Vec<String>>,
+        env: &Option<BTreeMap<String, String>>,
         close_on_exit: &Option<bool>,
         start_suspended: &Option<bool>,
         pane_node: &KdlNode,
@@ -1299,6 +1494,12 @@ impl<'a> KdlLayoutParser<'a> {
                 pane_node
             ));
         }
+        if let (None, None, true) = (pane_run, pane_template_run, env.is_some()) {
+            return Err(kdl_parsing_error!(
+                format!("env can only be specified if a command was specified either in the pane_template or in the pane"),
+                pane_node
+            ));
+        }
         if let (None, None, true) = (pane_run, pane_template_run, close_on_exit.is_some()) {
             return Err(kdl_parsing_error!(
                 format!("close_on_exit can only be specified if a command was specified either in the pane_template or in the pane"),
@@ -1317,6 +1518,7 @@ impl<'a> KdlLayoutParser<'a> {
         &self,
         command: &Option<PathBuf>,
         args: &Option<Vec<String>>,
+        env: &Option<BTreeMap<String, String>>,
         close_on_exit: &Option<bool>,
         start_suspended: &Option<bool>,
         pane_node: &KdlNode,
@@ -1343,6 +1545,13 @@ impl<'a> KdlLayoutParser<'a> {
                     pane_node.span().len(),
                 ));
             }
+            if env.is_some() {
+                return Err(ConfigError::new_layout_kdl_error(
+                    "env can only be set if a command was specified".into(),
+                    pane_node.span().offset(),
+                    pane_node.span().len(),
+                ));
+            }
         }
         Ok(())
     }
@@ -1543,6 +1752,7 @@ impl<'a> KdlLayoutParser<'a> {
                 let child_panes = self.parse_child_pane_nodes_for_tab(
                     children,
                     should_mark_external_children_index,
+                    tab_cwd.as_ref(),
                     &mut tab_template_floating_panes,
                 )?;
                 let child_panes_layout = TiledPaneLayout {
@@ -1649,19 +1859,24 @@ impl<'a> KdlLayoutParser<'a> {
                         ));
                     }
                     external_children_index = Some(i.saturating_sub(children_index_offset));
-                } else if let Some((pane_template, pane_template_kdl_node)) =
-                    self.pane_templates.get(kdl_name!(child)).cloned()
+                } else if let Some((pane_template, pane_template_kdl_node, instance_kdl_node)) =
+                    self.pane_template_for_node(child)?
                 {
                     let should_mark_external_children_index = false;
                     tab_children.push(self.parse_pane_node_with_template(
-                        child,
+                        &instance_kdl_node,
                         pane_template,
                         should_mark_external_children_index,
                         &pane_template_kdl_node,
                     )?);
                 } else if kdl_name!(child) == "floating_panes" {
                     children_index_offset += 1;
-                    self.populate_floating_pane_children(child, &mut tab_floating_children)?;
+                    let tab_cwd = None;
+                    self.populate_floating_pane_children(
+                        child,
+                        tab_cwd,
+                        &mut tab_floating_children,
+                    )?;
                 } else if self.is_a_valid_tab_property(kdl_name!(child)) {
                     return Err(ConfigError::new_layout_kdl_error(
                         format!("Tab property '{}' must be placed on the tab_template title line and not in the child braces", kdl_name!(child)),
@@ -1709,13 +1924,12 @@ impl<'a> KdlLayoutParser<'a> {
         let mut dependency_tree = HashMap::new();
         for child in kdl_children {
             if kdl_name!(child) == "pane_template" {
-                let template_name = kdl_get_string_property_or_child_value!(child, "name").ok_or(
-                    ConfigError::new_layout_kdl_error(
+                let template_name =
+                    pane_template_name(child).ok_or(ConfigError::new_layout_kdl_error(
                         "Pane templates must have a name".into(),
                         child.span().offset(),
                         child.span().len(),
-                    ),
-                )?;
+                    ))?;
                 let mut template_children = HashSet::new();
                 self.get_pane_template_dependencies(child, &mut template_children)?;
                 if dependency_tree.contains_key(template_name) {
@@ -1747,6 +1961,11 @@ impl<'a> KdlLayoutParser<'a> {
                 let child_name = kdl_name!(child);
                 if child_name == "pane" {
                     self.get_pane_template_dependencies(child, all_dependencies)?;
+                } else if child_name == "pane_instance" {
+                    if let Some(template_name) = pane_instance_template_name(child) {
+                        all_dependencies.insert(template_name);
+                    }
+                    self.get_pane_template_dependencies(child, all_dependencies)?;
                 } else if !self.is_a_reserved_word(child_name) {
                     all_dependencies.insert(child_name);
                     self.get_pane_template_dependencies(child, all_dependencies)?;
@@ -1763,8 +1982,7 @@ impl<'a> KdlLayoutParser<'a> {
         for child in kdl_children.iter() {
             let child_name = kdl_name!(child);
             if child_name == "pane_template" {
-                let child_name = kdl_get_string_property_or_child_value!(child, "name");
-                if child_name == Some(pane_template_name) {
+                if self::pane_template_name(child) == Some(pane_template_name) {
                     self.parse_pane_template_node(child)?;
                 }
             }
@@ -1829,6 +2047,20 @@ impl<'a> KdlLayoutParser<'a> {
         }
         Ok(())
     }
+    fn populate_background_plugins(
+        &self,
+        layout_children: &[KdlNode],
+        background_plugins: &mut Vec<RunPlugin>,
+    ) -> Result<(), ConfigError> {
+        for child in layout_children.iter() {
+            if kdl_name!(child) == "plugin_background" {
+                if let Some(Run::Plugin(run_plugin)) = self.parse_plugin_block(child)? {
+                    background_plugins.push(run_plugin);
+                }
+            }
+        }
+        Ok(())
+    }
     fn populate_swap_tiled_layouts(
         &mut self,
         layout_children: &[KdlNode],
@@ -2004,9 +2236,11 @@ impl<'a> KdlLayoutParser<'a> {
         let children = match kdl_children_nodes!(layout_node) {
             Some(children) => {
                 let should_mark_external_children_index = true;
+                let tab_cwd = None;
                 self.parse_child_pane_nodes_for_tab(
                     children,
                     should_mark_external_children_index,
+                    tab_cwd,
                     &mut child_floating_panes,
                 )?
             },
@@ -2041,7 +2275,8 @@ impl<'a> KdlLayoutParser<'a> {
     ) -> Result<Vec<FloatingPaneLayout>, ConfigError> {
         let mut floating_panes = vec![];
         self.assert_valid_tab_properties(layout_node)?;
-        self.populate_floating_pane_children(layout_node, &mut floating_panes)?;
+        let tab_cwd = None;
+        self.populate_floating_pane_children(layout_node, tab_cwd, &mut floating_panes)?;
         Ok(floating_panes)
     }
     fn populate_one_swap_floating_layout_with_template(
@@ -2167,7 +2402,8 @@ impl<'a> KdlLayoutParser<'a> {
             }
             child_panes.push(pane_node);
         } else if child_name == "floating_panes" {
-            self.populate_floating_pane_children(child, child_floating_panes)?;
+            let tab_cwd = None;
+            self.populate_floating_pane_children(child, tab_cwd, child_floating_panes)?;
         } else if child_name == "tab" {
             if !child_panes.is_empty() || !child_floating_panes.is_empty() {
                 return Err(ConfigError::new_layout_kdl_error(
@@ -2214,8 +2450,8 @@ impl<'a> KdlLayoutParser<'a> {
                 should_mark_external_children_index,
                 &tab_template_kdl_node,
             )?);
-        } else if let Some((pane_template, pane_template_kdl_node)) =
-            self.pane_templates.get(child_name).cloned()
+        } else if let Some((pane_template, pane_template_kdl_node, instance_kdl_node)) =
+            self.pane_template_for_node(child)?
         {
             if !child_tabs.is_empty() {
                 return Err(ConfigError::new_layout_kdl_error(
@@ -2226,7 +2462,7 @@ impl<'a> KdlLayoutParser<'a> {
             }
             let should_mark_external_children_index = false;
             let mut pane_template = self.parse_pane_node_with_template(
-                child,
+                &instance_kdl_node,
                 pane_template,
                 should_mark_external_children_index,
                 &pane_template_kdl_node,
@@ -2247,21 +2483,24 @@ impl<'a> KdlLayoutParser<'a> {
     fn populate_floating_pane_children(
         &self,
         child: &KdlNode,
+        tab_cwd: Option<&PathBuf>,
         child_floating_panes: &mut Vec<FloatingPaneLayout>,
     ) -> Result<(), ConfigError> {
         if let Some(children) = kdl_children_nodes!(child) {
             for child in children {
                 if kdl_name!(child) == "pane" {
                     let mut pane_node = self.parse_floating_pane_node(child)?;
-                    if let Some(global_cwd) = &self.global_cwd {
-                        pane_node.add_cwd_to_layout(&global_cwd);
+                    // floating panes inherit the cwd of their tab, which is itself relative to
+                    // the global cwd
+                    if let Some(cwd_prefix) = self.cwd_prefix(tab_cwd)? {
+                        pane_node.add_cwd_to_layout(&cwd_prefix);
                     }
                     child_floating_panes.push(pane_node);
-                } else if let Some((pane_template, pane_template_kdl_node)) =
-                    self.pane_templates.get(kdl_name!(child)).cloned()
+                } else if let Some((pane_template, pane_template_kdl_node, instance_kdl_node)) =
+                    self.pane_template_for_node(child)?
                 {
                     let pane_node = self.parse_floating_pane_node_with_template(
-                        child,
+                        &instance_kdl_node,
                         pane_template,
                         &pane_template_kdl_node,
                     )?;
@@ -2368,12 +2607,14 @@ impl<'a> KdlLayoutParser<'a> {
         let mut child_floating_panes = vec![];
         let mut swap_tiled_layouts = vec![];
         let mut swap_floating_layouts = vec![];
+        let mut background_plugins = vec![];
         if let Some(children) = kdl_children_nodes!(layout_node) {
             self.populate_global_cwd(layout_node)?;
             self.populate_pane_templates(children, &kdl_layout)?;
             self.populate_tab_templates(children)?;
             self.populate_swap_tiled_layouts(children, &mut swap_tiled_layouts)?;
             self.populate_swap_floating_layouts(children, &mut swap_floating_layouts)?;
+            self.populate_background_plugins(children, &mut background_plugins)?;
             for child in children {
                 self.populate_layout_child(
                     child,
@@ -2383,7 +2624,7 @@ impl<'a> KdlLayoutParser<'a> {
                 )?;
             }
         }
-        if !child_tabs.is_empty() {
+        let layout = if !child_tabs.is_empty() {
             let has_more_than_one_focused_tab = child_tabs
                 .iter()
                 .filter(|(is_focused, _, _, _)| *is_focused)
@@ -2427,6 +2668,112 @@ impl<'a> KdlLayoutParser<'a> {
                 swap_tiled_layouts,
                 swap_floating_layouts,
             )
+        }?;
+        Ok(Layout {
+            background_plugins,
+            ..layout
+        })
+    }
+}
+
+fn pane_template_name(kdl_node: &KdlNode) -> Option<&str> {
+    // either pane_template name="my_template" or pane_template "my_template"
+    kdl_get_string_property_or_child_value!(kdl_node, "name").or_else(|| {
+        kdl_node
+            .entries()
+            .iter()
+            .find(|entry| entry.name().is_none())
+            .and_then(|entry| entry.value().as_string())
+    })
+}
+
+fn pane_instance_template_name(kdl_node: &KdlNode) -> Option<&str> {
+    kdl_node
+        .get("name")
+        .and_then(|entry| entry.value().as_string())
+}
+
+fn without_positional_template_name(kdl_node: &KdlNode) -> KdlNode {
+    let mut kdl_node = kdl_node.clone();
+    if kdl_node.get("name").is_none() {
+        if let Some(position) = kdl_node
+            .entries()
+            .iter()
+            .position(|entry| entry.name().is_none())
+        {
+            kdl_node.entries_mut().remove(position);
+        }
+    }
+    kdl_node
+}
+
+// parameters appear in the string values of a pane_template as {{parameter_name}}, or as
+// {{parameter_name:default value}} if they have a default
+fn template_parameter_placeholders(value: &str) -> Vec<(usize, usize, &str, Option<&str>)> {
+    // (start, end, name, default)
+    let mut placeholders = vec![];
+    let mut search_from = 0;
+    while let Some(start) = value[search_from..].find("{{").map(|i| i + search_from) {
+        let end = match value[start..].find("}}") {
+            Some(i) => start + i + 2,
+            None => break,
+        };
+        let placeholder = &value[start + 2..end - 2];
+        let (name, default) = match placeholder.split_once(':') {
+            Some((name, default)) => (name.trim(), Some(default)),
+            None => (placeholder.trim(), None),
+        };
+        placeholders.push((start, end, name, default));
+        search_from = end;
+    }
+    placeholders
+}
+
+fn template_parameter_names(kdl_node: &KdlNode) -> HashSet<String> {
+    let mut parameter_names = HashSet::new();
+    for entry in kdl_node.entries() {
+        if let Some(value) = entry.value().as_string() {
+            for (_start, _end, name, _default) in template_parameter_placeholders(value) {
+                parameter_names.insert(name.to_owned());
+            }
+        }
+    }
+    if let Some(children) = kdl_node.children() {
+        for child in children.nodes() {
+            parameter_names.extend(template_parameter_names(child));
+        }
+    }
+    parameter_names
+}
+
+// a placeholder is only substituted if the parameter has a value or a default, others are left
+// as they are so that layouts can still pass a literal {{...}} to their commands
+fn substitute_template_parameters(kdl_node: &mut KdlNode, parameters: &HashMap<String, String>) {
+    for entry in kdl_node.entries_mut() {
+        let substituted_value = match entry.value().as_string() {
+            Some(value) if value.contains("{{") => {
+                let mut substituted_value = String::new();
+                let mut last_end = 0;
+                for (start, end, name, default) in template_parameter_placeholders(value) {
+                    let parameter_value = parameters
+                        .get(name)
+                        .map(|value| value.as_str())
+                        .or(default)
+                        .unwrap_or(&value[start..end]);
+                    substituted_value.push_str(&value[last_end..start]);
+                    substituted_value.push_str(parameter_value);
+                    last_end = end;
+                }
+                substituted_value.push_str(&value[last_end..]);
+                substituted_value
+            },
+            _ => continue,
+        };
+        entry.set_value(substituted_value);
+    }
+    if let Some(children) = kdl_node.children_mut().as_mut() {
+        for child in children.nodes_mut() {
+            substitute_template_parameters(child, parameters);
         }
     }
 }
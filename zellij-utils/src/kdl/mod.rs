@@ -1,14 +1,16 @@
 mod kdl_layout_parser;
 use crate::data::{
-    Direction, InputMode, Key, Palette, PaletteColor, PaneInfo, PaneManifest, PermissionType,
-    Resize, SessionInfo, TabInfo,
+    Direction, InputMode, Key, Palette, PaletteColor, PaneId, PaneInfo, PaneManifest,
+    PermissionType, Resize, ResizePreset, SessionInfo, TabInfo, WorkspaceInfo,
 };
 use crate::envs::EnvironmentVariables;
 use crate::home::{find_default_config_dir, get_layout_dir};
 use crate::input::config::{Config, ConfigError, KdlError};
 use crate::input::keybinds::Keybinds;
 use crate::input::layout::{Layout, PluginUserConfiguration, RunPlugin, RunPluginLocation};
-use crate::input::options::{Clipboard, OnForceClose, Options};
+use crate::input::options::{
+    Bell, Clipboard, OnForceClose, Options, PaneFrameStyle, ScrollLines, SurplusPanes,
+};
 use crate::input::permission::{GrantedPermission, PermissionCache};
 use crate::input::plugins::{PluginConfig, PluginTag, PluginType, PluginsConfig};
 use crate::input::theme::{FrameConfig, Theme, Themes, UiConfig};
@@ -23,7 +25,7 @@ use kdl::{KdlDocument, KdlEntry, KdlNode};
 use std::path::PathBuf;
 use std::str::FromStr;
 
-use crate::input::actions::{Action, SearchDirection, SearchOption};
+use crate::input::actions::{Action, PanePredicate, SearchDirection, SearchOption};
 use crate::input::command::RunCommandAction;
 
 #[macro_export]
@@ -344,6 +346,30 @@ pub fn kdl_arguments_that_are_strings<'a>(
     Ok(args)
 }
 
+/// Either a number of lines or "half_page", as in `scroll_lines 5` or `scroll_lines "half_page"`
+pub fn kdl_scroll_lines_entry(kdl_entry: &KdlEntry) -> Result<ScrollLines, ConfigError> {
+    let scroll_lines = match kdl_entry.value().as_i64() {
+        Some(lines) => ScrollLines::from_str(&lines.to_string()),
+        None => ScrollLines::from_str(kdl_entry.value().as_string().unwrap_or_default()),
+    };
+    scroll_lines.map_err(|e| kdl_parsing_error!(e, kdl_entry))
+}
+
+/// Either the id of a terminal pane or a pane id string, as in `1` or `"plugin_3"`
+pub fn kdl_pane_id_entry(kdl_entry: &KdlEntry) -> Result<PaneId, ConfigError> {
+    let pane_id = match kdl_entry.value().as_i64() {
+        Some(terminal_id) => u32::try_from(terminal_id)
+            .map(PaneId::Terminal)
+            .map_err(|e| e.to_string()),
+        None => kdl_entry
+            .value()
+            .as_string()
+            .ok_or_else(|| format!("Unknown PaneId: {}", kdl_entry.value()))
+            .and_then(PaneId::from_str),
+    };
+    pane_id.map_err(|e| kdl_parsing_error!(e, kdl_entry))
+}
+
 pub fn kdl_child_string_value_for_entry<'a>(
     command_metadata: &'a KdlDocument,
     entry_name: &'a str,
@@ -506,6 +532,22 @@ impl Action {
                 Ok(Action::Search(search_direction))
             },
             "RenameSession" => Ok(Action::RenameSession(string)),
+            "GoToTabByName" => Ok(Action::GoToTabByName(string)),
+            "SwitchWorkspace" => Ok(Action::SwitchWorkspace(string)),
+            "FocusPaneByName" => Ok(Action::FocusPaneByName(string)),
+            "ResizePaneToPreset" => {
+                let preset = ResizePreset::from_str(string.as_str()).map_err(|_| {
+                    ConfigError::new_kdl_error(
+                        format!(
+                            "Invalid resize preset: '{}', expected half, third or golden_ratio",
+                            string
+                        ),
+                        action_node.span().offset(),
+                        action_node.span().len(),
+                    )
+                })?;
+                Ok(Action::ResizePaneToPreset(preset))
+            },
             _ => Err(ConfigError::new_kdl_error(
                 format!("Unsupported action: {}", action_name),
                 action_node.span().offset(),
@@ -986,16 +1028,386 @@ impl TryFrom<(&KdlNode, &Options)> for Action {
                     should_open_in_place,
                 ))
             },
+            "MessageToPlugin" => {
+                let arguments = action_arguments.iter().copied();
+                let mut args = kdl_arguments_that_are_strings(arguments)?;
+                if args.is_empty() {
+                    return Err(ConfigError::new_kdl_error(
+                        "No plugin found to message in MessageToPlugin".into(),
+                        kdl_action.span().offset(),
+                        kdl_action.span().len(),
+                    ));
+                }
+                let plugin_url = args.remove(0);
+
+                let command_metadata = action_children.iter().next();
+                let name = command_metadata
+                    .and_then(|c_m| kdl_child_string_value_for_entry(c_m, "name"))
+                    .map(|name| name.to_owned())
+                    .ok_or_else(|| {
+                        ConfigError::new_kdl_error(
+                            "MessageToPlugin must have a message name".into(),
+                            kdl_action.span().offset(),
+                            kdl_action.span().len(),
+                        )
+                    })?;
+                let payload = command_metadata
+                    .and_then(|c_m| kdl_child_string_value_for_entry(c_m, "payload"))
+                    .map(|payload| payload.to_owned());
+                let current_dir = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+                // relative paths are resolved here, the server might not share our cwd
+                let plugin_url = match RunPluginLocation::parse(&plugin_url, Some(current_dir))? {
+                    location @ RunPluginLocation::File(_) => location.display(),
+                    _ => plugin_url,
+                };
+                Ok(Action::MessageToPlugin {
+                    plugin_url,
+                    name,
+                    payload,
+                })
+            },
             "PreviousSwapLayout" => Ok(Action::PreviousSwapLayout),
             "NextSwapLayout" => Ok(Action::NextSwapLayout),
             "BreakPane" => Ok(Action::BreakPane),
             "BreakPaneRight" => Ok(Action::BreakPaneRight),
             "BreakPaneLeft" => Ok(Action::BreakPaneLeft),
+            "ToggleRuler" => Ok(Action::ToggleRuler),
+            "PopOutPane" => Ok(Action::PopOutPane),
+            "TogglePanePassthrough" => Ok(Action::TogglePanePassthrough),
+            "UndoLayoutChange" => Ok(Action::UndoLayoutChange),
+            "ToggleShowInvisibles" => Ok(Action::ToggleShowInvisibles),
+            "ClearSearch" => Ok(Action::ClearSearch),
+            "DuplicatePane" => Ok(Action::DuplicatePane),
+            "FocusNextPaneSpatial" => Ok(Action::FocusNextPaneSpatial),
+            "ResizeTabToContent" => Ok(Action::ResizeTabToContent),
+            "TogglePaneFrame" => Ok(Action::TogglePaneFrame),
+            "TogglePanePinned" => Ok(Action::TogglePanePinned),
+            "ToggleZoom" => Ok(Action::ToggleZoom),
+            "CycleStack" => {
+                let direction = action_arguments
+                    .first()
+                    .and_then(|entry| entry.value().as_string())
+                    .and_then(|direction| Direction::from_str(direction).ok())
+                    .ok_or_else(|| {
+                        ConfigError::new_kdl_error(
+                            "CycleStack needs a direction (right, left, up or down)".into(),
+                            kdl_action.span().offset(),
+                            kdl_action.span().len(),
+                        )
+                    })?;
+                Ok(Action::CycleStack(direction))
+            },
+            "MoveTab" => {
+                let direction = action_arguments
+                    .first()
+                    .and_then(|entry| entry.value().as_string())
+                    .and_then(|direction| Direction::from_str(direction).ok())
+                    .ok_or_else(|| {
+                        ConfigError::new_kdl_error(
+                            "MoveTab needs a direction (right, left, up or down)".into(),
+                            kdl_action.span().offset(),
+                            kdl_action.span().len(),
+                        )
+                    })?;
+                Ok(Action::MoveTab(direction))
+            },
+            // eg. ResizePaneTo 80 24 or SetPaneMinSize 80 24
+            "ResizePaneTo" | "SetPaneMinSize" => {
+                let size = action_arguments
+                    .iter()
+                    .map(|entry| {
+                        entry
+                            .value()
+                            .as_i64()
+                            .and_then(|size| usize::try_from(size).ok())
+                    })
+                    .collect::<Option<Vec<usize>>>();
+                match size.as_deref() {
+                    Some([cols, rows]) if action_name == "ResizePaneTo" => {
+                        Ok(Action::ResizePaneTo(*cols, *rows))
+                    },
+                    Some([cols, rows]) => Ok(Action::SetPaneMinSize(*cols, *rows)),
+                    _ => Err(ConfigError::new_kdl_error(
+                        format!(
+                            "{} needs a number of columns and of rows, eg. {} 80 24",
+                            action_name, action_name
+                        ),
+                        kdl_action.span().offset(),
+                        kdl_action.span().len(),
+                    )),
+                }
+            },
+            // eg. StackPanes 1 2 "plugin_3"
+            "StackPanes" => {
+                let pane_ids = action_arguments
+                    .iter()
+                    .map(|entry| kdl_pane_id_entry(entry))
+                    .collect::<Result<Vec<PaneId>, ConfigError>>()?;
+                if pane_ids.is_empty() {
+                    return Err(ConfigError::new_kdl_error(
+                        "StackPanes needs the ids of the panes to stack".into(),
+                        kdl_action.span().offset(),
+                        kdl_action.span().len(),
+                    ));
+                }
+                Ok(Action::StackPanes(pane_ids))
+            },
+            // eg. MergePanes 1 2, where pane 1 grows over pane 2
+            "MergePanes" => {
+                let pane_ids = action_arguments
+                    .iter()
+                    .map(|entry| kdl_pane_id_entry(entry))
+                    .collect::<Result<Vec<PaneId>, ConfigError>>()?;
+                match pane_ids[..] {
+                    [keep_pane_id, absorb_pane_id] => {
+                        Ok(Action::MergePanes(keep_pane_id, absorb_pane_id))
+                    },
+                    _ => Err(ConfigError::new_kdl_error(
+                        "MergePanes needs the id of the pane to keep and of the pane to absorb"
+                            .into(),
+                        kdl_action.span().offset(),
+                        kdl_action.span().len(),
+                    )),
+                }
+            },
+            // eg. ClosePanesMatching "exited_zero" or ClosePanesMatching name="cargo *"
+            "ClosePanesMatching" => {
+                let predicate = match kdl_action.get("name") {
+                    Some(entry) => entry
+                        .value()
+                        .as_string()
+                        .map(|name_glob| PanePredicate::NameGlob(name_glob.to_owned())),
+                    None => match action_arguments
+                        .first()
+                        .and_then(|entry| entry.value().as_string())
+                    {
+                        Some("exited_zero") => Some(PanePredicate::ExitedZero),
+                        Some("exited_non_zero") => Some(PanePredicate::ExitedNonZero),
+                        _ => None,
+                    },
+                };
+                predicate.map(Action::ClosePanesMatching).ok_or_else(|| {
+                    ConfigError::new_kdl_error(
+                        "ClosePanesMatching needs \"exited_zero\", \"exited_non_zero\" or a \
+                         name glob, eg. name=\"cargo *\""
+                            .into(),
+                        kdl_action.span().offset(),
+                        kdl_action.span().len(),
+                    )
+                })
+            },
+            // eg. SearchInPane "error" or SearchInPane "Error" case_sensitive=true
+            "SearchInPane" => {
+                let query = action_arguments
+                    .iter()
+                    .find(|entry| entry.name().is_none())
+                    .and_then(|entry| entry.value().as_string())
+                    .ok_or_else(|| {
+                        ConfigError::new_kdl_error(
+                            "SearchInPane needs the text to search for".into(),
+                            kdl_action.span().offset(),
+                            kdl_action.span().len(),
+                        )
+                    })?;
+                let case_sensitive = kdl_action
+                    .get("case_sensitive")
+                    .and_then(|entry| entry.value().as_bool())
+                    .unwrap_or(false);
+                Ok(Action::SearchInPane(query.to_owned(), case_sensitive))
+            },
+            // eg. SplitPaneWithRatio "right" 30
+            "SplitPaneWithRatio" => {
+                let direction = action_arguments
+                    .first()
+                    .and_then(|entry| entry.value().as_string())
+                    .and_then(|direction| Direction::from_str(direction).ok())
+                    .ok_or_else(|| {
+                        ConfigError::new_kdl_error(
+                            "SplitPaneWithRatio needs a direction (right, left, up or down)".into(),
+                            kdl_action.span().offset(),
+                            kdl_action.span().len(),
+                        )
+                    })?;
+                let percent = action_arguments
+                    .get(1)
+                    .and_then(|entry| entry.value().as_i64())
+                    .filter(|percent| (1..100).contains(percent))
+                    .ok_or_else(|| {
+                        ConfigError::new_kdl_error(
+                            "SplitPaneWithRatio needs a percent between 1 and 99".into(),
+                            kdl_action.span().offset(),
+                            kdl_action.span().len(),
+                        )
+                    })?;
+                Ok(Action::SplitPaneWithRatio(direction, percent as u8))
+            },
+            // eg. FocusPaneById 3 or FocusPaneById 1 plugin=true
+            "FocusPaneById" => {
+                let pane_id = action_arguments
+                    .iter()
+                    .find(|entry| entry.name().is_none())
+                    .and_then(|entry| entry.value().as_i64())
+                    .and_then(|pane_id| u32::try_from(pane_id).ok())
+                    .ok_or_else(|| {
+                        ConfigError::new_kdl_error(
+                            "FocusPaneById needs the id of a pane".into(),
+                            kdl_action.span().offset(),
+                            kdl_action.span().len(),
+                        )
+                    })?;
+                let is_plugin = kdl_action
+                    .get("plugin")
+                    .and_then(|entry| entry.value().as_bool())
+                    .unwrap_or(false);
+                Ok(Action::FocusPaneById(pane_id, is_plugin))
+            },
+            // eg. SwapTabLayout 2, SwapTabLayout 2 previous=true or ToggleFloatingPanesInTab 2
+            "SwapTabLayout" | "ToggleFloatingPanesInTab" => {
+                let tab_position = action_arguments
+                    .iter()
+                    .find(|entry| entry.name().is_none())
+                    .and_then(|entry| entry.value().as_i64())
+                    .and_then(|tab_position| u32::try_from(tab_position).ok())
+                    .filter(|tab_position| *tab_position > 0)
+                    .ok_or_else(|| {
+                        ConfigError::new_kdl_error(
+                            format!("{} needs the position of a tab, starting at 1", action_name),
+                            kdl_action.span().offset(),
+                            kdl_action.span().len(),
+                        )
+                    })?;
+                if action_name == "ToggleFloatingPanesInTab" {
+                    return Ok(Action::ToggleFloatingPanesInTab(tab_position));
+                }
+                let previous = kdl_action
+                    .get("previous")
+                    .and_then(|entry| entry.value().as_bool())
+                    .unwrap_or(false);
+                Ok(Action::SwapTabLayout(tab_position, previous))
+            },
+            "AttachToPane" => action_arguments
+                .first()
+                .and_then(|entry| entry.value().as_i64())
+                .and_then(|pane_id| u32::try_from(pane_id).ok())
+                .map(Action::AttachToPane)
+                .ok_or_else(|| {
+                    ConfigError::new_kdl_error(
+                        "AttachToPane needs the id of a terminal pane".into(),
+                        kdl_action.span().offset(),
+                        kdl_action.span().len(),
+                    )
+                }),
+            // without an argument, the pane goes back to the scroll_lines option
+            "SetScrollLines" => match action_arguments.first() {
+                Some(entry) => Ok(Action::SetScrollLines(Some(kdl_scroll_lines_entry(entry)?))),
+                None => Ok(Action::SetScrollLines(None)),
+            },
+            // without an argument, frames are drawn with thin lines
+            "SetPaneFrameStyle" => match action_arguments.first() {
+                Some(entry) => {
+                    let pane_frame_style = entry
+                        .value()
+                        .as_string()
+                        .and_then(|string| PaneFrameStyle::from_str(string).ok())
+                        .ok_or_else(|| {
+                            kdl_parsing_error!(
+                                format!(
+                                    "Invalid value for SetPaneFrameStyle: '{}', expected \
+                                     unicode-heavy, unicode-rounded or ascii",
+                                    entry.value()
+                                ),
+                                entry
+                            )
+                        })?;
+                    Ok(Action::SetPaneFrameStyle(Some(pane_frame_style)))
+                },
+                None => Ok(Action::SetPaneFrameStyle(None)),
+            },
+            // eg. SetFocusDimming 30, without an argument (or with 0) panes are no longer dimmed
+            "SetFocusDimming" => match action_arguments.first() {
+                Some(entry) => {
+                    let percent = entry
+                        .value()
+                        .as_i64()
+                        .filter(|percent| (0..=100).contains(percent))
+                        .ok_or_else(|| {
+                            kdl_parsing_error!(
+                                format!(
+                                    "Invalid value for SetFocusDimming: '{}', expected a percent \
+                                     between 0 and 100",
+                                    entry.value()
+                                ),
+                                entry
+                            )
+                        })?;
+                    Ok(Action::SetFocusDimming(
+                        Some(percent as u8).filter(|p| *p > 0),
+                    ))
+                },
+                None => Ok(Action::SetFocusDimming(None)),
+            },
+            // eg. SetTabColor "red" or SetTabColor "#ff0000", without an argument the tab is no
+            // longer colored
+            "SetTabColor" => match action_arguments.first() {
+                Some(entry) => entry
+                    .value()
+                    .as_string()
+                    .map(|color| Action::SetTabColor(Some(color.to_owned())))
+                    .ok_or_else(|| {
+                        kdl_parsing_error!(
+                            format!(
+                                "Invalid value for SetTabColor: '{}', expected a color name or a \
+                                 hex color",
+                                entry.value()
+                            ),
+                            entry
+                        )
+                    }),
+                None => Ok(Action::SetTabColor(None)),
+            },
+            // eg. SetInputBroadcast true, SetPaneReadOnly false or SetSynchronizedScroll true
+            "SetInputBroadcast" | "SetPaneReadOnly" | "SetSynchronizedScroll" => {
+                let is_active = action_arguments
+                    .first()
+                    .and_then(|entry| entry.value().as_bool())
+                    .ok_or_else(|| {
+                        ConfigError::new_kdl_error(
+                            format!("{} needs true or false", action_name),
+                            kdl_action.span().offset(),
+                            kdl_action.span().len(),
+                        )
+                    })?;
+                match action_name {
+                    "SetInputBroadcast" => Ok(Action::SetInputBroadcast(is_active)),
+                    "SetPaneReadOnly" => Ok(Action::SetPaneReadOnly(is_active)),
+                    _ => Ok(Action::SetSynchronizedScroll(is_active)),
+                }
+            },
             "RenameSession" => parse_kdl_action_char_or_string_arguments!(
                 action_name,
                 action_arguments,
                 kdl_action
             ),
+            "GoToTabByName" => parse_kdl_action_char_or_string_arguments!(
+                action_name,
+                action_arguments,
+                kdl_action
+            ),
+            "ResizePaneToPreset" => parse_kdl_action_char_or_string_arguments!(
+                action_name,
+                action_arguments,
+                kdl_action
+            ),
+            "SwitchWorkspace" => parse_kdl_action_char_or_string_arguments!(
+                action_name,
+                action_arguments,
+                kdl_action
+            ),
+            "FocusPaneByName" => parse_kdl_action_char_or_string_arguments!(
+                action_name,
+                action_arguments,
+                kdl_action
+            ),
             _ => Err(ConfigError::new_kdl_error(
                 format!("Unsupported action: {}", action_name).into(),
                 kdl_action.span().offset(),
@@ -1477,12 +1889,156 @@ impl Options {
         let scrollback_lines_to_serialize =
             kdl_property_first_arg_as_i64_or_error!(kdl_options, "scrollback_lines_to_serialize")
                 .map(|(v, _)| v as usize);
+        let serialize_successfully_exited_panes = kdl_property_first_arg_as_bool_or_error!(
+            kdl_options,
+            "serialize_successfully_exited_panes"
+        )
+        .map(|(v, _)| v);
         let styled_underlines =
             kdl_property_first_arg_as_bool_or_error!(kdl_options, "styled_underlines")
                 .map(|(v, _)| v);
         let serialization_interval =
             kdl_property_first_arg_as_i64_or_error!(kdl_options, "serialization_interval")
                 .map(|(scroll_buffer_size, _entry)| scroll_buffer_size as u64);
+        let transparent_background =
+            kdl_property_first_arg_as_bool_or_error!(kdl_options, "transparent_background")
+                .map(|(v, _)| v);
+        let keep_scrollback_in_alternate_screen = kdl_property_first_arg_as_bool_or_error!(
+            kdl_options,
+            "keep_scrollback_in_alternate_screen"
+        )
+        .map(|(v, _)| v);
+        let pop_out_terminal_command =
+            kdl_property_first_arg_as_string_or_error!(kdl_options, "pop_out_terminal_command")
+                .map(|(pop_out_terminal_command, _entry)| pop_out_terminal_command.to_string());
+        let idle_detach_timeout =
+            match kdl_property_first_arg_as_i64_or_error!(kdl_options, "idle_detach_timeout") {
+                Some((idle_detach_timeout, entry)) if idle_detach_timeout < 1 => {
+                    return Err(kdl_parsing_error!(
+                        format!(
+                            "idle_detach_timeout must be at least 1 second, found: {}",
+                            idle_detach_timeout
+                        ),
+                        entry
+                    ));
+                },
+                Some((idle_detach_timeout, _entry)) => Some(idle_detach_timeout as u64),
+                None => None,
+            };
+        let session_activity_log_size =
+            match kdl_property_first_arg_as_i64_or_error!(kdl_options, "session_activity_log_size")
+            {
+                Some((session_activity_log_size, entry)) if session_activity_log_size < 1 => {
+                    return Err(kdl_parsing_error!(
+                        format!(
+                            "session_activity_log_size must be at least 1, found: {}",
+                            session_activity_log_size
+                        ),
+                        entry
+                    ));
+                },
+                Some((session_activity_log_size, _entry)) => {
+                    Some(session_activity_log_size as usize)
+                },
+                None => None,
+            };
+        let scroll_lines = match kdl_options
+            .get("scroll_lines")
+            .and_then(|property| property.entries().iter().next())
+        {
+            Some(entry) => Some(kdl_scroll_lines_entry(entry)?),
+            None => None,
+        };
+        let layout_undo_depth =
+            match kdl_property_first_arg_as_i64_or_error!(kdl_options, "layout_undo_depth") {
+                Some((layout_undo_depth, entry)) if layout_undo_depth < 0 => {
+                    return Err(kdl_parsing_error!(
+                        format!(
+                            "layout_undo_depth must be 0 (to disable undo) or more, found: {}",
+                            layout_undo_depth
+                        ),
+                        entry
+                    ));
+                },
+                Some((layout_undo_depth, _entry)) => Some(layout_undo_depth as usize),
+                None => None,
+            };
+        let plugin_metrics_interval =
+            match kdl_property_first_arg_as_i64_or_error!(kdl_options, "plugin_metrics_interval") {
+                Some((plugin_metrics_interval, entry)) if plugin_metrics_interval < 1 => {
+                    return Err(kdl_parsing_error!(
+                        format!(
+                            "plugin_metrics_interval must be at least 1 second, found: {}",
+                            plugin_metrics_interval
+                        ),
+                        entry
+                    ));
+                },
+                Some((plugin_metrics_interval, _entry)) => Some(plugin_metrics_interval as u64),
+                None => None,
+            };
+        let surplus_panes =
+            match kdl_property_first_arg_as_string_or_error!(kdl_options, "surplus_panes") {
+                Some((string, entry)) => Some(SurplusPanes::from_str(string).map_err(|_| {
+                    kdl_parsing_error!(
+                        format!("Invalid value for surplus_panes: '{}'", string),
+                        entry
+                    )
+                })?),
+                None => None,
+            };
+        // any number of handlers, eg. bell "visual" "tab"
+        let bell = match kdl_options.get("bell") {
+            Some(bell_node) => {
+                let handlers: Vec<&str> = bell_node
+                    .entries()
+                    .iter()
+                    .filter_map(|entry| entry.value().as_string())
+                    .collect();
+                Some(
+                    Bell::from_str(&handlers.join(" "))
+                        .map_err(|e| kdl_parsing_error!(e, bell_node))?,
+                )
+            },
+            None => None,
+        };
+        let passthrough_escape_key =
+            match kdl_property_first_arg_as_string_or_error!(kdl_options, "passthrough_escape_key")
+            {
+                Some((key, entry)) => Some(
+                    Key::from_str(key)
+                        .map(|_| key.to_owned())
+                        .map_err(|e| kdl_parsing_error!(e.to_string(), entry))?,
+                ),
+                None => None,
+            };
+        let pane_frame_style =
+            match kdl_property_first_arg_as_string_or_error!(kdl_options, "pane_frame_style") {
+                Some((string, entry)) => Some(PaneFrameStyle::from_str(string).map_err(|_| {
+                    kdl_parsing_error!(
+                        format!("Invalid value for pane_frame_style: '{}'", string),
+                        entry
+                    )
+                })?),
+                None => None,
+            };
+        let focus_dimming =
+            match kdl_property_first_arg_as_i64_or_error!(kdl_options, "focus_dimming") {
+                Some((focus_dimming, entry)) if !(0..=100).contains(&focus_dimming) => {
+                    return Err(kdl_parsing_error!(
+                        format!(
+                            "focus_dimming must be a percent between 0 and 100, found: {}",
+                            focus_dimming
+                        ),
+                        entry
+                    ));
+                },
+                Some((focus_dimming, _entry)) => Some(focus_dimming as u8),
+                None => None,
+            };
+        let paste_command =
+            kdl_property_first_arg_as_string_or_error!(kdl_options, "paste_command")
+                .map(|(paste_command, _entry)| paste_command.to_string());
         Ok(Options {
             simplified_ui,
             theme,
@@ -1507,8 +2063,23 @@ impl Options {
             session_serialization,
             serialize_pane_viewport,
             scrollback_lines_to_serialize,
+            serialize_successfully_exited_panes,
             styled_underlines,
             serialization_interval,
+            transparent_background,
+            keep_scrollback_in_alternate_screen,
+            pop_out_terminal_command,
+            idle_detach_timeout,
+            session_activity_log_size,
+            scroll_lines,
+            layout_undo_depth,
+            plugin_metrics_interval,
+            surplus_panes,
+            bell,
+            passthrough_escape_key,
+            pane_frame_style,
+            focus_dimming,
+            paste_command,
         })
     }
 }
@@ -1960,6 +2531,13 @@ impl SessionInfo {
             .and_then(|e| e.value().as_i64())
             .map(|c| c as usize)
             .ok_or("Failed to parse connected_clients")?;
+        // sessions written before observers existed don't have this node
+        let observer_clients = kdl_document
+            .get("observer_clients")
+            .and_then(|n| n.entries().iter().next())
+            .and_then(|e| e.value().as_i64())
+            .map(|c| c as usize)
+            .unwrap_or(0);
         let tabs: Vec<TabInfo> = kdl_document
             .get("tabs")
             .and_then(|t| t.children())
@@ -1984,6 +2562,7 @@ impl SessionInfo {
             tabs,
             panes,
             connected_clients,
+            observer_clients,
             is_current_session,
         })
     }
@@ -1996,6 +2575,9 @@ impl SessionInfo {
         let mut connected_clients = KdlNode::new("connected_clients");
         connected_clients.push(self.connected_clients as i64);
 
+        let mut observer_clients = KdlNode::new("observer_clients");
+        observer_clients.push(self.observer_clients as i64);
+
         let mut tabs = KdlNode::new("tabs");
         let mut tab_children = KdlDocument::new();
         for tab_info in &self.tabs {
@@ -2013,6 +2595,59 @@ impl SessionInfo {
         kdl_document.nodes_mut().push(tabs);
         kdl_document.nodes_mut().push(panes);
         kdl_document.nodes_mut().push(connected_clients);
+        kdl_document.nodes_mut().push(observer_clients);
+        kdl_document.fmt();
+        kdl_document.to_string()
+    }
+}
+
+impl WorkspaceInfo {
+    pub fn from_string(raw_workspace_info: &str) -> Result<Self, String> {
+        let kdl_document: KdlDocument = raw_workspace_info
+            .parse()
+            .map_err(|e| format!("Failed to parse kdl document: {}", e))?;
+        let name = kdl_document
+            .get("name")
+            .and_then(|n| n.entries().iter().next())
+            .and_then(|e| e.value().as_string())
+            .map(|s| s.to_owned())
+            .ok_or("Failed to parse workspace name")?;
+        let sessions = kdl_document
+            .get("sessions")
+            .and_then(|s| s.children())
+            .map(|s| {
+                s.nodes()
+                    .iter()
+                    .filter_map(|session| {
+                        session
+                            .entries()
+                            .iter()
+                            .next()
+                            .and_then(|e| e.value().as_string())
+                            .map(|s| s.to_owned())
+                    })
+                    .collect()
+            })
+            .ok_or("Failed to parse workspace sessions")?;
+        Ok(WorkspaceInfo { name, sessions })
+    }
+    pub fn to_string(&self) -> String {
+        let mut kdl_document = KdlDocument::new();
+
+        let mut name = KdlNode::new("name");
+        name.push(self.name.clone());
+
+        let mut sessions = KdlNode::new("sessions");
+        let mut session_children = KdlDocument::new();
+        for session_name in &self.sessions {
+            let mut session = KdlNode::new("session");
+            session.push(session_name.clone());
+            session_children.nodes_mut().push(session);
+        }
+        sessions.set_children(session_children);
+
+        kdl_document.nodes_mut().push(name);
+        kdl_document.nodes_mut().push(sessions);
         kdl_document.fmt();
         kdl_document.to_string()
     }
@@ -2079,6 +2714,13 @@ impl TabInfo {
         }
         let active_swap_layout_name = optional_string_node!("active_swap_layout_name");
         let is_swap_layout_dirty = bool_node!("is_swap_layout_dirty");
+        // not in the session info of older versions
+        let has_bell = kdl_document
+            .get("has_bell")
+            .and_then(|n| n.entries().iter().next())
+            .and_then(|e| e.value().as_bool())
+            .unwrap_or(false);
+        let color = optional_string_node!("color");
         Ok(TabInfo {
             position,
             name,
@@ -2090,6 +2732,8 @@ impl TabInfo {
             other_focused_clients,
             active_swap_layout_name,
             is_swap_layout_dirty,
+            has_bell,
+            color,
         })
     }
     pub fn encode_to_kdl(&self) -> KdlDocument {
@@ -2141,6 +2785,16 @@ impl TabInfo {
         is_swap_layout_dirty.push(self.is_swap_layout_dirty);
         kdl_doucment.nodes_mut().push(is_swap_layout_dirty);
 
+        let mut has_bell = KdlNode::new("has_bell");
+        has_bell.push(self.has_bell);
+        kdl_doucment.nodes_mut().push(has_bell);
+
+        if let Some(color) = self.color.as_ref() {
+            let mut color_node = KdlNode::new("color");
+            color_node.push(color.to_string());
+            kdl_doucment.nodes_mut().push(color_node);
+        }
+
         kdl_doucment
     }
 }
@@ -2404,6 +3058,58 @@ pub fn parse_plugin_user_configuration(
     Ok(configuration)
 }
 
+#[test]
+fn serialize_and_deserialize_workspace_info() {
+    let workspace_info = WorkspaceInfo::new(
+        "my workspace".to_owned(),
+        vec!["api".to_owned(), "frontend".to_owned()],
+    );
+    let serialized = workspace_info.to_string();
+    let deserialized = WorkspaceInfo::from_string(&serialized).unwrap();
+    assert_eq!(workspace_info, deserialized);
+    assert_eq!(deserialized.primary_session(), Some(&"api".to_owned()));
+    assert_eq!(deserialized.sibling_sessions(), &["frontend".to_owned()]);
+    assert_eq!(
+        deserialized.session_to_attach(None),
+        Ok("api".to_owned()),
+        "attaching to the workspace brings up its primary session"
+    );
+    assert_eq!(
+        deserialized.session_to_attach(Some("frontend")),
+        Ok("frontend".to_owned()),
+        "siblings can be attached to through the workspace"
+    );
+    assert!(deserialized.session_to_attach(Some("unrelated")).is_err());
+}
+
+#[test]
+fn attaching_to_a_workspace_starts_its_other_sessions_in_the_background() {
+    let workspace_info = WorkspaceInfo::new(
+        "my workspace".to_owned(),
+        vec!["api".to_owned(), "frontend".to_owned(), "db".to_owned()],
+    );
+    let session_to_attach = workspace_info.session_to_attach(None).unwrap();
+    assert_eq!(
+        workspace_info.sessions_to_start_in_background(&session_to_attach, &[]),
+        vec!["frontend".to_owned(), "db".to_owned()],
+        "all siblings are brought up along with the primary session"
+    );
+    assert_eq!(
+        workspace_info.sessions_to_start_in_background(
+            &session_to_attach,
+            &["db".to_owned(), "unrelated".to_owned()]
+        ),
+        vec!["frontend".to_owned()],
+        "siblings that are already running are left alone"
+    );
+    let session_to_attach = workspace_info.session_to_attach(Some("frontend")).unwrap();
+    assert_eq!(
+        workspace_info.sessions_to_start_in_background(&session_to_attach, &["api".to_owned()]),
+        vec!["db".to_owned()],
+        "the requested sibling is attached to rather than started in the background"
+    );
+}
+
 #[test]
 fn serialize_and_deserialize_session_info() {
     let session_info = SessionInfo::default();
@@ -2481,6 +3187,8 @@ fn serialize_and_deserialize_session_info_with_data() {
                 other_focused_clients: vec![2, 3],
                 active_swap_layout_name: Some("BASE".to_owned()),
                 is_swap_layout_dirty: true,
+                has_bell: true,
+                color: Some("#ff8800".to_owned()),
             },
             TabInfo {
                 position: 1,
@@ -2493,10 +3201,13 @@ fn serialize_and_deserialize_session_info_with_data() {
                 other_focused_clients: vec![2, 3],
                 active_swap_layout_name: None,
                 is_swap_layout_dirty: false,
+                has_bell: false,
+                color: None,
             },
         ],
         panes: PaneManifest { panes },
         connected_clients: 2,
+        observer_clients: 1,
         is_current_session: false,
     };
     let serialized = session_info.to_string();
@@ -2,12 +2,13 @@ pub use super::generated_api::api::{
     action::{
         action::OptionalPayload, Action as ProtobufAction, ActionName as ProtobufActionName,
         DumpScreenPayload, EditFilePayload, GoToTabNamePayload, IdAndName,
-        LaunchOrFocusPluginPayload, MovePanePayload, NameAndValue as ProtobufNameAndValue,
-        NewFloatingPanePayload, NewPanePayload, NewPluginPanePayload, NewTiledPanePayload,
-        PaneIdAndShouldFloat, PluginConfiguration as ProtobufPluginConfiguration,
-        Position as ProtobufPosition, RunCommandAction as ProtobufRunCommandAction,
-        ScrollAtPayload, SearchDirection as ProtobufSearchDirection,
-        SearchOption as ProtobufSearchOption, SwitchToModePayload, WriteCharsPayload, WritePayload,
+        LaunchOrFocusPluginPayload, MessageToPluginPayload, MovePanePayload,
+        NameAndValue as ProtobufNameAndValue, NewFloatingPanePayload, NewPanePayload,
+        NewPluginPanePayload, NewTiledPanePayload, PaneIdAndShouldFloat,
+        PluginConfiguration as ProtobufPluginConfiguration, Position as ProtobufPosition,
+        RunCommandAction as ProtobufRunCommandAction, ScrollAtPayload,
+        SearchDirection as ProtobufSearchDirection, SearchOption as ProtobufSearchOption,
+        SwitchToModePayload, WriteCharsPayload, WritePayload,
     },
     input_mode::InputMode as ProtobufInputMode,
     resize::{Resize as ProtobufResize, ResizeDirection as ProtobufResizeDirection},
@@ -658,6 +659,40 @@ impl TryFrom<ProtobufAction> for Action {
                 },
                 _ => Err("Wrong payload for Action::RenameSession"),
             },
+            Some(ProtobufActionName::ToggleRuler) => match protobuf_action.optional_payload {
+                Some(_) => Err("ToggleRuler should not have a payload"),
+                None => Ok(Action::ToggleRuler),
+            },
+            Some(ProtobufActionName::MessageToPlugin) => match protobuf_action.optional_payload {
+                Some(OptionalPayload::MessageToPluginPayload(payload)) => {
+                    Ok(Action::MessageToPlugin {
+                        plugin_url: payload.plugin_url,
+                        name: payload.name,
+                        payload: payload.payload,
+                    })
+                },
+                _ => Err("Wrong payload for Action::MessageToPlugin"),
+            },
+            Some(ProtobufActionName::PopOutPane) => match protobuf_action.optional_payload {
+                Some(_) => Err("PopOutPane should not have a payload"),
+                None => Ok(Action::PopOutPane),
+            },
+            Some(ProtobufActionName::GoToTabByName) => match protobuf_action.optional_payload {
+                Some(OptionalPayload::GoToTabByNamePayload(tab_name)) => {
+                    Ok(Action::GoToTabByName(tab_name))
+                },
+                _ => Err("Wrong payload for Action::GoToTabByName"),
+            },
+            Some(ProtobufActionName::TogglePanePassthrough) => {
+                match protobuf_action.optional_payload {
+                    Some(_) => Err("TogglePanePassthrough should not have a payload"),
+                    None => Ok(Action::TogglePanePassthrough),
+                }
+            },
+            Some(ProtobufActionName::UndoLayoutChange) => match protobuf_action.optional_payload {
+                Some(_) => Err("UndoLayoutChange should not have a payload"),
+                None => Ok(Action::UndoLayoutChange),
+            },
             _ => Err("Unknown Action"),
         }
     }
@@ -1215,6 +1250,40 @@ impl TryFrom<Action> for ProtobufAction {
                 name: ProtobufActionName::RenameSession as i32,
                 optional_payload: Some(OptionalPayload::RenameSessionPayload(session_name)),
             }),
+            Action::ToggleRuler => Ok(ProtobufAction {
+                name: ProtobufActionName::ToggleRuler as i32,
+                optional_payload: None,
+            }),
+            Action::MessageToPlugin {
+                plugin_url,
+                name,
+                payload,
+            } => Ok(ProtobufAction {
+                name: ProtobufActionName::MessageToPlugin as i32,
+                optional_payload: Some(OptionalPayload::MessageToPluginPayload(
+                    MessageToPluginPayload {
+                        plugin_url,
+                        name,
+                        payload,
+                    },
+                )),
+            }),
+            Action::PopOutPane => Ok(ProtobufAction {
+                name: ProtobufActionName::PopOutPane as i32,
+                optional_payload: None,
+            }),
+            Action::GoToTabByName(tab_name) => Ok(ProtobufAction {
+                name: ProtobufActionName::GoToTabByName as i32,
+                optional_payload: Some(OptionalPayload::GoToTabByNamePayload(tab_name)),
+            }),
+            Action::TogglePanePassthrough => Ok(ProtobufAction {
+                name: ProtobufActionName::TogglePanePassthrough as i32,
+                optional_payload: None,
+            }),
+            Action::UndoLayoutChange => Ok(ProtobufAction {
+                name: ProtobufActionName::UndoLayoutChange as i32,
+                optional_payload: None,
+            }),
             Action::NoOp
             | Action::Confirm
             | Action::NewInPlacePane(..)
@@ -1222,6 +1291,43 @@ impl TryFrom<Action> for ProtobufAction {
             | Action::Deny
             | Action::Copy
             | Action::DumpLayout
+            | Action::QuerySessionEnv
+            | Action::SetScrollLines(..)
+            | Action::SetPaneFrameStyle(..)
+            | Action::SplitPaneWithRatio(..)
+            | Action::FocusPaneById(..)
+            | Action::ToggleShowInvisibles
+            | Action::ResizePaneToPreset(..)
+            | Action::SwitchWorkspace(..)
+            | Action::SetClientTheme(..)
+            | Action::AttachToPane(..)
+            | Action::ApplyLayoutToTab(..)
+            | Action::CapturePaneText(..)
+            | Action::CaptureRendered
+            | Action::SearchInPane(..)
+            | Action::ClearSearch
+            | Action::ClosePanesMatching(..)
+            | Action::StackPanes(..)
+            | Action::CycleStack(..)
+            | Action::DuplicatePane
+            | Action::FocusNextPaneSpatial
+            | Action::FocusPaneByName(..)
+            | Action::MergePanes(..)
+            | Action::MoveTab(..)
+            | Action::ResizePaneTo(..)
+            | Action::ResizeTabToContent
+            | Action::SetFocusDimming(..)
+            | Action::SetInputBroadcast(..)
+            | Action::SetPaneMinSize(..)
+            | Action::SetPaneReadOnly(..)
+            | Action::SetSynchronizedScroll(..)
+            | Action::SetTabColor(..)
+            | Action::SwapTabLayout(..)
+            | Action::ToggleFloatingPanesInTab(..)
+            | Action::TogglePaneFrame
+            | Action::TogglePanePinned
+            | Action::ToggleZoom
+            | Action::SwitchModeForClient(..)
             | Action::SkipConfirm(..) => Err("Unsupported action"),
         }
     }
@@ -1,21 +1,29 @@
 pub use super::generated_api::api::{
     action::{Action as ProtobufAction, Position as ProtobufPosition},
     event::{
-        event::Payload as ProtobufEventPayload, CopyDestination as ProtobufCopyDestination,
-        Event as ProtobufEvent, EventNameList as ProtobufEventNameList,
-        EventType as ProtobufEventType, InputModeKeybinds as ProtobufInputModeKeybinds,
-        KeyBind as ProtobufKeyBind, ModeUpdatePayload as ProtobufModeUpdatePayload,
+        event::Payload as ProtobufEventPayload, ClientInputLatency as ProtobufClientInputLatency,
+        CopyDestination as ProtobufCopyDestination, Event as ProtobufEvent,
+        EventNameList as ProtobufEventNameList, EventType as ProtobufEventType,
+        InputModeKeybinds as ProtobufInputModeKeybinds, KeyBind as ProtobufKeyBind,
+        Keybindings as ProtobufKeybindings, ModeUpdatePayload as ProtobufModeUpdatePayload,
         PaneInfo as ProtobufPaneInfo, PaneManifest as ProtobufPaneManifest,
-        ResurrectableSession as ProtobufResurrectableSession,
-        SessionManifest as ProtobufSessionManifest, TabInfo as ProtobufTabInfo, *,
+        PluginMetric as ProtobufPluginMetric, ResurrectableSession as ProtobufResurrectableSession,
+        SelectionBounds as ProtobufSelectionBounds, SessionActivity as ProtobufSessionActivity,
+        SessionActivityKind as ProtobufSessionActivityKind, SessionLayout as ProtobufSessionLayout,
+        SessionManifest as ProtobufSessionManifest, StatusSegment as ProtobufStatusSegment,
+        TabInfo as ProtobufTabInfo, *,
     },
     input_mode::InputMode as ProtobufInputMode,
     key::Key as ProtobufKey,
+    pane_id::PaneId as ProtobufPaneId,
+    plugin_permission::PermissionType as ProtobufPermissionType,
     style::Style as ProtobufStyle,
 };
 use crate::data::{
-    CopyDestination, Event, EventType, InputMode, Key, ModeInfo, Mouse, PaneInfo, PaneManifest,
-    PermissionStatus, PluginCapabilities, SessionInfo, Style, TabInfo,
+    ClientInputLatency, CopyDestination, Event, EventType, InputMode, Key, Keybindings, ModeInfo,
+    Mouse, PaneId, PaneInfo, PaneManifest, PermissionStatus, PermissionType, PluginCapabilities,
+    PluginMetric, SelectionBounds, SessionActivity, SessionActivityKind, SessionInfo,
+    SessionLayout, SessionSharingStatus, StatusSegment, Style, TabInfo,
 };
 
 use crate::errors::prelude::*;
@@ -97,7 +105,11 @@ impl TryFrom<ProtobufEvent> for Event {
                 _ => Err("Malformed payload for the Copy To Clipboard Event"),
             },
             Some(ProtobufEventType::SystemClipboardFailure) => match protobuf_event.payload {
-                None => Ok(Event::SystemClipboardFailure),
+                Some(ProtobufEventPayload::SystemClipboardFailurePayload(byte_length)) => {
+                    Ok(Event::SystemClipboardFailure(byte_length as usize))
+                },
+                // sent without the length of the text by earlier versions
+                None => Ok(Event::SystemClipboardFailure(0)),
                 _ => Err("Malformed payload for the system clipboard failure Event"),
             },
             Some(ProtobufEventType::InputReceived) => match protobuf_event.payload {
@@ -228,6 +240,193 @@ impl TryFrom<ProtobufEvent> for Event {
                 },
                 _ => Err("Malformed payload for the WebRequestResult Event"),
             },
+            Some(ProtobufEventType::InputLatencyStats) => match protobuf_event.payload {
+                Some(ProtobufEventPayload::InputLatencyStatsPayload(
+                    input_latency_stats_payload,
+                )) => Ok(Event::InputLatencyStats(
+                    input_latency_stats_payload
+                        .client_input_latencies
+                        .into_iter()
+                        .map(|c| c.into())
+                        .collect(),
+                )),
+                _ => Err("Malformed payload for the InputLatencyStats Event"),
+            },
+            Some(ProtobufEventType::PaneClosed) => match protobuf_event.payload {
+                Some(ProtobufEventPayload::PaneClosedPayload(pane_closed_payload)) => {
+                    let pane_id = pane_closed_payload
+                        .pane_id
+                        .ok_or("No pane id in PaneClosed payload")?;
+                    Ok(Event::PaneClosed(
+                        PaneId::try_from(pane_id)?,
+                        pane_closed_payload.exit_status,
+                    ))
+                },
+                _ => Err("Malformed payload for the PaneClosed Event"),
+            },
+            Some(ProtobufEventType::SessionSharingStatus) => match protobuf_event.payload {
+                Some(ProtobufEventPayload::SessionSharingStatusPayload(
+                    session_sharing_status_payload,
+                )) => Ok(Event::SessionSharingStatus(SessionSharingStatus {
+                    connected_clients: session_sharing_status_payload.connected_clients as usize,
+                    is_recording: session_sharing_status_payload.is_recording,
+                })),
+                _ => Err("Malformed payload for the SessionSharingStatus Event"),
+            },
+            Some(ProtobufEventType::EnteredCopyMode) => match protobuf_event.payload {
+                None => Ok(Event::EnteredCopyMode),
+                _ => Err("Malformed payload for the EnteredCopyMode Event"),
+            },
+            Some(ProtobufEventType::ExitedCopyMode) => match protobuf_event.payload {
+                None => Ok(Event::ExitedCopyMode),
+                _ => Err("Malformed payload for the ExitedCopyMode Event"),
+            },
+            Some(ProtobufEventType::SelectionChanged) => match protobuf_event.payload {
+                Some(ProtobufEventPayload::SelectionChangedPayload(selection_changed_payload)) => {
+                    let pane_id = selection_changed_payload
+                        .pane_id
+                        .ok_or("No pane id in SelectionChanged payload")?;
+                    Ok(Event::SelectionChanged(
+                        PaneId::try_from(pane_id)?,
+                        selection_changed_payload.bounds.map(|b| b.into()),
+                    ))
+                },
+                _ => Err("Malformed payload for the SelectionChanged Event"),
+            },
+            Some(ProtobufEventType::SystemOpenerFailure) => match protobuf_event.payload {
+                Some(ProtobufEventPayload::SystemOpenerFailurePayload(
+                    system_opener_failure_payload,
+                )) => Ok(Event::SystemOpenerFailure(
+                    system_opener_failure_payload.target,
+                    system_opener_failure_payload.error,
+                )),
+                _ => Err("Malformed payload for the SystemOpenerFailure Event"),
+            },
+            Some(ProtobufEventType::PermissionDenied) => match protobuf_event.payload {
+                Some(ProtobufEventPayload::PermissionDeniedPayload(permission)) => {
+                    let permission = ProtobufPermissionType::from_i32(permission)
+                        .ok_or("Unknown permission in PermissionDenied payload")?;
+                    Ok(Event::PermissionDenied(PermissionType::try_from(
+                        permission,
+                    )?))
+                },
+                _ => Err("Malformed payload for the PermissionDenied Event"),
+            },
+            Some(ProtobufEventType::RestoreState) => match protobuf_event.payload {
+                Some(ProtobufEventPayload::RestoreStatePayload(state)) => {
+                    Ok(Event::RestoreState(state))
+                },
+                _ => Err("Malformed payload for the RestoreState Event"),
+            },
+            Some(ProtobufEventType::Paste) => match protobuf_event.payload {
+                Some(ProtobufEventPayload::PastePayload(pasted_text)) => {
+                    Ok(Event::Paste(pasted_text))
+                },
+                _ => Err("Malformed payload for the Paste Event"),
+            },
+            Some(ProtobufEventType::StatusSegmentsUpdate) => match protobuf_event.payload {
+                Some(ProtobufEventPayload::StatusSegmentsPayload(status_segments_payload)) => {
+                    Ok(Event::StatusSegmentsUpdate(
+                        status_segments_payload
+                            .status_segments
+                            .into_iter()
+                            .map(|s| s.into())
+                            .collect(),
+                    ))
+                },
+                _ => Err("Malformed payload for the StatusSegmentsUpdate Event"),
+            },
+            Some(ProtobufEventType::SessionActivity) => match protobuf_event.payload {
+                Some(ProtobufEventPayload::SessionActivityPayload(session_activity_payload)) => {
+                    let mut session_activities = vec![];
+                    for session_activity in session_activity_payload.session_activities {
+                        session_activities.push(session_activity.try_into()?);
+                    }
+                    Ok(Event::SessionActivity(session_activities))
+                },
+                _ => Err("Malformed payload for the SessionActivity Event"),
+            },
+            Some(ProtobufEventType::PaneOutput) => match protobuf_event.payload {
+                Some(ProtobufEventPayload::PaneOutputPayload(pane_output_payload)) => {
+                    let pane_id = pane_output_payload
+                        .pane_id
+                        .ok_or("No pane id in PaneOutput payload")?;
+                    Ok(Event::PaneOutput(
+                        PaneId::try_from(pane_id)?,
+                        pane_output_payload.output,
+                    ))
+                },
+                _ => Err("Malformed payload for the PaneOutput Event"),
+            },
+            Some(ProtobufEventType::ModeChanged) => match protobuf_event.payload {
+                Some(ProtobufEventPayload::ModeChangedPayload(input_mode)) => {
+                    let input_mode = ProtobufInputMode::from_i32(input_mode)
+                        .ok_or("Malformed InputMode in the ModeChanged Event")?;
+                    Ok(Event::ModeChanged(InputMode::try_from(input_mode)?))
+                },
+                _ => Err("Malformed payload for the ModeChanged Event"),
+            },
+            Some(ProtobufEventType::HyperlinkClicked) => match protobuf_event.payload {
+                Some(ProtobufEventPayload::HyperlinkClickedPayload(hyperlink_clicked_payload)) => {
+                    let pane_id = hyperlink_clicked_payload
+                        .pane_id
+                        .ok_or("No pane id in HyperlinkClicked payload")?;
+                    Ok(Event::HyperlinkClicked(
+                        PaneId::try_from(pane_id)?,
+                        hyperlink_clicked_payload.uri,
+                    ))
+                },
+                _ => Err("Malformed payload for the HyperlinkClicked Event"),
+            },
+            Some(ProtobufEventType::PluginMetrics) => match protobuf_event.payload {
+                Some(ProtobufEventPayload::PluginMetricsPayload(plugin_metrics_payload)) => {
+                    Ok(Event::PluginMetrics(
+                        plugin_metrics_payload
+                            .plugin_metrics
+                            .into_iter()
+                            .map(|m| m.into())
+                            .collect(),
+                    ))
+                },
+                _ => Err("Malformed payload for the PluginMetrics Event"),
+            },
+            Some(ProtobufEventType::Bell) => match protobuf_event.payload {
+                Some(ProtobufEventPayload::BellPayload(bell_payload)) => {
+                    let pane_id = bell_payload.pane_id.ok_or("No pane id in Bell payload")?;
+                    Ok(Event::Bell(PaneId::try_from(pane_id)?))
+                },
+                _ => Err("Malformed payload for the Bell Event"),
+            },
+            Some(ProtobufEventType::WorkspaceChanged) => match protobuf_event.payload {
+                Some(ProtobufEventPayload::WorkspaceChangedPayload(workspace_name)) => {
+                    Ok(Event::WorkspaceChanged(workspace_name))
+                },
+                _ => Err("Malformed payload for the WorkspaceChanged Event"),
+            },
+            Some(ProtobufEventType::ClipboardCopySucceeded) => match protobuf_event.payload {
+                Some(ProtobufEventPayload::ClipboardCopySucceededPayload(byte_length)) => {
+                    Ok(Event::ClipboardCopySucceeded(byte_length as usize))
+                },
+                _ => Err("Malformed payload for the ClipboardCopySucceeded Event"),
+            },
+            Some(ProtobufEventType::PaneTitleChanged) => match protobuf_event.payload {
+                Some(ProtobufEventPayload::PaneTitleChangedPayload(pane_title_changed_payload)) => {
+                    let pane_id = pane_title_changed_payload
+                        .pane_id
+                        .ok_or("No pane id in PaneTitleChanged payload")?;
+                    Ok(Event::PaneTitleChanged(
+                        PaneId::try_from(pane_id)?,
+                        pane_title_changed_payload.title,
+                    ))
+                },
+                _ => Err("Malformed payload for the PaneTitleChanged Event"),
+            },
+            Some(ProtobufEventType::TerminalPaneOpened) => match protobuf_event.payload {
+                Some(ProtobufEventPayload::TerminalPaneOpenedPayload(pane_id)) => {
+                    Ok(Event::TerminalPaneOpened(pane_id))
+                },
+                _ => Err("Malformed payload for the TerminalPaneOpened Event"),
+            },
             None => Err("Unknown Protobuf Event"),
         }
     }
@@ -303,9 +502,11 @@ impl TryFrom<Event> for ProtobufEvent {
                     )),
                 })
             },
-            Event::SystemClipboardFailure => Ok(ProtobufEvent {
+            Event::SystemClipboardFailure(byte_length) => Ok(ProtobufEvent {
                 name: ProtobufEventType::SystemClipboardFailure as i32,
-                payload: None,
+                payload: Some(event::Payload::SystemClipboardFailurePayload(
+                    byte_length as u64,
+                )),
             }),
             Event::InputReceived => Ok(ProtobufEvent {
                 name: ProtobufEventType::InputReceived as i32,
@@ -425,6 +626,174 @@ impl TryFrom<Event> for ProtobufEvent {
                     )),
                 })
             },
+            Event::InputLatencyStats(client_input_latencies) => {
+                let input_latency_stats_payload = InputLatencyStatsPayload {
+                    client_input_latencies: client_input_latencies
+                        .into_iter()
+                        .map(|c| c.into())
+                        .collect(),
+                };
+                Ok(ProtobufEvent {
+                    name: ProtobufEventType::InputLatencyStats as i32,
+                    payload: Some(event::Payload::InputLatencyStatsPayload(
+                        input_latency_stats_payload,
+                    )),
+                })
+            },
+            Event::PaneClosed(pane_id, exit_status) => {
+                let pane_closed_payload = PaneClosedPayload {
+                    pane_id: Some(ProtobufPaneId::try_from(pane_id)?),
+                    exit_status,
+                };
+                Ok(ProtobufEvent {
+                    name: ProtobufEventType::PaneClosed as i32,
+                    payload: Some(event::Payload::PaneClosedPayload(pane_closed_payload)),
+                })
+            },
+            Event::SessionSharingStatus(session_sharing_status) => {
+                let session_sharing_status_payload = SessionSharingStatusPayload {
+                    connected_clients: session_sharing_status.connected_clients as u32,
+                    is_recording: session_sharing_status.is_recording,
+                };
+                Ok(ProtobufEvent {
+                    name: ProtobufEventType::SessionSharingStatus as i32,
+                    payload: Some(event::Payload::SessionSharingStatusPayload(
+                        session_sharing_status_payload,
+                    )),
+                })
+            },
+            Event::EnteredCopyMode => Ok(ProtobufEvent {
+                name: ProtobufEventType::EnteredCopyMode as i32,
+                payload: None,
+            }),
+            Event::ExitedCopyMode => Ok(ProtobufEvent {
+                name: ProtobufEventType::ExitedCopyMode as i32,
+                payload: None,
+            }),
+            Event::SelectionChanged(pane_id, bounds) => {
+                let selection_changed_payload = SelectionChangedPayload {
+                    pane_id: Some(ProtobufPaneId::try_from(pane_id)?),
+                    bounds: bounds.map(|b| b.into()),
+                };
+                Ok(ProtobufEvent {
+                    name: ProtobufEventType::SelectionChanged as i32,
+                    payload: Some(event::Payload::SelectionChangedPayload(
+                        selection_changed_payload,
+                    )),
+                })
+            },
+            Event::SystemOpenerFailure(target, error) => Ok(ProtobufEvent {
+                name: ProtobufEventType::SystemOpenerFailure as i32,
+                payload: Some(event::Payload::SystemOpenerFailurePayload(
+                    SystemOpenerFailurePayload { target, error },
+                )),
+            }),
+            Event::PermissionDenied(permission) => Ok(ProtobufEvent {
+                name: ProtobufEventType::PermissionDenied as i32,
+                payload: Some(event::Payload::PermissionDeniedPayload(
+                    ProtobufPermissionType::try_from(permission)? as i32,
+                )),
+            }),
+            Event::RestoreState(state) => Ok(ProtobufEvent {
+                name: ProtobufEventType::RestoreState as i32,
+                payload: Some(event::Payload::RestoreStatePayload(state)),
+            }),
+            Event::Paste(pasted_text) => Ok(ProtobufEvent {
+                name: ProtobufEventType::Paste as i32,
+                payload: Some(event::Payload::PastePayload(pasted_text)),
+            }),
+            Event::StatusSegmentsUpdate(status_segments) => {
+                let status_segments_payload = StatusSegmentsPayload {
+                    status_segments: status_segments.into_iter().map(|s| s.into()).collect(),
+                };
+                Ok(ProtobufEvent {
+                    name: ProtobufEventType::StatusSegmentsUpdate as i32,
+                    payload: Some(event::Payload::StatusSegmentsPayload(
+                        status_segments_payload,
+                    )),
+                })
+            },
+            Event::SessionActivity(session_activities) => {
+                let mut protobuf_session_activities = vec![];
+                for session_activity in session_activities {
+                    protobuf_session_activities.push(session_activity.try_into()?);
+                }
+                let session_activity_payload = SessionActivityPayload {
+                    session_activities: protobuf_session_activities,
+                };
+                Ok(ProtobufEvent {
+                    name: ProtobufEventType::SessionActivity as i32,
+                    payload: Some(event::Payload::SessionActivityPayload(
+                        session_activity_payload,
+                    )),
+                })
+            },
+            Event::PaneOutput(pane_id, output) => {
+                let pane_output_payload = PaneOutputPayload {
+                    pane_id: Some(ProtobufPaneId::try_from(pane_id)?),
+                    output,
+                };
+                Ok(ProtobufEvent {
+                    name: ProtobufEventType::PaneOutput as i32,
+                    payload: Some(event::Payload::PaneOutputPayload(pane_output_payload)),
+                })
+            },
+            Event::ModeChanged(input_mode) => Ok(ProtobufEvent {
+                name: ProtobufEventType::ModeChanged as i32,
+                payload: Some(event::Payload::ModeChangedPayload(
+                    ProtobufInputMode::try_from(input_mode)? as i32,
+                )),
+            }),
+            Event::HyperlinkClicked(pane_id, uri) => {
+                let hyperlink_clicked_payload = HyperlinkClickedPayload {
+                    pane_id: Some(ProtobufPaneId::try_from(pane_id)?),
+                    uri,
+                };
+                Ok(ProtobufEvent {
+                    name: ProtobufEventType::HyperlinkClicked as i32,
+                    payload: Some(event::Payload::HyperlinkClickedPayload(
+                        hyperlink_clicked_payload,
+                    )),
+                })
+            },
+            Event::PluginMetrics(plugin_metrics) => {
+                let plugin_metrics_payload = PluginMetricsPayload {
+                    plugin_metrics: plugin_metrics.into_iter().map(|m| m.into()).collect(),
+                };
+                Ok(ProtobufEvent {
+                    name: ProtobufEventType::PluginMetrics as i32,
+                    payload: Some(event::Payload::PluginMetricsPayload(plugin_metrics_payload)),
+                })
+            },
+            Event::Bell(pane_id) => Ok(ProtobufEvent {
+                name: ProtobufEventType::Bell as i32,
+                payload: Some(event::Payload::BellPayload(BellPayload {
+                    pane_id: Some(ProtobufPaneId::try_from(pane_id)?),
+                })),
+            }),
+            Event::WorkspaceChanged(workspace_name) => Ok(ProtobufEvent {
+                name: ProtobufEventType::WorkspaceChanged as i32,
+                payload: Some(event::Payload::WorkspaceChangedPayload(workspace_name)),
+            }),
+            Event::ClipboardCopySucceeded(byte_length) => Ok(ProtobufEvent {
+                name: ProtobufEventType::ClipboardCopySucceeded as i32,
+                payload: Some(event::Payload::ClipboardCopySucceededPayload(
+                    byte_length as u64,
+                )),
+            }),
+            Event::PaneTitleChanged(pane_id, title) => Ok(ProtobufEvent {
+                name: ProtobufEventType::PaneTitleChanged as i32,
+                payload: Some(event::Payload::PaneTitleChangedPayload(
+                    PaneTitleChangedPayload {
+                        pane_id: Some(ProtobufPaneId::try_from(pane_id)?),
+                        title,
+                    },
+                )),
+            }),
+            Event::TerminalPaneOpened(pane_id) => Ok(ProtobufEvent {
+                name: ProtobufEventType::TerminalPaneOpened as i32,
+                payload: Some(event::Payload::TerminalPaneOpenedPayload(pane_id)),
+            }),
         }
     }
 }
@@ -453,6 +822,7 @@ impl TryFrom<SessionInfo> for ProtobufSessionManifest {
                 .collect(),
             connected_clients: session_info.connected_clients as u32,
             is_current_session: session_info.is_current_session,
+            observer_clients: session_info.observer_clients as u32,
         })
     }
 }
@@ -484,11 +854,119 @@ impl TryFrom<ProtobufSessionManifest> for SessionInfo {
                 .collect(),
             panes,
             connected_clients: protobuf_session_manifest.connected_clients as usize,
+            observer_clients: protobuf_session_manifest.observer_clients as usize,
             is_current_session: protobuf_session_manifest.is_current_session,
         })
     }
 }
 
+impl TryFrom<SessionLayout> for ProtobufSessionLayout {
+    type Error = &'static str;
+    fn try_from(session_layout: SessionLayout) -> Result<Self, &'static str> {
+        let mut protobuf_pane_manifests = vec![];
+        for (tab_index, pane_infos) in session_layout.panes.panes {
+            let mut protobuf_pane_infos = vec![];
+            for pane_info in pane_infos {
+                protobuf_pane_infos.push(pane_info.try_into()?);
+            }
+            protobuf_pane_manifests.push(ProtobufPaneManifest {
+                tab_index: tab_index as u32,
+                panes: protobuf_pane_infos,
+            });
+        }
+        let mut protobuf_tabs = vec![];
+        for tab_info in session_layout.tabs {
+            protobuf_tabs.push(tab_info.try_into()?);
+        }
+        Ok(ProtobufSessionLayout {
+            tabs: protobuf_tabs,
+            panes: protobuf_pane_manifests,
+        })
+    }
+}
+
+impl TryFrom<ProtobufSessionLayout> for SessionLayout {
+    type Error = &'static str;
+    fn try_from(protobuf_session_layout: ProtobufSessionLayout) -> Result<Self, &'static str> {
+        let mut pane_manifest: HashMap<usize, Vec<PaneInfo>> = HashMap::new();
+        for protobuf_pane_manifest in protobuf_session_layout.panes {
+            let tab_index = protobuf_pane_manifest.tab_index as usize;
+            let mut panes = vec![];
+            for protobuf_pane_info in protobuf_pane_manifest.panes {
+                panes.push(protobuf_pane_info.try_into()?);
+            }
+            if pane_manifest.contains_key(&tab_index) {
+                return Err("Duplicate tab definition in pane manifest");
+            }
+            pane_manifest.insert(tab_index, panes);
+        }
+        let mut tabs = vec![];
+        for protobuf_tab_info in protobuf_session_layout.tabs {
+            tabs.push(protobuf_tab_info.try_into()?);
+        }
+        Ok(SessionLayout {
+            tabs,
+            panes: PaneManifest {
+                panes: pane_manifest,
+            },
+        })
+    }
+}
+
+impl TryFrom<Keybindings> for ProtobufKeybindings {
+    type Error = &'static str;
+    fn try_from(keybindings: Keybindings) -> Result<Self, &'static str> {
+        let mut modes = vec![];
+        for (input_mode, keybinds) in keybindings.modes {
+            let mode: ProtobufInputMode = input_mode.try_into()?;
+            let mut key_bind = vec![];
+            for (key, actions) in keybinds {
+                key_bind.push(ProtobufKeyBind {
+                    key: Some(key.try_into()?),
+                    // actions that can't be serialized (eg. plugin-only ones) are left out
+                    action: actions
+                        .into_iter()
+                        .filter_map(|action| action.try_into().ok())
+                        .collect(),
+                });
+            }
+            modes.push(ProtobufInputModeKeybinds {
+                mode: mode as i32,
+                key_bind,
+            });
+        }
+        Ok(ProtobufKeybindings { modes })
+    }
+}
+
+impl TryFrom<ProtobufKeybindings> for Keybindings {
+    type Error = &'static str;
+    fn try_from(protobuf_keybindings: ProtobufKeybindings) -> Result<Self, &'static str> {
+        let mut modes = vec![];
+        for protobuf_input_mode_keybinds in protobuf_keybindings.modes {
+            let input_mode: InputMode =
+                ProtobufInputMode::from_i32(protobuf_input_mode_keybinds.mode)
+                    .ok_or("Malformed InputMode in Keybindings")?
+                    .try_into()?;
+            let mut keybinds = vec![];
+            for protobuf_key_bind in protobuf_input_mode_keybinds.key_bind {
+                let key: Key = protobuf_key_bind
+                    .key
+                    .ok_or("Missing key in Keybindings")?
+                    .try_into()?;
+                let actions: Vec<Action> = protobuf_key_bind
+                    .action
+                    .into_iter()
+                    .filter_map(|action| action.try_into().ok())
+                    .collect();
+                keybinds.push((key, actions));
+            }
+            modes.push((input_mode, keybinds));
+        }
+        Ok(Keybindings { modes })
+    }
+}
+
 impl TryFrom<CopyDestination> for ProtobufCopyDestination {
     type Error = &'static str;
     fn try_from(copy_destination: CopyDestination) -> Result<Self, &'static str> {
@@ -701,6 +1179,8 @@ impl TryFrom<ProtobufTabInfo> for TabInfo {
                 .collect(),
             active_swap_layout_name: protobuf_tab_info.active_swap_layout_name,
             is_swap_layout_dirty: protobuf_tab_info.is_swap_layout_dirty,
+            has_bell: protobuf_tab_info.has_bell,
+            color: protobuf_tab_info.color,
         })
     }
 }
@@ -723,6 +1203,8 @@ impl TryFrom<TabInfo> for ProtobufTabInfo {
                 .collect(),
             active_swap_layout_name: tab_info.active_swap_layout_name,
             is_swap_layout_dirty: tab_info.is_swap_layout_dirty,
+            has_bell: tab_info.has_bell,
+            color: tab_info.color,
         })
     }
 }
@@ -872,6 +1354,27 @@ impl TryFrom<ProtobufEventType> for EventType {
             ProtobufEventType::SessionUpdate => EventType::SessionUpdate,
             ProtobufEventType::RunCommandResult => EventType::RunCommandResult,
             ProtobufEventType::WebRequestResult => EventType::WebRequestResult,
+            ProtobufEventType::InputLatencyStats => EventType::InputLatencyStats,
+            ProtobufEventType::PaneClosed => EventType::PaneClosed,
+            ProtobufEventType::SessionSharingStatus => EventType::SessionSharingStatus,
+            ProtobufEventType::EnteredCopyMode => EventType::EnteredCopyMode,
+            ProtobufEventType::ExitedCopyMode => EventType::ExitedCopyMode,
+            ProtobufEventType::SelectionChanged => EventType::SelectionChanged,
+            ProtobufEventType::SystemOpenerFailure => EventType::SystemOpenerFailure,
+            ProtobufEventType::PermissionDenied => EventType::PermissionDenied,
+            ProtobufEventType::RestoreState => EventType::RestoreState,
+            ProtobufEventType::Paste => EventType::Paste,
+            ProtobufEventType::StatusSegmentsUpdate => EventType::StatusSegmentsUpdate,
+            ProtobufEventType::SessionActivity => EventType::SessionActivity,
+            ProtobufEventType::PaneOutput => EventType::PaneOutput,
+            ProtobufEventType::ModeChanged => EventType::ModeChanged,
+            ProtobufEventType::HyperlinkClicked => EventType::HyperlinkClicked,
+            ProtobufEventType::PluginMetrics => EventType::PluginMetrics,
+            ProtobufEventType::Bell => EventType::Bell,
+            ProtobufEventType::WorkspaceChanged => EventType::WorkspaceChanged,
+            ProtobufEventType::ClipboardCopySucceeded => EventType::ClipboardCopySucceeded,
+            ProtobufEventType::PaneTitleChanged => EventType::PaneTitleChanged,
+            ProtobufEventType::TerminalPaneOpened => EventType::TerminalPaneOpened,
         })
     }
 }
@@ -899,10 +1402,195 @@ impl TryFrom<EventType> for ProtobufEventType {
             EventType::SessionUpdate => ProtobufEventType::SessionUpdate,
             EventType::RunCommandResult => ProtobufEventType::RunCommandResult,
             EventType::WebRequestResult => ProtobufEventType::WebRequestResult,
+            EventType::InputLatencyStats => ProtobufEventType::InputLatencyStats,
+            EventType::PaneClosed => ProtobufEventType::PaneClosed,
+            EventType::SessionSharingStatus => ProtobufEventType::SessionSharingStatus,
+            EventType::EnteredCopyMode => ProtobufEventType::EnteredCopyMode,
+            EventType::ExitedCopyMode => ProtobufEventType::ExitedCopyMode,
+            EventType::SelectionChanged => ProtobufEventType::SelectionChanged,
+            EventType::SystemOpenerFailure => ProtobufEventType::SystemOpenerFailure,
+            EventType::PermissionDenied => ProtobufEventType::PermissionDenied,
+            EventType::RestoreState => ProtobufEventType::RestoreState,
+            EventType::Paste => ProtobufEventType::Paste,
+            EventType::StatusSegmentsUpdate => ProtobufEventType::StatusSegmentsUpdate,
+            EventType::SessionActivity => ProtobufEventType::SessionActivity,
+            EventType::PaneOutput => ProtobufEventType::PaneOutput,
+            EventType::ModeChanged => ProtobufEventType::ModeChanged,
+            EventType::HyperlinkClicked => ProtobufEventType::HyperlinkClicked,
+            EventType::PluginMetrics => ProtobufEventType::PluginMetrics,
+            EventType::Bell => ProtobufEventType::Bell,
+            EventType::WorkspaceChanged => ProtobufEventType::WorkspaceChanged,
+            EventType::ClipboardCopySucceeded => ProtobufEventType::ClipboardCopySucceeded,
+            EventType::PaneTitleChanged => ProtobufEventType::PaneTitleChanged,
+            EventType::TerminalPaneOpened => ProtobufEventType::TerminalPaneOpened,
+        })
+    }
+}
+
+impl From<ProtobufClientInputLatency> for ClientInputLatency {
+    fn from(protobuf_client_input_latency: ProtobufClientInputLatency) -> ClientInputLatency {
+        ClientInputLatency {
+            client_id: protobuf_client_input_latency.client_id as u16,
+            sample_count: protobuf_client_input_latency.sample_count as usize,
+            last: Duration::from_micros(protobuf_client_input_latency.last_micros),
+            average: Duration::from_micros(protobuf_client_input_latency.average_micros),
+            min: Duration::from_micros(protobuf_client_input_latency.min_micros),
+            max: Duration::from_micros(protobuf_client_input_latency.max_micros),
+        }
+    }
+}
+
+impl From<ClientInputLatency> for ProtobufClientInputLatency {
+    fn from(client_input_latency: ClientInputLatency) -> ProtobufClientInputLatency {
+        ProtobufClientInputLatency {
+            client_id: client_input_latency.client_id as u32,
+            sample_count: client_input_latency.sample_count as u64,
+            last_micros: client_input_latency.last.as_micros() as u64,
+            average_micros: client_input_latency.average.as_micros() as u64,
+            min_micros: client_input_latency.min.as_micros() as u64,
+            max_micros: client_input_latency.max.as_micros() as u64,
+        }
+    }
+}
+
+impl From<ProtobufStatusSegment> for StatusSegment {
+    fn from(protobuf_status_segment: ProtobufStatusSegment) -> StatusSegment {
+        StatusSegment {
+            plugin_id: protobuf_status_segment.plugin_id,
+            render_callback_id: protobuf_status_segment.render_callback_id,
+            priority: protobuf_status_segment.priority,
+            content: protobuf_status_segment.content,
+        }
+    }
+}
+
+impl From<StatusSegment> for ProtobufStatusSegment {
+    fn from(status_segment: StatusSegment) -> ProtobufStatusSegment {
+        ProtobufStatusSegment {
+            plugin_id: status_segment.plugin_id,
+            render_callback_id: status_segment.render_callback_id,
+            priority: status_segment.priority,
+            content: status_segment.content,
+        }
+    }
+}
+
+impl From<ProtobufPluginMetric> for PluginMetric {
+    fn from(protobuf_plugin_metric: ProtobufPluginMetric) -> PluginMetric {
+        PluginMetric {
+            plugin_id: protobuf_plugin_metric.plugin_id,
+            memory_bytes: protobuf_plugin_metric.memory_bytes,
+            last_render_micros: protobuf_plugin_metric.last_render_micros,
+        }
+    }
+}
+
+impl From<PluginMetric> for ProtobufPluginMetric {
+    fn from(plugin_metric: PluginMetric) -> ProtobufPluginMetric {
+        ProtobufPluginMetric {
+            plugin_id: plugin_metric.plugin_id,
+            memory_bytes: plugin_metric.memory_bytes,
+            last_render_micros: plugin_metric.last_render_micros,
+        }
+    }
+}
+
+impl TryFrom<ProtobufSessionActivity> for SessionActivity {
+    type Error = &'static str;
+    fn try_from(protobuf_session_activity: ProtobufSessionActivity) -> Result<Self, &'static str> {
+        let pane_id = || -> Result<PaneId, &'static str> {
+            let pane_id = protobuf_session_activity
+                .pane_id
+                .clone()
+                .ok_or("No pane id in SessionActivity")?;
+            PaneId::try_from(pane_id)
+        };
+        let kind = match ProtobufSessionActivityKind::from_i32(protobuf_session_activity.kind) {
+            Some(ProtobufSessionActivityKind::PaneOpened) => {
+                SessionActivityKind::PaneOpened(pane_id()?)
+            },
+            Some(ProtobufSessionActivityKind::PaneClosed) => {
+                SessionActivityKind::PaneClosed(pane_id()?)
+            },
+            Some(ProtobufSessionActivityKind::TabChanged) => SessionActivityKind::TabChanged(
+                protobuf_session_activity
+                    .client_id
+                    .ok_or("No client id in SessionActivity")? as u16,
+                protobuf_session_activity
+                    .tab_position
+                    .ok_or("No tab position in SessionActivity")? as usize,
+            ),
+            Some(ProtobufSessionActivityKind::CommandSpawned) => {
+                SessionActivityKind::CommandSpawned(
+                    pane_id()?,
+                    protobuf_session_activity
+                        .command
+                        .clone()
+                        .ok_or("No command in SessionActivity")?,
+                )
+            },
+            None => return Err("Unknown SessionActivity kind"),
+        };
+        Ok(SessionActivity {
+            timestamp: Duration::from_micros(protobuf_session_activity.timestamp_micros),
+            kind,
         })
     }
 }
 
+impl TryFrom<SessionActivity> for ProtobufSessionActivity {
+    type Error = &'static str;
+    fn try_from(session_activity: SessionActivity) -> Result<Self, &'static str> {
+        let mut protobuf_session_activity = ProtobufSessionActivity {
+            timestamp_micros: session_activity.timestamp.as_micros() as u64,
+            ..Default::default()
+        };
+        match session_activity.kind {
+            SessionActivityKind::PaneOpened(pane_id) => {
+                protobuf_session_activity.kind = ProtobufSessionActivityKind::PaneOpened as i32;
+                protobuf_session_activity.pane_id = Some(pane_id.try_into()?);
+            },
+            SessionActivityKind::PaneClosed(pane_id) => {
+                protobuf_session_activity.kind = ProtobufSessionActivityKind::PaneClosed as i32;
+                protobuf_session_activity.pane_id = Some(pane_id.try_into()?);
+            },
+            SessionActivityKind::TabChanged(client_id, tab_position) => {
+                protobuf_session_activity.kind = ProtobufSessionActivityKind::TabChanged as i32;
+                protobuf_session_activity.client_id = Some(client_id as u32);
+                protobuf_session_activity.tab_position = Some(tab_position as u32);
+            },
+            SessionActivityKind::CommandSpawned(pane_id, command) => {
+                protobuf_session_activity.kind = ProtobufSessionActivityKind::CommandSpawned as i32;
+                protobuf_session_activity.pane_id = Some(pane_id.try_into()?);
+                protobuf_session_activity.command = Some(command);
+            },
+        }
+        Ok(protobuf_session_activity)
+    }
+}
+
+impl From<ProtobufSelectionBounds> for SelectionBounds {
+    fn from(protobuf_selection_bounds: ProtobufSelectionBounds) -> SelectionBounds {
+        SelectionBounds {
+            start_line: protobuf_selection_bounds.start_line as isize,
+            start_column: protobuf_selection_bounds.start_column as usize,
+            end_line: protobuf_selection_bounds.end_line as isize,
+            end_column: protobuf_selection_bounds.end_column as usize,
+        }
+    }
+}
+
+impl From<SelectionBounds> for ProtobufSelectionBounds {
+    fn from(selection_bounds: SelectionBounds) -> ProtobufSelectionBounds {
+        ProtobufSelectionBounds {
+            start_line: selection_bounds.start_line as i64,
+            start_column: selection_bounds.start_column as u32,
+            end_line: selection_bounds.end_line as i64,
+            end_column: selection_bounds.end_column as u32,
+        }
+    }
+}
+
 impl From<ProtobufResurrectableSession> for (String, Duration) {
     fn from(protobuf_resurrectable_session: ProtobufResurrectableSession) -> (String, Duration) {
         (
@@ -1040,6 +1728,8 @@ fn serialize_tab_update_event_with_non_default_values() {
             other_focused_clients: vec![2, 3, 4],
             active_swap_layout_name: Some("my cool swap layout".to_owned()),
             is_swap_layout_dirty: false,
+            has_bell: false,
+            color: Some("gold".to_owned()),
         },
         TabInfo {
             position: 1,
@@ -1052,6 +1742,8 @@ fn serialize_tab_update_event_with_non_default_values() {
             other_focused_clients: vec![1, 5, 111],
             active_swap_layout_name: None,
             is_swap_layout_dirty: true,
+            has_bell: true,
+            color: None,
         },
         TabInfo::default(),
     ]);
@@ -1159,7 +1851,7 @@ fn serialize_copy_to_clipboard_event() {
 #[test]
 fn serialize_clipboard_failure_event() {
     use prost::Message;
-    let copy_event = Event::SystemClipboardFailure;
+    let copy_event = Event::SystemClipboardFailure(23);
     let protobuf_event: ProtobufEvent = copy_event.clone().try_into().unwrap();
     let serialized_protobuf_event = protobuf_event.encode_to_vec();
     let deserialized_protobuf_event: ProtobufEvent =
@@ -1204,7 +1896,7 @@ fn serialize_visible_event() {
 #[test]
 fn serialize_custom_message_event() {
     use prost::Message;
-    let custom_message_event = Event::CustomMessage("foo".to_owned(), "bar".to_owned());
+    let custom_message_event = Event::CustomMessage("foo".to_owned(), b"bar".to_vec());
     let protobuf_event: ProtobufEvent = custom_message_event.clone().try_into().unwrap();
     let serialized_protobuf_event = protobuf_event.encode_to_vec();
     let deserialized_protobuf_event: ProtobufEvent =
@@ -1216,6 +1908,77 @@ fn serialize_custom_message_event() {
     );
 }
 
+#[test]
+fn binary_message_payload_survives_worker_round_trip() {
+    use crate::data::{PluginCommand, PluginMessage};
+    use crate::plugin_api::message::ProtobufMessage;
+    use crate::plugin_api::plugin_command::ProtobufPluginCommand;
+    use prost::Message;
+    // protobuf bytes cross the wasm boundary serialized as json (see wasi_read_bytes)
+    fn across_wasm_boundary(protobuf_bytes: Vec<u8>) -> Vec<u8> {
+        let serialized = serde_json::to_string(&protobuf_bytes).unwrap();
+        serde_json::from_str(&serialized).unwrap()
+    }
+    let payload = vec![0xff, 0xfe, 0x00, 0xc3, 0x28, b'\n', 0x80];
+    assert!(
+        String::from_utf8(payload.clone()).is_err(),
+        "payload is not valid UTF-8"
+    );
+
+    // plugin -> server
+    let post_message_to =
+        PluginCommand::PostMessageTo(PluginMessage::new_to_worker("test", "ping", &payload));
+    let protobuf_plugin_command: ProtobufPluginCommand = post_message_to.try_into().unwrap();
+    let protobuf_bytes = across_wasm_boundary(protobuf_plugin_command.encode_to_vec());
+    let protobuf_plugin_command: ProtobufPluginCommand =
+        Message::decode(protobuf_bytes.as_slice()).unwrap();
+    let message_to_worker = match protobuf_plugin_command.try_into().unwrap() {
+        PluginCommand::PostMessageTo(plugin_message) => plugin_message,
+        _ => panic!("expected a PostMessageTo command"),
+    };
+
+    // server -> worker
+    let protobuf_message = ProtobufMessage {
+        name: message_to_worker.name,
+        payload: message_to_worker.payload,
+        ..Default::default()
+    };
+    let protobuf_bytes = across_wasm_boundary(protobuf_message.encode_to_vec());
+    let protobuf_message: ProtobufMessage = Message::decode(protobuf_bytes.as_slice()).unwrap();
+    let received_by_worker: PluginMessage = protobuf_message.try_into().unwrap();
+    assert_eq!(
+        received_by_worker.payload, payload,
+        "worker received the exact bytes"
+    );
+
+    // worker -> server
+    let post_message_to_plugin = PluginCommand::PostMessageToPlugin(PluginMessage::new_to_plugin(
+        "pong",
+        &received_by_worker.payload,
+    ));
+    let protobuf_plugin_command: ProtobufPluginCommand = post_message_to_plugin.try_into().unwrap();
+    let protobuf_bytes = across_wasm_boundary(protobuf_plugin_command.encode_to_vec());
+    let protobuf_plugin_command: ProtobufPluginCommand =
+        Message::decode(protobuf_bytes.as_slice()).unwrap();
+    let message_to_plugin = match protobuf_plugin_command.try_into().unwrap() {
+        PluginCommand::PostMessageToPlugin(plugin_message) => plugin_message,
+        _ => panic!("expected a PostMessageToPlugin command"),
+    };
+
+    // server -> plugin
+    let custom_message_event =
+        Event::CustomMessage(message_to_plugin.name, message_to_plugin.payload);
+    let protobuf_event: ProtobufEvent = custom_message_event.try_into().unwrap();
+    let protobuf_bytes = across_wasm_boundary(protobuf_event.encode_to_vec());
+    let protobuf_event: ProtobufEvent = Message::decode(protobuf_bytes.as_slice()).unwrap();
+    let received_by_plugin: Event = protobuf_event.try_into().unwrap();
+    assert_eq!(
+        received_by_plugin,
+        Event::CustomMessage("pong".to_owned(), payload),
+        "payload made it back to the plugin byte for byte"
+    );
+}
+
 #[test]
 fn serialize_file_system_create_event() {
     use prost::Message;
@@ -1280,6 +2043,86 @@ fn serialize_file_system_delete_event() {
     );
 }
 
+#[test]
+fn serialize_session_layout() {
+    use prost::Message;
+    let mut panes = HashMap::new();
+    panes.insert(
+        1,
+        vec![PaneInfo {
+            id: 2,
+            is_focused: true,
+            title: "pane title".to_owned(),
+            ..Default::default()
+        }],
+    );
+    let session_layout = SessionLayout {
+        tabs: vec![
+            TabInfo {
+                position: 0,
+                name: "first tab".to_owned(),
+                ..Default::default()
+            },
+            TabInfo {
+                position: 1,
+                name: "second tab".to_owned(),
+                active: true,
+                ..Default::default()
+            },
+        ],
+        panes: PaneManifest { panes },
+    };
+    let protobuf_session_layout: ProtobufSessionLayout = session_layout.clone().try_into().unwrap();
+    let serialized_protobuf_session_layout = protobuf_session_layout.encode_to_vec();
+    let deserialized_protobuf_session_layout: ProtobufSessionLayout =
+        Message::decode(serialized_protobuf_session_layout.as_slice()).unwrap();
+    let deserialized_session_layout: SessionLayout =
+        deserialized_protobuf_session_layout.try_into().unwrap();
+    assert_eq!(
+        session_layout, deserialized_session_layout,
+        "SessionLayout properly serialized/deserialized without change"
+    );
+}
+
+#[test]
+fn serialize_keybindings() {
+    use prost::Message;
+    let keybindings = Keybindings {
+        modes: vec![
+            (
+                InputMode::Normal,
+                vec![
+                    (
+                        Key::Ctrl('g'),
+                        vec![Action::SwitchToMode(InputMode::Locked)],
+                    ),
+                    (
+                        Key::Alt(crate::data::CharOrArrow::Char('n')),
+                        vec![Action::Write(vec![10])],
+                    ),
+                ],
+            ),
+            (
+                InputMode::Locked,
+                vec![(
+                    Key::Ctrl('g'),
+                    vec![Action::SwitchToMode(InputMode::Normal)],
+                )],
+            ),
+        ],
+    };
+    let protobuf_keybindings: ProtobufKeybindings = keybindings.clone().try_into().unwrap();
+    let serialized_protobuf_keybindings = protobuf_keybindings.encode_to_vec();
+    let deserialized_protobuf_keybindings: ProtobufKeybindings =
+        Message::decode(serialized_protobuf_keybindings.as_slice()).unwrap();
+    let deserialized_keybindings: Keybindings =
+        deserialized_protobuf_keybindings.try_into().unwrap();
+    assert_eq!(
+        keybindings, deserialized_keybindings,
+        "Keybindings properly serialized/deserialized without change"
+    );
+}
+
 #[test]
 fn serialize_session_update_event() {
     use prost::Message;
@@ -1310,6 +2153,8 @@ fn serialize_session_update_event_with_non_default_values() {
             other_focused_clients: vec![2, 3, 4],
             active_swap_layout_name: Some("my cool swap layout".to_owned()),
             is_swap_layout_dirty: false,
+            has_bell: false,
+            color: Some("gold".to_owned()),
         },
         TabInfo {
             position: 1,
@@ -1322,6 +2167,8 @@ fn serialize_session_update_event_with_non_default_values() {
             other_focused_clients: vec![1, 5, 111],
             active_swap_layout_name: None,
             is_swap_layout_dirty: true,
+            has_bell: true,
+            color: None,
         },
         TabInfo::default(),
     ];
@@ -1382,6 +2229,7 @@ fn serialize_session_update_event_with_non_default_values() {
         tabs: tab_infos,
         panes: PaneManifest { panes },
         connected_clients: 2,
+        observer_clients: 1,
         is_current_session: true,
     };
     let session_info_2 = SessionInfo {
@@ -1391,6 +2239,7 @@ fn serialize_session_update_event_with_non_default_values() {
             panes: HashMap::new(),
         },
         connected_clients: 0,
+        observer_clients: 0,
         is_current_session: false,
     };
     let session_infos = vec![session_info_1, session_info_2];
@@ -1407,3 +2256,374 @@ fn serialize_session_update_event_with_non_default_values() {
         "Event properly serialized/deserialized without change"
     );
 }
+
+#[test]
+fn serialize_input_latency_stats_event() {
+    use prost::Message;
+    let input_latency_stats_event = Event::InputLatencyStats(vec![ClientInputLatency {
+        client_id: 1,
+        sample_count: 3,
+        last: Duration::from_micros(1200),
+        average: Duration::from_micros(1500),
+        min: Duration::from_micros(900),
+        max: Duration::from_micros(2400),
+    }]);
+    let protobuf_event: ProtobufEvent = input_latency_stats_event.clone().try_into().unwrap();
+    let serialized_protobuf_event = protobuf_event.encode_to_vec();
+    let deserialized_protobuf_event: ProtobufEvent =
+        Message::decode(serialized_protobuf_event.as_slice()).unwrap();
+    let deserialized_event: Event = deserialized_protobuf_event.try_into().unwrap();
+    assert_eq!(
+        input_latency_stats_event, deserialized_event,
+        "Event properly serialized/deserialized without change"
+    );
+}
+
+#[test]
+fn serialize_pane_closed_event() {
+    use prost::Message;
+    let pane_closed_event = Event::PaneClosed(PaneId::Terminal(1), Some(1));
+    let protobuf_event: ProtobufEvent = pane_closed_event.clone().try_into().unwrap();
+    let serialized_protobuf_event = protobuf_event.encode_to_vec();
+    let deserialized_protobuf_event: ProtobufEvent =
+        Message::decode(serialized_protobuf_event.as_slice()).unwrap();
+    let deserialized_event: Event = deserialized_protobuf_event.try_into().unwrap();
+    assert_eq!(
+        pane_closed_event, deserialized_event,
+        "Event properly serialized/deserialized without change"
+    );
+}
+
+#[test]
+fn serialize_session_sharing_status_event() {
+    use prost::Message;
+    let session_sharing_status_event = Event::SessionSharingStatus(SessionSharingStatus {
+        connected_clients: 2,
+        is_recording: true,
+    });
+    let protobuf_event: ProtobufEvent = session_sharing_status_event.clone().try_into().unwrap();
+    let serialized_protobuf_event = protobuf_event.encode_to_vec();
+    let deserialized_protobuf_event: ProtobufEvent =
+        Message::decode(serialized_protobuf_event.as_slice()).unwrap();
+    let deserialized_event: Event = deserialized_protobuf_event.try_into().unwrap();
+    assert_eq!(
+        session_sharing_status_event, deserialized_event,
+        "Event properly serialized/deserialized without change"
+    );
+}
+
+#[test]
+fn serialize_copy_mode_events() {
+    use prost::Message;
+    let copy_mode_events = vec![
+        Event::EnteredCopyMode,
+        Event::SelectionChanged(
+            PaneId::Terminal(1),
+            Some(SelectionBounds {
+                start_line: -2,
+                start_column: 4,
+                end_line: 3,
+                end_column: 10,
+            }),
+        ),
+        Event::SelectionChanged(PaneId::Terminal(1), None),
+        Event::ExitedCopyMode,
+    ];
+    for copy_mode_event in copy_mode_events {
+        let protobuf_event: ProtobufEvent = copy_mode_event.clone().try_into().unwrap();
+        let serialized_protobuf_event = protobuf_event.encode_to_vec();
+        let deserialized_protobuf_event: ProtobufEvent =
+            Message::decode(serialized_protobuf_event.as_slice()).unwrap();
+        let deserialized_event: Event = deserialized_protobuf_event.try_into().unwrap();
+        assert_eq!(
+            copy_mode_event, deserialized_event,
+            "Event properly serialized/deserialized without change"
+        );
+    }
+}
+
+#[test]
+fn serialize_system_opener_failure_event() {
+    use prost::Message;
+    let system_opener_failure_event = Event::SystemOpenerFailure(
+        "https://zellij.dev".to_owned(),
+        "No such file or directory".to_owned(),
+    );
+    let protobuf_event: ProtobufEvent = system_opener_failure_event.clone().try_into().unwrap();
+    let serialized_protobuf_event = protobuf_event.encode_to_vec();
+    let deserialized_protobuf_event: ProtobufEvent =
+        Message::decode(serialized_protobuf_event.as_slice()).unwrap();
+    let deserialized_event: Event = deserialized_protobuf_event.try_into().unwrap();
+    assert_eq!(
+        system_opener_failure_event, deserialized_event,
+        "Event properly serialized/deserialized without change"
+    );
+}
+
+#[test]
+fn serialize_permission_denied_event() {
+    use prost::Message;
+    let permission_denied_event = Event::PermissionDenied(PermissionType::WriteToClipboard);
+    let protobuf_event: ProtobufEvent = permission_denied_event.clone().try_into().unwrap();
+    let serialized_protobuf_event = protobuf_event.encode_to_vec();
+    let deserialized_protobuf_event: ProtobufEvent =
+        Message::decode(serialized_protobuf_event.as_slice()).unwrap();
+    let deserialized_event: Event = deserialized_protobuf_event.try_into().unwrap();
+    assert_eq!(
+        permission_denied_event, deserialized_event,
+        "Event properly serialized/deserialized without change"
+    );
+}
+
+#[test]
+fn serialize_restore_state_event() {
+    use prost::Message;
+    let restore_state_event = Event::RestoreState(vec![1, 2, 3]);
+    let protobuf_event: ProtobufEvent = restore_state_event.clone().try_into().unwrap();
+    let serialized_protobuf_event = protobuf_event.encode_to_vec();
+    let deserialized_protobuf_event: ProtobufEvent =
+        Message::decode(serialized_protobuf_event.as_slice()).unwrap();
+    let deserialized_event: Event = deserialized_protobuf_event.try_into().unwrap();
+    assert_eq!(
+        restore_state_event, deserialized_event,
+        "Event properly serialized/deserialized without change"
+    );
+}
+
+#[test]
+fn serialize_paste_event() {
+    use prost::Message;
+    let paste_event = Event::Paste("pasted\ntext".to_owned());
+    let protobuf_event: ProtobufEvent = paste_event.clone().try_into().unwrap();
+    let serialized_protobuf_event = protobuf_event.encode_to_vec();
+    let deserialized_protobuf_event: ProtobufEvent =
+        Message::decode(serialized_protobuf_event.as_slice()).unwrap();
+    let deserialized_event: Event = deserialized_protobuf_event.try_into().unwrap();
+    assert_eq!(
+        paste_event, deserialized_event,
+        "Event properly serialized/deserialized without change"
+    );
+}
+
+#[test]
+fn serialize_status_segments_update_event() {
+    use prost::Message;
+    let status_segments_update_event = Event::StatusSegmentsUpdate(vec![
+        StatusSegment {
+            plugin_id: 1,
+            render_callback_id: 0,
+            priority: 10,
+            content: "CPU 12%".to_owned(),
+        },
+        StatusSegment {
+            plugin_id: 2,
+            render_callback_id: 3,
+            priority: 1,
+            content: "main".to_owned(),
+        },
+    ]);
+    let protobuf_event: ProtobufEvent = status_segments_update_event.clone().try_into().unwrap();
+    let serialized_protobuf_event = protobuf_event.encode_to_vec();
+    let deserialized_protobuf_event: ProtobufEvent =
+        Message::decode(serialized_protobuf_event.as_slice()).unwrap();
+    let deserialized_event: Event = deserialized_protobuf_event.try_into().unwrap();
+    assert_eq!(
+        status_segments_update_event, deserialized_event,
+        "Event properly serialized/deserialized without change"
+    );
+}
+
+#[test]
+fn serialize_session_activity_event() {
+    use prost::Message;
+    let timestamp = Duration::from_micros(1_700_000_000_000_000);
+    let session_activity_event = Event::SessionActivity(vec![
+        SessionActivity {
+            timestamp,
+            kind: SessionActivityKind::PaneOpened(PaneId::Terminal(1)),
+        },
+        SessionActivity {
+            timestamp,
+            kind: SessionActivityKind::CommandSpawned(PaneId::Terminal(1), "htop".to_owned()),
+        },
+        SessionActivity {
+            timestamp,
+            kind: SessionActivityKind::TabChanged(2, 1),
+        },
+        SessionActivity {
+            timestamp,
+            kind: SessionActivityKind::PaneClosed(PaneId::Plugin(3)),
+        },
+    ]);
+    let protobuf_event: ProtobufEvent = session_activity_event.clone().try_into().unwrap();
+    let serialized_protobuf_event = protobuf_event.encode_to_vec();
+    let deserialized_protobuf_event: ProtobufEvent =
+        Message::decode(serialized_protobuf_event.as_slice()).unwrap();
+    let deserialized_event: Event = deserialized_protobuf_event.try_into().unwrap();
+    assert_eq!(
+        session_activity_event, deserialized_event,
+        "Event properly serialized/deserialized without change"
+    );
+}
+
+#[test]
+fn serialize_pane_output_event() {
+    use prost::Message;
+    let output = b"\x1b[32mok\x1b[m\r\n".to_vec();
+    let pane_output_event = Event::PaneOutput(PaneId::Terminal(1), output);
+    let protobuf_event: ProtobufEvent = pane_output_event.clone().try_into().unwrap();
+    let serialized_protobuf_event = protobuf_event.encode_to_vec();
+    let deserialized_protobuf_event: ProtobufEvent =
+        Message::decode(serialized_protobuf_event.as_slice()).unwrap();
+    let deserialized_event: Event = deserialized_protobuf_event.try_into().unwrap();
+    assert_eq!(
+        pane_output_event, deserialized_event,
+        "Event properly serialized/deserialized without change"
+    );
+}
+
+#[test]
+fn serialize_mode_changed_event() {
+    use prost::Message;
+    let mode_changed_event = Event::ModeChanged(InputMode::Locked);
+    let protobuf_event: ProtobufEvent = mode_changed_event.clone().try_into().unwrap();
+    let serialized_protobuf_event = protobuf_event.encode_to_vec();
+    let deserialized_protobuf_event: ProtobufEvent =
+        Message::decode(serialized_protobuf_event.as_slice()).unwrap();
+    let deserialized_event: Event = deserialized_protobuf_event.try_into().unwrap();
+    assert_eq!(
+        mode_changed_event, deserialized_event,
+        "Event properly serialized/deserialized without change"
+    );
+}
+
+#[test]
+fn serialize_hyperlink_clicked_event() {
+    use prost::Message;
+    let hyperlink_clicked_event =
+        Event::HyperlinkClicked(PaneId::Terminal(1), "https://zellij.dev".to_owned());
+    let protobuf_event: ProtobufEvent = hyperlink_clicked_event.clone().try_into().unwrap();
+    let serialized_protobuf_event = protobuf_event.encode_to_vec();
+    let deserialized_protobuf_event: ProtobufEvent =
+        Message::decode(serialized_protobuf_event.as_slice()).unwrap();
+    let deserialized_event: Event = deserialized_protobuf_event.try_into().unwrap();
+    assert_eq!(
+        hyperlink_clicked_event, deserialized_event,
+        "Event properly serialized/deserialized without change"
+    );
+}
+
+#[test]
+fn serialize_plugin_metrics_event() {
+    use prost::Message;
+    let plugin_metrics_event = Event::PluginMetrics(vec![
+        PluginMetric {
+            plugin_id: 0,
+            memory_bytes: 1_114_112,
+            last_render_micros: 250,
+        },
+        PluginMetric {
+            plugin_id: 3,
+            memory_bytes: 2_228_224,
+            last_render_micros: 0,
+        },
+    ]);
+    let protobuf_event: ProtobufEvent = plugin_metrics_event.clone().try_into().unwrap();
+    let serialized_protobuf_event = protobuf_event.encode_to_vec();
+    let deserialized_protobuf_event: ProtobufEvent =
+        Message::decode(serialized_protobuf_event.as_slice()).unwrap();
+    let deserialized_event: Event = deserialized_protobuf_event.try_into().unwrap();
+    assert_eq!(
+        plugin_metrics_event, deserialized_event,
+        "Event properly serialized/deserialized without change"
+    );
+}
+
+#[test]
+fn serialize_bell_event() {
+    use prost::Message;
+    let bell_event = Event::Bell(PaneId::Terminal(1));
+    let protobuf_event: ProtobufEvent = bell_event.clone().try_into().unwrap();
+    let serialized_protobuf_event = protobuf_event.encode_to_vec();
+    let deserialized_protobuf_event: ProtobufEvent =
+        Message::decode(serialized_protobuf_event.as_slice()).unwrap();
+    let deserialized_event: Event = deserialized_protobuf_event.try_into().unwrap();
+    assert_eq!(
+        bell_event, deserialized_event,
+        "Event properly serialized/deserialized without change"
+    );
+}
+
+#[test]
+fn serialize_pane_title_changed_event() {
+    use prost::Message;
+    let pane_title_changed_event = Event::PaneTitleChanged(PaneId::Terminal(1), "vim".to_owned());
+    let protobuf_event: ProtobufEvent = pane_title_changed_event.clone().try_into().unwrap();
+    let serialized_protobuf_event = protobuf_event.encode_to_vec();
+    let deserialized_protobuf_event: ProtobufEvent =
+        Message::decode(serialized_protobuf_event.as_slice()).unwrap();
+    let deserialized_event: Event = deserialized_protobuf_event.try_into().unwrap();
+    assert_eq!(
+        pane_title_changed_event, deserialized_event,
+        "Event properly serialized/deserialized without change"
+    );
+}
+
+#[test]
+fn serialize_workspace_changed_event() {
+    use prost::Message;
+    let workspace_changed_event = Event::WorkspaceChanged("work".to_owned());
+    let protobuf_event: ProtobufEvent = workspace_changed_event.clone().try_into().unwrap();
+    let serialized_protobuf_event = protobuf_event.encode_to_vec();
+    let deserialized_protobuf_event: ProtobufEvent =
+        Message::decode(serialized_protobuf_event.as_slice()).unwrap();
+    let deserialized_event: Event = deserialized_protobuf_event.try_into().unwrap();
+    assert_eq!(
+        workspace_changed_event, deserialized_event,
+        "Event properly serialized/deserialized without change"
+    );
+}
+
+#[test]
+fn serialize_clipboard_copy_succeeded_event() {
+    use prost::Message;
+    let clipboard_copy_succeeded_event = Event::ClipboardCopySucceeded(23);
+    let protobuf_event: ProtobufEvent = clipboard_copy_succeeded_event.clone().try_into().unwrap();
+    let serialized_protobuf_event = protobuf_event.encode_to_vec();
+    let deserialized_protobuf_event: ProtobufEvent =
+        Message::decode(serialized_protobuf_event.as_slice()).unwrap();
+    let deserialized_event: Event = deserialized_protobuf_event.try_into().unwrap();
+    assert_eq!(
+        clipboard_copy_succeeded_event, deserialized_event,
+        "Event properly serialized/deserialized without change"
+    );
+}
+
+#[test]
+fn serialize_terminal_pane_opened_event() {
+    use prost::Message;
+    let terminal_pane_opened_event = Event::TerminalPaneOpened(3);
+    let protobuf_event: ProtobufEvent = terminal_pane_opened_event.clone().try_into().unwrap();
+    let serialized_protobuf_event = protobuf_event.encode_to_vec();
+    let deserialized_protobuf_event: ProtobufEvent =
+        Message::decode(serialized_protobuf_event.as_slice()).unwrap();
+    let deserialized_event: Event = deserialized_protobuf_event.try_into().unwrap();
+    assert_eq!(
+        terminal_pane_opened_event, deserialized_event,
+        "Event properly serialized/deserialized without change"
+    );
+}
+
+#[test]
+fn deserialize_system_clipboard_failure_event_without_payload() {
+    let protobuf_event = ProtobufEvent {
+        name: ProtobufEventType::SystemClipboardFailure as i32,
+        payload: None,
+    };
+    let deserialized_event: Event = protobuf_event.try_into().unwrap();
+    assert_eq!(
+        deserialized_event,
+        Event::SystemClipboardFailure(0),
+        "Event without the length of the text still deserialized"
+    );
+}
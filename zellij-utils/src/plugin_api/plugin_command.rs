@@ -2,20 +2,24 @@ pub use super::generated_api::api::{
     action::{PaneIdAndShouldFloat, SwitchToModePayload},
     event::{EventNameList as ProtobufEventNameList, Header},
     input_mode::InputMode as ProtobufInputMode,
+    pane_id::PaneId as ProtobufPaneId,
     plugin_command::{
         plugin_command::Payload, CommandName, ContextItem, EnvVariable, ExecCmdPayload,
-        HttpVerb as ProtobufHttpVerb, IdAndNewName, MovePayload, OpenCommandPanePayload,
-        OpenFilePayload, PluginCommand as ProtobufPluginCommand, PluginMessagePayload,
-        RequestPluginPermissionPayload, ResizeFloatingPaneByPercentPayload, ResizePayload,
-        RunCommandPayload, SetTimeoutPayload, SubscribePayload, SwitchSessionPayload,
-        SwitchTabToPayload, UnsubscribePayload, WebRequestPayload,
+        HttpVerb as ProtobufHttpVerb, IdAndNewName, MovePayload, NewPluginFedPanePayload,
+        OpenCommandPanePayload, OpenFilePayload, OpenTerminalPanePayload,
+        PluginCommand as ProtobufPluginCommand, PluginFileResult, PluginMessagePayload,
+        PluginWriteFilePayload, ReadClipboardResult, RegisterStatusSegmentPayload,
+        RenderChunkPayload, RenderOverlayPayload, RequestPluginPermissionPayload,
+        ResizeFloatingPaneByPercentPayload, ResizePayload, RunCommandPayload, SetTimeoutPayload,
+        SubscribePayload, SubscribeWithFileSystemFilterPayload, SwitchSessionPayload,
+        SwitchTabToPayload, UnsubscribePayload, WebRequestPayload, WriteToPaneScrollbackPayload,
     },
     plugin_permission::PermissionType as ProtobufPermissionType,
     resize::{ResizeAction as ProtobufResizeAction, ResizePercent as ProtobufResizePercent},
 };
 
 use crate::data::{
-    ConnectToSession, HttpVerb, PaneToResizeByPercent, PermissionType, PluginCommand,
+    ConnectToSession, HttpVerb, PaneId, PaneToResizeByPercent, PermissionType, PluginCommand,
     ResizeByPercent,
 };
 
@@ -670,6 +674,161 @@ impl TryFrom<ProtobufPluginCommand> for PluginCommand {
                 },
                 _ => Err("Mismatched payload for RenameSession"),
             },
+            Some(CommandName::PluginReadFile) => match protobuf_plugin_command.payload {
+                Some(Payload::PluginReadFilePayload(path)) => {
+                    Ok(PluginCommand::PluginReadFile(PathBuf::from(path)))
+                },
+                _ => Err("Mismatched payload for PluginReadFile"),
+            },
+            Some(CommandName::PluginWriteFile) => match protobuf_plugin_command.payload {
+                Some(Payload::PluginWriteFilePayload(plugin_write_file_payload)) => {
+                    Ok(PluginCommand::PluginWriteFile(
+                        PathBuf::from(plugin_write_file_payload.path),
+                        plugin_write_file_payload.contents,
+                    ))
+                },
+                _ => Err("Mismatched payload for PluginWriteFile"),
+            },
+            Some(CommandName::RequestSessionSharingStatus) => {
+                Ok(PluginCommand::RequestSessionSharingStatus)
+            },
+            Some(CommandName::SubscribeWithFileSystemFilter) => {
+                match protobuf_plugin_command.payload {
+                    Some(Payload::SubscribeWithFileSystemFilterPayload(payload)) => {
+                        match payload.subscriptions {
+                            Some(protobuf_event_list) => {
+                                Ok(PluginCommand::SubscribeWithFileSystemFilter(
+                                    protobuf_event_list.try_into()?,
+                                    payload.path_globs,
+                                ))
+                            },
+                            None => Err("malformed subscription event"),
+                        }
+                    },
+                    _ => Err("Mismatched payload for SubscribeWithFileSystemFilter"),
+                }
+            },
+            Some(CommandName::CopyToClipboard) => match protobuf_plugin_command.payload {
+                Some(Payload::CopyToClipboardPayload(text)) => {
+                    Ok(PluginCommand::CopyToClipboard(text))
+                },
+                _ => Err("Mismatched payload for CopyToClipboard"),
+            },
+            Some(CommandName::LogMessage) => match protobuf_plugin_command.payload {
+                Some(Payload::LogMessagePayload(message)) => Ok(PluginCommand::LogMessage(message)),
+                _ => Err("Mismatched payload for LogMessage"),
+            },
+            Some(CommandName::GetSessionLayout) => Ok(PluginCommand::GetSessionLayout),
+            Some(CommandName::OpenUrl) => match protobuf_plugin_command.payload {
+                Some(Payload::OpenUrlPayload(url)) => Ok(PluginCommand::OpenUrl(url)),
+                _ => Err("Mismatched payload for OpenUrl"),
+            },
+            Some(CommandName::OpenFileWithSystemOpener) => match protobuf_plugin_command.payload {
+                Some(Payload::OpenFileWithSystemOpenerPayload(path)) => {
+                    Ok(PluginCommand::OpenFileWithSystemOpener(PathBuf::from(path)))
+                },
+                _ => Err("Mismatched payload for OpenFileWithSystemOpener"),
+            },
+            Some(CommandName::RenderOverlay) => match protobuf_plugin_command.payload {
+                Some(Payload::RenderOverlayPayload(render_overlay_payload)) => {
+                    Ok(PluginCommand::RenderOverlay(
+                        render_overlay_payload.x as usize,
+                        render_overlay_payload.y as usize,
+                        render_overlay_payload.width as usize,
+                        render_overlay_payload.height as usize,
+                        render_overlay_payload.contents,
+                    ))
+                },
+                _ => Err("Mismatched payload for RenderOverlay"),
+            },
+            Some(CommandName::ClearOverlay) => Ok(PluginCommand::ClearOverlay),
+            Some(CommandName::NewPluginFedPane) => match protobuf_plugin_command.payload {
+                Some(Payload::NewPluginFedPanePayload(new_plugin_fed_pane_payload)) => Ok(
+                    PluginCommand::NewPluginFedPane(new_plugin_fed_pane_payload.pane_name),
+                ),
+                _ => Err("Mismatched payload for NewPluginFedPane"),
+            },
+            Some(CommandName::WriteToPaneScrollback) => match protobuf_plugin_command.payload {
+                Some(Payload::WriteToPaneScrollbackPayload(write_to_pane_scrollback_payload)) => {
+                    Ok(PluginCommand::WriteToPaneScrollback(
+                        write_to_pane_scrollback_payload.pane_id,
+                        write_to_pane_scrollback_payload.lines,
+                    ))
+                },
+                _ => Err("Mismatched payload for WriteToPaneScrollback"),
+            },
+            Some(CommandName::RegisterStatusSegment) => match protobuf_plugin_command.payload {
+                Some(Payload::RegisterStatusSegmentPayload(register_status_segment_payload)) => {
+                    Ok(PluginCommand::RegisterStatusSegment(
+                        register_status_segment_payload.priority,
+                        register_status_segment_payload.render_callback_id,
+                    ))
+                },
+                _ => Err("Mismatched payload for RegisterStatusSegment"),
+            },
+            Some(CommandName::RequestSessionActivity) => match protobuf_plugin_command.payload {
+                None => Ok(PluginCommand::RequestSessionActivity),
+                _ => Err("RequestSessionActivity should not have a payload"),
+            },
+            Some(CommandName::SubscribePaneOutput) => match protobuf_plugin_command.payload {
+                Some(Payload::SubscribePaneOutputPayload(pane_id)) => Ok(
+                    PluginCommand::SubscribePaneOutput(PaneId::try_from(pane_id)?),
+                ),
+                _ => Err("Mismatched payload for SubscribePaneOutput"),
+            },
+            Some(CommandName::UnsubscribePaneOutput) => match protobuf_plugin_command.payload {
+                Some(Payload::UnsubscribePaneOutputPayload(pane_id)) => Ok(
+                    PluginCommand::UnsubscribePaneOutput(PaneId::try_from(pane_id)?),
+                ),
+                _ => Err("Mismatched payload for UnsubscribePaneOutput"),
+            },
+            Some(CommandName::SetPaneName) => match protobuf_plugin_command.payload {
+                Some(Payload::SetPaneNamePayload(name)) => Ok(PluginCommand::SetPaneName(name)),
+                _ => Err("Mismatched payload for SetPaneName"),
+            },
+            Some(CommandName::SetTabName) => match protobuf_plugin_command.payload {
+                Some(Payload::SetTabNamePayload(name)) => Ok(PluginCommand::SetTabName(name)),
+                _ => Err("Mismatched payload for SetTabName"),
+            },
+            Some(CommandName::ReadClipboard) => match protobuf_plugin_command.payload {
+                None => Ok(PluginCommand::ReadClipboard),
+                _ => Err("ReadClipboard should not have a payload"),
+            },
+            Some(CommandName::FocusSelf) => match protobuf_plugin_command.payload {
+                None => Ok(PluginCommand::FocusSelf),
+                _ => Err("FocusSelf should not have a payload"),
+            },
+            Some(CommandName::GetKeybindings) => match protobuf_plugin_command.payload {
+                None => Ok(PluginCommand::GetKeybindings),
+                _ => Err("GetKeybindings should not have a payload"),
+            },
+            Some(CommandName::RenderChunk) => match protobuf_plugin_command.payload {
+                Some(Payload::RenderChunkPayload(render_chunk_payload)) => {
+                    Ok(PluginCommand::RenderChunk(
+                        render_chunk_payload.frame_id,
+                        render_chunk_payload.chunk,
+                        render_chunk_payload.is_last,
+                    ))
+                },
+                _ => Err("Mismatched payload for RenderChunk"),
+            },
+            Some(CommandName::OpenTerminalPane) => match protobuf_plugin_command.payload {
+                Some(Payload::OpenTerminalPanePayload(open_terminal_pane_payload)) => {
+                    let command_to_run = match open_terminal_pane_payload.command_to_run {
+                        Some(command_to_run) => Some(command_to_run.try_into()?),
+                        None => None,
+                    };
+                    Ok(PluginCommand::OpenTerminalPane(
+                        PathBuf::from(open_terminal_pane_payload.cwd),
+                        command_to_run,
+                    ))
+                },
+                _ => Err("Mismatched payload for OpenTerminalPane"),
+            },
+            Some(CommandName::ClosePane) => match protobuf_plugin_command.payload {
+                Some(Payload::ClosePanePayload(pane_id)) => Ok(PluginCommand::ClosePane(pane_id)),
+                _ => Err("Mismatched payload for ClosePane"),
+            },
             None => Err("Unrecognized plugin command"),
         }
     }
@@ -1117,6 +1276,155 @@ impl TryFrom<PluginCommand> for ProtobufPluginCommand {
                 name: CommandName::RenameSession as i32,
                 payload: Some(Payload::RenameSessionPayload(new_session_name)),
             }),
+            PluginCommand::PluginReadFile(path) => Ok(ProtobufPluginCommand {
+                name: CommandName::PluginReadFile as i32,
+                payload: Some(Payload::PluginReadFilePayload(path.display().to_string())),
+            }),
+            PluginCommand::PluginWriteFile(path, contents) => Ok(ProtobufPluginCommand {
+                name: CommandName::PluginWriteFile as i32,
+                payload: Some(Payload::PluginWriteFilePayload(PluginWriteFilePayload {
+                    path: path.display().to_string(),
+                    contents,
+                })),
+            }),
+            PluginCommand::RequestSessionSharingStatus => Ok(ProtobufPluginCommand {
+                name: CommandName::RequestSessionSharingStatus as i32,
+                payload: None,
+            }),
+            PluginCommand::SubscribeWithFileSystemFilter(subscriptions, path_globs) => {
+                let subscriptions: ProtobufEventNameList = subscriptions.try_into()?;
+                Ok(ProtobufPluginCommand {
+                    name: CommandName::SubscribeWithFileSystemFilter as i32,
+                    payload: Some(Payload::SubscribeWithFileSystemFilterPayload(
+                        SubscribeWithFileSystemFilterPayload {
+                            subscriptions: Some(subscriptions),
+                            path_globs,
+                        },
+                    )),
+                })
+            },
+            PluginCommand::CopyToClipboard(text) => Ok(ProtobufPluginCommand {
+                name: CommandName::CopyToClipboard as i32,
+                payload: Some(Payload::CopyToClipboardPayload(text)),
+            }),
+            PluginCommand::LogMessage(message) => Ok(ProtobufPluginCommand {
+                name: CommandName::LogMessage as i32,
+                payload: Some(Payload::LogMessagePayload(message)),
+            }),
+            PluginCommand::GetSessionLayout => Ok(ProtobufPluginCommand {
+                name: CommandName::GetSessionLayout as i32,
+                payload: None,
+            }),
+            PluginCommand::OpenUrl(url) => Ok(ProtobufPluginCommand {
+                name: CommandName::OpenUrl as i32,
+                payload: Some(Payload::OpenUrlPayload(url)),
+            }),
+            PluginCommand::OpenFileWithSystemOpener(path) => Ok(ProtobufPluginCommand {
+                name: CommandName::OpenFileWithSystemOpener as i32,
+                payload: Some(Payload::OpenFileWithSystemOpenerPayload(
+                    path.display().to_string(),
+                )),
+            }),
+            PluginCommand::RenderOverlay(x, y, width, height, contents) => {
+                Ok(ProtobufPluginCommand {
+                    name: CommandName::RenderOverlay as i32,
+                    payload: Some(Payload::RenderOverlayPayload(RenderOverlayPayload {
+                        x: x as u32,
+                        y: y as u32,
+                        width: width as u32,
+                        height: height as u32,
+                        contents,
+                    })),
+                })
+            },
+            PluginCommand::ClearOverlay => Ok(ProtobufPluginCommand {
+                name: CommandName::ClearOverlay as i32,
+                payload: None,
+            }),
+            PluginCommand::NewPluginFedPane(pane_name) => Ok(ProtobufPluginCommand {
+                name: CommandName::NewPluginFedPane as i32,
+                payload: Some(Payload::NewPluginFedPanePayload(NewPluginFedPanePayload {
+                    pane_name,
+                })),
+            }),
+            PluginCommand::WriteToPaneScrollback(pane_id, lines) => Ok(ProtobufPluginCommand {
+                name: CommandName::WriteToPaneScrollback as i32,
+                payload: Some(Payload::WriteToPaneScrollbackPayload(
+                    WriteToPaneScrollbackPayload { pane_id, lines },
+                )),
+            }),
+            PluginCommand::RegisterStatusSegment(priority, render_callback_id) => {
+                Ok(ProtobufPluginCommand {
+                    name: CommandName::RegisterStatusSegment as i32,
+                    payload: Some(Payload::RegisterStatusSegmentPayload(
+                        RegisterStatusSegmentPayload {
+                            priority,
+                            render_callback_id,
+                        },
+                    )),
+                })
+            },
+            PluginCommand::RequestSessionActivity => Ok(ProtobufPluginCommand {
+                name: CommandName::RequestSessionActivity as i32,
+                payload: None,
+            }),
+            PluginCommand::SubscribePaneOutput(pane_id) => Ok(ProtobufPluginCommand {
+                name: CommandName::SubscribePaneOutput as i32,
+                payload: Some(Payload::SubscribePaneOutputPayload(
+                    ProtobufPaneId::try_from(pane_id)?,
+                )),
+            }),
+            PluginCommand::UnsubscribePaneOutput(pane_id) => Ok(ProtobufPluginCommand {
+                name: CommandName::UnsubscribePaneOutput as i32,
+                payload: Some(Payload::UnsubscribePaneOutputPayload(
+                    ProtobufPaneId::try_from(pane_id)?,
+                )),
+            }),
+            PluginCommand::SetPaneName(name) => Ok(ProtobufPluginCommand {
+                name: CommandName::SetPaneName as i32,
+                payload: Some(Payload::SetPaneNamePayload(name)),
+            }),
+            PluginCommand::SetTabName(name) => Ok(ProtobufPluginCommand {
+                name: CommandName::SetTabName as i32,
+                payload: Some(Payload::SetTabNamePayload(name)),
+            }),
+            PluginCommand::ReadClipboard => Ok(ProtobufPluginCommand {
+                name: CommandName::ReadClipboard as i32,
+                payload: None,
+            }),
+            PluginCommand::FocusSelf => Ok(ProtobufPluginCommand {
+                name: CommandName::FocusSelf as i32,
+                payload: None,
+            }),
+            PluginCommand::GetKeybindings => Ok(ProtobufPluginCommand {
+                name: CommandName::GetKeybindings as i32,
+                payload: None,
+            }),
+            PluginCommand::RenderChunk(frame_id, chunk, is_last) => Ok(ProtobufPluginCommand {
+                name: CommandName::RenderChunk as i32,
+                payload: Some(Payload::RenderChunkPayload(RenderChunkPayload {
+                    frame_id,
+                    chunk,
+                    is_last,
+                })),
+            }),
+            PluginCommand::OpenTerminalPane(cwd, command_to_run) => {
+                let command_to_run = match command_to_run {
+                    Some(command_to_run) => Some(command_to_run.try_into()?),
+                    None => None,
+                };
+                Ok(ProtobufPluginCommand {
+                    name: CommandName::OpenTerminalPane as i32,
+                    payload: Some(Payload::OpenTerminalPanePayload(OpenTerminalPanePayload {
+                        cwd: cwd.display().to_string(),
+                        command_to_run,
+                    })),
+                })
+            },
+            PluginCommand::ClosePane(pane_id) => Ok(ProtobufPluginCommand {
+                name: CommandName::ClosePane as i32,
+                payload: Some(Payload::ClosePanePayload(pane_id)),
+            }),
         }
     }
 }
@@ -11,6 +11,7 @@ impl TryFrom<ProtobufPluginIds> for PluginIds {
         Ok(PluginIds {
             plugin_id: protobuf_plugin_ids.plugin_id as u32,
             zellij_pid: protobuf_plugin_ids.zellij_pid as u32,
+            stable_plugin_id: protobuf_plugin_ids.stable_plugin_id,
         })
     }
 }
@@ -21,6 +22,7 @@ impl TryFrom<PluginIds> for ProtobufPluginIds {
         Ok(ProtobufPluginIds {
             plugin_id: plugin_ids.plugin_id as i32,
             zellij_pid: plugin_ids.zellij_pid as i32,
+            stable_plugin_id: plugin_ids.stable_plugin_id,
         })
     }
 }
@@ -20,6 +20,14 @@ impl TryFrom<ProtobufPermissionType> for PermissionType {
             },
             ProtobufPermissionType::WriteToStdin => Ok(PermissionType::WriteToStdin),
             ProtobufPermissionType::WebAccess => Ok(PermissionType::WebAccess),
+            ProtobufPermissionType::WriteToClipboard => Ok(PermissionType::WriteToClipboard),
+            ProtobufPermissionType::ReadPaneOutput => Ok(PermissionType::ReadPaneOutput),
+            ProtobufPermissionType::ReadClipboard => Ok(PermissionType::ReadClipboard),
+            ProtobufPermissionType::FocusSelf => Ok(PermissionType::FocusSelf),
+            ProtobufPermissionType::OpenUrls => Ok(PermissionType::OpenUrls),
+            ProtobufPermissionType::OpenFilesWithSystemOpener => {
+                Ok(PermissionType::OpenFilesWithSystemOpener)
+            },
         }
     }
 }
@@ -41,6 +49,14 @@ impl TryFrom<PermissionType> for ProtobufPermissionType {
             },
             PermissionType::WriteToStdin => Ok(ProtobufPermissionType::WriteToStdin),
             PermissionType::WebAccess => Ok(ProtobufPermissionType::WebAccess),
+            PermissionType::WriteToClipboard => Ok(ProtobufPermissionType::WriteToClipboard),
+            PermissionType::ReadPaneOutput => Ok(ProtobufPermissionType::ReadPaneOutput),
+            PermissionType::ReadClipboard => Ok(ProtobufPermissionType::ReadClipboard),
+            PermissionType::FocusSelf => Ok(ProtobufPermissionType::FocusSelf),
+            PermissionType::OpenUrls => Ok(ProtobufPermissionType::OpenUrls),
+            PermissionType::OpenFilesWithSystemOpener => {
+                Ok(ProtobufPermissionType::OpenFilesWithSystemOpener)
+            },
         }
     }
 }
@@ -49,6 +49,7 @@ pub struct TabLayoutManifest {
     pub floating_panes: Vec<PaneLayoutManifest>,
     pub is_focused: bool,
     pub hide_floating_panes: bool,
+    pub color: Option<String>,
 }
 
 #[derive(Default, Debug, Clone)]
@@ -59,7 +60,10 @@ pub struct PaneLayoutManifest {
     pub is_borderless: bool,
     pub title: Option<String>,
     pub is_focused: bool,
+    pub is_pinned: bool,
+    pub is_read_only: bool,
     pub pane_contents: Option<String>,
+    pub plugin_state: Option<Vec<u8>>,
 }
 
 pub fn serialize_session_layout(
@@ -99,6 +103,7 @@ fn stringify_tab(
     tab_name: String,
     is_focused: bool,
     hide_floating_panes: bool,
+    color: Option<&String>,
     tiled_panes: &Vec<PaneLayoutManifest>,
     floating_panes: &Vec<PaneLayoutManifest>,
     pane_contents: &mut BTreeMap<String, String>,
@@ -122,6 +127,9 @@ fn stringify_tab(
             if hide_floating_panes {
                 tab_attributes.push(format!("hide_floating_panes=true"));
             }
+            if let Some(color) = color {
+                tab_attributes.push(format!("color=\"{}\"", color));
+            }
             kdl_string.push_str(&kdl_string_from_tab(
                 &tiled_panes,
                 &floating_panes_layout,
@@ -191,6 +199,7 @@ fn kdl_string_from_tiled_pane(
         cwd,
         layout.focus,
         &layout.pane_initial_contents,
+        &layout.plugin_initial_state,
         pane_contents,
         has_children,
     );
@@ -204,6 +213,7 @@ fn kdl_string_from_tiled_pane(
     if has_child_attributes {
         kdl_string.push_str(" {\n");
         stringify_args(args, &mut kdl_string);
+        stringify_env(&layout.run, &mut kdl_string);
         stringify_start_suspended(&command, &mut kdl_string);
         stringify_plugin(plugin, plugin_config, &mut kdl_string);
         if layout.children.is_empty() && layout.external_children_index.is_some() {
@@ -262,6 +272,7 @@ fn stringify_pane_title_and_attributes(
     cwd: Option<PathBuf>,
     focus: Option<bool>,
     initial_pane_contents: &Option<String>,
+    plugin_initial_state: &Option<Vec<u8>>,
     pane_contents: &mut BTreeMap<String, String>,
     has_children: bool,
 ) -> String {
@@ -289,9 +300,35 @@ fn stringify_pane_title_and_attributes(
             pane_contents.insert(file_name.to_string(), initial_pane_contents.clone());
         }
     }
+    if let Some(plugin_initial_state) = plugin_initial_state.as_ref() {
+        let file_name = format!("plugin_state_{}", pane_contents.keys().len() + 1);
+        kdl_string.push_str(&format!(" plugin_state_file=\"{}\"", file_name));
+        pane_contents.insert(file_name, encode_plugin_state(plugin_initial_state));
+    }
     kdl_string
 }
 
+/// Plugin states are arbitrary bytes, they are hex encoded so that they can be stored alongside
+/// the (textual) pane contents of a serialized session
+pub fn encode_plugin_state(plugin_state: &[u8]) -> String {
+    plugin_state.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+pub fn decode_plugin_state(encoded_plugin_state: &str) -> Option<Vec<u8>> {
+    let encoded_plugin_state = encoded_plugin_state.trim();
+    if encoded_plugin_state.len() % 2 != 0 {
+        return None;
+    }
+    (0..encoded_plugin_state.len())
+        .step_by(2)
+        .map(|i| {
+            encoded_plugin_state
+                .get(i..i + 2)
+                .and_then(|byte| u8::from_str_radix(byte, 16).ok())
+        })
+        .collect()
+}
+
 fn stringify_args(args: Vec<String>, kdl_string: &mut String) {
     if !args.is_empty() {
         let args = args
@@ -303,6 +340,40 @@ fn stringify_args(args: Vec<String>, kdl_string: &mut String) {
     }
 }
 
+fn stringify_env(layout_run: &Option<Run>, kdl_string: &mut String) {
+    if let Some(Run::Command(run_command)) = layout_run {
+        if !run_command.env.is_empty() {
+            kdl_string.push_str(&indent("env {\n", INDENT));
+            for (name, value) in &run_command.env {
+                kdl_string.push_str(&indent(
+                    &format!(
+                        "\"{}\" \"{}\"\n",
+                        escape_kdl_string(name),
+                        escape_kdl_string(value)
+                    ),
+                    DOUBLE_INDENT,
+                ));
+            }
+            kdl_string.push_str(&indent("}\n", INDENT));
+        }
+    }
+}
+
+fn escape_kdl_string(string: &str) -> String {
+    let mut escaped = String::with_capacity(string.len());
+    for character in string.chars() {
+        match character {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            character => escaped.push(character),
+        }
+    }
+    escaped
+}
+
 fn stringify_plugin(
     plugin: Option<String>,
     plugin_config: Option<PluginUserConfiguration>,
@@ -347,6 +418,9 @@ fn stringify_tiled_layout_attributes(
     if layout.borderless {
         kdl_string.push_str(&" borderless=true");
     }
+    if layout.read_only.unwrap_or(false) {
+        kdl_string.push_str(&" read_only=true");
+    }
     if layout.children_are_stacked {
         kdl_string.push_str(&" stacked=true");
     }
@@ -399,6 +473,12 @@ fn stringify_floating_layout_attributes(layout: &FloatingPaneLayout, kdl_string:
         },
         None => {},
     }
+    if layout.pinned.unwrap_or(false) {
+        kdl_string.push_str(&indent("pinned true\n", INDENT));
+    }
+    if layout.read_only.unwrap_or(false) {
+        kdl_string.push_str(&indent("read_only true\n", INDENT));
+    }
 }
 
 fn stringify_start_suspended(command: &Option<String>, kdl_string: &mut String) {
@@ -529,6 +609,7 @@ fn stringify_multiple_tabs(
             tab_name.clone(),
             tab_layout_manifest.is_focused,
             hide_floating_panes,
+            tab_layout_manifest.color.as_ref(),
             &tiled_panes,
             &floating_panes,
             pane_contents,
@@ -561,6 +642,7 @@ fn kdl_string_from_floating_pane(
         cwd,
         layout.focus,
         &layout.pane_initial_contents,
+        &layout.plugin_initial_state,
         pane_contents,
         has_children,
     );
@@ -568,6 +650,7 @@ fn kdl_string_from_floating_pane(
     stringify_start_suspended(&command, &mut kdl_string);
     stringify_floating_layout_attributes(&layout, &mut kdl_string);
     stringify_args(args, &mut kdl_string);
+    stringify_env(&layout.run, &mut kdl_string);
     stringify_plugin(plugin, plugin_config, &mut kdl_string);
     kdl_string.push_str("}\n");
     kdl_string
@@ -577,7 +660,16 @@ fn tiled_pane_layout_from_manifest(
     manifest: Option<&PaneLayoutManifest>,
     split_size: Option<SplitSize>,
 ) -> TiledPaneLayout {
-    let (run, borderless, is_expanded_in_stack, name, focus, pane_initial_contents) = manifest
+    let (
+        run,
+        borderless,
+        is_expanded_in_stack,
+        name,
+        focus,
+        read_only,
+        pane_initial_contents,
+        plugin_initial_state,
+    ) = manifest
         .map(|g| {
             let mut run = g.run.clone();
             if let Some(cwd) = &g.cwd {
@@ -593,10 +685,12 @@ fn tiled_pane_layout_from_manifest(
                 g.geom.is_stacked && g.geom.rows.inner > 1,
                 g.title.clone(),
                 Some(g.is_focused),
+                Some(g.is_read_only),
                 g.pane_contents.clone(),
+                g.plugin_state.clone(),
             )
         })
-        .unwrap_or((None, false, false, None, None, None));
+        .unwrap_or((None, false, false, None, None, None, None, None));
     TiledPaneLayout {
         split_size,
         run,
@@ -604,7 +698,9 @@ fn tiled_pane_layout_from_manifest(
         is_expanded_in_stack,
         name,
         focus,
+        read_only,
         pane_initial_contents,
+        plugin_initial_state,
         ..Default::default()
     }
 }
@@ -692,8 +788,12 @@ fn get_floating_panes_layout_from_panegeoms(
                 y: Some(PercentOrFixed::Fixed(m.geom.y)),
                 run,
                 focus: Some(m.is_focused),
+                pinned: Some(m.is_pinned),
+                read_only: Some(m.is_read_only),
+                scroll_lines: None,
                 already_running: false,
                 pane_initial_contents: m.pane_contents.clone(),
+                plugin_initial_state: m.plugin_state.clone(),
             }
         })
         .collect()
@@ -920,6 +1020,8 @@ fn get_split_sizes(constraints: &Vec<Constraint>) -> Vec<Option<SplitSize>> {
 mod tests {
 
     use super::*;
+    use crate::input::command::RunCommand;
+    use crate::input::layout::RunPlugin;
     use crate::pane_size::Dimension;
     use expect_test::expect;
     use serde_json::Value;
@@ -1123,6 +1225,134 @@ mod tests {
 }"#]]
         .assert_eq(&kdl.0);
     }
+    #[test]
+    fn plugin_state_stored_alongside_layout() {
+        let plugin_pane = PaneLayoutManifest {
+            geom: parse_panegeom_from_json(PANEGEOMS_JSON[0][0]),
+            run: Some(Run::Plugin(
+                RunPlugin::from_url("zellij:status-bar").unwrap(),
+            )),
+            plugin_state: Some(vec![1, 2, 3, 255]),
+            ..Default::default()
+        };
+        // a tab with a single pane is dumped without it
+        let mut tiled_panes = vec![plugin_pane];
+        tiled_panes.extend(
+            PANEGEOMS_JSON[0][1..]
+                .iter()
+                .map(|geom| PaneLayoutManifest {
+                    geom: parse_panegeom_from_json(geom),
+                    ..Default::default()
+                }),
+        );
+        let tab_layout_manifest = TabLayoutManifest {
+            tiled_panes,
+            ..Default::default()
+        };
+        let global_layout_manifest = GlobalLayoutManifest {
+            tabs: vec![("Tab #1".to_owned(), tab_layout_manifest)],
+            ..Default::default()
+        };
+        let (kdl, pane_contents) = serialize_session_layout(global_layout_manifest).unwrap();
+        assert!(
+            kdl.contains("plugin_state_file=\"plugin_state_1\""),
+            "plugin state file referenced from the plugin pane"
+        );
+        let encoded_plugin_state = pane_contents.get("plugin_state_1").unwrap();
+        assert_eq!(encoded_plugin_state, "010203ff");
+        assert_eq!(
+            decode_plugin_state(encoded_plugin_state),
+            Some(vec![1, 2, 3, 255]),
+            "plugin state decoded without change"
+        );
+    }
+    #[test]
+    fn floating_pane_geometry_round_trips_through_the_layout() {
+        let floating_pane = PaneLayoutManifest {
+            geom: PaneGeom {
+                x: 10,
+                y: 5,
+                rows: Dimension::fixed(8),
+                cols: Dimension::fixed(30),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let tab_layout_manifest = TabLayoutManifest {
+            tiled_panes: vec![PaneLayoutManifest {
+                geom: parse_panegeom_from_json(PANEGEOMS_JSON[0][0]),
+                ..Default::default()
+            }],
+            floating_panes: vec![floating_pane],
+            ..Default::default()
+        };
+        let global_layout_manifest = GlobalLayoutManifest {
+            tabs: vec![("Tab #1".to_owned(), tab_layout_manifest)],
+            ..Default::default()
+        };
+        let (kdl, _pane_contents) = serialize_session_layout(global_layout_manifest).unwrap();
+        let layout = Layout::from_kdl(&kdl, "dumped_layout".into(), None, None).unwrap();
+        let (_tab_name, _tiled_panes, floating_panes) = layout.tabs.first().unwrap();
+        let floating_pane = floating_panes.first().expect("floating pane was dumped");
+        assert_eq!(floating_pane.x, Some(PercentOrFixed::Fixed(10)));
+        assert_eq!(floating_pane.y, Some(PercentOrFixed::Fixed(5)));
+        assert_eq!(floating_pane.width, Some(PercentOrFixed::Fixed(30)));
+        assert_eq!(floating_pane.height, Some(PercentOrFixed::Fixed(8)));
+    }
+    #[test]
+    fn env_names_and_values_with_special_characters_round_trip_through_the_layout() {
+        let mut env = BTreeMap::new();
+        env.insert(
+            "GREETING".to_owned(),
+            "say \"hi\"\\n and C:\\temp\\".to_owned(),
+        );
+        // names are not always valid kdl identifiers
+        env.insert("MY-APP.CONFIG DIR=1".to_owned(), "/tmp".to_owned());
+        env.insert("1ST_RUN".to_owned(), "true".to_owned());
+        let command_pane = PaneLayoutManifest {
+            geom: parse_panegeom_from_json(PANEGEOMS_JSON[0][0]),
+            run: Some(Run::Command(RunCommand {
+                command: PathBuf::from("htop"),
+                env: env.clone(),
+                ..Default::default()
+            })),
+            ..Default::default()
+        };
+        // a tab with a single pane is dumped without it
+        let mut tiled_panes = vec![command_pane];
+        tiled_panes.extend(
+            PANEGEOMS_JSON[0][1..]
+                .iter()
+                .map(|geom| PaneLayoutManifest {
+                    geom: parse_panegeom_from_json(geom),
+                    ..Default::default()
+                }),
+        );
+        let tab_layout_manifest = TabLayoutManifest {
+            tiled_panes,
+            ..Default::default()
+        };
+        let global_layout_manifest = GlobalLayoutManifest {
+            tabs: vec![("Tab #1".to_owned(), tab_layout_manifest)],
+            ..Default::default()
+        };
+        let (kdl, _pane_contents) = serialize_session_layout(global_layout_manifest).unwrap();
+        let layout = Layout::from_kdl(&kdl, "dumped_layout".into(), None, None).unwrap();
+        let (_tab_name, tiled_panes, _floating_panes) = layout.tabs.first().unwrap();
+        let mut panes = vec![tiled_panes];
+        let mut dumped_env = None;
+        while let Some(pane) = panes.pop() {
+            if let Some(Run::Command(run_command)) = &pane.run {
+                dumped_env = Some(run_command.env.clone());
+            }
+            panes.extend(pane.children.iter());
+        }
+        assert_eq!(
+            dumped_env,
+            Some(env),
+            "env names and values were dumped without change"
+        );
+    }
     // utility functions
     fn parse_panegeom_from_json(data_str: &str) -> PaneGeom {
         //